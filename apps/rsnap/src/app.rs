@@ -1,15 +1,30 @@
+mod app_capture;
 mod capture;
+mod capture_popover;
+mod clipboard_watch;
+mod fullscreen;
 mod hotkeys;
+mod interval_capture;
+mod loupe_stream;
+mod pause;
+mod pins;
+mod prewarm;
+mod region_watch;
+mod replay;
 mod runtime;
 #[cfg(target_os = "macos")]
 mod scroll_input_macos;
 mod shell;
+mod supervisor;
+mod theme_pair;
+mod updates;
 
+use std::path::PathBuf;
+use std::sync::Arc;
 #[cfg(target_os = "macos")]
-use std::sync::{
-	Arc,
-	atomic::{AtomicBool, Ordering},
-};
+use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU32};
+use std::time::Instant;
 
 use color_eyre::eyre::Result;
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::HotKey};
@@ -17,26 +32,47 @@ use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::HotKey};
 use tray_icon::menu::Menu;
 use tray_icon::{
 	TrayIcon,
-	menu::{MenuEvent, MenuId},
+	menu::{CheckMenuItem, MenuEvent, MenuId, MenuItem, Submenu},
 };
-use winit::event_loop::ActiveEventLoop;
-#[cfg(target_os = "macos")]
-use winit::event_loop::EventLoopProxy;
+use winit::event_loop::{ActiveEventLoop, EventLoopProxy};
 
+use self::capture::PendingOverlayLaunch;
+use self::pins::PinMenuEntry;
+use self::prewarm::PrewarmHint;
 #[cfg(target_os = "macos")]
 use self::scroll_input_macos::SharedScrollInputState;
+use self::supervisor::OverlaySupervisor;
+use self::updates::UpdateEvent;
+use crate::capture_feedback::CapturedImage;
+use crate::capture_popover::{CapturePopover, PopoverSource};
 use crate::settings::AppSettings;
 use crate::settings_window::SettingsWindow;
-use rsnap_overlay::OverlaySession;
+use crate::updates::Release;
+use rsnap_overlay::{
+	ClipboardWatch, IntervalCapture, LoupeStreamConfig, LoupeStreamServer, OverlayProgress,
+	OverlaySession, RegionWatch, ReplayBuffer, ReplayConfig,
+};
 
 pub(crate) enum UserEvent {
 	TrayIcon,
 	Menu(MenuEvent),
-	HotKey(GlobalHotKeyEvent),
+	/// A global hotkey event and when its handler received it.
+	HotKey(GlobalHotKeyEvent, Instant),
 	#[cfg(target_os = "macos")]
 	OverlayStreamFrame,
 	#[cfg(target_os = "macos")]
 	OverlayWorkerResponse,
+	/// The focused full-screen application changed; `None` when there is none.
+	FullscreenApp(Option<String>),
+	/// The capture chord's modifiers were held long enough to prewarm, or released.
+	Prewarm(PrewarmHint),
+	/// An update check, install or notes window finished.
+	Update(UpdateEvent),
+	/// Annotate was picked on a capture notification; opens this file in the editor.
+	Annotate(PathBuf),
+	/// The clipboard watch saw a newly copied image, as PNG bytes and the fingerprint of its
+	/// pixels.
+	ClipboardImage(Vec<u8>, u64),
 }
 
 struct App {
@@ -44,6 +80,23 @@ struct App {
 	capture_hotkey_id: u32,
 	settings_hotkey: Option<HotKey>,
 	settings_hotkey_id: Option<u32>,
+	magnifier_hotkey: HotKey,
+	magnifier_hotkey_id: u32,
+	color_picker_hotkey: HotKey,
+	color_picker_hotkey_id: u32,
+	profile_cycle_hotkey: HotKey,
+	profile_cycle_hotkey_id: u32,
+	display_picker_hotkey: HotKey,
+	display_picker_hotkey_id: u32,
+	active_window_hotkey: HotKey,
+	active_window_hotkey_id: u32,
+	replay_hotkey: HotKey,
+	replay_hotkey_id: u32,
+	repeat_capture_hotkey: HotKey,
+	repeat_capture_hotkey_id: u32,
+	/// Toggles [`App::paused`]; stays registered while everything else is paused.
+	pause_hotkey: HotKey,
+	pause_hotkey_id: u32,
 	_hotkey_manager: Option<GlobalHotKeyManager>,
 	capture_hotkey_recording_suspended: bool,
 	tray_icon: Option<TrayIcon>,
@@ -51,14 +104,87 @@ struct App {
 	menubar_menu: Option<Menu>,
 	settings_menu_id: Option<MenuId>,
 	capture_menu_id: Option<MenuId>,
+	color_picker_menu_id: Option<MenuId>,
+	/// Tray entry that stops the region watch; disabled while nothing is watched.
+	stop_region_watch_menu_item: Option<MenuItem>,
+	start_interval_capture_menu_id: Option<MenuId>,
+	identify_displays_menu_id: Option<MenuId>,
+	/// Tray entry that stops the interval capture; disabled while none runs.
+	stop_interval_capture_menu_item: Option<MenuItem>,
+	pause_menu_item: Option<CheckMenuItem>,
 	quit_menu_id: Option<MenuId>,
+	/// "Check for Updates…", relabelled once a newer release is known.
+	update_menu_item: Option<MenuItem>,
+	/// Tray profile entries; index 0 is the base settings, index `n` is `settings.profiles[n - 1]`.
+	profile_menu_items: Vec<CheckMenuItem>,
+	/// Tray submenu of capture presets; disabled while none are configured.
+	capture_preset_menu: Option<Submenu>,
+	/// Entries of [`App::capture_preset_menu`], one per `settings.capture_presets` item.
+	capture_preset_menu_items: Vec<MenuItem>,
+	/// Tray submenu listing apps with visible windows; refreshed when the tray icon is used.
+	app_capture_menu: Option<Submenu>,
+	/// Entries of [`App::app_capture_menu`] with the pid each one captures.
+	app_capture_menu_items: Vec<(MenuItem, u32)>,
+	pins_menu: Option<Submenu>,
+	/// One submenu of [`App::pins_menu`] per pinned image, in pinning order.
+	pins_menu_entries: Vec<PinMenuEntry>,
+	close_all_pins_menu_item: Option<MenuItem>,
 	#[cfg(target_os = "macos")]
 	menubar_settings_menu_id: Option<MenuId>,
 	#[cfg(target_os = "macos")]
 	menubar_quit_menu_id: Option<MenuId>,
 	overlay_session: Option<OverlaySession>,
+	/// When the hotkey behind the next overlay start was pressed; its latency is measured from it.
+	overlay_requested_at: Option<Instant>,
+	/// An overlay start parked until its GPU device is ready, so the event loop never waits on it.
+	pending_overlay_launch: Option<PendingOverlayLaunch>,
+	/// A `.rsnap` project named on the command line, opened once the event loop is running.
+	pending_project: Option<PathBuf>,
+	/// Folder named by `rsnap capture-to`, as Explorer's "Capture region to this folder" passes
+	/// it. Such a launch skips the tray, runs one capture that saves there and then exits.
+	capture_folder: Option<PathBuf>,
+	/// Crash and hang bookkeeping for `overlay_session`.
+	overlay_supervisor: OverlaySupervisor,
+	/// A session prewarmed while the capture chord's modifiers are held; the next start adopts it.
+	prewarmed_overlay: Option<OverlaySession>,
+	/// Winit modifier bits the prewarm watcher waits for; 0 when prewarming is off.
+	prewarm_modifiers: Arc<AtomicU32>,
+	/// The running instant replay buffer and the config it was started with.
+	replay_buffer: Option<(ReplayConfig, ReplayBuffer)>,
+	region_watch: Option<RegionWatch>,
+	interval_capture: Option<IntervalCapture>,
+	/// The running loupe IPC stream and the config it was started with.
+	loupe_stream: Option<(LoupeStreamConfig, LoupeStreamServer)>,
 	settings_window: Option<SettingsWindow>,
+	/// The post-capture and clipboard popovers on screen, and the image queued to show next.
+	capture_popovers: Vec<CapturePopover>,
+	pending_capture_popover: Option<(CapturedImage, PopoverSource)>,
+	clipboard_watch: Option<ClipboardWatch>,
+	/// When rsnap last put an image on the clipboard, so the clipboard watch does not offer it.
+	last_clipboard_write: Option<Instant>,
+	/// Fingerprint of the image rsnap last put on the clipboard, so the watch skips it for good.
+	own_clipboard_image: Option<u64>,
 	settings: AppSettings,
+	/// Whether the full-screen watcher thread polls at all.
+	fullscreen_watch_enabled: Arc<AtomicBool>,
+	/// The last full-screen application the watcher reported.
+	fullscreen_app: Option<String>,
+	/// The full-screen application the global hotkeys are paused for.
+	hotkeys_paused_for: Option<String>,
+	/// The latest export progress of the overlay session, shown in the tray tooltip.
+	overlay_progress: Option<OverlayProgress>,
+	/// Whether the user paused rsnap: hotkeys, streams and sampling threads are all off.
+	paused: bool,
+	/// Whether the scheduled update checker may contact GitHub.
+	update_checks_enabled: Arc<AtomicBool>,
+	/// The newer release the last check found.
+	available_update: Option<Release>,
+	/// Set while an update window or download runs, so only one is open at a time.
+	update_task_running: Arc<AtomicBool>,
+	/// Sends results back to the event loop from update and notification threads.
+	event_proxy: Option<EventLoopProxy<UserEvent>>,
+	/// The installed update to start once the event loop has exited.
+	relaunch_after_exit: Option<PathBuf>,
 	#[cfg(target_os = "macos")]
 	overlay_proxy: EventLoopProxy<UserEvent>,
 	#[cfg(target_os = "macos")]
@@ -78,11 +204,36 @@ impl App {
 		#[cfg(target_os = "macos")] overlay_stream_event_pending: Arc<AtomicBool>,
 		#[cfg(target_os = "macos")] scroll_input_shared_state: Arc<SharedScrollInputState>,
 	) -> Self {
+		let magnifier_hotkey = settings.magnifier_hotkey();
+		let color_picker_hotkey = settings.color_picker_hotkey();
+		let profile_cycle_hotkey = settings.profile_cycle_hotkey();
+		let display_picker_hotkey = settings.display_picker_hotkey();
+		let active_window_hotkey = settings.active_window_hotkey();
+		let replay_hotkey = settings.replay_hotkey();
+		let repeat_capture_hotkey = settings.repeat_capture_hotkey();
+		let pause_hotkey = settings.pause_hotkey();
+
 		Self {
 			capture_hotkey_id: capture_hotkey.id(),
 			capture_hotkey,
 			settings_hotkey,
 			settings_hotkey_id: settings_hotkey.as_ref().map(HotKey::id),
+			magnifier_hotkey_id: magnifier_hotkey.id(),
+			magnifier_hotkey,
+			color_picker_hotkey_id: color_picker_hotkey.id(),
+			color_picker_hotkey,
+			profile_cycle_hotkey_id: profile_cycle_hotkey.id(),
+			profile_cycle_hotkey,
+			display_picker_hotkey_id: display_picker_hotkey.id(),
+			display_picker_hotkey,
+			active_window_hotkey_id: active_window_hotkey.id(),
+			active_window_hotkey,
+			replay_hotkey_id: replay_hotkey.id(),
+			replay_hotkey,
+			repeat_capture_hotkey_id: repeat_capture_hotkey.id(),
+			repeat_capture_hotkey,
+			pause_hotkey_id: pause_hotkey.id(),
+			pause_hotkey,
 			capture_hotkey_recording_suspended: false,
 			_hotkey_manager: hotkey_manager,
 			tray_icon: None,
@@ -90,13 +241,56 @@ impl App {
 			menubar_menu: None,
 			settings_menu_id: None,
 			capture_menu_id: None,
+			color_picker_menu_id: None,
+			stop_region_watch_menu_item: None,
+			start_interval_capture_menu_id: None,
+			identify_displays_menu_id: None,
+			stop_interval_capture_menu_item: None,
+			pause_menu_item: None,
 			quit_menu_id: None,
+			update_menu_item: None,
+			profile_menu_items: Vec::new(),
+			capture_preset_menu: None,
+			capture_preset_menu_items: Vec::new(),
+			app_capture_menu: None,
+			app_capture_menu_items: Vec::new(),
+			pins_menu: None,
+			pins_menu_entries: Vec::new(),
+			close_all_pins_menu_item: None,
 			#[cfg(target_os = "macos")]
 			menubar_settings_menu_id: None,
 			#[cfg(target_os = "macos")]
 			menubar_quit_menu_id: None,
 			overlay_session: None,
+			overlay_requested_at: None,
+			pending_overlay_launch: None,
+			pending_project: None,
+			capture_folder: None,
+			overlay_supervisor: OverlaySupervisor::default(),
+			prewarmed_overlay: None,
+			prewarm_modifiers: Arc::new(AtomicU32::new(0)),
+			replay_buffer: None,
+			region_watch: None,
+			interval_capture: None,
+			loupe_stream: None,
 			settings_window: None,
+			capture_popovers: Vec::new(),
+			pending_capture_popover: None,
+			clipboard_watch: None,
+			last_clipboard_write: None,
+			own_clipboard_image: None,
+			fullscreen_watch_enabled: Arc::new(AtomicBool::new(
+				settings.pause_hotkeys_in_fullscreen,
+			)),
+			fullscreen_app: None,
+			hotkeys_paused_for: None,
+			overlay_progress: None,
+			paused: false,
+			update_checks_enabled: Arc::new(AtomicBool::new(settings.check_for_updates)),
+			available_update: None,
+			update_task_running: Arc::new(AtomicBool::new(false)),
+			event_proxy: None,
+			relaunch_after_exit: None,
 			settings,
 			#[cfg(target_os = "macos")]
 			overlay_proxy,
@@ -119,9 +313,11 @@ impl App {
 		}
 
 		match SettingsWindow::open(event_loop) {
-			Ok(window) => {
+			Ok(mut window) => {
 				tracing::info!(requested_by = %requested_by, "Settings window opened.");
 
+				window.set_loupe_stream_token(self.loupe_stream_token());
+
 				window.focus();
 
 				self.settings_window = Some(window);
@@ -137,8 +333,10 @@ impl App {
 	}
 }
 
-pub fn run() -> Result<()> {
-	runtime::run()
+/// Runs the tray app, opening `project` in the frozen editor once it is up. With
+/// `capture_folder`, runs a single capture that saves into that folder instead, without the tray.
+pub fn run(project: Option<PathBuf>, capture_folder: Option<PathBuf>) -> Result<()> {
+	runtime::run(project, capture_folder)
 }
 
 #[cfg(target_os = "macos")]
@@ -150,6 +348,14 @@ fn begin_coalesced_overlay_user_event_send(pending: &AtomicBool) -> bool {
 mod tests {
 	#[cfg(target_os = "macos")]
 	use std::sync::atomic::AtomicBool;
+	use std::time::{Duration, Instant};
+
+	use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+	use winit::keyboard::ModifiersState;
+
+	use crate::app::capture::OverlayLaunch;
+	use crate::app::prewarm::{self, ModifierHoldDetector, PrewarmHint};
+	use crate::app::supervisor::{self, Heartbeat, OVERLAY_HANG_TIMEOUT, OverlaySupervisor};
 
 	#[cfg(target_os = "macos")]
 	#[test]
@@ -159,4 +365,133 @@ mod tests {
 		assert!(super::begin_coalesced_overlay_user_event_send(&pending));
 		assert!(!super::begin_coalesced_overlay_user_event_send(&pending));
 	}
+
+	#[test]
+	fn overlay_supervisor_restarts_a_crashed_session_once() {
+		let start = Instant::now();
+		let mut supervisor = OverlaySupervisor::default();
+
+		supervisor.begin(OverlayLaunch::Capture, 0, start);
+
+		assert!(supervisor.record_crash(start + Duration::from_secs(5)));
+
+		let Some((OverlayLaunch::Capture, restarts)) = supervisor.take_pending_restart() else {
+			panic!("expected a pending capture restart");
+		};
+
+		supervisor.begin(OverlayLaunch::Capture, restarts, start + Duration::from_secs(6));
+
+		assert!(!supervisor.record_crash(start + Duration::from_secs(20)));
+		assert!(supervisor.take_pending_restart().is_none());
+	}
+
+	#[test]
+	fn overlay_supervisor_does_not_restart_a_session_that_crashes_on_launch() {
+		let start = Instant::now();
+		let mut supervisor = OverlaySupervisor::default();
+
+		supervisor.begin(OverlayLaunch::Magnifier, 0, start);
+
+		assert!(!supervisor.record_crash(start + Duration::from_millis(100)));
+		assert!(supervisor.take_pending_restart().is_none());
+	}
+
+	#[test]
+	fn heartbeat_reports_stalls_to_the_next_beat_and_ignores_suspends() {
+		let start = Instant::now();
+		let second = Duration::from_secs(1);
+		let mut heartbeat = Heartbeat::default();
+
+		assert!(heartbeat.poll(start).is_none());
+
+		heartbeat.begin(start);
+
+		let mut now = start;
+
+		while now < start + OVERLAY_HANG_TIMEOUT {
+			now += second;
+
+			assert!(heartbeat.poll(now).is_none());
+		}
+
+		now += second;
+
+		assert_eq!(heartbeat.poll(now), Some(OVERLAY_HANG_TIMEOUT + second));
+		assert!(heartbeat.poll(now + second).is_none());
+		assert_eq!(heartbeat.beat(now + second * 2), Some(OVERLAY_HANG_TIMEOUT + second));
+		assert!(heartbeat.beat(now + second * 3).is_none());
+
+		// A poll long after the previous one was a system sleep, not a stall.
+		let woke = now + OVERLAY_HANG_TIMEOUT * 4;
+
+		assert!(heartbeat.poll(woke).is_none());
+		assert!(heartbeat.poll(woke + second).is_none());
+
+		heartbeat.end();
+
+		assert!(heartbeat.poll(woke + OVERLAY_HANG_TIMEOUT * 2).is_none());
+	}
+
+	#[test]
+	fn modifier_hold_detector_prewarms_after_the_threshold_and_cancels_on_release() {
+		let start = Instant::now();
+		let chord = ModifiersState::CONTROL | ModifiersState::SHIFT;
+		let mut detector = ModifierHoldDetector::default();
+
+		assert_eq!(detector.update(start, chord, chord), None);
+		assert_eq!(detector.update(start + Duration::from_millis(100), chord, chord), None);
+		assert_eq!(
+			detector.update(start + Duration::from_millis(160), chord, chord),
+			Some(PrewarmHint::Start)
+		);
+		assert_eq!(detector.update(start + Duration::from_millis(400), chord, chord), None);
+		assert_eq!(
+			detector.update(start + Duration::from_millis(450), chord, ModifiersState::CONTROL),
+			Some(PrewarmHint::Cancel)
+		);
+		assert_eq!(
+			detector.update(start + Duration::from_millis(500), chord, ModifiersState::empty()),
+			None
+		);
+	}
+
+	#[test]
+	fn modifier_hold_detector_ignores_partial_chords_and_empty_targets() {
+		let start = Instant::now();
+		let chord = ModifiersState::CONTROL | ModifiersState::SHIFT;
+		let mut detector = ModifierHoldDetector::default();
+
+		for step in 0..10 {
+			let now = start + Duration::from_millis(step * 50);
+
+			assert_eq!(detector.update(now, chord, ModifiersState::SHIFT), None);
+			assert_eq!(
+				detector.update(now, ModifiersState::empty(), ModifiersState::empty()),
+				None
+			);
+		}
+	}
+
+	#[test]
+	fn chord_modifiers_map_hotkey_modifiers_to_winit() {
+		let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyS);
+
+		assert_eq!(
+			prewarm::chord_modifiers(&hotkey),
+			ModifiersState::CONTROL | ModifiersState::SHIFT
+		);
+		assert_eq!(
+			prewarm::chord_modifiers(&HotKey::new(Some(Modifiers::SUPER), Code::KeyS)),
+			ModifiersState::SUPER
+		);
+	}
+
+	#[test]
+	fn catch_panic_returns_the_panic_message() {
+		assert_eq!(supervisor::catch_panic(|| 7), Ok(7));
+		assert_eq!(
+			supervisor::catch_panic(|| -> u32 { panic!("boom {}", 1) }),
+			Err(String::from("boom 1"))
+		);
+	}
 }
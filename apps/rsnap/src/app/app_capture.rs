@@ -0,0 +1,80 @@
+use std::thread;
+
+use crate::app::App;
+use rsnap_overlay::OverlaySession;
+use tray_icon::menu::MenuItem;
+
+impl App {
+	/// Refreshes the tray's Capture App Windows entries from the windows on screen now.
+	pub(super) fn sync_app_capture_menu(&mut self) {
+		let Some(menu) = self.app_capture_menu.as_ref() else {
+			return;
+		};
+		let apps = match rsnap_overlay::list_app_windows() {
+			Ok(apps) => apps,
+			Err(err) => {
+				tracing::debug!(error = %format!("{err:#}"), "Failed to list app windows.");
+
+				Vec::new()
+			},
+		};
+
+		for (item, _) in self.app_capture_menu_items.drain(..) {
+			if let Err(err) = menu.remove(&item) {
+				tracing::warn!(error = ?err, "Failed to remove tray app capture entry.");
+			}
+		}
+
+		for app in apps {
+			let label = match app.windows.len() {
+				1 => app.name.clone(),
+				count => format!("{} ({count} windows)", app.name),
+			};
+			let item = MenuItem::new(label, true, None);
+
+			if let Err(err) = menu.append(&item) {
+				tracing::warn!(error = ?err, "Failed to add tray app capture entry.");
+			}
+
+			self.app_capture_menu_items.push((item, app.pid));
+		}
+
+		menu.set_enabled(!self.app_capture_menu_items.is_empty());
+	}
+
+	/// Captures every window of the app with `pid` on a background thread, so the event loop
+	/// keeps running while the windows encode.
+	pub(super) fn start_app_capture(&self, pid: u32, requested_by: &'static str) {
+		let config = self.overlay_config();
+
+		tracing::info!(pid, requested_by = %requested_by, "App capture requested.");
+
+		let spawned =
+			thread::Builder::new().name(String::from("rsnap-app-capture")).spawn(move || {
+				let apps = match rsnap_overlay::list_app_windows() {
+					Ok(apps) => apps,
+					Err(err) => {
+						tracing::warn!(error = %format!("{err:#}"), "Failed to list app windows.");
+
+						return;
+					},
+				};
+				let Some(app) = apps.into_iter().find(|app| app.pid == pid) else {
+					tracing::warn!(pid, "The app has no visible windows anymore.");
+
+					return;
+				};
+
+				match OverlaySession::capture_app_windows_to_output_dir(&config, &app) {
+					Ok(paths) => {
+						tracing::info!(app = %app.name, count = paths.len(), "App windows saved.");
+					},
+					Err(err) => tracing::warn!(error = %err, "App capture failed."),
+				}
+			});
+
+		if let Err(err) = spawned {
+			tracing::warn!(error = %err, "Failed to start app capture thread.");
+		}
+	}
+}
@@ -1,48 +1,172 @@
+use std::path::PathBuf;
 #[cfg(target_os = "macos")]
 use std::sync::{Arc, atomic::Ordering};
+use std::time::Instant;
 
-use winit::event_loop::ActiveEventLoop;
+use winit::event_loop::{ActiveEventLoop, EventLoopProxy};
 
-use crate::app::App;
 #[cfg(target_os = "macos")]
-use crate::app::scroll_input_macos;
-#[cfg(target_os = "macos")]
-use crate::app::{self, UserEvent};
-use rsnap_overlay::{HudAnchor, OverlayConfig, OverlayControl, OverlayExit, OverlaySession};
+use crate::app::{self, scroll_input_macos};
+use crate::app::{App, UserEvent, supervisor};
+use crate::capture_feedback::{self, CapturedImage};
+use crate::capture_popover::PopoverSource;
+use crate::latency::{self, LatencyRecord};
+use crate::settings::CaptureRegion;
+#[cfg(feature = "telemetry")]
+use crate::telemetry::{self, ErrorCategory, UsageEvent};
+use rsnap_overlay::{
+	CaptureLatency, DoneAction, HudAnchor, MonitorRectPoints, OverlayConfig, OverlayControl,
+	OverlayExit, OverlayProgress, OverlaySession, ProgressStage,
+};
+
+/// An overlay start waiting for its session's GPU device, kept with everything it was requested
+/// with.
+pub(super) struct PendingOverlayLaunch {
+	session: OverlaySession,
+	requested_by: &'static str,
+	launch: OverlayLaunch,
+	restarts: u32,
+	requested_at: Option<Instant>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum OverlayLaunch {
+	Capture,
+	Magnifier,
+	PickColor,
+	PickDisplay,
+	IdentifyDisplays,
+	ActiveWindow,
+	RepeatRegion,
+	Preset(MonitorRectPoints),
+	IntervalCapture,
+	/// Reopens a saved `.rsnap` project in the frozen editor.
+	Project(PathBuf),
+	/// Opens an image, such as an earlier capture, in the frozen editor.
+	Image(PathBuf),
+}
+impl OverlayLaunch {
+	/// Captures that finish without showing the overlay, so nothing on screen confirms them.
+	const fn is_background(&self) -> bool {
+		matches!(self, Self::ActiveWindow | Self::RepeatRegion | Self::Preset(_))
+	}
+}
+#[cfg(feature = "telemetry")]
+impl OverlayLaunch {
+	const fn usage_mode(&self) -> &'static str {
+		match self {
+			Self::Capture => "capture",
+			Self::Magnifier => "magnifier",
+			Self::PickColor => "pick_color",
+			Self::PickDisplay => "pick_display",
+			Self::IdentifyDisplays => "identify_displays",
+			Self::ActiveWindow => "active_window",
+			Self::RepeatRegion => "repeat_region",
+			Self::Preset(_) => "preset",
+			Self::IntervalCapture => "interval_capture",
+			Self::Project(_) => "project",
+			Self::Image(_) => "image",
+		}
+	}
+}
 
 impl App {
-	fn overlay_config(&self) -> OverlayConfig {
-		let glass = self.settings.hud_glass_enabled;
-		let hud_opacity = self.settings.hud_opacity.clamp(0.0, 1.0);
-		let hud_blur = self.settings.hud_blur.clamp(0.0, 1.0);
-		let hud_tint = self.settings.hud_tint.clamp(0.0, 1.0);
-		let hud_tint_hue = self.settings.hud_tint_hue;
-		let loupe_sample_side_px = self.settings.loupe_sample_size.side_px();
+	pub(super) fn overlay_config(&self) -> OverlayConfig {
+		let settings = self.settings.effective();
+		let glass = settings.hud_glass_enabled;
+		let hud_opacity = settings.hud_opacity.clamp(0.0, 1.0);
+		let hud_blur = settings.hud_blur.clamp(0.0, 1.0);
+		let hud_tint = settings.hud_tint.clamp(0.0, 1.0);
+		let hud_tint_hue = settings.hud_tint_hue;
+		let loupe_sample_side_px = settings.loupe_sample_size.side_px();
 		let hud_opaque = !glass || hud_opacity >= 0.999;
 		let show_hud_blur = glass && hud_blur > 0.0 && !hud_opaque;
 
 		OverlayConfig {
 			hud_anchor: HudAnchor::Cursor,
-			show_alt_hint_keycap: self.settings.show_alt_hint_keycap,
-			selection_particles: self.settings.selection_particles,
-			selection_flow_stroke_width_px: self
-				.settings
-				.selection_flow_stroke_width_px
-				.clamp(1.0, 8.0),
+			show_alt_hint_keycap: settings.show_alt_hint_keycap,
+			selection_particles: settings.selection_particles,
+			selection_flow_stroke_width_px: settings.selection_flow_stroke_width_px.clamp(1.0, 8.0),
+			selection_flow_rgb: settings.selection_flow_rgb,
+			selection_flow_animated: settings.selection_flow_animated,
 			show_hud_blur,
 			hud_opaque,
 			hud_opacity,
 			hud_fog_amount: hud_blur,
 			hud_milk_amount: hud_tint,
 			hud_tint_hue,
-			alt_activation: Self::map_alt_activation(self.settings.alt_activation),
-			toolbar_placement: self.settings.toolbar_placement,
+			hud_blur_dither: settings.hud_blur_dither,
+			alt_activation: Self::map_alt_activation(settings.alt_activation),
+			toolbar_placement: settings.toolbar_placement,
 			loupe_sample_side_px,
-			theme_mode: self.settings.theme_mode,
-			output_dir: self.settings.output_dir.clone(),
-			output_filename_prefix: self.settings.output_filename_prefix.clone(),
-			output_naming: self.settings.output_naming,
-			window_capture_alpha_mode: self.settings.window_capture_alpha_mode,
+			loupe_row_column_highlight: settings.loupe_row_column_highlight,
+			theme_mode: settings.theme_mode,
+			output_dir: self.capture_folder.clone().unwrap_or(settings.output_dir),
+			output_filename_prefix: settings.output_filename_prefix,
+			output_naming: settings.output_naming,
+			output_filename_template: settings.output_filename_template,
+			export_format: settings.export_format,
+			export_quality: settings.export_quality,
+			clipboard_format: settings.clipboard_format,
+			window_capture_alpha_mode: settings.window_capture_alpha_mode,
+			window_shadow_mode: settings.window_shadow_mode,
+			hud_unit: settings.hud_unit,
+			magnifier_zoom: settings.magnifier_zoom,
+			keypad_quick_actions: std::array::from_fn(|slot| {
+				settings.keypad_quick_actions.get(slot).copied().unwrap_or_default()
+			}),
+			sample_composited_output: settings.sample_composited_output,
+			show_display_adjusted_color: settings.show_display_adjusted_color,
+			include_cursor: settings.include_cursor,
+			capture_mode: settings.capture_mode,
+			capture_delay_secs: settings.capture_delay_secs.min(30),
+			capture_flash: settings.capture_flash,
+			histogram_panel: settings.histogram_panel,
+			copy_export_summary: settings.copy_export_summary,
+			alt_text_command: Some(settings.alt_text_command.trim())
+				.filter(|command| settings.alt_text_enabled && !command.is_empty())
+				.map(String::from),
+			export_backdrop: settings.export_backdrop,
+			export_scaling: settings.export_scaling,
+			watermark: settings.watermark.clone(),
+			retina_pair: settings.retina_pair,
+			geometry_sidecar: settings.geometry_sidecar,
+			annotation_sidecar: settings.annotation_sidecar,
+			redact_ocr_command: Some(settings.redact_ocr_command.trim())
+				.filter(|command| !command.is_empty())
+				.map(String::from),
+			multi_region_collage: settings.multi_region_collage,
+			export_preview: settings.export_preview,
+			frozen_dim_opacity: settings.frozen_dim_opacity.clamp(0.0, 1.0),
+			frozen_dim_vignette: settings.frozen_dim_vignette.clamp(0.0, 1.0),
+			frozen_dim_noise: settings.frozen_dim_noise.clamp(0.0, 1.0),
+			open_with_command: Some(settings.open_with_command.trim())
+				.filter(|command| !command.is_empty())
+				.map(String::from),
+			done_actions: if self.capture_folder.is_some() {
+				vec![DoneAction::Save]
+			} else {
+				settings.done_actions
+			},
+			capture_backend: settings.capture_backend,
+			reduce_motion: settings.reduce_motion,
+			edge_dead_zones: settings.edge_dead_zones,
+			composition_guides: settings.composition_guides,
+			selection_snap_threshold_px: settings.selection_snap_threshold_px.min(64),
+			resident: settings.resident_overlay,
+			image_cache_budget_mb: settings.image_cache_budget_mb,
+			sampling_fps_cap: settings.sampling_fps_cap,
+			gpu_power_preference: settings.gpu_power_preference,
+			window_list_ttl_ms: settings.window_list_ttl_ms,
+			live_background_ttl_ms: settings.live_background_ttl_ms,
+			highlighter_rgb: settings.highlighter_rgb,
+			highlighter_width_px: settings.highlighter_width_px,
+			recent_capture_regions: self
+				.settings
+				.recent_capture_regions
+				.iter()
+				.map(|&region| region.into())
+				.collect(),
 		}
 	}
 
@@ -69,21 +193,142 @@ impl App {
 		event_loop: &ActiveEventLoop,
 		requested_by: &'static str,
 	) {
-		if self.overlay_session.is_some() {
-			tracing::info!(
-				requested_by = %requested_by,
-				"Capture already active; ignoring additional start request."
-			);
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::Capture);
+	}
+
+	pub(super) fn start_color_picker_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::PickColor);
+	}
+
+	pub(super) fn start_display_picker_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::PickDisplay);
+	}
+
+	/// Flashes each display's number, name, resolution and scale factor on the display itself.
+	pub(super) fn start_identify_displays_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::IdentifyDisplays);
+	}
+
+	/// Captures the focused window straight through the Done pipeline, with no overlay shown.
+	pub(super) fn start_active_window_capture(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::ActiveWindow);
+	}
+
+	/// Captures the region of the previous capture again, with no overlay shown.
+	pub(super) fn start_repeat_capture(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::RepeatRegion);
+	}
+
+	/// Captures the named fixed region at `index` in the capture presets, with no overlay shown.
+	pub(super) fn start_preset_capture(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		index: usize,
+		requested_by: &'static str,
+	) {
+		let Some(preset) = self.settings.capture_presets.get(index) else {
+			return;
+		};
+		let region = MonitorRectPoints::from(preset.region);
+
+		tracing::info!(preset = %preset.name, "Capture preset requested.");
+
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::Preset(region));
+	}
+
+	pub(super) fn start_interval_capture_picker_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::IntervalCapture);
+	}
+
+	pub(super) fn open_project_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		path: PathBuf,
+		requested_by: &'static str,
+	) {
+		tracing::info!(path = %path.display(), "Project open requested.");
+
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::Project(path));
+	}
+
+	pub(super) fn open_image_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		path: PathBuf,
+		requested_by: &'static str,
+	) {
+		tracing::info!(path = %path.display(), "Image annotation requested.");
+
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::Image(path));
+	}
+
+	pub(super) fn toggle_magnifier_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+	) {
+		if let Some(session) = self.overlay_session.as_ref() {
+			if session.is_magnifier() {
+				self.end_overlay_session(OverlayExit::Cancelled);
+			} else {
+				tracing::info!(
+					requested_by = %requested_by,
+					"Capture already active; ignoring magnifier request."
+				);
+			}
 
 			return;
 		}
 
-		let mut overlay_session = OverlaySession::with_config(self.overlay_config());
+		self.start_overlay_session(event_loop, requested_by, OverlayLaunch::Magnifier);
+	}
 
-		#[cfg(target_os = "macos")]
-		self.scroll_input_shared_state.clear();
-		#[cfg(target_os = "macos")]
-		self.scroll_input_shared_state.set_enabled(true);
+	fn start_overlay_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+		launch: OverlayLaunch,
+	) {
+		self.launch_overlay_session(event_loop, requested_by, launch, 0);
+	}
+
+	/// Relaunches a session the supervisor scheduled after a crash.
+	pub(super) fn restart_crashed_overlay_session(&mut self, event_loop: &ActiveEventLoop) {
+		let Some((launch, restarts)) = self.overlay_supervisor.take_pending_restart() else {
+			return;
+		};
+
+		self.launch_overlay_session(event_loop, "supervisor_restart", launch, restarts);
+	}
+
+	/// Creates a session with the app's wakers installed, ready to prewarm or start.
+	pub(super) fn new_overlay_session(&self) -> OverlaySession {
+		#[cfg_attr(not(target_os = "macos"), expect(unused_mut))]
+		let mut overlay_session = OverlaySession::with_config(self.overlay_config());
 
 		#[cfg(target_os = "macos")]
 		overlay_session.set_scroll_frame_waker(Arc::new({
@@ -114,18 +359,139 @@ impl App {
 			move |after_seq, through| shared_state.replay_after_seq_through(after_seq, through)
 		}));
 
-		match overlay_session.start(event_loop) {
+		overlay_session
+	}
+
+	fn launch_overlay_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		requested_by: &'static str,
+		launch: OverlayLaunch,
+		restarts: u32,
+	) {
+		let requested_at = self.overlay_requested_at.take();
+
+		if self.overlay_session.is_some() || self.pending_overlay_launch.is_some() {
+			tracing::info!(
+				requested_by = %requested_by,
+				"Capture already active; ignoring additional start request."
+			);
+
+			return;
+		}
+
+		let session = match self.prewarmed_overlay.take() {
+			Some(mut session) => {
+				session.set_config(self.overlay_config());
+
+				session
+			},
+			None => self.new_overlay_session(),
+		};
+
+		self.try_launch_overlay_session(
+			event_loop,
+			PendingOverlayLaunch { session, requested_by, launch, restarts, requested_at },
+		);
+	}
+
+	/// Retries a launch that was waiting for the GPU device; polled from `about_to_wait`.
+	pub(super) fn resume_pending_overlay_launch(&mut self, event_loop: &ActiveEventLoop) {
+		if let Some(pending) = self.pending_overlay_launch.take() {
+			self.try_launch_overlay_session(event_loop, pending);
+		}
+	}
+
+	/// Starts the launch once the session's GPU device is ready. While the device is still
+	/// initializing, the launch is parked in [`App::pending_overlay_launch`] so the event loop
+	/// keeps running; background captures draw nothing and start right away.
+	fn try_launch_overlay_session(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		pending: PendingOverlayLaunch,
+	) {
+		let PendingOverlayLaunch {
+			session: mut overlay_session,
+			requested_by,
+			launch,
+			restarts,
+			requested_at,
+		} = pending;
+
+		if !launch.is_background() {
+			match overlay_session.poll_gpu() {
+				Ok(true) => {},
+				Ok(false) => {
+					self.pending_overlay_launch = Some(PendingOverlayLaunch {
+						session: overlay_session,
+						requested_by,
+						launch,
+						restarts,
+						requested_at,
+					});
+
+					return;
+				},
+				Err(err) => {
+					tracing::warn!(
+						error = %err,
+						requested_by = %requested_by,
+						"Failed to start overlay session."
+					);
+
+					return;
+				},
+			}
+		}
+
+		#[cfg(target_os = "macos")]
+		self.scroll_input_shared_state.clear();
+		#[cfg(target_os = "macos")]
+		self.scroll_input_shared_state.set_enabled(true);
+
+		let last_region = self.settings.last_capture_region().map(MonitorRectPoints::from);
+		let started = supervisor::catch_panic(|| match &launch {
+			OverlayLaunch::Capture => overlay_session.start_capture(event_loop),
+			OverlayLaunch::Magnifier => overlay_session.start_magnifier(event_loop),
+			OverlayLaunch::PickColor => overlay_session.start_color_picker(event_loop),
+			OverlayLaunch::PickDisplay => overlay_session.start_display_picker(event_loop),
+			OverlayLaunch::IdentifyDisplays => overlay_session.start_display_identify(event_loop),
+			OverlayLaunch::ActiveWindow => overlay_session.start_focused_window_capture(),
+			OverlayLaunch::RepeatRegion => match last_region {
+				Some(region) => overlay_session.start_region_capture(region),
+				None => Err(String::from("No earlier capture to repeat")),
+			},
+			OverlayLaunch::Preset(region) => overlay_session.start_region_capture(*region),
+			OverlayLaunch::IntervalCapture => {
+				overlay_session.start_interval_capture_picker(event_loop)
+			},
+			OverlayLaunch::Project(path) => overlay_session.start_project(event_loop, path),
+			OverlayLaunch::Image(path) => overlay_session.start_image(event_loop, path),
+		})
+		.unwrap_or_else(|message| Err(format!("Overlay crashed while starting: {message}")));
+
+		match started {
 			Ok(()) => {
 				#[cfg(target_os = "macos")]
 				self.ensure_scroll_input_observer_started();
 
+				if let Some(requested_at) = requested_at {
+					overlay_session.set_capture_requested_at(requested_at);
+				}
+
 				tracing::info!(
 					requested_by = %requested_by,
 					hotkey = %self.capture_key_label(),
+					launch = ?launch,
+					restarts,
 					"Capture overlay started."
 				);
 
 				self.overlay_session = Some(overlay_session);
+				self.overlay_supervisor.begin(launch.clone(), restarts, Instant::now());
+
+				#[cfg(feature = "telemetry")]
+				self.record_usage(UsageEvent::Mode(launch.usage_mode()));
 			},
 			Err(err) => {
 				#[cfg(target_os = "macos")]
@@ -143,17 +509,51 @@ impl App {
 		}
 	}
 
+	#[cfg(feature = "telemetry")]
+	fn record_usage(&self, event: UsageEvent) {
+		if self.settings.telemetry_enabled {
+			telemetry::record(event);
+		}
+	}
+
 	pub(super) fn end_overlay_session(&mut self, exit: OverlayExit) {
-		let Some(_session) = self.overlay_session.take() else {
+		let Some(session) = self.overlay_session.take() else {
 			return;
 		};
 
+		let launch = self.overlay_supervisor.end();
+
+		self.clear_overlay_progress();
+		Self::record_capture_latency(&exit, &session.capture_latency());
+		self.persist_session_state(&session, &exit);
+		self.confirm_capture(launch.as_ref(), &exit);
+
+		let recyclable = !matches!(exit, OverlayExit::Error(_)) && !session.is_active();
+
 		#[cfg(target_os = "macos")]
 		{
 			self.scroll_input_shared_state.set_enabled(false);
 			self.scroll_input_shared_state.clear();
 		}
 
+		#[cfg(feature = "telemetry")]
+		match &exit {
+			OverlayExit::Cancelled => {},
+			OverlayExit::PngBytes(_) => self.record_usage(UsageEvent::Export("png_clipboard")),
+			OverlayExit::Saved(_) => self.record_usage(UsageEvent::Export("png_file")),
+			OverlayExit::OpenedWith(_) => self.record_usage(UsageEvent::Export("open_with")),
+			OverlayExit::Shared(_) => self.record_usage(UsageEvent::Export("share")),
+			OverlayExit::ColorCopied(_) => self.record_usage(UsageEvent::Export("color")),
+			OverlayExit::WatchRegion { .. } => self.record_usage(UsageEvent::Export("watch")),
+			OverlayExit::IntervalCapture { .. } => {
+				self.record_usage(UsageEvent::Export("interval"));
+			},
+			OverlayExit::ThemePair { .. } => self.record_usage(UsageEvent::Export("theme_pair")),
+			OverlayExit::Error(message) => {
+				self.record_usage(UsageEvent::Error(ErrorCategory::classify(message)));
+			},
+		}
+
 		match exit {
 			OverlayExit::Cancelled => tracing::info!("Capture cancelled."),
 			OverlayExit::PngBytes(png_bytes) => {
@@ -162,10 +562,172 @@ impl App {
 			OverlayExit::Saved(path) => {
 				tracing::info!(path = %path.display(), "Capture saved to file.");
 			},
+			OverlayExit::OpenedWith(path) => {
+				tracing::info!(path = %path.display(), "Capture opened in external editor.");
+			},
+			OverlayExit::Shared(path) => {
+				tracing::info!(path = %path.display(), "Capture handed to the share menu.");
+			},
+			OverlayExit::ColorCopied(color) => {
+				tracing::info!(color = %color, "Color copied to clipboard.");
+			},
+			OverlayExit::WatchRegion { monitor, rect_px } => {
+				self.start_region_watch(monitor, rect_px);
+			},
+			OverlayExit::IntervalCapture { monitor, rect_px } => {
+				self.start_interval_capture(monitor, rect_px);
+			},
+			OverlayExit::ThemePair { monitor, rect_px } => {
+				self.start_theme_pair_capture(monitor, rect_px);
+			},
 			OverlayExit::Error(message) => tracing::warn!(error = %message, "Capture failed."),
 		};
 
 		tracing::info!("Capture overlay ended.");
+
+		if recyclable && self.prewarmed_overlay.is_none() && self.resident_overlay_wanted() {
+			self.prewarmed_overlay = Some(session);
+		}
+
+		self.ensure_resident_overlay();
+	}
+
+	/// Plays the shutter sound and posts the notification the settings ask for once an export
+	/// finishes. Only copied and saved captures get a notification; an editor or share menu
+	/// already shows where the others went. Background captures have their own notification
+	/// toggle and also offer Delete and Annotate. When the capture popover is on, it replaces
+	/// the notification after interactive captures.
+	fn confirm_capture(&mut self, launch: Option<&OverlayLaunch>, exit: &OverlayExit) {
+		let exported = matches!(
+			exit,
+			OverlayExit::PngBytes(_)
+				| OverlayExit::Saved(_)
+				| OverlayExit::OpenedWith(_)
+				| OverlayExit::Shared(_)
+		);
+
+		if !exported {
+			return;
+		}
+		if let OverlayExit::PngBytes(png_bytes) = exit {
+			self.note_own_clipboard_write(png_bytes);
+		}
+		if self.settings.capture_sound {
+			capture_feedback::play_shutter_sound();
+		}
+
+		let background = launch.is_some_and(OverlayLaunch::is_background);
+
+		// `rsnap capture-to` exits as soon as the overlay closes, so it never shows the popover.
+		if !background && self.settings.capture_popover && self.capture_folder.is_none() {
+			self.pending_capture_popover = match exit {
+				OverlayExit::PngBytes(png_bytes) => Some(CapturedImage::Copied(png_bytes.clone())),
+				OverlayExit::Saved(path) => Some(CapturedImage::Saved(path.clone())),
+				_ => None,
+			}
+			.map(|image| (image, PopoverSource::Capture));
+
+			if self.pending_capture_popover.is_some() {
+				return;
+			}
+		}
+
+		let notify = if background {
+			self.settings.background_capture_notification
+		} else {
+			self.settings.capture_notification
+		};
+
+		if !notify {
+			return;
+		}
+
+		let (image, actions) = match exit {
+			OverlayExit::PngBytes(png_bytes) => (
+				CapturedImage::Copied(png_bytes.clone()),
+				if background {
+					capture_feedback::COPIED_BACKGROUND_ACTIONS
+				} else {
+					capture_feedback::INTERACTIVE_ACTIONS
+				},
+			),
+			OverlayExit::Saved(path) => (
+				CapturedImage::Saved(path.clone()),
+				if background {
+					capture_feedback::SAVED_BACKGROUND_ACTIONS
+				} else {
+					capture_feedback::INTERACTIVE_ACTIONS
+				},
+			),
+			_ => return,
+		};
+		let text = image.describe();
+
+		capture_feedback::spawn_capture_notification(
+			image,
+			text,
+			actions,
+			annotate_in_editor(self.event_proxy.clone()),
+		);
+	}
+
+	/// Keeps the `G` toggle and the exported region from the session that just ended for the
+	/// next one.
+	fn persist_session_state(&mut self, session: &OverlaySession, exit: &OverlayExit) {
+		let guides = session.composition_guides();
+		let exported = matches!(
+			exit,
+			OverlayExit::PngBytes(_)
+				| OverlayExit::Saved(_)
+				| OverlayExit::OpenedWith(_)
+				| OverlayExit::Shared(_)
+		);
+		let region = session.last_capture_region().filter(|_| exported).map(CaptureRegion::from);
+
+		if self.settings.composition_guides == guides
+			&& region.is_none_or(|region| self.settings.last_capture_region() == Some(region))
+		{
+			return;
+		}
+
+		self.settings.composition_guides = guides;
+
+		if let Some(region) = region {
+			self.settings.remember_capture_region(region);
+		}
+		if let Err(err) = self.settings.save() {
+			tracing::warn!(error = ?err, "Failed to save settings.");
+		}
+	}
+
+	fn record_capture_latency(exit: &OverlayExit, capture_latency: &CaptureLatency) {
+		let exit = match exit {
+			OverlayExit::Cancelled => "cancelled",
+			OverlayExit::PngBytes(_) => "png_clipboard",
+			OverlayExit::Saved(_) => "png_file",
+			OverlayExit::OpenedWith(_) => "open_with",
+			OverlayExit::Shared(_) => "share",
+			OverlayExit::ColorCopied(_) => "color",
+			OverlayExit::WatchRegion { .. } => "watch",
+			OverlayExit::IntervalCapture { .. } => "interval",
+			OverlayExit::ThemePair { .. } => "theme_pair",
+			OverlayExit::Error(_) => "error",
+		};
+		let record = LatencyRecord::new(exit, capture_latency);
+		let [overlay_visible_ms, freeze_complete_ms, encode_complete_ms, clipboard_done_ms] =
+			record.stages.map(|stage| stage.map(|stage| stage.as_secs_f64() * 1_000.0));
+
+		tracing::info!(
+			op = "app.capture_latency",
+			exit,
+			overlay_visible_ms,
+			freeze_complete_ms,
+			encode_complete_ms,
+			clipboard_done_ms,
+			"Capture latency recorded."
+		);
+
+		latency::record(record);
 	}
 
 	#[cfg(target_os = "macos")]
@@ -181,6 +743,87 @@ impl App {
 		self.scroll_input_observer_started = true;
 	}
 
+	/// Runs one overlay callback under the supervisor and applies the resulting control.
+	pub(super) fn drive_overlay_session(
+		&mut self,
+		f: impl FnOnce(&mut OverlaySession) -> OverlayControl,
+	) {
+		let Some(session) = self.overlay_session.as_mut() else {
+			return;
+		};
+		let control = match supervisor::catch_panic(|| f(session)) {
+			Ok(control) => control,
+			Err(message) => {
+				let restart = self.overlay_supervisor.record_crash(Instant::now());
+
+				tracing::error!(panic = %message, restart, "Overlay session panicked.");
+
+				OverlayControl::Exit(OverlayExit::Error(format!("Overlay crashed: {message}")))
+			},
+		};
+		let progress = self.overlay_session.as_mut().map(OverlaySession::take_progress);
+
+		self.report_overlay_progress(progress.unwrap_or_default());
+		self.handle_overlay_control(control);
+	}
+
+	/// Logs the export progress the session reported and shows the latest step in the tray
+	/// tooltip until the export finishes.
+	fn report_overlay_progress(&mut self, progress: Vec<OverlayProgress>) {
+		for event in &progress {
+			tracing::debug!(progress = %event.to_json_line(), "Overlay export progress.");
+		}
+
+		let Some(latest) = progress.last().copied() else {
+			return;
+		};
+
+		if latest.pct >= 100 {
+			self.clear_overlay_progress();
+
+			return;
+		}
+
+		self.overlay_progress = Some(latest);
+
+		let tooltip = match (latest.stage, latest.frames) {
+			(ProgressStage::Stitching, Some(frames)) => {
+				format!("rsnap — stitching, {frames} frames merged")
+			},
+			(ProgressStage::Stitching, None) => format!("rsnap — stitching {}%", latest.pct),
+			(ProgressStage::Composing, _) => format!("rsnap — composing {}%", latest.pct),
+			(ProgressStage::Encoding, _) => format!("rsnap — encoding {}%", latest.pct),
+		};
+
+		if let Some(tray_icon) = self.tray_icon.as_ref()
+			&& let Err(err) = tray_icon.set_tooltip(Some(tooltip))
+		{
+			tracing::warn!(error = ?err, "Failed to update tray tooltip.");
+		}
+	}
+
+	/// Restores the regular tray tooltip once an export stops reporting progress.
+	fn clear_overlay_progress(&mut self) {
+		if self.overlay_progress.take().is_some() {
+			self.sync_tray_hotkey_pause_state();
+		}
+	}
+
+	/// Sends the watchdog heartbeat and ends the session when the watchdog caught the event loop
+	/// stalled past the hang timeout.
+	pub(super) fn check_overlay_session_hang(&mut self) {
+		let Some(gap) = self.overlay_supervisor.tick(Instant::now()) else {
+			return;
+		};
+
+		tracing::error!(gap_ms = gap.as_millis(), "Overlay session stopped responding.");
+
+		self.end_overlay_session(OverlayExit::Error(format!(
+			"Overlay stopped responding for {} s",
+			gap.as_secs()
+		)));
+	}
+
 	pub(super) fn handle_overlay_control(&mut self, control: OverlayControl) {
 		let OverlayControl::Exit(exit) = control else {
 			return;
@@ -189,3 +832,15 @@ impl App {
 		self.end_overlay_session(exit);
 	}
 }
+
+/// Handler for a notification's Annotate button: asks the event loop to open the file in the
+/// editor.
+pub(super) fn annotate_in_editor(
+	proxy: Option<EventLoopProxy<UserEvent>>,
+) -> impl FnOnce(PathBuf) + Send + 'static {
+	move |path| {
+		if let Some(proxy) = proxy {
+			let _ = proxy.send_event(UserEvent::Annotate(path));
+		}
+	}
+}
@@ -0,0 +1,128 @@
+//! Opening, timing out and acting on the post-capture and clipboard popovers.
+
+use std::time::Instant;
+
+use winit::event::WindowEvent;
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use crate::app::App;
+use crate::capture_feedback::CapturedImage;
+use crate::capture_popover::{CapturePopover, PopoverChoice, PopoverSource};
+
+impl App {
+	/// Shows the image queued by the last export or clipboard change, timing out any popover
+	/// still on screen that was not pinned.
+	pub(super) fn open_pending_capture_popover(&mut self, event_loop: &ActiveEventLoop) {
+		let Some((image, source)) = self.pending_capture_popover.take() else {
+			return;
+		};
+		let (pinned, unpinned) =
+			self.capture_popovers.drain(..).partition(CapturePopover::is_pinned);
+
+		self.capture_popovers = pinned;
+
+		for popover in unpinned {
+			self.finish_capture_popover(event_loop, popover, PopoverChoice::Expired);
+		}
+
+		match CapturePopover::open(event_loop, image, source) {
+			Ok(popover) => self.capture_popovers.push(popover),
+			Err(err) => tracing::warn!(error = ?err, "Failed to open the capture popover."),
+		}
+	}
+
+	/// The earliest time a popover on screen times out.
+	pub(super) fn capture_popover_deadline(&self) -> Option<Instant> {
+		self.capture_popovers.iter().filter_map(CapturePopover::deadline).min()
+	}
+
+	/// Routes `event` to the popover owning `window_id`; returns `false` when none does.
+	pub(super) fn handle_capture_popover_event(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		window_id: WindowId,
+		event: &WindowEvent,
+	) -> bool {
+		let Some(index) =
+			self.capture_popovers.iter().position(|popover| popover.window_id() == window_id)
+		else {
+			return false;
+		};
+		let popover = &mut self.capture_popovers[index];
+		let choice = match event {
+			WindowEvent::RedrawRequested => popover.draw().unwrap_or_else(|err| {
+				tracing::warn!(error = ?err, "Capture popover draw failed.");
+
+				None
+			}),
+			_ => popover.handle_window_event(event),
+		};
+		let pin_command = popover.take_pin_command();
+
+		if let Some(choice) = choice {
+			let popover = self.capture_popovers.remove(index);
+
+			self.finish_capture_popover(event_loop, popover, choice);
+		}
+		if let Some(command) = pin_command {
+			self.apply_pin_command(event_loop, command);
+		}
+
+		true
+	}
+
+	pub(super) fn tick_capture_popovers(&mut self, event_loop: &ActiveEventLoop) {
+		let now = Instant::now();
+		let (expired, open) = self
+			.capture_popovers
+			.drain(..)
+			.partition(|popover| popover.deadline().is_some_and(|deadline| now >= deadline));
+
+		self.capture_popovers = open;
+
+		for popover in expired {
+			self.finish_capture_popover(event_loop, popover, PopoverChoice::Expired);
+		}
+	}
+
+	/// Closes the popover and carries out `choice`. A copied capture that timed out is saved,
+	/// so nothing is lost when the popover is ignored; a clipboard image is still on the
+	/// clipboard, so it is not.
+	pub(super) fn finish_capture_popover(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		popover: CapturePopover,
+		choice: PopoverChoice,
+	) {
+		let source = popover.source();
+
+		tracing::info!(op = "capture_popover.finish", ?choice, ?source, "Capture popover closed.");
+
+		let image = popover.into_image();
+		let result = match (choice, &image) {
+			(PopoverChoice::Expired, _) if source == PopoverSource::Clipboard => Ok(()),
+			(PopoverChoice::Annotate, _) => image
+				.file()
+				.map(|path| self.open_image_session(event_loop, path, "capture_popover")),
+			(PopoverChoice::Copy, _) => image.png_bytes().and_then(|png_bytes| {
+				self.note_own_clipboard_write(&png_bytes);
+
+				rsnap_overlay::copy_png_to_clipboard(&png_bytes)
+			}),
+			(PopoverChoice::Save | PopoverChoice::Expired, CapturedImage::Copied(png_bytes)) => {
+				rsnap_overlay::save_png_to_output_dir(png_bytes, &self.overlay_config()).map(
+					|path| {
+						tracing::info!(path = %path.display(), "Capture saved from the popover.");
+					},
+				)
+			},
+			(PopoverChoice::Save | PopoverChoice::Expired, CapturedImage::Saved(_))
+			| (PopoverChoice::Dismiss, _) => Ok(()),
+		};
+
+		if let Err(err) = result {
+			tracing::warn!(error = ?err, "Capture popover action failed.");
+		}
+	}
+}
@@ -0,0 +1,66 @@
+//! Offering images copied in other apps in the capture popover.
+
+use std::time::{Duration, Instant};
+
+use crate::app::{App, UserEvent};
+use crate::capture_feedback::CapturedImage;
+use crate::capture_popover::PopoverSource;
+use rsnap_overlay::ClipboardWatch;
+
+/// How long after rsnap copies an image the clipboard watch ignores new images, for clipboards
+/// that hand back different pixels than rsnap wrote.
+const OWN_CLIPBOARD_WRITE_GRACE: Duration = Duration::from_secs(3);
+
+impl App {
+	/// Starts or stops the clipboard watch to match the setting; it stays off while paused.
+	pub(super) fn sync_clipboard_watch(&mut self) {
+		if !self.settings.clipboard_watch || self.paused {
+			self.clipboard_watch = None;
+
+			return;
+		}
+		if self.clipboard_watch.is_some() {
+			return;
+		}
+
+		let Some(proxy) = self.event_proxy.clone() else {
+			return;
+		};
+
+		match ClipboardWatch::start(move |png_bytes, fingerprint| {
+			let _ = proxy.send_event(UserEvent::ClipboardImage(png_bytes, fingerprint));
+		}) {
+			Ok(watch) => self.clipboard_watch = Some(watch),
+			Err(err) => tracing::warn!(error = ?err, "Failed to start the clipboard watch."),
+		}
+	}
+
+	/// Remembers an image rsnap is about to put on the clipboard, so the watch does not offer it
+	/// back.
+	pub(super) fn note_own_clipboard_write(&mut self, png_bytes: &[u8]) {
+		self.last_clipboard_write = Some(Instant::now());
+		// Decoding only pays off while the watch runs; a watch started later sees it as current.
+		self.own_clipboard_image =
+			self.clipboard_watch.as_ref().and_then(|_| ClipboardWatch::fingerprint_png(png_bytes));
+	}
+
+	pub(super) fn handle_clipboard_image(&mut self, png_bytes: Vec<u8>, fingerprint: u64) {
+		let own_write = self.own_clipboard_image == Some(fingerprint)
+			|| self
+				.last_clipboard_write
+				.is_some_and(|written_at| written_at.elapsed() < OWN_CLIPBOARD_WRITE_GRACE);
+
+		if self.clipboard_watch.is_none() || self.overlay_session.is_some() || own_write {
+			return;
+		}
+
+		tracing::info!(
+			op = "clipboard_watch.image",
+			bytes = png_bytes.len(),
+			"Offering a copied image."
+		);
+
+		self.pending_capture_popover =
+			Some((CapturedImage::Copied(png_bytes), PopoverSource::Clipboard));
+	}
+}
@@ -0,0 +1,150 @@
+//! Pauses the global hotkeys while a full-screen application, typically a game, has focus, so
+//! shortcuts reach the game and never pull the overlay over it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use global_hotkey::hotkey::HotKey;
+use winit::event_loop::EventLoopProxy;
+
+use crate::app::{App, UserEvent};
+
+/// How often the watcher checks the focused application.
+const FULLSCREEN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls for a full-screen foreground application while `enabled` is set and reports changes.
+pub(super) fn spawn_fullscreen_watcher(proxy: EventLoopProxy<UserEvent>, enabled: Arc<AtomicBool>) {
+	let spawned =
+		thread::Builder::new().name(String::from("rsnap-fullscreen-watch")).spawn(move || {
+			let mut last_app = None;
+
+			loop {
+				thread::sleep(FULLSCREEN_POLL_INTERVAL);
+
+				let app = if enabled.load(Ordering::Relaxed) {
+					rsnap_overlay::fullscreen_foreground_app()
+				} else {
+					None
+				};
+
+				if app == last_app {
+					continue;
+				}
+				if proxy.send_event(UserEvent::FullscreenApp(app.clone())).is_err() {
+					return;
+				}
+
+				last_app = app;
+			}
+		});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start full-screen watcher.");
+	}
+}
+
+impl App {
+	/// Applies the pause setting to the watcher and re-evaluates the last detected application.
+	/// The watcher stays idle while rsnap itself is paused.
+	pub(super) fn sync_fullscreen_pause(&mut self) {
+		self.fullscreen_watch_enabled
+			.store(self.settings.pause_hotkeys_in_fullscreen && !self.paused, Ordering::Relaxed);
+		self.handle_fullscreen_app(self.fullscreen_app.clone());
+	}
+
+	pub(super) fn handle_fullscreen_app(&mut self, app: Option<String>) {
+		let pause_for = app.clone().filter(|name| {
+			self.settings.pause_hotkeys_in_fullscreen
+				&& !self.settings.fullscreen_hotkey_allowlist_contains(name)
+		});
+
+		self.fullscreen_app = app;
+
+		if pause_for == self.hotkeys_paused_for {
+			return;
+		}
+
+		let was_paused = self.hotkeys_paused_for.is_some();
+
+		self.hotkeys_paused_for = pause_for;
+
+		// A manual pause already released the hotkeys and restores them itself.
+		match (&self.hotkeys_paused_for, was_paused, self.paused) {
+			(Some(name), false, false) => {
+				tracing::info!(app = %name, "Pausing global hotkeys for a full-screen app.");
+
+				self.set_global_hotkeys_registered(false);
+			},
+			(None, true, false) => {
+				tracing::info!("Resuming global hotkeys.");
+
+				self.set_global_hotkeys_registered(true);
+			},
+			_ => {},
+		}
+
+		self.sync_tray_hotkey_pause_state();
+	}
+
+	pub(super) fn set_global_hotkeys_registered(&mut self, registered: bool) {
+		let hotkeys = self.pausable_hotkeys();
+		let Some(manager) = self._hotkey_manager.as_ref() else {
+			return;
+		};
+
+		for hotkey in hotkeys {
+			let result =
+				if registered { manager.register(hotkey) } else { manager.unregister(hotkey) };
+
+			if let Err(err) = result {
+				tracing::warn!(
+					error = %err,
+					hotkey = %hotkey,
+					registered,
+					"Failed to update global hotkey."
+				);
+			}
+		}
+	}
+
+	/// Registered global hotkeys; the capture hotkey is left out while it is being re-recorded.
+	fn pausable_hotkeys(&self) -> Vec<HotKey> {
+		let mut hotkeys = vec![
+			self.magnifier_hotkey,
+			self.color_picker_hotkey,
+			self.profile_cycle_hotkey,
+			self.display_picker_hotkey,
+			self.active_window_hotkey,
+			self.replay_hotkey,
+			self.repeat_capture_hotkey,
+		];
+
+		if !self.capture_hotkey_recording_suspended {
+			hotkeys.push(self.capture_hotkey);
+		}
+
+		hotkeys.extend(self.settings_hotkey);
+
+		hotkeys
+	}
+
+	pub(super) fn sync_tray_hotkey_pause_state(&self) {
+		let Some(tray_icon) = self.tray_icon.as_ref() else {
+			return;
+		};
+		let tooltip = if self.paused {
+			String::from("rsnap: paused")
+		} else {
+			self.hotkeys_paused_for.as_ref().map_or_else(
+				|| String::from("rsnap"),
+				|name| format!("rsnap: hotkeys paused while {name} is full-screen"),
+			)
+		};
+
+		if let Err(err) = tray_icon.set_tooltip(Some(tooltip)) {
+			tracing::warn!(error = ?err, "Failed to update tray tooltip.");
+		}
+	}
+}
@@ -1,7 +1,9 @@
 use global_hotkey::hotkey::HotKey;
+use winit::event_loop::ActiveEventLoop;
 
 use crate::app::App;
 use crate::settings_window::{CaptureHotkeyNotice, SettingsWindow, SettingsWindowAction};
+use rsnap_overlay::{tr, tr_args};
 
 impl App {
 	pub(super) fn capture_key_label(&self) -> String {
@@ -106,9 +108,15 @@ impl App {
 
 	pub(super) fn apply_settings_window_action(
 		&mut self,
+		event_loop: &ActiveEventLoop,
 		action: SettingsWindowAction,
 	) -> (bool, Option<bool>, Option<Option<CaptureHotkeyNotice>>) {
 		match action {
+			SettingsWindowAction::IdentifyDisplays => {
+				self.start_identify_displays_session(event_loop, "settings-window");
+
+				(false, None, None)
+			},
 			SettingsWindowAction::Begin => {
 				self.suspend_capture_hotkey();
 
@@ -129,8 +137,9 @@ impl App {
 					(
 						true,
 						Some(false),
-						Some(Some(CaptureHotkeyNotice::Success(format!(
-							"Capture hotkey updated to {display_hotkey}."
+						Some(Some(CaptureHotkeyNotice::Success(tr_args(
+							"settings.hotkey_updated",
+							&[("hotkey", &display_hotkey)],
 						)))),
 					)
 				} else {
@@ -139,9 +148,9 @@ impl App {
 					(
 						false,
 						Some(true),
-						Some(Some(CaptureHotkeyNotice::Error(String::from(
-							"Capture hotkey unavailable. Try another shortcut.",
-						)))),
+						Some(Some(CaptureHotkeyNotice::Error(String::from(tr(
+							"settings.hotkey_unavailable",
+						))))),
 					)
 				}
 			},
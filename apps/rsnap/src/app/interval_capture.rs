@@ -0,0 +1,71 @@
+use crate::app::{App, capture};
+use crate::capture_feedback::{self, CapturedImage};
+use rsnap_overlay::{IntervalCapture, MonitorRect, RectPoints, tr_args};
+
+impl App {
+	/// Replaces any running interval capture with one on `rect_px`, using the current settings.
+	pub(super) fn start_interval_capture(&mut self, monitor: MonitorRect, rect_px: RectPoints) {
+		// Stop the old run first so two capture threads never overlap.
+		self.interval_capture = None;
+
+		let config = self.settings.interval_capture_config();
+		let interval_secs = config.interval_secs;
+		let mut on_annotate = self
+			.settings
+			.background_capture_notification
+			.then(|| capture::annotate_in_editor(self.event_proxy.clone()));
+		// Only the first frame of a run is announced; later ones would bury the desktop.
+		let on_frame = move |path: &std::path::Path| {
+			let Some(on_annotate) = on_annotate.take() else {
+				return;
+			};
+			let file = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+			let text = tr_args(
+				"notification.interval_started",
+				&[("seconds", &interval_secs), ("file", &file)],
+			);
+
+			capture_feedback::spawn_capture_notification(
+				CapturedImage::Saved(path.to_path_buf()),
+				text,
+				capture_feedback::SAVED_BACKGROUND_ACTIONS,
+				on_annotate,
+			);
+		};
+
+		match IntervalCapture::start_with_frame_listener(monitor, rect_px, config, on_frame) {
+			Ok(capture) => self.interval_capture = Some(capture),
+			Err(err) => {
+				tracing::warn!(error = %format!("{err:#}"), "Failed to start interval capture.");
+			},
+		}
+
+		self.sync_interval_capture_status();
+	}
+
+	pub(super) fn stop_interval_capture(&mut self, requested_by: &'static str) {
+		if self.interval_capture.take().is_some() {
+			tracing::info!(requested_by = %requested_by, "Interval capture stop requested.");
+		}
+
+		self.sync_interval_capture_status();
+	}
+
+	/// Enables the tray stop entry and notes the running schedule in the tray tooltip.
+	fn sync_interval_capture_status(&self) {
+		if let Some(item) = self.stop_interval_capture_menu_item.as_ref() {
+			item.set_enabled(self.interval_capture.is_some());
+		}
+
+		let tooltip = self.interval_capture.as_ref().map_or_else(
+			|| String::from("rsnap"),
+			|capture| format!("rsnap — capturing every {} s", capture.interval_secs()),
+		);
+
+		if let Some(tray_icon) = self.tray_icon.as_ref()
+			&& let Err(err) = tray_icon.set_tooltip(Some(tooltip))
+		{
+			tracing::warn!(error = ?err, "Failed to update tray tooltip.");
+		}
+	}
+}
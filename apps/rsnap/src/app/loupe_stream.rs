@@ -0,0 +1,44 @@
+use crate::app::App;
+use rsnap_overlay::LoupeStreamServer;
+
+impl App {
+	/// Starts, restarts, or stops the loupe IPC stream to match the current settings.
+	pub(super) fn sync_loupe_stream(&mut self) {
+		self.restart_loupe_stream_if_changed();
+
+		let token = self.loupe_stream_token();
+
+		if let Some(settings_window) = self.settings_window.as_mut() {
+			settings_window.set_loupe_stream_token(token);
+		}
+	}
+
+	/// Session token of the running loupe stream, for the settings window.
+	pub(super) fn loupe_stream_token(&self) -> Option<String> {
+		self.loupe_stream.as_ref().map(|(_, server)| server.token().to_owned())
+	}
+
+	fn restart_loupe_stream_if_changed(&mut self) {
+		if !self.settings.loupe_stream_enabled || self.paused {
+			self.loupe_stream = None;
+
+			return;
+		}
+
+		let config = self.settings.effective().loupe_stream_config();
+
+		if self.loupe_stream.as_ref().is_some_and(|(running, _)| *running == config) {
+			return;
+		}
+
+		// Release the port before binding it again.
+		self.loupe_stream = None;
+
+		match LoupeStreamServer::start(config) {
+			Ok(server) => self.loupe_stream = Some((config, server)),
+			Err(err) => {
+				tracing::warn!(error = %format!("{err:#}"), "Failed to start loupe stream.");
+			},
+		}
+	}
+}
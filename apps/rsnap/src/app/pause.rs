@@ -0,0 +1,55 @@
+//! A manual pause for games and screen sharing: every global hotkey except the pause hotkey is
+//! released, and the replay buffer, loupe stream, prewarm and watcher threads stop sampling.
+
+use crate::app::App;
+use crate::icon;
+
+impl App {
+	pub(super) fn toggle_pause(&mut self, requested_by: &'static str) {
+		self.paused = !self.paused;
+
+		tracing::info!(requested_by = %requested_by, paused = self.paused, "Pause toggled.");
+
+		// While a full-screen app holds the hotkeys off, it restores them when it leaves.
+		if self.hotkeys_paused_for.is_none() {
+			self.set_global_hotkeys_registered(!self.paused);
+		}
+		if self.paused {
+			self.stop_region_watch(requested_by);
+			self.stop_interval_capture(requested_by);
+		}
+
+		self.sync_replay_buffer();
+		self.sync_loupe_stream();
+		self.sync_overlay_prewarm();
+		self.sync_clipboard_watch();
+		self.sync_fullscreen_pause();
+		self.sync_pause_indicator();
+	}
+
+	/// Checks the tray entry and greys out the tray icon while paused.
+	fn sync_pause_indicator(&self) {
+		if let Some(item) = self.pause_menu_item.as_ref() {
+			item.set_checked(self.paused);
+		}
+
+		let Some(tray_icon) = self.tray_icon.as_ref() else {
+			return;
+		};
+		let icon = if self.paused { icon::paused_tray_icon() } else { icon::default_tray_icon() };
+
+		match icon {
+			Ok(icon) => {
+				let result =
+					tray_icon.set_icon_with_as_template(Some(icon), cfg!(target_os = "macos"));
+
+				if let Err(err) = result {
+					tracing::warn!(error = ?err, "Failed to update tray icon.");
+				}
+			},
+			Err(err) => tracing::warn!(error = ?err, "Failed to create tray icon image."),
+		}
+
+		self.sync_tray_hotkey_pause_state();
+	}
+}
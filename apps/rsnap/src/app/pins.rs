@@ -0,0 +1,214 @@
+//! Managing pinned images: the tray's Pinned Images menu and the commands a pin's context menu
+//! hands to the app.
+
+use tray_icon::menu::{CheckMenuItem, Icon, MenuId, MenuItem, Submenu};
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+use crate::app::App;
+use crate::capture_popover::{CapturePopover, PinCommand, PopoverChoice, ScreenCorner};
+use rsnap_overlay::{tr, tr_args};
+
+/// The tray submenu of one pin.
+pub(super) struct PinMenuEntry {
+	window_id: WindowId,
+	menu: Submenu,
+	bring_to_front: MenuItem,
+	corners: Vec<(MenuItem, ScreenCorner)>,
+	click_through: CheckMenuItem,
+	close: MenuItem,
+}
+impl PinMenuEntry {
+	fn new(popover: &CapturePopover, title: &str) -> Self {
+		let menu = Submenu::new(title, true);
+		let bring_to_front = MenuItem::new(tr("pins.bring_to_front"), true, None);
+		let snap_menu = Submenu::new(tr("pins.snap"), true);
+		let corners = ScreenCorner::ALL
+			.into_iter()
+			.map(|corner| (MenuItem::new(tr(corner.label_key()), true, None), corner))
+			.collect::<Vec<_>>();
+		let click_through =
+			CheckMenuItem::new(tr("pins.click_through"), true, popover.is_click_through(), None);
+		let close = MenuItem::new(tr("pins.close"), true, None);
+
+		if let Some(thumbnail) = popover.thumbnail() {
+			let (width, height) = thumbnail.dimensions();
+
+			match Icon::from_rgba(thumbnail.to_vec(), width, height) {
+				Ok(icon) => menu.set_icon(Some(icon)),
+				Err(err) => tracing::debug!(error = ?err, "No tray thumbnail for the pin."),
+			}
+		}
+
+		for (item, _) in &corners {
+			if let Err(err) = snap_menu.append(item) {
+				tracing::warn!(error = ?err, "Failed to add tray pin corner entry.");
+			}
+		}
+
+		if let Err(err) = menu.append_items(&[&bring_to_front, &snap_menu, &click_through, &close])
+		{
+			tracing::warn!(error = ?err, "Failed to build tray pin entry.");
+		}
+
+		Self { window_id: popover.window_id(), menu, bring_to_front, corners, click_through, close }
+	}
+}
+
+/// What a click on a pin's tray entry asks for.
+enum PinMenuAction {
+	BringToFront,
+	Snap(ScreenCorner),
+	ClickThrough(bool),
+	Close,
+}
+
+impl App {
+	/// Rebuilds the tray's Pinned Images entries when pins came or went, keeps their
+	/// click-through checks current and hands every pin the titles for its context menu.
+	pub(super) fn sync_pins(&mut self) {
+		let pins = self.capture_popovers.iter().filter(|popover| popover.is_pinned());
+		let unchanged = self
+			.pins_menu_entries
+			.iter()
+			.map(|entry| entry.window_id)
+			.eq(pins.clone().map(CapturePopover::window_id));
+
+		if unchanged {
+			for (entry, popover) in self.pins_menu_entries.iter().zip(pins) {
+				if entry.click_through.is_checked() != popover.is_click_through() {
+					entry.click_through.set_checked(popover.is_click_through());
+				}
+			}
+
+			return;
+		}
+
+		let titles =
+			pins.enumerate().map(|(index, popover)| pin_title(index, popover)).collect::<Vec<_>>();
+
+		self.rebuild_pins_menu(&titles);
+
+		for popover in self.capture_popovers.iter_mut().filter(|popover| popover.is_pinned()) {
+			popover.set_pin_titles(titles.clone());
+		}
+	}
+
+	fn rebuild_pins_menu(&mut self, titles: &[String]) {
+		let entries = self
+			.capture_popovers
+			.iter()
+			.filter(|popover| popover.is_pinned())
+			.zip(titles)
+			.map(|(popover, title)| PinMenuEntry::new(popover, title))
+			.collect::<Vec<_>>();
+		let old_entries = std::mem::replace(&mut self.pins_menu_entries, entries);
+		let Some(menu) = self.pins_menu.as_ref() else {
+			return;
+		};
+
+		for entry in old_entries {
+			if let Err(err) = menu.remove(&entry.menu) {
+				tracing::warn!(error = ?err, "Failed to remove tray pin entry.");
+			}
+		}
+
+		// The entries go above the separator and Close All Pins, which stay in the menu.
+		for (position, entry) in self.pins_menu_entries.iter().enumerate() {
+			if let Err(err) = menu.insert(&entry.menu, position) {
+				tracing::warn!(error = ?err, "Failed to add tray pin entry.");
+			}
+		}
+
+		menu.set_enabled(!self.pins_menu_entries.is_empty());
+	}
+
+	/// Carries out a click on a Pinned Images entry; returns `false` when `id` is not one.
+	pub(super) fn handle_pins_menu_event(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		id: &MenuId,
+	) -> bool {
+		if self.close_all_pins_menu_item.as_ref().is_some_and(|item| item.id() == id) {
+			self.apply_pin_command(event_loop, PinCommand::CloseAll);
+
+			return true;
+		}
+
+		let Some((window_id, action)) = self.pins_menu_entries.iter().find_map(|entry| {
+			let action = if entry.bring_to_front.id() == id {
+				PinMenuAction::BringToFront
+			} else if entry.click_through.id() == id {
+				PinMenuAction::ClickThrough(entry.click_through.is_checked())
+			} else if entry.close.id() == id {
+				PinMenuAction::Close
+			} else {
+				let (_, corner) = entry.corners.iter().find(|(item, _)| item.id() == id)?;
+
+				PinMenuAction::Snap(*corner)
+			};
+
+			Some((entry.window_id, action))
+		}) else {
+			return false;
+		};
+		let Some(index) =
+			self.capture_popovers.iter().position(|popover| popover.window_id() == window_id)
+		else {
+			return true;
+		};
+
+		match action {
+			PinMenuAction::BringToFront => self.capture_popovers[index].bring_to_front(),
+			PinMenuAction::Snap(corner) => self.capture_popovers[index].snap_to(corner),
+			PinMenuAction::ClickThrough(click_through) => {
+				self.capture_popovers[index].set_click_through(click_through);
+			},
+			PinMenuAction::Close => {
+				let popover = self.capture_popovers.remove(index);
+
+				self.finish_capture_popover(event_loop, popover, PopoverChoice::Dismiss);
+			},
+		}
+
+		self.sync_pins();
+
+		true
+	}
+
+	pub(super) fn apply_pin_command(&mut self, event_loop: &ActiveEventLoop, command: PinCommand) {
+		tracing::info!(op = "pins.command", ?command, "Pin command requested.");
+
+		match command {
+			PinCommand::Focus(index) => {
+				if let Some(popover) =
+					self.capture_popovers.iter().filter(|popover| popover.is_pinned()).nth(index)
+				{
+					popover.bring_to_front();
+				}
+			},
+			PinCommand::CloseAll => {
+				let (pinned, open) =
+					self.capture_popovers.drain(..).partition(CapturePopover::is_pinned);
+
+				self.capture_popovers = open;
+
+				for popover in pinned {
+					self.finish_capture_popover(event_loop, popover, PopoverChoice::Dismiss);
+				}
+			},
+		}
+
+		self.sync_pins();
+	}
+}
+
+/// "Pin 2 · 1280 × 720", numbered from 1 in pinning order.
+fn pin_title(index: usize, popover: &CapturePopover) -> String {
+	let title = tr_args("pins.title", &[("index", &(index + 1))]);
+
+	match popover.image_size() {
+		Some((width, height)) => format!("{title} · {width} × {height}"),
+		None => title,
+	}
+}
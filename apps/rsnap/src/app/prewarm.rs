@@ -0,0 +1,176 @@
+//! Prewarms the overlay while the capture chord's modifiers are held, so the overlay appears
+//! without its cold-start delay once the chord completes.
+
+use std::mem;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use global_hotkey::hotkey::{HotKey, Modifiers};
+use winit::event_loop::EventLoopProxy;
+use winit::keyboard::ModifiersState;
+
+use crate::app::{App, UserEvent};
+use rsnap_overlay::ModifierProbe;
+
+/// How long the chord's modifiers must be held before prewarming starts.
+const PREWARM_HOLD_THRESHOLD: Duration = Duration::from_millis(150);
+/// How often the watcher samples the held modifiers.
+const MODIFIER_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PrewarmHint {
+	/// The chord's modifiers have been held past the threshold.
+	Start,
+	/// The modifiers changed before the chord completed.
+	Cancel,
+}
+
+/// Turns sampled modifier state into prewarm start/cancel hints.
+#[derive(Debug, Default)]
+pub(super) struct ModifierHoldDetector {
+	held_since: Option<Instant>,
+	started: bool,
+}
+impl ModifierHoldDetector {
+	pub(super) fn update(
+		&mut self,
+		now: Instant,
+		target: ModifiersState,
+		held: ModifiersState,
+	) -> Option<PrewarmHint> {
+		if target.is_empty() || held != target {
+			self.held_since = None;
+
+			return mem::take(&mut self.started).then_some(PrewarmHint::Cancel);
+		}
+
+		let held_since = *self.held_since.get_or_insert(now);
+
+		if self.started || now.saturating_duration_since(held_since) < PREWARM_HOLD_THRESHOLD {
+			return None;
+		}
+
+		self.started = true;
+
+		Some(PrewarmHint::Start)
+	}
+}
+
+/// Samples the held modifiers against `target` (winit modifier bits; 0 disables the watch).
+pub(super) fn spawn_prewarm_watcher(proxy: EventLoopProxy<UserEvent>, target: Arc<AtomicU32>) {
+	let spawned =
+		thread::Builder::new().name(String::from("rsnap-prewarm-watch")).spawn(move || {
+			let probe = ModifierProbe::new();
+			let mut detector = ModifierHoldDetector::default();
+
+			loop {
+				thread::sleep(MODIFIER_POLL_INTERVAL);
+
+				let target = ModifiersState::from_bits_truncate(target.load(Ordering::Relaxed));
+				let held = if target.is_empty() { ModifiersState::empty() } else { probe.held() };
+				let Some(hint) = detector.update(Instant::now(), target, held) else {
+					continue;
+				};
+
+				if proxy.send_event(UserEvent::Prewarm(hint)).is_err() {
+					return;
+				}
+			}
+		});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start prewarm watcher.");
+	}
+}
+
+/// The capture chord's modifiers as winit reports them.
+pub(super) fn chord_modifiers(hotkey: &HotKey) -> ModifiersState {
+	let mut modifiers = ModifiersState::empty();
+
+	modifiers.set(ModifiersState::SHIFT, hotkey.mods.contains(Modifiers::SHIFT));
+	modifiers.set(ModifiersState::CONTROL, hotkey.mods.contains(Modifiers::CONTROL));
+	modifiers.set(ModifiersState::ALT, hotkey.mods.contains(Modifiers::ALT));
+	modifiers
+		.set(ModifiersState::SUPER, hotkey.mods.intersects(Modifiers::SUPER | Modifiers::META));
+
+	modifiers
+}
+
+impl App {
+	/// Points the watcher at the current capture chord, or stops it when prewarming is off or
+	/// rsnap is paused.
+	pub(super) fn sync_overlay_prewarm(&mut self) {
+		let target = if self.settings.prewarm_on_modifier_hold && !self.paused {
+			chord_modifiers(&self.capture_hotkey)
+		} else {
+			ModifiersState::empty()
+		};
+
+		self.prewarm_modifiers.store(target.bits(), Ordering::Relaxed);
+
+		if self.resident_overlay_wanted() {
+			self.ensure_resident_overlay();
+		} else {
+			self.prewarmed_overlay = None;
+		}
+	}
+
+	pub(super) fn resident_overlay_wanted(&self) -> bool {
+		self.settings.resident_overlay && !self.paused
+	}
+
+	/// Keeps a prewarmed session ready between captures while the overlay is kept warm, reusing
+	/// the one that just ended when it can so its GPU device stays open.
+	pub(super) fn ensure_resident_overlay(&mut self) {
+		if !self.resident_overlay_wanted()
+			|| self.overlay_session.is_some()
+			|| self.pending_overlay_launch.is_some()
+		{
+			return;
+		}
+
+		let mut session = match self.prewarmed_overlay.take() {
+			Some(mut session) => {
+				session.set_config(self.overlay_config());
+
+				session
+			},
+			None => self.new_overlay_session(),
+		};
+
+		match session.prewarm() {
+			Ok(()) => self.prewarmed_overlay = Some(session),
+			Err(err) => tracing::warn!(error = %err, "Failed to keep the overlay warm."),
+		}
+	}
+
+	pub(super) fn handle_prewarm_hint(&mut self, hint: PrewarmHint) {
+		match hint {
+			PrewarmHint::Start => {
+				if self.overlay_session.is_some()
+					|| self.pending_overlay_launch.is_some()
+					|| self.prewarmed_overlay.is_some()
+				{
+					return;
+				}
+
+				let mut session = self.new_overlay_session();
+
+				match session.prewarm() {
+					Ok(()) => self.prewarmed_overlay = Some(session),
+					Err(err) => tracing::warn!(error = %err, "Failed to prewarm overlay."),
+				}
+			},
+			PrewarmHint::Cancel => {
+				if self.resident_overlay_wanted() {
+					return;
+				}
+				if self.prewarmed_overlay.take().is_some() {
+					tracing::debug!("Capture chord released; dropped the prewarmed overlay.");
+				}
+			},
+		}
+	}
+}
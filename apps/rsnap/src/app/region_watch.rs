@@ -0,0 +1,33 @@
+use crate::app::App;
+use rsnap_overlay::{MonitorRect, RectPoints, RegionWatch};
+
+impl App {
+	/// Replaces any running region watch with one on `rect_px`, using the current settings.
+	pub(super) fn start_region_watch(&mut self, monitor: MonitorRect, rect_px: RectPoints) {
+		// Stop the old watch first so two sampling threads never overlap.
+		self.region_watch = None;
+
+		match RegionWatch::start(monitor, rect_px, self.settings.region_watch_config()) {
+			Ok(watch) => self.region_watch = Some(watch),
+			Err(err) => {
+				tracing::warn!(error = %format!("{err:#}"), "Failed to start region watch.");
+			},
+		}
+
+		self.sync_region_watch_menu();
+	}
+
+	pub(super) fn stop_region_watch(&mut self, requested_by: &'static str) {
+		if self.region_watch.take().is_some() {
+			tracing::info!(requested_by = %requested_by, "Region watch stop requested.");
+		}
+
+		self.sync_region_watch_menu();
+	}
+
+	fn sync_region_watch_menu(&self) {
+		if let Some(item) = self.stop_region_watch_menu_item.as_ref() {
+			item.set_enabled(self.region_watch.is_some());
+		}
+	}
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::app::App;
+use rsnap_overlay::{ReplayBuffer, ReplayClip};
+
+impl App {
+	/// Starts, restarts, or stops the instant replay buffer to match the current settings.
+	pub(super) fn sync_replay_buffer(&mut self) {
+		if !self.settings.replay_enabled || self.paused {
+			if self.replay_buffer.take().is_some() {
+				tracing::info!(op = "replay.stop", "Replay buffer stopped.");
+			}
+
+			return;
+		}
+
+		let config = self.settings.replay_config();
+
+		if self.replay_buffer.as_ref().is_some_and(|(running, _)| *running == config) {
+			return;
+		}
+
+		// Drop the old buffer first so two capture threads never overlap.
+		self.replay_buffer = None;
+
+		match ReplayBuffer::start(config) {
+			Ok(buffer) => self.replay_buffer = Some((config, buffer)),
+			Err(err) => {
+				tracing::warn!(error = %format!("{err:#}"), "Failed to start replay buffer.");
+			},
+		}
+	}
+
+	/// Saves the last few buffered seconds as an animated PNG in the output directory.
+	pub(super) fn export_replay(&mut self, requested_by: &'static str) {
+		let Some((_, buffer)) = self.replay_buffer.as_ref() else {
+			tracing::info!(
+				requested_by = %requested_by,
+				"Replay buffer is off; enable it in Settings → Capture."
+			);
+
+			return;
+		};
+		let clip = match buffer.clip(self.settings.replay_seconds) {
+			Ok(clip) => clip,
+			Err(err) => {
+				tracing::warn!(error = %format!("{err:#}"), "Replay export skipped.");
+
+				return;
+			},
+		};
+		let output_dir = self.settings.output_dir.clone();
+		let prefix = self.settings.output_filename_prefix.clone();
+		// Encoding dozens of frames takes a moment; keep the event loop responsive.
+		let spawned =
+			thread::Builder::new().name(String::from("rsnap-replay-export")).spawn(move || {
+				match save_replay_clip(&clip, output_dir, &prefix) {
+					Ok(path) => {
+						tracing::info!(
+							op = "replay.export",
+							path = %path.display(),
+							"Replay saved."
+						);
+					},
+					Err(err) => {
+						tracing::warn!(error = %format!("{err:#}"), "Replay export failed.");
+					},
+				}
+			});
+
+		if let Err(err) = spawned {
+			tracing::warn!(error = %err, "Failed to start replay export thread.");
+		}
+	}
+}
+
+fn save_replay_clip(clip: &ReplayClip, output_dir: PathBuf, prefix: &str) -> Result<PathBuf> {
+	let apng = clip.encode_apng()?;
+	let unix_ms =
+		SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+	let path = output_dir.join(format!("{prefix}-replay-{unix_ms}.png"));
+
+	fs::create_dir_all(&output_dir)
+		.wrap_err_with(|| format!("Failed to create output dir: {}", output_dir.display()))?;
+	fs::write(&path, apng)
+		.wrap_err_with(|| format!("Failed to write replay: {}", path.display()))?;
+
+	Ok(path)
+}
@@ -1,9 +1,9 @@
 use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
 #[cfg(target_os = "macos")]
-use std::sync::{
-	Arc,
-	atomic::{AtomicBool, Ordering},
-};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
 
 use color_eyre::eyre;
@@ -24,39 +24,57 @@ use winit::{
 
 #[cfg(target_os = "macos")]
 use crate::app::scroll_input_macos::SharedScrollInputState;
-use crate::app::{App, UserEvent};
+use crate::app::{App, UserEvent, fullscreen, prewarm, updates};
 use crate::settings::AppSettings;
 use crate::settings_window::{CaptureHotkeyNotice, SettingsControl, SettingsWindowAction};
+use rsnap_overlay::OverlaySession;
 
 impl ApplicationHandler<UserEvent> for App {
 	fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+		if self.capture_folder.is_some() {
+			if self.overlay_session.is_none() {
+				self.start_capture_session(event_loop, "capture_to_folder");
+			}
+
+			return;
+		}
+
 		#[cfg(target_os = "macos")]
 		self.install_menubar(event_loop);
 		self.install_tray(event_loop);
+		self.sync_replay_buffer();
+		self.sync_loupe_stream();
+		self.sync_overlay_prewarm();
+		self.sync_clipboard_watch();
+
+		if let Some(path) = self.pending_project.take() {
+			self.open_project_session(event_loop, path, "command_line");
+		}
 	}
 
 	fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
 		match event {
 			UserEvent::Menu(event) => self.handle_menu_event(event_loop, &event),
-			UserEvent::HotKey(event) => self.handle_hotkey_event(event_loop, event),
-			UserEvent::TrayIcon => {},
+			UserEvent::HotKey(event, pressed_at) => {
+				self.handle_hotkey_event(event_loop, event, pressed_at);
+			},
+			UserEvent::TrayIcon => self.sync_app_capture_menu(),
+			UserEvent::FullscreenApp(app) => self.handle_fullscreen_app(app),
+			UserEvent::Prewarm(hint) => self.handle_prewarm_hint(hint),
+			UserEvent::Update(event) => self.handle_update_event(event_loop, event),
+			UserEvent::Annotate(path) => self.open_image_session(event_loop, path, "notification"),
+			UserEvent::ClipboardImage(png_bytes, fingerprint) => {
+				self.handle_clipboard_image(png_bytes, fingerprint);
+			},
 			#[cfg(target_os = "macos")]
 			UserEvent::OverlayStreamFrame => {
 				self.overlay_stream_event_pending.store(false, Ordering::Release);
 
-				if let Some(session) = self.overlay_session.as_mut() {
-					let control = session.handle_scroll_stream_frame_ready();
-
-					self.handle_overlay_control(control);
-				}
+				self.drive_overlay_session(OverlaySession::handle_scroll_stream_frame_ready);
 			},
 			#[cfg(target_os = "macos")]
 			UserEvent::OverlayWorkerResponse => {
-				if let Some(session) = self.overlay_session.as_mut() {
-					let control = session.handle_worker_response_ready();
-
-					self.handle_overlay_control(control);
-				}
+				self.drive_overlay_session(OverlaySession::handle_worker_response_ready);
 			},
 		}
 	}
@@ -103,7 +121,8 @@ impl ApplicationHandler<UserEvent> for App {
 			let mut action_changed = false;
 
 			for action in action_queue {
-				let (changed, recording_active, notice) = self.apply_settings_window_action(action);
+				let (changed, recording_active, notice) =
+					self.apply_settings_window_action(event_loop, action);
 
 				if let Some(recording_active) = recording_active {
 					ui_updates.push_back((Some(recording_active), None));
@@ -129,8 +148,19 @@ impl ApplicationHandler<UserEvent> for App {
 			if action_changed {
 				settings_changed = true;
 			}
+			if settings_changed {
+				rsnap_overlay::set_language(self.settings.ui_language());
+				self.update_checks_enabled
+					.store(self.settings.check_for_updates, Ordering::Relaxed);
+				self.sync_overlay_prewarm();
+				self.sync_capture_preset_menu();
+				self.sync_clipboard_watch();
+			}
 			if overlay_changed {
 				self.apply_overlay_settings();
+				self.sync_replay_buffer();
+				self.sync_loupe_stream();
+				self.sync_fullscreen_pause();
 			}
 			if settings_changed && let Err(err) = self.settings.save() {
 				tracing::warn!(error = ?err, "Failed to save settings.");
@@ -139,46 +169,65 @@ impl ApplicationHandler<UserEvent> for App {
 				return;
 			}
 
+			// The window was taken above, so `sync_loupe_stream` could not reach it.
+			settings_window.set_loupe_stream_token(self.loupe_stream_token());
+
 			self.settings_window = Some(settings_window);
 
 			return;
 		}
-		if let Some(session) = self.overlay_session.as_mut() {
-			let control = session.handle_window_event(window_id, &event);
-
-			self.handle_overlay_control(control);
+		if self.handle_capture_popover_event(event_loop, window_id, &event) {
+			return;
+		}
+		if self.overlay_session.is_some() {
+			self.drive_overlay_session(|session| session.handle_window_event(window_id, &event));
 		} else if let WindowEvent::CloseRequested = event {
 			event_loop.exit();
 		}
 	}
 
 	fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-		if self.overlay_session.is_some() || self.settings_window.is_some() {
+		if self.overlay_session.is_some()
+			|| self.pending_overlay_launch.is_some()
+			|| self.settings_window.is_some()
+		{
 			event_loop.set_control_flow(ControlFlow::WaitUntil(
 				Instant::now() + Duration::from_millis(16),
 			));
+		} else if let Some(deadline) = self.capture_popover_deadline() {
+			event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
 		} else {
 			event_loop.set_control_flow(ControlFlow::Wait);
 		}
 
-		if let Some(session) = self.overlay_session.as_mut() {
-			let control = session.about_to_wait();
-
-			self.handle_overlay_control(control);
+		self.check_overlay_session_hang();
+		self.drive_overlay_session(OverlaySession::about_to_wait);
+		self.restart_crashed_overlay_session(event_loop);
+		self.resume_pending_overlay_launch(event_loop);
+		self.open_pending_capture_popover(event_loop);
+		self.tick_capture_popovers(event_loop);
+		self.sync_pins();
+
+		if self.capture_folder.is_some()
+			&& self.overlay_session.is_none()
+			&& self.pending_overlay_launch.is_none()
+		{
+			event_loop.exit();
 		}
 	}
 }
 
-pub(super) fn run() -> Result<()> {
+pub(super) fn run(project: Option<PathBuf>, capture_folder: Option<PathBuf>) -> Result<()> {
 	let settings = AppSettings::load();
+
+	rsnap_overlay::set_language(settings.ui_language());
+
 	let capture_hotkey = settings.capture_hotkey();
-	let capture_hotkey_id = capture_hotkey.id();
 	let settings_hotkey = if cfg!(target_os = "macos") {
 		None
 	} else {
 		Some(HotKey::new(Some(global_hotkey::hotkey::CMD_OR_CTRL), Code::Comma))
 	};
-	let settings_hotkey_id = settings_hotkey.as_ref().map(HotKey::id);
 	let mut hotkey_manager = match GlobalHotKeyManager::new() {
 		Ok(manager) => Some(manager),
 		Err(err) => {
@@ -189,29 +238,21 @@ pub(super) fn run() -> Result<()> {
 	};
 
 	if let Some(manager) = hotkey_manager.as_mut() {
-		if let Err(err) = manager.register(capture_hotkey) {
-			tracing::warn!(
-				error = ?err,
-				hotkey_id = %capture_hotkey_id,
-				"Failed to register capture hotkey."
-			);
-		} else {
-			tracing::info!(hotkey_id = %capture_hotkey_id, "Registered capture hotkey.");
-		}
-		if let Some(settings_hotkey) = settings_hotkey.as_ref() {
-			if let Err(err) = manager.register(*settings_hotkey) {
-				tracing::warn!(
-					error = ?err,
-					hotkey_id = %settings_hotkey_id.unwrap_or_default(),
-					"Failed to register settings hotkey."
-				);
-			} else {
-				tracing::info!(
-					hotkey_id = %settings_hotkey_id.unwrap_or_default(),
-					"Registered settings hotkey."
-				);
-			}
-		}
+		let mut hotkeys = vec![(capture_hotkey, "capture")];
+
+		hotkeys.extend(settings_hotkey.map(|hotkey| (hotkey, "settings")));
+		hotkeys.extend([
+			(settings.magnifier_hotkey(), "magnifier"),
+			(settings.color_picker_hotkey(), "color picker"),
+			(settings.profile_cycle_hotkey(), "profile cycle"),
+			(settings.display_picker_hotkey(), "display picker"),
+			(settings.active_window_hotkey(), "active window"),
+			(settings.replay_hotkey(), "replay"),
+			(settings.repeat_capture_hotkey(), "repeat capture"),
+			(settings.pause_hotkey(), "pause"),
+		]);
+
+		register_hotkeys(manager, &hotkeys);
 	}
 
 	let mut event_loop_builder = EventLoop::with_user_event();
@@ -224,7 +265,6 @@ pub(super) fn run() -> Result<()> {
 	}
 
 	let event_loop = event_loop_builder.build()?;
-	let tray_proxy: EventLoopProxy<UserEvent> = event_loop.create_proxy();
 	#[cfg(target_os = "macos")]
 	let overlay_proxy: EventLoopProxy<UserEvent> = event_loop.create_proxy();
 	#[cfg(target_os = "macos")]
@@ -244,9 +284,51 @@ pub(super) fn run() -> Result<()> {
 		scroll_input_shared_state,
 	);
 
+	app.pending_project = project;
+	app.capture_folder = capture_folder;
+	app.event_proxy = Some(event_loop.create_proxy());
+
+	spawn_watchers(&event_loop, &app);
+
+	tracing::info!(
+		hotkey = %app.capture_key_label(),
+		settings_hotkey = %app.settings_key_label(),
+		"Starting menubar-only rsnap app."
+	);
+
+	event_loop.run_app(&mut app).map_err(|err: EventLoopError| eyre::eyre!(err))?;
+
+	if let Some(path) = app.relaunch_after_exit.take() {
+		crate::updates::relaunch(&path)?;
+	}
+
+	Ok(())
+}
+
+/// Registers each global hotkey with the manager, logging the ones the system refuses.
+fn register_hotkeys(manager: &mut GlobalHotKeyManager, hotkeys: &[(HotKey, &str)]) {
+	for (hotkey, name) in hotkeys {
+		if let Err(err) = manager.register(*hotkey) {
+			tracing::warn!(
+				error = ?err,
+				hotkey_id = %hotkey.id(),
+				"Failed to register {name} hotkey."
+			);
+		} else {
+			tracing::info!(hotkey_id = %hotkey.id(), "Registered {name} hotkey.");
+		}
+	}
+}
+
+/// Forwards tray, menu, and hotkey events into the event loop and starts the background watchers.
+fn spawn_watchers(event_loop: &EventLoop<UserEvent>, app: &App) {
+	let tray_proxy: EventLoopProxy<UserEvent> = event_loop.create_proxy();
+
 	TrayIconEvent::set_event_handler(Some(move |event| {
-		let _ = event;
-		let _ = tray_proxy.send_event(UserEvent::TrayIcon);
+		// Hovering or clicking the icon refreshes the app list before the menu opens.
+		if matches!(event, TrayIconEvent::Enter { .. } | TrayIconEvent::Click { .. }) {
+			let _ = tray_proxy.send_event(UserEvent::TrayIcon);
+		}
 	}));
 
 	let menu_proxy: EventLoopProxy<UserEvent> = event_loop.create_proxy();
@@ -255,19 +337,19 @@ pub(super) fn run() -> Result<()> {
 		let _ = menu_proxy.send_event(UserEvent::Menu(event));
 	}));
 
+	fullscreen::spawn_fullscreen_watcher(
+		event_loop.create_proxy(),
+		Arc::clone(&app.fullscreen_watch_enabled),
+	);
+	prewarm::spawn_prewarm_watcher(event_loop.create_proxy(), Arc::clone(&app.prewarm_modifiers));
+	updates::spawn_update_checker(
+		event_loop.create_proxy(),
+		Arc::clone(&app.update_checks_enabled),
+	);
+
 	let hotkey_proxy: EventLoopProxy<UserEvent> = event_loop.create_proxy();
 
 	GlobalHotKeyEvent::set_event_handler(Some(move |event| {
-		let _ = hotkey_proxy.send_event(UserEvent::HotKey(event));
+		let _ = hotkey_proxy.send_event(UserEvent::HotKey(event, Instant::now()));
 	}));
-
-	tracing::info!(
-		hotkey = %app.capture_key_label(),
-		settings_hotkey = %app.settings_key_label(),
-		"Starting menubar-only rsnap app."
-	);
-
-	event_loop.run_app(&mut app).map_err(|err: EventLoopError| eyre::eyre!(err))?;
-
-	Ok(())
 }
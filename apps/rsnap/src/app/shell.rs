@@ -1,18 +1,19 @@
+use std::time::Instant;
+
 use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
-use tray_icon::TrayIconBuilder;
 use tray_icon::menu::Menu;
 use tray_icon::menu::MenuEvent;
-#[cfg(target_os = "macos")]
 use tray_icon::menu::Submenu;
 use tray_icon::menu::{
-	MenuItem, PredefinedMenuItem,
+	CheckMenuItem, MenuItem, PredefinedMenuItem,
 	accelerator::{self, Accelerator, Code, Modifiers},
 };
+use tray_icon::{TrayIcon, TrayIconBuilder};
 use winit::event_loop::ActiveEventLoop;
 
 use crate::app::App;
 use crate::icon;
-use rsnap_overlay::OverlayExit;
+use rsnap_overlay::{OverlayExit, tr};
 
 impl App {
 	#[cfg(target_os = "macos")]
@@ -23,12 +24,12 @@ impl App {
 
 		let menubar = Menu::new();
 		let settings_item = MenuItem::new(
-			"Settings…",
+			tr("tray.settings"),
 			true,
 			Some(Accelerator::new(Some(Modifiers::SUPER), Code::Comma)),
 		);
 		let quit_item = MenuItem::new(
-			"Quit rsnap",
+			tr("tray.quit_rsnap"),
 			true,
 			Some(Accelerator::new(Some(Modifiers::SUPER), Code::KeyQ)),
 		);
@@ -69,28 +70,65 @@ impl App {
 
 		let tray_menu = Menu::new();
 		let capture_item = MenuItem::new(
-			"Capture",
+			tr("tray.capture"),
 			true,
 			Some(Accelerator::new(Some(Modifiers::ALT), Code::KeyX)),
 		);
+		let color_picker_item = MenuItem::new(
+			tr("tray.pick_color"),
+			true,
+			Some(Accelerator::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyC)),
+		);
+		let stop_region_watch_item =
+			MenuItem::new(tr("tray.stop_region_watch"), self.region_watch.is_some(), None);
+		let start_interval_capture_item = MenuItem::new(tr("tray.interval_capture"), true, None);
+		let stop_interval_capture_item =
+			MenuItem::new(tr("tray.stop_interval_capture"), self.interval_capture.is_some(), None);
+		let identify_displays_item = MenuItem::new(tr("tray.identify_displays"), true, None);
+		let update_item = MenuItem::new(tr("tray.check_updates"), true, None);
+		let pause_item = CheckMenuItem::new(tr("tray.pause"), true, self.paused, None);
+		let capture_preset_menu = Submenu::new(tr("tray.capture_preset"), false);
+		let app_capture_menu = Submenu::new(tr("tray.capture_app_windows"), false);
+		let (pins_menu, close_all_pins_item) = Self::build_pins_menu();
 		let settings_item = MenuItem::new(
-			"Settings…",
+			tr("tray.settings"),
 			true,
 			Some(Accelerator::new(Some(accelerator::CMD_OR_CTRL), Code::Comma)),
 		);
 		let quit_item = MenuItem::new(
-			"Quit",
+			tr("tray.quit"),
 			true,
 			Some(Accelerator::new(Some(accelerator::CMD_OR_CTRL), Code::KeyQ)),
 		);
 
-		if let Err(err) = tray_menu.append_items(&[
+		let profile_items = self.profile_menu_entries();
+		let profile_menu = Self::build_profile_menu(&profile_items);
+		let separator = PredefinedMenuItem::separator();
+		let mut items: Vec<&dyn tray_icon::menu::IsMenuItem> = vec![
 			&capture_item,
-			&PredefinedMenuItem::separator(),
-			&settings_item,
-			&PredefinedMenuItem::separator(),
-			&quit_item,
-		]) {
+			&capture_preset_menu,
+			&app_capture_menu,
+			&color_picker_item,
+			&stop_region_watch_item,
+			&start_interval_capture_item,
+			&stop_interval_capture_item,
+			&identify_displays_item,
+			&pins_menu,
+			&separator,
+			&pause_item,
+			&separator,
+		];
+
+		if let Some(profile_menu) = profile_menu.as_ref() {
+			items.push(profile_menu);
+		}
+
+		items.push(&settings_item);
+		items.push(&update_item);
+		items.push(&separator);
+		items.push(&quit_item);
+
+		if let Err(err) = tray_menu.append_items(&items) {
 			tracing::warn!(error = ?err, "Failed to build tray menu.");
 
 			event_loop.exit();
@@ -98,37 +136,153 @@ impl App {
 			return;
 		}
 
-		let icon = match icon::default_tray_icon() {
-			Ok(icon) => icon,
-			Err(err) => {
-				tracing::warn!(error = ?err, "Failed to create tray icon image.");
-
-				event_loop.exit();
+		let Some(tray_icon) = Self::build_tray_icon(tray_menu) else {
+			event_loop.exit();
 
-				return;
-			},
+			return;
 		};
-		let tray_icon = match TrayIconBuilder::new()
+
+		self.settings_menu_id = Some(settings_item.id().clone());
+		self.capture_menu_id = Some(capture_item.id().clone());
+		self.color_picker_menu_id = Some(color_picker_item.id().clone());
+		self.stop_region_watch_menu_item = Some(stop_region_watch_item);
+		self.start_interval_capture_menu_id = Some(start_interval_capture_item.id().clone());
+		self.stop_interval_capture_menu_item = Some(stop_interval_capture_item);
+		self.identify_displays_menu_id = Some(identify_displays_item.id().clone());
+		self.pause_menu_item = Some(pause_item);
+		self.quit_menu_id = Some(quit_item.id().clone());
+		self.update_menu_item = Some(update_item);
+		self.profile_menu_items = if profile_menu.is_some() { profile_items } else { Vec::new() };
+		self.capture_preset_menu = Some(capture_preset_menu);
+		self.app_capture_menu = Some(app_capture_menu);
+		self.pins_menu = Some(pins_menu);
+		self.close_all_pins_menu_item = Some(close_all_pins_item);
+		self.tray_icon = Some(tray_icon);
+
+		self.sync_capture_preset_menu();
+		self.sync_app_capture_menu();
+		self.sync_update_menu_item();
+	}
+
+	/// Builds the pinned images submenu, which starts with only its close-all entry.
+	fn build_pins_menu() -> (Submenu, MenuItem) {
+		let pins_menu = Submenu::new(tr("tray.pins"), false);
+		let close_all_pins_item = MenuItem::new(tr("pins.close_all"), true, None);
+
+		if let Err(err) =
+			pins_menu.append_items(&[&PredefinedMenuItem::separator(), &close_all_pins_item])
+		{
+			tracing::warn!(error = ?err, "Failed to build tray pins menu.");
+		}
+
+		(pins_menu, close_all_pins_item)
+	}
+
+	/// Builds the profile submenu; a single profile has nothing to switch to, so it gets none.
+	fn build_profile_menu(profile_items: &[CheckMenuItem]) -> Option<Submenu> {
+		if profile_items.len() <= 1 {
+			return None;
+		}
+
+		let item_refs = profile_items
+			.iter()
+			.map(|item| item as &dyn tray_icon::menu::IsMenuItem)
+			.collect::<Vec<_>>();
+
+		Submenu::with_items(tr("tray.profile"), true, &item_refs)
+			.inspect_err(|err| tracing::warn!(error = ?err, "Failed to build tray profile menu."))
+			.ok()
+	}
+
+	/// Builds the tray icon around its menu; `None` when the icon image or the icon fails.
+	fn build_tray_icon(tray_menu: Menu) -> Option<TrayIcon> {
+		let icon = icon::default_tray_icon()
+			.inspect_err(|err| tracing::warn!(error = ?err, "Failed to create tray icon image."))
+			.ok()?;
+
+		TrayIconBuilder::new()
 			.with_tooltip("rsnap")
 			.with_menu(Box::new(tray_menu))
 			.with_icon(icon)
 			.with_icon_as_template(cfg!(target_os = "macos"))
 			.build()
-		{
-			Ok(icon) => icon,
-			Err(err) => {
-				tracing::warn!(error = ?err, "Failed to build tray icon.");
-
-				event_loop.exit();
+			.inspect_err(|err| tracing::warn!(error = ?err, "Failed to build tray icon."))
+			.ok()
+	}
 
-				return;
-			},
+	/// Rebuilds the tray's capture preset entries when the preset names changed.
+	pub(super) fn sync_capture_preset_menu(&mut self) {
+		let Some(menu) = self.capture_preset_menu.as_ref() else {
+			return;
 		};
+		let unchanged = self.capture_preset_menu_items.iter().map(MenuItem::text).eq(self
+			.settings
+			.capture_presets
+			.iter()
+			.map(|preset| preset.name.clone()));
 
-		self.settings_menu_id = Some(settings_item.id().clone());
-		self.capture_menu_id = Some(capture_item.id().clone());
-		self.quit_menu_id = Some(quit_item.id().clone());
-		self.tray_icon = Some(tray_icon);
+		if unchanged {
+			return;
+		}
+
+		for item in self.capture_preset_menu_items.drain(..) {
+			if let Err(err) = menu.remove(&item) {
+				tracing::warn!(error = ?err, "Failed to remove tray capture preset entry.");
+			}
+		}
+
+		for preset in &self.settings.capture_presets {
+			let item = MenuItem::new(&preset.name, true, None);
+
+			if let Err(err) = menu.append(&item) {
+				tracing::warn!(error = ?err, "Failed to add tray capture preset entry.");
+			}
+
+			self.capture_preset_menu_items.push(item);
+		}
+
+		menu.set_enabled(!self.capture_preset_menu_items.is_empty());
+	}
+
+	fn profile_menu_entries(&self) -> Vec<CheckMenuItem> {
+		let active = self.settings.active_profile_index();
+		let default_item =
+			CheckMenuItem::new(tr("tray.profile_default"), true, active.is_none(), None);
+
+		std::iter::once(default_item)
+			.chain(self.settings.profiles.iter().enumerate().map(|(index, profile)| {
+				CheckMenuItem::new(&profile.name, true, active == Some(index), None)
+			}))
+			.collect()
+	}
+
+	fn sync_profile_menu(&self) {
+		let active = self.settings.active_profile_index();
+
+		for (slot, item) in self.profile_menu_items.iter().enumerate() {
+			item.set_checked(slot.checked_sub(1) == active);
+		}
+	}
+
+	/// Activates a settings profile (or the base settings for `None`) and persists the choice.
+	pub(super) fn select_profile(&mut self, index: Option<usize>, requested_by: &'static str) {
+		self.settings.select_profile(index);
+
+		tracing::info!(
+			op = "settings.profile_selected",
+			requested_by = %requested_by,
+			profile = self.settings.active_profile.as_deref().unwrap_or("Default"),
+			"Settings profile selected."
+		);
+
+		if let Err(err) = self.settings.save() {
+			tracing::warn!(error = ?err, "Failed to save settings.");
+		}
+
+		self.sync_profile_menu();
+		self.apply_overlay_settings();
+		self.sync_replay_buffer();
+		self.sync_loupe_stream();
 	}
 
 	pub(super) fn handle_menu_event(&mut self, event_loop: &ActiveEventLoop, event: &MenuEvent) {
@@ -149,6 +303,73 @@ impl App {
 
 			self.start_capture_session(event_loop, "tray-menu");
 		}
+		if Some(id) == self.color_picker_menu_id.as_ref() {
+			handled = true;
+
+			tracing::info!("Color picker requested from tray menu.");
+
+			self.start_color_picker_session(event_loop, "tray-menu");
+		}
+		if self.stop_region_watch_menu_item.as_ref().is_some_and(|item| item.id() == id) {
+			handled = true;
+
+			self.stop_region_watch("tray-menu");
+		}
+		if Some(id) == self.start_interval_capture_menu_id.as_ref() {
+			handled = true;
+
+			tracing::info!("Interval capture requested from tray menu.");
+
+			self.start_interval_capture_picker_session(event_loop, "tray-menu");
+		}
+		if self.stop_interval_capture_menu_item.as_ref().is_some_and(|item| item.id() == id) {
+			handled = true;
+
+			self.stop_interval_capture("tray-menu");
+		}
+		if Some(id) == self.identify_displays_menu_id.as_ref() {
+			handled = true;
+
+			tracing::info!("Identify displays requested from tray menu.");
+
+			self.start_identify_displays_session(event_loop, "tray-menu");
+		}
+		if self.pause_menu_item.as_ref().is_some_and(|item| item.id() == id) {
+			handled = true;
+
+			self.toggle_pause("tray-menu");
+		}
+		if let Some(slot) = self.profile_menu_items.iter().position(|item| item.id() == id) {
+			handled = true;
+
+			self.select_profile(slot.checked_sub(1), "tray-menu");
+		}
+		if let Some(index) = self.capture_preset_menu_items.iter().position(|item| item.id() == id)
+		{
+			handled = true;
+
+			self.start_preset_capture(event_loop, index, "tray-menu");
+		}
+		if let Some(pid) = self
+			.app_capture_menu_items
+			.iter()
+			.find(|(item, _)| item.id() == id)
+			.map(|(_, pid)| *pid)
+		{
+			handled = true;
+
+			self.start_app_capture(pid, "tray-menu");
+		}
+		if self.handle_pins_menu_event(event_loop, id) {
+			handled = true;
+		}
+		if self.update_menu_item.as_ref().is_some_and(|item| item.id() == id) {
+			handled = true;
+
+			tracing::info!("Updates requested from tray menu.");
+
+			self.open_update_menu();
+		}
 		if Some(id) == self.quit_menu_id.as_ref() {
 			handled = true;
 
@@ -192,10 +413,23 @@ impl App {
 		&mut self,
 		event_loop: &ActiveEventLoop,
 		event: GlobalHotKeyEvent,
+		pressed_at: Instant,
 	) {
 		if event.state() != HotKeyState::Pressed {
 			return;
 		}
+		if [
+			self.capture_hotkey_id,
+			self.magnifier_hotkey_id,
+			self.color_picker_hotkey_id,
+			self.display_picker_hotkey_id,
+			self.active_window_hotkey_id,
+			self.repeat_capture_hotkey_id,
+		]
+		.contains(&event.id())
+		{
+			self.overlay_requested_at = Some(pressed_at);
+		}
 		if event.id() == self.capture_hotkey_id {
 			tracing::info!(
 				hotkey = %self.capture_key_label(),
@@ -210,6 +444,56 @@ impl App {
 			);
 
 			self.open_settings_window(event_loop, "global-hotkey");
+		} else if event.id() == self.magnifier_hotkey_id {
+			tracing::info!(
+				hotkey = %self.magnifier_hotkey,
+				"Magnifier toggle requested from hotkey."
+			);
+
+			self.toggle_magnifier_session(event_loop, "global-hotkey");
+		} else if event.id() == self.color_picker_hotkey_id {
+			tracing::info!(
+				hotkey = %self.color_picker_hotkey,
+				"Color picker requested from hotkey."
+			);
+
+			self.start_color_picker_session(event_loop, "global-hotkey");
+		} else if event.id() == self.display_picker_hotkey_id {
+			tracing::info!(
+				hotkey = %self.display_picker_hotkey,
+				"Display picker requested from hotkey."
+			);
+
+			self.start_display_picker_session(event_loop, "global-hotkey");
+		} else if event.id() == self.active_window_hotkey_id {
+			tracing::info!(
+				hotkey = %self.active_window_hotkey,
+				"Active window capture requested from hotkey."
+			);
+
+			self.start_active_window_capture(event_loop, "global-hotkey");
+		} else if event.id() == self.repeat_capture_hotkey_id {
+			tracing::info!(
+				hotkey = %self.repeat_capture_hotkey,
+				"Repeat capture requested from hotkey."
+			);
+
+			self.start_repeat_capture(event_loop, "global-hotkey");
+		} else if event.id() == self.profile_cycle_hotkey_id {
+			tracing::info!(
+				hotkey = %self.profile_cycle_hotkey,
+				"Profile cycle requested from hotkey."
+			);
+
+			self.select_profile(self.settings.next_profile_index(), "global-hotkey");
+		} else if event.id() == self.replay_hotkey_id {
+			tracing::info!(hotkey = %self.replay_hotkey, "Replay export requested from hotkey.");
+
+			self.export_replay("global-hotkey");
+		} else if event.id() == self.pause_hotkey_id {
+			tracing::info!(hotkey = %self.pause_hotkey, "Pause toggle requested from hotkey.");
+
+			self.toggle_pause("global-hotkey");
 		}
 	}
 }
@@ -0,0 +1,215 @@
+//! Supervision of the in-process overlay session.
+//!
+//! A panic inside the overlay ends the session with `OverlayExit::Error` instead of unwinding
+//! through the event loop and taking the tray app down; a session that crashes after running for
+//! a while is relaunched once. The event loop sends a heartbeat to a watchdog thread every cycle;
+//! the watchdog reports a main thread that stops beating for too long as soon as it happens, and
+//! the session is ended as hung once the main thread runs again. A stuck main thread cannot be
+//! preempted from another thread, so the watchdog only reports it.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::app::capture::OverlayLaunch;
+
+/// Heartbeat gaps longer than this end the running session as hung.
+pub(super) const OVERLAY_HANG_TIMEOUT: Duration = Duration::from_secs(15);
+/// How often the watchdog thread looks at the heartbeat.
+const WATCHDOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// A watchdog poll this much later than scheduled means the whole process was suspended, as in
+/// system sleep, rather than the event loop stalling.
+const WATCHDOG_SUSPEND_SLACK: Duration = Duration::from_secs(5);
+/// Crashes sooner than this after launch are not retried, so a session that panics on start does
+/// not relaunch in a loop.
+const OVERLAY_RESTART_MIN_UPTIME: Duration = Duration::from_secs(2);
+/// How many times one hotkey press may relaunch a crashed session.
+const OVERLAY_MAX_RESTARTS: u32 = 1;
+
+#[derive(Debug, Default)]
+pub(super) struct OverlaySupervisor {
+	session: Option<SupervisedSession>,
+	pending_restart: Option<(OverlayLaunch, u32)>,
+	/// Started with the first session and kept for the life of the app.
+	watchdog: Option<OverlayWatchdog>,
+}
+impl OverlaySupervisor {
+	pub(super) fn begin(&mut self, launch: OverlayLaunch, restarts: u32, now: Instant) {
+		self.session = Some(SupervisedSession { launch, restarts, started_at: now });
+
+		if self.watchdog.is_none() {
+			self.watchdog = OverlayWatchdog::start()
+				.inspect_err(|err| {
+					tracing::warn!(error = %err, "Failed to start the overlay watchdog thread.");
+				})
+				.ok();
+		}
+		if let Some(watchdog) = &self.watchdog {
+			watchdog.with_heartbeat(|heartbeat| heartbeat.begin(now));
+		}
+	}
+
+	/// Stops supervising the running session, returning how it was launched.
+	pub(super) fn end(&mut self) -> Option<OverlayLaunch> {
+		if let Some(watchdog) = &self.watchdog {
+			watchdog.with_heartbeat(Heartbeat::end);
+		}
+
+		self.session.take().map(|session| session.launch)
+	}
+
+	/// Sends the event-loop heartbeat and returns how long the loop stalled when the watchdog
+	/// caught it stalling since the previous one.
+	pub(super) fn tick(&mut self, now: Instant) -> Option<Duration> {
+		self.session.as_ref()?;
+
+		self.watchdog.as_ref()?.with_heartbeat(|heartbeat| heartbeat.beat(now))
+	}
+
+	/// Records a crash of the running session and schedules a relaunch when one is still allowed.
+	pub(super) fn record_crash(&mut self, now: Instant) -> bool {
+		let Some(session) = self.session.take() else {
+			return false;
+		};
+		let uptime = now.saturating_duration_since(session.started_at);
+		let restart =
+			session.restarts < OVERLAY_MAX_RESTARTS && uptime >= OVERLAY_RESTART_MIN_UPTIME;
+
+		if restart {
+			self.pending_restart = Some((session.launch, session.restarts + 1));
+		}
+
+		restart
+	}
+
+	pub(super) fn take_pending_restart(&mut self) -> Option<(OverlayLaunch, u32)> {
+		self.pending_restart.take()
+	}
+}
+
+#[derive(Debug)]
+struct SupervisedSession {
+	launch: OverlayLaunch,
+	restarts: u32,
+	started_at: Instant,
+}
+
+/// Event-loop heartbeat shared between the main thread and the watchdog thread.
+#[derive(Debug, Default)]
+pub(super) struct Heartbeat {
+	/// The latest beat of the running session; `None` while no session runs.
+	last_beat: Option<Instant>,
+	last_poll: Option<Instant>,
+	/// A stall the watchdog caught that the main thread has not picked up yet.
+	stall: Option<Duration>,
+}
+impl Heartbeat {
+	pub(super) fn begin(&mut self, now: Instant) {
+		self.last_beat = Some(now);
+		self.stall = None;
+	}
+
+	pub(super) fn end(&mut self) {
+		self.last_beat = None;
+		self.stall = None;
+	}
+
+	/// Records a beat from the event loop and takes the stall the watchdog caught, if any.
+	pub(super) fn beat(&mut self, now: Instant) -> Option<Duration> {
+		let last_beat = self.last_beat.as_mut()?;
+
+		*last_beat = now;
+
+		self.stall.take()
+	}
+
+	/// Looks at the heartbeat from the watchdog thread; returns the gap when it newly exceeds the
+	/// hang timeout. A poll that comes far later than scheduled restarts the gap instead, since
+	/// the event loop could not beat while the process was suspended either.
+	pub(super) fn poll(&mut self, now: Instant) -> Option<Duration> {
+		let last_poll = self.last_poll.replace(now);
+		let last_beat = self.last_beat.as_mut()?;
+
+		if last_poll.is_some_and(|last_poll| {
+			now.saturating_duration_since(last_poll)
+				> WATCHDOG_POLL_INTERVAL + WATCHDOG_SUSPEND_SLACK
+		}) {
+			*last_beat = now;
+
+			return None;
+		}
+		if self.stall.is_some() {
+			return None;
+		}
+
+		let gap = now.saturating_duration_since(*last_beat);
+
+		(gap > OVERLAY_HANG_TIMEOUT).then(|| *self.stall.insert(gap))
+	}
+}
+
+#[derive(Debug)]
+struct OverlayWatchdog {
+	heartbeat: Arc<Mutex<Heartbeat>>,
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl OverlayWatchdog {
+	fn start() -> std::io::Result<Self> {
+		let heartbeat = Arc::new(Mutex::new(Heartbeat::default()));
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let watched = Arc::clone(&heartbeat);
+		let worker = thread::Builder::new().name(String::from("rsnap-overlay-watchdog")).spawn(
+			move || {
+				loop {
+					match stop_rx.recv_timeout(WATCHDOG_POLL_INTERVAL) {
+						Err(RecvTimeoutError::Timeout) => {},
+						Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+					}
+
+					let stall =
+						watched.lock().unwrap_or_else(PoisonError::into_inner).poll(Instant::now());
+
+					if let Some(gap) = stall {
+						tracing::error!(
+							gap_ms = gap.as_millis(),
+							"Overlay event loop stopped responding; the session ends once it \
+							 resumes."
+						);
+					}
+				}
+			},
+		)?;
+
+		Ok(Self { heartbeat, stop_tx, worker: Some(worker) })
+	}
+
+	fn with_heartbeat<T>(&self, f: impl FnOnce(&mut Heartbeat) -> T) -> T {
+		f(&mut self.heartbeat.lock().unwrap_or_else(PoisonError::into_inner))
+	}
+}
+impl Drop for OverlayWatchdog {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+	}
+}
+
+/// Runs `f`, turning a panic into its message.
+pub(super) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+	panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+	payload
+		.downcast_ref::<&str>()
+		.map(|message| (*message).to_owned())
+		.or_else(|| payload.downcast_ref::<String>().cloned())
+		.unwrap_or_else(|| String::from("unknown panic payload"))
+}
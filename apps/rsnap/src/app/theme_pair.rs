@@ -0,0 +1,14 @@
+use crate::app::App;
+use rsnap_overlay::{MonitorRect, RectPoints};
+
+impl App {
+	/// Captures `rect_px` in the light and dark appearance in the background, using the current
+	/// settings.
+	pub(super) fn start_theme_pair_capture(&self, monitor: MonitorRect, rect_px: RectPoints) {
+		if let Err(err) =
+			rsnap_overlay::capture_theme_pair(monitor, rect_px, self.settings.theme_pair_config())
+		{
+			tracing::warn!(error = %format!("{err:#}"), "Failed to start theme pair capture.");
+		}
+	}
+}
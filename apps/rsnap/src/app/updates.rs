@@ -0,0 +1,169 @@
+//! Opt-in background update checks and the tray's update entry.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use winit::event_loop::{ActiveEventLoop, EventLoopProxy};
+
+use crate::app::{App, UserEvent};
+use crate::updates::{self, NotesChoice, Release};
+use rsnap_overlay::{OverlayExit, tr, tr_args};
+
+/// How often the checker wakes to see whether a check is due.
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// How long to wait between scheduled checks.
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Results from the update threads. Dialogs are shown on those threads; the event loop only
+/// records what they found.
+#[derive(Debug)]
+pub(crate) enum UpdateEvent {
+	/// A check finished with a newer release, none, or an error.
+	Checked(Result<Option<Release>, String>),
+	/// An update was installed at this path; rsnap restarts into it.
+	Installed(PathBuf),
+}
+
+/// Checks for updates about a minute after launch and then daily, while `enabled` is set.
+pub(super) fn spawn_update_checker(proxy: EventLoopProxy<UserEvent>, enabled: Arc<AtomicBool>) {
+	let spawned =
+		thread::Builder::new().name(String::from("rsnap-update-check")).spawn(move || {
+			let mut last_check: Option<Instant> = None;
+
+			loop {
+				thread::sleep(UPDATE_POLL_INTERVAL);
+
+				let due = last_check.is_none_or(|at| at.elapsed() >= UPDATE_CHECK_INTERVAL);
+
+				if !enabled.load(Ordering::Relaxed) || !due {
+					continue;
+				}
+
+				last_check = Some(Instant::now());
+
+				let result = updates::check_for_update().map_err(|err| format!("{err:#}"));
+
+				if proxy.send_event(UserEvent::Update(UpdateEvent::Checked(result))).is_err() {
+					return;
+				}
+			}
+		});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start update checker.");
+	}
+}
+
+impl App {
+	pub(super) fn handle_update_event(&mut self, event_loop: &ActiveEventLoop, event: UpdateEvent) {
+		match event {
+			UpdateEvent::Checked(Ok(Some(release))) => {
+				tracing::info!(version = %release.version, "Update available.");
+
+				self.available_update = Some(release);
+
+				self.sync_update_menu_item();
+			},
+			UpdateEvent::Checked(Ok(None)) => {},
+			UpdateEvent::Checked(Err(err)) => {
+				tracing::warn!(error = %err, "Update check failed.");
+			},
+			UpdateEvent::Installed(path) => {
+				tracing::info!(path = %path.display(), "Restarting into the installed update.");
+
+				self.relaunch_after_exit = Some(path);
+
+				self.end_overlay_session(OverlayExit::Cancelled);
+
+				self.settings_window = None;
+
+				event_loop.exit();
+			},
+		}
+	}
+
+	/// The tray's update entry: shows the notes of a known update, or checks right away and
+	/// reports the outcome.
+	pub(super) fn open_update_menu(&mut self) {
+		match self.available_update.clone() {
+			Some(release) => self.spawn_update_task(move |proxy| offer_update(proxy, &release)),
+			None => self.spawn_update_task(|proxy| {
+				let result = updates::check_for_update().map_err(|err| format!("{err:#}"));
+
+				let _ = proxy.send_event(UserEvent::Update(UpdateEvent::Checked(result.clone())));
+
+				match result {
+					Ok(Some(release)) => offer_update(proxy, &release),
+					Ok(None) => updates::show_message(&tr_args(
+						"updates.up_to_date",
+						&[("version", &updates::CURRENT_VERSION)],
+					)),
+					Err(err) => {
+						updates::show_message(&tr_args("updates.check_failed", &[("error", &err)]));
+					},
+				}
+			}),
+		}
+	}
+
+	pub(super) fn sync_update_menu_item(&self) {
+		let Some(item) = self.update_menu_item.as_ref() else {
+			return;
+		};
+
+		match self.available_update.as_ref() {
+			Some(release) => {
+				item.set_text(tr_args("tray.update_available", &[("version", &release.version)]));
+			},
+			None => item.set_text(tr("tray.check_updates")),
+		}
+	}
+
+	/// Runs `task` on its own thread unless another update window or download is still open.
+	fn spawn_update_task(
+		&mut self,
+		task: impl FnOnce(&EventLoopProxy<UserEvent>) + Send + 'static,
+	) {
+		let Some(proxy) = self.event_proxy.clone() else {
+			return;
+		};
+
+		if self.update_task_running.swap(true, Ordering::AcqRel) {
+			tracing::info!("An update window is already open.");
+
+			return;
+		}
+
+		let running = Arc::clone(&self.update_task_running);
+		let spawned = thread::Builder::new().name(String::from("rsnap-update")).spawn(move || {
+			task(&proxy);
+			running.store(false, Ordering::Release);
+		});
+
+		if let Err(err) = spawned {
+			self.update_task_running.store(false, Ordering::Release);
+
+			tracing::warn!(error = %err, "Failed to start update task.");
+		}
+	}
+}
+
+/// Shows the release notes, then installs the update or opens its page as picked.
+fn offer_update(proxy: &EventLoopProxy<UserEvent>, release: &Release) {
+	match updates::show_release_notes(release) {
+		NotesChoice::Install => match release.install() {
+			Ok(path) => {
+				let _ = proxy.send_event(UserEvent::Update(UpdateEvent::Installed(path)));
+			},
+			Err(err) => updates::show_message(&tr_args(
+				"updates.install_failed",
+				&[("error", &format!("{err:#}"))],
+			)),
+		},
+		NotesChoice::OpenPage => updates::open_page(&release.page_url),
+		NotesChoice::Later => {},
+	}
+}
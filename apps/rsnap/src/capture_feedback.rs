@@ -0,0 +1,257 @@
+//! Confirmation that a capture finished: a shutter sound and a notification about the capture.
+//!
+//! The sound goes through the desktop's own player, so it follows the system volume. The
+//! notification shows a thumbnail and carries out the button picked on it; see
+//! [`crate::notifications`] for what each platform can show.
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::process::Command;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+use std::process::Stdio;
+use std::thread;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::notifications::{self, Notification, NotificationAction};
+use rsnap_overlay::{tr, tr_args};
+
+#[cfg(target_os = "macos")]
+const FILE_OPENER: &str = "open";
+#[cfg(target_os = "windows")]
+const FILE_OPENER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const FILE_OPENER: &str = "xdg-open";
+/// Longest side of the notification thumbnail, in pixels.
+const THUMBNAIL_SIDE_PX: u32 = 320;
+
+/// Buttons after an interactive capture; its editor just closed, so Annotate is left out.
+pub(crate) const INTERACTIVE_ACTIONS: &[NotificationAction] =
+	&[NotificationAction::Open, NotificationAction::Copy];
+/// Buttons after a background capture that was saved to a file.
+pub(crate) const SAVED_BACKGROUND_ACTIONS: &[NotificationAction] = &[
+	NotificationAction::Open,
+	NotificationAction::Copy,
+	NotificationAction::Delete,
+	NotificationAction::Annotate,
+];
+/// Buttons after a background capture that was only copied; there is no file to delete.
+pub(crate) const COPIED_BACKGROUND_ACTIONS: &[NotificationAction] =
+	&[NotificationAction::Open, NotificationAction::Copy, NotificationAction::Annotate];
+
+/// A finished export, as the notification describes and acts on it.
+#[derive(Debug)]
+pub(crate) enum CapturedImage {
+	/// Encoded PNG that was only copied to the clipboard.
+	Copied(Vec<u8>),
+	/// File the export was saved to.
+	Saved(PathBuf),
+}
+impl CapturedImage {
+	/// The notification text for this capture.
+	pub(crate) fn describe(&self) -> String {
+		match self {
+			Self::Copied(_) => tr("notification.copied").to_owned(),
+			Self::Saved(path) => tr_args("notification.saved", &[("file", &file_name(path))]),
+		}
+	}
+
+	pub(crate) fn png_bytes(&self) -> Result<Cow<'_, [u8]>> {
+		match self {
+			Self::Copied(png_bytes) => Ok(Cow::Borrowed(png_bytes)),
+			Self::Saved(path) => std::fs::read(path)
+				.map(Cow::Owned)
+				.wrap_err_with(|| format!("Failed to read {}", path.display())),
+		}
+	}
+
+	/// The saved file, or a temporary copy of a clipboard-only capture.
+	pub(crate) fn file(&self) -> Result<PathBuf> {
+		match self {
+			Self::Saved(path) => Ok(path.clone()),
+			Self::Copied(png_bytes) => {
+				let path = notification_dir()?.join(format!("capture-{}.png", unix_millis()));
+
+				std::fs::write(&path, png_bytes)
+					.wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+
+				Ok(path)
+			},
+		}
+	}
+}
+
+/// Plays the desktop's screenshot sound without waiting for it to finish.
+pub(crate) fn play_shutter_sound() {
+	let spawned = thread::Builder::new().name(String::from("rsnap-shutter")).spawn(|| {
+		let played = shutter_sound_commands()
+			.into_iter()
+			.any(|mut command| command.status().is_ok_and(|status| status.success()));
+
+		if !played {
+			tracing::debug!("No sound player available for the shutter sound.");
+		}
+	});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start the shutter sound.");
+	}
+}
+
+/// Runs [`show_capture_notification`] on its own thread, handing the file to `on_annotate` when
+/// Annotate is picked.
+pub(crate) fn spawn_capture_notification(
+	image: CapturedImage,
+	text: String,
+	actions: &'static [NotificationAction],
+	on_annotate: impl FnOnce(PathBuf) + Send + 'static,
+) {
+	let spawned =
+		thread::Builder::new().name(String::from("rsnap-notification")).spawn(move || {
+			match show_capture_notification(&image, &text, actions) {
+				Ok(Some(path)) => on_annotate(path),
+				Ok(None) => {},
+				Err(err) => tracing::warn!(error = ?err, "Capture notification failed."),
+			}
+		});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start the capture notification.");
+	}
+}
+
+/// Shows a notification for `image` with a thumbnail and `actions`, blocks until it closes, and
+/// carries out Open, Copy or Delete when picked. Returns the file to open in the editor when
+/// Annotate is picked, since only the caller can do that.
+pub(crate) fn show_capture_notification(
+	image: &CapturedImage,
+	text: &str,
+	actions: &[NotificationAction],
+) -> Result<Option<PathBuf>> {
+	// AppleScript notifications cannot show a thumbnail, so none is written there.
+	let thumbnail = if cfg!(target_os = "macos") {
+		None
+	} else {
+		write_thumbnail(&image.png_bytes()?)
+			.inspect_err(|err| tracing::debug!(error = ?err, "No notification thumbnail."))
+			.ok()
+	};
+	let notification = Notification { text, image: thumbnail.as_deref(), actions };
+	let Some(action) = notifications::show(&notification)? else {
+		return Ok(None);
+	};
+
+	tracing::info!(op = "notification.action", ?action, "Capture notification clicked.");
+
+	match action {
+		NotificationAction::Open => {
+			let path = image.file()?;
+
+			Command::new(FILE_OPENER)
+				.arg(&path)
+				.status()
+				.wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+		},
+		NotificationAction::Copy => rsnap_overlay::copy_png_to_clipboard(&image.png_bytes()?)?,
+		NotificationAction::Delete => {
+			if let CapturedImage::Saved(path) = image {
+				std::fs::remove_file(path)
+					.wrap_err_with(|| format!("Failed to delete {}", path.display()))?;
+			}
+		},
+		NotificationAction::Annotate => return image.file().map(Some),
+	}
+
+	Ok(None)
+}
+
+fn file_name(path: &std::path::Path) -> String {
+	path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned()
+}
+
+fn notification_dir() -> Result<PathBuf> {
+	let dir = std::env::temp_dir().join("rsnap-notifications");
+
+	std::fs::create_dir_all(&dir)
+		.wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+
+	Ok(dir)
+}
+
+fn unix_millis() -> u128 {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map_or(0, |elapsed| elapsed.as_millis())
+}
+
+/// Writes a small copy of the capture for the notification to show.
+fn write_thumbnail(png_bytes: &[u8]) -> Result<PathBuf> {
+	let image = image::load_from_memory(png_bytes).wrap_err("Failed to decode the capture")?;
+	let path = notification_dir()?.join("thumbnail.png");
+
+	image
+		.thumbnail(THUMBNAIL_SIDE_PX, THUMBNAIL_SIDE_PX)
+		.save(&path)
+		.wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+
+	Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn shutter_sound_commands() -> Vec<Command> {
+	[
+		"/System/Library/Components/CoreAudio.component/Contents/SharedSupport/SystemSounds/\
+		 system/Screen Capture.aif",
+		"/System/Library/Sounds/Tink.aiff",
+	]
+	.into_iter()
+	.filter(|path| std::path::Path::new(path).exists())
+	.map(|path| {
+		let mut command = Command::new("afplay");
+
+		command.arg(path);
+
+		command
+	})
+	.collect()
+}
+
+#[cfg(target_os = "windows")]
+fn shutter_sound_commands() -> Vec<Command> {
+	let mut command = Command::new("powershell");
+
+	command.args([
+		"-NoProfile",
+		"-Command",
+		"(New-Object Media.SoundPlayer \"$env:WINDIR\\Media\\Windows Navigation Start.wav\")\
+		 .PlaySync()",
+	]);
+
+	vec![command]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn shutter_sound_commands() -> Vec<Command> {
+	let mut canberra = Command::new("canberra-gtk-play");
+	let mut paplay = Command::new("paplay");
+
+	canberra.args(["--id", "screen-capture"]).stderr(Stdio::null());
+	paplay.arg("/usr/share/sounds/freedesktop/stereo/screen-capture.oga").stderr(Stdio::null());
+
+	vec![canberra, paplay]
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use crate::capture_feedback::CapturedImage;
+
+	#[test]
+	fn captures_describe_where_they_went() {
+		let saved = CapturedImage::Saved(PathBuf::from("shots").join("rsnap-0001.png"));
+
+		assert_eq!(CapturedImage::Copied(Vec::new()).describe(), "Capture copied to the clipboard");
+		assert_eq!(saved.describe(), "Capture saved as rsnap-0001.png");
+	}
+}
@@ -0,0 +1,556 @@
+//! A small popover that previews a finished capture in a screen corner, like the thumbnail
+//! macOS shows after a screenshot.
+//!
+//! It offers Annotate, Copy, Save and Dismiss for a few seconds. Left alone, it times out and
+//! the caller saves a capture that was only copied. On macOS and Windows the preview can also be
+//! dragged into another app, which receives the capture as a PNG file.
+//!
+//! The clipboard watch reuses it to offer images copied in other apps. Pinning one keeps it on
+//! top as a reference image until it is dismissed; dragging a pinned preview moves the window,
+//! and right-clicking it lists every pin with snap, click-through and close-all commands.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use egui::{PointerButton, TextureOptions, ViewportId};
+use egui_wgpu::{Renderer, ScreenDescriptor};
+use image::RgbaImage;
+use wgpu::{Surface, SurfaceConfiguration, SurfaceError, TextureViewDescriptor};
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event::{ElementState, WindowEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::keyboard::{Key, NamedKey};
+use winit::monitor::MonitorHandle;
+use winit::window::{Window, WindowId, WindowLevel};
+
+use crate::capture_feedback::CapturedImage;
+use crate::settings_window::GpuContext;
+use rsnap_overlay::{exclude_window_from_capture, tr};
+
+/// How long the popover stays up while the pointer is away from it.
+const POPOVER_LINGER: Duration = Duration::from_secs(5);
+const POPOVER_WIDTH: f64 = 300.0;
+const POPOVER_HEIGHT: f64 = 220.0;
+const POPOVER_SIDE_MARGIN: f64 = 16.0;
+/// Leaves room for the macOS menu bar along the top edge.
+const POPOVER_TOP_MARGIN: f64 = 40.0;
+/// Leaves room for a Dock or taskbar along the bottom edge.
+const POPOVER_BOTTOM_MARGIN: f64 = 72.0;
+/// Longest side of the preview texture, in pixels.
+const POPOVER_PREVIEW_SIDE_PX: u32 = 600;
+/// Longest side of the thumbnail the tray shows next to a pin, in pixels.
+const POPOVER_THUMBNAIL_SIDE_PX: u32 = 32;
+/// Whether [`rsnap_overlay::start_file_drag`] works on this platform.
+const POPOVER_DRAG_SUPPORTED: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// Where the image in a popover came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PopoverSource {
+	/// A capture that rsnap just copied or saved.
+	Capture,
+	/// An image another app copied to the clipboard; it can be pinned and is never auto-saved.
+	Clipboard,
+}
+
+/// A screen corner a pinned popover can snap to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ScreenCorner {
+	TopLeft,
+	TopRight,
+	BottomLeft,
+	BottomRight,
+}
+impl ScreenCorner {
+	pub(crate) const ALL: [Self; 4] =
+		[Self::TopLeft, Self::TopRight, Self::BottomLeft, Self::BottomRight];
+
+	pub(crate) const fn label_key(self) -> &'static str {
+		match self {
+			Self::TopLeft => "pins.top_left",
+			Self::TopRight => "pins.top_right",
+			Self::BottomLeft => "pins.bottom_left",
+			Self::BottomRight => "pins.bottom_right",
+		}
+	}
+}
+
+/// A pin's context-menu command that reaches beyond its own window, for the app to carry out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PinCommand {
+	/// Bring the pin at this position in the pin list to the front.
+	Focus(usize),
+	CloseAll,
+}
+
+/// How the popover closed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PopoverChoice {
+	Annotate,
+	Copy,
+	Save,
+	Dismiss,
+	/// Nothing was picked before the popover timed out.
+	Expired,
+}
+
+pub(crate) struct CapturePopover {
+	window: Arc<Window>,
+	gpu: GpuContext,
+	surface: Surface<'static>,
+	surface_config: SurfaceConfiguration,
+	egui_ctx: egui::Context,
+	egui_state: egui_winit::State,
+	renderer: Renderer,
+	preview: Option<Preview>,
+	image: CapturedImage,
+	source: PopoverSource,
+	/// Pinned popovers stay open until dismissed.
+	pinned: bool,
+	/// Whether clicks pass through the pin to the windows below it.
+	click_through: bool,
+	/// Titles of every pin, for the context menu's Bring to Front list.
+	pin_titles: Vec<String>,
+	pin_command: Option<PinCommand>,
+	/// When the popover times out; `None` while the pointer is over it or once it is pinned.
+	deadline: Option<Instant>,
+}
+impl CapturePopover {
+	/// Opens the popover in the bottom-right corner of the primary display without taking focus.
+	pub(crate) fn open(
+		event_loop: &ActiveEventLoop,
+		image: CapturedImage,
+		source: PopoverSource,
+	) -> Result<Self> {
+		let mut attrs = Window::default_attributes()
+			.with_title("rsnap")
+			.with_decorations(false)
+			.with_resizable(false)
+			.with_active(false)
+			.with_window_level(WindowLevel::AlwaysOnTop)
+			.with_inner_size(LogicalSize::new(POPOVER_WIDTH, POPOVER_HEIGHT));
+
+		if let Some(monitor) =
+			event_loop.primary_monitor().or_else(|| event_loop.available_monitors().next())
+		{
+			attrs = attrs.with_position(corner_position(&monitor, ScreenCorner::BottomRight));
+		}
+
+		let window = event_loop.create_window(attrs).wrap_err("create capture popover")?;
+
+		// Keep the popover out of the next capture.
+		exclude_window_from_capture(&window);
+
+		let window = Arc::new(window);
+		let (gpu, surface, surface_config) = GpuContext::new_with_surface(Arc::clone(&window))?;
+		let egui_ctx = egui::Context::default();
+		let egui_state = egui_winit::State::new(
+			egui_ctx.clone(),
+			ViewportId::ROOT,
+			window.as_ref(),
+			None,
+			None,
+			None,
+		);
+		let renderer = Renderer::new(
+			&gpu.device,
+			surface_config.format,
+			egui_wgpu::RendererOptions {
+				msaa_samples: 1,
+				depth_stencil_format: None,
+				dithering: false,
+				predictable_texture_filtering: false,
+			},
+		);
+		let preview = load_preview(&egui_ctx, &image)
+			.inspect_err(|err| tracing::debug!(error = ?err, "No capture popover preview."))
+			.ok();
+
+		Ok(Self {
+			window,
+			gpu,
+			surface,
+			surface_config,
+			egui_ctx,
+			egui_state,
+			renderer,
+			preview,
+			image,
+			source,
+			pinned: false,
+			click_through: false,
+			pin_titles: Vec::new(),
+			pin_command: None,
+			deadline: Some(Instant::now() + POPOVER_LINGER),
+		})
+	}
+
+	#[must_use]
+	pub(crate) fn window_id(&self) -> WindowId {
+		self.window.id()
+	}
+
+	/// When the popover times out, unless the pointer is over it.
+	pub(crate) const fn deadline(&self) -> Option<Instant> {
+		self.deadline
+	}
+
+	pub(crate) const fn source(&self) -> PopoverSource {
+		self.source
+	}
+
+	pub(crate) const fn is_pinned(&self) -> bool {
+		self.pinned
+	}
+
+	pub(crate) const fn is_click_through(&self) -> bool {
+		self.click_through
+	}
+
+	/// The full size of the image, when it could be decoded.
+	pub(crate) fn image_size(&self) -> Option<(u32, u32)> {
+		self.preview.as_ref().map(|preview| preview.image_size)
+	}
+
+	/// A small copy of the image for menus.
+	pub(crate) fn thumbnail(&self) -> Option<&RgbaImage> {
+		self.preview.as_ref().map(|preview| &preview.thumbnail)
+	}
+
+	pub(crate) fn set_pin_titles(&mut self, titles: Vec<String>) {
+		if self.pin_titles != titles {
+			self.pin_titles = titles;
+
+			self.window.request_redraw();
+		}
+	}
+
+	/// The last context-menu command that the app has to carry out.
+	pub(crate) const fn take_pin_command(&mut self) -> Option<PinCommand> {
+		self.pin_command.take()
+	}
+
+	pub(crate) fn bring_to_front(&self) {
+		self.window.set_minimized(false);
+		self.window.focus_window();
+	}
+
+	/// Moves the popover to `corner` of the display it is on.
+	pub(crate) fn snap_to(&self, corner: ScreenCorner) {
+		let Some(monitor) = self.window.current_monitor() else {
+			return;
+		};
+
+		self.window.set_outer_position(corner_position(&monitor, corner));
+	}
+
+	/// Lets clicks pass through the popover, or catches them again. A click-through pin can
+	/// only be reached from the tray.
+	pub(crate) fn set_click_through(&mut self, click_through: bool) {
+		if let Err(err) = self.window.set_cursor_hittest(!click_through) {
+			tracing::warn!(error = ?err, "Failed to change pin click-through.");
+
+			return;
+		}
+
+		self.click_through = click_through;
+	}
+
+	/// The capture the popover was showing.
+	pub(crate) fn into_image(self) -> CapturedImage {
+		self.image
+	}
+
+	/// Handles every event but redraws; closing the window or pressing Escape dismisses it.
+	pub(crate) fn handle_window_event(&mut self, event: &WindowEvent) -> Option<PopoverChoice> {
+		match event {
+			WindowEvent::CloseRequested => return Some(PopoverChoice::Dismiss),
+			WindowEvent::KeyboardInput { event, .. }
+				if event.state == ElementState::Pressed
+					&& event.logical_key == Key::Named(NamedKey::Escape) =>
+			{
+				return Some(PopoverChoice::Dismiss);
+			},
+			WindowEvent::CursorEntered { .. } => self.deadline = None,
+			WindowEvent::CursorLeft { .. } if !self.pinned => {
+				self.deadline = Some(Instant::now() + POPOVER_LINGER);
+			},
+			WindowEvent::Resized(size) => {
+				self.surface_config.width = size.width.max(1);
+				self.surface_config.height = size.height.max(1);
+
+				self.gpu.configure_surface(&self.surface, &mut self.surface_config);
+			},
+			_ => {},
+		}
+
+		let _ = self.egui_state.on_window_event(&self.window, event);
+
+		self.window.request_redraw();
+
+		None
+	}
+
+	/// Paints the preview and buttons, returning the button clicked.
+	pub(crate) fn draw(&mut self) -> Result<Option<PopoverChoice>> {
+		let raw_input = self.egui_state.take_egui_input(&self.window);
+		let mut input = PopoverInput::default();
+		let egui_ctx = self.egui_ctx.clone();
+		let full_output = egui_ctx.run(raw_input, |ctx| input = self.ui(ctx));
+
+		if full_output
+			.viewport_output
+			.get(&ViewportId::ROOT)
+			.is_some_and(|viewport_output| viewport_output.repaint_delay < Duration::from_secs(1))
+		{
+			self.window.request_redraw();
+		}
+
+		self.egui_state.handle_platform_output(&self.window, full_output.platform_output);
+
+		for (id, delta) in &full_output.textures_delta.set {
+			self.renderer.update_texture(&self.gpu.device, &self.gpu.queue, *id, delta);
+		}
+		for id in &full_output.textures_delta.free {
+			self.renderer.free_texture(id);
+		}
+
+		let pixels_per_point = self.window.scale_factor() as f32;
+		let paint_jobs = self.egui_ctx.tessellate(full_output.shapes, pixels_per_point);
+		let size = self.window.inner_size();
+		let screen_descriptor = ScreenDescriptor {
+			size_in_pixels: [size.width.max(1), size.height.max(1)],
+			pixels_per_point,
+		};
+		let frame = match self.surface.get_current_texture() {
+			Ok(frame) => frame,
+			Err(SurfaceError::Outdated | SurfaceError::Lost) => {
+				self.gpu.configure_surface(&self.surface, &mut self.surface_config);
+				self.window.request_redraw();
+
+				return Ok(input.choice);
+			},
+			Err(err) => return Err(eyre::eyre!("get_current_texture failed: {err:?}")),
+		};
+		let view = frame.texture.create_view(&TextureViewDescriptor::default());
+		let mut encoder = self.gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+			label: Some("rsnap-popover encoder"),
+		});
+
+		self.renderer.update_buffers(
+			&self.gpu.device,
+			&self.gpu.queue,
+			&mut encoder,
+			&paint_jobs,
+			&screen_descriptor,
+		);
+
+		{
+			let panel_fill = self.egui_ctx.style().visuals.panel_fill;
+			let clear = wgpu::Color {
+				r: f64::from(panel_fill.r()) / 255.0,
+				g: f64::from(panel_fill.g()) / 255.0,
+				b: f64::from(panel_fill.b()) / 255.0,
+				a: 1.0,
+			};
+			let rpass_desc = wgpu::RenderPassDescriptor {
+				label: Some("rsnap-popover rpass"),
+				color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+					view: &view,
+					depth_slice: None,
+					resolve_target: None,
+					ops: wgpu::Operations {
+						load: wgpu::LoadOp::Clear(clear),
+						store: wgpu::StoreOp::Store,
+					},
+				})],
+				depth_stencil_attachment: None,
+				timestamp_writes: None,
+				occlusion_query_set: None,
+			};
+			let mut rpass = encoder.begin_render_pass(&rpass_desc).forget_lifetime();
+
+			self.renderer.render(&mut rpass, &paint_jobs, &screen_descriptor);
+		}
+
+		self.gpu.queue.submit(Some(encoder.finish()));
+		frame.present();
+
+		if input.pin {
+			self.pinned = true;
+			self.deadline = None;
+
+			self.window.request_redraw();
+		}
+		if let Some(corner) = input.snap {
+			self.snap_to(corner);
+		}
+		if input.click_through {
+			self.set_click_through(true);
+		}
+		if input.pin_command.is_some() {
+			self.pin_command = input.pin_command;
+		}
+		if input.drag_started && self.pinned {
+			if let Err(err) = self.window.drag_window() {
+				tracing::debug!(error = ?err, "Pinned popover move failed.");
+			}
+		} else if input.drag_started {
+			self.drag_capture();
+		}
+
+		Ok(input.choice)
+	}
+
+	/// Drags the capture out as a file; a copied capture is dragged from a temporary copy.
+	fn drag_capture(&mut self) {
+		let result =
+			self.image.file().and_then(|path| rsnap_overlay::start_file_drag(&self.window, &path));
+
+		if let Err(err) = result {
+			tracing::warn!(error = ?err, "Capture popover drag failed.");
+		}
+
+		// The pointer may leave the popover mid-drag without a `CursorLeft`, so restart the
+		// timeout instead of waiting for one.
+		self.deadline = Some(Instant::now() + POPOVER_LINGER);
+	}
+
+	fn ui(&self, ctx: &egui::Context) -> PopoverInput {
+		let mut input = PopoverInput::default();
+		let clipboard = self.source == PopoverSource::Clipboard;
+		// A clipboard image is already on the clipboard and a saved capture is already on disk,
+		// so neither offers that again.
+		let buttons = [
+			(clipboard && !self.pinned).then_some((None, "popover.pin")),
+			Some((Some(PopoverChoice::Annotate), "popover.annotate")),
+			(!clipboard).then_some((Some(PopoverChoice::Copy), "popover.copy")),
+			matches!(self.image, CapturedImage::Copied(_))
+				.then_some((Some(PopoverChoice::Save), "popover.save")),
+			Some((Some(PopoverChoice::Dismiss), "popover.dismiss")),
+		];
+
+		egui::TopBottomPanel::bottom("popover-actions").show(ctx, |ui| {
+			ui.horizontal_centered(|ui| {
+				for (choice, label) in buttons.into_iter().flatten() {
+					if ui.button(tr(label)).clicked() {
+						input.pin = choice.is_none();
+						input.choice = choice;
+					}
+				}
+			});
+		});
+		egui::CentralPanel::default().show(ctx, |ui| {
+			if let Some(preview) = self.preview.as_ref() {
+				ui.centered_and_justified(|ui| {
+					let sense = if self.pinned {
+						egui::Sense::click_and_drag()
+					} else if POPOVER_DRAG_SUPPORTED {
+						egui::Sense::drag()
+					} else {
+						egui::Sense::hover()
+					};
+					let response =
+						ui.add(egui::Image::new(&preview.texture).shrink_to_fit().sense(sense));
+
+					if self.pinned {
+						response.context_menu(|ui| self.pin_menu_ui(ui, &mut input));
+					}
+					if POPOVER_DRAG_SUPPORTED || self.pinned {
+						input.drag_started = response
+							.on_hover_cursor(egui::CursorIcon::Grab)
+							.drag_started_by(PointerButton::Primary);
+					}
+				});
+			}
+		});
+
+		input
+	}
+
+	/// The context menu of a pinned popover.
+	fn pin_menu_ui(&self, ui: &mut egui::Ui, input: &mut PopoverInput) {
+		ui.menu_button(tr("pins.bring_to_front"), |ui| {
+			for (index, title) in self.pin_titles.iter().enumerate() {
+				if ui.button(title).clicked() {
+					input.pin_command = Some(PinCommand::Focus(index));
+				}
+			}
+		});
+		ui.menu_button(tr("pins.snap"), |ui| {
+			for corner in ScreenCorner::ALL {
+				if ui.button(tr(corner.label_key())).clicked() {
+					input.snap = Some(corner);
+				}
+			}
+		});
+
+		if ui.button(tr("pins.click_through")).clicked() {
+			input.click_through = true;
+		}
+
+		ui.separator();
+
+		if ui.button(tr("pins.close_all")).clicked() {
+			input.pin_command = Some(PinCommand::CloseAll);
+		}
+	}
+}
+
+/// The decoded image, scaled for the popover and for menus.
+struct Preview {
+	texture: egui::TextureHandle,
+	thumbnail: RgbaImage,
+	image_size: (u32, u32),
+}
+
+/// What one frame of the popover asked for. Pinning keeps the popover open, so it is not a
+/// [`PopoverChoice`].
+#[derive(Default)]
+struct PopoverInput {
+	choice: Option<PopoverChoice>,
+	pin: bool,
+	/// The preview started being dragged: out as a file, or to move a pinned popover.
+	drag_started: bool,
+	snap: Option<ScreenCorner>,
+	click_through: bool,
+	pin_command: Option<PinCommand>,
+}
+
+/// Where the popover's top-left corner goes to sit in `corner` of `monitor`, clear of the
+/// menu bar, Dock and taskbar.
+fn corner_position(monitor: &MonitorHandle, corner: ScreenCorner) -> PhysicalPosition<i32> {
+	let scale = monitor.scale_factor();
+	let origin = monitor.position();
+	let size = monitor.size();
+	let left = origin.x + (POPOVER_SIDE_MARGIN * scale) as i32;
+	let right =
+		origin.x + size.width as i32 - ((POPOVER_WIDTH + POPOVER_SIDE_MARGIN) * scale) as i32;
+	let top = origin.y + (POPOVER_TOP_MARGIN * scale) as i32;
+	let bottom =
+		origin.y + size.height as i32 - ((POPOVER_HEIGHT + POPOVER_BOTTOM_MARGIN) * scale) as i32;
+
+	match corner {
+		ScreenCorner::TopLeft => PhysicalPosition::new(left, top),
+		ScreenCorner::TopRight => PhysicalPosition::new(right, top),
+		ScreenCorner::BottomLeft => PhysicalPosition::new(left, bottom),
+		ScreenCorner::BottomRight => PhysicalPosition::new(right, bottom),
+	}
+}
+
+/// Uploads a downscaled copy of the capture for the popover to show.
+fn load_preview(ctx: &egui::Context, image: &CapturedImage) -> Result<Preview> {
+	let decoded =
+		image::load_from_memory(&image.png_bytes()?).wrap_err("Failed to decode the capture")?;
+	let preview = decoded.thumbnail(POPOVER_PREVIEW_SIDE_PX, POPOVER_PREVIEW_SIDE_PX).into_rgba8();
+	let size = [preview.width() as usize, preview.height() as usize];
+	let texture = ctx.load_texture(
+		"capture-popover-preview",
+		egui::ColorImage::from_rgba_unmultiplied(size, preview.as_raw()),
+		TextureOptions::LINEAR,
+	);
+	let thumbnail =
+		decoded.thumbnail(POPOVER_THUMBNAIL_SIDE_PX, POPOVER_THUMBNAIL_SIDE_PX).into_rgba8();
+
+	Ok(Preview { texture, thumbnail, image_size: (decoded.width(), decoded.height()) })
+}
@@ -0,0 +1,1141 @@
+//! Headless subcommands that run without starting the tray app.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::RgbaImage;
+use rsnap_overlay::{
+	CaptureBackendKind, CaptureBackendProbe, DiffOptions, DiffReport, MonitorRectPoints,
+	OverlayConfig, OverlaySession, PROJECT_EXTENSION,
+};
+
+use crate::capture_feedback::{self, CapturedImage};
+use crate::latency::LatencyLog;
+use crate::notifications::NotificationAction;
+#[cfg(all(target_os = "windows", not(test)))]
+use crate::notifications::{self, Notification};
+use crate::settings::AppSettings;
+use crate::shell_integration;
+
+const CAPTURE_USAGE: &str = "\
+Usage: rsnap capture --preset <name> [--notify]
+       rsnap capture --app <name|pid> [--notify]
+       rsnap capture --list-apps
+
+Captures without showing the overlay and prints the saved paths. `--preset` saves a named region
+from `capture_presets` in `settings.toml` as a PNG in the output directory. `--app` saves every
+visible window of an application as its own PNG, side by side in the output directory.
+`--list-apps` prints the applications with visible windows as `<pid>\t<windows>\t<name>`.
+
+`--notify` posts a notification per saved file with Open, Copy and Delete buttons and waits for
+it to close before exiting.
+
+Exit status: 0 when the capture was saved, 2 on usage or capture errors.";
+const CAPTURE_TO_USAGE: &str = "\
+Usage: rsnap capture-to <folder>
+
+Starts rsnap for a single region capture that is saved into <folder>, then exits. This is what
+the Explorer entry added by `rsnap shell-integration install` runs.";
+const DIFF_USAGE: &str = "\
+Usage: rsnap diff <expected.png> <actual.png> [options]
+
+Options:
+  --threshold <0..1>       Per-pixel perceptual sensitivity; lower is stricter (default 0.1)
+  --max-diff-ratio <0..1>  Share of differing pixels still treated as a pass (default 0)
+  --out <diff.png>         Write an image highlighting differing pixels in red
+  --html <report.html>     Write a self-contained HTML report
+
+Exit status: 0 when the images match, 1 when they differ, 2 on usage or I/O errors.";
+const DOCTOR_USAGE: &str = "\
+Usage: rsnap doctor
+
+Probes every capture backend against the main display, then prints which backend live and
+freeze capture use with the current `capture_backend` setting.
+
+Exit status: 0 when the selected backend works, 1 when it does not, 2 on usage or probe errors.";
+const LATENCY_USAGE: &str = "\
+Usage: rsnap latency [--csv]
+
+Summarizes how long recent captures took from the hotkey press to each pipeline stage: overlay
+visible, freeze complete, encode complete, and clipboard done.
+
+Options:
+  --csv  Print every recorded session as CSV (milliseconds) instead of the summary";
+const OPEN_USAGE: &str = "\
+Usage: rsnap open <project.rsnap>
+
+Starts rsnap with a project saved by Cmd/Ctrl+Shift+S open in the frozen editor, with its
+selection, annotations, and undo history. `rsnap <project.rsnap>` does the same.";
+const SETTINGS_USAGE: &str = "\
+Usage: rsnap settings --export <settings.json>
+       rsnap settings --import <settings.json>
+
+`--export` writes every setting to a JSON file, for example to copy the configuration to another
+machine. `--import` merges such a file into `settings.toml`: keys missing from the file keep their
+current value, and unknown keys or invalid values are reported and skipped. Restart rsnap after
+an import so a running tray app picks up the new settings.
+
+Exit status: 0 when every key was imported, 1 when some keys were skipped, 2 on usage or I/O
+errors.";
+const SHELL_INTEGRATION_USAGE: &str = "\
+Usage: rsnap shell-integration install
+       rsnap shell-integration uninstall
+
+Windows only. `install` adds \"Capture region to this folder\" to the Explorer context menu of
+folders and of the empty space inside a folder, for the current user; the capture is saved into
+that folder. `uninstall` removes the entry again.
+
+Exit status: 0 on success, 1 when `uninstall` found nothing to remove, 2 on usage or registry
+errors.";
+const EXIT_DIFFERENT: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+/// Runs a headless subcommand when `args` (without the program name) starts with one, returning
+/// the process exit code. Returns `None` to start the tray app instead.
+pub fn run_cli(args: impl IntoIterator<Item = OsString>) -> Option<ExitCode> {
+	let mut args = args.into_iter();
+
+	match args.next()?.to_str()? {
+		"capture" => Some(run_capture(args)),
+		"capture-to" => check_capture_to_args(args),
+		"diff" => Some(run_diff(args)),
+		"doctor" => Some(run_doctor(args)),
+		"latency" => Some(run_latency(args)),
+		"open" => check_open_args(args),
+		"settings" => Some(run_settings(args)),
+		"shell-integration" => Some(run_shell_integration(args)),
+		_ => None,
+	}
+}
+
+/// Returns the project named by `rsnap open <project.rsnap>`, or by a bare
+/// `rsnap <project.rsnap>` as file managers pass it, for the tray app to open once it starts.
+pub fn project_to_open(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+	let mut args = args.into_iter();
+	let first = args.next()?;
+
+	if first == "open" {
+		return parse_open_args(args).ok().flatten();
+	}
+
+	let path = PathBuf::from(first);
+
+	(path.extension().is_some_and(|extension| extension == PROJECT_EXTENSION)
+		&& args.next().is_none())
+	.then_some(path)
+}
+
+/// Returns the folder named by `rsnap capture-to <folder>`, for a one-shot capture saved there.
+pub fn capture_folder_to_open(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+	let mut args = args.into_iter();
+
+	if args.next()? != "capture-to" {
+		return None;
+	}
+
+	parse_capture_to_args(args).ok().flatten()
+}
+
+#[derive(Debug, PartialEq)]
+enum CaptureTarget {
+	Preset(String),
+	App(String),
+	ListApps,
+}
+
+#[derive(Debug, PartialEq)]
+enum SettingsTransfer {
+	Export(PathBuf),
+	Import(PathBuf),
+}
+
+#[derive(Debug, PartialEq)]
+enum ShellIntegrationAction {
+	Install,
+	Uninstall,
+}
+
+#[derive(Debug, PartialEq)]
+struct CaptureArgs {
+	target: CaptureTarget,
+	notify: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct DiffArgs {
+	expected: PathBuf,
+	actual: PathBuf,
+	options: DiffOptions,
+	max_diff_ratio: f64,
+	out: Option<PathBuf>,
+	html: Option<PathBuf>,
+}
+
+fn run_capture(args: impl Iterator<Item = OsString>) -> ExitCode {
+	let CaptureArgs { target, notify } = match parse_capture_args(args) {
+		Ok(Some(capture)) => capture,
+		Ok(None) => {
+			println!("{CAPTURE_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Err(err) => {
+			eprintln!("rsnap capture: {err}\n\n{CAPTURE_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+	let settings = AppSettings::load();
+	let effective = settings.effective();
+	let config = OverlayConfig {
+		output_dir: effective.output_dir,
+		output_filename_prefix: effective.output_filename_prefix,
+		output_naming: effective.output_naming,
+		output_filename_template: effective.output_filename_template,
+		export_format: effective.export_format,
+		export_quality: effective.export_quality,
+		capture_backend: effective.capture_backend,
+		window_capture_alpha_mode: effective.window_capture_alpha_mode,
+		window_shadow_mode: effective.window_shadow_mode,
+		..OverlayConfig::default()
+	};
+
+	match target {
+		CaptureTarget::Preset(name) => capture_preset(&settings, &config, &name, notify),
+		CaptureTarget::App(query) => capture_app(&config, &query, notify),
+		CaptureTarget::ListApps => list_apps(),
+	}
+}
+
+/// Notifies about every saved capture at once and waits until all the notifications close.
+fn notify_saved_captures(paths: &[PathBuf]) {
+	let actions = [NotificationAction::Open, NotificationAction::Copy, NotificationAction::Delete];
+
+	thread::scope(|scope| {
+		for path in paths {
+			scope.spawn(move || {
+				let image = CapturedImage::Saved(path.clone());
+
+				if let Err(err) =
+					capture_feedback::show_capture_notification(&image, &image.describe(), &actions)
+				{
+					eprintln!("rsnap capture: notification failed: {err:#}");
+				}
+			});
+		}
+	});
+}
+
+fn capture_preset(
+	settings: &AppSettings,
+	config: &OverlayConfig,
+	name: &str,
+	notify: bool,
+) -> ExitCode {
+	let Some(preset) = settings.capture_preset(name) else {
+		let known = settings
+			.capture_presets
+			.iter()
+			.map(|preset| preset.name.as_str())
+			.collect::<Vec<_>>()
+			.join(", ");
+
+		eprintln!(
+			"rsnap capture: no capture preset named `{name}` (configured: {})",
+			if known.is_empty() { "none" } else { &known }
+		);
+
+		return ExitCode::from(EXIT_ERROR);
+	};
+
+	match OverlaySession::capture_region_to_output_dir(
+		config,
+		MonitorRectPoints::from(preset.region),
+	) {
+		Ok(path) => {
+			println!("{}", path.display());
+
+			if notify {
+				notify_saved_captures(&[path]);
+			}
+
+			ExitCode::SUCCESS
+		},
+		Err(err) => {
+			eprintln!("rsnap capture: {err}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+fn capture_app(config: &OverlayConfig, query: &str, notify: bool) -> ExitCode {
+	let apps = match rsnap_overlay::list_app_windows() {
+		Ok(apps) => apps,
+		Err(err) => {
+			eprintln!("rsnap capture: failed to list windows: {err:#}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+	let Some(app) = apps.iter().find(|app| app.matches(query)) else {
+		eprintln!(
+			"rsnap capture: no application named `{query}` has visible windows (see --list-apps)"
+		);
+
+		return ExitCode::from(EXIT_ERROR);
+	};
+
+	match OverlaySession::capture_app_windows_to_output_dir(config, app) {
+		Ok(paths) => {
+			for path in &paths {
+				println!("{}", path.display());
+			}
+
+			if notify {
+				notify_saved_captures(&paths);
+			}
+
+			ExitCode::SUCCESS
+		},
+		Err(err) => {
+			eprintln!("rsnap capture: {err}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+fn list_apps() -> ExitCode {
+	match rsnap_overlay::list_app_windows() {
+		Ok(apps) => {
+			for app in apps {
+				println!("{}\t{}\t{}", app.pid, app.windows.len(), app.name);
+			}
+
+			ExitCode::SUCCESS
+		},
+		Err(err) => {
+			eprintln!("rsnap capture: failed to list windows: {err:#}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+/// Parses `capture` arguments into what to capture, returning `None` when help was requested.
+fn parse_capture_args(mut args: impl Iterator<Item = OsString>) -> Result<Option<CaptureArgs>> {
+	let mut target = None;
+	let mut notify = false;
+
+	while let Some(arg) = args.next() {
+		let flag = arg.to_str();
+		let parsed = match flag {
+			Some("-h" | "--help") => return Ok(None),
+			Some("--notify") => {
+				notify = true;
+
+				continue;
+			},
+			Some("--list-apps") => CaptureTarget::ListApps,
+			Some(flag @ ("--preset" | "--app")) => {
+				let value = args.next().ok_or_else(|| missing_value(flag))?;
+				let value = value
+					.into_string()
+					.map_err(|value| eyre::eyre!("`{flag}` expects text, got {value:?}"))?;
+
+				if value.trim().is_empty() {
+					return Err(eyre::eyre!("`{flag}` expects a name"));
+				}
+
+				if flag == "--preset" {
+					CaptureTarget::Preset(value)
+				} else {
+					CaptureTarget::App(value)
+				}
+			},
+			_ => return Err(eyre::eyre!("unexpected argument {arg:?}")),
+		};
+
+		if target.replace(parsed).is_some() {
+			return Err(eyre::eyre!("pass only one of `--preset`, `--app` or `--list-apps`"));
+		}
+	}
+
+	let target = target
+		.ok_or_else(|| eyre::eyre!("one of `--preset`, `--app` or `--list-apps` is required"))?;
+
+	if notify && target == CaptureTarget::ListApps {
+		return Err(eyre::eyre!("`--notify` needs `--preset` or `--app`"));
+	}
+
+	Ok(Some(CaptureArgs { target, notify }))
+}
+
+/// Handles `open` help and usage errors; a valid `open` starts the tray app instead.
+fn check_open_args(args: impl Iterator<Item = OsString>) -> Option<ExitCode> {
+	match parse_open_args(args) {
+		Ok(Some(_)) => None,
+		Ok(None) => {
+			println!("{OPEN_USAGE}");
+
+			Some(ExitCode::SUCCESS)
+		},
+		Err(err) => {
+			eprintln!("rsnap open: {err}\n\n{OPEN_USAGE}");
+
+			Some(ExitCode::from(EXIT_ERROR))
+		},
+	}
+}
+
+/// Parses `open` arguments into the project path, returning `None` when help was requested.
+fn parse_open_args(args: impl Iterator<Item = OsString>) -> Result<Option<PathBuf>> {
+	let mut path = None;
+
+	for arg in args {
+		match arg.to_str() {
+			Some("-h" | "--help") => return Ok(None),
+			_ if path.is_none() => path = Some(PathBuf::from(arg)),
+			_ => return Err(eyre::eyre!("unexpected argument {arg:?}")),
+		}
+	}
+
+	path.map(Some).ok_or_else(|| eyre::eyre!("a project path is required"))
+}
+
+/// Handles `capture-to` help and usage errors; a valid `capture-to` starts the app instead.
+fn check_capture_to_args(args: impl Iterator<Item = OsString>) -> Option<ExitCode> {
+	match parse_capture_to_args(args) {
+		Ok(Some(_)) => None,
+		Ok(None) => {
+			println!("{CAPTURE_TO_USAGE}");
+
+			Some(ExitCode::SUCCESS)
+		},
+		Err(err) => {
+			eprintln!("rsnap capture-to: {err}\n\n{CAPTURE_TO_USAGE}");
+			notify_capture_to_error(&err);
+
+			Some(ExitCode::from(EXIT_ERROR))
+		},
+	}
+}
+
+/// Explorer starts `capture-to` without a console, so the error is also shown as a notification.
+#[cfg(all(target_os = "windows", not(test)))]
+fn notify_capture_to_error(err: &eyre::Report) {
+	let text = format!("rsnap capture-to: {err}");
+	let notification = Notification { text: &text, image: None, actions: &[] };
+
+	if let Err(err) = notifications::show(&notification) {
+		eprintln!("rsnap capture-to: notification failed: {err:#}");
+	}
+}
+
+#[cfg(not(all(target_os = "windows", not(test))))]
+fn notify_capture_to_error(_err: &eyre::Report) {}
+
+/// Parses `capture-to` arguments into an existing folder, returning `None` when help was
+/// requested.
+fn parse_capture_to_args(args: impl Iterator<Item = OsString>) -> Result<Option<PathBuf>> {
+	let mut folder = None;
+
+	for arg in args {
+		match arg.to_str() {
+			Some("-h" | "--help") => return Ok(None),
+			_ if folder.is_none() => folder = Some(capture_to_folder(arg)),
+			_ => return Err(eyre::eyre!("unexpected argument {arg:?}")),
+		}
+	}
+
+	let folder = folder.ok_or_else(|| eyre::eyre!("a folder is required"))?;
+
+	if !folder.is_dir() {
+		return Err(eyre::eyre!("{} is not a folder", folder.display()));
+	}
+
+	Ok(Some(folder))
+}
+
+/// Undoes how Windows splits `"%V"` for a drive root: `"C:\"` reaches argv as `C:"` because `\"`
+/// is an escaped quote, so the stray quote is dropped and the bare drive gets its root back.
+fn capture_to_folder(arg: OsString) -> PathBuf {
+	let Some(text) = arg.to_str().filter(|text| text.ends_with('"')) else {
+		return PathBuf::from(arg);
+	};
+	let mut folder = text.trim_end_matches('"').to_owned();
+	let bare_drive = folder.len() == 2
+		&& folder.starts_with(|letter: char| letter.is_ascii_alphabetic())
+		&& folder.ends_with(':');
+
+	if bare_drive {
+		folder.push('\\');
+	}
+
+	PathBuf::from(folder)
+}
+
+fn run_settings(args: impl Iterator<Item = OsString>) -> ExitCode {
+	let transfer = match parse_settings_args(args) {
+		Ok(Some(transfer)) => transfer,
+		Ok(None) => {
+			println!("{SETTINGS_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Err(err) => {
+			eprintln!("rsnap settings: {err}\n\n{SETTINGS_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+
+	match transfer_settings(&transfer) {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::from(EXIT_DIFFERENT),
+		Err(err) => {
+			eprintln!("rsnap settings: {err:#}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+/// Parses `settings` arguments, returning `None` when help was requested.
+fn parse_settings_args(
+	mut args: impl Iterator<Item = OsString>,
+) -> Result<Option<SettingsTransfer>> {
+	let mut transfer = None;
+
+	while let Some(arg) = args.next() {
+		let parsed = match arg.to_str() {
+			Some("-h" | "--help") => return Ok(None),
+			Some(flag @ ("--export" | "--import")) => {
+				let path = PathBuf::from(args.next().ok_or_else(|| missing_value(flag))?);
+
+				if flag == "--export" {
+					SettingsTransfer::Export(path)
+				} else {
+					SettingsTransfer::Import(path)
+				}
+			},
+			_ => return Err(eyre::eyre!("unexpected argument {arg:?}")),
+		};
+
+		if transfer.replace(parsed).is_some() {
+			return Err(eyre::eyre!("pass only one of `--export` or `--import`"));
+		}
+	}
+
+	transfer.map(Some).ok_or_else(|| eyre::eyre!("one of `--export` or `--import` is required"))
+}
+
+/// Runs an export or import, returning whether every imported key was applied.
+fn transfer_settings(transfer: &SettingsTransfer) -> Result<bool> {
+	match transfer {
+		SettingsTransfer::Export(path) => {
+			AppSettings::load()
+				.export_json(path)
+				.wrap_err_with(|| format!("Failed to write settings: {}", path.display()))?;
+
+			println!("{}", path.display());
+
+			Ok(true)
+		},
+		SettingsTransfer::Import(path) => {
+			let import = AppSettings::load()
+				.import_json(path)
+				.wrap_err_with(|| format!("Failed to read settings: {}", path.display()))?;
+
+			import.settings.save().wrap_err("Failed to save imported settings")?;
+
+			println!("{}", import.summary());
+
+			Ok(import.rejected.is_empty())
+		},
+	}
+}
+
+fn run_shell_integration(args: impl Iterator<Item = OsString>) -> ExitCode {
+	let action = match parse_shell_integration_args(args) {
+		Ok(Some(action)) => action,
+		Ok(None) => {
+			println!("{SHELL_INTEGRATION_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Err(err) => {
+			eprintln!("rsnap shell-integration: {err}\n\n{SHELL_INTEGRATION_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+	let result = match action {
+		ShellIntegrationAction::Install => std::env::current_exe()
+			.wrap_err("Failed to locate the rsnap executable")
+			.and_then(|exe| shell_integration::install(&exe))
+			.map(|()| true),
+		ShellIntegrationAction::Uninstall => shell_integration::uninstall(),
+	};
+
+	match result {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => {
+			eprintln!("rsnap shell-integration: the Explorer entry is not installed");
+
+			ExitCode::from(EXIT_DIFFERENT)
+		},
+		Err(err) => {
+			eprintln!("rsnap shell-integration: {err:#}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+/// Parses `shell-integration` arguments, returning `None` when help was requested.
+fn parse_shell_integration_args(
+	args: impl Iterator<Item = OsString>,
+) -> Result<Option<ShellIntegrationAction>> {
+	let mut action = None;
+
+	for arg in args {
+		let parsed = match arg.to_str() {
+			Some("-h" | "--help") => return Ok(None),
+			Some("install") => ShellIntegrationAction::Install,
+			Some("uninstall") => ShellIntegrationAction::Uninstall,
+			_ => return Err(eyre::eyre!("unexpected argument {arg:?}")),
+		};
+
+		if action.replace(parsed).is_some() {
+			return Err(eyre::eyre!("pass only one of `install` or `uninstall`"));
+		}
+	}
+
+	action.map(Some).ok_or_else(|| eyre::eyre!("one of `install` or `uninstall` is required"))
+}
+
+fn run_diff(args: impl Iterator<Item = OsString>) -> ExitCode {
+	let args = match parse_diff_args(args) {
+		Ok(Some(args)) => args,
+		Ok(None) => {
+			println!("{DIFF_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Err(err) => {
+			eprintln!("rsnap diff: {err}\n\n{DIFF_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+
+	match diff(&args) {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::from(EXIT_DIFFERENT),
+		Err(err) => {
+			eprintln!("rsnap diff: {err:#}");
+
+			ExitCode::from(EXIT_ERROR)
+		},
+	}
+}
+
+/// Parses `diff` arguments, returning `None` when help was requested.
+fn parse_diff_args(mut args: impl Iterator<Item = OsString>) -> Result<Option<DiffArgs>> {
+	let mut paths = Vec::new();
+	let mut options = DiffOptions::default();
+	let mut max_diff_ratio = 0.0;
+	let mut out = None;
+	let mut html = None;
+
+	while let Some(arg) = args.next() {
+		match arg.to_str() {
+			Some("-h" | "--help") => return Ok(None),
+			Some("--threshold") => {
+				options.threshold = parse_unit_value("--threshold", args.next())? as f32;
+			},
+			Some("--max-diff-ratio") => {
+				max_diff_ratio = parse_unit_value("--max-diff-ratio", args.next())?;
+			},
+			Some("--out") => {
+				out = Some(PathBuf::from(args.next().ok_or_else(|| missing_value("--out"))?));
+			},
+			Some("--html") => {
+				html = Some(PathBuf::from(args.next().ok_or_else(|| missing_value("--html"))?));
+			},
+			Some(flag) if flag.starts_with("--") => {
+				return Err(eyre::eyre!("unknown option `{flag}`"));
+			},
+			_ => paths.push(PathBuf::from(arg)),
+		}
+	}
+
+	let [expected, actual]: [PathBuf; 2] = paths
+		.try_into()
+		.map_err(|paths: Vec<_>| eyre::eyre!("expected two images, got {}", paths.len()))?;
+
+	Ok(Some(DiffArgs { expected, actual, options, max_diff_ratio, out, html }))
+}
+
+fn parse_unit_value(flag: &str, value: Option<OsString>) -> Result<f64> {
+	let value = value.ok_or_else(|| missing_value(flag))?;
+	let parsed = value
+		.to_str()
+		.and_then(|value| value.parse::<f64>().ok())
+		.filter(|value| (0.0..=1.0).contains(value))
+		.ok_or_else(|| eyre::eyre!("`{flag}` expects a number from 0 to 1, got {value:?}"))?;
+
+	Ok(parsed)
+}
+
+fn missing_value(flag: &str) -> eyre::Report {
+	eyre::eyre!("`{flag}` expects a value")
+}
+
+/// Compares the two images, writes the requested artifacts, and returns whether they match.
+fn diff(args: &DiffArgs) -> Result<bool> {
+	let (expected_png, expected) = read_image(&args.expected)?;
+	let (actual_png, actual) = read_image(&args.actual)?;
+	let report = rsnap_overlay::diff_images(&expected, &actual, args.options);
+	let pass = !report.size_mismatch && report.differing_ratio() <= args.max_diff_ratio;
+
+	if report.size_mismatch {
+		println!(
+			"Size mismatch: {}×{} vs {}×{}.",
+			expected.width(),
+			expected.height(),
+			actual.width(),
+			actual.height()
+		);
+	}
+
+	println!(
+		"{} of {} pixels differ ({:.4}%): {}.",
+		report.differing_pixels,
+		report.total_pixels,
+		report.differing_ratio() * 100.0,
+		if pass { "pass" } else { "fail" }
+	);
+
+	if let Some(out) = args.out.as_deref() {
+		report
+			.diff_image
+			.save(out)
+			.wrap_err_with(|| format!("Failed to write diff image: {}", out.display()))?;
+	}
+	if let Some(html) = args.html.as_deref() {
+		let diff_png = encode_png(&report)?;
+		let page = html_report(args, &report, pass, [&expected_png, &actual_png, &diff_png]);
+
+		fs::write(html, page)
+			.wrap_err_with(|| format!("Failed to write HTML report: {}", html.display()))?;
+	}
+
+	Ok(pass)
+}
+
+fn run_doctor(mut args: impl Iterator<Item = OsString>) -> ExitCode {
+	match args.next() {
+		None => {},
+		Some(arg) if arg == "-h" || arg == "--help" => {
+			println!("{DOCTOR_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Some(arg) => {
+			eprintln!("rsnap doctor: unexpected argument {arg:?}\n\n{DOCTOR_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	}
+
+	let selected = AppSettings::load().effective().capture_backend;
+	let probes = match rsnap_overlay::probe_capture_backends() {
+		Ok(probes) => probes,
+		Err(err) => {
+			eprintln!("rsnap doctor: {err:#}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+
+	print!("{}", doctor_report(selected, &probes));
+	print!("\n{}", LatencyLog::load().summary());
+
+	let selected_works =
+		probes.iter().any(|probe| probe.kind == selected.resolve() && probe.available);
+
+	if selected_works { ExitCode::SUCCESS } else { ExitCode::from(EXIT_DIFFERENT) }
+}
+
+fn run_latency(mut args: impl Iterator<Item = OsString>) -> ExitCode {
+	let csv = match args.next() {
+		None => false,
+		Some(arg) if arg == "--csv" => true,
+		Some(arg) if arg == "-h" || arg == "--help" => {
+			println!("{LATENCY_USAGE}");
+
+			return ExitCode::SUCCESS;
+		},
+		Some(arg) => {
+			eprintln!("rsnap latency: unexpected argument {arg:?}\n\n{LATENCY_USAGE}");
+
+			return ExitCode::from(EXIT_ERROR);
+		},
+	};
+
+	if let Some(arg) = args.next() {
+		eprintln!("rsnap latency: unexpected argument {arg:?}\n\n{LATENCY_USAGE}");
+
+		return ExitCode::from(EXIT_ERROR);
+	}
+
+	let log = LatencyLog::load();
+
+	if csv {
+		print!("{}", log.to_csv());
+	} else {
+		print!("{}", log.summary());
+	}
+
+	ExitCode::SUCCESS
+}
+
+/// Formats the probe table followed by the capture paths the selected backend resolves to.
+fn doctor_report(selected: CaptureBackendKind, probes: &[CaptureBackendProbe]) -> String {
+	let mut report = format!(
+		"{:<10} {:<12} {:<10} {:<13} {}\n",
+		"Backend", "Status", "Latency", "Permission", "Notes"
+	);
+
+	for probe in probes {
+		let latency = probe.latency.map_or_else(
+			|| String::from("-"),
+			|latency| format!("{:.1} ms", latency.as_secs_f64() * 1_000.0),
+		);
+
+		report.push_str(
+			format!(
+				"{:<10} {:<12} {:<10} {:<13} {}",
+				probe.kind.name(),
+				if probe.available { "available" } else { "unavailable" },
+				latency,
+				probe.permission.label(),
+				probe.detail.as_deref().unwrap_or_default()
+			)
+			.trim_end(),
+		);
+		report.push('\n');
+	}
+
+	let resolved = selected.resolve();
+
+	report.push('\n');
+
+	match selected.unsupported_reason() {
+		Some(reason) => report.push_str(&format!(
+			"Selected backend: {} is unavailable ({reason}); using {}\n",
+			selected.name(),
+			resolved.name()
+		)),
+		None if selected == resolved => {
+			report.push_str(&format!("Selected backend: {}\n", selected.name()));
+		},
+		None => report.push_str(&format!(
+			"Selected backend: {} -> {}\n",
+			selected.name(),
+			resolved.name()
+		)),
+	}
+
+	report.push_str(&format!("Live capture: {}\n", selected.live_path()));
+	report.push_str(&format!("Freeze capture: {}\n", selected.freeze_path()));
+
+	report
+}
+
+/// Reads an image file, keeping its bytes for the HTML report.
+fn read_image(path: &Path) -> Result<(Vec<u8>, RgbaImage)> {
+	let bytes =
+		fs::read(path).wrap_err_with(|| format!("Failed to read image: {}", path.display()))?;
+	let image = image::load_from_memory(&bytes)
+		.wrap_err_with(|| format!("Failed to decode image: {}", path.display()))?
+		.into_rgba8();
+
+	Ok((bytes, image))
+}
+
+fn encode_png(report: &DiffReport) -> Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+
+	report
+		.diff_image
+		.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+		.wrap_err("Failed to encode diff image")?;
+
+	Ok(bytes)
+}
+
+/// Renders a single-file report with the images inlined, so CI can archive it as one artifact.
+fn html_report(args: &DiffArgs, report: &DiffReport, pass: bool, pngs: [&[u8]; 3]) -> String {
+	let [expected_png, actual_png, diff_png] = pngs;
+	let figure = |caption: &str, png: &[u8]| {
+		format!(
+			"<figure><img src=\"data:image/png;base64,{}\"><figcaption>{}</figcaption></figure>",
+			base64(png),
+			escape_html(caption)
+		)
+	};
+
+	format!(
+		"<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>rsnap diff</title>\
+		<style>body{{font-family:sans-serif}}figure{{display:inline-block;margin:8px;\
+		vertical-align:top}}img{{max-width:480px;border:1px solid #ccc}}</style></head><body>\
+		<h1>{}</h1><p>{} of {} pixels differ ({:.4}%), threshold {}, max diff ratio {}.</p>\
+		{}{}{}</body></html>\n",
+		if pass { "Pass" } else { "Fail" },
+		report.differing_pixels,
+		report.total_pixels,
+		report.differing_ratio() * 100.0,
+		args.options.threshold,
+		args.max_diff_ratio,
+		figure(&format!("Expected: {}", args.expected.display()), expected_png),
+		figure(&format!("Actual: {}", args.actual.display()), actual_png),
+		figure("Diff", diff_png),
+	)
+}
+
+fn escape_html(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn base64(bytes: &[u8]) -> String {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+	for chunk in bytes.chunks(3) {
+		let triple = chunk
+			.iter()
+			.enumerate()
+			.fold(0_u32, |acc, (index, byte)| acc | u32::from(*byte) << (16 - 8 * index));
+
+		for index in 0..4 {
+			if index <= chunk.len() {
+				encoded.push(char::from(ALPHABET[(triple >> (18 - 6 * index)) as usize & 63]));
+			} else {
+				encoded.push('=');
+			}
+		}
+	}
+
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ffi::OsString;
+	use std::path::PathBuf;
+
+	use std::time::Duration;
+
+	use rsnap_overlay::{CaptureBackendKind, CaptureBackendProbe, CapturePermission, DiffOptions};
+
+	use crate::cli::{
+		self, CaptureArgs, CaptureTarget, DiffArgs, SettingsTransfer, ShellIntegrationAction,
+	};
+
+	fn args(values: &[&str]) -> impl Iterator<Item = OsString> {
+		values.iter().map(OsString::from).collect::<Vec<_>>().into_iter()
+	}
+
+	#[test]
+	fn diff_args_parse_options_and_reject_bad_values() {
+		let parsed = cli::parse_diff_args(args(&[
+			"a.png",
+			"--threshold",
+			"0.2",
+			"b.png",
+			"--out",
+			"diff.png",
+			"--max-diff-ratio",
+			"0.01",
+		]))
+		.expect("valid args");
+
+		assert_eq!(
+			parsed,
+			Some(DiffArgs {
+				expected: PathBuf::from("a.png"),
+				actual: PathBuf::from("b.png"),
+				options: DiffOptions { threshold: 0.2 },
+				max_diff_ratio: 0.01,
+				out: Some(PathBuf::from("diff.png")),
+				html: None,
+			})
+		);
+		assert_eq!(cli::parse_diff_args(args(&["--help"])).expect("help"), None);
+		assert!(cli::parse_diff_args(args(&["a.png"])).is_err());
+		assert!(cli::parse_diff_args(args(&["a.png", "b.png", "--threshold", "2"])).is_err());
+		assert!(cli::parse_diff_args(args(&["a.png", "b.png", "--fuzz"])).is_err());
+		assert!(cli::run_cli(args(&["-psn_0_12345"])).is_none());
+	}
+
+	#[test]
+	fn capture_args_require_a_preset_or_an_app() {
+		let target = |list: &[&str]| {
+			cli::parse_capture_args(args(list)).expect("valid args").map(|capture| capture.target)
+		};
+
+		assert_eq!(
+			target(&["--preset", "demo-area"]),
+			Some(CaptureTarget::Preset(String::from("demo-area")))
+		);
+		assert_eq!(target(&["--app", "Safari"]), Some(CaptureTarget::App(String::from("Safari"))));
+		assert_eq!(target(&["--list-apps"]), Some(CaptureTarget::ListApps));
+		assert_eq!(
+			cli::parse_capture_args(args(&["--notify", "--app", "Safari"])).expect("valid args"),
+			Some(CaptureArgs { target: CaptureTarget::App(String::from("Safari")), notify: true })
+		);
+		assert!(cli::parse_capture_args(args(&["--list-apps", "--notify"])).is_err());
+		assert!(cli::parse_capture_args(args(&["--app", "Safari", "--preset", "demo"])).is_err());
+		assert!(cli::parse_capture_args(args(&["--app", " "])).is_err());
+		assert_eq!(cli::parse_capture_args(args(&["--help"])).expect("help"), None);
+		assert!(cli::parse_capture_args(args(&[])).is_err());
+		assert!(cli::parse_capture_args(args(&["--preset"])).is_err());
+		assert!(cli::parse_capture_args(args(&["demo-area"])).is_err());
+	}
+
+	#[test]
+	fn settings_args_take_one_export_or_import_path() {
+		assert_eq!(
+			cli::parse_settings_args(args(&["--export", "rsnap.json"])).expect("valid args"),
+			Some(SettingsTransfer::Export(PathBuf::from("rsnap.json")))
+		);
+		assert_eq!(
+			cli::parse_settings_args(args(&["--import", "rsnap.json"])).expect("valid args"),
+			Some(SettingsTransfer::Import(PathBuf::from("rsnap.json")))
+		);
+		assert_eq!(cli::parse_settings_args(args(&["--help"])).expect("help"), None);
+		assert!(cli::parse_settings_args(args(&[])).is_err());
+		assert!(cli::parse_settings_args(args(&["--import"])).is_err());
+		assert!(
+			cli::parse_settings_args(args(&["--export", "a.json", "--import", "b.json"])).is_err()
+		);
+		assert!(cli::parse_settings_args(args(&["rsnap.json"])).is_err());
+	}
+
+	#[test]
+	fn capture_to_takes_one_existing_folder() {
+		let folder = std::env::temp_dir();
+		let folder_arg = folder.to_str().expect("utf-8 temp dir");
+
+		assert_eq!(
+			cli::capture_folder_to_open(args(&["capture-to", folder_arg])),
+			Some(folder.clone())
+		);
+		assert_eq!(cli::capture_folder_to_open(args(&["open", folder_arg])), None);
+		assert_eq!(cli::parse_capture_to_args(args(&["--help"])).expect("help"), None);
+		assert!(cli::parse_capture_to_args(args(&[])).is_err());
+		assert!(cli::parse_capture_to_args(args(&[folder_arg, folder_arg])).is_err());
+		assert!(cli::parse_capture_to_args(args(&["no/such/rsnap/folder"])).is_err());
+		assert!(cli::run_cli(args(&["capture-to", folder_arg])).is_none());
+		assert!(cli::run_cli(args(&["capture-to"])).is_some());
+	}
+
+	#[test]
+	fn capture_to_restores_drive_roots_split_by_windows() {
+		// Explorer expands `"%V"` to `"C:\"`, which Windows splits into the argument `C:"`.
+		assert_eq!(cli::capture_to_folder(OsString::from("C:\"")), PathBuf::from("C:\\"));
+		assert_eq!(cli::capture_to_folder(OsString::from("D:\\")), PathBuf::from("D:\\"));
+		assert_eq!(
+			cli::capture_to_folder(OsString::from("C:\\Users\\me")),
+			PathBuf::from("C:\\Users\\me")
+		);
+
+		let folder = std::env::temp_dir();
+		let quoted = format!("{}\"", folder.display());
+
+		assert_eq!(
+			cli::parse_capture_to_args(args(&[quoted.as_str()])).expect("folder"),
+			Some(folder)
+		);
+	}
+
+	#[test]
+	fn shell_integration_args_take_install_or_uninstall() {
+		assert_eq!(
+			cli::parse_shell_integration_args(args(&["install"])).expect("valid args"),
+			Some(ShellIntegrationAction::Install)
+		);
+		assert_eq!(
+			cli::parse_shell_integration_args(args(&["uninstall"])).expect("valid args"),
+			Some(ShellIntegrationAction::Uninstall)
+		);
+		assert_eq!(cli::parse_shell_integration_args(args(&["-h"])).expect("help"), None);
+		assert!(cli::parse_shell_integration_args(args(&[])).is_err());
+		assert!(cli::parse_shell_integration_args(args(&["install", "uninstall"])).is_err());
+		assert!(cli::parse_shell_integration_args(args(&["register"])).is_err());
+	}
+
+	#[test]
+	fn projects_open_by_subcommand_or_bare_path() {
+		let project = Some(PathBuf::from("shots/bug.rsnap"));
+
+		assert_eq!(cli::project_to_open(args(&["open", "shots/bug.rsnap"])), project);
+		assert_eq!(cli::project_to_open(args(&["shots/bug.rsnap"])), project);
+		assert_eq!(cli::project_to_open(args(&["shots/bug.png"])), None);
+		assert_eq!(cli::project_to_open(args(&["open"])), None);
+		assert!(cli::run_cli(args(&["open", "shots/bug.rsnap"])).is_none());
+		assert!(cli::run_cli(args(&["open"])).is_some());
+		assert!(cli::parse_open_args(args(&["a.rsnap", "b.rsnap"])).is_err());
+	}
+
+	#[test]
+	fn doctor_report_lists_probes_and_the_resolved_paths() {
+		let native = CaptureBackendKind::native();
+		let probes = [
+			CaptureBackendProbe {
+				kind: native,
+				available: true,
+				latency: Some(Duration::from_micros(12_340)),
+				permission: CapturePermission::NotRequired,
+				detail: None,
+			},
+			CaptureBackendProbe {
+				kind: CaptureBackendKind::Wgc,
+				available: false,
+				latency: None,
+				permission: CapturePermission::NotApplicable,
+				detail: Some(String::from("not built in this version")),
+			},
+		];
+		let report = cli::doctor_report(CaptureBackendKind::Auto, &probes);
+
+		assert!(report.contains("available    12.3 ms"));
+		assert!(report.contains("wgc        unavailable  -          -             not built"));
+		assert!(report.contains(&format!("Selected backend: auto -> {}\n", native.name())));
+		assert!(report.contains(&format!("Live capture: {}\n", native.live_path())));
+
+		let fallback = cli::doctor_report(CaptureBackendKind::Pipewire, &probes);
+
+		assert!(fallback.contains(&format!(
+			"Selected backend: pipewire is unavailable (not built in this version); using {}",
+			native.name()
+		)));
+	}
+
+	#[test]
+	fn base64_matches_rfc_4648_vectors() {
+		assert_eq!(cli::base64(b""), "");
+		assert_eq!(cli::base64(b"f"), "Zg==");
+		assert_eq!(cli::base64(b"fo"), "Zm8=");
+		assert_eq!(cli::base64(b"foo"), "Zm9v");
+		assert_eq!(cli::base64(b"foobar"), "Zm9vYmFy");
+	}
+}
@@ -3,11 +3,28 @@ use tray_icon::Icon;
 
 const TRAY_ICON_PNG_BYTES: &[u8] =
 	include_bytes!("../assets/tray-icon/generated/tray-icon-template.png");
+/// Opacity kept by the paused tray icon, so it reads as greyed out in light and dark menu bars.
+const PAUSED_TRAY_ICON_ALPHA_PERCENT: u16 = 35;
 
 pub(crate) fn default_tray_icon() -> Result<Icon> {
+	tray_icon_with_alpha(100)
+}
+
+pub(crate) fn paused_tray_icon() -> Result<Icon> {
+	tray_icon_with_alpha(PAUSED_TRAY_ICON_ALPHA_PERCENT)
+}
+
+fn tray_icon_with_alpha(alpha_percent: u16) -> Result<Icon> {
 	let image = image::load_from_memory(TRAY_ICON_PNG_BYTES)
 		.wrap_err("Failed to decode tray icon PNG bytes")?;
-	let rgba = image.into_rgba8();
+	let mut rgba = image.into_rgba8();
+
+	if alpha_percent < 100 {
+		for pixel in rgba.pixels_mut() {
+			pixel[3] = (u16::from(pixel[3]) * alpha_percent / 100) as u8;
+		}
+	}
+
 	let (width, height) = rgba.dimensions();
 
 	Icon::from_rgba(rgba.into_raw(), width, height)
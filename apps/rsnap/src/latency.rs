@@ -0,0 +1,257 @@
+//! Local end-to-end capture latency history.
+//!
+//! Every finished overlay session appends one row to `latency.csv` in the data directory: when it
+//! ended, how it ended, and how long after the request each pipeline stage was reached. Only the
+//! newest [`LATENCY_LOG_MAX_RECORDS`] rows are kept. `rsnap latency` summarizes them and
+//! `rsnap latency --csv` prints the raw rows so runs on different releases can be compared.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use rsnap_overlay::{CaptureLatency, LatencyStage};
+
+const LATENCY_LOG_MAX_RECORDS: usize = 500;
+const LATENCY_LOG_FIXED_COLUMNS: [&str; 2] = ["unix_secs", "exit"];
+
+/// One finished session; stages it never reached are `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LatencyRecord {
+	pub unix_secs: u64,
+	/// How the session ended, e.g. `png_clipboard` or `cancelled`.
+	pub exit: String,
+	pub stages: [Option<Duration>; 4],
+}
+impl LatencyRecord {
+	pub(crate) fn new(exit: &str, latency: &CaptureLatency) -> Self {
+		Self {
+			unix_secs: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|elapsed| elapsed.as_secs())
+				.unwrap_or_default(),
+			exit: exit.to_owned(),
+			stages: LatencyStage::ALL.map(|stage| latency.elapsed(stage)),
+		}
+	}
+
+	fn to_csv_row(&self) -> String {
+		let mut row = format!("{},{}", self.unix_secs, self.exit);
+
+		for stage in self.stages {
+			row.push(',');
+
+			if let Some(stage) = stage {
+				row.push_str(&format!("{:.1}", stage.as_secs_f64() * 1_000.0));
+			}
+		}
+
+		row
+	}
+
+	fn from_csv_row(row: &str) -> Option<Self> {
+		let mut fields = row.split(',');
+		let unix_secs = fields.next()?.parse().ok()?;
+		let exit = fields.next()?.to_owned();
+		let mut stages = [None; 4];
+
+		for stage in &mut stages {
+			let field = fields.next()?;
+
+			if !field.is_empty() {
+				let millis = field.parse::<f64>().ok()?.max(0.0);
+
+				*stage = Some(Duration::from_micros((millis * 1_000.0).round() as u64));
+			}
+		}
+
+		Some(Self { unix_secs, exit, stages })
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct LatencyLog {
+	pub records: Vec<LatencyRecord>,
+}
+impl LatencyLog {
+	/// Loads the history, skipping rows it cannot parse.
+	#[must_use]
+	pub fn load() -> Self {
+		let Some(contents) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+			return Self::default();
+		};
+
+		Self { records: contents.lines().skip(1).filter_map(LatencyRecord::from_csv_row).collect() }
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(path) = Self::path() else {
+			return Ok(());
+		};
+
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+
+		fs::write(path, self.to_csv())
+	}
+
+	/// Appends `record`, dropping the oldest rows beyond the retention limit.
+	pub fn push(&mut self, record: LatencyRecord) {
+		self.records.push(record);
+
+		let excess = self.records.len().saturating_sub(LATENCY_LOG_MAX_RECORDS);
+
+		self.records.drain(..excess);
+	}
+
+	/// The full history as CSV with a header row; latencies are in milliseconds.
+	#[must_use]
+	pub fn to_csv(&self) -> String {
+		let header = LATENCY_LOG_FIXED_COLUMNS
+			.into_iter()
+			.chain(LatencyStage::ALL.map(|stage| stage.name()))
+			.collect::<Vec<_>>()
+			.join(",");
+		let mut csv = format!("{header}\n");
+
+		for record in &self.records {
+			csv.push_str(&record.to_csv_row());
+			csv.push('\n');
+		}
+
+		csv
+	}
+
+	/// Renders per-stage percentiles over the recorded sessions, one stage per line.
+	#[must_use]
+	pub fn summary(&self) -> String {
+		let mut summary = format!(
+			"Capture latency over the last {} session(s), from the request:\n",
+			self.records.len()
+		);
+
+		if self.records.is_empty() {
+			summary.push_str("(no captures recorded)\n");
+
+			return summary;
+		}
+
+		summary.push_str(&format!(
+			"{:<16} {:>7} {:>10} {:>10} {:>10}\n",
+			"Stage", "Count", "p50", "p95", "Max"
+		));
+
+		for (index, stage) in LatencyStage::ALL.into_iter().enumerate() {
+			let mut samples =
+				self.records.iter().filter_map(|record| record.stages[index]).collect::<Vec<_>>();
+
+			samples.sort_unstable();
+
+			let [p50, p95, max] = [0.5, 0.95, 1.0].map(|quantile| {
+				percentile(&samples, quantile).map_or_else(
+					|| String::from("-"),
+					|latency| format!("{:.1} ms", latency.as_secs_f64() * 1_000.0),
+				)
+			});
+
+			summary.push_str(&format!(
+				"{:<16} {:>7} {p50:>10} {p95:>10} {max:>10}\n",
+				stage.name(),
+				samples.len()
+			));
+		}
+
+		summary
+	}
+
+	#[must_use]
+	pub fn path() -> Option<PathBuf> {
+		let dirs = ProjectDirs::from("ink", "hack", "rsnap")?;
+
+		Some(dirs.data_dir().join("latency.csv"))
+	}
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[Duration], quantile: f64) -> Option<Duration> {
+	let rank = (quantile * sorted.len() as f64).ceil() as usize;
+
+	sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+/// Appends one session to the on-disk history. Failures are logged and otherwise ignored.
+pub(crate) fn record(record: LatencyRecord) {
+	let mut log = LatencyLog::load();
+
+	log.push(record);
+
+	if let Err(err) = log.save() {
+		tracing::warn!(error = %err, "Failed to persist capture latency.");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::latency::{self, LATENCY_LOG_MAX_RECORDS, LatencyLog, LatencyRecord};
+
+	fn record(exit: &str, stages: [Option<u64>; 4]) -> LatencyRecord {
+		LatencyRecord {
+			unix_secs: 1_700_000_000,
+			exit: exit.to_owned(),
+			stages: stages.map(|stage| stage.map(Duration::from_millis)),
+		}
+	}
+
+	#[test]
+	fn latency_log_round_trips_through_csv() {
+		let mut log = LatencyLog::default();
+
+		log.push(record("png_clipboard", [Some(42), Some(180), Some(260), Some(275)]));
+		log.push(record("cancelled", [Some(38), None, None, None]));
+
+		let csv = log.to_csv();
+
+		assert!(csv.starts_with(
+			"unix_secs,exit,overlay_visible,freeze_complete,encode_complete,clipboard_done\n"
+		));
+		assert!(csv.contains("1700000000,cancelled,38.0,,,\n"));
+
+		let parsed =
+			csv.lines().skip(1).filter_map(LatencyRecord::from_csv_row).collect::<Vec<_>>();
+
+		assert_eq!(parsed, log.records);
+	}
+
+	#[test]
+	fn latency_log_keeps_only_the_newest_records() {
+		let mut log = LatencyLog::default();
+
+		for unix_secs in 0..LATENCY_LOG_MAX_RECORDS as u64 + 3 {
+			log.push(LatencyRecord { unix_secs, ..record("cancelled", [None; 4]) });
+		}
+
+		assert_eq!(log.records.len(), LATENCY_LOG_MAX_RECORDS);
+		assert_eq!(log.records[0].unix_secs, 3);
+	}
+
+	#[test]
+	fn latency_summary_reports_nearest_rank_percentiles_per_stage() {
+		let mut log = LatencyLog::default();
+
+		for millis in 1..=20 {
+			log.push(record("png_clipboard", [Some(millis * 10), None, None, None]));
+		}
+
+		let summary = log.summary();
+
+		assert!(summary.contains("over the last 20 session(s)"));
+		assert!(summary.contains("overlay_visible       20   100.0 ms   190.0 ms   200.0 ms\n"));
+		assert!(summary.contains("clipboard_done         0          -          -          -\n"));
+		assert!(LatencyLog::default().summary().contains("(no captures recorded)"));
+		assert_eq!(latency::percentile(&[], 0.5), None);
+	}
+}
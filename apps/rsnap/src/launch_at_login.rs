@@ -0,0 +1,209 @@
+//! Starting rsnap when the user logs in.
+//!
+//! The operating system's own login items are the source of truth, so the toggle reflects
+//! entries made or removed outside rsnap too: a LaunchAgent plist on macOS, a value under the
+//! per-user `Run` registry key on Windows, and an XDG autostart `.desktop` file elsewhere.
+
+use std::path::Path;
+#[cfg(not(target_os = "windows"))]
+use std::path::PathBuf;
+
+use color_eyre::eyre::{self, Result, WrapErr};
+
+/// LaunchAgent label, matching the bundle identifier.
+#[cfg(any(target_os = "macos", test))]
+const LAUNCH_AGENT_LABEL: &str = "ink.hack.rsnap";
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE: &str = "rsnap";
+
+/// Whether rsnap is currently registered to start at login.
+#[must_use]
+pub(crate) fn is_enabled() -> bool {
+	enabled()
+}
+
+/// Registers the running executable to start at login, or removes the registration.
+///
+/// Enabling replaces an older entry, so a moved or updated binary is picked up; disabling when
+/// nothing is registered succeeds.
+pub(crate) fn set_enabled(enabled: bool) -> Result<()> {
+	if enabled {
+		let exe = std::env::current_exe().wrap_err("Failed to locate the rsnap executable")?;
+
+		register(&exe)?;
+	} else {
+		unregister()?;
+	}
+
+	tracing::info!(op = "settings.launch_at_login", enabled, "Updated launch at login.");
+
+	Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Result<PathBuf> {
+	let dirs = directories::BaseDirs::new().ok_or_else(|| eyre::eyre!("No home directory"))?;
+
+	Ok(dirs.home_dir().join("Library/LaunchAgents").join(format!("{LAUNCH_AGENT_LABEL}.plist")))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn entry_path() -> Result<PathBuf> {
+	let dirs = directories::BaseDirs::new().ok_or_else(|| eyre::eyre!("No home directory"))?;
+
+	Ok(dirs.config_dir().join("autostart/rsnap.desktop"))
+}
+
+#[cfg(target_os = "macos")]
+fn entry_contents(exe: &Path) -> String {
+	launch_agent_plist(exe)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn entry_contents(exe: &Path) -> String {
+	autostart_desktop_entry(exe)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enabled() -> bool {
+	let Ok(path) = entry_path() else {
+		return false;
+	};
+
+	// A `Hidden=true` copy is how desktop environments record an autostart entry the user
+	// turned off.
+	std::fs::read_to_string(path).is_ok_and(|contents| !contents.contains("Hidden=true"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn register(exe: &Path) -> Result<()> {
+	let path = entry_path()?;
+
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent)
+			.wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+	}
+
+	std::fs::write(&path, entry_contents(exe))
+		.wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn unregister() -> Result<()> {
+	let path = entry_path()?;
+
+	match std::fs::remove_file(&path) {
+		Ok(()) => Ok(()),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(err) => Err(eyre::eyre!("Failed to remove {}: {err}", path.display())),
+	}
+}
+
+#[cfg(target_os = "windows")]
+fn enabled() -> bool {
+	std::process::Command::new("reg")
+		.args(["query", RUN_KEY, "/v", RUN_VALUE])
+		.output()
+		.is_ok_and(|output| output.status.success())
+}
+
+#[cfg(target_os = "windows")]
+fn register(exe: &Path) -> Result<()> {
+	let command = format!("\"{}\"", exe.display());
+
+	run_reg(&["add", RUN_KEY, "/v", RUN_VALUE, "/d", &command, "/f"])
+}
+
+#[cfg(target_os = "windows")]
+fn unregister() -> Result<()> {
+	if !enabled() {
+		return Ok(());
+	}
+
+	run_reg(&["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])
+}
+
+#[cfg(target_os = "windows")]
+fn run_reg(args: &[&str]) -> Result<()> {
+	let output =
+		std::process::Command::new("reg").args(args).output().wrap_err("Failed to run reg.exe")?;
+
+	if output.status.success() {
+		Ok(())
+	} else {
+		Err(eyre::eyre!(
+			"reg {} failed: {}",
+			args[0],
+			String::from_utf8_lossy(&output.stderr).trim()
+		))
+	}
+}
+
+/// A LaunchAgent that runs `exe` once at login and does not keep it alive after it quits.
+#[cfg(any(target_os = "macos", test))]
+fn launch_agent_plist(exe: &Path) -> String {
+	let exe =
+		exe.display().to_string().replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+
+	format!(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>{LAUNCH_AGENT_LABEL}</string>
+	<key>ProgramArguments</key>
+	<array>
+		<string>{exe}</string>
+	</array>
+	<key>RunAtLoad</key>
+	<true/>
+	<key>ProcessType</key>
+	<string>Interactive</string>
+</dict>
+</plist>
+"#
+	)
+}
+
+/// An XDG autostart entry that runs `exe`, quoted as the Desktop Entry spec requires.
+#[cfg(any(not(any(target_os = "macos", target_os = "windows")), test))]
+fn autostart_desktop_entry(exe: &Path) -> String {
+	let mut quoted = String::from("\"");
+
+	for char in exe.display().to_string().chars() {
+		if matches!(char, '"' | '`' | '$' | '\\') {
+			quoted.push('\\');
+		}
+
+		quoted.push(char);
+	}
+
+	quoted.push('"');
+
+	format!(
+		"[Desktop Entry]\nType=Application\nName=rsnap\nComment=Screenshot tool\nExec={}\n\
+		 X-GNOME-Autostart-enabled=true\n",
+		quoted.replace('%', "%%")
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use crate::launch_at_login;
+
+	#[test]
+	fn login_entries_run_the_escaped_executable() {
+		let plist = launch_at_login::launch_agent_plist(Path::new("/Apps/R&D/rsnap.app/rsnap"));
+		let desktop = launch_at_login::autostart_desktop_entry(Path::new("/opt/my $apps/rsnap"));
+
+		assert!(plist.contains("<string>/Apps/R&amp;D/rsnap.app/rsnap</string>"));
+		assert!(plist.contains("<key>RunAtLoad</key>\n\t<true/>"));
+		assert!(desktop.starts_with("[Desktop Entry]\n"));
+		assert!(desktop.contains("Exec=\"/opt/my \\$apps/rsnap\"\n"));
+	}
+}
@@ -1,10 +1,21 @@
 //! Library surface for `rsnap` benchmark and test support.
 
 mod app;
+mod capture_feedback;
+mod capture_popover;
+mod cli;
 mod icon;
+mod latency;
+mod launch_at_login;
+mod notifications;
 mod settings;
 pub mod settings_window;
+mod shell_integration;
 mod startup;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+mod updates;
 
 pub use app::run;
+pub use cli::{capture_folder_to_open, project_to_open, run_cli};
 pub use startup::{StartupBuildInfo, init_logging, startup_build_info};
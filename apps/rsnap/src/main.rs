@@ -1,10 +1,18 @@
 //! Desktop binary entrypoint for the `rsnap` application.
 
+use std::process::ExitCode;
+
 use color_eyre::eyre::Result;
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
 	color_eyre::install()?;
 
+	if let Some(code) = rsnap::run_cli(std::env::args_os().skip(1)) {
+		return Ok(code);
+	}
+
+	let project = rsnap::project_to_open(std::env::args_os().skip(1));
+	let capture_folder = rsnap::capture_folder_to_open(std::env::args_os().skip(1));
 	let _guard = rsnap::init_logging();
 	let build_info = rsnap::startup_build_info();
 
@@ -14,5 +22,7 @@ fn main() -> Result<()> {
 		"Starting rsnap."
 	);
 
-	rsnap::run()
+	rsnap::run(project, capture_folder)?;
+
+	Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,194 @@
+//! Native desktop notifications with an image and action buttons.
+//!
+//! Linux posts through `notify-send` and Windows shows a toast from PowerShell; both wait for the
+//! notification to be clicked or dismissed and report the button picked. Notifications posted
+//! through AppleScript on macOS carry text only, so there no button is ever reported.
+
+use std::path::Path;
+use std::process::Command;
+#[cfg(not(target_os = "macos"))]
+use std::process::Stdio;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+/// How long a Windows toast is watched for a click before the helper gives up.
+#[cfg(target_os = "windows")]
+const TOAST_WAIT_SECS: u32 = 10;
+
+/// A button on a notification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum NotificationAction {
+	Open,
+	Copy,
+	Delete,
+	Annotate,
+}
+#[cfg(any(not(target_os = "macos"), test))]
+impl NotificationAction {
+	/// Identifier the notification system reports back for this button.
+	const fn key(self) -> &'static str {
+		match self {
+			Self::Open => "open",
+			Self::Copy => "copy",
+			Self::Delete => "delete",
+			Self::Annotate => "annotate",
+		}
+	}
+
+	fn label(self) -> &'static str {
+		match self {
+			Self::Open => rsnap_overlay::tr("notification.open"),
+			Self::Copy => rsnap_overlay::tr("notification.copy"),
+			Self::Delete => rsnap_overlay::tr("notification.delete"),
+			Self::Annotate => rsnap_overlay::tr("notification.annotate"),
+		}
+	}
+}
+
+/// What to show; `image` and `actions` are left out where the platform cannot show them.
+#[derive(Debug)]
+pub(crate) struct Notification<'a> {
+	pub(crate) text: &'a str,
+	#[cfg_attr(all(target_os = "macos", not(test)), expect(dead_code))]
+	pub(crate) image: Option<&'a Path>,
+	#[cfg_attr(all(target_os = "macos", not(test)), expect(dead_code))]
+	pub(crate) actions: &'a [NotificationAction],
+}
+
+/// Shows `notification` and blocks until it is clicked, dismissed or times out, returning the
+/// button picked.
+#[cfg(target_os = "macos")]
+pub(crate) fn show(notification: &Notification<'_>) -> Result<Option<NotificationAction>> {
+	Command::new("osascript")
+		.args([
+			"-e",
+			"display notification (system attribute \"RSNAP_NOTIFICATION_TEXT\") \
+			 with title \"rsnap\"",
+		])
+		.env("RSNAP_NOTIFICATION_TEXT", notification.text)
+		.status()
+		.wrap_err("Failed to run osascript")?;
+
+	Ok(None)
+}
+
+/// Shows `notification` and blocks until it is clicked, dismissed or times out, returning the
+/// button picked.
+#[cfg(target_os = "windows")]
+pub(crate) fn show(notification: &Notification<'_>) -> Result<Option<NotificationAction>> {
+	let script = format!(
+		"[void][Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, \
+		 ContentType = WindowsRuntime]; \
+		 [void][Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, \
+		 ContentType = WindowsRuntime]; \
+		 $xml = New-Object Windows.Data.Xml.Dom.XmlDocument; \
+		 $xml.LoadXml($env:RSNAP_NOTIFICATION_XML); \
+		 $toast = New-Object Windows.UI.Notifications.ToastNotification $xml; \
+		 Register-ObjectEvent -InputObject $toast -EventName Activated \
+		 -SourceIdentifier rsnapToast | Out-Null; \
+		 [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier(\
+		 '{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe')\
+		 .Show($toast); \
+		 $event = Wait-Event -SourceIdentifier rsnapToast -Timeout {TOAST_WAIT_SECS}; \
+		 if ($event) {{ $event.SourceArgs[1].Arguments }}"
+	);
+	let output = Command::new("powershell")
+		.args(["-NoProfile", "-STA", "-Command", &script])
+		.env("RSNAP_NOTIFICATION_XML", toast_xml(notification))
+		.stderr(Stdio::null())
+		.output()
+		.wrap_err("Failed to run powershell")?;
+
+	Ok(parse_action(notification.actions, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Shows `notification` and blocks until it is clicked, dismissed or times out, returning the
+/// button picked.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn show(notification: &Notification<'_>) -> Result<Option<NotificationAction>> {
+	let mut command = Command::new("notify-send");
+
+	command.arg("--app-name=rsnap");
+
+	if let Some(image) = notification.image {
+		command
+			.arg(format!("--icon={}", image.display()))
+			.arg(format!("--hint=string:image-path:{}", image.display()));
+	}
+	for action in notification.actions {
+		command.arg(format!("--action={}={}", action.key(), action.label()));
+	}
+
+	let output = command
+		.args(["--wait", "rsnap", notification.text])
+		.stderr(Stdio::null())
+		.output()
+		.wrap_err("Failed to run notify-send")?;
+
+	Ok(parse_action(notification.actions, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The offered button whose key the notification helper printed.
+#[cfg(any(not(target_os = "macos"), test))]
+fn parse_action(actions: &[NotificationAction], stdout: &str) -> Option<NotificationAction> {
+	let key = stdout.trim();
+
+	actions.iter().copied().find(|action| action.key() == key)
+}
+
+/// Toast markup with the image and a button per action that reports back to the caller.
+#[cfg(any(target_os = "windows", test))]
+fn toast_xml(notification: &Notification<'_>) -> String {
+	let escape = |value: &str| {
+		value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+	};
+	let image = notification.image.map_or_else(String::new, |image| {
+		format!("<image placement=\"hero\" src=\"{}\"/>", escape(&image.display().to_string()))
+	});
+	let actions = notification
+		.actions
+		.iter()
+		.map(|action| {
+			format!(
+				"<action content=\"{}\" arguments=\"{}\" activationType=\"foreground\"/>",
+				escape(action.label()),
+				action.key()
+			)
+		})
+		.collect::<String>();
+
+	format!(
+		"<toast><visual><binding template=\"ToastGeneric\"><text>rsnap</text><text>{}</text>\
+		 {image}</binding></visual><actions>{actions}</actions></toast>",
+		escape(notification.text),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use crate::notifications::{self, Notification, NotificationAction};
+
+	#[test]
+	fn toasts_escape_text_and_report_only_offered_actions() {
+		let actions = [NotificationAction::Open, NotificationAction::Delete];
+		let notification = Notification {
+			text: "Saved as a&b.png",
+			image: Some(Path::new(r"C:\Temp\t.png")),
+			actions: &actions,
+		};
+		let xml = notifications::toast_xml(&notification);
+
+		assert!(xml.contains("<text>Saved as a&amp;b.png</text>"));
+		assert!(xml.contains(r#"src="C:\Temp\t.png""#));
+		assert!(xml.contains(r#"arguments="delete""#));
+		assert!(!xml.contains(r#"arguments="copy""#));
+		assert_eq!(
+			notifications::parse_action(&actions, "delete\n"),
+			Some(NotificationAction::Delete)
+		);
+		assert_eq!(notifications::parse_action(&actions, "copy"), None);
+		assert_eq!(notifications::parse_action(&actions, ""), None);
+	}
+}
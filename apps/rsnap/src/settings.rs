@@ -1,13 +1,37 @@
+mod migrations;
+mod profiles;
+mod transfer;
+
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{self, Error, ErrorKind, Write as _};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use directories::{ProjectDirs, UserDirs};
 use global_hotkey::hotkey::{Code, HotKey, Modifiers};
 use serde::{Deserialize, Serialize};
 
-use rsnap_overlay::{OutputNaming, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode};
+use rsnap_overlay::{
+	CaptureBackendKind, CaptureMode, ClipboardFormat, CompositionGuides,
+	DEFAULT_KEYPAD_QUICK_ACTIONS, DoneAction, EdgeDeadZones, ExportBackdrop, ExportFormat,
+	ExportScaling, GpuPowerPreference, HudUnit, IntervalCaptureConfig, KEYPAD_QUICK_ACTION_SLOTS,
+	KeypadQuickAction, Language, LoupeStreamConfig, MonitorRectPoints, OutputNaming, RectPoints,
+	RegionWatchConfig, ReplayConfig, ThemeMode, ThemePairConfig, ToolbarPlacement, Watermark,
+	WindowCaptureAlphaMode, WindowShadowMode,
+};
+
+use crate::settings::migrations::{CURRENT_SETTINGS_VERSION, MigrationOutcome};
+
+pub(crate) use self::profiles::SettingsProfile;
+
+/// How many earlier capture regions are kept per display for "repeat last capture".
+const RECENT_CAPTURE_REGIONS_PER_MONITOR: usize = 5;
+
+const OPEN_WITH_COMMAND_MACOS: &str = "open -a Preview";
+const OPEN_WITH_COMMAND_WINDOWS: &str = "mspaint";
+const OPEN_WITH_COMMAND_DEFAULT: &str = "xdg-open";
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,8 +69,43 @@ impl LoupeSampleSize {
 	}
 }
 
+/// A captured selection in monitor-local points, remembered so it can be captured again.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub(crate) struct CaptureRegion {
+	pub monitor_id: u32,
+	pub x: u32,
+	pub y: u32,
+	pub width: u32,
+	pub height: u32,
+}
+/// A named fixed region that the tray menu and `rsnap capture --preset` capture directly.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub(crate) struct CapturePreset {
+	pub name: String,
+	#[serde(flatten)]
+	pub region: CaptureRegion,
+}
+
+impl From<MonitorRectPoints> for CaptureRegion {
+	fn from(region: MonitorRectPoints) -> Self {
+		let RectPoints { x, y, width, height } = region.rect;
+
+		Self { monitor_id: region.monitor_id, x, y, width, height }
+	}
+}
+impl From<CaptureRegion> for MonitorRectPoints {
+	fn from(region: CaptureRegion) -> Self {
+		Self {
+			monitor_id: region.monitor_id,
+			rect: RectPoints::new(region.x, region.y, region.width, region.height),
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub(crate) struct AppSettings {
+	#[serde(default = "default_settings_version")]
+	pub version: u32,
 	#[serde(default)]
 	pub show_alt_hint_keycap: bool,
 	#[serde(default)]
@@ -61,13 +120,27 @@ pub(crate) struct AppSettings {
 	pub hud_tint: f32,
 	#[serde(default = "default_hud_tint_hue")]
 	pub hud_tint_hue: f32,
+	#[serde(default = "default_hud_blur_dither")]
+	pub hud_blur_dither: bool,
 	#[serde(default)]
 	pub alt_activation: AltActivationMode,
 	#[serde(default = "default_selection_particles")]
 	pub selection_particles: bool,
 	#[serde(default = "default_selection_flow_stroke_width_px")]
 	pub selection_flow_stroke_width_px: f32,
+	#[serde(default)]
+	pub selection_flow_rgb: Option<[u8; 3]>,
+	#[serde(default = "default_selection_flow_animated")]
+	pub selection_flow_animated: bool,
 	pub log_filter: Option<String>,
+	#[serde(default)]
+	pub log_json: bool,
+	/// UI language; unset follows the system locale.
+	#[serde(default)]
+	pub language: Option<Language>,
+	/// Check GitHub for a newer release daily; off until the user opts in.
+	#[serde(default)]
+	pub check_for_updates: bool,
 	#[serde(default = "default_output_dir")]
 	pub output_dir: PathBuf,
 	#[serde(default = "default_output_filename_prefix")]
@@ -75,13 +148,189 @@ pub(crate) struct AppSettings {
 	#[serde(default)]
 	pub output_naming: OutputNaming,
 	#[serde(default)]
+	pub output_filename_template: String,
+	#[serde(default)]
+	pub export_format: ExportFormat,
+	#[serde(default = "default_export_quality")]
+	pub export_quality: u8,
+	#[serde(default)]
+	pub clipboard_format: ClipboardFormat,
+	#[serde(default)]
 	pub window_capture_alpha_mode: WindowCaptureAlphaMode,
 	#[serde(default)]
+	pub window_shadow_mode: WindowShadowMode,
+	#[serde(default)]
 	pub toolbar_placement: ToolbarPlacement,
 	#[serde(default)]
 	pub loupe_sample_size: LoupeSampleSize,
 	#[serde(default)]
+	pub loupe_row_column_highlight: bool,
+	#[serde(default)]
 	pub theme_mode: ThemeMode,
+	#[serde(default)]
+	pub hud_unit: HudUnit,
+	#[serde(default = "default_magnifier_hotkey")]
+	pub magnifier_hotkey: String,
+	#[serde(default = "default_magnifier_zoom")]
+	pub magnifier_zoom: u32,
+	#[serde(default = "default_keypad_quick_actions")]
+	pub keypad_quick_actions: Vec<KeypadQuickAction>,
+	#[serde(default = "default_color_picker_hotkey")]
+	pub color_picker_hotkey: String,
+	#[serde(default = "default_profile_cycle_hotkey")]
+	pub profile_cycle_hotkey: String,
+	#[serde(default = "default_display_picker_hotkey")]
+	pub display_picker_hotkey: String,
+	#[serde(default = "default_active_window_hotkey")]
+	pub active_window_hotkey: String,
+	#[serde(default = "default_pause_hotkey")]
+	pub pause_hotkey: String,
+	#[serde(default = "default_repeat_capture_hotkey")]
+	pub repeat_capture_hotkey: String,
+	/// Newest first, at most [`RECENT_CAPTURE_REGIONS_PER_MONITOR`] per display.
+	#[serde(default)]
+	pub recent_capture_regions: Vec<CaptureRegion>,
+	#[serde(default)]
+	pub capture_presets: Vec<CapturePreset>,
+	#[serde(default)]
+	pub active_profile: Option<String>,
+	#[serde(default)]
+	pub profiles: Vec<SettingsProfile>,
+	#[serde(default)]
+	pub sample_composited_output: bool,
+	#[serde(default)]
+	pub show_display_adjusted_color: bool,
+	#[serde(default)]
+	pub include_cursor: bool,
+	#[serde(default)]
+	pub capture_mode: CaptureMode,
+	#[serde(default)]
+	pub capture_delay_secs: u32,
+	/// Play the desktop's screenshot sound when a capture is exported.
+	#[serde(default)]
+	pub capture_sound: bool,
+	/// Flash the captured region before the overlay closes.
+	#[serde(default)]
+	pub capture_flash: bool,
+	/// Post a system notification with a thumbnail after a capture is copied or saved.
+	#[serde(default)]
+	pub capture_notification: bool,
+	/// Post a notification with Open, Copy, Delete and Annotate after captures that show no
+	/// overlay: the active-window hotkey, repeat region, presets and interval captures.
+	#[serde(default = "default_background_capture_notification")]
+	pub background_capture_notification: bool,
+	/// Preview copied and saved captures in a corner popover with Annotate, Copy, Save and
+	/// Dismiss; a copy left alone is saved when it closes.
+	#[serde(default)]
+	pub capture_popover: bool,
+	/// Offer images copied in other apps in the same popover, where they can be pinned on top.
+	#[serde(default)]
+	pub clipboard_watch: bool,
+	#[serde(default)]
+	pub histogram_panel: bool,
+	#[serde(default)]
+	pub copy_export_summary: bool,
+	#[serde(default)]
+	pub alt_text_enabled: bool,
+	#[serde(default)]
+	pub alt_text_command: String,
+	#[serde(default)]
+	pub export_backdrop: ExportBackdrop,
+	#[serde(default)]
+	pub export_scaling: ExportScaling,
+	#[serde(default)]
+	pub watermark: Watermark,
+	#[serde(default)]
+	pub retina_pair: bool,
+	#[serde(default)]
+	pub geometry_sidecar: bool,
+	#[serde(default)]
+	pub annotation_sidecar: bool,
+	#[serde(default)]
+	pub redact_ocr_command: String,
+	#[serde(default)]
+	pub multi_region_collage: bool,
+	#[serde(default = "default_export_preview")]
+	pub export_preview: bool,
+	#[serde(default = "default_done_actions")]
+	pub done_actions: Vec<DoneAction>,
+	#[serde(default = "default_open_with_command")]
+	pub open_with_command: String,
+	#[serde(default)]
+	pub replay_enabled: bool,
+	#[serde(default = "default_replay_seconds")]
+	pub replay_seconds: u32,
+	#[serde(default = "default_replay_fps")]
+	pub replay_fps: u32,
+	#[serde(default = "default_replay_memory_budget_mb")]
+	pub replay_memory_budget_mb: u32,
+	#[serde(default = "default_replay_hotkey")]
+	pub replay_hotkey: String,
+	#[serde(default = "default_region_watch_interval_secs")]
+	pub region_watch_interval_secs: u32,
+	#[serde(default = "default_region_watch_min_changed_percent")]
+	pub region_watch_min_changed_percent: f32,
+	#[serde(default)]
+	pub region_watch_command: String,
+	#[serde(default = "default_interval_capture_secs")]
+	pub interval_capture_secs: u32,
+	#[serde(default)]
+	pub interval_capture_timelapse: bool,
+	#[serde(default)]
+	pub appearance_toggle_command: String,
+	#[serde(default = "default_appearance_settle_ms")]
+	pub appearance_settle_ms: u32,
+	#[serde(default)]
+	pub loupe_stream_enabled: bool,
+	#[serde(default = "default_loupe_stream_port")]
+	pub loupe_stream_port: u16,
+	#[serde(default = "default_loupe_stream_fps")]
+	pub loupe_stream_fps: u32,
+	#[serde(default)]
+	pub frozen_dim_opacity: f32,
+	#[serde(default)]
+	pub frozen_dim_vignette: f32,
+	#[serde(default)]
+	pub frozen_dim_noise: f32,
+	#[serde(default)]
+	pub capture_backend: CaptureBackendKind,
+	#[serde(default)]
+	pub reduce_motion: bool,
+	#[serde(default)]
+	pub edge_dead_zones: EdgeDeadZones,
+	#[serde(default)]
+	pub composition_guides: CompositionGuides,
+	#[serde(default = "default_selection_snap_threshold_px")]
+	pub selection_snap_threshold_px: u32,
+	#[serde(default)]
+	pub pause_hotkeys_in_fullscreen: bool,
+	#[serde(default)]
+	pub fullscreen_hotkey_allowlist: String,
+	#[serde(default)]
+	pub prewarm_on_modifier_hold: bool,
+	#[serde(default)]
+	pub resident_overlay: bool,
+	#[serde(default = "default_image_cache_budget_mb")]
+	pub image_cache_budget_mb: u32,
+	#[serde(default = "default_sampling_fps_cap")]
+	pub sampling_fps_cap: u32,
+	#[serde(default)]
+	pub gpu_power_preference: GpuPowerPreference,
+	#[serde(default = "default_window_list_ttl_ms")]
+	pub window_list_ttl_ms: u32,
+	#[serde(default = "default_live_background_ttl_ms")]
+	pub live_background_ttl_ms: u32,
+	#[serde(default = "default_highlighter_rgb")]
+	pub highlighter_rgb: [u8; 3],
+	#[serde(default = "default_highlighter_width_px")]
+	pub highlighter_width_px: f32,
+	#[cfg(feature = "telemetry")]
+	#[serde(default)]
+	pub telemetry_enabled: bool,
+	/// The whole file as read when a newer rsnap wrote it; keys this build does not know are
+	/// written back from here on save so downgrading does not drop them.
+	#[serde(skip)]
+	newer_version_table: Option<toml::Table>,
 }
 impl AppSettings {
 	#[must_use]
@@ -92,27 +341,145 @@ impl AppSettings {
 		let Ok(bytes) = fs::read(&path) else {
 			return Self::default();
 		};
-		let Ok(contents) = std::str::from_utf8(&bytes) else {
-			return Self::default();
+		let mut settings = match Self::parse_versioned(&bytes) {
+			Ok((settings, MigrationOutcome::Current)) => settings,
+			Ok((settings, MigrationOutcome::Migrated { from })) => {
+				tracing::info!(
+					op = "settings.migrated",
+					from,
+					to = CURRENT_SETTINGS_VERSION,
+					"Migrated settings file."
+				);
+
+				if let Err(err) = settings.save() {
+					tracing::warn!(error = %err, "Failed to persist migrated settings.");
+				}
+
+				settings
+			},
+			Ok((settings, MigrationOutcome::Newer { version })) => {
+				tracing::warn!(
+					op = "settings.newer_version",
+					version,
+					supported = CURRENT_SETTINGS_VERSION,
+					"Settings file was written by a newer rsnap; unknown keys are kept but not used."
+				);
+
+				settings
+			},
+			Err(err) => {
+				Self::recover_corrupt(&path, &err);
+
+				Self::default()
+			},
 		};
-		let mut settings: Self = toml::from_str(contents).unwrap_or_default();
-
-		settings.capture_hotkey = sanitize_capture_hotkey(&settings.capture_hotkey)
-			.unwrap_or_else(default_capture_hotkey);
-		settings.hud_opacity = settings.hud_opacity.clamp(0.0, 1.0);
-		settings.hud_blur = settings.hud_blur.clamp(0.0, 1.0);
-		settings.hud_tint = settings.hud_tint.clamp(0.0, 1.0);
-		settings.hud_tint_hue = settings.hud_tint_hue.clamp(0.0, 1.0);
-		settings.selection_flow_stroke_width_px =
-			settings.selection_flow_stroke_width_px.clamp(1.0, 8.0);
-		settings.loupe_sample_size = settings.loupe_sample_size.sanitize();
-		settings.output_dir = sanitize_output_dir(&settings.output_dir);
-		settings.output_filename_prefix =
-			sanitize_output_filename_prefix(&settings.output_filename_prefix);
+
+		settings.sanitize();
 
 		settings
 	}
 
+	/// Clamps every value into its supported range and replaces invalid hotkeys with defaults.
+	fn sanitize(&mut self) {
+		self.capture_hotkey =
+			sanitize_capture_hotkey(&self.capture_hotkey).unwrap_or_else(default_capture_hotkey);
+		self.hud_opacity = self.hud_opacity.clamp(0.0, 1.0);
+		self.hud_blur = self.hud_blur.clamp(0.0, 1.0);
+		self.hud_tint = self.hud_tint.clamp(0.0, 1.0);
+		self.hud_tint_hue = self.hud_tint_hue.clamp(0.0, 1.0);
+		self.selection_flow_stroke_width_px = self.selection_flow_stroke_width_px.clamp(1.0, 8.0);
+		self.loupe_sample_size = self.loupe_sample_size.sanitize();
+		self.output_dir = sanitize_output_dir(&self.output_dir);
+		self.output_filename_prefix = sanitize_output_filename_prefix(&self.output_filename_prefix);
+		self.keypad_quick_actions.truncate(KEYPAD_QUICK_ACTION_SLOTS);
+		self.magnifier_hotkey = sanitize_capture_hotkey(&self.magnifier_hotkey)
+			.unwrap_or_else(default_magnifier_hotkey);
+		self.magnifier_zoom = self.magnifier_zoom.clamp(2, 8);
+		self.color_picker_hotkey = sanitize_capture_hotkey(&self.color_picker_hotkey)
+			.unwrap_or_else(default_color_picker_hotkey);
+		self.profile_cycle_hotkey = sanitize_capture_hotkey(&self.profile_cycle_hotkey)
+			.unwrap_or_else(default_profile_cycle_hotkey);
+		self.display_picker_hotkey = sanitize_capture_hotkey(&self.display_picker_hotkey)
+			.unwrap_or_else(default_display_picker_hotkey);
+		self.active_window_hotkey = sanitize_capture_hotkey(&self.active_window_hotkey)
+			.unwrap_or_else(default_active_window_hotkey);
+		self.pause_hotkey =
+			sanitize_capture_hotkey(&self.pause_hotkey).unwrap_or_else(default_pause_hotkey);
+		self.repeat_capture_hotkey = sanitize_capture_hotkey(&self.repeat_capture_hotkey)
+			.unwrap_or_else(default_repeat_capture_hotkey);
+		self.recent_capture_regions.retain(|region| region.width > 0 && region.height > 0);
+		self.trim_recent_capture_regions();
+		self.sanitize_capture_presets();
+		self.replay_seconds = self.replay_seconds.clamp(1, 60);
+		self.replay_fps = self.replay_fps.clamp(1, 15);
+		self.replay_memory_budget_mb = self.replay_memory_budget_mb.clamp(32, 2_048);
+		self.replay_hotkey =
+			sanitize_capture_hotkey(&self.replay_hotkey).unwrap_or_else(default_replay_hotkey);
+		self.region_watch_interval_secs = self.region_watch_interval_secs.clamp(1, 3_600);
+		self.region_watch_min_changed_percent =
+			self.region_watch_min_changed_percent.clamp(0.0, 100.0);
+		self.interval_capture_secs = self.interval_capture_secs.clamp(1, 86_400);
+		self.appearance_settle_ms = self.appearance_settle_ms.clamp(100, 10_000);
+		self.capture_delay_secs = self.capture_delay_secs.min(30);
+		self.export_quality = self.export_quality.clamp(1, 100);
+		self.loupe_stream_port = self.loupe_stream_port.max(1_024);
+		self.loupe_stream_fps = self.loupe_stream_fps.clamp(1, 60);
+		self.frozen_dim_opacity = self.frozen_dim_opacity.clamp(0.0, 1.0);
+		self.selection_snap_threshold_px = self.selection_snap_threshold_px.min(64);
+		self.image_cache_budget_mb = self.image_cache_budget_mb.min(16_384);
+		self.sampling_fps_cap = self.sampling_fps_cap.clamp(15, 120);
+		self.window_list_ttl_ms = self.window_list_ttl_ms.clamp(30, 2_000);
+		self.live_background_ttl_ms = self.live_background_ttl_ms.clamp(100, 5_000);
+		self.highlighter_width_px = self.highlighter_width_px.clamp(4.0, 96.0);
+		self.frozen_dim_vignette = self.frozen_dim_vignette.clamp(0.0, 1.0);
+		self.frozen_dim_noise = self.frozen_dim_noise.clamp(0.0, 1.0);
+
+		self.sanitize_profiles();
+	}
+
+	fn parse_versioned(bytes: &[u8]) -> io::Result<(Self, MigrationOutcome)> {
+		let contents =
+			std::str::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+		let mut table: toml::Table =
+			toml::from_str(contents).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+		let outcome = migrations::migrate(&mut table);
+		let newer_version_table =
+			matches!(outcome, MigrationOutcome::Newer { .. }).then(|| table.clone());
+		let mut settings: Self =
+			table.try_into().map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+		if newer_version_table.is_none() {
+			settings.version = CURRENT_SETTINGS_VERSION;
+		}
+
+		settings.newer_version_table = newer_version_table;
+
+		Ok((settings, outcome))
+	}
+
+	/// Moves an unreadable settings file aside and writes fresh defaults in its place.
+	fn recover_corrupt(path: &Path, err: &Error) {
+		let stamp =
+			SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+		let backup = path.with_extension(format!("toml.corrupt-{stamp}"));
+
+		tracing::warn!(
+			op = "settings.corrupt",
+			error = %err,
+			backup = %backup.display(),
+			"Settings file could not be read; backing it up and resetting to defaults."
+		);
+
+		if let Err(err) = fs::rename(path, &backup) {
+			tracing::warn!(error = %err, "Failed to back up corrupt settings file.");
+
+			return;
+		}
+		if let Err(err) = Self::default().save() {
+			tracing::warn!(error = %err, "Failed to write default settings after reset.");
+		}
+	}
+
 	pub fn save(&self) -> io::Result<()> {
 		let Some(path) = Self::path() else {
 			return Ok(());
@@ -122,13 +489,26 @@ impl AppSettings {
 		};
 
 		fs::create_dir_all(dir)?;
+		write_atomic(&path, self.to_toml()?.as_bytes())?;
+
+		Ok(())
+	}
 
-		let content =
-			toml::to_string_pretty(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+	/// Serializes the settings file, carrying over keys a newer rsnap wrote that this build does
+	/// not know.
+	fn to_toml(&self) -> io::Result<String> {
+		let Some(newer) = &self.newer_version_table else {
+			return toml::to_string_pretty(self)
+				.map_err(|err| Error::new(ErrorKind::InvalidData, err));
+		};
+		let mut table =
+			toml::Table::try_from(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
 
-		write_atomic(&path, content.as_bytes())?;
+		for (key, value) in newer {
+			table.entry(key.as_str()).or_insert_with(|| value.clone());
+		}
 
-		Ok(())
+		toml::to_string_pretty(&table).map_err(|err| Error::new(ErrorKind::InvalidData, err))
 	}
 
 	#[must_use]
@@ -138,16 +518,205 @@ impl AppSettings {
 		Some(dirs.config_dir().join("settings.toml"))
 	}
 
+	/// Language the UI is shown in: the chosen one, or the system locale's when unset.
+	#[must_use]
+	pub fn ui_language(&self) -> Language {
+		self.language.unwrap_or_else(rsnap_overlay::detect_language)
+	}
+
 	#[must_use]
 	pub fn capture_hotkey(&self) -> HotKey {
 		parse_capture_hotkey(&self.capture_hotkey)
 			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT), Code::KeyX))
 	}
+
+	#[must_use]
+	pub fn magnifier_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.magnifier_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyZ))
+	}
+
+	#[must_use]
+	pub fn color_picker_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.color_picker_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyC))
+	}
+
+	#[must_use]
+	pub fn profile_cycle_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.profile_cycle_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyP))
+	}
+
+	#[must_use]
+	pub fn display_picker_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.display_picker_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyD))
+	}
+
+	#[must_use]
+	pub fn active_window_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.active_window_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyW))
+	}
+
+	#[must_use]
+	pub fn pause_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.pause_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyP))
+	}
+
+	#[must_use]
+	pub fn repeat_capture_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.repeat_capture_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyX))
+	}
+
+	/// Returns the region of the latest capture, on whichever display it was.
+	#[must_use]
+	pub(crate) fn last_capture_region(&self) -> Option<CaptureRegion> {
+		self.recent_capture_regions.first().copied()
+	}
+
+	/// Moves `region` to the front of the recent regions, dropping the oldest ones on its display.
+	pub(crate) fn remember_capture_region(&mut self, region: CaptureRegion) {
+		self.recent_capture_regions.retain(|recent| *recent != region);
+		self.recent_capture_regions.insert(0, region);
+		self.trim_recent_capture_regions();
+	}
+
+	/// Looks up a capture preset by name, ignoring case.
+	#[must_use]
+	pub(crate) fn capture_preset(&self, name: &str) -> Option<&CapturePreset> {
+		self.capture_presets.iter().find(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+	}
+
+	/// Trims preset names and drops unnamed, empty, and duplicate presets.
+	fn sanitize_capture_presets(&mut self) {
+		let mut seen = Vec::new();
+
+		self.capture_presets.retain_mut(|preset| {
+			preset.name = preset.name.trim().to_owned();
+
+			let keep = !preset.name.is_empty()
+				&& preset.region.width > 0
+				&& preset.region.height > 0
+				&& !seen.contains(&preset.name.to_ascii_lowercase());
+
+			seen.push(preset.name.to_ascii_lowercase());
+
+			keep
+		});
+	}
+
+	/// Puts everything in Settings → Advanced back to its default, leaving other sections alone.
+	pub fn reset_advanced(&mut self) {
+		let defaults = Self::default();
+
+		self.frozen_dim_opacity = defaults.frozen_dim_opacity;
+		self.frozen_dim_vignette = defaults.frozen_dim_vignette;
+		self.frozen_dim_noise = defaults.frozen_dim_noise;
+		self.capture_backend = defaults.capture_backend;
+		self.image_cache_budget_mb = defaults.image_cache_budget_mb;
+		self.sampling_fps_cap = defaults.sampling_fps_cap;
+		self.gpu_power_preference = defaults.gpu_power_preference;
+		self.window_list_ttl_ms = defaults.window_list_ttl_ms;
+		self.live_background_ttl_ms = defaults.live_background_ttl_ms;
+	}
+
+	fn trim_recent_capture_regions(&mut self) {
+		let mut kept_per_monitor = HashMap::new();
+
+		self.recent_capture_regions.retain(|region| {
+			let kept = kept_per_monitor.entry(region.monitor_id).or_insert(0_usize);
+
+			*kept += 1;
+
+			*kept <= RECENT_CAPTURE_REGIONS_PER_MONITOR
+		});
+	}
+
+	#[must_use]
+	pub fn replay_hotkey(&self) -> HotKey {
+		parse_capture_hotkey(&self.replay_hotkey)
+			.unwrap_or_else(|| HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyR))
+	}
+
+	/// Ring buffer tunables; the buffer keeps exactly as much history as one export covers.
+	#[must_use]
+	pub fn replay_config(&self) -> ReplayConfig {
+		ReplayConfig {
+			fps: self.replay_fps,
+			history_secs: self.replay_seconds,
+			memory_budget_mb: self.replay_memory_budget_mb,
+			capture_backend: self.capture_backend,
+			..ReplayConfig::default()
+		}
+	}
+
+	/// Loupe stream tunables; patches match the loupe's sample size.
+	#[must_use]
+	pub fn loupe_stream_config(&self) -> LoupeStreamConfig {
+		LoupeStreamConfig {
+			port: self.loupe_stream_port,
+			fps: self.loupe_stream_fps,
+			patch_side_px: self.loupe_sample_size.side_px(),
+			capture_backend: self.capture_backend,
+		}
+	}
+
+	/// Whether `app_name` is on the full-screen allowlist; names match case-insensitively.
+	#[must_use]
+	pub fn fullscreen_hotkey_allowlist_contains(&self, app_name: &str) -> bool {
+		self.fullscreen_hotkey_allowlist
+			.split(',')
+			.map(str::trim)
+			.any(|entry| !entry.is_empty() && entry.eq_ignore_ascii_case(app_name.trim()))
+	}
+
+	/// Region watch tunables; an empty command means alerts are only logged.
+	#[must_use]
+	pub fn region_watch_config(&self) -> RegionWatchConfig {
+		RegionWatchConfig {
+			interval_secs: self.region_watch_interval_secs,
+			min_changed_ratio: f64::from(self.region_watch_min_changed_percent) / 100.0,
+			alert_command: Some(self.region_watch_command.trim())
+				.filter(|command| !command.is_empty())
+				.map(str::to_owned),
+			capture_backend: self.capture_backend,
+			..RegionWatchConfig::default()
+		}
+	}
+
+	/// Interval capture tunables; frames land in the output directory.
+	#[must_use]
+	pub fn interval_capture_config(&self) -> IntervalCaptureConfig {
+		IntervalCaptureConfig {
+			interval_secs: self.interval_capture_secs,
+			output_dir: self.output_dir.clone(),
+			timelapse: self.interval_capture_timelapse,
+			capture_backend: self.capture_backend,
+		}
+	}
+
+	/// Light/dark pair tunables; an empty command uses the system-wide appearance toggle.
+	#[must_use]
+	pub fn theme_pair_config(&self) -> ThemePairConfig {
+		ThemePairConfig {
+			toggle_command: Some(self.appearance_toggle_command.trim())
+				.filter(|command| !command.is_empty())
+				.map(str::to_owned),
+			settle_ms: self.appearance_settle_ms,
+			output_dir: self.output_dir.clone(),
+			capture_backend: self.capture_backend,
+		}
+	}
 }
 
 impl Default for AppSettings {
 	fn default() -> Self {
 		Self {
+			version: CURRENT_SETTINGS_VERSION,
 			show_alt_hint_keycap: true,
 			hud_glass_enabled: true,
 			capture_hotkey: default_capture_hotkey(),
@@ -155,17 +724,106 @@ impl Default for AppSettings {
 			hud_blur: default_hud_blur(),
 			hud_tint: default_hud_tint(),
 			hud_tint_hue: default_hud_tint_hue(),
+			hud_blur_dither: default_hud_blur_dither(),
 			alt_activation: AltActivationMode::default(),
 			selection_particles: default_selection_particles(),
 			selection_flow_stroke_width_px: default_selection_flow_stroke_width_px(),
+			selection_flow_rgb: None,
+			selection_flow_animated: default_selection_flow_animated(),
 			log_filter: None,
+			log_json: false,
+			language: None,
+			check_for_updates: false,
 			output_dir: default_output_dir(),
 			output_filename_prefix: default_output_filename_prefix(),
 			output_naming: OutputNaming::default(),
+			output_filename_template: String::new(),
+			export_format: ExportFormat::default(),
+			export_quality: default_export_quality(),
+			clipboard_format: ClipboardFormat::default(),
 			window_capture_alpha_mode: WindowCaptureAlphaMode::default(),
+			window_shadow_mode: WindowShadowMode::default(),
 			toolbar_placement: ToolbarPlacement::Bottom,
 			loupe_sample_size: LoupeSampleSize::default(),
+			loupe_row_column_highlight: false,
 			theme_mode: ThemeMode::System,
+			hud_unit: HudUnit::Points,
+			magnifier_hotkey: default_magnifier_hotkey(),
+			magnifier_zoom: default_magnifier_zoom(),
+			keypad_quick_actions: default_keypad_quick_actions(),
+			color_picker_hotkey: default_color_picker_hotkey(),
+			profile_cycle_hotkey: default_profile_cycle_hotkey(),
+			display_picker_hotkey: default_display_picker_hotkey(),
+			active_window_hotkey: default_active_window_hotkey(),
+			pause_hotkey: default_pause_hotkey(),
+			repeat_capture_hotkey: default_repeat_capture_hotkey(),
+			recent_capture_regions: Vec::new(),
+			capture_presets: Vec::new(),
+			active_profile: None,
+			profiles: Vec::new(),
+			sample_composited_output: false,
+			show_display_adjusted_color: false,
+			include_cursor: false,
+			capture_mode: CaptureMode::Region,
+			capture_delay_secs: 0,
+			capture_sound: false,
+			capture_flash: false,
+			capture_notification: false,
+			background_capture_notification: default_background_capture_notification(),
+			capture_popover: false,
+			clipboard_watch: false,
+			histogram_panel: false,
+			copy_export_summary: false,
+			alt_text_enabled: false,
+			alt_text_command: String::new(),
+			export_backdrop: ExportBackdrop::default(),
+			export_scaling: ExportScaling::default(),
+			watermark: Watermark::default(),
+			retina_pair: false,
+			geometry_sidecar: false,
+			annotation_sidecar: false,
+			redact_ocr_command: String::new(),
+			multi_region_collage: false,
+			export_preview: default_export_preview(),
+			done_actions: default_done_actions(),
+			open_with_command: default_open_with_command(),
+			replay_enabled: false,
+			replay_seconds: default_replay_seconds(),
+			replay_fps: default_replay_fps(),
+			replay_memory_budget_mb: default_replay_memory_budget_mb(),
+			replay_hotkey: default_replay_hotkey(),
+			region_watch_interval_secs: default_region_watch_interval_secs(),
+			region_watch_min_changed_percent: default_region_watch_min_changed_percent(),
+			region_watch_command: String::new(),
+			interval_capture_secs: default_interval_capture_secs(),
+			interval_capture_timelapse: false,
+			appearance_toggle_command: String::new(),
+			appearance_settle_ms: default_appearance_settle_ms(),
+			loupe_stream_enabled: false,
+			loupe_stream_port: default_loupe_stream_port(),
+			loupe_stream_fps: default_loupe_stream_fps(),
+			frozen_dim_opacity: 0.0,
+			frozen_dim_vignette: 0.0,
+			frozen_dim_noise: 0.0,
+			capture_backend: CaptureBackendKind::Auto,
+			reduce_motion: false,
+			edge_dead_zones: EdgeDeadZones::default(),
+			composition_guides: CompositionGuides::default(),
+			selection_snap_threshold_px: default_selection_snap_threshold_px(),
+			pause_hotkeys_in_fullscreen: false,
+			fullscreen_hotkey_allowlist: String::new(),
+			prewarm_on_modifier_hold: false,
+			resident_overlay: false,
+			image_cache_budget_mb: default_image_cache_budget_mb(),
+			sampling_fps_cap: default_sampling_fps_cap(),
+			gpu_power_preference: GpuPowerPreference::default(),
+			window_list_ttl_ms: default_window_list_ttl_ms(),
+			live_background_ttl_ms: default_live_background_ttl_ms(),
+			highlighter_rgb: default_highlighter_rgb(),
+			highlighter_width_px: default_highlighter_width_px(),
+			#[cfg(feature = "telemetry")]
+			telemetry_enabled: false,
+			newer_version_table: None,
 		}
 	}
 }
@@ -187,6 +845,10 @@ pub(crate) fn sanitize_output_filename_prefix(raw: &str) -> String {
 	if sanitized.is_empty() { default_output_filename_prefix() } else { sanitized.to_owned() }
 }
 
+fn default_settings_version() -> u32 {
+	CURRENT_SETTINGS_VERSION
+}
+
 fn default_hud_opacity() -> f32 {
 	0.5
 }
@@ -203,10 +865,26 @@ fn default_hud_tint_hue() -> f32 {
 	215.0 / 360.0
 }
 
+fn default_hud_blur_dither() -> bool {
+	true
+}
+
 fn default_selection_particles() -> bool {
 	true
 }
 
+fn default_export_preview() -> bool {
+	true
+}
+
+fn default_background_capture_notification() -> bool {
+	true
+}
+
+fn default_keypad_quick_actions() -> Vec<KeypadQuickAction> {
+	DEFAULT_KEYPAD_QUICK_ACTIONS.to_vec()
+}
+
 fn default_output_dir() -> PathBuf {
 	let Some(user_dirs) = UserDirs::new() else {
 		return PathBuf::from(".");
@@ -234,6 +912,94 @@ fn default_capture_hotkey() -> String {
 	HotKey::new(Some(Modifiers::ALT), Code::KeyX).to_string()
 }
 
+fn default_magnifier_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyZ).to_string()
+}
+
+fn default_color_picker_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyC).to_string()
+}
+
+fn default_profile_cycle_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyP).to_string()
+}
+
+fn default_display_picker_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyD).to_string()
+}
+
+fn default_active_window_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyW).to_string()
+}
+
+fn default_pause_hotkey() -> String {
+	HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyP).to_string()
+}
+
+fn default_repeat_capture_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyX).to_string()
+}
+
+fn default_magnifier_zoom() -> u32 {
+	4
+}
+
+fn default_replay_seconds() -> u32 {
+	10
+}
+
+fn default_replay_fps() -> u32 {
+	5
+}
+
+fn default_replay_memory_budget_mb() -> u32 {
+	256
+}
+
+fn default_replay_hotkey() -> String {
+	HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyR).to_string()
+}
+
+fn default_done_actions() -> Vec<DoneAction> {
+	vec![DoneAction::Copy]
+}
+
+fn default_open_with_command() -> String {
+	let command = if cfg!(target_os = "macos") {
+		OPEN_WITH_COMMAND_MACOS
+	} else if cfg!(target_os = "windows") {
+		OPEN_WITH_COMMAND_WINDOWS
+	} else {
+		OPEN_WITH_COMMAND_DEFAULT
+	};
+
+	String::from(command)
+}
+
+fn default_loupe_stream_port() -> u16 {
+	LoupeStreamConfig::default().port
+}
+
+fn default_loupe_stream_fps() -> u32 {
+	LoupeStreamConfig::default().fps
+}
+
+fn default_region_watch_interval_secs() -> u32 {
+	5
+}
+
+fn default_region_watch_min_changed_percent() -> f32 {
+	0.1
+}
+
+fn default_interval_capture_secs() -> u32 {
+	60
+}
+
+fn default_appearance_settle_ms() -> u32 {
+	ThemePairConfig::default().settle_ms
+}
+
 fn parse_capture_hotkey(raw: &str) -> Option<HotKey> {
 	let mut modifiers = Modifiers::empty();
 	let mut has_required_modifier = false;
@@ -293,11 +1059,50 @@ fn default_selection_flow_stroke_width_px() -> f32 {
 	2.4
 }
 
+fn default_selection_flow_animated() -> bool {
+	true
+}
+
+fn default_export_quality() -> u8 {
+	90
+}
+
+fn default_selection_snap_threshold_px() -> u32 {
+	8
+}
+
+fn default_image_cache_budget_mb() -> u32 {
+	1_024
+}
+
+fn default_sampling_fps_cap() -> u32 {
+	120
+}
+
+fn default_window_list_ttl_ms() -> u32 {
+	120
+}
+
+fn default_live_background_ttl_ms() -> u32 {
+	500
+}
+
+fn default_highlighter_rgb() -> [u8; 3] {
+	[255, 230, 0]
+}
+
+fn default_highlighter_width_px() -> f32 {
+	20.0
+}
+
 fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
 	let tmp = path.with_extension("toml.tmp");
 	let mut file = File::create(&tmp)?;
 
 	file.write_all(bytes)?;
+	file.sync_all()?;
+
+	drop(file);
 
 	fs::rename(&tmp, path)?;
 
@@ -308,8 +1113,15 @@ fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
 mod tests {
 	use std::path::PathBuf;
 
-	use crate::settings::{AltActivationMode, AppSettings, LoupeSampleSize};
-	use rsnap_overlay::{OutputNaming, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode};
+	use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+
+	use crate::settings::migrations::{CURRENT_SETTINGS_VERSION, MigrationOutcome};
+	use crate::settings::{AltActivationMode, AppSettings, CaptureRegion, LoupeSampleSize};
+	use rsnap_overlay::{
+		CaptureBackendKind, CaptureMode, ClipboardFormat, DEFAULT_KEYPAD_QUICK_ACTIONS, DoneAction,
+		ExportFormat, GpuPowerPreference, HudUnit, KeypadQuickAction, Language, OutputNaming,
+		ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode,
+	};
 
 	#[test]
 	fn toml_roundtrip() {
@@ -333,6 +1145,9 @@ mod tests {
 	alt_activation = "toggle"
 	selection_particles = true
 	selection_flow_stroke_width_px = 2.4
+	selection_flow_rgb = [255, 64, 0]
+	selection_flow_animated = false
+	frozen_dim_opacity = 0.4
 	output_dir = "/tmp/rsnap-output"
 	output_filename_prefix = "shot"
 	output_naming = "sequence"
@@ -340,12 +1155,16 @@ mod tests {
 	toolbar_placement = "top"
 	loupe_sample_size = "large"
 	theme_mode = "dark"
+	hud_unit = "millimeters"
 	"#;
 		let settings: AppSettings = toml::from_str(input).unwrap();
 
 		assert_eq!(settings.alt_activation, AltActivationMode::Toggle);
 		assert!(settings.selection_particles);
 		assert_eq!(settings.selection_flow_stroke_width_px, 2.4);
+		assert_eq!(settings.selection_flow_rgb, Some([255, 64, 0]));
+		assert!(!settings.selection_flow_animated);
+		assert_eq!(settings.frozen_dim_opacity, 0.4);
 		assert_eq!(settings.output_dir, PathBuf::from("/tmp/rsnap-output"));
 		assert_eq!(settings.output_filename_prefix, "shot");
 		assert_eq!(settings.output_naming, OutputNaming::Sequence);
@@ -353,6 +1172,24 @@ mod tests {
 		assert_eq!(settings.toolbar_placement, ToolbarPlacement::Top);
 		assert_eq!(settings.loupe_sample_size, LoupeSampleSize::Large);
 		assert_eq!(settings.theme_mode, ThemeMode::Dark);
+		assert_eq!(settings.hud_unit, HudUnit::Millimeters);
+	}
+
+	#[test]
+	fn toml_parses_keypad_quick_actions() {
+		let input = r#"
+	keypad_quick_actions = ["save", "none", "copy"]
+	"#;
+		let settings: AppSettings = toml::from_str(input).unwrap();
+
+		assert_eq!(
+			settings.keypad_quick_actions,
+			vec![KeypadQuickAction::Save, KeypadQuickAction::None, KeypadQuickAction::Copy]
+		);
+		assert_eq!(
+			AppSettings::default().keypad_quick_actions,
+			DEFAULT_KEYPAD_QUICK_ACTIONS.to_vec()
+		);
 	}
 
 	#[test]
@@ -375,6 +1212,49 @@ mod tests {
 		assert_eq!(settings.window_capture_alpha_mode, WindowCaptureAlphaMode::Background);
 	}
 
+	#[test]
+	fn parse_versioned_migrates_legacy_files() {
+		let input = br#"
+	tray_icon_filled = true
+	window_capture_alpha_mode = "preserve"
+	toolbar_placement = "top"
+	"#;
+		let (settings, outcome) = AppSettings::parse_versioned(input).unwrap();
+
+		assert_eq!(outcome, MigrationOutcome::Migrated { from: 0 });
+		assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+		assert_eq!(settings.window_capture_alpha_mode, WindowCaptureAlphaMode::Background);
+		assert_eq!(settings.toolbar_placement, ToolbarPlacement::Top);
+	}
+
+	#[test]
+	fn newer_settings_files_keep_unknown_keys_on_save() {
+		let input = format!(
+			"version = {}\nmagnifier_zoom = 5\nfuture_flag = true\n\n[future_table]\nkey = 1\n",
+			CURRENT_SETTINGS_VERSION + 1
+		);
+		let (mut settings, outcome) = AppSettings::parse_versioned(input.as_bytes()).unwrap();
+
+		assert_eq!(outcome, MigrationOutcome::Newer { version: CURRENT_SETTINGS_VERSION + 1 });
+
+		settings.magnifier_zoom = 3;
+
+		let saved: toml::Table = toml::from_str(&settings.to_toml().unwrap()).unwrap();
+
+		assert_eq!(saved["version"].as_integer(), Some(i64::from(CURRENT_SETTINGS_VERSION + 1)));
+		assert_eq!(saved["magnifier_zoom"].as_integer(), Some(3));
+		assert_eq!(saved["future_flag"].as_bool(), Some(true));
+		assert_eq!(saved["future_table"]["key"].as_integer(), Some(1));
+		assert!(!AppSettings::default().to_toml().unwrap().contains("future_flag"));
+	}
+
+	#[test]
+	fn parse_versioned_rejects_corrupt_files() {
+		assert!(AppSettings::parse_versioned(b"hud_opacity = [").is_err());
+		assert!(AppSettings::parse_versioned(b"hud_opacity = \"high\"").is_err());
+		assert!(AppSettings::parse_versioned(&[0xff, 0xfe]).is_err());
+	}
+
 	#[test]
 	fn capture_hotkey_falls_back_to_default_on_invalid() {
 		let input = r#"
@@ -388,6 +1268,270 @@ mod tests {
 		assert_eq!(loaded, AppSettings::default().capture_hotkey);
 	}
 
+	#[test]
+	fn fullscreen_hotkey_allowlist_matches_trimmed_names_case_insensitively() {
+		let settings = AppSettings {
+			fullscreen_hotkey_allowlist: String::from(" Keynote , mpv,,"),
+			..AppSettings::default()
+		};
+
+		assert!(settings.fullscreen_hotkey_allowlist_contains("keynote"));
+		assert!(settings.fullscreen_hotkey_allowlist_contains("MPV"));
+		assert!(!settings.fullscreen_hotkey_allowlist_contains("Counter-Strike 2"));
+		assert!(!settings.fullscreen_hotkey_allowlist_contains(""));
+		assert!(!AppSettings::default().fullscreen_hotkey_allowlist_contains("Keynote"));
+	}
+
+	#[test]
+	fn magnifier_hotkey_parses_default_and_custom_bindings() {
+		let settings = AppSettings::default();
+
+		assert_eq!(settings.magnifier_hotkey().to_string(), settings.magnifier_hotkey);
+
+		let settings: AppSettings = toml::from_str(r#"magnifier_hotkey = "ctrl+KeyM""#).unwrap();
+
+		assert_eq!(settings.magnifier_hotkey(), HotKey::new(Some(Modifiers::CONTROL), Code::KeyM));
+	}
+
+	#[test]
+	fn invalid_color_picker_hotkey_falls_back_to_default() {
+		let settings: AppSettings = toml::from_str(r#"color_picker_hotkey = "KeyC""#).unwrap();
+
+		assert_eq!(
+			settings.color_picker_hotkey(),
+			HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyC)
+		);
+	}
+
+	#[test]
+	fn pause_hotkey_defaults_to_ctrl_alt_p_and_parses_custom_bindings() {
+		assert_eq!(
+			AppSettings::default().pause_hotkey(),
+			HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyP)
+		);
+
+		let settings: AppSettings = toml::from_str(r#"pause_hotkey = "alt+F9""#).unwrap();
+
+		assert_eq!(settings.pause_hotkey(), HotKey::new(Some(Modifiers::ALT), Code::F9));
+	}
+
+	#[test]
+	fn capture_presets_parse_from_toml_and_look_up_by_name() {
+		let input = r#"
+		[[capture_presets]]
+		name = " demo-area "
+		monitor_id = 1
+		x = 0
+		y = 0
+		width = 1920
+		height = 1080
+
+		[[capture_presets]]
+		name = "Demo-Area"
+		monitor_id = 2
+		x = 10
+		y = 10
+		width = 100
+		height = 100
+
+		[[capture_presets]]
+		name = "empty"
+		monitor_id = 1
+		x = 0
+		y = 0
+		width = 0
+		height = 10
+		"#;
+		let mut settings: AppSettings = toml::from_str(input).unwrap();
+
+		settings.sanitize_capture_presets();
+
+		assert_eq!(settings.capture_presets.len(), 1);
+		assert_eq!(
+			settings.capture_preset("DEMO-AREA").map(|preset| preset.region),
+			Some(CaptureRegion { monitor_id: 1, x: 0, y: 0, width: 1920, height: 1080 })
+		);
+		assert!(settings.capture_preset("empty").is_none());
+	}
+
+	#[test]
+	fn recent_capture_regions_keep_the_newest_few_per_display() {
+		let region = |monitor_id, x| CaptureRegion { monitor_id, x, y: 0, width: 10, height: 10 };
+		let mut settings = AppSettings::default();
+
+		assert_eq!(settings.last_capture_region(), None);
+
+		for x in 0..7 {
+			settings.remember_capture_region(region(1, x));
+		}
+
+		settings.remember_capture_region(region(2, 0));
+		settings.remember_capture_region(region(1, 3));
+
+		assert_eq!(settings.last_capture_region(), Some(region(1, 3)));
+		assert_eq!(
+			settings.recent_capture_regions,
+			vec![
+				region(1, 3),
+				region(2, 0),
+				region(1, 6),
+				region(1, 5),
+				region(1, 4),
+				region(1, 2)
+			]
+		);
+		assert_eq!(
+			settings.repeat_capture_hotkey(),
+			HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyX)
+		);
+	}
+
+	#[test]
+	fn replay_settings_default_off_and_map_to_buffer_config() {
+		let settings: AppSettings = toml::from_str("replay_seconds = 20").unwrap();
+		let config = settings.replay_config();
+
+		assert!(!settings.replay_enabled);
+		assert_eq!(config.history_secs, 20);
+		assert_eq!(config.fps, 5);
+		assert_eq!(config.memory_budget_mb, 256);
+		assert_eq!(
+			settings.replay_hotkey(),
+			HotKey::new(Some(Modifiers::ALT | Modifiers::SHIFT), Code::KeyR)
+		);
+	}
+
+	#[test]
+	fn done_actions_default_to_copy_and_parse_in_order() {
+		assert_eq!(AppSettings::default().done_actions, vec![DoneAction::Copy]);
+
+		let settings: AppSettings = toml::from_str(r#"done_actions = ["save", "copy"]"#).unwrap();
+
+		assert_eq!(settings.done_actions, vec![DoneAction::Save, DoneAction::Copy]);
+	}
+
+	#[test]
+	fn capture_mode_defaults_to_region_and_parses_by_name() {
+		assert_eq!(AppSettings::default().capture_mode, CaptureMode::Region);
+		assert_eq!(AppSettings::default().capture_delay_secs, 0);
+
+		let settings: AppSettings =
+			toml::from_str("capture_mode = \"color_pick\"\ncapture_delay_secs = 5").unwrap();
+
+		assert_eq!(settings.capture_mode, CaptureMode::ColorPick);
+		assert_eq!(settings.capture_delay_secs, 5);
+	}
+
+	#[test]
+	fn export_format_settings_default_to_png_and_parse_by_name() {
+		let defaults = AppSettings::default();
+
+		assert_eq!(defaults.export_format, ExportFormat::Png);
+		assert_eq!(defaults.export_quality, 90);
+		assert_eq!(defaults.clipboard_format, ClipboardFormat::Image);
+		assert!(defaults.output_filename_template.is_empty());
+
+		let settings: AppSettings = toml::from_str(
+			"export_format = \"jpeg\"\nexport_quality = 75\nclipboard_format = \"file\"\n\
+			 output_filename_template = \"{prefix}-{date}-{seq}\"",
+		)
+		.unwrap();
+
+		assert_eq!(settings.export_format, ExportFormat::Jpeg);
+		assert_eq!(settings.export_quality, 75);
+		assert_eq!(settings.clipboard_format, ClipboardFormat::File);
+		assert_eq!(settings.output_filename_template, "{prefix}-{date}-{seq}");
+	}
+
+	#[test]
+	fn language_setting_follows_the_system_until_chosen() {
+		assert_eq!(AppSettings::default().language, None);
+
+		let settings: AppSettings = toml::from_str("language = \"de\"").unwrap();
+
+		assert_eq!(settings.language, Some(Language::German));
+		assert_eq!(settings.ui_language(), Language::German);
+		assert!(toml::to_string_pretty(&settings).unwrap().contains("language = \"de\""));
+	}
+
+	#[test]
+	fn loupe_stream_settings_default_off_and_follow_loupe_size() {
+		let settings: AppSettings =
+			toml::from_str("loupe_stream_fps = 30\nloupe_sample_size = \"large\"").unwrap();
+		let config = settings.loupe_stream_config();
+
+		assert!(!settings.loupe_stream_enabled);
+		assert_eq!(config.port, 47_810);
+		assert_eq!(config.fps, 30);
+		assert_eq!(config.patch_side_px, 31);
+	}
+
+	#[test]
+	fn region_watch_settings_map_to_watch_config() {
+		let settings: AppSettings =
+			toml::from_str("region_watch_min_changed_percent = 2.5\nregion_watch_command = \"  \"")
+				.unwrap();
+		let config = settings.region_watch_config();
+
+		assert_eq!(config.interval_secs, 5);
+		assert!((config.min_changed_ratio - 0.025).abs() < 1e-9);
+		assert_eq!(config.alert_command, None);
+	}
+
+	#[test]
+	fn capture_backend_defaults_to_auto_and_reaches_worker_configs() {
+		assert_eq!(AppSettings::default().capture_backend, CaptureBackendKind::Auto);
+
+		let settings: AppSettings = toml::from_str("capture_backend = \"stub\"").unwrap();
+
+		assert_eq!(settings.capture_backend, CaptureBackendKind::Stub);
+		assert_eq!(settings.replay_config().capture_backend, CaptureBackendKind::Stub);
+		assert_eq!(settings.loupe_stream_config().capture_backend, CaptureBackendKind::Stub);
+		assert_eq!(settings.region_watch_config().capture_backend, CaptureBackendKind::Stub);
+		assert_eq!(settings.interval_capture_config().capture_backend, CaptureBackendKind::Stub);
+		assert_eq!(settings.theme_pair_config().capture_backend, CaptureBackendKind::Stub);
+		assert!(toml::from_str::<AppSettings>("capture_backend = \"gdi\"").is_err());
+	}
+
+	#[test]
+	fn reset_advanced_restores_only_advanced_settings() {
+		let mut settings: AppSettings = toml::from_str(
+			"sampling_fps_cap = 30\ngpu_power_preference = \"high_performance\"\n\
+			 window_list_ttl_ms = 600\ncapture_backend = \"stub\"\n\
+			 output_filename_prefix = \"shot\"",
+		)
+		.unwrap();
+
+		assert_eq!(settings.gpu_power_preference, GpuPowerPreference::HighPerformance);
+
+		settings.reset_advanced();
+
+		assert_eq!(settings.sampling_fps_cap, 120);
+		assert_eq!(settings.gpu_power_preference, GpuPowerPreference::LowPower);
+		assert_eq!(settings.window_list_ttl_ms, 120);
+		assert_eq!(settings.capture_backend, CaptureBackendKind::Auto);
+		assert_eq!(settings.output_filename_prefix, "shot");
+	}
+
+	#[test]
+	fn profiles_parse_from_array_of_tables() {
+		let settings: AppSettings = toml::from_str(
+			r#"
+active_profile = "Docs"
+
+[[profiles]]
+name = "Docs"
+hud_unit = "pixels"
+keypad_quick_actions = ["save"]
+"#,
+		)
+		.unwrap();
+
+		assert_eq!(settings.active_profile_index(), Some(0));
+		assert_eq!(settings.effective().hud_unit, HudUnit::Pixels);
+		assert_eq!(settings.effective().keypad_quick_actions, vec![KeypadQuickAction::Save]);
+	}
+
 	#[test]
 	fn output_filename_prefix_sanitizes_invalid_chars() {
 		let sanitized = super::sanitize_output_filename_prefix("  rsnap:/demo?  ");
@@ -0,0 +1,142 @@
+use toml::{Table, Value};
+
+/// Schema version written by this build of rsnap.
+pub(crate) const CURRENT_SETTINGS_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "version";
+
+/// Outcome of bringing a raw settings table up to [`CURRENT_SETTINGS_VERSION`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum MigrationOutcome {
+	/// The table was already at the current version.
+	Current,
+	/// The table was migrated forward from the contained version.
+	Migrated { from: u32 },
+	/// The table was written by a newer rsnap and was left untouched.
+	Newer { version: u32 },
+}
+
+/// Applies every forward migration step needed to reach [`CURRENT_SETTINGS_VERSION`].
+///
+/// Files written before versioning was introduced carry no `version` key and are treated as
+/// version 0.
+pub(crate) fn migrate(table: &mut Table) -> MigrationOutcome {
+	let from = table_version(table);
+
+	if from > CURRENT_SETTINGS_VERSION {
+		return MigrationOutcome::Newer { version: from };
+	}
+	if from == CURRENT_SETTINGS_VERSION {
+		return MigrationOutcome::Current;
+	}
+
+	let mut version = from;
+
+	while version < CURRENT_SETTINGS_VERSION {
+		match version {
+			0 => migrate_v0_to_v1(table),
+			_ => unreachable!("missing settings migration step from version {version}"),
+		}
+
+		version += 1;
+	}
+
+	let _ = table.insert(VERSION_KEY.to_owned(), Value::Integer(i64::from(version)));
+
+	MigrationOutcome::Migrated { from }
+}
+
+fn table_version(table: &Table) -> u32 {
+	table
+		.get(VERSION_KEY)
+		.and_then(Value::as_integer)
+		.and_then(|version| u32::try_from(version).ok())
+		.unwrap_or(0)
+}
+
+/// v0 -> v1: drop the retired tray icon style keys and spell the window alpha mode explicitly.
+fn migrate_v0_to_v1(table: &mut Table) {
+	let _ = table.remove("tray_icon_inverted");
+	let _ = table.remove("tray_icon_filled");
+
+	if let Some(mode) = table.get_mut("window_capture_alpha_mode")
+		&& mode.as_str() == Some("preserve")
+	{
+		*mode = Value::String(String::from("background"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use toml::{Table, Value};
+
+	use crate::settings::migrations::{self, CURRENT_SETTINGS_VERSION, MigrationOutcome};
+
+	fn parse(input: &str) -> Table {
+		toml::from_str(input).unwrap()
+	}
+
+	#[test]
+	fn unversioned_table_is_treated_as_v0() {
+		let mut table = parse("hud_opacity = 0.4");
+
+		assert_eq!(migrations::migrate(&mut table), MigrationOutcome::Migrated { from: 0 });
+		assert_eq!(
+			table.get("version").and_then(Value::as_integer),
+			Some(i64::from(CURRENT_SETTINGS_VERSION))
+		);
+		assert_eq!(table.get("hud_opacity").and_then(Value::as_float), Some(0.4));
+	}
+
+	#[test]
+	fn v0_to_v1_drops_tray_icon_keys_and_renames_preserve() {
+		let mut table = parse(
+			r#"
+	tray_icon_inverted = true
+	tray_icon_filled = false
+	window_capture_alpha_mode = "preserve"
+	"#,
+		);
+
+		migrations::migrate_v0_to_v1(&mut table);
+
+		assert!(!table.contains_key("tray_icon_inverted"));
+		assert!(!table.contains_key("tray_icon_filled"));
+		assert_eq!(
+			table.get("window_capture_alpha_mode").and_then(Value::as_str),
+			Some("background")
+		);
+	}
+
+	#[test]
+	fn v0_to_v1_keeps_other_alpha_modes() {
+		let mut table = parse(r#"window_capture_alpha_mode = "matte_dark""#);
+
+		migrations::migrate_v0_to_v1(&mut table);
+
+		assert_eq!(
+			table.get("window_capture_alpha_mode").and_then(Value::as_str),
+			Some("matte_dark")
+		);
+	}
+
+	#[test]
+	fn current_version_is_left_untouched() {
+		let input = format!("version = {CURRENT_SETTINGS_VERSION}\ntray_icon_filled = true");
+		let mut table = parse(&input);
+
+		assert_eq!(migrations::migrate(&mut table), MigrationOutcome::Current);
+		assert!(table.contains_key("tray_icon_filled"));
+	}
+
+	#[test]
+	fn newer_version_is_reported_without_changes() {
+		let input = format!("version = {}", CURRENT_SETTINGS_VERSION + 1);
+		let mut table = parse(&input);
+
+		assert_eq!(
+			migrations::migrate(&mut table),
+			MigrationOutcome::Newer { version: CURRENT_SETTINGS_VERSION + 1 }
+		);
+	}
+}
@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use rsnap_overlay::{
+	HudUnit, KeypadQuickAction, OutputNaming, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode,
+};
+
+use crate::settings::{AppSettings, LoupeSampleSize};
+
+/// A named preset layered over the base settings while it is active.
+///
+/// Every override is optional; unset fields fall through to the base value, so a profile only
+/// needs to spell out what makes it different.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub(crate) struct SettingsProfile {
+	pub name: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub loupe_sample_size: Option<LoupeSampleSize>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub hud_unit: Option<HudUnit>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub show_alt_hint_keycap: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub selection_particles: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub toolbar_placement: Option<ToolbarPlacement>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub theme_mode: Option<ThemeMode>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub output_dir: Option<PathBuf>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub output_naming: Option<OutputNaming>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub window_capture_alpha_mode: Option<WindowCaptureAlphaMode>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub keypad_quick_actions: Option<Vec<KeypadQuickAction>>,
+}
+impl SettingsProfile {
+	fn apply_to(&self, settings: &mut AppSettings) {
+		if let Some(size) = self.loupe_sample_size {
+			settings.loupe_sample_size = size.sanitize();
+		}
+		if let Some(unit) = self.hud_unit {
+			settings.hud_unit = unit;
+		}
+		if let Some(show) = self.show_alt_hint_keycap {
+			settings.show_alt_hint_keycap = show;
+		}
+		if let Some(particles) = self.selection_particles {
+			settings.selection_particles = particles;
+		}
+		if let Some(placement) = self.toolbar_placement {
+			settings.toolbar_placement = placement;
+		}
+		if let Some(mode) = self.theme_mode {
+			settings.theme_mode = mode;
+		}
+		if let Some(dir) = self.output_dir.as_ref() {
+			settings.output_dir = dir.clone();
+		}
+		if let Some(naming) = self.output_naming {
+			settings.output_naming = naming;
+		}
+		if let Some(mode) = self.window_capture_alpha_mode {
+			settings.window_capture_alpha_mode = mode;
+		}
+		if let Some(actions) = self.keypad_quick_actions.as_ref() {
+			settings.keypad_quick_actions = actions.clone();
+		}
+	}
+}
+
+impl AppSettings {
+	/// Returns the index of the active profile, if it still names an existing profile.
+	#[must_use]
+	pub fn active_profile_index(&self) -> Option<usize> {
+		let name = self.active_profile.as_deref()?;
+
+		self.profiles.iter().position(|profile| profile.name == name)
+	}
+
+	/// Returns these settings with the active profile's overrides applied.
+	#[must_use]
+	pub fn effective(&self) -> Self {
+		let mut settings = self.clone();
+
+		if let Some(profile) = self.active_profile_index().map(|index| &self.profiles[index]) {
+			profile.apply_to(&mut settings);
+		}
+
+		settings
+	}
+
+	/// Activates the profile at `index`, or the base settings when `index` is `None`.
+	pub fn select_profile(&mut self, index: Option<usize>) {
+		self.active_profile =
+			index.and_then(|index| self.profiles.get(index)).map(|profile| profile.name.clone());
+	}
+
+	/// Returns the profile that follows the active one, wrapping through the base settings
+	/// after the last profile.
+	#[must_use]
+	pub fn next_profile_index(&self) -> Option<usize> {
+		let next = self.active_profile_index().map_or(0, |index| index + 1);
+
+		(next < self.profiles.len()).then_some(next)
+	}
+
+	/// Drops unnamed and duplicate profiles and clears a dangling active profile.
+	pub(crate) fn sanitize_profiles(&mut self) {
+		let mut seen = Vec::with_capacity(self.profiles.len());
+
+		self.profiles.retain_mut(|profile| {
+			profile.name = profile.name.trim().to_owned();
+
+			if profile.name.is_empty() || seen.contains(&profile.name) {
+				return false;
+			}
+
+			seen.push(profile.name.clone());
+
+			true
+		});
+
+		if self.active_profile_index().is_none() {
+			self.active_profile = None;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rsnap_overlay::HudUnit;
+
+	use crate::settings::{AppSettings, LoupeSampleSize, profiles::SettingsProfile};
+
+	fn settings_with_profiles() -> AppSettings {
+		AppSettings {
+			profiles: vec![
+				SettingsProfile {
+					name: String::from("Design review"),
+					loupe_sample_size: Some(LoupeSampleSize::Large),
+					..SettingsProfile::default()
+				},
+				SettingsProfile {
+					name: String::from("Docs"),
+					hud_unit: Some(HudUnit::Pixels),
+					..SettingsProfile::default()
+				},
+			],
+			..AppSettings::default()
+		}
+	}
+
+	#[test]
+	fn effective_applies_only_active_overrides() {
+		let mut settings = settings_with_profiles();
+
+		assert_eq!(settings.effective(), settings);
+
+		settings.select_profile(Some(0));
+
+		let effective = settings.effective();
+
+		assert_eq!(effective.loupe_sample_size, LoupeSampleSize::Large);
+		assert_eq!(effective.hud_unit, settings.hud_unit);
+	}
+
+	#[test]
+	fn next_profile_index_wraps_through_base_settings() {
+		let mut settings = settings_with_profiles();
+
+		settings.select_profile(settings.next_profile_index());
+		assert_eq!(settings.active_profile.as_deref(), Some("Design review"));
+
+		settings.select_profile(settings.next_profile_index());
+		assert_eq!(settings.active_profile.as_deref(), Some("Docs"));
+
+		settings.select_profile(settings.next_profile_index());
+		assert_eq!(settings.active_profile, None);
+	}
+
+	#[test]
+	fn sanitize_profiles_drops_duplicates_and_dangling_selection() {
+		let mut settings = settings_with_profiles();
+
+		settings
+			.profiles
+			.push(SettingsProfile { name: String::from(" Docs "), ..SettingsProfile::default() });
+		settings.active_profile = Some(String::from("Missing"));
+		settings.sanitize_profiles();
+
+		assert_eq!(settings.profiles.len(), 2);
+		assert_eq!(settings.active_profile, None);
+	}
+}
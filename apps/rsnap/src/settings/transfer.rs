@@ -0,0 +1,145 @@
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::settings::AppSettings;
+use crate::settings::migrations::CURRENT_SETTINGS_VERSION;
+
+const VERSION_KEY: &str = "version";
+
+/// Outcome of merging a settings JSON file into the current settings.
+///
+/// Keys that were missing from the file keep their current value; keys that are unknown or hold
+/// a value of the wrong shape are listed in `rejected` and keep their current value too.
+#[derive(Debug)]
+pub(crate) struct SettingsImport {
+	pub settings: AppSettings,
+	pub applied: Vec<String>,
+	pub rejected: Vec<(String, String)>,
+}
+impl SettingsImport {
+	/// One-line report of how many keys were applied, followed by one line per rejected key.
+	#[must_use]
+	pub fn summary(&self) -> String {
+		let mut summary = format!(
+			"Imported {} setting{}.",
+			self.applied.len(),
+			if self.applied.len() == 1 { "" } else { "s" }
+		);
+
+		for (key, reason) in &self.rejected {
+			summary.push_str(&format!("\nSkipped `{key}`: {reason}"));
+		}
+
+		summary
+	}
+}
+
+impl AppSettings {
+	/// Writes every setting to `path` as pretty-printed JSON.
+	pub(crate) fn export_json(&self, path: &Path) -> io::Result<()> {
+		let content = serde_json::to_string_pretty(self)
+			.map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+		fs::write(path, content + "\n")
+	}
+
+	/// Reads a JSON file written by [`AppSettings::export_json`] and merges it over `self`.
+	pub(crate) fn import_json(&self, path: &Path) -> io::Result<SettingsImport> {
+		self.merge_json(&fs::read_to_string(path)?)
+	}
+
+	/// Merges the top-level keys of a JSON object over `self` one at a time, so a single bad
+	/// value does not discard the rest of the file. Returns an error only when `text` is not a
+	/// JSON object.
+	pub(crate) fn merge_json(&self, text: &str) -> io::Result<SettingsImport> {
+		let imported: Map<String, Value> =
+			serde_json::from_str(text).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+		let Value::Object(mut merged) =
+			serde_json::to_value(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?
+		else {
+			return Err(Error::new(ErrorKind::InvalidData, "settings did not serialize to a map"));
+		};
+		let mut applied = Vec::new();
+		let mut rejected = Vec::new();
+
+		for (key, value) in imported {
+			if key == VERSION_KEY {
+				continue;
+			}
+
+			let Some(current) = merged.get(&key).cloned() else {
+				rejected.push((key, String::from("unknown setting")));
+
+				continue;
+			};
+
+			merged.insert(key.clone(), value);
+
+			match serde_json::from_value::<Self>(Value::Object(merged.clone())) {
+				Ok(_) => applied.push(key),
+				Err(err) => {
+					merged.insert(key.clone(), current);
+					rejected.push((key, err.to_string()));
+				},
+			}
+		}
+
+		let mut settings: Self = serde_json::from_value(Value::Object(merged))
+			.map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+		settings.version = CURRENT_SETTINGS_VERSION;
+		settings.sanitize();
+
+		Ok(SettingsImport { settings, applied, rejected })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rsnap_overlay::ExportFormat;
+
+	use crate::settings::AppSettings;
+
+	#[test]
+	fn json_export_roundtrips_through_import() {
+		let settings = AppSettings {
+			export_format: ExportFormat::Webp,
+			magnifier_zoom: 6,
+			..AppSettings::default()
+		};
+		let json = serde_json::to_string_pretty(&settings).expect("serialize");
+		let import = AppSettings::default().merge_json(&json).expect("valid json");
+
+		assert_eq!(import.settings, settings);
+		assert!(import.rejected.is_empty());
+		assert!(import.applied.iter().any(|key| key == "magnifier_zoom"));
+	}
+
+	#[test]
+	fn json_import_keeps_valid_keys_and_reports_bad_ones() {
+		let current = AppSettings { magnifier_zoom: 3, ..AppSettings::default() };
+		let import = current
+			.merge_json(
+				r#"{
+					"export_format": "jpeg",
+					"export_quality": 250,
+					"magnifier_zoom": "huge",
+					"no_such_setting": true
+				}"#,
+			)
+			.expect("valid json");
+		let rejected = import.rejected.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+
+		assert_eq!(import.settings.export_format, ExportFormat::Jpeg);
+		assert_eq!(import.settings.export_quality, 100);
+		assert_eq!(import.settings.magnifier_zoom, 3);
+		assert_eq!(import.applied, ["export_format", "export_quality"]);
+		assert_eq!(rejected, ["magnifier_zoom", "no_such_setting"]);
+		assert!(import.summary().contains("Skipped `magnifier_zoom`"));
+		assert!(current.merge_json("[1, 2]").is_err());
+		assert!(current.merge_json("{ not json").is_err());
+	}
+}
@@ -4,6 +4,7 @@ mod chrome;
 mod hotkey;
 mod platform;
 mod render;
+mod search;
 mod sections;
 
 use std::collections::VecDeque;
@@ -24,7 +25,9 @@ use winit::keyboard::ModifiersState;
 use winit::window::Theme;
 use winit::window::{Window, WindowId};
 
-use render::GpuContext;
+pub(crate) use render::GpuContext;
+use rsnap_overlay::exclude_window_from_capture;
+use search::SettingsSearch;
 
 const SETTINGS_ROW_HEIGHT: f32 = 22.0;
 const SETTINGS_SECTION_GAP: f32 = 6.0;
@@ -51,6 +54,7 @@ pub(crate) enum SettingsWindowAction {
 	Begin,
 	Cancel,
 	Apply(HotKey),
+	IdentifyDisplays,
 }
 
 #[derive(Clone, Debug)]
@@ -80,11 +84,16 @@ pub(crate) struct SettingsWindow {
 	capture_hotkey_recording: bool,
 	capture_hotkey_notice: Option<CaptureHotkeyNotice>,
 	action_queue: VecDeque<SettingsWindowAction>,
+	search: SettingsSearch,
+	loupe_stream_token: Option<String>,
 }
 impl SettingsWindow {
 	pub(crate) fn open(event_loop: &ActiveEventLoop) -> Result<Self> {
 		let attrs = platform::settings_window_attributes();
 		let window = event_loop.create_window(attrs).wrap_err("create settings window")?;
+
+		exclude_window_from_capture(&window);
+
 		let window = std::sync::Arc::new(window);
 		let (gpu, surface, surface_config) =
 			GpuContext::new_with_surface(std::sync::Arc::clone(&window))?;
@@ -137,6 +146,8 @@ impl SettingsWindow {
 			capture_hotkey_recording: false,
 			capture_hotkey_notice: None,
 			action_queue: VecDeque::new(),
+			search: SettingsSearch::default(),
+			loupe_stream_token: None,
 		})
 	}
 
@@ -150,6 +161,17 @@ impl SettingsWindow {
 		self.window.request_redraw();
 	}
 
+	/// Shows the running loupe stream's session token, or hides it when the stream is off.
+	pub fn set_loupe_stream_token(&mut self, token: Option<String>) {
+		if self.loupe_stream_token == token {
+			return;
+		}
+
+		self.loupe_stream_token = token;
+
+		self.window.request_redraw();
+	}
+
 	pub fn handle_window_event(&mut self, event: &WindowEvent) -> SettingsControl {
 		match event {
 			WindowEvent::CloseRequested => return SettingsControl::CloseRequested,
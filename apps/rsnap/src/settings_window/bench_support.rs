@@ -10,7 +10,7 @@ use egui::Vec2;
 use egui::ViewportId;
 use egui::Visuals;
 use egui::epaint::{ClippedPrimitive, Primitive};
-use rsnap_overlay::{OutputNaming, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode};
+use rsnap_overlay::{HudUnit, OutputNaming, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode};
 use winit::keyboard::ModifiersState;
 
 use crate::settings::{AltActivationMode, AppSettings, LoupeSampleSize};
@@ -18,7 +18,7 @@ use crate::settings::{AltActivationMode, AppSettings, LoupeSampleSize};
 use super::CaptureHotkeyNotice;
 use super::SETTINGS_COMBO_WIDTH;
 use super::hotkey::SettingsUiHotkeyHost;
-use super::sections::{self, SettingsUiHost, SettingsUiSectionDefaults};
+use super::sections::{self, SettingsSection, SettingsUiHost, SettingsUiSectionDefaults};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SettingsUiBenchScenario {
@@ -247,6 +247,16 @@ impl SettingsUiHost for BenchSettingsUiHost {
 	fn combo_width(&self) -> f32 {
 		self.combo_width
 	}
+
+	fn identify_displays(&mut self) {}
+
+	fn take_revealed_section(&mut self) -> Option<SettingsSection> {
+		None
+	}
+
+	fn loupe_stream_token(&self) -> Option<&str> {
+		None
+	}
 }
 
 fn settings_for_scenario(scenario: SettingsUiBenchScenario) -> AppSettings {
@@ -275,6 +285,7 @@ fn settings_for_scenario(scenario: SettingsUiBenchScenario) -> AppSettings {
 		settings.toolbar_placement = ToolbarPlacement::Top;
 		settings.loupe_sample_size = LoupeSampleSize::Large;
 		settings.theme_mode = ThemeMode::Dark;
+		settings.hud_unit = HudUnit::Millimeters;
 	}
 
 	settings
@@ -25,8 +25,16 @@ impl SettingsWindow {
 
 			sections::with_settings_density(ui, combo_width, |ui| {
 				changed |= self.render_titlebar_controls(ui, ctx, settings);
+
+				self.search.render_box(ui);
+
+				ui.add_space(SETTINGS_SECTION_GAP);
 				ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
-					changed |= sections::render_all_sections(self, ui, ctx, settings);
+					if self.search.is_active() {
+						changed |= self.search.render_results(ui, settings);
+					} else {
+						changed |= sections::render_all_sections(self, ui, ctx, settings);
+					}
 				});
 			});
 		});
@@ -47,6 +55,9 @@ impl SettingsWindow {
 		};
 		let max_label = [
 			"Capture hotkey",
+			"Magnifier hotkey",
+			"Color picker hotkey",
+			"Profile hotkey",
 			"Log level",
 			"Output directory",
 			"Filename prefix",
@@ -57,6 +68,8 @@ impl SettingsWindow {
 			"Flow thickness",
 			"Alt activation",
 			"Loupe sample size",
+			"HUD units",
+			"Magnifier zoom",
 			"Opacity",
 			"Blur",
 			"Tint",
@@ -81,6 +94,7 @@ impl SettingsWindow {
 			"Large (31x31)",
 			"Timestamp (unix ms)",
 			"Sequence (0001)",
+			"Millimeters (mm)",
 		]
 		.into_iter()
 		.map(measure)
@@ -201,9 +201,34 @@ pub(super) fn render_hotkeys_section(
 		ui.small(notice.as_rich_text(ui.visuals()));
 	}
 
+	render_config_hotkey_row(ui, "Magnifier hotkey", &settings.magnifier_hotkey);
+	render_config_hotkey_row(ui, "Color picker hotkey", &settings.color_picker_hotkey);
+	render_config_hotkey_row(ui, "Profile hotkey", &settings.profile_cycle_hotkey);
+	render_config_hotkey_row(ui, "Display picker hotkey", &settings.display_picker_hotkey);
+	render_config_hotkey_row(ui, "Active window hotkey", &settings.active_window_hotkey);
+	render_config_hotkey_row(ui, "Replay hotkey", &settings.replay_hotkey);
+	render_config_hotkey_row(ui, "Repeat capture hotkey", &settings.repeat_capture_hotkey);
+	render_config_hotkey_row(ui, "Pause hotkey", &settings.pause_hotkey);
+
 	false
 }
 
+/// Shows a hotkey that is configured through `settings.toml` rather than recorded in the UI.
+fn render_config_hotkey_row(ui: &mut Ui, label: &str, raw: &str) {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+
+	ui.horizontal(|ui| {
+		ui.add_sized(
+			egui::vec2(value_width, row_height),
+			egui::Label::new(format_capture_hotkey(raw)).truncate(),
+		)
+		.on_hover_text("Edit this binding in settings.toml.");
+		ui.add_space(SETTINGS_VALUE_BOX_WIDTH + ui.spacing().item_spacing.x);
+		ui.label(label);
+	});
+}
+
 pub(super) fn handle_capture_hotkey_recording_input(
 	modifiers: &ModifiersState,
 	event: &KeyEvent,
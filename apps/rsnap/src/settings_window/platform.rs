@@ -1,6 +1,10 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
 #[cfg(target_os = "macos")]
 use egui::Sense;
 use egui::{Rect, Ui};
+use rsnap_overlay::tr;
 use winit::dpi::LogicalSize;
 use winit::event::{ElementState, KeyEvent};
 use winit::keyboard::{Key, ModifiersState};
@@ -12,10 +16,23 @@ const SETTINGS_TITLEBAR_THEME_BUTTONS_Y_OFFSET_MACOS: f32 = -3.0;
 const SETTINGS_TITLEBAR_THEME_BUTTONS_Y_OFFSET_DEFAULT: f32 = 0.0;
 const SAVE_SHORTCUT_LABEL_MACOS: &str = "Cmd+S";
 const SAVE_SHORTCUT_LABEL_DEFAULT: &str = "Ctrl+S";
+const OPEN_WITH_SHORTCUT_LABEL_MACOS: &str = "Cmd+O";
+const OPEN_WITH_SHORTCUT_LABEL_DEFAULT: &str = "Ctrl+O";
+const OPEN_WITH_COMMAND_HINT_MACOS: &str = "open -a Preview";
+const OPEN_WITH_COMMAND_HINT_WINDOWS: &str = "mspaint";
+const OPEN_WITH_COMMAND_HINT_DEFAULT: &str = "gimp";
+const EXPORT_SUMMARY_NOTE_MACOS: &str =
+	"Copy keeps the summary next to the image; Save copies the summary alone.";
+const EXPORT_SUMMARY_NOTE_DEFAULT: &str =
+	"Save copies the summary; Copy keeps only the image on this platform.";
+const ALT_TEXT_NOTE_MACOS: &str =
+	"Copy adds the caption to the clipboard when ready; Save writes <name>.alt.txt.";
+const ALT_TEXT_NOTE_DEFAULT: &str =
+	"Save writes <name>.alt.txt; Copy skips the hook on this platform.";
 
 pub(super) fn settings_window_attributes() -> WindowAttributes {
 	let attrs = Window::default_attributes()
-		.with_title("Settings")
+		.with_title(tr("settings.title"))
 		.with_inner_size(LogicalSize::new(520.0, 360.0))
 		.with_resizable(false)
 		.with_visible(true);
@@ -39,6 +56,145 @@ pub(super) fn save_shortcut_label() -> &'static str {
 	if cfg!(target_os = "macos") { SAVE_SHORTCUT_LABEL_MACOS } else { SAVE_SHORTCUT_LABEL_DEFAULT }
 }
 
+pub(super) fn open_with_shortcut_label() -> &'static str {
+	if cfg!(target_os = "macos") {
+		OPEN_WITH_SHORTCUT_LABEL_MACOS
+	} else {
+		OPEN_WITH_SHORTCUT_LABEL_DEFAULT
+	}
+}
+
+pub(super) fn open_with_command_hint() -> &'static str {
+	if cfg!(target_os = "macos") {
+		OPEN_WITH_COMMAND_HINT_MACOS
+	} else if cfg!(target_os = "windows") {
+		OPEN_WITH_COMMAND_HINT_WINDOWS
+	} else {
+		OPEN_WITH_COMMAND_HINT_DEFAULT
+	}
+}
+
+pub(super) fn export_summary_note() -> &'static str {
+	if cfg!(target_os = "macos") { EXPORT_SUMMARY_NOTE_MACOS } else { EXPORT_SUMMARY_NOTE_DEFAULT }
+}
+
+pub(super) fn alt_text_note() -> &'static str {
+	if cfg!(target_os = "macos") { ALT_TEXT_NOTE_MACOS } else { ALT_TEXT_NOTE_DEFAULT }
+}
+
+/// Shows the system folder chooser starting at `start` and blocks until it closes. Returns `None`
+/// when the user cancels or no chooser is available (`zenity` or `kdialog` on Linux).
+pub(super) fn choose_directory(start: &Path) -> Option<PathBuf> {
+	first_chosen_path(directory_chooser_commands(start))
+}
+
+/// Shows the system file chooser for a settings JSON file and blocks until it closes. `save`
+/// asks for a file name to write instead of an existing file to read.
+pub(super) fn choose_settings_file(save: bool) -> Option<PathBuf> {
+	first_chosen_path(settings_file_chooser_commands(save))
+}
+
+/// Runs the first chooser command that can be spawned and returns the path it printed.
+fn first_chosen_path(commands: Vec<Command>) -> Option<PathBuf> {
+	let output = commands.into_iter().find_map(|mut command| command.output().ok())?;
+	let chosen = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+	(output.status.success() && !chosen.is_empty()).then(|| PathBuf::from(chosen))
+}
+
+#[cfg(target_os = "macos")]
+fn directory_chooser_commands(start: &Path) -> Vec<Command> {
+	let script = format!(
+		"POSIX path of (choose folder with prompt \"Save captures to\" default location \
+		 (POSIX file \"{}\"))",
+		start.display().to_string().replace('\\', "\\\\").replace('"', "\\\"")
+	);
+	let mut command = Command::new("osascript");
+
+	command.args(["-e", &script]);
+
+	vec![command]
+}
+
+#[cfg(target_os = "windows")]
+fn directory_chooser_commands(start: &Path) -> Vec<Command> {
+	let script = format!(
+		"Add-Type -AssemblyName System.Windows.Forms; \
+		 $dialog = New-Object System.Windows.Forms.FolderBrowserDialog; \
+		 $dialog.SelectedPath = '{}'; \
+		 if ($dialog.ShowDialog() -eq 'OK') {{ $dialog.SelectedPath }}",
+		start.display().to_string().replace('\'', "''")
+	);
+	let mut command = Command::new("powershell");
+
+	command.args(["-NoProfile", "-STA", "-Command", &script]);
+
+	vec![command]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn directory_chooser_commands(start: &Path) -> Vec<Command> {
+	let mut zenity = Command::new("zenity");
+	let mut kdialog = Command::new("kdialog");
+
+	zenity
+		.args(["--file-selection", "--directory", "--title=Save captures to"])
+		.arg(format!("--filename={}/", start.display()));
+	kdialog.arg("--getexistingdirectory").arg(start);
+
+	vec![zenity, kdialog]
+}
+
+#[cfg(target_os = "macos")]
+fn settings_file_chooser_commands(save: bool) -> Vec<Command> {
+	let script = if save {
+		"POSIX path of (choose file name with prompt \"Export settings\" default name \
+		 \"rsnap-settings.json\")"
+	} else {
+		"POSIX path of (choose file with prompt \"Import settings\" of type {\"public.json\"})"
+	};
+	let mut command = Command::new("osascript");
+
+	command.args(["-e", script]);
+
+	vec![command]
+}
+
+#[cfg(target_os = "windows")]
+fn settings_file_chooser_commands(save: bool) -> Vec<Command> {
+	let script = format!(
+		"Add-Type -AssemblyName System.Windows.Forms; \
+		 $dialog = New-Object System.Windows.Forms.{}; \
+		 $dialog.Filter = 'JSON files (*.json)|*.json'; \
+		 $dialog.FileName = '{}'; \
+		 if ($dialog.ShowDialog() -eq 'OK') {{ $dialog.FileName }}",
+		if save { "SaveFileDialog" } else { "OpenFileDialog" },
+		if save { "rsnap-settings.json" } else { "" }
+	);
+	let mut command = Command::new("powershell");
+
+	command.args(["-NoProfile", "-STA", "-Command", &script]);
+
+	vec![command]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn settings_file_chooser_commands(save: bool) -> Vec<Command> {
+	let mut zenity = Command::new("zenity");
+	let mut kdialog = Command::new("kdialog");
+
+	zenity.args(["--file-selection", "--file-filter=*.json"]);
+
+	if save {
+		zenity.args(["--save", "--confirm-overwrite", "--filename=rsnap-settings.json"]);
+		kdialog.args(["--getsavefilename", "rsnap-settings.json", "*.json"]);
+	} else {
+		kdialog.args(["--getopenfilename", ".", "*.json"]);
+	}
+
+	vec![zenity, kdialog]
+}
+
 pub(super) fn theme_buttons_y_offset() -> f32 {
 	if cfg!(target_os = "macos") {
 		SETTINGS_TITLEBAR_THEME_BUTTONS_Y_OFFSET_MACOS
@@ -142,12 +142,7 @@ impl SettingsWindow {
 	}
 
 	fn reconfigure_surface(&mut self) {
-		let caps = self.surface.get_capabilities(&self.gpu.adapter);
-
-		self.surface_config.present_mode = caps.present_modes[0];
-		self.surface_config.alpha_mode = pick_surface_alpha(&caps);
-
-		self.surface.configure(&self.gpu.device, &self.surface_config);
+		self.gpu.configure_surface(&self.surface, &mut self.surface_config);
 	}
 
 	pub(super) fn resize(&mut self, size: PhysicalSize<u32>) {
@@ -158,14 +153,15 @@ impl SettingsWindow {
 	}
 }
 
-pub(super) struct GpuContext {
+/// GPU handles for an egui window; the capture popover shares the settings window's setup.
+pub(crate) struct GpuContext {
 	instance: wgpu::Instance,
 	adapter: Adapter,
-	pub(super) device: Device,
-	queue: Queue,
+	pub(crate) device: Device,
+	pub(crate) queue: Queue,
 }
 impl GpuContext {
-	pub(super) fn new_with_surface(
+	pub(crate) fn new_with_surface(
 		window: std::sync::Arc<Window>,
 	) -> Result<(Self, Surface<'static>, wgpu::SurfaceConfiguration)> {
 		let instance = wgpu::Instance::new(&InstanceDescriptor::default());
@@ -206,6 +202,20 @@ impl GpuContext {
 
 		Ok((Self { instance, adapter, device, queue }, surface, surface_config))
 	}
+
+	/// Reapplies `config` to `surface` after it went out of date or was recreated.
+	pub(crate) fn configure_surface(
+		&self,
+		surface: &Surface<'_>,
+		surface_config: &mut wgpu::SurfaceConfiguration,
+	) {
+		let caps = surface.get_capabilities(&self.adapter);
+
+		surface_config.present_mode = caps.present_modes[0];
+		surface_config.alpha_mode = pick_surface_alpha(&caps);
+
+		surface.configure(&self.device, surface_config);
+	}
 }
 
 fn pick_surface_format(caps: &SurfaceCapabilities) -> TextureFormat {
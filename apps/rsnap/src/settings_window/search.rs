@@ -0,0 +1,425 @@
+use egui::text::LayoutJob;
+use egui::{
+	Align, Color32, Key, Label, Layout, Modifiers, RichText, Sense, StrokeKind, TextEdit,
+	TextFormat, TextStyle, Ui,
+};
+
+use crate::settings::AppSettings;
+use crate::settings_window::sections::SettingsSection;
+use rsnap_overlay::tr;
+
+/// What activating a search result does.
+#[derive(Clone, Copy)]
+enum SettingsRowControl {
+	/// Flips the checkbox in place, without leaving the results.
+	Toggle(fn(&mut AppSettings) -> &mut bool),
+	/// Opens the row's section and scrolls to it, for controls that need more than a toggle.
+	Reveal,
+}
+
+/// One searchable settings row. `keywords` holds extra words that should find it.
+struct SettingsRow {
+	section: SettingsSection,
+	label: &'static str,
+	keywords: &'static str,
+	control: SettingsRowControl,
+}
+
+const fn toggle(
+	section: SettingsSection,
+	label: &'static str,
+	keywords: &'static str,
+	field: fn(&mut AppSettings) -> &mut bool,
+) -> SettingsRow {
+	SettingsRow { section, label, keywords, control: SettingsRowControl::Toggle(field) }
+}
+
+const fn reveal(
+	section: SettingsSection,
+	label: &'static str,
+	keywords: &'static str,
+) -> SettingsRow {
+	SettingsRow { section, label, keywords, control: SettingsRowControl::Reveal }
+}
+
+const SETTINGS_ROWS: &[SettingsRow] = &[
+	reveal(SettingsSection::General, "Language", "locale translation deutsch german english"),
+	reveal(SettingsSection::General, "Launch at login", "autostart startup boot login item"),
+	toggle(SettingsSection::General, "Check for updates", "update release version github", |s| {
+		&mut s.check_for_updates
+	}),
+	reveal(SettingsSection::General, "Log level", "logging verbosity debug trace"),
+	toggle(SettingsSection::General, "JSON log file", "logging", |s| &mut s.log_json),
+	reveal(SettingsSection::General, "Show recent log", "logging bug report"),
+	toggle(SettingsSection::Overlay, "Show Alt hint in HUD", "keycap", |s| {
+		&mut s.show_alt_hint_keycap
+	}),
+	toggle(SettingsSection::Overlay, "Glass HUD", "blur transparency", |s| {
+		&mut s.hud_glass_enabled
+	}),
+	toggle(SettingsSection::Overlay, "Selection particles", "border flow animation", |s| {
+		&mut s.selection_particles
+	}),
+	toggle(SettingsSection::Overlay, "Selection histogram", "rgb luminance", |s| {
+		&mut s.histogram_panel
+	}),
+	toggle(SettingsSection::Overlay, "Toolbar export preview", "thumbnail", |s| {
+		&mut s.export_preview
+	}),
+	toggle(SettingsSection::Overlay, "Reduce motion", "animation accessibility", |s| {
+		&mut s.reduce_motion
+	}),
+	reveal(SettingsSection::Overlay, "Flow thickness", "selection border stroke"),
+	reveal(SettingsSection::Overlay, "Snap to windows", "selection edges magnet"),
+	reveal(SettingsSection::Overlay, "Highlighter", "color width annotation"),
+	reveal(SettingsSection::Overlay, "Alt activation", "hold toggle loupe"),
+	reveal(SettingsSection::Overlay, "Loupe sample size", "magnifier zoom"),
+	toggle(SettingsSection::Overlay, "Highlight loupe row and column", "crosshair", |s| {
+		&mut s.loupe_row_column_highlight
+	}),
+	reveal(SettingsSection::Overlay, "Toolbar placement", "top bottom"),
+	reveal(SettingsSection::Overlay, "HUD units", "points pixels millimeters"),
+	reveal(SettingsSection::Overlay, "Magnifier zoom", "loupe"),
+	reveal(SettingsSection::Overlay, "Opacity", "hud glass"),
+	reveal(SettingsSection::Overlay, "Blur", "hud glass"),
+	reveal(SettingsSection::Overlay, "Tint", "hud glass hue color"),
+	reveal(SettingsSection::Hotkeys, "Capture hotkey", "shortcut keyboard"),
+	reveal(SettingsSection::Hotkeys, "Magnifier hotkey", "shortcut"),
+	reveal(SettingsSection::Hotkeys, "Color picker hotkey", "shortcut"),
+	reveal(SettingsSection::Hotkeys, "Display picker hotkey", "shortcut"),
+	reveal(SettingsSection::Hotkeys, "Replay hotkey", "shortcut"),
+	reveal(SettingsSection::Hotkeys, "Pause hotkey", "shortcut"),
+	toggle(SettingsSection::Hotkeys, "Pause in full-screen apps", "games presentations", |s| {
+		&mut s.pause_hotkeys_in_fullscreen
+	}),
+	toggle(SettingsSection::Hotkeys, "Prewarm on modifier hold", "startup latency", |s| {
+		&mut s.prewarm_on_modifier_hold
+	}),
+	toggle(SettingsSection::Hotkeys, "Keep overlay warm", "resident latency memory", |s| {
+		&mut s.resident_overlay
+	}),
+	reveal(SettingsSection::Capture, "Capture mode", "region window display color"),
+	reveal(SettingsSection::Capture, "Capture delay", "timer countdown"),
+	reveal(SettingsSection::Capture, "Window background", "alpha transparency"),
+	reveal(SettingsSection::Capture, "Window shadow", "trim corners"),
+	toggle(SettingsSection::Capture, "Include cursor", "pointer mouse", |s| &mut s.include_cursor),
+	toggle(SettingsSection::Capture, "Shutter sound", "feedback audio confirmation", |s| {
+		&mut s.capture_sound
+	}),
+	toggle(SettingsSection::Capture, "Flash captured region", "feedback animation", |s| {
+		&mut s.capture_flash
+	}),
+	toggle(SettingsSection::Capture, "Notification after capture", "feedback thumbnail", |s| {
+		&mut s.capture_notification
+	}),
+	toggle(
+		SettingsSection::Capture,
+		"Notify after background captures",
+		"notification hotkey timer delete annotate",
+		|s| &mut s.background_capture_notification,
+	),
+	toggle(SettingsSection::Capture, "Quick-action popover", "preview thumbnail auto-save", |s| {
+		&mut s.capture_popover
+	}),
+	toggle(
+		SettingsSection::Capture,
+		"Offer to pin copied images",
+		"clipboard watch reference float",
+		|s| &mut s.clipboard_watch,
+	),
+	toggle(SettingsSection::Capture, "Sample composited output", "color picker", |s| {
+		&mut s.sample_composited_output
+	}),
+	toggle(SettingsSection::Capture, "Show display-adjusted color", "flux night shift", |s| {
+		&mut s.show_display_adjusted_color
+	}),
+	toggle(SettingsSection::Capture, "Instant replay buffer", "recording gif", |s| {
+		&mut s.replay_enabled
+	}),
+	toggle(SettingsSection::Capture, "Loupe stream for assistive tools", "accessibility", |s| {
+		&mut s.loupe_stream_enabled
+	}),
+	reveal(SettingsSection::Capture, "Edge dead zones", "click through"),
+	reveal(SettingsSection::Capture, "Watch interval", "region watch change"),
+	reveal(SettingsSection::Capture, "Capture interval", "timelapse periodic"),
+	toggle(SettingsSection::Capture, "Assemble timelapse", "interval", |s| {
+		&mut s.interval_capture_timelapse
+	}),
+	reveal(SettingsSection::Capture, "Appearance toggle", "light dark theme pair"),
+	reveal(SettingsSection::Capture, "Capture presets", "region saved"),
+	reveal(SettingsSection::Capture, "Identify displays", "monitor screen"),
+	reveal(SettingsSection::Output, "Output directory", "folder save path"),
+	reveal(SettingsSection::Output, "Filename prefix", "name"),
+	reveal(SettingsSection::Output, "Filename naming", "timestamp sequence"),
+	reveal(SettingsSection::Output, "Filename template", "name date time sequence"),
+	reveal(SettingsSection::Output, "Export format", "png jpeg webp quality"),
+	reveal(SettingsSection::Output, "Copy as", "clipboard file image"),
+	reveal(SettingsSection::Output, "Export size", "scale retina resolution"),
+	toggle(SettingsSection::Output, "Export @2x + @1x pair", "retina asset", |s| {
+		&mut s.retina_pair
+	}),
+	toggle(SettingsSection::Output, "Copy size summary", "dimensions clipboard", |s| {
+		&mut s.copy_export_summary
+	}),
+	toggle(SettingsSection::Output, "Save geometry JSON", "sidecar metadata", |s| {
+		&mut s.geometry_sidecar
+	}),
+	toggle(SettingsSection::Output, "Save annotations JSON", "sidecar metadata", |s| {
+		&mut s.annotation_sidecar
+	}),
+	toggle(SettingsSection::Output, "Save region collage", "multi region", |s| {
+		&mut s.multi_region_collage
+	}),
+	toggle(SettingsSection::Output, "Suggest alt text", "caption accessibility", |s| {
+		&mut s.alt_text_enabled
+	}),
+	reveal(SettingsSection::Output, "OCR command", "redact text tesseract"),
+	reveal(SettingsSection::Output, "Open with", "external editor"),
+	reveal(SettingsSection::Output, "Done runs", "pipeline actions order"),
+	reveal(SettingsSection::Output, "Keypad quick actions", "numpad numeric keys copy save"),
+	reveal(SettingsSection::Output, "Watermark text", "logo stamp"),
+	reveal(SettingsSection::Output, "Backdrop fill", "background padding shadow"),
+	reveal(SettingsSection::Advanced, "Frozen dim layer", "vignette noise darken"),
+	reveal(SettingsSection::Advanced, "Capture backend", "screencapturekit x11 portal"),
+	reveal(SettingsSection::Advanced, "Image cache budget", "memory"),
+	reveal(SettingsSection::Advanced, "Sampling rate cap", "fps performance power"),
+	reveal(SettingsSection::Advanced, "GPU", "power integrated discrete performance"),
+	reveal(SettingsSection::Advanced, "Window list cache", "ttl performance"),
+	reveal(SettingsSection::Advanced, "Blur background cache", "ttl performance"),
+	reveal(SettingsSection::Advanced, "Reset to defaults", "advanced restore"),
+	reveal(SettingsSection::Advanced, "Export settings", "json backup sync copy machine"),
+	reveal(SettingsSection::Advanced, "Import settings", "json restore sync merge machine"),
+];
+
+/// The search box above the settings sections and the filtered rows it shows in their place.
+#[derive(Debug, Default)]
+pub(super) struct SettingsSearch {
+	query: String,
+	focused: usize,
+	activate_focused: bool,
+	scroll_to_focused: bool,
+	focus_box: bool,
+	revealed: Option<SettingsSection>,
+}
+impl SettingsSearch {
+	pub(super) fn is_active(&self) -> bool {
+		!self.query.trim().is_empty()
+	}
+
+	pub(super) fn take_revealed_section(&mut self) -> Option<SettingsSection> {
+		self.revealed.take()
+	}
+
+	/// Draws the search box. Cmd/Ctrl+F focuses it; while it has a query, Up/Down move through
+	/// the results, Enter activates the focused one and Escape clears the query.
+	pub(super) fn render_box(&mut self, ui: &mut Ui) {
+		let active = self.is_active();
+		let (find, down, up, enter, escape) = ui.input_mut(|input| {
+			(
+				input.consume_key(Modifiers::COMMAND, Key::F),
+				active && input.consume_key(Modifiers::NONE, Key::ArrowDown),
+				active && input.consume_key(Modifiers::NONE, Key::ArrowUp),
+				active && input.consume_key(Modifiers::NONE, Key::Enter),
+				active && input.consume_key(Modifiers::NONE, Key::Escape),
+			)
+		});
+
+		if down {
+			self.focused = self.focused.saturating_add(1);
+		}
+		if up {
+			self.focused = self.focused.saturating_sub(1);
+		}
+		if escape {
+			self.query.clear();
+		}
+
+		self.scroll_to_focused |= down || up;
+		self.activate_focused |= enter;
+
+		let response = ui.add(
+			TextEdit::singleline(&mut self.query)
+				.hint_text(tr("settings.search_hint"))
+				.desired_width(f32::INFINITY),
+		);
+
+		if response.changed() {
+			self.focused = 0;
+		}
+		if find || self.focus_box || enter {
+			response.request_focus();
+
+			self.focus_box = false;
+		}
+	}
+
+	/// Draws the rows matching the query with the matched text highlighted. Returns whether a
+	/// setting was toggled.
+	pub(super) fn render_results(&mut self, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+		let rows = matching_rows(&self.query);
+		let mut changed = false;
+
+		if rows.is_empty() {
+			ui.label(RichText::new("No matching settings.").weak());
+
+			self.activate_focused = false;
+
+			return false;
+		}
+
+		self.focused = self.focused.min(rows.len() - 1);
+
+		for (index, row) in rows.iter().enumerate() {
+			let focused = index == self.focused;
+			let activate = focused && std::mem::take(&mut self.activate_focused);
+			let row_response = ui.horizontal(|ui| {
+				let label = highlighted_label(ui, row.label, &self.query);
+				let clicked = match row.control {
+					SettingsRowControl::Toggle(field) => {
+						let value = field(settings);
+						let response = ui.checkbox(value, label);
+
+						if activate {
+							*value = !*value;
+						}
+
+						response.clicked() || activate
+					},
+					SettingsRowControl::Reveal => {
+						ui.add(Label::new(label).sense(Sense::click())).clicked() || activate
+					},
+				};
+
+				ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+					ui.label(RichText::new(row.section.title()).small().weak());
+				});
+
+				clicked
+			});
+
+			if focused {
+				ui.painter().rect_stroke(
+					row_response.response.rect.expand(1.0),
+					3.0,
+					ui.visuals().selection.stroke,
+					StrokeKind::Outside,
+				);
+
+				if std::mem::take(&mut self.scroll_to_focused) {
+					row_response.response.scroll_to_me(None);
+				}
+			}
+			if row_response.inner {
+				self.focused = index;
+
+				match row.control {
+					SettingsRowControl::Toggle(_) => {
+						changed = true;
+						self.focus_box = true;
+					},
+					SettingsRowControl::Reveal => {
+						self.revealed = Some(row.section);
+
+						self.query.clear();
+						ui.ctx().request_repaint();
+					},
+				}
+			}
+		}
+
+		changed
+	}
+}
+
+/// Rows whose label, section or keywords contain every word of `query`, ignoring ASCII case.
+fn matching_rows(query: &str) -> Vec<&'static SettingsRow> {
+	let terms = query_terms(query);
+
+	if terms.is_empty() {
+		return Vec::new();
+	}
+
+	SETTINGS_ROWS
+		.iter()
+		.filter(|row| {
+			let haystack = format!("{} {} {}", row.label, row.section.title(), row.keywords)
+				.to_ascii_lowercase();
+
+			terms.iter().all(|term| haystack.contains(term.as_str()))
+		})
+		.collect()
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+	query.split_whitespace().map(str::to_ascii_lowercase).collect()
+}
+
+/// Lays out `label` with each occurrence of a query term drawn on the selection color.
+fn highlighted_label(ui: &Ui, label: &str, query: &str) -> LayoutJob {
+	let lower = label.to_ascii_lowercase();
+	let mut highlighted = vec![false; label.len()];
+
+	for term in query_terms(query) {
+		for (start, _) in lower.match_indices(term.as_str()) {
+			highlighted[start..start + term.len()].fill(true);
+		}
+	}
+
+	let font_id = TextStyle::Body.resolve(ui.style());
+	let plain = TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+	let marked = TextFormat {
+		background: ui.visuals().selection.bg_fill,
+		color: ui.visuals().strong_text_color(),
+		..TextFormat::simple(font_id, Color32::PLACEHOLDER)
+	};
+	let mut job = LayoutJob::default();
+	let mut start = 0;
+
+	while start < label.len() {
+		let mark = highlighted[start];
+		let end = (start..label.len())
+			.find(|&index| highlighted[index] != mark && label.is_char_boundary(index))
+			.unwrap_or(label.len());
+
+		job.append(&label[start..end], 0.0, if mark { marked.clone() } else { plain.clone() });
+
+		start = end;
+	}
+
+	job
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::settings::AppSettings;
+	use crate::settings_window::search::{self, SettingsRowControl};
+	use crate::settings_window::sections::SettingsSection;
+
+	#[test]
+	fn every_query_word_must_match_label_section_or_keywords() {
+		let labels = |query: &str| {
+			search::matching_rows(query).into_iter().map(|row| row.label).collect::<Vec<_>>()
+		};
+
+		assert!(labels("  ").is_empty());
+		assert_eq!(labels("JPEG"), vec!["Export format"]);
+		assert_eq!(labels("cursor capture"), vec!["Include cursor"]);
+		assert!(labels("hotkey").contains(&"Pause hotkey"));
+		assert!(labels("advanced ttl").iter().all(|label| label.ends_with("cache")));
+	}
+
+	#[test]
+	fn toggle_rows_flip_their_setting() {
+		let row = search::matching_rows("reduce motion").into_iter().next().expect("row");
+		let mut settings = AppSettings::default();
+		let SettingsRowControl::Toggle(field) = row.control else {
+			panic!("Reduce motion should toggle in place");
+		};
+
+		assert_eq!(row.section, SettingsSection::Overlay);
+
+		*field(&mut settings) = true;
+
+		assert!(settings.reduce_motion);
+	}
+}
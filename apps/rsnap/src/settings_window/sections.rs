@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use egui::Align;
 use egui::CollapsingHeader;
 use egui::ComboBox;
 use egui::Context;
@@ -13,19 +14,58 @@ use egui::TextEdit;
 use egui::Ui;
 use egui::style::HandleShape;
 
-use crate::settings::{self, AltActivationMode, AppSettings, LoupeSampleSize};
+use crate::launch_at_login;
+use crate::settings::{self, AltActivationMode, AppSettings, CapturePreset, LoupeSampleSize};
 use crate::settings_window::hotkey;
 use crate::settings_window::hotkey::SettingsUiHotkeyHost;
 use crate::settings_window::{
 	SETTINGS_HUE_SLIDER_HEIGHT, SETTINGS_HUE_SLIDER_LIGHTNESS, SETTINGS_HUE_SLIDER_SATURATION,
 	SETTINGS_HUE_SLIDER_STEPS, SETTINGS_ROW_HEIGHT, SETTINGS_SECTION_GAP,
 	SETTINGS_SLIDER_RAIL_HEIGHT, SETTINGS_SLIDER_WIDGET_HEIGHT, SETTINGS_VALUE_BOX_WIDTH,
-	SettingsWindow, platform,
+	SettingsWindow, SettingsWindowAction, platform,
 };
-use rsnap_overlay::{OutputNaming, ToolbarPlacement, WindowCaptureAlphaMode};
+use crate::startup;
+#[cfg(feature = "telemetry")]
+use crate::telemetry::UsageLog;
+use rsnap_overlay::{
+	BackdropFill, CaptureBackendKind, CaptureMode, ClipboardFormat, DoneAction, ExportFormat,
+	ExportScaleMode, GpuPowerPreference, HudUnit, KEYPAD_QUICK_ACTION_SLOTS, KeypadQuickAction,
+	Language, OutputNaming, ToolbarPlacement, WatermarkCorner, WindowCaptureAlphaMode,
+	WindowShadowMode, detect_language, tr, tr_args,
+};
+
+const LOG_VIEWER_MAX_LINES: usize = 200;
 
 pub(super) trait SettingsUiHost: SettingsUiHotkeyHost {
 	fn combo_width(&self) -> f32;
+	fn identify_displays(&mut self);
+	/// Section that settings search asked to open and scroll to on this frame.
+	fn take_revealed_section(&mut self) -> Option<SettingsSection>;
+	/// Token of the running loupe stream, which clients send before they receive frames.
+	fn loupe_stream_token(&self) -> Option<&str>;
+}
+
+/// The collapsible sections whose rows settings search can reveal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum SettingsSection {
+	General,
+	Overlay,
+	Hotkeys,
+	Capture,
+	Output,
+	Advanced,
+}
+impl SettingsSection {
+	pub(super) fn title(self) -> &'static str {
+		tr(match self {
+			Self::General => "settings.section.general",
+			Self::Overlay => "settings.section.overlay",
+			Self::Hotkeys => "settings.section.hotkeys",
+			Self::Capture => "settings.section.capture",
+			Self::Output => "settings.section.output",
+			Self::Advanced => "settings.section.advanced",
+		})
+	}
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -86,6 +126,18 @@ impl SettingsUiHost for SettingsWindow {
 	fn combo_width(&self) -> f32 {
 		self.combo_width
 	}
+
+	fn identify_displays(&mut self) {
+		self.queue_action(SettingsWindowAction::IdentifyDisplays);
+	}
+
+	fn take_revealed_section(&mut self) -> Option<SettingsSection> {
+		self.search.take_revealed_section()
+	}
+
+	fn loupe_stream_token(&self) -> Option<&str> {
+		self.loupe_stream_token.as_deref()
+	}
 }
 
 pub(super) fn with_settings_density<R>(
@@ -125,52 +177,239 @@ pub(super) fn render_all_sections_with_defaults(
 	defaults: SettingsUiSectionDefaults,
 ) -> bool {
 	let combo_width = host.combo_width();
+	let reveal = host.take_revealed_section();
 	let mut changed = false;
 
-	CollapsingHeader::new("General").default_open(defaults.general).show(ui, |ui| {
+	show_section(ui, SettingsSection::General, defaults.general, reveal, |ui| {
 		changed |= render_general_section(combo_width, ui, ctx, settings);
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("Overlay").default_open(defaults.overlay).show(ui, |ui| {
+	show_section(ui, SettingsSection::Overlay, defaults.overlay, reveal, |ui| {
 		changed |= render_overlay_section(combo_width, ui, settings);
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("Hotkeys").default_open(defaults.hotkeys).show(ui, |ui| {
+	show_section(ui, SettingsSection::Hotkeys, defaults.hotkeys, reveal, |ui| {
 		changed |= hotkey::render_hotkeys_section(host, ui, settings);
+		changed |= render_fullscreen_pause_settings(ui, settings);
+		changed |= ui
+			.checkbox(&mut settings.prewarm_on_modifier_hold, "Prewarm on modifier hold")
+			.on_hover_text(
+				"Prepare the overlay while the capture hotkey's modifiers are held so it opens \
+				 instantly when the chord completes.",
+			)
+			.changed();
+		changed |= ui
+			.checkbox(&mut settings.resident_overlay, "Keep overlay warm")
+			.on_hover_text(
+				"Keep the GPU device, capture worker and display list ready between captures, so \
+				 every capture opens without a cold start. Uses more memory and, on macOS, keeps \
+				 the capture stream running.",
+			)
+			.changed();
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("Capture").default_open(defaults.capture).show(ui, |ui| {
-		changed |= render_capture_section(combo_width, ui, settings);
+	show_section(ui, SettingsSection::Capture, defaults.capture, reveal, |ui| {
+		let loupe_stream_token = host.loupe_stream_token().map(str::to_owned);
+
+		changed |= render_capture_section(combo_width, ui, settings, loupe_stream_token.as_deref());
+
+		if ui
+			.button("Identify displays")
+			.on_hover_text(
+				"Briefly show each display's number, name, resolution and scale factor on the \
+				 display itself.",
+			)
+			.clicked()
+		{
+			host.identify_displays();
+		}
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("Output").default_open(defaults.output).show(ui, |ui| {
+	show_section(ui, SettingsSection::Output, defaults.output, reveal, |ui| {
 		changed |= render_output_section(combo_width, ui, settings);
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("Advanced").default_open(defaults.advanced).show(ui, |ui| {
-		ui.label("Advanced options are coming soon.");
+	show_section(ui, SettingsSection::Advanced, defaults.advanced, reveal, |ui| {
+		changed |= render_advanced_section(ui, settings);
 	});
 
 	ui.add_space(SETTINGS_SECTION_GAP);
 
-	CollapsingHeader::new("About").default_open(defaults.about).show(ui, |ui| {
-		ui.label(format!("rsnap {}", env!("CARGO_PKG_VERSION")));
+	#[cfg(feature = "telemetry")]
+	{
+		CollapsingHeader::new(tr("settings.section.usage_metrics")).default_open(false).show(
+			ui,
+			|ui| {
+				changed |= render_usage_metrics_section(ui, settings);
+			},
+		);
+
+		ui.add_space(SETTINGS_SECTION_GAP);
+	}
+
+	CollapsingHeader::new(tr("settings.section.about")).default_open(defaults.about).show(
+		ui,
+		|ui| {
+			ui.label(format!("rsnap {}", env!("CARGO_PKG_VERSION")));
+		},
+	);
+
+	changed
+}
+
+/// Shows one collapsible section, forcing it open and scrolling to it when search revealed it.
+fn show_section(
+	ui: &mut Ui,
+	section: SettingsSection,
+	default_open: bool,
+	reveal: Option<SettingsSection>,
+	add_contents: impl FnOnce(&mut Ui),
+) {
+	let revealed = reveal == Some(section);
+	let response = CollapsingHeader::new(section.title())
+		.default_open(default_open)
+		.open(revealed.then_some(true))
+		.show(ui, add_contents);
+
+	if revealed {
+		response.header_response.scroll_to_me(Some(Align::TOP));
+	}
+}
+
+/// Opt-in toggle plus a review screen showing exactly what has been counted so far.
+#[cfg(feature = "telemetry")]
+fn render_usage_metrics_section(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let report_id = egui::Id::new("rsnap-usage-report");
+	let changed =
+		ui.checkbox(&mut settings.telemetry_enabled, "Record anonymous usage counts").changed();
+
+	ui.small("Counts modes used, export kinds, and error categories. Stored only on this machine;");
+	ui.small("nothing is sent. Review the report below and share it by hand if you like.");
+
+	ui.horizontal(|ui| {
+		if ui.button("Review report").clicked() {
+			let report = UsageLog::load().report();
+
+			ui.data_mut(|data| data.insert_temp(report_id, report));
+		}
+		if ui.button("Clear").clicked() {
+			let log = UsageLog::default();
+
+			if let Err(err) = log.save() {
+				tracing::warn!(error = %err, "Failed to clear usage counters.");
+			}
+
+			ui.data_mut(|data| data.insert_temp(report_id, log.report()));
+		}
+	});
+
+	if let Some(mut report) = ui.data(|data| data.get_temp::<String>(report_id)) {
+		ui.add(TextEdit::multiline(&mut report).code_editor().interactive(false).desired_rows(6));
+
+		if ui.button("Copy report").clicked() {
+			ui.ctx().copy_text(report);
+		}
+	}
+
+	changed
+}
+
+fn render_fullscreen_pause_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = ui
+		.checkbox(&mut settings.pause_hotkeys_in_fullscreen, "Pause in full-screen apps")
+		.on_hover_text(
+			"Release the global hotkeys while a full-screen app such as a game is focused.",
+		)
+		.changed();
+
+	if settings.pause_hotkeys_in_fullscreen {
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(
+					TextEdit::singleline(&mut settings.fullscreen_hotkey_allowlist)
+						.hint_text("Keynote, mpv"),
+				)
+				.on_hover_text("Comma-separated app names that keep the hotkeys when full-screen.")
+				.changed();
+
+			ui.label("Allowlist");
+		});
+		ui.small("The tray tooltip shows when the hotkeys are paused.");
+	}
+
+	changed
+}
+
+fn render_capture_section(
+	combo_width: f32,
+	ui: &mut Ui,
+	settings: &mut AppSettings,
+	loupe_stream_token: Option<&str>,
+) -> bool {
+	let previous_capture_mode = settings.capture_mode;
+	let mut changed = false;
+
+	ComboBox::from_label("Capture mode")
+		.selected_text(capture_mode_label(settings.capture_mode))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for mode in [
+				CaptureMode::Region,
+				CaptureMode::Window,
+				CaptureMode::Display,
+				CaptureMode::ColorPick,
+			] {
+				ui.selectable_value(&mut settings.capture_mode, mode, capture_mode_label(mode));
+			}
+		});
+
+	if settings.capture_mode != previous_capture_mode {
+		changed = true;
+	}
+
+	ui.small("What the capture hotkey and the tray's Capture item start with.");
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(DragValue::new(&mut settings.capture_delay_secs).range(0..=30).suffix(" s"))
+			.on_hover_text(
+				"Wait this long after a selection before capturing it. Clicks pass through to \
+				 other apps meanwhile, so menus and hover states can be opened.",
+			)
+			.changed();
+
+		ui.label("Capture delay");
 	});
+	ui.small("0 captures immediately; Esc during the countdown drops the selection.");
+
+	changed |= render_window_capture_settings(combo_width, ui, settings);
+	changed |= render_capture_feedback_settings(ui, settings);
+	changed |= render_edge_dead_zone_settings(ui, settings);
+	changed |= render_color_sampling_settings(ui, settings);
+	changed |= render_replay_settings(ui, settings);
+	changed |= render_region_watch_settings(ui, settings);
+	changed |= render_interval_capture_settings(ui, settings);
+	changed |= render_theme_pair_settings(ui, settings);
+	changed |= render_capture_preset_settings(ui, settings);
+	changed |= render_loupe_stream_settings(ui, settings, loupe_stream_token);
 
 	changed
 }
 
-fn render_capture_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+fn render_window_capture_settings(
+	combo_width: f32,
+	ui: &mut Ui,
+	settings: &mut AppSettings,
+) -> bool {
 	let previous_alpha_mode = settings.window_capture_alpha_mode;
 	let mut changed = false;
 
@@ -207,6 +446,404 @@ fn render_capture_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSetti
 	ui.small("Background matches region-style capture inside the window bounds.");
 	ui.small("Matte modes flatten transparency onto a solid background.");
 
+	let previous_shadow_mode = settings.window_shadow_mode;
+
+	ComboBox::from_label("Window shadow")
+		.selected_text(match settings.window_shadow_mode {
+			WindowShadowMode::Trim => "Trim (tight rect)",
+			WindowShadowMode::Keep => "Keep (transparent)",
+		})
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(
+				&mut settings.window_shadow_mode,
+				WindowShadowMode::Trim,
+				"Trim (tight rect)",
+			);
+			ui.selectable_value(
+				&mut settings.window_shadow_mode,
+				WindowShadowMode::Keep,
+				"Keep (transparent)",
+			);
+		});
+
+	if settings.window_shadow_mode != previous_shadow_mode {
+		changed = true;
+	}
+
+	ui.small("Trim drops the shadow and squares rounded corners; Keep exports the macOS shadow.");
+
+	changed
+}
+
+/// Renders what happens after a capture: cursor, sound, flash, notifications and popovers.
+fn render_capture_feedback_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
+	changed |= ui
+		.checkbox(&mut settings.include_cursor, "Include cursor")
+		.on_hover_text("Draw the pointer into frozen captures and exported images.")
+		.changed();
+
+	ui.small("Uses the system cursor image on macOS and a standard arrow elsewhere.");
+
+	changed |= ui
+		.checkbox(&mut settings.capture_sound, "Shutter sound")
+		.on_hover_text("Play the system screenshot sound when a capture is copied or saved.")
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.capture_flash, "Flash captured region")
+		.on_hover_text("Briefly wash the selection in white before the overlay closes.")
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.capture_notification, "Notification after capture")
+		.on_hover_text("Post a system notification with a thumbnail and Open and Copy actions.")
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.background_capture_notification, "Notify after background captures")
+		.on_hover_text(
+			"After active-window, repeat, preset and interval captures, post a notification with \
+			 Open, Copy, Delete and Annotate actions.",
+		)
+		.changed();
+
+	ui.small("macOS notifications show text only, without a thumbnail or actions.");
+
+	changed |= ui
+		.checkbox(&mut settings.capture_popover, "Quick-action popover")
+		.on_hover_text(
+			"Preview each copied or saved capture in a screen corner with Annotate, Copy, Save \
+			 and Dismiss. A copied capture is saved when the popover closes on its own.",
+		)
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.clipboard_watch, "Offer to pin copied images")
+		.on_hover_text(
+			"When another app copies an image, show it in the same popover with Pin, Annotate, \
+			 Save and Dismiss. A pinned image stays on top until dismissed.",
+		)
+		.changed();
+
+	changed
+}
+
+fn render_color_sampling_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
+	changed |= ui
+		.checkbox(&mut settings.sample_composited_output, "Sample composited output")
+		.on_hover_text(
+			"Pick colors from what the display shows, including other apps' overlay windows.",
+		)
+		.changed();
+
+	ui.small("Off samples window content only; on also sees overlays such as cursor highlights.");
+	ui.small("Night Shift and color profiles are applied later and never appear in either.");
+
+	changed |= ui
+		.checkbox(&mut settings.show_display_adjusted_color, "Show display-adjusted color")
+		.on_hover_text(
+			"When the display's gamma table is tinted (e.g. by f.lux), also show the color the \
+			 screen emits next to the true sampled color.",
+		)
+		.changed();
+
+	ui.small("Sampled values stay the true design colors; the extra row appears only when tinted.");
+	ui.small(
+		"Night Shift on recent macOS may not change the gamma table and then goes undetected.",
+	);
+
+	changed
+}
+
+fn render_replay_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
+	changed |= ui
+		.checkbox(&mut settings.replay_enabled, "Instant replay buffer")
+		.on_hover_text("Keep recent frames of the main display in memory for the replay hotkey.")
+		.changed();
+
+	if settings.replay_enabled {
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(DragValue::new(&mut settings.replay_seconds).range(1..=60).suffix(" s"))
+				.changed();
+
+			ui.label("Length");
+			changed |= ui
+				.add(DragValue::new(&mut settings.replay_fps).range(1..=15).suffix(" fps"))
+				.changed();
+
+			ui.label("Rate");
+		});
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(
+					DragValue::new(&mut settings.replay_memory_budget_mb)
+						.range(32..=2_048)
+						.suffix(" MB"),
+				)
+				.changed();
+
+			ui.label("Memory budget");
+		});
+		ui.small(
+			"The replay hotkey saves the last seconds as an animated PNG in the output directory.",
+		);
+		ui.small("Frames stay in memory only; the oldest drop first when the budget is reached.");
+	}
+
+	changed
+}
+
+fn render_loupe_stream_settings(
+	ui: &mut Ui,
+	settings: &mut AppSettings,
+	loupe_stream_token: Option<&str>,
+) -> bool {
+	let mut changed = false;
+
+	changed |= ui
+		.checkbox(&mut settings.loupe_stream_enabled, "Loupe stream for assistive tools")
+		.on_hover_text("Stream the loupe patch around the cursor to local apps over TCP.")
+		.changed();
+
+	if settings.loupe_stream_enabled {
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(DragValue::new(&mut settings.loupe_stream_port).range(1_024..=u16::MAX))
+				.changed();
+
+			ui.label("Port");
+			changed |= ui
+				.add(DragValue::new(&mut settings.loupe_stream_fps).range(1..=60).suffix(" fps"))
+				.changed();
+
+			ui.label("Rate");
+		});
+
+		if let Some(token) = loupe_stream_token {
+			ui.horizontal(|ui| {
+				ui.monospace(token);
+
+				if ui.button("Copy").on_hover_text("Copy the session token.").clicked() {
+					ui.ctx().copy_text(token.to_owned());
+				}
+
+				ui.label("Token");
+			});
+		}
+
+		ui.small("Listens on 127.0.0.1 only and samples only while a client is connected.");
+		ui.small(
+			"Clients send the token and a newline before they receive frames; it changes each \
+			 time the stream starts.",
+		);
+	}
+
+	changed
+}
+
+fn render_edge_dead_zone_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let zones = &mut settings.edge_dead_zones;
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		changed |= ui.checkbox(&mut zones.top, "Top").changed();
+		changed |= ui.checkbox(&mut zones.bottom, "Bottom").changed();
+		changed |= ui.checkbox(&mut zones.left, "Left").changed();
+		changed |= ui.checkbox(&mut zones.right, "Right").changed();
+		changed |= ui
+			.add(DragValue::new(&mut zones.size_px).range(1..=64).suffix(" px"))
+			.on_hover_text("Width of each edge band.")
+			.changed();
+
+		ui.label("Edge dead zones");
+	});
+	ui.small("Clicks on checked edges pass through the live overlay, e.g. to reveal the Dock.");
+
+	changed
+}
+
+fn render_composition_guide_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let guides = &mut settings.composition_guides;
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.checkbox(&mut guides.visible, "Composition guides")
+			.on_hover_text("Draw guides inside the selection while dragging. G toggles them.")
+			.changed();
+
+		ui.add_enabled_ui(guides.visible, |ui| {
+			changed |= ui.checkbox(&mut guides.thirds, "Thirds").changed();
+			changed |= ui.checkbox(&mut guides.center, "Center").changed();
+			changed |= ui.checkbox(&mut guides.safe_margins, "Safe margins").changed();
+		});
+	});
+
+	changed
+}
+
+fn render_region_watch_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.region_watch_interval_secs)
+					.range(1..=3_600)
+					.suffix(" s"),
+			)
+			.changed();
+
+		ui.label("Watch interval");
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.region_watch_min_changed_percent)
+					.range(0.0..=100.0)
+					.speed(0.05)
+					.suffix(" %"),
+			)
+			.on_hover_text("Share of the region that must change before an alert fires.")
+			.changed();
+
+		ui.label("Change");
+	});
+	ui.horizontal(|ui| {
+		let command_response = ui.add_sized(
+			egui::vec2(value_width, row_height),
+			TextEdit::singleline(&mut settings.region_watch_command)
+				.hint_text("curl -fsS -X POST https://example.com/hook"),
+		);
+
+		changed |= command_response.changed();
+
+		command_response.on_hover_text(
+			"Runs in a shell with the changed region as PNG on stdin and RSNAP_CHANGED_RATIO set.",
+		);
+		ui.label("Alert command");
+	});
+	ui.small("Press W on a frozen selection to watch it; stop from the tray menu.");
+
+	changed
+}
+
+fn render_interval_capture_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(DragValue::new(&mut settings.interval_capture_secs).range(1..=86_400).suffix(" s"))
+			.changed();
+
+		ui.label("Capture interval");
+		changed |= ui
+			.checkbox(&mut settings.interval_capture_timelapse, "Assemble timelapse")
+			.on_hover_text("When the run stops, also save its frames as one animated PNG.")
+			.changed();
+	});
+	ui.small(
+		"Press I on a frozen selection, or use the tray's Interval Capture…, to save it on this \
+		 schedule into dated folders in the output directory.",
+	);
+
+	changed
+}
+
+fn render_theme_pair_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		let command_response = ui.add_sized(
+			egui::vec2(value_width, row_height),
+			TextEdit::singleline(&mut settings.appearance_toggle_command)
+				.hint_text("System light/dark toggle"),
+		);
+
+		changed |= command_response.changed();
+
+		command_response.on_hover_text(
+			"Runs in a shell to flip the appearance, e.g. an AppleScript or a shortcut that \
+			 switches only the target app. Leave empty for the system-wide toggle.",
+		);
+		ui.label("Appearance toggle");
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.appearance_settle_ms)
+					.range(100..=10_000)
+					.speed(50)
+					.suffix(" ms"),
+			)
+			.on_hover_text("How long apps get to repaint before the second capture.")
+			.changed();
+
+		ui.label("Settle");
+	});
+	ui.small("Press T on a frozen selection to save it in both light and dark appearance.");
+
+	changed
+}
+
+fn render_capture_preset_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+	let mut remove = None;
+
+	ui.label("Capture presets");
+
+	for (index, preset) in settings.capture_presets.iter_mut().enumerate() {
+		let region = &mut preset.region;
+
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(TextEdit::singleline(&mut preset.name).desired_width(96.0).hint_text("Name"))
+				.changed();
+			changed |= ui
+				.add(DragValue::new(&mut region.x).prefix("x "))
+				.on_hover_text("Left edge in points on the preset's display.")
+				.changed();
+			changed |= ui.add(DragValue::new(&mut region.y).prefix("y ")).changed();
+			changed |= ui.add(DragValue::new(&mut region.width).range(1..=u32::MAX)).changed();
+
+			ui.label("×");
+			changed |= ui.add(DragValue::new(&mut region.height).range(1..=u32::MAX)).changed();
+
+			if ui.button("✕").on_hover_text("Remove preset").clicked() {
+				remove = Some(index);
+			}
+		});
+	}
+
+	let last_region = settings.last_capture_region();
+
+	if ui
+		.add_enabled(last_region.is_some(), egui::Button::new("+ Add from last capture"))
+		.on_disabled_hover_text("Capture a region first; the new preset starts from it.")
+		.clicked()
+		&& let Some(region) = last_region
+	{
+		let name = format!("Preset {}", settings.capture_presets.len() + 1);
+
+		settings.capture_presets.push(CapturePreset { name, region });
+
+		changed = true;
+	}
+	if let Some(index) = remove {
+		settings.capture_presets.remove(index);
+
+		changed = true;
+	}
+
+	ui.small(
+		"Capture a preset from the tray's Capture Preset menu or with `rsnap capture --preset \
+		 <name>`, without selecting anything.",
+	);
+
 	changed
 }
 
@@ -233,10 +870,34 @@ fn render_output_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSettin
 			changed = true;
 		}
 
-		dir_response.on_hover_text("Directory where Save writes PNG files.");
+		dir_response.on_hover_text("Directory where Save writes captures.");
+
+		if ui.button("Browse…").on_hover_text("Choose the output directory.").clicked()
+			&& let Some(dir) = platform::choose_directory(&settings.output_dir)
+		{
+			settings.output_dir = dir;
+			changed = true;
+		}
+
 		ui.label("Output directory");
 	});
 
+	changed |= render_filename_settings(combo_width, ui, settings);
+	changed |= render_format_settings(combo_width, ui, settings);
+	changed |= render_sidecar_settings(ui, settings);
+	changed |= render_external_command_settings(ui, settings);
+	changed |= render_done_actions_settings(ui, settings);
+	changed |= render_keypad_quick_action_settings(combo_width, ui, settings);
+	changed |= render_backdrop_settings(combo_width, ui, settings);
+	changed |= render_watermark_settings(combo_width, ui, settings);
+
+	changed
+}
+
+fn render_filename_settings(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let mut changed = false;
 	let mut prefix = settings.output_filename_prefix.clone();
 
 	ui.horizontal(|ui| {
@@ -245,44 +906,535 @@ fn render_output_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSettin
 			TextEdit::singleline(&mut prefix).hint_text("rsnap"),
 		);
 
-		if prefix_response.changed() {
-			settings.output_filename_prefix = settings::sanitize_output_filename_prefix(&prefix);
-			changed = true;
-		}
+		if prefix_response.changed() {
+			settings.output_filename_prefix = settings::sanitize_output_filename_prefix(&prefix);
+			changed = true;
+		}
+
+		prefix_response.on_hover_text("Filename prefix used for saved captures.");
+		ui.label("Filename prefix");
+	});
+
+	let previous_naming = settings.output_naming;
+
+	ComboBox::from_label("Filename naming")
+		.selected_text(match settings.output_naming {
+			OutputNaming::Timestamp => "Timestamp (unix ms)",
+			OutputNaming::Sequence => "Sequence (0001)",
+		})
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(
+				&mut settings.output_naming,
+				OutputNaming::Timestamp,
+				"Timestamp (unix ms)",
+			);
+			ui.selectable_value(
+				&mut settings.output_naming,
+				OutputNaming::Sequence,
+				"Sequence (0001)",
+			);
+		});
+
+	if settings.output_naming != previous_naming {
+		changed = true;
+	}
+
+	ui.horizontal(|ui| {
+		let template_response = ui.add_sized(
+			egui::vec2(value_width, row_height),
+			TextEdit::singleline(&mut settings.output_filename_template)
+				.hint_text("{prefix}-{date}-{seq}"),
+		);
+
+		changed |= template_response.changed();
+
+		template_response.on_hover_text(
+			"Tokens: {prefix}, {date}, {time} (UTC), {ts} (unix ms) and {seq}. Empty uses the \
+			 prefix and naming above.",
+		);
+		ui.label("Filename template");
+	});
+	ui.small(format!(
+		"Next file: {}",
+		rsnap_overlay::output_filename_preview(
+			&settings.output_filename_template,
+			&settings.output_filename_prefix,
+			settings.output_naming,
+			settings.export_format,
+		)
+	));
+
+	changed
+}
+
+fn render_format_settings(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let previous_format = settings.export_format;
+	let mut changed = false;
+
+	ComboBox::from_label("Export format")
+		.selected_text(export_format_label(settings.export_format))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for format in [ExportFormat::Png, ExportFormat::Jpeg, ExportFormat::Webp] {
+				ui.selectable_value(
+					&mut settings.export_format,
+					format,
+					export_format_label(format),
+				);
+			}
+		});
+
+	if settings.export_format != previous_format {
+		changed = true;
+	}
+	if settings.export_format == ExportFormat::Jpeg {
+		ui.horizontal(|ui| {
+			changed |=
+				ui.add(DragValue::new(&mut settings.export_quality).range(1..=100)).changed();
+
+			ui.label("JPEG quality");
+		});
+	}
+
+	let previous_clipboard_format = settings.clipboard_format;
+
+	ComboBox::from_label("Copy as")
+		.selected_text(clipboard_format_label(settings.clipboard_format))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for format in [ClipboardFormat::Image, ClipboardFormat::File] {
+				ui.selectable_value(
+					&mut settings.clipboard_format,
+					format,
+					clipboard_format_label(format),
+				);
+			}
+		});
+
+	if settings.clipboard_format != previous_clipboard_format {
+		changed = true;
+	}
+
+	let scaling = &mut settings.export_scaling;
+	let previous_scale_mode = scaling.mode;
+
+	ComboBox::from_label("Export size")
+		.selected_text(export_scale_mode_label(scaling.mode))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for mode in
+				[ExportScaleMode::Native, ExportScaleMode::LogicalPoints, ExportScaleMode::MaxSide]
+			{
+				ui.selectable_value(&mut scaling.mode, mode, export_scale_mode_label(mode));
+			}
+		});
+
+	if scaling.mode != previous_scale_mode {
+		changed = true;
+	}
+	if scaling.mode == ExportScaleMode::MaxSide {
+		ui.horizontal(|ui| {
+			changed |= ui
+				.add(DragValue::new(&mut scaling.max_side_px).range(64..=16_384).suffix(" px"))
+				.changed();
+
+			ui.label("Longest side");
+		});
+	}
+
+	ui.small("Exports are downscaled with Lanczos resampling before encoding, never upscaled.");
+
+	changed |= ui
+		.checkbox(&mut settings.retina_pair, "Export @2x + @1x pair")
+		.on_hover_text(
+			"Save <name>@2x.png next to a half-size <name>.png; Copy places both files on the \
+			 clipboard.",
+		)
+		.changed();
+
+	ui.small(format!(
+		"Space/Copy -> clipboard. {}/Save -> write PNG to output directory.",
+		platform::save_shortcut_label()
+	));
+
+	changed |= ui
+		.checkbox(&mut settings.copy_export_summary, "Copy size summary")
+		.on_hover_text("Copy text like \"1920×1080 PNG, 412 KB\" for alt text and size notes.")
+		.changed();
+
+	ui.small(platform::export_summary_note());
+
+	changed
+}
+
+/// Renders the files and text written next to each export: JSON sidecars, the collage and alt
+/// text.
+fn render_sidecar_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let mut changed = false;
+
+	changed |= ui
+		.checkbox(&mut settings.geometry_sidecar, "Save geometry JSON")
+		.on_hover_text(
+			"Write <name>.json next to saved PNGs with the capture's image and screen rects.",
+		)
+		.changed();
+
+	changed |= ui
+		.checkbox(&mut settings.annotation_sidecar, "Save annotations JSON")
+		.on_hover_text(
+			"Write <name>.annotations.json next to saved PNGs with the highlights, stamps, and \
+			 callouts as editable data.",
+		)
+		.changed();
+
+	changed |= ui
+		.checkbox(&mut settings.multi_region_collage, "Save region collage")
+		.on_hover_text("With extra Shift-drag regions, also save one image combining every region.")
+		.changed();
+
+	changed |= ui
+		.checkbox(&mut settings.alt_text_enabled, "Suggest alt text")
+		.on_hover_text(
+			"Pipe each exported PNG to a captioning command and keep the alt text it prints.",
+		)
+		.changed();
+
+	if settings.alt_text_enabled {
+		ui.horizontal(|ui| {
+			let command_response = ui.add_sized(
+				egui::vec2(value_width, row_height),
+				TextEdit::singleline(&mut settings.alt_text_command)
+					.hint_text("caption-image --stdin"),
+			);
+
+			changed |= command_response.changed();
+
+			command_response.on_hover_text(
+				"Runs in a shell with the PNG on stdin; saved files also get RSNAP_IMAGE_PATH.",
+			);
+			ui.label("Caption command");
+		});
+		ui.small(platform::alt_text_note());
+		ui.small("The image leaves rsnap: remote endpoints receive the full capture.");
+	}
+
+	changed
+}
+
+fn render_external_command_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		let command_response = ui.add_sized(
+			egui::vec2(value_width, row_height),
+			TextEdit::singleline(&mut settings.redact_ocr_command)
+				.hint_text("tesseract stdin stdout tsv"),
+		);
+
+		changed |= command_response.changed();
+
+		command_response.on_hover_text(
+			"Runs in a shell with the selection PNG on stdin and must print Tesseract TSV. Empty \
+			 disables the R auto-redaction scan.",
+		);
+		ui.label("OCR command");
+	});
+	ui.small("R in Frozen mode proposes emails, IPs, and tokens to mosaic; click each to accept.");
+
+	ui.horizontal(|ui| {
+		let command_response = ui.add_sized(
+			egui::vec2(value_width, row_height),
+			TextEdit::singleline(&mut settings.open_with_command)
+				.hint_text(platform::open_with_command_hint()),
+		);
+
+		changed |= command_response.changed();
+
+		command_response.on_hover_text(
+			"Runs in a shell; {path} becomes the capture's file (appended when missing). Empty \
+			 disables Open with.",
+		);
+		ui.label("Open with");
+	});
+	ui.small(format!(
+		"{} opens the capture in this editor; add it to Done below to chain it.",
+		platform::open_with_shortcut_label()
+	));
+
+	changed
+}
+
+/// Edits the ordered steps run by the frozen toolbar's Done button and Space.
+fn render_done_actions_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let step_count = settings.done_actions.len();
+	let mut move_up = None;
+	let mut remove = None;
+
+	ui.label("Done runs");
+
+	for (index, action) in settings.done_actions.iter().enumerate() {
+		ui.horizontal(|ui| {
+			ui.label(format!("{}. {}", index + 1, action.label()));
+
+			if ui
+				.add_enabled(index > 0, egui::Button::new("↑"))
+				.on_hover_text("Run earlier")
+				.clicked()
+			{
+				move_up = Some(index);
+			}
+			if ui
+				.add_enabled(step_count > 1, egui::Button::new("✕"))
+				.on_hover_text("Remove step")
+				.clicked()
+			{
+				remove = Some(index);
+			}
+		});
+	}
+
+	let mut added = None;
+
+	ui.horizontal(|ui| {
+		for action in DoneAction::ALL {
+			if action.is_supported()
+				&& !settings.done_actions.contains(&action)
+				&& ui.button(format!("+ {}", action.label())).clicked()
+			{
+				added = Some(action);
+			}
+		}
+	});
+
+	if let Some(index) = move_up {
+		settings.done_actions.swap(index - 1, index);
+	}
+	if let Some(index) = remove {
+		settings.done_actions.remove(index);
+	}
+	if let Some(action) = added {
+		settings.done_actions.push(action);
+	}
+
+	ui.small("Space and the toolbar's Done button run these steps in order.");
+
+	move_up.is_some() || remove.is_some() || added.is_some()
+}
+
+/// Binds a frozen-capture quick action to each numeric keypad key.
+fn render_keypad_quick_action_settings(
+	combo_width: f32,
+	ui: &mut Ui,
+	settings: &mut AppSettings,
+) -> bool {
+	let mut actions: [KeypadQuickAction; KEYPAD_QUICK_ACTION_SLOTS] = std::array::from_fn(|slot| {
+		settings.keypad_quick_actions.get(slot).copied().unwrap_or_default()
+	});
+	let previous_actions = actions;
+
+	ui.label("Keypad quick actions");
+
+	for (slot, action) in actions.iter_mut().enumerate() {
+		ComboBox::from_label(format!("Keypad {}", slot + 1))
+			.selected_text(keypad_quick_action_label(*action))
+			.width(combo_width)
+			.show_ui(ui, |ui| {
+				for candidate in [
+					KeypadQuickAction::None,
+					KeypadQuickAction::Copy,
+					KeypadQuickAction::Save,
+					KeypadQuickAction::OpenWith,
+				] {
+					ui.selectable_value(action, candidate, keypad_quick_action_label(candidate));
+				}
+			});
+	}
+
+	ui.small("Numeric keypad keys run these actions in Frozen mode and close the overlay.");
+
+	if actions == previous_actions {
+		return false;
+	}
+
+	settings.keypad_quick_actions = actions.to_vec();
+
+	true
+}
+
+fn keypad_quick_action_label(action: KeypadQuickAction) -> &'static str {
+	match action {
+		KeypadQuickAction::None => "Unbound",
+		KeypadQuickAction::Copy => "Copy",
+		KeypadQuickAction::Save => "Save",
+		KeypadQuickAction::OpenWith => "Open with",
+	}
+}
+
+fn render_watermark_settings(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let row_height = ui.spacing().interact_size.y;
+	let value_width = ui.spacing().slider_width;
+	let watermark = &mut settings.watermark;
+	let mut changed = ui
+		.checkbox(&mut watermark.enabled, "Watermark exports")
+		.on_hover_text("Stamp text or a logo onto every export. The overlay never shows it.")
+		.changed();
+
+	if !watermark.enabled {
+		return changed;
+	}
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add_sized(
+				egui::vec2(value_width, row_height),
+				TextEdit::singleline(&mut watermark.text).hint_text("© Your Name"),
+			)
+			.changed();
+		changed |= ui.color_edit_button_srgb(&mut watermark.text_rgb).changed();
+
+		ui.label("Watermark text");
+	});
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add_sized(
+				egui::vec2(value_width, row_height),
+				TextEdit::singleline(&mut watermark.logo_path).hint_text("/path/to/logo.png"),
+			)
+			.on_hover_text("A PNG used instead of the text; empty uses the text.")
+			.changed();
+
+		ui.label("Watermark logo");
+	});
+
+	let previous_corner = watermark.corner;
+
+	ComboBox::from_label("Watermark corner")
+		.selected_text(watermark_corner_label(watermark.corner))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for corner in [
+				WatermarkCorner::TopLeft,
+				WatermarkCorner::TopRight,
+				WatermarkCorner::BottomLeft,
+				WatermarkCorner::BottomRight,
+			] {
+				ui.selectable_value(&mut watermark.corner, corner, watermark_corner_label(corner));
+			}
+		});
+
+	if watermark.corner != previous_corner {
+		changed = true;
+	}
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(DragValue::new(&mut watermark.opacity_percent).range(0..=100).suffix(" %"))
+			.changed();
+
+		ui.label("Opacity");
+		changed |=
+			ui.add(DragValue::new(&mut watermark.margin_px).range(0..=512).suffix(" px")).changed();
+
+		ui.label("Margin");
+		changed |= ui
+			.add(DragValue::new(&mut watermark.text_size_px).range(6..=256).suffix(" px"))
+			.changed();
+
+		ui.label("Text size");
+	});
+
+	changed
+}
+
+fn watermark_corner_label(corner: WatermarkCorner) -> &'static str {
+	match corner {
+		WatermarkCorner::TopLeft => "Top left",
+		WatermarkCorner::TopRight => "Top right",
+		WatermarkCorner::BottomLeft => "Bottom left",
+		WatermarkCorner::BottomRight => "Bottom right",
+	}
+}
+
+fn export_format_label(format: ExportFormat) -> &'static str {
+	match format {
+		ExportFormat::Png => "PNG",
+		ExportFormat::Jpeg => "JPEG",
+		ExportFormat::Webp => "WebP (lossless)",
+	}
+}
 
-		prefix_response.on_hover_text("Filename prefix used for saved captures.");
-		ui.label("Filename prefix");
-	});
+fn clipboard_format_label(format: ClipboardFormat) -> &'static str {
+	match format {
+		ClipboardFormat::Image => "Image",
+		ClipboardFormat::File => "File (export format)",
+	}
+}
 
-	let previous_naming = settings.output_naming;
+fn export_scale_mode_label(mode: ExportScaleMode) -> &'static str {
+	match mode {
+		ExportScaleMode::Native => "Native pixels",
+		ExportScaleMode::LogicalPoints => "Logical points (1x)",
+		ExportScaleMode::MaxSide => "Cap longest side",
+	}
+}
 
-	ComboBox::from_label("Filename naming")
-		.selected_text(match settings.output_naming {
-			OutputNaming::Timestamp => "Timestamp (unix ms)",
-			OutputNaming::Sequence => "Sequence (0001)",
+fn render_backdrop_settings(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let backdrop = &mut settings.export_backdrop;
+	let previous_fill = backdrop.fill;
+	let mut changed = false;
+
+	ComboBox::from_label("Backdrop fill")
+		.selected_text(match backdrop.fill {
+			BackdropFill::Solid => "Solid color",
+			BackdropFill::Gradient => "Gradient",
+			BackdropFill::Blur => "Desktop blur",
 		})
 		.width(combo_width)
 		.show_ui(ui, |ui| {
-			ui.selectable_value(
-				&mut settings.output_naming,
-				OutputNaming::Timestamp,
-				"Timestamp (unix ms)",
-			);
-			ui.selectable_value(
-				&mut settings.output_naming,
-				OutputNaming::Sequence,
-				"Sequence (0001)",
-			);
+			ui.selectable_value(&mut backdrop.fill, BackdropFill::Solid, "Solid color");
+			ui.selectable_value(&mut backdrop.fill, BackdropFill::Gradient, "Gradient");
+			ui.selectable_value(&mut backdrop.fill, BackdropFill::Blur, "Desktop blur");
 		});
 
-	if settings.output_naming != previous_naming {
+	if backdrop.fill != previous_fill {
 		changed = true;
 	}
 
-	ui.small(format!(
-		"Space/Copy -> clipboard. {}/Save -> write PNG to output directory.",
-		platform::save_shortcut_label()
-	));
+	ui.horizontal(|ui| {
+		changed |= ui.color_edit_button_srgb(&mut backdrop.primary_rgb).changed();
+
+		if backdrop.fill == BackdropFill::Gradient {
+			changed |= ui.color_edit_button_srgb(&mut backdrop.secondary_rgb).changed();
+		}
+
+		ui.label("Backdrop colors");
+	});
+	ui.horizontal(|ui| {
+		changed |=
+			ui.add(DragValue::new(&mut backdrop.padding_px).range(0..=256).suffix(" px")).changed();
+
+		ui.label("Padding");
+		changed |= ui
+			.add(DragValue::new(&mut backdrop.corner_radius_px).range(0..=64).suffix(" px"))
+			.changed();
+
+		ui.label("Corner radius");
+	});
+
+	changed |= ui.checkbox(&mut backdrop.shadow, "Backdrop shadow").changed();
+	changed |= ui
+		.checkbox(&mut backdrop.enabled_by_default, "Backdrop on by default")
+		.on_hover_text("The frozen toolbar's backdrop button toggles it for each capture.")
+		.changed();
+
+	ui.small(
+		"Desktop blur uses the screen around the selection; scroll captures use the gradient.",
+	);
 
 	changed
 }
@@ -293,7 +1445,17 @@ fn render_general_section(
 	_ctx: &Context,
 	settings: &mut AppSettings,
 ) -> bool {
-	let mut changed = false;
+	let mut changed = render_language_picker(combo_width, ui, settings);
+
+	render_launch_at_login(ui);
+
+	changed |= ui
+		.checkbox(&mut settings.check_for_updates, "Check for updates")
+		.on_hover_text(
+			"Ask GitHub once a day whether a newer rsnap is out. The tray menu shows it and its \
+			 release notes.",
+		)
+		.changed();
 
 	#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 	enum LogLevelPreset {
@@ -365,29 +1527,231 @@ fn render_general_section(
 		}
 	}
 
-	ui.small("Log level changes require restarting rsnap.");
+	changed |= ui
+		.checkbox(&mut settings.log_json, "JSON log file")
+		.on_hover_text("Write one JSON object per line, including span timings, for bug reports.")
+		.changed();
+
+	ui.small("Log level and format changes require restarting rsnap.");
+
+	render_log_viewer(ui);
 
 	changed
 }
 
+fn render_language_picker(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let previous = settings.language;
+	let system_label =
+		tr_args("settings.language_system", &[("language", &detect_language().native_name())]);
+
+	let selected = match settings.language {
+		Some(language) => language.native_name(),
+		None => system_label.as_str(),
+	};
+
+	ComboBox::from_label(tr("settings.language"))
+		.selected_text(selected)
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(&mut settings.language, None, system_label.as_str());
+
+			for language in Language::ALL {
+				ui.selectable_value(&mut settings.language, Some(language), language.native_name());
+			}
+		})
+		.response
+		.on_hover_text(tr("settings.language_note"));
+
+	settings.language != previous
+}
+
+/// Toggles the OS login item directly; the state is read from the OS once per window rather
+/// than stored in `settings.toml`, so entries changed in System Settings or Task Manager show up.
+fn render_launch_at_login(ui: &mut Ui) {
+	let state_id = egui::Id::new("rsnap-launch-at-login");
+	let error_id = state_id.with("error");
+	let mut enabled = ui
+		.data_mut(|data| data.get_temp::<bool>(state_id))
+		.unwrap_or_else(launch_at_login::is_enabled);
+
+	ui.data_mut(|data| data.insert_temp(state_id, enabled));
+
+	if ui
+		.checkbox(&mut enabled, "Launch at login")
+		.on_hover_text("Start rsnap in the menu bar or tray when you log in.")
+		.changed()
+	{
+		match launch_at_login::set_enabled(enabled) {
+			Ok(()) => ui.data_mut(|data| {
+				data.insert_temp(state_id, enabled);
+				data.remove::<String>(error_id);
+			}),
+			Err(err) => {
+				ui.data_mut(|data| data.insert_temp(error_id, format!("{err:#}")));
+			},
+		}
+	}
+
+	if let Some(error) = ui.data(|data| data.get_temp::<String>(error_id)) {
+		ui.small(error);
+	}
+}
+
+/// Shows the tail of the newest log file so it can be copied into a bug report.
+fn render_log_viewer(ui: &mut Ui) {
+	let log_id = egui::Id::new("rsnap-log-viewer");
+
+	ui.horizontal(|ui| {
+		if ui.button("Show recent log").clicked() {
+			let log = startup::recent_log_tail(LOG_VIEWER_MAX_LINES).map_or_else(
+				|| String::from("No log file found."),
+				|(path, tail)| format!("{}\n\n{tail}", path.display()),
+			);
+
+			ui.data_mut(|data| data.insert_temp(log_id, log));
+		}
+		if ui.data(|data| data.get_temp::<String>(log_id)).is_some() && ui.button("Hide").clicked()
+		{
+			ui.data_mut(|data| data.remove::<String>(log_id));
+		}
+	});
+
+	if let Some(mut log) = ui.data(|data| data.get_temp::<String>(log_id)) {
+		egui::ScrollArea::vertical().max_height(220.0).stick_to_bottom(true).show(ui, |ui| {
+			ui.add(
+				TextEdit::multiline(&mut log)
+					.code_editor()
+					.interactive(false)
+					.desired_width(f32::INFINITY),
+			);
+		});
+
+		if ui.button("Copy log").clicked() {
+			ui.ctx().copy_text(log);
+		}
+	}
+}
+
 fn render_overlay_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSettings) -> bool {
 	let mut changed = false;
 
 	changed |= ui.checkbox(&mut settings.show_alt_hint_keycap, "Show Alt hint in HUD").changed();
 	changed |= ui.checkbox(&mut settings.hud_glass_enabled, "Glass HUD").changed();
 	changed |= ui.checkbox(&mut settings.selection_particles, "Selection particles").changed();
+	changed |= ui
+		.checkbox(&mut settings.histogram_panel, "Selection histogram")
+		.on_hover_text("Show RGB and luminance histograms of the frozen selection. G collapses it.")
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.export_preview, "Toolbar export preview")
+		.on_hover_text("Show a thumbnail of the pending Copy/Save result in the frozen toolbar.")
+		.changed();
+	changed |= ui
+		.checkbox(&mut settings.reduce_motion, "Reduce motion")
+		.on_hover_text(
+			"Skip the HUD and toolbar entry animations and drag the toolbar without easing.",
+		)
+		.changed();
+	changed |= render_selection_border_settings(ui, settings);
+
+	ui.horizontal(|ui| {
+		changed |= ui.color_edit_button_srgb(&mut settings.highlighter_rgb).changed();
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.highlighter_width_px)
+					.range(4.0..=96.0)
+					.speed(0.5)
+					.suffix(" pt"),
+			)
+			.on_hover_text("Ink and stroke width of the frozen toolbar's highlighter.")
+			.changed();
+
+		ui.label("Highlighter");
+	});
+
+	ui.add_space(SETTINGS_SECTION_GAP);
+	ui.separator();
+	ui.add_space(SETTINGS_SECTION_GAP);
+
+	changed |= render_overlay_behavior_settings(combo_width, ui, settings);
+
+	let enabled = settings.hud_glass_enabled;
+
+	changed |= overlay_slider_row(ui, "Opacity", &mut settings.hud_opacity, enabled);
+	changed |= overlay_slider_row(ui, "Blur", &mut settings.hud_blur, enabled);
+	changed |= overlay_slider_row(ui, "Tint", &mut settings.hud_tint, enabled);
+	changed |= overlay_hue_slider_row(ui, "Hue", &mut settings.hud_tint_hue, enabled);
+	changed |= ui
+		.add_enabled(enabled, egui::Checkbox::new(&mut settings.hud_blur_dither, "Dither blur"))
+		.on_hover_text("Add fine noise to the HUD blur so smooth gradients do not band.")
+		.changed();
+
+	changed
+}
+
+/// Renders the selection border's flow, snapping and color, with a live preview.
+fn render_selection_border_settings(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
 	changed |= overlay_range_slider_row(
 		ui,
 		"Flow thickness",
 		&mut settings.selection_flow_stroke_width_px,
 		settings.selection_particles,
 	);
+	changed |= render_composition_guide_settings(ui, settings);
 
-	ui.add_space(SETTINGS_SECTION_GAP);
-	ui.separator();
-	ui.add_space(SETTINGS_SECTION_GAP);
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.selection_snap_threshold_px)
+					.range(0..=64)
+					.suffix(" pt"),
+			)
+			.on_hover_text(
+				"Drag selection edges this close to a window edge snap onto it. 0 turns it off. \
+				 Hold Cmd (macOS) or Ctrl to drag freely.",
+			)
+			.changed();
+
+		ui.label("Snap to windows");
+	});
+	changed |= ui
+		.add_enabled(
+			settings.selection_particles,
+			egui::Checkbox::new(&mut settings.selection_flow_animated, "Animate selection border"),
+		)
+		.on_hover_text("Off draws the selection border as a still outline.")
+		.changed();
+
+	let mut single_color = settings.selection_flow_rgb.is_some();
+
+	ui.add_enabled_ui(settings.selection_particles, |ui| {
+		ui.horizontal(|ui| {
+			if ui.checkbox(&mut single_color, "Single border color").changed() {
+				settings.selection_flow_rgb =
+					single_color.then(|| rsnap_overlay::selection_flow_palette_rgb(0.0));
+				changed = true;
+			}
+			if let Some(rgb) = settings.selection_flow_rgb.as_mut() {
+				changed |= ui.color_edit_button_srgb(rgb).changed();
+			}
+		});
+	});
+
+	render_selection_style_preview(ui, settings);
+
+	changed
+}
 
+/// Renders how the overlay responds: Alt activation, loupe, toolbar, HUD units and magnifier.
+fn render_overlay_behavior_settings(
+	combo_width: f32,
+	ui: &mut Ui,
+	settings: &mut AppSettings,
+) -> bool {
 	let before_alt = settings.alt_activation;
+	let mut changed = false;
 
 	ComboBox::from_label("Alt activation")
 		.selected_text(alt_activation_label(settings.alt_activation))
@@ -428,6 +1792,11 @@ fn render_overlay_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSetti
 		changed = true;
 	}
 
+	changed |= ui
+		.checkbox(&mut settings.loupe_row_column_highlight, "Highlight loupe row and column")
+		.on_hover_text("At high loupe zoom, tint the row and column of the sampled pixel.")
+		.changed();
+
 	let before_toolbar_placement = settings.toolbar_placement;
 
 	ComboBox::from_label("Toolbar placement")
@@ -446,16 +1815,293 @@ fn render_overlay_section(combo_width: f32, ui: &mut Ui, settings: &mut AppSetti
 		changed = true;
 	}
 
-	let enabled = settings.hud_glass_enabled;
+	let before_hud_unit = settings.hud_unit;
 
-	changed |= overlay_slider_row(ui, "Opacity", &mut settings.hud_opacity, enabled);
-	changed |= overlay_slider_row(ui, "Blur", &mut settings.hud_blur, enabled);
-	changed |= overlay_slider_row(ui, "Tint", &mut settings.hud_tint, enabled);
-	changed |= overlay_hue_slider_row(ui, "Hue", &mut settings.hud_tint_hue, enabled);
+	ComboBox::from_label("HUD units")
+		.selected_text(hud_unit_label(settings.hud_unit))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(&mut settings.hud_unit, HudUnit::Points, "Points (pt)");
+			ui.selectable_value(&mut settings.hud_unit, HudUnit::Pixels, "Pixels (px)");
+			ui.selectable_value(&mut settings.hud_unit, HudUnit::Millimeters, "Millimeters (mm)");
+		})
+		.response
+		.on_hover_text("Press U during capture to cycle units.");
+
+	if settings.hud_unit != before_hud_unit {
+		changed = true;
+	}
+
+	let before_magnifier_zoom = settings.magnifier_zoom;
+
+	ComboBox::from_label("Magnifier zoom")
+		.selected_text(format!("{}×", settings.magnifier_zoom))
+		.width(combo_width)
+		.show_ui(ui, |ui| {
+			for zoom in 2..=8 {
+				ui.selectable_value(&mut settings.magnifier_zoom, zoom, format!("{zoom}×"));
+			}
+		})
+		.response
+		.on_hover_text("Toggle the magnifier with its hotkey; press +/- to change zoom.");
+
+	if settings.magnifier_zoom != before_magnifier_zoom {
+		changed = true;
+	}
+
+	changed
+}
+
+fn render_advanced_section(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let mut changed = false;
+
+	ui.label("Frozen dim layer").on_hover_text(
+		"Styles the frozen screen outside the selection. All at 0% keep the plain capture.",
+	);
+
+	changed |= overlay_slider_row(ui, "Dim", &mut settings.frozen_dim_opacity, true);
+	changed |= overlay_slider_row(ui, "Vignette", &mut settings.frozen_dim_vignette, true);
+	changed |= overlay_slider_row(ui, "Noise", &mut settings.frozen_dim_noise, true);
+
+	render_selection_style_preview(ui, settings);
+
+	let previous_backend = settings.capture_backend;
+
+	ComboBox::from_label("Capture backend")
+		.selected_text(capture_backend_label(settings.capture_backend))
+		.show_ui(ui, |ui| {
+			for kind in CaptureBackendKind::ALL {
+				if kind.unsupported_reason().is_none() {
+					ui.selectable_value(
+						&mut settings.capture_backend,
+						kind,
+						capture_backend_label(kind),
+					);
+				}
+			}
+		});
+
+	if settings.capture_backend != previous_backend {
+		changed = true;
+	}
+
+	ui.small("Applies to the next capture. Run `rsnap doctor` to see which backends work here.");
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.image_cache_budget_mb).range(0..=16_384).suffix(" MB"),
+			)
+			.changed();
+
+		ui.label("Image cache budget").on_hover_text(
+			"Screen images the overlay keeps for the HUD blur and the frozen capture. Over the \
+			 budget, the blur background is downsampled. 0 disables the budget.",
+		);
+	});
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(DragValue::new(&mut settings.sampling_fps_cap).range(15..=120).suffix(" fps"))
+			.changed();
+
+		ui.label("Sampling rate cap").on_hover_text(
+			"Limits how often the live overlay repaints, polls the cursor and samples colors. \
+			 Lower values save power on high-refresh displays.",
+		);
+	});
+
+	let previous_power = settings.gpu_power_preference;
+
+	ComboBox::from_label("GPU")
+		.selected_text(gpu_power_preference_label(settings.gpu_power_preference))
+		.show_ui(ui, |ui| {
+			for preference in [GpuPowerPreference::LowPower, GpuPowerPreference::HighPerformance] {
+				ui.selectable_value(
+					&mut settings.gpu_power_preference,
+					preference,
+					gpu_power_preference_label(preference),
+				);
+			}
+		});
+
+	if settings.gpu_power_preference != previous_power {
+		changed = true;
+	}
+
+	ui.small("A GPU change applies the next time the overlay opens its GPU device.");
+
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(DragValue::new(&mut settings.window_list_ttl_ms).range(30..=2_000).suffix(" ms"))
+			.changed();
+
+		ui.label("Window list cache").on_hover_text(
+			"How long the list of windows used for hover highlighting is reused before it is \
+			 refreshed.",
+		);
+	});
+	ui.horizontal(|ui| {
+		changed |= ui
+			.add(
+				DragValue::new(&mut settings.live_background_ttl_ms)
+					.range(100..=5_000)
+					.suffix(" ms"),
+			)
+			.changed();
+
+		ui.label("Blur background cache").on_hover_text(
+			"How long a screen image behind the HUD blur is reused before it is recaptured.",
+		);
+	});
+
+	if ui.button("Reset to defaults").on_hover_text("Reset the settings in this section.").clicked()
+	{
+		settings.reset_advanced();
+
+		changed = true;
+	}
+
+	changed |= render_settings_transfer(ui, settings);
+
+	changed
+}
+
+/// Export and import buttons for syncing every setting through a JSON file, with the outcome of
+/// the last attempt underneath.
+fn render_settings_transfer(ui: &mut Ui, settings: &mut AppSettings) -> bool {
+	let report_id = egui::Id::new("rsnap-settings-transfer");
+	let mut changed = false;
+
+	ui.horizontal(|ui| {
+		if ui
+			.button("Export settings…")
+			.on_hover_text("Write every setting to a JSON file to use on another machine.")
+			.clicked()
+			&& let Some(path) = platform::choose_settings_file(true)
+		{
+			let report = match settings.export_json(&path) {
+				Ok(()) => format!("Exported settings to {}.", path.display()),
+				Err(err) => format!("Export failed: {err}"),
+			};
+
+			ui.data_mut(|data| data.insert_temp(report_id, report));
+		}
+		if ui
+			.button("Import settings…")
+			.on_hover_text(
+				"Merge a JSON file written by Export settings. Missing keys keep their value; \
+				 invalid ones are skipped and listed below.",
+			)
+			.clicked()
+			&& let Some(path) = platform::choose_settings_file(false)
+		{
+			let report = match settings.import_json(&path) {
+				Ok(import) => {
+					let summary = import.summary();
+
+					*settings = import.settings;
+					changed = true;
+
+					format!("{summary}\nImported hotkeys apply after restarting rsnap.")
+				},
+				Err(err) => format!("Import failed: {err}"),
+			};
+
+			ui.data_mut(|data| data.insert_temp(report_id, report));
+		}
+	});
+
+	if let Some(report) = ui.data(|data| data.get_temp::<String>(report_id)) {
+		ui.small(report);
+	}
 
 	changed
 }
 
+fn gpu_power_preference_label(preference: GpuPowerPreference) -> &'static str {
+	match preference {
+		GpuPowerPreference::LowPower => "Integrated (low power)",
+		GpuPowerPreference::HighPerformance => "Discrete (high performance)",
+	}
+}
+
+/// Paints a small frozen-mode mock-up: the dim layer around a selection and its border, so the
+/// overlay styling can be judged without starting a capture.
+fn render_selection_style_preview(ui: &mut Ui, settings: &AppSettings) {
+	const PREVIEW_SAMPLES: usize = 96;
+
+	let (rect, _) =
+		ui.allocate_exact_size(egui::vec2(ui.available_width().min(240.0), 72.0), Sense::hover());
+	let painter = ui.painter_at(rect);
+	let selection = rect.shrink2(egui::vec2(rect.width() * 0.25, rect.height() * 0.22));
+	// The vignette's base shade, without its falloff towards the corners.
+	let dim = (settings.frozen_dim_opacity + settings.frozen_dim_vignette * 0.3).clamp(0.0, 1.0);
+	let dim_color = egui::Color32::from_black_alpha((dim * 255.0).round() as u8);
+
+	painter.rect_filled(rect, 4.0, egui::Color32::from_gray(150));
+
+	for band in [
+		Rect::from_min_max(rect.min, Pos2::new(rect.max.x, selection.min.y)),
+		Rect::from_min_max(Pos2::new(rect.min.x, selection.max.y), rect.max),
+		Rect::from_min_max(
+			Pos2::new(rect.min.x, selection.min.y),
+			Pos2::new(selection.min.x, selection.max.y),
+		),
+		Rect::from_min_max(
+			Pos2::new(selection.max.x, selection.min.y),
+			Pos2::new(rect.max.x, selection.max.y),
+		),
+	] {
+		painter.rect_filled(band, 0.0, dim_color);
+	}
+
+	if !settings.selection_particles {
+		return;
+	}
+
+	let width = settings.selection_flow_stroke_width_px.clamp(1.0, 8.0);
+	let phase = if settings.selection_flow_animated {
+		ui.ctx().request_repaint();
+
+		ui.input(|input| input.time) as f32 * 0.24
+	} else {
+		0.0
+	};
+	let perimeter = 2.0 * (selection.width() + selection.height());
+	let point_at = |progress: f32| {
+		let mut distance = progress * perimeter;
+
+		for (start, end) in [
+			(selection.left_top(), selection.right_top()),
+			(selection.right_top(), selection.right_bottom()),
+			(selection.right_bottom(), selection.left_bottom()),
+		] {
+			let length = start.distance(end);
+
+			if distance <= length {
+				return start + (end - start) * (distance / length);
+			}
+
+			distance -= length;
+		}
+
+		selection.left_bottom()
+			+ (selection.left_top() - selection.left_bottom()) * (distance / selection.height())
+	};
+
+	for index in 0..PREVIEW_SAMPLES {
+		let from = index as f32 / PREVIEW_SAMPLES as f32;
+		let to = (index + 1) as f32 / PREVIEW_SAMPLES as f32;
+		let [r, g, b] = settings
+			.selection_flow_rgb
+			.unwrap_or_else(|| rsnap_overlay::selection_flow_palette_rgb(from + phase));
+
+		painter.line_segment(
+			[point_at(from), point_at(to)],
+			Stroke::new(width, egui::Color32::from_rgb(r, g, b)),
+		);
+	}
+}
+
 fn overlay_slider_row(ui: &mut Ui, label: &str, amount: &mut f32, enabled: bool) -> bool {
 	let mut changed = false;
 	let mut value = (*amount).clamp(0.0, 1.0);
@@ -705,6 +2351,27 @@ fn hue_to_rgb(p: f32, q: f32, hue: f32) -> f32 {
 	p
 }
 
+fn capture_mode_label(mode: CaptureMode) -> &'static str {
+	match mode {
+		CaptureMode::Region => "Region or window",
+		CaptureMode::Window => "Window",
+		CaptureMode::Display => "Whole display",
+		CaptureMode::ColorPick => "Pick color",
+	}
+}
+
+fn capture_backend_label(kind: CaptureBackendKind) -> &'static str {
+	match kind {
+		CaptureBackendKind::Auto => "Automatic",
+		CaptureBackendKind::Sck => "ScreenCaptureKit",
+		CaptureBackendKind::Wgc => "Windows.Graphics.Capture",
+		CaptureBackendKind::Pipewire => "PipeWire",
+		CaptureBackendKind::X11 => "X11",
+		CaptureBackendKind::Xcap => "xcap",
+		CaptureBackendKind::Stub => "Stub (no capture)",
+	}
+}
+
 fn alt_activation_label(mode: AltActivationMode) -> &'static str {
 	match mode {
 		AltActivationMode::Hold => "Hold",
@@ -720,6 +2387,14 @@ fn loupe_sample_size_label(size: LoupeSampleSize) -> &'static str {
 	}
 }
 
+fn hud_unit_label(unit: HudUnit) -> &'static str {
+	match unit {
+		HudUnit::Pixels => "Pixels (px)",
+		HudUnit::Points => "Points (pt)",
+		HudUnit::Millimeters => "Millimeters (mm)",
+	}
+}
+
 fn toolbar_placement_label(placement: ToolbarPlacement) -> &'static str {
 	match placement {
 		ToolbarPlacement::Top => "Top",
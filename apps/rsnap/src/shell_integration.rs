@@ -0,0 +1,147 @@
+//! Windows Explorer integration: a "Capture region to this folder" entry in folder context
+//! menus that runs `rsnap capture-to <folder>`.
+//!
+//! The entry is registered per user under `HKCU\Software\Classes` through `reg.exe`, so
+//! installing it needs no elevation and uninstalling it leaves nothing behind.
+
+use std::path::Path;
+
+use color_eyre::eyre::{self, Result};
+
+/// Verb key shared by every context menu rsnap adds to.
+const VERB_KEY: &str = "rsnap.CaptureToFolder";
+const MENU_TEXT: &str = "Capture region to this folder";
+/// Context menus of a folder itself and of the empty space inside an open folder.
+const PARENT_KEYS: [&str; 2] =
+	[r"HKCU\Software\Classes\Directory\shell", r"HKCU\Software\Classes\Directory\Background\shell"];
+
+/// One registry value to write, with `name == None` for a key's default value.
+#[derive(Debug, PartialEq)]
+struct RegistryValue {
+	key: String,
+	name: Option<&'static str>,
+	data: String,
+}
+
+/// Adds the context-menu entry for `exe`, replacing an earlier registration.
+pub(crate) fn install(exe: &Path) -> Result<()> {
+	ensure_supported()?;
+
+	for value in registry_values(exe) {
+		let mut args = vec![String::from("add"), value.key];
+
+		match value.name {
+			Some(name) => args.extend([String::from("/v"), name.to_owned()]),
+			None => args.push(String::from("/ve")),
+		}
+
+		args.extend([String::from("/d"), value.data, String::from("/f")]);
+		run_reg(&args)?;
+	}
+
+	Ok(())
+}
+
+/// Removes the context-menu entry, returning whether one was registered.
+pub(crate) fn uninstall() -> Result<bool> {
+	ensure_supported()?;
+
+	let mut removed = false;
+
+	for key in verb_keys() {
+		if run_reg(&[String::from("query"), key.clone()]).is_err() {
+			continue;
+		}
+
+		run_reg(&[String::from("delete"), key, String::from("/f")])?;
+
+		removed = true;
+	}
+
+	Ok(removed)
+}
+
+fn ensure_supported() -> Result<()> {
+	if cfg!(target_os = "windows") {
+		Ok(())
+	} else {
+		Err(eyre::eyre!("Explorer integration is only available on Windows."))
+	}
+}
+
+fn verb_keys() -> impl Iterator<Item = String> {
+	PARENT_KEYS.into_iter().map(|parent| format!(r"{parent}\{VERB_KEY}"))
+}
+
+/// Every value the entry needs, in the order they must be written.
+///
+/// `%V` expands to the clicked folder, or to the open folder for a background click. A drive root
+/// such as `C:\` arrives as `C:"`, which `capture-to` turns back into the root.
+fn registry_values(exe: &Path) -> Vec<RegistryValue> {
+	let exe = exe.display().to_string();
+
+	verb_keys()
+		.flat_map(|key| {
+			[
+				RegistryValue { key: key.clone(), name: None, data: MENU_TEXT.to_owned() },
+				RegistryValue { key: key.clone(), name: Some("Icon"), data: exe.clone() },
+				RegistryValue {
+					key: format!(r"{key}\command"),
+					name: None,
+					data: format!(r#""{exe}" capture-to "%V""#),
+				},
+			]
+		})
+		.collect()
+}
+
+fn run_reg(args: &[String]) -> Result<()> {
+	let output = std::process::Command::new("reg")
+		.args(args)
+		.output()
+		.map_err(|err| eyre::eyre!("Failed to run reg.exe: {err}"))?;
+
+	if output.status.success() {
+		return Ok(());
+	}
+
+	Err(eyre::eyre!(
+		"reg {} failed: {}",
+		args.first().map_or("", String::as_str),
+		String::from_utf8_lossy(&output.stderr).trim()
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use crate::shell_integration::{self, RegistryValue};
+
+	#[test]
+	fn folder_and_background_menus_run_capture_to_with_the_folder() {
+		let values = shell_integration::registry_values(Path::new(r"C:\Tools\rsnap.exe"));
+		let commands = values
+			.iter()
+			.filter(|value| value.key.ends_with(r"\command"))
+			.map(|value| (value.key.as_str(), value.data.as_str()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(values.len(), 6);
+		assert_eq!(
+			values[0],
+			RegistryValue {
+				key: String::from(r"HKCU\Software\Classes\Directory\shell\rsnap.CaptureToFolder"),
+				name: None,
+				data: String::from("Capture region to this folder"),
+			}
+		);
+		assert_eq!(commands.len(), 2);
+		assert!(commands[0].0.starts_with(r"HKCU\Software\Classes\Directory\shell\"));
+		assert!(commands[1].0.starts_with(r"HKCU\Software\Classes\Directory\Background\shell\"));
+
+		for (_, command) in commands {
+			assert_eq!(command, r#""C:\Tools\rsnap.exe" capture-to "%V""#);
+		}
+	}
+}
@@ -5,9 +5,12 @@ use directories::ProjectDirs;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 use crate::settings::AppSettings;
 
+const LOG_FILE_PREFIX: &str = "rsnap";
+
 /// Build metadata logged during application startup.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StartupBuildInfo {
@@ -25,7 +28,8 @@ pub fn startup_build_info() -> StartupBuildInfo {
 }
 
 pub fn init_logging() -> Option<WorkerGuard> {
-	let filter = default_log_filter();
+	let settings = AppSettings::load();
+	let filter = default_log_filter(&settings);
 	let Some(log_dir) = resolve_log_dir() else {
 		init_console_logging(filter);
 
@@ -42,8 +46,8 @@ pub fn init_logging() -> Option<WorkerGuard> {
 
 	let appender = match RollingFileAppender::builder()
 		.rotation(Rotation::DAILY)
-		.filename_prefix("rsnap")
-		.filename_suffix("log")
+		.filename_prefix(LOG_FILE_PREFIX)
+		.filename_suffix(if settings.log_json { "jsonl" } else { "log" })
 		.max_log_files(15)
 		.build(&log_dir)
 	{
@@ -58,9 +62,28 @@ pub fn init_logging() -> Option<WorkerGuard> {
 	};
 	let (writer, guard) = tracing_appender::non_blocking(appender);
 
-	tracing_subscriber::fmt().with_writer(writer).with_env_filter(filter).with_ansi(false).init();
+	// Closing spans log their busy/idle time, which is how capture, sample and encode are timed.
+	if settings.log_json {
+		tracing_subscriber::fmt()
+			.json()
+			.with_span_events(FmtSpan::CLOSE)
+			.with_writer(writer)
+			.with_env_filter(filter)
+			.init();
+	} else {
+		tracing_subscriber::fmt()
+			.with_span_events(FmtSpan::CLOSE)
+			.with_writer(writer)
+			.with_env_filter(filter)
+			.with_ansi(false)
+			.init();
+	}
 
-	tracing::info!(log_dir = %log_dir.display(), "File logging initialized.");
+	tracing::info!(
+		log_dir = %log_dir.display(),
+		json = settings.log_json,
+		"File logging initialized."
+	);
 
 	Some(guard)
 }
@@ -69,9 +92,34 @@ fn init_console_logging(filter: EnvFilter) {
 	tracing_subscriber::fmt().with_env_filter(filter).init();
 }
 
-fn default_log_filter() -> EnvFilter {
+/// Returns the newest log file and its last `max_lines` lines, for the settings log viewer.
+pub(crate) fn recent_log_tail(max_lines: usize) -> Option<(PathBuf, String)> {
+	let log_dir = resolve_log_dir()?;
+	let newest = fs::read_dir(&log_dir)
+		.ok()?
+		.filter_map(Result::ok)
+		.filter(|entry| entry.file_name().to_string_lossy().starts_with(LOG_FILE_PREFIX))
+		.filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+		.max_by_key(|(modified, _)| *modified)?
+		.1;
+	let contents = fs::read_to_string(&newest).ok()?;
+
+	Some((newest, tail_lines(&contents, max_lines).to_owned()))
+}
+
+fn tail_lines(contents: &str, max_lines: usize) -> &str {
+	let trimmed = contents.trim_end_matches('\n');
+	let start = trimmed
+		.rmatch_indices('\n')
+		.nth(max_lines.saturating_sub(1))
+		.map_or(0, |(index, _)| index + 1);
+
+	if max_lines == 0 { "" } else { &trimmed[start..] }
+}
+
+fn default_log_filter(settings: &AppSettings) -> EnvFilter {
 	EnvFilter::try_from_default_env()
-		.or_else(|_| load_log_filter_from_settings().ok_or(()))
+		.or_else(|_| log_filter_from_settings(settings).ok_or(()))
 		.unwrap_or_else(|_| EnvFilter::new("warn,rsnap=info"))
 }
 
@@ -79,8 +127,7 @@ fn resolve_log_dir() -> Option<PathBuf> {
 	ProjectDirs::from("ink", "hack", "rsnap").map(|dirs| dirs.data_dir().join("logs"))
 }
 
-fn load_log_filter_from_settings() -> Option<EnvFilter> {
-	let settings = AppSettings::load();
+fn log_filter_from_settings(settings: &AppSettings) -> Option<EnvFilter> {
 	let filter = settings.log_filter.as_deref()?.trim();
 
 	if filter.is_empty() {
@@ -108,4 +155,12 @@ mod tests {
 		assert!(!info.version.is_empty());
 		assert!(!info.git_commit.is_empty());
 	}
+
+	#[test]
+	fn tail_lines_keeps_only_the_last_lines() {
+		assert_eq!(startup::tail_lines("a\nb\nc\n", 2), "b\nc");
+		assert_eq!(startup::tail_lines("a\nb", 5), "a\nb");
+		assert_eq!(startup::tail_lines("a\nb", 0), "");
+		assert_eq!(startup::tail_lines("", 3), "");
+	}
 }
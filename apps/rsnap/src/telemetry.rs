@@ -0,0 +1,193 @@
+//! Opt-in, local-only usage counters.
+//!
+//! Compiled only with the `telemetry` feature and recorded only while
+//! `AppSettings::telemetry_enabled` is on. Counters are plain totals (which overlay modes start,
+//! which exports finish, which error categories occur) kept in `usage.toml` next to
+//! `settings.toml`. Nothing is transmitted: the settings window shows the exact report so it can
+//! be reviewed and shared by hand.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+/// A single countable usage fact. Events carry categories only, never capture content or paths.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum UsageEvent {
+	/// An overlay session of the given mode started.
+	Mode(&'static str),
+	/// A session finished with the given export kind.
+	Export(&'static str),
+	/// A session failed with an error of the given category.
+	Error(ErrorCategory),
+}
+impl UsageEvent {
+	fn key(self) -> String {
+		match self {
+			Self::Mode(mode) => format!("mode.{mode}"),
+			Self::Export(kind) => format!("export.{kind}"),
+			Self::Error(category) => format!("error.{}", category.as_str()),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ErrorCategory {
+	Permission,
+	Gpu,
+	Clipboard,
+	Io,
+	Other,
+}
+impl ErrorCategory {
+	/// Buckets a free-form error message so only the category is ever stored.
+	pub(crate) fn classify(message: &str) -> Self {
+		let message = message.to_ascii_lowercase();
+
+		if message.contains("permission") || message.contains("not authorized") {
+			Self::Permission
+		} else if message.contains("gpu") || message.contains("wgpu") || message.contains("adapter")
+		{
+			Self::Gpu
+		} else if message.contains("clipboard") {
+			Self::Clipboard
+		} else if message.contains("file") || message.contains("directory") {
+			Self::Io
+		} else {
+			Self::Other
+		}
+	}
+
+	const fn as_str(self) -> &'static str {
+		match self {
+			Self::Permission => "permission",
+			Self::Gpu => "gpu",
+			Self::Clipboard => "clipboard",
+			Self::Io => "io",
+			Self::Other => "other",
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub(crate) struct UsageLog {
+	#[serde(default)]
+	pub since_unix_secs: u64,
+	#[serde(default)]
+	pub counters: BTreeMap<String, u64>,
+}
+impl UsageLog {
+	#[must_use]
+	pub fn load() -> Self {
+		Self::path()
+			.and_then(|path| fs::read_to_string(path).ok())
+			.and_then(|contents| toml::from_str(&contents).ok())
+			.unwrap_or_default()
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(path) = Self::path() else {
+			return Ok(());
+		};
+
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+
+		let content =
+			toml::to_string_pretty(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+		fs::write(path, content)
+	}
+
+	pub fn record(&mut self, event: UsageEvent) {
+		if self.since_unix_secs == 0 {
+			self.since_unix_secs = SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|elapsed| elapsed.as_secs())
+				.unwrap_or_default();
+		}
+
+		*self.counters.entry(event.key()).or_default() += 1;
+	}
+
+	/// Renders the exact text a user would share, one `key: count` pair per line.
+	#[must_use]
+	pub fn report(&self) -> String {
+		let mut report = format!(
+			"rsnap {} usage (since unix {})\n",
+			env!("CARGO_PKG_VERSION"),
+			self.since_unix_secs
+		);
+
+		if self.counters.is_empty() {
+			report.push_str("(no usage recorded)\n");
+		}
+
+		for (key, count) in &self.counters {
+			report.push_str(&format!("{key}: {count}\n"));
+		}
+
+		report
+	}
+
+	#[must_use]
+	pub fn path() -> Option<PathBuf> {
+		let dirs = ProjectDirs::from("ink", "hack", "rsnap")?;
+
+		Some(dirs.config_dir().join("usage.toml"))
+	}
+}
+
+/// Adds one event to the on-disk log. Failures are logged and otherwise ignored.
+pub(crate) fn record(event: UsageEvent) {
+	let mut log = UsageLog::load();
+
+	log.record(event);
+
+	if let Err(err) = log.save() {
+		tracing::warn!(error = %err, "Failed to persist usage counters.");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::telemetry::{ErrorCategory, UsageEvent, UsageLog};
+
+	#[test]
+	fn classify_buckets_messages_without_keeping_them() {
+		assert_eq!(
+			ErrorCategory::classify("Screen Recording permission denied"),
+			ErrorCategory::Permission
+		);
+		assert_eq!(ErrorCategory::classify("No wgpu adapter found"), ErrorCategory::Gpu);
+		assert_eq!(ErrorCategory::classify("clipboard busy"), ErrorCategory::Clipboard);
+		assert_eq!(ErrorCategory::classify("something odd"), ErrorCategory::Other);
+	}
+
+	#[test]
+	fn record_counts_events_and_reports_them() {
+		let mut log = UsageLog::default();
+
+		log.record(UsageEvent::Mode("capture"));
+		log.record(UsageEvent::Mode("capture"));
+		log.record(UsageEvent::Error(ErrorCategory::Gpu));
+
+		assert_eq!(log.counters.get("mode.capture"), Some(&2));
+		assert!(log.since_unix_secs > 0);
+
+		let report = log.report();
+
+		assert!(report.contains("error.gpu: 1\n"));
+		assert!(report.contains("mode.capture: 2\n"));
+	}
+
+	#[test]
+	fn empty_report_says_so() {
+		assert!(UsageLog::default().report().contains("(no usage recorded)"));
+	}
+}
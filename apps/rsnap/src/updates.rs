@@ -0,0 +1,640 @@
+//! Checking GitHub for a newer rsnap release and installing it.
+//!
+//! Network and archive work goes through the tools every supported system ships with (`curl`
+//! and `tar`), so no HTTP or archive crates are needed. A download is only installed when its
+//! minisign signature verifies against [`UPDATE_PUBLIC_KEY`], which is built into the binary.
+//! The archive is read into memory once, and those same bytes are verified and then unpacked.
+
+use std::collections::hash_map::RandomState;
+use std::fs::{self, DirBuilder};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use minisign_verify::{PublicKey, Signature};
+use serde::Deserialize;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/hack-ink/rsnap/releases/latest";
+/// The minisign public key release archives are signed with, set by the release workflow at
+/// build time. Builds without one never install in place and link to the release page instead.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("RSNAP_UPDATE_PUBLIC_KEY");
+/// Appended to an asset's name to get the asset holding its minisign signature.
+const SIGNATURE_SUFFIX: &str = ".minisig";
+/// The running version, compared against release tags.
+pub(crate) const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Release notes longer than this are cut short in the notes window.
+const NOTES_MAX_CHARS: usize = 2_000;
+/// The release build target of this binary, or `None` when releases do not ship one.
+const RELEASE_TARGET: Option<&str> = if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+	Some("aarch64-apple-darwin")
+} else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+	Some("x86_64-pc-windows-msvc")
+} else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+	Some("x86_64-unknown-linux-gnu")
+} else {
+	None
+};
+/// Hands a URL to the default browser.
+#[cfg(target_os = "macos")]
+const URL_OPENER: &str = "open";
+#[cfg(target_os = "windows")]
+const URL_OPENER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const URL_OPENER: &str = "xdg-open";
+
+/// A published release, as far as the updater needs it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Release {
+	/// Version without the tag's `v` prefix.
+	pub version: String,
+	/// Markdown release notes.
+	pub notes: String,
+	/// The release page on GitHub.
+	pub page_url: String,
+	assets: Vec<ReleaseAsset>,
+}
+impl Release {
+	/// Whether this build can download, verify and apply the release in place.
+	#[must_use]
+	pub fn can_install(&self) -> bool {
+		UPDATE_PUBLIC_KEY.is_some()
+			&& release_asset_name().is_some_and(|name| {
+				self.asset(&name).is_some()
+					&& self.asset(&format!("{name}{SIGNATURE_SUFFIX}")).is_some()
+			})
+	}
+
+	/// Downloads this platform's asset, verifies its minisign signature and swaps it in for the
+	/// running app. Returns the path to relaunch.
+	pub fn install(&self) -> Result<PathBuf> {
+		let public_key = UPDATE_PUBLIC_KEY
+			.ok_or_else(|| eyre::eyre!("This build cannot verify updates; get it from GitHub."))?;
+		let name = release_asset_name()
+			.ok_or_else(|| eyre::eyre!("Releases do not include a build for this platform."))?;
+		let archive_url = self
+			.asset(&name)
+			.ok_or_else(|| eyre::eyre!("Release {} has no `{name}` asset.", self.version))?;
+		let signature_url = self.asset(&format!("{name}{SIGNATURE_SUFFIX}")).ok_or_else(|| {
+			eyre::eyre!("Release {} has no signature for `{name}`.", self.version)
+		})?;
+		let target = install_target()?;
+		// Both downloads stay in memory, so nothing can swap the archive between the signature
+		// check and the extraction.
+		let archive = run(Command::new("curl").args(["-fsSL", archive_url]))
+			.wrap_err("Failed to download the update")?
+			.stdout;
+		let signature = run(Command::new("curl").args(["-fsSL", signature_url]))
+			.wrap_err("Failed to download the update signature")?
+			.stdout;
+
+		verify_signature(public_key, &archive, &String::from_utf8_lossy(&signature))?;
+
+		// Extract next to the app so the final renames stay on one volume.
+		let parent = target.parent().ok_or_else(|| eyre::eyre!("The app has no parent folder."))?;
+		let staging = create_private_dir(parent)?;
+		let installed = extract(&archive, &staging).and_then(|()| {
+			let replacement = staging.join(target.file_name().unwrap_or_default());
+
+			if !replacement.exists() {
+				return Err(eyre::eyre!(
+					"The update archive does not contain {}.",
+					target.display()
+				));
+			}
+
+			replace(&target, &replacement)
+		});
+
+		remove_path(&staging);
+		installed?;
+
+		tracing::info!(
+			op = "updates.install",
+			version = %self.version,
+			path = %target.display(),
+			"Installed update."
+		);
+
+		Ok(target)
+	}
+
+	fn asset(&self, name: &str) -> Option<&str> {
+		self.assets.iter().find(|asset| asset.name == name).map(|asset| asset.url.as_str())
+	}
+}
+
+/// What the user picked in the release notes window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum NotesChoice {
+	Install,
+	OpenPage,
+	Later,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ReleaseAsset {
+	name: String,
+	url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+	tag_name: String,
+	#[serde(default)]
+	body: Option<String>,
+	html_url: String,
+	#[serde(default)]
+	assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+	name: String,
+	browser_download_url: String,
+}
+
+/// Asks GitHub for the latest release and returns it when it is newer than this build.
+pub(crate) fn check_for_update() -> Result<Option<Release>> {
+	let output = run(Command::new("curl").args([
+		"-fsSL",
+		"--max-time",
+		"20",
+		"-H",
+		"Accept: application/vnd.github+json",
+		"-H",
+		&format!("User-Agent: rsnap/{CURRENT_VERSION}"),
+		LATEST_RELEASE_URL,
+	]))
+	.wrap_err("Failed to reach GitHub")?;
+	let release = parse_release(&String::from_utf8_lossy(&output.stdout))?;
+
+	tracing::info!(
+		op = "updates.check",
+		latest = %release.version,
+		current = CURRENT_VERSION,
+		"Checked for updates."
+	);
+
+	Ok(is_newer(&release.version, CURRENT_VERSION).then_some(release))
+}
+
+/// Shows `release`'s notes in a small native window and blocks until it closes.
+pub(crate) fn show_release_notes(release: &Release) -> NotesChoice {
+	let installable = release.can_install();
+	let mut notes = release.notes.trim().chars().take(NOTES_MAX_CHARS).collect::<String>();
+
+	if notes.len() < release.notes.trim().len() {
+		notes.push_str("\n…");
+	}
+
+	let text =
+		format!("rsnap {} is available (you have {CURRENT_VERSION}).\n\n{notes}", release.version);
+	let output = notes_window_commands(installable).into_iter().find_map(|mut command| {
+		command.env("RSNAP_UPDATE_TEXT", &text).stderr(Stdio::null()).output().ok()
+	});
+
+	output.map_or(NotesChoice::Later, |output| {
+		parse_notes_choice(
+			installable,
+			output.status.success(),
+			&String::from_utf8_lossy(&output.stdout),
+		)
+	})
+}
+
+/// Shows a short message with an OK button and blocks until it closes.
+pub(crate) fn show_message(text: &str) {
+	let shown = message_commands().into_iter().any(|mut command| {
+		command
+			.env("RSNAP_UPDATE_TEXT", text)
+			.stderr(Stdio::null())
+			.status()
+			.is_ok_and(|status| status.success())
+	});
+
+	if !shown {
+		tracing::info!(message = %text, "No dialog tool available for an update message.");
+	}
+}
+
+/// Opens `url` in the default browser and waits for the opener to hand it off.
+pub(crate) fn open_page(url: &str) {
+	if let Err(err) = Command::new(URL_OPENER).arg(url).status() {
+		tracing::warn!(error = %err, "Failed to open the release page.");
+	}
+}
+
+/// Starts the freshly installed app. The caller exits right after.
+pub(crate) fn relaunch(path: &Path) -> Result<()> {
+	let mut command = if cfg!(target_os = "macos") {
+		let mut open = Command::new("open");
+
+		open.arg("-n").arg(path);
+
+		open
+	} else {
+		Command::new(path)
+	};
+
+	command
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.wrap_err_with(|| format!("Failed to start {}", path.display()))?;
+
+	Ok(())
+}
+
+fn parse_release(json: &str) -> Result<Release> {
+	let release: GithubRelease =
+		serde_json::from_str(json).wrap_err("GitHub returned an unexpected release")?;
+	let version = release.tag_name.trim_start_matches('v');
+
+	semver::Version::parse(version)
+		.wrap_err_with(|| format!("Release tag `{}` is not a version", release.tag_name))?;
+
+	Ok(Release {
+		version: version.to_owned(),
+		notes: release.body.unwrap_or_default(),
+		page_url: release.html_url,
+		assets: release
+			.assets
+			.into_iter()
+			.map(|asset| ReleaseAsset { name: asset.name, url: asset.browser_download_url })
+			.collect(),
+	})
+}
+
+/// Compares dotted versions numerically; a pre-release sorts before its release.
+fn is_newer(candidate: &str, current: &str) -> bool {
+	fn key(version: &str) -> (Vec<u64>, bool) {
+		let (core, pre) =
+			version.split_once('-').map_or((version, None), |(core, pre)| (core, Some(pre)));
+		let mut parts = core.split('.').map(|part| part.parse().unwrap_or(0)).collect::<Vec<u64>>();
+
+		while parts.last() == Some(&0) {
+			parts.pop();
+		}
+
+		(parts, pre.is_none())
+	}
+
+	key(candidate) > key(current)
+}
+
+fn release_asset_name() -> Option<String> {
+	let extension = if cfg!(target_os = "linux") { "tar.gz" } else { "zip" };
+
+	RELEASE_TARGET.map(|target| format!("rsnap-{target}.{extension}"))
+}
+
+/// Checks `archive` against a minisign `signature` made with the key `public_key` encodes.
+fn verify_signature(public_key: &str, archive: &[u8], signature: &str) -> Result<()> {
+	let public_key = PublicKey::from_base64(public_key)
+		.map_err(|err| eyre::eyre!("The built-in update key is invalid: {err}"))?;
+	let signature = Signature::decode(signature)
+		.map_err(|err| eyre::eyre!("The update signature is malformed: {err}"))?;
+
+	public_key
+		.verify(archive, &signature, false)
+		.map_err(|err| eyre::eyre!("The update signature does not match: {err}"))
+}
+
+fn parse_notes_choice(installable: bool, success: bool, stdout: &str) -> NotesChoice {
+	if stdout.contains("View on GitHub") {
+		NotesChoice::OpenPage
+	} else if installable && (stdout.contains("Install") || (success && stdout.trim().is_empty())) {
+		NotesChoice::Install
+	} else {
+		NotesChoice::Later
+	}
+}
+
+/// The file or, on macOS, the `.app` bundle an update replaces.
+fn install_target() -> Result<PathBuf> {
+	let exe = std::env::current_exe().wrap_err("Failed to locate the rsnap executable")?;
+
+	if !cfg!(target_os = "macos") {
+		return Ok(exe);
+	}
+
+	exe.ancestors()
+		.find(|path| path.extension().is_some_and(|extension| extension == "app"))
+		.map(Path::to_path_buf)
+		.ok_or_else(|| {
+			eyre::eyre!("Updates install only into Rsnap.app; this copy runs outside it.")
+		})
+}
+
+/// Creates a new directory with an unguessable name in `parent` that only this user can enter.
+/// It never reuses an existing directory.
+fn create_private_dir(parent: &Path) -> Result<PathBuf> {
+	#[cfg(unix)]
+	let builder = {
+		let mut builder = DirBuilder::new();
+
+		std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+		builder
+	};
+	// The per-user temporary and app folders already keep other users out on Windows.
+	#[cfg(not(unix))]
+	let builder = DirBuilder::new();
+
+	for _ in 0..8 {
+		let suffix = RandomState::new().build_hasher().finish();
+		let dir = parent.join(format!(".rsnap-update-{suffix:016x}"));
+
+		match builder.create(&dir) {
+			Ok(()) => return Ok(dir),
+			Err(err) if err.kind() == ErrorKind::AlreadyExists => {},
+			Err(err) => {
+				return Err(err).wrap_err_with(|| format!("Failed to create {}", dir.display()));
+			},
+		}
+	}
+
+	Err(eyre::eyre!("Failed to create a staging folder in {}", parent.display()))
+}
+
+/// Moves `target` aside and `replacement` into its place, restoring `target` if that fails.
+/// A running executable can be renamed on every supported system, just not deleted on Windows,
+/// so the old copy is left as `<name>.old` until the next update.
+fn replace(target: &Path, replacement: &Path) -> Result<()> {
+	let file_name = target.file_name().unwrap_or_default().to_string_lossy();
+	let backup = target.with_file_name(format!("{file_name}.old"));
+
+	remove_path(&backup);
+	fs::rename(target, &backup)
+		.wrap_err_with(|| format!("Failed to move {} aside", target.display()))?;
+
+	if let Err(err) = fs::rename(replacement, target) {
+		let _ = fs::rename(&backup, target);
+
+		return Err(eyre::eyre!("Failed to install {}: {err}", target.display()));
+	}
+
+	remove_path(&backup);
+
+	Ok(())
+}
+
+fn remove_path(path: &Path) {
+	let _ = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+}
+
+fn run(command: &mut Command) -> Result<Output> {
+	let output = command.stdin(Stdio::null()).output().wrap_err("Failed to run a helper tool")?;
+
+	if output.status.success() {
+		return Ok(output);
+	}
+
+	Err(eyre::eyre!("{}", String::from_utf8_lossy(&output.stderr).trim()))
+}
+
+/// Unpacks the release archive in `archive` into `dir` by piping it to `tar`. macOS and Windows
+/// ship bsdtar, which also reads the zip archives those releases use.
+fn extract(archive: &[u8], dir: &Path) -> Result<()> {
+	let mut command = tar_command();
+
+	command.arg("-x");
+
+	// Linux releases are gzipped tarballs; GNU tar only detects that for files, not stdin.
+	if cfg!(not(any(target_os = "macos", target_os = "windows"))) {
+		command.arg("-z");
+	}
+
+	command.args(["-f", "-", "-C"]).arg(dir);
+
+	let mut child = command
+		.stdin(Stdio::piped())
+		.stdout(Stdio::null())
+		.stderr(Stdio::piped())
+		.spawn()
+		.wrap_err("Failed to start tar")?;
+	let mut stdin = child.stdin.take().ok_or_else(|| eyre::eyre!("tar has no stdin"))?;
+	// Feed the archive from its own thread so a chatty stderr cannot stall both processes.
+	let output = thread::scope(|scope| {
+		let writer = scope.spawn(move || stdin.write_all(archive));
+		let output = child.wait_with_output();
+
+		(writer.join(), output)
+	});
+	let output = match output {
+		(Ok(Ok(())), Ok(output)) => output,
+		(_, Err(err)) => return Err(err).wrap_err("Failed to unpack the update"),
+		(Ok(Err(err)), _) => return Err(err).wrap_err("Failed to unpack the update"),
+		(Err(_), _) => return Err(eyre::eyre!("Failed to unpack the update")),
+	};
+
+	if output.status.success() {
+		return Ok(());
+	}
+
+	Err(eyre::eyre!(
+		"Failed to unpack the update: {}",
+		String::from_utf8_lossy(&output.stderr).trim()
+	))
+}
+
+/// The system's own `tar`; on Windows, the bsdtar in System32 rather than any other on `PATH`.
+fn tar_command() -> Command {
+	#[cfg(target_os = "windows")]
+	if let Some(root) = std::env::var_os("SystemRoot") {
+		return Command::new(Path::new(&root).join("System32").join("tar.exe"));
+	}
+
+	Command::new("tar")
+}
+
+#[cfg(target_os = "macos")]
+fn notes_window_commands(installable: bool) -> Vec<Command> {
+	let buttons = if installable {
+		r#"{"Later", "View on GitHub", "Install"}"#
+	} else {
+		r#"{"Later", "View on GitHub"}"#
+	};
+	let script = format!(
+		"display dialog (system attribute \"RSNAP_UPDATE_TEXT\") with title \"rsnap update\" \
+		 buttons {buttons} default button {} cancel button \"Later\"",
+		if installable { "\"Install\"" } else { "\"View on GitHub\"" }
+	);
+	let mut command = Command::new("osascript");
+
+	command.args(["-e", &script]);
+
+	vec![command]
+}
+
+#[cfg(target_os = "windows")]
+fn notes_window_commands(installable: bool) -> Vec<Command> {
+	let script = format!(
+		"Add-Type -AssemblyName System.Windows.Forms; \
+		 $form = New-Object System.Windows.Forms.Form; \
+		 $form.Text = 'rsnap update'; $form.Width = 520; $form.Height = 420; \
+		 $form.StartPosition = 'CenterScreen'; $form.TopMost = $true; \
+		 $text = New-Object System.Windows.Forms.TextBox; \
+		 $text.Multiline = $true; $text.ReadOnly = $true; $text.ScrollBars = 'Vertical'; \
+		 $text.Dock = 'Fill'; $text.Text = $env:RSNAP_UPDATE_TEXT -replace \"`r?`n\", \"`r`n\"; \
+		 $panel = New-Object System.Windows.Forms.FlowLayoutPanel; \
+		 $panel.Dock = 'Bottom'; $panel.Height = 40; $panel.FlowDirection = 'RightToLeft'; \
+		 $script:choice = 'Later'; \
+		 foreach ($label in @({})) {{ \
+		   $button = New-Object System.Windows.Forms.Button; $button.Text = $label; \
+		   $button.AutoSize = $true; \
+		   $button.Add_Click({{ $script:choice = $this.Text; $form.Close() }}); \
+		   $panel.Controls.Add($button) }}; \
+		 $form.Controls.Add($text); $form.Controls.Add($panel); \
+		 [void]$form.ShowDialog(); $script:choice",
+		if installable {
+			"'Install', 'View on GitHub', 'Later'"
+		} else {
+			"'View on GitHub', 'Later'"
+		}
+	);
+	let mut command = Command::new("powershell");
+
+	command.args(["-NoProfile", "-STA", "-Command", &script]);
+
+	vec![command]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn notes_window_commands(installable: bool) -> Vec<Command> {
+	let mut zenity = Command::new("sh");
+
+	// zenity reads the notes from stdin; the extra button prints its label and exits with 1.
+	zenity.args([
+		"-c",
+		&format!(
+			"printf '%s\\n' \"$RSNAP_UPDATE_TEXT\" | zenity --text-info --title='rsnap update' \
+			 --width=520 --height=420 --ok-label='{}' --cancel-label=Later \
+			 --extra-button='View on GitHub'",
+			if installable { "Install" } else { "Close" }
+		),
+	]);
+
+	vec![zenity]
+}
+
+#[cfg(target_os = "macos")]
+fn message_commands() -> Vec<Command> {
+	let mut command = Command::new("osascript");
+
+	command.args([
+		"-e",
+		"display dialog (system attribute \"RSNAP_UPDATE_TEXT\") with title \"rsnap update\" \
+		 buttons {\"OK\"} default button \"OK\"",
+	]);
+
+	vec![command]
+}
+
+#[cfg(target_os = "windows")]
+fn message_commands() -> Vec<Command> {
+	let mut command = Command::new("powershell");
+
+	command.args([
+		"-NoProfile",
+		"-STA",
+		"-Command",
+		"Add-Type -AssemblyName System.Windows.Forms; \
+		 [void][System.Windows.Forms.MessageBox]::Show($env:RSNAP_UPDATE_TEXT, 'rsnap update')",
+	]);
+
+	vec![command]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn message_commands() -> Vec<Command> {
+	let mut zenity = Command::new("sh");
+	let mut kdialog = Command::new("sh");
+
+	zenity.args(["-c", "zenity --info --title='rsnap update' --text=\"$RSNAP_UPDATE_TEXT\""]);
+	kdialog.args(["-c", "kdialog --title 'rsnap update' --msgbox \"$RSNAP_UPDATE_TEXT\""]);
+
+	vec![zenity, kdialog]
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::updates::{self, NotesChoice};
+
+	#[test]
+	fn versions_compare_numerically_with_prereleases_first() {
+		assert!(updates::is_newer("0.2.0", "0.1.9"));
+		assert!(updates::is_newer("0.10.0", "0.9.3"));
+		assert!(updates::is_newer("1.0.0", "1.0.0-rc.1"));
+		assert!(!updates::is_newer("1.0.0-rc.1", "1.0.0"));
+		assert!(!updates::is_newer("1.0", "1.0.0"));
+		assert!(!updates::is_newer("0.1.0", "0.1.0"));
+	}
+
+	const TEST_PUBLIC_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+	const TEST_SIGNATURE: &str = "untrusted comment: signature from rsnap test key
+RUQBAgMEBQYHCAq6mUCFVYmh1G+99zOiQxXiAkJKDGMqWTTazAIhhsZWM/1A8aox4AMWDtqR0WaE4BzTxkzLlix/zOG7ajipCQw=
+trusted comment: timestamp:0\tfile:rsnap-test.tar.gz
+2L995Zi/qGrloJYkhkT0YoYxnl7gwUhjKw7EejjT11mkQhKM0D7IDkrpg+UteZedNMJb2Jf1UJdXFDEc6D7ABA==
+";
+
+	#[test]
+	fn releases_parse_from_github_and_reject_non_version_tags() {
+		let release = updates::parse_release(
+			r#"{
+				"tag_name": "v0.3.0",
+				"body": "- Faster freeze",
+				"html_url": "https://github.com/hack-ink/rsnap/releases/tag/v0.3.0",
+				"assets": [
+					{ "name": "SHA256", "browser_download_url": "https://example.com/SHA256" },
+					{
+						"name": "rsnap-x86_64-pc-windows-msvc.zip",
+						"browser_download_url": "https://example.com/w.zip"
+					}
+				]
+			}"#,
+		)
+		.expect("valid release");
+
+		assert_eq!(release.version, "0.3.0");
+		assert_eq!(release.notes, "- Faster freeze");
+		assert_eq!(release.asset("SHA256"), Some("https://example.com/SHA256"));
+		assert!(updates::parse_release("{}").is_err());
+		assert!(
+			updates::parse_release(r#"{ "tag_name": "v1.0/../../x", "html_url": "" }"#).is_err()
+		);
+	}
+
+	#[test]
+	fn update_signatures_verify_only_the_signed_archive() {
+		assert!(
+			updates::verify_signature(TEST_PUBLIC_KEY, b"rsnap update archive", TEST_SIGNATURE)
+				.is_ok()
+		);
+		assert!(
+			updates::verify_signature(TEST_PUBLIC_KEY, b"rsnap update archivf", TEST_SIGNATURE)
+				.is_err()
+		);
+		assert!(updates::verify_signature(TEST_PUBLIC_KEY, b"rsnap update archive", "").is_err());
+	}
+
+	#[test]
+	fn notes_window_choices_map_from_dialog_output() {
+		assert_eq!(
+			updates::parse_notes_choice(true, true, "button returned:Install\n"),
+			NotesChoice::Install
+		);
+		assert_eq!(updates::parse_notes_choice(true, true, ""), NotesChoice::Install);
+		assert_eq!(updates::parse_notes_choice(false, true, ""), NotesChoice::Later);
+		assert_eq!(
+			updates::parse_notes_choice(true, false, "View on GitHub\n"),
+			NotesChoice::OpenPage
+		);
+		assert_eq!(updates::parse_notes_choice(true, true, "Later\n"), NotesChoice::Later);
+		assert_eq!(updates::parse_notes_choice(true, false, ""), NotesChoice::Later);
+	}
+}
@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+
+use crate::callout::{Callout, CalloutPart};
+use crate::highlighter::HighlightStroke;
+use crate::stamp::{PlacedStamp, STAMP_DEFAULT_SIZE_PX, StampKind};
+
+/// Smallest and largest stamp side the wheel can scale to.
+const STAMP_MIN_SIZE_PX: u32 = 16;
+const STAMP_MAX_SIZE_PX: u32 = 512;
+/// Each wheel notch scales the stamp under the pointer by this factor.
+const STAMP_WHEEL_SCALE_STEP: f32 = 1.125;
+
+/// Everything drawn on the frozen selection, as one undo step.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+struct Snapshot {
+	highlights: Vec<HighlightStroke>,
+	stamps: Vec<PlacedStamp>,
+	callouts: Vec<Callout>,
+}
+
+/// An [`AnnotationLayer`] as stored in a project file: what is drawn plus its undo and redo
+/// history.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct SavedAnnotations {
+	current: Snapshot,
+	undo: Vec<Snapshot>,
+	redo: Vec<Snapshot>,
+}
+
+/// A callout body or tail following the pointer.
+#[derive(Clone, Copy, Debug)]
+struct CalloutDrag {
+	index: usize,
+	part: CalloutPart,
+	last_px: (i64, i64),
+	moved: bool,
+}
+
+/// The highlights, stamps, and callouts on the frozen selection with their shared undo and redo
+/// history.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AnnotationLayer {
+	pub(crate) highlights: Vec<HighlightStroke>,
+	pub(crate) stamps: Vec<PlacedStamp>,
+	pub(crate) callouts: Vec<Callout>,
+	/// The callout whose text keystrokes go to.
+	pub(crate) editing: Option<usize>,
+	drag: Option<CalloutDrag>,
+	/// The last highlight stroke is still following the pointer.
+	highlighting: bool,
+	/// Set once the current edit has pushed its undo step, so typing a word is one step.
+	edit_checkpointed: bool,
+	undo: Vec<Snapshot>,
+	redo: Vec<Snapshot>,
+	/// The stamp the wheel is scaling; consecutive notches on it form one undo step.
+	scaling: Option<usize>,
+}
+impl AnnotationLayer {
+	/// Starts a highlight stroke at `point`; it grows with [`Self::extend_highlight`].
+	pub(crate) fn begin_highlight(&mut self, point: (i64, i64), width_px: u32, rgb: [u8; 3]) {
+		self.finish_edit();
+		self.checkpoint();
+		self.highlights.push(HighlightStroke { points: vec![point], width_px, rgb });
+
+		self.highlighting = true;
+	}
+
+	pub(crate) fn extend_highlight(&mut self, point: (i64, i64)) -> bool {
+		let Some(stroke) = self.highlights.last_mut().filter(|_| self.highlighting) else {
+			return false;
+		};
+
+		if stroke.points.last() == Some(&point) {
+			return false;
+		}
+
+		stroke.points.push(point);
+
+		true
+	}
+
+	/// Ends the stroke in progress; `false` when there was none.
+	pub(crate) fn end_highlight(&mut self) -> bool {
+		std::mem::take(&mut self.highlighting)
+	}
+
+	pub(crate) fn place_stamp(&mut self, kind: StampKind, center_px: (u32, u32)) {
+		self.finish_edit();
+		self.checkpoint();
+		self.stamps.push(PlacedStamp { kind, center_px, size_px: STAMP_DEFAULT_SIZE_PX });
+	}
+
+	/// Scales the topmost stamp under `point` by `notches` wheel steps; `false` when there is
+	/// none.
+	pub(crate) fn scale_stamp_at(&mut self, point: (u32, u32), notches: f32) -> bool {
+		let Some(index) = self.stamps.iter().rposition(|stamp| stamp.contains(point)) else {
+			return false;
+		};
+
+		if self.scaling != Some(index) {
+			self.checkpoint();
+
+			self.scaling = Some(index);
+		}
+
+		let stamp = &mut self.stamps[index];
+		let size = stamp.size_px as f32 * STAMP_WHEEL_SCALE_STEP.powf(notches);
+
+		stamp.size_px = (size.round() as u32).clamp(STAMP_MIN_SIZE_PX, STAMP_MAX_SIZE_PX);
+
+		true
+	}
+
+	/// Adds an empty callout and starts editing its text.
+	pub(crate) fn add_callout(&mut self, callout: Callout) {
+		self.finish_edit();
+		self.checkpoint();
+		self.callouts.push(callout);
+
+		self.editing = Some(self.callouts.len() - 1);
+		self.edit_checkpointed = true;
+	}
+
+	/// Starts moving the body or tail of the topmost callout under `point`; `false` when there
+	/// is none.
+	pub(crate) fn begin_callout_drag(&mut self, point: (i64, i64)) -> bool {
+		let hit = |callouts: &[Callout]| {
+			callouts
+				.iter()
+				.enumerate()
+				.rev()
+				.find_map(|(index, callout)| callout.hit(point).map(|part| (index, part)))
+		};
+
+		// Finishing an edit elsewhere can remove an empty callout and shift the indices.
+		if self.editing.is_some() && hit(&self.callouts).map(|(index, _)| index) != self.editing {
+			self.finish_edit();
+		}
+
+		let Some((index, part)) = hit(&self.callouts) else {
+			return false;
+		};
+
+		self.checkpoint();
+
+		self.drag = Some(CalloutDrag { index, part, last_px: point, moved: false });
+		// Text typed after the drag is its own undo step.
+		self.edit_checkpointed = false;
+
+		true
+	}
+
+	pub(crate) fn dragging_callout(&self) -> bool {
+		self.drag.is_some()
+	}
+
+	pub(crate) fn drag_callout_to(&mut self, point: (i64, i64)) -> bool {
+		let Some(drag) = self.drag.as_mut() else {
+			return false;
+		};
+		let (dx, dy) = (point.0 - drag.last_px.0, point.1 - drag.last_px.1);
+
+		if (dx, dy) == (0, 0) {
+			return false;
+		}
+
+		let callout = &mut self.callouts[drag.index];
+		let target = match drag.part {
+			CalloutPart::Body => &mut callout.origin_px,
+			CalloutPart::Tail => &mut callout.tail_px,
+		};
+
+		*target = (target.0 + dx, target.1 + dy);
+		drag.last_px = point;
+		drag.moved = true;
+
+		true
+	}
+
+	/// Ends a drag. A body clicked without moving starts editing its text.
+	pub(crate) fn end_callout_drag(&mut self) {
+		let Some(drag) = self.drag.take() else {
+			return;
+		};
+
+		self.settle();
+
+		if !drag.moved && drag.part == CalloutPart::Body {
+			self.editing = Some(drag.index);
+		}
+	}
+
+	pub(crate) fn type_text(&mut self, text: &str) -> bool {
+		let text = text.chars().filter(|ch| *ch == '\n' || !ch.is_control()).collect::<String>();
+
+		if text.is_empty() {
+			return false;
+		}
+
+		self.edit_callout(|callout| callout.text.push_str(&text))
+	}
+
+	pub(crate) fn backspace(&mut self) -> bool {
+		self.edit_callout(|callout| {
+			callout.text.pop();
+		})
+	}
+
+	/// Stops editing; a callout left without text is removed.
+	pub(crate) fn finish_edit(&mut self) -> bool {
+		let Some(index) = self.editing.take() else {
+			return false;
+		};
+
+		self.edit_checkpointed = false;
+
+		if self.callouts.get(index).is_some_and(|callout| callout.text.is_empty()) {
+			self.callouts.remove(index);
+		}
+
+		self.settle();
+
+		true
+	}
+
+	pub(crate) fn undo(&mut self) -> bool {
+		self.finish_edit();
+
+		let Some(previous) = self.undo.pop() else {
+			return false;
+		};
+		let current = self.restore(previous);
+
+		self.redo.push(current);
+
+		true
+	}
+
+	pub(crate) fn redo(&mut self) -> bool {
+		self.finish_edit();
+
+		let Some(next) = self.redo.pop() else {
+			return false;
+		};
+		let current = self.restore(next);
+
+		self.undo.push(current);
+
+		true
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.highlights.is_empty() && self.stamps.is_empty() && self.callouts.is_empty()
+	}
+
+	pub(crate) fn clear(&mut self) {
+		*self = Self::default();
+	}
+
+	pub(crate) fn to_saved(&self) -> SavedAnnotations {
+		SavedAnnotations {
+			current: self.snapshot(),
+			undo: self.undo.clone(),
+			redo: self.redo.clone(),
+		}
+	}
+
+	/// Restores a saved layer; nothing is being edited or dragged afterwards.
+	pub(crate) fn from_saved(saved: SavedAnnotations) -> Self {
+		let SavedAnnotations { current, undo, redo } = saved;
+
+		Self {
+			highlights: current.highlights,
+			stamps: current.stamps,
+			callouts: current.callouts,
+			undo,
+			redo,
+			..Self::default()
+		}
+	}
+
+	fn edit_callout(&mut self, edit: impl FnOnce(&mut Callout)) -> bool {
+		let Some(index) = self.editing else {
+			return false;
+		};
+
+		if !self.edit_checkpointed {
+			self.checkpoint();
+
+			self.edit_checkpointed = true;
+		}
+
+		edit(&mut self.callouts[index]);
+
+		true
+	}
+
+	fn snapshot(&self) -> Snapshot {
+		Snapshot {
+			highlights: self.highlights.clone(),
+			stamps: self.stamps.clone(),
+			callouts: self.callouts.clone(),
+		}
+	}
+
+	fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+		let current = Snapshot {
+			highlights: std::mem::replace(&mut self.highlights, snapshot.highlights),
+			stamps: std::mem::replace(&mut self.stamps, snapshot.stamps),
+			callouts: std::mem::replace(&mut self.callouts, snapshot.callouts),
+		};
+
+		self.drag = None;
+		self.highlighting = false;
+		self.scaling = None;
+
+		current
+	}
+
+	fn checkpoint(&mut self) {
+		self.undo.push(self.snapshot());
+		self.redo.clear();
+
+		self.scaling = None;
+	}
+
+	/// Drops the last undo step when it turned out to change nothing, e.g. a click on a callout.
+	fn settle(&mut self) {
+		if self.undo.last() == Some(&self.snapshot()) {
+			self.undo.pop();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::annotation::AnnotationLayer;
+	use crate::callout::Callout;
+	use crate::stamp::StampKind;
+
+	fn empty_callout() -> Callout {
+		Callout { origin_px: (100, 20), tail_px: (40, 120), text: String::new(), text_size_px: 16 }
+	}
+
+	#[test]
+	fn stamp_edits_undo_and_redo_with_wheel_notches_grouped() {
+		let mut layer = AnnotationLayer::default();
+
+		layer.place_stamp(StampKind::Star, (100, 100));
+
+		assert!(layer.scale_stamp_at((110, 90), 1.0));
+		assert!(layer.scale_stamp_at((110, 90), 1.0));
+		assert!(!layer.scale_stamp_at((10, 10), 1.0));
+		assert_eq!(layer.stamps[0].size_px, 81);
+		assert!(layer.undo());
+		assert_eq!(layer.stamps[0].size_px, 64);
+		assert!(layer.undo());
+		assert!(layer.stamps.is_empty());
+		assert!(!layer.undo());
+		assert!(layer.redo());
+		assert!(layer.redo());
+		assert_eq!(layer.stamps[0].size_px, 81);
+		assert!(!layer.redo());
+	}
+
+	#[test]
+	fn callout_text_and_moves_are_single_undo_steps() {
+		let mut layer = AnnotationLayer::default();
+
+		layer.add_callout(empty_callout());
+
+		assert!(layer.type_text("Bug"));
+		assert!(layer.type_text("s"));
+		assert!(layer.backspace());
+		assert!(layer.finish_edit());
+		assert_eq!(layer.callouts[0].text, "Bug");
+
+		// A click on the body without moving edits the text and leaves no undo step behind.
+		assert!(layer.begin_callout_drag((110, 30)));
+		layer.end_callout_drag();
+		assert_eq!(layer.editing, Some(0));
+		assert!(layer.finish_edit());
+
+		assert!(layer.begin_callout_drag((40, 120)));
+		assert!(layer.drag_callout_to((60, 150)));
+		layer.end_callout_drag();
+		assert_eq!(layer.callouts[0].tail_px, (60, 150));
+		assert_eq!(layer.callouts[0].origin_px, (100, 20));
+
+		assert!(layer.undo());
+		assert_eq!(layer.callouts[0].tail_px, (40, 120));
+		assert!(layer.undo());
+		assert!(layer.callouts.is_empty());
+		assert!(!layer.undo());
+	}
+
+	#[test]
+	fn a_highlight_stroke_is_one_undo_step() {
+		let mut layer = AnnotationLayer::default();
+
+		layer.begin_highlight((0, 0), 20, [255, 230, 0]);
+
+		assert!(layer.extend_highlight((10, 0)));
+		assert!(!layer.extend_highlight((10, 0)));
+		assert!(layer.extend_highlight((20, 5)));
+		assert!(layer.end_highlight());
+		assert!(!layer.extend_highlight((30, 5)));
+		assert_eq!(layer.highlights[0].points, vec![(0, 0), (10, 0), (20, 5)]);
+		assert!(layer.undo());
+		assert!(layer.highlights.is_empty());
+		assert!(layer.redo());
+		assert_eq!(layer.highlights[0].points.len(), 3);
+	}
+
+	#[test]
+	fn an_abandoned_empty_callout_leaves_no_trace() {
+		let mut layer = AnnotationLayer::default();
+
+		layer.place_stamp(StampKind::Check, (10, 10));
+		layer.add_callout(empty_callout());
+
+		assert!(layer.finish_edit());
+		assert!(layer.callouts.is_empty());
+		assert!(layer.undo());
+		assert!(layer.stamps.is_empty());
+	}
+}
@@ -0,0 +1,167 @@
+//! Grouping of on-screen windows by the application that owns them, so every window of one app
+//! can be captured in a single action.
+
+use color_eyre::eyre::Result;
+
+use crate::state::WindowRect;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// An application and its visible windows.
+pub struct AppWindows {
+	/// Process id of the owning application.
+	pub pid: u32,
+	/// Display name of the application, or `pid <n>` when it has none.
+	pub name: String,
+	/// The application's windows, front to back.
+	pub windows: Vec<WindowRect>,
+}
+impl AppWindows {
+	#[must_use]
+	/// Whether `query` names this application: its pid, or its name ignoring case.
+	pub fn matches(&self, query: &str) -> bool {
+		let query = query.trim();
+
+		query.parse::<u32>().is_ok_and(|pid| pid == self.pid)
+			|| self.name.eq_ignore_ascii_case(query)
+	}
+}
+
+/// Lists applications with visible windows, ordered by their frontmost window. This process's
+/// windows are skipped.
+pub fn list_app_windows() -> Result<Vec<AppWindows>> {
+	Ok(group_by_app(imp::owned_windows()?))
+}
+
+/// Groups `(pid, name, window)` triples, given front to back, into one entry per pid.
+fn group_by_app(windows: Vec<(u32, String, WindowRect)>) -> Vec<AppWindows> {
+	let mut apps: Vec<AppWindows> = Vec::new();
+
+	for (pid, name, window) in windows {
+		match apps.iter_mut().find(|app| app.pid == pid) {
+			Some(app) => app.windows.push(window),
+			None => {
+				let name = if name.trim().is_empty() { format!("pid {pid}") } else { name };
+
+				apps.push(AppWindows { pid, name, windows: vec![window] });
+			},
+		}
+	}
+
+	apps
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+	use std::collections::HashMap;
+
+	use color_eyre::eyre::Result;
+	use objc2_app_kit::NSRunningApplication;
+
+	use crate::backend;
+	use crate::state::WindowRect;
+
+	pub(super) fn owned_windows() -> Result<Vec<(u32, String, WindowRect)>> {
+		let mut names = HashMap::new();
+
+		Ok(backend::normal_windows_with_owner_pids()
+			.into_iter()
+			.map(|(pid, window)| {
+				let name = names.entry(pid).or_insert_with(|| app_name(pid)).clone();
+
+				(pid, name, window)
+			})
+			.collect())
+	}
+
+	fn app_name(pid: u32) -> String {
+		i32::try_from(pid)
+			.ok()
+			.and_then(NSRunningApplication::runningApplicationWithProcessIdentifier)
+			.and_then(|app| app.localizedName())
+			.map(|name| name.to_string())
+			.unwrap_or_default()
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+	use std::process;
+
+	use color_eyre::eyre::{Result, WrapErr};
+	use xcap::Window;
+
+	use crate::state::WindowRect;
+
+	pub(super) fn owned_windows() -> Result<Vec<(u32, String, WindowRect)>> {
+		let windows = Window::all().wrap_err("xcap Window::all failed")?;
+		let self_pid = process::id();
+
+		Ok(windows
+			.into_iter()
+			.filter(|window| !window.is_minimized().unwrap_or(true))
+			.filter_map(|window| {
+				let pid = window.pid().ok().filter(|pid| *pid != self_pid)?;
+				let rect = WindowRect {
+					window_id: Some(window.id().ok()?),
+					x: i64::from(window.x().ok()?),
+					y: i64::from(window.y().ok()?),
+					width: i64::from(window.width().ok()?),
+					height: i64::from(window.height().ok()?),
+				};
+
+				(rect.width > 0 && rect.height > 0)
+					.then(|| (pid, window.app_name().unwrap_or_default(), rect))
+			})
+			.collect())
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+	use color_eyre::eyre::Result;
+
+	use crate::state::WindowRect;
+	use crate::x11_capture_linux;
+
+	pub(super) fn owned_windows() -> Result<Vec<(u32, String, WindowRect)>> {
+		Ok(x11_capture_linux::windows_with_pids()?
+			.into_iter()
+			.map(|(pid, window)| (pid, x11_capture_linux::process_name(pid), window))
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::app_windows::{self, AppWindows};
+	use crate::state::WindowRect;
+
+	fn window(window_id: u32, x: i64) -> WindowRect {
+		WindowRect { window_id: Some(window_id), x, y: 0, width: 40, height: 30 }
+	}
+
+	#[test]
+	fn windows_group_by_owner_in_front_to_back_order() {
+		let apps = app_windows::group_by_app(vec![
+			(7, String::from("Editor"), window(1, 0)),
+			(9, String::new(), window(2, 10)),
+			(7, String::from("Editor"), window(3, 20)),
+		]);
+
+		assert_eq!(
+			apps,
+			vec![
+				AppWindows {
+					pid: 7,
+					name: String::from("Editor"),
+					windows: vec![window(1, 0), window(3, 20)],
+				},
+				AppWindows { pid: 9, name: String::from("pid 9"), windows: vec![window(2, 10)] },
+			]
+		);
+		assert!(apps[0].matches("editor"));
+		assert!(apps[0].matches(" 7 "));
+		assert!(!apps[0].matches("9"));
+		assert!(!apps[0].matches("Edit"));
+	}
+}
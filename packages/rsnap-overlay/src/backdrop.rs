@@ -0,0 +1,219 @@
+use image::imageops::{self, FilterType};
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+use crate::overlay::{BackdropFill, ExportBackdrop};
+
+// Blurs run on a downscaled copy; a small sigma there reads as a wide desktop-style blur.
+const BLUR_DOWNSCALE: u32 = 8;
+const BLUR_SIGMA: f32 = 6.0;
+const SHADOW_DOWNSCALE: u32 = 4;
+const SHADOW_OPACITY: f32 = 0.45;
+
+/// Everything the worker needs to place an export on its backdrop.
+#[derive(Debug)]
+pub(crate) struct BackdropJob {
+	pub(crate) backdrop: ExportBackdrop,
+	/// Screen pixels around the capture, used by [`BackdropFill::Blur`]. Without it the blur
+	/// falls back to the gradient.
+	pub(crate) blur_source: Option<RgbaImage>,
+}
+
+/// Pads `image` onto an opaque canvas, rounding its corners and optionally casting a shadow.
+pub(crate) fn compose_backdrop(image: &RgbaImage, job: &BackdropJob) -> RgbaImage {
+	let backdrop = job.backdrop;
+	let padding = backdrop.padding_px;
+	let width = image.width().saturating_add(padding.saturating_mul(2));
+	let height = image.height().saturating_add(padding.saturating_mul(2));
+	let radius =
+		(backdrop.corner_radius_px as f32).min(image.width().min(image.height()) as f32 / 2.0);
+	let mut canvas = backdrop_canvas(width, height, &backdrop, job.blur_source.as_ref());
+
+	if backdrop.shadow && padding > 0 {
+		draw_shadow(&mut canvas, padding, image.width(), image.height(), radius);
+	}
+
+	for (x, y, pixel) in image.enumerate_pixels() {
+		let coverage = rounded_rect_coverage(x, y, image.width(), image.height(), radius);
+		let alpha = f32::from(pixel[3]) / 255.0 * coverage;
+
+		if alpha <= 0.0 {
+			continue;
+		}
+
+		let dst = canvas.get_pixel_mut(x + padding, y + padding);
+
+		for channel in 0..3 {
+			dst[channel] = lerp_u8(dst[channel], pixel[channel], alpha);
+		}
+	}
+
+	canvas
+}
+
+fn backdrop_canvas(
+	width: u32,
+	height: u32,
+	backdrop: &ExportBackdrop,
+	blur_source: Option<&RgbaImage>,
+) -> RgbaImage {
+	match (backdrop.fill, blur_source) {
+		(BackdropFill::Solid, _) => {
+			RgbaImage::from_pixel(width, height, opaque(backdrop.primary_rgb))
+		},
+		(BackdropFill::Blur, Some(source)) if source.width() > 0 && source.height() > 0 => {
+			blurred_canvas(source, width, height)
+		},
+		_ => gradient_canvas(width, height, backdrop.primary_rgb, backdrop.secondary_rgb),
+	}
+}
+
+fn gradient_canvas(width: u32, height: u32, from: [u8; 3], to: [u8; 3]) -> RgbaImage {
+	let span_x = width.saturating_sub(1).max(1) as f32;
+	let span_y = height.saturating_sub(1).max(1) as f32;
+
+	RgbaImage::from_fn(width, height, |x, y| {
+		let t = (x as f32 / span_x + y as f32 / span_y) / 2.0;
+
+		Rgba([
+			lerp_u8(from[0], to[0], t),
+			lerp_u8(from[1], to[1], t),
+			lerp_u8(from[2], to[2], t),
+			255,
+		])
+	})
+}
+
+fn blurred_canvas(source: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+	let small = imageops::resize(
+		source,
+		(width / BLUR_DOWNSCALE).max(1),
+		(height / BLUR_DOWNSCALE).max(1),
+		FilterType::Triangle,
+	);
+	let blurred = imageops::blur(&small, BLUR_SIGMA);
+	let mut canvas = imageops::resize(&blurred, width, height, FilterType::Triangle);
+
+	for pixel in canvas.pixels_mut() {
+		pixel[3] = 255;
+	}
+
+	canvas
+}
+
+fn draw_shadow(canvas: &mut RgbaImage, padding: u32, width: u32, height: u32, radius: f32) {
+	let scale = SHADOW_DOWNSCALE as f32;
+	let mask_width = canvas.width().div_ceil(SHADOW_DOWNSCALE);
+	let mask_height = canvas.height().div_ceil(SHADOW_DOWNSCALE);
+	// The shadow sits slightly below the capture, as if lit from above.
+	let origin_x = padding as f32 / scale;
+	let origin_y = (padding + padding / 8) as f32 / scale;
+	let shadow_width = (width as f32 / scale).ceil() as u32;
+	let shadow_height = (height as f32 / scale).ceil() as u32;
+	let mask = GrayImage::from_fn(mask_width, mask_height, |x, y| {
+		let local_x = x as f32 - origin_x;
+		let local_y = y as f32 - origin_y;
+
+		if local_x < 0.0 || local_y < 0.0 {
+			return Luma([0]);
+		}
+
+		let coverage = rounded_rect_coverage(
+			local_x as u32,
+			local_y as u32,
+			shadow_width,
+			shadow_height,
+			radius / scale,
+		);
+		let inside = (local_x as u32) < shadow_width && (local_y as u32) < shadow_height;
+
+		Luma([if inside { (coverage * 255.0).round() as u8 } else { 0 }])
+	});
+	let sigma = (padding as f32 / 3.0 / scale).max(1.0);
+	let mask = imageops::blur(&mask, sigma);
+	let mask = imageops::resize(&mask, canvas.width(), canvas.height(), FilterType::Triangle);
+
+	for (pixel, shade) in canvas.pixels_mut().zip(mask.pixels()) {
+		let alpha = f32::from(shade[0]) / 255.0 * SHADOW_OPACITY;
+
+		for channel in 0..3 {
+			pixel[channel] = lerp_u8(pixel[channel], 0, alpha);
+		}
+	}
+}
+
+/// Anti-aliased coverage of pixel `(x, y)` inside a `width`×`height` rect with rounded corners.
+fn rounded_rect_coverage(x: u32, y: u32, width: u32, height: u32, radius: f32) -> f32 {
+	if radius <= 0.0 {
+		return 1.0;
+	}
+
+	let px = x as f32 + 0.5;
+	let py = y as f32 + 0.5;
+	let cx = px.clamp(radius, (width as f32 - radius).max(radius));
+	let cy = py.clamp(radius, (height as f32 - radius).max(radius));
+	let distance = (px - cx).hypot(py - cy);
+
+	(radius - distance + 0.5).clamp(0.0, 1.0)
+}
+
+fn lerp_u8(from: u8, to: u8, t: f32) -> u8 {
+	(f32::from(from) + (f32::from(to) - f32::from(from)) * t).round().clamp(0.0, 255.0) as u8
+}
+
+fn opaque(rgb: [u8; 3]) -> Rgba<u8> {
+	Rgba([rgb[0], rgb[1], rgb[2], 255])
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::backdrop::{self, BackdropJob};
+	use crate::overlay::{BackdropFill, ExportBackdrop};
+
+	#[test]
+	fn backdrop_pads_and_rounds_the_capture() {
+		let red = Rgba([255, 0, 0, 255]);
+		let blue = Rgba([0, 0, 255, 255]);
+		let image = RgbaImage::from_pixel(20, 10, red);
+		let job = BackdropJob {
+			backdrop: ExportBackdrop {
+				fill: BackdropFill::Solid,
+				primary_rgb: [0, 0, 255],
+				padding_px: 8,
+				corner_radius_px: 4,
+				shadow: false,
+				..ExportBackdrop::default()
+			},
+			blur_source: None,
+		};
+		let composed = backdrop::compose_backdrop(&image, &job);
+
+		assert_eq!(composed.dimensions(), (36, 26));
+		assert_eq!(*composed.get_pixel(0, 0), blue);
+		assert_eq!(*composed.get_pixel(18, 13), red);
+		// The capture's own corner pixel is cut away by the radius.
+		assert_eq!(*composed.get_pixel(8, 8), blue);
+		assert_eq!(*composed.get_pixel(12, 8), red);
+	}
+
+	#[test]
+	fn blur_backdrop_without_source_falls_back_to_gradient() {
+		let image = RgbaImage::from_pixel(4, 4, Rgba([255, 255, 255, 255]));
+		let job = BackdropJob {
+			backdrop: ExportBackdrop {
+				fill: BackdropFill::Blur,
+				primary_rgb: [0, 0, 0],
+				secondary_rgb: [200, 200, 200],
+				padding_px: 4,
+				shadow: false,
+				..ExportBackdrop::default()
+			},
+			blur_source: None,
+		};
+		let composed = backdrop::compose_backdrop(&image, &job);
+
+		assert_eq!(*composed.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+		assert_eq!(*composed.get_pixel(11, 11), Rgba([200, 200, 200, 255]));
+	}
+}
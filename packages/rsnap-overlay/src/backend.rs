@@ -3,11 +3,10 @@
 	reason = "XY-113 narrows the public crate facade while leaving backend implementation cleanup to a separate follow-up lane."
 )]
 
-#[cfg(target_os = "macos")]
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 #[cfg(target_os = "macos")]
 use std::ffi::{CString, c_char, c_void};
-#[cfg(not(target_os = "macos"))]
+#[cfg(any(target_os = "macos", target_os = "windows"))]
 use std::process;
 #[cfg(target_os = "macos")]
 use std::ptr;
@@ -23,18 +22,26 @@ use image::imageops;
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 #[cfg(target_os = "macos")]
 use objc2_core_graphics::{
-	CGDataProvider, CGImage, CGRectNull, CGWindowID, CGWindowImageOption, CGWindowListOption,
+	CGDataProvider, CGError, CGImage, CGImageAlphaInfo, CGImageByteOrderInfo, CGRectNull,
+	CGWindowID, CGWindowImageOption, CGWindowListOption,
 };
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-#[cfg(not(target_os = "macos"))]
+#[cfg(target_os = "windows")]
 use xcap::Window;
 
 #[cfg(target_os = "macos")]
 use crate::live_frame_stream_macos::MacLiveFrameStream;
+#[cfg(target_os = "linux")]
+use crate::portal_screenshot_linux::PortalScreenshotCaptureBackend;
 use crate::state::{
-	GlobalPoint, LiveCursorSample, MonitorImageSnapshot, MonitorRect, RectPoints, Rgb, WindowHit,
-	WindowListSnapshot, WindowRect,
+	CursorImage, DisplayTransfer, GlobalPoint, LiveCursorSample, MonitorImageSnapshot, MonitorRect,
+	RectPoints, Rgb, WindowHit, WindowLabel, WindowListSnapshot, WindowRect,
 };
+#[cfg(target_os = "linux")]
+use crate::x11_capture_linux::{self, X11Capture};
+
+type WindowLabels = HashMap<u32, WindowLabel>;
 
 #[cfg(target_os = "macos")]
 type CFBooleanRef = *const c_void;
@@ -112,6 +119,20 @@ pub trait CaptureBackend: Send {
 		point: GlobalPoint,
 	) -> Result<Option<Rgb>>;
 
+	/// Checks whether the target monitor can be captured at all right now.
+	///
+	/// Displays can refuse capture individually (for example protected external outputs), so the
+	/// overlay probes each monitor at session start instead of failing at freeze time. A sample
+	/// that yields no color is treated as inconclusive rather than unavailable.
+	fn probe_monitor_capture(&mut self, monitor: MonitorRect) -> Result<()> {
+		let center = GlobalPoint::new(
+			monitor.origin.x.saturating_add_unsigned(monitor.width / 2),
+			monitor.origin.y.saturating_add_unsigned(monitor.height / 2),
+		);
+
+		self.pixel_rgb_in_monitor(monitor, center).map(|_| ())
+	}
+
 	/// Samples the live cursor color and optional loupe patch from the target monitor.
 	fn live_sample_cursor(
 		&mut self,
@@ -145,6 +166,11 @@ pub trait CaptureBackend: Send {
 		Err(CaptureBackendError::NotSupported { backend: "capture backend" }.into())
 	}
 
+	/// Captures a single window including its native drop shadow, where the platform draws one.
+	fn capture_window_with_shadow(&mut self, window_id: u32) -> Result<RgbaImage> {
+		self.capture_window(window_id)
+	}
+
 	/// Samples an RGBA patch around a global point on the target monitor.
 	fn rgba_patch_in_monitor(
 		&mut self,
@@ -184,7 +210,7 @@ pub enum CaptureBackendError {
 	#[error("screen capture is not supported on this platform (backend: {backend})")]
 	NotSupported { backend: &'static str },
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "windows")]
 	#[error("no monitor matched rect: {monitor:?}")]
 	MonitorNotFound { monitor: MonitorRect },
 
@@ -192,6 +218,105 @@ pub enum CaptureBackendError {
 	WindowNotFound { window_id: u32 },
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Capture backend requested in settings.
+pub enum CaptureBackendKind {
+	#[default]
+	/// Use the native backend for this platform.
+	Auto,
+	/// ScreenCaptureKit stream for live sampling and CoreGraphics for freezes (macOS).
+	Sck,
+	/// Windows.Graphics.Capture (Windows). Reserved; not built yet.
+	Wgc,
+	/// PipeWire screencast streams (Linux). Reserved; not built yet.
+	Pipewire,
+	/// Direct X11 capture through MIT-SHM and XFixes, with the screenshot portal as the freeze
+	/// fallback (Linux).
+	X11,
+	/// The xcap crate (Windows).
+	Xcap,
+	/// Captures nothing; every request reports unsupported.
+	Stub,
+}
+impl CaptureBackendKind {
+	/// Every selectable backend, `Auto` first.
+	pub const ALL: [Self; 7] =
+		[Self::Auto, Self::Sck, Self::Wgc, Self::Pipewire, Self::X11, Self::Xcap, Self::Stub];
+
+	#[must_use]
+	/// The settings and command-line name of the backend.
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Auto => "auto",
+			Self::Sck => "sck",
+			Self::Wgc => "wgc",
+			Self::Pipewire => "pipewire",
+			Self::X11 => "x11",
+			Self::Xcap => "xcap",
+			Self::Stub => "stub",
+		}
+	}
+
+	#[must_use]
+	/// The backend `Auto` selects on this platform.
+	pub const fn native() -> Self {
+		if cfg!(target_os = "macos") {
+			Self::Sck
+		} else if cfg!(target_os = "linux") {
+			Self::X11
+		} else {
+			Self::Xcap
+		}
+	}
+
+	#[must_use]
+	/// Why this backend cannot be used in this build, or `None` when it can.
+	pub const fn unsupported_reason(self) -> Option<&'static str> {
+		match self {
+			Self::Auto | Self::Stub => None,
+			Self::Wgc | Self::Pipewire => Some("not built in this version"),
+			Self::Sck if !cfg!(target_os = "macos") => Some("macOS only"),
+			Self::X11 if !cfg!(target_os = "linux") => Some("Linux only"),
+			Self::Xcap if !cfg!(target_os = "windows") => Some("Windows only"),
+			Self::Sck | Self::X11 | Self::Xcap => None,
+		}
+	}
+
+	#[must_use]
+	/// The backend that actually runs: `Auto` and unsupported selections fall back to the
+	/// native one.
+	pub const fn resolve(self) -> Self {
+		match self {
+			Self::Auto => Self::native(),
+			kind if kind.unsupported_reason().is_some() => Self::native(),
+			kind => kind,
+		}
+	}
+
+	#[must_use]
+	/// Describes where live sampling reads pixels from once resolved.
+	pub const fn live_path(self) -> &'static str {
+		match self.resolve() {
+			Self::Sck => "ScreenCaptureKit stream",
+			Self::X11 => "X11 MIT-SHM",
+			Self::Xcap => "xcap monitor capture",
+			_ => "none",
+		}
+	}
+
+	#[must_use]
+	/// Describes where freeze and export captures come from once resolved.
+	pub const fn freeze_path(self) -> &'static str {
+		match self.resolve() {
+			Self::Sck => "CoreGraphics display capture",
+			Self::X11 => "X11 MIT-SHM, screenshot portal when X11 is unavailable",
+			Self::Xcap => "xcap region capture",
+			_ => "none",
+		}
+	}
+}
+
 /// No-op backend used by tests and unsupported-code paths.
 pub struct StubCaptureBackend {}
 impl StubCaptureBackend {
@@ -263,6 +388,216 @@ impl CaptureBackend for StubCaptureBackend {
 	}
 }
 
+/// Deterministic backend that serves scripted frames, used to drive the overlay without a display.
+///
+/// Each monitor replays its queued frames in order for full and region captures, repeating the
+/// last one once the queue drains. Monitors without a scripted frame serve
+/// [`ScriptedCaptureBackend::synthetic_frame`]. Pixel and patch samples read the frame the next
+/// capture would return, so the loupe and the freeze agree.
+#[derive(Default)]
+pub struct ScriptedCaptureBackend {
+	frames: HashMap<u32, VecDeque<RgbaImage>>,
+	windows: Vec<WindowRect>,
+	window_images: HashMap<u32, RgbaImage>,
+	window_labels: HashMap<u32, WindowLabel>,
+	cursor: Option<GlobalPoint>,
+	cache: Option<Arc<MonitorImageSnapshot>>,
+	window_cache: Option<Arc<WindowListSnapshot>>,
+}
+impl ScriptedCaptureBackend {
+	#[must_use]
+	/// Creates a backend that serves synthetic frames for every monitor.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[must_use]
+	/// Returns the synthetic frame served for monitors without scripted frames.
+	///
+	/// Red and green encode the pixel column and row (modulo 256), so a sample's color identifies
+	/// where it was taken.
+	pub fn synthetic_frame(width_px: u32, height_px: u32) -> RgbaImage {
+		RgbaImage::from_fn(width_px.max(1), height_px.max(1), |x, y| {
+			image::Rgba([(x % 256) as u8, (y % 256) as u8, ((x ^ y) % 256) as u8, 255])
+		})
+	}
+
+	#[must_use]
+	/// Queues `frame` for `monitor_id` after any frames queued before it.
+	pub fn with_frame(mut self, monitor_id: u32, frame: RgbaImage) -> Self {
+		self.frames.entry(monitor_id).or_default().push_back(frame);
+
+		self
+	}
+
+	#[must_use]
+	/// Adds a window for hit testing, topmost first, captured as `image`.
+	pub fn with_window(mut self, window: WindowRect, image: RgbaImage) -> Self {
+		if let Some(window_id) = window.window_id {
+			self.window_images.insert(window_id, image);
+		}
+
+		self.windows.push(window);
+
+		self
+	}
+
+	#[must_use]
+	/// Reports `label` as the owner and title of window `window_id`.
+	pub fn with_window_label(mut self, window_id: u32, label: WindowLabel) -> Self {
+		self.window_labels.insert(window_id, label);
+
+		self
+	}
+
+	#[must_use]
+	/// Sets the position reported as the global cursor.
+	pub fn with_cursor(mut self, cursor: GlobalPoint) -> Self {
+		self.cursor = Some(cursor);
+
+		self
+	}
+
+	fn current_frame(&self, monitor: MonitorRect) -> RgbaImage {
+		match self.frames.get(&monitor.id).and_then(VecDeque::front) {
+			Some(frame) => frame.clone(),
+			None => Self::synthetic_frame(
+				point_extent_to_pixel_extent(monitor.width, monitor.scale_factor()),
+				point_extent_to_pixel_extent(monitor.height, monitor.scale_factor()),
+			),
+		}
+	}
+
+	fn next_frame(&mut self, monitor: MonitorRect) -> RgbaImage {
+		let frame = self.current_frame(monitor);
+
+		if let Some(queue) = self.frames.get_mut(&monitor.id)
+			&& queue.len() > 1
+		{
+			queue.pop_front();
+		}
+
+		frame
+	}
+}
+
+impl CaptureBackend for ScriptedCaptureBackend {
+	fn global_cursor_position(&mut self) -> Result<Option<GlobalPoint>> {
+		Ok(self.cursor)
+	}
+
+	fn capture_monitor(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
+		Ok(self.next_frame(monitor))
+	}
+
+	fn capture_monitor_region(
+		&mut self,
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+	) -> Result<RgbaImage> {
+		crop_monitor_image_region(&self.next_frame(monitor), rect_px)
+	}
+
+	fn pixel_rgb_in_monitor(
+		&mut self,
+		monitor: MonitorRect,
+		point: GlobalPoint,
+	) -> Result<Option<Rgb>> {
+		let Some((x, y)) = monitor.local_u32_pixels(point) else {
+			return Ok(None);
+		};
+		let frame = self.current_frame(monitor);
+
+		Ok(frame.get_pixel_checked(x, y).map(|pixel| Rgb::new(pixel.0[0], pixel.0[1], pixel.0[2])))
+	}
+
+	fn rgba_patch_in_monitor(
+		&mut self,
+		monitor: MonitorRect,
+		point: GlobalPoint,
+		width_px: u32,
+		height_px: u32,
+	) -> Result<Option<RgbaImage>> {
+		let Some((center_x, center_y)) = monitor.local_u32_pixels(point) else {
+			return Ok(None);
+		};
+
+		Ok(Some(copy_rgba_patch(
+			&self.current_frame(monitor),
+			center_x,
+			center_y,
+			width_px,
+			height_px,
+		)))
+	}
+
+	fn hit_test_window_in_monitor(
+		&mut self,
+		monitor: MonitorRect,
+		point: GlobalPoint,
+	) -> Result<Option<WindowHit>> {
+		let Some((local_x, local_y)) = monitor.local_u32(point) else {
+			return Ok(None);
+		};
+
+		for window in &self.windows {
+			let Some(window_rect) = monitor.clip_global_rect_i64(
+				window.x,
+				window.y,
+				window.x.saturating_add(window.width),
+				window.y.saturating_add(window.height),
+			) else {
+				continue;
+			};
+
+			if window_rect.contains((local_x, local_y)) {
+				return Ok(Some(WindowHit { window_id: window.window_id, rect: window_rect }));
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn capture_window(&mut self, window_id: u32) -> Result<RgbaImage> {
+		self.window_images
+			.get(&window_id)
+			.cloned()
+			.ok_or_else(|| CaptureBackendError::WindowNotFound { window_id }.into())
+	}
+
+	fn refresh_monitor_cache(&mut self, monitor: MonitorRect) -> Result<Arc<MonitorImageSnapshot>> {
+		let snapshot = Arc::new(MonitorImageSnapshot {
+			captured_at: Instant::now(),
+			monitor,
+			image: Arc::new(self.current_frame(monitor)),
+		});
+
+		self.cache = Some(Arc::clone(&snapshot));
+
+		Ok(snapshot)
+	}
+
+	fn latest_monitor_cache_snapshot(&self) -> Option<Arc<MonitorImageSnapshot>> {
+		self.cache.clone()
+	}
+
+	fn refresh_window_cache(&mut self) -> Result<Arc<WindowListSnapshot>> {
+		let snapshot = Arc::new(WindowListSnapshot {
+			captured_at: Instant::now(),
+			windows: Arc::new(self.windows.clone()),
+			labels: Arc::new(self.window_labels.clone()),
+		});
+
+		self.window_cache = Some(Arc::clone(&snapshot));
+
+		Ok(snapshot)
+	}
+
+	fn latest_window_cache_snapshot(&self) -> Option<Arc<WindowListSnapshot>> {
+		self.window_cache.clone()
+	}
+}
+
 /// Production backend that captures monitors and windows through the native platform stack.
 pub struct XcapCaptureBackend {
 	cache: Option<Arc<MonitorImageSnapshot>>,
@@ -273,6 +608,14 @@ pub struct XcapCaptureBackend {
 	live_frame_stream: MacLiveFrameStream,
 	#[cfg(target_os = "macos")]
 	last_region_capture: HashMap<u32, MacosRegionCaptureState>,
+	#[cfg(target_os = "linux")]
+	x11: Option<X11Capture>,
+	#[cfg(target_os = "linux")]
+	portal_fallback: PortalScreenshotCaptureBackend,
+	/// Live sampling reads only the pixels under the loupe until a direct region capture fails,
+	/// then falls back to the cached full-monitor capture.
+	#[cfg(not(target_os = "macos"))]
+	direct_sampling: bool,
 }
 impl XcapCaptureBackend {
 	#[must_use]
@@ -287,7 +630,23 @@ impl XcapCaptureBackend {
 			live_frame_stream: MacLiveFrameStream::new(),
 			#[cfg(target_os = "macos")]
 			last_region_capture: HashMap::new(),
+			#[cfg(target_os = "linux")]
+			x11: None,
+			#[cfg(target_os = "linux")]
+			portal_fallback: PortalScreenshotCaptureBackend::new(),
+			#[cfg(not(target_os = "macos"))]
+			direct_sampling: true,
+		}
+	}
+
+	/// Returns the X11 connection, connecting on first use and again after a failed attempt.
+	#[cfg(target_os = "linux")]
+	fn x11(&mut self) -> Result<&mut X11Capture> {
+		if self.x11.is_none() {
+			self.x11 = Some(X11Capture::connect()?);
 		}
+
+		self.x11.as_mut().ok_or_else(|| eyre::eyre!("missing X11 connection"))
 	}
 
 	fn cache_valid_for(&self, monitor: MonitorRect) -> bool {
@@ -350,35 +709,30 @@ impl XcapCaptureBackend {
 			.wrap_err_with(|| format!("failed to decode display image for monitor: {monitor:?}"))
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "linux")]
+	fn capture_monitor_image(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
+		self.x11()?.capture_monitor(monitor)
+	}
+
+	#[cfg(target_os = "windows")]
 	fn capture_monitor_image(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
 		capture_monitor_image(monitor)
 	}
 
 	#[cfg(target_os = "macos")]
-	#[expect(
-		deprecated,
-		reason = "CoreGraphics window capture remains the verified macOS fallback until XY-74/XY-75 replace this path."
-	)]
 	fn capture_window_image(&mut self, window_id: u32) -> Result<RgbaImage> {
-		let cg_rect: CGRect = unsafe { CGRectNull };
-		let image_option =
-			CGWindowImageOption::BoundsIgnoreFraming | CGWindowImageOption::BestResolution;
-		let cg_image = objc2_core_graphics::CGWindowListCreateImage(
-			cg_rect,
-			CGWindowListOption::OptionIncludingWindow,
-			window_id as CGWindowID,
-			image_option,
-		);
-		let Some(cg_image) = cg_image.as_deref() else {
-			return Err(CaptureBackendError::WindowNotFound { window_id }.into());
-		};
+		capture_window_image_with_options(
+			window_id,
+			CGWindowImageOption::BoundsIgnoreFraming | CGWindowImageOption::BestResolution,
+		)
+	}
 
-		rgba_image_from_cg_image(cg_image)
-			.wrap_err_with(|| format!("Failed to decode window capture bytes: {window_id}"))
+	#[cfg(target_os = "linux")]
+	fn capture_window_image(&mut self, window_id: u32) -> Result<RgbaImage> {
+		self.x11()?.capture_window(window_id)
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "windows")]
 	fn capture_window_image(&mut self, window_id: u32) -> Result<RgbaImage> {
 		let windows = Window::all().wrap_err("xcap Window::all failed")?;
 
@@ -395,7 +749,7 @@ impl XcapCaptureBackend {
 		Err(CaptureBackendError::WindowNotFound { window_id }.into())
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "windows")]
 	fn capture_monitor_region_with_xcap(
 		&mut self,
 		monitor: MonitorRect,
@@ -419,7 +773,7 @@ impl XcapCaptureBackend {
 		Ok(image)
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "windows")]
 	fn crop_monitor_region_fallback(
 		&mut self,
 		monitor: MonitorRect,
@@ -430,6 +784,118 @@ impl XcapCaptureBackend {
 		crop_monitor_image_region(&image, rect_px)
 	}
 
+	/// Captures only the requested pixels of the root window, clamped to the monitor.
+	#[cfg(target_os = "linux")]
+	fn capture_monitor_region_direct(
+		&mut self,
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+	) -> Result<RgbaImage> {
+		let (origin_x, origin_y) = monitor.scaled_origin_px();
+		let (monitor_width, monitor_height) = monitor.size_px();
+		let width = rect_px.width.max(1).min(monitor_width.max(1));
+		let height = rect_px.height.max(1).min(monitor_height.max(1));
+		let x = rect_px.x.min(monitor_width.saturating_sub(width));
+		let y = rect_px.y.min(monitor_height.saturating_sub(height));
+
+		self.x11()?.capture_root_region(
+			origin_x + i64::from(x),
+			origin_y + i64::from(y),
+			width,
+			height,
+		)
+	}
+
+	#[cfg(target_os = "windows")]
+	fn capture_monitor_region_direct(
+		&mut self,
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+	) -> Result<RgbaImage> {
+		// TODO(system-api): replace xcap-based monitor region capture with a native per-platform path.
+		if let Ok(image) = self.capture_monitor_region_with_xcap(
+			monitor,
+			rect_px.x,
+			rect_px.y,
+			rect_px.width,
+			rect_px.height,
+		) {
+			return Ok(image);
+		}
+
+		self.crop_monitor_region_fallback(monitor, rect_px)
+	}
+
+	/// Reads a patch centered on a monitor pixel straight from the screen instead of through the
+	/// monitor cache. The part beyond the monitor edge stays transparent, as in `copy_rgba_patch`.
+	#[cfg(not(target_os = "macos"))]
+	fn sample_rgba_patch_direct(
+		&mut self,
+		monitor: MonitorRect,
+		center_x: u32,
+		center_y: u32,
+		width_px: u32,
+		height_px: u32,
+	) -> Option<RgbaImage> {
+		if !self.direct_sampling {
+			return None;
+		}
+
+		let (rect_px, offset_x, offset_y) =
+			visible_patch_rect_px(monitor.size_px(), center_x, center_y, width_px, height_px)?;
+		#[cfg(target_os = "linux")]
+		let region = self.capture_monitor_region_direct(monitor, rect_px);
+		// Unlike freeze capture, sampling never falls back to a full-monitor crop per tick.
+		#[cfg(target_os = "windows")]
+		let region = self.capture_monitor_region_with_xcap(
+			monitor,
+			rect_px.x,
+			rect_px.y,
+			rect_px.width,
+			rect_px.height,
+		);
+
+		match region {
+			Ok(region) => {
+				let mut patch = RgbaImage::new(width_px.max(1), height_px.max(1));
+
+				imageops::replace(&mut patch, &region, i64::from(offset_x), i64::from(offset_y));
+
+				Some(patch)
+			},
+			Err(err) => {
+				self.direct_sampling = false;
+
+				tracing::warn!(
+					op = "capture_backend.direct_sampling_disabled",
+					error = %format!("{err:#}"),
+					"Direct region sampling failed; sampling from the cached monitor capture."
+				);
+
+				None
+			},
+		}
+	}
+
+	/// Retries a failed freeze capture through the screenshot portal. The portal prompts the user,
+	/// so live sampling and scroll capture never take this path.
+	#[cfg(target_os = "linux")]
+	fn capture_with_portal_fallback(
+		&mut self,
+		err: eyre::Report,
+		capture: impl FnOnce(&mut PortalScreenshotCaptureBackend) -> Result<RgbaImage>,
+	) -> Result<RgbaImage> {
+		tracing::warn!(
+			op = "capture_backend.portal_fallback",
+			error = %format!("{err:#}"),
+			"Direct capture failed; requesting a screenshot through the desktop portal."
+		);
+
+		capture(&mut self.portal_fallback).map_err(|portal_err| {
+			err.wrap_err(format!("screenshot portal fallback failed: {portal_err:#}"))
+		})
+	}
+
 	#[cfg(target_os = "macos")]
 	fn capture_monitor_region_with_system_apis(
 		&mut self,
@@ -580,10 +1046,12 @@ impl XcapCaptureBackend {
 	}
 
 	fn refresh_window_cache_impl(&mut self) -> Result<Arc<WindowListSnapshot>> {
-		let windows = collect_window_geometries().wrap_err("failed to refresh window cache")?;
+		let (windows, labels) =
+			self.collect_window_geometries().wrap_err("failed to refresh window cache")?;
 		let snapshot = Arc::new(WindowListSnapshot {
 			captured_at: Instant::now(),
 			windows: Arc::new(windows),
+			labels: Arc::new(labels),
 		});
 
 		self.window_cache = Some(snapshot.clone());
@@ -594,6 +1062,16 @@ impl XcapCaptureBackend {
 	fn latest_window_cache_snapshot_impl(&self) -> Option<Arc<WindowListSnapshot>> {
 		self.window_cache.clone()
 	}
+
+	#[cfg(target_os = "linux")]
+	fn collect_window_geometries(&mut self) -> Result<(Vec<WindowRect>, WindowLabels)> {
+		self.x11()?.window_geometries_with_labels()
+	}
+
+	#[cfg(not(target_os = "linux"))]
+	fn collect_window_geometries(&mut self) -> Result<(Vec<WindowRect>, WindowLabels)> {
+		collect_window_geometries()
+	}
 }
 
 impl Default for XcapCaptureBackend {
@@ -603,6 +1081,15 @@ impl Default for XcapCaptureBackend {
 }
 
 impl CaptureBackend for XcapCaptureBackend {
+	#[cfg(target_os = "linux")]
+	fn global_cursor_position(&mut self) -> Result<Option<GlobalPoint>> {
+		let Ok(x11) = self.x11() else {
+			return Ok(None);
+		};
+
+		x11.cursor_position().map(Some)
+	}
+
 	fn capture_monitor_region(
 		&mut self,
 		monitor: MonitorRect,
@@ -620,19 +1107,16 @@ impl CaptureBackend for XcapCaptureBackend {
 		}
 
 		#[cfg(not(target_os = "macos"))]
-		// TODO(system-api): replace xcap-based monitor region capture with a native per-platform path.
-		if let Ok(image) = self.capture_monitor_region_with_xcap(
-			monitor,
-			rect_px.x,
-			rect_px.y,
-			rect_px.width,
-			rect_px.height,
-		) {
-			return Ok(image);
-		}
+		let image = self.capture_monitor_region_direct(monitor, rect_px);
+		#[cfg(target_os = "linux")]
+		let image = image.or_else(|err| {
+			self.capture_with_portal_fallback(err, |portal| {
+				portal.capture_monitor_region(monitor, rect_px)
+			})
+		});
 
 		#[cfg(not(target_os = "macos"))]
-		self.crop_monitor_region_fallback(monitor, rect_px).wrap_err_with(|| {
+		image.wrap_err_with(|| {
 			format!(
 				"failed to capture monitor region for freeze/export: {monitor:?} rect={rect_px:?}"
 			)
@@ -657,7 +1141,13 @@ impl CaptureBackend for XcapCaptureBackend {
 		}
 		#[cfg(not(target_os = "macos"))]
 		{
-			self.capture_monitor_region(monitor, rect_px).map(Some)
+			self.capture_monitor_region_direct(monitor, rect_px)
+				.wrap_err_with(|| {
+					format!(
+						"failed to capture fresh monitor region for scroll capture: {monitor:?} rect={rect_px:?}"
+					)
+				})
+				.map(Some)
 		}
 	}
 
@@ -716,7 +1206,12 @@ impl CaptureBackend for XcapCaptureBackend {
 	}
 
 	fn capture_monitor(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
-		let image = self.capture_monitor_image(monitor).wrap_err_with(|| {
+		let image = self.capture_monitor_image(monitor);
+		#[cfg(target_os = "linux")]
+		let image = image.or_else(|err| {
+			self.capture_with_portal_fallback(err, |portal| portal.capture_monitor(monitor))
+		});
+		let image = image.wrap_err_with(|| {
 			format!("failed to capture monitor for freeze/export: {monitor:?}")
 		})?;
 
@@ -734,6 +1229,12 @@ impl CaptureBackend for XcapCaptureBackend {
 			.wrap_err_with(|| format!("failed to capture window for freeze/export: {window_id}"))
 	}
 
+	#[cfg(target_os = "macos")]
+	fn capture_window_with_shadow(&mut self, window_id: u32) -> Result<RgbaImage> {
+		capture_window_image_with_options(window_id, CGWindowImageOption::BestResolution)
+			.wrap_err_with(|| format!("failed to capture shadowed window for export: {window_id}"))
+	}
+
 	fn pixel_rgb_in_monitor(
 		&mut self,
 		monitor: MonitorRect,
@@ -770,7 +1271,9 @@ impl CaptureBackend for XcapCaptureBackend {
 				}
 			}
 			#[cfg(not(target_os = "macos"))]
-			{
+			if let Some(patch) = self.sample_rgba_patch_direct(monitor, x, y, 1, 1) {
+				patch
+			} else {
 				self.ensure_cache(monitor)?;
 
 				let Some(cache) = self.cache.as_ref() else {
@@ -816,12 +1319,21 @@ impl CaptureBackend for XcapCaptureBackend {
 		}
 		#[cfg(not(target_os = "macos"))]
 		{
-			let rgb = self.pixel_rgb_in_monitor(monitor, point)?;
-			let patch = if want_patch {
-				self.rgba_patch_in_monitor(monitor, point, patch_width_px, patch_height_px)?
-			} else {
-				None
-			};
+			if !want_patch {
+				return Ok(LiveCursorSample {
+					rgb: self.pixel_rgb_in_monitor(monitor, point)?,
+					patch: None,
+				});
+			}
+
+			// One region read serves both: the patch's center pixel is the one under the cursor.
+			let patch =
+				self.rgba_patch_in_monitor(monitor, point, patch_width_px, patch_height_px)?;
+			let rgb = patch.as_ref().and_then(|patch| {
+				patch
+					.get_pixel_checked(patch.width() / 2, patch.height() / 2)
+					.map(|pixel| Rgb::new(pixel.0[0], pixel.0[1], pixel.0[2]))
+			});
 
 			Ok(LiveCursorSample { rgb, patch })
 		}
@@ -853,17 +1365,8 @@ impl CaptureBackend for XcapCaptureBackend {
 		let patch = {
 			#[cfg(target_os = "macos")]
 			{
-				let monitor_width =
-					point_extent_to_pixel_extent(monitor.width, monitor.scale_factor());
-				let monitor_height =
-					point_extent_to_pixel_extent(monitor.height, monitor.scale_factor());
-				let width = width_px.max(1).min(monitor_width.max(1));
-				let height = height_px.max(1).min(monitor_height.max(1));
-				let region_x =
-					center_x.saturating_sub(width / 2).min(monitor_width.saturating_sub(width));
-				let region_y =
-					center_y.saturating_sub(height / 2).min(monitor_height.saturating_sub(height));
-				let rect_px = RectPoints::new(region_x, region_y, width, height);
+				let rect_px =
+					centered_patch_rect_px(monitor, center_x, center_y, width_px, height_px);
 
 				match capture_monitor_region_with_core_graphics(monitor, rect_px) {
 					Ok(patch) => patch,
@@ -874,12 +1377,22 @@ impl CaptureBackend for XcapCaptureBackend {
 							return Ok(None);
 						};
 
-						copy_rgba_patch(&cache.image, center_x, center_y, width, height)
+						copy_rgba_patch(
+							&cache.image,
+							center_x,
+							center_y,
+							rect_px.width,
+							rect_px.height,
+						)
 					},
 				}
 			}
 			#[cfg(not(target_os = "macos"))]
+			if let Some(patch) =
+				self.sample_rgba_patch_direct(monitor, center_x, center_y, width_px, height_px)
 			{
+				patch
+			} else {
 				self.ensure_cache(monitor)?;
 
 				let Some(cache) = self.cache.as_ref() else {
@@ -913,9 +1426,22 @@ impl Drop for MacWindowListRefGuard {
 }
 
 #[must_use]
-/// Builds the default capture backend used by overlay worker threads.
-pub fn default_capture_backend() -> Box<dyn CaptureBackend> {
-	Box::new(XcapCaptureBackend::new())
+/// Builds the capture backend used by overlay worker threads for the selected kind.
+pub fn capture_backend(kind: CaptureBackendKind) -> Box<dyn CaptureBackend> {
+	if let Some(reason) = kind.unsupported_reason() {
+		tracing::warn!(
+			op = "capture_backend.select",
+			requested = kind.name(),
+			fallback = kind.resolve().name(),
+			reason,
+			"Requested capture backend is unavailable; using the native backend."
+		);
+	}
+
+	match kind.resolve() {
+		CaptureBackendKind::Stub => Box::new(StubCaptureBackend::new()),
+		_ => Box::new(XcapCaptureBackend::new()),
+	}
 }
 
 #[cfg(target_os = "macos")]
@@ -976,6 +1502,264 @@ fn capture_monitor_region_with_core_graphics(
 		.wrap_err("failed to crop full-monitor fallback to requested rect")
 }
 
+/// Samples the cursor color (and optionally the loupe patch) from the display's composited output.
+///
+/// The ScreenCaptureKit stream only sees window content, while this reads back what the window
+/// server composited for the display, so other apps' overlay windows such as cursor highlighters
+/// are included. rsnap's own windows opt out of sharing and stay excluded. Color adjustments
+/// applied after composition (Night Shift, f.lux, display color profiles) are not visible to any
+/// capture API and are therefore not reflected by either path.
+#[cfg(target_os = "macos")]
+pub(crate) fn sample_composited_cursor(
+	monitor: MonitorRect,
+	x_px: u32,
+	y_px: u32,
+	want_patch: bool,
+	patch_width_px: u32,
+	patch_height_px: u32,
+) -> Result<LiveCursorSample> {
+	let (width_px, height_px) = if want_patch { (patch_width_px, patch_height_px) } else { (1, 1) };
+	let rect_px = centered_patch_rect_px(monitor, x_px, y_px, width_px, height_px);
+	let region = capture_monitor_region_with_core_graphics(monitor, rect_px)
+		.wrap_err("failed to sample composited display output")?;
+	let rgb = region
+		.get_pixel_checked(x_px.saturating_sub(rect_px.x), y_px.saturating_sub(rect_px.y))
+		.map(|pixel| Rgb::new(pixel.0[0], pixel.0[1], pixel.0[2]));
+
+	Ok(LiveCursorSample { rgb, patch: want_patch.then_some(region) })
+}
+
+/// Reads the display's current transfer (gamma) table.
+///
+/// Tools that tint the screen through this table (f.lux, calibration utilities) are visible here.
+/// Night Shift on recent macOS releases is applied elsewhere in the display pipeline and may leave
+/// the table untouched.
+#[cfg(target_os = "macos")]
+pub(crate) fn display_transfer(display_id: u32) -> Option<DisplayTransfer> {
+	const CAPACITY: u32 = 256;
+
+	let mut red = vec![0.0; CAPACITY as usize];
+	let mut green = vec![0.0; CAPACITY as usize];
+	let mut blue = vec![0.0; CAPACITY as usize];
+	let mut sample_count = 0;
+	let err = unsafe {
+		objc2_core_graphics::CGGetDisplayTransferByTable(
+			display_id,
+			CAPACITY,
+			red.as_mut_ptr(),
+			green.as_mut_ptr(),
+			blue.as_mut_ptr(),
+			&mut sample_count,
+		)
+	};
+
+	if err != objc2_core_graphics::CGError::Success || sample_count < 2 {
+		return None;
+	}
+
+	let sample_count = sample_count.min(CAPACITY) as usize;
+
+	red.truncate(sample_count);
+	green.truncate(sample_count);
+	blue.truncate(sample_count);
+
+	Some(DisplayTransfer { red, green, blue })
+}
+
+/// Captures one window; without `BoundsIgnoreFraming` the image includes the drop shadow.
+#[cfg(target_os = "macos")]
+#[expect(
+	deprecated,
+	reason = "CoreGraphics window capture remains the verified macOS fallback until XY-74/XY-75 replace this path."
+)]
+fn capture_window_image_with_options(
+	window_id: u32,
+	image_option: CGWindowImageOption,
+) -> Result<RgbaImage> {
+	let cg_rect: CGRect = unsafe { CGRectNull };
+	let cg_image = objc2_core_graphics::CGWindowListCreateImage(
+		cg_rect,
+		CGWindowListOption::OptionIncludingWindow,
+		window_id as CGWindowID,
+		image_option,
+	);
+	let Some(cg_image) = cg_image.as_deref() else {
+		return Err(CaptureBackendError::WindowNotFound { window_id }.into());
+	};
+
+	rgba_image_from_cg_image(cg_image)
+		.wrap_err_with(|| format!("Failed to decode window capture bytes: {window_id}"))
+}
+
+/// Decodes an arbitrary 8-bit RGBA-family `CGImage` (any alpha placement, byte order, or
+/// premultiplication) into straight-alpha RGBA.
+#[cfg(target_os = "macos")]
+fn straight_rgba_image_from_cg_image(cg_image: &CGImage) -> Result<RgbaImage> {
+	let width = CGImage::width(Some(cg_image));
+	let height = CGImage::height(Some(cg_image));
+
+	if width == 0 || height == 0 {
+		return Err(eyre::eyre!("CGImage has zero dimensions"));
+	}
+	if CGImage::bits_per_component(Some(cg_image)) != 8
+		|| CGImage::bits_per_pixel(Some(cg_image)) != 32
+	{
+		return Err(eyre::eyre!("Unsupported CGImage pixel layout"));
+	}
+
+	let alpha_info = CGImage::alpha_info(Some(cg_image));
+	let (alpha_first, premultiplied, opaque) = match alpha_info {
+		CGImageAlphaInfo::First => (true, false, false),
+		CGImageAlphaInfo::PremultipliedFirst => (true, true, false),
+		CGImageAlphaInfo::NoneSkipFirst => (true, false, true),
+		CGImageAlphaInfo::Last => (false, false, false),
+		CGImageAlphaInfo::PremultipliedLast => (false, true, false),
+		CGImageAlphaInfo::NoneSkipLast => (false, false, true),
+		_ => return Err(eyre::eyre!("Unsupported CGImage alpha info: {alpha_info:?}")),
+	};
+	let little_endian =
+		CGImage::byte_order_info(Some(cg_image)) == CGImageByteOrderInfo::Order32Little;
+	let data_provider = CGImage::data_provider(Some(cg_image))
+		.ok_or_else(|| eyre::eyre!("Failed to get CGImage data provider"))?;
+	let data = CGDataProvider::data(Some(data_provider.as_ref()))
+		.ok_or_else(|| eyre::eyre!("Failed to copy CGImage bytes"))?;
+	let bytes_per_row = CGImage::bytes_per_row(Some(cg_image));
+	let mut buffer = Vec::with_capacity(width * height * 4);
+
+	for row in data.to_vec().chunks_exact(bytes_per_row).take(height) {
+		for pixel in row[..width * 4].chunks_exact(4) {
+			let mut pixel = [pixel[0], pixel[1], pixel[2], pixel[3]];
+
+			if little_endian {
+				pixel.reverse();
+			}
+			if alpha_first {
+				pixel.rotate_left(1);
+			}
+			if opaque {
+				pixel[3] = u8::MAX;
+			} else if premultiplied && pixel[3] > 0 {
+				let alpha = u16::from(pixel[3]);
+
+				for channel in &mut pixel[..3] {
+					*channel = (u16::from(*channel) * 255 / alpha).min(255) as u8;
+				}
+			}
+
+			buffer.extend_from_slice(&pixel);
+		}
+	}
+
+	RgbaImage::from_raw(width as u32, height as u32, buffer)
+		.ok_or_else(|| eyre::eyre!("RgbaImage::from_raw failed"))
+}
+
+/// Reads the current system pointer sprite and hotspot.
+///
+/// Must run on the main thread. Returns `None` when AppKit does not expose the cursor (it may stop
+/// doing so in a future macOS release), so callers fall back to a generic arrow.
+#[cfg(target_os = "macos")]
+#[expect(
+	deprecated,
+	reason = "currentSystemCursor is the only public API that returns another app's cursor image."
+)]
+pub(crate) fn current_cursor_image() -> Option<CursorImage> {
+	let cursor = objc2_app_kit::NSCursor::currentSystemCursor()?;
+	let ns_image = cursor.image();
+	let size = ns_image.size();
+	let hotspot = cursor.hotSpot();
+	let cg_image =
+		unsafe { ns_image.CGImageForProposedRect_context_hints(ptr::null_mut(), None, None) }?;
+
+	if size.width <= 0.0 {
+		return None;
+	}
+
+	let image = match straight_rgba_image_from_cg_image(&cg_image) {
+		Ok(image) => image,
+		Err(err) => {
+			tracing::debug!(op = "backend.cursor_image", error = %err, "Cursor decode failed.");
+
+			return None;
+		},
+	};
+	let scale_factor = image.width() as f32 / size.width as f32;
+
+	Some(CursorImage {
+		image,
+		hotspot_x: hotspot.x as f32,
+		hotspot_y: hotspot.y as f32,
+		scale_factor,
+	})
+}
+
+/// Reads the system cursor sprite through XFixes.
+#[cfg(target_os = "linux")]
+pub(crate) fn current_cursor_image() -> Option<CursorImage> {
+	x11_capture_linux::current_cursor_image()
+}
+
+/// The system cursor image is not queried on this platform yet.
+#[cfg(target_os = "windows")]
+pub(crate) fn current_cursor_image() -> Option<CursorImage> {
+	None
+}
+
+/// Display transfer tables are not read on this platform yet.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn display_transfer(_display_id: u32) -> Option<DisplayTransfer> {
+	None
+}
+
+/// Returns a patch rect around a monitor-local pixel, shifted inward so it stays on the monitor.
+#[cfg(target_os = "macos")]
+fn centered_patch_rect_px(
+	monitor: MonitorRect,
+	center_x: u32,
+	center_y: u32,
+	width_px: u32,
+	height_px: u32,
+) -> RectPoints {
+	let monitor_width = point_extent_to_pixel_extent(monitor.width, monitor.scale_factor());
+	let monitor_height = point_extent_to_pixel_extent(monitor.height, monitor.scale_factor());
+	let width = width_px.max(1).min(monitor_width.max(1));
+	let height = height_px.max(1).min(monitor_height.max(1));
+	let region_x = center_x.saturating_sub(width / 2).min(monitor_width.saturating_sub(width));
+	let region_y = center_y.saturating_sub(height / 2).min(monitor_height.saturating_sub(height));
+
+	RectPoints::new(region_x, region_y, width, height)
+}
+
+/// The on-monitor part of a patch centered like `copy_rgba_patch`, with where that part sits
+/// inside the patch. `None` when the patch misses the monitor entirely.
+#[cfg(any(not(target_os = "macos"), test))]
+fn visible_patch_rect_px(
+	(monitor_width, monitor_height): (u32, u32),
+	center_x: u32,
+	center_y: u32,
+	width_px: u32,
+	height_px: u32,
+) -> Option<(RectPoints, u32, u32)> {
+	let width = width_px.max(1);
+	let height = height_px.max(1);
+	let left = i64::from(center_x) - i64::from(width / 2);
+	let top = i64::from(center_y) - i64::from(height / 2);
+	let x0 = left.max(0);
+	let y0 = top.max(0);
+	let x1 = (left + i64::from(width)).min(i64::from(monitor_width));
+	let y1 = (top + i64::from(height)).min(i64::from(monitor_height));
+
+	if x1 <= x0 || y1 <= y0 {
+		return None;
+	}
+
+	Some((
+		RectPoints::new(x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32),
+		(x0 - left) as u32,
+		(y0 - top) as u32,
+	))
+}
+
 fn copy_rgba_patch(
 	image: &RgbaImage,
 	center_x: u32,
@@ -1015,12 +1799,14 @@ fn normalize_capture_rect(rect_px: RectPoints) -> RectPoints {
 	RectPoints::new(rect_px.x, rect_px.y, rect_px.width.max(1), rect_px.height.max(1))
 }
 
-#[cfg(target_os = "macos")]
 fn point_extent_to_pixel_extent(points: u32, scale_factor: f32) -> u32 {
 	((points as f32) * scale_factor.max(1.0)).round().max(1.0) as u32
 }
 
-fn crop_monitor_image_region(image: &RgbaImage, rect_px: RectPoints) -> Result<RgbaImage> {
+pub(crate) fn crop_monitor_image_region(
+	image: &RgbaImage,
+	rect_px: RectPoints,
+) -> Result<RgbaImage> {
 	let rect_px = normalize_capture_rect(rect_px);
 	let x = rect_px.x.min(image.width());
 	let y = rect_px.y.min(image.height());
@@ -1035,7 +1821,7 @@ fn crop_monitor_image_region(image: &RgbaImage, rect_px: RectPoints) -> Result<R
 }
 
 #[cfg(target_os = "macos")]
-fn collect_window_geometries() -> Result<Vec<WindowRect>> {
+fn collect_window_geometries() -> Result<(Vec<WindowRect>, WindowLabels)> {
 	let window_list_ref = unsafe {
 		CGWindowListCopyWindowInfo(
 			KCG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | KCG_WINDOW_LIST_OPTION_EXCLUDE_DESKTOP,
@@ -1044,17 +1830,18 @@ fn collect_window_geometries() -> Result<Vec<WindowRect>> {
 	};
 
 	if window_list_ref.is_null() {
-		return Ok(Vec::new());
+		return Ok((Vec::new(), HashMap::new()));
 	}
 
 	let _guard = MacWindowListRefGuard(window_list_ref);
 	let window_count = unsafe { CFArrayGetCount(window_list_ref) };
 
 	if window_count <= 0 {
-		return Ok(Vec::new());
+		return Ok((Vec::new(), HashMap::new()));
 	}
 
 	let mut windows = Vec::with_capacity(window_count as usize);
+	let mut labels = HashMap::new();
 	let mut i = 0_isize;
 
 	while i < window_count {
@@ -1065,13 +1852,24 @@ fn collect_window_geometries() -> Result<Vec<WindowRect>> {
 		};
 
 		if let Some(window_geometry) = window_geometry_from_dictionary(window_dict) {
+			if let Some(window_id) = window_geometry.window_id {
+				labels.insert(
+					window_id,
+					WindowLabel {
+						app_name: cf_string_value(window_dict, "kCGWindowOwnerName")
+							.unwrap_or_default(),
+						title: cf_string_value(window_dict, "kCGWindowName").unwrap_or_default(),
+					},
+				);
+			}
+
 			windows.push(window_geometry);
 		}
 
 		i += 1;
 	}
 
-	Ok(windows)
+	Ok((windows, labels))
 }
 
 #[cfg(target_os = "macos")]
@@ -1092,6 +1890,141 @@ fn window_geometry_from_dictionary(window_dictionary: CFDictionaryRef) -> Option
 	Some(WindowRect { window_id, x, y, width, height })
 }
 
+/// Owner pids of on-screen, normal-layer windows whose bounds match a whole active display.
+#[cfg(target_os = "macos")]
+pub(crate) fn display_covering_window_pids() -> Vec<u32> {
+	let displays = active_display_bounds();
+
+	if displays.is_empty() {
+		return Vec::new();
+	}
+
+	let window_list_ref = unsafe {
+		CGWindowListCopyWindowInfo(
+			KCG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | KCG_WINDOW_LIST_OPTION_EXCLUDE_DESKTOP,
+			0,
+		)
+	};
+
+	if window_list_ref.is_null() {
+		return Vec::new();
+	}
+
+	let _guard = MacWindowListRefGuard(window_list_ref);
+	let window_count = unsafe { CFArrayGetCount(window_list_ref) };
+
+	(0..window_count)
+		.filter_map(|i| cf_dictionary_at_index(window_list_ref, i))
+		.filter(|window_dict| cf_number_to_u64(*window_dict, "kCGWindowLayer") == Some(0))
+		.filter_map(|window_dict| {
+			let bounds_dict = cf_dictionary_value(window_dict, "kCGWindowBounds")?;
+			let bounds = (
+				cf_number_to_i64(bounds_dict, "X")?,
+				cf_number_to_i64(bounds_dict, "Y")?,
+				cf_number_to_i64(bounds_dict, "Width")?,
+				cf_number_to_i64(bounds_dict, "Height")?,
+			);
+
+			if !displays.contains(&bounds) {
+				return None;
+			}
+
+			cf_number_to_u32(window_dict, "kCGWindowOwnerPID")
+		})
+		.collect()
+}
+
+/// The frontmost on-screen, normal-layer window owned by `pid`.
+#[cfg(target_os = "macos")]
+pub(crate) fn frontmost_window_of_pid(pid: u32) -> Option<WindowRect> {
+	let window_list_ref = unsafe {
+		CGWindowListCopyWindowInfo(
+			KCG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | KCG_WINDOW_LIST_OPTION_EXCLUDE_DESKTOP,
+			0,
+		)
+	};
+
+	if window_list_ref.is_null() {
+		return None;
+	}
+
+	let _guard = MacWindowListRefGuard(window_list_ref);
+	let window_count = unsafe { CFArrayGetCount(window_list_ref) };
+
+	// The window list is ordered front to back.
+	(0..window_count)
+		.filter_map(|i| cf_dictionary_at_index(window_list_ref, i))
+		.filter(|window_dict| cf_number_to_u64(*window_dict, "kCGWindowLayer") == Some(0))
+		.filter(|window_dict| cf_number_to_u32(*window_dict, "kCGWindowOwnerPID") == Some(pid))
+		.find_map(window_geometry_from_dictionary)
+}
+
+/// Every on-screen, normal-layer window with its owner pid, front to back, skipping this
+/// process's windows.
+#[cfg(target_os = "macos")]
+pub(crate) fn normal_windows_with_owner_pids() -> Vec<(u32, WindowRect)> {
+	let window_list_ref = unsafe {
+		CGWindowListCopyWindowInfo(
+			KCG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | KCG_WINDOW_LIST_OPTION_EXCLUDE_DESKTOP,
+			0,
+		)
+	};
+
+	if window_list_ref.is_null() {
+		return Vec::new();
+	}
+
+	let _guard = MacWindowListRefGuard(window_list_ref);
+	let window_count = unsafe { CFArrayGetCount(window_list_ref) };
+	let self_pid = process::id();
+
+	(0..window_count)
+		.filter_map(|i| cf_dictionary_at_index(window_list_ref, i))
+		.filter(|window_dict| cf_number_to_u64(*window_dict, "kCGWindowLayer") == Some(0))
+		.filter_map(|window_dict| {
+			let pid = cf_number_to_u32(window_dict, "kCGWindowOwnerPID")?;
+
+			if pid == self_pid {
+				return None;
+			}
+
+			Some((pid, window_geometry_from_dictionary(window_dict)?))
+		})
+		.collect()
+}
+
+/// Global `(x, y, width, height)` of every active display, in points.
+#[cfg(target_os = "macos")]
+fn active_display_bounds() -> Vec<(i64, i64, i64, i64)> {
+	let mut display_ids = [0_u32; 16];
+	let mut display_count = 0_u32;
+	let err = unsafe {
+		objc2_core_graphics::CGGetActiveDisplayList(
+			display_ids.len() as u32,
+			display_ids.as_mut_ptr(),
+			&raw mut display_count,
+		)
+	};
+
+	if err != CGError::Success {
+		return Vec::new();
+	}
+
+	display_ids[..(display_count as usize).min(display_ids.len())]
+		.iter()
+		.map(|&display_id| {
+			let bounds = objc2_core_graphics::CGDisplayBounds(display_id);
+
+			(
+				bounds.origin.x.round() as i64,
+				bounds.origin.y.round() as i64,
+				bounds.size.width.round() as i64,
+				bounds.size.height.round() as i64,
+			)
+		})
+		.collect()
+}
+
 #[cfg(target_os = "macos")]
 fn cf_dictionary_value(dictionary: CFDictionaryRef, key: &str) -> Option<CFTypeRef> {
 	let key_ref = cf_string_ref_for_key(key)?;
@@ -1110,6 +2043,27 @@ fn cf_bool_value(dictionary: CFDictionaryRef, key: &str) -> Option<bool> {
 	Some(value != 0)
 }
 
+#[cfg(target_os = "macos")]
+fn cf_string_value(dictionary: CFDictionaryRef, key: &str) -> Option<String> {
+	let raw = cf_dictionary_value(dictionary, key)? as CFStringRef;
+	let capacity = unsafe {
+		CFStringGetMaximumSizeForEncoding(CFStringGetLength(raw), KCF_STRING_ENCODING_UTF8)
+	} + 1;
+	let mut buffer = vec![0_u8; usize::try_from(capacity).ok()?];
+
+	if !unsafe {
+		CFStringGetCString(raw, buffer.as_mut_ptr().cast(), capacity, KCF_STRING_ENCODING_UTF8)
+	} {
+		return None;
+	}
+
+	let len = buffer.iter().position(|byte| *byte == 0).unwrap_or(buffer.len());
+
+	buffer.truncate(len);
+
+	String::from_utf8(buffer).ok()
+}
+
 #[cfg(target_os = "macos")]
 fn cf_number_to_i64(dictionary: CFDictionaryRef, key: &str) -> Option<i64> {
 	let raw = cf_dictionary_value(dictionary, key)? as CFNumberRef;
@@ -1207,11 +2161,12 @@ fn cf_dictionary_at_index(array: CFArrayRef, index: isize) -> Option<CFDictionar
 	if value.is_null() { None } else { Some(value) }
 }
 
-#[cfg(not(target_os = "macos"))]
-fn collect_window_geometries() -> Result<Vec<WindowRect>> {
+#[cfg(target_os = "windows")]
+fn collect_window_geometries() -> Result<(Vec<WindowRect>, WindowLabels)> {
 	let windows = Window::all().wrap_err("xcap Window::all failed")?;
 	let self_pid = process::id();
 	let mut cached_windows = Vec::with_capacity(windows.len());
+	let mut labels = HashMap::new();
 
 	for window in windows {
 		let Ok(is_minimized) = window.is_minimized() else {
@@ -1250,6 +2205,16 @@ fn collect_window_geometries() -> Result<Vec<WindowRect>> {
 			continue;
 		}
 
+		if let Some(window_id) = window_id {
+			labels.insert(
+				window_id,
+				WindowLabel {
+					app_name: window.app_name().unwrap_or_default(),
+					title: window.title().unwrap_or_default(),
+				},
+			);
+		}
+
 		cached_windows.push(WindowRect {
 			window_id,
 			x: i64::from(x),
@@ -1259,7 +2224,7 @@ fn collect_window_geometries() -> Result<Vec<WindowRect>> {
 		});
 	}
 
-	Ok(cached_windows)
+	Ok((cached_windows, labels))
 }
 
 #[cfg(target_os = "macos")]
@@ -1282,9 +2247,17 @@ unsafe extern "C" {
 		c_string: *const c_char,
 		encoding: u32,
 	) -> CFStringRef;
+	fn CFStringGetCString(
+		the_string: CFStringRef,
+		buffer: *mut c_char,
+		buffer_size: isize,
+		encoding: u32,
+	) -> bool;
+	fn CFStringGetLength(the_string: CFStringRef) -> isize;
+	fn CFStringGetMaximumSizeForEncoding(length: isize, encoding: u32) -> isize;
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(target_os = "windows")]
 fn capture_monitor_image(monitor: MonitorRect) -> Result<RgbaImage> {
 	let xcap_monitor = xcap_find_monitor(monitor)?;
 	let image = xcap_monitor.capture_image().wrap_err("xcap capture_image failed")?;
@@ -1292,7 +2265,7 @@ fn capture_monitor_image(monitor: MonitorRect) -> Result<RgbaImage> {
 	Ok(image)
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(target_os = "windows")]
 fn xcap_find_monitor(monitor: MonitorRect) -> Result<xcap::Monitor> {
 	let monitors = xcap::Monitor::all().wrap_err("xcap Monitor::all failed")?;
 
@@ -1307,9 +2280,30 @@ fn xcap_find_monitor(monitor: MonitorRect) -> Result<xcap::Monitor> {
 
 #[cfg(test)]
 mod tests {
-	use crate::backend::{CaptureBackend, StubCaptureBackend};
-	#[cfg(target_os = "macos")]
-	use crate::state::{GlobalPoint, MonitorRect, RectPoints};
+	use image::{Rgba, RgbaImage};
+
+	use crate::backend::{
+		CaptureBackend, CaptureBackendKind, ScriptedCaptureBackend, StubCaptureBackend,
+		visible_patch_rect_px,
+	};
+	use crate::state::{GlobalPoint, MonitorRect, RectPoints, Rgb, WindowRect};
+
+	#[test]
+	fn visible_patch_rect_clips_the_patch_to_the_monitor_and_keeps_its_offset() {
+		assert_eq!(
+			visible_patch_rect_px((100, 80), 50, 40, 11, 11),
+			Some((RectPoints::new(45, 35, 11, 11), 0, 0))
+		);
+		assert_eq!(
+			visible_patch_rect_px((100, 80), 2, 78, 11, 11),
+			Some((RectPoints::new(0, 73, 8, 7), 3, 0))
+		);
+		assert_eq!(
+			visible_patch_rect_px((100, 80), 0, 0, 1, 1),
+			Some((RectPoints::new(0, 0, 1, 1), 0, 0))
+		);
+		assert_eq!(visible_patch_rect_px((100, 80), 200, 40, 11, 11), None);
+	}
 
 	#[test]
 	fn stub_backend_returns_cursor_position() {
@@ -1319,6 +2313,77 @@ mod tests {
 		assert!(pos.is_none());
 	}
 
+	#[test]
+	fn scripted_backend_replays_frames_and_repeats_the_last_one() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 4,
+			height: 4,
+			scale_factor_x1000: 1_000,
+		};
+		let mut backend = ScriptedCaptureBackend::new()
+			.with_frame(1, RgbaImage::from_pixel(4, 4, Rgba([10, 0, 0, 255])))
+			.with_frame(1, RgbaImage::from_pixel(4, 4, Rgba([20, 0, 0, 255])));
+		let first = backend.capture_monitor(monitor).unwrap();
+		let sampled = backend.pixel_rgb_in_monitor(monitor, GlobalPoint::new(1, 1)).unwrap();
+		let second = backend.capture_monitor_region(monitor, RectPoints::new(0, 0, 2, 2)).unwrap();
+		let repeated = backend.capture_monitor(monitor).unwrap();
+
+		assert_eq!(first.get_pixel(0, 0).0[0], 10);
+		assert_eq!(sampled, Some(Rgb::new(20, 0, 0)));
+		assert_eq!((second.width(), second.height()), (2, 2));
+		assert_eq!(second.get_pixel(0, 0).0[0], 20);
+		assert_eq!(repeated.get_pixel(0, 0).0[0], 20);
+	}
+
+	#[test]
+	fn scripted_backend_synthesizes_frames_and_hit_tests_windows() {
+		let monitor = MonitorRect {
+			id: 2,
+			origin: GlobalPoint::new(100, 0),
+			width: 200,
+			height: 100,
+			scale_factor_x1000: 2_000,
+		};
+		let window = WindowRect { window_id: Some(7), x: 120, y: 10, width: 50, height: 40 };
+		let mut backend = ScriptedCaptureBackend::new()
+			.with_window(window, RgbaImage::new(100, 80))
+			.with_cursor(GlobalPoint::new(130, 20));
+		let frame = backend.capture_monitor(monitor).unwrap();
+		let rgb = backend.pixel_rgb_in_monitor(monitor, GlobalPoint::new(110, 5)).unwrap();
+		let patch = backend.rgba_patch_in_monitor(monitor, GlobalPoint::new(110, 5), 3, 3).unwrap();
+		let hit = backend.hit_test_window_in_monitor(monitor, GlobalPoint::new(130, 20)).unwrap();
+
+		assert_eq!((frame.width(), frame.height()), (400, 200));
+		assert_eq!(rgb, Some(Rgb::new(20, 10, 30)));
+		assert_eq!(patch.map(|patch| patch.get_pixel(1, 1).0), Some([20, 10, 30, 255]));
+		assert_eq!(
+			hit.map(|hit| (hit.window_id, hit.rect)),
+			Some((Some(7), RectPoints::new(20, 10, 50, 40)))
+		);
+		assert_eq!(backend.capture_window(7).unwrap().width(), 100);
+		assert!(backend.capture_window(8).is_err());
+		assert_eq!(backend.global_cursor_position().unwrap(), Some(GlobalPoint::new(130, 20)));
+	}
+
+	#[test]
+	fn capture_backend_kind_falls_back_to_the_native_backend() {
+		let native = CaptureBackendKind::native();
+
+		assert_eq!(CaptureBackendKind::Auto.resolve(), native);
+		assert_eq!(native.resolve(), native);
+		assert_eq!(CaptureBackendKind::Stub.resolve(), CaptureBackendKind::Stub);
+		assert_eq!(CaptureBackendKind::Pipewire.resolve(), native);
+		assert_eq!(
+			CaptureBackendKind::ALL
+				.iter()
+				.filter(|kind| kind.unsupported_reason().is_none())
+				.count(),
+			3
+		);
+	}
+
 	#[cfg(target_os = "macos")]
 	#[test]
 	fn region_capture_after_seq_only_reuses_matching_monitor_and_rect() {
@@ -0,0 +1,282 @@
+use ab_glyph::{Font, FontRef, GlyphId, PxScale, ScaleFont, point};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::watermark;
+
+/// Padding between the bubble edge and its text, in multiples of the text size.
+const CALLOUT_PADDING_EM: f32 = 0.6;
+/// Lines wrap at this width and an empty bubble is never narrower than the minimum.
+const CALLOUT_MAX_TEXT_WIDTH_EM: f32 = 18.0;
+const CALLOUT_MIN_TEXT_WIDTH_EM: f32 = 3.0;
+const CALLOUT_FILL: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const CALLOUT_INK: Rgba<u8> = Rgba([28, 28, 32, 255]);
+
+/// Which part of a callout a press landed on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum CalloutPart {
+	Body,
+	Tail,
+}
+
+/// A speech bubble whose tail points at a target, positioned in pixels of the selection image.
+/// The body grows with its text.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) struct Callout {
+	/// Top-left corner of the body.
+	pub(crate) origin_px: (i64, i64),
+	pub(crate) tail_px: (i64, i64),
+	pub(crate) text: String,
+	pub(crate) text_size_px: u32,
+}
+impl Callout {
+	pub(crate) fn body_size_px(&self) -> (u32, u32) {
+		let Some(font) = callout_font() else {
+			return (1, 1);
+		};
+		let layout = TextLayout::new(&font, &self.text, self.text_size_px as f32);
+		let size = self.text_size_px as f32;
+		let padding = 2.0 * CALLOUT_PADDING_EM * size;
+		let width = layout.width.max(CALLOUT_MIN_TEXT_WIDTH_EM * size) + padding;
+
+		(width.ceil() as u32, (layout.height + padding).ceil() as u32)
+	}
+
+	/// The tail tip wins over the body, so a tail tucked under the body can still be dragged out.
+	pub(crate) fn hit(&self, (x, y): (i64, i64)) -> Option<CalloutPart> {
+		let grab_radius = i64::from((self.text_size_px / 2).max(6));
+		let (tail_x, tail_y) = self.tail_px;
+
+		if (x - tail_x).abs() <= grab_radius && (y - tail_y).abs() <= grab_radius {
+			return Some(CalloutPart::Tail);
+		}
+
+		let (width, height) = self.body_size_px();
+		let (left, top) = self.origin_px;
+
+		(x >= left && y >= top && x < left + i64::from(width) && y < top + i64::from(height))
+			.then_some(CalloutPart::Body)
+	}
+}
+
+/// Rasterizes `callout` and returns it with the selection pixel its top-left lands on. With
+/// `caret`, a text cursor follows the last character.
+pub(crate) fn render_callout(callout: &Callout, caret: bool) -> Option<(RgbaImage, (i64, i64))> {
+	let font = callout_font()?;
+	let size = callout.text_size_px.max(1) as f32;
+	let text = if caret { format!("{}|", callout.text) } else { callout.text.clone() };
+	let layout = TextLayout::new(&font, &text, size);
+	let (body_width, body_height) = callout.body_size_px();
+	let stroke = (size / 12.0).max(1.0);
+	let body_min = (callout.origin_px.0 as f32, callout.origin_px.1 as f32);
+	let body_max = (body_min.0 + body_width as f32, body_min.1 + body_height as f32);
+	let tail = (callout.tail_px.0 as f32, callout.tail_px.1 as f32);
+	let radius = (size * 0.5).min(body_width.min(body_height) as f32 / 2.0);
+	let (base_a, base_b) = tail_base(body_min, body_max, tail);
+	let margin = stroke + 2.0;
+	let left = (body_min.0.min(tail.0) - margin).floor() as i64;
+	let top = (body_min.1.min(tail.1) - margin).floor() as i64;
+	let right = (body_max.0.max(tail.0) + margin).ceil() as i64;
+	let bottom = (body_max.1.max(tail.1) + margin).ceil() as i64;
+	let (width, height) = ((right - left).max(1) as u32, (bottom - top).max(1) as u32);
+	let mut image = RgbaImage::new(width, height);
+
+	for (x, y, pixel) in image.enumerate_pixels_mut() {
+		let sample = ((left + i64::from(x)) as f32 + 0.5, (top + i64::from(y)) as f32 + 0.5);
+		let distance = rounded_rect_distance(sample, body_min, body_max, radius)
+			.min(triangle_distance(sample, base_a, base_b, tail));
+		let outline = (0.5 - distance).clamp(0.0, 1.0);
+		let fill = (0.5 - (distance + stroke)).clamp(0.0, 1.0);
+
+		watermark::blend_pixel(pixel, CALLOUT_INK, outline);
+		watermark::blend_pixel(pixel, CALLOUT_FILL, fill);
+	}
+
+	let padding = CALLOUT_PADDING_EM * size;
+	let text_left = body_min.0 + padding - left as f32;
+	let text_top = body_min.1 + padding - top as f32;
+
+	for (id, position) in layout.glyphs {
+		let glyph =
+			id.with_scale_and_position(size, point(text_left + position.0, text_top + position.1));
+		let Some(outlined) = font.outline_glyph(glyph) else {
+			continue;
+		};
+		let bounds = outlined.px_bounds();
+
+		outlined.draw(|gx, gy, coverage| {
+			let x = bounds.min.x as i64 + i64::from(gx);
+			let y = bounds.min.y as i64 + i64::from(gy);
+
+			if (0..i64::from(width)).contains(&x) && (0..i64::from(height)).contains(&y) {
+				watermark::blend_pixel(
+					image.get_pixel_mut(x as u32, y as u32),
+					CALLOUT_INK,
+					coverage.min(1.0),
+				);
+			}
+		});
+	}
+
+	Some((image, (left, top)))
+}
+
+/// Composites `callouts` onto the cropped export in placement order.
+pub(crate) fn apply_callouts(image: &mut RgbaImage, callouts: &[Callout]) {
+	for callout in callouts.iter().filter(|callout| !callout.text.is_empty()) {
+		let Some((rendered, (x, y))) = render_callout(callout, false) else {
+			continue;
+		};
+
+		watermark::blend_onto(image, &rendered, x, y, 1.0);
+	}
+}
+
+fn callout_font() -> Option<FontRef<'static>> {
+	FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT).ok()
+}
+
+/// Glyph positions relative to the top-left of the text block, greedily wrapped at word breaks.
+struct TextLayout {
+	glyphs: Vec<(GlyphId, (f32, f32))>,
+	width: f32,
+	height: f32,
+}
+impl TextLayout {
+	fn new(font: &FontRef<'_>, text: &str, size_px: f32) -> Self {
+		let scaled = font.as_scaled(PxScale::from(size_px));
+		let line_height = scaled.height() + scaled.line_gap();
+		let max_width = CALLOUT_MAX_TEXT_WIDTH_EM * size_px;
+		let advance = |word: &str| -> f32 {
+			word.chars().map(|ch| scaled.h_advance(scaled.glyph_id(ch))).sum()
+		};
+		let mut glyphs = Vec::new();
+		let mut width = 0.0_f32;
+		let mut line = 0_u32;
+
+		for (index, paragraph) in text.split('\n').enumerate() {
+			let mut caret = 0.0;
+
+			if index > 0 {
+				line += 1;
+			}
+
+			for word in paragraph.split_inclusive(' ') {
+				if caret > 0.0 && caret + advance(word.trim_end()) > max_width {
+					caret = 0.0;
+					line += 1;
+				}
+
+				let baseline = scaled.ascent() + line as f32 * line_height;
+
+				for ch in word.chars() {
+					let id = scaled.glyph_id(ch);
+
+					glyphs.push((id, (caret, baseline)));
+
+					caret += scaled.h_advance(id);
+				}
+
+				width = width.max(caret);
+			}
+		}
+
+		Self { glyphs, width, height: (line + 1) as f32 * line_height }
+	}
+}
+
+/// The two base corners of the tail, straddling the body center across the tail's direction.
+fn tail_base(
+	body_min: (f32, f32),
+	body_max: (f32, f32),
+	tail: (f32, f32),
+) -> ((f32, f32), (f32, f32)) {
+	let center = ((body_min.0 + body_max.0) / 2.0, (body_min.1 + body_max.1) / 2.0);
+	let (dx, dy) = (tail.0 - center.0, tail.1 - center.1);
+	let length = (dx * dx + dy * dy).sqrt().max(1.0);
+	let half_base = ((body_max.0 - body_min.0).min(body_max.1 - body_min.1) / 4.0).max(4.0);
+	let (px, py) = (-dy / length * half_base, dx / length * half_base);
+
+	((center.0 + px, center.1 + py), (center.0 - px, center.1 - py))
+}
+
+fn rounded_rect_distance(p: (f32, f32), min: (f32, f32), max: (f32, f32), radius: f32) -> f32 {
+	let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+	let half = ((max.0 - min.0) / 2.0, (max.1 - min.1) / 2.0);
+	let qx = (p.0 - center.0).abs() - half.0 + radius;
+	let qy = (p.1 - center.1).abs() - half.1 + radius;
+	let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+
+	outside + qx.max(qy).min(0.0) - radius
+}
+
+fn triangle_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+	let sub = |u: (f32, f32), v: (f32, f32)| (u.0 - v.0, u.1 - v.1);
+	let dot = |u: (f32, f32), v: (f32, f32)| u.0 * v.0 + u.1 * v.1;
+	let cross = |u: (f32, f32), v: (f32, f32)| u.0 * v.1 - u.1 * v.0;
+	let edges = [(a, sub(b, a)), (b, sub(c, b)), (c, sub(a, c))];
+	let winding = cross(sub(b, a), sub(c, a)).signum();
+	let mut nearest = f32::MAX;
+	let mut inside = true;
+
+	for (start, edge) in edges {
+		let to_point = sub(p, start);
+		let t = (dot(to_point, edge) / dot(edge, edge).max(f32::EPSILON)).clamp(0.0, 1.0);
+		let offset = (to_point.0 - edge.0 * t, to_point.1 - edge.1 * t);
+
+		nearest = nearest.min(dot(offset, offset));
+		inside &= winding * cross(edge, to_point) >= 0.0;
+	}
+
+	if inside { -nearest.sqrt() } else { nearest.sqrt() }
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::callout::{self, Callout, CalloutPart};
+
+	fn callout(text: &str) -> Callout {
+		Callout {
+			origin_px: (100, 20),
+			tail_px: (40, 120),
+			text: String::from(text),
+			text_size_px: 16,
+		}
+	}
+
+	#[test]
+	fn callout_body_grows_with_its_text_and_wraps_long_lines() {
+		let (empty_width, empty_height) = callout("").body_size_px();
+		let (word_width, _) = callout("Broken button").body_size_px();
+		let (wrapped_width, wrapped_height) = callout(&"overflowing ".repeat(12)).body_size_px();
+
+		assert!(word_width > empty_width);
+		assert!(wrapped_height >= 2 * empty_height - 20);
+		assert!(wrapped_width <= (18.0 * 16.0 + 2.0 * 0.6 * 16.0) as u32 + 1);
+	}
+
+	#[test]
+	fn callout_hit_testing_prefers_the_tail_tip() {
+		let callout = callout("Here");
+
+		assert_eq!(callout.hit((42, 118)), Some(CalloutPart::Tail));
+		assert_eq!(callout.hit((110, 30)), Some(CalloutPart::Body));
+		assert_eq!(callout.hit((10, 10)), None);
+	}
+
+	#[test]
+	fn callouts_paint_body_tail_and_text_in_the_export() {
+		let background = Rgba([0, 0, 0, 255]);
+		let mut image = RgbaImage::from_pixel(300, 160, background);
+
+		callout::apply_callouts(&mut image, &[callout("Broken")]);
+
+		// Body fill, the tail partway to its tip, and untouched pixels away from both.
+		assert_eq!(*image.get_pixel(104, 24), Rgba([255, 255, 255, 255]));
+		assert_ne!(*image.get_pixel(70, 94), background);
+		assert_eq!(*image.get_pixel(280, 150), background);
+		assert_eq!(*image.get_pixel(10, 10), background);
+	}
+}
@@ -0,0 +1,99 @@
+//! Keeps rsnap's own windows out of screenshots, recordings and screen shares.
+
+use winit::window::Window;
+
+/// Excludes `window` from screen capture wherever the platform allows it, so the overlay, HUD,
+/// toolbar and settings never show up in another app's screen share or in rsnap's own captures.
+///
+/// - macOS: `NSWindowSharingNone`.
+/// - Windows: `WDA_EXCLUDEFROMCAPTURE` (Windows 10 2004 and later), falling back to
+///   `WDA_MONITOR`, which shows the window as black in captures, on older systems.
+/// - Linux: X11 has no per-window exclusion, and Wayland leaves it to the compositor's
+///   screencast portal, so the window is left as created.
+pub fn exclude_window_from_capture(window: &Window) {
+	imp::exclude_window_from_capture(window);
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+	use objc::runtime::Object;
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use winit::window::Window;
+
+	macro_rules! sel {
+		($($tt:tt)*) => {
+			objc::sel!($($tt)*)
+		};
+	}
+
+	macro_rules! sel_impl {
+		($($tt:tt)*) => {
+			objc::sel_impl!($($tt)*)
+		};
+	}
+
+	const NS_WINDOW_SHARING_NONE: u64 = 0;
+
+	pub(super) fn exclude_window_from_capture(window: &Window) {
+		let Ok(handle) = window.window_handle() else {
+			return;
+		};
+		let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+			return;
+		};
+		let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+
+		// SAFETY: `ns_view` comes from a live winit window and is only messaged on the main
+		// thread.
+		unsafe {
+			let ns_window: *mut Object = objc::msg_send![ns_view, window];
+
+			if ns_window.is_null() {
+				return;
+			}
+
+			let _: () = objc::msg_send![ns_window, setSharingType: NS_WINDOW_SHARING_NONE];
+		}
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use windows_sys::Win32::Foundation::HWND;
+	use windows_sys::Win32::UI::WindowsAndMessaging::{
+		SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_MONITOR,
+	};
+	use winit::window::Window;
+
+	pub(super) fn exclude_window_from_capture(window: &Window) {
+		let Ok(handle) = window.window_handle() else {
+			return;
+		};
+		let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+			return;
+		};
+		let hwnd = handle.hwnd.get() as HWND;
+
+		// SAFETY: `hwnd` comes from a live winit window owned by this thread.
+		unsafe {
+			if SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) == 0
+				&& SetWindowDisplayAffinity(hwnd, WDA_MONITOR) == 0
+			{
+				tracing::debug!(
+					op = "window.capture_exclusion",
+					"SetWindowDisplayAffinity failed."
+				);
+			}
+		}
+	}
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod imp {
+	use winit::window::Window;
+
+	pub(super) fn exclude_window_from_capture(window: &Window) {
+		let _ = window;
+	}
+}
@@ -0,0 +1,229 @@
+//! Watches the system clipboard for images copied by any app.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use arboard::Clipboard;
+use color_eyre::eyre::{Result, WrapErr};
+use image::RgbaImage;
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+#[cfg(target_os = "linux")]
+use crate::clipboard_watch_linux::SelectionChanges;
+#[cfg(target_os = "macos")]
+use crate::overlay::output;
+use crate::png;
+
+/// How often the clipboard is checked for a new image.
+const CLIPBOARD_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Checks the clipboard on a background thread and reports each newly copied image once, as
+/// PNG bytes.
+///
+/// The image already on the clipboard when the watch starts counts as seen. The image is only
+/// read after the platform reports a clipboard change: the change count on macOS and Windows,
+/// XFixes selection events on X11, and `wl-paste --watch` on Wayland. Without a change source
+/// every check reads it.
+pub struct ClipboardWatch {
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl ClipboardWatch {
+	/// Starts watching, calling `on_image` on the watch thread with each new image and its
+	/// [`Self::fingerprint_png`].
+	pub fn start(on_image: impl FnMut(Vec<u8>, u64) + Send + 'static) -> Result<Self> {
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let worker = thread::Builder::new()
+			.name(String::from("rsnap-clipboard-watch"))
+			.spawn(move || clipboard_watch_loop(&stop_rx, on_image))
+			.wrap_err("Failed to start the clipboard watch thread")?;
+
+		tracing::info!(op = "clipboard_watch.start", "Clipboard watch started.");
+
+		Ok(Self { stop_tx, worker: Some(worker) })
+	}
+
+	/// Fingerprints the pixels of an encoded PNG the way the watch fingerprints copied images, so
+	/// callers can recognize an image they put on the clipboard themselves.
+	#[must_use]
+	pub fn fingerprint_png(png_bytes: &[u8]) -> Option<u64> {
+		let image = image::load_from_memory_with_format(png_bytes, image::ImageFormat::Png).ok()?;
+
+		Some(image_fingerprint(&image.to_rgba8()))
+	}
+}
+impl Drop for ClipboardWatch {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+
+		tracing::info!(op = "clipboard_watch.stop", "Clipboard watch stopped.");
+	}
+}
+
+fn clipboard_watch_loop(stop_rx: &Receiver<()>, mut on_image: impl FnMut(Vec<u8>, u64)) {
+	let mut clipboard = match Clipboard::new() {
+		Ok(clipboard) => clipboard,
+		Err(err) => {
+			tracing::warn!(
+				op = "clipboard_watch.open",
+				error = %err,
+				"Clipboard watch could not open the clipboard."
+			);
+
+			return;
+		},
+	};
+	let mut changes = ClipboardChanges::start();
+	let mut last_change = changes.count();
+	let mut last_image = read_clipboard_image(&mut clipboard).as_ref().map(image_fingerprint);
+
+	loop {
+		match stop_rx.recv_timeout(CLIPBOARD_WATCH_INTERVAL) {
+			Err(RecvTimeoutError::Timeout) => {},
+			Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+		}
+
+		let change = changes.count();
+
+		if change.is_some() && change == last_change {
+			continue;
+		}
+
+		last_change = change;
+
+		let Some(image) = read_clipboard_image(&mut clipboard) else {
+			last_image = None;
+
+			continue;
+		};
+		let fingerprint = image_fingerprint(&image);
+
+		if last_image == Some(fingerprint) {
+			continue;
+		}
+
+		last_image = Some(fingerprint);
+
+		match png::rgba_image_to_png_bytes(&image) {
+			Ok(png_bytes) => on_image(png_bytes, fingerprint),
+			Err(err) => tracing::debug!(
+				op = "clipboard_watch.encode",
+				error = %format!("{err:#}"),
+				"Failed to encode the copied image."
+			),
+		}
+	}
+}
+
+fn read_clipboard_image(clipboard: &mut Clipboard) -> Option<RgbaImage> {
+	let image = clipboard.get_image().ok()?;
+
+	RgbaImage::from_raw(
+		u32::try_from(image.width).ok()?,
+		u32::try_from(image.height).ok()?,
+		image.bytes.into_owned(),
+	)
+}
+
+/// Tells images apart without keeping a copy of the last one around.
+fn image_fingerprint(image: &RgbaImage) -> u64 {
+	let mut hasher = DefaultHasher::new();
+
+	image.dimensions().hash(&mut hasher);
+	image.as_raw().hash(&mut hasher);
+
+	hasher.finish()
+}
+
+/// Where the watch learns that the clipboard changed; `None` from [`Self::count`] means every
+/// check reads the image.
+struct ClipboardChanges {
+	#[cfg(target_os = "linux")]
+	selection: Option<SelectionChanges>,
+}
+impl ClipboardChanges {
+	fn start() -> Self {
+		Self {
+			#[cfg(target_os = "linux")]
+			selection: SelectionChanges::start(),
+		}
+	}
+
+	#[cfg(target_os = "macos")]
+	fn count(&mut self) -> Option<i64> {
+		Some(output::pasteboard_change_count() as i64)
+	}
+
+	#[cfg(target_os = "windows")]
+	fn count(&mut self) -> Option<i64> {
+		// SAFETY: `GetClipboardSequenceNumber` only reads a counter and has no preconditions.
+		Some(i64::from(unsafe { GetClipboardSequenceNumber() }))
+	}
+
+	#[cfg(target_os = "linux")]
+	fn count(&mut self) -> Option<i64> {
+		let count = self.selection.as_mut()?.count();
+
+		// A source that stopped reporting is dropped, so later checks read the image.
+		if count.is_none() {
+			self.selection = None;
+		}
+
+		count
+	}
+
+	#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+	fn count(&mut self) -> Option<i64> {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::clipboard_watch::{self, ClipboardWatch};
+	use crate::png;
+
+	#[test]
+	fn fingerprints_change_with_pixels_and_size() {
+		let image = RgbaImage::from_pixel(4, 2, Rgba([1, 2, 3, 255]));
+		let mut edited = image.clone();
+
+		edited.put_pixel(3, 1, Rgba([1, 2, 4, 255]));
+
+		let transposed = RgbaImage::from_pixel(2, 4, Rgba([1, 2, 3, 255]));
+
+		assert_eq!(
+			clipboard_watch::image_fingerprint(&image),
+			clipboard_watch::image_fingerprint(&image.clone())
+		);
+		assert_ne!(
+			clipboard_watch::image_fingerprint(&image),
+			clipboard_watch::image_fingerprint(&edited)
+		);
+		assert_ne!(
+			clipboard_watch::image_fingerprint(&image),
+			clipboard_watch::image_fingerprint(&transposed)
+		);
+	}
+
+	#[test]
+	fn png_fingerprints_match_the_decoded_pixels() {
+		let image = RgbaImage::from_pixel(4, 2, Rgba([1, 2, 3, 255]));
+		let png_bytes = png::rgba_image_to_png_bytes(&image).expect("encode");
+
+		assert_eq!(
+			ClipboardWatch::fingerprint_png(&png_bytes),
+			Some(clipboard_watch::image_fingerprint(&image))
+		);
+		assert_eq!(ClipboardWatch::fingerprint_png(b"not a png"), None);
+	}
+}
@@ -0,0 +1,119 @@
+//! Clipboard change counting for Linux: XFixes selection events on X11 and `wl-paste --watch` on
+//! Wayland, so the clipboard watch only reads the image after something new was copied.
+
+use std::env;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::thread;
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xfixes::{self, ConnectionExt as _, SelectionEventMask};
+use x11rb::protocol::xproto::ConnectionExt as _;
+use x11rb::rust_connection::RustConnection;
+
+/// Counts changes of the `CLIPBOARD` selection as they are reported.
+pub(crate) enum SelectionChanges {
+	/// XFixes selection-owner events on the root window.
+	X11 { conn: Box<RustConnection>, changes: i64 },
+	/// One line of `wl-paste --watch` output per change, counted on a reader thread.
+	Wayland { child: Child, changes: Arc<AtomicI64> },
+}
+impl SelectionChanges {
+	/// Subscribes to the session's clipboard; `None` when neither source is available, in which
+	/// case every check reads the image.
+	pub(crate) fn start() -> Option<Self> {
+		let started = if env::var_os("WAYLAND_DISPLAY").is_some() {
+			Self::start_wayland()
+		} else {
+			Self::start_x11()
+		};
+
+		started
+			.inspect_err(|err| {
+				tracing::warn!(
+					op = "clipboard_watch.changes",
+					error = %format!("{err:#}"),
+					"Clipboard changes are not reported; the watch reads the clipboard on every check."
+				);
+			})
+			.ok()
+	}
+
+	fn start_x11() -> Result<Self> {
+		let (conn, screen_num) = x11rb::connect(None).wrap_err("Failed to connect to X11")?;
+		let root = conn.setup().roots[screen_num].root;
+		let clipboard = conn.intern_atom(false, b"CLIPBOARD")?.reply()?.atom;
+
+		conn.xfixes_query_version(xfixes::X11_XML_VERSION.0, xfixes::X11_XML_VERSION.1)?
+			.reply()
+			.wrap_err("XFixes is unavailable")?;
+		conn.xfixes_select_selection_input(
+			root,
+			clipboard,
+			SelectionEventMask::SET_SELECTION_OWNER
+				| SelectionEventMask::SELECTION_WINDOW_DESTROY
+				| SelectionEventMask::SELECTION_CLIENT_CLOSE,
+		)?;
+		conn.flush()?;
+
+		Ok(Self::X11 { conn: Box::new(conn), changes: 0 })
+	}
+
+	fn start_wayland() -> Result<Self> {
+		let mut child = Command::new("wl-paste")
+			.args(["--watch", "echo"])
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::null())
+			.spawn()
+			.wrap_err("Failed to run `wl-paste --watch`; is wl-clipboard installed?")?;
+		let stdout = child.stdout.take().ok_or_else(|| eyre::eyre!("wl-paste has no stdout"))?;
+		let changes = Arc::new(AtomicI64::new(0));
+		let counted = Arc::clone(&changes);
+
+		thread::Builder::new()
+			.name(String::from("rsnap-clipboard-wl-paste"))
+			.spawn(move || {
+				for _ in BufReader::new(stdout).lines().map_while(Result::ok) {
+					counted.fetch_add(1, Ordering::Relaxed);
+				}
+			})
+			.wrap_err("Failed to start the wl-paste reader thread")?;
+
+		Ok(Self::Wayland { child, changes })
+	}
+
+	/// Changes seen so far, or `None` once the source stopped reporting them.
+	pub(crate) fn count(&mut self) -> Option<i64> {
+		match self {
+			Self::X11 { conn, changes } => {
+				loop {
+					match conn.poll_for_event() {
+						Ok(Some(Event::XfixesSelectionNotify(_))) => *changes += 1,
+						Ok(Some(_)) => {},
+						Ok(None) => break,
+						Err(_) => return None,
+					}
+				}
+
+				Some(*changes)
+			},
+			Self::Wayland { child, changes } => match child.try_wait() {
+				Ok(None) => Some(changes.load(Ordering::Relaxed)),
+				Ok(Some(_)) | Err(_) => None,
+			},
+		}
+	}
+}
+impl Drop for SelectionChanges {
+	fn drop(&mut self) {
+		if let Self::Wayland { child, .. } = self {
+			let _ = child.kill();
+			let _ = child.wait();
+		}
+	}
+}
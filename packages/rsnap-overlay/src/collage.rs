@@ -0,0 +1,73 @@
+use image::imageops;
+use image::{Rgba, RgbaImage};
+
+/// Transparent space between collage cells.
+pub(crate) const COLLAGE_GAP_PX: u32 = 16;
+
+/// Lays `images` out in reading order on a transparent canvas, `ceil(sqrt(n))` per row.
+///
+/// Each row is as tall as its tallest image and images keep their own size, so the collage never
+/// rescales a region.
+pub(crate) fn compose_collage(images: &[RgbaImage], gap_px: u32) -> RgbaImage {
+	let columns = (images.len() as f64).sqrt().ceil().max(1.0) as usize;
+	let rows = images.chunks(columns).collect::<Vec<_>>();
+	let row_width = |row: &[RgbaImage]| {
+		row.iter().map(RgbaImage::width).sum::<u32>()
+			+ gap_px.saturating_mul(row.len().saturating_sub(1) as u32)
+	};
+	let row_height = |row: &[RgbaImage]| row.iter().map(RgbaImage::height).max().unwrap_or(0);
+	let width = rows.iter().map(|row| row_width(row)).max().unwrap_or(0);
+	let height = rows.iter().map(|row| row_height(row)).sum::<u32>()
+		+ gap_px.saturating_mul(rows.len().saturating_sub(1) as u32);
+	let mut canvas = RgbaImage::from_pixel(width.max(1), height.max(1), Rgba([0, 0, 0, 0]));
+	let mut y = 0;
+
+	for row in rows {
+		let mut x = 0;
+
+		for image in row {
+			imageops::replace(&mut canvas, image, i64::from(x), i64::from(y));
+
+			x += image.width() + gap_px;
+		}
+
+		y += row_height(row) + gap_px;
+	}
+
+	canvas
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::collage;
+
+	#[test]
+	fn collage_packs_regions_into_rows_with_gaps() {
+		let red = Rgba([255, 0, 0, 255]);
+		let green = Rgba([0, 255, 0, 255]);
+		let blue = Rgba([0, 0, 255, 255]);
+		let images = [
+			RgbaImage::from_pixel(20, 10, red),
+			RgbaImage::from_pixel(10, 30, green),
+			RgbaImage::from_pixel(15, 5, blue),
+		];
+		let composed = collage::compose_collage(&images, 4);
+
+		// Two per row: 20 + 4 + 10 wide, then rows of 30 and 5 with one gap between them.
+		assert_eq!(composed.dimensions(), (34, 39));
+		assert_eq!(*composed.get_pixel(0, 0), red);
+		assert_eq!(*composed.get_pixel(24, 29), green);
+		assert_eq!(*composed.get_pixel(14, 38), blue);
+		assert_eq!(composed.get_pixel(21, 0)[3], 0);
+		assert_eq!(composed.get_pixel(0, 20)[3], 0);
+	}
+
+	#[test]
+	fn collage_of_one_region_is_that_region() {
+		let image = RgbaImage::from_pixel(7, 3, Rgba([1, 2, 3, 255]));
+
+		assert_eq!(collage::compose_collage(std::slice::from_ref(&image), 16), image);
+	}
+}
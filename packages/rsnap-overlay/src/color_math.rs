@@ -0,0 +1,144 @@
+use std::f64::consts::PI;
+
+use crate::state::Rgb;
+
+/// D65 reference white, scaled so Y is 1.
+const WHITE_D65: [f64; 3] = [0.950_47, 1.0, 1.088_83];
+
+/// A CIELAB color under the D65 white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Lab {
+	pub(crate) l: f64,
+	pub(crate) a: f64,
+	pub(crate) b: f64,
+}
+impl Lab {
+	/// Converts an sRGB sample through linear light and XYZ.
+	pub(crate) fn from_rgb(rgb: Rgb) -> Self {
+		let linear = |channel: u8| {
+			let value = f64::from(channel) / 255.0;
+
+			if value <= 0.040_45 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+		};
+		let (r, g, b) = (linear(rgb.r), linear(rgb.g), linear(rgb.b));
+		let xyz = [
+			0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b,
+			0.212_672_9 * r + 0.715_152_2 * g + 0.072_175_0 * b,
+			0.019_333_9 * r + 0.119_192_0 * g + 0.950_304_1 * b,
+		];
+		let [fx, fy, fz] = [0, 1, 2].map(|index| {
+			let t = xyz[index] / WHITE_D65[index];
+
+			if t > 216.0 / 24_389.0 { t.cbrt() } else { (24_389.0 / 27.0 * t + 16.0) / 116.0 }
+		});
+
+		Self { l: 116.0 * fy - 16.0, a: 500.0 * (fx - fy), b: 200.0 * (fy - fz) }
+	}
+}
+
+/// The CIEDE2000 color difference between two colors, with unit weighting factors.
+pub(crate) fn ciede2000(first: Lab, second: Lab) -> f64 {
+	let chroma = |lab: Lab| lab.a.hypot(lab.b);
+	let mean_chroma = (chroma(first) + chroma(second)) / 2.0;
+	let g = 0.5 * (1.0 - (mean_chroma.powi(7) / (mean_chroma.powi(7) + 25_f64.powi(7))).sqrt());
+	let adjusted = |lab: Lab| {
+		let a = lab.a * (1.0 + g);
+		let c = a.hypot(lab.b);
+		let h = if a == 0.0 && lab.b == 0.0 { 0.0 } else { lab.b.atan2(a).rem_euclid(2.0 * PI) };
+
+		(c, h)
+	};
+	let (c1, h1) = adjusted(first);
+	let (c2, h2) = adjusted(second);
+	let delta_l = second.l - first.l;
+	let delta_c = c2 - c1;
+	let delta_h = if c1 * c2 == 0.0 {
+		0.0
+	} else if (h2 - h1).abs() <= PI {
+		h2 - h1
+	} else if h2 <= h1 {
+		h2 - h1 + 2.0 * PI
+	} else {
+		h2 - h1 - 2.0 * PI
+	};
+	let delta_big_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).sin();
+	let mean_l = (first.l + second.l) / 2.0;
+	let mean_c = (c1 + c2) / 2.0;
+	let mean_h = if c1 * c2 == 0.0 {
+		h1 + h2
+	} else if (h1 - h2).abs() <= PI {
+		(h1 + h2) / 2.0
+	} else if h1 + h2 < 2.0 * PI {
+		(h1 + h2 + 2.0 * PI) / 2.0
+	} else {
+		(h1 + h2 - 2.0 * PI) / 2.0
+	};
+	let t = 1.0 - 0.17 * (mean_h - 30_f64.to_radians()).cos()
+		+ 0.24 * (2.0 * mean_h).cos()
+		+ 0.32 * (3.0 * mean_h + 6_f64.to_radians()).cos()
+		- 0.20 * (4.0 * mean_h - 63_f64.to_radians()).cos();
+	let delta_theta = 30_f64.to_radians() * (-((mean_h.to_degrees() - 275.0) / 25.0).powi(2)).exp();
+	let r_c = 2.0 * (mean_c.powi(7) / (mean_c.powi(7) + 25_f64.powi(7))).sqrt();
+	let s_l = 1.0 + 0.015 * (mean_l - 50.0).powi(2) / (20.0 + (mean_l - 50.0).powi(2)).sqrt();
+	let s_c = 1.0 + 0.045 * mean_c;
+	let s_h = 1.0 + 0.015 * mean_c * t;
+	let r_t = -(2.0 * delta_theta).sin() * r_c;
+	let (l_term, c_term, h_term) = (delta_l / s_l, delta_c / s_c, delta_big_h / s_h);
+
+	(l_term.powi(2) + c_term.powi(2) + h_term.powi(2) + r_t * c_term * h_term).sqrt()
+}
+
+/// The CIEDE2000 difference between two sRGB samples.
+pub(crate) fn delta_e(first: Rgb, second: Rgb) -> f64 {
+	ciede2000(Lab::from_rgb(first), Lab::from_rgb(second))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::color_math::{self, Lab};
+	use crate::state::Rgb;
+
+	fn lab(l: f64, a: f64, b: f64) -> Lab {
+		Lab { l, a, b }
+	}
+
+	fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+		assert!(
+			(actual - expected).abs() <= tolerance,
+			"{actual} is not within {tolerance} of {expected}"
+		);
+	}
+
+	#[test]
+	fn ciede2000_matches_the_sharma_reference_pairs() {
+		// Sharma, Wu and Dalal (2005), pairs 1, 2, 7, 13, 15, 17 and 25.
+		let pairs = [
+			(lab(50.0, 2.6772, -79.7751), lab(50.0, 0.0, -82.7485), 2.0425),
+			(lab(50.0, 3.1571, -77.2803), lab(50.0, 0.0, -82.7485), 2.8615),
+			(lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+			(lab(50.0, 2.49, -0.001), lab(50.0, -2.49, 0.0009), 7.1792),
+			(lab(50.0, 2.49, -0.001), lab(50.0, -2.49, 0.0011), 7.2195),
+			(lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+			(lab(60.2574, -34.0099, 36.2677), lab(60.4626, -34.1751, 39.4387), 1.2644),
+		];
+
+		for (first, second, expected) in pairs {
+			assert_close(color_math::ciede2000(first, second), expected, 1e-4);
+			assert_close(color_math::ciede2000(second, first), expected, 1e-4);
+		}
+	}
+
+	#[test]
+	fn srgb_converts_to_the_expected_lab_values() {
+		let white = Lab::from_rgb(Rgb::new(255, 255, 255));
+		let red = Lab::from_rgb(Rgb::new(255, 0, 0));
+
+		assert_close(white.l, 100.0, 1e-3);
+		assert_close(white.a, 0.0, 1e-2);
+		assert_close(white.b, 0.0, 1e-2);
+		assert_close(red.l, 53.24, 0.01);
+		assert_close(red.a, 80.09, 0.01);
+		assert_close(red.b, 67.20, 0.01);
+		assert_eq!(color_math::delta_e(Rgb::new(12, 34, 56), Rgb::new(12, 34, 56)), 0.0);
+	}
+}
@@ -0,0 +1,75 @@
+/// A color vision deficiency the frozen preview can simulate. View-only: exports are untouched.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum ColorVisionFilter {
+	#[default]
+	Off,
+	Protanopia,
+	Deuteranopia,
+	Tritanopia,
+}
+impl ColorVisionFilter {
+	const ALL: [Self; 4] = [Self::Off, Self::Protanopia, Self::Deuteranopia, Self::Tritanopia];
+
+	pub(crate) const fn label(self) -> &'static str {
+		match self {
+			Self::Off => "Off",
+			Self::Protanopia => "Protanopia",
+			Self::Deuteranopia => "Deuteranopia",
+			Self::Tritanopia => "Tritanopia",
+		}
+	}
+
+	/// The `V` key cycles through [`Self::ALL`], back to `Off`.
+	pub(crate) fn next(self) -> Self {
+		let index = Self::ALL.iter().position(|filter| *filter == self).unwrap_or(0);
+
+		Self::ALL[(index + 1) % Self::ALL.len()]
+	}
+
+	/// Row-major linear-RGB simulation matrix (Machado, Oliveira and Fernandes 2009, full
+	/// severity); `None` when off.
+	pub(crate) const fn matrix(self) -> Option<[[f32; 3]; 3]> {
+		match self {
+			Self::Off => None,
+			Self::Protanopia => Some([
+				[0.152_286, 1.052_583, -0.204_868],
+				[0.114_503, 0.786_281, 0.099_216],
+				[-0.003_882, -0.048_116, 1.051_998],
+			]),
+			Self::Deuteranopia => Some([
+				[0.367_322, 0.860_646, -0.227_968],
+				[0.280_085, 0.672_501, 0.047_413],
+				[-0.011_820, 0.042_940, 0.968_881],
+			]),
+			Self::Tritanopia => Some([
+				[1.255_528, -0.076_749, -0.178_779],
+				[-0.078_411, 0.930_809, 0.147_602],
+				[0.004_733, 0.691_367, 0.303_900],
+			]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::color_vision::ColorVisionFilter;
+
+	#[test]
+	fn simulations_keep_neutral_grays_and_cycle_back_to_off() {
+		let mut filter = ColorVisionFilter::Off;
+
+		assert_eq!(filter.matrix(), None);
+
+		for _ in 0..3 {
+			filter = filter.next();
+
+			let matrix = filter.matrix().expect("simulation matrix");
+
+			for row in matrix {
+				assert!((row.iter().sum::<f32>() - 1.0).abs() < 1e-5, "{filter:?}: {row:?}");
+			}
+		}
+
+		assert_eq!(filter.next(), ColorVisionFilter::Off);
+	}
+}
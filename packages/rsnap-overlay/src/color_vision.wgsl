@@ -0,0 +1,44 @@
+struct VsOut {
+	@builtin(position) pos: vec4<f32>,
+	@location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VsOut {
+	// Fullscreen triangle, with the same upright mapping as the mipgen surface pass.
+	var pos = array<vec2<f32>, 3>(
+		vec2<f32>(-1.0, -1.0),
+		vec2<f32>( 3.0, -1.0),
+		vec2<f32>(-1.0,  3.0),
+	);
+	var uv = array<vec2<f32>, 3>(
+		vec2<f32>(0.0,  1.0),
+		vec2<f32>(2.0,  1.0),
+		vec2<f32>(0.0, -1.0),
+	);
+
+	var out: VsOut;
+	out.pos = vec4<f32>(pos[vertex_index], 0.0, 1.0);
+	out.uv = uv[vertex_index];
+	return out;
+}
+
+struct ColorVisionUniform {
+	// Rows of the linear-RGB simulation matrix; w unused.
+	r: vec4<f32>,
+	g: vec4<f32>,
+	b: vec4<f32>,
+}
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var src_samp: sampler;
+@group(1) @binding(0) var<uniform> u: ColorVisionUniform;
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+	// The frozen texture is sRGB, so the sample is already linear.
+	let c = textureSample(src_tex, src_samp, in.uv);
+	let simulated = vec3<f32>(dot(u.r.xyz, c.rgb), dot(u.g.xyz, c.rgb), dot(u.b.xyz, c.rgb));
+
+	return vec4<f32>(clamp(simulated, vec3<f32>(0.0), vec3<f32>(1.0)), c.a);
+}
@@ -0,0 +1,156 @@
+use image::{Rgba, RgbaImage};
+
+// Largest possible YIQ delta between two colors, used to scale the per-pixel threshold.
+const MAX_YIQ_DELTA: f32 = 35_215.0;
+// How strongly unchanged pixels are faded towards white in the diff image.
+const UNCHANGED_FADE: f32 = 0.1;
+const DIFF_RGBA: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Tunables for [`diff_images`].
+pub struct DiffOptions {
+	/// Per-pixel perceptual sensitivity from 0.0 (any change counts) to 1.0 (nothing counts).
+	pub threshold: f32,
+}
+impl Default for DiffOptions {
+	fn default() -> Self {
+		Self { threshold: 0.1 }
+	}
+}
+
+#[derive(Debug)]
+/// Outcome of comparing two images pixel by pixel.
+pub struct DiffReport {
+	/// Pixels whose perceptual difference exceeds the threshold, including pixels covered by
+	/// only one image when the sizes differ.
+	pub differing_pixels: u64,
+	/// Pixels in the compared canvas, which spans the larger of the two sizes.
+	pub total_pixels: u64,
+	/// Whether the two images have different dimensions.
+	pub size_mismatch: bool,
+	/// Faded copy of the first image with differing pixels painted red.
+	pub diff_image: RgbaImage,
+}
+impl DiffReport {
+	#[must_use]
+	/// Returns the share of differing pixels, from 0.0 to 1.0.
+	pub fn differing_ratio(&self) -> f64 {
+		if self.total_pixels == 0 {
+			return 0.0;
+		}
+
+		self.differing_pixels as f64 / self.total_pixels as f64
+	}
+}
+
+#[must_use]
+/// Compares two images in YIQ color space, so small shifts the eye barely notices (such as
+/// anti-aliasing noise) stay under the threshold while visible changes do not.
+pub fn diff_images(expected: &RgbaImage, actual: &RgbaImage, options: DiffOptions) -> DiffReport {
+	let width = expected.width().max(actual.width());
+	let height = expected.height().max(actual.height());
+	let threshold = options.threshold.clamp(0.0, 1.0);
+	let max_delta = MAX_YIQ_DELTA * threshold * threshold;
+	let mut differing_pixels = 0_u64;
+	let diff_image = RgbaImage::from_fn(width, height, |x, y| {
+		let pixels =
+			(expected.get_pixel_checked(x, y).copied(), actual.get_pixel_checked(x, y).copied());
+		let (Some(expected), Some(actual)) = pixels else {
+			differing_pixels += 1;
+
+			return DIFF_RGBA;
+		};
+
+		if yiq_delta(expected, actual) > max_delta {
+			differing_pixels += 1;
+
+			return DIFF_RGBA;
+		}
+
+		let gray = blend(luma(blend_on_white(expected)), UNCHANGED_FADE).round() as u8;
+
+		Rgba([gray, gray, gray, 255])
+	});
+
+	DiffReport {
+		differing_pixels,
+		total_pixels: u64::from(width) * u64::from(height),
+		size_mismatch: expected.dimensions() != actual.dimensions(),
+		diff_image,
+	}
+}
+
+fn yiq_delta(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+	if a == b {
+		return 0.0;
+	}
+
+	let a = blend_on_white(a);
+	let b = blend_on_white(b);
+	let y = luma(a) - luma(b);
+	let i = in_phase(a) - in_phase(b);
+	let q = quadrature(a) - quadrature(b);
+
+	0.505_3 * y * y + 0.299 * i * i + 0.195_7 * q * q
+}
+
+fn blend_on_white(pixel: Rgba<u8>) -> [f32; 3] {
+	let alpha = f32::from(pixel[3]) / 255.0;
+
+	[0, 1, 2].map(|channel| blend(f32::from(pixel[channel]), alpha))
+}
+
+fn blend(value: f32, alpha: f32) -> f32 {
+	255.0 + (value - 255.0) * alpha
+}
+
+fn luma([r, g, b]: [f32; 3]) -> f32 {
+	r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_23
+}
+
+fn in_phase([r, g, b]: [f32; 3]) -> f32 {
+	r * 0.595_978 - g * 0.274_176_1 - b * 0.321_801_9
+}
+
+fn quadrature([r, g, b]: [f32; 3]) -> f32 {
+	r * 0.211_470_17 - g * 0.522_617_1 + b * 0.311_146_94
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::diff::{self, DiffOptions};
+
+	#[test]
+	fn diff_counts_visible_changes_and_size_mismatches() {
+		let base = RgbaImage::from_pixel(4, 4, Rgba([120, 120, 120, 255]));
+		let same = diff::diff_images(&base, &base, DiffOptions::default());
+
+		assert_eq!(same.differing_pixels, 0);
+		assert!(!same.size_mismatch);
+
+		let mut changed = base.clone();
+
+		changed.put_pixel(1, 1, Rgba([121, 120, 120, 255]));
+		changed.put_pixel(2, 2, Rgba([255, 0, 0, 255]));
+
+		let report = diff::diff_images(&base, &changed, DiffOptions::default());
+
+		assert_eq!(report.differing_pixels, 1);
+		assert_eq!(*report.diff_image.get_pixel(2, 2), Rgba([255, 0, 0, 255]));
+		assert_ne!(*report.diff_image.get_pixel(1, 1), Rgba([255, 0, 0, 255]));
+
+		let strict = diff::diff_images(&base, &changed, DiffOptions { threshold: 0.0 });
+
+		assert_eq!(strict.differing_pixels, 2);
+
+		let wider = RgbaImage::from_pixel(5, 4, Rgba([120, 120, 120, 255]));
+		let mismatch = diff::diff_images(&base, &wider, DiffOptions::default());
+
+		assert!(mismatch.size_mismatch);
+		assert_eq!(mismatch.diff_image.dimensions(), (5, 4));
+		assert_eq!(mismatch.differing_pixels, 4);
+		assert!((mismatch.differing_ratio() - 0.2).abs() < f64::EPSILON);
+	}
+}
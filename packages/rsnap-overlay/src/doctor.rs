@@ -0,0 +1,126 @@
+//! Capture diagnostics behind `rsnap doctor`: probes every backend against the main display.
+
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result};
+
+use crate::backend::{self, CaptureBackendKind};
+use crate::overlay::OverlaySession;
+use crate::state::MonitorRect;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Whether the system lets a backend read screen contents.
+pub enum CapturePermission {
+	/// Capture is allowed.
+	Granted,
+	/// Capture is blocked until the user grants access in system settings.
+	Denied,
+	/// The platform does not gate capture behind a permission.
+	NotRequired,
+	/// The backend does not capture, so there is nothing to check.
+	NotApplicable,
+}
+impl CapturePermission {
+	#[must_use]
+	/// Short label used in diagnostics output.
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Granted => "granted",
+			Self::Denied => "denied",
+			Self::NotRequired => "not required",
+			Self::NotApplicable => "-",
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+/// Result of probing one capture backend.
+pub struct CaptureBackendProbe {
+	/// The probed backend.
+	pub kind: CaptureBackendKind,
+	/// The backend captured the main display successfully.
+	pub available: bool,
+	/// Time taken by the probe capture, when one ran.
+	pub latency: Option<Duration>,
+	/// Screen capture permission as seen by this backend.
+	pub permission: CapturePermission,
+	/// Why the backend is unavailable, or what it does instead of capturing.
+	pub detail: Option<String>,
+}
+
+/// Probes every concrete backend against the main display. Call this from the main thread:
+/// macOS only enumerates displays there.
+pub fn probe_capture_backends() -> Result<Vec<CaptureBackendProbe>> {
+	let monitor = OverlaySession::available_overlay_monitors()
+		.map_err(|err| eyre::eyre!(err))?
+		.into_iter()
+		.next()
+		.ok_or_else(|| eyre::eyre!("No display available to probe"))?;
+
+	Ok(CaptureBackendKind::ALL
+		.into_iter()
+		.filter(|kind| *kind != CaptureBackendKind::Auto)
+		.map(|kind| probe_capture_backend(kind, monitor))
+		.collect())
+}
+
+fn probe_capture_backend(kind: CaptureBackendKind, monitor: MonitorRect) -> CaptureBackendProbe {
+	let permission = capture_permission(kind);
+	let unavailable = |detail: String| CaptureBackendProbe {
+		kind,
+		available: false,
+		latency: None,
+		permission,
+		detail: Some(detail),
+	};
+
+	if let Some(reason) = kind.unsupported_reason() {
+		return unavailable(reason.to_owned());
+	}
+	if kind == CaptureBackendKind::Stub {
+		return CaptureBackendProbe {
+			kind,
+			available: true,
+			latency: None,
+			permission,
+			detail: Some(String::from("captures nothing")),
+		};
+	}
+	if permission == CapturePermission::Denied {
+		return unavailable(String::from("screen recording permission is not granted"));
+	}
+
+	let mut capture_backend = backend::capture_backend(kind);
+	let started_at = Instant::now();
+
+	match capture_backend.probe_monitor_capture(monitor) {
+		Ok(()) => CaptureBackendProbe {
+			kind,
+			available: true,
+			latency: Some(started_at.elapsed()),
+			permission,
+			detail: None,
+		},
+		Err(err) => unavailable(format!("{err:#}")),
+	}
+}
+
+#[cfg(target_os = "macos")]
+fn capture_permission(kind: CaptureBackendKind) -> CapturePermission {
+	match kind {
+		CaptureBackendKind::Sck if objc2_core_graphics::CGPreflightScreenCaptureAccess() => {
+			CapturePermission::Granted
+		},
+		CaptureBackendKind::Sck => CapturePermission::Denied,
+		_ => CapturePermission::NotApplicable,
+	}
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture_permission(kind: CaptureBackendKind) -> CapturePermission {
+	if kind.unsupported_reason().is_some() || kind == CaptureBackendKind::Stub {
+		CapturePermission::NotApplicable
+	} else {
+		CapturePermission::NotRequired
+	}
+}
@@ -0,0 +1,127 @@
+//! Runs user-configured hook commands (OCR, alt text, alerts, appearance toggles) through the
+//! platform shell with a deadline.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Builds a command that runs `command` through `cmd /C` on Windows and `sh -c` elsewhere.
+#[cfg(target_os = "windows")]
+pub(crate) fn shell_command(command: &str) -> Command {
+	let mut shell = Command::new("cmd");
+
+	shell.args(["/C", command]);
+
+	shell
+}
+
+/// Builds a command that runs `command` through `cmd /C` on Windows and `sh -c` elsewhere.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn shell_command(command: &str) -> Command {
+	let mut shell = Command::new("sh");
+
+	shell.args(["-c", command]);
+
+	shell
+}
+
+/// Runs `command` with `stdin` piped in, returning its stdout once it exits successfully.
+///
+/// The command is killed when it outlives `timeout`; a non-zero exit is an error too. Stderr is
+/// discarded.
+pub(crate) fn run_with_timeout(
+	mut command: Command,
+	stdin: Option<Vec<u8>>,
+	timeout: Duration,
+) -> Result<Vec<u8>> {
+	let mut child = command
+		.stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.wrap_err("Failed to start the command")?;
+	let mut stdout =
+		child.stdout.take().ok_or_else(|| eyre::eyre!("Command stdout unavailable"))?;
+	// Feed and drain on helper threads so a command that reads lazily cannot deadlock the pipe.
+	// Neither is joined on timeout: a grandchild may still hold the pipes open.
+	let writer = child.stdin.take().zip(stdin).map(|(mut pipe, bytes)| {
+		thread::spawn(move || {
+			// A hook that ignores stdin closes the pipe early; that is not an error.
+			let _ = pipe.write_all(&bytes);
+		})
+	});
+	let reader = thread::spawn(move || {
+		let mut output = Vec::new();
+
+		stdout.read_to_end(&mut output).map(|_| output)
+	});
+	let started_at = Instant::now();
+	let status = loop {
+		if let Some(status) = child.try_wait().wrap_err("Failed to poll the command")? {
+			break status;
+		}
+		if started_at.elapsed() >= timeout {
+			let _ = child.kill();
+			let _ = child.wait();
+
+			return Err(eyre::eyre!("Command timed out after {timeout:?}"));
+		}
+
+		thread::sleep(POLL_INTERVAL);
+	};
+
+	if let Some(writer) = writer {
+		let _ = writer.join();
+	}
+
+	let output = reader
+		.join()
+		.map_err(|_| eyre::eyre!("Command output reader panicked"))?
+		.wrap_err("Failed to read the command output")?;
+
+	if !status.success() {
+		return Err(eyre::eyre!("Command exited with {status}"));
+	}
+
+	Ok(output)
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+	use std::time::Duration;
+
+	use crate::external_command;
+
+	#[test]
+	fn run_with_timeout_pipes_stdin_to_stdout_and_reports_failures() {
+		let echoed = external_command::run_with_timeout(
+			external_command::shell_command("cat"),
+			Some(b"rsnap".to_vec()),
+			Duration::from_secs(5),
+		)
+		.expect("cat should echo its input");
+
+		assert_eq!(echoed, b"rsnap");
+		assert!(
+			external_command::run_with_timeout(
+				external_command::shell_command("exit 3"),
+				None,
+				Duration::from_secs(5),
+			)
+			.is_err()
+		);
+		assert!(
+			external_command::run_with_timeout(
+				external_command::shell_command("sleep 5"),
+				None,
+				Duration::from_millis(100),
+			)
+			.is_err()
+		);
+	}
+}
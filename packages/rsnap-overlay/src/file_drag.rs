@@ -0,0 +1,203 @@
+//! Dragging a saved capture out of an rsnap window into another app as a file.
+
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use winit::window::Window;
+
+/// Starts dragging the file at `path` out of `window`, so it can be dropped into a chat, a
+/// design tool or a file manager. Call it while the pointer button that started the drag is
+/// still held.
+///
+/// - macOS: an `NSDraggingSession` offering the file URL; the drag runs after this returns.
+/// - Windows: `SHDoDragDrop` with a shell data object for the file; it returns once the file was
+///   dropped or the drag was cancelled.
+/// - Linux: not supported yet, so this returns an error.
+pub fn start_file_drag(window: &Window, path: &Path) -> Result<()> {
+	imp::start_file_drag(window, path)
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+	use std::cell::OnceCell;
+	use std::path::Path;
+
+	use color_eyre::eyre::{self, Result};
+	use objc2::rc::Retained;
+	use objc2::runtime::{AnyObject, ProtocolObject};
+	use objc2::{AnyThread, MainThreadMarker, MainThreadOnly};
+	use objc2_app_kit::{
+		NSApplication, NSDragOperation, NSDraggingContext, NSDraggingItem, NSDraggingSession,
+		NSDraggingSource, NSImage, NSView,
+	};
+	use objc2_foundation::{NSArray, NSObject, NSObjectProtocol, NSString, NSURL};
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use winit::window::Window;
+
+	objc2::define_class!(
+		/// Offers dragged files as copies, so the drop target never moves rsnap's file away.
+		#[unsafe(super = NSObject)]
+		#[thread_kind = MainThreadOnly]
+		#[name = "RsnapFileDragSource"]
+		struct FileDragSource;
+
+		unsafe impl NSObjectProtocol for FileDragSource {}
+
+		unsafe impl NSDraggingSource for FileDragSource {
+			#[unsafe(method(draggingSession:sourceOperationMaskForDraggingContext:))]
+			fn source_operation_mask(
+				&self,
+				_session: &NSDraggingSession,
+				_context: NSDraggingContext,
+			) -> NSDragOperation {
+				NSDragOperation::Copy
+			}
+		}
+	);
+
+	thread_local! {
+		static DRAG_SOURCE: OnceCell<Retained<FileDragSource>> = const { OnceCell::new() };
+	}
+
+	pub(super) fn start_file_drag(window: &Window, path: &Path) -> Result<()> {
+		let mtm = MainThreadMarker::new()
+			.ok_or_else(|| eyre::eyre!("File drags must start on the main thread"))?;
+		let handle =
+			window.window_handle().map_err(|err| eyre::eyre!("No window to drag from: {err}"))?;
+		let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+			return Err(eyre::eyre!("File drags need an AppKit window"));
+		};
+		// SAFETY: `ns_view` comes from a live winit window, which outlives this call, and the view
+		// is only messaged on the main thread.
+		let view = unsafe { appkit.ns_view.cast::<NSView>().as_ref() };
+		// The session starts from the mouse event that is being handled right now.
+		let event = NSApplication::sharedApplication(mtm)
+			.currentEvent()
+			.ok_or_else(|| eyre::eyre!("No mouse event to start the drag from"))?;
+		let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+		let item = NSDraggingItem::initWithPasteboardWriter(
+			NSDraggingItem::alloc(),
+			ProtocolObject::from_ref(&*url),
+		);
+		let image = NSImage::initWithContentsOfURL(NSImage::alloc(), &url);
+
+		// SAFETY: drag item contents must be an `NSImage`, which `image` is.
+		unsafe {
+			item.setDraggingFrame_contents(
+				view.bounds(),
+				image.as_deref().map(AsRef::<AnyObject>::as_ref),
+			);
+		}
+
+		let items = NSArray::from_retained_slice(&[item]);
+
+		DRAG_SOURCE.with(|source| {
+			// SAFETY: `init` is `NSObject`'s designated initializer, which the class inherits.
+			let source = source
+				.get_or_init(|| unsafe { objc2::msg_send![FileDragSource::alloc(mtm), init] });
+
+			view.beginDraggingSessionWithItems_event_source(
+				&items,
+				&event,
+				ProtocolObject::from_ref(&**source),
+			);
+		});
+
+		tracing::info!(op = "file_drag.start", path = %path.display(), "Started a file drag.");
+
+		Ok(())
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+	use std::ffi::c_void;
+	use std::os::windows::ffi::OsStrExt;
+	use std::path::Path;
+	use std::ptr;
+
+	use color_eyre::eyre::{self, Result};
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use windows_sys::Win32::Foundation::HWND;
+	use windows_sys::Win32::System::Ole::DROPEFFECT_COPY;
+	use windows_sys::Win32::UI::Shell::{
+		ILClone, ILCreateFromPathW, ILFindLastID, ILFree, ILRemoveLastID, SHCreateDataObject,
+		SHDoDragDrop,
+	};
+	use windows_sys::core::{GUID, IUnknown_Vtbl};
+	use winit::window::Window;
+
+	const IID_IDATAOBJECT: GUID = GUID::from_u128(0x0000_010e_0000_0000_c000_0000_0000_0046);
+
+	pub(super) fn start_file_drag(window: &Window, path: &Path) -> Result<()> {
+		let handle =
+			window.window_handle().map_err(|err| eyre::eyre!("No window to drag from: {err}"))?;
+		let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+			return Err(eyre::eyre!("File drags need a Win32 window"));
+		};
+		let hwnd = handle.hwnd.get() as HWND;
+		let wide_path = path.as_os_str().encode_wide().chain(Some(0)).collect::<Vec<_>>();
+
+		// SAFETY: `wide_path` is NUL-terminated, every ID list is freed once the data object has
+		// copied it, and the data object is released after the modal drag loop returns. winit
+		// initializes OLE on the window's thread, which `SHDoDragDrop` requires.
+		unsafe {
+			let item = ILCreateFromPathW(wide_path.as_ptr());
+
+			if item.is_null() {
+				return Err(eyre::eyre!("Unable to resolve {} for dragging", path.display()));
+			}
+
+			let folder = ILClone(item);
+
+			ILRemoveLastID(folder);
+
+			let child = ILFindLastID(item).cast_const();
+			let mut data_object: *mut c_void = ptr::null_mut();
+			let created = SHCreateDataObject(
+				folder,
+				1,
+				&child,
+				ptr::null_mut(),
+				&IID_IDATAOBJECT,
+				&mut data_object,
+			);
+
+			ILFree(folder);
+			ILFree(item);
+
+			if created < 0 || data_object.is_null() {
+				return Err(eyre::eyre!("SHCreateDataObject failed: {created:#x}"));
+			}
+
+			let mut effect = 0;
+			let dragged =
+				SHDoDragDrop(hwnd, data_object, ptr::null_mut(), DROPEFFECT_COPY, &mut effect);
+			let vtable = *data_object.cast::<*const IUnknown_Vtbl>();
+
+			((*vtable).Release)(data_object);
+
+			if dragged < 0 {
+				return Err(eyre::eyre!("SHDoDragDrop failed: {dragged:#x}"));
+			}
+		}
+
+		tracing::info!(op = "file_drag.finish", path = %path.display(), "Finished a file drag.");
+
+		Ok(())
+	}
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod imp {
+	use std::path::Path;
+
+	use color_eyre::eyre::{self, Result};
+	use winit::window::Window;
+
+	pub(super) fn start_file_drag(window: &Window, path: &Path) -> Result<()> {
+		let _ = window;
+
+		Err(eyre::eyre!("Dragging {} out of rsnap is not supported on Linux", path.display()))
+	}
+}
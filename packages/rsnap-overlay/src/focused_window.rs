@@ -0,0 +1,80 @@
+//! Lookup of the window that has keyboard focus, so it can be captured without showing the
+//! overlay first.
+
+use color_eyre::eyre::Result;
+
+use crate::state::WindowRect;
+
+/// Returns the focused window in global points, or `None` when focus is on the desktop or on one
+/// of this process's own windows.
+pub(crate) fn focused_window() -> Result<Option<WindowRect>> {
+	imp::focused_window()
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+	use std::process;
+
+	use color_eyre::eyre::Result;
+	use objc2_app_kit::NSWorkspace;
+
+	use crate::backend;
+	use crate::state::WindowRect;
+
+	pub(super) fn focused_window() -> Result<Option<WindowRect>> {
+		let Some(app) = NSWorkspace::sharedWorkspace().frontmostApplication() else {
+			return Ok(None);
+		};
+		let Ok(pid) = u32::try_from(app.processIdentifier()) else {
+			return Ok(None);
+		};
+
+		if pid == process::id() {
+			return Ok(None);
+		}
+
+		Ok(backend::frontmost_window_of_pid(pid))
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+	use std::process;
+
+	use color_eyre::eyre::{Result, WrapErr};
+	use xcap::Window;
+
+	use crate::state::WindowRect;
+
+	pub(super) fn focused_window() -> Result<Option<WindowRect>> {
+		let windows = Window::all().wrap_err("xcap Window::all failed")?;
+		let Some(window) = windows.into_iter().find(|window| window.is_focused().unwrap_or(false))
+		else {
+			return Ok(None);
+		};
+
+		if window.pid()? == process::id() || window.is_minimized().unwrap_or(true) {
+			return Ok(None);
+		}
+
+		Ok(Some(WindowRect {
+			window_id: Some(window.id()?),
+			x: i64::from(window.x()?),
+			y: i64::from(window.y()?),
+			width: i64::from(window.width()?),
+			height: i64::from(window.height()?),
+		}))
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+	use color_eyre::eyre::Result;
+
+	use crate::state::WindowRect;
+	use crate::x11_capture_linux;
+
+	pub(super) fn focused_window() -> Result<Option<WindowRect>> {
+		x11_capture_linux::active_window()
+	}
+}
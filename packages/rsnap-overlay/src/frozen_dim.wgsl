@@ -0,0 +1,61 @@
+struct VsOut {
+	@builtin(position) pos: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VsOut {
+	// Fullscreen triangle.
+	var pos = array<vec2<f32>, 3>(
+		vec2<f32>(-1.0, -1.0),
+		vec2<f32>( 3.0, -1.0),
+		vec2<f32>(-1.0,  3.0),
+	);
+
+	var out: VsOut;
+	out.pos = vec4<f32>(pos[vertex_index], 0.0, 1.0);
+	return out;
+}
+
+struct FrozenDimUniform {
+	// min.xy, size.xy of the selection in *physical pixels*; the selection itself is never dimmed.
+	selection_min_size: vec4<f32>,
+	// surface_size_px.xy, vignette, noise
+	surface_vignette_noise: vec4<f32>,
+	// x: flat dim opacity; yzw unused.
+	dim: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> u: FrozenDimUniform;
+
+fn hash(p: vec2<f32>) -> f32 {
+	return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+	let surface_size = u.surface_vignette_noise.xy;
+	if surface_size.x <= 0.0 || surface_size.y <= 0.0 {
+		return vec4<f32>(0.0);
+	}
+
+	let p = in.pos.xy;
+	let sel_min = u.selection_min_size.xy;
+	let sel_max = sel_min + max(u.selection_min_size.zw, vec2<f32>(0.0));
+	if all(p >= sel_min) && all(p < sel_max) {
+		return vec4<f32>(0.0);
+	}
+
+	let vignette = clamp(u.surface_vignette_noise.z, 0.0, 1.0);
+	let noise = clamp(u.surface_vignette_noise.w, 0.0, 1.0);
+	let dim = clamp(u.dim.x, 0.0, 1.0);
+	// A light base dim everywhere outside the selection, deepening towards the surface corners.
+	let centered = (p / surface_size) * 2.0 - vec2<f32>(1.0);
+	let falloff = smoothstep(0.25, 1.45, length(centered));
+	let shade = vignette * (0.3 + 0.55 * falloff);
+	// Per-pixel grain keeps large dimmed areas from banding on 8-bit surfaces.
+	let grain = noise * 0.2 * hash(floor(p));
+	let alpha = clamp(dim + shade + grain, 0.0, 1.0);
+
+	// Premultiplied black.
+	return vec4<f32>(0.0, 0.0, 0.0, alpha);
+}
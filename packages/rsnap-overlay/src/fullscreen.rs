@@ -0,0 +1,65 @@
+//! Detection of a focused application that fills a whole display, such as a game running
+//! full-screen, so the app can keep its global shortcuts out of the way meanwhile.
+
+/// Returns the name of the focused application when one of its windows covers an entire display.
+///
+/// Always `None` on Linux, where focus and window geometry are not queried yet.
+#[must_use]
+pub fn fullscreen_foreground_app() -> Option<String> {
+	imp::fullscreen_foreground_app()
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+	use objc2_app_kit::NSWorkspace;
+
+	use crate::backend;
+
+	pub(super) fn fullscreen_foreground_app() -> Option<String> {
+		let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+		let pid = u32::try_from(app.processIdentifier()).ok()?;
+
+		if !backend::display_covering_window_pids().contains(&pid) {
+			return None;
+		}
+
+		Some(app.localizedName().map_or_else(|| format!("pid {pid}"), |name| name.to_string()))
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+	use std::process;
+
+	use xcap::Window;
+
+	pub(super) fn fullscreen_foreground_app() -> Option<String> {
+		let window =
+			Window::all().ok()?.into_iter().find(|window| window.is_focused().unwrap_or(false))?;
+
+		if window.pid().ok()? == process::id() || window.is_minimized().unwrap_or(true) {
+			return None;
+		}
+
+		let monitor = window.current_monitor().ok()?;
+		let covers_monitor = window.x().ok()? <= monitor.x().ok()?
+			&& window.y().ok()? <= monitor.y().ok()?
+			&& i64::from(window.x().ok()?) + i64::from(window.width().ok()?)
+				>= i64::from(monitor.x().ok()?) + i64::from(monitor.width().ok()?)
+			&& i64::from(window.y().ok()?) + i64::from(window.height().ok()?)
+				>= i64::from(monitor.y().ok()?) + i64::from(monitor.height().ok()?);
+
+		if !covers_monitor {
+			return None;
+		}
+
+		window.app_name().ok().filter(|name| !name.is_empty())
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+	pub(super) fn fullscreen_foreground_app() -> Option<String> {
+		None
+	}
+}
@@ -0,0 +1,197 @@
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::state::RectPoints;
+
+/// A highlighter stroke, positioned in pixels of the selection image. It is multiplied onto
+/// what is beneath, so dark text under a light marker stays readable.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub(crate) struct HighlightStroke {
+	pub(crate) points: Vec<(i64, i64)>,
+	pub(crate) width_px: u32,
+	pub(crate) rgb: [u8; 3],
+}
+impl HighlightStroke {
+	/// The pixels the stroke can touch, as `(left, top, right, bottom)` with exclusive ends.
+	fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+		let reach = i64::from(self.width_px / 2) + 1;
+		let (mut left, mut top) = *self.points.first()?;
+		let (mut right, mut bottom) = (left, top);
+
+		for &(x, y) in &self.points {
+			left = left.min(x);
+			top = top.min(y);
+			right = right.max(x);
+			bottom = bottom.max(y);
+		}
+
+		Some((left - reach, top - reach, right + reach + 1, bottom + reach + 1))
+	}
+
+	/// Coverage of each pixel in the `width`×`height` block whose top-left is `origin`; where
+	/// segments overlap the stroke does not get darker.
+	fn coverage(&self, origin: (i64, i64), width: u32, height: u32) -> Vec<f32> {
+		let mut coverage = vec![0.0_f32; width as usize * height as usize];
+		let radius = self.width_px.max(1) as f32 / 2.0;
+		let reach = i64::from(self.width_px / 2) + 1;
+		let segments = self.points.windows(2).map(|pair| (pair[0], pair[1])).chain(
+			self.points.first().filter(|_| self.points.len() == 1).map(|&point| (point, point)),
+		);
+
+		for (start, end) in segments {
+			let left = (start.0.min(end.0) - reach - origin.0).max(0);
+			let top = (start.1.min(end.1) - reach - origin.1).max(0);
+			let right = (start.0.max(end.0) + reach + 1 - origin.0).min(i64::from(width));
+			let bottom = (start.1.max(end.1) + reach + 1 - origin.1).min(i64::from(height));
+			let (ax, ay) = (start.0 as f32 + 0.5, start.1 as f32 + 0.5);
+			let (dx, dy) = ((end.0 - start.0) as f32, (end.1 - start.1) as f32);
+			let length_squared = (dx * dx + dy * dy).max(f32::EPSILON);
+
+			for y in top..bottom {
+				for x in left..right {
+					let px = (origin.0 + x) as f32 + 0.5 - ax;
+					let py = (origin.1 + y) as f32 + 0.5 - ay;
+					let t = ((px * dx + py * dy) / length_squared).clamp(0.0, 1.0);
+					let distance = ((px - dx * t).powi(2) + (py - dy * t).powi(2)).sqrt();
+					let value = (radius - distance + 0.5).clamp(0.0, 1.0);
+					let cell = &mut coverage[y as usize * width as usize + x as usize];
+
+					*cell = cell.max(value);
+				}
+			}
+		}
+
+		coverage
+	}
+}
+
+/// Multiplies `strokes` onto the cropped export in drawing order.
+pub(crate) fn apply_highlights(image: &mut RgbaImage, strokes: &[HighlightStroke]) {
+	for stroke in strokes {
+		apply_stroke(image, stroke, (0, 0));
+	}
+}
+
+/// Rasterizes `strokes[index]` for display over the frozen screen: each covered pixel holds the
+/// multiplied color of what is beneath, including earlier strokes, with the stroke's coverage as
+/// alpha. Returns it with the selection pixel its top-left lands on.
+pub(crate) fn render_highlight(
+	frozen: &RgbaImage,
+	selection_px: RectPoints,
+	strokes: &[HighlightStroke],
+	index: usize,
+) -> Option<(RgbaImage, (i64, i64))> {
+	let stroke = strokes.get(index)?;
+	let (left, top, right, bottom) = stroke.bounds()?;
+	let left = left.max(0);
+	let top = top.max(0);
+	let right = right.min(i64::from(selection_px.width));
+	let bottom = bottom.min(i64::from(selection_px.height));
+
+	if right <= left || bottom <= top {
+		return None;
+	}
+
+	let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+	let frozen_x = u32::try_from(i64::from(selection_px.x) + left).ok()?;
+	let frozen_y = u32::try_from(i64::from(selection_px.y) + top).ok()?;
+
+	if frozen_x + width > frozen.width() || frozen_y + height > frozen.height() {
+		return None;
+	}
+
+	let mut beneath =
+		image::imageops::crop_imm(frozen, frozen_x, frozen_y, width, height).to_image();
+
+	for earlier in &strokes[..index] {
+		apply_stroke(&mut beneath, earlier, (left, top));
+	}
+
+	let coverage = stroke.coverage((left, top), width, height);
+
+	for (pixel, value) in beneath.pixels_mut().zip(coverage) {
+		let [r, g, b, _] = multiply(pixel.0, stroke.rgb);
+
+		*pixel = Rgba([r, g, b, (value * 255.0).round() as u8]);
+	}
+
+	Some((beneath, (left, top)))
+}
+
+/// Applies `stroke` to `image`, whose top-left pixel is `origin` in selection pixels.
+fn apply_stroke(image: &mut RgbaImage, stroke: &HighlightStroke, origin: (i64, i64)) {
+	let (width, height) = image.dimensions();
+	let coverage = stroke.coverage(origin, width, height);
+
+	for (pixel, value) in image.pixels_mut().zip(coverage) {
+		if value <= 0.0 {
+			continue;
+		}
+
+		let multiplied = multiply(pixel.0, stroke.rgb);
+
+		for (channel, target) in pixel.0.iter_mut().zip(multiplied).take(3) {
+			let base = f32::from(*channel);
+
+			*channel = (base + (f32::from(target) - base) * value).round() as u8;
+		}
+	}
+}
+
+fn multiply([r, g, b, a]: [u8; 4], rgb: [u8; 3]) -> [u8; 4] {
+	let channel = |base: u8, ink: u8| ((u16::from(base) * u16::from(ink) + 127) / 255) as u8;
+
+	[channel(r, rgb[0]), channel(g, rgb[1]), channel(b, rgb[2]), a]
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::highlighter::{self, HighlightStroke};
+	use crate::state::RectPoints;
+
+	fn stroke(points: Vec<(i64, i64)>) -> HighlightStroke {
+		HighlightStroke { points, width_px: 10, rgb: [255, 230, 0] }
+	}
+
+	#[test]
+	fn highlights_multiply_so_dark_pixels_stay_dark() {
+		let mut image = RgbaImage::from_pixel(60, 30, Rgba([200, 200, 200, 255]));
+
+		image.put_pixel(30, 15, Rgba([10, 10, 10, 255]));
+
+		highlighter::apply_highlights(&mut image, &[stroke(vec![(5, 15), (55, 15)])]);
+
+		assert_eq!(*image.get_pixel(20, 15), Rgba([200, 180, 0, 255]));
+		assert_eq!(*image.get_pixel(30, 15), Rgba([10, 9, 0, 255]));
+		assert_eq!(*image.get_pixel(20, 2), Rgba([200, 200, 200, 255]));
+	}
+
+	#[test]
+	fn overlapping_segments_of_one_stroke_do_not_darken_twice() {
+		let mut image = RgbaImage::from_pixel(60, 30, Rgba([200, 200, 200, 255]));
+
+		highlighter::apply_highlights(&mut image, &[stroke(vec![(5, 15), (40, 15), (10, 15)])]);
+
+		assert_eq!(*image.get_pixel(20, 15), Rgba([200, 180, 0, 255]));
+	}
+
+	#[test]
+	fn rendered_highlight_matches_the_export_when_drawn_over_the_screen() {
+		let frozen = RgbaImage::from_fn(80, 40, |x, y| Rgba([x as u8 * 3, y as u8 * 5, 90, 255]));
+		let selection_px = RectPoints::new(10, 5, 60, 30);
+		let strokes = [stroke(vec![(5, 10), (50, 10)]), stroke(vec![(20, 2), (20, 25)])];
+		let mut export = image::imageops::crop_imm(&frozen, 10, 5, 60, 30).to_image();
+
+		highlighter::apply_highlights(&mut export, &strokes);
+
+		let (rendered, (left, top)) =
+			highlighter::render_highlight(&frozen, selection_px, &strokes, 1).expect("stroke");
+		let (x, y) = (20_u32, 10_u32);
+		let texel = rendered.get_pixel(x - left as u32, y - top as u32);
+
+		assert_eq!(texel.0[3], 255);
+		assert_eq!(texel.0[..3], export.get_pixel(x, y).0[..3]);
+	}
+}
@@ -37,7 +37,7 @@ struct HudBlurUniform {
 	surface_size_px: vec4<f32>,
 	// Reserved tint payload, not used by this pass.
 	tint_rgba: vec4<f32>,
-	// blur_amount, tint_amount, max_lod, _pad
+	// blur_amount, tint_amount, max_lod, dither (0 or 1)
 	effects: vec4<f32>,
 }
 
@@ -53,6 +53,35 @@ fn sd_rounded_rect(p: vec2<f32>, center: vec2<f32>, half_size: vec2<f32>, radius
 	return outside;
 }
 
+// Rank of `p` in the 8×8 Bayer matrix (0..63), built by interleaving the bits of x^y and y.
+fn bayer8(p: vec2<u32>) -> u32 {
+	let x = p.x & 7u;
+	let y = p.y & 7u;
+	let z = x ^ y;
+	return ((z & 1u) << 5u) | ((y & 1u) << 4u) | ((z & 2u) << 2u) | ((y & 2u) << 1u)
+		| ((z & 4u) >> 1u) | ((y & 4u) >> 2u);
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+	let lo = c * 12.92;
+	let hi = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055;
+	return select(hi, lo, c <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+	let lo = c / 12.92;
+	let hi = pow((c + 0.055) / 1.055, vec3<f32>(2.4));
+	return select(hi, lo, c <= vec3<f32>(0.04045));
+}
+
+// Offsets each channel by up to half an 8-bit step in the encoded (sRGB) space the surface is
+// quantized in, so a smooth blur spreads across neighboring levels instead of banding.
+fn ordered_dither(color: vec3<f32>, pos: vec2<f32>) -> vec3<f32> {
+	let threshold = (f32(bayer8(vec2<u32>(pos))) + 0.5) / 64.0 - 0.5;
+	let encoded = linear_to_srgb(max(color, vec3<f32>(0.0))) + threshold / 255.0;
+	return srgb_to_linear(clamp(encoded, vec3<f32>(0.0), vec3<f32>(1.0)));
+}
+
 @fragment
 fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
 	let surface_size = u.surface_size_px.xy;
@@ -97,6 +126,9 @@ fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
 
 		blurred = c / 16.0;
 	}
+	if u.effects.w > 0.5 {
+		blurred = ordered_dither(blurred, pos.xy);
+	}
 
 	return vec4<f32>(blurred * alpha, alpha);
 }
@@ -0,0 +1,212 @@
+//! Key-based string catalogs for the overlay, tray and settings UI.
+//!
+//! Every user-facing string is looked up by a stable dotted key such as `toolbar.pen`. Catalogs
+//! are plain `(key, text)` tables, one per [`Language`]; a key missing from a translation falls
+//! back to English, and a key missing from English is shown as-is so it is easy to spot.
+//! Placeholders are written as `{name}` and filled in by [`tr_args`].
+
+mod de;
+mod en;
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+static ACTIVE_LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+/// A language with a string catalog.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Language {
+	#[default]
+	#[serde(rename = "en")]
+	/// English, also the fallback for unsupported locales.
+	English,
+	#[serde(rename = "de")]
+	/// German.
+	German,
+}
+impl Language {
+	/// Every supported language, in the order the settings picker lists them.
+	pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+	/// ISO 639-1 code, as used in the settings file.
+	#[must_use]
+	pub const fn code(self) -> &'static str {
+		match self {
+			Self::English => "en",
+			Self::German => "de",
+		}
+	}
+
+	/// The language's name in itself, for the language picker.
+	#[must_use]
+	pub const fn native_name(self) -> &'static str {
+		match self {
+			Self::English => "English",
+			Self::German => "Deutsch",
+		}
+	}
+
+	/// Matches a POSIX (`de_DE.UTF-8`) or BCP 47 (`de-AT`) locale by its language part.
+	#[must_use]
+	pub fn from_locale(locale: &str) -> Option<Self> {
+		let language = locale.split(['_', '-', '.', '@']).next()?.trim();
+
+		Self::ALL.into_iter().find(|candidate| candidate.code().eq_ignore_ascii_case(language))
+	}
+
+	/// Looks `key` up in this language's catalog, falling back to English and then to the key.
+	#[must_use]
+	pub fn lookup(self, key: &'static str) -> &'static str {
+		find(self.catalog(), key).or_else(|| find(en::CATALOG, key)).unwrap_or(key)
+	}
+
+	const fn catalog(self) -> &'static [(&'static str, &'static str)] {
+		match self {
+			Self::English => en::CATALOG,
+			Self::German => de::CATALOG,
+		}
+	}
+
+	const fn from_index(index: u8) -> Self {
+		match index {
+			1 => Self::German,
+			_ => Self::English,
+		}
+	}
+}
+
+/// Switches every later [`tr`] lookup in this process to `language`.
+pub fn set_language(language: Language) {
+	ACTIVE_LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// The language [`tr`] currently translates into.
+#[must_use]
+pub fn language() -> Language {
+	Language::from_index(ACTIVE_LANGUAGE.load(Ordering::Relaxed))
+}
+
+/// Picks the catalog for the system locale, or English when it has none.
+///
+/// `LC_ALL`, `LC_MESSAGES` and `LANG` win when set, as they are for apps started from a
+/// terminal; otherwise the platform's preferred UI language is asked for. The answer is cached
+/// for the life of the process.
+#[must_use]
+pub fn detect_language() -> Language {
+	static DETECTED: OnceLock<Language> = OnceLock::new();
+
+	*DETECTED.get_or_init(|| {
+		["LC_ALL", "LC_MESSAGES", "LANG"]
+			.into_iter()
+			.filter_map(|name| std::env::var(name).ok())
+			.find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+			.or_else(system_locale)
+			.and_then(|locale| Language::from_locale(&locale))
+			.unwrap_or_default()
+	})
+}
+
+/// Translates `key` into the active language.
+#[must_use]
+pub fn tr(key: &'static str) -> &'static str {
+	language().lookup(key)
+}
+
+/// Translates `key` and replaces each `{name}` placeholder with its value from `args`.
+#[must_use]
+pub fn tr_args(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+	fill(tr(key), args)
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+	args.iter().fold(template.to_owned(), |text, (name, value)| {
+		text.replace(&format!("{{{name}}}"), &value.to_string())
+	})
+}
+
+fn find(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+	catalog.iter().find(|(candidate, _)| *candidate == key).map(|(_, text)| *text)
+}
+
+#[cfg(target_os = "macos")]
+fn system_locale() -> Option<String> {
+	let output = std::process::Command::new("defaults")
+		.args(["read", "-g", "AppleLanguages"])
+		.output()
+		.ok()?;
+	let text = String::from_utf8_lossy(&output.stdout);
+
+	text.split('"').nth(1).map(str::to_owned)
+}
+
+#[cfg(target_os = "windows")]
+fn system_locale() -> Option<String> {
+	let output = std::process::Command::new("reg")
+		.args(["query", r"HKCU\Control Panel\International", "/v", "LocaleName"])
+		.output()
+		.ok()?;
+	let text = String::from_utf8_lossy(&output.stdout);
+
+	text.lines().find_map(|line| line.split("REG_SZ").nth(1)).map(|value| value.trim().to_owned())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_locale() -> Option<String> {
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::i18n::{self, Language, de, en};
+
+	fn placeholders(text: &str) -> Vec<&str> {
+		let mut names = text
+			.split('{')
+			.skip(1)
+			.filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+			.collect::<Vec<_>>();
+
+		names.sort_unstable();
+
+		names
+	}
+
+	#[test]
+	fn translations_cover_every_english_key_with_the_same_placeholders() {
+		for (key, text) in en::CATALOG {
+			let translated = de::CATALOG.iter().find(|(candidate, _)| candidate == key);
+
+			assert!(translated.is_some(), "`de` is missing `{key}`");
+			assert_eq!(placeholders(translated.unwrap().1), placeholders(text), "`{key}`");
+		}
+		for (key, _) in de::CATALOG {
+			assert!(en::CATALOG.iter().any(|(candidate, _)| candidate == key), "stray `{key}`");
+		}
+	}
+
+	#[test]
+	fn lookup_falls_back_and_fills_placeholders() {
+		assert_eq!(Language::German.lookup("toolbar.pen"), "Stift");
+		assert_eq!(Language::English.lookup("toolbar.pen"), "Pen");
+		assert_eq!(Language::German.lookup("no.such.key"), "no.such.key");
+		assert_eq!(
+			i18n::fill(
+				Language::German.lookup("toolbar.stamp_hint"),
+				&[("tool", &"Stempel"), ("stamp", &"Herz")]
+			),
+			"Stempel: Herz (erneut klicken für den nächsten Stempel)"
+		);
+	}
+
+	#[test]
+	fn locales_match_by_language_part() {
+		assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+		assert_eq!(Language::from_locale("de-AT"), Some(Language::German));
+		assert_eq!(Language::from_locale("en"), Some(Language::English));
+		assert_eq!(Language::from_locale("fr_FR"), None);
+		assert_eq!(Language::from_locale(""), None);
+	}
+}
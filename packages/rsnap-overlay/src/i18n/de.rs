@@ -0,0 +1,93 @@
+//! German strings.
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+	("toolbar.pointer", "Zeiger"),
+	("toolbar.pen", "Stift"),
+	("toolbar.highlighter", "Textmarker"),
+	("toolbar.text", "Text"),
+	("toolbar.mosaic", "Mosaik"),
+	("toolbar.stamp", "Stempel"),
+	("toolbar.callout", "Hinweis"),
+	("toolbar.undo", "Rückgängig"),
+	("toolbar.redo", "Wiederholen"),
+	("toolbar.scroll", "Scroll-Aufnahme ↓"),
+	("toolbar.last_region", "Letzter Bereich"),
+	("toolbar.backdrop", "Hintergrund"),
+	("toolbar.copy", "Kopieren"),
+	("toolbar.save", "Speichern"),
+	("toolbar.done", "Fertig"),
+	("toolbar.stamp_hint", "{tool}: {stamp} (erneut klicken für den nächsten Stempel)"),
+	("stamp.arrow", "Pfeil"),
+	("stamp.check", "Haken"),
+	("stamp.cross", "Kreuz"),
+	("stamp.thumbs_up", "Daumen hoch"),
+	("stamp.heart", "Herz"),
+	("stamp.star", "Stern"),
+	("stamp.fire", "Feuer"),
+	("hud.copy", "Kopieren"),
+	("hud.loupe", "Lupe"),
+	("hud.freeze", "Einfrieren"),
+	("tray.capture", "Aufnehmen"),
+	("tray.capture_preset", "Aufnahmevorlage"),
+	("tray.capture_app_windows", "App-Fenster aufnehmen"),
+	("tray.pins", "Angeheftete Bilder"),
+	("tray.pick_color", "Farbe aufnehmen"),
+	("tray.stop_region_watch", "Bereichsüberwachung beenden"),
+	("tray.interval_capture", "Intervallaufnahme…"),
+	("tray.stop_interval_capture", "Intervallaufnahme beenden"),
+	("tray.identify_displays", "Bildschirme identifizieren"),
+	("tray.pause", "rsnap pausieren"),
+	("tray.profile", "Profil"),
+	("tray.profile_default", "Standard"),
+	("tray.settings", "Einstellungen…"),
+	("tray.quit", "Beenden"),
+	("tray.quit_rsnap", "rsnap beenden"),
+	("tray.check_updates", "Nach Updates suchen…"),
+	("tray.update_available", "Auf {version} aktualisieren…"),
+	("updates.up_to_date", "rsnap {version} ist die neueste Version."),
+	("updates.check_failed", "Suche nach Updates fehlgeschlagen: {error}"),
+	("updates.install_failed", "Update konnte nicht installiert werden: {error}"),
+	("notification.copied", "Aufnahme in die Zwischenablage kopiert"),
+	("notification.saved", "Aufnahme als {file} gespeichert"),
+	("notification.open", "Öffnen"),
+	("notification.copy", "Kopieren"),
+	("notification.delete", "Löschen"),
+	("notification.annotate", "Annotieren"),
+	(
+		"notification.interval_started",
+		"Aufnahme alle {seconds} s; erstes Bild als {file} gespeichert",
+	),
+	("popover.annotate", "Annotieren"),
+	("popover.copy", "Kopieren"),
+	("popover.save", "Speichern"),
+	("popover.dismiss", "Verwerfen"),
+	("popover.pin", "Anheften"),
+	("pins.title", "Pin {index}"),
+	("pins.bring_to_front", "In den Vordergrund"),
+	("pins.snap", "In Ecke verschieben"),
+	("pins.top_left", "Oben links"),
+	("pins.top_right", "Oben rechts"),
+	("pins.bottom_left", "Unten links"),
+	("pins.bottom_right", "Unten rechts"),
+	("pins.click_through", "Klicks durchlassen"),
+	("pins.close", "Schließen"),
+	("pins.close_all", "Alle Pins schließen"),
+	("settings.title", "Einstellungen"),
+	("settings.section.general", "Allgemein"),
+	("settings.section.overlay", "Overlay"),
+	("settings.section.hotkeys", "Tastenkürzel"),
+	("settings.section.capture", "Aufnahme"),
+	("settings.section.output", "Ausgabe"),
+	("settings.section.advanced", "Erweitert"),
+	("settings.section.usage_metrics", "Nutzungsstatistik"),
+	("settings.section.about", "Über"),
+	("settings.search_hint", "Einstellungen durchsuchen"),
+	("settings.language", "Sprache"),
+	("settings.language_system", "System ({language})"),
+	("settings.language_note", "Das Tray-Menü wechselt die Sprache nach einem Neustart von rsnap."),
+	("settings.hotkey_updated", "Aufnahme-Tastenkürzel auf {hotkey} geändert."),
+	(
+		"settings.hotkey_unavailable",
+		"Aufnahme-Tastenkürzel nicht verfügbar. Versuche ein anderes Kürzel.",
+	),
+];
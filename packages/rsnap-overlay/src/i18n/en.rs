@@ -0,0 +1,87 @@
+//! English strings; the reference catalog every other language is checked against.
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+	("toolbar.pointer", "Pointer"),
+	("toolbar.pen", "Pen"),
+	("toolbar.highlighter", "Highlighter"),
+	("toolbar.text", "Text"),
+	("toolbar.mosaic", "Mosaic"),
+	("toolbar.stamp", "Stamp"),
+	("toolbar.callout", "Callout"),
+	("toolbar.undo", "Undo"),
+	("toolbar.redo", "Redo"),
+	("toolbar.scroll", "Scroll Capture ↓"),
+	("toolbar.last_region", "Last Region"),
+	("toolbar.backdrop", "Backdrop"),
+	("toolbar.copy", "Copy"),
+	("toolbar.save", "Save"),
+	("toolbar.done", "Done"),
+	("toolbar.stamp_hint", "{tool}: {stamp} (click again for the next stamp)"),
+	("stamp.arrow", "Arrow"),
+	("stamp.check", "Check"),
+	("stamp.cross", "Cross"),
+	("stamp.thumbs_up", "Thumbs up"),
+	("stamp.heart", "Heart"),
+	("stamp.star", "Star"),
+	("stamp.fire", "Fire"),
+	("hud.copy", "Copy"),
+	("hud.loupe", "Loupe"),
+	("hud.freeze", "Freeze"),
+	("tray.capture", "Capture"),
+	("tray.capture_preset", "Capture Preset"),
+	("tray.capture_app_windows", "Capture App Windows"),
+	("tray.pins", "Pinned Images"),
+	("tray.pick_color", "Pick Color"),
+	("tray.stop_region_watch", "Stop Watching Region"),
+	("tray.interval_capture", "Interval Capture…"),
+	("tray.stop_interval_capture", "Stop Interval Capture"),
+	("tray.identify_displays", "Identify Displays"),
+	("tray.pause", "Pause rsnap"),
+	("tray.profile", "Profile"),
+	("tray.profile_default", "Default"),
+	("tray.settings", "Settings…"),
+	("tray.quit", "Quit"),
+	("tray.quit_rsnap", "Quit rsnap"),
+	("tray.check_updates", "Check for Updates…"),
+	("tray.update_available", "Update to {version}…"),
+	("updates.up_to_date", "rsnap {version} is the latest version."),
+	("updates.check_failed", "Could not check for updates: {error}"),
+	("updates.install_failed", "Could not install the update: {error}"),
+	("notification.copied", "Capture copied to the clipboard"),
+	("notification.saved", "Capture saved as {file}"),
+	("notification.open", "Open"),
+	("notification.copy", "Copy"),
+	("notification.delete", "Delete"),
+	("notification.annotate", "Annotate"),
+	("notification.interval_started", "Capturing every {seconds} s; first frame saved as {file}"),
+	("popover.annotate", "Annotate"),
+	("popover.copy", "Copy"),
+	("popover.save", "Save"),
+	("popover.dismiss", "Dismiss"),
+	("popover.pin", "Pin"),
+	("pins.title", "Pin {index}"),
+	("pins.bring_to_front", "Bring to Front"),
+	("pins.snap", "Snap to Corner"),
+	("pins.top_left", "Top Left"),
+	("pins.top_right", "Top Right"),
+	("pins.bottom_left", "Bottom Left"),
+	("pins.bottom_right", "Bottom Right"),
+	("pins.click_through", "Click Through"),
+	("pins.close", "Close"),
+	("pins.close_all", "Close All Pins"),
+	("settings.title", "Settings"),
+	("settings.section.general", "General"),
+	("settings.section.overlay", "Overlay"),
+	("settings.section.hotkeys", "Hotkeys"),
+	("settings.section.capture", "Capture"),
+	("settings.section.output", "Output"),
+	("settings.section.advanced", "Advanced"),
+	("settings.section.usage_metrics", "Usage metrics"),
+	("settings.section.about", "About"),
+	("settings.search_hint", "Search settings"),
+	("settings.language", "Language"),
+	("settings.language_system", "System ({language})"),
+	("settings.language_note", "The tray menu switches language after restarting rsnap."),
+	("settings.hotkey_updated", "Capture hotkey updated to {hotkey}."),
+	("settings.hotkey_unavailable", "Capture hotkey unavailable. Try another shortcut."),
+];
@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::RgbaImage;
+
+use crate::backend::{self, CaptureBackendKind};
+use crate::png;
+use crate::state::{MonitorRect, RectPoints};
+
+const INTERVAL_CAPTURE_MIN_SECS: u32 = 1;
+/// How long each frame is shown in an assembled timelapse.
+const TIMELAPSE_FRAME_DELAY: Duration = Duration::from_millis(100);
+/// Longer runs are thinned evenly to this many frames so assembly stays within memory.
+const TIMELAPSE_MAX_FRAMES: usize = 600;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Tunables for an [`IntervalCapture`].
+pub struct IntervalCaptureConfig {
+	/// Seconds between captures.
+	pub interval_secs: u32,
+	/// Directory that receives one dated folder per day of captures.
+	pub output_dir: PathBuf,
+	/// Whether stopping the run also assembles its frames into an animated PNG.
+	pub timelapse: bool,
+	/// Backend that captures the frames.
+	pub capture_backend: CaptureBackendKind,
+}
+impl IntervalCaptureConfig {
+	fn interval(&self) -> Duration {
+		Duration::from_secs(u64::from(self.interval_secs.max(INTERVAL_CAPTURE_MIN_SECS)))
+	}
+
+	fn output_dir(&self) -> PathBuf {
+		if self.output_dir.as_os_str().is_empty() {
+			PathBuf::from(".")
+		} else {
+			self.output_dir.clone()
+		}
+	}
+}
+impl Default for IntervalCaptureConfig {
+	fn default() -> Self {
+		Self {
+			interval_secs: 60,
+			output_dir: PathBuf::new(),
+			timelapse: false,
+			capture_backend: CaptureBackendKind::Auto,
+		}
+	}
+}
+
+/// Captures a screen region on a background thread every few seconds or minutes and saves each
+/// frame as `<output_dir>/<YYYY-MM-DD>/<HH-MM-SS>.png`, with UTC dates and times.
+pub struct IntervalCapture {
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	interval_secs: u32,
+	frames_saved: Arc<AtomicU32>,
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl IntervalCapture {
+	/// Starts capturing `rect_px`, given in monitor-local pixels. The first frame is taken
+	/// immediately.
+	pub fn start(
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+		config: IntervalCaptureConfig,
+	) -> Result<Self> {
+		Self::start_with_frame_listener(monitor, rect_px, config, |_| {})
+	}
+
+	/// Like [`Self::start`], calling `on_frame` on the capture thread with the path of every
+	/// saved frame.
+	pub fn start_with_frame_listener(
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+		config: IntervalCaptureConfig,
+		mut on_frame: impl FnMut(&Path) + Send + 'static,
+	) -> Result<Self> {
+		let interval_secs = config.interval_secs.max(INTERVAL_CAPTURE_MIN_SECS);
+		let frames_saved = Arc::new(AtomicU32::new(0));
+		let worker_frames_saved = Arc::clone(&frames_saved);
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let worker = thread::Builder::new()
+			.name(String::from("rsnap-interval-capture"))
+			.spawn(move || {
+				interval_capture_loop(
+					monitor,
+					rect_px,
+					&config,
+					&worker_frames_saved,
+					&stop_rx,
+					&mut on_frame,
+				);
+			})
+			.wrap_err("Failed to start the interval capture thread")?;
+
+		tracing::info!(
+			op = "interval_capture.start",
+			monitor_id = monitor.id,
+			x = rect_px.x,
+			y = rect_px.y,
+			width = rect_px.width,
+			height = rect_px.height,
+			interval_secs,
+			"Interval capture started."
+		);
+
+		Ok(Self { monitor, rect_px, interval_secs, frames_saved, stop_tx, worker: Some(worker) })
+	}
+
+	#[must_use]
+	/// Returns the captured monitor.
+	pub fn monitor(&self) -> MonitorRect {
+		self.monitor
+	}
+
+	#[must_use]
+	/// Returns the captured region in monitor-local pixels.
+	pub fn rect_px(&self) -> RectPoints {
+		self.rect_px
+	}
+
+	#[must_use]
+	/// Returns the effective seconds between captures.
+	pub fn interval_secs(&self) -> u32 {
+		self.interval_secs
+	}
+
+	#[must_use]
+	/// Returns how many frames have been saved so far.
+	pub fn frames_saved(&self) -> u32 {
+		self.frames_saved.load(Ordering::Relaxed)
+	}
+}
+impl Drop for IntervalCapture {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+
+		tracing::info!(
+			op = "interval_capture.stop",
+			frames = self.frames_saved(),
+			"Interval capture stopped."
+		);
+	}
+}
+
+fn interval_capture_loop(
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	config: &IntervalCaptureConfig,
+	frames_saved: &AtomicU32,
+	stop_rx: &Receiver<()>,
+	on_frame: &mut dyn FnMut(&Path),
+) {
+	let interval = config.interval();
+	let output_dir = config.output_dir();
+	let mut backend = backend::capture_backend(config.capture_backend);
+	let mut frame_paths = Vec::new();
+	let mut next_at = Instant::now();
+
+	// A stop request, or the handle being dropped, ends the run.
+	while let Err(RecvTimeoutError::Timeout) =
+		stop_rx.recv_timeout(next_at.saturating_duration_since(Instant::now()))
+	{
+		next_at = (next_at + interval).max(Instant::now());
+
+		let frame = match backend.capture_monitor_region(monitor, rect_px) {
+			Ok(frame) => frame,
+			Err(err) => {
+				tracing::debug!(
+					op = "interval_capture.capture",
+					error = %format!("{err:#}"),
+					"Interval capture frame failed."
+				);
+
+				continue;
+			},
+		};
+
+		match save_frame(&output_dir, &frame, SystemTime::now()) {
+			Ok(path) => {
+				frames_saved.fetch_add(1, Ordering::Relaxed);
+				on_frame(&path);
+				frame_paths.push(path);
+			},
+			Err(err) => {
+				tracing::warn!(
+					error = %format!("{err:#}"),
+					"Failed to save interval capture frame."
+				);
+			},
+		}
+	}
+
+	if config.timelapse && !frame_paths.is_empty() {
+		spawn_timelapse_assembly(frame_paths);
+	}
+}
+
+fn save_frame(output_dir: &Path, frame: &RgbaImage, now: SystemTime) -> Result<PathBuf> {
+	let unix_secs = now.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+	let (date, time) = utc_date_and_time(unix_secs);
+	let day_dir = output_dir.join(date);
+
+	fs::create_dir_all(&day_dir).wrap_err_with(|| {
+		format!("Failed to create interval capture directory: {}", day_dir.display())
+	})?;
+
+	let path = day_dir.join(format!("{time}.png"));
+
+	fs::write(&path, png::rgba_image_to_png_bytes(frame)?)
+		.wrap_err_with(|| format!("Failed to write interval capture frame: {}", path.display()))?;
+
+	Ok(path)
+}
+
+/// Assembles the run on its own thread, so stopping never waits for the encode.
+fn spawn_timelapse_assembly(frame_paths: Vec<PathBuf>) {
+	let spawned = thread::Builder::new().name(String::from("rsnap-timelapse")).spawn(move || {
+		match assemble_timelapse(&frame_paths) {
+			Ok(path) => {
+				tracing::info!(
+					op = "interval_capture.timelapse",
+					path = %path.display(),
+					"Timelapse saved."
+				);
+			},
+			Err(err) => tracing::warn!(error = %format!("{err:#}"), "Timelapse assembly failed."),
+		}
+	});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start timelapse assembly thread.");
+	}
+}
+
+/// Writes the frames as `timelapse-<HH-MM-SS>.png` next to the last one, named after it.
+fn assemble_timelapse(frame_paths: &[PathBuf]) -> Result<PathBuf> {
+	let Some(last) = frame_paths.last() else {
+		return Err(eyre::eyre!("no frames to assemble"));
+	};
+	let step = frame_paths.len().div_ceil(TIMELAPSE_MAX_FRAMES);
+	let frames = frame_paths
+		.iter()
+		.step_by(step)
+		.map(|path| {
+			image::open(path)
+				.map(|frame| frame.to_rgba8())
+				.wrap_err_with(|| format!("Failed to read frame {}", path.display()))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let timed = frames.iter().map(|frame| (frame, TIMELAPSE_FRAME_DELAY)).collect::<Vec<_>>();
+	let stem = last.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+	let path = last.with_file_name(format!("timelapse-{stem}.png"));
+
+	fs::write(&path, png::rgba_frames_to_apng_bytes(&timed)?)
+		.wrap_err_with(|| format!("Failed to write timelapse: {}", path.display()))?;
+
+	Ok(path)
+}
+
+/// Splits a Unix timestamp into UTC `YYYY-MM-DD` and `HH-MM-SS`, using Howard Hinnant's
+/// days-to-civil conversion.
+pub(crate) fn utc_date_and_time(unix_secs: u64) -> (String, String) {
+	let days = (unix_secs / 86_400) as i64 + 719_468;
+	let secs = unix_secs % 86_400;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days.rem_euclid(146_097);
+	let year_of_era =
+		(day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let shifted_month = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+	let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+	let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+	(
+		format!("{year:04}-{month:02}-{day:02}"),
+		format!("{:02}-{:02}-{:02}", secs / 3_600, secs / 60 % 60, secs % 60),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::time::{Duration, UNIX_EPOCH};
+
+	use image::{Rgba, RgbaImage};
+
+	use crate::interval_capture;
+	use crate::png;
+
+	#[test]
+	fn utc_date_and_time_formats_folder_and_file_names() {
+		let names = |secs: u64| interval_capture::utc_date_and_time(secs);
+
+		assert_eq!(names(0), (String::from("1970-01-01"), String::from("00-00-00")));
+		assert_eq!(names(951_782_400), (String::from("2000-02-29"), String::from("00-00-00")));
+		assert_eq!(names(1_700_000_000), (String::from("2023-11-14"), String::from("22-13-20")));
+	}
+
+	#[test]
+	fn frames_land_in_a_dated_folder_and_assemble_into_a_timelapse() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-interval-{}", std::process::id()));
+		let frame_paths = (0..3_u8)
+			.map(|index| {
+				let frame = RgbaImage::from_pixel(4, 3, Rgba([index * 80, 0, 0, 255]));
+				let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000 + u64::from(index) * 60);
+
+				interval_capture::save_frame(&output_dir, &frame, at).expect("frame")
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(frame_paths[0], output_dir.join("2023-11-14").join("22-13-20.png"));
+		assert_eq!(frame_paths[2], output_dir.join("2023-11-14").join("22-15-20.png"));
+
+		let timelapse = interval_capture::assemble_timelapse(&frame_paths).expect("timelapse");
+
+		assert_eq!(timelapse, output_dir.join("2023-11-14").join("timelapse-22-15-20.png"));
+		assert_eq!(png::png_dimensions(&fs::read(&timelapse).expect("read")), Some((4, 3)));
+
+		let _ = fs::remove_dir_all(output_dir);
+	}
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+/// A point in the capture pipeline whose latency from the request is measured.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LatencyStage {
+	/// The first overlay frame was presented.
+	OverlayVisible,
+	/// The frozen image arrived from the capture worker.
+	FreezeComplete,
+	/// The export finished encoding to PNG.
+	EncodeComplete,
+	/// The encoded PNG was written to the clipboard.
+	ClipboardDone,
+}
+impl LatencyStage {
+	/// Every stage in pipeline order.
+	pub const ALL: [Self; 4] =
+		[Self::OverlayVisible, Self::FreezeComplete, Self::EncodeComplete, Self::ClipboardDone];
+
+	/// Stable snake_case name used in logs and exports.
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::OverlayVisible => "overlay_visible",
+			Self::FreezeComplete => "freeze_complete",
+			Self::EncodeComplete => "encode_complete",
+			Self::ClipboardDone => "clipboard_done",
+		}
+	}
+
+	const fn index(self) -> usize {
+		match self {
+			Self::OverlayVisible => 0,
+			Self::FreezeComplete => 1,
+			Self::EncodeComplete => 2,
+			Self::ClipboardDone => 3,
+		}
+	}
+}
+
+/// When one capture was requested and when each stage after it was first reached.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CaptureLatency {
+	requested_at: Instant,
+	reached_at: [Option<Instant>; 4],
+}
+impl CaptureLatency {
+	pub(crate) fn new(requested_at: Instant) -> Self {
+		Self { requested_at, reached_at: [None; 4] }
+	}
+
+	/// The hotkey press (or other request) the stages are measured from.
+	#[must_use]
+	pub fn requested_at(&self) -> Instant {
+		self.requested_at
+	}
+
+	pub(crate) fn set_requested_at(&mut self, requested_at: Instant) {
+		self.requested_at = requested_at;
+	}
+
+	/// Records `stage` at `at` unless it was already reached; repeated frames keep the first.
+	pub(crate) fn mark(&mut self, stage: LatencyStage, at: Instant) {
+		self.reached_at[stage.index()].get_or_insert(at);
+	}
+
+	/// Time from the request to `stage`, or `None` when the session never reached it.
+	#[must_use]
+	pub fn elapsed(&self, stage: LatencyStage) -> Option<Duration> {
+		self.reached_at[stage.index()].map(|at| at.saturating_duration_since(self.requested_at))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use crate::latency::{CaptureLatency, LatencyStage};
+
+	#[test]
+	fn capture_latency_keeps_the_first_mark_per_stage() {
+		let requested_at = Instant::now();
+		let mut latency = CaptureLatency::new(requested_at + Duration::from_millis(5));
+
+		latency.set_requested_at(requested_at);
+		latency.mark(LatencyStage::OverlayVisible, requested_at + Duration::from_millis(40));
+		latency.mark(LatencyStage::OverlayVisible, requested_at + Duration::from_millis(90));
+		latency.mark(LatencyStage::EncodeComplete, requested_at + Duration::from_millis(300));
+
+		assert_eq!(latency.elapsed(LatencyStage::OverlayVisible), Some(Duration::from_millis(40)));
+		assert_eq!(latency.elapsed(LatencyStage::FreezeComplete), None);
+		assert_eq!(latency.elapsed(LatencyStage::EncodeComplete), Some(Duration::from_millis(300)));
+	}
+}
@@ -10,23 +10,86 @@ pub mod bench_support {
 	};
 }
 
+mod annotation;
+mod app_windows;
+mod backdrop;
 mod backend;
+mod callout;
+mod capture_exclusion;
+mod clipboard_watch;
+#[cfg(target_os = "linux")]
+mod clipboard_watch_linux;
+mod collage;
+mod color_math;
+mod color_vision;
+mod diff;
+mod doctor;
+mod external_command;
+mod file_drag;
+mod focused_window;
+mod fullscreen;
+mod highlighter;
+mod i18n;
+mod interval_capture;
+mod latency;
 #[cfg(target_os = "macos")]
 mod live_frame_stream_macos;
+mod loupe_stream;
+mod memory_budget;
+mod modifiers;
 mod overlay;
 mod png;
+#[cfg(target_os = "linux")]
+mod portal_screenshot_linux;
+mod progress;
+mod project;
+mod recognition;
+mod redact;
+mod region_watch;
+mod replay;
 mod scroll_capture;
+#[cfg(target_os = "macos")]
+mod share_sheet_macos;
+mod stamp;
 mod state;
+mod theme_pair;
+mod thumbnail;
+mod watermark;
 mod worker;
+#[cfg(target_os = "linux")]
+mod x11_capture_linux;
 
+pub use crate::app_windows::{AppWindows, list_app_windows};
+pub use crate::backend::CaptureBackendKind;
+pub use crate::capture_exclusion::exclude_window_from_capture;
+pub use crate::clipboard_watch::ClipboardWatch;
+pub use crate::diff::{DiffOptions, DiffReport, diff_images};
+pub use crate::doctor::{CaptureBackendProbe, CapturePermission, probe_capture_backends};
+pub use crate::file_drag::start_file_drag;
+pub use crate::fullscreen::fullscreen_foreground_app;
+pub use crate::i18n::{Language, detect_language, language, set_language, tr, tr_args};
+pub use crate::interval_capture::{IntervalCapture, IntervalCaptureConfig};
+pub use crate::latency::{CaptureLatency, LatencyStage};
+pub use crate::loupe_stream::{LoupeStreamConfig, LoupeStreamServer};
+pub use crate::modifiers::ModifierProbe;
 pub use crate::overlay::{
-	AltActivationMode, HudAnchor, OutputNaming, OverlayConfig, OverlayControl, OverlayExit,
-	OverlaySession, ThemeMode, ToolbarPlacement, WindowCaptureAlphaMode,
+	AltActivationMode, BackdropFill, CaptureMode, ClipboardFormat, CompositionGuides,
+	DEFAULT_KEYPAD_QUICK_ACTIONS, DoneAction, EdgeDeadZones, ExportBackdrop, ExportFormat,
+	ExportScaleMode, ExportScaling, GpuPowerPreference, HudAnchor, HudUnit,
+	KEYPAD_QUICK_ACTION_SLOTS, KeypadQuickAction, OutputNaming, OverlayConfig, OverlayControl,
+	OverlayExit, OverlaySession, ThemeMode, ToolbarPlacement, Watermark, WatermarkCorner,
+	WindowCaptureAlphaMode, WindowShadowMode, copy_png_to_clipboard, output_filename_preview,
+	save_png_to_output_dir, selection_flow_palette_rgb,
 };
+pub use crate::progress::{OverlayProgress, ProgressStage};
+pub use crate::project::PROJECT_EXTENSION;
+pub use crate::region_watch::{RegionWatch, RegionWatchConfig};
+pub use crate::replay::{ReplayBuffer, ReplayClip, ReplayConfig};
 pub use crate::state::{
-	GlobalPoint, LiveCursorSample, MonitorImageSnapshot, MonitorRect, RectPoints, Rgb, WindowHit,
-	WindowListSnapshot, WindowRect,
+	GlobalPoint, LiveCursorSample, MonitorImageSnapshot, MonitorRect, MonitorRectPoints,
+	RectPoints, Rgb, WindowHit, WindowLabel, WindowListSnapshot, WindowRect,
 };
+pub use crate::theme_pair::{ThemePairConfig, capture_theme_pair};
 
 /// Returns the `rsnap-overlay` crate version.
 pub fn overlay_version() -> &'static str {
@@ -285,7 +285,7 @@ impl MacLiveFrameStream {
 		reply_rx.recv_timeout(STREAM_RPC_TIMEOUT).ok()
 	}
 
-	fn ensure_monitor_nonblocking(&self, monitor: MonitorRect) {
+	pub(crate) fn ensure_monitor_nonblocking(&self, monitor: MonitorRect) {
 		if !self.shared_latest_frame.begin_ensure_monitor(monitor.id) {
 			return;
 		}
@@ -0,0 +1,546 @@
+//! Loopback stream of loupe patches for external magnifiers and assistive tools.
+//!
+//! Clients connect to `127.0.0.1:<port>`, send the session token shown in settings followed by
+//! `\n`, and then read frames back to back. A client that sends anything else, or nothing within
+//! two seconds, is disconnected. The token changes every time the stream starts. Each frame is a
+//! little-endian header followed by the patch pixels:
+//!
+//! | Bytes | Field                                              |
+//! |-------|----------------------------------------------------|
+//! | 4     | Magic `RSLP`                                       |
+//! | 4     | Patch width in pixels (`u32`)                      |
+//! | 4     | Patch height in pixels (`u32`)                     |
+//! | 4     | Cursor X in global points (`i32`)                  |
+//! | 4     | Cursor Y in global points (`i32`)                  |
+//! | 8     | Milliseconds since the stream started (`u64`)      |
+//! | w·h·4 | Row-major, unpremultiplied RGBA8 pixels            |
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+#[cfg(not(target_os = "macos"))]
+use std::panic;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+#[cfg(not(target_os = "macos"))]
+use device_query::DeviceQuery;
+use image::RgbaImage;
+
+use crate::backend::{self, CaptureBackend, CaptureBackendKind};
+#[cfg(target_os = "macos")]
+use crate::overlay;
+use crate::overlay::OverlaySession;
+use crate::state::{GlobalPoint, MonitorRect};
+
+const LOUPE_STREAM_MAGIC: &[u8; 4] = b"RSLP";
+const LOUPE_STREAM_HEADER_LEN: usize = 28;
+const LOUPE_STREAM_MAX_FPS: u32 = 60;
+const LOUPE_STREAM_MAX_PATCH_SIDE_PX: u32 = 255;
+// Each client is written on its own thread; while one is backed up its frames are skipped, and
+// one that takes no bytes for this long is disconnected.
+const LOUPE_STREAM_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+const LOUPE_STREAM_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+const LOUPE_STREAM_TOKEN_BYTES: usize = 16;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Tunables for the loupe patch stream.
+pub struct LoupeStreamConfig {
+	/// Loopback TCP port clients connect to.
+	pub port: u16,
+	/// Frames sent per second while at least one client is connected.
+	pub fps: u32,
+	/// Side length of the square patch around the cursor, in pixels.
+	pub patch_side_px: u32,
+	/// Backend that samples the patches.
+	pub capture_backend: CaptureBackendKind,
+}
+impl LoupeStreamConfig {
+	fn frame_interval(self) -> Duration {
+		Duration::from_secs(1) / self.fps.clamp(1, LOUPE_STREAM_MAX_FPS)
+	}
+
+	fn patch_side_px(self) -> u32 {
+		// Odd sides keep the cursor pixel in the middle, as in the loupe.
+		self.patch_side_px.clamp(1, LOUPE_STREAM_MAX_PATCH_SIDE_PX) | 1
+	}
+}
+impl Default for LoupeStreamConfig {
+	fn default() -> Self {
+		Self { port: 47_810, fps: 15, patch_side_px: 21, capture_backend: CaptureBackendKind::Auto }
+	}
+}
+
+/// Serves loupe patches around the cursor to loopback clients. Sampling only runs while a
+/// client is connected.
+pub struct LoupeStreamServer {
+	local_addr: SocketAddr,
+	token: String,
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl LoupeStreamServer {
+	/// Binds the loopback port and starts streaming on a background thread. Call this from the
+	/// main thread: macOS only enumerates displays there.
+	pub fn start(config: LoupeStreamConfig) -> Result<Self> {
+		let monitors =
+			OverlaySession::available_overlay_monitors().map_err(|err| eyre::eyre!(err))?;
+		let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
+			.wrap_err_with(|| format!("Failed to bind loupe stream port {}", config.port))?;
+
+		listener.set_nonblocking(true).wrap_err("Failed to configure loupe stream listener")?;
+
+		let local_addr = listener.local_addr().wrap_err("Failed to read loupe stream address")?;
+		let token = session_token()?;
+		let worker_token = token.clone();
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let worker = thread::Builder::new()
+			.name(String::from("rsnap-loupe-stream"))
+			.spawn(move || {
+				loupe_stream_loop(&listener, &monitors, config, worker_token.as_bytes(), &stop_rx);
+			})
+			.wrap_err("Failed to start the loupe stream thread")?;
+
+		tracing::info!(
+			op = "loupe_stream.start",
+			addr = %local_addr,
+			fps = config.fps,
+			patch_side_px = config.patch_side_px(),
+			"Loupe stream listening."
+		);
+
+		Ok(Self { local_addr, token, stop_tx, worker: Some(worker) })
+	}
+
+	#[must_use]
+	/// Returns the loopback address clients connect to.
+	pub fn local_addr(&self) -> SocketAddr {
+		self.local_addr
+	}
+
+	#[must_use]
+	/// Returns the token clients must send before they receive frames.
+	pub fn token(&self) -> &str {
+		&self.token
+	}
+}
+impl Drop for LoupeStreamServer {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+
+		tracing::info!(op = "loupe_stream.stop", "Loupe stream stopped.");
+	}
+}
+
+fn loupe_stream_loop(
+	listener: &TcpListener,
+	monitors: &[MonitorRect],
+	config: LoupeStreamConfig,
+	token: &[u8],
+	stop_rx: &Receiver<()>,
+) {
+	let interval = config.frame_interval();
+	let side_px = config.patch_side_px();
+	let mut backend = backend::capture_backend(config.capture_backend);
+	let mut cursor = CursorSource::new();
+	let mut pending = Vec::<PendingClient>::new();
+	let mut authenticated = Vec::<TcpStream>::new();
+	let mut clients = Vec::<StreamClient>::new();
+	let started_at = Instant::now();
+	let mut next_at = started_at;
+
+	loop {
+		match stop_rx.recv_timeout(next_at.saturating_duration_since(Instant::now())) {
+			Err(RecvTimeoutError::Timeout) => {},
+			Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+		}
+
+		next_at = (next_at + interval).max(Instant::now());
+
+		accept_clients(listener, &mut pending);
+		authenticate_clients(&mut pending, &mut authenticated, token);
+		clients.extend(authenticated.drain(..).filter_map(StreamClient::spawn));
+
+		if clients.is_empty() {
+			continue;
+		}
+
+		let Some(point) = cursor.location() else {
+			continue;
+		};
+		let Some(patch) = sample_patch(backend.as_mut(), monitors, point, side_px) else {
+			continue;
+		};
+		let elapsed_ms = u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX);
+		let frame = Arc::<[u8]>::from(encode_loupe_frame(&patch, point, elapsed_ms));
+
+		clients.retain(|client| client.send(&frame));
+	}
+}
+
+/// An authenticated client, fed by its own writer thread so a slow reader never delays sampling
+/// or the other clients.
+struct StreamClient {
+	frames: SyncSender<Arc<[u8]>>,
+}
+impl StreamClient {
+	fn spawn(mut stream: TcpStream) -> Option<Self> {
+		// One queued frame: a client still writing the previous one skips the frames in between.
+		let (frames, frame_rx) = mpsc::sync_channel::<Arc<[u8]>>(1);
+		let spawned =
+			thread::Builder::new().name(String::from("rsnap-loupe-client")).spawn(move || {
+				for frame in frame_rx {
+					if let Err(err) = stream.write_all(&frame) {
+						tracing::info!(
+							op = "loupe_stream.client_dropped",
+							error = %err,
+							"Loupe stream client disconnected."
+						);
+
+						return;
+					}
+				}
+			});
+
+		if let Err(err) = spawned {
+			tracing::warn!(error = %err, "Failed to start a loupe stream client thread.");
+
+			return None;
+		}
+
+		Some(Self { frames })
+	}
+
+	/// Queues `frame` unless the client is still busy with an earlier one. Returns `false` once
+	/// the writer gave up on the client.
+	fn send(&self, frame: &Arc<[u8]>) -> bool {
+		match self.frames.try_send(Arc::clone(frame)) {
+			Ok(()) | Err(TrySendError::Full(_)) => true,
+			Err(TrySendError::Disconnected(_)) => false,
+		}
+	}
+}
+
+/// A connection that has not sent the session token yet.
+struct PendingClient {
+	stream: TcpStream,
+	received: Vec<u8>,
+	deadline: Instant,
+}
+
+fn accept_clients(listener: &TcpListener, pending: &mut Vec<PendingClient>) {
+	loop {
+		match listener.accept() {
+			Ok((stream, addr)) => {
+				// Stays non-blocking until the handshake completes, so a silent client cannot
+				// stall the frame loop.
+				if let Err(err) = stream.set_nonblocking(true) {
+					tracing::warn!(error = %err, "Failed to configure loupe stream client.");
+
+					continue;
+				}
+
+				tracing::debug!(
+					op = "loupe_stream.pending",
+					addr = %addr,
+					"Loupe stream client awaiting handshake."
+				);
+
+				pending.push(PendingClient {
+					stream,
+					received: Vec::new(),
+					deadline: Instant::now() + LOUPE_STREAM_HANDSHAKE_TIMEOUT,
+				});
+			},
+			Err(err) if err.kind() == ErrorKind::WouldBlock => return,
+			Err(err) => {
+				tracing::debug!(error = %err, "Loupe stream accept failed.");
+
+				return;
+			},
+		}
+	}
+}
+
+/// Promotes pending clients that sent `token` and a newline; drops those that sent anything
+/// else, disconnected, or ran out of time.
+fn authenticate_clients(
+	pending: &mut Vec<PendingClient>,
+	clients: &mut Vec<TcpStream>,
+	token: &[u8],
+) {
+	let now = Instant::now();
+
+	for mut client in std::mem::take(pending) {
+		match read_handshake(&mut client, token) {
+			Some(true) => {
+				let configured = client
+					.stream
+					.set_nonblocking(false)
+					.and_then(|()| {
+						client.stream.set_write_timeout(Some(LOUPE_STREAM_WRITE_TIMEOUT))
+					})
+					.and_then(|()| client.stream.set_nodelay(true));
+
+				if let Err(err) = configured {
+					tracing::warn!(error = %err, "Failed to configure loupe stream client.");
+
+					continue;
+				}
+
+				tracing::info!(op = "loupe_stream.client", "Loupe stream client connected.");
+
+				clients.push(client.stream);
+			},
+			Some(false) => {
+				tracing::warn!(
+					op = "loupe_stream.rejected",
+					"Loupe stream client sent a wrong token."
+				);
+			},
+			None if now >= client.deadline => {
+				tracing::info!(
+					op = "loupe_stream.rejected",
+					"Loupe stream client did not send a token in time."
+				);
+			},
+			None => pending.push(client),
+		}
+	}
+}
+
+/// Reads what a pending client has sent so far. Returns `None` while the handshake line is
+/// incomplete and whether it matched `token` once it is.
+fn read_handshake(client: &mut PendingClient, token: &[u8]) -> Option<bool> {
+	let mut chunk = [0_u8; LOUPE_STREAM_TOKEN_BYTES * 2 + 2];
+
+	loop {
+		if let Some(matched) = handshake_matches(&client.received, token) {
+			return Some(matched);
+		}
+		// Longer than the token and an optional `\r` without a newline: it cannot match.
+		if client.received.len() > token.len() + 1 {
+			return Some(false);
+		}
+
+		match client.stream.read(&mut chunk) {
+			Ok(0) => return Some(false),
+			Ok(read) => client.received.extend_from_slice(&chunk[..read]),
+			Err(err) if err.kind() == ErrorKind::WouldBlock => return None,
+			Err(err) if err.kind() == ErrorKind::Interrupted => {},
+			Err(_) => return Some(false),
+		}
+	}
+}
+
+/// Checks the first line of `received` against `token` in constant time, or returns `None`
+/// before the newline arrives.
+fn handshake_matches(received: &[u8], token: &[u8]) -> Option<bool> {
+	let end = received.iter().position(|&byte| byte == b'\n')?;
+	let line = received[..end].strip_suffix(b"\r").unwrap_or(&received[..end]);
+
+	Some(
+		line.len() == token.len()
+			&& line.iter().zip(token).fold(0_u8, |diff, (left, right)| diff | (left ^ right)) == 0,
+	)
+}
+
+/// Builds a random hex token for one run of the stream.
+fn session_token() -> Result<String> {
+	let mut bytes = [0_u8; LOUPE_STREAM_TOKEN_BYTES];
+
+	getrandom::fill(&mut bytes).wrap_err("Failed to generate the loupe stream token")?;
+
+	Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+fn sample_patch(
+	backend: &mut dyn CaptureBackend,
+	monitors: &[MonitorRect],
+	point: GlobalPoint,
+	side_px: u32,
+) -> Option<RgbaImage> {
+	let monitor = monitors.iter().copied().find(|monitor| monitor.contains(point))?;
+
+	match backend.live_sample_cursor(monitor, point, true, side_px, side_px) {
+		Ok(sample) => sample.patch,
+		Err(err) => {
+			tracing::debug!(
+				op = "loupe_stream.sample",
+				error = %format!("{err:#}"),
+				"Loupe stream sample failed."
+			);
+
+			None
+		},
+	}
+}
+
+/// Serializes one frame in the wire format described in the module docs.
+fn encode_loupe_frame(patch: &RgbaImage, cursor: GlobalPoint, elapsed_ms: u64) -> Vec<u8> {
+	let mut frame = Vec::with_capacity(LOUPE_STREAM_HEADER_LEN + patch.as_raw().len());
+
+	frame.extend_from_slice(LOUPE_STREAM_MAGIC);
+	frame.extend_from_slice(&patch.width().to_le_bytes());
+	frame.extend_from_slice(&patch.height().to_le_bytes());
+	frame.extend_from_slice(&cursor.x.to_le_bytes());
+	frame.extend_from_slice(&cursor.y.to_le_bytes());
+	frame.extend_from_slice(&elapsed_ms.to_le_bytes());
+	frame.extend_from_slice(patch.as_raw());
+
+	frame
+}
+
+/// Reads the global cursor position without an overlay session.
+struct CursorSource {
+	#[cfg(not(target_os = "macos"))]
+	device: Option<device_query::DeviceState>,
+}
+impl CursorSource {
+	fn new() -> Self {
+		#[cfg(not(target_os = "macos"))]
+		{
+			Self { device: panic::catch_unwind(device_query::DeviceState::new).ok() }
+		}
+		#[cfg(target_os = "macos")]
+		{
+			Self {}
+		}
+	}
+
+	fn location(&mut self) -> Option<GlobalPoint> {
+		#[cfg(not(target_os = "macos"))]
+		{
+			let mouse = self.device.as_ref()?.get_mouse();
+
+			Some(GlobalPoint::new(mouse.coords.0, mouse.coords.1))
+		}
+		#[cfg(target_os = "macos")]
+		{
+			overlay::macos_mouse_location()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+	use std::net::{Ipv4Addr, TcpListener, TcpStream};
+	use std::sync::Arc;
+	use std::thread;
+	use std::time::{Duration, Instant};
+
+	use image::{Rgba, RgbaImage};
+
+	use crate::loupe_stream::{self, LOUPE_STREAM_HEADER_LEN, LoupeStreamConfig, StreamClient};
+	use crate::state::GlobalPoint;
+
+	#[test]
+	fn loupe_frames_use_the_documented_layout() {
+		let patch = RgbaImage::from_pixel(3, 3, Rgba([1, 2, 3, 4]));
+		let frame = loupe_stream::encode_loupe_frame(&patch, GlobalPoint::new(-5, 7), 1_000);
+
+		assert_eq!(frame.len(), LOUPE_STREAM_HEADER_LEN + 3 * 3 * 4);
+		assert_eq!(&frame[0..4], b"RSLP");
+		assert_eq!(frame[4..8], 3_u32.to_le_bytes());
+		assert_eq!(frame[8..12], 3_u32.to_le_bytes());
+		assert_eq!(frame[12..16], (-5_i32).to_le_bytes());
+		assert_eq!(frame[16..20], 7_i32.to_le_bytes());
+		assert_eq!(frame[20..28], 1_000_u64.to_le_bytes());
+		assert_eq!(&frame[28..32], &[1, 2, 3, 4]);
+	}
+
+	#[test]
+	fn loupe_handshake_requires_the_exact_token_line() {
+		let token = b"0123abcd";
+
+		assert_eq!(loupe_stream::handshake_matches(b"0123", token), None);
+		assert_eq!(loupe_stream::handshake_matches(b"0123abcd\n", token), Some(true));
+		assert_eq!(loupe_stream::handshake_matches(b"0123abcd\r\n", token), Some(true));
+		assert_eq!(loupe_stream::handshake_matches(b"0123abce\n", token), Some(false));
+		assert_eq!(loupe_stream::handshake_matches(b"0123abcd0\n", token), Some(false));
+		assert_eq!(loupe_stream::handshake_matches(b"\n", token), Some(false));
+
+		let session = loupe_stream::session_token().unwrap();
+
+		assert_eq!(session.len(), 32);
+		assert!(session.bytes().all(|byte| byte.is_ascii_hexdigit()));
+		assert_ne!(session, loupe_stream::session_token().unwrap());
+	}
+
+	#[test]
+	fn loupe_clients_only_join_after_sending_the_token() {
+		let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+
+		listener.set_nonblocking(true).unwrap();
+
+		let addr = listener.local_addr().unwrap();
+		let mut honest = TcpStream::connect(addr).unwrap();
+		let mut guesser = TcpStream::connect(addr).unwrap();
+		let mut pending = Vec::new();
+		let mut clients = Vec::new();
+
+		honest.write_all(b"secret\n").unwrap();
+		guesser.write_all(b"guess\n").unwrap();
+
+		let deadline = Instant::now() + Duration::from_secs(5);
+
+		while pending.len() < 2 && Instant::now() < deadline {
+			loupe_stream::accept_clients(&listener, &mut pending);
+			thread::sleep(Duration::from_millis(5));
+		}
+		while !pending.is_empty() && Instant::now() < deadline {
+			loupe_stream::authenticate_clients(&mut pending, &mut clients, b"secret");
+			thread::sleep(Duration::from_millis(5));
+		}
+
+		assert_eq!(clients.len(), 1);
+		assert!(pending.is_empty());
+		assert_eq!(clients[0].peer_addr().unwrap(), honest.local_addr().unwrap());
+	}
+
+	#[test]
+	fn loupe_backed_up_clients_skip_frames_without_blocking_the_sender() {
+		let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let reader = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+		let (stream, _) = listener.accept().unwrap();
+		let client = StreamClient::spawn(stream).unwrap();
+		let frame = Arc::<[u8]>::from(vec![0_u8; 1 << 20]);
+		let started_at = Instant::now();
+
+		// The reader never reads, so the socket buffers fill after a few frames.
+		for _ in 0..64 {
+			assert!(client.send(&frame));
+		}
+
+		assert!(started_at.elapsed() < Duration::from_secs(1));
+
+		drop(reader);
+
+		let deadline = Instant::now() + Duration::from_secs(5);
+
+		while client.send(&frame) && Instant::now() < deadline {
+			thread::sleep(Duration::from_millis(5));
+		}
+
+		assert!(!client.send(&frame));
+	}
+
+	#[test]
+	fn loupe_patch_side_stays_odd_and_bounded() {
+		let side = |patch_side_px| {
+			LoupeStreamConfig { patch_side_px, ..LoupeStreamConfig::default() }.patch_side_px()
+		};
+
+		assert_eq!(side(21), 21);
+		assert_eq!(side(20), 21);
+		assert_eq!(side(0), 1);
+		assert_eq!(side(10_000), 255);
+	}
+}
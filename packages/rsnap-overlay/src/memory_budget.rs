@@ -0,0 +1,156 @@
+use image::RgbaImage;
+
+/// The high-water mark is logged again only after growing by this much, so a steady session
+/// does not log on every frame.
+const HIGH_WATER_LOG_STEP_BYTES: u64 = 64 * 1024 * 1024;
+
+/// An image cache whose size the overlay session accounts for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ImageCache {
+	/// The live screen copy behind the HUD blur.
+	LiveBg,
+	/// The frozen screen shown and exported after a freeze.
+	Frozen,
+	/// The separately captured window of a window freeze.
+	FrozenWindow,
+	/// The pixels under the loupe.
+	Loupe,
+	/// The HUD blur texture with its mip chain, built from the live or frozen screen.
+	HudBg,
+}
+impl ImageCache {
+	const ALL: [Self; 5] =
+		[Self::LiveBg, Self::Frozen, Self::FrozenWindow, Self::Loupe, Self::HudBg];
+
+	const fn index(self) -> usize {
+		match self {
+			Self::LiveBg => 0,
+			Self::Frozen => 1,
+			Self::FrozenWindow => 2,
+			Self::Loupe => 3,
+			Self::HudBg => 4,
+		}
+	}
+
+	const fn name(self) -> &'static str {
+		match self {
+			Self::LiveBg => "live_bg",
+			Self::Frozen => "frozen",
+			Self::FrozenWindow => "frozen_window",
+			Self::Loupe => "loupe",
+			Self::HudBg => "hud_bg",
+		}
+	}
+}
+
+/// Tracks how many bytes each image cache holds against a budget and remembers the peak.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MemoryAccountant {
+	/// 0 means unlimited.
+	budget_bytes: u64,
+	bytes: [u64; 5],
+	high_water_bytes: u64,
+	logged_high_water_bytes: u64,
+}
+impl MemoryAccountant {
+	pub(crate) fn new(budget_mb: u32) -> Self {
+		Self { budget_bytes: mb_to_bytes(budget_mb), ..Self::default() }
+	}
+
+	pub(crate) fn set_budget_mb(&mut self, budget_mb: u32) {
+		self.budget_bytes = mb_to_bytes(budget_mb);
+	}
+
+	/// Replaces the recorded size of `cache` and updates the high-water mark.
+	pub(crate) fn record(&mut self, cache: ImageCache, bytes: u64) {
+		self.bytes[cache.index()] = bytes;
+
+		let total = self.total_bytes();
+
+		if total <= self.high_water_bytes {
+			return;
+		}
+
+		self.high_water_bytes = total;
+
+		if total >= self.logged_high_water_bytes + HIGH_WATER_LOG_STEP_BYTES {
+			self.logged_high_water_bytes = total;
+
+			let breakdown = ImageCache::ALL
+				.iter()
+				.map(|cache| format!("{}={}", cache.name(), self.bytes[cache.index()]))
+				.collect::<Vec<_>>()
+				.join(" ");
+
+			tracing::info!(
+				op = "overlay.memory_high_water",
+				total_bytes = total,
+				budget_bytes = self.budget_bytes,
+				breakdown,
+				"Image caches reached a new high-water mark."
+			);
+		}
+	}
+
+	pub(crate) fn total_bytes(&self) -> u64 {
+		self.bytes.iter().sum()
+	}
+
+	pub(crate) fn high_water_bytes(&self) -> u64 {
+		self.high_water_bytes
+	}
+
+	/// Bytes above the budget, or 0 when within it or unlimited.
+	pub(crate) fn excess_bytes(&self) -> u64 {
+		if self.budget_bytes == 0 {
+			return 0;
+		}
+
+		self.total_bytes().saturating_sub(self.budget_bytes)
+	}
+}
+
+pub(crate) fn rgba_bytes(image: &RgbaImage) -> u64 {
+	u64::from(image.width()) * u64::from(image.height()) * 4
+}
+
+/// A full mip chain adds about a third on top of the base level.
+pub(crate) fn mip_chain_bytes(base_bytes: u64) -> u64 {
+	base_bytes + base_bytes / 3
+}
+
+fn mb_to_bytes(mb: u32) -> u64 {
+	u64::from(mb) * 1024 * 1024
+}
+
+#[cfg(test)]
+mod tests {
+	use image::RgbaImage;
+
+	use crate::memory_budget::{ImageCache, MemoryAccountant, mip_chain_bytes, rgba_bytes};
+
+	#[test]
+	fn accountant_tracks_excess_over_the_budget_and_keeps_the_peak() {
+		let mut accountant = MemoryAccountant::new(1);
+
+		accountant.record(ImageCache::LiveBg, 800 * 1024);
+		accountant.record(ImageCache::HudBg, mip_chain_bytes(600 * 1024));
+
+		assert_eq!(accountant.excess_bytes(), 800 * 1024 + 800 * 1024 - 1024 * 1024);
+
+		accountant.record(ImageCache::HudBg, 0);
+
+		assert_eq!(accountant.excess_bytes(), 0);
+		assert_eq!(accountant.high_water_bytes(), 1600 * 1024);
+
+		accountant.set_budget_mb(0);
+		accountant.record(ImageCache::Frozen, 64 * 1024 * 1024);
+
+		assert_eq!(accountant.excess_bytes(), 0);
+	}
+
+	#[test]
+	fn rgba_bytes_count_four_bytes_per_pixel() {
+		assert_eq!(rgba_bytes(&RgbaImage::new(3, 2)), 24);
+	}
+}
@@ -0,0 +1,73 @@
+//! System-wide modifier key state, readable without an overlay session or a focused window.
+
+#[cfg(not(target_os = "macos"))]
+use std::panic;
+
+#[cfg(not(target_os = "macos"))]
+use device_query::{DeviceQuery, Keycode};
+#[cfg(target_os = "macos")]
+use objc2_core_graphics::{CGEventFlags, CGEventSource, CGEventSourceStateID};
+use winit::keyboard::ModifiersState;
+
+/// Reads which modifier keys are held right now, wherever keyboard focus is.
+pub struct ModifierProbe {
+	#[cfg(not(target_os = "macos"))]
+	device: Option<device_query::DeviceState>,
+}
+impl ModifierProbe {
+	#[must_use]
+	/// Creates a probe; on X11 this opens a display connection that the probe keeps.
+	pub fn new() -> Self {
+		#[cfg(not(target_os = "macos"))]
+		{
+			Self { device: panic::catch_unwind(device_query::DeviceState::new).ok() }
+		}
+		#[cfg(target_os = "macos")]
+		{
+			Self {}
+		}
+	}
+
+	#[must_use]
+	/// Returns the held modifiers; empty when the platform state cannot be read.
+	pub fn held(&self) -> ModifiersState {
+		let mut modifiers = ModifiersState::empty();
+
+		#[cfg(not(target_os = "macos"))]
+		{
+			let Some(device) = self.device.as_ref() else {
+				return modifiers;
+			};
+
+			for key in device.get_keys() {
+				match key {
+					Keycode::LShift | Keycode::RShift => modifiers |= ModifiersState::SHIFT,
+					Keycode::LControl | Keycode::RControl => modifiers |= ModifiersState::CONTROL,
+					Keycode::LAlt | Keycode::RAlt | Keycode::LOption | Keycode::ROption => {
+						modifiers |= ModifiersState::ALT;
+					},
+					Keycode::LMeta | Keycode::RMeta | Keycode::Command | Keycode::RCommand => {
+						modifiers |= ModifiersState::SUPER;
+					},
+					_ => {},
+				}
+			}
+		}
+		#[cfg(target_os = "macos")]
+		{
+			let flags = CGEventSource::flags_state(CGEventSourceStateID::CombinedSessionState);
+
+			modifiers.set(ModifiersState::SHIFT, flags.contains(CGEventFlags::MaskShift));
+			modifiers.set(ModifiersState::CONTROL, flags.contains(CGEventFlags::MaskControl));
+			modifiers.set(ModifiersState::ALT, flags.contains(CGEventFlags::MaskAlternate));
+			modifiers.set(ModifiersState::SUPER, flags.contains(CGEventFlags::MaskCommand));
+		}
+
+		modifiers
+	}
+}
+impl Default for ModifierProbe {
+	fn default() -> Self {
+		Self::new()
+	}
+}
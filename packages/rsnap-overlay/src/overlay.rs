@@ -1,9 +1,47 @@
+pub(crate) mod alt_text;
+mod annotation_runtime;
+mod backdrop_runtime;
+mod callout_runtime;
+mod capture_countdown_runtime;
+mod capture_flash_runtime;
+mod color_picker_runtime;
+mod color_vision_runtime;
+mod composition_guides_runtime;
+mod direct_capture_runtime;
+mod display_picker_runtime;
+mod edge_dead_zone_runtime;
+mod export_preview_runtime;
+mod frozen_view_runtime;
+#[cfg(test)]
+mod headless;
+mod highlighter_runtime;
+mod histogram_runtime;
+mod hud_actions_runtime;
 mod hud_helpers;
 mod image_helpers;
-mod output;
+mod interval_capture_runtime;
+mod keyboard_nav_runtime;
+mod loupe_zoom_runtime;
+mod magnifier_runtime;
+mod memory_budget_runtime;
+mod motion;
+mod nonactivating_window;
+mod open_with;
+pub(crate) mod output;
+mod perf_hud_runtime;
+mod project_runtime;
+mod qr_runtime;
+mod redact_runtime;
+mod reference_sample_runtime;
+mod region_watch_runtime;
 mod scroll_runtime;
+mod selection_snap_runtime;
 mod session_state;
+mod shader_runtime;
+mod sidecar;
+mod stamp_runtime;
 mod window_runtime;
+mod window_shape;
 
 #[cfg(target_os = "macos")]
 use std::ffi::c_void;
@@ -15,8 +53,12 @@ use std::{
 	borrow::Cow,
 	cmp::Ordering,
 	collections::HashMap,
-	path::PathBuf,
-	sync::{Arc, Mutex},
+	path::{Path, PathBuf},
+	sync::{
+		Arc, Mutex,
+		mpsc::{self, Receiver},
+	},
+	thread,
 	time::{Duration, Instant},
 };
 
@@ -109,33 +151,59 @@ use winit::{
 	dpi::PhysicalSize,
 	event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
 	event_loop::ActiveEventLoop,
-	keyboard::{Key, ModifiersState, NamedKey},
+	keyboard::{Key, KeyCode, ModifiersState, NamedKey, PhysicalKey},
 	window::{WindowId, WindowLevel},
 };
 
+use self::color_picker_runtime::ColorPickerKey;
+use self::motion::{DragSpring, EntryAnimation};
+use self::output::AssetPairPaths;
+pub use self::output::{copy_png_to_clipboard, output_filename_preview, save_png_to_output_dir};
+use self::perf_hud_runtime::PerfHudCounters;
 #[cfg(all(target_os = "macos", test))]
 use self::session_state::InflightScrollCaptureObservation;
 use self::session_state::{
-	CursorMoveTrace, FrozenToolbarPointerState, FrozenToolbarState, HudDrawConfig,
-	LiveSampleApplyResult, ScrollCaptureState, SlowOperationLogger, WindowFreezeCaptureTarget,
+	CaptureCountdown, CaptureFlash, CursorMoveTrace, EventLoopProgress, ExportPreview,
+	FrozenRequestIds, FrozenToolbarPointerState, FrozenToolbarState, HudDrawConfig,
+	HudPointerState, LiveSampleApplyResult, LiveSampleRequests, ScrollCaptureState,
+	SlowOperationLogger, WindowFreezeCaptureTarget,
 };
 #[cfg(target_os = "macos")]
 use self::session_state::{
 	LiveStreamStaleGrace, MacOSHudWindowConfigState, MacOSScrollPixelResidual,
 	MacOSScrollWheelEvent,
 };
+use self::shader_runtime::{ShaderHotReload, ShaderSources};
+use crate::backend::{self, CaptureBackendKind};
+use crate::callout;
+use crate::capture_exclusion;
+use crate::color_math;
+use crate::color_vision::ColorVisionFilter;
+use crate::highlighter;
+use crate::i18n;
+use crate::latency::{CaptureLatency, LatencyStage};
 #[cfg(target_os = "macos")]
 use crate::live_frame_stream_macos::MacLiveFrameStream;
+use crate::memory_budget::MemoryAccountant;
+use crate::png;
+use crate::progress::OverlayProgress;
+use crate::redact;
 use crate::scroll_capture::{ScrollDirection, ScrollObserveOutcome, ScrollSession};
-use crate::state::LiveCursorSample;
+#[cfg(target_os = "macos")]
+use crate::share_sheet_macos::{self, ShareAnchor};
+use crate::stamp;
+use crate::state::{CursorImage, LiveCursorSample};
 #[cfg(any(not(target_os = "macos"), test))]
 use crate::worker::CapturedMonitorRegionResult;
 use crate::{
 	state::{
-		GlobalPoint, MonitorRect, MonitorRectPoints, OverlayMode, OverlayState, RectPoints, Rgb,
-		WindowHit, WindowListSnapshot,
+		GlobalPoint, MonitorRect, MonitorRectPoints, OverlayMode, OverlayState, PerfHudMetrics,
+		RectPoints, Rgb, WindowHit, WindowListSnapshot,
+	},
+	worker::{
+		ExportResize, FreezeCaptureTarget, OverlayWorker, PngBatchExport, PngExport,
+		WorkerRequestSendError, WorkerResponse,
 	},
-	worker::{FreezeCaptureTarget, OverlayWorker, WorkerRequestSendError, WorkerResponse},
 };
 
 #[cfg(target_os = "macos")]
@@ -182,6 +250,19 @@ const HUD_PILL_BODY_FILL_LIGHT_SRGBA8: [u8; 4] = [232, 236, 243, 176];
 const HUD_PILL_BLUR_TINT_ALPHA_DARK: f32 = 0.18;
 const HUD_PILL_BLUR_TINT_ALPHA_LIGHT: f32 = 0.22;
 const LOUPE_TILE_CORNER_RADIUS_POINTS: f64 = 12.0;
+pub(crate) const LOUPE_CELL_POINTS: f32 = 10.0;
+const LOUPE_ZOOM_CELL_POINTS: [f32; 6] = [4.0, 6.0, 8.0, LOUPE_CELL_POINTS, 14.0, 20.0];
+const LOUPE_ZOOM_DEFAULT_INDEX: usize = 3;
+const LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP: f32 = 40.0;
+/// Zoom factor applied per frozen view step (`+`/`-` or one wheel notch).
+const FROZEN_VIEW_ZOOM_STEP: f32 = 1.25;
+/// How far an arrow key pans the zoomed frozen view, in window points.
+const FROZEN_VIEW_PAN_KEY_POINTS: f32 = 40.0;
+/// From this cell size up the loupe draws its pixel grid with coordinates.
+const LOUPE_PIXEL_GRID_MIN_CELL_POINTS: f32 = 14.0;
+const MAGNIFIER_LENS_SIDE_POINTS: f32 = 240.0;
+const MAGNIFIER_ZOOM_MIN: u32 = 2;
+const MAGNIFIER_ZOOM_MAX: u32 = 8;
 #[cfg(target_os = "macos")]
 const MACOS_HUD_WINDOW_LEVEL: isize = 26;
 #[cfg(target_os = "macos")]
@@ -189,14 +270,39 @@ const MACOS_OVERLAY_WINDOW_LEVEL: isize = 25;
 const FROZEN_TOOLBAR_BUTTON_SIZE_POINTS: f32 = 24.0;
 const FROZEN_TOOLBAR_ITEM_SPACING_POINTS: f32 = 4.0;
 const TOOLBAR_MAX_TOOL_COUNT: usize = 9;
+const EXPORT_PREVIEW_SLOT_WIDTH_POINTS: f32 = 40.0;
+// Twice the slot size, so the preview stays sharp on Retina displays.
+const EXPORT_PREVIEW_MAX_SIZE_PX: (u32, u32) = (
+	(EXPORT_PREVIEW_SLOT_WIDTH_POINTS * 2.0) as u32,
+	(FROZEN_TOOLBAR_BUTTON_SIZE_POINTS * 2.0) as u32,
+);
+/// Number of numeric keypad keys (1–9) that can carry a quick action.
+pub const KEYPAD_QUICK_ACTION_SLOTS: usize = 9;
+/// Keypad bindings used when the user has not configured any: 1 copies, 2 saves.
+pub const DEFAULT_KEYPAD_QUICK_ACTIONS: [KeypadQuickAction; KEYPAD_QUICK_ACTION_SLOTS] = [
+	KeypadQuickAction::Copy,
+	KeypadQuickAction::Save,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+	KeypadQuickAction::None,
+];
 const LIVE_EVENT_CURSOR_CACHE_TTL: Duration = Duration::from_millis(120);
 const CURSOR_EVENT_TICK_TTL: Duration = Duration::from_millis(24);
 const LIVE_HOVER_HIT_TEST_INTERVAL: Duration = Duration::from_millis(60);
 const LIVE_WINDOW_LIST_REFRESH_INTERVAL: Duration = Duration::from_millis(120);
+const LIVE_BG_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
 const LIVE_PRESENT_INTERVAL_MIN: Duration = Duration::from_nanos(8_333_333);
 const HUD_LOUPE_MOVE_INTERVAL_MIN: Duration = LIVE_PRESENT_INTERVAL_MIN;
 const CURSOR_POLL_INTERVAL_MIN: Duration = LIVE_PRESENT_INTERVAL_MIN;
 const OVERLAY_EVENT_LOOP_STALL_THRESHOLD: Duration = Duration::from_millis(250);
+/// How long opening the overlay waits for the GPU device before giving up.
+const GPU_INIT_TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(test)]
+const OFFSCREEN_EGUI_FRAME_STEP: Duration = Duration::from_secs(1);
 #[cfg(target_os = "macos")]
 const SLOW_OP_WARN_CURSOR_LOCATION: Duration = Duration::from_millis(8);
 #[cfg(target_os = "macos")]
@@ -223,11 +329,17 @@ const TOOLBAR_EXPANDED_HEIGHT_PX: f32 = FROZEN_TOOLBAR_BUTTON_SIZE_POINTS
 const TOOLBAR_CAPTURE_GAP_PX: f32 = 10.0;
 const TOOLBAR_SCREEN_MARGIN_PX: f32 = 10.0;
 const HUD_PILL_CORNER_RADIUS_POINTS: u8 = 18;
+const HISTOGRAM_PLOT_WIDTH_POINTS: f32 = 256.0;
+const HISTOGRAM_PLOT_HEIGHT_POINTS: f32 = 96.0;
 const TOOLBAR_DRAG_START_THRESHOLD_PX: f32 = 6.0;
-#[cfg(target_os = "macos")]
 const TOOLBAR_WINDOW_WARMUP_REDRAWS: u8 = 30;
+const HUD_ENTRY_FADE_DURATION: Duration = Duration::from_millis(140);
+const TOOLBAR_ENTRY_DURATION: Duration = Duration::from_millis(180);
+const TOOLBAR_ENTRY_SLIDE_POINTS: f32 = 12.0;
 const LOUPE_WINDOW_WARMUP_REDRAWS: u8 = 30;
 const LIVE_DRAG_START_THRESHOLD_PX: f32 = 6.0;
+const KEYBOARD_NAV_STEP_POINTS: i32 = 1;
+const KEYBOARD_NAV_FAST_STEP_POINTS: i32 = 10;
 const SELECTION_FLOW_CORNER_RADIUS_PX: f32 = 9.0;
 const SELECTION_FLOW_MIN_SEGMENTS: usize = 160;
 const SELECTION_FLOW_MAX_SEGMENTS: usize = 1_536;
@@ -284,6 +396,33 @@ pub enum OverlayExit {
 	PngBytes(Vec<u8>),
 	/// The session completed by saving a file to disk.
 	Saved(PathBuf),
+	/// The session completed by opening the contained file in the external editor.
+	OpenedWith(PathBuf),
+	/// The session completed by showing the share menu for the contained file.
+	Shared(PathBuf),
+	/// The color picker completed by copying the contained color value to the clipboard.
+	ColorCopied(String),
+	/// The user asked to keep watching the frozen selection for changes after the session.
+	WatchRegion {
+		/// Monitor holding the selection.
+		monitor: MonitorRect,
+		/// Selection in monitor-local pixels.
+		rect_px: RectPoints,
+	},
+	/// The user asked to capture the frozen selection on a schedule after the session.
+	IntervalCapture {
+		/// Monitor holding the selection.
+		monitor: MonitorRect,
+		/// Selection in monitor-local pixels.
+		rect_px: RectPoints,
+	},
+	/// The user asked to capture the frozen selection in both the light and dark appearance.
+	ThemePair {
+		/// Monitor holding the selection.
+		monitor: MonitorRect,
+		/// Selection in monitor-local pixels.
+		rect_px: RectPoints,
+	},
 	/// The session failed with a user-visible error message.
 	Error(String),
 }
@@ -330,6 +469,41 @@ pub enum OutputNaming {
 	Sequence,
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects the image format of saved captures.
+pub enum ExportFormat {
+	#[default]
+	/// Lossless PNG with transparency.
+	Png,
+	/// JPEG at the configured quality, flattened onto white.
+	Jpeg,
+	/// Lossless WebP with transparency.
+	Webp,
+}
+
+impl ExportFormat {
+	/// File extension written for this format.
+	pub fn extension(self) -> &'static str {
+		match self {
+			Self::Png => "png",
+			Self::Jpeg => "jpg",
+			Self::Webp => "webp",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects what Copy places on the clipboard.
+pub enum ClipboardFormat {
+	#[default]
+	/// The image itself, for pasting into editors and chats.
+	Image,
+	/// A file in the export format, for pasting into file managers and uploads.
+	File,
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 /// Controls how transparent window captures are composited before export.
@@ -344,6 +518,348 @@ pub enum WindowCaptureAlphaMode {
 	MatteDark,
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Controls how a single-window capture treats the native drop shadow and rounded corners.
+pub enum WindowShadowMode {
+	#[default]
+	/// Drop the shadow and square off rounded corners for a tight, opaque rectangle.
+	Trim,
+	/// Keep the native drop shadow and export the window with transparency.
+	Keep,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects which GPU adapter the overlay asks for on machines with more than one.
+pub enum GpuPowerPreference {
+	#[default]
+	/// Prefer the integrated GPU, which saves battery and is plenty for the overlay.
+	LowPower,
+	/// Prefer the discrete GPU.
+	HighPerformance,
+}
+
+impl GpuPowerPreference {
+	fn to_wgpu(self) -> PowerPreference {
+		match self {
+			Self::LowPower => PowerPreference::LowPower,
+			Self::HighPerformance => PowerPreference::HighPerformance,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects what a capture session started with [`OverlaySession::start_capture`] captures.
+pub enum CaptureMode {
+	#[default]
+	/// Drag a region, or click a window to capture it.
+	Region,
+	/// Click a window to capture it; dragging does not select a region.
+	Window,
+	/// Click anywhere on a display to capture the whole display.
+	Display,
+	/// Pick a color instead of capturing.
+	ColorPick,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects what fills the canvas behind a capture exported with a backdrop.
+pub enum BackdropFill {
+	/// Fill with the primary color.
+	Solid,
+	#[default]
+	/// Fill with a diagonal gradient from the primary to the secondary color.
+	Gradient,
+	/// Fill with a heavily blurred copy of the screen around the capture.
+	Blur,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+/// Describes the share-ready canvas that exports can be placed on.
+pub struct ExportBackdrop {
+	/// Starts each frozen capture with the backdrop turned on; the toolbar toggles it per capture.
+	pub enabled_by_default: bool,
+	/// What fills the canvas behind the capture.
+	pub fill: BackdropFill,
+	/// Solid fill color and the gradient's top-left stop.
+	pub primary_rgb: [u8; 3],
+	/// The gradient's bottom-right stop.
+	pub secondary_rgb: [u8; 3],
+	/// Canvas margin around the capture, in export pixels.
+	pub padding_px: u32,
+	/// Corner radius applied to the capture, in export pixels.
+	pub corner_radius_px: u32,
+	/// Draws a soft drop shadow beneath the capture.
+	pub shadow: bool,
+}
+impl Default for ExportBackdrop {
+	fn default() -> Self {
+		Self {
+			enabled_by_default: false,
+			fill: BackdropFill::Gradient,
+			primary_rgb: [99, 102, 241],
+			secondary_rgb: [236, 72, 153],
+			padding_px: 64,
+			corner_radius_px: 12,
+			shadow: true,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects how exports are resized before encoding.
+pub enum ExportScaleMode {
+	#[default]
+	/// Keep the captured pixels.
+	Native,
+	/// Downscale HiDPI captures so one export pixel is one logical point.
+	LogicalPoints,
+	/// Downscale so the longest side is at most [`ExportScaling::max_side_px`].
+	MaxSide,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+/// Shrinks exports before encoding; exports are never upscaled.
+pub struct ExportScaling {
+	/// How exports are shrunk, if at all.
+	pub mode: ExportScaleMode,
+	/// Longest-side cap used by [`ExportScaleMode::MaxSide`], in export pixels.
+	pub max_side_px: u32,
+}
+impl ExportScaling {
+	#[must_use]
+	/// Returns the size an export of `size` pixels from a display with `scale_factor` is resampled
+	/// to, or `None` when it stays as is.
+	pub fn target_size(&self, size: (u32, u32), scale_factor: f32) -> Option<(u32, u32)> {
+		let (width, height) = size;
+		let ratio = match self.mode {
+			ExportScaleMode::Native => return None,
+			ExportScaleMode::LogicalPoints => 1.0 / scale_factor.max(1.0),
+			ExportScaleMode::MaxSide if self.max_side_px == 0 => return None,
+			ExportScaleMode::MaxSide => {
+				(self.max_side_px as f32 / width.max(height).max(1) as f32).min(1.0)
+			},
+		};
+		let scaled = |value: u32| ((value as f32 * ratio).round() as u32).max(1);
+		let target = (scaled(width), scaled(height));
+
+		(target != size).then_some(target)
+	}
+}
+impl Default for ExportScaling {
+	fn default() -> Self {
+		Self { mode: ExportScaleMode::Native, max_side_px: 1_600 }
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Export corner a watermark is anchored to.
+pub enum WatermarkCorner {
+	/// Anchor to the top-left corner.
+	TopLeft,
+	/// Anchor to the top-right corner.
+	TopRight,
+	/// Anchor to the bottom-left corner.
+	BottomLeft,
+	#[default]
+	/// Anchor to the bottom-right corner.
+	BottomRight,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+/// Text or PNG logo stamped onto exports. It is applied on the worker during encoding only and
+/// never drawn in the overlay.
+pub struct Watermark {
+	/// Stamps the watermark onto every export.
+	pub enabled: bool,
+	/// Drawn in the bundled UI font when no logo is set.
+	pub text: String,
+	/// Text color.
+	pub text_rgb: [u8; 3],
+	/// Text height, in export pixels.
+	pub text_size_px: u32,
+	/// PNG file composited instead of the text; empty uses the text.
+	pub logo_path: String,
+	/// Export corner the watermark sits in.
+	pub corner: WatermarkCorner,
+	/// 0..=100.
+	pub opacity_percent: u8,
+	/// Distance from the export edges, in export pixels.
+	pub margin_px: u32,
+}
+impl Default for Watermark {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			text: String::new(),
+			text_rgb: [255, 255, 255],
+			text_size_px: 24,
+			logo_path: String::new(),
+			corner: WatermarkCorner::BottomRight,
+			opacity_percent: 60,
+			margin_px: 16,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+/// Screen-edge bands where the live overlay lets clicks through, so edge-activated docks and hot
+/// corners keep working during a capture.
+pub struct EdgeDeadZones {
+	/// Band width in screen points.
+	pub size_px: u32,
+	/// Passes clicks through along the top edge.
+	pub top: bool,
+	/// Passes clicks through along the bottom edge.
+	pub bottom: bool,
+	/// Passes clicks through along the left edge.
+	pub left: bool,
+	/// Passes clicks through along the right edge.
+	pub right: bool,
+}
+impl EdgeDeadZones {
+	#[must_use]
+	/// Returns whether `point` falls in an enabled band of `monitor`.
+	pub fn contains(&self, monitor: MonitorRect, point: GlobalPoint) -> bool {
+		let Some((x, y)) = monitor.local_u32(point) else {
+			return false;
+		};
+		let size = self.size_px;
+
+		(self.left && x < size)
+			|| (self.top && y < size)
+			|| (self.right && x >= monitor.width.saturating_sub(size))
+			|| (self.bottom && y >= monitor.height.saturating_sub(size))
+	}
+
+	const fn is_enabled(&self) -> bool {
+		self.size_px > 0 && (self.top || self.bottom || self.left || self.right)
+	}
+}
+impl Default for EdgeDeadZones {
+	fn default() -> Self {
+		Self { size_px: 4, top: false, bottom: false, left: false, right: false }
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+/// Composition guides drawn inside the live drag selection. `G` toggles `visible` mid-session.
+pub struct CompositionGuides {
+	/// Draws the enabled guides; starts each session from the configured value.
+	pub visible: bool,
+	/// Rule-of-thirds grid.
+	pub thirds: bool,
+	/// Small crosshair at the selection center.
+	pub center: bool,
+	/// Action-safe (5%) and title-safe (10%) margins.
+	pub safe_margins: bool,
+}
+impl Default for CompositionGuides {
+	fn default() -> Self {
+		Self { visible: false, thirds: true, center: true, safe_margins: false }
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects the unit system used by HUD coordinate readouts.
+pub enum HudUnit {
+	/// Show monitor-local physical pixels.
+	Pixels,
+	#[default]
+	/// Show global logical points as reported by the window system.
+	Points,
+	/// Show monitor-local millimeters derived from the reported panel size.
+	Millimeters,
+}
+impl HudUnit {
+	const fn next(self) -> Self {
+		match self {
+			Self::Pixels => Self::Points,
+			Self::Points => Self::Millimeters,
+			Self::Millimeters => Self::Pixels,
+		}
+	}
+
+	const fn suffix(self) -> &'static str {
+		match self {
+			Self::Pixels => "px",
+			Self::Points => "pt",
+			Self::Millimeters => "mm",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// Selects the quick action bound to a numeric keypad key while the capture is frozen.
+pub enum KeypadQuickAction {
+	#[default]
+	/// Leave the keypad key unbound.
+	None,
+	/// Copy the current capture to the clipboard and close the overlay.
+	Copy,
+	/// Save the current capture to the configured output directory and close the overlay.
+	Save,
+	/// Open the current capture in the configured external editor and close the overlay.
+	OpenWith,
+}
+impl KeypadQuickAction {
+	const fn png_action(self) -> Option<PngAction> {
+		match self {
+			Self::None => None,
+			Self::Copy => Some(PngAction::Copy),
+			Self::Save => Some(PngAction::Save),
+			Self::OpenWith => Some(PngAction::OpenWith),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// One step of the pipeline run by the frozen toolbar's Done action.
+pub enum DoneAction {
+	/// Copy the capture to the clipboard.
+	Copy,
+	/// Save the capture to the configured output directory.
+	Save,
+	/// Open the capture in the configured external editor.
+	OpenWith,
+	/// Show the native share menu (Messages, AirDrop, Mail, …) for the capture. macOS only.
+	Share,
+}
+impl DoneAction {
+	/// Every step, in the order the settings window lists them.
+	pub const ALL: [Self; 4] = [Self::Copy, Self::Save, Self::OpenWith, Self::Share];
+
+	#[must_use]
+	/// Returns the user-facing step name.
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Copy => "Copy to clipboard",
+			Self::Save => "Save to file",
+			Self::OpenWith => "Open in editor",
+			Self::Share => "Share…",
+		}
+	}
+
+	#[must_use]
+	/// Returns whether this platform can run the step.
+	pub const fn is_supported(self) -> bool {
+		!matches!(self, Self::Share) || cfg!(target_os = "macos")
+	}
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum OverlayEventLoopPhase {
 	Idle,
@@ -380,45 +896,101 @@ enum HudTheme {
 enum FrozenToolbarTool {
 	Pointer,
 	Pen,
+	Highlighter,
 	Text,
 	Mosaic,
+	Stamp,
+	Callout,
 	Undo,
 	Redo,
 	Scroll,
+	LastRegion,
+	Backdrop,
 	Copy,
 	Save,
+	Done,
 }
 impl FrozenToolbarTool {
-	const fn label(self) -> &'static str {
-		match self {
-			Self::Pointer => "Pointer",
-			Self::Pen => "Pen",
-			Self::Text => "Text",
-			Self::Mosaic => "Mosaic",
-			Self::Undo => "Undo",
-			Self::Redo => "Redo",
-			Self::Scroll => "Scroll Capture ↓",
-			Self::Copy => "Copy",
-			Self::Save => "Save",
-		}
+	fn label(self) -> &'static str {
+		i18n::tr(match self {
+			Self::Pointer => "toolbar.pointer",
+			Self::Pen => "toolbar.pen",
+			Self::Highlighter => "toolbar.highlighter",
+			Self::Text => "toolbar.text",
+			Self::Mosaic => "toolbar.mosaic",
+			Self::Stamp => "toolbar.stamp",
+			Self::Callout => "toolbar.callout",
+			Self::Undo => "toolbar.undo",
+			Self::Redo => "toolbar.redo",
+			Self::Scroll => "toolbar.scroll",
+			Self::LastRegion => "toolbar.last_region",
+			Self::Backdrop => "toolbar.backdrop",
+			Self::Copy => "toolbar.copy",
+			Self::Save => "toolbar.save",
+			Self::Done => "toolbar.done",
+		})
 	}
 
 	const fn icon(self) -> &'static str {
 		match self {
 			Self::Pointer => regular::CURSOR,
 			Self::Pen => regular::PENCIL_SIMPLE,
+			Self::Highlighter => regular::HIGHLIGHTER,
 			Self::Text => regular::TEXT_T,
 			Self::Mosaic => regular::CHECKERBOARD,
+			Self::Stamp => regular::STICKER,
+			Self::Callout => regular::CHAT_TEXT,
 			Self::Undo => regular::ARROW_COUNTER_CLOCKWISE,
 			Self::Redo => regular::ARROW_CLOCKWISE,
 			Self::Scroll => "↓",
+			Self::LastRegion => regular::CLOCK_COUNTER_CLOCKWISE,
+			Self::Backdrop => regular::FRAME_CORNERS,
 			Self::Copy => regular::COPY,
 			Self::Save => regular::FLOPPY_DISK,
+			Self::Done => regular::CHECK,
 		}
 	}
 
 	const fn is_mode_tool(self) -> bool {
-		matches!(self, Self::Pointer | Self::Pen | Self::Text | Self::Mosaic)
+		matches!(
+			self,
+			Self::Pointer
+				| Self::Pen | Self::Highlighter
+				| Self::Text | Self::Mosaic
+				| Self::Stamp
+				| Self::Callout
+		)
+	}
+}
+
+/// Buttons shown in the live HUD while it is pinned.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum HudQuickAction {
+	CopyColor,
+	CycleColorFormat,
+	ToggleLoupe,
+	Freeze,
+}
+impl HudQuickAction {
+	const ALL: [Self; 4] =
+		[Self::CopyColor, Self::CycleColorFormat, Self::ToggleLoupe, Self::Freeze];
+
+	const fn icon(self) -> &'static str {
+		match self {
+			Self::CopyColor => regular::COPY,
+			Self::CycleColorFormat => regular::SWATCHES,
+			Self::ToggleLoupe => regular::MAGNIFYING_GLASS,
+			Self::Freeze => regular::CAMERA,
+		}
+	}
+
+	fn label(self, state: &OverlayState) -> &'static str {
+		match self {
+			Self::CopyColor => i18n::tr("hud.copy"),
+			Self::CycleColorFormat => state.color_format.label(),
+			Self::ToggleLoupe => i18n::tr("hud.loupe"),
+			Self::Freeze => i18n::tr("hud.freeze"),
+		}
 	}
 }
 
@@ -453,6 +1025,9 @@ impl ScrollCaptureFrameSource {
 enum PngAction {
 	Copy,
 	Save,
+	/// Runs [`OverlayConfig::done_actions`] in order.
+	Done,
+	OpenWith,
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
@@ -486,6 +1061,14 @@ enum SelectionFlowStyle {
 	FullBorder,
 }
 
+/// Selection border styling taken from [`OverlayConfig`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SelectionFlowStroke {
+	width_px: f32,
+	rgb: Option<[u8; 3]>,
+	animated: bool,
+}
+
 #[derive(Clone, Debug)]
 /// Runtime configuration applied to a capture overlay session.
 pub struct OverlayConfig {
@@ -499,6 +1082,10 @@ pub struct OverlayConfig {
 	pub selection_particles: bool,
 	/// Sets the core stroke width used for the animated selection border.
 	pub selection_flow_stroke_width_px: f32,
+	/// Draws the selection border in this color instead of the built-in palette.
+	pub selection_flow_rgb: Option<[u8; 3]>,
+	/// Animates the selection border; when disabled it is drawn as a still outline.
+	pub selection_flow_animated: bool,
 	/// Forces an opaque HUD background instead of glass styling.
 	pub hud_opaque: bool,
 	/// 0..=1. Controls HUD background alpha.
@@ -509,12 +1096,16 @@ pub struct OverlayConfig {
 	pub hud_milk_amount: f32,
 	/// Hue value for tint, 0..=1.
 	pub hud_tint_hue: f32,
+	/// Adds an ordered dither to the HUD blur so smooth gradients do not band on 8-bit panels.
+	pub hud_blur_dither: bool,
 	/// Selects whether Alt must be held or can toggle the loupe.
 	pub alt_activation: AltActivationMode,
 	/// Chooses where the frozen toolbar is placed.
 	pub toolbar_placement: ToolbarPlacement,
 	/// Sets the loupe sample size in source pixels.
 	pub loupe_sample_side_px: u32,
+	/// Tints the row and column of the sampled pixel while the loupe shows its pixel grid.
+	pub loupe_row_column_highlight: bool,
 	/// Requests the light, dark, or system theme.
 	pub theme_mode: ThemeMode,
 	/// Chooses the destination directory for saved captures.
@@ -523,8 +1114,123 @@ pub struct OverlayConfig {
 	pub output_filename_prefix: String,
 	/// Selects the disk naming strategy for saved captures.
 	pub output_naming: OutputNaming,
+	/// Names saved captures from tokens such as `{prefix}-{date}-{seq}` instead of
+	/// `output_naming`; empty keeps the prefix-based names.
+	pub output_filename_template: String,
+	/// Image format of saved captures. Asset pairs and editor hand-offs stay PNG.
+	pub export_format: ExportFormat,
+	/// JPEG quality, 1..=100.
+	pub export_quality: u8,
+	/// Selects whether Copy places the image or an exported file on the clipboard.
+	pub clipboard_format: ClipboardFormat,
 	/// Selects how transparent window captures are flattened.
 	pub window_capture_alpha_mode: WindowCaptureAlphaMode,
+	/// Selects whether window captures keep or trim the native shadow and rounded corners.
+	pub window_shadow_mode: WindowShadowMode,
+	/// Selects the initial unit system for HUD coordinate readouts.
+	pub hud_unit: HudUnit,
+	/// Initial zoom factor for magnifier sessions, 2..=8.
+	pub magnifier_zoom: u32,
+	/// Quick actions bound to keypad keys 1 through 9 in frozen mode.
+	pub keypad_quick_actions: [KeypadQuickAction; KEYPAD_QUICK_ACTION_SLOTS],
+	/// Samples live colors from the display's composited output instead of the capture stream.
+	///
+	/// The stream (macOS) sees window content only; the composited path also includes other
+	/// apps' overlay windows. Other platforms already sample the composited desktop.
+	pub sample_composited_output: bool,
+	/// Shows the on-screen color next to the sampled color when the display's transfer table is
+	/// tinted (for example by f.lux). The sampled value itself stays the unadjusted design color.
+	pub show_display_adjusted_color: bool,
+	/// Composites the pointer into frozen captures and their exports.
+	pub include_cursor: bool,
+	/// Shows an RGB and luminance histogram of the selection in frozen mode.
+	pub histogram_panel: bool,
+	/// Copies a short `W×H PNG, size` summary alongside each export.
+	pub copy_export_summary: bool,
+	/// Opt-in shell command that receives each exported PNG on stdin and prints suggested alt
+	/// text, which is attached to the copied capture or saved next to the file. `None` disables it.
+	pub alt_text_command: Option<String>,
+	/// Canvas used when exports are placed on a backdrop from the frozen toolbar.
+	pub export_backdrop: ExportBackdrop,
+	/// Resampling applied to every export on the worker before PNG encoding.
+	pub export_scaling: ExportScaling,
+	/// Watermark stamped onto exports after scaling; off unless `enabled`.
+	pub watermark: Watermark,
+	/// Exports single selections as an asset pair: the export saved as `<name>@2x.png` next to a
+	/// half-size `<name>.png`, with Copy placing both files on the clipboard.
+	pub retina_pair: bool,
+	/// Writes `<name>.json` next to saved exports with the capture's image and screen rects.
+	pub geometry_sidecar: bool,
+	/// Writes `<name>.annotations.json` next to saved exports with the frozen-toolbar annotations
+	/// as vector data, so they can be re-edited.
+	pub annotation_sidecar: bool,
+	/// Shell command that reads the frozen selection as PNG on stdin and prints Tesseract-style TSV
+	/// word boxes, used by the `R` auto-redaction scan. `None` disables the scan.
+	pub redact_ocr_command: Option<String>,
+	/// When a frozen session has extra Shift-drag regions, also saves one collage image of all of
+	/// them next to the per-region files.
+	pub multi_region_collage: bool,
+	/// Shows a small live preview of the pending export at the start of the frozen toolbar.
+	pub export_preview: bool,
+	/// 0..=1. Flat darkening of the frozen screen outside the selection. 0 disables it.
+	pub frozen_dim_opacity: f32,
+	/// 0..=1. Darkens the frozen screen outside the selection towards the edges. 0 disables it.
+	pub frozen_dim_vignette: f32,
+	/// 0..=1. Film-grain noise over the frozen dim layer. 0 disables it.
+	pub frozen_dim_noise: f32,
+	/// Shell command that opens exported captures in an external editor, with `{path}` replaced
+	/// by the image path (appended when absent). `None` disables "Open with…".
+	pub open_with_command: Option<String>,
+	/// Ordered steps run by the Done action (toolbar button or Space); empty falls back to copy.
+	pub done_actions: Vec<DoneAction>,
+	/// Capture backend used by the overlay worker and, on macOS, the live sampling stream.
+	pub capture_backend: CaptureBackendKind,
+	/// Disables the HUD/toolbar entry animations and the springy toolbar drag.
+	pub reduce_motion: bool,
+	/// Screen edges where the live overlay passes clicks through instead of starting a capture.
+	pub edge_dead_zones: EdgeDeadZones,
+	/// Guides drawn inside the live drag selection.
+	pub composition_guides: CompositionGuides,
+	/// Drag selection edges within this many points of a window edge snap onto it. 0 disables.
+	pub selection_snap_threshold_px: u32,
+	/// Earlier capture regions, newest first, offered by the toolbar's Last Region button.
+	pub recent_capture_regions: Vec<MonitorRectPoints>,
+	/// Keeps the GPU device open after the session exits, so restarting the same session skips
+	/// the slowest part of the cold start.
+	pub resident: bool,
+	/// Memory budget in MiB for the session's cached screen images. Over it, the live background
+	/// behind the HUD blur is downsampled. 0 disables the budget.
+	pub image_cache_budget_mb: u32,
+	/// Upper bound on the live overlay's repaint, cursor polling and sampling rate, 15..=120. The
+	/// display's refresh rate still applies when it is lower.
+	pub sampling_fps_cap: u32,
+	/// GPU adapter requested for the overlay's renderer. Takes effect when the GPU device is next
+	/// opened.
+	pub gpu_power_preference: GpuPowerPreference,
+	/// How long the live window list used for hover hit-testing is reused before it is refreshed.
+	pub window_list_ttl_ms: u32,
+	/// How long a live screen image behind the HUD blur is reused before it is recaptured.
+	pub live_background_ttl_ms: u32,
+	/// Ink of the frozen toolbar's highlighter; multiplied onto the capture.
+	pub highlighter_rgb: [u8; 3],
+	/// Highlighter stroke width in points.
+	pub highlighter_width_px: f32,
+	/// What [`OverlaySession::start_capture`] captures.
+	pub capture_mode: CaptureMode,
+	/// Seconds between finishing a selection and capturing it; the overlay lets clicks through
+	/// while it counts down. 0 captures immediately.
+	pub capture_delay_secs: u32,
+	/// Briefly flash the captured region white once an export finishes.
+	pub capture_flash: bool,
+}
+impl OverlayConfig {
+	fn selection_flow_stroke(&self) -> SelectionFlowStroke {
+		SelectionFlowStroke {
+			width_px: self.selection_flow_stroke_width_px,
+			rgb: self.selection_flow_rgb,
+			animated: self.selection_flow_animated,
+		}
+	}
 }
 impl Default for OverlayConfig {
 	fn default() -> Self {
@@ -534,19 +1240,68 @@ impl Default for OverlayConfig {
 			show_hud_blur: true,
 			selection_particles: true,
 			selection_flow_stroke_width_px: SELECTION_FLOW_CORE_WIDTH_PX,
+			selection_flow_rgb: None,
+			selection_flow_animated: true,
 			hud_opaque: false,
 			hud_opacity: 0.35,
 			hud_fog_amount: 0.16,
 			hud_milk_amount: 0.0,
 			hud_tint_hue: 0.585,
+			hud_blur_dither: true,
 			alt_activation: AltActivationMode::Hold,
 			toolbar_placement: ToolbarPlacement::Bottom,
 			loupe_sample_side_px: 21,
+			loupe_row_column_highlight: false,
 			theme_mode: ThemeMode::System,
 			output_dir: PathBuf::from("."),
 			output_filename_prefix: String::from("rsnap"),
 			output_naming: OutputNaming::Timestamp,
+			output_filename_template: String::new(),
+			export_format: ExportFormat::Png,
+			export_quality: 90,
+			clipboard_format: ClipboardFormat::Image,
 			window_capture_alpha_mode: WindowCaptureAlphaMode::Background,
+			window_shadow_mode: WindowShadowMode::Trim,
+			hud_unit: HudUnit::Points,
+			magnifier_zoom: 4,
+			keypad_quick_actions: DEFAULT_KEYPAD_QUICK_ACTIONS,
+			sample_composited_output: false,
+			show_display_adjusted_color: false,
+			include_cursor: false,
+			histogram_panel: false,
+			copy_export_summary: false,
+			alt_text_command: None,
+			export_backdrop: ExportBackdrop::default(),
+			export_scaling: ExportScaling::default(),
+			watermark: Watermark::default(),
+			retina_pair: false,
+			geometry_sidecar: false,
+			annotation_sidecar: false,
+			redact_ocr_command: None,
+			multi_region_collage: false,
+			export_preview: true,
+			frozen_dim_opacity: 0.0,
+			frozen_dim_vignette: 0.0,
+			frozen_dim_noise: 0.0,
+			open_with_command: None,
+			done_actions: vec![DoneAction::Copy],
+			capture_backend: CaptureBackendKind::Auto,
+			reduce_motion: false,
+			edge_dead_zones: EdgeDeadZones::default(),
+			composition_guides: CompositionGuides::default(),
+			selection_snap_threshold_px: 8,
+			recent_capture_regions: Vec::new(),
+			resident: false,
+			image_cache_budget_mb: 1024,
+			sampling_fps_cap: INTERACTIVE_REPAINT_FPS_CAP as u32,
+			gpu_power_preference: GpuPowerPreference::LowPower,
+			window_list_ttl_ms: LIVE_WINDOW_LIST_REFRESH_INTERVAL.as_millis() as u32,
+			live_background_ttl_ms: LIVE_BG_REQUEST_INTERVAL.as_millis() as u32,
+			highlighter_rgb: [255, 230, 0],
+			highlighter_width_px: 20.0,
+			capture_mode: CaptureMode::Region,
+			capture_delay_secs: 0,
+			capture_flash: false,
 		}
 	}
 }
@@ -564,6 +1319,8 @@ pub struct OverlaySession {
 	state: OverlayState,
 	cursor_monitor: Option<MonitorRect>,
 	egui_repaint_deadline: Arc<Mutex<Option<Instant>>>,
+	/// Debug-only shader watcher, enabled by `RSNAP_SHADER_DIR`.
+	shader_hot_reload: Option<ShaderHotReload>,
 	windows: HashMap<WindowId, OverlayWindow>,
 	hud_window: Option<HudOverlayWindow>,
 	loupe_window: Option<HudOverlayWindow>,
@@ -579,10 +1336,22 @@ pub struct OverlaySession {
 	loupe_inner_size_points: Option<(u32, u32)>,
 	toolbar_outer_pos: Option<GlobalPoint>,
 	toolbar_inner_size_points: Option<(u32, u32)>,
+	hud_entry: EntryAnimation,
+	toolbar_entry: EntryAnimation,
+	/// Smooths the toolbar towards the dragged position, in global points on the given monitor.
+	toolbar_drag_spring: Option<(DragSpring, MonitorRect)>,
 	gpu: Option<GpuContext>,
+	/// GPU initialization running off the event loop, started by prewarm or the first start.
+	pending_gpu: Option<PendingGpuContext>,
 	last_hud_window_move_at: Instant,
 	last_loupe_window_move_at: Instant,
 	last_present_at: Instant,
+	/// Pipeline milestones of the current session, measured from the capture request.
+	capture_latency: CaptureLatency,
+	/// Sizes of the cached screen images against `image_cache_budget_mb`.
+	memory: MemoryAccountant,
+	/// The live background was shrunk to fit the budget and cannot stand in for the frozen image.
+	live_bg_downsampled: bool,
 	last_live_cursor_poll_at: Instant,
 	last_frozen_cursor_poll_at: Instant,
 	window_list_snapshot: Option<Arc<WindowListSnapshot>>,
@@ -593,44 +1362,68 @@ pub struct OverlaySession {
 	hit_test_send_full_count: u64,
 	hit_test_send_disconnected_count: u64,
 	hit_test_request_id: u64,
-	live_cursor_sample_request_id: u64,
-	latest_live_cursor_sample_request_id: Option<u64>,
-	applied_live_cursor_sample_request_id: Option<u64>,
-	latest_live_cursor_sample_requested_at: Option<Instant>,
-	last_idle_live_sample_request_at: Option<Instant>,
+	live_sample: LiveSampleRequests,
 	pending_click_hit_test_request_id: Option<u64>,
-	last_live_sample_cursor: Option<GlobalPoint>,
 	last_event_cursor: Option<(MonitorRect, GlobalPoint)>,
 	last_event_cursor_at: Option<Instant>,
-	live_sample_stall_started_at: Option<Instant>,
-	last_live_sample_stall_log_at: Option<Instant>,
 	slow_op_logger: SlowOperationLogger,
 	last_alt_press_at: Option<Instant>,
 	alt_modifier_down: bool,
 	keyboard_modifiers: ModifiersState,
-	event_loop_phase: OverlayEventLoopPhase,
-	event_loop_progress_seq: u64,
-	event_loop_last_progress_at: Instant,
-	event_loop_last_progress_window_id: Option<WindowId>,
-	event_loop_last_progress_monitor_id: Option<u32>,
-	event_loop_last_progress_detail: Option<&'static str>,
-	event_loop_last_stall_warn_at: Option<Instant>,
+	keyboard_nav_active: bool,
+	keyboard_window_cycle_index: Option<usize>,
+	event_loop: EventLoopProgress,
 	loupe_patch_width_px: u32,
 	loupe_patch_height_px: u32,
+	magnifier_active: bool,
+	magnifier_zoom: u32,
+	color_picker_active: bool,
+	color_picker_held_key: Option<ColorPickerKey>,
+	/// Captures without showing the overlay (focused window, repeated region) and exits once the
+	/// export finishes.
+	direct_capture: bool,
+	/// Monitor and selection of the latest single-region export, reported to the caller so the
+	/// capture can be repeated.
+	last_export_region: Option<MonitorRectPoints>,
+	/// Export progress from the worker that the caller has not taken yet.
+	pending_progress: Vec<OverlayProgress>,
+	interval_capture_picker: bool,
+	/// When the identify-displays session closes itself.
+	display_identify_until: Option<Instant>,
+	/// A finished selection waiting out the capture delay.
+	capture_countdown: Option<CaptureCountdown>,
+	/// A finished export waiting out the confirmation flash.
+	capture_flash: Option<CaptureFlash>,
+	edge_dead_zone_passthrough: bool,
+	loupe_zoom_index: usize,
+	loupe_zoom_wheel_accum_px: f32,
+	frozen_view_wheel_accum_px: f32,
+	/// Last pointer position of a middle-button pan of the zoomed frozen view.
+	frozen_view_drag: Option<GlobalPoint>,
 	pending_freeze_capture: Option<MonitorRect>,
 	pending_freeze_capture_armed: bool,
+	/// Monitors enumerated by `prewarm`, consumed by the next `start`.
+	prewarmed_monitors: Option<Vec<MonitorRect>>,
 	pending_window_freeze_capture: Option<WindowFreezeCaptureTarget>,
 	inflight_window_freeze_capture: Option<WindowFreezeCaptureTarget>,
 	frozen_window_image: Option<RgbaImage>,
+	/// Pointer sprite and position captured at freeze begin, composited once the image arrives.
+	pending_freeze_cursor: Option<(GlobalPoint, CursorImage)>,
+	/// Ids of the newest frozen-image worker requests; older results are dropped.
+	frozen_request_ids: FrozenRequestIds,
+	/// Performance HUD counters; `None` while the HUD is off.
+	perf_hud: Option<PerfHudCounters>,
 	frozen_capture_source: FrozenCaptureSource,
 	capture_windows_hidden: bool,
-	pending_encode_png: Option<RgbaImage>,
+	pending_encode_png: Option<Box<PngExport>>,
+	pending_encode_png_batch: Option<Box<PngBatchExport>>,
 	pending_png_action: Option<PngAction>,
 	toolbar_state: FrozenToolbarState,
 	toolbar_left_button_down: bool,
 	toolbar_left_button_went_down: bool,
 	toolbar_left_button_went_up: bool,
 	toolbar_pointer_local: Option<Pos2>,
+	hud_pointer: HudPointerState,
 	left_mouse_button_down: bool,
 	left_mouse_button_down_monitor: Option<MonitorRect>,
 	left_mouse_button_down_global: Option<GlobalPoint>,
@@ -652,26 +1445,14 @@ impl OverlaySession {
 	#[must_use]
 	/// Creates a new overlay session with the provided runtime configuration.
 	pub fn with_config(config: OverlayConfig) -> Self {
-		let live_bg_request_interval = Duration::from_millis(500);
+		let live_bg_request_interval = Duration::from_millis(config.live_background_ttl_ms.into());
 		let loupe_sample_side_px =
 			Self::normalized_loupe_sample_side_px(config.loupe_sample_side_px);
-		let window_list_refresh_interval = LIVE_WINDOW_LIST_REFRESH_INTERVAL;
+		let window_list_refresh_interval = Duration::from_millis(config.window_list_ttl_ms.into());
+		let toolbar_state = FrozenToolbarState::with_config(&config);
+		let image_cache_budget_mb = config.image_cache_budget_mb;
 		let now = Instant::now();
-		#[cfg(not(target_os = "macos"))]
-		let cursor_device = match panic::catch_unwind(device_query::DeviceState::new) {
-			Ok(cursor_device) => Some(cursor_device),
-			Err(_) => {
-				tracing::warn!(
-					op = "overlay.cursor_device_unavailable",
-					"Falling back to a headless-safe cursor device stub."
-				);
-
-				None
-			},
-		};
-		let mut state = OverlayState::new();
-
-		state.loupe_patch_side_px = loupe_sample_side_px;
+		let state = Self::initial_state(&config, loupe_sample_side_px);
 
 		Self {
 			config,
@@ -681,7 +1462,7 @@ impl OverlaySession {
 			#[cfg(target_os = "macos")]
 			live_sample_stream: None,
 			#[cfg(not(target_os = "macos"))]
-			cursor_device,
+			cursor_device: Self::open_cursor_device(),
 			state,
 			cursor_monitor: None,
 			windows: HashMap::new(),
@@ -699,10 +1480,17 @@ impl OverlaySession {
 			loupe_inner_size_points: None,
 			toolbar_outer_pos: None,
 			toolbar_inner_size_points: None,
+			hud_entry: EntryAnimation::default(),
+			toolbar_entry: EntryAnimation::default(),
+			toolbar_drag_spring: None,
 			gpu: None,
+			pending_gpu: None,
 			last_hud_window_move_at: now,
 			last_loupe_window_move_at: now,
 			last_present_at: Instant::now(),
+			capture_latency: CaptureLatency::new(now),
+			memory: MemoryAccountant::new(image_cache_budget_mb),
+			live_bg_downsampled: false,
 			last_live_cursor_poll_at: now - CURSOR_POLL_INTERVAL_MIN,
 			last_frozen_cursor_poll_at: now - CURSOR_POLL_INTERVAL_MIN,
 			window_list_snapshot: None,
@@ -713,45 +1501,57 @@ impl OverlaySession {
 			hit_test_send_full_count: 0,
 			hit_test_send_disconnected_count: 0,
 			hit_test_request_id: 0,
-			live_cursor_sample_request_id: 0,
-			latest_live_cursor_sample_request_id: None,
-			applied_live_cursor_sample_request_id: None,
-			latest_live_cursor_sample_requested_at: None,
-			last_idle_live_sample_request_at: None,
+			live_sample: LiveSampleRequests::default(),
 			pending_click_hit_test_request_id: None,
-			last_live_sample_cursor: None,
 			last_event_cursor: None,
 			last_event_cursor_at: None,
-			live_sample_stall_started_at: None,
-			last_live_sample_stall_log_at: None,
 			slow_op_logger: SlowOperationLogger::default(),
 			last_alt_press_at: None,
 			alt_modifier_down: false,
 			keyboard_modifiers: ModifiersState::default(),
-			event_loop_phase: OverlayEventLoopPhase::Idle,
-			event_loop_progress_seq: 0,
-			event_loop_last_progress_at: now,
-			event_loop_last_progress_window_id: None,
-			event_loop_last_progress_monitor_id: None,
-			event_loop_last_progress_detail: None,
-			event_loop_last_stall_warn_at: None,
+			keyboard_nav_active: false,
+			keyboard_window_cycle_index: None,
+			event_loop: EventLoopProgress::new(now),
 			loupe_patch_width_px: loupe_sample_side_px,
 			loupe_patch_height_px: loupe_sample_side_px,
+			magnifier_active: false,
+			magnifier_zoom: MAGNIFIER_ZOOM_MIN,
+			color_picker_active: false,
+			color_picker_held_key: None,
+			direct_capture: false,
+			last_export_region: None,
+			pending_progress: Vec::new(),
+			interval_capture_picker: false,
+			display_identify_until: None,
+			capture_countdown: None,
+			capture_flash: None,
+			edge_dead_zone_passthrough: false,
+			loupe_zoom_index: LOUPE_ZOOM_DEFAULT_INDEX,
+			loupe_zoom_wheel_accum_px: 0.0,
+			frozen_view_wheel_accum_px: 0.0,
+			frozen_view_drag: None,
 			egui_repaint_deadline: Arc::new(Mutex::new(None)),
+			shader_hot_reload: ShaderHotReload::from_env(),
 			pending_freeze_capture: None,
 			pending_freeze_capture_armed: false,
+			prewarmed_monitors: None,
 			pending_window_freeze_capture: None,
 			inflight_window_freeze_capture: None,
 			frozen_window_image: None,
+			pending_freeze_cursor: None,
+			frozen_request_ids: FrozenRequestIds::default(),
+			perf_hud: None,
 			frozen_capture_source: FrozenCaptureSource::None,
 			capture_windows_hidden: false,
 			pending_encode_png: None,
+			pending_encode_png_batch: None,
 			pending_png_action: None,
-			toolbar_state: FrozenToolbarState::default(),
+			toolbar_state,
 			toolbar_left_button_down: false,
 			toolbar_left_button_went_down: false,
 			toolbar_left_button_went_up: false,
 			toolbar_pointer_local: None,
+			hud_pointer: HudPointerState::default(),
 			left_mouse_button_down: false,
 			left_mouse_button_down_monitor: None,
 			left_mouse_button_down_global: None,
@@ -766,6 +1566,31 @@ impl OverlaySession {
 		}
 	}
 
+	fn initial_state(config: &OverlayConfig, loupe_sample_side_px: u32) -> OverlayState {
+		let mut state = OverlayState::new();
+
+		state.loupe_patch_side_px = loupe_sample_side_px;
+		state.hud_unit = config.hud_unit;
+		state.composition_guides = config.composition_guides;
+
+		state
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	fn open_cursor_device() -> Option<device_query::DeviceState> {
+		match panic::catch_unwind(device_query::DeviceState::new) {
+			Ok(cursor_device) => Some(cursor_device),
+			Err(_) => {
+				tracing::warn!(
+					op = "overlay.cursor_device_unavailable",
+					"Falling back to a headless-safe cursor device stub."
+				);
+
+				None
+			},
+		}
+	}
+
 	#[cfg(target_os = "macos")]
 	/// Registers a wake callback for macOS live-stream frame notifications.
 	pub fn set_scroll_frame_waker(&mut self, waker: Arc<dyn Fn() + Send + Sync>) {
@@ -805,16 +1630,81 @@ impl OverlaySession {
 		);
 	}
 
+	/// Measures this session's pipeline from `requested_at`, typically the hotkey press that
+	/// started it. Call after `start`, which otherwise measures from its own start.
+	pub fn set_capture_requested_at(&mut self, requested_at: Instant) {
+		self.capture_latency.set_requested_at(requested_at);
+	}
+
+	/// Returns the pipeline milestones this session has reached so far.
+	#[must_use]
+	pub fn capture_latency(&self) -> CaptureLatency {
+		self.capture_latency
+	}
+
+	/// Returns the monitor and selection of the latest export, so the caller can repeat it.
+	#[must_use]
+	pub fn last_capture_region(&self) -> Option<MonitorRectPoints> {
+		self.last_export_region
+	}
+
+	/// Takes the export progress events the worker reported since the last call, oldest first.
+	pub fn take_progress(&mut self) -> Vec<OverlayProgress> {
+		std::mem::take(&mut self.pending_progress)
+	}
+
+	/// Returns the composition guides as last toggled, so the caller can persist `G` presses.
+	#[must_use]
+	pub fn composition_guides(&self) -> CompositionGuides {
+		self.state.composition_guides
+	}
+
 	/// Applies updated runtime configuration to an existing session.
 	pub fn set_config(&mut self, config: OverlayConfig) {
 		let prev = self.config.clone();
 		let previous_loupe_patch = self.loupe_patch_width_px;
-		let loupe_sample_side = Self::normalized_loupe_sample_side_px(config.loupe_sample_side_px);
+		let (loupe_sample_side, loupe_cell_points) = if self.magnifier_active {
+			(self.loupe_patch_width_px, self.state.loupe_cell_points)
+		} else {
+			Self::loupe_zoom_geometry(config.loupe_sample_side_px, self.loupe_zoom_index)
+		};
+
+		if config.hud_unit != self.config.hud_unit {
+			self.state.hud_unit = config.hud_unit;
+		}
+		if config.composition_guides != self.config.composition_guides {
+			self.state.composition_guides = config.composition_guides;
+		}
+		// A kept GPU device was opened with the previous preference; the next prewarm or start
+		// reopens it.
+		if config.gpu_power_preference != self.config.gpu_power_preference && !self.is_active() {
+			self.gpu = None;
+			self.pending_gpu = None;
+		}
+		// A prewarmed capture pipeline belongs to the previous backend; the next prewarm or start
+		// recreates it.
+		if config.capture_backend != self.config.capture_backend && !self.is_active() {
+			self.worker = None;
+
+			#[cfg(target_os = "macos")]
+			{
+				self.live_sample_stream = None;
+			}
+		}
 
+		self.toolbar_state.keypad_quick_actions = config.keypad_quick_actions;
+		self.window_list_refresh_interval = Duration::from_millis(config.window_list_ttl_ms.into());
+		self.live_bg_request_interval = Duration::from_millis(config.live_background_ttl_ms.into());
 		self.config = config;
 		self.loupe_patch_width_px = loupe_sample_side;
 		self.loupe_patch_height_px = loupe_sample_side;
 		self.state.loupe_patch_side_px = loupe_sample_side;
+		self.state.loupe_cell_points = loupe_cell_points;
+		self.state.frozen_dim_opacity = self.config.frozen_dim_opacity;
+		self.state.frozen_dim_vignette = self.config.frozen_dim_vignette;
+		self.state.frozen_dim_noise = self.config.frozen_dim_noise;
+		self.state.hud_blur_dither = self.config.hud_blur_dither;
+		self.state.loupe_row_column_highlight = self.config.loupe_row_column_highlight;
 
 		let patch_changed = self.loupe_patch_width_px != previous_loupe_patch;
 
@@ -973,10 +1863,20 @@ impl OverlaySession {
 	}
 
 	#[must_use]
-	pub(crate) fn is_active(&self) -> bool {
+	/// Returns whether the session still has overlay windows open.
+	pub fn is_active(&self) -> bool {
 		!self.windows.is_empty()
 	}
 
+	/// Notes a presented frame for the stall watchdog and the session's first-visible latency.
+	fn record_present(&mut self) {
+		let now = Instant::now();
+
+		self.last_present_at = now;
+
+		self.capture_latency.mark(LatencyStage::OverlayVisible, now);
+	}
+
 	fn use_fake_hud_blur(&self) -> bool {
 		self.config.show_hud_blur && !cfg!(target_os = "macos")
 	}
@@ -1063,6 +1963,20 @@ impl OverlaySession {
 		self.maybe_tick_frozen_cursor_tracking();
 		self.maybe_tick_scroll_capture();
 		self.maybe_keep_live_cursor_sample_redraw();
+		self.maybe_tick_shader_hot_reload();
+
+		if let Some(control) = self.maybe_finish_display_identify(now) {
+			return control;
+		}
+
+		self.maybe_tick_capture_countdown(now);
+
+		if let Some(control) = self.maybe_tick_capture_flash(now) {
+			return control;
+		}
+		if let Some(control) = self.poll_color_picker_keys() {
+			return control;
+		}
 
 		self.drain_worker_responses()
 	}
@@ -1076,33 +1990,34 @@ impl OverlaySession {
 		phase: OverlayEventLoopPhase,
 		detail: Option<&'static str>,
 	) {
-		self.event_loop_phase = phase;
-		self.event_loop_last_progress_detail = detail;
-		self.event_loop_progress_seq = self.event_loop_progress_seq.saturating_add(1);
-		self.event_loop_last_progress_at = Instant::now();
+		self.event_loop.phase = phase;
+		self.event_loop.last_progress_detail = detail;
+		self.event_loop.progress_seq = self.event_loop.progress_seq.saturating_add(1);
+		self.event_loop.last_progress_at = Instant::now();
 	}
 
 	fn maybe_log_event_loop_stall(&mut self, now: Instant) {
-		let stall = now.duration_since(self.event_loop_last_progress_at);
+		let stall = now.duration_since(self.event_loop.last_progress_at);
 
 		if stall < OVERLAY_EVENT_LOOP_STALL_THRESHOLD {
 			return;
 		}
 		if self
-			.event_loop_last_stall_warn_at
+			.event_loop
+			.last_stall_warn_at
 			.is_none_or(|last| now.duration_since(last) >= SLOW_OP_WARN_INTERVAL)
 		{
-			let _ = self.event_loop_last_stall_warn_at.insert(now);
+			let _ = self.event_loop.last_stall_warn_at.insert(now);
 
 			tracing::warn!(
 				op = "overlay.event_loop_stall",
 				stall_ms = stall.as_millis(),
-				phase = %self.event_loop_phase.as_str(),
-				progress_seq = self.event_loop_progress_seq,
+				phase = %self.event_loop.phase.as_str(),
+				progress_seq = self.event_loop.progress_seq,
 				mode = ?self.state.mode,
-				window_id = ?self.event_loop_last_progress_window_id,
-				monitor_id = ?self.event_loop_last_progress_monitor_id,
-				detail = ?self.event_loop_last_progress_detail,
+				window_id = ?self.event_loop.last_progress_window_id,
+				monitor_id = ?self.event_loop.last_progress_monitor_id,
+				detail = ?self.event_loop.last_progress_detail,
 				"Event loop stalled"
 			);
 		}
@@ -1146,11 +2061,11 @@ impl OverlaySession {
 			return;
 		}
 
-		let Some(latest_request_id) = self.latest_live_cursor_sample_request_id else {
+		let Some(latest_request_id) = self.live_sample.latest_request_id else {
 			return;
 		};
 
-		if self.applied_live_cursor_sample_request_id == Some(latest_request_id) {
+		if self.live_sample.applied_request_id == Some(latest_request_id) {
 			return;
 		}
 
@@ -1160,7 +2075,10 @@ impl OverlaySession {
 	}
 
 	fn maybe_keep_selection_flow_repaint(&self) {
-		if !self.is_active() || !self.config.selection_particles {
+		if !self.is_active()
+			|| !self.config.selection_particles
+			|| !self.config.selection_flow_animated
+		{
 			return;
 		}
 
@@ -1225,7 +2143,8 @@ impl OverlaySession {
 				if fps.is_finite() && fps > 0.0 { Some(fps) } else { None }
 			})
 			.max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
-		let fps = Self::interactive_repaint_fps(monitor_fps, fallback_fps);
+		let fps = Self::interactive_repaint_fps(monitor_fps, fallback_fps)
+			.min(self.config.sampling_fps_cap.max(1) as f32);
 
 		Duration::from_secs_f32(1.0 / fps)
 	}
@@ -1380,29 +2299,22 @@ impl OverlaySession {
 			return;
 		}
 
-		#[cfg(not(target_os = "macos"))]
+		if !matches!(self.state.mode, OverlayMode::Frozen)
+			|| !self.toolbar_state.visible
+			|| self.state.frozen_image.is_none()
+			|| self.state.monitor.is_none()
 		{
 			self.toolbar_window_warmup_redraws_remaining = 0;
+
+			return;
 		}
-		#[cfg(target_os = "macos")]
-		{
-			if !matches!(self.state.mode, OverlayMode::Frozen)
-				|| !self.toolbar_state.visible
-				|| self.state.frozen_image.is_none()
-				|| self.state.monitor.is_none()
-			{
-				self.toolbar_window_warmup_redraws_remaining = 0;
 
-				return;
-			}
+		self.toolbar_window_warmup_redraws_remaining =
+			self.toolbar_window_warmup_redraws_remaining.saturating_sub(1);
 
-			self.toolbar_window_warmup_redraws_remaining =
-				self.toolbar_window_warmup_redraws_remaining.saturating_sub(1);
-
-			self.request_redraw_toolbar_window();
-			self.schedule_egui_repaint_after(self.repaint_interval_for_monitor(self.state.monitor));
-		}
-	}
+		self.request_redraw_toolbar_window();
+		self.schedule_egui_repaint_after(self.repaint_interval_for_monitor(self.state.monitor));
+	}
 
 	fn maybe_tick_frozen_cursor_tracking(&mut self) {
 		if !self.is_active() || !matches!(self.state.mode, OverlayMode::Frozen) {
@@ -1501,7 +2413,10 @@ impl OverlaySession {
 	}
 
 	fn maybe_tick_live_cursor_tracking(&mut self) {
-		if !self.is_active() || !matches!(self.state.mode, OverlayMode::Live) {
+		if !self.is_active()
+			|| !matches!(self.state.mode, OverlayMode::Live)
+			|| self.state.hud_pinned
+		{
 			return;
 		}
 
@@ -1627,9 +2542,8 @@ impl OverlaySession {
 		{
 			return;
 		}
-		if self.latest_live_cursor_sample_request_id.is_some()
-			&& self.applied_live_cursor_sample_request_id
-				!= self.latest_live_cursor_sample_request_id
+		if self.live_sample.latest_request_id.is_some()
+			&& self.live_sample.applied_request_id != self.live_sample.latest_request_id
 		{
 			return;
 		}
@@ -1643,7 +2557,7 @@ impl OverlaySession {
 			self.maybe_request_live_bg(monitor);
 		}
 		if self.request_live_samples_for_cursor(monitor, cursor) {
-			self.last_idle_live_sample_request_at = Some(now);
+			self.live_sample.last_idle_request_at = Some(now);
 		}
 	}
 
@@ -1736,11 +2650,17 @@ impl OverlaySession {
 			}
 		}
 
-		if let Some(image) = self.pending_encode_png.take()
+		if let Some(export) = self.pending_encode_png.take()
 			&& let Some(worker) = self.worker.as_ref()
-			&& let Err(image) = worker.request_encode_png(image)
+			&& let Err(export) = worker.request_encode_png(export)
 		{
-			self.pending_encode_png = Some(image);
+			self.pending_encode_png = Some(export);
+		}
+		if let Some(batch) = self.pending_encode_png_batch.take()
+			&& let Some(worker) = self.worker.as_ref()
+			&& let Err(batch) = worker.request_encode_png_batch(batch)
+		{
+			self.pending_encode_png_batch = Some(batch);
 		}
 
 		#[cfg(any(not(target_os = "macos"), test))]
@@ -1769,7 +2689,7 @@ impl OverlaySession {
 			}
 		}
 
-		OverlayControl::Continue
+		self.direct_capture_failure().unwrap_or(OverlayControl::Continue)
 	}
 
 	fn request_live_samples_for_cursor(
@@ -1887,42 +2807,69 @@ impl OverlaySession {
 
 		#[cfg(target_os = "macos")]
 		{
-			let Some(stream) = self.live_sample_stream.as_ref() else {
-				return false;
-			};
-			let request_id = self.live_cursor_sample_request_id.wrapping_add(1);
+			let request_id = self.live_sample.request_id.wrapping_add(1);
 			let patch_width_px = if want_patch { self.loupe_patch_width_px } else { 0 };
 			let patch_height_px = if want_patch { self.loupe_patch_height_px } else { 0 };
 			let Some((x_px, y_px)) = monitor.local_u32_pixels(cursor) else {
 				return false;
 			};
-			let sample = stream.latest_cursor_sample(
-				monitor,
-				x_px,
-				y_px,
-				want_patch,
-				patch_width_px,
-				patch_height_px,
-			);
+			let (source, sample) = if self.config.sample_composited_output {
+				let sample = backend::sample_composited_cursor(
+					monitor,
+					x_px,
+					y_px,
+					want_patch,
+					patch_width_px,
+					patch_height_px,
+				)
+				.inspect_err(|err| {
+					tracing::debug!(
+						monitor_id = monitor.id,
+						error = %format!("{err:#}"),
+						"Composited live sample failed."
+					);
+				})
+				.ok();
+
+				("macos_composited", sample)
+			} else {
+				let Some(stream) = self.live_sample_stream.as_ref() else {
+					return false;
+				};
 
-			self.live_cursor_sample_request_id = request_id;
-			self.latest_live_cursor_sample_request_id = Some(request_id);
-			self.latest_live_cursor_sample_requested_at = Some(Instant::now());
+				(
+					"macos_stream",
+					stream.latest_cursor_sample(
+						monitor,
+						x_px,
+						y_px,
+						want_patch,
+						patch_width_px,
+						patch_height_px,
+					),
+				)
+			};
+
+			self.live_sample.request_id = request_id;
+			self.live_sample.latest_request_id = Some(request_id);
+			self.live_sample.latest_requested_at = Some(Instant::now());
 
 			let Some(sample) = sample else {
 				return false;
 			};
 
-			self.applied_live_cursor_sample_request_id = Some(request_id);
+			self.live_sample.applied_request_id = Some(request_id);
 
 			let apply = self.apply_live_cursor_sample_detail(monitor, cursor, sample);
 			let sample_latency = self
-				.latest_live_cursor_sample_requested_at
+				.live_sample
+				.latest_requested_at
 				.take()
 				.map_or(Duration::ZERO, |requested_at| requested_at.elapsed());
 
+			self.record_perf_hud_sample_latency(sample_latency);
 			self.log_live_sample_apply_timing(
-				"macos_stream",
+				source,
 				monitor,
 				cursor,
 				request_id,
@@ -1938,9 +2885,8 @@ impl OverlaySession {
 		}
 		#[cfg(not(target_os = "macos"))]
 		{
-			if self.latest_live_cursor_sample_request_id.is_some()
-				&& self.applied_live_cursor_sample_request_id
-					!= self.latest_live_cursor_sample_request_id
+			if self.live_sample.latest_request_id.is_some()
+				&& self.live_sample.applied_request_id != self.live_sample.latest_request_id
 			{
 				return false;
 			}
@@ -1948,7 +2894,7 @@ impl OverlaySession {
 			let Some(worker) = self.worker.as_ref() else {
 				return false;
 			};
-			let request_id = self.live_cursor_sample_request_id.wrapping_add(1);
+			let request_id = self.live_sample.request_id.wrapping_add(1);
 			let patch_width_px = if want_patch { self.loupe_patch_width_px } else { 0 };
 			let patch_height_px = if want_patch { self.loupe_patch_height_px } else { 0 };
 
@@ -1961,9 +2907,9 @@ impl OverlaySession {
 				patch_height_px,
 			) {
 				Ok(()) => {
-					self.live_cursor_sample_request_id = request_id;
-					self.latest_live_cursor_sample_request_id = Some(request_id);
-					self.latest_live_cursor_sample_requested_at = Some(Instant::now());
+					self.live_sample.request_id = request_id;
+					self.live_sample.latest_request_id = Some(request_id);
+					self.live_sample.latest_requested_at = Some(Instant::now());
 
 					true
 				},
@@ -2034,10 +2980,19 @@ impl OverlaySession {
 				_ => true,
 			};
 
-			if loupe_changed {
+			if loupe_changed && !self.loupe_held_by_reference() {
 				self.state.loupe = loupe;
 				changed.loupe_changed = true;
 			}
+
+			let locked_rgb = self.state.loupe_lock.and_then(|offset| {
+				self.state.loupe.as_ref().and_then(|loupe| loupe.rgb_at(offset))
+			});
+
+			if locked_rgb.is_some() && self.state.rgb != locked_rgb {
+				self.state.rgb = locked_rgb;
+				changed.hud_changed = true;
+			}
 		} else if self.state.loupe.is_some() {
 			self.state.loupe = None;
 			changed.loupe_changed = true;
@@ -2047,15 +3002,19 @@ impl OverlaySession {
 	}
 
 	fn apply_live_hover_cache_state(&mut self, monitor: MonitorRect, cursor: GlobalPoint) -> bool {
-		if !matches!(self.state.mode, OverlayMode::Live) {
+		if !matches!(self.state.mode, OverlayMode::Live) || self.is_loupe_only_session() {
 			return false;
 		}
 		if !monitor.contains(cursor) {
 			return false;
 		}
 
-		let hovered_window_rect = self
-			.hovered_window_hit_from_window_list_snapshot(monitor, cursor)
+		let hovered_window_hit = self.hovered_window_hit_from_window_list_snapshot(monitor, cursor);
+		let hovered_window_label =
+			hovered_window_hit.and_then(|hit| hit.window_id).and_then(|window_id| {
+				self.window_list_snapshot.as_ref()?.labels.get(&window_id).cloned()
+			});
+		let hovered_window_rect = hovered_window_hit
 			.map(|hit| MonitorRectPoints { monitor_id: monitor.id, rect: hit.rect });
 		let mut updated = false;
 
@@ -2063,6 +3022,10 @@ impl OverlaySession {
 			self.state.hovered_window_rect = hovered_window_rect;
 			updated = true;
 		}
+		if self.state.hovered_window_label != hovered_window_label {
+			self.state.hovered_window_label = hovered_window_label;
+			updated = true;
+		}
 
 		updated
 	}
@@ -2088,7 +3051,7 @@ impl OverlaySession {
 	}
 
 	fn idle_live_sampling_request_allowed(&self, now: Instant, monitor: MonitorRect) -> bool {
-		self.last_idle_live_sample_request_at.is_none_or(|last_request_at| {
+		self.live_sample.last_idle_request_at.is_none_or(|last_request_at| {
 			now.duration_since(last_request_at) >= self.idle_live_sampling_interval(monitor)
 		})
 	}
@@ -2120,18 +3083,18 @@ impl OverlaySession {
 	fn record_live_sample_stall(&mut self, cursor: GlobalPoint, monitor: MonitorRect) {
 		let now = Instant::now();
 
-		match self.last_live_sample_cursor {
+		match self.live_sample.last_cursor {
 			Some(last_cursor) if last_cursor == cursor => {
-				let stall_started_at = self.live_sample_stall_started_at;
+				let stall_started_at = self.live_sample.stall_started_at;
 
-				if self.live_sample_stall_started_at.is_none() {
-					self.live_sample_stall_started_at = Some(now);
+				if self.live_sample.stall_started_at.is_none() {
+					self.live_sample.stall_started_at = Some(now);
 				} else if stall_started_at
 					.is_some_and(|start| now.duration_since(start) >= Duration::from_millis(100))
-					&& self.last_live_sample_stall_log_at.is_none_or(|last_log| {
+					&& self.live_sample.last_stall_log_at.is_none_or(|last_log| {
 						now.duration_since(last_log) >= Duration::from_millis(250)
 					}) {
-					let Some(stall_started_at) = self.live_sample_stall_started_at else {
+					let Some(stall_started_at) = self.live_sample.stall_started_at else {
 						return;
 					};
 
@@ -2142,19 +3105,19 @@ impl OverlaySession {
 						"Live sampling cursor unchanged while sampling ticks continue."
 					);
 
-					self.last_live_sample_stall_log_at = Some(now);
+					self.live_sample.last_stall_log_at = Some(now);
 				}
 			},
 			Some(_) => {
-				self.live_sample_stall_started_at = None;
-				self.last_live_sample_stall_log_at = None;
+				self.live_sample.stall_started_at = None;
+				self.live_sample.last_stall_log_at = None;
 			},
 			None => {
-				self.live_sample_stall_started_at = Some(now);
+				self.live_sample.stall_started_at = Some(now);
 			},
 		}
 
-		self.last_live_sample_cursor = Some(cursor);
+		self.live_sample.last_cursor = Some(cursor);
 	}
 
 	fn maybe_tick_worker_response_limiter(&mut self, resp: WorkerResponse) -> OverlayControl {
@@ -2175,6 +3138,11 @@ impl OverlaySession {
 
 				OverlayControl::Continue
 			},
+			WorkerResponse::ProbedMonitor { monitor, error } => {
+				self.handle_probed_monitor_response(monitor, error);
+
+				OverlayControl::Continue
+			},
 			WorkerResponse::CapturedFreeze { monitor, image, window_image, captured_window_id } => {
 				self.handle_captured_freeze_response(
 					monitor,
@@ -2192,7 +3160,40 @@ impl OverlaySession {
 
 				OverlayControl::Continue
 			},
-			WorkerResponse::EncodedPng { png_bytes } => self.handle_encoded_png_response(png_bytes),
+			WorkerResponse::Progress(progress) => {
+				self.pending_progress.push(progress);
+
+				OverlayControl::Continue
+			},
+			WorkerResponse::EncodedPng { png_bytes, one_x_png: Some(one_x_png) } => {
+				self.handle_encoded_asset_pair_response(png_bytes, one_x_png)
+			},
+			WorkerResponse::EncodedPng { png_bytes, one_x_png: None } => {
+				self.handle_encoded_png_response(png_bytes, None, None)
+			},
+			WorkerResponse::ScannedRedactions { request_id, proposals } => {
+				self.handle_scanned_redactions_response(request_id, proposals);
+
+				OverlayControl::Continue
+			},
+			WorkerResponse::ScannedCodes { request_id, codes } => {
+				self.handle_scanned_codes_response(request_id, codes);
+
+				OverlayControl::Continue
+			},
+			WorkerResponse::EncodedPngBatch { region_pngs, collage_png } => {
+				self.handle_encoded_png_batch_response(region_pngs, collage_png)
+			},
+			WorkerResponse::ComputedHistogram { request_id, histogram } => {
+				self.handle_computed_histogram_response(request_id, *histogram);
+
+				OverlayControl::Continue
+			},
+			WorkerResponse::RenderedExportPreview { request_id, image } => {
+				self.handle_rendered_export_preview_response(request_id, image);
+
+				OverlayControl::Continue
+			},
 		}
 	}
 
@@ -2210,18 +3211,20 @@ impl OverlaySession {
 		if self.active_cursor_monitor() != Some(monitor) {
 			return;
 		}
-		if self.latest_live_cursor_sample_request_id != Some(request_id) {
+		if self.live_sample.latest_request_id != Some(request_id) {
 			return;
 		}
 
-		self.applied_live_cursor_sample_request_id = Some(request_id);
+		self.live_sample.applied_request_id = Some(request_id);
 
 		let apply = self.apply_live_cursor_sample_detail(monitor, point, sample);
 		let sample_latency = self
-			.latest_live_cursor_sample_requested_at
+			.live_sample
+			.latest_requested_at
 			.take()
 			.map_or(Duration::ZERO, |requested_at| requested_at.elapsed());
 
+		self.record_perf_hud_sample_latency(sample_latency);
 		self.log_live_sample_apply_timing(
 			"worker_response",
 			monitor,
@@ -2304,7 +3307,7 @@ impl OverlaySession {
 				})
 			});
 
-			self.begin_frozen_capture_with_rect(monitor, capture_rect, window_target, Some(point));
+			self.begin_selected_capture(monitor, capture_rect, window_target, Some(point));
 		}
 	}
 
@@ -2314,7 +3317,7 @@ impl OverlaySession {
 		if self.window_list_snapshot.is_none() {
 			let request_id = self.hit_test_request_id.wrapping_add(1);
 			let Some(worker) = self.worker.as_ref() else {
-				self.begin_frozen_capture_with_rect(monitor, None, None, Some(cursor));
+				self.begin_selected_capture(monitor, None, None, Some(cursor));
 
 				return;
 			};
@@ -2363,7 +3366,7 @@ impl OverlaySession {
 			})
 		});
 
-		self.begin_frozen_capture_with_rect(monitor, capture_rect, window_target, Some(cursor));
+		self.begin_selected_capture(monitor, capture_rect, window_target, Some(cursor));
 	}
 
 	fn begin_frozen_capture_with_rect(
@@ -2373,6 +3376,15 @@ impl OverlaySession {
 		window_target: Option<WindowFreezeCaptureTarget>,
 		cursor: Option<GlobalPoint>,
 	) {
+		if let Some(reason) = self.state.capture_unavailable_reason(monitor.id) {
+			let message = format!("Capture unavailable on this display: {reason}");
+
+			self.state.set_error(message);
+			self.request_redraw_for_monitor(monitor);
+
+			return;
+		}
+
 		self.frozen_capture_source = if rect.is_none() {
 			FrozenCaptureSource::FullscreenFallback
 		} else if window_target.is_some() {
@@ -2389,6 +3401,7 @@ impl OverlaySession {
 		});
 
 		self.state.clear_error();
+		self.clear_edge_dead_zone_passthrough();
 		self.state.begin_freeze(monitor);
 
 		self.state.frozen_capture_rect = Some(capture_rect);
@@ -2457,6 +3470,7 @@ impl OverlaySession {
 		self.pending_window_freeze_capture = window_target;
 		self.inflight_window_freeze_capture = None;
 		self.frozen_window_image = None;
+		self.pending_freeze_cursor = self.freeze_cursor_overlay(cursor);
 		self.capture_windows_hidden = false;
 		self.pending_click_hit_test_request_id = None;
 		self.left_mouse_button_down = false;
@@ -2469,6 +3483,7 @@ impl OverlaySession {
 		if self.use_fake_hud_blur()
 			&& window_target.is_none()
 			&& self.state.live_bg_monitor == Some(monitor)
+			&& !self.live_bg_downsampled
 			&& let Some(image) = self.state.live_bg_image.take()
 		{
 			self.state.live_bg_monitor = None;
@@ -2491,36 +3506,49 @@ impl OverlaySession {
 		}
 	}
 
+	/// Tracks the drag selection: the capture drag in live mode, or an extra region drag in frozen
+	/// mode.
 	fn update_live_drag_rect(&mut self, monitor: MonitorRect, global: GlobalPoint) {
-		if !matches!(self.state.mode, OverlayMode::Live) {
-			self.state.drag_rect = None;
-
-			return;
-		}
-		if !self.left_mouse_button_down || self.left_mouse_button_down_monitor != Some(monitor) {
+		let (Some(start_monitor), Some(start_global)) =
+			(self.left_mouse_button_down_monitor, self.left_mouse_button_down_global)
+		else {
 			self.state.drag_rect = None;
 
 			return;
-		}
+		};
 
-		let Some(start_global) = self.left_mouse_button_down_global else {
+		if !self.left_mouse_button_down
+			|| (matches!(self.state.mode, OverlayMode::Live)
+				&& self.config.capture_mode != CaptureMode::Region)
+		{
 			self.state.drag_rect = None;
 
 			return;
-		};
-		let Some(rect) = monitor.local_rect_from_points(start_global, global) else {
-			self.state.drag_rect = None;
+		}
 
-			return;
-		};
+		let previous = self.state.drag_rect;
 
-		if rect.is_empty() {
-			self.state.drag_rect = None;
+		self.state.drag_rect = Self::anchored_drag_rect(start_monitor, start_global, global)
+			.map(|drag_rect| self.snap_drag_rect(start_monitor, drag_rect));
 
-			return;
+		// The selection stays on the monitor where the drag began; when the cursor is over another
+		// monitor, that monitor's overlay is not the one being redrawn for the move.
+		if start_monitor != monitor && previous != self.state.drag_rect {
+			self.request_redraw_for_monitor(start_monitor);
 		}
+	}
 
-		self.state.drag_rect = Some(MonitorRectPoints { monitor_id: monitor.id, rect });
+	/// Builds the drag selection on the monitor where the press began. Once the cursor crosses
+	/// onto another display the moving corner is clipped to the start monitor's edge, instead of
+	/// the selection vanishing or being re-read in the other monitor's scale.
+	fn anchored_drag_rect(
+		start_monitor: MonitorRect,
+		start_global: GlobalPoint,
+		global: GlobalPoint,
+	) -> Option<MonitorRectPoints> {
+		let rect = start_monitor.local_rect_from_points(start_global, global)?;
+
+		(!rect.is_empty()).then_some(MonitorRectPoints { monitor_id: start_monitor.id, rect })
 	}
 
 	fn cropped_frozen_capture_image(&self) -> Option<RgbaImage> {
@@ -2528,6 +3556,11 @@ impl OverlaySession {
 			&& let Some(window_image) = self.frozen_window_image.as_ref()
 		{
 			match self.config.window_capture_alpha_mode {
+				WindowCaptureAlphaMode::Background
+					if self.config.window_shadow_mode == WindowShadowMode::Keep =>
+				{
+					return Some(window_image.clone());
+				},
 				WindowCaptureAlphaMode::Background => {},
 				WindowCaptureAlphaMode::MatteLight => {
 					return Some(Self::flatten_window_image_with_matte(
@@ -2552,7 +3585,18 @@ impl OverlaySession {
 			.state
 			.frozen_capture_rect
 			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
-		let capture_rect = monitor.local_rect_to_pixels(capture_rect);
+
+		self.cropped_frozen_region_image(monitor, capture_rect)
+	}
+
+	/// Crops `rect`, in monitor-local points, out of the frozen image.
+	fn cropped_frozen_region_image(
+		&self,
+		monitor: MonitorRect,
+		rect: RectPoints,
+	) -> Option<RgbaImage> {
+		let frozen_image = self.state.frozen_image.as_ref()?;
+		let capture_rect = monitor.local_rect_to_pixels(rect);
 		let x = capture_rect.x.min(frozen_image.width());
 		let y = capture_rect.y.min(frozen_image.height());
 		let max_width = frozen_image.width().saturating_sub(x);
@@ -2655,6 +3699,21 @@ impl OverlaySession {
 		monitor_image
 	}
 
+	fn freeze_cursor_overlay(
+		&self,
+		cursor: Option<GlobalPoint>,
+	) -> Option<(GlobalPoint, CursorImage)> {
+		if !self.config.include_cursor {
+			return None;
+		}
+
+		let cursor = cursor.or(self.state.cursor)?;
+		let cursor_image =
+			backend::current_cursor_image().unwrap_or_else(image_helpers::fallback_arrow_cursor);
+
+		Some((cursor, cursor_image))
+	}
+
 	fn handle_captured_freeze_response(
 		&mut self,
 		monitor: MonitorRect,
@@ -2674,12 +3733,42 @@ impl OverlaySession {
 				&& target.monitor == monitor
 				&& target.window_id == window_id
 			{
+				let keep_shadow = self.config.window_shadow_mode == WindowShadowMode::Keep;
+				let window_capture_image = if keep_shadow {
+					window_capture_image
+				} else {
+					image_helpers::trim_window_image(&window_capture_image)
+				};
+
 				match self.config.window_capture_alpha_mode {
-					WindowCaptureAlphaMode::Background => {},
+					// The shadowed image exports as-is; the screen preview already shows it.
+					WindowCaptureAlphaMode::Background if keep_shadow => {
+						self.frozen_window_image = Some(window_capture_image);
+					},
+					// The window's own render replaces whatever covered it on screen. A window
+					// hanging off the display renders larger than its visible rect and keeps the
+					// screen pixels instead of being squeezed into them.
+					WindowCaptureAlphaMode::Background => {
+						let rect_px = monitor.local_rect_to_pixels(target.rect);
+
+						if (rect_px.width, rect_px.height) == window_capture_image.dimensions() {
+							frozen_preview_image = Self::composite_window_capture_preview(
+								frozen_preview_image,
+								&window_capture_image,
+								monitor,
+								target.rect,
+								WindowCaptureAlphaMode::Background,
+							);
+						}
+					},
 					WindowCaptureAlphaMode::MatteLight | WindowCaptureAlphaMode::MatteDark => {
 						self.frozen_window_image = Some(window_capture_image);
 
-						if let Some(window_capture_image) = self.frozen_window_image.as_ref() {
+						// A shadowed image is larger than the window rect, so it cannot be laid
+						// over the preview in place.
+						if !keep_shadow
+							&& let Some(window_capture_image) = self.frozen_window_image.as_ref()
+						{
 							frozen_preview_image = Self::composite_window_capture_preview(
 								frozen_preview_image,
 								window_capture_image,
@@ -2693,11 +3782,11 @@ impl OverlaySession {
 			}
 
 			self.state.finish_freeze(monitor, frozen_preview_image);
+			self.capture_latency.mark(LatencyStage::FreezeComplete, Instant::now());
 			self.restore_capture_windows_visibility();
 
 			self.toolbar_state.needs_redraw = true;
 
-			#[cfg(target_os = "macos")]
 			if self.toolbar_state.visible {
 				self.toolbar_window_warmup_redraws_remaining =
 					self.toolbar_window_warmup_redraws_remaining.max(TOOLBAR_WINDOW_WARMUP_REDRAWS);
@@ -2716,10 +3805,23 @@ impl OverlaySession {
 				.map(|patch| crate::state::LoupeSample { center: cursor, patch });
 			}
 
+			// Composite after sampling so the frozen HUD reports the pixel under the pointer, not
+			// the pointer itself.
+			if let Some((cursor, cursor_image)) = self.pending_freeze_cursor.take()
+				&& let Some(frozen_image) = self.state.frozen_image.as_mut()
+			{
+				image_helpers::composite_cursor(frozen_image, monitor, cursor, &cursor_image);
+			}
+
+			self.account_image_caches();
+			self.request_selection_histogram();
+			self.request_export_preview();
+
 			self.maybe_start_loupe_window_warmup_redraw();
 			self.request_redraw_for_monitor(monitor);
 			#[cfg(not(target_os = "macos"))]
 			self.raise_hud_windows();
+			self.maybe_finish_direct_capture_freeze();
 
 			return;
 		}
@@ -2734,147 +3836,510 @@ impl OverlaySession {
 			self.state.live_bg_monitor = Some(monitor);
 			self.state.live_bg_image = Some(image);
 			self.state.live_bg_generation = self.state.live_bg_generation.wrapping_add(1);
+			self.live_bg_downsampled = false;
 
+			self.account_image_caches();
 			self.request_redraw_for_monitor(monitor);
 		}
 	}
 
-	fn handle_encoded_png_response(&mut self, png_bytes: Vec<u8>) -> OverlayControl {
-		let action = self.pending_png_action.take().unwrap_or(PngAction::Copy);
-
-		match action {
-			PngAction::Copy => match output::write_png_bytes_to_clipboard(&png_bytes) {
-				Ok(()) => self.exit(OverlayExit::PngBytes(png_bytes)),
-				Err(err) => {
-					self.state.set_error(format!("{err:#}"));
-					self.request_redraw_all();
+	/// Runs the pending action on an encoded export. `presaved` is a path the export was already
+	/// written to, which a Save step reuses instead of writing a second file. With `asset_pair`,
+	/// a Copy step places the pair's files on the clipboard instead of the image.
+	fn handle_encoded_png_response(
+		&mut self,
+		png_bytes: Vec<u8>,
+		mut presaved: Option<PathBuf>,
+		asset_pair: Option<AssetPairPaths>,
+	) -> OverlayControl {
+		self.capture_latency.mark(LatencyStage::EncodeComplete, Instant::now());
 
-					OverlayControl::Continue
+		let action = self.pending_png_action.take().unwrap_or(PngAction::Copy);
+		let summary =
+			if self.config.copy_export_summary { output::export_summary(&png_bytes) } else { None };
+
+		if let Some(summary) = summary.as_deref() {
+			tracing::info!(op = "overlay.export_summary", summary, "Export summary ready.");
+		}
+
+		let steps = self.png_action_steps(action);
+		// A pipeline that also copies owns the clipboard, so saving must not replace it.
+		let copies = steps.contains(&DoneAction::Copy);
+		let mut saved_path = None;
+		let mut opened_path = None;
+		let mut shared_path = None;
+
+		for step in steps {
+			let result = match step {
+				DoneAction::Copy => match asset_pair.as_ref() {
+					Some(pair) => self.copy_exported_files(&[&pair.one_x, &pair.two_x]),
+					None => self.copy_exported_png(
+						&png_bytes,
+						summary.as_deref(),
+						saved_path.as_deref(),
+					),
 				},
-			},
-			PngAction::Save => {
-				match output::save_png_bytes_to_configured_dir(&png_bytes, &self.config) {
-					Ok(path) => self.exit(OverlayExit::Saved(path)),
-					Err(err) => {
-						self.state.set_error(format!("{err:#}"));
-						self.request_redraw_all();
+				DoneAction::Save => match presaved.take() {
+					Some(path) => {
+						saved_path = Some(path);
 
-						OverlayControl::Continue
+						Ok(())
 					},
-				}
-			},
+					None => self
+						.save_exported_png(&png_bytes, summary.as_deref().filter(|_| !copies))
+						.map(|path| saved_path = Some(path)),
+				},
+				DoneAction::OpenWith => self
+					.open_exported_png(&png_bytes, saved_path.as_deref())
+					.map(|path| opened_path = Some(path)),
+				DoneAction::Share => self
+					.share_exported_png(&png_bytes, saved_path.as_deref())
+					.map(|path| shared_path = Some(path)),
+			};
+
+			if let Err(err) = result {
+				self.state.set_error(format!("{err:#}"));
+				self.request_redraw_all();
+
+				return OverlayControl::Continue;
+			}
+		}
+
+		match (saved_path, opened_path, shared_path) {
+			(Some(path), _, _) => self.finish_export(OverlayExit::Saved(path)),
+			(None, Some(path), _) => self.finish_export(OverlayExit::OpenedWith(path)),
+			(None, None, Some(path)) => self.finish_export(OverlayExit::Shared(path)),
+			(None, None, None) => self.finish_export(OverlayExit::PngBytes(png_bytes)),
 		}
 	}
 
-	/// Handles a winit window event for one of the overlay-owned windows.
-	pub fn handle_window_event(
+	/// Saves every region of a multi-region export as its own file, then finishes the action on
+	/// the collage when there is one and on the primary region otherwise.
+	fn handle_encoded_png_batch_response(
 		&mut self,
-		window_id: WindowId,
-		event: &WindowEvent,
+		region_pngs: Vec<Vec<u8>>,
+		collage_png: Option<Vec<u8>>,
 	) -> OverlayControl {
-		let started_at = Instant::now();
-		let kind = Self::window_event_kind(event);
-		let now = Instant::now();
+		self.capture_latency.mark(LatencyStage::EncodeComplete, Instant::now());
 
-		self.event_loop_last_progress_window_id = Some(window_id);
-		self.event_loop_last_progress_monitor_id =
-			self.windows.get(&window_id).map(|window| window.monitor.id);
+		let mut region_paths = Vec::with_capacity(region_pngs.len());
 
-		self.maybe_log_event_loop_stall(now);
-		self.mark_progress_with_detail(OverlayEventLoopPhase::WindowEvent, Some(kind));
+		for png_bytes in &region_pngs {
+			match output::save_png_bytes_to_configured_dir(png_bytes, &self.config) {
+				Ok(path) => region_paths.push(path),
+				Err(err) => {
+					self.pending_png_action = None;
 
-		if self
-			.scroll_preview_window
-			.as_ref()
-			.is_some_and(|preview_window| preview_window.window.id() == window_id)
-		{
-			return match event {
-				WindowEvent::RedrawRequested => self.handle_scroll_preview_redraw_requested(),
-				WindowEvent::MouseInput {
-					state: ElementState::Pressed,
-					button: MouseButton::Right,
-					..
-				} => self.exit(OverlayExit::Cancelled),
-				WindowEvent::KeyboardInput { event, .. } => self.handle_key_event(event),
-				WindowEvent::ModifiersChanged(modifiers) => {
-					self.handle_modifiers_changed(modifiers)
+					self.state.set_error(format!("{err:#}"));
+					self.request_redraw_all();
+
+					return OverlayControl::Continue;
 				},
-				_ => self.handle_scroll_preview_window_event(event),
-			};
+			}
 		}
 
-		let toolbar_window_id = self
-			.toolbar_window
-			.as_ref()
-			.is_some_and(|toolbar_window| toolbar_window.window.id() == window_id);
-		let control = match event {
-			WindowEvent::CloseRequested => self.exit(OverlayExit::Cancelled),
-			WindowEvent::MouseInput {
-				state: ElementState::Pressed,
-				button: MouseButton::Right,
-				..
-			} => self.exit(OverlayExit::Cancelled),
-			WindowEvent::Resized(size) if toolbar_window_id => {
-				self.handle_toolbar_window_resized(*size)
-			},
-			WindowEvent::Resized(size) => self.handle_resized(window_id, *size),
-			WindowEvent::ScaleFactorChanged { .. } if toolbar_window_id => {
-				self.handle_toolbar_window_scale_factor_changed(window_id)
-			},
-			WindowEvent::ScaleFactorChanged { .. } => self.handle_scale_factor_changed(window_id),
-			WindowEvent::CursorEntered { .. } if toolbar_window_id => OverlayControl::Continue,
-			WindowEvent::CursorLeft { .. } if toolbar_window_id => {
-				self.toolbar_pointer_local = None;
-				self.toolbar_left_button_down = false;
-				self.toolbar_left_button_went_down = false;
-				self.toolbar_left_button_went_up = false;
-				self.toolbar_state.dragging = false;
-				self.toolbar_state.drag_offset = Vec2::ZERO;
-				self.toolbar_state.drag_anchor = None;
+		tracing::info!(
+			op = "overlay.multi_region_export",
+			paths = ?region_paths,
+			collage = collage_png.is_some(),
+			"Saved selection regions."
+		);
 
-				#[cfg(target_os = "macos")]
-				{
-					self.request_redraw_toolbar_window();
-				}
+		match collage_png {
+			Some(collage_png) => self.handle_encoded_png_response(collage_png, None, None),
+			None => {
+				let primary_png = region_pngs.into_iter().next().unwrap_or_default();
+				let presaved = region_paths.into_iter().next();
 
-				OverlayControl::Continue
-			},
-			WindowEvent::CursorMoved { position, .. } => {
-				if toolbar_window_id {
-					self.handle_toolbar_cursor_moved(window_id, *position)
-				} else {
-					self.handle_cursor_moved(window_id, *position)
-				}
-			},
-			WindowEvent::MouseWheel { delta, .. } if toolbar_window_id => OverlayControl::Continue,
-			WindowEvent::MouseWheel { delta, .. } => {
-				self.handle_scroll_mouse_wheel(window_id, delta)
-			},
-			WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
-				if toolbar_window_id {
-					self.handle_toolbar_mouse_input(*state)
-				} else {
-					self.handle_left_mouse_input(window_id, *state)
-				}
+				self.handle_encoded_png_response(primary_png, presaved, None)
 			},
-			WindowEvent::RedrawRequested if toolbar_window_id => {
-				self.handle_toolbar_window_redraw_requested()
+		}
+	}
+
+	/// Writes an `@2x` + `@1x` asset pair side by side, into the output directory when the action
+	/// saves and the temp directory when it only copies, then finishes the action on the `@2x`
+	/// export.
+	fn handle_encoded_asset_pair_response(
+		&mut self,
+		png_bytes: Vec<u8>,
+		one_x_png: Vec<u8>,
+	) -> OverlayControl {
+		let steps = self.png_action_steps(self.pending_png_action.unwrap_or(PngAction::Copy));
+		let saves = steps.contains(&DoneAction::Save);
+		let written = if saves {
+			output::save_asset_pair_to_configured_dir(&png_bytes, &one_x_png, &self.config)
+		} else if steps.contains(&DoneAction::Copy) {
+			output::save_asset_pair_to_temp_dir(&png_bytes, &one_x_png)
+		} else {
+			return self.handle_encoded_png_response(png_bytes, None, None);
+		};
+
+		match written {
+			Ok(pair) => {
+				tracing::info!(
+					op = "overlay.asset_pair_export",
+					one_x = %pair.one_x.display(),
+					two_x = %pair.two_x.display(),
+					"Wrote @2x + @1x asset pair."
+				);
+
+				let presaved = saves.then(|| pair.two_x.clone());
+
+				self.handle_encoded_png_response(png_bytes, presaved, Some(pair))
 			},
-			WindowEvent::ThemeChanged(_) => {
-				// Keep the HUD palette in sync with system changes when ThemeMode::System is active.
-				if let Some(monitor) = self.windows.get(&window_id).map(|w| w.monitor) {
-					self.request_redraw_for_monitor(monitor);
-				} else {
-					self.request_redraw_all();
-				}
+			Err(err) => {
+				self.pending_png_action = None;
+
+				self.state.set_error(format!("{err:#}"));
+				self.request_redraw_all();
 
 				OverlayControl::Continue
 			},
-			WindowEvent::KeyboardInput { event, .. } => self.handle_key_event(event),
-			WindowEvent::ModifiersChanged(modifiers) => self.handle_modifiers_changed(modifiers),
-			WindowEvent::RedrawRequested => self.handle_redraw_requested(window_id),
-			_ => OverlayControl::Continue,
-		};
+		}
+	}
+
+	fn png_action_steps(&self, action: PngAction) -> Vec<DoneAction> {
+		match action {
+			PngAction::Copy => vec![DoneAction::Copy],
+			PngAction::Save => vec![DoneAction::Save],
+			PngAction::Done => Self::done_pipeline(&self.config.done_actions),
+			PngAction::OpenWith => vec![DoneAction::OpenWith],
+		}
+	}
+
+	fn open_exported_png(&self, png_bytes: &[u8], saved_path: Option<&Path>) -> Result<PathBuf> {
+		let command = self.config.open_with_command.as_deref().ok_or_else(|| {
+			eyre::eyre!("No external editor is configured. Set one in Settings → Output.")
+		})?;
+
+		open_with::open_png_with(command, png_bytes, saved_path)
+	}
+
+	/// Shows the share menu for the export, anchored to the toolbar when it is up and to the
+	/// middle of the frozen display otherwise. An export that was already saved is shared in
+	/// place; otherwise it is written to a temp file first. Returns the shared file.
+	#[cfg(target_os = "macos")]
+	fn share_exported_png(&self, png_bytes: &[u8], saved_path: Option<&Path>) -> Result<PathBuf> {
+		let toolbar = self.toolbar_window.as_ref().filter(|_| self.toolbar_window_visible);
+		let (window, anchor) = match toolbar {
+			Some(toolbar) => (&toolbar.window, ShareAnchor::Window),
+			None => {
+				let monitor_id = self.state.monitor.map(|monitor| monitor.id);
+				let overlay = self
+					.windows
+					.values()
+					.find(|overlay| Some(overlay.monitor.id) == monitor_id)
+					.ok_or_else(|| eyre::eyre!("No overlay window to show the share menu on"))?;
+
+				(&overlay.window, ShareAnchor::Center)
+			},
+		};
+		let path = match saved_path {
+			Some(path) => path.to_path_buf(),
+			None => output::save_export_to_temp_dir(png_bytes, &self.config)?,
+		};
+
+		share_sheet_macos::show_share_picker(window, anchor, &path)?;
+
+		Ok(path)
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	fn share_exported_png(&self, _png_bytes: &[u8], _saved_path: Option<&Path>) -> Result<PathBuf> {
+		Err(eyre::eyre!("Sharing is only available on macOS."))
+	}
+
+	/// Done steps in run order, without repeats; an empty pipeline copies.
+	fn done_pipeline(done_actions: &[DoneAction]) -> Vec<DoneAction> {
+		let mut steps = Vec::new();
+
+		for step in done_actions {
+			if !steps.contains(step) {
+				steps.push(*step);
+			}
+		}
+
+		if steps.is_empty() {
+			steps.push(DoneAction::Copy);
+		}
+
+		steps
+	}
+
+	/// Copies the export as configured: the image itself, or a file in the export format that
+	/// reuses `saved_path` when an earlier step already saved one.
+	fn copy_exported_png(
+		&mut self,
+		png_bytes: &[u8],
+		summary: Option<&str>,
+		saved_path: Option<&Path>,
+	) -> Result<()> {
+		match self.config.clipboard_format {
+			ClipboardFormat::Image => output::write_png_bytes_to_clipboard(png_bytes, summary)?,
+			ClipboardFormat::File => {
+				let path = match saved_path {
+					Some(path) => path.to_path_buf(),
+					None => output::save_export_to_temp_dir(png_bytes, &self.config)?,
+				};
+
+				output::write_files_to_clipboard(&[&path])?;
+			},
+		}
+
+		self.capture_latency.mark(LatencyStage::ClipboardDone, Instant::now());
+
+		self.spawn_copied_alt_text_hook(png_bytes);
+
+		Ok(())
+	}
+
+	fn copy_exported_files(&mut self, paths: &[&Path]) -> Result<()> {
+		output::write_files_to_clipboard(paths)?;
+
+		self.capture_latency.mark(LatencyStage::ClipboardDone, Instant::now());
+
+		Ok(())
+	}
+
+	/// Saves the export, copying `summary` on its own when the clipboard is free for it.
+	fn save_exported_png(&self, png_bytes: &[u8], summary: Option<&str>) -> Result<PathBuf> {
+		let path = output::save_png_bytes_to_configured_dir(png_bytes, &self.config)?;
+
+		if let Some(summary) = summary
+			&& let Err(err) = output::write_text_to_clipboard(summary)
+		{
+			tracing::warn!(error = %format!("{err:#}"), "Export summary copy failed.");
+		}
+		if self.config.geometry_sidecar {
+			self.write_export_geometry(&path, png_bytes);
+		}
+		if self.config.annotation_sidecar {
+			self.write_export_annotations(&path, png_bytes);
+		}
+		if let Some(command) = self.config.alt_text_command.clone() {
+			alt_text::spawn_alt_text_hook(
+				command,
+				png_bytes.to_vec(),
+				alt_text::AltTextDestination::Sidecar(path.clone()),
+			);
+		}
+
+		Ok(path)
+	}
+
+	fn export_resize(&self) -> ExportResize {
+		ExportResize {
+			scaling: self.config.export_scaling,
+			scale_factor: self.state.monitor.map_or(1.0, |monitor| monitor.scale_factor()),
+		}
+	}
+
+	fn write_export_geometry(&self, image_path: &Path, png_bytes: &[u8]) {
+		let Some(image_size) = png::png_dimensions(png_bytes) else {
+			return;
+		};
+		let padding_px = self.export_padding_px(image_size);
+		let capture_rect =
+			self.state.monitor.filter(|_| !self.scroll_capture.active).map(|monitor| {
+				self.state
+					.frozen_capture_rect
+					.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height))
+			});
+		let geometry =
+			sidecar::export_geometry(image_size, padding_px, self.state.monitor, capture_rect);
+
+		if let Err(err) = sidecar::write_geometry_sidecar(image_path, &geometry) {
+			tracing::warn!(error = %format!("{err:#}"), "Export geometry sidecar write failed.");
+		}
+	}
+
+	/// Writes the annotations drawn on the export; nothing is written when there are none.
+	fn write_export_annotations(&self, image_path: &Path, png_bytes: &[u8]) {
+		if self.scroll_capture.active || self.state.annotations.is_empty() {
+			return;
+		}
+
+		let Some(image_size) = png::png_dimensions(png_bytes) else {
+			return;
+		};
+		let Some(capture) =
+			self.cropped_frozen_capture_image().or_else(|| self.state.frozen_image.clone())
+		else {
+			return;
+		};
+		let annotations = sidecar::export_annotations(
+			image_size,
+			self.export_padding_px(image_size),
+			capture.dimensions(),
+			&self.state.annotations,
+		);
+
+		if let Err(err) = sidecar::write_annotation_sidecar(image_path, &annotations) {
+			tracing::warn!(error = %format!("{err:#}"), "Export annotation sidecar write failed.");
+		}
+	}
+
+	/// Backdrop padding around the capture in an export of `image_size`.
+	fn export_padding_px(&self, image_size: (u32, u32)) -> u32 {
+		let mut padding_px = if self.toolbar_state.backdrop_active {
+			self.config.export_backdrop.padding_px
+		} else {
+			0
+		};
+
+		// A resampled export shrinks the backdrop padding along with the capture.
+		if padding_px > 0
+			&& self.config.export_scaling.mode != ExportScaleMode::Native
+			&& let Some(image) = self.current_export_image()
+		{
+			let unscaled_width = image.width() + padding_px * 2;
+
+			padding_px =
+				(padding_px as f32 * image_size.0 as f32 / unscaled_width as f32).round() as u32;
+		}
+
+		padding_px
+	}
+
+	/// Starts the alt-text hook for a capture that was just copied. The caption joins the pasteboard
+	/// item once it is ready; platforms without a multi-representation clipboard skip the hook.
+	fn spawn_copied_alt_text_hook(&self, png_bytes: &[u8]) {
+		let Some(command) = self.config.alt_text_command.clone() else {
+			return;
+		};
+
+		#[cfg(target_os = "macos")]
+		alt_text::spawn_alt_text_hook(
+			command,
+			png_bytes.to_vec(),
+			alt_text::AltTextDestination::Pasteboard {
+				change_count: output::pasteboard_change_count(),
+			},
+		);
+		#[cfg(not(target_os = "macos"))]
+		{
+			let _ = (command, png_bytes);
+
+			tracing::info!(
+				op = "overlay.alt_text",
+				"Alt text for copies needs a multi-format clipboard; save to get a sidecar instead."
+			);
+		}
+	}
+
+	/// Handles a winit window event for one of the overlay-owned windows.
+	pub fn handle_window_event(
+		&mut self,
+		window_id: WindowId,
+		event: &WindowEvent,
+	) -> OverlayControl {
+		let started_at = Instant::now();
+		let kind = Self::window_event_kind(event);
+		let now = Instant::now();
+
+		self.event_loop.last_progress_window_id = Some(window_id);
+		self.event_loop.last_progress_monitor_id =
+			self.windows.get(&window_id).map(|window| window.monitor.id);
+
+		self.maybe_log_event_loop_stall(now);
+		self.mark_progress_with_detail(OverlayEventLoopPhase::WindowEvent, Some(kind));
+
+		if self
+			.scroll_preview_window
+			.as_ref()
+			.is_some_and(|preview_window| preview_window.window.id() == window_id)
+		{
+			return match event {
+				WindowEvent::RedrawRequested => self.handle_scroll_preview_redraw_requested(),
+				WindowEvent::MouseInput {
+					state: ElementState::Pressed,
+					button: MouseButton::Right,
+					..
+				} => self.exit(OverlayExit::Cancelled),
+				WindowEvent::KeyboardInput { event, .. } => self.handle_key_event(event),
+				WindowEvent::ModifiersChanged(modifiers) => {
+					self.handle_modifiers_changed(modifiers)
+				},
+				_ => self.handle_scroll_preview_window_event(event),
+			};
+		}
+
+		if let Some(control) = self.handle_hud_window_event(window_id, event) {
+			return control;
+		}
+
+		let toolbar_window_id = self
+			.toolbar_window
+			.as_ref()
+			.is_some_and(|toolbar_window| toolbar_window.window.id() == window_id);
+		let control = match event {
+			WindowEvent::CloseRequested => self.exit(OverlayExit::Cancelled),
+			WindowEvent::MouseInput {
+				state: ElementState::Pressed,
+				button: MouseButton::Right,
+				..
+			} => self.exit(OverlayExit::Cancelled),
+			WindowEvent::Resized(size) if toolbar_window_id => {
+				self.handle_toolbar_window_resized(*size)
+			},
+			WindowEvent::Resized(size) => self.handle_resized(window_id, *size),
+			WindowEvent::ScaleFactorChanged { .. } if toolbar_window_id => {
+				self.handle_toolbar_window_scale_factor_changed(window_id)
+			},
+			WindowEvent::ScaleFactorChanged { .. } => self.handle_scale_factor_changed(window_id),
+			WindowEvent::CursorEntered { .. } if toolbar_window_id => OverlayControl::Continue,
+			WindowEvent::CursorLeft { .. } if toolbar_window_id => {
+				self.toolbar_pointer_local = None;
+				self.toolbar_left_button_down = false;
+				self.toolbar_left_button_went_down = false;
+				self.toolbar_left_button_went_up = false;
+				self.toolbar_state.dragging = false;
+				self.toolbar_state.drag_offset = Vec2::ZERO;
+				self.toolbar_state.drag_anchor = None;
+
+				self.request_redraw_toolbar_window();
+
+				OverlayControl::Continue
+			},
+			WindowEvent::CursorMoved { position, .. } => {
+				if toolbar_window_id {
+					self.handle_toolbar_cursor_moved(window_id, *position)
+				} else {
+					self.handle_cursor_moved(window_id, *position)
+				}
+			},
+			WindowEvent::MouseWheel { .. } if toolbar_window_id => OverlayControl::Continue,
+			WindowEvent::MouseWheel { delta, .. } => self.handle_mouse_wheel(window_id, delta),
+			WindowEvent::MouseInput { state, button: MouseButton::Middle, .. }
+				if !toolbar_window_id =>
+			{
+				self.handle_frozen_view_pan_button(*state)
+			},
+			WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+				if toolbar_window_id {
+					self.handle_toolbar_mouse_input(*state)
+				} else {
+					self.handle_left_mouse_input(window_id, *state)
+				}
+			},
+			WindowEvent::RedrawRequested if toolbar_window_id => {
+				self.handle_toolbar_window_redraw_requested()
+			},
+			WindowEvent::ThemeChanged(_) => {
+				// Keep the HUD palette in sync with system changes when ThemeMode::System is active.
+				if let Some(monitor) = self.windows.get(&window_id).map(|w| w.monitor) {
+					self.request_redraw_for_monitor(monitor);
+				} else {
+					self.request_redraw_all();
+				}
+
+				OverlayControl::Continue
+			},
+			WindowEvent::KeyboardInput { event, .. } => self.handle_key_event(event),
+			WindowEvent::ModifiersChanged(modifiers) => self.handle_modifiers_changed(modifiers),
+			WindowEvent::RedrawRequested => self.handle_redraw_requested(window_id),
+			_ => OverlayControl::Continue,
+		};
 
 		self.slow_op_logger.warn_if_slow(
 			"overlay.window_event",
@@ -2886,6 +4351,26 @@ impl OverlaySession {
 		control
 	}
 
+	/// Routes the wheel: Alt steps the loupe zoom, the Stamp tool resizes stamps, a frozen
+	/// monitor zooms its view, and scroll capture reads the rest.
+	fn handle_mouse_wheel(
+		&mut self,
+		window_id: WindowId,
+		delta: &MouseScrollDelta,
+	) -> OverlayControl {
+		if self.state.alt_held && !self.scroll_capture.active {
+			return self.handle_loupe_zoom_wheel(delta);
+		}
+		if self.stamp_tool_active() {
+			return self.handle_stamp_wheel(delta);
+		}
+		if matches!(self.state.mode, OverlayMode::Frozen) && !self.scroll_capture.active {
+			return self.handle_frozen_view_wheel(delta);
+		}
+
+		self.handle_scroll_mouse_wheel(window_id, delta)
+	}
+
 	fn handle_toolbar_mouse_input(&mut self, state: ElementState) -> OverlayControl {
 		let toolbar_left_button_down = matches!(state, ElementState::Pressed);
 
@@ -2910,10 +4395,7 @@ impl OverlaySession {
 			self.toolbar_state.drag_anchor = None;
 		}
 
-		#[cfg(target_os = "macos")]
-		{
-			self.request_redraw_toolbar_window();
-		}
+		self.request_redraw_toolbar_window();
 
 		OverlayControl::Continue
 	}
@@ -2995,11 +4477,16 @@ impl OverlaySession {
 				global_cursor.x as f32 - self.toolbar_state.drag_offset.x,
 				global_cursor.y as f32 - self.toolbar_state.drag_offset.y,
 			);
-			let desired_local = Pos2::new(
-				desired_global.x - drag_monitor.origin.x as f32,
-				desired_global.y - drag_monitor.origin.y as f32,
-			);
-			let _ = self.update_toolbar_outer_position(drag_monitor, desired_local);
+
+			if self.config.reduce_motion {
+				let desired_local = Pos2::new(
+					desired_global.x - drag_monitor.origin.x as f32,
+					desired_global.y - drag_monitor.origin.y as f32,
+				);
+				let _ = self.update_toolbar_outer_position(drag_monitor, desired_local);
+			} else {
+				self.set_toolbar_drag_target(drag_monitor, desired_global);
+			}
 		}
 
 		self.request_redraw_toolbar_window();
@@ -3027,6 +4514,11 @@ impl OverlaySession {
 			return OverlayControl::Continue;
 		};
 
+		window_shape::apply_rounded_window_region(
+			toolbar_window.window.as_ref(),
+			f64::from(HUD_PILL_CORNER_RADIUS_POINTS),
+		);
+
 		match toolbar_window.renderer.resize(size) {
 			Ok(()) => OverlayControl::Continue,
 			Err(err) => self.exit(OverlayExit::Error(format!("{err:#}"))),
@@ -3066,6 +4558,7 @@ impl OverlaySession {
 			|| !self.toolbar_state.visible
 			|| self.state.frozen_image.is_none()
 			|| self.pending_freeze_capture == Some(monitor)
+			|| self.capture_flash.is_some()
 	}
 
 	fn set_toolbar_window_hidden(&mut self) {
@@ -3075,7 +4568,48 @@ impl OverlaySession {
 
 		self.toolbar_window_visible = false;
 		self.toolbar_window_warmup_redraws_remaining = 0;
-		self.last_present_at = Instant::now();
+		self.toolbar_drag_spring = None;
+		self.record_present();
+	}
+
+	fn set_toolbar_drag_target(&mut self, monitor: MonitorRect, target: Pos2) {
+		let now = Instant::now();
+		let (spring, spring_monitor) = self.toolbar_drag_spring.get_or_insert_with(|| {
+			let start = self
+				.toolbar_outer_pos
+				.map_or(target, |outer_pos| Pos2::new(outer_pos.x as f32, outer_pos.y as f32));
+
+			(DragSpring::new(start, now), monitor)
+		});
+
+		spring.set_target(target);
+
+		*spring_monitor = monitor;
+	}
+
+	/// Advances the toolbar drag spring and moves the window; returns whether it is still moving.
+	fn step_toolbar_drag_spring(&mut self) -> bool {
+		let Some((spring, monitor)) = self.toolbar_drag_spring.as_mut() else {
+			return false;
+		};
+		let monitor = *monitor;
+		let position = spring.step(Instant::now());
+		let settled = spring.is_settled();
+		let local =
+			Pos2::new(position.x - monitor.origin.x as f32, position.y - monitor.origin.y as f32);
+
+		let _ = self.update_toolbar_outer_position(monitor, local);
+
+		if settled && !self.toolbar_state.dragging {
+			self.toolbar_drag_spring = None;
+		}
+
+		!settled
+	}
+
+	/// Vertical offset of the toolbar window while it slides up into place.
+	fn toolbar_entry_slide_offset(&self) -> f32 {
+		(1.0 - self.toolbar_entry.progress(Instant::now())) * TOOLBAR_ENTRY_SLIDE_POINTS
 	}
 
 	fn draw_toolbar_window_frame(
@@ -3085,105 +4619,94 @@ impl OverlaySession {
 	) -> Result<()> {
 		self.sync_scroll_toolbar_state();
 
-		#[cfg(not(target_os = "macos"))]
-		{
-			let _ = (&monitor, &toolbar_input);
-			let Some(toolbar_window) = self.toolbar_window.as_ref() else {
-				return Ok(());
-			};
+		let should_focus_frozen_keyboard = !self.toolbar_window_visible
+			&& matches!(self.state.mode, OverlayMode::Frozen)
+			&& !self.scroll_capture.active;
+		let Some(gpu) = self.gpu.as_ref() else {
+			return Ok(());
+		};
+		let Some(toolbar_window) = self.toolbar_window.as_ref() else {
+			return Ok(());
+		};
 
-			toolbar_window.window.set_visible(false);
+		toolbar_window.window.set_visible(true);
 
-			self.last_present_at = Instant::now();
+		if !self.toolbar_window_visible {
+			self.toolbar_window_visible = true;
+			self.toolbar_window_warmup_redraws_remaining = TOOLBAR_WINDOW_WARMUP_REDRAWS;
 
-			Ok(())
+			if !self.config.reduce_motion {
+				self.toolbar_entry.start(Instant::now(), TOOLBAR_ENTRY_DURATION);
+			}
+		}
+		if should_focus_frozen_keyboard {
+			self.focus_frozen_keyboard_window();
 		}
-		#[cfg(target_os = "macos")]
-		{
-			let should_focus_frozen_keyboard = !self.toolbar_window_visible
-				&& matches!(self.state.mode, OverlayMode::Frozen)
-				&& !self.scroll_capture.active;
-			let Some(gpu) = self.gpu.as_ref() else {
-				return Ok(());
-			};
-			let Some(toolbar_window) = self.toolbar_window.as_ref() else {
-				return Ok(());
-			};
-
-			toolbar_window.window.set_visible(true);
 
-			if !self.toolbar_window_visible {
-				self.toolbar_window_visible = true;
-				self.toolbar_window_warmup_redraws_remaining = TOOLBAR_WINDOW_WARMUP_REDRAWS;
-			}
-			if should_focus_frozen_keyboard {
-				self.focus_frozen_keyboard_window();
-			}
+		let previous_floating_position = self.toolbar_state.floating_position;
 
-			let previous_floating_position = self.toolbar_state.floating_position;
+		self.toolbar_state.floating_position = Some(Pos2::ZERO);
 
-			self.toolbar_state.floating_position = Some(Pos2::ZERO);
+		let Some(toolbar_window) = self.toolbar_window.as_mut() else {
+			return Ok(());
+		};
 
-			let Some(toolbar_window) = self.toolbar_window.as_mut() else {
-				return Ok(());
-			};
-			let draw_result = toolbar_window.renderer.draw(
-				gpu,
-				&self.state,
-				monitor,
-				false,
-				Some(Pos2::ZERO),
-				false,
-				HudAnchor::Cursor,
-				self.config.toolbar_placement,
-				self.config.show_alt_hint_keycap,
-				false,
-				self.config.hud_opaque,
-				self.config.hud_opacity,
-				self.config.hud_fog_amount,
-				self.config.hud_milk_amount,
-				self.config.hud_tint_hue,
-				self.config.theme_mode,
-				self.config.selection_particles,
-				self.config.selection_flow_stroke_width_px,
-				false,
-				false,
-				self.frozen_capture_source == FrozenCaptureSource::FullscreenFallback,
-				Some(&mut self.toolbar_state),
-				toolbar_input,
-			);
+		toolbar_window.renderer.content_opacity = self.toolbar_entry.progress(Instant::now());
 
-			self.toolbar_state.floating_position = previous_floating_position;
+		let draw_result = toolbar_window.renderer.draw(
+			gpu,
+			&self.state,
+			monitor,
+			false,
+			Some(Pos2::ZERO),
+			false,
+			HudAnchor::Cursor,
+			self.config.toolbar_placement,
+			self.config.show_alt_hint_keycap,
+			false,
+			self.config.hud_opaque,
+			self.config.hud_opacity,
+			self.config.hud_fog_amount,
+			self.config.hud_milk_amount,
+			self.config.hud_tint_hue,
+			self.config.theme_mode,
+			self.config.selection_particles,
+			self.config.selection_flow_stroke(),
+			false,
+			false,
+			self.frozen_capture_source == FrozenCaptureSource::FullscreenFallback,
+			Some(&mut self.toolbar_state),
+			toolbar_input,
+		);
 
-			draw_result?;
+		self.toolbar_state.floating_position = previous_floating_position;
 
-			let desired_inner_size = toolbar_window.renderer.hud_pill.map(|hud_pill| {
-				(
-					hud_pill.rect.width().ceil().max(1.0) as u32,
-					hud_pill.rect.height().ceil().max(1.0) as u32,
-				)
-			});
-			let toolbar_window = Arc::clone(&toolbar_window.window);
+		draw_result?;
 
-			if let Some(desired) = desired_inner_size
-				&& self.toolbar_inner_size_points != Some(desired)
-			{
-				self.toolbar_inner_size_points = Some(desired);
+		let desired_inner_size = toolbar_window.renderer.hud_pill.map(|hud_pill| {
+			(
+				hud_pill.rect.width().ceil().max(1.0) as u32,
+				hud_pill.rect.height().ceil().max(1.0) as u32,
+			)
+		});
+		let toolbar_window = Arc::clone(&toolbar_window.window);
 
-				let _ = toolbar_window.request_inner_size(LogicalSize::new(
-					f64::from(desired.0),
-					f64::from(desired.1),
-				));
-			}
+		if let Some(desired) = desired_inner_size
+			&& self.toolbar_inner_size_points != Some(desired)
+		{
+			self.toolbar_inner_size_points = Some(desired);
 
-			Ok(())
+			let _ = toolbar_window
+				.request_inner_size(LogicalSize::new(f64::from(desired.0), f64::from(desired.1)));
 		}
+
+		Ok(())
 	}
 
 	fn handle_toolbar_window_redraw_requested(&mut self) -> OverlayControl {
-		self.event_loop_last_progress_window_id =
+		self.event_loop.last_progress_window_id =
 			self.toolbar_window.as_ref().map(|toolbar_window| toolbar_window.window.id());
-		self.event_loop_last_progress_monitor_id = self.state.monitor.map(|monitor| monitor.id);
+		self.event_loop.last_progress_monitor_id = self.state.monitor.map(|monitor| monitor.id);
 
 		self.maybe_log_event_loop_stall(Instant::now());
 		self.mark_progress(OverlayEventLoopPhase::ToolbarRedraw);
@@ -3206,9 +4729,14 @@ impl OverlaySession {
 
 		self.update_scroll_toolbar_default_position(monitor);
 
+		let drag_spring_moving = self.step_toolbar_drag_spring();
+
 		if let Some(toolbar_pos) = self.toolbar_state.floating_position {
 			let _ = self.update_toolbar_outer_position(monitor, toolbar_pos);
 		}
+		if drag_spring_moving || self.toolbar_entry.is_running(Instant::now()) {
+			self.toolbar_state.needs_redraw = true;
+		}
 		if let Some(action) = self.toolbar_state.pending_action.take() {
 			let control = self.handle_toolbar_action(action);
 
@@ -3217,7 +4745,7 @@ impl OverlaySession {
 			}
 		}
 
-		self.last_present_at = Instant::now();
+		self.record_present();
 
 		if self.toolbar_state.needs_redraw {
 			self.toolbar_state.needs_redraw = false;
@@ -3234,6 +4762,10 @@ impl OverlaySession {
 
 		self.keyboard_modifiers = modifiers.state();
 
+		if self.is_loupe_only_session() {
+			return OverlayControl::Continue;
+		}
+
 		let alt = self.resolve_alt_modifier_state(self.keyboard_modifiers.alt_key());
 
 		match self.config.alt_activation {
@@ -3345,6 +4877,9 @@ impl OverlaySession {
 	}
 
 	fn sync_alt_held_from_global_keys(&mut self) {
+		if self.is_loupe_only_session() {
+			return;
+		}
 		if matches!(self.config.alt_activation, AltActivationMode::Hold)
 			&& self.state.alt_held
 			&& !self.is_option_key_down()
@@ -3603,17 +5138,9 @@ impl OverlaySession {
 			return self.handle_cursor_moved_without_overlay_window(window_id, old_monitor);
 		};
 		let window_monitor = overlay_window.monitor;
-		let scale_factor = overlay_window.window.scale_factor();
-		let window_size = overlay_window.window.inner_size();
-		// Clamp to overlay window bounds and map to monitor coordinates.
-		let max_local_x = ((window_size.width as f64) / scale_factor).max(1.0) as i32 - 1;
-		let max_local_y = ((window_size.height as f64) / scale_factor).max(1.0) as i32 - 1;
-		let local_x = (position.x / scale_factor).round() as i32;
-		let local_y = (position.y / scale_factor).round() as i32;
-		let event_global = GlobalPoint::new(
-			window_monitor.origin.x + local_x.clamp(0, max_local_x),
-			window_monitor.origin.y + local_y.clamp(0, max_local_y),
-		);
+		// Convert with the monitor's own scale: while a window crosses a DPI boundary its reported
+		// scale factor can briefly belong to the other monitor.
+		let event_global = window_monitor.global_from_local_pixels(position.x, position.y);
 		let monitor = window_monitor;
 		let global = event_global;
 		let source = DeviceCursorPointSource::EventRecentFallback;
@@ -3635,29 +5162,7 @@ impl OverlaySession {
 		};
 
 		self.trace_cursor_moved_with_mapping(trace);
-		self.update_cursor_for_live_move(monitor, global);
-
-		let previous_drag_rect = self.state.drag_rect;
-
-		self.update_live_drag_rect(monitor, global);
-		self.request_cursor_move_samples(monitor, global);
-
-		if let Some(old_monitor) = old_monitor
-			&& old_monitor != monitor
-		{
-			self.request_redraw_for_monitor(old_monitor);
-		}
-
-		if Self::live_overlay_redraw_needed_for_cursor_update(
-			old_monitor,
-			monitor,
-			previous_drag_rect,
-			self.state.drag_rect,
-		) {
-			self.request_redraw_for_monitor(monitor);
-		}
-
-		OverlayControl::Continue
+		self.apply_live_cursor_move(old_monitor, monitor, global)
 	}
 
 	fn handle_cursor_moved_without_overlay_window(
@@ -3691,7 +5196,20 @@ impl OverlaySession {
 			);
 		}
 
+		self.apply_live_cursor_move(old_monitor, monitor, global)
+	}
+
+	/// Moves the live cursor to `global`, updating the drag, samples, and affected redraws.
+	fn apply_live_cursor_move(
+		&mut self,
+		old_monitor: Option<MonitorRect>,
+		monitor: MonitorRect,
+		global: GlobalPoint,
+	) -> OverlayControl {
 		self.update_cursor_for_live_move(monitor, global);
+		self.extend_highlight_to(global);
+		self.drag_callout_to(global);
+		self.drag_frozen_view_to(global);
 
 		let previous_drag_rect = self.state.drag_rect;
 
@@ -3771,8 +5289,13 @@ impl OverlaySession {
 	}
 
 	fn update_cursor_for_live_move(&mut self, monitor: MonitorRect, global: GlobalPoint) {
+		if self.state.hud_pinned && matches!(self.state.mode, OverlayMode::Live) {
+			return;
+		}
+
 		self.update_cursor_state(monitor, global);
 		self.update_hud_window_position(monitor, global);
+		self.sync_edge_dead_zone_passthrough(monitor, global);
 
 		if matches!(self.state.mode, OverlayMode::Live) && self.use_fake_hud_blur() {
 			if self.state.live_bg_monitor != Some(monitor) {
@@ -3823,6 +5346,33 @@ impl OverlaySession {
 		window_id: WindowId,
 		state: ElementState,
 	) -> OverlayControl {
+		if self.magnifier_active {
+			return OverlayControl::Continue;
+		}
+		if self.state.identify_displays {
+			return match state {
+				ElementState::Pressed => self.exit(OverlayExit::Cancelled),
+				ElementState::Released => OverlayControl::Continue,
+			};
+		}
+		if self.capture_countdown.is_some() || self.capture_flash.is_some() {
+			return OverlayControl::Continue;
+		}
+		if self.color_picker_active {
+			return match state {
+				ElementState::Pressed => self.pick_color(),
+				ElementState::Released => OverlayControl::Continue,
+			};
+		}
+		if self.state.hud_pinned {
+			// A click outside the pinned HUD only releases it; it never starts a capture.
+			if matches!(state, ElementState::Pressed) {
+				self.set_hud_pinned(false);
+			}
+
+			return OverlayControl::Continue;
+		}
+
 		let monitor = self
 			.windows
 			.get(&window_id)
@@ -3835,6 +5385,7 @@ impl OverlaySession {
 
 		if matches!(self.state.mode, OverlayMode::Frozen) {
 			self.reset_toolbar_pointer_state();
+			self.handle_frozen_region_input(state);
 			self.request_redraw_for_monitor(monitor);
 
 			return OverlayControl::Continue;
@@ -3865,6 +5416,21 @@ impl OverlaySession {
 					return OverlayControl::Continue;
 				};
 
+				if self.config.edge_dead_zones.contains(press_monitor, press_global) {
+					tracing::debug!(
+						monitor_id = press_monitor.id,
+						x = press_global.x,
+						y = press_global.y,
+						"Ignored a press inside an edge dead zone."
+					);
+
+					return OverlayControl::Continue;
+				}
+				// A click on a decoded QR code acts on the code instead of starting a capture.
+				if self.activate_code_at(press_monitor, press_global) {
+					return OverlayControl::Continue;
+				}
+
 				self.left_mouse_button_down = true;
 				self.left_mouse_button_down_monitor = Some(press_monitor);
 				self.left_mouse_button_down_global = Some(press_global);
@@ -3898,41 +5464,138 @@ impl OverlaySession {
 						(start_monitor, start_global)
 					};
 
-				self.left_mouse_button_down = false;
-				self.left_mouse_button_down_monitor = None;
-				self.left_mouse_button_down_global = None;
+				self.finish_live_press(start_monitor, release_monitor, release_global)
+			},
+		}
+	}
 
-				let drag_rect = if start_monitor == release_monitor {
-					self.state.drag_rect.take()
-				} else {
-					None
-				};
+	/// Shift-drags on the frozen monitor add extra selection regions; a Shift-click inside one
+	/// removes it again. A plain click on a decoded QR code copies it; on an auto-redaction
+	/// proposal it accepts or skips the proposal.
+	fn handle_frozen_region_input(&mut self, state: ElementState) {
+		let Some(frozen_monitor) = self.state.monitor else {
+			return;
+		};
 
-				if let Some(rect) = drag_rect
-					&& start_monitor == release_monitor
-					&& rect.monitor_id == release_monitor.id
-					&& rect.rect.width as f32 >= LIVE_DRAG_START_THRESHOLD_PX
-					&& rect.rect.height as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+		match state {
+			ElementState::Pressed => {
+				if self.left_mouse_button_down
+					|| self.scroll_capture.active
+					|| self.state.frozen_image.is_none()
 				{
-					self.begin_frozen_capture_with_rect(
-						release_monitor,
-						Some(rect.rect),
-						None,
-						Some(release_global),
-					);
+					return;
+				}
 
-					return OverlayControl::Continue;
+				let raw_cursor = self.current_device_cursor();
+				let Some((press_monitor, press_global, _)) =
+					self.resolve_live_cursor_point(raw_cursor)
+				else {
+					return;
+				};
+
+				if press_monitor != frozen_monitor {
+					return;
+				}
+				if !self.keyboard_modifiers.shift_key() {
+					if self.highlighter_tool_active() {
+						self.begin_highlight_at(press_global);
+					} else if self.callout_tool_active() {
+						self.press_callout_at(press_global);
+					} else if self.stamp_tool_active() {
+						self.place_stamp_at(press_global);
+					} else if !self.activate_code_at(press_monitor, press_global) {
+						self.toggle_redaction_at(press_global);
+					}
+
+					return;
+				}
+				// Region drags are tracked in window points, which only match the image unzoomed.
+				if self.state.frozen_view.is_zoomed() {
+					return;
 				}
 
+				self.left_mouse_button_down = true;
+				self.left_mouse_button_down_monitor = Some(press_monitor);
+				self.left_mouse_button_down_global = Some(press_global);
 				self.state.drag_rect = None;
+			},
+			ElementState::Released => {
+				if self.finish_highlight() || self.release_callout() || !self.left_mouse_button_down
+				{
+					return;
+				}
 
-				self.request_click_capture_hit_test(release_monitor, release_global);
+				let start_global = self.left_mouse_button_down_global.take();
 
-				OverlayControl::Continue
+				self.left_mouse_button_down = false;
+				self.left_mouse_button_down_monitor = None;
+
+				if let Some(drag) = self.state.drag_rect.take()
+					&& drag.monitor_id == frozen_monitor.id
+					&& drag.rect.width as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+					&& drag.rect.height as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+				{
+					self.state.extra_capture_rects.push(drag.rect);
+
+					tracing::debug!(
+						op = "overlay.extra_region_added",
+						rect = ?drag.rect,
+						regions = self.state.extra_capture_rects.len() + 1,
+						"Added a selection region."
+					);
+				} else if let Some(point) =
+					start_global.and_then(|global| frozen_monitor.local_u32(global))
+					&& let Some(index) =
+						self.state.extra_capture_rects.iter().rposition(|rect| rect.contains(point))
+				{
+					self.state.extra_capture_rects.remove(index);
+				}
 			},
 		}
 	}
 
+	/// Completes a live press: a large enough drag freezes that region, anything else freezes
+	/// the window under the release point.
+	fn finish_live_press(
+		&mut self,
+		start_monitor: MonitorRect,
+		release_monitor: MonitorRect,
+		release_global: GlobalPoint,
+	) -> OverlayControl {
+		self.left_mouse_button_down = false;
+		self.left_mouse_button_down_monitor = None;
+		self.left_mouse_button_down_global = None;
+
+		if self.config.capture_mode == CaptureMode::Display {
+			self.state.drag_rect = None;
+
+			self.begin_selected_capture(release_monitor, None, None, Some(release_global));
+
+			return OverlayControl::Continue;
+		}
+		// A drag that ends on another display still freezes on the monitor where it began.
+		if let Some(rect) = self.state.drag_rect.take()
+			&& rect.monitor_id == start_monitor.id
+			&& rect.rect.width as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+			&& rect.rect.height as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+		{
+			self.begin_selected_capture(
+				start_monitor,
+				Some(rect.rect),
+				None,
+				Some(start_monitor.clamp_global(release_global)),
+			);
+
+			return OverlayControl::Continue;
+		}
+
+		self.state.drag_rect = None;
+
+		self.request_click_capture_hit_test(release_monitor, release_global);
+
+		OverlayControl::Continue
+	}
+
 	fn handle_scroll_mouse_wheel(
 		&mut self,
 		window_id: WindowId,
@@ -4333,31 +5996,68 @@ impl OverlaySession {
 		if event.state != ElementState::Pressed {
 			return OverlayControl::Continue;
 		}
+		// Typing into a callout accepts key repeat, so it goes first.
+		if let Some(control) = self.handle_callout_key_event(event) {
+			return control;
+		}
 		if event.repeat {
 			return OverlayControl::Continue;
 		}
+		if event.logical_key == Key::Named(NamedKey::F12) {
+			return self.toggle_perf_hud();
+		}
 		if self.scroll_capture.active {
 			return self.handle_scroll_capture_key_event(event);
 		}
+		if self.magnifier_active {
+			return self.handle_magnifier_key_event(event);
+		}
+		if self.capture_countdown.is_some() {
+			return self.handle_capture_countdown_key_event(event);
+		}
+		if self.capture_flash.is_some() {
+			return OverlayControl::Continue;
+		}
+		if self.color_picker_active {
+			return self.handle_color_picker_key_event(event);
+		}
+		if !self.state.display_picker.is_empty() {
+			return self.handle_display_picker_key_event(event);
+		}
+		let mode_control = match self.state.mode {
+			OverlayMode::Live => self.handle_live_key(event),
+			OverlayMode::Frozen => self.handle_frozen_key(event),
+		};
+
+		if let Some(control) = mode_control {
+			return control;
+		}
 
 		match &event.logical_key {
 			Key::Named(NamedKey::Escape) => self.exit(OverlayExit::Cancelled),
-			Key::Named(NamedKey::Tab) => {
-				let Some(rgb) = self.state.rgb else {
-					return OverlayControl::Continue;
-				};
-				let hex = rgb.hex_upper();
+			Key::Named(NamedKey::Tab) => self.copy_sampled_color(),
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("l") => {
+				self.toggle_reference_sample()
+			},
+			Key::Character(key_text) if matches!(key_text.as_str(), "+" | "=") => {
+				self.step_loupe_zoom(1)
+			},
+			Key::Character(key_text) if matches!(key_text.as_str(), "-" | "_") => {
+				self.step_loupe_zoom(-1)
+			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("u") => {
+				self.state.hud_unit = self.state.hud_unit.next();
 
-				match output::write_text_to_clipboard(&hex) {
-					Ok(()) => {},
-					Err(err) => {
-						self.state.set_error(format!("{err:#}"));
-						self.request_redraw_all();
-					},
-				}
+				self.request_redraw_all();
 
 				OverlayControl::Continue
 			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("g") => {
+				self.toggle_composition_guides()
+			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("q") => {
+				self.scan_for_codes()
+			},
 			Key::Character(key_text) if key_text == "h" || key_text == "H" => {
 				self.toolbar_state.visible = !self.toolbar_state.visible;
 
@@ -4365,41 +6065,127 @@ impl OverlaySession {
 
 				OverlayControl::Continue
 			},
-			Key::Character(key_text)
-				if key_text.as_str().eq_ignore_ascii_case("s")
-					&& self.is_save_shortcut_pressed() =>
-			{
-				self.begin_png_action(PngAction::Save);
+			_ => OverlayControl::Continue,
+		}
+	}
 
-				OverlayControl::Continue
+	/// Handles the keys that only mean something before the capture freezes. Returns `None` for
+	/// keys shared with frozen mode.
+	fn handle_live_key(&mut self, event: &KeyEvent) -> Option<OverlayControl> {
+		if let Some(control) = self.handle_keyboard_nav_key_event(event) {
+			return Some(control);
+		}
+
+		match &event.logical_key {
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("p") => {
+				Some(self.toggle_hud_pin())
+			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("d") => {
+				Some(self.toggle_display_picker())
 			},
-			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("s") => {
-				let available = self.scroll_capture_is_available();
-				let selection_ready = self.scroll_capture_selection_is_ready();
+			_ => None,
+		}
+	}
 
-				tracing::info!(
-					op = "scroll_capture.frozen_s_pressed",
-					available,
-					scroll_capture_active = self.scroll_capture.active,
-					selection_ready,
-					frozen_capture_source = ?self.frozen_capture_source,
-					state_mode = ?self.state.mode,
-					"Received `s` while frozen."
-				);
+	/// Handles the editing, export and analysis keys of a frozen capture. Returns `None` for keys
+	/// shared with live mode.
+	fn handle_frozen_key(&mut self, event: &KeyEvent) -> Option<OverlayControl> {
+		if let Some(slot) = Self::keypad_quick_action_slot(event.physical_key) {
+			self.run_keypad_quick_action(slot);
 
-				if selection_ready {
-					self.start_scroll_capture();
-				}
+			return Some(OverlayControl::Continue);
+		}
+
+		if event.logical_key == Key::Named(NamedKey::Space) {
+			return Some(self.finish_frozen_done());
+		}
+		if let Some(control) = self.handle_frozen_view_key(event) {
+			return Some(control);
+		}
+
+		let Key::Character(key_text) = &event.logical_key else {
+			return None;
+		};
+		let key = key_text.as_str().to_ascii_lowercase();
+		let save_shortcut = self.is_save_shortcut_pressed();
+		let shift = self.keyboard_modifiers.shift_key();
+		let control = match key.as_str() {
+			"z" if save_shortcut && shift => self.redo_annotation(),
+			"z" if save_shortcut => self.undo_annotation(),
+			"s" if save_shortcut && shift => self.save_frozen_project(),
+			"s" if save_shortcut => {
+				self.begin_png_action(PngAction::Save);
 
 				OverlayControl::Continue
 			},
-			Key::Named(NamedKey::Space) => {
-				self.begin_png_action(PngAction::Copy);
+			"o" if save_shortcut => {
+				self.begin_png_action(PngAction::OpenWith);
 
 				OverlayControl::Continue
 			},
-			_ => OverlayControl::Continue,
-		}
+			"s" => self.start_scroll_capture_from_key(),
+			"g" => self.toggle_histogram_panel(),
+			"w" => self.watch_frozen_region(),
+			"v" => self.cycle_color_vision_filter(),
+			"i" => self.capture_frozen_region_on_interval(),
+			"t" => self.capture_frozen_region_in_both_themes(),
+			"r" => self.scan_for_redactions(),
+			_ => return None,
+		};
+
+		Some(control)
+	}
+
+	fn start_scroll_capture_from_key(&mut self) -> OverlayControl {
+		let available = self.scroll_capture_is_available();
+		let selection_ready = self.scroll_capture_selection_is_ready();
+
+		tracing::info!(
+			op = "scroll_capture.frozen_s_pressed",
+			available,
+			scroll_capture_active = self.scroll_capture.active,
+			selection_ready,
+			frozen_capture_source = ?self.frozen_capture_source,
+			state_mode = ?self.state.mode,
+			"Received `s` while frozen."
+		);
+
+		if selection_ready {
+			self.start_scroll_capture();
+		}
+
+		OverlayControl::Continue
+	}
+
+	fn keypad_quick_action_slot(physical_key: PhysicalKey) -> Option<usize> {
+		let PhysicalKey::Code(code) = physical_key else {
+			return None;
+		};
+
+		match code {
+			KeyCode::Numpad1 => Some(0),
+			KeyCode::Numpad2 => Some(1),
+			KeyCode::Numpad3 => Some(2),
+			KeyCode::Numpad4 => Some(3),
+			KeyCode::Numpad5 => Some(4),
+			KeyCode::Numpad6 => Some(5),
+			KeyCode::Numpad7 => Some(6),
+			KeyCode::Numpad8 => Some(7),
+			KeyCode::Numpad9 => Some(8),
+			_ => None,
+		}
+	}
+
+	fn run_keypad_quick_action(&mut self, slot: usize) {
+		let Some(action) = self.config.keypad_quick_actions.get(slot).copied() else {
+			return;
+		};
+
+		tracing::debug!(op = "overlay.keypad_quick_action", key = slot + 1, action = ?action);
+
+		if let Some(png_action) = action.png_action() {
+			self.begin_png_action(png_action);
+		}
 	}
 
 	fn is_save_shortcut_pressed(&self) -> bool {
@@ -4417,7 +6203,7 @@ impl OverlaySession {
 		match &event.logical_key {
 			Key::Named(NamedKey::Escape) => self.exit(OverlayExit::Cancelled),
 			Key::Named(NamedKey::Space) => {
-				self.begin_png_action(PngAction::Copy);
+				self.begin_png_action(PngAction::Done);
 
 				OverlayControl::Continue
 			},
@@ -4429,6 +6215,14 @@ impl OverlaySession {
 
 				OverlayControl::Continue
 			},
+			Key::Character(key_text)
+				if key_text.as_str().eq_ignore_ascii_case("o")
+					&& self.is_save_shortcut_pressed() =>
+			{
+				self.begin_png_action(PngAction::OpenWith);
+
+				OverlayControl::Continue
+			},
 			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("u") => {
 				self.undo_scroll_capture_append();
 
@@ -4452,7 +6246,28 @@ impl OverlaySession {
 				.map(|session| session.export_image().clone());
 		}
 
-		self.cropped_frozen_capture_image().or_else(|| self.state.frozen_image.clone())
+		let mut image =
+			self.cropped_frozen_capture_image().or_else(|| self.state.frozen_image.clone())?;
+
+		redact::apply_accepted_redactions(&mut image, &self.state.redactions);
+		highlighter::apply_highlights(&mut image, &self.state.annotations.highlights);
+		stamp::apply_stamps(&mut image, &self.state.annotations.stamps);
+		callout::apply_callouts(&mut image, &self.state.annotations.callouts);
+
+		Some(image)
+	}
+
+	/// Crops of the extra Shift-drag regions, in the order they were added.
+	fn extra_capture_region_images(&self) -> Vec<RgbaImage> {
+		let Some(monitor) = self.state.monitor.filter(|_| !self.scroll_capture.active) else {
+			return Vec::new();
+		};
+
+		self.state
+			.extra_capture_rects
+			.iter()
+			.filter_map(|rect| self.cropped_frozen_region_image(monitor, *rect))
+			.collect()
 	}
 
 	fn scroll_capture_selection_is_ready(&self) -> bool {
@@ -4724,13 +6539,47 @@ impl OverlaySession {
 		};
 
 		self.pending_png_action = Some(action);
+		self.last_export_region = self
+			.state
+			.monitor
+			.zip(self.state.frozen_capture_rect)
+			.filter(|_| !self.scroll_capture.active)
+			.map(|(monitor, rect)| MonitorRectPoints { monitor_id: monitor.id, rect });
 
 		match action {
 			PngAction::Copy => self.state.set_error("Copying..."),
 			PngAction::Save => self.state.set_error("Saving..."),
+			PngAction::Done
+				if Self::done_pipeline(&self.config.done_actions).contains(&DoneAction::Save) =>
+			{
+				self.state.set_error("Saving...");
+			},
+			PngAction::Done => self.state.set_error("Copying..."),
+			PngAction::OpenWith => self.state.set_error("Opening..."),
 		}
 
-		self.pending_encode_png = Some(export_image);
+		let export = PngExport {
+			image: export_image,
+			backdrop: self.export_backdrop_job(),
+			resize: self.export_resize(),
+			watermark: self.config.watermark.enabled.then(|| self.config.watermark.clone()),
+			retina_pair: self.config.retina_pair,
+		};
+		let regions = if matches!(action, PngAction::Save | PngAction::Done) {
+			self.extra_capture_region_images()
+		} else {
+			Vec::new()
+		};
+
+		if regions.is_empty() {
+			self.pending_encode_png = Some(Box::new(export));
+		} else {
+			self.pending_encode_png_batch = Some(Box::new(PngBatchExport {
+				primary: export,
+				regions,
+				collage: self.config.multi_region_collage,
+			}));
+		}
 
 		self.request_redraw_all();
 	}
@@ -4738,8 +6587,8 @@ impl OverlaySession {
 	fn handle_redraw_requested(&mut self, window_id: WindowId) -> OverlayControl {
 		let now = Instant::now();
 
-		self.event_loop_last_progress_window_id = Some(window_id);
-		self.event_loop_last_progress_monitor_id =
+		self.event_loop.last_progress_window_id = Some(window_id);
+		self.event_loop.last_progress_monitor_id =
 			self.windows.get(&window_id).map(|window| window.monitor.id);
 
 		self.maybe_log_event_loop_stall(now);
@@ -4750,6 +6599,15 @@ impl OverlaySession {
 		if !matches!(control, OverlayControl::Continue) {
 			return control;
 		}
+
+		let control = self.dispatch_window_redraw(window_id);
+
+		self.record_perf_hud_frame(now.elapsed());
+
+		control
+	}
+
+	fn dispatch_window_redraw(&mut self, window_id: WindowId) -> OverlayControl {
 		if self.hud_window.as_ref().is_some_and(|hud_window| hud_window.window.id() == window_id) {
 			return self.handle_hud_redraw_requested();
 		}
@@ -4801,12 +6659,16 @@ impl OverlaySession {
 	}
 
 	fn maybe_skip_hud_redraw(&mut self) -> Option<OverlayControl> {
-		if self.scroll_capture.active {
+		if self.scroll_capture.active
+			|| self.state.identify_displays
+			|| self.state.capture_countdown.is_some()
+			|| self.state.capture_flash.is_some()
+		{
 			if let Some(hud_window) = self.hud_window.as_ref() {
 				hud_window.window.set_visible(false);
 			}
 
-			self.last_present_at = Instant::now();
+			self.record_present();
 
 			return Some(OverlayControl::Continue);
 		}
@@ -4816,7 +6678,7 @@ impl OverlaySession {
 				hud_window.window.set_visible(false);
 			}
 
-			self.last_present_at = Instant::now();
+			self.record_present();
 
 			#[cfg(not(target_os = "macos"))]
 			return Some(OverlayControl::Continue);
@@ -4832,6 +6694,7 @@ impl OverlaySession {
 		let monitor =
 			self.monitor_for_mode().or_else(|| self.windows.values().next().map(|w| w.monitor));
 		let mut summary = HudRedrawSummary::default();
+		let hud_pointer = if self.state.hud_pinned { self.hud_pointer.take_frame() } else { None };
 
 		if let (Some(monitor), Some(hud_window)) = (monitor, self.hud_window.as_mut()) {
 			summary.redraw_window_id = Some(hud_window.window.id());
@@ -4841,6 +6704,8 @@ impl OverlaySession {
 
 			let draw_started_at = Instant::now();
 
+			hud_window.renderer.content_opacity = self.hud_entry.progress(draw_started_at);
+
 			hud_window.renderer.draw(
 				gpu,
 				&self.state,
@@ -4859,15 +6724,21 @@ impl OverlaySession {
 				self.config.hud_tint_hue,
 				self.config.theme_mode,
 				self.config.selection_particles,
-				self.config.selection_flow_stroke_width_px,
+				self.config.selection_flow_stroke(),
 				true,
 				false,
 				self.frozen_capture_source == FrozenCaptureSource::FullscreenFallback,
 				None,
-				None,
+				hud_pointer,
 			)?;
 
 			summary.renderer_draw_elapsed = Some(draw_started_at.elapsed());
+			summary.hud_action = hud_window.renderer.hud_action.take();
+			summary.loupe_cell_click = hud_window.renderer.loupe_cell_click.take();
+
+			if self.hud_entry.is_running(Instant::now()) {
+				hud_window.window.request_redraw();
+			}
 
 			if let Some(hud_pill) = hud_window.renderer.hud_pill {
 				let height_points = hud_pill.rect.height();
@@ -5010,9 +6881,9 @@ impl OverlaySession {
 		let redraw_started_at = Instant::now();
 		let live_loupe_in_hud = self.live_loupe_renders_in_hud_window();
 
-		self.event_loop_last_progress_window_id =
+		self.event_loop.last_progress_window_id =
 			self.hud_window.as_ref().map(|hud_window| hud_window.window.id());
-		self.event_loop_last_progress_monitor_id =
+		self.event_loop.last_progress_monitor_id =
 			self.monitor_for_mode().map(|monitor| monitor.id);
 
 		self.maybe_log_event_loop_stall(Instant::now());
@@ -5035,9 +6906,16 @@ impl OverlaySession {
 
 		self.log_hud_redraw_metrics(redraw_elapsed, &summary);
 
-		self.last_present_at = Instant::now();
+		self.record_present();
 
-		OverlayControl::Continue
+		if let Some(offset) = summary.loupe_cell_click {
+			self.toggle_loupe_lock(offset);
+		}
+
+		match summary.hud_action {
+			Some(action) => self.handle_hud_quick_action(action),
+			None => OverlayControl::Continue,
+		}
 	}
 
 	fn hide_loupe_window(&mut self) {
@@ -5049,7 +6927,7 @@ impl OverlaySession {
 
 		self.reset_loupe_window_warmup_redraws();
 
-		self.last_present_at = Instant::now();
+		self.record_present();
 	}
 
 	fn should_skip_loupe_redraw(&self) -> bool {
@@ -5153,9 +7031,9 @@ impl OverlaySession {
 	fn handle_loupe_redraw_requested(&mut self) -> OverlayControl {
 		let redraw_started_at = Instant::now();
 
-		self.event_loop_last_progress_window_id =
+		self.event_loop.last_progress_window_id =
 			self.loupe_window.as_ref().map(|loupe_window| loupe_window.window.id());
-		self.event_loop_last_progress_monitor_id =
+		self.event_loop.last_progress_monitor_id =
 			self.monitor_for_mode().map(|monitor| monitor.id);
 
 		self.maybe_log_event_loop_stall(Instant::now());
@@ -5171,7 +7049,7 @@ impl OverlaySession {
 		}
 
 		let Some((monitor, cursor)) = self.current_loupe_draw_target() else {
-			self.last_present_at = Instant::now();
+			self.record_present();
 
 			return OverlayControl::Continue;
 		};
@@ -5242,7 +7120,7 @@ impl OverlaySession {
 			},
 		);
 
-		self.last_present_at = Instant::now();
+		self.record_present();
 
 		OverlayControl::Continue
 	}
@@ -5467,6 +7345,13 @@ impl OverlaySession {
 		macos_make_window_key(target_window);
 	}
 
+	#[cfg(not(target_os = "macos"))]
+	fn focus_frozen_keyboard_window(&self) {
+		if let Some(toolbar_window) = self.toolbar_window.as_ref() {
+			toolbar_window.window.focus_window();
+		}
+	}
+
 	#[cfg(target_os = "macos")]
 	fn focus_live_capture_window(&self) {
 		macos_activate_app();
@@ -5550,23 +7435,15 @@ impl OverlaySession {
 
 		self.sync_scroll_toolbar_state();
 
-		self.event_loop_last_progress_window_id = Some(window_id);
-		self.event_loop_last_progress_monitor_id = Some(overlay_monitor.id);
+		self.event_loop.last_progress_window_id = Some(window_id);
+		self.event_loop.last_progress_monitor_id = Some(overlay_monitor.id);
 
 		self.maybe_log_event_loop_stall(Instant::now());
 		self.mark_progress(OverlayEventLoopPhase::OverlayRedraw);
 
-		// On macOS the frozen toolbar is now rendered in its own native HUD window; keep this
+		// The frozen toolbar renders in its own popup window on every platform; keep this
 		// fullscreen overlay free of toolbar UI so shader-backed blur and monitor-aligned offsets
 		// do not conflict with native-window positioning.
-		let draw_toolbar = !cfg!(target_os = "macos")
-			&& matches!(self.state.mode, OverlayMode::Frozen)
-			&& self.toolbar_state.visible
-			&& self.state.monitor == Some(overlay_monitor)
-			&& self.state.frozen_image.is_some()
-			&& self.pending_freeze_capture != Some(overlay_monitor);
-		let toolbar_input =
-			if draw_toolbar { self.toolbar_pointer_state(overlay_monitor, None) } else { None };
 		let Some(gpu) = self.gpu.as_ref() else {
 			return self.exit(OverlayExit::Error(String::from("Missing GPU context")));
 		};
@@ -5580,7 +7457,6 @@ impl OverlaySession {
 				frozen_generation = self.state.frozen_generation,
 				frozen_image_ready = self.state.frozen_image.is_some(),
 				pending_freeze_capture = self.pending_freeze_capture.map(|m| m.id),
-				draw_toolbar,
 				toolbar_visible = self.toolbar_state.visible,
 				toolbar_floating_position = ?self.toolbar_state.floating_position,
 				toolbar_stable_frames = self.toolbar_state.layout_stable_frames,
@@ -5589,7 +7465,6 @@ impl OverlaySession {
 			);
 		}
 
-		let toolbar_state = if draw_toolbar { Some(&mut self.toolbar_state) } else { None };
 		let capture_in_progress = self.pending_freeze_capture == Some(overlay_monitor)
 			&& matches!(self.state.mode, OverlayMode::Frozen)
 			&& self.state.monitor == Some(overlay_monitor)
@@ -5620,26 +7495,24 @@ impl OverlaySession {
 				self.config.hud_tint_hue,
 				self.config.theme_mode,
 				draw_selection_particles,
-				self.config.selection_flow_stroke_width_px,
+				self.config.selection_flow_stroke(),
 				!self.scroll_capture.active,
 				self.scroll_capture.active,
 				self.frozen_capture_source == FrozenCaptureSource::FullscreenFallback,
-				toolbar_state,
-				toolbar_input,
+				None,
+				None,
 			) {
 				return self.exit(OverlayExit::Error(format!("{err:#}")));
 			}
 		}
-		self.last_present_at = Instant::now();
+		self.record_present();
 
-		self.handle_capture_and_toolbar_redraw_post(overlay_monitor, draw_toolbar)
+		self.handle_capture_redraw_post(overlay_monitor);
+
+		OverlayControl::Continue
 	}
 
-	fn handle_capture_and_toolbar_redraw_post(
-		&mut self,
-		overlay_monitor: MonitorRect,
-		draw_toolbar: bool,
-	) -> OverlayControl {
+	fn handle_capture_redraw_post(&mut self, overlay_monitor: MonitorRect) {
 		if self.pending_freeze_capture == Some(overlay_monitor)
 			&& matches!(self.state.mode, OverlayMode::Frozen)
 			&& self.state.monitor == Some(overlay_monitor)
@@ -5649,9 +7522,12 @@ impl OverlaySession {
 			let pending_window_target = self
 				.pending_window_freeze_capture
 				.filter(|target| target.monitor == overlay_monitor);
-			let freeze_target = pending_window_target
-				.map_or(FreezeCaptureTarget::Monitor, |target| FreezeCaptureTarget::Window {
-					window_id: target.window_id,
+			let freeze_target =
+				pending_window_target.map_or(FreezeCaptureTarget::Monitor, |target| {
+					FreezeCaptureTarget::Window {
+						window_id: target.window_id,
+						keep_shadow: self.config.window_shadow_mode == WindowShadowMode::Keep,
+					}
 				});
 
 			#[cfg(target_os = "macos")]
@@ -5686,20 +7562,6 @@ impl OverlaySession {
 				}
 			}
 		}
-		if draw_toolbar && let Some(action) = self.toolbar_state.pending_action.take() {
-			let control = self.handle_toolbar_action(action);
-
-			if !matches!(control, OverlayControl::Continue) {
-				return control;
-			}
-		}
-		if draw_toolbar && self.toolbar_state.needs_redraw {
-			self.toolbar_state.needs_redraw = false;
-
-			self.request_redraw_for_monitor(overlay_monitor);
-		}
-
-		OverlayControl::Continue
 	}
 
 	fn handle_toolbar_action(&mut self, action: FrozenToolbarTool) -> OverlayControl {
@@ -5714,11 +7576,24 @@ impl OverlaySession {
 
 				OverlayControl::Continue
 			},
+			FrozenToolbarTool::Done => self.finish_frozen_done(),
 			FrozenToolbarTool::Scroll => {
 				self.start_scroll_capture();
 
 				OverlayControl::Continue
 			},
+			FrozenToolbarTool::LastRegion => {
+				self.reselect_last_capture_region();
+
+				OverlayControl::Continue
+			},
+			FrozenToolbarTool::Backdrop => {
+				self.toggle_export_backdrop();
+
+				OverlayControl::Continue
+			},
+			FrozenToolbarTool::Undo => self.undo_annotation(),
+			FrozenToolbarTool::Redo => self.redo_annotation(),
 			_ => OverlayControl::Continue,
 		}
 	}
@@ -5728,6 +7603,15 @@ impl OverlaySession {
 		self.set_scroll_overlay_mouse_passthrough(false);
 		self.windows.clear();
 
+		self.magnifier_active = false;
+		self.color_picker_active = false;
+		self.color_picker_held_key = None;
+		self.direct_capture = false;
+		self.interval_capture_picker = false;
+		self.display_identify_until = None;
+		self.capture_countdown = None;
+		self.capture_flash = None;
+		self.state.capture_flash = None;
 		self.hud_window = None;
 		self.hud_inner_size_points = None;
 		self.hud_outer_pos = None;
@@ -5746,28 +7630,31 @@ impl OverlaySession {
 		self.loupe_window_warmup_redraws_remaining = 0;
 		self.scroll_capture = ScrollCaptureState::default();
 		self.frozen_capture_source = FrozenCaptureSource::None;
+		self.pending_freeze_cursor = None;
 		self.cursor_monitor = None;
-		self.gpu = None;
+		if !self.config.resident {
+			self.gpu = None;
+			self.pending_gpu = None;
+		}
+
 		self.worker = None;
 		#[cfg(target_os = "macos")]
 		{
 			self.live_sample_worker = None;
 			self.live_sample_stream = None;
 		}
-		self.event_loop_phase = OverlayEventLoopPhase::Idle;
-		self.event_loop_progress_seq = 0;
-		self.event_loop_last_progress_at = Instant::now();
-		self.event_loop_last_progress_window_id = None;
-		self.event_loop_last_progress_monitor_id = None;
-		self.event_loop_last_progress_detail = None;
-		self.event_loop_last_stall_warn_at = None;
+		self.event_loop = EventLoopProgress::new(Instant::now());
 		self.toolbar_left_button_down = false;
 		self.toolbar_left_button_went_down = false;
 		self.toolbar_left_button_went_up = false;
 		self.toolbar_pointer_local = None;
+		self.hud_pointer = HudPointerState::default();
 		self.pending_encode_png = None;
+		self.pending_encode_png_batch = None;
 		self.pending_png_action = None;
 		self.keyboard_modifiers = ModifiersState::default();
+		self.keyboard_nav_active = false;
+		self.keyboard_window_cycle_index = None;
 
 		OverlayControl::Exit(exit)
 	}
@@ -5833,38 +7720,9 @@ impl OverlaySession {
 		}
 
 		for monitor in self.windows.values().map(|window| window.monitor) {
-			let sf = f64::from(monitor.scale_factor()).max(1.0);
-			let origin_px_x = (monitor.origin.x as f64 * sf).round() as i64;
-			let origin_px_y = (monitor.origin.y as f64 * sf).round() as i64;
-			let size_px_x = (monitor.width as f64 * sf).round() as i64;
-			let size_px_y = (monitor.height as f64 * sf).round() as i64;
-			let local_px_x = (raw.x as i64).saturating_sub(origin_px_x);
-			let local_px_y = (raw.y as i64).saturating_sub(origin_px_y);
-
-			if local_px_x < 0
-				|| local_px_y < 0
-				|| local_px_x >= size_px_x
-				|| local_px_y >= size_px_y
+			if let Some(candidate) =
+				monitor.global_from_scaled_pixels(i64::from(raw.x), i64::from(raw.y))
 			{
-				continue;
-			}
-
-			let local_points_x = (local_px_x as f64 / sf).round() as i64;
-			let local_points_y = (local_px_y as f64 / sf).round() as i64;
-			let local_points_x = match i32::try_from(local_points_x) {
-				Ok(value) => value,
-				Err(_) => continue,
-			};
-			let local_points_y = match i32::try_from(local_points_y) {
-				Ok(value) => value,
-				Err(_) => continue,
-			};
-			let candidate = GlobalPoint::new(
-				monitor.origin.x.saturating_add(local_points_x),
-				monitor.origin.y.saturating_add(local_points_y),
-			);
-
-			if monitor.contains(candidate) {
 				return Some((monitor, candidate, DeviceCursorPointSource::DevicePixelsFallback));
 			}
 		}
@@ -6070,9 +7928,10 @@ impl OverlaySession {
 			TOOLBAR_SCREEN_MARGIN_PX,
 			TOOLBAR_SCREEN_MARGIN_PX,
 		);
+		let slide_offset = self.toolbar_entry_slide_offset();
 		let desired = GlobalPoint::new(
 			monitor.origin.x.saturating_add(clamped_local_pos.x.round() as i32),
-			monitor.origin.y.saturating_add(clamped_local_pos.y.round() as i32),
+			monitor.origin.y.saturating_add((clamped_local_pos.y + slide_offset).round() as i32),
 		);
 
 		if self.toolbar_outer_pos == Some(desired) {
@@ -6120,6 +7979,11 @@ impl OverlaySession {
 
 				self.state.rgb =
 					image_helpers::frozen_rgb(&self.state.frozen_image, frozen_monitor, cursor);
+
+				if self.loupe_held_by_reference() {
+					return;
+				}
+
 				self.state.loupe = if self.state.alt_held {
 					image_helpers::frozen_loupe_patch(
 						&self.state.frozen_image,
@@ -6136,6 +8000,14 @@ impl OverlaySession {
 		}
 	}
 
+	/// A locked reference keeps the loupe patch it was showing while the cursor moves on. The
+	/// color picker's loupe keeps aiming at the color being compared instead.
+	fn loupe_held_by_reference(&self) -> bool {
+		self.state.reference_sample.is_some()
+			&& self.state.loupe.is_some()
+			&& !self.color_picker_active
+	}
+
 	#[cfg(not(target_os = "macos"))]
 	fn hide_capture_windows(&mut self) {
 		self.capture_windows_hidden = true;
@@ -6146,6 +8018,10 @@ impl OverlaySession {
 		if let Some(loupe_window) = &self.loupe_window {
 			loupe_window.window.set_visible(false);
 		}
+		if self.toolbar_window.is_some() {
+			// The next toolbar redraw shows it again once the frozen frame is ready.
+			self.set_toolbar_window_hidden();
+		}
 	}
 
 	fn restore_capture_windows_visibility(&mut self) {
@@ -6167,6 +8043,10 @@ impl OverlaySession {
 
 	#[cfg(not(target_os = "macos"))]
 	fn raise_hud_windows(&self) {
+		// Raising focuses, which would take the foreground from the app being sampled.
+		if self.color_picker_active {
+			return;
+		}
 		if let Some(hud_window) = self.hud_window.as_ref() {
 			hud_window.window.focus_window();
 		}
@@ -6223,6 +8103,9 @@ impl ScrollPreviewWindow {
 			.create_window(attrs)
 			.map_err(|err| format!("Unable to create scroll preview window: {err}"))?;
 		let window = Arc::new(window);
+
+		capture_exclusion::exclude_window_from_capture(window.as_ref());
+
 		let surface = gpu
 			.instance
 			.create_surface(Arc::clone(&window))
@@ -6549,6 +8432,8 @@ struct HudRedrawSummary {
 	resize_target: Option<(u32, u32)>,
 	redraw_window_id: Option<WindowId>,
 	redraw_monitor_id: Option<u32>,
+	hud_action: Option<HudQuickAction>,
+	loupe_cell_click: Option<(i32, i32)>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -6693,14 +8578,29 @@ struct GpuContext {
 	queue: Queue,
 }
 impl GpuContext {
-	fn new() -> Result<Self> {
+	/// Opens the GPU device, falling back to the platform's software rasterizer (WARP on Windows,
+	/// llvmpipe or lavapipe on Linux) when no hardware adapter is usable, e.g. in VMs.
+	fn new(power_preference: GpuPowerPreference) -> Result<Self> {
 		let instance = wgpu::Instance::new(&InstanceDescriptor::default());
-		let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-			power_preference: PowerPreference::LowPower,
-			compatible_surface: None,
-			force_fallback_adapter: false,
-		}))
-		.map_err(|err| eyre::eyre!("Failed to request GPU adapter: {err}"))?;
+		let power_preference = power_preference.to_wgpu();
+		let adapter = match Self::request_adapter(&instance, power_preference, false) {
+			Ok(adapter) => adapter,
+			Err(err) => {
+				tracing::warn!(
+					op = "overlay.gpu_init",
+					error = %err,
+					"No hardware GPU adapter; using the software renderer."
+				);
+
+				Self::request_adapter(&instance, power_preference, true).map_err(
+					|fallback_err| {
+						eyre::eyre!(
+							"Failed to request GPU adapter: {err}; software fallback: {fallback_err}"
+						)
+					},
+				)?
+			},
+		};
 		let adapter_limits = adapter.limits();
 		let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
 			label: Some("rsnap-overlay device"),
@@ -6716,28 +8616,157 @@ impl GpuContext {
 
 		Ok(Self { instance, adapter, device, queue })
 	}
+
+	fn request_adapter(
+		instance: &wgpu::Instance,
+		power_preference: PowerPreference,
+		force_fallback_adapter: bool,
+	) -> Result<Adapter, wgpu::RequestAdapterError> {
+		pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+			power_preference,
+			compatible_surface: None,
+			force_fallback_adapter,
+		}))
+	}
+}
+
+/// A [`GpuContext`] being created on its own thread, so a slow or hung driver never blocks the
+/// event loop.
+struct PendingGpuContext {
+	receiver: Receiver<Result<GpuContext>>,
+	started_at: Instant,
+}
+impl PendingGpuContext {
+	fn spawn(power_preference: GpuPowerPreference) -> Self {
+		let (sender, receiver) = mpsc::channel();
+		let worker_sender = sender.clone();
+		let spawned =
+			thread::Builder::new().name(String::from("rsnap-gpu-init")).spawn(move || {
+				let _ = worker_sender.send(GpuContext::new(power_preference));
+			});
+
+		if let Err(err) = spawned {
+			let _ = sender.send(Err(eyre::eyre!("Failed to start GPU initialization: {err}")));
+		}
+
+		Self { receiver, started_at: Instant::now() }
+	}
+}
+
+/// Where a [`WindowRenderer`] draws its frames.
+enum RenderTarget {
+	/// The window's swapchain surface, presented on screen.
+	Window { window: Arc<winit::window::Window>, surface: Surface<'static> },
+	/// A texture that is read back instead of presented, for golden-image tests.
+	#[cfg(test)]
+	Offscreen { texture: Texture, scale_factor: f64, frames_drawn: u32 },
+}
+
+/// One frame's color attachment, and the surface texture to present once it is drawn.
+struct RenderFrame {
+	view: TextureView,
+	surface_texture: Option<SurfaceTexture>,
+}
+
+/// The shader-backed overlay pipelines built from one [`ShaderSources`] generation.
+///
+/// A pipeline whose shader failed validation is still stored, but it is invalid: draws check the
+/// matching flag and skip that pass rather than submit it.
+struct ShaderPipelines {
+	mipgen: RenderPipeline,
+	mipgen_surface: RenderPipeline,
+	hud_blur: RenderPipeline,
+	frozen_dim: RenderPipeline,
+	color_vision: RenderPipeline,
+	mipgen_valid: bool,
+	hud_blur_valid: bool,
+	frozen_dim_valid: bool,
+	color_vision_valid: bool,
+}
+impl ShaderPipelines {
+	/// Shader files whose pipelines failed validation.
+	fn failed_shaders(&self) -> Vec<&'static str> {
+		[
+			("mipgen.wgsl", self.mipgen_valid),
+			("hud_blur.wgsl", self.hud_blur_valid),
+			("frozen_dim.wgsl", self.frozen_dim_valid),
+			("color_vision.wgsl", self.color_vision_valid),
+		]
+		.into_iter()
+		.filter_map(|(file, valid)| (!valid).then_some(file))
+		.collect()
+	}
+
+	/// Takes over every valid pipeline in `next`, keeping the current one where `next` failed.
+	/// Returns `true` when any pipeline changed.
+	fn adopt_valid(&mut self, next: Self) -> bool {
+		let mut adopted = false;
+
+		if next.mipgen_valid {
+			self.mipgen = next.mipgen;
+			self.mipgen_surface = next.mipgen_surface;
+			self.mipgen_valid = true;
+			adopted = true;
+		}
+		if next.hud_blur_valid {
+			self.hud_blur = next.hud_blur;
+			self.hud_blur_valid = true;
+			adopted = true;
+		}
+		if next.frozen_dim_valid {
+			self.frozen_dim = next.frozen_dim;
+			self.frozen_dim_valid = true;
+			adopted = true;
+		}
+		if next.color_vision_valid {
+			self.color_vision = next.color_vision;
+			self.color_vision_valid = true;
+			adopted = true;
+		}
+
+		adopted
+	}
 }
 
 struct WindowRenderer {
-	window: Arc<winit::window::Window>,
-	surface: Surface<'static>,
+	target: RenderTarget,
+	/// Size and format of the render target, whether it is a surface or offscreen.
 	surface_config: wgpu::SurfaceConfiguration,
 	needs_reconfigure: bool,
 	egui_ctx: egui::Context,
 	egui_renderer: Renderer,
 	bg_sampler: Sampler,
-	mipgen_pipeline: RenderPipeline,
-	mipgen_surface_pipeline: RenderPipeline,
+	shader_pipelines: ShaderPipelines,
 	mipgen_bind_group_layout: BindGroupLayout,
-	hud_blur_pipeline: RenderPipeline,
 	hud_blur_bind_group_layout: BindGroupLayout,
 	hud_blur_uniform: Buffer,
+	frozen_dim_bind_group_layout: BindGroupLayout,
+	frozen_dim_uniform: Buffer,
+	frozen_dim_bind_group: BindGroup,
+	/// The frozen dim layer is drawn on the next frame.
+	frozen_dim_active: bool,
+	color_vision_bind_group_layout: BindGroupLayout,
+	color_vision_uniform: Buffer,
+	color_vision_bind_group: BindGroup,
+	/// The frozen background is drawn through the color vision simulation on the next frame.
+	color_vision_active: bool,
+	/// Generation of the [`ShaderSources`] the pipelines were built from.
+	shader_generation: u64,
 	hud_bg: Option<HudBg>,
 	hud_bg_generation: u64,
 	hud_pill: Option<HudPillGeometry>,
+	hud_action: Option<HudQuickAction>,
+	/// A loupe cell clicked on the pinned HUD, in pixels from the patch center.
+	loupe_cell_click: Option<(i32, i32)>,
 	loupe_tile: Option<Rect>,
 	live_loupe_texture: Option<LiveLoupeTexture>,
+	/// Uploaded toolbar export preview, keyed by the worker request that rendered it.
+	export_preview_texture: Option<(u64, TextureHandle)>,
+	/// Uploaded frozen image while the frozen view is zoomed, keyed by its freeze generation.
+	frozen_view_texture: Option<(u64, TextureHandle)>,
 	hud_theme: Option<HudTheme>,
+	/// 0..=1 multiplier over the egui output, used by the window entry animations.
+	content_opacity: f32,
 	egui_start_time: Instant,
 	egui_last_frame_time: Instant,
 	selection_flow_cache: SelectionFlowGeometryCache,
@@ -6750,42 +8779,47 @@ impl WindowRenderer {
 		(32_u32.saturating_sub(max_dim.leading_zeros())).max(1)
 	}
 
+	fn create_mipgen_bind_group_layout(gpu: &GpuContext) -> BindGroupLayout {
+		gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("rsnap-mipgen bgl"),
+			entries: &[
+				wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						view_dimension: TextureViewDimension::D2,
+						sample_type: TextureSampleType::Float { filterable: true },
+					},
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 1,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Sampler(SamplerBindingType::Filtering),
+					count: None,
+				},
+			],
+		})
+	}
+
 	fn create_mipgen_pipeline(
 		gpu: &GpuContext,
 		format: wgpu::TextureFormat,
-	) -> (RenderPipeline, BindGroupLayout) {
+		bind_group_layout: &BindGroupLayout,
+		source: &str,
+	) -> RenderPipeline {
 		let shader = gpu.device.create_shader_module(wgpu::ShaderModuleDescriptor {
 			label: Some("rsnap-mipgen shader"),
-			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("mipgen.wgsl"))),
+			source: ShaderSource::Wgsl(Cow::Borrowed(source)),
 		});
-		let bind_group_layout =
-			gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-				label: Some("rsnap-mipgen bgl"),
-				entries: &[
-					wgpu::BindGroupLayoutEntry {
-						binding: 0,
-						visibility: ShaderStages::FRAGMENT,
-						ty: BindingType::Texture {
-							multisampled: false,
-							view_dimension: TextureViewDimension::D2,
-							sample_type: TextureSampleType::Float { filterable: true },
-						},
-						count: None,
-					},
-					wgpu::BindGroupLayoutEntry {
-						binding: 1,
-						visibility: ShaderStages::FRAGMENT,
-						ty: BindingType::Sampler(SamplerBindingType::Filtering),
-						count: None,
-					},
-				],
-			});
 		let pipeline_layout = gpu.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
 			label: Some("rsnap-mipgen pipeline layout"),
-			bind_group_layouts: &[&bind_group_layout],
+			bind_group_layouts: &[bind_group_layout],
 			push_constant_ranges: &[],
 		});
-		let pipeline = gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+
+		gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
 			label: Some("rsnap-mipgen pipeline"),
 			layout: Some(&pipeline_layout),
 			vertex: wgpu::VertexState {
@@ -6817,19 +8851,18 @@ impl WindowRenderer {
 			}),
 			multiview: None,
 			cache: None,
-		});
-
-		(pipeline, bind_group_layout)
+		})
 	}
 
 	fn create_mipgen_surface_pipeline(
 		gpu: &GpuContext,
 		format: wgpu::TextureFormat,
 		bind_group_layout: &BindGroupLayout,
+		source: &str,
 	) -> RenderPipeline {
 		let shader = gpu.device.create_shader_module(wgpu::ShaderModuleDescriptor {
 			label: Some("rsnap-mipgen fullscreen shader"),
-			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("mipgen.wgsl"))),
+			source: ShaderSource::Wgsl(Cow::Borrowed(source)),
 		});
 		let pipeline_layout = gpu.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
 			label: Some("rsnap-mipgen fullscreen pipeline layout"),
@@ -6873,7 +8906,7 @@ impl WindowRenderer {
 	}
 
 	fn generate_mipmaps(&self, gpu: &GpuContext, texture: &Texture, mip_level_count: u32) {
-		if mip_level_count <= 1 {
+		if mip_level_count <= 1 || !self.shader_pipelines.mipgen_valid {
 			return;
 		}
 
@@ -6935,7 +8968,7 @@ impl WindowRenderer {
 			};
 			let mut rpass = encoder.begin_render_pass(&rpass_desc).forget_lifetime();
 
-			rpass.set_pipeline(&self.mipgen_pipeline);
+			rpass.set_pipeline(&self.shader_pipelines.mipgen);
 			rpass.set_bind_group(0, &bind_group, &[]);
 			rpass.draw(0..3, 0..1);
 		}
@@ -7008,54 +9041,59 @@ impl WindowRenderer {
 		})
 	}
 
+	fn create_hud_blur_bind_group_layout(gpu: &GpuContext) -> BindGroupLayout {
+		gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("rsnap-hud-blur bgl"),
+			entries: &[
+				wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Texture {
+						multisampled: false,
+						view_dimension: TextureViewDimension::D2,
+						sample_type: TextureSampleType::Float { filterable: true },
+					},
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 1,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Sampler(SamplerBindingType::Filtering),
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 2,
+					visibility: ShaderStages::FRAGMENT,
+					ty: BindingType::Buffer {
+						ty: BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: BufferSize::new(
+							mem::size_of::<HudBlurUniformRaw>() as u64
+						),
+					},
+					count: None,
+				},
+			],
+		})
+	}
+
 	fn create_hud_blur_pipeline(
 		gpu: &GpuContext,
 		surface_format: wgpu::TextureFormat,
-	) -> (RenderPipeline, BindGroupLayout) {
+		bind_group_layout: &BindGroupLayout,
+		source: &str,
+	) -> RenderPipeline {
 		let shader = gpu.device.create_shader_module(wgpu::ShaderModuleDescriptor {
 			label: Some("rsnap-hud-blur shader"),
-			source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("hud_blur.wgsl"))),
+			source: ShaderSource::Wgsl(Cow::Borrowed(source)),
 		});
-		let bind_group_layout =
-			gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-				label: Some("rsnap-hud-blur bgl"),
-				entries: &[
-					wgpu::BindGroupLayoutEntry {
-						binding: 0,
-						visibility: ShaderStages::FRAGMENT,
-						ty: BindingType::Texture {
-							multisampled: false,
-							view_dimension: TextureViewDimension::D2,
-							sample_type: TextureSampleType::Float { filterable: true },
-						},
-						count: None,
-					},
-					wgpu::BindGroupLayoutEntry {
-						binding: 1,
-						visibility: ShaderStages::FRAGMENT,
-						ty: BindingType::Sampler(SamplerBindingType::Filtering),
-						count: None,
-					},
-					wgpu::BindGroupLayoutEntry {
-						binding: 2,
-						visibility: ShaderStages::FRAGMENT,
-						ty: BindingType::Buffer {
-							ty: BufferBindingType::Uniform,
-							has_dynamic_offset: false,
-							min_binding_size: BufferSize::new(
-								mem::size_of::<HudBlurUniformRaw>() as u64
-							),
-						},
-						count: None,
-					},
-				],
-			});
 		let pipeline_layout = gpu.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
 			label: Some("rsnap-hud-blur pipeline layout"),
-			bind_group_layouts: &[&bind_group_layout],
+			bind_group_layouts: &[bind_group_layout],
 			push_constant_ranges: &[],
 		});
-		let pipeline = gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+
+		gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
 			label: Some("rsnap-hud-blur pipeline"),
 			layout: Some(&pipeline_layout),
 			vertex: wgpu::VertexState {
@@ -7087,58 +9125,344 @@ impl WindowRenderer {
 			}),
 			multiview: None,
 			cache: None,
-		});
-
-		(pipeline, bind_group_layout)
+		})
 	}
 
-	fn apply_pending_reconfigure(&mut self, gpu: &GpuContext) {
-		if self.needs_reconfigure {
-			self.reconfigure(gpu);
-
-			self.needs_reconfigure = false;
-		}
+	fn create_frozen_dim_bind_group_layout(gpu: &GpuContext) -> BindGroupLayout {
+		gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("rsnap-frozen-dim bgl"),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: ShaderStages::FRAGMENT,
+				ty: BindingType::Buffer {
+					ty: BufferBindingType::Uniform,
+					has_dynamic_offset: false,
+					min_binding_size: BufferSize::new(mem::size_of::<FrozenDimUniformRaw>() as u64),
+				},
+				count: None,
+			}],
+		})
 	}
 
-	fn prepare_egui_input(
-		&mut self,
+	fn create_frozen_dim_pipeline(
 		gpu: &GpuContext,
-		pointer_state: Option<FrozenToolbarPointerState>,
-		pixels_per_point_override: Option<f32>,
-	) -> (PhysicalSize<u32>, f32, egui::RawInput) {
-		// egui animations depend on a monotonic time base. Without this, animation state can appear
-		// to "snap" only after an input event (e.g. CursorMoved) triggers a new frame.
-		let now = Instant::now();
-		let elapsed = now.duration_since(self.egui_start_time).as_secs_f64().max(0.0);
-		let predicted_dt =
-			now.duration_since(self.egui_last_frame_time).as_secs_f32().clamp(0.0, 0.5);
-
-		self.egui_last_frame_time = now;
-
-		// Keep the wgpu surface configuration in sync with the OS-reported window size.
-		//
-		// On macOS we can observe transient mismatches where `surface_config` is smaller than the
-		// actual window size (e.g. right after entering Frozen mode), which causes egui to build
-		// a smaller `screen_rect` and results in UI elements appearing clipped/offset until a
-		// later redraw or input event triggers a resize/reconfigure.
-		let actual_size = self.window.inner_size();
-		let desired_w = actual_size.width.max(1);
-		let desired_h = actual_size.height.max(1);
-
-		if self.surface_config.width != desired_w || self.surface_config.height != desired_h {
-			tracing::debug!(
-				window_id = ?self.window.id(),
-				actual_size_px = ?actual_size,
-				old_surface_px = ?(self.surface_config.width, self.surface_config.height),
-				new_surface_px = ?(desired_w, desired_h),
-				window_scale_factor = self.window.scale_factor(),
-				pixels_per_point_override,
-				"Reconfiguring wgpu surface to match window."
-			);
+		surface_format: wgpu::TextureFormat,
+		bind_group_layout: &BindGroupLayout,
+		source: &str,
+	) -> RenderPipeline {
+		let shader = gpu.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+			label: Some("rsnap-frozen-dim shader"),
+			source: ShaderSource::Wgsl(Cow::Borrowed(source)),
+		});
+		let pipeline_layout = gpu.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label: Some("rsnap-frozen-dim pipeline layout"),
+			bind_group_layouts: &[bind_group_layout],
+			push_constant_ranges: &[],
+		});
 
-			self.surface_config.width = desired_w;
-			self.surface_config.height = desired_h;
-			self.needs_reconfigure = false;
+		gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("rsnap-frozen-dim pipeline"),
+			layout: Some(&pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &shader,
+				entry_point: Some("vs_main"),
+				compilation_options: PipelineCompilationOptions::default(),
+				buffers: &[],
+			},
+			primitive: wgpu::PrimitiveState {
+				topology: PrimitiveTopology::TriangleList,
+				strip_index_format: None,
+				front_face: FrontFace::Ccw,
+				cull_mode: None,
+				polygon_mode: PolygonMode::Fill,
+				unclipped_depth: false,
+				conservative: false,
+			},
+			depth_stencil: None,
+			multisample: MultisampleState::default(),
+			fragment: Some(wgpu::FragmentState {
+				module: &shader,
+				entry_point: Some("fs_main"),
+				compilation_options: PipelineCompilationOptions::default(),
+				targets: &[Some(wgpu::ColorTargetState {
+					format: surface_format,
+					blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+					write_mask: ColorWrites::ALL,
+				})],
+			}),
+			multiview: None,
+			cache: None,
+		})
+	}
+
+	fn create_color_vision_bind_group_layout(gpu: &GpuContext) -> BindGroupLayout {
+		gpu.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("rsnap-color-vision bgl"),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: ShaderStages::FRAGMENT,
+				ty: BindingType::Buffer {
+					ty: BufferBindingType::Uniform,
+					has_dynamic_offset: false,
+					min_binding_size: BufferSize::new(
+						mem::size_of::<ColorVisionUniformRaw>() as u64
+					),
+				},
+				count: None,
+			}],
+		})
+	}
+
+	/// Draws the frozen background through a color vision simulation. Group 0 is the same
+	/// texture and sampler bind group the mipgen surface pass uses.
+	fn create_color_vision_pipeline(
+		gpu: &GpuContext,
+		surface_format: wgpu::TextureFormat,
+		mipgen_bind_group_layout: &BindGroupLayout,
+		color_vision_bind_group_layout: &BindGroupLayout,
+		source: &str,
+	) -> RenderPipeline {
+		let shader = gpu.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+			label: Some("rsnap-color-vision shader"),
+			source: ShaderSource::Wgsl(Cow::Borrowed(source)),
+		});
+		let pipeline_layout = gpu.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label: Some("rsnap-color-vision pipeline layout"),
+			bind_group_layouts: &[mipgen_bind_group_layout, color_vision_bind_group_layout],
+			push_constant_ranges: &[],
+		});
+
+		gpu.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("rsnap-color-vision pipeline"),
+			layout: Some(&pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &shader,
+				entry_point: Some("vs_main"),
+				compilation_options: PipelineCompilationOptions::default(),
+				buffers: &[],
+			},
+			primitive: wgpu::PrimitiveState {
+				topology: PrimitiveTopology::TriangleList,
+				strip_index_format: None,
+				front_face: FrontFace::Ccw,
+				cull_mode: None,
+				polygon_mode: PolygonMode::Fill,
+				unclipped_depth: false,
+				conservative: false,
+			},
+			depth_stencil: None,
+			multisample: MultisampleState::default(),
+			fragment: Some(wgpu::FragmentState {
+				module: &shader,
+				entry_point: Some("fs_main"),
+				compilation_options: PipelineCompilationOptions::default(),
+				targets: &[Some(wgpu::ColorTargetState {
+					format: surface_format,
+					blend: None,
+					write_mask: ColorWrites::ALL,
+				})],
+			}),
+			multiview: None,
+			cache: None,
+		})
+	}
+
+	/// Builds every shader-backed pipeline from `sources`, validating each shader on its own so
+	/// one bad file only disables its own effect.
+	fn create_shader_pipelines(
+		gpu: &GpuContext,
+		surface_format: wgpu::TextureFormat,
+		mipgen_bind_group_layout: &BindGroupLayout,
+		hud_blur_bind_group_layout: &BindGroupLayout,
+		frozen_dim_bind_group_layout: &BindGroupLayout,
+		color_vision_bind_group_layout: &BindGroupLayout,
+		sources: &ShaderSources,
+	) -> ShaderPipelines {
+		let generation = sources.generation;
+		let ((mipgen, mipgen_surface), mipgen_valid) =
+			Self::validate_shader(gpu, "mipgen.wgsl", generation, || {
+				(
+					Self::create_mipgen_pipeline(
+						gpu,
+						wgpu::TextureFormat::Rgba8UnormSrgb,
+						mipgen_bind_group_layout,
+						&sources.mipgen,
+					),
+					Self::create_mipgen_surface_pipeline(
+						gpu,
+						surface_format,
+						mipgen_bind_group_layout,
+						&sources.mipgen,
+					),
+				)
+			});
+		let (hud_blur, hud_blur_valid) =
+			Self::validate_shader(gpu, "hud_blur.wgsl", generation, || {
+				Self::create_hud_blur_pipeline(
+					gpu,
+					surface_format,
+					hud_blur_bind_group_layout,
+					&sources.hud_blur,
+				)
+			});
+		let (frozen_dim, frozen_dim_valid) =
+			Self::validate_shader(gpu, "frozen_dim.wgsl", generation, || {
+				Self::create_frozen_dim_pipeline(
+					gpu,
+					surface_format,
+					frozen_dim_bind_group_layout,
+					&sources.frozen_dim,
+				)
+			});
+		let (color_vision, color_vision_valid) =
+			Self::validate_shader(gpu, "color_vision.wgsl", generation, || {
+				Self::create_color_vision_pipeline(
+					gpu,
+					surface_format,
+					mipgen_bind_group_layout,
+					color_vision_bind_group_layout,
+					&sources.color_vision,
+				)
+			});
+
+		ShaderPipelines {
+			mipgen,
+			mipgen_surface,
+			hud_blur,
+			frozen_dim,
+			color_vision,
+			mipgen_valid,
+			hud_blur_valid,
+			frozen_dim_valid,
+			color_vision_valid,
+		}
+	}
+
+	/// Runs `create` inside a validation error scope, returning its result and whether `file`
+	/// validated. A failure is logged instead of reaching the device's panicking error handler.
+	fn validate_shader<T>(
+		gpu: &GpuContext,
+		file: &'static str,
+		generation: u64,
+		create: impl FnOnce() -> T,
+	) -> (T, bool) {
+		gpu.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+		let created = create();
+		let error = pollster::block_on(gpu.device.pop_error_scope());
+
+		if let Some(err) = error.as_ref() {
+			tracing::error!(
+				op = "overlay.shader_validation",
+				file,
+				generation,
+				error = %err,
+				"Overlay shader failed validation."
+			);
+		}
+
+		(created, error.is_none())
+	}
+
+	/// Rebuilds the pipelines from `sources` when they changed since the last sync, keeping the
+	/// current pipeline for any shader that fails validation. Returns `true` when pipelines
+	/// changed.
+	fn sync_shaders(&mut self, gpu: &GpuContext, sources: &ShaderSources) -> bool {
+		if self.shader_generation == sources.generation {
+			return false;
+		}
+
+		self.shader_generation = sources.generation;
+
+		let next = Self::create_shader_pipelines(
+			gpu,
+			self.surface_config.format,
+			&self.mipgen_bind_group_layout,
+			&self.hud_blur_bind_group_layout,
+			&self.frozen_dim_bind_group_layout,
+			&self.color_vision_bind_group_layout,
+			sources,
+		);
+		let failed = next.failed_shaders();
+
+		if !failed.is_empty() {
+			tracing::warn!(
+				op = "overlay.shader_hot_reload",
+				generation = sources.generation,
+				failed = ?failed,
+				"Shader reload failed; keeping the previous pipelines for those shaders."
+			);
+		}
+
+		self.shader_pipelines.adopt_valid(next)
+	}
+
+	fn apply_pending_reconfigure(&mut self, gpu: &GpuContext) {
+		if self.needs_reconfigure {
+			self.reconfigure(gpu);
+
+			self.needs_reconfigure = false;
+		}
+	}
+
+	/// Offscreen frames are compared pixel for pixel, so their clock advances a fixed step per
+	/// frame: egui fades settle by the second frame and time-driven effects repeat exactly.
+	#[cfg(test)]
+	fn advance_offscreen_egui_clock(&mut self) -> Option<(f64, f32)> {
+		let RenderTarget::Offscreen { frames_drawn, .. } = &mut self.target else {
+			return None;
+		};
+
+		*frames_drawn += 1;
+
+		Some((
+			OFFSCREEN_EGUI_FRAME_STEP.as_secs_f64() * f64::from(*frames_drawn),
+			OFFSCREEN_EGUI_FRAME_STEP.as_secs_f32(),
+		))
+	}
+
+	fn prepare_egui_input(
+		&mut self,
+		gpu: &GpuContext,
+		pointer_state: Option<FrozenToolbarPointerState>,
+		pixels_per_point_override: Option<f32>,
+	) -> (PhysicalSize<u32>, f32, egui::RawInput) {
+		// egui animations depend on a monotonic time base. Without this, animation state can appear
+		// to "snap" only after an input event (e.g. CursorMoved) triggers a new frame.
+		let now = Instant::now();
+		let elapsed = now.duration_since(self.egui_start_time).as_secs_f64().max(0.0);
+		let predicted_dt =
+			now.duration_since(self.egui_last_frame_time).as_secs_f32().clamp(0.0, 0.5);
+		#[cfg(test)]
+		let (elapsed, predicted_dt) =
+			self.advance_offscreen_egui_clock().unwrap_or((elapsed, predicted_dt));
+
+		self.egui_last_frame_time = now;
+
+		// Keep the wgpu surface configuration in sync with the OS-reported window size.
+		//
+		// On macOS we can observe transient mismatches where `surface_config` is smaller than the
+		// actual window size (e.g. right after entering Frozen mode), which causes egui to build
+		// a smaller `screen_rect` and results in UI elements appearing clipped/offset until a
+		// later redraw or input event triggers a resize/reconfigure.
+		let actual_size = self.target_inner_size();
+		let desired_w = actual_size.width.max(1);
+		let desired_h = actual_size.height.max(1);
+
+		if self.surface_config.width != desired_w || self.surface_config.height != desired_h {
+			tracing::debug!(
+				window_id = ?self.window_id(),
+				actual_size_px = ?actual_size,
+				old_surface_px = ?(self.surface_config.width, self.surface_config.height),
+				new_surface_px = ?(desired_w, desired_h),
+				window_scale_factor = self.target_scale_factor(),
+				pixels_per_point_override,
+				"Reconfiguring wgpu surface to match window."
+			);
+
+			self.surface_config.width = desired_w;
+			self.surface_config.height = desired_h;
+			self.needs_reconfigure = false;
 
 			self.reconfigure(gpu);
 		}
@@ -7146,7 +9470,7 @@ impl WindowRenderer {
 		let size = PhysicalSize::new(self.surface_config.width, self.surface_config.height);
 		let pixels_per_point = pixels_per_point_override
 			.filter(|v| *v > 0.0)
-			.unwrap_or_else(|| self.window.scale_factor() as f32);
+			.unwrap_or_else(|| self.target_scale_factor() as f32);
 		let screen_size_points =
 			Vec2::new(size.width as f32 / pixels_per_point, size.height as f32 / pixels_per_point);
 		let max_texture_side = gpu.device.limits().max_texture_dimension_2d as usize;
@@ -7218,7 +9542,7 @@ impl WindowRenderer {
 		hud_tint_hue: f32,
 		theme: HudTheme,
 		selection_particles: bool,
-		selection_flow_stroke_width_px: f32,
+		selection_flow_stroke: SelectionFlowStroke,
 		needs_frozen_surface_bg: bool,
 		show_frozen_capture_affordance: bool,
 		frozen_capture_is_fullscreen_fallback: bool,
@@ -7238,8 +9562,22 @@ impl WindowRenderer {
 		};
 		let mut hud_pill = None;
 		let mut _show_selection_particles = false;
+		let export_preview = match toolbar_state.as_deref() {
+			Some(toolbar_state)
+				if matches!(state.mode, OverlayMode::Frozen) && state.monitor == Some(monitor) =>
+			{
+				self.sync_export_preview_texture(toolbar_state.export_preview.as_ref())
+			},
+			_ => None,
+		};
+		let frozen_view_texture =
+			self.sync_frozen_view_texture(state, monitor, needs_frozen_surface_bg);
 		let egui_ctx = self.egui_ctx.clone();
 		let full_output = egui_ctx.run(raw_input, |ctx| {
+			if let Some(texture_id) = frozen_view_texture {
+				Self::render_frozen_view(ctx, state, monitor, texture_id);
+			}
+
 			Self::render_frozen_toolbar_ui(
 				ctx,
 				state,
@@ -7253,6 +9591,7 @@ impl WindowRenderer {
 				hud_tint_hue,
 				toolbar_state.as_deref_mut(),
 				toolbar_pointer,
+				export_preview,
 				&mut hud_pill,
 			);
 
@@ -7278,6 +9617,19 @@ impl WindowRenderer {
 				);
 			}
 
+			if matches!(state.mode, OverlayMode::Live)
+				&& let Some(reason) = state.capture_unavailable_reason(monitor.id)
+			{
+				Self::render_capture_unavailable_banner(ctx, monitor, reason, theme);
+			}
+			if matches!(state.mode, OverlayMode::Frozen) && state.monitor == Some(monitor) {
+				Self::render_frozen_panels(ctx, state, monitor, theme, needs_frozen_surface_bg);
+			}
+			if let Some(metrics) = state.perf_hud.as_ref()
+				&& Self::is_perf_hud_monitor(state, monitor)
+			{
+				Self::render_perf_hud(ctx, metrics, monitor, theme);
+			}
 			if selection_particles && matches!(state.mode, OverlayMode::Live) && !can_draw_hud {
 				let screen_rect = ctx.input(|i| i.viewport_rect());
 				let layer = LayerId::new(
@@ -7293,12 +9645,13 @@ impl WindowRenderer {
 					monitor,
 					screen_rect,
 					theme,
-					selection_flow_stroke_width_px,
+					selection_flow_stroke,
 					selection_flow_geometry_cache,
 				);
 			}
 			if selection_particles
 				&& matches!(state.mode, OverlayMode::Frozen)
+				&& !state.frozen_view.is_zoomed()
 				&& (needs_frozen_surface_bg || show_frozen_capture_affordance)
 				&& state.monitor == Some(monitor)
 				&& state.frozen_capture_rect.is_some()
@@ -7312,2747 +9665,4158 @@ impl WindowRenderer {
 					screen_rect,
 					theme,
 					frozen_capture_is_fullscreen_fallback,
-					selection_flow_stroke_width_px,
+					selection_flow_stroke,
 					selection_flow_geometry_cache,
 				);
 			}
+			if matches!(state.mode, OverlayMode::Frozen) && state.monitor == Some(monitor) {
+				Self::render_extra_capture_regions(ctx, state, monitor, theme);
+				Self::render_redaction_proposals(ctx, state, monitor);
+				Self::render_annotations(ctx, state, monitor);
+			}
+			Self::render_monitor_indicators(ctx, state, monitor, theme);
 		});
 
 		(full_output, hud_pill)
 	}
 
-	#[allow(clippy::too_many_arguments)]
-	fn render_live_capture_affordances(
+	/// Draws the badges and the histogram panel on the frozen monitor. The badges describe the
+	/// frozen surface, so they only show while it is drawn.
+	fn render_frozen_panels(
 		ctx: &egui::Context,
-		painter: &Painter,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		screen_rect: Rect,
 		theme: HudTheme,
-		selection_flow_stroke_width_px: f32,
-		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
-	) -> bool {
-		let mut has_rect = false;
+		needs_frozen_surface_bg: bool,
+	) {
+		if needs_frozen_surface_bg
+			&& state.frozen_image.as_ref().is_some_and(|image| {
+				let max_texture_side = ctx.input(|i| i.max_texture_side);
 
-		if !matches!(state.mode, OverlayMode::Live) {
-			return false;
+				image_helpers::exceeds_gpu_texture_side(image, max_texture_side as u32)
+			}) {
+			Self::render_preview_downscaled_badge(ctx, monitor, theme);
+		}
+		if needs_frozen_surface_bg && state.color_vision != ColorVisionFilter::Off {
+			Self::render_color_vision_badge(ctx, monitor, state.color_vision, theme);
 		}
+		if state.histogram_panel {
+			Self::render_histogram_panel(ctx, state, monitor, theme);
+		}
+	}
 
-		if let Some(hovered_window) = state.hovered_window_rect
-			&& hovered_window.monitor_id == monitor.id
+	/// Draws decoded codes, the capture countdown and flash, and the display picker badge when
+	/// they target this monitor.
+	fn render_monitor_indicators(
+		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		theme: HudTheme,
+	) {
+		if state.decoded_codes_monitor == Some(monitor) {
+			Self::render_decoded_codes(ctx, state, monitor);
+		}
+		if let Some((region, remaining_secs)) = state.capture_countdown
+			&& region.monitor_id == monitor.id
 		{
-			let rect = Rect::from_min_size(
-				Pos2::new(hovered_window.rect.x as f32, hovered_window.rect.y as f32),
-				Vec2::new(hovered_window.rect.width as f32, hovered_window.rect.height as f32),
-			);
-			let rect = rect.intersect(screen_rect);
-
-			if rect.width() >= LIVE_DRAG_START_THRESHOLD_PX
-				&& rect.height() >= LIVE_DRAG_START_THRESHOLD_PX
-			{
-				Self::render_selection_flow_ring(
-					painter,
-					rect,
-					ctx,
-					theme,
-					SelectionFlowStyle::Band,
-					selection_flow_stroke_width_px,
-					selection_flow_geometry_cache,
-				);
-
-				has_rect = true;
-			}
+			Self::render_capture_countdown(ctx, monitor, region.rect, remaining_secs, theme);
 		}
-		if let Some(drag_rect) = state.drag_rect
-			&& drag_rect.monitor_id == monitor.id
-			&& drag_rect.rect.width as f32 >= LIVE_DRAG_START_THRESHOLD_PX
-			&& drag_rect.rect.height as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+		if let Some((region, alpha)) = state.capture_flash
+			&& region.monitor_id == monitor.id
 		{
-			let rect = Rect::from_min_size(
-				Pos2::new(drag_rect.rect.x as f32, drag_rect.rect.y as f32),
-				Vec2::new(drag_rect.rect.width as f32, drag_rect.rect.height as f32),
-			);
-			let rect = rect.intersect(screen_rect);
+			Self::render_capture_flash(ctx, monitor, region.rect, alpha);
+		}
+		if let Some(index) = state.display_picker.iter().position(|picked| *picked == monitor) {
+			let label = if state.identify_displays {
+				let (width_px, height_px) = monitor.size_px();
+				let name = state.display_names.get(&monitor.id).map_or("Display", String::as_str);
 
-			Self::render_selection_flow_ring(
-				painter,
-				rect,
-				ctx,
-				theme,
-				SelectionFlowStyle::FullBorder,
-				selection_flow_stroke_width_px,
-				selection_flow_geometry_cache,
-			);
+				format!("{name} · {width_px}×{height_px} · {}×", monitor.scale_factor())
+			} else {
+				format!("{}×{} · press {} to capture", monitor.width, monitor.height, index + 1)
+			};
 
-			has_rect = true;
+			Self::render_display_picker_badge(ctx, monitor, index, &label, theme);
 		}
+	}
 
-		let has_hovered_window_for_this_monitor =
-			state.hovered_window_rect.is_some_and(|hovered| hovered.monitor_id == monitor.id);
-		let has_drag_rect_for_this_monitor =
-			state.drag_rect.is_some_and(|drag_rect| drag_rect.monitor_id == monitor.id);
-		let cursor_on_monitor = state.cursor.is_some_and(|cursor| monitor.contains(cursor));
-		let primary_not_down = !ctx.input(|i| i.pointer.primary_down());
-
-		if !has_hovered_window_for_this_monitor
-			&& !has_drag_rect_for_this_monitor
-			&& cursor_on_monitor
-			&& primary_not_down
-		{
-			Self::render_selection_flow_ring(
-				painter,
-				screen_rect,
-				ctx,
-				theme,
-				SelectionFlowStyle::Band,
-				selection_flow_stroke_width_px,
-				selection_flow_geometry_cache,
-			);
-
-			has_rect = true;
-		}
-
-		has_rect
-	}
-
-	#[allow(clippy::too_many_arguments)]
-	fn render_frozen_pending_affordance(
+	/// Greys out a monitor whose capture probe failed and explains why at the top edge.
+	fn render_capture_unavailable_banner(
 		ctx: &egui::Context,
-		state: &OverlayState,
 		monitor: MonitorRect,
-		screen_rect: Rect,
+		reason: &str,
 		theme: HudTheme,
-		frozen_capture_is_fullscreen_fallback: bool,
-		selection_flow_stroke_width_px: f32,
-		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
-	) -> bool {
-		let Some(capture_rect) = state.frozen_capture_rect else {
-			return false;
+	) {
+		let screen_rect = ctx.input(|i| i.viewport_rect());
+		let layer =
+			LayerId::new(Order::Background, Id::new(format!("capture-unavailable-{}", monitor.id)));
+
+		ctx.layer_painter(layer).rect_filled(
+			screen_rect,
+			0.0,
+			Color32::from_rgba_unmultiplied(60, 60, 64, 140),
+		);
+
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 230),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 235),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(245, 245, 248, 235),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 235),
+			),
 		};
-		let capture_width = capture_rect.width as f32;
-		let capture_height = capture_rect.height as f32;
 
-		if capture_width < LIVE_DRAG_START_THRESHOLD_PX
-			|| capture_height < LIVE_DRAG_START_THRESHOLD_PX
-		{
-			return false;
-		}
+		Area::new(Id::new(format!("capture-unavailable-banner-{}", monitor.id)))
+			.order(Order::Foreground)
+			.anchor(Align2::CENTER_TOP, egui::vec2(0.0, 24.0))
+			.interactable(false)
+			.show(ctx, |ui| {
+				Frame::new()
+					.fill(fill)
+					.corner_radius(f32::from(HUD_PILL_CORNER_RADIUS_POINTS))
+					.inner_margin(Margin::symmetric(14, 8))
+					.show(ui, |ui| {
+						ui.label(
+							RichText::new(format!("Capture unavailable on this display: {reason}"))
+								.color(text_color),
+						);
+					});
+			});
+	}
 
-		let layer =
-			LayerId::new(Order::Foreground, Id::new(format!("frozen-pending-{}", monitor.id)));
-		let painter = ctx.layer_painter(layer);
-		let rect = Rect::from_min_size(
-			Pos2::new(capture_rect.x as f32, capture_rect.y as f32),
-			Vec2::new(capture_rect.width as f32, capture_rect.height as f32),
-		)
-		.intersect(screen_rect);
+	/// Flags a frozen preview that had to be downscaled to fit the GPU texture limit.
+	fn render_preview_downscaled_badge(ctx: &egui::Context, monitor: MonitorRect, theme: HudTheme) {
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 200),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 220),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(245, 245, 248, 215),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 220),
+			),
+		};
 
-		if rect.width() < LIVE_DRAG_START_THRESHOLD_PX
-			|| rect.height() < LIVE_DRAG_START_THRESHOLD_PX
-		{
-			return false;
-		}
+		Area::new(Id::new(format!("preview-downscaled-badge-{}", monitor.id)))
+			.order(Order::Foreground)
+			.anchor(Align2::RIGHT_TOP, egui::vec2(-24.0, 24.0))
+			.interactable(false)
+			.show(ctx, |ui| {
+				Frame::new()
+					.fill(fill)
+					.corner_radius(f32::from(HUD_PILL_CORNER_RADIUS_POINTS))
+					.inner_margin(Margin::symmetric(12, 6))
+					.show(ui, |ui| {
+						ui.label(
+							RichText::new("Preview downscaled (display limit)")
+								.color(text_color)
+								.small(),
+						);
+					});
+			});
+	}
 
-		Self::render_selection_flow_ring(
-			&painter,
-			rect,
-			ctx,
-			theme,
-			if frozen_capture_is_fullscreen_fallback {
-				SelectionFlowStyle::Band
-			} else {
-				SelectionFlowStyle::FullBorder
-			},
-			selection_flow_stroke_width_px,
-			selection_flow_geometry_cache,
-		);
+	fn render_color_vision_badge(
+		ctx: &egui::Context,
+		monitor: MonitorRect,
+		filter: ColorVisionFilter,
+		theme: HudTheme,
+	) {
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 200),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 220),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(245, 245, 248, 215),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 220),
+			),
+		};
 
-		true
+		Area::new(Id::new(format!("color-vision-badge-{}", monitor.id)))
+			.order(Order::Foreground)
+			.anchor(Align2::LEFT_TOP, egui::vec2(24.0, 24.0))
+			.interactable(false)
+			.show(ctx, |ui| {
+				Frame::new()
+					.fill(fill)
+					.corner_radius(f32::from(HUD_PILL_CORNER_RADIUS_POINTS))
+					.inner_margin(Margin::symmetric(12, 6))
+					.show(ui, |ui| {
+						ui.label(
+							RichText::new(format!(
+								"{} simulation · preview only (V)",
+								filter.label()
+							))
+							.color(text_color)
+							.small(),
+						);
+					});
+			});
 	}
 
-	fn render_selection_flow_ring(
-		painter: &Painter,
-		rect: Rect,
+	/// Outlines the extra Shift-drag regions with corner handles and their export number; the
+	/// primary selection is region 1. A region drag in progress is outlined without a number.
+	fn render_extra_capture_regions(
 		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
 		theme: HudTheme,
-		style: SelectionFlowStyle,
-		selection_flow_stroke_width_px: f32,
-		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
 	) {
-		if rect.width() < LIVE_DRAG_START_THRESHOLD_PX
-			|| rect.height() < LIVE_DRAG_START_THRESHOLD_PX
-		{
+		let in_progress = state.drag_rect.filter(|drag| drag.monitor_id == monitor.id);
+
+		if state.extra_capture_rects.is_empty() && in_progress.is_none() {
 			return;
 		}
 
-		let corner_radius = SELECTION_FLOW_CORNER_RADIUS_PX
-			.min(rect.width() / 2.0 - 0.25)
-			.min(rect.height() / 2.0 - 0.25)
-			.max(0.0);
-		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
-		let time = ctx.input(|i| i.time) as f32;
-		let sample_count = Self::selection_flow_sample_count(perimeter);
-		let seam_offset = if rect.width() > corner_radius * 2.0 {
-			(rect.width() - corner_radius * 2.0) * 0.5
-		} else {
-			0.0
+		let (stroke_color, handle_fill, badge_text) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(235, 235, 245, 230),
+				Color32::from_rgb(28, 28, 32),
+				Color32::from_rgb(28, 28, 32),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 230),
+				Color32::from_rgb(245, 245, 248),
+				Color32::from_rgb(245, 245, 248),
+			),
 		};
-		let (samples, normals) = Self::selection_flow_cached_geometry(
-			selection_flow_geometry_cache,
-			rect,
-			corner_radius,
-			sample_count,
-			seam_offset,
-		);
-		let base_alpha_scale = match theme {
-			HudTheme::Light => 0.86,
-			HudTheme::Dark => 1.0,
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-regions-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+		let stroke = Stroke::new(1.5, stroke_color);
+		let to_screen = |rect: RectPoints| {
+			Rect::from_min_size(
+				Pos2::new(rect.x as f32, rect.y as f32),
+				Vec2::new(rect.width as f32, rect.height as f32),
+			)
 		};
-		let stroke_width = selection_flow_stroke_width_px.clamp(1.0, 8.0);
 
-		if samples.is_empty() {
-			return;
-		}
+		for (index, rect) in state.extra_capture_rects.iter().enumerate() {
+			let rect = to_screen(*rect);
 
-		let flow_time = time * SELECTION_FLOW_SPEED;
-		let phase = flow_time * 1.28 + 0.72;
+			painter.rect_stroke(rect, 0.0, stroke, StrokeKind::Outside);
 
-		match style {
-			SelectionFlowStyle::Band => Self::selection_flow_draw_layer(
-				painter,
-				samples,
-				normals,
-				stroke_width,
-				base_alpha_scale * 0.52,
-				phase,
-				SELECTION_FLOW_CORE_FLOW_WIDTH,
-				theme,
-			),
-			SelectionFlowStyle::FullBorder => Self::selection_flow_draw_layer_full_border(
-				painter,
-				samples,
-				normals,
-				stroke_width,
-				base_alpha_scale * SELECTION_FLOW_FROZEN_ALPHA_SCALE,
-				phase,
-				SELECTION_FLOW_FROZEN_INTENSITY,
-				theme,
-			),
-		}
-	}
+			for corner in
+				[rect.left_top(), rect.right_top(), rect.left_bottom(), rect.right_bottom()]
+			{
+				let handle = Rect::from_center_size(corner, Vec2::splat(7.0));
 
-	fn selection_flow_cached_geometry(
-		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
-		rect: Rect,
-		corner_radius: f32,
-		sample_count: usize,
-		seam_offset: f32,
-	) -> (&[(Pos2, f32)], &[Vec2]) {
-		let key =
-			SelectionFlowGeometryCacheKey::new(rect, corner_radius, seam_offset, sample_count);
+				painter.rect_filled(handle, 1.0, handle_fill);
+				painter.rect_stroke(handle, 1.0, stroke, StrokeKind::Inside);
+			}
 
-		if selection_flow_geometry_cache.key == Some(key)
-			&& !selection_flow_geometry_cache.samples.is_empty()
-		{
-			return (
-				&selection_flow_geometry_cache.samples,
-				&selection_flow_geometry_cache.normals,
+			let badge_center = rect.left_top() + Vec2::new(14.0, 14.0);
+
+			painter.circle_filled(badge_center, 9.0, stroke_color);
+			painter.text(
+				badge_center,
+				Align2::CENTER_CENTER,
+				(index + 2).to_string(),
+				FontId::proportional(11.0),
+				badge_text,
 			);
 		}
 
-		let samples =
-			Self::selection_flow_path_samples(rect, corner_radius, sample_count, seam_offset);
-		let normals = Self::selection_flow_compute_normals(&samples);
-
-		selection_flow_geometry_cache.key = Some(key);
-		selection_flow_geometry_cache.samples = samples;
-		selection_flow_geometry_cache.normals = normals;
-
-		(&selection_flow_geometry_cache.samples, &selection_flow_geometry_cache.normals)
+		if let Some(drag) = in_progress {
+			painter.rect_stroke(to_screen(drag.rect), 0.0, stroke, StrokeKind::Outside);
+		}
 	}
 
-	fn selection_flow_compute_normals(samples: &[(Pos2, f32)]) -> Vec<Vec2> {
-		let n = samples.len();
-
-		if n == 0 {
-			return Vec::new();
+	/// Marks auto-redaction matches over the frozen selection: accepted ones as a mosaic block,
+	/// the rest as an outline labelled with what matched.
+	fn render_redaction_proposals(ctx: &egui::Context, state: &OverlayState, monitor: MonitorRect) {
+		if state.redactions.is_empty() {
+			return;
 		}
 
-		let mut normals = Vec::with_capacity(n);
-		let mut first_non_zero = None;
-
-		for i in 0..n {
-			let (current_point, _) = samples[i];
-			let (prev_point, _) = samples[(i + n - 1) % n];
-			let (next_point, _) = samples[(i + 1) % n];
-			let prev_tangent = current_point - prev_point;
-			let next_tangent = next_point - current_point;
-			let mut normal = Vec2::ZERO;
-
-			if prev_tangent.length_sq() > f32::EPSILON {
-				let prev_len = prev_tangent.length();
+		let capture_rect = state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let scale = monitor.scale_factor();
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-redactions-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+		let proposal_color = Color32::from_rgb(255, 159, 10);
+		let mosaic_colors = [Color32::from_rgb(58, 58, 64), Color32::from_rgb(92, 92, 100)];
 
-				normal += Vec2::new(-prev_tangent.y / prev_len, prev_tangent.x / prev_len);
-			}
-			if next_tangent.length_sq() > f32::EPSILON {
-				let next_len = next_tangent.length();
+		for proposal in &state.redactions {
+			let rect = Rect::from_min_size(
+				Pos2::new(
+					capture_rect.x as f32 + proposal.rect_px.x as f32 / scale,
+					capture_rect.y as f32 + proposal.rect_px.y as f32 / scale,
+				),
+				Vec2::new(
+					proposal.rect_px.width as f32 / scale,
+					proposal.rect_px.height as f32 / scale,
+				),
+			);
 
-				normal += Vec2::new(-next_tangent.y / next_len, next_tangent.x / next_len);
-			}
-			if normal.length_sq() <= f32::EPSILON {
-				if next_tangent.length_sq() > f32::EPSILON {
-					let next_len = next_tangent.length();
+			if proposal.accepted {
+				let cell = 6.0;
+				let columns = (rect.width() / cell).ceil() as usize;
+				let rows = (rect.height() / cell).ceil() as usize;
 
-					normal = Vec2::new(-next_tangent.y / next_len, next_tangent.x / next_len);
-				} else if prev_tangent.length_sq() > f32::EPSILON {
-					let prev_len = prev_tangent.length();
+				for row in 0..rows {
+					for column in 0..columns {
+						let min = rect.min + Vec2::new(column as f32 * cell, row as f32 * cell);
+						let tile = Rect::from_min_size(min, Vec2::splat(cell)).intersect(rect);
 
-					normal = Vec2::new(-prev_tangent.y / prev_len, prev_tangent.x / prev_len);
+						painter.rect_filled(tile, 0.0, mosaic_colors[(row + column) % 2]);
+					}
 				}
+			} else {
+				painter.rect_stroke(
+					rect,
+					2.0,
+					Stroke::new(1.5, proposal_color),
+					StrokeKind::Outside,
+				);
+				painter.text(
+					rect.left_top() - Vec2::new(0.0, 3.0),
+					Align2::LEFT_BOTTOM,
+					proposal.kind.label(),
+					FontId::proportional(10.0),
+					proposal_color,
+				);
 			}
+		}
+	}
 
-			let normal = if normal.length_sq() > f32::EPSILON {
-				let normalized = normal / normal.length();
+	/// Outlines each decoded QR code and labels it with the start of its payload.
+	fn render_decoded_codes(ctx: &egui::Context, state: &OverlayState, monitor: MonitorRect) {
+		const LABEL_MAX_CHARS: usize = 48;
 
-				if first_non_zero.is_none() && normalized.length_sq() > f32::EPSILON {
-					first_non_zero = Some(i);
-				}
+		let scale = monitor.scale_factor();
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("decoded-codes-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+		let code_color = Color32::from_rgb(48, 209, 88);
 
-				normalized
-			} else {
-				Vec2::ZERO
-			};
+		for code in &state.decoded_codes {
+			let rect = Rect::from_min_size(
+				Pos2::new(code.bounds_px.x as f32 / scale, code.bounds_px.y as f32 / scale),
+				Vec2::new(
+					code.bounds_px.width as f32 / scale,
+					code.bounds_px.height as f32 / scale,
+				),
+			);
+			let mut label = code.payload.chars().take(LABEL_MAX_CHARS).collect::<String>();
 
-			normals.push(normal);
-		}
+			if code.payload.chars().count() > LABEL_MAX_CHARS {
+				label.push('…');
+			}
 
-		if let Some(first_idx) = first_non_zero {
-			let mut previous = normals[first_idx];
-
-			for normal in normals.iter_mut().skip(first_idx + 1) {
-				if normal.length_sq() > f32::EPSILON && normal.dot(previous) < 0.0 {
-					*normal = -*normal;
-				}
-				if normal.length_sq() > f32::EPSILON {
-					previous = *normal;
-				}
-			}
-			for normal in normals.iter_mut().take(first_idx).rev() {
-				if normal.length_sq() > f32::EPSILON && normal.dot(previous) < 0.0 {
-					*normal = -*normal;
-				}
-				if normal.length_sq() > f32::EPSILON {
-					previous = *normal;
-				}
-			}
-
-			if normals[first_idx].length_sq() > f32::EPSILON
-				&& normals[(first_idx + n - 1) % n].length_sq() > f32::EPSILON
-				&& normals[first_idx].dot(normals[(first_idx + n - 1) % n]) < 0.0
-			{
-				for normal in &mut normals {
-					*normal = -*normal;
-				}
-			}
+			painter.rect_stroke(rect, 2.0, Stroke::new(2.0, code_color), StrokeKind::Outside);
+			painter.text(
+				rect.left_bottom() + Vec2::new(0.0, 4.0),
+				Align2::LEFT_TOP,
+				label,
+				FontId::proportional(11.0),
+				code_color,
+			);
 		}
-
-		normals
 	}
 
-	#[allow(clippy::too_many_arguments)]
-	fn selection_flow_draw_layer(
-		painter: &Painter,
-		samples: &[(Pos2, f32)],
-		normals: &[Vec2],
-		line_width: f32,
-		alpha_scale: f32,
-		phase: f32,
-		flow_band_width: f32,
+	/// Draws the display picker's number in the middle of `monitor`.
+	fn render_display_picker_badge(
+		ctx: &egui::Context,
+		monitor: MonitorRect,
+		index: usize,
+		label: &str,
 		theme: HudTheme,
 	) {
-		if samples.is_empty() || normals.is_empty() || samples.len() != normals.len() {
-			return;
-		}
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => {
+				(Color32::from_rgba_unmultiplied(28, 28, 32, 220), Color32::from_rgb(245, 245, 248))
+			},
+			HudTheme::Light => {
+				(Color32::from_rgba_unmultiplied(245, 245, 248, 220), Color32::from_rgb(28, 28, 32))
+			},
+		};
+		let screen_rect = ctx.input(|i| i.viewport_rect());
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("display-picker-{}", monitor.id)));
+		let painter = ctx.layer_painter(layer);
+		let center = screen_rect.center();
+
+		painter.circle_filled(center, 56.0, fill);
+		painter.text(
+			center,
+			Align2::CENTER_CENTER,
+			(index + 1).to_string(),
+			FontId::proportional(64.0),
+			text_color,
+		);
 
-		let half = (line_width * 0.5).max(0.1);
-		let n = samples.len();
-		let mut mesh = Mesh::default();
+		let galley =
+			painter.layout_no_wrap(label.to_owned(), FontId::proportional(14.0), text_color);
+		let label_rect = Rect::from_center_size(
+			center + Vec2::new(0.0, 84.0),
+			galley.size() + Vec2::new(16.0, 8.0),
+		);
 
-		for i in 0..n {
-			let (current_point, t) = samples[i];
-			let movement = Self::selection_flow_flow_band(t, phase, flow_band_width);
-			let intensity = SELECTION_FLOW_FLOW_BOOST * movement;
-			let color = Self::selection_flow_color(t + phase, theme, alpha_scale, intensity);
-			let normal = normals[i] * half;
+		painter.rect_filled(label_rect, 6.0, fill);
+		painter.galley(label_rect.min + Vec2::new(8.0, 4.0), galley, text_color);
+	}
 
-			mesh.colored_vertex(current_point + normal, color);
-			mesh.colored_vertex(current_point - normal, color);
-		}
-		for i in 0..n {
-			let i0 = (i * 2) as u32;
-			let i1 = ((i * 2) + 1) as u32;
-			let n0 = (((i + 1) % n) * 2) as u32;
-			let n1 = (((i + 1) % n) * 2 + 1) as u32;
+	/// Outlines the pending selection and shows the seconds left before it is captured.
+	fn render_capture_countdown(
+		ctx: &egui::Context,
+		monitor: MonitorRect,
+		rect: RectPoints,
+		remaining_secs: u32,
+		theme: HudTheme,
+	) {
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => {
+				(Color32::from_rgba_unmultiplied(28, 28, 32, 220), Color32::from_rgb(245, 245, 248))
+			},
+			HudTheme::Light => {
+				(Color32::from_rgba_unmultiplied(245, 245, 248, 220), Color32::from_rgb(28, 28, 32))
+			},
+		};
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("capture-countdown-{}", monitor.id)));
+		let painter = ctx.layer_painter(layer);
+		let rect = Rect::from_min_size(
+			Pos2::new(rect.x as f32, rect.y as f32),
+			Vec2::new(rect.width as f32, rect.height as f32),
+		);
 
-			mesh.add_triangle(i0, i1, n0);
-			mesh.add_triangle(i1, n1, n0);
-		}
+		painter.rect_stroke(rect, 0.0, Stroke::new(2.0, text_color), StrokeKind::Inside);
+		painter.circle_filled(rect.center(), 44.0, fill);
+		painter.text(
+			rect.center(),
+			Align2::CENTER_CENTER,
+			remaining_secs.to_string(),
+			FontId::proportional(48.0),
+			text_color,
+		);
+	}
 
-		painter.add(Shape::Mesh(mesh.into()));
+	/// Washes the captured region in white to confirm the export.
+	fn render_capture_flash(
+		ctx: &egui::Context,
+		monitor: MonitorRect,
+		rect: RectPoints,
+		alpha: u8,
+	) {
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("capture-flash-{}", monitor.id)));
+		let rect = Rect::from_min_size(
+			Pos2::new(rect.x as f32, rect.y as f32),
+			Vec2::new(rect.width as f32, rect.height as f32),
+		);
+
+		ctx.layer_painter(layer).rect_filled(
+			rect,
+			0.0,
+			Color32::from_rgba_unmultiplied(255, 255, 255, alpha),
+		);
 	}
 
-	#[allow(clippy::too_many_arguments)]
-	fn selection_flow_draw_layer_full_border(
+	/// Names the hovered window next to the cursor so the user can tell which window a click will
+	/// capture.
+	fn render_hovered_window_label(
 		painter: &Painter,
-		samples: &[(Pos2, f32)],
-		normals: &[Vec2],
-		line_width: f32,
-		alpha_scale: f32,
-		phase: f32,
-		intensity: f32,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		rect: RectPoints,
+		screen_rect: Rect,
 		theme: HudTheme,
 	) {
-		if samples.is_empty() || normals.is_empty() || samples.len() != normals.len() {
+		let Some(label) = state.hovered_window_label.as_ref() else {
 			return;
-		}
-
-		let half = (line_width * 0.5).max(0.1);
-		let n = samples.len();
-		let mut mesh = Mesh::default();
-
-		for i in 0..n {
-			let (current_point, t) = samples[i];
-			let color = Self::selection_flow_color(t + phase, theme, alpha_scale, intensity);
-			let normal = normals[i] * half;
+		};
+		let Some((cursor_x, cursor_y)) = state.cursor.and_then(|cursor| monitor.local_u32(cursor))
+		else {
+			return;
+		};
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => {
+				(Color32::from_rgba_unmultiplied(28, 28, 32, 220), Color32::from_rgb(245, 245, 248))
+			},
+			HudTheme::Light => {
+				(Color32::from_rgba_unmultiplied(245, 245, 248, 220), Color32::from_rgb(28, 28, 32))
+			},
+		};
+		let name = match (label.app_name.is_empty(), label.title.is_empty()) {
+			(false, false) => format!("{} — {}", label.app_name, label.title),
+			(false, true) => label.app_name.clone(),
+			(true, false) => label.title.clone(),
+			(true, true) => String::from("Window"),
+		};
+		let (hud_unit, millimeters_per_point) =
+			hud_helpers::monitor_hud_unit(state.hud_unit, monitor);
+		let size_text =
+			hud_helpers::format_hud_size_text(monitor, rect, hud_unit, millimeters_per_point);
+		let text = format!("{name}\n{size_text}");
+		let galley = painter.layout(text, FontId::proportional(12.0), text_color, 360.0);
+		let size = galley.size() + Vec2::new(12.0, 8.0);
+		let cursor = Pos2::new(cursor_x as f32, cursor_y as f32);
+		let mut min = cursor + Vec2::new(16.0, 16.0);
 
-			mesh.colored_vertex(current_point + normal, color);
-			mesh.colored_vertex(current_point - normal, color);
+		// Flip to the other side of the cursor rather than run off the display edge.
+		if min.x + size.x > screen_rect.max.x {
+			min.x = cursor.x - 16.0 - size.x;
 		}
-		for i in 0..n {
-			let i0 = (i * 2) as u32;
-			let i1 = ((i * 2) + 1) as u32;
-			let n0 = (((i + 1) % n) * 2) as u32;
-			let n1 = (((i + 1) % n) * 2 + 1) as u32;
-
-			mesh.add_triangle(i0, i1, n0);
-			mesh.add_triangle(i1, n1, n0);
+		if min.y + size.y > screen_rect.max.y {
+			min.y = cursor.y - 16.0 - size.y;
 		}
 
-		painter.add(Shape::Mesh(mesh.into()));
-	}
-
-	fn selection_flow_flow_band(progress: f32, phase: f32, band_width: f32) -> f32 {
-		let width = band_width.clamp(0.001, 0.5);
-		let distance = (progress - phase).rem_euclid(1.0);
-		let distance = distance.min(1.0 - distance);
-		let normalized = (distance / width).min(1.0);
+		let label_rect = Rect::from_min_size(min.max(screen_rect.min), size);
 
-		(1.0 - normalized).powf(2.0)
+		painter.rect_filled(label_rect, 6.0, fill);
+		painter.galley(label_rect.min + Vec2::new(6.0, 4.0), galley, text_color);
 	}
 
-	fn selection_flow_sample_count(perimeter: f32) -> usize {
-		if perimeter <= 0.0 || !perimeter.is_finite() {
-			return SELECTION_FLOW_MIN_SEGMENTS;
+	fn is_perf_hud_monitor(state: &OverlayState, monitor: MonitorRect) -> bool {
+		match state.mode {
+			OverlayMode::Frozen => state.monitor == Some(monitor),
+			OverlayMode::Live => state.cursor.is_some_and(|cursor| monitor.contains(cursor)),
 		}
-
-		let by_step = (perimeter / SELECTION_FLOW_SAMPLE_STEP_PX).ceil() as usize;
-
-		by_step.clamp(SELECTION_FLOW_MIN_SEGMENTS, SELECTION_FLOW_MAX_SEGMENTS)
 	}
 
-	fn selection_flow_path_samples(
-		rect: Rect,
-		corner_radius: f32,
-		sample_count: usize,
-		start_offset: f32,
-	) -> Vec<(Pos2, f32)> {
-		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
-
-		if perimeter <= 0.0 {
-			return Vec::new();
-		}
-
-		let start = (start_offset / perimeter).rem_euclid(1.0);
+	/// Draws the F12 performance readout in the top-left corner of the active display.
+	fn render_perf_hud(
+		ctx: &egui::Context,
+		metrics: &PerfHudMetrics,
+		monitor: MonitorRect,
+		theme: HudTheme,
+	) {
+		let (fill, text_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 230),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 235),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(245, 245, 248, 235),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 235),
+			),
+		};
+		let sample_latency = metrics
+			.sample_latency_ms
+			.map_or_else(|| String::from("–"), |latency| format!("{latency:.1} ms"));
+		let lines = [
+			format!(
+				"frame   {:.1} ms (max {:.1})",
+				metrics.frame_time_ms, metrics.frame_time_max_ms
+			),
+			format!("redraws {:.0}/s", metrics.redraws_per_second),
+			format!("sample  {sample_latency}"),
+			format!("queue   {}/{}", metrics.queue_depth, metrics.queue_capacity),
+			format!("dropped {}", metrics.dropped_requests),
+		];
 
-		(0..sample_count)
-			.map(|index| {
-				let t = (index as f32 + 0.5) / sample_count as f32;
-				let progress = (t + start).rem_euclid(1.0);
+		Area::new(Id::new(format!("perf-hud-{}", monitor.id)))
+			.order(Order::Tooltip)
+			.anchor(Align2::LEFT_TOP, egui::vec2(24.0, 24.0))
+			.interactable(false)
+			.show(ctx, |ui| {
+				Frame::new()
+					.fill(fill)
+					.corner_radius(f32::from(HUD_PILL_CORNER_RADIUS_POINTS))
+					.inner_margin(Margin::symmetric(12, 8))
+					.show(ui, |ui| {
+						ui.label(RichText::new("Performance  F12").color(text_color).monospace());
 
-				(
-					Self::selection_flow_sample_at_distance(
-						rect,
-						corner_radius,
-						perimeter * progress,
-					),
-					t,
-				)
-			})
-			.collect()
+						for line in lines {
+							ui.label(RichText::new(line).color(text_color).monospace().small());
+						}
+					});
+			});
 	}
 
-	fn selection_flow_sample_at_distance(rect: Rect, corner_radius: f32, distance: f32) -> Pos2 {
-		if corner_radius <= f32::EPSILON {
-			let perimeter = Self::selection_flow_perimeter(rect, 0.0);
-			let keep = distance.rem_euclid(perimeter);
-			let edge_top = rect.width();
-			let edge_right = rect.height();
+	/// Draws the selection histogram panel in the bottom-left corner of the frozen display.
+	///
+	/// Luminance is filled and the RGB channels are outlined, all scaled to the tallest bin.
+	fn render_histogram_panel(
+		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		theme: HudTheme,
+	) {
+		let (fill, text_color, secondary_color, luma_fill) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 230),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 235),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 150),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 70),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(245, 245, 248, 235),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 235),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 160),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 60),
+			),
+		};
 
-			if keep < edge_top {
-				return Pos2::new(rect.min.x + keep, rect.min.y);
-			}
-			if keep < edge_top + edge_right {
-				return Pos2::new(rect.max.x, rect.min.y + (keep - edge_top));
-			}
-			if keep < edge_top * 2.0 + edge_right {
-				return Pos2::new(rect.max.x - (keep - edge_top - edge_right), rect.max.y);
-			}
+		Area::new(Id::new(format!("histogram-panel-{}", monitor.id)))
+			.order(Order::Foreground)
+			.anchor(Align2::LEFT_BOTTOM, egui::vec2(24.0, -24.0))
+			.interactable(false)
+			.show(ctx, |ui| {
+				Frame::new()
+					.fill(fill)
+					.corner_radius(f32::from(HUD_PILL_CORNER_RADIUS_POINTS))
+					.inner_margin(Margin::symmetric(12, 8))
+					.show(ui, |ui| {
+						let arrow = if state.histogram_expanded { "▾" } else { "▸" };
 
-			return Pos2::new(rect.min.x, rect.max.y - (keep - edge_top * 2.0 - edge_right));
-		}
+						ui.label(
+							RichText::new(format!("{arrow} Histogram  G"))
+								.color(text_color)
+								.monospace(),
+						);
 
-		let x0 = rect.min.x;
-		let x1 = rect.max.x;
-		let y0 = rect.min.y;
-		let y1 = rect.max.y;
-		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
-		let remain = distance.rem_euclid(perimeter);
-		let edge_top_len = (rect.width() - corner_radius * 2.0).max(0.0);
-		let edge_right_len = (rect.height() - corner_radius * 2.0).max(0.0);
-		let corner_len = std::f32::consts::FRAC_PI_2 * corner_radius;
+						if !state.histogram_expanded {
+							return;
+						}
 
-		if remain < edge_top_len {
-			return Pos2::new(x0 + corner_radius + remain, y0);
-		}
+						let Some(histogram) = state.histogram.as_ref() else {
+							ui.label(RichText::new("Computing…").color(secondary_color));
+
+							return;
+						};
+						let (rect, _) = ui.allocate_exact_size(
+							egui::vec2(HISTOGRAM_PLOT_WIDTH_POINTS, HISTOGRAM_PLOT_HEIGHT_POINTS),
+							Sense::hover(),
+						);
+						let painter = ui.painter();
+						let peak = histogram.peak().max(1) as f32;
+						let bin_width = rect.width() / 256.0;
+						let bin_height = |count: u32| count as f32 / peak * rect.height();
+
+						for (bin, &count) in histogram.luma.iter().enumerate() {
+							if count == 0 {
+								continue;
+							}
+
+							let left = rect.left() + bin as f32 * bin_width;
+
+							painter.rect_filled(
+								Rect::from_min_max(
+									Pos2::new(left, rect.bottom() - bin_height(count)),
+									Pos2::new(left + bin_width, rect.bottom()),
+								),
+								0.0,
+								luma_fill,
+							);
+						}
+						for (color, bins) in [
+							(Color32::from_rgb(255, 69, 58), &histogram.red),
+							(Color32::from_rgb(48, 209, 88), &histogram.green),
+							(Color32::from_rgb(10, 132, 255), &histogram.blue),
+						] {
+							let points = bins
+								.iter()
+								.enumerate()
+								.map(|(bin, &count)| {
+									Pos2::new(
+										rect.left() + (bin as f32 + 0.5) * bin_width,
+										rect.bottom() - bin_height(count),
+									)
+								})
+								.collect();
+
+							painter.add(Shape::line(points, Stroke::new(1.0, color)));
+						}
 
-		let mut offset = remain - edge_top_len;
+						let (shadows, highlights) = histogram.clipped_percent();
 
-		if offset < corner_len {
-			let angle = -std::f32::consts::FRAC_PI_2 + offset / corner_radius;
+						ui.label(
+							RichText::new(format!(
+								"Clipped {shadows:.1}% black · {highlights:.1}% white"
+							))
+							.color(secondary_color)
+							.monospace(),
+						);
+					});
+			});
+	}
 
-			return Pos2::new(
-				x1 - corner_radius + corner_radius * angle.cos(),
-				y0 + corner_radius + corner_radius * angle.sin(),
-			);
-		}
-
-		offset -= corner_len;
+	#[allow(clippy::too_many_arguments)]
+	fn render_live_capture_affordances(
+		ctx: &egui::Context,
+		painter: &Painter,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		screen_rect: Rect,
+		theme: HudTheme,
+		selection_flow_stroke: SelectionFlowStroke,
+		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
+	) -> bool {
+		let mut has_rect = false;
 
-		if offset < edge_right_len {
-			return Pos2::new(x1, y0 + corner_radius + offset);
+		if !matches!(state.mode, OverlayMode::Live)
+			|| state.magnifier
+			|| state.color_picker
+			|| state.identify_displays
+			|| state.capture_countdown.is_some()
+		{
+			return false;
 		}
 
-		offset -= edge_right_len;
-
-		if offset < corner_len {
-			let angle = offset / corner_radius;
-
-			return Pos2::new(
-				x1 - corner_radius + corner_radius * angle.cos(),
-				y1 - corner_radius + corner_radius * angle.sin(),
+		if let Some(hovered_window) = state.hovered_window_rect
+			&& hovered_window.monitor_id == monitor.id
+		{
+			let rect = Rect::from_min_size(
+				Pos2::new(hovered_window.rect.x as f32, hovered_window.rect.y as f32),
+				Vec2::new(hovered_window.rect.width as f32, hovered_window.rect.height as f32),
 			);
-		}
-
-		offset -= corner_len;
-
-		if offset < edge_top_len {
-			return Pos2::new(x1 - corner_radius - offset, y1);
-		}
+			let rect = rect.intersect(screen_rect);
 
-		offset -= edge_top_len;
+			if rect.width() >= LIVE_DRAG_START_THRESHOLD_PX
+				&& rect.height() >= LIVE_DRAG_START_THRESHOLD_PX
+			{
+				Self::render_selection_flow_ring(
+					painter,
+					rect,
+					ctx,
+					theme,
+					SelectionFlowStyle::Band,
+					selection_flow_stroke,
+					selection_flow_geometry_cache,
+				);
 
-		if offset < corner_len {
-			let angle = std::f32::consts::FRAC_PI_2 + offset / corner_radius;
+				if state.drag_rect.is_none() {
+					Self::render_hovered_window_label(
+						painter,
+						state,
+						monitor,
+						hovered_window.rect,
+						screen_rect,
+						theme,
+					);
+				}
 
-			return Pos2::new(
-				x0 + corner_radius + corner_radius * angle.cos(),
-				y1 - corner_radius + corner_radius * angle.sin(),
-			);
+				has_rect = true;
+			}
 		}
+		if let Some(drag_rect) = state.drag_rect
+			&& drag_rect.monitor_id == monitor.id
+			&& drag_rect.rect.width as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+			&& drag_rect.rect.height as f32 >= LIVE_DRAG_START_THRESHOLD_PX
+		{
+			let rect = Rect::from_min_size(
+				Pos2::new(drag_rect.rect.x as f32, drag_rect.rect.y as f32),
+				Vec2::new(drag_rect.rect.width as f32, drag_rect.rect.height as f32),
+			);
+			let rect = rect.intersect(screen_rect);
 
-		offset -= corner_len;
+			composition_guides_runtime::paint_composition_guides(
+				painter,
+				state.composition_guides,
+				rect,
+			);
+			Self::render_selection_flow_ring(
+				painter,
+				rect,
+				ctx,
+				theme,
+				SelectionFlowStyle::FullBorder,
+				selection_flow_stroke,
+				selection_flow_geometry_cache,
+			);
 
-		if offset < edge_right_len {
-			return Pos2::new(x0, y1 - corner_radius - offset);
+			has_rect = true;
 		}
 
-		offset -= edge_right_len;
-
-		if offset < corner_len {
-			let angle = std::f32::consts::PI + offset / corner_radius;
+		let has_hovered_window_for_this_monitor =
+			state.hovered_window_rect.is_some_and(|hovered| hovered.monitor_id == monitor.id);
+		let has_drag_rect_for_this_monitor =
+			state.drag_rect.is_some_and(|drag_rect| drag_rect.monitor_id == monitor.id);
+		let cursor_on_monitor = state.cursor.is_some_and(|cursor| monitor.contains(cursor));
+		let primary_not_down = !ctx.input(|i| i.pointer.primary_down());
 
-			return Pos2::new(
-				x0 + corner_radius + corner_radius * angle.cos(),
-				y0 + corner_radius + corner_radius * angle.sin(),
+		if !has_hovered_window_for_this_monitor
+			&& !has_drag_rect_for_this_monitor
+			&& cursor_on_monitor
+			&& primary_not_down
+		{
+			Self::render_selection_flow_ring(
+				painter,
+				screen_rect,
+				ctx,
+				theme,
+				SelectionFlowStyle::Band,
+				selection_flow_stroke,
+				selection_flow_geometry_cache,
 			);
-		}
-
-		Pos2::new(x0 + corner_radius, y0)
-	}
-
-	fn selection_flow_perimeter(rect: Rect, corner_radius: f32) -> f32 {
-		let edge_top_len = (rect.width() - corner_radius * 2.0).max(0.0);
-		let edge_right_len = (rect.height() - corner_radius * 2.0).max(0.0);
-		let corner_len = std::f32::consts::FRAC_PI_2 * corner_radius;
 
-		2.0 * (edge_top_len + edge_right_len) + 4.0 * corner_len
-	}
-
-	fn selection_flow_color(
-		progress: f32,
-		theme: HudTheme,
-		alpha_scale: f32,
-		intensity: f32,
-	) -> Color32 {
-		let palette = SELECTION_FLOW_PALETTE;
-		let normalized = progress.rem_euclid(1.0);
-		let band_position = normalized * palette.len() as f32;
-		let band = band_position.floor() as usize % palette.len();
-		let local = band_position - band as f32;
-		let (r0, g0, b0) = palette[band];
-		let (r1, g1, b1) = palette[(band + 1) % palette.len()];
-		let blend = |a: u8, b: u8, ratio: f32| -> u8 {
-			(a as f32 + (b as f32 - a as f32) * ratio).clamp(0.0, 255.0).round() as u8
-		};
-		let theme_alpha = match theme {
-			HudTheme::Dark => 1.0,
-			HudTheme::Light => 0.82,
-		};
-		let alpha = (255.0 * alpha_scale * intensity * theme_alpha).clamp(0.0, 255.0);
+			has_rect = true;
+		}
 
-		Color32::from_rgba_unmultiplied(
-			blend(r0, r1, local),
-			blend(g0, g1, local),
-			blend(b0, b1, local),
-			alpha as u8,
-		)
+		has_rect
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn render_frozen_toolbar_ui(
+	fn render_frozen_pending_affordance(
 		ctx: &egui::Context,
 		state: &OverlayState,
 		monitor: MonitorRect,
+		screen_rect: Rect,
 		theme: HudTheme,
-		toolbar_placement: ToolbarPlacement,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-		toolbar_state: Option<&mut FrozenToolbarState>,
-		pointer_state: Option<FrozenToolbarPointerState>,
-		hud_pill_out: &mut Option<HudPillGeometry>,
-	) {
-		let Some(toolbar_state) = toolbar_state else {
-			return;
+		frozen_capture_is_fullscreen_fallback: bool,
+		selection_flow_stroke: SelectionFlowStroke,
+		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
+	) -> bool {
+		let Some(capture_rect) = state.frozen_capture_rect else {
+			return false;
 		};
+		let capture_width = capture_rect.width as f32;
+		let capture_height = capture_rect.height as f32;
 
-		if !matches!(state.mode, OverlayMode::Frozen) || !toolbar_state.visible {
-			return;
-		}
-		if state.monitor != Some(monitor) {
-			return;
+		if capture_width < LIVE_DRAG_START_THRESHOLD_PX
+			|| capture_height < LIVE_DRAG_START_THRESHOLD_PX
+		{
+			return false;
 		}
 
-		let (cursor, left_button_down) = if let Some(pointer_state) = pointer_state {
-			(pointer_state.cursor_local, pointer_state.left_button_down)
-		} else {
-			toolbar_state.dragging = false;
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-pending-{}", monitor.id)));
+		let painter = ctx.layer_painter(layer);
+		let rect = Rect::from_min_size(
+			Pos2::new(capture_rect.x as f32, capture_rect.y as f32),
+			Vec2::new(capture_rect.width as f32, capture_rect.height as f32),
+		)
+		.intersect(screen_rect);
 
-			(Pos2::new(-1.0, -1.0), false)
-		};
-		let toolbar_size = Self::frozen_toolbar_size(toolbar_state);
-		let screen_rect = ctx.input(|i| i.viewport_rect());
-		let capture_rect = Self::frozen_toolbar_capture_rect(state, monitor, screen_rect);
-		let Some(toolbar_pos) = Self::resolve_frozen_toolbar_birth(
-			ctx,
-			state,
-			monitor,
-			toolbar_state,
-			screen_rect,
-			capture_rect,
-			toolbar_size,
-			toolbar_placement,
-		) else {
-			return;
-		};
+		if rect.width() < LIVE_DRAG_START_THRESHOLD_PX
+			|| rect.height() < LIVE_DRAG_START_THRESHOLD_PX
+		{
+			return false;
+		}
 
-		Self::draw_frozen_toolbar(
+		Self::render_selection_flow_ring(
+			&painter,
+			rect,
 			ctx,
-			toolbar_state,
-			monitor,
-			screen_rect,
-			toolbar_pos,
-			toolbar_size,
 			theme,
-			hud_blur_active,
-			hud_opaque,
-			hud_opacity,
-			hud_milk_amount,
-			hud_tint_hue,
-			cursor,
-			left_button_down,
-			hud_pill_out,
+			if frozen_capture_is_fullscreen_fallback {
+				SelectionFlowStyle::Band
+			} else {
+				SelectionFlowStyle::FullBorder
+			},
+			selection_flow_stroke,
+			selection_flow_geometry_cache,
 		);
-	}
 
-	fn frozen_toolbar_tools(toolbar_state: &FrozenToolbarState) -> &'static [FrozenToolbarTool] {
-		const TOOLS_SCROLL_MODE: [FrozenToolbarTool; 2] =
-			[FrozenToolbarTool::Copy, FrozenToolbarTool::Save];
-		const TOOLS_WITH_SCROLL: [FrozenToolbarTool; 9] = [
-			FrozenToolbarTool::Pointer,
-			FrozenToolbarTool::Pen,
-			FrozenToolbarTool::Text,
-			FrozenToolbarTool::Mosaic,
-			FrozenToolbarTool::Undo,
-			FrozenToolbarTool::Redo,
-			FrozenToolbarTool::Scroll,
-			FrozenToolbarTool::Copy,
-			FrozenToolbarTool::Save,
-		];
-		const TOOLS_WITHOUT_SCROLL: [FrozenToolbarTool; 8] = [
-			FrozenToolbarTool::Pointer,
-			FrozenToolbarTool::Pen,
-			FrozenToolbarTool::Text,
-			FrozenToolbarTool::Mosaic,
-			FrozenToolbarTool::Undo,
-			FrozenToolbarTool::Redo,
-			FrozenToolbarTool::Copy,
-			FrozenToolbarTool::Save,
-		];
+		true
+	}
 
-		if toolbar_state.scroll_capture_active {
-			&TOOLS_SCROLL_MODE
-		} else if toolbar_state.scroll_capture_available {
-			&TOOLS_WITH_SCROLL
-		} else {
-			&TOOLS_WITHOUT_SCROLL
-		}
-	}
-
-	fn frozen_toolbar_size(toolbar_state: &FrozenToolbarState) -> Vec2 {
-		let tool_count = Self::frozen_toolbar_tools(toolbar_state).len() as f32;
-		let spacing_count = (tool_count - 1.0).max(0.0);
-		let width = tool_count * FROZEN_TOOLBAR_BUTTON_SIZE_POINTS
-			+ spacing_count * FROZEN_TOOLBAR_ITEM_SPACING_POINTS
-			+ 2.0 * HUD_PILL_INNER_MARGIN_X_POINTS
-			+ 2.0 * HUD_PILL_STROKE_WIDTH_POINTS;
-		let height = toolbar_state.pill_height_points.unwrap_or(TOOLBAR_EXPANDED_HEIGHT_PX);
-
-		Vec2::new(width, height)
-	}
-
-	#[allow(clippy::too_many_arguments)]
-	fn resolve_frozen_toolbar_birth(
+	fn render_selection_flow_ring(
+		painter: &Painter,
+		rect: Rect,
 		ctx: &egui::Context,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		toolbar_state: &mut FrozenToolbarState,
-		screen_rect: Rect,
-		capture_rect: Rect,
-		toolbar_size: Vec2,
-		toolbar_placement: ToolbarPlacement,
-	) -> Option<Pos2> {
-		if let Some(pos) = toolbar_state.floating_position {
-			return Some(pos);
+		theme: HudTheme,
+		style: SelectionFlowStyle,
+		selection_flow_stroke: SelectionFlowStroke,
+		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
+	) {
+		if rect.width() < LIVE_DRAG_START_THRESHOLD_PX
+			|| rect.height() < LIVE_DRAG_START_THRESHOLD_PX
+		{
+			return;
 		}
 
-		let screen_size_points = screen_rect.size();
-
-		tracing::trace!(
-			monitor_id = monitor.id,
-			frozen_generation = state.frozen_generation,
-			screen_rect = ?screen_rect,
-			screen_size_points = ?screen_size_points,
-			pixels_per_point = ctx.pixels_per_point(),
-			last_screen_size_points = ?toolbar_state.layout_last_screen_size_points,
-			stable_frames = toolbar_state.layout_stable_frames,
-			"Frozen toolbar birth attempt."
+		let corner_radius = SELECTION_FLOW_CORNER_RADIUS_PX
+			.min(rect.width() / 2.0 - 0.25)
+			.min(rect.height() / 2.0 - 0.25)
+			.max(0.0);
+		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
+		let time = ctx.input(|i| i.time) as f32;
+		let sample_count = Self::selection_flow_sample_count(perimeter);
+		let seam_offset = if rect.width() > corner_radius * 2.0 {
+			(rect.width() - corner_radius * 2.0) * 0.5
+		} else {
+			0.0
+		};
+		let (samples, normals) = Self::selection_flow_cached_geometry(
+			selection_flow_geometry_cache,
+			rect,
+			corner_radius,
+			sample_count,
+			seam_offset,
 		);
+		let base_alpha_scale = match theme {
+			HudTheme::Light => 0.86,
+			HudTheme::Dark => 1.0,
+		};
+		let stroke_width = selection_flow_stroke.width_px.clamp(1.0, 8.0);
+		let rgb = selection_flow_stroke.rgb;
 
-		let needs_new_sample = match toolbar_state.layout_last_screen_size_points {
-			None => true,
-			Some(last) => {
-				let dx = (last.x - screen_size_points.x).abs();
-				let dy = (last.y - screen_size_points.y).abs();
+		if samples.is_empty() {
+			return;
+		}
 
-				dx > 0.5 || dy > 0.5
+		// A still border keeps the palette where the animation starts.
+		let flow_time =
+			if selection_flow_stroke.animated { time * SELECTION_FLOW_SPEED } else { 0.0 };
+		let phase = flow_time * 1.28 + 0.72;
+
+		match style {
+			SelectionFlowStyle::Band if selection_flow_stroke.animated => {
+				Self::selection_flow_draw_layer(
+					painter,
+					samples,
+					normals,
+					stroke_width,
+					base_alpha_scale * 0.52,
+					phase,
+					SELECTION_FLOW_CORE_FLOW_WIDTH,
+					theme,
+					rgb,
+				);
 			},
-		};
+			// Without motion a travelling band would just be a bright smudge, so the live border is
+			// drawn evenly instead.
+			SelectionFlowStyle::Band => Self::selection_flow_draw_layer_full_border(
+				painter,
+				samples,
+				normals,
+				stroke_width,
+				base_alpha_scale * 0.52,
+				phase,
+				1.0,
+				theme,
+				rgb,
+			),
+			SelectionFlowStyle::FullBorder => Self::selection_flow_draw_layer_full_border(
+				painter,
+				samples,
+				normals,
+				stroke_width,
+				base_alpha_scale * SELECTION_FLOW_FROZEN_ALPHA_SCALE,
+				phase,
+				SELECTION_FLOW_FROZEN_INTENSITY,
+				theme,
+				rgb,
+			),
+		}
+	}
 
-		if needs_new_sample {
-			toolbar_state.layout_last_screen_size_points = Some(screen_size_points);
-			toolbar_state.layout_stable_frames = 0;
-			toolbar_state.needs_redraw = true;
+	fn selection_flow_cached_geometry(
+		selection_flow_geometry_cache: &mut SelectionFlowGeometryCache,
+		rect: Rect,
+		corner_radius: f32,
+		sample_count: usize,
+		seam_offset: f32,
+	) -> (&[(Pos2, f32)], &[Vec2]) {
+		let key =
+			SelectionFlowGeometryCacheKey::new(rect, corner_radius, seam_offset, sample_count);
 
-			tracing::debug!(
-				monitor_id = monitor.id,
-				frozen_generation = state.frozen_generation,
-				new_screen_size_points = ?screen_size_points,
-				"Frozen toolbar waiting for stable screen rect (new sample)."
+		if selection_flow_geometry_cache.key == Some(key)
+			&& !selection_flow_geometry_cache.samples.is_empty()
+		{
+			return (
+				&selection_flow_geometry_cache.samples,
+				&selection_flow_geometry_cache.normals,
 			);
+		}
 
-			ctx.request_repaint();
+		let samples =
+			Self::selection_flow_path_samples(rect, corner_radius, sample_count, seam_offset);
+		let normals = Self::selection_flow_compute_normals(&samples);
 
-			return None;
-		}
-		if toolbar_state.layout_stable_frames < 1 {
-			toolbar_state.layout_stable_frames =
-				toolbar_state.layout_stable_frames.saturating_add(1);
-			toolbar_state.needs_redraw = true;
+		selection_flow_geometry_cache.key = Some(key);
+		selection_flow_geometry_cache.samples = samples;
+		selection_flow_geometry_cache.normals = normals;
 
-			tracing::debug!(
-				monitor_id = monitor.id,
-				frozen_generation = state.frozen_generation,
-				screen_size_points = ?screen_size_points,
-				stable_frames = toolbar_state.layout_stable_frames,
-				"Frozen toolbar waiting for stable screen rect (confirm)."
-			);
+		(&selection_flow_geometry_cache.samples, &selection_flow_geometry_cache.normals)
+	}
 
-			ctx.request_repaint();
+	fn selection_flow_compute_normals(samples: &[(Pos2, f32)]) -> Vec<Vec2> {
+		let n = samples.len();
 
-			return None;
+		if n == 0 {
+			return Vec::new();
 		}
 
-		let default_pos = Self::frozen_toolbar_default_pos(
-			screen_rect,
-			capture_rect,
-			toolbar_size,
-			toolbar_placement,
-		);
-
-		tracing::debug!(
-			monitor_id = monitor.id,
-			frozen_generation = state.frozen_generation,
-			toolbar_size_points = ?toolbar_size,
-			default_pos = ?default_pos,
-			"Frozen toolbar birth resolved."
-		);
+		let mut normals = Vec::with_capacity(n);
+		let mut first_non_zero = None;
 
-		toolbar_state.floating_position = Some(default_pos);
+		for i in 0..n {
+			let (current_point, _) = samples[i];
+			let (prev_point, _) = samples[(i + n - 1) % n];
+			let (next_point, _) = samples[(i + 1) % n];
+			let prev_tangent = current_point - prev_point;
+			let next_tangent = next_point - current_point;
+			let mut normal = Vec2::ZERO;
 
-		Some(default_pos)
-	}
+			if prev_tangent.length_sq() > f32::EPSILON {
+				let prev_len = prev_tangent.length();
 
-	fn frozen_toolbar_capture_rect(
-		state: &OverlayState,
-		monitor: MonitorRect,
-		screen_rect: Rect,
-	) -> Rect {
-		let Some(capture_rect) = state.frozen_capture_rect else {
-			return screen_rect;
-		};
-		let Some(frozen_monitor) = state.monitor else {
-			return screen_rect;
-		};
+				normal += Vec2::new(-prev_tangent.y / prev_len, prev_tangent.x / prev_len);
+			}
+			if next_tangent.length_sq() > f32::EPSILON {
+				let next_len = next_tangent.length();
 
-		if frozen_monitor != monitor {
-			return screen_rect;
-		}
+				normal += Vec2::new(-next_tangent.y / next_len, next_tangent.x / next_len);
+			}
+			if normal.length_sq() <= f32::EPSILON {
+				if next_tangent.length_sq() > f32::EPSILON {
+					let next_len = next_tangent.length();
 
-		let capture_rect = Rect::from_min_size(
-			Pos2::new(capture_rect.x as f32, capture_rect.y as f32),
-			Vec2::new(capture_rect.width as f32, capture_rect.height as f32),
-		);
+					normal = Vec2::new(-next_tangent.y / next_len, next_tangent.x / next_len);
+				} else if prev_tangent.length_sq() > f32::EPSILON {
+					let prev_len = prev_tangent.length();
 
-		capture_rect.intersect(screen_rect)
-	}
+					normal = Vec2::new(-prev_tangent.y / prev_len, prev_tangent.x / prev_len);
+				}
+			}
 
-	fn frozen_toolbar_default_pos(
-		screen_rect: Rect,
-		capture_rect: Rect,
-		toolbar_size: Vec2,
-		toolbar_placement: ToolbarPlacement,
-	) -> Pos2 {
-		let y = match toolbar_placement {
-			ToolbarPlacement::Bottom => {
-				let below_y = capture_rect.max.y + TOOLBAR_CAPTURE_GAP_PX;
-				let within_screen =
-					below_y + toolbar_size.y + TOOLBAR_SCREEN_MARGIN_PX <= screen_rect.max.y;
+			let normal = if normal.length_sq() > f32::EPSILON {
+				let normalized = normal / normal.length();
 
-				if within_screen {
-					below_y
-				} else {
-					capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y
+				if first_non_zero.is_none() && normalized.length_sq() > f32::EPSILON {
+					first_non_zero = Some(i);
 				}
-			},
-			ToolbarPlacement::Top => {
-				let above_y = capture_rect.min.y - TOOLBAR_CAPTURE_GAP_PX - toolbar_size.y;
-				let within_screen = above_y >= screen_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX;
 
-				if within_screen { above_y } else { capture_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX }
-			},
-		};
-		let min_x = screen_rect.min.x + TOOLBAR_SCREEN_MARGIN_PX;
-		let min_y = screen_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX;
-		let max_x = (screen_rect.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX).max(min_x);
-		let max_y = (screen_rect.max.y - toolbar_size.y - TOOLBAR_SCREEN_MARGIN_PX).max(min_y);
-		let x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(min_x, max_x);
-		let y = y.max(min_y).min(max_y);
+				normalized
+			} else {
+				Vec2::ZERO
+			};
 
-		Pos2::new(x, y)
-	}
+			normals.push(normal);
+		}
 
-	#[allow(clippy::too_many_arguments)]
-	fn draw_frozen_toolbar(
-		ctx: &egui::Context,
-		toolbar_state: &mut FrozenToolbarState,
-		monitor: MonitorRect,
-		screen_rect: Rect,
-		toolbar_pos: Pos2,
-		toolbar_size: Vec2,
-		theme: HudTheme,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-		cursor: Pos2,
-		left_button_down: bool,
-		hud_pill_out: &mut Option<HudPillGeometry>,
-	) {
-		Area::new(Id::new(format!("frozen-toolbar-{}", monitor.id)))
-			.order(Order::Foreground)
-			.fixed_pos(toolbar_pos)
-			.show(ctx, |ui| {
-				let (rect, response) =
-					ui.allocate_exact_size(toolbar_size, Sense::click_and_drag());
-				let body_fill = Self::tinted_hud_body_fill(
-					theme,
-					hud_blur_active,
-					hud_opaque,
-					hud_opacity,
-					hud_milk_amount,
-					hud_tint_hue,
-				);
-				let toolbar_frame =
-					Self::hud_pill_frame(theme, hud_opaque, hud_opacity, body_fill, false);
+		if let Some(first_idx) = first_non_zero {
+			let mut previous = normals[first_idx];
 
-				if response.drag_started() {
-					toolbar_state.dragging = true;
-					toolbar_state.floating_position = Some(toolbar_pos);
-					toolbar_state.drag_offset = cursor - toolbar_pos;
+			for normal in normals.iter_mut().skip(first_idx + 1) {
+				if normal.length_sq() > f32::EPSILON && normal.dot(previous) < 0.0 {
+					*normal = -*normal;
 				}
-				if toolbar_state.dragging && left_button_down {
-					let desired_pos = cursor - toolbar_state.drag_offset;
+				if normal.length_sq() > f32::EPSILON {
+					previous = *normal;
+				}
+			}
+			for normal in normals.iter_mut().take(first_idx).rev() {
+				if normal.length_sq() > f32::EPSILON && normal.dot(previous) < 0.0 {
+					*normal = -*normal;
+				}
+				if normal.length_sq() > f32::EPSILON {
+					previous = *normal;
+				}
+			}
 
-					toolbar_state.floating_position = Some(Self::clamp_toolbar_position(
-						screen_rect,
-						toolbar_size,
-						desired_pos,
-						TOOLBAR_SCREEN_MARGIN_PX,
-						TOOLBAR_SCREEN_MARGIN_PX,
-					));
-				} else if toolbar_state.dragging {
-					toolbar_state.dragging = false;
+			if normals[first_idx].length_sq() > f32::EPSILON
+				&& normals[(first_idx + n - 1) % n].length_sq() > f32::EPSILON
+				&& normals[first_idx].dot(normals[(first_idx + n - 1) % n]) < 0.0
+			{
+				for normal in &mut normals {
+					*normal = -*normal;
 				}
+			}
+		}
 
-				// Draw the capsule ourselves at the exact allocated rect. This keeps the visible pill
-				// and the blur rect perfectly aligned (no shrink-to-content surprises on first frame).
-				ui.painter().rect_filled(
-					rect,
-					f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
-					toolbar_frame.fill,
-				);
-				ui.painter().rect_stroke(
-					rect.shrink(0.5),
-					CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS),
-					toolbar_frame.stroke,
-					StrokeKind::Inside,
-				);
+		normals
+	}
 
-				let inner_stroke_color = match theme {
-					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-					HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
-				};
-				let inner_stroke = Stroke::new(1.0, inner_stroke_color);
-				let inner_rect = rect.shrink(1.0);
+	#[allow(clippy::too_many_arguments)]
+	fn selection_flow_draw_layer(
+		painter: &Painter,
+		samples: &[(Pos2, f32)],
+		normals: &[Vec2],
+		line_width: f32,
+		alpha_scale: f32,
+		phase: f32,
+		flow_band_width: f32,
+		theme: HudTheme,
+		rgb: Option<[u8; 3]>,
+	) {
+		if samples.is_empty() || normals.is_empty() || samples.len() != normals.len() {
+			return;
+		}
 
-				ui.painter().rect_stroke(
-					inner_rect,
-					CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS.saturating_sub(1)),
-					inner_stroke,
-					StrokeKind::Inside,
-				);
+		let half = (line_width * 0.5).max(0.1);
+		let n = samples.len();
+		let mut mesh = Mesh::default();
 
-				let inner_rect = rect.shrink2(egui::vec2(
-					HUD_PILL_INNER_MARGIN_X_POINTS,
-					HUD_PILL_INNER_MARGIN_Y_POINTS,
-				));
-				let _ = ui.scope_builder(UiBuilder::new().max_rect(inner_rect), |ui| {
-					ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-						ui.spacing_mut().item_spacing = egui::vec2(4.0, 0.0);
+		for i in 0..n {
+			let (current_point, t) = samples[i];
+			let movement = Self::selection_flow_flow_band(t, phase, flow_band_width);
+			let intensity = SELECTION_FLOW_FLOW_BOOST * movement;
+			let color = Self::selection_flow_color(t + phase, theme, alpha_scale, intensity, rgb);
+			let normal = normals[i] * half;
 
-						Self::render_frozen_toolbar_controls(ui, toolbar_state, theme);
-					});
-				});
+			mesh.colored_vertex(current_point + normal, color);
+			mesh.colored_vertex(current_point - normal, color);
+		}
+		for i in 0..n {
+			let i0 = (i * 2) as u32;
+			let i1 = ((i * 2) + 1) as u32;
+			let n0 = (((i + 1) % n) * 2) as u32;
+			let n1 = (((i + 1) % n) * 2 + 1) as u32;
 
-				*hud_pill_out = Some(HudPillGeometry {
-					rect,
-					radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
-				});
-			});
+			mesh.add_triangle(i0, i1, n0);
+			mesh.add_triangle(i1, n1, n0);
+		}
+
+		painter.add(Shape::Mesh(mesh.into()));
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn render_frozen_toolbar_controls(
-		ui: &mut Ui,
-		toolbar_state: &mut FrozenToolbarState,
+	fn selection_flow_draw_layer_full_border(
+		painter: &Painter,
+		samples: &[(Pos2, f32)],
+		normals: &[Vec2],
+		line_width: f32,
+		alpha_scale: f32,
+		phase: f32,
+		intensity: f32,
 		theme: HudTheme,
+		rgb: Option<[u8; 3]>,
 	) {
-		if toolbar_state.selected_tool == FrozenToolbarTool::Scroll {
-			toolbar_state.selected_tool = FrozenToolbarTool::Pointer;
+		if samples.is_empty() || normals.is_empty() || samples.len() != normals.len() {
+			return;
 		}
 
-		let tools = Self::frozen_toolbar_tools(toolbar_state);
-		let button_size = FROZEN_TOOLBAR_BUTTON_SIZE_POINTS;
-		let button_font_size = 18.0;
-		let item_spacing = FROZEN_TOOLBAR_ITEM_SPACING_POINTS;
-		let hit_area_inset = 5.0;
-		let (normal_color, hover_color, selected_color, hover_bg, selected_bg, selected_border) =
-			Self::frozen_toolbar_colors(theme);
+		let half = (line_width * 0.5).max(0.1);
+		let n = samples.len();
+		let mut mesh = Mesh::default();
 
-		ui.horizontal_centered(|ui| {
-			ui.spacing_mut().item_spacing.x = item_spacing;
+		for i in 0..n {
+			let (current_point, t) = samples[i];
+			let color = Self::selection_flow_color(t + phase, theme, alpha_scale, intensity, rgb);
+			let normal = normals[i] * half;
 
-			for tool in tools {
-				let is_mode_tool = tool.is_mode_tool();
-				let response =
-					ui.allocate_response(Vec2::new(button_size, button_size), Sense::click());
-				let hovered = response.hovered();
-				let response = response.on_hover_text(tool.label());
-				let hover_anim: f32 = if hovered { 1.0 } else { 0.0 };
+			mesh.colored_vertex(current_point + normal, color);
+			mesh.colored_vertex(current_point - normal, color);
+		}
+		for i in 0..n {
+			let i0 = (i * 2) as u32;
+			let i1 = ((i * 2) + 1) as u32;
+			let n0 = (((i + 1) % n) * 2) as u32;
+			let n1 = (((i + 1) % n) * 2 + 1) as u32;
 
-				if response.clicked() {
-					let tool = *tool;
+			mesh.add_triangle(i0, i1, n0);
+			mesh.add_triangle(i1, n1, n0);
+		}
 
-					if is_mode_tool {
-						toolbar_state.selected_tool = tool;
-					} else {
-						toolbar_state.pending_action = Some(tool);
-					}
+		painter.add(Shape::Mesh(mesh.into()));
+	}
 
-					toolbar_state.needs_redraw = true;
-				}
+	fn selection_flow_flow_band(progress: f32, phase: f32, band_width: f32) -> f32 {
+		let width = band_width.clamp(0.001, 0.5);
+		let distance = (progress - phase).rem_euclid(1.0);
+		let distance = distance.min(1.0 - distance);
+		let normalized = (distance / width).min(1.0);
 
-				let selected = is_mode_tool && *tool == toolbar_state.selected_tool;
-				let selected_anim: f32 = if selected { 1.0 } else { 0.0 };
-				let glow = hover_anim.max(selected_anim);
-				let icon_font = if selected {
-					FontFamily::Name("phosphor-fill".into())
-				} else {
-					FontFamily::Proportional
-				};
-				let mut icon_color = normal_color;
-				let mut bg_color = Color32::from_rgba_unmultiplied(255, 255, 255, 0);
-				let mut border_alpha = 0.0;
+		(1.0 - normalized).powf(2.0)
+	}
 
-				if selected_anim > 0.0 {
-					icon_color = Self::blend_color(icon_color, selected_color, selected_anim);
-					bg_color = Self::blend_color(bg_color, selected_bg, selected_anim);
-					border_alpha = selected_anim;
-				}
-				if hover_anim > 0.0 {
-					icon_color = Self::blend_color(icon_color, hover_color, hover_anim);
-					bg_color =
-						Self::blend_color(bg_color, hover_bg, hover_anim * (1.0 - selected_anim));
-				}
-				if glow > 0.0 {
-					let bg_rect = response.rect.shrink(hit_area_inset);
+	fn selection_flow_sample_count(perimeter: f32) -> usize {
+		if perimeter <= 0.0 || !perimeter.is_finite() {
+			return SELECTION_FLOW_MIN_SEGMENTS;
+		}
 
-					ui.painter().rect_filled(bg_rect, 8.0, bg_color);
-				}
-				if border_alpha > 0.0 {
-					let selected_border = Color32::from_rgba_unmultiplied(
-						selected_border.r(),
-						selected_border.g(),
-						selected_border.b(),
-						(selected_border.a() as f32 * border_alpha).round() as u8,
-					);
-
-					ui.painter().rect_stroke(
-						response.rect.shrink(hit_area_inset),
-						8.0,
-						Stroke::new(1.0, selected_border),
-						StrokeKind::Inside,
-					);
-				}
+		let by_step = (perimeter / SELECTION_FLOW_SAMPLE_STEP_PX).ceil() as usize;
 
-				ui.painter().text(
-					response.rect.center(),
-					Align2::CENTER_CENTER,
-					tool.icon(),
-					FontId::new(button_font_size, icon_font),
-					icon_color,
-				);
-			}
-		});
+		by_step.clamp(SELECTION_FLOW_MIN_SEGMENTS, SELECTION_FLOW_MAX_SEGMENTS)
 	}
 
-	fn frozen_toolbar_colors(
-		theme: HudTheme,
-	) -> (Color32, Color32, Color32, Color32, Color32, Color32) {
-		let (normal_color, hover_color, selected_color) = match theme {
-			HudTheme::Dark => (
-				Color32::from_rgba_unmultiplied(255, 255, 255, 160),
-				Color32::from_rgba_unmultiplied(255, 255, 255, 222),
-				Color32::from_rgba_unmultiplied(255, 255, 255, 255),
-			),
-			HudTheme::Light => (
-				Color32::from_rgba_unmultiplied(28, 28, 32, 182),
-				Color32::from_rgba_unmultiplied(28, 28, 32, 220),
-				Color32::from_rgba_unmultiplied(28, 28, 32, 255),
-			),
-		};
-		let hover_bg = match theme {
-			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 20),
-			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 20),
-		};
-		let selected_bg = match theme {
-			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 28),
-			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 24),
-		};
-		let selected_border = match theme {
-			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 82),
-			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 72),
-		};
+	fn selection_flow_path_samples(
+		rect: Rect,
+		corner_radius: f32,
+		sample_count: usize,
+		start_offset: f32,
+	) -> Vec<(Pos2, f32)> {
+		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
 
-		(normal_color, hover_color, selected_color, hover_bg, selected_bg, selected_border)
+		if perimeter <= 0.0 {
+			return Vec::new();
+		}
+
+		let start = (start_offset / perimeter).rem_euclid(1.0);
+
+		(0..sample_count)
+			.map(|index| {
+				let t = (index as f32 + 0.5) / sample_count as f32;
+				let progress = (t + start).rem_euclid(1.0);
+
+				(
+					Self::selection_flow_sample_at_distance(
+						rect,
+						corner_radius,
+						perimeter * progress,
+					),
+					t,
+				)
+			})
+			.collect()
 	}
 
-	fn blend_color(a: Color32, b: Color32, t: f32) -> Color32 {
-		let t = t.clamp(0.0, 1.0);
-		let u = 1.0 - t;
+	fn selection_flow_sample_at_distance(rect: Rect, corner_radius: f32, distance: f32) -> Pos2 {
+		if corner_radius <= f32::EPSILON {
+			let perimeter = Self::selection_flow_perimeter(rect, 0.0);
+			let keep = distance.rem_euclid(perimeter);
+			let edge_top = rect.width();
+			let edge_right = rect.height();
 
-		Color32::from_rgba_unmultiplied(
-			((f32::from(a.r()) * u + f32::from(b.r()) * t).round().clamp(0.0, 255.0)) as u8,
-			((f32::from(a.g()) * u + f32::from(b.g()) * t).round().clamp(0.0, 255.0)) as u8,
-			((f32::from(a.b()) * u + f32::from(b.b()) * t).round().clamp(0.0, 255.0)) as u8,
-			((f32::from(a.a()) * u + f32::from(b.a()) * t).round().clamp(0.0, 255.0)) as u8,
-		)
+			if keep < edge_top {
+				return Pos2::new(rect.min.x + keep, rect.min.y);
+			}
+			if keep < edge_top + edge_right {
+				return Pos2::new(rect.max.x, rect.min.y + (keep - edge_top));
+			}
+			if keep < edge_top * 2.0 + edge_right {
+				return Pos2::new(rect.max.x - (keep - edge_top - edge_right), rect.max.y);
+			}
+
+			return Pos2::new(rect.min.x, rect.max.y - (keep - edge_top * 2.0 - edge_right));
+		}
+
+		let x0 = rect.min.x;
+		let x1 = rect.max.x;
+		let y0 = rect.min.y;
+		let y1 = rect.max.y;
+		let perimeter = Self::selection_flow_perimeter(rect, corner_radius);
+		let remain = distance.rem_euclid(perimeter);
+		let edge_top_len = (rect.width() - corner_radius * 2.0).max(0.0);
+		let edge_right_len = (rect.height() - corner_radius * 2.0).max(0.0);
+		let corner_len = std::f32::consts::FRAC_PI_2 * corner_radius;
+
+		if remain < edge_top_len {
+			return Pos2::new(x0 + corner_radius + remain, y0);
+		}
+
+		let mut offset = remain - edge_top_len;
+
+		if offset < corner_len {
+			let angle = -std::f32::consts::FRAC_PI_2 + offset / corner_radius;
+
+			return Pos2::new(
+				x1 - corner_radius + corner_radius * angle.cos(),
+				y0 + corner_radius + corner_radius * angle.sin(),
+			);
+		}
+
+		offset -= corner_len;
+
+		if offset < edge_right_len {
+			return Pos2::new(x1, y0 + corner_radius + offset);
+		}
+
+		offset -= edge_right_len;
+
+		if offset < corner_len {
+			let angle = offset / corner_radius;
+
+			return Pos2::new(
+				x1 - corner_radius + corner_radius * angle.cos(),
+				y1 - corner_radius + corner_radius * angle.sin(),
+			);
+		}
+
+		offset -= corner_len;
+
+		if offset < edge_top_len {
+			return Pos2::new(x1 - corner_radius - offset, y1);
+		}
+
+		offset -= edge_top_len;
+
+		if offset < corner_len {
+			let angle = std::f32::consts::FRAC_PI_2 + offset / corner_radius;
+
+			return Pos2::new(
+				x0 + corner_radius + corner_radius * angle.cos(),
+				y1 - corner_radius + corner_radius * angle.sin(),
+			);
+		}
+
+		offset -= corner_len;
+
+		if offset < edge_right_len {
+			return Pos2::new(x0, y1 - corner_radius - offset);
+		}
+
+		offset -= edge_right_len;
+
+		if offset < corner_len {
+			let angle = std::f32::consts::PI + offset / corner_radius;
+
+			return Pos2::new(
+				x0 + corner_radius + corner_radius * angle.cos(),
+				y0 + corner_radius + corner_radius * angle.sin(),
+			);
+		}
+
+		Pos2::new(x0 + corner_radius, y0)
 	}
 
-	fn clamp_toolbar_position(
-		screen_rect: Rect,
-		toolbar_size: Vec2,
-		cursor: Pos2,
-		side_margin: f32,
-		top_margin: f32,
-	) -> Pos2 {
-		let min_x = screen_rect.min.x + side_margin;
-		let min_y = screen_rect.min.y + top_margin;
-		let max_x = (screen_rect.max.x - toolbar_size.x - side_margin).max(min_x);
-		let max_y = (screen_rect.max.y - toolbar_size.y - top_margin * 0.5).max(min_y);
+	fn selection_flow_perimeter(rect: Rect, corner_radius: f32) -> f32 {
+		let edge_top_len = (rect.width() - corner_radius * 2.0).max(0.0);
+		let edge_right_len = (rect.height() - corner_radius * 2.0).max(0.0);
+		let corner_len = std::f32::consts::FRAC_PI_2 * corner_radius;
 
-		Pos2::new(cursor.x.clamp(min_x, max_x.max(min_x)), cursor.y.clamp(min_y, max_y.max(min_y)))
+		2.0 * (edge_top_len + edge_right_len) + 4.0 * corner_len
 	}
 
-	fn should_draw_hud(state: &OverlayState, monitor: MonitorRect) -> bool {
-		if cfg!(target_os = "macos") && matches!(state.mode, OverlayMode::Frozen) {
-			return true;
-		}
+	fn selection_flow_color(
+		progress: f32,
+		theme: HudTheme,
+		alpha_scale: f32,
+		intensity: f32,
+		rgb: Option<[u8; 3]>,
+	) -> Color32 {
+		let [r, g, b] = rgb.unwrap_or_else(|| selection_flow_palette_rgb(progress));
+		let theme_alpha = match theme {
+			HudTheme::Dark => 1.0,
+			HudTheme::Light => 0.82,
+		};
+		let alpha = (255.0 * alpha_scale * intensity * theme_alpha).clamp(0.0, 255.0);
 
-		!matches!(state.mode, OverlayMode::Frozen)
-			|| state.monitor != Some(monitor)
-			|| state.frozen_image.is_some()
-			|| state.error_message.is_some()
+		Color32::from_rgba_unmultiplied(r, g, b, alpha as u8)
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn render_hud(
-		&mut self,
+	fn render_frozen_toolbar_ui(
 		ctx: &egui::Context,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		cursor: GlobalPoint,
-		local_cursor: Pos2,
-		hud_compact: bool,
-		hud_anchor: HudAnchor,
-		show_alt_hint_keycap: bool,
+		theme: HudTheme,
+		toolbar_placement: ToolbarPlacement,
 		hud_blur_active: bool,
 		hud_opaque: bool,
 		hud_opacity: f32,
 		hud_milk_amount: f32,
 		hud_tint_hue: f32,
-		theme: HudTheme,
+		toolbar_state: Option<&mut FrozenToolbarState>,
+		pointer_state: Option<FrozenToolbarPointerState>,
+		export_preview: Option<(TextureId, Vec2)>,
 		hud_pill_out: &mut Option<HudPillGeometry>,
 	) {
-		let (hud_x, hud_y) = match hud_anchor {
-			HudAnchor::Cursor => (local_cursor.x + 14.0, local_cursor.y + 14.0),
+		let Some(toolbar_state) = toolbar_state else {
+			return;
 		};
 
-		Area::new("hud".into()).order(Order::Foreground).fixed_pos(Pos2::new(hud_x, hud_y)).show(
-			ctx,
-			|ui| {
-				self.render_hud_frame(
-					ui,
-					state,
-					monitor,
-					cursor,
-					hud_compact,
-					show_alt_hint_keycap,
-					hud_blur_active,
-					hud_opaque,
-					hud_opacity,
-					hud_milk_amount,
-					hud_tint_hue,
-					theme,
-					hud_pill_out,
-				);
-			},
-		);
-	}
+		if !matches!(state.mode, OverlayMode::Frozen) || !toolbar_state.visible {
+			return;
+		}
+		if state.monitor != Some(monitor) {
+			return;
+		}
 
-	#[allow(clippy::too_many_arguments)]
-	fn render_hud_frame(
-		&mut self,
-		ui: &mut Ui,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		cursor: GlobalPoint,
-		hud_compact: bool,
-		show_alt_hint_keycap: bool,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-		theme: HudTheme,
-		hud_pill_out: &mut Option<HudPillGeometry>,
-	) {
-		let body_fill = Self::tinted_hud_body_fill(
+		let (cursor, left_button_down) = if let Some(pointer_state) = pointer_state {
+			(pointer_state.cursor_local, pointer_state.left_button_down)
+		} else {
+			toolbar_state.dragging = false;
+
+			(Pos2::new(-1.0, -1.0), false)
+		};
+		let toolbar_size = Self::frozen_toolbar_size(toolbar_state);
+		let screen_rect = ctx.input(|i| i.viewport_rect());
+		let capture_rect = Self::frozen_toolbar_capture_rect(state, monitor, screen_rect);
+		let Some(toolbar_pos) = Self::resolve_frozen_toolbar_birth(
+			ctx,
+			state,
+			monitor,
+			toolbar_state,
+			screen_rect,
+			capture_rect,
+			toolbar_size,
+			toolbar_placement,
+		) else {
+			return;
+		};
+
+		Self::draw_frozen_toolbar(
+			ctx,
+			toolbar_state,
+			monitor,
+			screen_rect,
+			toolbar_pos,
+			toolbar_size,
 			theme,
 			hud_blur_active,
 			hud_opaque,
 			hud_opacity,
 			hud_milk_amount,
 			hud_tint_hue,
+			cursor,
+			left_button_down,
+			export_preview,
+			hud_pill_out,
 		);
-		let pill_frame =
-			Self::hud_pill_frame(theme, hud_opaque, hud_opacity, body_fill, !hud_compact);
-		let inner = pill_frame.show(ui, |ui| {
-			ui.spacing_mut().item_spacing = egui::vec2(10.0, 6.0);
-
-			if let Some(err) = &state.error_message {
-				let err_color = match theme {
-					HudTheme::Dark => Color32::from_rgba_unmultiplied(235, 235, 245, 235),
-					HudTheme::Light => Color32::from_rgba_unmultiplied(28, 28, 32, 235),
-				};
-
-				ui.label(RichText::new(err).color(err_color).monospace());
-			} else {
-				Self::render_hud_content(ui, state, monitor, cursor, show_alt_hint_keycap, theme);
-			}
-		});
-		let pill_rect = inner.response.rect;
+	}
 
-		*hud_pill_out = Some(HudPillGeometry {
-			rect: pill_rect,
-			radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
-		});
+	fn frozen_toolbar_tools(toolbar_state: &FrozenToolbarState) -> &'static [FrozenToolbarTool] {
+		const TOOLS_SCROLL_MODE: [FrozenToolbarTool; 3] =
+			[FrozenToolbarTool::Copy, FrozenToolbarTool::Save, FrozenToolbarTool::Done];
+		const TOOLS_WITH_SCROLL: [FrozenToolbarTool; 15] = [
+			FrozenToolbarTool::Pointer,
+			FrozenToolbarTool::Pen,
+			FrozenToolbarTool::Highlighter,
+			FrozenToolbarTool::Text,
+			FrozenToolbarTool::Mosaic,
+			FrozenToolbarTool::Stamp,
+			FrozenToolbarTool::Callout,
+			FrozenToolbarTool::Undo,
+			FrozenToolbarTool::Redo,
+			FrozenToolbarTool::Scroll,
+			FrozenToolbarTool::LastRegion,
+			FrozenToolbarTool::Backdrop,
+			FrozenToolbarTool::Copy,
+			FrozenToolbarTool::Save,
+			FrozenToolbarTool::Done,
+		];
+		const TOOLS_WITHOUT_SCROLL: [FrozenToolbarTool; 14] = [
+			FrozenToolbarTool::Pointer,
+			FrozenToolbarTool::Pen,
+			FrozenToolbarTool::Highlighter,
+			FrozenToolbarTool::Text,
+			FrozenToolbarTool::Mosaic,
+			FrozenToolbarTool::Stamp,
+			FrozenToolbarTool::Callout,
+			FrozenToolbarTool::Undo,
+			FrozenToolbarTool::Redo,
+			FrozenToolbarTool::LastRegion,
+			FrozenToolbarTool::Backdrop,
+			FrozenToolbarTool::Copy,
+			FrozenToolbarTool::Save,
+			FrozenToolbarTool::Done,
+		];
 
-		if hud_compact {
-			return;
+		if toolbar_state.scroll_capture_active {
+			&TOOLS_SCROLL_MODE
+		} else if toolbar_state.scroll_capture_available {
+			&TOOLS_WITH_SCROLL
+		} else {
+			&TOOLS_WITHOUT_SCROLL
 		}
+	}
 
-		let inner_stroke_color = match theme {
-			HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-			HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
-		};
-		let inner_stroke = Stroke::new(1.0, inner_stroke_color);
-		let inner_rect = pill_rect.shrink(1.0);
-
-		ui.painter().rect_stroke(
-			inner_rect,
-			CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS.saturating_sub(1)),
-			inner_stroke,
-			StrokeKind::Inside,
-		);
-
-		if !hud_compact {
-			self.render_loupe_tile(
-				ui,
-				state,
-				pill_rect,
-				hud_blur_active,
-				hud_opaque,
-				body_fill,
-				theme,
-			);
-		}
+	/// The preview slot is reserved from the first frame, so the toolbar does not grow once the
+	/// worker's render arrives.
+	fn frozen_toolbar_shows_export_preview(toolbar_state: &FrozenToolbarState) -> bool {
+		toolbar_state.export_preview_enabled && !toolbar_state.scroll_capture_active
 	}
 
-	fn hud_pill_frame(
-		theme: HudTheme,
-		_hud_opaque: bool,
-		_hud_opacity: f32,
-		body_fill: Color32,
-		with_shadow: bool,
-	) -> Frame {
-		let outer_stroke_color = match theme {
-			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
-			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-		};
-		let pill_shadow = if with_shadow {
-			egui::epaint::Shadow {
-				offset: [0, 0],
-				blur: 10,
-				spread: 0,
-				color: match theme {
-					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
-					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
-				},
-			}
+	fn frozen_toolbar_size(toolbar_state: &FrozenToolbarState) -> Vec2 {
+		let tool_count = Self::frozen_toolbar_tools(toolbar_state).len() as f32;
+		let spacing_count = (tool_count - 1.0).max(0.0);
+		let preview_width = if Self::frozen_toolbar_shows_export_preview(toolbar_state) {
+			EXPORT_PREVIEW_SLOT_WIDTH_POINTS + FROZEN_TOOLBAR_ITEM_SPACING_POINTS
 		} else {
-			egui::Shadow::NONE
+			0.0
 		};
+		let width = tool_count * FROZEN_TOOLBAR_BUTTON_SIZE_POINTS
+			+ spacing_count * FROZEN_TOOLBAR_ITEM_SPACING_POINTS
+			+ preview_width
+			+ 2.0 * HUD_PILL_INNER_MARGIN_X_POINTS
+			+ 2.0 * HUD_PILL_STROKE_WIDTH_POINTS;
+		let height = toolbar_state.pill_height_points.unwrap_or(TOOLBAR_EXPANDED_HEIGHT_PX);
 
-		Frame {
-			fill: body_fill,
-			stroke: Stroke::new(1.0, outer_stroke_color),
-			shadow: pill_shadow,
-			corner_radius: CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS),
-			inner_margin: Margin::symmetric(12, 8),
-			..Frame::default()
-		}
+		Vec2::new(width, height)
 	}
 
-	fn render_hud_content(
-		ui: &mut Ui,
+	#[allow(clippy::too_many_arguments)]
+	fn resolve_frozen_toolbar_birth(
+		ctx: &egui::Context,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		cursor: GlobalPoint,
-		show_alt_hint_keycap: bool,
-		theme: HudTheme,
-	) {
-		let (label_color, secondary_color) = match theme {
-			HudTheme::Dark => (
-				Color32::from_rgba_unmultiplied(235, 235, 245, 235),
-				Color32::from_rgba_unmultiplied(235, 235, 245, 150),
-			),
-			HudTheme::Light => (
-				Color32::from_rgba_unmultiplied(28, 28, 32, 235),
-				Color32::from_rgba_unmultiplied(28, 28, 32, 160),
-			),
+		toolbar_state: &mut FrozenToolbarState,
+		screen_rect: Rect,
+		capture_rect: Rect,
+		toolbar_size: Vec2,
+		toolbar_placement: ToolbarPlacement,
+	) -> Option<Pos2> {
+		if let Some(pos) = toolbar_state.floating_position {
+			return Some(pos);
+		}
+
+		let screen_size_points = screen_rect.size();
+
+		tracing::trace!(
+			monitor_id = monitor.id,
+			frozen_generation = state.frozen_generation,
+			screen_rect = ?screen_rect,
+			screen_size_points = ?screen_size_points,
+			pixels_per_point = ctx.pixels_per_point(),
+			last_screen_size_points = ?toolbar_state.layout_last_screen_size_points,
+			stable_frames = toolbar_state.layout_stable_frames,
+			"Frozen toolbar birth attempt."
+		);
+
+		let needs_new_sample = match toolbar_state.layout_last_screen_size_points {
+			None => true,
+			Some(last) => {
+				let dx = (last.x - screen_size_points.x).abs();
+				let dy = (last.y - screen_size_points.y).abs();
+
+				dx > 0.5 || dy > 0.5
+			},
 		};
-		let pos_text = hud_helpers::format_live_hud_position_text(monitor, cursor);
-		let (hex_text, rgb_text) = hud_helpers::format_live_hud_rgb_text(state.rgb);
-		let swatch_size = egui::vec2(10.0, 10.0);
 
-		ui.vertical(|ui| {
-			ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-				ui.label(RichText::new(pos_text).color(label_color).monospace());
-				ui.label(RichText::new("•").color(secondary_color).monospace());
+		if needs_new_sample {
+			toolbar_state.layout_last_screen_size_points = Some(screen_size_points);
+			toolbar_state.layout_stable_frames = 0;
+			toolbar_state.needs_redraw = true;
 
-				let (rect, _) = ui.allocate_exact_size(swatch_size, Sense::hover());
-				let swatch_color = match state.rgb {
-					Some(rgb) => Color32::from_rgb(rgb.r, rgb.g, rgb.b),
-					None => Color32::from_rgba_unmultiplied(255, 255, 255, 26),
-				};
+			tracing::debug!(
+				monitor_id = monitor.id,
+				frozen_generation = state.frozen_generation,
+				new_screen_size_points = ?screen_size_points,
+				"Frozen toolbar waiting for stable screen rect (new sample)."
+			);
 
-				ui.painter().rect_filled(rect, 3.0, swatch_color);
-				ui.painter().rect_stroke(
-					rect,
-					3.0,
-					Stroke::new(
-						1.0,
-						match theme {
-							HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 36),
-							HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-						},
-					),
-					StrokeKind::Inside,
-				);
-				ui.label(RichText::new(hex_text).color(label_color).monospace());
-				ui.label(RichText::new(rgb_text).color(secondary_color).monospace());
+			ctx.request_repaint();
 
-				if show_alt_hint_keycap {
-					let alt_active = state.alt_held;
-					let (keycap_fill, keycap_stroke, keycap_text) = match theme {
-						HudTheme::Dark if alt_active => (
-							Color32::from_rgba_unmultiplied(255, 255, 255, 40),
-							Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 70)),
-							label_color,
-						),
-						HudTheme::Dark => (
-							Color32::from_rgba_unmultiplied(255, 255, 255, 18),
-							Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 30)),
-							secondary_color,
-						),
-						HudTheme::Light if alt_active => (
-							Color32::from_rgba_unmultiplied(0, 0, 0, 22),
-							Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 64)),
-							label_color,
-						),
-						HudTheme::Light => (
-							Color32::from_rgba_unmultiplied(0, 0, 0, 12),
-							Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 32)),
-							secondary_color,
-						),
-					};
-
-					Frame {
-						fill: keycap_fill,
-						stroke: keycap_stroke,
-						corner_radius: CornerRadius::same(6),
-						inner_margin: Margin::symmetric(6, 2),
-						..Frame::default()
-					}
-					.show(ui, |ui| {
-						ui.label(RichText::new("Alt").color(keycap_text).monospace());
-					});
-				}
-			});
-		});
-	}
-
-	#[allow(clippy::too_many_arguments)]
-	fn render_loupe_tile(
-		&mut self,
-		ui: &mut Ui,
-		state: &OverlayState,
-		pill_rect: Rect,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		body_fill: Color32,
-		theme: HudTheme,
-	) {
-		let ctx = ui.ctx().clone();
-
-		self.loupe_tile = None;
-
-		if !state.alt_held {
-			return;
+			return None;
 		}
+		if toolbar_state.layout_stable_frames < 1 {
+			toolbar_state.layout_stable_frames =
+				toolbar_state.layout_stable_frames.saturating_add(1);
+			toolbar_state.needs_redraw = true;
 
-		const CELL: f32 = 10.0;
-
-		let side = hud_helpers::stable_live_loupe_side_points(state, CELL);
-		let tile_padding = Margin::same(10);
-		let tile_w = side + (tile_padding.left as f32) + (tile_padding.right as f32);
-		let tile_h = side + (tile_padding.top as f32) + (tile_padding.bottom as f32);
-		let screen = ctx.content_rect();
-		let gap = 10.0;
-		let mut x = pill_rect.min.x;
+			tracing::debug!(
+				monitor_id = monitor.id,
+				frozen_generation = state.frozen_generation,
+				screen_size_points = ?screen_size_points,
+				stable_frames = toolbar_state.layout_stable_frames,
+				"Frozen toolbar waiting for stable screen rect (confirm)."
+			);
 
-		x = x.clamp(screen.min.x + 6.0, (screen.max.x - tile_w - 6.0).max(screen.min.x + 6.0));
+			ctx.request_repaint();
 
-		let below_y = pill_rect.max.y + gap;
-		let above_y = pill_rect.min.y - gap - tile_h;
-		let mut y = if below_y + tile_h <= screen.max.y { below_y } else { above_y };
+			return None;
+		}
 
-		y = y.clamp(screen.min.y + 6.0, (screen.max.y - tile_h - 6.0).max(screen.min.y + 6.0));
+		let default_pos = Self::frozen_toolbar_default_pos(
+			screen_rect,
+			capture_rect,
+			toolbar_size,
+			toolbar_placement,
+		);
 
-		let pos = Pos2::new(x, y);
-		let tile = Area::new(Id::new("rsnap-loupe-tile"))
-			.order(Order::Foreground)
-			.fixed_pos(pos)
-			.show(&ctx, |ui| {
-				let _ = hud_blur_active;
-				let fill = body_fill;
-				let outer_stroke_color = match theme {
-					HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
-					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-				};
-				let outer_stroke = Stroke::new(1.0, outer_stroke_color);
-				let shadow = egui::epaint::Shadow {
-					offset: [0, 0],
-					blur: 10,
-					spread: 0,
-					color: match theme {
-						HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
-						HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
-					},
-				};
-				let frame = Frame {
-					fill,
-					stroke: outer_stroke,
-					shadow,
-					corner_radius: CornerRadius::same(18),
-					inner_margin: tile_padding,
-					..Frame::default()
-				};
+		tracing::debug!(
+			monitor_id = monitor.id,
+			frozen_generation = state.frozen_generation,
+			toolbar_size_points = ?toolbar_size,
+			default_pos = ?default_pos,
+			"Frozen toolbar birth resolved."
+		);
 
-				frame.show(ui, |ui| {
-					ui.set_min_size(Vec2::new(side, side));
-					self.render_loupe(ui, state, hud_blur_active, hud_opaque, theme);
-				});
-			});
+		toolbar_state.floating_position = Some(default_pos);
 
-		self.loupe_tile = Some(tile.response.rect);
+		Some(default_pos)
 	}
 
-	fn render_loupe(
-		&mut self,
-		ui: &mut Ui,
+	fn frozen_toolbar_capture_rect(
 		state: &OverlayState,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		theme: HudTheme,
-	) {
-		const CELL: f32 = 10.0;
-
-		let mode = state.mode;
-
-		if matches!(mode, OverlayMode::Live) {
-			self.render_live_loupe(ui, state, CELL, hud_blur_active, hud_opaque, theme);
-		} else if matches!(mode, OverlayMode::Frozen)
-			&& (state.frozen_image.is_some() || state.loupe.is_some())
-		{
-			let Some(monitor) = state.monitor else {
-				return;
-			};
-			let Some(cursor) = state.cursor else {
-				return;
-			};
+		monitor: MonitorRect,
+		screen_rect: Rect,
+	) -> Rect {
+		let Some(capture_rect) = state.frozen_capture_rect else {
+			return screen_rect;
+		};
+		let Some(frozen_monitor) = state.monitor else {
+			return screen_rect;
+		};
 
-			self.render_frozen_loupe(
-				ui,
-				state,
-				monitor,
-				cursor,
-				CELL,
-				hud_blur_active,
-				hud_opaque,
-				theme,
-			);
+		if frozen_monitor != monitor {
+			return screen_rect;
 		}
-	}
 
-	fn sync_live_loupe_texture(
-		&mut self,
-		loupe: Option<&crate::state::LoupeSample>,
-	) -> Option<TextureId> {
-		let Some(loupe) = loupe else {
-			self.live_loupe_texture = None;
+		let capture_rect = Rect::from_min_size(
+			Pos2::new(capture_rect.x as f32, capture_rect.y as f32),
+			Vec2::new(capture_rect.width as f32, capture_rect.height as f32),
+		);
 
-			return None;
-		};
-		let patch_size_px = [loupe.patch.width() as usize, loupe.patch.height() as usize];
-		let patch_rgba = loupe.patch.as_raw();
+		capture_rect.intersect(screen_rect)
+	}
 
-		match self.live_loupe_texture.as_mut() {
-			Some(cached) if cached.patch_size_px == patch_size_px => {
-				if cached.rgba != *patch_rgba {
-					let color_image = ColorImage::from_rgba_unmultiplied(
-						[patch_size_px[0], patch_size_px[1]],
-						patch_rgba,
-					);
+	fn frozen_toolbar_default_pos(
+		screen_rect: Rect,
+		capture_rect: Rect,
+		toolbar_size: Vec2,
+		toolbar_placement: ToolbarPlacement,
+	) -> Pos2 {
+		let y = match toolbar_placement {
+			ToolbarPlacement::Bottom => {
+				let below_y = capture_rect.max.y + TOOLBAR_CAPTURE_GAP_PX;
+				let within_screen =
+					below_y + toolbar_size.y + TOOLBAR_SCREEN_MARGIN_PX <= screen_rect.max.y;
 
-					cached.texture.set(color_image, TextureOptions::NEAREST);
-					cached.rgba.clone_from(patch_rgba);
+				if within_screen {
+					below_y
+				} else {
+					capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y
 				}
 			},
-			_ => {
-				let color_image = ColorImage::from_rgba_unmultiplied(
-					[patch_size_px[0], patch_size_px[1]],
-					patch_rgba,
-				);
-				let texture = self.egui_ctx.load_texture(
-					String::from("live-loupe-image"),
-					color_image,
-					TextureOptions::NEAREST,
-				);
+			ToolbarPlacement::Top => {
+				let above_y = capture_rect.min.y - TOOLBAR_CAPTURE_GAP_PX - toolbar_size.y;
+				let within_screen = above_y >= screen_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX;
 
-				self.live_loupe_texture =
-					Some(LiveLoupeTexture { texture, patch_size_px, rgba: patch_rgba.clone() });
+				if within_screen { above_y } else { capture_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX }
 			},
-		}
+		};
+		let min_x = screen_rect.min.x + TOOLBAR_SCREEN_MARGIN_PX;
+		let min_y = screen_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX;
+		let max_x = (screen_rect.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX).max(min_x);
+		let max_y = (screen_rect.max.y - toolbar_size.y - TOOLBAR_SCREEN_MARGIN_PX).max(min_y);
+		let x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(min_x, max_x);
+		let y = y.max(min_y).min(max_y);
 
-		self.live_loupe_texture.as_ref().map(|cached| cached.texture.id())
+		Pos2::new(x, y)
 	}
 
-	fn render_live_loupe(
-		&mut self,
-		ui: &mut Ui,
-		state: &OverlayState,
-		cell: f32,
-		_hud_blur_active: bool,
-		hud_opaque: bool,
+	#[allow(clippy::too_many_arguments)]
+	fn draw_frozen_toolbar(
+		ctx: &egui::Context,
+		toolbar_state: &mut FrozenToolbarState,
+		monitor: MonitorRect,
+		screen_rect: Rect,
+		toolbar_pos: Pos2,
+		toolbar_size: Vec2,
 		theme: HudTheme,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		cursor: Pos2,
+		left_button_down: bool,
+		export_preview: Option<(TextureId, Vec2)>,
+		hud_pill_out: &mut Option<HudPillGeometry>,
 	) {
-		let fallback_side_px = state.loupe_patch_side_px.max(1);
-		let (w, h) = state
-			.loupe
-			.as_ref()
-			.map(|loupe| loupe.patch.dimensions())
-			.unwrap_or((fallback_side_px, fallback_side_px));
-		let side = hud_helpers::stable_live_loupe_side_points(state, cell);
-		let (rect, _) = ui.allocate_exact_size(Vec2::new(side, side), Sense::hover());
-		let body_fill = hud_helpers::hud_body_fill_srgba8(theme, hud_opaque);
-		let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 140));
-		let placeholder_fill =
-			Color32::from_rgba_unmultiplied(body_fill[0], body_fill[1], body_fill[2], 255);
-		let image_rect =
-			Rect::from_center_size(rect.center(), Vec2::new((w as f32) * cell, (h as f32) * cell));
+		Area::new(Id::new(format!("frozen-toolbar-{}", monitor.id)))
+			.order(Order::Foreground)
+			.fixed_pos(toolbar_pos)
+			.show(ctx, |ui| {
+				let (rect, response) =
+					ui.allocate_exact_size(toolbar_size, Sense::click_and_drag());
+				let body_fill = Self::tinted_hud_body_fill(
+					theme,
+					hud_blur_active,
+					hud_opaque,
+					hud_opacity,
+					hud_milk_amount,
+					hud_tint_hue,
+				);
+				let toolbar_frame =
+					Self::hud_pill_frame(theme, hud_opaque, hud_opacity, body_fill, false);
 
-		if let Some(texture_id) = self.sync_live_loupe_texture(state.loupe.as_ref()) {
-			ui.painter().rect_filled(rect, 3.0, placeholder_fill);
-			ui.painter().image(
-				texture_id,
-				image_rect,
-				Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(1.0, 1.0)),
-				Color32::WHITE,
-			);
-		} else {
-			ui.painter().rect_filled(rect, 3.0, placeholder_fill);
-		}
+				if response.drag_started() {
+					toolbar_state.dragging = true;
+					toolbar_state.floating_position = Some(toolbar_pos);
+					toolbar_state.drag_offset = cursor - toolbar_pos;
+				}
+				if toolbar_state.dragging && left_button_down {
+					let desired_pos = cursor - toolbar_state.drag_offset;
 
-		ui.painter().rect_stroke(rect, 3.0, stroke, StrokeKind::Outside);
+					toolbar_state.floating_position = Some(Self::clamp_toolbar_position(
+						screen_rect,
+						toolbar_size,
+						desired_pos,
+						TOOLBAR_SCREEN_MARGIN_PX,
+						TOOLBAR_SCREEN_MARGIN_PX,
+					));
+				} else if toolbar_state.dragging {
+					toolbar_state.dragging = false;
+				}
 
-		let center_x = (w / 2) as f32;
-		let center_y = (h / 2) as f32;
-		let center_min =
-			Pos2::new(image_rect.min.x + center_x * cell, image_rect.min.y + center_y * cell);
-		let center_rect = Rect::from_min_size(center_min, Vec2::splat(cell));
+				// Draw the capsule ourselves at the exact allocated rect. This keeps the visible pill
+				// and the blur rect perfectly aligned (no shrink-to-content surprises on first frame).
+				ui.painter().rect_filled(
+					rect,
+					f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
+					toolbar_frame.fill,
+				);
+				ui.painter().rect_stroke(
+					rect.shrink(0.5),
+					CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS),
+					toolbar_frame.stroke,
+					StrokeKind::Inside,
+				);
 
-		ui.painter().rect_stroke(
-			center_rect,
-			0.0,
-			Stroke::new(2.0, Color32::from_rgba_unmultiplied(255, 255, 255, 180)),
-			StrokeKind::Inside,
-		);
+				let inner_stroke_color = match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
+				};
+				let inner_stroke = Stroke::new(1.0, inner_stroke_color);
+				let inner_rect = rect.shrink(1.0);
+
+				ui.painter().rect_stroke(
+					inner_rect,
+					CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS.saturating_sub(1)),
+					inner_stroke,
+					StrokeKind::Inside,
+				);
+
+				let inner_rect = rect.shrink2(egui::vec2(
+					HUD_PILL_INNER_MARGIN_X_POINTS,
+					HUD_PILL_INNER_MARGIN_Y_POINTS,
+				));
+				let _ = ui.scope_builder(UiBuilder::new().max_rect(inner_rect), |ui| {
+					ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+						ui.spacing_mut().item_spacing = egui::vec2(4.0, 0.0);
+
+						Self::render_frozen_toolbar_controls(
+							ui,
+							toolbar_state,
+							theme,
+							export_preview,
+						);
+					});
+				});
+
+				*hud_pill_out = Some(HudPillGeometry {
+					rect,
+					radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
+				});
+			});
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn render_frozen_loupe(
-		&mut self,
+	fn render_frozen_toolbar_controls(
 		ui: &mut Ui,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		cursor: GlobalPoint,
-		cell: f32,
-		hud_blur_active: bool,
-		hud_opaque: bool,
+		toolbar_state: &mut FrozenToolbarState,
 		theme: HudTheme,
+		export_preview: Option<(TextureId, Vec2)>,
 	) {
-		if state.loupe.is_some() {
-			self.render_live_loupe(ui, state, cell, hud_blur_active, hud_opaque, theme);
-
-			return;
+		if toolbar_state.selected_tool == FrozenToolbarTool::Scroll {
+			toolbar_state.selected_tool = FrozenToolbarTool::Pointer;
 		}
 
-		const LOUPE_RADIUS_PX: i32 = 5;
-		const LOUPE_SIDE_PX: i32 = (LOUPE_RADIUS_PX * 2) + 1;
-
-		let side = (LOUPE_SIDE_PX as f32) * cell;
-		let (rect, _) = ui.allocate_exact_size(Vec2::new(side, side), Sense::hover());
-		let Some(image) = state.frozen_image.as_ref() else {
-			return;
-		};
-		let Some((center_x, center_y)) = monitor.local_u32_pixels(cursor) else {
-			return;
-		};
-		let (width, height) = image.dimensions();
-		let width = width as i32;
-		let height = height as i32;
-		let center_x = center_x as i32;
-		let center_y = center_y as i32;
-		let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 140));
-		let grid_stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 26));
-
-		for dy in -LOUPE_RADIUS_PX..=LOUPE_RADIUS_PX {
-			for dx in -LOUPE_RADIUS_PX..=LOUPE_RADIUS_PX {
-				let x = center_x + dx;
-				let y = center_y + dy;
-				let cell_x = dx + LOUPE_RADIUS_PX;
-				let cell_y = dy + LOUPE_RADIUS_PX;
-				let cell_min = Pos2::new(
-					rect.min.x + (cell_x as f32) * cell,
-					rect.min.y + (cell_y as f32) * cell,
-				);
-				let cell_rect = Rect::from_min_size(cell_min, Vec2::splat(cell));
-				let fill = if x < 0 || y < 0 || x >= width || y >= height {
-					Color32::from_rgba_unmultiplied(0, 0, 0, 0)
-				} else {
-					let pixel =
-						image.get_pixel_checked(x as u32, y as u32).expect("pixel bounds checked");
+		let tools = Self::frozen_toolbar_tools(toolbar_state);
+		let button_size = FROZEN_TOOLBAR_BUTTON_SIZE_POINTS;
+		let button_font_size = 18.0;
+		let item_spacing = FROZEN_TOOLBAR_ITEM_SPACING_POINTS;
+		let hit_area_inset = 5.0;
+		let (normal_color, hover_color, selected_color, hover_bg, selected_bg, selected_border) =
+			Self::frozen_toolbar_colors(theme);
 
-					Color32::from_rgb(pixel.0[0], pixel.0[1], pixel.0[2])
-				};
+		ui.horizontal_centered(|ui| {
+			ui.spacing_mut().item_spacing.x = item_spacing;
 
-				ui.painter().rect_filled(cell_rect, 0.0, fill);
+			if Self::frozen_toolbar_shows_export_preview(toolbar_state) {
+				Self::render_frozen_toolbar_export_preview(ui, export_preview, selected_border);
 			}
-		}
-		for i in 0..=LOUPE_SIDE_PX {
-			let x = rect.min.x + (i as f32) * cell;
-			let y = rect.min.y + (i as f32) * cell;
-
-			ui.painter()
-				.line_segment([Pos2::new(x, rect.min.y), Pos2::new(x, rect.max.y)], grid_stroke);
-			ui.painter()
-				.line_segment([Pos2::new(rect.min.x, y), Pos2::new(rect.max.x, y)], grid_stroke);
-		}
 
-		ui.painter().rect_stroke(rect, 3.0, stroke, StrokeKind::Outside);
+			for tool in tools {
+				let is_mode_tool = tool.is_mode_tool();
+				let response =
+					ui.allocate_response(Vec2::new(button_size, button_size), Sense::click());
+				let hovered = response.hovered();
+				let hover_text = if *tool == FrozenToolbarTool::Stamp {
+					i18n::tr_args(
+						"toolbar.stamp_hint",
+						&[("tool", &tool.label()), ("stamp", &toolbar_state.stamp_kind.label())],
+					)
+				} else {
+					Self::frozen_toolbar_tool_hover_text(*tool, &toolbar_state.keypad_quick_actions)
+				};
+				let response = response.on_hover_text(hover_text);
+				let hover_anim: f32 = if hovered { 1.0 } else { 0.0 };
 
-		let center_min = Pos2::new(
-			rect.min.x + (LOUPE_RADIUS_PX as f32) * cell,
-			rect.min.y + (LOUPE_RADIUS_PX as f32) * cell,
-		);
-		let center_rect = Rect::from_min_size(center_min, Vec2::splat(cell));
+				if response.clicked() {
+					let tool = *tool;
 
-		ui.painter().rect_stroke(
-			center_rect,
-			0.0,
-			Stroke::new(2.0, Color32::from_rgba_unmultiplied(255, 255, 255, 180)),
-			StrokeKind::Inside,
-		);
-	}
+					if tool == FrozenToolbarTool::Stamp && toolbar_state.selected_tool == tool {
+						toolbar_state.stamp_kind = toolbar_state.stamp_kind.next();
+					} else if is_mode_tool {
+						toolbar_state.selected_tool = tool;
+					} else {
+						toolbar_state.pending_action = Some(tool);
+					}
 
-	fn sync_egui_textures(&mut self, gpu: &GpuContext, full_output: &FullOutput) {
-		for (id, image_delta) in &full_output.textures_delta.set {
-			self.egui_renderer.update_texture(&gpu.device, &gpu.queue, *id, image_delta);
-		}
-		for id in &full_output.textures_delta.free {
-			self.egui_renderer.free_texture(id);
-		}
-	}
+					toolbar_state.needs_redraw = true;
+				}
 
-	fn acquire_frame(&mut self, gpu: &GpuContext) -> Result<SurfaceTexture> {
-		let started_at = Instant::now();
-		let frame = match self.surface.get_current_texture() {
-			Ok(frame) => Ok(frame),
-			Err(SurfaceError::Outdated | SurfaceError::Lost) => {
-				self.reconfigure(gpu);
+				let selected = (is_mode_tool && *tool == toolbar_state.selected_tool)
+					|| (*tool == FrozenToolbarTool::Backdrop && toolbar_state.backdrop_active);
+				let selected_anim: f32 = if selected { 1.0 } else { 0.0 };
+				let glow = hover_anim.max(selected_anim);
+				let icon_font = if selected {
+					FontFamily::Name("phosphor-fill".into())
+				} else {
+					FontFamily::Proportional
+				};
+				let mut icon_color = normal_color;
+				let mut bg_color = Color32::from_rgba_unmultiplied(255, 255, 255, 0);
+				let mut border_alpha = 0.0;
 
-				self.needs_reconfigure = false;
+				if selected_anim > 0.0 {
+					icon_color = Self::blend_color(icon_color, selected_color, selected_anim);
+					bg_color = Self::blend_color(bg_color, selected_bg, selected_anim);
+					border_alpha = selected_anim;
+				}
+				if hover_anim > 0.0 {
+					icon_color = Self::blend_color(icon_color, hover_color, hover_anim);
+					bg_color =
+						Self::blend_color(bg_color, hover_bg, hover_anim * (1.0 - selected_anim));
+				}
+				if glow > 0.0 {
+					let bg_rect = response.rect.shrink(hit_area_inset);
 
-				self.surface
-					.get_current_texture()
-					.wrap_err("Surface was lost and could not be reacquired")
-			},
-			Err(err) => Err(err).wrap_err("Failed to acquire surface texture"),
-		};
-		let elapsed = started_at.elapsed();
+					ui.painter().rect_filled(bg_rect, 8.0, bg_color);
+				}
+				if border_alpha > 0.0 {
+					let selected_border = Color32::from_rgba_unmultiplied(
+						selected_border.r(),
+						selected_border.g(),
+						selected_border.b(),
+						(selected_border.a() as f32 * border_alpha).round() as u8,
+					);
 
-		self.slow_op_logger.warn_if_slow(
-			"overlay.window_renderer_acquire_frame",
-			elapsed,
-			SLOW_OP_WARN_RENDER,
-			|| format!("needs_reconfigure={}", self.needs_reconfigure),
-		);
-
-		frame
-	}
-
-	#[allow(clippy::too_many_arguments)]
-	fn render_frame(
-		&mut self,
-		gpu: &GpuContext,
-		draw_frozen_bg: bool,
-		hud_blur_active: bool,
-		frame: SurfaceTexture,
-		paint_jobs: &[ClippedPrimitive],
-		screen_descriptor: &ScreenDescriptor,
-	) -> Result<()> {
-		let started_at = Instant::now();
-		let view = frame.texture.create_view(&TextureViewDescriptor::default());
-		let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-			label: Some("rsnap-overlay encoder"),
-		});
-		let _user_cmds = self.egui_renderer.update_buffers(
-			&gpu.device,
-			&gpu.queue,
-			&mut encoder,
-			paint_jobs,
-			screen_descriptor,
-		);
-
-		{
-			let rpass_desc = wgpu::RenderPassDescriptor {
-				label: Some("rsnap-overlay renderpass"),
-				color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-					view: &view,
-					depth_slice: None,
-					resolve_target: None,
-					ops: wgpu::Operations {
-						load: LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 }),
-						store: StoreOp::Store,
-					},
-				})],
-				depth_stencil_attachment: None,
-				timestamp_writes: None,
-				occlusion_query_set: None,
-			};
-			let mut rpass = encoder.begin_render_pass(&rpass_desc).forget_lifetime();
-
-			if draw_frozen_bg && let Some(bg) = &self.hud_bg {
-				rpass.set_pipeline(&self.mipgen_surface_pipeline);
-				rpass.set_bind_group(0, &bg.mipgen_bind_group, &[]);
-				rpass.draw(0..3, 0..1);
-			}
-			if hud_blur_active
-				&& self.hud_pill.is_some()
-				&& let Some(bg) = &self.hud_bg
-			{
-				if let Some(pill) = self.hud_pill {
-					let ppp = screen_descriptor.pixels_per_point;
-					let pad_px = (24.0 * ppp).ceil() as i32;
-					let surface_w = screen_descriptor.size_in_pixels[0].max(1) as i32;
-					let surface_h = screen_descriptor.size_in_pixels[1].max(1) as i32;
-					let min_x_bound = (surface_w - 1).max(0);
-					let min_y_bound = (surface_h - 1).max(0);
-					let min_x =
-						((pill.rect.min.x * ppp).floor() as i32 - pad_px).clamp(0, min_x_bound);
-					let min_y =
-						((pill.rect.min.y * ppp).floor() as i32 - pad_px).clamp(0, min_y_bound);
-					let max_x =
-						((pill.rect.max.x * ppp).ceil() as i32 + pad_px).clamp(0, surface_w);
-					let max_y =
-						((pill.rect.max.y * ppp).ceil() as i32 + pad_px).clamp(0, surface_h);
-					let w = (max_x - min_x).max(1) as u32;
-					let h = (max_y - min_y).max(1) as u32;
-
-					rpass.set_scissor_rect(min_x as u32, min_y as u32, w, h);
+					ui.painter().rect_stroke(
+						response.rect.shrink(hit_area_inset),
+						8.0,
+						Stroke::new(1.0, selected_border),
+						StrokeKind::Inside,
+					);
 				}
 
-				rpass.set_pipeline(&self.hud_blur_pipeline);
-				rpass.set_bind_group(0, &bg.hud_blur_bind_group, &[]);
-				rpass.draw(0..3, 0..1);
-				rpass.set_scissor_rect(
-					0,
-					0,
-					screen_descriptor.size_in_pixels[0].max(1),
-					screen_descriptor.size_in_pixels[1].max(1),
+				ui.painter().text(
+					response.rect.center(),
+					Align2::CENTER_CENTER,
+					tool.icon(),
+					FontId::new(button_font_size, icon_font),
+					icon_color,
 				);
 			}
+		});
+	}
 
-			self.egui_renderer.render(&mut rpass, paint_jobs, screen_descriptor);
-		}
-
-		gpu.queue.submit(Some(encoder.finish()));
-		frame.present();
-		self.slow_op_logger.warn_if_slow(
-			"overlay.window_renderer_render_frame",
-			started_at.elapsed(),
-			SLOW_OP_WARN_RENDER,
-			|| {
-				format!(
-					"draw_frozen_bg={} hud_blur_active={} paint_jobs={}",
-					draw_frozen_bg,
-					hud_blur_active,
-					paint_jobs.len()
-				)
-			},
+	/// Draws the pending export fitted into the toolbar's leading slot, or an empty frame while
+	/// the worker is still rendering it.
+	fn render_frozen_toolbar_export_preview(
+		ui: &mut Ui,
+		export_preview: Option<(TextureId, Vec2)>,
+		frame_color: Color32,
+	) {
+		let (slot, response) = ui.allocate_exact_size(
+			Vec2::new(EXPORT_PREVIEW_SLOT_WIDTH_POINTS, FROZEN_TOOLBAR_BUTTON_SIZE_POINTS),
+			Sense::hover(),
 		);
+		let _ = response.on_hover_text("Export preview");
+		let Some((texture_id, size_px)) = export_preview else {
+			ui.painter().rect_stroke(slot, 4.0, Stroke::new(1.0, frame_color), StrokeKind::Inside);
 
-		Ok(())
+			return;
+		};
+		let scale = (slot.width() / size_px.x.max(1.0)).min(slot.height() / size_px.y.max(1.0));
+		let rect = Rect::from_center_size(slot.center(), size_px * scale);
+
+		ui.painter().image(
+			texture_id,
+			rect,
+			Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+			Color32::WHITE,
+		);
+		ui.painter().rect_stroke(rect, 0.0, Stroke::new(1.0, frame_color), StrokeKind::Outside);
 	}
 
-	fn new(
-		gpu: &GpuContext,
-		window: Arc<winit::window::Window>,
-		egui_repaint_deadline: Arc<Mutex<Option<Instant>>>,
-	) -> Result<Self> {
-		let surface = gpu
-			.instance
-			.create_surface(Arc::clone(&window))
-			.wrap_err("wgpu create_surface failed")?;
-		let caps = surface.get_capabilities(&gpu.adapter);
-		let surface_format = Self::pick_surface_format(&caps);
-		let surface_alpha = Self::pick_surface_alpha(&caps);
-		let surface_config =
-			Self::make_surface_config(window.as_ref(), surface_format, surface_alpha);
+	fn frozen_toolbar_tool_hover_text(
+		tool: FrozenToolbarTool,
+		keypad_quick_actions: &[KeypadQuickAction],
+	) -> String {
+		let bound_action = match tool {
+			FrozenToolbarTool::Copy => KeypadQuickAction::Copy,
+			FrozenToolbarTool::Save => KeypadQuickAction::Save,
+			_ => return tool.label().to_owned(),
+		};
+		let keys = keypad_quick_actions
+			.iter()
+			.enumerate()
+			.filter(|(_, action)| **action == bound_action)
+			.map(|(slot, _)| format!("Num {}", slot + 1))
+			.collect::<Vec<_>>();
 
-		surface.configure(&gpu.device, &surface_config);
+		if keys.is_empty() {
+			tool.label().to_owned()
+		} else {
+			format!("{} ({})", tool.label(), keys.join(", "))
+		}
+	}
 
-		let egui_ctx = egui::Context::default();
-		let mut fonts = FontDefinitions::default();
+	fn frozen_toolbar_colors(
+		theme: HudTheme,
+	) -> (Color32, Color32, Color32, Color32, Color32, Color32) {
+		let (normal_color, hover_color, selected_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(255, 255, 255, 160),
+				Color32::from_rgba_unmultiplied(255, 255, 255, 222),
+				Color32::from_rgba_unmultiplied(255, 255, 255, 255),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 182),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 220),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 255),
+			),
+		};
+		let hover_bg = match theme {
+			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 20),
+			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 20),
+		};
+		let selected_bg = match theme {
+			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 28),
+			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 24),
+		};
+		let selected_border = match theme {
+			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 82),
+			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 72),
+		};
 
-		egui_phosphor::add_to_fonts(&mut fonts, Variant::Regular);
+		(normal_color, hover_color, selected_color, hover_bg, selected_bg, selected_border)
+	}
 
-		let phosphor_fill = String::from("phosphor-fill");
-		let proportional_fallback =
-			fonts.families.get(&FontFamily::Proportional).and_then(|names| names.first()).cloned();
+	fn blend_color(a: Color32, b: Color32, t: f32) -> Color32 {
+		let t = t.clamp(0.0, 1.0);
+		let u = 1.0 - t;
 
-		fonts.font_data.insert(phosphor_fill.clone(), Variant::Fill.font_data().into());
+		Color32::from_rgba_unmultiplied(
+			((f32::from(a.r()) * u + f32::from(b.r()) * t).round().clamp(0.0, 255.0)) as u8,
+			((f32::from(a.g()) * u + f32::from(b.g()) * t).round().clamp(0.0, 255.0)) as u8,
+			((f32::from(a.b()) * u + f32::from(b.b()) * t).round().clamp(0.0, 255.0)) as u8,
+			((f32::from(a.a()) * u + f32::from(b.a()) * t).round().clamp(0.0, 255.0)) as u8,
+		)
+	}
 
-		{
-			let family =
-				fonts.families.entry(FontFamily::Name(phosphor_fill.clone().into())).or_default();
+	fn clamp_toolbar_position(
+		screen_rect: Rect,
+		toolbar_size: Vec2,
+		cursor: Pos2,
+		side_margin: f32,
+		top_margin: f32,
+	) -> Pos2 {
+		let min_x = screen_rect.min.x + side_margin;
+		let min_y = screen_rect.min.y + top_margin;
+		let max_x = (screen_rect.max.x - toolbar_size.x - side_margin).max(min_x);
+		let max_y = (screen_rect.max.y - toolbar_size.y - top_margin * 0.5).max(min_y);
 
-			family.insert(0, phosphor_fill.clone());
+		Pos2::new(cursor.x.clamp(min_x, max_x.max(min_x)), cursor.y.clamp(min_y, max_y.max(min_y)))
+	}
 
-			if let Some(fallback) = proportional_fallback
-				&& !family.contains(&fallback)
-			{
-				family.push(fallback);
-			}
+	fn should_draw_hud(state: &OverlayState, monitor: MonitorRect) -> bool {
+		if cfg!(target_os = "macos") && matches!(state.mode, OverlayMode::Frozen) {
+			return true;
 		}
 
-		egui_ctx.set_fonts(fonts);
-
-		let repaint_deadline = Arc::clone(&egui_repaint_deadline);
-
-		egui_ctx.set_request_repaint_callback(move |info| {
-			let deadline = Instant::now() + info.delay;
-			let mut next_repaint = repaint_deadline.lock().unwrap_or_else(|err| err.into_inner());
-			let needs_update = next_repaint.is_none_or(|previous| deadline < previous);
+		!matches!(state.mode, OverlayMode::Frozen)
+			|| state.monitor != Some(monitor)
+			|| state.frozen_image.is_some()
+			|| state.error_message.is_some()
+			|| state.status_message.is_some()
+	}
 
-			if needs_update {
-				*next_repaint = Some(deadline);
-			}
-		});
+	#[allow(clippy::too_many_arguments)]
+	fn render_hud(
+		&mut self,
+		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		cursor: GlobalPoint,
+		local_cursor: Pos2,
+		hud_compact: bool,
+		hud_anchor: HudAnchor,
+		show_alt_hint_keycap: bool,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		theme: HudTheme,
+		hud_pill_out: &mut Option<HudPillGeometry>,
+	) {
+		let (hud_x, hud_y) = match hud_anchor {
+			HudAnchor::Cursor => (local_cursor.x + 14.0, local_cursor.y + 14.0),
+		};
 
-		let egui_renderer = Renderer::new(
-			&gpu.device,
-			surface_format,
-			egui_wgpu::RendererOptions {
-				msaa_samples: 1,
-				depth_stencil_format: None,
-				dithering: false,
-				predictable_texture_filtering: false,
+		Area::new("hud".into()).order(Order::Foreground).fixed_pos(Pos2::new(hud_x, hud_y)).show(
+			ctx,
+			|ui| {
+				self.render_hud_frame(
+					ui,
+					state,
+					monitor,
+					cursor,
+					hud_compact,
+					show_alt_hint_keycap,
+					hud_blur_active,
+					hud_opaque,
+					hud_opacity,
+					hud_milk_amount,
+					hud_tint_hue,
+					theme,
+					hud_pill_out,
+				);
 			},
 		);
-		let bg_sampler = Self::create_bg_sampler(gpu);
-		let (mipgen_pipeline, mipgen_bind_group_layout) =
-			Self::create_mipgen_pipeline(gpu, wgpu::TextureFormat::Rgba8UnormSrgb);
-		let mipgen_surface_pipeline =
-			Self::create_mipgen_surface_pipeline(gpu, surface_format, &mipgen_bind_group_layout);
-		let (hud_blur_pipeline, hud_blur_bind_group_layout) =
-			Self::create_hud_blur_pipeline(gpu, surface_format);
-		let hud_blur_uniform = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-			label: Some("rsnap-hud-blur uniform"),
-			size: mem::size_of::<HudBlurUniformRaw>() as u64,
-			usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-			mapped_at_creation: false,
-		});
-		let now = Instant::now();
-
-		Ok(Self {
-			window,
-			surface,
-			surface_config,
-			needs_reconfigure: false,
-			egui_ctx,
-			egui_renderer,
-			bg_sampler,
-			mipgen_pipeline,
-			mipgen_surface_pipeline,
-			mipgen_bind_group_layout,
-			hud_blur_pipeline,
-			hud_blur_bind_group_layout,
-			hud_blur_uniform,
-			hud_bg: None,
-			hud_bg_generation: 0,
-			hud_pill: None,
-			loupe_tile: None,
-			live_loupe_texture: None,
-			hud_theme: None,
-			egui_start_time: now,
-			egui_last_frame_time: now,
-			selection_flow_cache: SelectionFlowGeometryCache::default(),
-			slow_op_logger: SlowOperationLogger::default(),
-		})
-	}
-
-	fn resize(&mut self, size: PhysicalSize<u32>) -> Result<()> {
-		self.surface_config.width = size.width.max(1);
-		self.surface_config.height = size.height.max(1);
-		self.needs_reconfigure = true;
-
-		Ok(())
-	}
-
-	fn reconfigure(&mut self, gpu: &GpuContext) {
-		self.surface.configure(&gpu.device, &self.surface_config);
-	}
-
-	fn sync_egui_theme(&mut self, theme: HudTheme) {
-		if self.hud_theme == Some(theme) {
-			return;
-		}
-
-		match theme {
-			HudTheme::Dark => self.egui_ctx.set_visuals(Visuals::dark()),
-			HudTheme::Light => self.egui_ctx.set_visuals(Visuals::light()),
-		}
-
-		self.hud_theme = Some(theme);
-	}
-
-	fn prepare_window_renderer_input(
-		&mut self,
-		gpu: &GpuContext,
-		monitor: MonitorRect,
-		toolbar_pointer: Option<FrozenToolbarPointerState>,
-		theme_mode: ThemeMode,
-		phase_timings: &mut WindowRendererPhaseTimings,
-	) -> (HudTheme, PhysicalSize<u32>, f32, egui::RawInput) {
-		self.apply_pending_reconfigure(gpu);
-
-		let theme = hud_helpers::effective_hud_theme(theme_mode, self.window.theme());
-
-		self.sync_egui_theme(theme);
-
-		let prepare_input_started_at = Instant::now();
-		let (size, pixels_per_point, raw_input) =
-			self.prepare_egui_input(gpu, toolbar_pointer, Some(monitor.scale_factor()));
-
-		phase_timings.prepare_input = prepare_input_started_at.elapsed();
-
-		(theme, size, pixels_per_point, raw_input)
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn maybe_update_hud_blur_uniform(
+	fn render_hud_frame(
 		&mut self,
-		gpu: &GpuContext,
-		size: PhysicalSize<u32>,
-		pixels_per_point: f32,
-		theme: HudTheme,
-		hud_shader_blur_active: bool,
-		hud_fog_amount: f32,
+		ui: &mut Ui,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		cursor: GlobalPoint,
+		hud_compact: bool,
+		show_alt_hint_keycap: bool,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
 		hud_milk_amount: f32,
 		hud_tint_hue: f32,
-		phase_timings: &mut WindowRendererPhaseTimings,
+		theme: HudTheme,
+		hud_pill_out: &mut Option<HudPillGeometry>,
 	) {
-		if !hud_shader_blur_active {
-			return;
-		}
-
-		let update_hud_blur_uniform_started_at = Instant::now();
-
-		self.update_hud_blur_uniform(
-			gpu,
-			size,
-			pixels_per_point,
+		let body_fill = Self::tinted_hud_body_fill(
 			theme,
-			hud_fog_amount,
+			hud_blur_active,
+			hud_opaque,
+			hud_opacity,
 			hud_milk_amount,
 			hud_tint_hue,
 		);
+		let pill_frame =
+			Self::hud_pill_frame(theme, hud_opaque, hud_opacity, body_fill, !hud_compact);
+		let inner = pill_frame.show(ui, |ui| {
+			ui.spacing_mut().item_spacing = egui::vec2(10.0, 6.0);
 
-		phase_timings.update_hud_blur_uniform = update_hud_blur_uniform_started_at.elapsed();
-	}
+			if let Some(err) = &state.error_message {
+				let err_color = match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(235, 235, 245, 235),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(28, 28, 32, 235),
+				};
 
-	#[allow(clippy::too_many_arguments)]
-	fn finish_window_renderer_draw(
-		&mut self,
-		gpu: &GpuContext,
-		state: &OverlayState,
-		path: WindowRendererPath,
-		monitor: MonitorRect,
-		size: PhysicalSize<u32>,
-		pixels_per_point: f32,
-		draw_started_at: Instant,
-		phase_timings: &mut WindowRendererPhaseTimings,
-		paint_jobs: Vec<ClippedPrimitive>,
-		draw_frozen_bg: bool,
-		hud_shader_blur_active: bool,
-		toolbar_active: bool,
-	) -> Result<()> {
-		let screen_descriptor =
-			ScreenDescriptor { size_in_pixels: [size.width, size.height], pixels_per_point };
-		let acquire_frame_started_at = Instant::now();
-		let frame = self.acquire_frame(gpu)?;
+				ui.label(RichText::new(err).color(err_color).monospace());
+			} else if let Some(status) = &state.status_message {
+				let status_color = match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(235, 235, 245, 150),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(28, 28, 32, 160),
+				};
 
-		phase_timings.acquire_frame = acquire_frame_started_at.elapsed();
+				ui.label(RichText::new(status).color(status_color).monospace());
+			} else {
+				Self::render_hud_content(ui, state, monitor, cursor, show_alt_hint_keycap, theme);
 
-		let render_frame_started_at = Instant::now();
+				if state.hud_pinned && matches!(state.mode, OverlayMode::Live) {
+					self.hud_action =
+						Self::render_hud_quick_actions(ui, state, theme).or(self.hud_action);
+				}
+			}
+		});
+		let pill_rect = inner.response.rect;
 
-		self.render_frame(
-			gpu,
-			draw_frozen_bg,
-			hud_shader_blur_active,
-			frame,
-			&paint_jobs,
-			&screen_descriptor,
-		)?;
+		*hud_pill_out = Some(HudPillGeometry {
+			rect: pill_rect,
+			radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
+		});
 
-		phase_timings.render_frame = render_frame_started_at.elapsed();
-		phase_timings.total = draw_started_at.elapsed();
+		if hud_compact {
+			return;
+		}
 
-		phase_timings.warn_if_substeps_slow(
-			&mut self.slow_op_logger,
-			path,
-			self.window.id(),
-			monitor.id,
-			state.mode,
-			paint_jobs.len(),
-		);
-		phase_timings.trace(
-			path,
-			self.window.id(),
-			monitor.id,
-			state.mode,
-			toolbar_active,
-			paint_jobs.len(),
+		let inner_stroke_color = match theme {
+			HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+			HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
+		};
+		let inner_stroke = Stroke::new(1.0, inner_stroke_color);
+		let inner_rect = pill_rect.shrink(1.0);
+
+		ui.painter().rect_stroke(
+			inner_rect,
+			CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS.saturating_sub(1)),
+			inner_stroke,
+			StrokeKind::Inside,
 		);
 
-		Ok(())
+		if !hud_compact {
+			self.render_loupe_tile(
+				ui,
+				state,
+				monitor,
+				pill_rect,
+				hud_blur_active,
+				hud_opaque,
+				body_fill,
+				theme,
+			);
+		}
 	}
 
-	#[allow(clippy::too_many_arguments)]
-	fn draw(
-		&mut self,
-		gpu: &GpuContext,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		draw_hud: bool,
-		hud_local_cursor_override: Option<Pos2>,
-		hud_compact: bool,
-		hud_anchor: HudAnchor,
-		toolbar_placement: ToolbarPlacement,
-		show_alt_hint_keycap: bool,
-		show_hud_blur: bool,
-		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_fog_amount: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-		theme_mode: ThemeMode,
-		selection_particles: bool,
-		selection_flow_stroke_width_px: f32,
-		allow_frozen_surface_bg: bool,
-		show_frozen_capture_affordance: bool,
-		frozen_capture_is_fullscreen_fallback: bool,
-		toolbar_state: Option<&mut FrozenToolbarState>,
-		toolbar_pointer: Option<FrozenToolbarPointerState>,
-	) -> Result<()> {
-		let draw_started_at = Instant::now();
-		let mut phase_timings = WindowRendererPhaseTimings::default();
-		let (theme, size, pixels_per_point, raw_input) = self.prepare_window_renderer_input(
-			gpu,
-			monitor,
-			toolbar_pointer,
-			theme_mode,
-			&mut phase_timings,
-		);
-		let toolbar_active = toolbar_state.is_some();
-
-		self.trace_frozen_frame_metrics(state, monitor, size, pixels_per_point, toolbar_active);
+	fn hud_pill_frame(
+		theme: HudTheme,
+		_hud_opaque: bool,
+		_hud_opacity: f32,
+		body_fill: Color32,
+		with_shadow: bool,
+	) -> Frame {
+		let outer_stroke_color = match theme {
+			HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
+			HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+		};
+		let pill_shadow = if with_shadow {
+			egui::epaint::Shadow {
+				offset: [0, 0],
+				blur: 10,
+				spread: 0,
+				color: match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
+				},
+			}
+		} else {
+			egui::Shadow::NONE
+		};
 
-		self.loupe_tile = None;
+		Frame {
+			fill: body_fill,
+			stroke: Stroke::new(1.0, outer_stroke_color),
+			shadow: pill_shadow,
+			corner_radius: CornerRadius::same(HUD_PILL_CORNER_RADIUS_POINTS),
+			inner_margin: Margin::symmetric(12, 8),
+			..Frame::default()
+		}
+	}
 
-		let hud_cfg = Self::resolve_hud_draw_config(
-			state,
+	fn render_hud_content(
+		ui: &mut Ui,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		cursor: GlobalPoint,
+		show_alt_hint_keycap: bool,
+		theme: HudTheme,
+	) {
+		let (label_color, secondary_color) = match theme {
+			HudTheme::Dark => (
+				Color32::from_rgba_unmultiplied(235, 235, 245, 235),
+				Color32::from_rgba_unmultiplied(235, 235, 245, 150),
+			),
+			HudTheme::Light => (
+				Color32::from_rgba_unmultiplied(28, 28, 32, 235),
+				Color32::from_rgba_unmultiplied(28, 28, 32, 160),
+			),
+		};
+		let (hud_unit, millimeters_per_point) =
+			hud_helpers::monitor_hud_unit(state.hud_unit, monitor);
+		let pos_text = hud_helpers::format_live_hud_position_text(
 			monitor,
-			draw_hud,
-			allow_frozen_surface_bg,
-			toolbar_active,
-			show_hud_blur,
-			hud_opaque,
+			cursor,
+			hud_unit,
+			millimeters_per_point,
 		);
-		let sync_hud_bg_started_at = Instant::now();
+		let (hex_text, rgb_text) = hud_helpers::format_live_hud_rgb_text(state.rgb);
 
-		self.sync_or_clear_hud_bg(gpu, state, monitor, hud_cfg)?;
+		ui.vertical(|ui| {
+			ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+				ui.label(RichText::new(pos_text).color(label_color).monospace());
+				ui.label(RichText::new(hud_unit.suffix()).color(secondary_color).monospace());
+				ui.label(RichText::new("•").color(secondary_color).monospace());
+				Self::render_hud_swatch(ui, state.rgb, theme);
+				ui.label(RichText::new(hex_text).color(label_color).monospace());
+				ui.label(RichText::new(rgb_text).color(secondary_color).monospace());
 
-		phase_timings.sync_hud_bg = sync_hud_bg_started_at.elapsed();
+				if show_alt_hint_keycap {
+					let alt_active = state.alt_held;
+					let (keycap_fill, keycap_stroke, keycap_text) = match theme {
+						HudTheme::Dark if alt_active => (
+							Color32::from_rgba_unmultiplied(255, 255, 255, 40),
+							Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 70)),
+							label_color,
+						),
+						HudTheme::Dark => (
+							Color32::from_rgba_unmultiplied(255, 255, 255, 18),
+							Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 30)),
+							secondary_color,
+						),
+						HudTheme::Light if alt_active => (
+							Color32::from_rgba_unmultiplied(0, 0, 0, 22),
+							Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 64)),
+							label_color,
+						),
+						HudTheme::Light => (
+							Color32::from_rgba_unmultiplied(0, 0, 0, 12),
+							Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 32)),
+							secondary_color,
+						),
+					};
 
-		let hud_shader_blur_active = self.hud_shader_blur_active(state, monitor, hud_cfg);
-		let mut selection_flow_cache = mem::take(&mut self.selection_flow_cache);
-		let run_egui_started_at = Instant::now();
-		let (full_output, hud_pill) = self.run_egui(
-			raw_input,
-			state,
-			monitor,
-			hud_cfg.can_draw_hud,
-			hud_local_cursor_override,
-			hud_compact,
-			show_hud_blur,
-			hud_anchor,
-			toolbar_placement,
-			show_alt_hint_keycap,
-			hud_cfg.hud_glass_active,
-			hud_opaque,
-			hud_opacity,
-			hud_milk_amount,
-			hud_tint_hue,
-			theme,
-			selection_particles,
-			selection_flow_stroke_width_px,
-			hud_cfg.needs_frozen_surface_bg,
-			show_frozen_capture_affordance,
-			frozen_capture_is_fullscreen_fallback,
-			&mut selection_flow_cache,
-			toolbar_state,
-			toolbar_pointer,
-		);
+					Frame {
+						fill: keycap_fill,
+						stroke: keycap_stroke,
+						corner_radius: CornerRadius::same(6),
+						inner_margin: Margin::symmetric(6, 2),
+						..Frame::default()
+					}
+					.show(ui, |ui| {
+						ui.label(RichText::new("Alt").color(keycap_text).monospace());
+					});
+				}
+			});
 
-		phase_timings.run_egui = run_egui_started_at.elapsed();
-		self.selection_flow_cache = selection_flow_cache;
-		self.hud_pill = hud_pill;
+			// Captures read pixels before the display transfer table, so the sample above is the
+			// drawn color; this row shows what the tinted panel actually emits.
+			if let (Some(rgb), Some(transfer)) =
+				(state.rgb, state.display_transfer.get(&monitor.id))
+			{
+				let on_screen = transfer.apply(rgb);
 
-		self.maybe_update_hud_blur_uniform(
-			gpu,
-			size,
-			pixels_per_point,
-			theme,
-			hud_shader_blur_active,
-			hud_fog_amount,
-			hud_milk_amount,
-			hud_tint_hue,
-			&mut phase_timings,
-		);
+				ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+					ui.label(RichText::new("on screen").color(secondary_color).monospace());
+					ui.label(
+						RichText::new(on_screen.hex_upper()).color(secondary_color).monospace(),
+					);
+				});
+			}
+			if let Some(reference) = state.reference_sample {
+				let (hex_text, rgb_text) =
+					hud_helpers::format_live_hud_rgb_text(Some(reference.rgb));
+
+				ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+					ui.label(RichText::new("ref").color(secondary_color).monospace());
+					Self::render_hud_swatch(ui, Some(reference.rgb), theme);
+					ui.label(RichText::new(hex_text).color(label_color).monospace());
+					ui.label(RichText::new(rgb_text).color(secondary_color).monospace());
+
+					if let Some(rgb) = state.rgb {
+						let delta_e = color_math::delta_e(reference.rgb, rgb);
+
+						ui.label(
+							RichText::new(format!("ΔE {delta_e:.2}"))
+								.color(label_color)
+								.monospace(),
+						);
+					}
+				});
+			}
+		});
+	}
 
-		let sync_egui_textures_started_at = Instant::now();
+	fn render_hud_swatch(ui: &mut Ui, rgb: Option<Rgb>, theme: HudTheme) {
+		let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), Sense::hover());
+		let swatch_color = match rgb {
+			Some(rgb) => Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+			None => Color32::from_rgba_unmultiplied(255, 255, 255, 26),
+		};
 
-		self.sync_egui_textures(gpu, &full_output);
+		ui.painter().rect_filled(rect, 3.0, swatch_color);
+		ui.painter().rect_stroke(
+			rect,
+			3.0,
+			Stroke::new(
+				1.0,
+				match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 36),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+				},
+			),
+			StrokeKind::Inside,
+		);
+	}
 
-		phase_timings.sync_egui_textures = sync_egui_textures_started_at.elapsed();
+	/// Draws the pinned HUD's quick-action row and returns the action clicked this frame.
+	fn render_hud_quick_actions(
+		ui: &mut Ui,
+		state: &OverlayState,
+		theme: HudTheme,
+	) -> Option<HudQuickAction> {
+		let (normal_color, hover_color, _, hover_bg, _, _) = Self::frozen_toolbar_colors(theme);
+		let font = FontId::proportional(12.0);
+		let mut clicked = None;
+
+		ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+			ui.spacing_mut().item_spacing.x = 4.0;
+
+			for action in HudQuickAction::ALL {
+				let text = format!("{} {}", action.icon(), action.label(state));
+				let text_size =
+					ui.painter().layout_no_wrap(text.clone(), font.clone(), normal_color).size();
+				let response =
+					ui.allocate_response(text_size + Vec2::new(12.0, 6.0), Sense::click());
+				let color = if response.hovered() {
+					ui.painter().rect_filled(response.rect, 6.0, hover_bg);
 
-		let tessellate_started_at = Instant::now();
-		let paint_jobs = self.egui_ctx.tessellate(full_output.shapes, pixels_per_point);
+					hover_color
+				} else {
+					normal_color
+				};
 
-		phase_timings.tessellate = tessellate_started_at.elapsed();
+				ui.painter().text(
+					response.rect.center(),
+					Align2::CENTER_CENTER,
+					text,
+					font.clone(),
+					color,
+				);
 
-		let draw_frozen_bg = hud_cfg.needs_frozen_surface_bg
-			&& state.monitor == Some(monitor)
-			&& state.frozen_image.is_some();
+				if response.clicked() {
+					clicked = Some(action);
+				}
+			}
+		});
 
-		self.finish_window_renderer_draw(
-			gpu,
-			state,
-			WindowRendererPath::Overlay,
-			monitor,
-			size,
-			pixels_per_point,
-			draw_started_at,
-			&mut phase_timings,
-			paint_jobs,
-			draw_frozen_bg,
-			hud_shader_blur_active,
-			toolbar_active,
-		)
+		clicked
 	}
 
-	fn trace_frozen_frame_metrics(
-		&self,
+	#[allow(clippy::too_many_arguments)]
+	fn render_loupe_tile(
+		&mut self,
+		ui: &mut Ui,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		size: PhysicalSize<u32>,
-		pixels_per_point: f32,
-		toolbar_active: bool,
+		pill_rect: Rect,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		body_fill: Color32,
+		theme: HudTheme,
 	) {
-		if !matches!(state.mode, OverlayMode::Frozen) || state.monitor != Some(monitor) {
+		let ctx = ui.ctx().clone();
+
+		self.loupe_tile = None;
+
+		if !state.alt_held {
 			return;
 		}
 
-		let screen_size_points =
-			Vec2::new(size.width as f32 / pixels_per_point, size.height as f32 / pixels_per_point);
+		let side = hud_helpers::stable_live_loupe_side_points(state, state.loupe_cell_points);
+		let tile_padding = Margin::same(10);
+		let tile_w = side + (tile_padding.left as f32) + (tile_padding.right as f32);
+		let tile_h = side + (tile_padding.top as f32) + (tile_padding.bottom as f32);
+		let screen = ctx.content_rect();
+		let gap = 10.0;
+		let mut x = pill_rect.min.x;
 
-		tracing::trace!(
-					window_id = ?self.window.id(),
-					monitor_id = monitor.id,
-					window_scale_factor = self.window.scale_factor(),
-		monitor_scale_factor = monitor.scale_factor(),
-					size_in_pixels = ?size,
-					pixels_per_point,
-					screen_size_points = ?screen_size_points,
-					flip_y = false,
-					frozen_generation = state.frozen_generation,
-					frozen_image_ready = state.frozen_image.is_some(),
-					toolbar_active,
-					"Frozen frame metrics."
-				);
-	}
+		x = x.clamp(screen.min.x + 6.0, (screen.max.x - tile_w - 6.0).max(screen.min.x + 6.0));
 
-	fn resolve_hud_draw_config(
+		let below_y = pill_rect.max.y + gap;
+		let above_y = pill_rect.min.y - gap - tile_h;
+		let mut y = if below_y + tile_h <= screen.max.y { below_y } else { above_y };
+
+		y = y.clamp(screen.min.y + 6.0, (screen.max.y - tile_h - 6.0).max(screen.min.y + 6.0));
+
+		let pos = Pos2::new(x, y);
+		let tile = Area::new(Id::new("rsnap-loupe-tile"))
+			.order(Order::Foreground)
+			.fixed_pos(pos)
+			.show(&ctx, |ui| {
+				let _ = hud_blur_active;
+				let fill = body_fill;
+				let outer_stroke_color = match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+				};
+				let outer_stroke = Stroke::new(1.0, outer_stroke_color);
+				let shadow = egui::epaint::Shadow {
+					offset: [0, 0],
+					blur: 10,
+					spread: 0,
+					color: match theme {
+						HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
+						HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
+					},
+				};
+				let frame = Frame {
+					fill,
+					stroke: outer_stroke,
+					shadow,
+					corner_radius: CornerRadius::same(18),
+					inner_margin: tile_padding,
+					..Frame::default()
+				};
+
+				frame.show(ui, |ui| {
+					ui.set_min_size(Vec2::new(side, side));
+					self.render_loupe(ui, state, monitor, hud_blur_active, hud_opaque, theme);
+				});
+			});
+
+		self.loupe_tile = Some(tile.response.rect);
+	}
+
+	fn render_loupe(
+		&mut self,
+		ui: &mut Ui,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		draw_hud: bool,
-		allow_frozen_surface_bg: bool,
-		toolbar_active: bool,
-		show_hud_blur: bool,
+		hud_blur_active: bool,
 		hud_opaque: bool,
-	) -> HudDrawConfig {
-		let can_draw_hud = draw_hud && Self::should_draw_hud(state, monitor);
-		let needs_frozen_surface_bg =
-			allow_frozen_surface_bg && !draw_hud && matches!(state.mode, OverlayMode::Frozen);
-		// `show_hud_blur` is a UX toggle for "glass mode".
-		// - On macOS: HUD uses native compositor blur; toolbar uses native HUD windowing, so shader
-		//   blur stays tied to monitor-aligned overlay windows.
-		// - On non-macOS: HUD and toolbar remain in overlay windows with shader blur paths.
-		let hud_glass_active = can_draw_hud && show_hud_blur && !hud_opaque;
-		let toolbar_glass_active = toolbar_active && show_hud_blur && !hud_opaque;
-		let use_shader_blur_for_hud = !cfg!(target_os = "macos");
-		let needs_shader_blur_bg =
-			toolbar_glass_active || (hud_glass_active && use_shader_blur_for_hud);
+		theme: HudTheme,
+	) {
+		let mode = state.mode;
 
-		HudDrawConfig {
-			can_draw_hud,
-			needs_frozen_surface_bg,
-			needs_shader_blur_bg,
-			hud_glass_active,
+		if matches!(mode, OverlayMode::Live) {
+			let cell = state.loupe_cell_points;
+
+			self.render_live_loupe(ui, state, monitor, cell, hud_blur_active, hud_opaque, theme);
+		} else if matches!(mode, OverlayMode::Frozen)
+			&& (state.frozen_image.is_some() || state.loupe.is_some())
+		{
+			let monitor = state.monitor.unwrap_or(monitor);
+			let Some(cursor) = state.cursor else {
+				return;
+			};
+
+			self.render_frozen_loupe(
+				ui,
+				state,
+				monitor,
+				cursor,
+				state.loupe_cell_points,
+				hud_blur_active,
+				hud_opaque,
+				theme,
+			);
 		}
 	}
 
-	fn sync_or_clear_hud_bg(
+	/// Uploads the toolbar export preview when a newer one arrived, returning its texture and
+	/// pixel size.
+	fn sync_export_preview_texture(
 		&mut self,
-		gpu: &GpuContext,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		hud_cfg: HudDrawConfig,
-	) -> Result<()> {
-		if hud_cfg.needs_frozen_surface_bg || hud_cfg.needs_shader_blur_bg {
-			return self.sync_hud_bg(gpu, state, monitor);
-		}
+		preview: Option<&ExportPreview>,
+	) -> Option<(TextureId, Vec2)> {
+		let Some(preview) = preview else {
+			self.export_preview_texture = None;
 
-		self.hud_bg = None;
-		self.hud_bg_generation = match state.mode {
-			OverlayMode::Live => state.live_bg_generation,
-			OverlayMode::Frozen => state.frozen_generation,
+			return None;
 		};
 
-		Ok(())
+		if self.export_preview_texture.as_ref().is_none_or(|(id, _)| *id != preview.request_id) {
+			let size = [preview.image.width() as usize, preview.image.height() as usize];
+			let color_image = ColorImage::from_rgba_unmultiplied(size, preview.image.as_raw());
+			let texture = self.egui_ctx.load_texture(
+				String::from("frozen-toolbar-export-preview"),
+				color_image,
+				TextureOptions::LINEAR,
+			);
+
+			self.export_preview_texture = Some((preview.request_id, texture));
+		}
+
+		self.export_preview_texture.as_ref().map(|(_, texture)| {
+			(texture.id(), Vec2::new(preview.image.width() as f32, preview.image.height() as f32))
+		})
 	}
 
-	fn hud_shader_blur_active(
-		&self,
-		state: &OverlayState,
-		monitor: MonitorRect,
-		hud_cfg: HudDrawConfig,
-	) -> bool {
-		hud_cfg.needs_shader_blur_bg
-			&& self.hud_bg.is_some()
-			&& match state.mode {
-				OverlayMode::Live => state.live_bg_monitor == Some(monitor),
-				OverlayMode::Frozen => state.monitor == Some(monitor),
-			}
+	fn sync_live_loupe_texture(
+		&mut self,
+		loupe: Option<&crate::state::LoupeSample>,
+	) -> Option<TextureId> {
+		let Some(loupe) = loupe else {
+			self.live_loupe_texture = None;
+
+			return None;
+		};
+		let patch_size_px = [loupe.patch.width() as usize, loupe.patch.height() as usize];
+		let patch_rgba = loupe.patch.as_raw();
+
+		match self.live_loupe_texture.as_mut() {
+			Some(cached) if cached.patch_size_px == patch_size_px => {
+				if cached.rgba != *patch_rgba {
+					let color_image = ColorImage::from_rgba_unmultiplied(
+						[patch_size_px[0], patch_size_px[1]],
+						patch_rgba,
+					);
+
+					cached.texture.set(color_image, TextureOptions::NEAREST);
+					cached.rgba.clone_from(patch_rgba);
+				}
+			},
+			_ => {
+				let color_image = ColorImage::from_rgba_unmultiplied(
+					[patch_size_px[0], patch_size_px[1]],
+					patch_rgba,
+				);
+				let texture = self.egui_ctx.load_texture(
+					String::from("live-loupe-image"),
+					color_image,
+					TextureOptions::NEAREST,
+				);
+
+				self.live_loupe_texture =
+					Some(LiveLoupeTexture { texture, patch_size_px, rgba: patch_rgba.clone() });
+			},
+		}
+
+		self.live_loupe_texture.as_ref().map(|cached| cached.texture.id())
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn draw_loupe_tile_window(
+	fn render_live_loupe(
 		&mut self,
-		gpu: &GpuContext,
+		ui: &mut Ui,
 		state: &OverlayState,
 		monitor: MonitorRect,
-		show_hud_blur: bool,
+		cell: f32,
+		_hud_blur_active: bool,
 		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_fog_amount: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-		theme_mode: ThemeMode,
-	) -> Result<()> {
-		let draw_started_at = Instant::now();
-		let mut phase_timings = WindowRendererPhaseTimings::default();
-		let (theme, size, pixels_per_point, raw_input) =
-			self.prepare_window_renderer_input(gpu, monitor, None, theme_mode, &mut phase_timings);
+		theme: HudTheme,
+	) {
+		let fallback_side_px = state.loupe_patch_side_px.max(1);
+		let (w, h) = state
+			.loupe
+			.as_ref()
+			.map(|loupe| loupe.patch.dimensions())
+			.unwrap_or((fallback_side_px, fallback_side_px));
+		let side = hud_helpers::stable_live_loupe_side_points(state, cell);
+		// Only the pinned HUD receives clicks; a click on a cell locks the readout to it.
+		let sense = if state.hud_pinned { Sense::click() } else { Sense::hover() };
+		let (rect, response) = ui.allocate_exact_size(Vec2::new(side, side), sense);
+		let body_fill = hud_helpers::hud_body_fill_srgba8(theme, hud_opaque);
+		let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 140));
+		let placeholder_fill =
+			Color32::from_rgba_unmultiplied(body_fill[0], body_fill[1], body_fill[2], 255);
+		let image_rect =
+			Rect::from_center_size(rect.center(), Vec2::new((w as f32) * cell, (h as f32) * cell));
 
-		self.loupe_tile = None;
+		if let Some(texture_id) = self.sync_live_loupe_texture(state.loupe.as_ref()) {
+			ui.painter().rect_filled(rect, 3.0, placeholder_fill);
+			ui.painter().image(
+				texture_id,
+				image_rect,
+				Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(1.0, 1.0)),
+				Color32::WHITE,
+			);
+		} else {
+			ui.painter().rect_filled(rect, 3.0, placeholder_fill);
+		}
 
-		let shader_blur_active = !cfg!(target_os = "macos")
-			&& matches!(state.mode, OverlayMode::Frozen)
-			&& show_hud_blur
-			&& !hud_opaque;
-		let hud_cfg = HudDrawConfig {
-			can_draw_hud: false,
-			needs_frozen_surface_bg: false,
-			needs_shader_blur_bg: shader_blur_active,
-			hud_glass_active: shader_blur_active,
+		ui.painter().rect_stroke(rect, 3.0, stroke, StrokeKind::Outside);
+
+		let cell_rect_at = |(dx, dy): (i32, i32)| {
+			let min = Pos2::new(
+				image_rect.min.x + (((w / 2) as i32 + dx) as f32) * cell,
+				image_rect.min.y + (((h / 2) as i32 + dy) as f32) * cell,
+			);
+
+			Rect::from_min_size(min, Vec2::splat(cell))
 		};
-		let sync_hud_bg_started_at = Instant::now();
+		let focus_rect = cell_rect_at(state.loupe_lock.unwrap_or((0, 0)));
 
-		self.sync_or_clear_hud_bg(gpu, state, monitor, hud_cfg)?;
+		if cell >= LOUPE_PIXEL_GRID_MIN_CELL_POINTS {
+			Self::render_loupe_pixel_grid(ui, state, monitor, rect, image_rect, cell, focus_rect);
+		}
 
-		phase_timings.sync_hud_bg = sync_hud_bg_started_at.elapsed();
+		ui.painter().rect_stroke(
+			cell_rect_at((0, 0)),
+			0.0,
+			Stroke::new(2.0, Color32::from_rgba_unmultiplied(255, 255, 255, 180)),
+			StrokeKind::Inside,
+		);
 
-		let hud_shader_blur_active = self.hud_shader_blur_active(state, monitor, hud_cfg);
-		let hud_blur_active = show_hud_blur && !hud_opaque;
-		let body_fill = Self::tinted_hud_body_fill(
-			theme,
-			hud_blur_active,
-			hud_opaque,
-			hud_opacity,
-			hud_milk_amount,
-			hud_tint_hue,
+		if state.loupe_lock.is_some() {
+			ui.painter().rect_stroke(
+				focus_rect,
+				0.0,
+				Stroke::new(2.0, Color32::from_rgb(255, 204, 0)),
+				StrokeKind::Inside,
+			);
+		}
+		if response.clicked()
+			&& let Some(pos) = response.interact_pointer_pos()
+			&& image_rect.contains(pos)
+		{
+			let column = ((pos.x - image_rect.min.x) / cell).floor() as i32;
+			let row = ((pos.y - image_rect.min.y) / cell).floor() as i32;
+
+			self.loupe_cell_click = Some((column - (w / 2) as i32, row - (h / 2) as i32));
+		}
+
+		let zoom_label = hud_helpers::format_loupe_zoom_label(cell, ui.ctx().pixels_per_point());
+		let zoom_galley = ui.painter().layout_no_wrap(
+			zoom_label,
+			FontId::monospace(10.0),
+			Color32::from_rgba_unmultiplied(255, 255, 255, 230),
 		);
-		let run_loupe_tile_egui_started_at = Instant::now();
-		let (full_output, loupe_tile_rect) = self.run_loupe_tile_egui(
-			raw_input,
-			state,
-			theme,
-			hud_blur_active,
-			hud_opaque,
-			body_fill,
+		let zoom_rect = Rect::from_min_size(
+			rect.right_bottom() - zoom_galley.size() - Vec2::new(8.0, 6.0),
+			zoom_galley.size() + Vec2::new(6.0, 2.0),
 		);
 
-		phase_timings.run_egui = run_loupe_tile_egui_started_at.elapsed();
-		self.loupe_tile = loupe_tile_rect;
+		ui.painter().rect_filled(zoom_rect, 3.0, Color32::from_rgba_unmultiplied(0, 0, 0, 140));
+		ui.painter().galley(
+			zoom_rect.min + Vec2::new(3.0, 1.0),
+			zoom_galley,
+			Color32::from_rgba_unmultiplied(255, 255, 255, 230),
+		);
+	}
 
-		if hud_shader_blur_active {
-			self.hud_pill = loupe_tile_rect.map(|rect| HudPillGeometry {
-				rect,
-				radius_points: LOUPE_TILE_CORNER_RADIUS_POINTS as f32,
-			});
+	/// Draws the pixel grid, the optional row and column tint around `focus_rect`, and the pixel
+	/// coordinates of the columns and rows along the top and left edges.
+	#[allow(clippy::too_many_arguments)]
+	fn render_loupe_pixel_grid(
+		ui: &Ui,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		rect: Rect,
+		image_rect: Rect,
+		cell: f32,
+		focus_rect: Rect,
+	) {
+		let painter = ui.painter().with_clip_rect(rect);
+		let columns = (image_rect.width() / cell).round() as u32;
+		let rows = (image_rect.height() / cell).round() as u32;
+		let grid_stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 64));
 
-			if self.hud_pill.is_some() {
-				self.maybe_update_hud_blur_uniform(
-					gpu,
-					size,
-					pixels_per_point,
-					theme,
-					hud_shader_blur_active,
-					hud_fog_amount,
-					hud_milk_amount,
-					hud_tint_hue,
-					&mut phase_timings,
-				);
-			}
-		} else {
-			self.hud_pill = None;
-		}
+		if state.loupe_row_column_highlight {
+			let tint = Color32::from_rgba_unmultiplied(255, 255, 255, 40);
 
-		let sync_egui_textures_started_at = Instant::now();
+			painter.rect_filled(
+				Rect::from_x_y_ranges(image_rect.x_range(), focus_rect.y_range()),
+				0.0,
+				tint,
+			);
+			painter.rect_filled(
+				Rect::from_x_y_ranges(focus_rect.x_range(), image_rect.y_range()),
+				0.0,
+				tint,
+			);
+		}
+		for column in 0..=columns {
+			let x = image_rect.min.x + (column as f32) * cell;
 
-		self.sync_egui_textures(gpu, &full_output);
+			painter.line_segment(
+				[Pos2::new(x, image_rect.min.y), Pos2::new(x, image_rect.max.y)],
+				grid_stroke,
+			);
+		}
+		for row in 0..=rows {
+			let y = image_rect.min.y + (row as f32) * cell;
 
-		phase_timings.sync_egui_textures = sync_egui_textures_started_at.elapsed();
+			painter.line_segment(
+				[Pos2::new(image_rect.min.x, y), Pos2::new(image_rect.max.x, y)],
+				grid_stroke,
+			);
+		}
 
-		let tessellate_started_at = Instant::now();
-		let paint_jobs = self.egui_ctx.tessellate(full_output.shapes, pixels_per_point);
+		let Some((center_x, center_y)) =
+			state.loupe.as_ref().and_then(|loupe| monitor.local_u32_pixels(loupe.center))
+		else {
+			return;
+		};
+		let font = FontId::monospace(8.0);
+		let text_color = Color32::from_rgba_unmultiplied(255, 255, 255, 230);
+		let backdrop = Color32::from_rgba_unmultiplied(0, 0, 0, 150);
+		let widest = (center_x.max(center_y) + columns.max(rows)).to_string();
+		let label_width = painter.layout_no_wrap(widest, font.clone(), text_color).size().x;
+		let step = hud_helpers::loupe_coordinate_label_step(cell, label_width);
+		let label = |text: String, anchor: Pos2, centered_x: bool| {
+			let galley = painter.layout_no_wrap(text, font.clone(), text_color);
+			let size = galley.size();
+			let min = if centered_x {
+				Pos2::new(anchor.x - size.x / 2.0, anchor.y)
+			} else {
+				Pos2::new(anchor.x, anchor.y - size.y / 2.0)
+			};
 
-		phase_timings.tessellate = tessellate_started_at.elapsed();
+			painter.rect_filled(Rect::from_min_size(min, size).expand(1.0), 2.0, backdrop);
+			painter.galley(min, galley, text_color);
+		};
 
-		self.finish_window_renderer_draw(
-			gpu,
-			state,
-			WindowRendererPath::LoupeTile,
-			monitor,
-			size,
-			pixels_per_point,
-			draw_started_at,
-			&mut phase_timings,
-			paint_jobs,
-			false,
-			hud_shader_blur_active,
-			false,
-		)
-	}
+		for column in 0..columns {
+			let Some(x) = (center_x + column).checked_sub(columns / 2) else {
+				continue;
+			};
 
-	fn tinted_hud_body_fill(
-		theme: HudTheme,
-		hud_blur_active: bool,
-		hud_opaque: bool,
-		hud_opacity: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
-	) -> Color32 {
-		let mut opacity = if hud_opaque { 1.0 } else { hud_opacity.clamp(0.0, 1.0) };
+			if x % step == 0 {
+				let anchor_x = image_rect.min.x + (column as f32 + 0.5) * cell;
 
-		if hud_blur_active {
-			opacity = opacity.max(hud_helpers::hud_blur_tint_alpha(theme));
+				label(x.to_string(), Pos2::new(anchor_x, rect.min.y + 1.0), true);
+			}
 		}
+		for row in 0..rows {
+			let Some(y) = (center_y + row).checked_sub(rows / 2) else {
+				continue;
+			};
 
-		let tint = hud_milk_amount.clamp(0.0, 1.0);
-		let mut fill = hud_helpers::hud_body_fill_srgba8(theme, false);
-		let tint_hue = hud_tint_hue.clamp(0.0, 1.0);
-		let tint_saturation = 1.0;
-		let (_, _, base_lightness) = hud_helpers::rgb_to_hsl(Rgb::new(fill[0], fill[1], fill[2]));
-		let tinted_target = hud_helpers::hsl_to_rgb(tint_hue, tint_saturation, base_lightness);
+			if y % step == 0 {
+				let anchor_y = image_rect.min.y + (row as f32 + 0.5) * cell;
 
-		fn lerp_u8(a: u8, b: u8, t: f32) -> u8 {
-			((f32::from(a) + ((f32::from(b) - f32::from(a)) * t)).round().clamp(0.0, 255.0)) as u8
+				label(y.to_string(), Pos2::new(rect.min.x + 1.0, anchor_y), false);
+			}
 		}
-
-		fill[0] = lerp_u8(fill[0], tinted_target.r, tint);
-		fill[1] = lerp_u8(fill[1], tinted_target.g, tint);
-		fill[2] = lerp_u8(fill[2], tinted_target.b, tint);
-		fill[3] = (opacity * 255.0).round().clamp(0.0, 255.0) as u8;
-
-		Color32::from_rgba_unmultiplied(fill[0], fill[1], fill[2], fill[3])
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn run_loupe_tile_egui(
+	fn render_frozen_loupe(
 		&mut self,
-		raw_input: egui::RawInput,
+		ui: &mut Ui,
 		state: &OverlayState,
-		theme: HudTheme,
+		monitor: MonitorRect,
+		cursor: GlobalPoint,
+		cell: f32,
 		hud_blur_active: bool,
 		hud_opaque: bool,
-		body_fill: Color32,
-	) -> (FullOutput, Option<Rect>) {
-		let mut loupe_tile_rect = None;
-		let egui_ctx = self.egui_ctx.clone();
-		let full_output = egui_ctx.run(raw_input, |ctx| {
-			if !state.alt_held {
-				return;
-			}
-
-			const CELL: f32 = 10.0;
-
-			let side = hud_helpers::stable_live_loupe_side_points(state, CELL);
-			let tile_padding = Margin::same(10);
-			let outer_stroke_color = match theme {
-				HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
-				HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-			};
-			let outer_stroke = Stroke::new(1.0, outer_stroke_color);
-			let shadow = egui::epaint::Shadow {
-				offset: [0, 0],
-				blur: 10,
-				spread: 0,
-				color: match theme {
-					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
-					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
-				},
-			};
-			let tile_radius = LOUPE_TILE_CORNER_RADIUS_POINTS as u8;
-			let frame = Frame {
-				fill: body_fill,
-				stroke: outer_stroke,
-				shadow,
-				corner_radius: CornerRadius::same(tile_radius),
-				inner_margin: tile_padding,
-				..Frame::default()
-			};
-			let pad = 6.0;
-
-			Area::new(Id::new("rsnap-loupe-window"))
-				.order(Order::Foreground)
-				.fixed_pos(Pos2::new(pad, pad))
-				.show(ctx, |ui| {
-					let inner = frame.show(ui, |ui| {
-						ui.set_min_size(Vec2::new(side, side));
-						self.render_loupe(ui, state, hud_blur_active, hud_opaque, theme);
-					});
-					let tile_rect = inner.response.rect;
-
-					loupe_tile_rect = Some(tile_rect);
-
-					let inner_stroke_color = match theme {
-						HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
-						HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
-					};
-					let inner_stroke = Stroke::new(1.0, inner_stroke_color);
-					let inner_rect = tile_rect.shrink(1.0);
-
-					ui.painter().rect_stroke(
-						inner_rect,
-						CornerRadius::same(tile_radius.saturating_sub(1)),
-						inner_stroke,
-						StrokeKind::Inside,
-					);
-				});
-		});
-
-		(full_output, loupe_tile_rect)
-	}
-
-	#[allow(clippy::too_many_arguments)]
-	fn update_hud_blur_uniform(
-		&mut self,
-		gpu: &GpuContext,
-		size: PhysicalSize<u32>,
-		pixels_per_point: f32,
 		theme: HudTheme,
-		hud_fog_amount: f32,
-		hud_milk_amount: f32,
-		hud_tint_hue: f32,
 	) {
-		if self.hud_bg.is_none() {
+		if state.loupe.is_some() {
+			self.render_live_loupe(ui, state, monitor, cell, hud_blur_active, hud_opaque, theme);
+
 			return;
 		}
 
-		let Some(hud_pill) = self.hud_pill else {
+		const LOUPE_RADIUS_PX: i32 = 5;
+		const LOUPE_SIDE_PX: i32 = (LOUPE_RADIUS_PX * 2) + 1;
+
+		let side = (LOUPE_SIDE_PX as f32) * cell;
+		let (rect, _) = ui.allocate_exact_size(Vec2::new(side, side), Sense::hover());
+		let Some(image) = state.frozen_image.as_ref() else {
 			return;
 		};
-		let surface_w = size.width as f32;
-		let surface_h = size.height as f32;
-
-		if surface_w <= 0.0 || surface_h <= 0.0 {
+		let Some((center_x, center_y)) = monitor.local_u32_pixels(cursor) else {
 			return;
-		}
-
-		let rect_min_px =
-			[hud_pill.rect.min.x * pixels_per_point, hud_pill.rect.min.y * pixels_per_point];
-		let rect_size_px =
-			[hud_pill.rect.width() * pixels_per_point, hud_pill.rect.height() * pixels_per_point];
-		let rect_min_size = [rect_min_px[0], rect_min_px[1], rect_size_px[0], rect_size_px[1]];
-		let max_lod = self.hud_bg.as_ref().map(|bg| bg.max_lod).unwrap_or(0.0);
-		let tint =
-			Self::tinted_hud_body_fill(theme, false, false, 1.0, hud_milk_amount, hud_tint_hue);
-		let tint_rgba = [
-			hud_helpers::srgb8_to_linear_f32(tint[0]),
-			hud_helpers::srgb8_to_linear_f32(tint[1]),
-			hud_helpers::srgb8_to_linear_f32(tint[2]),
-			hud_helpers::hud_blur_tint_alpha(theme),
-		];
-		let effects =
-			[hud_fog_amount.clamp(0.0, 1.0), hud_milk_amount.clamp(0.0, 1.0), max_lod, 0.0];
-		let u = HudBlurUniformRaw {
-			rect_min_size,
-			radius_blur_soft: [
-				hud_pill.radius_points * pixels_per_point,
-				(0.9 + (hud_fog_amount.clamp(0.0, 1.0) * 3.2)) * pixels_per_point,
-				1.0 * pixels_per_point,
-				0.0,
-			],
-			surface_size_px: [surface_w, surface_h, 0.0, 0.0],
-			tint_rgba,
-			effects,
 		};
+		let (width, height) = image.dimensions();
+		let width = width as i32;
+		let height = height as i32;
+		let center_x = center_x as i32;
+		let center_y = center_y as i32;
+		let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(0, 0, 0, 140));
+		let grid_stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 26));
 
-		gpu.queue.write_buffer(&self.hud_blur_uniform, 0, u.as_bytes());
-	}
+		for dy in -LOUPE_RADIUS_PX..=LOUPE_RADIUS_PX {
+			for dx in -LOUPE_RADIUS_PX..=LOUPE_RADIUS_PX {
+				let x = center_x + dx;
+				let y = center_y + dy;
+				let cell_x = dx + LOUPE_RADIUS_PX;
+				let cell_y = dy + LOUPE_RADIUS_PX;
+				let cell_min = Pos2::new(
+					rect.min.x + (cell_x as f32) * cell,
+					rect.min.y + (cell_y as f32) * cell,
+				);
+				let cell_rect = Rect::from_min_size(cell_min, Vec2::splat(cell));
+				let fill = if x < 0 || y < 0 || x >= width || y >= height {
+					Color32::from_rgba_unmultiplied(0, 0, 0, 0)
+				} else {
+					let pixel =
+						image.get_pixel_checked(x as u32, y as u32).expect("pixel bounds checked");
 
-	fn sync_hud_bg(
-		&mut self,
-		gpu: &GpuContext,
-		state: &OverlayState,
-		monitor: MonitorRect,
-	) -> Result<()> {
-		let (target_generation, target_image) = match state.mode {
-			OverlayMode::Live if state.live_bg_monitor == Some(monitor) => {
-				(state.live_bg_generation, state.live_bg_image.as_ref())
-			},
-			OverlayMode::Frozen if state.monitor == Some(monitor) => {
-				(state.frozen_generation, state.frozen_image.as_ref())
-			},
-			OverlayMode::Live => {
-				self.hud_bg = None;
-				self.hud_bg_generation = state.live_bg_generation;
-
-				return Ok(());
-			},
-			OverlayMode::Frozen => {
-				self.hud_bg = None;
-				self.hud_bg_generation = state.frozen_generation;
-
-				return Ok(());
-			},
-		};
+					Color32::from_rgb(pixel.0[0], pixel.0[1], pixel.0[2])
+				};
 
-		if self.hud_bg.is_some() && self.hud_bg_generation == target_generation {
-			if target_image.is_none() {
-				// Keep displaying the already-uploaded background even if image bytes moved.
-				return Ok(());
+				ui.painter().rect_filled(cell_rect, 0.0, fill);
 			}
+		}
+		for i in 0..=LOUPE_SIDE_PX {
+			let x = rect.min.x + (i as f32) * cell;
+			let y = rect.min.y + (i as f32) * cell;
 
-			return Ok(());
+			ui.painter()
+				.line_segment([Pos2::new(x, rect.min.y), Pos2::new(x, rect.max.y)], grid_stroke);
+			ui.painter()
+				.line_segment([Pos2::new(rect.min.x, y), Pos2::new(rect.max.x, y)], grid_stroke);
 		}
 
-		let Some(image) = target_image else {
-			// Capture is in progress and no image is available yet.
-			self.hud_bg = None;
-			self.hud_bg_generation = target_generation;
+		ui.painter().rect_stroke(rect, 3.0, stroke, StrokeKind::Outside);
 
-			return Ok(());
+		let center_min = Pos2::new(
+			rect.min.x + (LOUPE_RADIUS_PX as f32) * cell,
+			rect.min.y + (LOUPE_RADIUS_PX as f32) * cell,
+		);
+		let center_rect = Rect::from_min_size(center_min, Vec2::splat(cell));
+
+		ui.painter().rect_stroke(
+			center_rect,
+			0.0,
+			Stroke::new(2.0, Color32::from_rgba_unmultiplied(255, 255, 255, 180)),
+			StrokeKind::Inside,
+		);
+	}
+
+	fn sync_egui_textures(&mut self, gpu: &GpuContext, full_output: &FullOutput) {
+		for (id, image_delta) in &full_output.textures_delta.set {
+			self.egui_renderer.update_texture(&gpu.device, &gpu.queue, *id, image_delta);
+		}
+		for id in &full_output.textures_delta.free {
+			self.egui_renderer.free_texture(id);
+		}
+	}
+
+	fn acquire_frame(&mut self, gpu: &GpuContext) -> Result<RenderFrame> {
+		let started_at = Instant::now();
+		let frame = match &self.target {
+			RenderTarget::Window { surface, .. } => match surface.get_current_texture() {
+				Ok(frame) => Ok(frame),
+				Err(SurfaceError::Outdated | SurfaceError::Lost) => {
+					self.reconfigure(gpu);
+
+					self.needs_reconfigure = false;
+
+					match &self.target {
+						RenderTarget::Window { surface, .. } => surface
+							.get_current_texture()
+							.wrap_err("Surface was lost and could not be reacquired"),
+						#[cfg(test)]
+						RenderTarget::Offscreen { .. } => unreachable!("the target is a window"),
+					}
+				},
+				Err(err) => Err(err).wrap_err("Failed to acquire surface texture"),
+			}
+			.map(|surface_texture| RenderFrame {
+				view: surface_texture.texture.create_view(&TextureViewDescriptor::default()),
+				surface_texture: Some(surface_texture),
+			}),
+			#[cfg(test)]
+			RenderTarget::Offscreen { texture, .. } => Ok(RenderFrame {
+				view: texture.create_view(&TextureViewDescriptor::default()),
+				surface_texture: None,
+			}),
 		};
+		let elapsed = started_at.elapsed();
 
-		self.render_frozen_bg_to_texture(gpu, image, target_generation)
+		self.slow_op_logger.warn_if_slow(
+			"overlay.window_renderer_acquire_frame",
+			elapsed,
+			SLOW_OP_WARN_RENDER,
+			|| format!("needs_reconfigure={}", self.needs_reconfigure),
+		);
+
+		frame
 	}
 
-	fn render_frozen_bg_to_texture(
+	#[allow(clippy::too_many_arguments)]
+	fn render_frame(
 		&mut self,
 		gpu: &GpuContext,
-		image: &RgbaImage,
-		target_generation: u64,
+		draw_frozen_bg: bool,
+		hud_blur_active: bool,
+		frame: RenderFrame,
+		paint_jobs: &[ClippedPrimitive],
+		screen_descriptor: &ScreenDescriptor,
 	) -> Result<()> {
-		let upload_image = image_helpers::downscale_for_gpu_upload(
-			image,
-			gpu.device.limits().max_texture_dimension_2d,
+		let started_at = Instant::now();
+		let RenderFrame { view, surface_texture } = frame;
+		let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+			label: Some("rsnap-overlay encoder"),
+		});
+		let _user_cmds = self.egui_renderer.update_buffers(
+			&gpu.device,
+			&gpu.queue,
+			&mut encoder,
+			paint_jobs,
+			screen_descriptor,
 		);
-		let (width, height) = upload_image.dimensions();
-		let max_side = gpu.device.limits().max_texture_dimension_2d;
-		let mip_level_count = Self::mip_level_count(width, height).min(10);
 
-		debug_assert!(width <= max_side && height <= max_side);
+		{
+			let rpass_desc = wgpu::RenderPassDescriptor {
+				label: Some("rsnap-overlay renderpass"),
+				color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+					view: &view,
+					depth_slice: None,
+					resolve_target: None,
+					ops: wgpu::Operations {
+						load: LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 }),
+						store: StoreOp::Store,
+					},
+				})],
+				depth_stencil_attachment: None,
+				timestamp_writes: None,
+				occlusion_query_set: None,
+			};
+			let mut rpass = encoder.begin_render_pass(&rpass_desc).forget_lifetime();
 
-		let texture = gpu.device.create_texture(&wgpu::TextureDescriptor {
-			label: Some("rsnap-frozen-bg texture"),
-			size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
-			mip_level_count,
-			sample_count: 1,
-			dimension: TextureDimension::D2,
-			format: wgpu::TextureFormat::Rgba8UnormSrgb,
-			usage: TextureUsages::TEXTURE_BINDING
-				| TextureUsages::COPY_DST
-				| TextureUsages::RENDER_ATTACHMENT,
-			view_formats: &[],
-		});
-		let upload_bytes = upload_image.as_raw();
-		let bytes_per_pixel = 4_usize;
-		let unpadded_bytes_per_row = (width as usize) * bytes_per_pixel;
-		let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
-		let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
-		let rgba_padded;
-		let rgba_bytes: &[u8] = if padded_bytes_per_row == unpadded_bytes_per_row {
-			upload_bytes
-		} else {
-			let src = upload_bytes;
+			if draw_frozen_bg
+				&& self.shader_pipelines.mipgen_valid
+				&& let Some(bg) = &self.hud_bg
+			{
+				if self.color_vision_active && self.shader_pipelines.color_vision_valid {
+					rpass.set_pipeline(&self.shader_pipelines.color_vision);
+					rpass.set_bind_group(1, &self.color_vision_bind_group, &[]);
+				} else {
+					rpass.set_pipeline(&self.shader_pipelines.mipgen_surface);
+				}
 
-			rgba_padded = image_helpers::pad_rows(
-				src,
-				unpadded_bytes_per_row,
-				padded_bytes_per_row,
-				height as usize,
-			);
+				rpass.set_bind_group(0, &bg.mipgen_bind_group, &[]);
+				rpass.draw(0..3, 0..1);
 
-			&rgba_padded
-		};
+				if self.frozen_dim_active && self.shader_pipelines.frozen_dim_valid {
+					rpass.set_pipeline(&self.shader_pipelines.frozen_dim);
+					rpass.set_bind_group(0, &self.frozen_dim_bind_group, &[]);
+					rpass.draw(0..3, 0..1);
+				}
+			}
+			// The blur samples the mip chain, so it is skipped when either shader failed.
+			if hud_blur_active
+				&& self.shader_pipelines.hud_blur_valid
+				&& self.shader_pipelines.mipgen_valid
+				&& self.hud_pill.is_some()
+				&& let Some(bg) = &self.hud_bg
+			{
+				if let Some(pill) = self.hud_pill {
+					let ppp = screen_descriptor.pixels_per_point;
+					let pad_px = (24.0 * ppp).ceil() as i32;
+					let surface_w = screen_descriptor.size_in_pixels[0].max(1) as i32;
+					let surface_h = screen_descriptor.size_in_pixels[1].max(1) as i32;
+					let min_x_bound = (surface_w - 1).max(0);
+					let min_y_bound = (surface_h - 1).max(0);
+					let min_x =
+						((pill.rect.min.x * ppp).floor() as i32 - pad_px).clamp(0, min_x_bound);
+					let min_y =
+						((pill.rect.min.y * ppp).floor() as i32 - pad_px).clamp(0, min_y_bound);
+					let max_x =
+						((pill.rect.max.x * ppp).ceil() as i32 + pad_px).clamp(0, surface_w);
+					let max_y =
+						((pill.rect.max.y * ppp).ceil() as i32 + pad_px).clamp(0, surface_h);
+					let w = (max_x - min_x).max(1) as u32;
+					let h = (max_y - min_y).max(1) as u32;
 
-		gpu.queue.write_texture(
-			wgpu::TexelCopyTextureInfo {
-				texture: &texture,
-				mip_level: 0,
-				origin: Origin3d::ZERO,
-				aspect: TextureAspect::All,
-			},
-			rgba_bytes,
-			wgpu::TexelCopyBufferLayout {
-				offset: 0,
-				bytes_per_row: Some(padded_bytes_per_row as u32),
-				rows_per_image: Some(height),
-			},
-			wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
-		);
-		self.generate_mipmaps(gpu, &texture, mip_level_count);
+					rpass.set_scissor_rect(min_x as u32, min_y as u32, w, h);
+				}
 
-		let view = texture.create_view(&TextureViewDescriptor::default());
-		let hud_blur_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
-			label: Some("rsnap-hud-blur bind group"),
-			layout: &self.hud_blur_bind_group_layout,
-			entries: &[
-				wgpu::BindGroupEntry { binding: 0, resource: BindingResource::TextureView(&view) },
-				wgpu::BindGroupEntry {
-					binding: 1,
-					resource: BindingResource::Sampler(&self.bg_sampler),
-				},
-				wgpu::BindGroupEntry {
-					binding: 2,
-					resource: self.hud_blur_uniform.as_entire_binding(),
-				},
-			],
-		});
-		let mipgen_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
-			label: Some("rsnap-mipgen fullscreen bind group"),
-			layout: &self.mipgen_bind_group_layout,
-			entries: &[
-				wgpu::BindGroupEntry { binding: 0, resource: BindingResource::TextureView(&view) },
-				wgpu::BindGroupEntry {
-					binding: 1,
-					resource: BindingResource::Sampler(&self.bg_sampler),
-				},
-			],
-		});
-		let max_lod = (mip_level_count.saturating_sub(1)) as f32;
+				rpass.set_pipeline(&self.shader_pipelines.hud_blur);
+				rpass.set_bind_group(0, &bg.hud_blur_bind_group, &[]);
+				rpass.draw(0..3, 0..1);
+				rpass.set_scissor_rect(
+					0,
+					0,
+					screen_descriptor.size_in_pixels[0].max(1),
+					screen_descriptor.size_in_pixels[1].max(1),
+				);
+			}
 
-		self.hud_bg = Some(HudBg {
-			_texture: texture,
-			_view: view,
-			hud_blur_bind_group,
-			mipgen_bind_group,
-			max_lod,
-		});
-		self.hud_bg_generation = target_generation;
+			self.egui_renderer.render(&mut rpass, paint_jobs, screen_descriptor);
+		}
 
-		Ok(())
-	}
-}
+		gpu.queue.submit(Some(encoder.finish()));
 
-struct HudBg {
-	_texture: Texture,
-	_view: TextureView,
-	hud_blur_bind_group: BindGroup,
-	mipgen_bind_group: BindGroup,
-	max_lod: f32,
-}
+		if let Some(surface_texture) = surface_texture {
+			surface_texture.present();
+		}
+
+		self.slow_op_logger.warn_if_slow(
+			"overlay.window_renderer_render_frame",
+			started_at.elapsed(),
+			SLOW_OP_WARN_RENDER,
+			|| {
+				format!(
+					"draw_frozen_bg={} hud_blur_active={} paint_jobs={}",
+					draw_frozen_bg,
+					hud_blur_active,
+					paint_jobs.len()
+				)
+			},
+		);
+
+		Ok(())
+	}
+
+	fn new(
+		gpu: &GpuContext,
+		window: Arc<winit::window::Window>,
+		egui_repaint_deadline: Arc<Mutex<Option<Instant>>>,
+	) -> Result<Self> {
+		let surface = gpu
+			.instance
+			.create_surface(Arc::clone(&window))
+			.wrap_err("wgpu create_surface failed")?;
+		let caps = surface.get_capabilities(&gpu.adapter);
+		let surface_format = Self::pick_surface_format(&caps);
+		let surface_alpha = Self::pick_surface_alpha(&caps);
+		let surface_config =
+			Self::make_surface_config(window.as_ref(), surface_format, surface_alpha);
+
+		surface.configure(&gpu.device, &surface_config);
+
+		Ok(Self::with_target(
+			gpu,
+			RenderTarget::Window { window, surface },
+			surface_config,
+			egui_repaint_deadline,
+		))
+	}
+
+	/// Creates a renderer that draws into an offscreen texture of `size` physical pixels.
+	#[cfg(test)]
+	fn new_offscreen(gpu: &GpuContext, size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+		let surface_config = wgpu::SurfaceConfiguration {
+			usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+			format: wgpu::TextureFormat::Rgba8UnormSrgb,
+			width: size.width.max(1),
+			height: size.height.max(1),
+			present_mode: PresentMode::Fifo,
+			alpha_mode: CompositeAlphaMode::PreMultiplied,
+			view_formats: vec![],
+			desired_maximum_frame_latency: 2,
+		};
+		let texture = Self::create_offscreen_texture(gpu, &surface_config);
+
+		Self::with_target(
+			gpu,
+			RenderTarget::Offscreen { texture, scale_factor, frames_drawn: 0 },
+			surface_config,
+			Arc::new(Mutex::new(None)),
+		)
+	}
+
+	fn with_target(
+		gpu: &GpuContext,
+		target: RenderTarget,
+		surface_config: wgpu::SurfaceConfiguration,
+		egui_repaint_deadline: Arc<Mutex<Option<Instant>>>,
+	) -> Self {
+		let surface_format = surface_config.format;
+		let egui_ctx = Self::create_egui_context(&egui_repaint_deadline);
+		let egui_renderer = Renderer::new(
+			&gpu.device,
+			surface_format,
+			egui_wgpu::RendererOptions {
+				msaa_samples: 1,
+				depth_stencil_format: None,
+				dithering: false,
+				predictable_texture_filtering: false,
+			},
+		);
+		let bg_sampler = Self::create_bg_sampler(gpu);
+		let sources = ShaderSources::default();
+		let mipgen_bind_group_layout = Self::create_mipgen_bind_group_layout(gpu);
+		let hud_blur_bind_group_layout = Self::create_hud_blur_bind_group_layout(gpu);
+		let frozen_dim_bind_group_layout = Self::create_frozen_dim_bind_group_layout(gpu);
+		let color_vision_bind_group_layout = Self::create_color_vision_bind_group_layout(gpu);
+		let shader_pipelines = Self::create_shader_pipelines(
+			gpu,
+			surface_format,
+			&mipgen_bind_group_layout,
+			&hud_blur_bind_group_layout,
+			&frozen_dim_bind_group_layout,
+			&color_vision_bind_group_layout,
+			&sources,
+		);
+		let failed_shaders = shader_pipelines.failed_shaders();
+
+		if !failed_shaders.is_empty() {
+			tracing::error!(
+				op = "overlay.shader_validation",
+				failed = ?failed_shaders,
+				"Built-in overlay shaders failed validation; drawing without those effects."
+			);
+		}
+
+		let (frozen_dim_uniform, frozen_dim_bind_group) = Self::create_uniform_binding(
+			gpu,
+			"rsnap-frozen-dim",
+			&frozen_dim_bind_group_layout,
+			mem::size_of::<FrozenDimUniformRaw>(),
+		);
+		let (color_vision_uniform, color_vision_bind_group) = Self::create_uniform_binding(
+			gpu,
+			"rsnap-color-vision",
+			&color_vision_bind_group_layout,
+			mem::size_of::<ColorVisionUniformRaw>(),
+		);
+		let hud_blur_uniform = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("rsnap-hud-blur uniform"),
+			size: mem::size_of::<HudBlurUniformRaw>() as u64,
+			usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let now = Instant::now();
+
+		Self {
+			target,
+			surface_config,
+			needs_reconfigure: false,
+			egui_ctx,
+			egui_renderer,
+			bg_sampler,
+			shader_pipelines,
+			mipgen_bind_group_layout,
+			hud_blur_bind_group_layout,
+			hud_blur_uniform,
+			frozen_dim_bind_group_layout,
+			frozen_dim_uniform,
+			frozen_dim_bind_group,
+			frozen_dim_active: false,
+			color_vision_bind_group_layout,
+			color_vision_uniform,
+			color_vision_bind_group,
+			color_vision_active: false,
+			shader_generation: sources.generation,
+			hud_bg: None,
+			hud_bg_generation: 0,
+			hud_pill: None,
+			hud_action: None,
+			loupe_cell_click: None,
+			loupe_tile: None,
+			live_loupe_texture: None,
+			export_preview_texture: None,
+			frozen_view_texture: None,
+			hud_theme: None,
+			content_opacity: 1.0,
+			egui_start_time: now,
+			egui_last_frame_time: now,
+			selection_flow_cache: SelectionFlowGeometryCache::default(),
+			slow_op_logger: SlowOperationLogger::default(),
+		}
+	}
+
+	/// Creates the egui context with the Phosphor icon fonts and a repaint callback that records
+	/// the earliest requested repaint in `egui_repaint_deadline`.
+	fn create_egui_context(egui_repaint_deadline: &Arc<Mutex<Option<Instant>>>) -> egui::Context {
+		let egui_ctx = egui::Context::default();
+		let mut fonts = FontDefinitions::default();
+
+		egui_phosphor::add_to_fonts(&mut fonts, Variant::Regular);
+
+		let phosphor_fill = String::from("phosphor-fill");
+		let proportional_fallback =
+			fonts.families.get(&FontFamily::Proportional).and_then(|names| names.first()).cloned();
+
+		fonts.font_data.insert(phosphor_fill.clone(), Variant::Fill.font_data().into());
+
+		{
+			let family =
+				fonts.families.entry(FontFamily::Name(phosphor_fill.clone().into())).or_default();
+
+			family.insert(0, phosphor_fill.clone());
+
+			if let Some(fallback) = proportional_fallback
+				&& !family.contains(&fallback)
+			{
+				family.push(fallback);
+			}
+		}
+
+		egui_ctx.set_fonts(fonts);
+
+		let repaint_deadline = Arc::clone(egui_repaint_deadline);
+
+		egui_ctx.set_request_repaint_callback(move |info| {
+			let deadline = Instant::now() + info.delay;
+			let mut next_repaint = repaint_deadline.lock().unwrap_or_else(|err| err.into_inner());
+			let needs_update = next_repaint.is_none_or(|previous| deadline < previous);
+
+			if needs_update {
+				*next_repaint = Some(deadline);
+			}
+		});
+
+		egui_ctx
+	}
+
+	/// Creates a uniform buffer of `size` bytes and a bind group exposing it at binding 0.
+	fn create_uniform_binding(
+		gpu: &GpuContext,
+		label: &str,
+		layout: &BindGroupLayout,
+		size: usize,
+	) -> (Buffer, BindGroup) {
+		let uniform = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some(&format!("{label} uniform")),
+			size: size as u64,
+			usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some(&format!("{label} bind group")),
+			layout,
+			entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform.as_entire_binding() }],
+		});
+
+		(uniform, bind_group)
+	}
+
+	#[cfg(test)]
+	fn create_offscreen_texture(gpu: &GpuContext, config: &wgpu::SurfaceConfiguration) -> Texture {
+		gpu.device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("rsnap-overlay offscreen target"),
+			size: wgpu::Extent3d {
+				width: config.width,
+				height: config.height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: TextureDimension::D2,
+			format: config.format,
+			usage: config.usage,
+			view_formats: &[],
+		})
+	}
+
+	/// Reads the offscreen target back as straight RGBA, as last drawn.
+	#[cfg(test)]
+	fn read_offscreen_image(&self, gpu: &GpuContext) -> Result<RgbaImage> {
+		let RenderTarget::Offscreen { texture, .. } = &self.target else {
+			return Err(eyre::eyre!("Renderer does not draw offscreen"));
+		};
+		let width = texture.width();
+		let height = texture.height();
+		let unpadded_row_bytes = width as usize * 4;
+		let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+		let padded_row_bytes = unpadded_row_bytes.div_ceil(align) * align;
+		let buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("rsnap-overlay offscreen readback"),
+			size: (padded_row_bytes * height as usize) as u64,
+			usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+			mapped_at_creation: false,
+		});
+		let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+			label: Some("rsnap-overlay offscreen readback encoder"),
+		});
+
+		encoder.copy_texture_to_buffer(
+			texture.as_image_copy(),
+			wgpu::TexelCopyBufferInfo {
+				buffer: &buffer,
+				layout: wgpu::TexelCopyBufferLayout {
+					offset: 0,
+					bytes_per_row: Some(padded_row_bytes as u32),
+					rows_per_image: Some(height),
+				},
+			},
+			texture.size(),
+		);
+		gpu.queue.submit(Some(encoder.finish()));
+
+		let slice = buffer.slice(..);
+
+		slice.map_async(wgpu::MapMode::Read, |_| {});
+		gpu.device
+			.poll(wgpu::PollType::wait_indefinitely())
+			.map_err(|err| eyre::eyre!("Offscreen readback failed: {err}"))?;
+
+		let mapped = slice.get_mapped_range();
+		let mut pixels = Vec::with_capacity(unpadded_row_bytes * height as usize);
+
+		for row in mapped.chunks(padded_row_bytes).take(height as usize) {
+			pixels.extend_from_slice(&row[..unpadded_row_bytes]);
+		}
+
+		drop(mapped);
+		buffer.unmap();
+
+		let mut image = RgbaImage::from_raw(width, height, pixels)
+			.ok_or_else(|| eyre::eyre!("Offscreen readback has the wrong size"))?;
+
+		// The target holds premultiplied color; golden images store straight alpha.
+		for pixel in image.pixels_mut() {
+			let alpha = u16::from(pixel.0[3]);
+
+			if alpha > 0 && alpha < 255 {
+				for channel in &mut pixel.0[..3] {
+					*channel = ((u16::from(*channel) * 255 + alpha / 2) / alpha).min(255) as u8;
+				}
+			}
+		}
+
+		Ok(image)
+	}
+
+	fn window_id(&self) -> WindowId {
+		match &self.target {
+			RenderTarget::Window { window, .. } => window.id(),
+			#[cfg(test)]
+			RenderTarget::Offscreen { .. } => WindowId::dummy(),
+		}
+	}
+
+	fn target_inner_size(&self) -> PhysicalSize<u32> {
+		match &self.target {
+			RenderTarget::Window { window, .. } => window.inner_size(),
+			#[cfg(test)]
+			RenderTarget::Offscreen { .. } => {
+				PhysicalSize::new(self.surface_config.width, self.surface_config.height)
+			},
+		}
+	}
+
+	fn target_scale_factor(&self) -> f64 {
+		match &self.target {
+			RenderTarget::Window { window, .. } => window.scale_factor(),
+			#[cfg(test)]
+			RenderTarget::Offscreen { scale_factor, .. } => *scale_factor,
+		}
+	}
+
+	fn resize(&mut self, size: PhysicalSize<u32>) -> Result<()> {
+		self.surface_config.width = size.width.max(1);
+		self.surface_config.height = size.height.max(1);
+		self.needs_reconfigure = true;
+
+		Ok(())
+	}
+
+	fn reconfigure(&mut self, gpu: &GpuContext) {
+		match &mut self.target {
+			RenderTarget::Window { surface, .. } => {
+				surface.configure(&gpu.device, &self.surface_config);
+			},
+			#[cfg(test)]
+			RenderTarget::Offscreen { texture, .. } => {
+				*texture = Self::create_offscreen_texture(gpu, &self.surface_config);
+			},
+		}
+	}
+
+	fn sync_egui_theme(&mut self, theme: HudTheme) {
+		if self.hud_theme == Some(theme) {
+			return;
+		}
+
+		match theme {
+			HudTheme::Dark => self.egui_ctx.set_visuals(Visuals::dark()),
+			HudTheme::Light => self.egui_ctx.set_visuals(Visuals::light()),
+		}
+
+		self.hud_theme = Some(theme);
+	}
+
+	fn prepare_window_renderer_input(
+		&mut self,
+		gpu: &GpuContext,
+		monitor: MonitorRect,
+		toolbar_pointer: Option<FrozenToolbarPointerState>,
+		theme_mode: ThemeMode,
+		phase_timings: &mut WindowRendererPhaseTimings,
+	) -> (HudTheme, PhysicalSize<u32>, f32, egui::RawInput) {
+		self.apply_pending_reconfigure(gpu);
+
+		let window_theme = match &self.target {
+			RenderTarget::Window { window, .. } => window.theme(),
+			#[cfg(test)]
+			RenderTarget::Offscreen { .. } => None,
+		};
+		let theme = hud_helpers::effective_hud_theme(theme_mode, window_theme);
+
+		self.sync_egui_theme(theme);
+
+		let prepare_input_started_at = Instant::now();
+		let (size, pixels_per_point, raw_input) =
+			self.prepare_egui_input(gpu, toolbar_pointer, Some(monitor.scale_factor()));
+
+		phase_timings.prepare_input = prepare_input_started_at.elapsed();
+
+		(theme, size, pixels_per_point, raw_input)
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn maybe_update_hud_blur_uniform(
+		&mut self,
+		gpu: &GpuContext,
+		size: PhysicalSize<u32>,
+		pixels_per_point: f32,
+		theme: HudTheme,
+		hud_shader_blur_active: bool,
+		hud_fog_amount: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		hud_blur_dither: bool,
+		phase_timings: &mut WindowRendererPhaseTimings,
+	) {
+		if !hud_shader_blur_active {
+			return;
+		}
+
+		let update_hud_blur_uniform_started_at = Instant::now();
+
+		self.update_hud_blur_uniform(
+			gpu,
+			size,
+			pixels_per_point,
+			theme,
+			hud_fog_amount,
+			hud_milk_amount,
+			hud_tint_hue,
+			hud_blur_dither,
+		);
+
+		phase_timings.update_hud_blur_uniform = update_hud_blur_uniform_started_at.elapsed();
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn finish_window_renderer_draw(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		path: WindowRendererPath,
+		monitor: MonitorRect,
+		size: PhysicalSize<u32>,
+		pixels_per_point: f32,
+		draw_started_at: Instant,
+		phase_timings: &mut WindowRendererPhaseTimings,
+		paint_jobs: Vec<ClippedPrimitive>,
+		draw_frozen_bg: bool,
+		hud_shader_blur_active: bool,
+		toolbar_active: bool,
+	) -> Result<()> {
+		let screen_descriptor =
+			ScreenDescriptor { size_in_pixels: [size.width, size.height], pixels_per_point };
+		let acquire_frame_started_at = Instant::now();
+		let frame = self.acquire_frame(gpu)?;
+
+		phase_timings.acquire_frame = acquire_frame_started_at.elapsed();
+
+		let render_frame_started_at = Instant::now();
+
+		self.render_frame(
+			gpu,
+			draw_frozen_bg,
+			hud_shader_blur_active,
+			frame,
+			&paint_jobs,
+			&screen_descriptor,
+		)?;
+
+		phase_timings.render_frame = render_frame_started_at.elapsed();
+		phase_timings.total = draw_started_at.elapsed();
+
+		let window_id = self.window_id();
+
+		phase_timings.warn_if_substeps_slow(
+			&mut self.slow_op_logger,
+			path,
+			window_id,
+			monitor.id,
+			state.mode,
+			paint_jobs.len(),
+		);
+		phase_timings.trace(
+			path,
+			window_id,
+			monitor.id,
+			state.mode,
+			toolbar_active,
+			paint_jobs.len(),
+		);
+
+		Ok(())
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn draw(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		draw_hud: bool,
+		hud_local_cursor_override: Option<Pos2>,
+		hud_compact: bool,
+		hud_anchor: HudAnchor,
+		toolbar_placement: ToolbarPlacement,
+		show_alt_hint_keycap: bool,
+		show_hud_blur: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
+		hud_fog_amount: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		theme_mode: ThemeMode,
+		selection_particles: bool,
+		selection_flow_stroke: SelectionFlowStroke,
+		allow_frozen_surface_bg: bool,
+		show_frozen_capture_affordance: bool,
+		frozen_capture_is_fullscreen_fallback: bool,
+		toolbar_state: Option<&mut FrozenToolbarState>,
+		toolbar_pointer: Option<FrozenToolbarPointerState>,
+	) -> Result<()> {
+		let draw_started_at = Instant::now();
+		let mut phase_timings = WindowRendererPhaseTimings::default();
+		let (theme, size, pixels_per_point, raw_input) = self.prepare_window_renderer_input(
+			gpu,
+			monitor,
+			toolbar_pointer,
+			theme_mode,
+			&mut phase_timings,
+		);
+		let toolbar_active = toolbar_state.is_some();
+
+		self.trace_frozen_frame_metrics(state, monitor, size, pixels_per_point, toolbar_active);
+
+		self.loupe_tile = None;
+
+		let hud_cfg = Self::resolve_hud_draw_config(
+			state,
+			monitor,
+			draw_hud,
+			allow_frozen_surface_bg,
+			toolbar_active,
+			show_hud_blur,
+			hud_opaque,
+		);
+		let sync_hud_bg_started_at = Instant::now();
+
+		self.sync_or_clear_hud_bg(gpu, state, monitor, hud_cfg)?;
+
+		phase_timings.sync_hud_bg = sync_hud_bg_started_at.elapsed();
+
+		let hud_shader_blur_active = self.hud_shader_blur_active(state, monitor, hud_cfg);
+		let mut selection_flow_cache = mem::take(&mut self.selection_flow_cache);
+		let run_egui_started_at = Instant::now();
+		let (full_output, hud_pill) = self.run_egui(
+			raw_input,
+			state,
+			monitor,
+			hud_cfg.can_draw_hud,
+			hud_local_cursor_override,
+			hud_compact,
+			show_hud_blur,
+			hud_anchor,
+			toolbar_placement,
+			show_alt_hint_keycap,
+			hud_cfg.hud_glass_active,
+			hud_opaque,
+			hud_opacity,
+			hud_milk_amount,
+			hud_tint_hue,
+			theme,
+			selection_particles,
+			selection_flow_stroke,
+			hud_cfg.needs_frozen_surface_bg,
+			show_frozen_capture_affordance,
+			frozen_capture_is_fullscreen_fallback,
+			&mut selection_flow_cache,
+			toolbar_state,
+			toolbar_pointer,
+		);
+
+		phase_timings.run_egui = run_egui_started_at.elapsed();
+		self.selection_flow_cache = selection_flow_cache;
+		self.hud_pill = hud_pill;
+
+		self.maybe_update_hud_blur_uniform(
+			gpu,
+			size,
+			pixels_per_point,
+			theme,
+			hud_shader_blur_active,
+			hud_fog_amount,
+			hud_milk_amount,
+			hud_tint_hue,
+			state.hud_blur_dither,
+			&mut phase_timings,
+		);
+
+		let sync_egui_textures_started_at = Instant::now();
+
+		self.sync_egui_textures(gpu, &full_output);
+
+		phase_timings.sync_egui_textures = sync_egui_textures_started_at.elapsed();
+
+		let tessellate_started_at = Instant::now();
+		let mut paint_jobs = self.egui_ctx.tessellate(full_output.shapes, pixels_per_point);
+
+		phase_timings.tessellate = tessellate_started_at.elapsed();
+
+		motion::fade_paint_jobs(&mut paint_jobs, self.content_opacity);
+
+		// A zoomed frozen view draws the background through egui instead.
+		let draw_frozen_bg = hud_cfg.needs_frozen_surface_bg
+			&& state.monitor == Some(monitor)
+			&& state.frozen_image.is_some()
+			&& self.frozen_view_texture.is_none();
+
+		self.update_frozen_dim_uniform(gpu, state, size, pixels_per_point, draw_frozen_bg);
+		self.update_color_vision_uniform(gpu, state, draw_frozen_bg);
+
+		self.finish_window_renderer_draw(
+			gpu,
+			state,
+			WindowRendererPath::Overlay,
+			monitor,
+			size,
+			pixels_per_point,
+			draw_started_at,
+			&mut phase_timings,
+			paint_jobs,
+			draw_frozen_bg,
+			hud_shader_blur_active,
+			toolbar_active,
+		)
+	}
+
+	fn update_frozen_dim_uniform(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		size: PhysicalSize<u32>,
+		pixels_per_point: f32,
+		draw_frozen_bg: bool,
+	) {
+		let uniform = state
+			.frozen_capture_rect
+			.filter(|_| draw_frozen_bg && matches!(state.mode, OverlayMode::Frozen))
+			.and_then(|selection| {
+				FrozenDimUniformRaw::new(
+					selection,
+					pixels_per_point,
+					size,
+					state.frozen_dim_opacity,
+					state.frozen_dim_vignette,
+					state.frozen_dim_noise,
+				)
+			});
+
+		self.frozen_dim_active = uniform.is_some();
+
+		if let Some(uniform) = uniform {
+			gpu.queue.write_buffer(&self.frozen_dim_uniform, 0, uniform.as_bytes());
+		}
+	}
+
+	fn update_color_vision_uniform(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		draw_frozen_bg: bool,
+	) {
+		let uniform = state
+			.color_vision
+			.matrix()
+			.filter(|_| draw_frozen_bg && matches!(state.mode, OverlayMode::Frozen))
+			.map(ColorVisionUniformRaw::new);
+
+		self.color_vision_active = uniform.is_some();
+
+		if let Some(uniform) = uniform {
+			gpu.queue.write_buffer(&self.color_vision_uniform, 0, uniform.as_bytes());
+		}
+	}
+
+	fn trace_frozen_frame_metrics(
+		&self,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		size: PhysicalSize<u32>,
+		pixels_per_point: f32,
+		toolbar_active: bool,
+	) {
+		if !matches!(state.mode, OverlayMode::Frozen) || state.monitor != Some(monitor) {
+			return;
+		}
+
+		let screen_size_points =
+			Vec2::new(size.width as f32 / pixels_per_point, size.height as f32 / pixels_per_point);
+
+		tracing::trace!(
+					window_id = ?self.window_id(),
+					monitor_id = monitor.id,
+					window_scale_factor = self.target_scale_factor(),
+		monitor_scale_factor = monitor.scale_factor(),
+					size_in_pixels = ?size,
+					pixels_per_point,
+					screen_size_points = ?screen_size_points,
+					flip_y = false,
+					frozen_generation = state.frozen_generation,
+					frozen_image_ready = state.frozen_image.is_some(),
+					toolbar_active,
+					"Frozen frame metrics."
+				);
+	}
+
+	fn resolve_hud_draw_config(
+		state: &OverlayState,
+		monitor: MonitorRect,
+		draw_hud: bool,
+		allow_frozen_surface_bg: bool,
+		toolbar_active: bool,
+		show_hud_blur: bool,
+		hud_opaque: bool,
+	) -> HudDrawConfig {
+		let can_draw_hud = draw_hud && Self::should_draw_hud(state, monitor);
+		let needs_frozen_surface_bg =
+			allow_frozen_surface_bg && !draw_hud && matches!(state.mode, OverlayMode::Frozen);
+		// `show_hud_blur` is a UX toggle for "glass mode".
+		// - On macOS: HUD uses native compositor blur.
+		// - On non-macOS: HUD keeps the shader blur path.
+		// - Everywhere: the toolbar is its own popup window and relies on compositor blur, so
+		//   shader blur stays tied to monitor-aligned overlay windows.
+		let hud_glass_active = can_draw_hud && show_hud_blur && !hud_opaque;
+		let toolbar_glass_active = toolbar_active && show_hud_blur && !hud_opaque;
+		let use_shader_blur_for_hud = !cfg!(target_os = "macos");
+		let needs_shader_blur_bg =
+			toolbar_glass_active || (hud_glass_active && use_shader_blur_for_hud);
+
+		HudDrawConfig {
+			can_draw_hud,
+			needs_frozen_surface_bg,
+			needs_shader_blur_bg,
+			hud_glass_active,
+		}
+	}
+
+	fn sync_or_clear_hud_bg(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		hud_cfg: HudDrawConfig,
+	) -> Result<()> {
+		if hud_cfg.needs_frozen_surface_bg || hud_cfg.needs_shader_blur_bg {
+			return self.sync_hud_bg(gpu, state, monitor);
+		}
+
+		self.hud_bg = None;
+		self.hud_bg_generation = match state.mode {
+			OverlayMode::Live => state.live_bg_generation,
+			OverlayMode::Frozen => state.frozen_generation,
+		};
+
+		Ok(())
+	}
+
+	fn hud_shader_blur_active(
+		&self,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		hud_cfg: HudDrawConfig,
+	) -> bool {
+		hud_cfg.needs_shader_blur_bg
+			&& self.hud_bg.is_some()
+			&& match state.mode {
+				OverlayMode::Live => state.live_bg_monitor == Some(monitor),
+				OverlayMode::Frozen => state.monitor == Some(monitor),
+			}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn draw_loupe_tile_window(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		show_hud_blur: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
+		hud_fog_amount: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		theme_mode: ThemeMode,
+	) -> Result<()> {
+		let draw_started_at = Instant::now();
+		let mut phase_timings = WindowRendererPhaseTimings::default();
+		let (theme, size, pixels_per_point, raw_input) =
+			self.prepare_window_renderer_input(gpu, monitor, None, theme_mode, &mut phase_timings);
+
+		self.loupe_tile = None;
+
+		let shader_blur_active = !cfg!(target_os = "macos")
+			&& matches!(state.mode, OverlayMode::Frozen)
+			&& show_hud_blur
+			&& !hud_opaque;
+		let hud_cfg = HudDrawConfig {
+			can_draw_hud: false,
+			needs_frozen_surface_bg: false,
+			needs_shader_blur_bg: shader_blur_active,
+			hud_glass_active: shader_blur_active,
+		};
+		let sync_hud_bg_started_at = Instant::now();
+
+		self.sync_or_clear_hud_bg(gpu, state, monitor, hud_cfg)?;
+
+		phase_timings.sync_hud_bg = sync_hud_bg_started_at.elapsed();
+
+		let hud_shader_blur_active = self.hud_shader_blur_active(state, monitor, hud_cfg);
+		let hud_blur_active = show_hud_blur && !hud_opaque;
+		let body_fill = Self::tinted_hud_body_fill(
+			theme,
+			hud_blur_active,
+			hud_opaque,
+			hud_opacity,
+			hud_milk_amount,
+			hud_tint_hue,
+		);
+		let run_loupe_tile_egui_started_at = Instant::now();
+		let (full_output, loupe_tile_rect) = self.run_loupe_tile_egui(
+			raw_input,
+			state,
+			monitor,
+			theme,
+			hud_blur_active,
+			hud_opaque,
+			body_fill,
+		);
+
+		phase_timings.run_egui = run_loupe_tile_egui_started_at.elapsed();
+		self.loupe_tile = loupe_tile_rect;
+
+		if hud_shader_blur_active {
+			self.hud_pill = loupe_tile_rect.map(|rect| HudPillGeometry {
+				rect,
+				radius_points: LOUPE_TILE_CORNER_RADIUS_POINTS as f32,
+			});
+
+			if self.hud_pill.is_some() {
+				self.maybe_update_hud_blur_uniform(
+					gpu,
+					size,
+					pixels_per_point,
+					theme,
+					hud_shader_blur_active,
+					hud_fog_amount,
+					hud_milk_amount,
+					hud_tint_hue,
+					state.hud_blur_dither,
+					&mut phase_timings,
+				);
+			}
+		} else {
+			self.hud_pill = None;
+		}
+
+		let sync_egui_textures_started_at = Instant::now();
+
+		self.sync_egui_textures(gpu, &full_output);
+
+		phase_timings.sync_egui_textures = sync_egui_textures_started_at.elapsed();
+
+		let tessellate_started_at = Instant::now();
+		let paint_jobs = self.egui_ctx.tessellate(full_output.shapes, pixels_per_point);
+
+		phase_timings.tessellate = tessellate_started_at.elapsed();
+
+		self.finish_window_renderer_draw(
+			gpu,
+			state,
+			WindowRendererPath::LoupeTile,
+			monitor,
+			size,
+			pixels_per_point,
+			draw_started_at,
+			&mut phase_timings,
+			paint_jobs,
+			false,
+			hud_shader_blur_active,
+			false,
+		)
+	}
+
+	fn tinted_hud_body_fill(
+		theme: HudTheme,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		hud_opacity: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+	) -> Color32 {
+		let mut opacity = if hud_opaque { 1.0 } else { hud_opacity.clamp(0.0, 1.0) };
+
+		if hud_blur_active {
+			opacity = opacity.max(hud_helpers::hud_blur_tint_alpha(theme));
+		}
+
+		let tint = hud_milk_amount.clamp(0.0, 1.0);
+		let mut fill = hud_helpers::hud_body_fill_srgba8(theme, false);
+		let tint_hue = hud_tint_hue.clamp(0.0, 1.0);
+		let tint_saturation = 1.0;
+		let (_, _, base_lightness) = hud_helpers::rgb_to_hsl(Rgb::new(fill[0], fill[1], fill[2]));
+		let tinted_target = hud_helpers::hsl_to_rgb(tint_hue, tint_saturation, base_lightness);
+
+		fn lerp_u8(a: u8, b: u8, t: f32) -> u8 {
+			((f32::from(a) + ((f32::from(b) - f32::from(a)) * t)).round().clamp(0.0, 255.0)) as u8
+		}
+
+		fill[0] = lerp_u8(fill[0], tinted_target.r, tint);
+		fill[1] = lerp_u8(fill[1], tinted_target.g, tint);
+		fill[2] = lerp_u8(fill[2], tinted_target.b, tint);
+		fill[3] = (opacity * 255.0).round().clamp(0.0, 255.0) as u8;
+
+		Color32::from_rgba_unmultiplied(fill[0], fill[1], fill[2], fill[3])
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn run_loupe_tile_egui(
+		&mut self,
+		raw_input: egui::RawInput,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		theme: HudTheme,
+		hud_blur_active: bool,
+		hud_opaque: bool,
+		body_fill: Color32,
+	) -> (FullOutput, Option<Rect>) {
+		let mut loupe_tile_rect = None;
+		let egui_ctx = self.egui_ctx.clone();
+		let full_output = egui_ctx.run(raw_input, |ctx| {
+			if !state.alt_held {
+				return;
+			}
+
+			let side = hud_helpers::stable_live_loupe_side_points(state, state.loupe_cell_points);
+			let tile_padding = Margin::same(10);
+			let outer_stroke_color = match theme {
+				HudTheme::Dark => Color32::from_rgba_unmultiplied(255, 255, 255, 40),
+				HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+			};
+			let outer_stroke = Stroke::new(1.0, outer_stroke_color);
+			let shadow = egui::epaint::Shadow {
+				offset: [0, 0],
+				blur: 10,
+				spread: 0,
+				color: match theme {
+					HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 28),
+					HudTheme::Light => Color32::from_rgba_unmultiplied(0, 0, 0, 18),
+				},
+			};
+			let tile_radius = LOUPE_TILE_CORNER_RADIUS_POINTS as u8;
+			let frame = Frame {
+				fill: body_fill,
+				stroke: outer_stroke,
+				shadow,
+				corner_radius: CornerRadius::same(tile_radius),
+				inner_margin: tile_padding,
+				..Frame::default()
+			};
+			let pad = 6.0;
+
+			Area::new(Id::new("rsnap-loupe-window"))
+				.order(Order::Foreground)
+				.fixed_pos(Pos2::new(pad, pad))
+				.show(ctx, |ui| {
+					let inner = frame.show(ui, |ui| {
+						ui.set_min_size(Vec2::new(side, side));
+						self.render_loupe(ui, state, monitor, hud_blur_active, hud_opaque, theme);
+					});
+					let tile_rect = inner.response.rect;
+
+					loupe_tile_rect = Some(tile_rect);
+
+					let inner_stroke_color = match theme {
+						HudTheme::Dark => Color32::from_rgba_unmultiplied(0, 0, 0, 44),
+						HudTheme::Light => Color32::from_rgba_unmultiplied(255, 255, 255, 140),
+					};
+					let inner_stroke = Stroke::new(1.0, inner_stroke_color);
+					let inner_rect = tile_rect.shrink(1.0);
+
+					ui.painter().rect_stroke(
+						inner_rect,
+						CornerRadius::same(tile_radius.saturating_sub(1)),
+						inner_stroke,
+						StrokeKind::Inside,
+					);
+				});
+		});
+
+		(full_output, loupe_tile_rect)
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn update_hud_blur_uniform(
+		&mut self,
+		gpu: &GpuContext,
+		size: PhysicalSize<u32>,
+		pixels_per_point: f32,
+		theme: HudTheme,
+		hud_fog_amount: f32,
+		hud_milk_amount: f32,
+		hud_tint_hue: f32,
+		hud_blur_dither: bool,
+	) {
+		if self.hud_bg.is_none() {
+			return;
+		}
+
+		let Some(hud_pill) = self.hud_pill else {
+			return;
+		};
+		let surface_w = size.width as f32;
+		let surface_h = size.height as f32;
+
+		if surface_w <= 0.0 || surface_h <= 0.0 {
+			return;
+		}
+
+		let rect_min_px =
+			[hud_pill.rect.min.x * pixels_per_point, hud_pill.rect.min.y * pixels_per_point];
+		let rect_size_px =
+			[hud_pill.rect.width() * pixels_per_point, hud_pill.rect.height() * pixels_per_point];
+		let rect_min_size = [rect_min_px[0], rect_min_px[1], rect_size_px[0], rect_size_px[1]];
+		let max_lod = self.hud_bg.as_ref().map(|bg| bg.max_lod).unwrap_or(0.0);
+		let tint =
+			Self::tinted_hud_body_fill(theme, false, false, 1.0, hud_milk_amount, hud_tint_hue);
+		let tint_rgba = [
+			hud_helpers::srgb8_to_linear_f32(tint[0]),
+			hud_helpers::srgb8_to_linear_f32(tint[1]),
+			hud_helpers::srgb8_to_linear_f32(tint[2]),
+			hud_helpers::hud_blur_tint_alpha(theme),
+		];
+		let effects =
+			HudBlurUniformRaw::effects(hud_fog_amount, hud_milk_amount, max_lod, hud_blur_dither);
+		let u = HudBlurUniformRaw {
+			rect_min_size,
+			radius_blur_soft: [
+				hud_pill.radius_points * pixels_per_point,
+				(0.9 + (hud_fog_amount.clamp(0.0, 1.0) * 3.2)) * pixels_per_point,
+				1.0 * pixels_per_point,
+				0.0,
+			],
+			surface_size_px: [surface_w, surface_h, 0.0, 0.0],
+			tint_rgba,
+			effects,
+		};
+
+		gpu.queue.write_buffer(&self.hud_blur_uniform, 0, u.as_bytes());
+	}
+
+	fn sync_hud_bg(
+		&mut self,
+		gpu: &GpuContext,
+		state: &OverlayState,
+		monitor: MonitorRect,
+	) -> Result<()> {
+		let (target_generation, target_image) = match state.mode {
+			OverlayMode::Live if state.live_bg_monitor == Some(monitor) => {
+				(state.live_bg_generation, state.live_bg_image.as_ref())
+			},
+			OverlayMode::Frozen if state.monitor == Some(monitor) => {
+				(state.frozen_generation, state.frozen_image.as_ref())
+			},
+			OverlayMode::Live => {
+				self.hud_bg = None;
+				self.hud_bg_generation = state.live_bg_generation;
+
+				return Ok(());
+			},
+			OverlayMode::Frozen => {
+				self.hud_bg = None;
+				self.hud_bg_generation = state.frozen_generation;
+
+				return Ok(());
+			},
+		};
+
+		if self.hud_bg.is_some() && self.hud_bg_generation == target_generation {
+			if target_image.is_none() {
+				// Keep displaying the already-uploaded background even if image bytes moved.
+				return Ok(());
+			}
+
+			return Ok(());
+		}
+
+		let Some(image) = target_image else {
+			// Capture is in progress and no image is available yet.
+			self.hud_bg = None;
+			self.hud_bg_generation = target_generation;
+
+			return Ok(());
+		};
+
+		self.render_frozen_bg_to_texture(gpu, image, target_generation)
+	}
+
+	fn render_frozen_bg_to_texture(
+		&mut self,
+		gpu: &GpuContext,
+		image: &RgbaImage,
+		target_generation: u64,
+	) -> Result<()> {
+		let max_side = gpu.device.limits().max_texture_dimension_2d;
+
+		if image_helpers::exceeds_gpu_texture_side(image, max_side) {
+			tracing::warn!(
+				op = "overlay.frozen_preview_downscaled",
+				width = image.width(),
+				height = image.height(),
+				max_side,
+				"Frozen capture exceeds the GPU texture limit; the preview is downscaled."
+			);
+		}
+
+		let upload_image = image_helpers::downscale_for_gpu_upload(image, max_side);
+		let (width, height) = upload_image.dimensions();
+		let mip_level_count = Self::mip_level_count(width, height).min(10);
+
+		debug_assert!(width <= max_side && height <= max_side);
+
+		let texture = gpu.device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("rsnap-frozen-bg texture"),
+			size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+			mip_level_count,
+			sample_count: 1,
+			dimension: TextureDimension::D2,
+			format: wgpu::TextureFormat::Rgba8UnormSrgb,
+			usage: TextureUsages::TEXTURE_BINDING
+				| TextureUsages::COPY_DST
+				| TextureUsages::RENDER_ATTACHMENT,
+			view_formats: &[],
+		});
+		let upload_bytes = upload_image.as_raw();
+		let bytes_per_pixel = 4_usize;
+		let unpadded_bytes_per_row = (width as usize) * bytes_per_pixel;
+		let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+		let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+		let rgba_padded;
+		let rgba_bytes: &[u8] = if padded_bytes_per_row == unpadded_bytes_per_row {
+			upload_bytes
+		} else {
+			let src = upload_bytes;
+
+			rgba_padded = image_helpers::pad_rows(
+				src,
+				unpadded_bytes_per_row,
+				padded_bytes_per_row,
+				height as usize,
+			);
+
+			&rgba_padded
+		};
+
+		gpu.queue.write_texture(
+			wgpu::TexelCopyTextureInfo {
+				texture: &texture,
+				mip_level: 0,
+				origin: Origin3d::ZERO,
+				aspect: TextureAspect::All,
+			},
+			rgba_bytes,
+			wgpu::TexelCopyBufferLayout {
+				offset: 0,
+				bytes_per_row: Some(padded_bytes_per_row as u32),
+				rows_per_image: Some(height),
+			},
+			wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+		);
+		self.generate_mipmaps(gpu, &texture, mip_level_count);
+
+		let view = texture.create_view(&TextureViewDescriptor::default());
+		let hud_blur_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("rsnap-hud-blur bind group"),
+			layout: &self.hud_blur_bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: BindingResource::TextureView(&view) },
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: BindingResource::Sampler(&self.bg_sampler),
+				},
+				wgpu::BindGroupEntry {
+					binding: 2,
+					resource: self.hud_blur_uniform.as_entire_binding(),
+				},
+			],
+		});
+		let mipgen_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("rsnap-mipgen fullscreen bind group"),
+			layout: &self.mipgen_bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: BindingResource::TextureView(&view) },
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: BindingResource::Sampler(&self.bg_sampler),
+				},
+			],
+		});
+		let max_lod = (mip_level_count.saturating_sub(1)) as f32;
+
+		self.hud_bg = Some(HudBg {
+			_texture: texture,
+			_view: view,
+			hud_blur_bind_group,
+			mipgen_bind_group,
+			max_lod,
+		});
+		self.hud_bg_generation = target_generation;
+
+		Ok(())
+	}
+}
+
+struct HudBg {
+	_texture: Texture,
+	_view: TextureView,
+	hud_blur_bind_group: BindGroup,
+	mipgen_bind_group: BindGroup,
+	max_lod: f32,
+}
 
 #[derive(Clone, Copy, Debug)]
 struct HudPillGeometry {
@@ -10060,493 +13824,2514 @@ struct HudPillGeometry {
 	radius_points: f32,
 }
 
-#[repr(C)]
-#[derive(Clone, Copy, Debug)]
-struct HudBlurUniformRaw {
-	rect_min_size: [f32; 4],
-	radius_blur_soft: [f32; 4],
-	surface_size_px: [f32; 4],
-	tint_rgba: [f32; 4],
-	effects: [f32; 4],
-}
-impl HudBlurUniformRaw {
-	fn as_bytes(&self) -> &[u8] {
-		unsafe { slice::from_raw_parts(ptr::from_ref(self).cast::<u8>(), mem::size_of::<Self>()) }
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct HudBlurUniformRaw {
+	rect_min_size: [f32; 4],
+	radius_blur_soft: [f32; 4],
+	surface_size_px: [f32; 4],
+	tint_rgba: [f32; 4],
+	effects: [f32; 4],
+}
+impl HudBlurUniformRaw {
+	/// Packs `blur_amount, tint_amount, max_lod, dither` as `hud_blur.wgsl` reads them.
+	fn effects(
+		hud_fog_amount: f32,
+		hud_milk_amount: f32,
+		max_lod: f32,
+		hud_blur_dither: bool,
+	) -> [f32; 4] {
+		[
+			hud_fog_amount.clamp(0.0, 1.0),
+			hud_milk_amount.clamp(0.0, 1.0),
+			max_lod,
+			if hud_blur_dither { 1.0 } else { 0.0 },
+		]
+	}
+
+	fn as_bytes(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(ptr::from_ref(self).cast::<u8>(), mem::size_of::<Self>()) }
+	}
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct FrozenDimUniformRaw {
+	selection_min_size: [f32; 4],
+	surface_vignette_noise: [f32; 4],
+	dim: [f32; 4],
+}
+impl FrozenDimUniformRaw {
+	/// Returns `None` when no effect is enabled, so the pass can be skipped.
+	fn new(
+		selection: RectPoints,
+		pixels_per_point: f32,
+		size: PhysicalSize<u32>,
+		opacity: f32,
+		vignette: f32,
+		noise: f32,
+	) -> Option<Self> {
+		let opacity = opacity.clamp(0.0, 1.0);
+		let vignette = vignette.clamp(0.0, 1.0);
+		let noise = noise.clamp(0.0, 1.0);
+
+		if opacity <= 0.0 && vignette <= 0.0 && noise <= 0.0 {
+			return None;
+		}
+
+		Some(Self {
+			selection_min_size: [
+				selection.x as f32 * pixels_per_point,
+				selection.y as f32 * pixels_per_point,
+				selection.width as f32 * pixels_per_point,
+				selection.height as f32 * pixels_per_point,
+			],
+			surface_vignette_noise: [size.width as f32, size.height as f32, vignette, noise],
+			dim: [opacity, 0.0, 0.0, 0.0],
+		})
+	}
+
+	fn as_bytes(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(ptr::from_ref(self).cast::<u8>(), mem::size_of::<Self>()) }
+	}
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ColorVisionUniformRaw {
+	rows: [[f32; 4]; 3],
+}
+impl ColorVisionUniformRaw {
+	fn new(matrix: [[f32; 3]; 3]) -> Self {
+		Self { rows: matrix.map(|[r, g, b]| [r, g, b, 0.0]) }
+	}
+
+	fn as_bytes(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(ptr::from_ref(self).cast::<u8>(), mem::size_of::<Self>()) }
+	}
+}
+
+/// Returns the built-in selection border color at `progress` along the border, wrapping at 1.0.
+pub fn selection_flow_palette_rgb(progress: f32) -> [u8; 3] {
+	let palette = SELECTION_FLOW_PALETTE;
+	let band_position = progress.rem_euclid(1.0) * palette.len() as f32;
+	let band = band_position.floor() as usize % palette.len();
+	let local = band_position - band as f32;
+	let (r0, g0, b0) = palette[band];
+	let (r1, g1, b1) = palette[(band + 1) % palette.len()];
+	let blend = |a: u8, b: u8| -> u8 {
+		(a as f32 + (b as f32 - a as f32) * local).clamp(0.0, 255.0).round() as u8
+	};
+
+	[blend(r0, r1), blend(g0, g1), blend(b0, b1)]
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct MacOSCGPoint {
+	x: f64,
+	y: f64,
+}
+
+#[cfg(target_os = "macos")]
+fn macos_is_option_key_down() -> bool {
+	let flags = unsafe { CGEventSourceFlagsState(macos_hid_event_source_state_id()) };
+
+	flags & KCG_EVENT_FLAGS_MASK_ALTERNATE != 0
+}
+
+#[cfg(target_os = "macos")]
+fn macos_hid_event_source_state_id() -> u32 {
+	KCG_EVENT_SOURCE_STATE_HID_SYSTEM_STATE
+}
+
+fn global_to_local(cursor: GlobalPoint, monitor: MonitorRect) -> Option<Pos2> {
+	let (x, y) = monitor.local_u32(cursor)?;
+
+	Some(Pos2::new(x as f32, y as f32))
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+	fn CGEventGetLocation(event: CGEventRef) -> MacOSCGPoint;
+	fn CGEventCreate(source: *const c_void) -> CGEventRef;
+	fn CGEventSourceCreate(source_state_id: u32) -> CFTypeRef;
+	fn CGEventCreateScrollWheelEvent2(
+		source: *const c_void,
+		units: u32,
+		wheel_count: u32,
+		wheel1: i32,
+		wheel2: i32,
+		wheel3: i32,
+	) -> CGEventRef;
+	fn CGEventPost(tap_location: u32, event: CGEventRef);
+	fn CGEventSetLocation(event: CGEventRef, location: MacOSCGPoint);
+	fn CGEventSourceFlagsState(source_state_id: u32) -> u64;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+	fn CFRelease(obj: CFTypeRef);
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn macos_mouse_location() -> Option<GlobalPoint> {
+	let event = unsafe { CGEventCreate(ptr::null()) };
+
+	if event.is_null() {
+		return None;
+	}
+
+	let point = unsafe { CGEventGetLocation(event) };
+
+	unsafe { CFRelease(event) };
+
+	Some(GlobalPoint::new(point.x as i32, point.y as i32))
+}
+
+#[cfg(target_os = "macos")]
+fn macos_activate_app() {
+	unsafe {
+		let app: *mut Object = objc::msg_send![objc::class!(NSApplication), sharedApplication];
+
+		if app.is_null() {
+			return;
+		}
+
+		let _: () = objc::msg_send![app, activateIgnoringOtherApps: YES];
+	}
+}
+
+#[cfg(target_os = "macos")]
+fn macos_make_window_key(window: &winit::window::Window) {
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+		return;
+	};
+	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+
+	unsafe {
+		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+
+		if ns_window.is_null() {
+			return;
+		}
+
+		let nil: *mut Object = ptr::null_mut();
+		let _: () = objc::msg_send![ns_window, makeKeyAndOrderFront: nil];
+	}
+
+	window.focus_window();
+}
+
+#[cfg(target_os = "macos")]
+fn macos_post_scroll_wheel_event(
+	delta: MacOSScrollWheelEvent,
+	target_point: GlobalPoint,
+) -> Result<()> {
+	let units = delta.units;
+	let wheel1 = delta.posted_y;
+	let wheel2 = delta.posted_x;
+
+	if wheel1 == 0 && wheel2 == 0 {
+		return Ok(());
+	}
+
+	let source = unsafe { CGEventSourceCreate(macos_hid_event_source_state_id()) };
+
+	if source.is_null() {
+		return Err(eyre::eyre!("failed to create macOS scroll wheel event source"));
+	}
+
+	let wheel_count = if wheel2 != 0 { 2 } else { 1 };
+	let event =
+		unsafe { CGEventCreateScrollWheelEvent2(source, units, wheel_count, wheel1, wheel2, 0) };
+
+	if event.is_null() {
+		unsafe {
+			CFRelease(source);
+		}
+
+		return Err(eyre::eyre!("failed to create macOS scroll wheel event"));
+	}
+
+	unsafe {
+		CGEventSetLocation(
+			event,
+			MacOSCGPoint { x: f64::from(target_point.x), y: f64::from(target_point.y) },
+		);
+		CGEventPost(KCG_HID_EVENT_TAP, event);
+		CFRelease(event);
+		CFRelease(source);
+	}
+
+	Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn macos_configure_overlay_window_mouse_moved_events(window: &winit::window::Window) {
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+		return;
+	};
+	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+
+	unsafe {
+		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+
+		if ns_window.is_null() {
+			return;
+		}
+
+		let _: () = objc::msg_send![ns_window, setOpaque: false];
+		let _: () = objc::msg_send![ns_window, setHasShadow: false];
+		let clear: *mut Object = objc::msg_send![objc::class!(NSColor), clearColor];
+		let _: () = objc::msg_send![ns_window, setBackgroundColor: clear];
+		let _: () = objc::msg_send![ns_window, setLevel: MACOS_OVERLAY_WINDOW_LEVEL];
+		let _: () = objc::msg_send![ns_window, setAcceptsMouseMovedEvents: YES];
+	}
+}
+
+#[cfg(target_os = "macos")]
+fn macos_configure_hud_window(
+	window: &winit::window::Window,
+	blur_enabled: bool,
+	blur_amount: f32,
+	corner_radius_points: Option<f64>,
+) {
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+		return;
+	};
+	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+
+	unsafe {
+		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+
+		if ns_window.is_null() {
+			return;
+		}
+
+		// winit exposes blur as a boolean. We also set an explicit radius so we can drive it from
+		// settings (this uses the same private CGS API that winit uses internally).
+		{
+			#[link(name = "CoreGraphics", kind = "framework")]
+			unsafe extern "C" {
+				fn CGSMainConnectionID() -> *mut c_void;
+
+				fn CGSSetWindowBackgroundBlurRadius(
+					connection_id: *mut c_void,
+					window_id: isize,
+					radius: i64,
+				) -> i32;
+			}
+
+			let amount = blur_amount.clamp(0.0, 1.0);
+			let radius = if blur_enabled {
+				// Map the slider linearly (0..=1) to the native blur radius.
+				// Keep the upper bound conservative; CGS blur radius gets strong quickly.
+				let max_radius = 12.0;
+
+				(amount * max_radius).round().clamp(0.0, 200.0) as i64
+			} else {
+				0
+			};
+			let window_number: isize = objc::msg_send![ns_window, windowNumber];
+			let _ = CGSSetWindowBackgroundBlurRadius(CGSMainConnectionID(), window_number, radius);
+		}
+
+		let _: () = objc::msg_send![ns_window, setOpaque: false];
+		let _: () = objc::msg_send![ns_window, setHasShadow: false];
+		let _: () = objc::msg_send![ns_window, setAcceptsMouseMovedEvents: YES];
+		let _: () = objc::msg_send![ns_window, setLevel: MACOS_HUD_WINDOW_LEVEL];
+		let clear: *mut Object = objc::msg_send![objc::class!(NSColor), clearColor];
+		let _: () = objc::msg_send![ns_window, setBackgroundColor: clear];
+		let content_view: *mut Object = objc::msg_send![ns_window, contentView];
+
+		if content_view.is_null() {
+			return;
+		}
+
+		let _: () = objc::msg_send![content_view, setWantsLayer: YES];
+		let layer: *mut Object = objc::msg_send![content_view, layer];
+
+		if layer.is_null() {
+			return;
+		}
+
+		// Round the window itself so native blur doesn't show a rectangular boundary.
+		let scale = window.scale_factor().max(1.0);
+		let size = window.inner_size();
+		let height_points = (size.height as f64) / scale;
+		let radius = corner_radius_points.unwrap_or(height_points * 0.5);
+		let _: () = objc::msg_send![layer, setCornerRadius: radius];
+		let _: () = objc::msg_send![layer, setMasksToBounds: YES];
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+	#[cfg(target_os = "macos")]
+	use std::sync::Arc;
+	use std::sync::mpsc;
+	use std::time::Duration;
+	use std::time::Instant;
+
+	use color_eyre::eyre;
+	use image::{Rgba, RgbaImage};
+	#[cfg(target_os = "macos")]
+	use winit::dpi::PhysicalPosition;
+	use winit::event::{ElementState, MouseScrollDelta};
+	use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+
+	use crate::annotation::AnnotationLayer;
+	use crate::backend::ScriptedCaptureBackend;
+	use crate::callout::Callout;
+	use crate::color_vision::ColorVisionFilter;
+	use crate::latency::LatencyStage;
+	#[cfg(target_os = "macos")]
+	use crate::live_frame_stream_macos::MacLiveFrameStream;
+	use crate::overlay::headless::HeadlessOverlay;
+	use crate::overlay::{
+		CaptureMode, CompositionGuides, DEFAULT_KEYPAD_QUICK_ACTIONS, DoneAction, DragSpring,
+		EXPORT_PREVIEW_SLOT_WIDTH_POINTS, EdgeDeadZones, EntryAnimation, ExportFormat,
+		ExportScaleMode, ExportScaling, FROZEN_TOOLBAR_ITEM_SPACING_POINTS, FrozenCaptureSource,
+		FrozenDimUniformRaw, FrozenToolbarState, FrozenToolbarTool, GPU_INIT_TIMEOUT, GpuContext,
+		GpuPowerPreference, HudAnchor, HudBlurUniformRaw, HudTheme, HudUnit, KeypadQuickAction,
+		LOUPE_ZOOM_DEFAULT_INDEX, OutputNaming, OverlayConfig, OverlayControl, OverlayExit,
+		OverlaySession, PendingGpuContext, PerfHudCounters, PhysicalSize, Pos2, Rect,
+		ScrollCaptureFrameSource, ShaderSources, TOOLBAR_CAPTURE_GAP_PX, TOOLBAR_SCREEN_MARGIN_PX,
+		ThemeMode, ToolbarPlacement, Vec2, WindowRenderer, alt_text, composition_guides_runtime,
+		display_picker_runtime, hud_helpers, image_helpers, open_with, output,
+		output_filename_preview, selection_snap_runtime, sidecar,
+	};
+	#[cfg(target_os = "macos")]
+	use crate::overlay::{
+		HUD_PILL_CORNER_RADIUS_POINTS, HudPillGeometry, InflightScrollCaptureObservation,
+		KCG_SCROLL_EVENT_UNIT_PIXEL, LiveSampleApplyResult, LiveStreamStaleGrace,
+		MacOSScrollPixelResidual, SCROLL_CAPTURE_INPUT_FRESHNESS,
+		SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES, SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE,
+	};
+	use crate::progress::{OverlayProgress, ProgressStage};
+	use crate::project;
+	use crate::scroll_capture::{ScrollDirection, ScrollObserveOutcome, ScrollSession};
+	use crate::stamp::StampKind;
+	#[cfg(target_os = "macos")]
+	use crate::state::LiveCursorSample;
+	use crate::state::{
+		CursorImage, FrozenView, GlobalPoint, LoupeSample, MonitorRect, MonitorRectPoints,
+		OverlayMode, RectPoints, ReferenceSample, Rgb, WindowLabel, WindowRect,
+	};
+
+	fn make_scroll_capture_test_image(width: u32, rows: &[[u8; 4]]) -> image::RgbaImage {
+		let mut image = image::RgbaImage::new(width, rows.len() as u32);
+
+		for (y, row) in rows.iter().enumerate() {
+			for x in 0..width {
+				image.put_pixel(x, y as u32, Rgba(*row));
+			}
+		}
+
+		image
+	}
+
+	fn make_scroll_capture_window(
+		document: &[[u8; 4]],
+		width: u32,
+		start_row: usize,
+		window_rows: usize,
+	) -> image::RgbaImage {
+		make_scroll_capture_test_image(width, &document[start_row..start_row + window_rows])
+	}
+
+	fn set_scroll_capture_input(session: &mut OverlaySession, direction: ScrollDirection) {
+		session.scroll_capture.input_direction = Some(direction);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
+	}
+
+	fn observe_scroll_capture_frame(
+		session: &mut OverlaySession,
+		frame: image::RgbaImage,
+	) -> Option<ScrollObserveOutcome> {
+		session.observe_scroll_capture_frame(frame).transpose().unwrap()
+	}
+
+	fn scroll_capture_export_height(session: &OverlaySession) -> u32 {
+		session.scroll_capture.session.as_ref().unwrap().export_image().height()
+	}
+
+	#[test]
+	fn scroll_capture_reports_stitching_progress_per_merged_frame() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+		];
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+
+		for start_row in 1..=2 {
+			set_scroll_capture_input(&mut session, ScrollDirection::Down);
+			session.handle_scroll_capture_frame(
+				make_scroll_capture_window(&document, 3, start_row, 5),
+				ScrollCaptureFrameSource::Worker { request_id: start_row as u64 },
+				false,
+				Instant::now(),
+			);
+		}
+
+		assert_eq!(
+			session.take_progress(),
+			vec![OverlayProgress::stitched(1), OverlayProgress::stitched(2)]
+		);
+	}
+
+	#[test]
+	fn frozen_toolbar_default_position_fits_below_capture_rect() {
+		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+		let capture_rect = Rect::from_min_size(Pos2::new(50.0, 100.0), Vec2::new(300.0, 200.0));
+		let toolbar_size = Vec2::new(460.0, 54.0);
+		let pos = WindowRenderer::frozen_toolbar_default_pos(
+			monitor,
+			capture_rect,
+			toolbar_size,
+			ToolbarPlacement::Bottom,
+		);
+		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
+			TOOLBAR_SCREEN_MARGIN_PX,
+			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
+				.max(TOOLBAR_SCREEN_MARGIN_PX),
+		);
+
+		assert!((pos.x - expected_x).abs() < f32::EPSILON);
+		assert_eq!(pos.y, capture_rect.max.y + TOOLBAR_CAPTURE_GAP_PX);
+	}
+
+	#[test]
+	fn frozen_toolbar_default_position_falls_inside_when_no_space_below_capture_rect() {
+		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 600.0));
+		let toolbar_size = Vec2::new(460.0, 54.0);
+		let capture_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 560.0));
+		let pos = WindowRenderer::frozen_toolbar_default_pos(
+			monitor,
+			capture_rect,
+			toolbar_size,
+			ToolbarPlacement::Bottom,
+		);
+		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
+			TOOLBAR_SCREEN_MARGIN_PX,
+			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
+				.max(TOOLBAR_SCREEN_MARGIN_PX),
+		);
+		let expected_y = capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y;
+
+		assert_eq!(pos.x, expected_x);
+		assert_eq!(pos.y, capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y);
+		assert_eq!(pos.y, expected_y);
+	}
+
+	#[test]
+	fn frozen_toolbar_top_default_position_fits_above_capture_rect() {
+		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+		let capture_rect = Rect::from_min_size(Pos2::new(50.0, 180.0), Vec2::new(300.0, 200.0));
+		let toolbar_size = Vec2::new(460.0, 54.0);
+		let pos = WindowRenderer::frozen_toolbar_default_pos(
+			monitor,
+			capture_rect,
+			toolbar_size,
+			ToolbarPlacement::Top,
+		);
+		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
+			TOOLBAR_SCREEN_MARGIN_PX,
+			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
+				.max(TOOLBAR_SCREEN_MARGIN_PX),
+		);
+
+		assert_eq!(pos.x, expected_x);
+		assert_eq!(pos.y, capture_rect.min.y - TOOLBAR_CAPTURE_GAP_PX - toolbar_size.y);
+	}
+
+	#[test]
+	fn frozen_toolbar_top_default_position_falls_inside_when_no_space_above_capture_rect() {
+		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 600.0));
+		let capture_rect = Rect::from_min_size(Pos2::new(0.0, 20.0), Vec2::new(500.0, 400.0));
+		let toolbar_size = Vec2::new(460.0, 54.0);
+		let pos = WindowRenderer::frozen_toolbar_default_pos(
+			monitor,
+			capture_rect,
+			toolbar_size,
+			ToolbarPlacement::Top,
+		);
+		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
+			TOOLBAR_SCREEN_MARGIN_PX,
+			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
+				.max(TOOLBAR_SCREEN_MARGIN_PX),
+		);
+
+		assert_eq!(pos.x, expected_x);
+		assert_eq!(pos.y, capture_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX);
+	}
+
+	#[test]
+	fn scroll_toolbar_compacts_to_export_buttons() {
+		let frozen_toolbar_size =
+			WindowRenderer::frozen_toolbar_size(&FrozenToolbarState::default());
+		let scroll_toolbar_size = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState {
+			scroll_capture_active: true,
+			..FrozenToolbarState::default()
+		});
+
+		assert!(scroll_toolbar_size.x < frozen_toolbar_size.x);
+		assert_eq!(scroll_toolbar_size.y, frozen_toolbar_size.y);
+	}
+
+	#[test]
+	fn export_preview_slot_reserves_toolbar_width_outside_scroll_mode() {
+		let plain = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState::default());
+		let with_preview = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState {
+			export_preview_enabled: true,
+			..FrozenToolbarState::default()
+		});
+		let scroll_plain = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState {
+			scroll_capture_active: true,
+			..FrozenToolbarState::default()
+		});
+		let scroll_with_preview = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState {
+			export_preview_enabled: true,
+			scroll_capture_active: true,
+			..FrozenToolbarState::default()
+		});
+
+		assert_eq!(
+			with_preview.x - plain.x,
+			EXPORT_PREVIEW_SLOT_WIDTH_POINTS + FROZEN_TOOLBAR_ITEM_SPACING_POINTS
+		);
+		assert_eq!(scroll_with_preview, scroll_plain);
+	}
+
+	#[test]
+	fn edge_dead_zones_cover_only_enabled_edges() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(100, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let zones = EdgeDeadZones { size_px: 6, bottom: true, left: true, ..Default::default() };
+
+		assert!(zones.contains(monitor, GlobalPoint::new(100, 400)));
+		assert!(zones.contains(monitor, GlobalPoint::new(500, 794)));
+		assert!(!zones.contains(monitor, GlobalPoint::new(500, 793)));
+		assert!(!zones.contains(monitor, GlobalPoint::new(500, 0)));
+		assert!(!zones.contains(monitor, GlobalPoint::new(1_099, 400)));
+		assert!(!zones.contains(monitor, GlobalPoint::new(50, 400)));
+		assert!(!EdgeDeadZones::default().contains(monitor, GlobalPoint::new(100, 0)));
+	}
+
+	#[test]
+	fn export_scaling_only_ever_shrinks() {
+		let logical = ExportScaling { mode: ExportScaleMode::LogicalPoints, max_side_px: 0 };
+		let capped = ExportScaling { mode: ExportScaleMode::MaxSide, max_side_px: 1_000 };
+
+		assert_eq!(ExportScaling::default().target_size((3_000, 2_000), 2.0), None);
+		assert_eq!(logical.target_size((3_001, 2_000), 2.0), Some((1_501, 1_000)));
+		assert_eq!(logical.target_size((3_000, 2_000), 1.0), None);
+		assert_eq!(capped.target_size((3_000, 1_500), 2.0), Some((1_000, 500)));
+		assert_eq!(capped.target_size((800, 1_200), 1.0), Some((667, 1_000)));
+		assert_eq!(capped.target_size((800, 600), 1.0), None);
+		assert_eq!(capped.target_size((5_000, 1), 1.0), Some((1_000, 1)));
+	}
+
+	#[test]
+	fn scroll_preview_prefers_right_side_when_space_exists() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_400,
+			height: 900,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
+
+		session.state.frozen_capture_rect = Some(RectPoints::new(120, 160, 400, 320));
+
+		let preview = session.scroll_preview_local_rect(monitor);
+
+		assert_eq!(preview.min.y, 160.0);
+		assert_eq!(preview.height(), 320.0);
+		assert!(preview.min.x >= 120.0 + 400.0);
+	}
+
+	#[test]
+	fn scroll_preview_falls_back_to_left_when_right_side_is_tight() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 900,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
+
+		session.state.frozen_capture_rect = Some(RectPoints::new(760, 180, 200, 260));
+
+		let preview = session.scroll_preview_local_rect(monitor);
+
+		assert_eq!(preview.min.y, 180.0);
+		assert_eq!(preview.height(), 260.0);
+		assert!(preview.max.x <= 760.0);
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	#[test]
+	fn scroll_capture_is_unavailable_on_non_macos_even_with_drag_selection() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
+
+		session.state.mode = OverlayMode::Frozen;
+		session.state.monitor = Some(monitor);
+		session.state.frozen_capture_rect = Some(RectPoints::new(100, 120, 200, 240));
+		session.frozen_capture_source = FrozenCaptureSource::DragRegion;
+
+		assert!(!session.scroll_capture_is_available());
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn reset_for_start_preserves_external_scroll_input_drain_reader() {
+		let mut session = OverlaySession::default();
+
+		session.set_external_scroll_input_drain_reader(Arc::new(|_, _| {
+			vec![(1, Instant::now(), 10.0, 20.0, -4.0, true, false)]
+		}));
+		session.reset_for_start();
+
+		assert!(session.scroll_capture.external_scroll_input_drain_reader.is_some());
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn apply_live_cursor_sample_updates_rgb_and_loupe_state() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let cursor = GlobalPoint::new(120, 180);
+		let patch = image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]));
+		let mut session = OverlaySession::new();
+
+		session.cursor_monitor = Some(monitor);
+		session.state.cursor = Some(cursor);
+		session.state.alt_held = true;
+
+		assert!(
+			session
+				.apply_live_cursor_sample_detail(
+					monitor,
+					cursor,
+					LiveCursorSample {
+						rgb: Some(Rgb::new(10, 20, 30)),
+						patch: Some(patch.clone()),
+					},
+				)
+				.any_changed()
+		);
+		assert_eq!(session.state.rgb, Some(Rgb::new(10, 20, 30)));
+		assert_eq!(session.state.loupe.as_ref().map(|loupe| loupe.center), Some(cursor));
+		assert_eq!(
+			session.state.loupe.as_ref().map(|loupe| loupe.patch.dimensions()),
+			Some(patch.dimensions())
+		);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn apply_live_cursor_sample_detail_keeps_overlay_redraw_narrow_for_rgb_and_loupe_updates() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let cursor = GlobalPoint::new(120, 180);
+		let patch = image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]));
+		let mut session = OverlaySession::new();
+
+		session.cursor_monitor = Some(monitor);
+		session.state.cursor = Some(cursor);
+		session.state.alt_held = true;
+
+		let apply = session.apply_live_cursor_sample_detail(
+			monitor,
+			cursor,
+			LiveCursorSample { rgb: Some(Rgb::new(10, 20, 30)), patch: Some(patch) },
+		);
+
+		assert_eq!(
+			apply,
+			LiveSampleApplyResult {
+				overlay_changed: false,
+				hud_changed: true,
+				loupe_changed: true,
+			}
+		);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn live_sample_request_redraw_intent_only_redraws_immediate_hover_changes() {
+		let session = OverlaySession::new();
+
+		assert_eq!(
+			session.live_sample_request_redraw_intent(false, true, true),
+			LiveSampleApplyResult::default()
+		);
+		assert_eq!(
+			session.live_sample_request_redraw_intent(true, true, true),
+			LiveSampleApplyResult {
+				overlay_changed: true,
+				hud_changed: true,
+				loupe_changed: false,
+			}
+		);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn live_loupe_renders_in_hud_window_only_for_live_alt() {
+		let mut session = OverlaySession::new();
+
+		session.state.mode = OverlayMode::Frozen;
+
+		assert!(!session.live_loupe_uses_hud_window());
+		assert!(!session.live_loupe_renders_in_hud_window());
+
+		session.state.mode = OverlayMode::Live;
+
+		assert!(session.live_loupe_uses_hud_window());
+		assert!(!session.live_loupe_renders_in_hud_window());
+
+		session.state.alt_held = true;
+
+		assert!(session.live_loupe_renders_in_hud_window());
+
+		session.state.mode = OverlayMode::Frozen;
+
+		assert!(!session.live_loupe_uses_hud_window());
+		assert!(!session.live_loupe_renders_in_hud_window());
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn hud_window_content_rect_includes_live_loupe_tile_bounds() {
+		let hud_pill = HudPillGeometry {
+			rect: Rect::from_min_max(Pos2::new(14.0, 14.0), Pos2::new(200.0, 58.0)),
+			radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
+		};
+		let loupe_tile = Rect::from_min_max(Pos2::new(14.0, 68.0), Pos2::new(246.0, 300.0));
+		let live_rect = OverlaySession::hud_window_content_rect(
+			OverlayMode::Live,
+			true,
+			hud_pill,
+			Some(loupe_tile),
+		);
+
+		assert_eq!(live_rect.min, Pos2::new(14.0, 14.0));
+		assert_eq!(live_rect.max, Pos2::new(246.0, 300.0));
+
+		let frozen_rect = OverlaySession::hud_window_content_rect(
+			OverlayMode::Frozen,
+			true,
+			hud_pill,
+			Some(loupe_tile),
+		);
+
+		assert_eq!(frozen_rect, hud_pill.rect);
+	}
+
+	#[test]
+	fn live_overlay_selection_flow_repaint_active_requires_drag_rect() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
+
+		session.state.mode = OverlayMode::Live;
+		session.cursor_monitor = Some(monitor);
+		session.state.cursor = Some(GlobalPoint::new(120, 180));
+
+		assert!(!session.live_overlay_selection_flow_repaint_active());
+
+		session.state.hovered_window_rect = Some(MonitorRectPoints {
+			monitor_id: monitor.id,
+			rect: RectPoints::new(100, 120, 240, 320),
+		});
+
+		assert!(!session.live_overlay_selection_flow_repaint_active());
+
+		session.state.drag_rect = Some(MonitorRectPoints {
+			monitor_id: monitor.id,
+			rect: RectPoints::new(100, 120, 240, 320),
+		});
+
+		assert!(session.live_overlay_selection_flow_repaint_active());
+	}
+
+	#[test]
+	fn drag_across_mixed_dpi_monitors_stays_on_start_monitor() {
+		let standard = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_920,
+			height: 1_080,
+			scale_factor_x1000: 1_000,
+		};
+		let fractional = MonitorRect {
+			id: 2,
+			origin: GlobalPoint::new(1_920, 0),
+			width: 1_536,
+			height: 864,
+			scale_factor_x1000: 1_250,
+		};
+		let retina = MonitorRect {
+			id: 3,
+			origin: GlobalPoint::new(3_456, 0),
+			width: 1_512,
+			height: 982,
+			scale_factor_x1000: 2_000,
+		};
+		let start = GlobalPoint::new(2_020, 100);
+		let onto_retina =
+			OverlaySession::anchored_drag_rect(fractional, start, GlobalPoint::new(3_506, 500))
+				.expect("drag rect");
+
+		assert_eq!(onto_retina.monitor_id, fractional.id);
+		assert_eq!(onto_retina.rect, RectPoints::new(100, 100, 1_436, 400));
+		assert_eq!(
+			fractional.local_rect_to_pixels(onto_retina.rect),
+			RectPoints::new(125, 125, 1_795, 500)
+		);
+		assert!(!retina.contains(start));
+
+		let onto_standard =
+			OverlaySession::anchored_drag_rect(fractional, start, GlobalPoint::new(500, 50))
+				.expect("drag rect");
+
+		assert_eq!(onto_standard.monitor_id, fractional.id);
+		assert_eq!(onto_standard.rect, RectPoints::new(0, 50, 100, 50));
+		assert!(standard.contains(GlobalPoint::new(500, 50)));
+	}
+
+	#[test]
+	fn drag_rect_snaps_to_nearby_window_edges() {
+		let monitor = MonitorRect {
+			id: 2,
+			origin: GlobalPoint::new(1_920, 0),
+			width: 1_536,
+			height: 864,
+			scale_factor_x1000: 1_250,
+		};
+		let windows = [
+			WindowRect { window_id: Some(1), x: 2_020, y: 100, width: 400, height: 300 },
+			// Far below the selection, so its left edge at x = 104 must not pull the selection.
+			WindowRect { window_id: Some(2), x: 2_024, y: 700, width: 200, height: 100 },
+		];
+		let snap = |rect, threshold| {
+			selection_snap_runtime::snap_rect_to_windows(rect, monitor, &windows, threshold)
+		};
+
+		assert_eq!(
+			snap(RectPoints::new(95, 106, 410, 290), 8),
+			RectPoints::new(100, 100, 400, 300)
+		);
+		assert_eq!(snap(RectPoints::new(90, 106, 410, 290), 8), RectPoints::new(90, 100, 410, 300));
+		assert_eq!(snap(RectPoints::new(95, 106, 410, 290), 0), RectPoints::new(95, 106, 410, 290));
+		// Both vertical edges would land on the window's left edge; the drag is kept as is.
+		assert_eq!(snap(RectPoints::new(98, 150, 4, 50), 8), RectPoints::new(98, 150, 4, 50));
+	}
+
+	#[test]
+	fn live_overlay_redraw_needed_for_cursor_update_only_for_monitor_or_drag_changes() {
+		let monitor_a = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let monitor_b = MonitorRect {
+			id: 2,
+			origin: GlobalPoint::new(1_000, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let drag = Some(MonitorRectPoints {
+			monitor_id: monitor_a.id,
+			rect: RectPoints::new(100, 120, 240, 320),
+		});
+
+		assert!(!OverlaySession::live_overlay_redraw_needed_for_cursor_update(
+			Some(monitor_a),
+			monitor_a,
+			None,
+			None,
+		));
+		assert!(OverlaySession::live_overlay_redraw_needed_for_cursor_update(
+			Some(monitor_a),
+			monitor_a,
+			None,
+			drag,
+		));
+		assert!(OverlaySession::live_overlay_redraw_needed_for_cursor_update(
+			Some(monitor_a),
+			monitor_b,
+			None,
+			None,
+		));
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn apply_live_cursor_sample_clears_existing_loupe_when_alt_is_released() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let cursor = GlobalPoint::new(120, 180);
+		let mut session = OverlaySession::new();
+
+		session.cursor_monitor = Some(monitor);
+		session.state.cursor = Some(cursor);
+		session.state.alt_held = true;
+
+		let _ = session.apply_live_cursor_sample_detail(
+			monitor,
+			cursor,
+			LiveCursorSample {
+				rgb: Some(Rgb::new(10, 20, 30)),
+				patch: Some(image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]))),
+			},
+		);
+
+		session.state.alt_held = false;
+
+		assert!(
+			session
+				.apply_live_cursor_sample_detail(
+					monitor,
+					cursor,
+					LiveCursorSample { rgb: None, patch: None },
+				)
+				.any_changed()
+		);
+		assert!(session.state.loupe.is_none());
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn stabilized_live_hud_inner_size_keeps_live_width_from_shrinking() {
+		let mut session = OverlaySession::new();
+
+		session.state.mode = OverlayMode::Live;
+		session.hud_inner_size_points = Some((826, 44));
+
+		assert_eq!(
+			OverlaySession::stabilized_live_hud_inner_size(
+				OverlayMode::Live,
+				session.hud_inner_size_points,
+				(810, 44),
+			),
+			(826, 44)
+		);
+		assert_eq!(
+			OverlaySession::stabilized_live_hud_inner_size(
+				OverlayMode::Live,
+				session.hud_inner_size_points,
+				(780, 44),
+			),
+			(826, 44)
+		);
+
+		session.state.mode = OverlayMode::Frozen;
+
+		assert_eq!(
+			OverlaySession::stabilized_live_hud_inner_size(
+				OverlayMode::Frozen,
+				session.hud_inner_size_points,
+				(810, 44),
+			),
+			(810, 44)
+		);
+	}
+
+	#[test]
+	fn live_hud_position_text_uses_stable_monitor_width() {
+		let monitor = MonitorRect {
+			id: 5,
+			origin: GlobalPoint::new(0, 0),
+			width: 3_008,
+			height: 1_692,
+			scale_factor_x1000: 2_000,
+		};
+		let short = hud_helpers::format_live_hud_position_text(
+			monitor,
+			GlobalPoint::new(842, 846),
+			HudUnit::Points,
+			1.0,
+		);
+		let long = hud_helpers::format_live_hud_position_text(
+			monitor,
+			GlobalPoint::new(1_504, 1_320),
+			HudUnit::Points,
+			1.0,
+		);
+
+		assert_eq!(short.len(), long.len());
+		assert_eq!(short, "x= 842, y= 846");
+		assert_eq!(long, "x=1504, y=1320");
+	}
+
+	#[test]
+	fn live_hud_position_text_converts_to_pixels_and_millimeters() {
+		let monitor = MonitorRect {
+			id: 5,
+			origin: GlobalPoint::new(-1_000, 0),
+			width: 1_000,
+			height: 500,
+			scale_factor_x1000: 2_000,
+		};
+		let cursor = GlobalPoint::new(-990, 6);
+
+		assert_eq!(
+			hud_helpers::format_live_hud_position_text(monitor, cursor, HudUnit::Pixels, 0.25),
+			"x=  20, y= 12"
+		);
+		assert_eq!(
+			hud_helpers::format_live_hud_position_text(monitor, cursor, HudUnit::Millimeters, 0.25),
+			"x=  2.5, y=  1.5"
+		);
+	}
+
+	#[test]
+	fn hud_size_text_follows_the_unit_and_hides_unknown_millimeters() {
+		let monitor = MonitorRect {
+			id: 5,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 500,
+			scale_factor_x1000: 2_000,
+		};
+		let rect = RectPoints::new(10, 10, 120, 40);
+
+		assert_eq!(
+			hud_helpers::format_hud_size_text(monitor, rect, HudUnit::Points, 0.0),
+			"120×40 pt"
+		);
+		assert_eq!(
+			hud_helpers::format_hud_size_text(monitor, rect, HudUnit::Pixels, 0.0),
+			"240×80 px"
+		);
+		assert_eq!(
+			hud_helpers::format_hud_size_text(monitor, rect, HudUnit::Millimeters, 0.25),
+			"30.0×10.0 mm"
+		);
+		assert_eq!(
+			hud_helpers::displayable_hud_unit(HudUnit::Millimeters, None),
+			(HudUnit::Points, 0.0)
+		);
+		assert_eq!(
+			hud_helpers::displayable_hud_unit(HudUnit::Millimeters, Some(0.25)),
+			(HudUnit::Millimeters, 0.25)
+		);
+	}
+
+	#[test]
+	fn loupe_zoom_geometry_keeps_tile_size_stable() {
+		assert_eq!(OverlaySession::loupe_zoom_geometry(21, LOUPE_ZOOM_DEFAULT_INDEX), (21, 10.0));
+		assert_eq!(OverlaySession::loupe_zoom_geometry(21, 0), (53, 4.0));
+		assert_eq!(OverlaySession::loupe_zoom_geometry(21, 5), (11, 20.0));
+		assert_eq!(OverlaySession::loupe_zoom_geometry(21, 99), (11, 20.0));
+	}
+
+	#[test]
+	fn keyboard_window_cycle_skips_offscreen_windows_and_wraps() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let windows = [
+			WindowRect { window_id: Some(1), x: 100, y: 100, width: 200, height: 100 },
+			WindowRect { window_id: Some(2), x: 2_000, y: 0, width: 400, height: 400 },
+			WindowRect { window_id: Some(3), x: 900, y: 700, width: 400, height: 400 },
+		];
+
+		assert_eq!(
+			OverlaySession::next_keyboard_window_target(&windows, monitor, None),
+			Some((0, GlobalPoint::new(200, 150)))
+		);
+		assert_eq!(
+			OverlaySession::next_keyboard_window_target(&windows, monitor, Some(0)),
+			Some((2, GlobalPoint::new(950, 750)))
+		);
+		assert_eq!(
+			OverlaySession::next_keyboard_window_target(&windows, monitor, Some(2)),
+			Some((0, GlobalPoint::new(200, 150)))
+		);
+	}
+
+	#[test]
+	fn cursor_composite_scales_sprite_and_aligns_hotspot() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(100, 50),
+			width: 20,
+			height: 20,
+			scale_factor_x1000: 2_000,
+		};
+		let gray = Rgba([128, 128, 128, 255]);
+		let red = Rgba([255, 0, 0, 255]);
+		let mut image = RgbaImage::from_pixel(40, 40, gray);
+		let cursor_image = CursorImage {
+			image: RgbaImage::from_pixel(2, 2, red),
+			hotspot_x: 1.0,
+			hotspot_y: 1.0,
+			scale_factor: 1.0,
+		};
+
+		image_helpers::composite_cursor(
+			&mut image,
+			monitor,
+			GlobalPoint::new(105, 55),
+			&cursor_image,
+		);
+
+		assert_eq!(*image.get_pixel(8, 8), red);
+		assert_eq!(*image.get_pixel(11, 11), red);
+		assert_eq!(*image.get_pixel(7, 7), gray);
+		assert_eq!(*image.get_pixel(12, 12), gray);
+
+		let arrow = image_helpers::fallback_arrow_cursor();
+
+		assert_eq!(arrow.image.get_pixel(4, 16).0, [255, 255, 255, 255]);
+		assert_eq!(arrow.image.get_pixel(1, 16).0, [0, 0, 0, 255]);
+		assert_eq!(arrow.image.get_pixel(25, 0).0[3], 0);
+	}
+
+	#[test]
+	fn export_summary_reports_dimensions_and_rounded_size() {
+		let image = RgbaImage::from_pixel(4, 3, Rgba([1, 2, 3, 255]));
+		let png_bytes = crate::png::rgba_image_to_png_bytes(&image).expect("png");
+		let summary = output::export_summary(&png_bytes).expect("summary");
+
+		assert_eq!(summary, format!("4×3 PNG, {} B", png_bytes.len()));
+
+		let mut large = png_bytes.clone();
+
+		large.resize(421_888, 0);
+
+		assert_eq!(output::export_summary(&large).as_deref(), Some("4×3 PNG, 412 KB"));
+
+		large.resize(3 * 1_024 * 1_024 / 2, 0);
+
+		assert_eq!(output::export_summary(&large).as_deref(), Some("4×3 PNG, 1.5 MB"));
+		assert_eq!(output::export_summary(b"not a png"), None);
+	}
+
+	#[test]
+	fn saves_follow_the_export_format_and_filename_template() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-export-format-{}", std::process::id()));
+		let image = RgbaImage::from_pixel(4, 3, Rgba([0, 0, 0, 0]));
+		let png_bytes = crate::png::rgba_image_to_png_bytes(&image).expect("png");
+		let mut config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			output_filename_prefix: String::from("shot"),
+			output_filename_template: String::from("{prefix}_{seq}"),
+			export_format: ExportFormat::Jpeg,
+			..OverlayConfig::default()
+		};
+
+		let _ = std::fs::remove_dir_all(&output_dir);
+
+		let first = output::save_png_bytes_to_configured_dir(&png_bytes, &config).expect("jpeg");
+		let second = output::save_png_bytes_to_configured_dir(&png_bytes, &config).expect("jpeg");
+
+		config.export_format = ExportFormat::Webp;
+
+		let third = output::save_png_bytes_to_configured_dir(&png_bytes, &config).expect("webp");
+		let name =
+			|path: &std::path::Path| path.file_name().unwrap().to_string_lossy().into_owned();
+
+		assert_eq!(name(&first), "shot_0001.jpg");
+		assert_eq!(name(&second), "shot_0002.jpg");
+		assert_eq!(name(&third), "shot_0003.webp");
+
+		let jpeg = image::open(&first).expect("decode jpeg").to_rgb8();
+
+		assert!(jpeg.get_pixel(1, 1).0.iter().all(|&channel| channel > 240));
+		assert_eq!(image::ImageFormat::from_path(&third).ok(), Some(image::ImageFormat::WebP));
+		assert_eq!(image::open(&third).expect("decode webp").to_rgba8(), image);
+
+		let _ = std::fs::remove_dir_all(&output_dir);
+
+		assert_eq!(
+			output_filename_preview("", "shot", OutputNaming::Sequence, ExportFormat::Png),
+			"shot-0001.png"
+		);
+		assert_eq!(
+			output_filename_preview(
+				"{prefix} {seq}/x",
+				"a b",
+				OutputNaming::Timestamp,
+				ExportFormat::Webp
+			),
+			"a_b_0001_x.webp"
+		);
+	}
+
+	#[test]
+	fn export_annotations_list_each_layer_in_capture_pixels() {
+		let mut annotations = AnnotationLayer::default();
+
+		annotations.begin_highlight((4, 10), 20, [255, 230, 0]);
+		annotations.extend_highlight((60, 12));
+		annotations.end_highlight();
+		annotations.place_stamp(StampKind::ThumbsUp, (150, 40));
+		annotations.add_callout(Callout {
+			origin_px: (20, 30),
+			tail_px: (90, 80),
+			text: String::from("Fix this"),
+			text_size_px: 16,
+		});
+
+		let exported = sidecar::export_annotations((264, 164), 32, (200, 100), &annotations);
+		let json = serde_json::to_value(&exported).expect("annotations json");
+
+		assert_eq!(json["version"], 1);
+		assert_eq!(
+			json["capture"],
+			serde_json::json!({
+				"width": 200,
+				"height": 100,
+				"image_rect": { "x": 32, "y": 32, "width": 200, "height": 100 },
+			})
+		);
+		assert_eq!(
+			json["highlights"][0],
+			serde_json::json!({ "points": [[4, 10], [60, 12]], "width_px": 20, "color": "#FFE600" })
+		);
+		assert_eq!(
+			json["stamps"][0],
+			serde_json::json!({ "kind": "thumbs_up", "center": [150, 40], "size_px": 64 })
+		);
+		assert_eq!(
+			json["callouts"][0],
+			serde_json::json!({
+				"origin": [20, 30],
+				"tail": [90, 80],
+				"text": "Fix this",
+				"text_size_px": 16,
+			})
+		);
+	}
+
+	#[test]
+	fn export_geometry_maps_capture_to_image_and_screen_rects() {
+		let monitor = MonitorRect {
+			id: 7,
+			origin: GlobalPoint::new(-1_440, 0),
+			width: 1_440,
+			height: 900,
+			scale_factor_x1000: 2_000,
+		};
+		let geometry = sidecar::export_geometry(
+			(528, 328),
+			64,
+			Some(monitor),
+			Some(RectPoints::new(100, 50, 200, 100)),
+		);
+		let json = serde_json::to_value(&geometry).expect("geometry json");
+
+		assert_eq!(json["image"], serde_json::json!({ "width": 528, "height": 328 }));
+		assert_eq!(json["monitor_id"], 7);
+		assert_eq!(json["scale_factor"], 2.0);
+		assert_eq!(
+			json["regions"][0],
+			serde_json::json!({
+				"kind": "capture",
+				"image_rect": { "x": 64, "y": 64, "width": 400, "height": 200 },
+				"screen_rect_points": { "x": -1_340, "y": 50, "width": 200, "height": 100 },
+			})
+		);
+
+		let scroll = sidecar::export_geometry((10, 4_000), 0, Some(monitor), None);
+		let json = serde_json::to_value(&scroll).expect("geometry json");
+
+		assert_eq!(json["regions"][0]["screen_rect_points"], serde_json::Value::Null);
+	}
+
+	#[test]
+	fn alt_text_output_is_collapsed_and_bounded() {
+		assert_eq!(
+			alt_text::sanitize_alt_text("  A chart\n\n showing\tlatency.  \n").as_deref(),
+			Some("A chart showing latency.")
+		);
+		assert_eq!(alt_text::sanitize_alt_text(" \n\t "), None);
+
+		let long = "word ".repeat(500);
+		let sanitized = alt_text::sanitize_alt_text(&long).expect("alt text");
+
+		assert_eq!(sanitized.chars().count(), 1_000);
+	}
+
+	#[test]
+	fn window_trim_crops_transparent_margins_and_squares_corners() {
+		let clear = Rgba([0, 0, 0, 0]);
+		let red = Rgba([255, 0, 0, 255]);
+		let green = Rgba([0, 255, 0, 255]);
+		let mut image = RgbaImage::from_pixel(6, 5, clear);
+
+		for y in 1..5 {
+			for x in 0..5 {
+				image.put_pixel(x, y, red);
+			}
+		}
+
+		image.put_pixel(0, 1, clear);
+		image.put_pixel(4, 1, clear);
+		image.put_pixel(1, 1, green);
+
+		let trimmed = image_helpers::trim_window_image(&image);
+
+		assert_eq!(trimmed.dimensions(), (5, 4));
+		assert_eq!(*trimmed.get_pixel(0, 0), green);
+		assert_eq!(*trimmed.get_pixel(4, 0), red);
+		assert!(trimmed.pixels().all(|pixel| pixel[3] == 255));
+	}
+
+	#[test]
+	fn oversized_frozen_image_downscales_preview_but_samples_full_resolution() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 64,
+			height: 8,
+			scale_factor_x1000: 1_000,
+		};
+		let marker = Rgba([12, 34, 56, 255]);
+		let mut image = RgbaImage::from_pixel(64, 8, Rgba([0, 0, 0, 255]));
+
+		image.put_pixel(63, 7, marker);
+
+		assert!(image_helpers::exceeds_gpu_texture_side(&image, 16));
+		assert!(!image_helpers::exceeds_gpu_texture_side(&image, 64));
+		assert_eq!(image_helpers::downscale_for_gpu_upload(&image, 16).dimensions(), (16, 2));
+
+		let frozen_image = Some(image);
+
+		assert_eq!(
+			image_helpers::frozen_rgb(&frozen_image, Some(monitor), GlobalPoint::new(63, 7)),
+			Some(Rgb::new(12, 34, 56))
+		);
+
+		let patch = image_helpers::frozen_loupe_patch(
+			&frozen_image,
+			Some(monitor),
+			GlobalPoint::new(63, 7),
+			3,
+			3,
+		)
+		.unwrap();
+
+		assert_eq!(*patch.get_pixel(1, 1), marker);
+	}
+
+	#[test]
+	fn loupe_zoom_label_reports_physical_magnification() {
+		assert_eq!(hud_helpers::format_loupe_zoom_label(10.0, 1.0), "10×");
+		assert_eq!(hud_helpers::format_loupe_zoom_label(4.0, 2.0), "8×");
+		assert_eq!(hud_helpers::format_loupe_zoom_label(6.0, 1.25), "7.5×");
+	}
+
+	#[test]
+	fn reference_sample_holds_the_loupe_while_the_readout_follows_the_cursor() {
+		let monitor = headless_monitor();
+		let mut session = OverlaySession::new();
+
+		session.state.mode = OverlayMode::Frozen;
+		session.state.monitor = Some(monitor);
+		session.state.alt_held = true;
+		session.state.frozen_image = Some(ScriptedCaptureBackend::synthetic_frame(200, 100));
+		session.update_cursor_state(monitor, GlobalPoint::new(10, 20));
+		session.toggle_reference_sample();
+		session.update_cursor_state(monitor, GlobalPoint::new(30, 40));
+
+		let reference = session.state.reference_sample.expect("reference");
+		let loupe_center =
+			|session: &OverlaySession| session.state.loupe.as_ref().map(|loupe| loupe.center);
+
+		assert_eq!(reference.rgb, Rgb::new(10, 20, 10 ^ 20));
+		assert_eq!(session.state.rgb, Some(Rgb::new(30, 40, 30 ^ 40)));
+		assert_eq!(loupe_center(&session), Some(GlobalPoint::new(10, 20)));
+
+		session.toggle_reference_sample();
+
+		assert_eq!(session.state.reference_sample, None);
+		assert_eq!(loupe_center(&session), Some(GlobalPoint::new(30, 40)));
+	}
+
+	#[test]
+	fn loupe_coordinate_labels_thin_out_until_they_fit() {
+		assert_eq!(hud_helpers::loupe_coordinate_label_step(20.0, 15.0), 1);
+		assert_eq!(hud_helpers::loupe_coordinate_label_step(14.0, 19.0), 2);
+		assert_eq!(hud_helpers::loupe_coordinate_label_step(4.0, 19.0), 10);
+	}
+
+	#[test]
+	fn clicking_a_loupe_cell_locks_the_color_readout_until_clicked_again() {
+		let mut session = OverlaySession::new();
+
+		session.state.hud_pinned = true;
+		session.state.rgb = Some(Rgb::new(2, 2, 0));
+		session.state.loupe = Some(LoupeSample {
+			center: GlobalPoint::new(40, 30),
+			patch: ScriptedCaptureBackend::synthetic_frame(5, 5),
+		});
+
+		session.toggle_loupe_lock((1, -1));
+
+		assert_eq!(session.state.loupe_lock, Some((1, -1)));
+		assert_eq!(session.state.rgb, Some(Rgb::new(3, 1, 3 ^ 1)));
+
+		session.toggle_loupe_lock((1, -1));
+
+		assert_eq!(session.state.loupe_lock, None);
+		assert_eq!(session.state.rgb, Some(Rgb::new(2, 2, 0)));
+
+		session.toggle_loupe_lock((-2, 2));
+		session.set_hud_pinned(false);
+
+		assert_eq!(session.state.loupe_lock, None);
+		assert_eq!(session.state.rgb, Some(Rgb::new(2, 2, 0)));
+	}
+
+	#[test]
+	fn magnifier_geometry_scales_cell_with_zoom_and_display_density() {
+		let retina = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_512,
+			height: 982,
+			scale_factor_x1000: 2_000,
+		};
+		let standard = MonitorRect { scale_factor_x1000: 1_000, ..retina };
+
+		assert_eq!(OverlaySession::magnifier_geometry(standard, 4), (61, 4.0));
+		assert_eq!(OverlaySession::magnifier_geometry(retina, 4), (121, 2.0));
+		assert_eq!(OverlaySession::magnifier_geometry(standard, 1), (121, 2.0));
+		assert_eq!(OverlaySession::magnifier_geometry(standard, 20), (31, 8.0));
+	}
+
+	#[test]
+	fn hud_unit_cycles_through_all_units() {
+		assert_eq!(HudUnit::Points.next(), HudUnit::Millimeters);
+		assert_eq!(HudUnit::Millimeters.next(), HudUnit::Pixels);
+		assert_eq!(HudUnit::Pixels.next(), HudUnit::Points);
+	}
+
+	#[test]
+	fn composition_guides_toggle_in_live_mode_and_lay_out_inside_the_selection() {
+		let config = OverlayConfig {
+			composition_guides: CompositionGuides {
+				safe_margins: true,
+				..CompositionGuides::default()
+			},
+			..headless_config()
+		};
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
+		let rect = Rect::from_min_size(Pos2::new(0.0, 0.0), Vec2::new(300.0, 150.0));
+		let segments =
+			|guides| composition_guides_runtime::composition_guide_segments(guides, rect);
+
+		assert!(segments(overlay.session.composition_guides()).is_empty());
+
+		let _ = overlay.session.toggle_composition_guides();
+		let guides = overlay.session.composition_guides();
+
+		assert!(guides.visible);
+		// Four thirds lines, the two crosshair arms and two four-sided safe margins.
+		assert_eq!(segments(guides).len(), 14);
+		assert!(segments(guides).contains(&[Pos2::new(100.0, 0.0), Pos2::new(100.0, 150.0)]));
+		assert!(segments(guides).contains(&[Pos2::new(30.0, 15.0), Pos2::new(270.0, 15.0)]));
+		assert!(
+			composition_guides_runtime::composition_guide_segments(
+				guides,
+				Rect::from_min_size(Pos2::ZERO, Vec2::new(300.0, 10.0))
+			)
+			.is_empty()
+		);
+	}
+
+	#[test]
+	fn live_hud_rgb_text_uses_fixed_width_placeholders() {
+		let (missing_hex, missing_rgb) = hud_helpers::format_live_hud_rgb_text(None);
+		let (hex, rgb) = hud_helpers::format_live_hud_rgb_text(Some(Rgb::new(7, 128, 255)));
+
+		assert_eq!(missing_hex.len(), hex.len());
+		assert_eq!(missing_rgb.len(), rgb.len());
+		assert_eq!(missing_hex, "#??????");
+		assert_eq!(missing_rgb, "RGB(???, ???, ???)");
+		assert_eq!(rgb, "RGB(  7, 128, 255)");
+	}
+
+	#[test]
+	fn stable_live_loupe_side_prefers_configured_patch_side() {
+		let mut state = crate::state::OverlayState::new();
+
+		state.loupe_patch_side_px = 21;
+		state.loupe = Some(LoupeSample {
+			center: GlobalPoint::new(100, 120),
+			patch: RgbaImage::from_pixel(17, 19, image::Rgba([0, 0, 0, 255])),
+		});
+
+		assert_eq!(hud_helpers::stable_live_loupe_side_px(&state), 21);
+	}
+
+	#[test]
+	fn stable_live_loupe_side_ignores_larger_runtime_patch() {
+		let mut state = crate::state::OverlayState::new();
+
+		state.loupe_patch_side_px = 21;
+		state.loupe = Some(LoupeSample {
+			center: GlobalPoint::new(100, 120),
+			patch: RgbaImage::from_pixel(25, 25, image::Rgba([0, 0, 0, 255])),
+		});
+
+		assert_eq!(hud_helpers::stable_live_loupe_side_px(&state), 21);
+	}
+
+	#[test]
+	fn stable_live_loupe_window_inner_size_matches_runtime_target() {
+		assert_eq!(hud_helpers::stable_live_loupe_window_inner_size_points(21), (232, 232));
+		assert_eq!(hud_helpers::stable_live_loupe_window_inner_size_points(1), (32, 32));
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn drain_external_scroll_input_events_through_advances_last_seen_seq() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let start = Instant::now();
+		let events = Arc::new([
+			(1, start, 150.0, 160.0, -4.0, true, false),
+			(2, start + Duration::from_millis(2), 150.0, 160.0, 4.0, false, true),
+		]);
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.set_external_scroll_input_drain_reader(Arc::new({
+			let events = Arc::clone(&events);
+
+			move |after_seq, through| {
+				events
+					.iter()
+					.copied()
+					.filter(|event| event.0 > after_seq && event.1 <= through)
+					.collect()
+			}
+		}));
+
+		session.drain_external_scroll_input_events_through(start);
+
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert!(session.scroll_capture.input_gesture_active);
+		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 1);
+
+		session.drain_external_scroll_input_events_through(start);
+
+		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 1);
+
+		session.drain_external_scroll_input_events_through(start + Duration::from_millis(2));
+
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Up));
+		assert!(!session.scroll_capture.input_gesture_active);
+		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 2);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn drain_external_scroll_input_events_through_uses_pairing_time_for_freshness() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let through = Instant::now();
+		let recorded_at = through - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50);
+		let events = Arc::new([(1, recorded_at, 150.0, 160.0, -4.0, false, false)]);
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.set_external_scroll_input_drain_reader(Arc::new({
+			let events = Arc::clone(&events);
+
+			move |after_seq, paired_through| {
+				events
+					.iter()
+					.copied()
+					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
+					.collect()
+			}
+		}));
+
+		session.drain_external_scroll_input_events_through(through);
+
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert_eq!(session.scroll_capture.input_direction_at, Some(through));
+		assert_eq!(session.scroll_capture_observation_block_reason(), None);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn replayed_stream_input_uses_frame_time_for_stale_gate_without_global_relaxation() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+		];
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
+		let through = Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50);
+		let recorded_at = through - Duration::from_millis(12);
+		let events = Arc::new([(1, recorded_at, 150.0, 160.0, -4.0, false, false)]);
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.set_external_scroll_input_drain_reader(Arc::new({
+			let events = Arc::clone(&events);
+
+			move |after_seq, paired_through| {
+				events
+					.iter()
+					.copied()
+					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
+					.collect()
+			}
+		}));
+
+		session.drain_external_scroll_input_events_through(through);
+
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert_eq!(session.scroll_capture.input_direction_at, Some(through));
+		assert_eq!(session.scroll_capture_observation_block_reason(), Some("stale_input"));
+		assert_eq!(session.scroll_capture_observation_block_reason_at(through), None);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame_at(
+					make_scroll_capture_window(&document, 3, 1, 5),
+					through,
+				)
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn replayed_downward_input_allows_bounded_stale_live_stream_frame() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+		];
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
+		let through = Instant::now();
+		let events =
+			Arc::new([(7, through - Duration::from_millis(10), 150.0, 160.0, -4.0, false, false)]);
+		let stale_at = through + SCROLL_CAPTURE_INPUT_FRESHNESS + Duration::from_millis(1);
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.set_external_scroll_input_drain_reader(Arc::new({
+			let events = Arc::clone(&events);
+
+			move |after_seq, paired_through| {
+				events
+					.iter()
+					.copied()
+					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
+					.collect()
+			}
+		}));
+
+		session.drain_external_scroll_input_events_through(through);
+
+		assert_eq!(
+			session.scroll_capture.live_stream_stale_grace,
+			Some(LiveStreamStaleGrace {
+				external_input_seq: 7,
+				input_direction: ScrollDirection::Down,
+				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES,
+			})
+		);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame_at(
+					make_scroll_capture_window(&document, 3, 1, 5),
+					stale_at,
+				)
+				.transpose()
+				.unwrap(),
+			None
+		);
+
+		session.handle_scroll_capture_frame(
+			make_scroll_capture_window(&document, 3, 1, 5),
+			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
+			false,
+			stale_at,
+		);
+
+		assert_eq!(scroll_capture_export_height(&session), 6);
+		assert_eq!(
+			session.scroll_capture.live_stream_stale_grace,
+			Some(LiveStreamStaleGrace {
+				external_input_seq: 7,
+				input_direction: ScrollDirection::Down,
+				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES - 1,
+			})
+		);
+	}
+
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn stale_live_stream_grace_survives_same_direction_overlay_wheel_update() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+		];
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
+		let through = Instant::now();
+		let wheel_at = through + Duration::from_millis(10);
+		let events =
+			Arc::new([(7, through - Duration::from_millis(10), 150.0, 160.0, -4.0, false, false)]);
+		let stale_at = wheel_at + SCROLL_CAPTURE_INPUT_FRESHNESS + Duration::from_millis(1);
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.set_external_scroll_input_drain_reader(Arc::new({
+			let events = Arc::clone(&events);
+
+			move |after_seq, paired_through| {
+				events
+					.iter()
+					.copied()
+					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
+					.collect()
+			}
+		}));
+
+		session.drain_external_scroll_input_events_through(through);
+		session.record_scroll_capture_input_direction_from_overlay_wheel_at(
+			&MouseScrollDelta::LineDelta(0.0, -1.0),
+			wheel_at,
+		);
+
+		assert_eq!(session.scroll_capture.input_direction_at, Some(wheel_at));
+		assert_eq!(
+			session.scroll_capture.live_stream_stale_grace,
+			Some(LiveStreamStaleGrace {
+				external_input_seq: 7,
+				input_direction: ScrollDirection::Down,
+				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES,
+			})
+		);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame_at(
+					make_scroll_capture_window(&document, 3, 1, 5),
+					stale_at,
+				)
+				.transpose()
+				.unwrap(),
+			None
+		);
+
+		session.handle_scroll_capture_frame(
+			make_scroll_capture_window(&document, 3, 1, 5),
+			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
+			false,
+			stale_at,
+		);
+
+		assert_eq!(scroll_capture_export_height(&session), 6);
+		assert_eq!(
+			session.scroll_capture.live_stream_stale_grace,
+			Some(LiveStreamStaleGrace {
+				external_input_seq: 7,
+				input_direction: ScrollDirection::Down,
+				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES - 1,
+			})
+		);
 	}
-}
 
-#[cfg(target_os = "macos")]
-#[repr(C)]
-struct MacOSCGPoint {
-	x: f64,
-	y: f64,
-}
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn live_stream_stale_grace_is_consumed_and_superseded() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+		];
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
+		let stale_at = Instant::now() - Duration::from_millis(1);
+		let mut session = OverlaySession::new();
 
-#[cfg(target_os = "macos")]
-fn macos_is_option_key_down() -> bool {
-	let flags = unsafe { CGEventSourceFlagsState(macos_hid_event_source_state_id()) };
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.scroll_capture.last_external_scroll_input_seq = 7;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at =
+			Some(stale_at - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(1));
+		session.scroll_capture.input_gesture_active = false;
+		session.scroll_capture.live_stream_stale_grace = Some(LiveStreamStaleGrace {
+			external_input_seq: 7,
+			input_direction: ScrollDirection::Down,
+			remaining_stale_frames: 1,
+		});
 
-	flags & KCG_EVENT_FLAGS_MASK_ALTERNATE != 0
-}
+		session.handle_scroll_capture_frame(
+			make_scroll_capture_window(&document, 3, 1, 5),
+			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
+			false,
+			stale_at,
+		);
 
-#[cfg(target_os = "macos")]
-fn macos_hid_event_source_state_id() -> u32 {
-	KCG_EVENT_SOURCE_STATE_HID_SYSTEM_STATE
-}
+		assert_eq!(scroll_capture_export_height(&session), 6);
+		assert_eq!(session.scroll_capture.live_stream_stale_grace, None);
 
-fn global_to_local(cursor: GlobalPoint, monitor: MonitorRect) -> Option<Pos2> {
-	let (x, y) = monitor.local_u32(cursor)?;
+		let height_after_first_stale = scroll_capture_export_height(&session);
 
-	Some(Pos2::new(x as f32, y as f32))
-}
+		session.handle_scroll_capture_frame(
+			make_scroll_capture_window(&document, 3, 2, 5),
+			ScrollCaptureFrameSource::LiveStream { frame_seq: 144 },
+			false,
+			stale_at,
+		);
 
-#[cfg(target_os = "macos")]
-#[link(name = "CoreGraphics", kind = "framework")]
-unsafe extern "C" {
-	fn CGEventGetLocation(event: CGEventRef) -> MacOSCGPoint;
-	fn CGEventCreate(source: *const c_void) -> CGEventRef;
-	fn CGEventSourceCreate(source_state_id: u32) -> CFTypeRef;
-	fn CGEventCreateScrollWheelEvent2(
-		source: *const c_void,
-		units: u32,
-		wheel_count: u32,
-		wheel1: i32,
-		wheel2: i32,
-		wheel3: i32,
-	) -> CGEventRef;
-	fn CGEventPost(tap_location: u32, event: CGEventRef);
-	fn CGEventSetLocation(event: CGEventRef, location: MacOSCGPoint);
-	fn CGEventSourceFlagsState(source_state_id: u32) -> u64;
-}
+		assert_eq!(scroll_capture_export_height(&session), height_after_first_stale);
 
-#[cfg(target_os = "macos")]
-#[link(name = "CoreFoundation", kind = "framework")]
-unsafe extern "C" {
-	fn CFRelease(obj: CFTypeRef);
-}
+		session.scroll_capture.last_external_scroll_input_seq = 8;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
+		session.scroll_capture.input_direction_at =
+			Some(stale_at - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(1));
+		session.scroll_capture.input_gesture_active = false;
+		session.scroll_capture.live_stream_stale_grace = Some(LiveStreamStaleGrace {
+			external_input_seq: 7,
+			input_direction: ScrollDirection::Down,
+			remaining_stale_frames: 1,
+		});
 
-#[cfg(target_os = "macos")]
-fn macos_mouse_location() -> Option<GlobalPoint> {
-	let event = unsafe { CGEventCreate(ptr::null()) };
+		session.handle_scroll_capture_frame(
+			make_scroll_capture_window(&document, 3, 1, 5),
+			ScrollCaptureFrameSource::LiveStream { frame_seq: 145 },
+			false,
+			stale_at,
+		);
 
-	if event.is_null() {
-		return None;
+		assert_eq!(scroll_capture_export_height(&session), height_after_first_stale);
+		assert_eq!(session.scroll_capture.live_stream_stale_grace, None);
 	}
 
-	let point = unsafe { CGEventGetLocation(event) };
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn wrapped_pixel_delta_normalizes_back_to_signed_values() {
+		assert_eq!(OverlaySession::normalize_macos_scroll_pixel_component(4_294_967_294.0), -2.0);
+		assert_eq!(OverlaySession::normalize_macos_scroll_pixel_component(4_294_967_290.0), -6.0);
+	}
 
-	unsafe { CFRelease(event) };
+	#[test]
+	fn negative_vertical_wheel_delta_maps_to_downward_scroll_capture() {
+		assert_eq!(
+			OverlaySession::scroll_capture_direction_from_wheel_delta(
+				&MouseScrollDelta::LineDelta(0.0, -1.0)
+			),
+			Some(ScrollDirection::Down)
+		);
+	}
 
-	Some(GlobalPoint::new(point.x as i32, point.y as i32))
-}
+	#[test]
+	fn positive_vertical_wheel_delta_maps_to_upward_scroll_capture() {
+		assert_eq!(
+			OverlaySession::scroll_capture_direction_from_wheel_delta(
+				&MouseScrollDelta::LineDelta(0.0, 1.0)
+			),
+			Some(ScrollDirection::Up)
+		);
+	}
+
+	#[test]
+	fn external_scroll_input_inside_capture_rect_updates_direction() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
 
-#[cfg(target_os = "macos")]
-fn macos_activate_app() {
-	unsafe {
-		let app: *mut Object = objc::msg_send![objc::class!(NSApplication), sharedApplication];
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
 
-		if app.is_null() {
-			return;
-		}
+		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, true, false);
 
-		let _: () = objc::msg_send![app, activateIgnoringOtherApps: YES];
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert!(session.scroll_capture.input_direction_at.is_some());
+		assert!(session.scroll_capture.input_gesture_active);
 	}
-}
 
-#[cfg(target_os = "macos")]
-fn macos_make_window_key(window: &winit::window::Window) {
-	let Ok(handle) = window.window_handle() else {
-		return;
-	};
-	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
-		return;
-	};
-	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+	#[test]
+	fn external_scroll_input_outside_capture_rect_is_ignored() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
 
-	unsafe {
-		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
 
-		if ns_window.is_null() {
-			return;
-		}
+		session.handle_external_scroll_input_delta_y(50.0, 50.0, -4.0, true, false);
 
-		let nil: *mut Object = ptr::null_mut();
-		let _: () = objc::msg_send![ns_window, makeKeyAndOrderFront: nil];
+		assert_eq!(session.scroll_capture.input_direction, None);
+		assert!(session.scroll_capture.input_direction_at.is_none());
 	}
 
-	window.focus_window();
-}
-
-#[cfg(target_os = "macos")]
-fn macos_post_scroll_wheel_event(
-	delta: MacOSScrollWheelEvent,
-	target_point: GlobalPoint,
-) -> Result<()> {
-	let units = delta.units;
-	let wheel1 = delta.posted_y;
-	let wheel2 = delta.posted_x;
+	#[test]
+	fn external_scroll_input_terminal_event_preserves_last_direction_for_freshness() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
 
-	if wheel1 == 0 && wheel2 == 0 {
-		return Ok(());
-	}
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-	let source = unsafe { CGEventSourceCreate(macos_hid_event_source_state_id()) };
+		session.handle_external_scroll_input_delta_y(150.0, 160.0, 0.0, false, true);
 
-	if source.is_null() {
-		return Err(eyre::eyre!("failed to create macOS scroll wheel event source"));
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert!(session.scroll_capture.input_direction_at.is_some());
+		assert!(!session.scroll_capture.input_gesture_active);
+		assert!(session.scroll_capture_input_allows_growth());
 	}
 
-	let wheel_count = if wheel2 != 0 { 2 } else { 1 };
-	let event =
-		unsafe { CGEventCreateScrollWheelEvent2(source, units, wheel_count, wheel1, wheel2, 0) };
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn scroll_overlay_mouse_passthrough_window_arms_and_expires() {
+		let now = Instant::now();
+		let mut session = OverlaySession::new();
 
-	if event.is_null() {
-		unsafe {
-			CFRelease(source);
-		}
+		session.scroll_capture.active = true;
 
-		return Err(eyre::eyre!("failed to create macOS scroll wheel event"));
-	}
+		session.arm_scroll_overlay_mouse_passthrough_window(now, "test");
 
-	unsafe {
-		CGEventSetLocation(
-			event,
-			MacOSCGPoint { x: f64::from(target_point.x), y: f64::from(target_point.y) },
+		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
+		assert_eq!(
+			session.scroll_capture.overlay_mouse_passthrough_until,
+			Some(now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE)
 		);
-		CGEventPost(KCG_HID_EVENT_TAP, event);
-		CFRelease(event);
-		CFRelease(source);
-	}
-
-	Ok(())
-}
 
-#[cfg(target_os = "macos")]
-fn macos_configure_overlay_window_mouse_moved_events(window: &winit::window::Window) {
-	let Ok(handle) = window.window_handle() else {
-		return;
-	};
-	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
-		return;
-	};
-	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+		session.sync_scroll_overlay_mouse_passthrough_window(
+			now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE / 2,
+		);
 
-	unsafe {
-		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
 
-		if ns_window.is_null() {
-			return;
-		}
+		session.sync_scroll_overlay_mouse_passthrough_window(
+			now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE + Duration::from_millis(1),
+		);
 
-		let _: () = objc::msg_send![ns_window, setOpaque: false];
-		let _: () = objc::msg_send![ns_window, setHasShadow: false];
-		let sharing_type_none = 0_u64;
-		let _: () = objc::msg_send![ns_window, setSharingType: sharing_type_none];
-		let clear: *mut Object = objc::msg_send![objc::class!(NSColor), clearColor];
-		let _: () = objc::msg_send![ns_window, setBackgroundColor: clear];
-		let _: () = objc::msg_send![ns_window, setLevel: MACOS_OVERLAY_WINDOW_LEVEL];
-		let _: () = objc::msg_send![ns_window, setAcceptsMouseMovedEvents: YES];
+		assert!(!session.scroll_capture.overlay_mouse_passthrough_active);
+		assert!(session.scroll_capture.overlay_mouse_passthrough_until.is_none());
 	}
-}
 
-#[cfg(target_os = "macos")]
-fn macos_configure_hud_window(
-	window: &winit::window::Window,
-	blur_enabled: bool,
-	blur_amount: f32,
-	corner_radius_points: Option<f64>,
-) {
-	let Ok(handle) = window.window_handle() else {
-		return;
-	};
-	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
-		return;
-	};
-	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn external_scroll_input_extends_passthrough_window_inside_capture_rect() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let earlier = Instant::now() - Duration::from_millis(20);
+		let mut session = OverlaySession::new();
 
-	unsafe {
-		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
 
-		if ns_window.is_null() {
-			return;
-		}
+		session.arm_scroll_overlay_mouse_passthrough_window(earlier, "test");
 
-		// winit exposes blur as a boolean. We also set an explicit radius so we can drive it from
-		// settings (this uses the same private CGS API that winit uses internally).
-		{
-			#[link(name = "CoreGraphics", kind = "framework")]
-			unsafe extern "C" {
-				fn CGSMainConnectionID() -> *mut c_void;
+		let first_deadline = session.scroll_capture.overlay_mouse_passthrough_until;
 
-				fn CGSSetWindowBackgroundBlurRadius(
-					connection_id: *mut c_void,
-					window_id: isize,
-					radius: i64,
-				) -> i32;
-			}
+		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, true, false);
 
-			let amount = blur_amount.clamp(0.0, 1.0);
-			let radius = if blur_enabled {
-				// Map the slider linearly (0..=1) to the native blur radius.
-				// Keep the upper bound conservative; CGS blur radius gets strong quickly.
-				let max_radius = 12.0;
+		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
+		assert!(session.scroll_capture.overlay_mouse_passthrough_until > first_deadline);
+	}
 
-				(amount * max_radius).round().clamp(0.0, 200.0) as i64
-			} else {
-				0
-			};
-			let window_number: isize = objc::msg_send![ns_window, windowNumber];
-			let _ = CGSSetWindowBackgroundBlurRadius(CGSMainConnectionID(), window_number, radius);
-		}
+	#[test]
+	fn terminal_downward_scroll_event_sets_direction_before_finishing() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
 
-		let _: () = objc::msg_send![ns_window, setOpaque: false];
-		let _: () = objc::msg_send![ns_window, setHasShadow: false];
-		let _: () = objc::msg_send![ns_window, setAcceptsMouseMovedEvents: YES];
-		let _: () = objc::msg_send![ns_window, setLevel: MACOS_HUD_WINDOW_LEVEL];
-		let sharing_type_none = 0_u64;
-		let _: () = objc::msg_send![ns_window, setSharingType: sharing_type_none];
-		let clear: *mut Object = objc::msg_send![objc::class!(NSColor), clearColor];
-		let _: () = objc::msg_send![ns_window, setBackgroundColor: clear];
-		let content_view: *mut Object = objc::msg_send![ns_window, contentView];
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
 
-		if content_view.is_null() {
-			return;
-		}
+		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, false, true);
 
-		let _: () = objc::msg_send![content_view, setWantsLayer: YES];
-		let layer: *mut Object = objc::msg_send![content_view, layer];
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert!(session.scroll_capture.input_direction_at.is_some());
+		assert!(!session.scroll_capture.input_gesture_active);
+		assert!(session.scroll_capture_input_allows_growth());
+	}
+
+	#[test]
+	fn terminal_upward_scroll_event_does_not_allow_growth() {
+		let monitor = MonitorRect {
+			id: 1,
+			origin: GlobalPoint::new(0, 0),
+			width: 1_000,
+			height: 800,
+			scale_factor_x1000: 1_000,
+		};
+		let mut session = OverlaySession::new();
 
-		if layer.is_null() {
-			return;
-		}
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
 
-		// Round the window itself so native blur doesn't show a rectangular boundary.
-		let scale = window.scale_factor().max(1.0);
-		let size = window.inner_size();
-		let height_points = (size.height as f64) / scale;
-		let radius = corner_radius_points.unwrap_or(height_points * 0.5);
-		let _: () = objc::msg_send![layer, setCornerRadius: radius];
-		let _: () = objc::msg_send![layer, setMasksToBounds: YES];
+		session.handle_external_scroll_input_delta_y(150.0, 160.0, 4.0, false, true);
+
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Up));
+		assert!(session.scroll_capture.input_direction_at.is_some());
+		assert!(!session.scroll_capture.input_gesture_active);
+		assert!(!session.scroll_capture_input_allows_growth());
 	}
-}
 
-#[cfg(test)]
-mod tests {
-	#[cfg(target_os = "macos")]
-	use std::sync::Arc;
 	#[cfg(target_os = "macos")]
-	use std::time::Duration;
-	use std::time::Instant;
+	#[test]
+	fn overlay_wheel_fallback_records_direction_with_drain_reader_present() {
+		let observed_at = Instant::now();
+		let mut session = OverlaySession::new();
 
-	use image::{Rgba, RgbaImage};
-	#[cfg(target_os = "macos")]
-	use winit::dpi::PhysicalPosition;
-	use winit::event::MouseScrollDelta;
+		session.scroll_capture.active = true;
 
-	#[cfg(target_os = "macos")]
-	use crate::live_frame_stream_macos::MacLiveFrameStream;
-	#[cfg(not(target_os = "macos"))]
-	use crate::overlay::FrozenCaptureSource;
-	use crate::overlay::{
-		FrozenToolbarState, FrozenToolbarTool, HudTheme, OverlaySession, Pos2, Rect,
-		TOOLBAR_CAPTURE_GAP_PX, TOOLBAR_SCREEN_MARGIN_PX, ToolbarPlacement, Vec2, WindowRenderer,
-		hud_helpers,
-	};
-	#[cfg(target_os = "macos")]
-	use crate::overlay::{
-		HUD_PILL_CORNER_RADIUS_POINTS, HudPillGeometry, InflightScrollCaptureObservation,
-		KCG_SCROLL_EVENT_UNIT_PIXEL, LiveSampleApplyResult, LiveStreamStaleGrace,
-		MacOSScrollPixelResidual, SCROLL_CAPTURE_INPUT_FRESHNESS,
-		SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES, SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE,
-		ScrollCaptureFrameSource,
-	};
-	use crate::scroll_capture::{ScrollDirection, ScrollObserveOutcome, ScrollSession};
-	#[cfg(target_os = "macos")]
-	use crate::state::LiveCursorSample;
-	use crate::state::{
-		GlobalPoint, LoupeSample, MonitorRect, MonitorRectPoints, OverlayMode, RectPoints, Rgb,
-	};
+		session.set_external_scroll_input_drain_reader(Arc::new(|_, _| Vec::new()));
+		session.record_scroll_capture_input_direction_from_overlay_wheel_at(
+			&MouseScrollDelta::LineDelta(0.0, -1.0),
+			observed_at,
+		);
 
-	fn make_scroll_capture_test_image(width: u32, rows: &[[u8; 4]]) -> image::RgbaImage {
-		let mut image = image::RgbaImage::new(width, rows.len() as u32);
+		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
+		assert_eq!(session.scroll_capture.input_direction_at, Some(observed_at));
+		assert!(!session.scroll_capture.input_gesture_active);
+	}
 
-		for (y, row) in rows.iter().enumerate() {
-			for x in 0..width {
-				image.put_pixel(x, y as u32, Rgba(*row));
-			}
-		}
+	#[test]
+	fn missing_scroll_direction_does_not_allow_growth() {
+		let mut session = OverlaySession::new();
 
-		image
-	}
+		session.scroll_capture.active = true;
 
-	fn make_scroll_capture_window(
-		document: &[[u8; 4]],
-		width: u32,
-		start_row: usize,
-		window_rows: usize,
-	) -> image::RgbaImage {
-		make_scroll_capture_test_image(width, &document[start_row..start_row + window_rows])
+		assert!(!session.scroll_capture_input_allows_growth());
 	}
 
-	fn set_scroll_capture_input(session: &mut OverlaySession, direction: ScrollDirection) {
-		session.scroll_capture.input_direction = Some(direction);
+	#[test]
+	fn fresh_upward_direction_still_allows_observation() {
+		let mut session = OverlaySession::new();
+
+		session.scroll_capture.active = true;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
 		session.scroll_capture.input_direction_at = Some(Instant::now());
 		session.scroll_capture.input_gesture_active = true;
-	}
-
-	fn observe_scroll_capture_frame(
-		session: &mut OverlaySession,
-		frame: image::RgbaImage,
-	) -> Option<ScrollObserveOutcome> {
-		session.observe_scroll_capture_frame(frame).transpose().unwrap()
-	}
 
-	fn scroll_capture_export_height(session: &OverlaySession) -> u32 {
-		session.scroll_capture.session.as_ref().unwrap().export_image().height()
+		assert!(session.scroll_capture_input_allows_observation());
+		assert!(!session.scroll_capture_input_allows_growth());
 	}
 
 	#[test]
-	fn frozen_toolbar_default_position_fits_below_capture_rect() {
-		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
-		let capture_rect = Rect::from_min_size(Pos2::new(50.0, 100.0), Vec2::new(300.0, 200.0));
-		let toolbar_size = Vec2::new(460.0, 54.0);
-		let pos = WindowRenderer::frozen_toolbar_default_pos(
-			monitor,
-			capture_rect,
-			toolbar_size,
-			ToolbarPlacement::Bottom,
-		);
-		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
-			TOOLBAR_SCREEN_MARGIN_PX,
-			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
-				.max(TOOLBAR_SCREEN_MARGIN_PX),
-		);
+	fn fresh_downward_direction_allows_growth_without_active_gesture() {
+		let mut session = OverlaySession::new();
 
-		assert!((pos.x - expected_x).abs() < f32::EPSILON);
-		assert_eq!(pos.y, capture_rect.max.y + TOOLBAR_CAPTURE_GAP_PX);
+		session.scroll_capture.active = true;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = false;
+
+		assert!(session.scroll_capture_input_allows_growth());
 	}
 
 	#[test]
-	fn frozen_toolbar_default_position_falls_inside_when_no_space_below_capture_rect() {
-		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 600.0));
-		let toolbar_size = Vec2::new(460.0, 54.0);
-		let capture_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 560.0));
-		let pos = WindowRenderer::frozen_toolbar_default_pos(
-			monitor,
-			capture_rect,
-			toolbar_size,
-			ToolbarPlacement::Bottom,
-		);
-		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
-			TOOLBAR_SCREEN_MARGIN_PX,
-			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
-				.max(TOOLBAR_SCREEN_MARGIN_PX),
-		);
-		let expected_y = capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y;
+	fn upward_direction_never_allows_growth() {
+		let mut session = OverlaySession::new();
 
-		assert_eq!(pos.x, expected_x);
-		assert_eq!(pos.y, capture_rect.max.y - TOOLBAR_SCREEN_MARGIN_PX - toolbar_size.y);
-		assert_eq!(pos.y, expected_y);
+		session.scroll_capture.active = true;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
+
+		assert!(!session.scroll_capture_input_allows_growth());
 	}
 
 	#[test]
-	fn frozen_toolbar_top_default_position_fits_above_capture_rect() {
-		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
-		let capture_rect = Rect::from_min_size(Pos2::new(50.0, 180.0), Vec2::new(300.0, 200.0));
-		let toolbar_size = Vec2::new(460.0, 54.0);
-		let pos = WindowRenderer::frozen_toolbar_default_pos(
-			monitor,
-			capture_rect,
-			toolbar_size,
-			ToolbarPlacement::Top,
-		);
-		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
-			TOOLBAR_SCREEN_MARGIN_PX,
-			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
-				.max(TOOLBAR_SCREEN_MARGIN_PX),
-		);
+	fn upward_rewind_frame_is_observed_before_resume_frontier_growth() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+			[80, 0, 0, 255],
+		];
+		let mut session = OverlaySession::new();
 
-		assert_eq!(pos.x, expected_x);
-		assert_eq!(pos.y, capture_rect.min.y - TOOLBAR_CAPTURE_GAP_PX - toolbar_size.y);
-	}
+		session.scroll_capture.active = true;
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
 
-	#[test]
-	fn frozen_toolbar_top_default_position_falls_inside_when_no_space_above_capture_rect() {
-		let monitor = Rect::from_min_size(Pos2::ZERO, Vec2::new(500.0, 600.0));
-		let capture_rect = Rect::from_min_size(Pos2::new(0.0, 20.0), Vec2::new(500.0, 400.0));
-		let toolbar_size = Vec2::new(460.0, 54.0);
-		let pos = WindowRenderer::frozen_toolbar_default_pos(
-			monitor,
-			capture_rect,
-			toolbar_size,
-			ToolbarPlacement::Top,
+		set_scroll_capture_input(&mut session, ScrollDirection::Down);
+
+		assert_eq!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 1, 5),
+			),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
 		);
-		let expected_x = (capture_rect.center().x - toolbar_size.x / 2.0).clamp(
-			TOOLBAR_SCREEN_MARGIN_PX,
-			(monitor.max.x - toolbar_size.x - TOOLBAR_SCREEN_MARGIN_PX)
-				.max(TOOLBAR_SCREEN_MARGIN_PX),
+		assert_eq!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 2, 5),
+			),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
 		);
 
-		assert_eq!(pos.x, expected_x);
-		assert_eq!(pos.y, capture_rect.min.y + TOOLBAR_SCREEN_MARGIN_PX);
-	}
+		let height_after_second_append = scroll_capture_export_height(&session);
+
+		set_scroll_capture_input(&mut session, ScrollDirection::Up);
+
+		assert!(matches!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 0, 5),
+			),
+			Some(
+				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
+					| ScrollObserveOutcome::PreviewUpdated
+			)
+		));
+		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+
+		set_scroll_capture_input(&mut session, ScrollDirection::Down);
+
+		assert!(matches!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 2, 5),
+			),
+			Some(ScrollObserveOutcome::NoChange) | Some(ScrollObserveOutcome::PreviewUpdated)
+		));
+		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+
+		set_scroll_capture_input(&mut session, ScrollDirection::Up);
+
+		assert!(matches!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 1, 5),
+			),
+			Some(
+				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
+					| ScrollObserveOutcome::PreviewUpdated
+					| ScrollObserveOutcome::NoChange
+			)
+		));
+		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
 
-	#[test]
-	fn scroll_toolbar_compacts_to_two_buttons() {
-		let frozen_toolbar_size =
-			WindowRenderer::frozen_toolbar_size(&FrozenToolbarState::default());
-		let scroll_toolbar_size = WindowRenderer::frozen_toolbar_size(&FrozenToolbarState {
-			scroll_capture_active: true,
-			..FrozenToolbarState::default()
-		});
+		set_scroll_capture_input(&mut session, ScrollDirection::Down);
 
-		assert!(scroll_toolbar_size.x < frozen_toolbar_size.x);
-		assert_eq!(scroll_toolbar_size.y, frozen_toolbar_size.y);
+		assert!(matches!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 2, 5),
+			),
+			Some(ScrollObserveOutcome::NoChange) | Some(ScrollObserveOutcome::PreviewUpdated)
+		));
+		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+		assert_eq!(
+			observe_scroll_capture_frame(
+				&mut session,
+				make_scroll_capture_window(&document, 3, 3, 5),
+			),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 	}
 
+	#[cfg(target_os = "macos")]
 	#[test]
-	fn scroll_preview_prefers_right_side_when_space_exists() {
+	fn stale_latched_worker_input_rewinds_without_ax_position() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+			[80, 0, 0, 255],
+		];
 		let monitor = MonitorRect {
 			id: 1,
 			origin: GlobalPoint::new(0, 0),
-			width: 1_400,
-			height: 900,
+			width: 1_000,
+			height: 800,
 			scale_factor_x1000: 1_000,
 		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
 		let mut session = OverlaySession::new();
 
-		session.state.frozen_capture_rect = Some(RectPoints::new(120, 160, 400, 320));
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-		let preview = session.scroll_preview_local_rect(monitor);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 
-		assert_eq!(preview.min.y, 160.0);
-		assert_eq!(preview.height(), 320.0);
-		assert!(preview.min.x >= 120.0 + 400.0);
-	}
+		let height_after_second_append =
+			session.scroll_capture.session.as_ref().unwrap().export_image().height();
 
-	#[test]
-	fn scroll_preview_falls_back_to_left_when_right_side_is_tight() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 900,
-			scale_factor_x1000: 1_000,
-		};
-		let mut session = OverlaySession::new();
+		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
+		session.scroll_capture.input_direction_at =
+			Some(Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50));
+		session.scroll_capture.input_gesture_active = false;
+		session.scroll_capture.last_external_scroll_input_seq = 7;
+		session.scroll_capture.inflight_request_id = Some(41);
+		session.scroll_capture.inflight_request_observation =
+			Some(InflightScrollCaptureObservation {
+				input_direction: Some(ScrollDirection::Up),
+				was_observable: true,
+				external_input_seq: 7,
+			});
 
-		session.state.frozen_capture_rect = Some(RectPoints::new(760, 180, 200, 260));
+		session.handle_captured_scroll_region(
+			monitor,
+			capture_rect,
+			41,
+			make_scroll_capture_window(&document, 3, 1, 5),
+		);
 
-		let preview = session.scroll_preview_local_rect(monitor);
+		assert_eq!(session.scroll_capture.inflight_request_id, None);
+		assert_eq!(session.scroll_capture.inflight_request_observation, None);
 
-		assert_eq!(preview.min.y, 180.0);
-		assert_eq!(preview.height(), 260.0);
-		assert!(preview.max.x <= 760.0);
+		let scroll_session_debug =
+			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+
+		assert!(
+			scroll_session_debug.contains("resume_frontier_top_y: Some(2)"),
+			"{scroll_session_debug}"
+		);
+		assert!(
+			scroll_session_debug.contains("observed_viewport_top_y: 1"),
+			"{scroll_session_debug}"
+		);
+		assert_eq!(
+			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
+			height_after_second_append
+		);
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "macos")]
 	#[test]
-	fn scroll_capture_is_unavailable_on_non_macos_even_with_drag_selection() {
+	fn newer_input_supersedes_latched_worker_observation_context() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+			[80, 0, 0, 255],
+		];
 		let monitor = MonitorRect {
 			id: 1,
 			origin: GlobalPoint::new(0, 0),
@@ -10554,32 +16339,104 @@ mod tests {
 			height: 800,
 			scale_factor_x1000: 1_000,
 		};
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
 		let mut session = OverlaySession::new();
 
-		session.state.mode = OverlayMode::Frozen;
-		session.state.monitor = Some(monitor);
-		session.state.frozen_capture_rect = Some(RectPoints::new(100, 120, 200, 240));
-		session.frozen_capture_source = FrozenCaptureSource::DragRegion;
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-		assert!(!session.scroll_capture_is_available());
-	}
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn reset_for_start_preserves_external_scroll_input_drain_reader() {
-		let mut session = OverlaySession::default();
+		let height_after_second_append =
+			session.scroll_capture.session.as_ref().unwrap().export_image().height();
 
-		session.set_external_scroll_input_drain_reader(Arc::new(|_, _| {
-			vec![(1, Instant::now(), 10.0, 20.0, -4.0, true, false)]
-		}));
-		session.reset_for_start();
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at =
+			Some(Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50));
+		session.scroll_capture.input_gesture_active = false;
+		session.scroll_capture.last_external_scroll_input_seq = 8;
+		session.scroll_capture.inflight_request_id = Some(41);
+		session.scroll_capture.inflight_request_observation =
+			Some(InflightScrollCaptureObservation {
+				input_direction: Some(ScrollDirection::Up),
+				was_observable: true,
+				external_input_seq: 7,
+			});
 
-		assert!(session.scroll_capture.external_scroll_input_drain_reader.is_some());
+		session.handle_captured_scroll_region(
+			monitor,
+			capture_rect,
+			41,
+			make_scroll_capture_window(&document, 3, 1, 5),
+		);
+
+		assert_eq!(session.scroll_capture.inflight_request_id, None);
+		assert_eq!(session.scroll_capture.inflight_request_observation, None);
+
+		let scroll_session_debug =
+			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+
+		assert!(scroll_session_debug.contains("resume_frontier_top_y: None"));
+		assert!(scroll_session_debug.contains("current_viewport_top_y: 2"));
+		assert_eq!(
+			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
+			height_after_second_append
+		);
+
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
+
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 3, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 	}
 
 	#[cfg(target_os = "macos")]
 	#[test]
-	fn apply_live_cursor_sample_updates_rgb_and_loupe_state() {
+	fn missing_worker_scroll_frame_clears_inflight_without_mutating_session() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+			[80, 0, 0, 255],
+		];
 		let monitor = MonitorRect {
 			id: 1,
 			origin: GlobalPoint::new(0, 0),
@@ -10587,37 +16444,42 @@ mod tests {
 			height: 800,
 			scale_factor_x1000: 1_000,
 		};
-		let cursor = GlobalPoint::new(120, 180);
-		let patch = image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]));
+		let capture_rect = RectPoints::new(100, 120, 200, 240);
 		let mut session = OverlaySession::new();
 
-		session.cursor_monitor = Some(monitor);
-		session.state.cursor = Some(cursor);
-		session.state.alt_held = true;
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
+		session.scroll_capture.last_external_scroll_input_seq = 11;
+		session.scroll_capture.inflight_request_id = Some(41);
+		session.scroll_capture.inflight_request_observation =
+			Some(InflightScrollCaptureObservation {
+				input_direction: Some(ScrollDirection::Down),
+				was_observable: true,
+				external_input_seq: 11,
+			});
 
-		assert!(
-			session
-				.apply_live_cursor_sample_detail(
-					monitor,
-					cursor,
-					LiveCursorSample {
-						rgb: Some(Rgb::new(10, 20, 30)),
-						patch: Some(patch.clone()),
-					},
-				)
-				.any_changed()
-		);
-		assert_eq!(session.state.rgb, Some(Rgb::new(10, 20, 30)));
-		assert_eq!(session.state.loupe.as_ref().map(|loupe| loupe.center), Some(cursor));
+		let scroll_session_before =
+			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+
+		session.handle_missing_scroll_region(monitor, capture_rect, 41);
+
+		assert_eq!(session.scroll_capture.inflight_request_id, None);
+		assert_eq!(session.scroll_capture.inflight_request_observation, None);
 		assert_eq!(
-			session.state.loupe.as_ref().map(|loupe| loupe.patch.dimensions()),
-			Some(patch.dimensions())
+			format!("{:?}", session.scroll_capture.session.as_ref().unwrap()),
+			scroll_session_before
 		);
 	}
 
 	#[cfg(target_os = "macos")]
 	#[test]
-	fn apply_live_cursor_sample_detail_keeps_overlay_redraw_narrow_for_rgb_and_loupe_updates() {
+	fn maybe_tick_scroll_capture_stays_on_stream_path_without_worker_fallback() {
 		let monitor = MonitorRect {
 			id: 1,
 			origin: GlobalPoint::new(0, 0),
@@ -10625,1593 +16487,1876 @@ mod tests {
 			height: 800,
 			scale_factor_x1000: 1_000,
 		};
-		let cursor = GlobalPoint::new(120, 180);
-		let patch = image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]));
 		let mut session = OverlaySession::new();
 
-		session.cursor_monitor = Some(monitor);
-		session.state.cursor = Some(cursor);
-		session.state.alt_held = true;
-
-		let apply = session.apply_live_cursor_sample_detail(
-			monitor,
-			cursor,
-			LiveCursorSample { rgb: Some(Rgb::new(10, 20, 30)), patch: Some(patch) },
-		);
-
-		assert_eq!(
-			apply,
-			LiveSampleApplyResult {
-				overlay_changed: false,
-				hud_changed: true,
-				loupe_changed: true,
-			}
-		);
-	}
+		session.scroll_capture.active = true;
+		session.scroll_capture.monitor = Some(monitor);
+		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.scroll_capture.live_stream = Some(MacLiveFrameStream::new());
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn live_sample_request_redraw_intent_only_redraws_immediate_hover_changes() {
-		let session = OverlaySession::new();
+		session.maybe_tick_scroll_capture();
 
-		assert_eq!(
-			session.live_sample_request_redraw_intent(false, true, true),
-			LiveSampleApplyResult::default()
-		);
-		assert_eq!(
-			session.live_sample_request_redraw_intent(true, true, true),
-			LiveSampleApplyResult {
-				overlay_changed: true,
-				hud_changed: true,
-				loupe_changed: false,
-			}
-		);
+		assert!(!session.scroll_capture.paused);
+		assert!(session.state.error_message.is_none());
+		assert_eq!(session.scroll_capture.inflight_request_id, None);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn live_loupe_renders_in_hud_window_only_for_live_alt() {
+	fn upward_input_with_lower_frame_never_appends_growth() {
+		let document = [
+			[10, 0, 0, 255],
+			[20, 0, 0, 255],
+			[30, 0, 0, 255],
+			[40, 0, 0, 255],
+			[50, 0, 0, 255],
+			[60, 0, 0, 255],
+			[70, 0, 0, 255],
+		];
 		let mut session = OverlaySession::new();
 
-		session.state.mode = OverlayMode::Frozen;
-
-		assert!(!session.live_loupe_uses_hud_window());
-		assert!(!session.live_loupe_renders_in_hud_window());
+		session.scroll_capture.active = true;
+		session.scroll_capture.session =
+			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-		session.state.mode = OverlayMode::Live;
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 
-		assert!(session.live_loupe_uses_hud_window());
-		assert!(!session.live_loupe_renders_in_hud_window());
+		let height_after_first_append =
+			session.scroll_capture.session.as_ref().unwrap().export_image().height();
 
-		session.state.alt_held = true;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-		assert!(session.live_loupe_renders_in_hud_window());
+		assert!(matches!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
+				.transpose()
+				.unwrap(),
+			Some(
+				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
+					| ScrollObserveOutcome::PreviewUpdated
+					| ScrollObserveOutcome::NoChange
+			)
+		));
+		assert_eq!(
+			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
+			height_after_first_append
+		);
+		assert!(matches!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::PreviewUpdated | ScrollObserveOutcome::NoChange)
+		));
 
-		session.state.mode = OverlayMode::Frozen;
+		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
+		session.scroll_capture.input_direction_at = Some(Instant::now());
+		session.scroll_capture.input_gesture_active = true;
 
-		assert!(!session.live_loupe_uses_hud_window());
-		assert!(!session.live_loupe_renders_in_hud_window());
+		assert_eq!(
+			session
+				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
+				.transpose()
+				.unwrap(),
+			Some(ScrollObserveOutcome::Committed {
+				direction: ScrollDirection::Down,
+				growth_rows: 1,
+			})
+		);
 	}
 
 	#[cfg(target_os = "macos")]
 	#[test]
-	fn hud_window_content_rect_includes_live_loupe_tile_bounds() {
-		let hud_pill = HudPillGeometry {
-			rect: Rect::from_min_max(Pos2::new(14.0, 14.0), Pos2::new(200.0, 58.0)),
-			radius_points: f32::from(HUD_PILL_CORNER_RADIUS_POINTS),
-		};
-		let loupe_tile = Rect::from_min_max(Pos2::new(14.0, 68.0), Pos2::new(246.0, 300.0));
-		let live_rect = OverlaySession::hud_window_content_rect(
-			OverlayMode::Live,
-			true,
-			hud_pill,
-			Some(loupe_tile),
+	fn positive_pixel_delta_maps_to_upward_scroll_capture() {
+		assert_eq!(
+			OverlaySession::scroll_capture_direction_from_wheel_delta(
+				&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 2.0))
+			),
+			Some(ScrollDirection::Up)
 		);
+	}
 
-		assert_eq!(live_rect.min, Pos2::new(14.0, 14.0));
-		assert_eq!(live_rect.max, Pos2::new(246.0, 300.0));
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn macos_scroll_wheel_events_use_hid_system_source_state() {
+		assert_eq!(
+			super::macos_hid_event_source_state_id(),
+			super::KCG_EVENT_SOURCE_STATE_HID_SYSTEM_STATE
+		);
+	}
 
-		let frozen_rect = OverlaySession::hud_window_content_rect(
-			OverlayMode::Frozen,
-			true,
-			hud_pill,
-			Some(loupe_tile),
+	#[cfg(target_os = "macos")]
+	#[test]
+	fn pixel_delta_residuals_accumulate_until_whole_pixels_emit() {
+		let mut residual = MacOSScrollPixelResidual::default();
+		let first = OverlaySession::normalize_macos_scroll_wheel_delta(
+			&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.4, -0.4)),
+			&mut residual,
+		);
+		let second = OverlaySession::normalize_macos_scroll_wheel_delta(
+			&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.7, -0.8)),
+			&mut residual,
 		);
 
-		assert_eq!(frozen_rect, hud_pill.rect);
+		assert_eq!(first.units, KCG_SCROLL_EVENT_UNIT_PIXEL);
+		assert_eq!(first.posted_x, 0);
+		assert_eq!(first.posted_y, 0);
+		assert!((first.residual.x - 0.4).abs() < f64::EPSILON);
+		assert!((first.residual.y + 0.4).abs() < f64::EPSILON);
+		assert_eq!(second.posted_x, 1);
+		assert_eq!(second.posted_y, -1);
+		assert!((second.residual.x - 0.1).abs() < 1e-9);
+		assert!((second.residual.y + 0.2).abs() < 1e-9);
 	}
 
 	#[test]
-	fn live_overlay_selection_flow_repaint_active_requires_drag_rect() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let mut session = OverlaySession::new();
-
-		session.state.mode = OverlayMode::Live;
-		session.cursor_monitor = Some(monitor);
-		session.state.cursor = Some(GlobalPoint::new(120, 180));
+	fn frozen_toolbar_mode_tools_are_identifiable() {
+		assert!(FrozenToolbarTool::Pointer.is_mode_tool());
+		assert!(FrozenToolbarTool::Pen.is_mode_tool());
+		assert!(FrozenToolbarTool::Text.is_mode_tool());
+		assert!(FrozenToolbarTool::Mosaic.is_mode_tool());
+		assert!(FrozenToolbarTool::Stamp.is_mode_tool());
+		assert!(FrozenToolbarTool::Callout.is_mode_tool());
+		assert!(FrozenToolbarTool::Highlighter.is_mode_tool());
+	}
 
-		assert!(!session.live_overlay_selection_flow_repaint_active());
+	#[test]
+	fn frozen_toolbar_action_tools_are_not_mode_tools() {
+		assert!(!FrozenToolbarTool::Undo.is_mode_tool());
+		assert!(!FrozenToolbarTool::Redo.is_mode_tool());
+		assert!(!FrozenToolbarTool::Scroll.is_mode_tool());
+		assert!(!FrozenToolbarTool::Copy.is_mode_tool());
+		assert!(!FrozenToolbarTool::Save.is_mode_tool());
+		assert!(!FrozenToolbarTool::Done.is_mode_tool());
+	}
 
-		session.state.hovered_window_rect = Some(MonitorRectPoints {
-			monitor_id: monitor.id,
-			rect: RectPoints::new(100, 120, 240, 320),
-		});
+	#[test]
+	fn done_pipeline_keeps_order_drops_repeats_and_defaults_to_copy() {
+		assert_eq!(
+			OverlaySession::done_pipeline(&[DoneAction::Save, DoneAction::Copy, DoneAction::Save]),
+			vec![DoneAction::Save, DoneAction::Copy]
+		);
+		assert_eq!(OverlaySession::done_pipeline(&[]), vec![DoneAction::Copy]);
+	}
 
-		assert!(!session.live_overlay_selection_flow_repaint_active());
+	#[test]
+	fn share_step_is_offered_only_on_macos() {
+		assert_eq!(DoneAction::Share.is_supported(), cfg!(target_os = "macos"));
+		assert!(
+			DoneAction::ALL
+				.iter()
+				.filter(|action| **action != DoneAction::Share)
+				.all(|action| action.is_supported())
+		);
 
-		session.state.drag_rect = Some(MonitorRectPoints {
-			monitor_id: monitor.id,
-			rect: RectPoints::new(100, 120, 240, 320),
-		});
+		#[cfg(not(target_os = "macos"))]
+		{
+			let session = OverlaySession::new();
+			let err = session.share_exported_png(&[], None).expect_err("no share menu");
 
-		assert!(session.live_overlay_selection_flow_repaint_active());
+			assert!(format!("{err:#}").contains("only available on macOS"));
+		}
 	}
 
 	#[test]
-	fn live_overlay_redraw_needed_for_cursor_update_only_for_monitor_or_drag_changes() {
-		let monitor_a = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let monitor_b = MonitorRect {
-			id: 2,
-			origin: GlobalPoint::new(1_000, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let drag = Some(MonitorRectPoints {
-			monitor_id: monitor_a.id,
-			rect: RectPoints::new(100, 120, 240, 320),
-		});
+	fn frozen_dim_uniform_scales_selection_and_skips_when_disabled() {
+		let selection = RectPoints::new(10, 20, 30, 40);
+		let size = PhysicalSize::new(800, 600);
 
-		assert!(!OverlaySession::live_overlay_redraw_needed_for_cursor_update(
-			Some(monitor_a),
-			monitor_a,
-			None,
-			None,
-		));
-		assert!(OverlaySession::live_overlay_redraw_needed_for_cursor_update(
-			Some(monitor_a),
-			monitor_a,
-			None,
-			drag,
-		));
-		assert!(OverlaySession::live_overlay_redraw_needed_for_cursor_update(
-			Some(monitor_a),
-			monitor_b,
-			None,
-			None,
-		));
+		assert_eq!(FrozenDimUniformRaw::new(selection, 2.0, size, 0.0, 0.0, 0.0), None);
+		assert_eq!(
+			FrozenDimUniformRaw::new(selection, 2.0, size, 0.0, 0.5, 3.0),
+			Some(FrozenDimUniformRaw {
+				selection_min_size: [20.0, 40.0, 60.0, 80.0],
+				surface_vignette_noise: [800.0, 600.0, 0.5, 1.0],
+				dim: [0.0; 4],
+			})
+		);
+		assert_eq!(
+			FrozenDimUniformRaw::new(selection, 2.0, size, 1.4, 0.0, 0.0).map(|raw| raw.dim),
+			Some([1.0, 0.0, 0.0, 0.0])
+		);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn apply_live_cursor_sample_clears_existing_loupe_when_alt_is_released() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let cursor = GlobalPoint::new(120, 180);
-		let mut session = OverlaySession::new();
+	fn shader_sources_fall_back_to_built_in_files() {
+		let sources = ShaderSources::from_dir(Path::new("/nonexistent/rsnap-shaders"), 3);
 
-		session.cursor_monitor = Some(monitor);
-		session.state.cursor = Some(cursor);
-		session.state.alt_held = true;
+		assert_eq!(sources, ShaderSources { generation: 3, ..ShaderSources::default() });
+		assert!(sources.frozen_dim.contains("fn fs_main"));
+	}
 
-		let _ = session.apply_live_cursor_sample_detail(
-			monitor,
-			cursor,
-			LiveCursorSample {
-				rgb: Some(Rgb::new(10, 20, 30)),
-				patch: Some(image::RgbaImage::from_pixel(3, 3, Rgba([10, 20, 30, 255]))),
-			},
+	/// Mirrors `bayer8` and `ordered_dither` in `hud_blur.wgsl` for an encoded channel given in
+	/// 1/64ths of an 8-bit step, assuming the GPU rounds to the nearest level on store.
+	fn reference_hud_dither(value_64ths: u32, x: u32, y: u32) -> u32 {
+		let (x, y) = (x & 7, y & 7);
+		let z = x ^ y;
+		let rank = ((z & 1) << 5)
+			| ((y & 1) << 4)
+			| ((z & 2) << 2)
+			| ((y & 2) << 1)
+			| ((z & 4) >> 1)
+			| ((y & 4) >> 2);
+
+		(value_64ths + rank) / 64
+	}
+
+	#[test]
+	fn hud_blur_dither_matches_golden_gradient_and_removes_bands() {
+		// A blurred backdrop ramping from level 100 to 102 across 32 px, a typical banding case.
+		let gradient = |x: u32| 100 * 64 + x * 4;
+		let dithered_row =
+			|y: u32| (0..32).map(|x| reference_hud_dither(gradient(x), x, y)).collect::<Vec<_>>();
+		let banded = (0..32).map(|x| (gradient(x) + 32) / 64).collect::<Vec<_>>();
+
+		assert_eq!(
+			dithered_row(0),
+			[
+				100, 100, 100, 100, 100, 100, 100, 101, 100, 101, 100, 101, 100, 101, 101, 101,
+				101, 101, 101, 101, 101, 101, 101, 102, 101, 102, 101, 102, 101, 102, 102, 102,
+			]
 		);
+		assert_eq!(
+			dithered_row(1),
+			[
+				100, 100, 101, 100, 101, 100, 101, 100, 101, 100, 101, 101, 101, 101, 101, 101,
+				101, 101, 102, 101, 102, 101, 102, 101, 102, 101, 102, 102, 102, 102, 102, 102,
+			]
+		);
+		// Without dithering the ramp collapses into three flat bands.
+		assert_eq!(banded.iter().filter(|level| **level == 101).count(), 16);
 
-		session.state.alt_held = false;
+		// Every 8×8 tile averages back to the exact gradient value, so the eye sees the ramp.
+		for value_64ths in [100 * 64, 100 * 64 + 1, 100 * 64 + 37, 101 * 64 + 63] {
+			let sum = (0..8)
+				.flat_map(|y| (0..8).map(move |x| reference_hud_dither(value_64ths, x, y)))
+				.sum::<u32>();
+
+			assert_eq!(sum, value_64ths);
+		}
 
 		assert!(
-			session
-				.apply_live_cursor_sample_detail(
-					monitor,
-					cursor,
-					LiveCursorSample { rgb: None, patch: None },
-				)
-				.any_changed()
+			include_str!("hud_blur.wgsl").contains("blurred = ordered_dither(blurred, pos.xy);")
 		);
-		assert!(session.state.loupe.is_none());
+		assert_eq!(HudBlurUniformRaw::effects(0.5, 2.0, 6.0, true), [0.5, 1.0, 6.0, 1.0]);
+		assert_eq!(HudBlurUniformRaw::effects(0.5, 0.0, 6.0, false)[3], 0.0);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn stabilized_live_hud_inner_size_keeps_live_width_from_shrinking() {
-		let mut session = OverlaySession::new();
+	fn open_with_command_line_fills_or_appends_the_path() {
+		let path = Path::new("/tmp/rsnap/it's.png");
+		#[cfg(not(target_os = "windows"))]
+		let quoted = r"'/tmp/rsnap/it'\''s.png'";
+		#[cfg(target_os = "windows")]
+		let quoted = "\"/tmp/rsnap/it's.png\"";
 
-		session.state.mode = OverlayMode::Live;
-		session.hud_inner_size_points = Some((826, 44));
+		assert_eq!(
+			open_with::editor_command_line("open -a Preview ", path),
+			format!("open -a Preview {quoted}")
+		);
+		assert_eq!(
+			open_with::editor_command_line("gimp --new-instance {path} &", path),
+			format!("gimp --new-instance {quoted} &")
+		);
+	}
 
+	#[test]
+	fn keypad_quick_action_slots_map_numpad_digits() {
 		assert_eq!(
-			OverlaySession::stabilized_live_hud_inner_size(
-				OverlayMode::Live,
-				session.hud_inner_size_points,
-				(810, 44),
-			),
-			(826, 44)
+			OverlaySession::keypad_quick_action_slot(PhysicalKey::Code(KeyCode::Numpad1)),
+			Some(0)
 		);
 		assert_eq!(
-			OverlaySession::stabilized_live_hud_inner_size(
-				OverlayMode::Live,
-				session.hud_inner_size_points,
-				(780, 44),
-			),
-			(826, 44)
+			OverlaySession::keypad_quick_action_slot(PhysicalKey::Code(KeyCode::Numpad9)),
+			Some(8)
 		);
+		assert_eq!(
+			OverlaySession::keypad_quick_action_slot(PhysicalKey::Code(KeyCode::Numpad0)),
+			None
+		);
+		assert_eq!(
+			OverlaySession::keypad_quick_action_slot(PhysicalKey::Code(KeyCode::Digit1)),
+			None
+		);
+	}
 
-		session.state.mode = OverlayMode::Frozen;
+	#[test]
+	fn frozen_toolbar_hover_text_lists_bound_keypad_keys() {
+		let mut actions = DEFAULT_KEYPAD_QUICK_ACTIONS;
+
+		actions[4] = KeypadQuickAction::Copy;
 
 		assert_eq!(
-			OverlaySession::stabilized_live_hud_inner_size(
-				OverlayMode::Frozen,
-				session.hud_inner_size_points,
-				(810, 44),
+			WindowRenderer::frozen_toolbar_tool_hover_text(FrozenToolbarTool::Copy, &actions),
+			"Copy (Num 1, Num 5)"
+		);
+		assert_eq!(
+			WindowRenderer::frozen_toolbar_tool_hover_text(FrozenToolbarTool::Save, &actions),
+			"Save (Num 2)"
+		);
+		assert_eq!(
+			WindowRenderer::frozen_toolbar_tool_hover_text(
+				FrozenToolbarTool::Save,
+				&[KeypadQuickAction::None; 9]
 			),
-			(810, 44)
+			"Save"
+		);
+		assert_eq!(
+			WindowRenderer::frozen_toolbar_tool_hover_text(FrozenToolbarTool::Pen, &actions),
+			"Pen"
 		);
 	}
 
 	#[test]
-	fn live_hud_position_text_uses_stable_monitor_width() {
-		let monitor = MonitorRect {
-			id: 5,
-			origin: GlobalPoint::new(0, 0),
-			width: 3_008,
-			height: 1_692,
-			scale_factor_x1000: 2_000,
-		};
-		let short = hud_helpers::format_live_hud_position_text(monitor, GlobalPoint::new(842, 846));
-		let long =
-			hud_helpers::format_live_hud_position_text(monitor, GlobalPoint::new(1_504, 1_320));
+	fn tinted_hud_body_fill_amount_zero_keeps_base_fill() {
+		for theme in [HudTheme::Dark, HudTheme::Light] {
+			let base_fill = hud_helpers::hud_body_fill_srgba8(theme, false);
+			let no_tint =
+				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 0.0, 0.585);
 
-		assert_eq!(short.len(), long.len());
-		assert_eq!(short, "x= 842, y= 846");
-		assert_eq!(long, "x=1504, y=1320");
+			assert_eq!(no_tint.r(), base_fill[0]);
+			assert_eq!(no_tint.g(), base_fill[1]);
+			assert_eq!(no_tint.b(), base_fill[2]);
+			assert_eq!(no_tint.a(), 255);
+		}
 	}
 
 	#[test]
-	fn live_hud_rgb_text_uses_fixed_width_placeholders() {
-		let (missing_hex, missing_rgb) = hud_helpers::format_live_hud_rgb_text(None);
-		let (hex, rgb) = hud_helpers::format_live_hud_rgb_text(Some(Rgb::new(7, 128, 255)));
+	fn tinted_hud_body_fill_100pct_tint_is_visibly_blue() {
+		let dark_min_delta: u16 = 57;
+		let light_min_delta: u16 = 24;
+		let sky_tint = 0.585;
 
-		assert_eq!(missing_hex.len(), hex.len());
-		assert_eq!(missing_rgb.len(), rgb.len());
-		assert_eq!(missing_hex, "#??????");
-		assert_eq!(missing_rgb, "RGB(???, ???, ???)");
-		assert_eq!(rgb, "RGB(  7, 128, 255)");
+		for theme in [HudTheme::Dark, HudTheme::Light] {
+			let base_fill =
+				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 0.0, sky_tint);
+			let tinted_fill =
+				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 1.0, sky_tint);
+			let rgb_delta = u16::from(base_fill.r()).abs_diff(u16::from(tinted_fill.r()))
+				+ u16::from(base_fill.g()).abs_diff(u16::from(tinted_fill.g()))
+				+ u16::from(base_fill.b()).abs_diff(u16::from(tinted_fill.b()));
+			let min_delta =
+				if matches!(theme, HudTheme::Dark) { dark_min_delta } else { light_min_delta };
+
+			assert!(
+				rgb_delta >= min_delta,
+				"expected minimum tint delta >= {min_delta}, got {rgb_delta}"
+			);
+		}
 	}
 
 	#[test]
-	fn stable_live_loupe_side_prefers_configured_patch_side() {
-		let mut state = crate::state::OverlayState::new();
+	fn tinted_hud_body_fill_preserves_alpha() {
+		for theme in [HudTheme::Dark, HudTheme::Light] {
+			let tint_hue = 0.585;
+			let opaque =
+				WindowRenderer::tinted_hud_body_fill(theme, false, true, 0.25, 1.0, tint_hue);
+			let translucent =
+				WindowRenderer::tinted_hud_body_fill(theme, false, false, 0.33, 1.0, tint_hue);
 
-		state.loupe_patch_side_px = 21;
-		state.loupe = Some(LoupeSample {
-			center: GlobalPoint::new(100, 120),
-			patch: RgbaImage::from_pixel(17, 19, image::Rgba([0, 0, 0, 255])),
-		});
+			assert_eq!(opaque.a(), 255);
+			assert_eq!(translucent.a(), (0.33_f32 * 255.0).round().clamp(0.0, 255.0) as u8);
+		}
+	}
 
-		assert_eq!(hud_helpers::stable_live_loupe_side_px(&state), 21);
+	#[test]
+	fn tinted_hud_body_fill_blur_active_enforces_min_opacity() {
+		for theme in [HudTheme::Dark, HudTheme::Light] {
+			let tint_hue = 0.585;
+			let fill = WindowRenderer::tinted_hud_body_fill(theme, true, false, 0.0, 0.0, tint_hue);
+			let expected =
+				(hud_helpers::hud_blur_tint_alpha(theme) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+			assert_eq!(fill.a(), expected);
+		}
 	}
 
 	#[test]
-	fn stable_live_loupe_side_ignores_larger_runtime_patch() {
-		let mut state = crate::state::OverlayState::new();
+	fn frozen_toolbar_clamps_floating_position() {
+		let monitor = Rect::from_min_size(Pos2::new(-200.0, -100.0), Vec2::new(500.0, 400.0));
+		let toolbar_size = Vec2::new(220.0, 42.0);
+		let clamped = WindowRenderer::clamp_toolbar_position(
+			monitor,
+			toolbar_size,
+			Pos2::new(-400.0, -240.0),
+			TOOLBAR_SCREEN_MARGIN_PX,
+			TOOLBAR_SCREEN_MARGIN_PX,
+		);
 
-		state.loupe_patch_side_px = 21;
-		state.loupe = Some(LoupeSample {
-			center: GlobalPoint::new(100, 120),
-			patch: RgbaImage::from_pixel(25, 25, image::Rgba([0, 0, 0, 255])),
-		});
+		assert_eq!(clamped.x, monitor.min.x + TOOLBAR_SCREEN_MARGIN_PX);
+		assert_eq!(clamped.y, monitor.min.y + TOOLBAR_SCREEN_MARGIN_PX);
+	}
 
-		assert_eq!(hud_helpers::stable_live_loupe_side_px(&state), 21);
+	#[test]
+	fn interactive_repaint_fps_uses_known_lower_monitor_refresh() {
+		assert_eq!(OverlaySession::interactive_repaint_fps(Some(60.0), Some(144.0)), 60.0);
+		assert_eq!(OverlaySession::interactive_repaint_fps(Some(75.0), Some(120.0)), 75.0);
+	}
+
+	#[test]
+	fn interactive_repaint_fps_caps_known_higher_refresh_to_contract_limit() {
+		assert_eq!(OverlaySession::interactive_repaint_fps(Some(144.0), Some(60.0)), 120.0);
+		assert_eq!(OverlaySession::interactive_repaint_fps(Some(240.0), None), 120.0);
 	}
 
 	#[test]
-	fn stable_live_loupe_window_inner_size_matches_runtime_target() {
-		assert_eq!(hud_helpers::stable_live_loupe_window_inner_size_points(21), (232, 232));
-		assert_eq!(hud_helpers::stable_live_loupe_window_inner_size_points(1), (32, 32));
+	fn interactive_repaint_fps_falls_back_to_known_or_default_cap() {
+		assert_eq!(OverlaySession::interactive_repaint_fps(None, Some(90.0)), 90.0);
+		assert_eq!(OverlaySession::interactive_repaint_fps(None, Some(144.0)), 120.0);
+		assert_eq!(OverlaySession::interactive_repaint_fps(None, None), 120.0);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn drain_external_scroll_input_events_through_advances_last_seen_seq() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let start = Instant::now();
-		let events = Arc::new([
-			(1, start, 150.0, 160.0, -4.0, true, false),
-			(2, start + Duration::from_millis(2), 150.0, 160.0, 4.0, false, true),
-		]);
-		let mut session = OverlaySession::new();
-
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
-		session.set_external_scroll_input_drain_reader(Arc::new({
-			let events = Arc::clone(&events);
+	fn advanced_config_caps_sampling_and_sets_cache_ttls() {
+		let mut session = OverlaySession::with_config(OverlayConfig {
+			sampling_fps_cap: 30,
+			window_list_ttl_ms: 400,
+			live_background_ttl_ms: 2_000,
+			..OverlayConfig::default()
+		});
 
-			move |after_seq, through| {
-				events
-					.iter()
-					.copied()
-					.filter(|event| event.0 > after_seq && event.1 <= through)
-					.collect()
-			}
-		}));
+		assert_eq!(session.repaint_interval_for_monitor(None), Duration::from_secs_f32(1.0 / 30.0));
+		assert_eq!(session.window_list_refresh_interval, Duration::from_millis(400));
+		assert_eq!(session.live_bg_request_interval, Duration::from_secs(2));
 
-		session.drain_external_scroll_input_events_through(start);
+		session.set_config(OverlayConfig::default());
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert!(session.scroll_capture.input_gesture_active);
-		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 1);
+		assert_eq!(
+			session.repaint_interval_for_monitor(None),
+			Duration::from_secs_f32(1.0 / 120.0)
+		);
+		assert_eq!(session.window_list_refresh_interval, Duration::from_millis(120));
+		assert_eq!(session.live_bg_request_interval, Duration::from_millis(500));
+	}
 
-		session.drain_external_scroll_input_events_through(start);
+	#[test]
+	fn entry_animation_eases_to_one_and_defaults_to_finished() {
+		let start = Instant::now();
+		let mut entry = EntryAnimation::default();
 
-		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 1);
+		assert_eq!(entry.progress(start), 1.0);
 
-		session.drain_external_scroll_input_events_through(start + Duration::from_millis(2));
+		entry.start(start, Duration::from_millis(100));
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Up));
-		assert!(!session.scroll_capture.input_gesture_active);
-		assert_eq!(session.scroll_capture.last_external_scroll_input_seq, 2);
+		assert_eq!(entry.progress(start), 0.0);
+		assert!(entry.progress(start + Duration::from_millis(50)) > 0.5);
+		assert!(entry.is_running(start + Duration::from_millis(50)));
+		assert_eq!(entry.progress(start + Duration::from_millis(150)), 1.0);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn drain_external_scroll_input_events_through_uses_pairing_time_for_freshness() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let through = Instant::now();
-		let recorded_at = through - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50);
-		let events = Arc::new([(1, recorded_at, 150.0, 160.0, -4.0, false, false)]);
-		let mut session = OverlaySession::new();
+	fn drag_spring_settles_on_target_without_overshoot() {
+		let start = Instant::now();
+		let mut spring = DragSpring::new(Pos2::ZERO, start);
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
-		session.set_external_scroll_input_drain_reader(Arc::new({
-			let events = Arc::clone(&events);
+		spring.set_target(Pos2::new(100.0, 0.0));
 
-			move |after_seq, paired_through| {
-				events
-					.iter()
-					.copied()
-					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
-					.collect()
-			}
-		}));
+		let mut previous_x = 0.0;
 
-		session.drain_external_scroll_input_events_through(through);
+		for frame in 1..=60 {
+			let position = spring.step(start + Duration::from_millis(frame * 8));
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert_eq!(session.scroll_capture.input_direction_at, Some(through));
-		assert_eq!(session.scroll_capture_observation_block_reason(), None);
+			assert!(position.x >= previous_x && position.x <= 100.0);
+
+			previous_x = position.x;
+		}
+
+		assert!(spring.is_settled());
+		assert_eq!(spring.step(start + Duration::from_millis(500)), Pos2::new(100.0, 0.0));
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn replayed_stream_input_uses_frame_time_for_stale_gate_without_global_relaxation() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let through = Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50);
-		let recorded_at = through - Duration::from_millis(12);
-		let events = Arc::new([(1, recorded_at, 150.0, 160.0, -4.0, false, false)]);
-		let mut session = OverlaySession::new();
-
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.set_external_scroll_input_drain_reader(Arc::new({
-			let events = Arc::clone(&events);
+	fn perf_hud_counters_refresh_at_most_every_interval() {
+		let start = Instant::now();
+		let mut counters = PerfHudCounters::default();
 
-			move |after_seq, paired_through| {
-				events
-					.iter()
-					.copied()
-					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
-					.collect()
-			}
-		}));
+		counters.record_frame(Duration::from_millis(4));
 
-		session.drain_external_scroll_input_events_through(through);
+		let first = counters.refresh(start, 2, 1).unwrap();
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert_eq!(session.scroll_capture.input_direction_at, Some(through));
-		assert_eq!(session.scroll_capture_observation_block_reason(), Some("stale_input"));
-		assert_eq!(session.scroll_capture_observation_block_reason_at(through), None);
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame_at(
-					make_scroll_capture_window(&document, 3, 1, 5),
-					through,
-				)
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
-	}
+		assert_eq!(first.frame_time_ms, 4.0);
+		assert_eq!(first.queue_depth, 2);
+		assert_eq!(first.dropped_requests, 1);
+		assert_eq!(first.sample_latency_ms, None);
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn replayed_downward_input_allows_bounded_stale_live_stream_frame() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let through = Instant::now();
-		let events =
-			Arc::new([(7, through - Duration::from_millis(10), 150.0, 160.0, -4.0, false, false)]);
-		let stale_at = through + SCROLL_CAPTURE_INPUT_FRESHNESS + Duration::from_millis(1);
-		let mut session = OverlaySession::new();
+		for _ in 0..5 {
+			counters.record_frame(Duration::from_millis(20));
+		}
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.set_external_scroll_input_drain_reader(Arc::new({
-			let events = Arc::clone(&events);
+		counters.record_sample_latency(Duration::from_millis(3));
 
-			move |after_seq, paired_through| {
-				events
-					.iter()
-					.copied()
-					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
-					.collect()
-			}
-		}));
+		assert!(counters.refresh(start + Duration::from_millis(100), 0, 1).is_none());
 
-		session.drain_external_scroll_input_events_through(through);
+		let second = counters.refresh(start + Duration::from_millis(500), 0, 1).unwrap();
 
-		assert_eq!(
-			session.scroll_capture.live_stream_stale_grace,
-			Some(LiveStreamStaleGrace {
-				external_input_seq: 7,
-				input_direction: ScrollDirection::Down,
-				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES,
-			})
-		);
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame_at(
-					make_scroll_capture_window(&document, 3, 1, 5),
-					stale_at,
-				)
-				.transpose()
-				.unwrap(),
-			None
-		);
+		assert_eq!(second.frame_time_max_ms, 20.0);
+		assert!(second.frame_time_ms > 4.0 && second.frame_time_ms < 20.0);
+		assert_eq!(second.redraws_per_second, 10.0);
+		assert_eq!(second.sample_latency_ms, Some(3.0));
+	}
 
-		session.handle_scroll_capture_frame(
-			make_scroll_capture_window(&document, 3, 1, 5),
-			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
-			false,
-			stale_at,
-		);
+	fn headless_monitor() -> MonitorRect {
+		MonitorRect {
+			id: 3,
+			origin: GlobalPoint::new(0, 0),
+			width: 200,
+			height: 100,
+			scale_factor_x1000: 1_000,
+		}
+	}
 
-		assert_eq!(scroll_capture_export_height(&session), 6);
-		assert_eq!(
-			session.scroll_capture.live_stream_stale_grace,
-			Some(LiveStreamStaleGrace {
-				external_input_seq: 7,
-				input_direction: ScrollDirection::Down,
-				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES - 1,
-			})
-		);
+	/// Keeps the non-macOS HUD backdrop capture from coalescing away the requests under test.
+	fn headless_config() -> OverlayConfig {
+		OverlayConfig { show_hud_blur: false, ..OverlayConfig::default() }
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn stale_live_stream_grace_survives_same_direction_overlay_wheel_update() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let through = Instant::now();
-		let wheel_at = through + Duration::from_millis(10);
-		let events =
-			Arc::new([(7, through - Duration::from_millis(10), 150.0, 160.0, -4.0, false, false)]);
-		let stale_at = wheel_at + SCROLL_CAPTURE_INPUT_FRESHNESS + Duration::from_millis(1);
-		let mut session = OverlaySession::new();
+	fn headless_drag_freezes_the_selection_and_saves_it() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-export-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.set_external_scroll_input_drain_reader(Arc::new({
-			let events = Arc::clone(&events);
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-			move |after_seq, paired_through| {
-				events
-					.iter()
-					.copied()
-					.filter(|event| event.0 > after_seq && event.1 <= paired_through)
-					.collect()
-			}
-		}));
+		assert!(matches!(overlay.session.state.mode, OverlayMode::Frozen));
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::DragRegion);
 
-		session.drain_external_scroll_input_events_through(through);
-		session.record_scroll_capture_input_direction_from_overlay_wheel_at(
-			&MouseScrollDelta::LineDelta(0.0, -1.0),
-			wheel_at,
-		);
+		let rect = overlay.session.state.frozen_capture_rect.expect("drag selection");
 
-		assert_eq!(session.scroll_capture.input_direction_at, Some(wheel_at));
-		assert_eq!(
-			session.scroll_capture.live_stream_stale_grace,
-			Some(LiveStreamStaleGrace {
-				external_input_seq: 7,
-				input_direction: ScrollDirection::Down,
-				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES,
-			})
-		);
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame_at(
-					make_scroll_capture_window(&document, 3, 1, 5),
-					stale_at,
-				)
-				.transpose()
-				.unwrap(),
-			None
-		);
+		// The frozen HUD and loupe read the frozen frame under the release point.
+		assert_eq!(overlay.session.state.rgb, Some(Rgb::new(70, 60, 70 ^ 60)));
+		assert!(overlay.session.state.loupe.is_some());
 
-		session.handle_scroll_capture_frame(
-			make_scroll_capture_window(&document, 3, 1, 5),
-			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
-			false,
-			stale_at,
-		);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
 
-		assert_eq!(scroll_capture_export_height(&session), 6);
+		assert_eq!((saved.width(), saved.height()), (rect.width, rect.height));
 		assert_eq!(
-			session.scroll_capture.live_stream_stale_grace,
-			Some(LiveStreamStaleGrace {
-				external_input_seq: 7,
-				input_direction: ScrollDirection::Down,
-				remaining_stale_frames: SCROLL_CAPTURE_LIVE_STREAM_STALE_GRACE_FRAMES - 1,
-			})
+			saved.get_pixel(0, 0).0,
+			[rect.x as u8, rect.y as u8, (rect.x ^ rect.y) as u8, 255]
 		);
+
+		// Saving to disk leaves the clipboard stage unreached.
+		let latency = overlay.session.capture_latency();
+		let freeze = latency.elapsed(LatencyStage::FreezeComplete).expect("freeze latency");
+		let encode = latency.elapsed(LatencyStage::EncodeComplete).expect("encode latency");
+
+		assert!(freeze <= encode);
+		assert_eq!(latency.elapsed(LatencyStage::ClipboardDone), None);
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn live_stream_stale_grace_is_consumed_and_superseded() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let stale_at = Instant::now() - Duration::from_millis(1);
-		let mut session = OverlaySession::new();
+	fn headless_capture_delay_counts_down_before_freezing_the_selection() {
+		let config = OverlayConfig { capture_delay_secs: 2, ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.scroll_capture.last_external_scroll_input_seq = 7;
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at =
-			Some(stale_at - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(1));
-		session.scroll_capture.input_gesture_active = false;
-		session.scroll_capture.live_stream_stale_grace = Some(LiveStreamStaleGrace {
-			external_input_seq: 7,
-			input_direction: ScrollDirection::Down,
-			remaining_stale_frames: 1,
-		});
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
 
-		session.handle_scroll_capture_frame(
-			make_scroll_capture_window(&document, 3, 1, 5),
-			ScrollCaptureFrameSource::LiveStream { frame_seq: 143 },
-			false,
-			stale_at,
-		);
+		let (region, remaining_secs) =
+			overlay.session.state.capture_countdown.expect("countdown after the drag");
 
-		assert_eq!(scroll_capture_export_height(&session), 6);
-		assert_eq!(session.scroll_capture.live_stream_stale_grace, None);
+		assert!(matches!(overlay.session.state.mode, OverlayMode::Live));
+		assert_eq!(remaining_secs, 2);
 
-		let height_after_first_stale = scroll_capture_export_height(&session);
+		overlay.session.maybe_tick_capture_countdown(Instant::now() + Duration::from_secs(3));
 
-		session.handle_scroll_capture_frame(
-			make_scroll_capture_window(&document, 3, 2, 5),
-			ScrollCaptureFrameSource::LiveStream { frame_seq: 144 },
-			false,
-			stale_at,
-		);
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		assert_eq!(scroll_capture_export_height(&session), height_after_first_stale);
+		assert!(overlay.session.state.capture_countdown.is_none());
+		assert_eq!(overlay.session.state.frozen_capture_rect, Some(region.rect));
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::DragRegion);
+	}
 
-		session.scroll_capture.last_external_scroll_input_seq = 8;
-		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
-		session.scroll_capture.input_direction_at =
-			Some(stale_at - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(1));
-		session.scroll_capture.input_gesture_active = false;
-		session.scroll_capture.live_stream_stale_grace = Some(LiveStreamStaleGrace {
-			external_input_seq: 7,
-			input_direction: ScrollDirection::Down,
-			remaining_stale_frames: 1,
-		});
+	#[test]
+	fn headless_capture_flash_holds_the_export_until_the_flash_fades() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-flash-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			capture_flash: true,
+			..headless_config()
+		};
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.handle_scroll_capture_frame(
-			make_scroll_capture_window(&document, 3, 1, 5),
-			ScrollCaptureFrameSource::LiveStream { frame_seq: 145 },
-			false,
-			stale_at,
-		);
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|session| session.capture_flash.is_some());
+		let (region, alpha) = overlay.session.state.capture_flash.expect("flash after the export");
 
-		assert_eq!(scroll_capture_export_height(&session), height_after_first_stale);
-		assert_eq!(session.scroll_capture.live_stream_stale_grace, None);
+		assert!(matches!(control, OverlayControl::Continue));
+		assert_eq!(Some(region.rect), overlay.session.state.frozen_capture_rect);
+		assert!(alpha > 0);
+
+		let control =
+			overlay.session.maybe_tick_capture_flash(Instant::now() + Duration::from_secs(1));
+
+		assert!(matches!(control, Some(OverlayControl::Exit(OverlayExit::Saved(_)))));
+		assert!(overlay.session.state.capture_flash.is_none());
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn wrapped_pixel_delta_normalizes_back_to_signed_values() {
-		assert_eq!(OverlaySession::normalize_macos_scroll_pixel_component(4_294_967_294.0), -2.0);
-		assert_eq!(OverlaySession::normalize_macos_scroll_pixel_component(4_294_967_290.0), -6.0);
+	fn headless_display_capture_mode_freezes_the_whole_display_on_drag() {
+		let config = OverlayConfig { capture_mode: CaptureMode::Display, ..headless_config() };
+		let monitor = headless_monitor();
+		let mut overlay = HeadlessOverlay::new(config, monitor, ScriptedCaptureBackend::new());
+
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::FullscreenFallback);
+		assert_eq!(
+			overlay.session.state.frozen_capture_rect,
+			Some(RectPoints::new(0, 0, monitor.width, monitor.height))
+		);
 	}
 
 	#[test]
-	fn negative_vertical_wheel_delta_maps_to_downward_scroll_capture() {
+	fn headless_saved_projects_reopen_with_their_undo_history() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-project-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config.clone(), headless_monitor(), ScriptedCaptureBackend::new());
+
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		let rect = overlay.session.state.frozen_capture_rect.expect("drag selection");
+
+		overlay.session.state.annotations.place_stamp(StampKind::Check, (20, 10));
+		overlay.session.state.annotations.place_stamp(StampKind::Star, (40, 20));
+
+		let _ = overlay.session.undo_annotation();
+		let control = overlay.session.save_frozen_project();
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved project, got {control:?}");
+		};
+
+		assert_eq!(path.extension().and_then(|extension| extension.to_str()), Some("rsnap"));
+
+		let project =
+			project::decode_project(&std::fs::read(&path).expect("project")).expect("decode");
+		let mut reopened =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
+
+		reopened.session.restore_project(headless_monitor(), project).expect("restore");
+
+		let session = &mut reopened.session;
+
+		assert!(matches!(session.state.mode, OverlayMode::Frozen));
+		assert_eq!(session.state.frozen_capture_rect, Some(rect));
+		assert_eq!(session.pending_freeze_capture, None);
 		assert_eq!(
-			OverlaySession::scroll_capture_direction_from_wheel_delta(
-				&MouseScrollDelta::LineDelta(0.0, -1.0)
-			),
-			Some(ScrollDirection::Down)
+			session.cropped_frozen_capture_image().map(|image| image.get_pixel(0, 0).0),
+			Some([rect.x as u8, rect.y as u8, (rect.x ^ rect.y) as u8, 255])
 		);
+		assert_eq!(session.state.annotations.stamps.len(), 1);
+
+		let _ = session.redo_annotation();
+
+		assert_eq!(session.state.annotations.stamps[1].kind, StampKind::Star);
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
 	#[test]
-	fn positive_vertical_wheel_delta_maps_to_upward_scroll_capture() {
+	fn headless_click_freezes_the_window_under_the_pointer() {
+		let window = WindowRect { window_id: Some(9), x: 40, y: 30, width: 80, height: 50 };
+		let backend = ScriptedCaptureBackend::new()
+			.with_window(window, RgbaImage::from_pixel(80, 50, Rgba([200, 0, 0, 255])));
+		let mut overlay = HeadlessOverlay::new(headless_config(), headless_monitor(), backend);
+
+		let _ = overlay.move_cursor(GlobalPoint::new(60, 40));
+		let _ = overlay.press();
+		let _ = overlay.release();
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::Window);
 		assert_eq!(
-			OverlaySession::scroll_capture_direction_from_wheel_delta(
-				&MouseScrollDelta::LineDelta(0.0, 1.0)
-			),
-			Some(ScrollDirection::Up)
+			overlay.session.state.frozen_capture_rect,
+			Some(RectPoints::new(40, 30, 80, 50))
 		);
 	}
 
 	#[test]
-	fn external_scroll_input_inside_capture_rect_updates_direction() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+	fn headless_window_freeze_shows_the_window_render_over_whatever_covers_it() {
+		// The screen frame is the synthetic pattern, standing in for another window on top.
+		let window = WindowRect { window_id: Some(9), x: 40, y: 30, width: 80, height: 50 };
+		let backend = ScriptedCaptureBackend::new()
+			.with_window(window, RgbaImage::from_pixel(80, 50, Rgba([200, 0, 0, 255])));
+		let mut overlay = HeadlessOverlay::new(headless_config(), headless_monitor(), backend);
+
+		let _ = overlay.move_cursor(GlobalPoint::new(60, 40));
+		let _ = overlay.press();
+		let _ = overlay.release();
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		let frozen = overlay.session.state.frozen_image.as_ref().expect("frozen image");
+
+		assert_eq!(frozen.get_pixel(40, 30).0, [200, 0, 0, 255]);
+		assert_eq!(frozen.get_pixel(119, 79).0, [200, 0, 0, 255]);
+		assert_eq!(frozen.get_pixel(39, 30).0, [39, 30, 39 ^ 30, 255]);
+	}
+
+	#[test]
+	fn headless_hovering_a_window_names_its_app_and_title() {
+		let window = WindowRect { window_id: Some(9), x: 40, y: 30, width: 80, height: 50 };
+		let label = WindowLabel { app_name: String::from("Notes"), title: String::from("Todo") };
+		let backend = ScriptedCaptureBackend::new()
+			.with_window(window, RgbaImage::from_pixel(80, 50, Rgba([200, 0, 0, 255])))
+			.with_window_label(9, label.clone());
+		let mut overlay = HeadlessOverlay::new(headless_config(), headless_monitor(), backend);
+
+		let _ = overlay.move_cursor(GlobalPoint::new(60, 40));
+		let _ = overlay.pump_until(|session| session.state.hovered_window_label.is_some());
+
+		assert_eq!(overlay.session.state.hovered_window_label, Some(label));
+
+		let _ = overlay.move_cursor(GlobalPoint::new(10, 10));
+		let _ = overlay.pump_until(|session| session.state.hovered_window_label.is_none());
+
+		assert_eq!(overlay.session.state.hovered_window_label, None);
+	}
+
+	#[test]
+	fn headless_focused_window_capture_saves_without_overlay_windows() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-focused-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			done_actions: vec![DoneAction::Save],
+			..headless_config()
 		};
+		// The window hangs off the right edge, so only its visible part is exported.
+		let window = WindowRect { window_id: Some(9), x: 150, y: 30, width: 80, height: 50 };
+		let backend = ScriptedCaptureBackend::new()
+			.with_window(window, RgbaImage::from_pixel(80, 50, Rgba([200, 0, 0, 255])));
+		let mut overlay = HeadlessOverlay::new(config, headless_monitor(), backend);
+
+		overlay
+			.session
+			.begin_focused_window_capture(&[headless_monitor()], window)
+			.expect("focused window capture");
+
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
+
+		assert!(overlay.session.windows.is_empty());
+		assert_eq!(saved.dimensions(), (50, 50));
+		assert_eq!(saved.get_pixel(0, 0).0, [150, 30, 150 ^ 30, 255]);
+		assert!(!overlay.session.direct_capture);
+
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
+
+	#[test]
+	fn gpu_init_times_out_without_blocking_and_reports_its_failure_later() {
 		let mut session = OverlaySession::new();
+		let (sender, receiver) = mpsc::channel();
+		let started_at = Instant::now().checked_sub(GPU_INIT_TIMEOUT).unwrap_or_else(Instant::now);
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.pending_gpu = Some(PendingGpuContext { receiver, started_at });
 
-		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, true, false);
+		let timed_out = session.ensure_gpu().expect_err("init still running");
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert!(session.scroll_capture.input_direction_at.is_some());
-		assert!(session.scroll_capture.input_gesture_active);
+		assert!(timed_out.contains("did not finish"), "{timed_out}");
+		assert!(session.pending_gpu.is_some());
+
+		sender.send(Err(eyre::eyre!("no adapter"))).expect("send");
+
+		assert_eq!(session.ensure_gpu(), Err(String::from("no adapter")));
+		assert!(session.pending_gpu.is_none());
+		assert!(session.gpu.is_none());
 	}
 
 	#[test]
-	fn external_scroll_input_outside_capture_rect_is_ignored() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
+	fn gpu_poll_returns_while_initialization_is_still_running() {
 		let mut session = OverlaySession::new();
+		let (_sender, receiver) = mpsc::channel();
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		session.pending_gpu = Some(PendingGpuContext { receiver, started_at: Instant::now() });
 
-		session.handle_external_scroll_input_delta_y(50.0, 50.0, -4.0, true, false);
+		let polled_at = Instant::now();
 
-		assert_eq!(session.scroll_capture.input_direction, None);
-		assert!(session.scroll_capture.input_direction_at.is_none());
+		assert_eq!(session.poll_gpu(), Ok(false));
+		assert!(polled_at.elapsed() < Duration::from_secs(1));
+		assert_eq!(session.ensure_gpu(), Err(String::from("GPU initialization is still running")));
+		assert!(session.pending_gpu.is_some());
 	}
 
 	#[test]
-	fn external_scroll_input_terminal_event_preserves_last_direction_for_freshness() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+	fn resident_sessions_keep_gpu_initialization_across_exits() {
+		let pending_gpu = || {
+			let (_sender, receiver) = mpsc::channel();
+
+			Some(PendingGpuContext { receiver, started_at: Instant::now() })
 		};
 		let mut session = OverlaySession::new();
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		session.pending_gpu = pending_gpu();
 
-		session.handle_external_scroll_input_delta_y(150.0, 160.0, 0.0, false, true);
+		let _ = session.exit(OverlayExit::Cancelled);
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert!(session.scroll_capture.input_direction_at.is_some());
-		assert!(!session.scroll_capture.input_gesture_active);
-		assert!(session.scroll_capture_input_allows_growth());
+		assert!(session.pending_gpu.is_none());
+
+		session.set_config(OverlayConfig { resident: true, ..headless_config() });
+
+		session.pending_gpu = pending_gpu();
+
+		let _ = session.exit(OverlayExit::Cancelled);
+
+		assert!(session.pending_gpu.is_some());
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn scroll_overlay_mouse_passthrough_window_arms_and_expires() {
-		let now = Instant::now();
-		let mut session = OverlaySession::new();
+	fn image_caches_over_budget_downsample_the_live_background_only() {
+		let mut session = OverlaySession::with_config(OverlayConfig {
+			image_cache_budget_mb: 1,
+			..headless_config()
+		});
 
-		session.scroll_capture.active = true;
+		session.state.live_bg_image = Some(RgbaImage::new(4096, 2048));
+		session.state.frozen_image = Some(RgbaImage::new(600, 600));
 
-		session.arm_scroll_overlay_mouse_passthrough_window(now, "test");
+		let generation = session.state.live_bg_generation;
 
-		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
+		session.account_image_caches();
+
+		let live_bg = session.state.live_bg_image.as_ref().expect("live background");
+
+		assert_eq!((live_bg.width(), live_bg.height()), (512, 256));
+		assert_ne!(session.state.live_bg_generation, generation);
+		assert!(session.live_bg_downsampled);
+		assert_eq!(session.state.frozen_image.as_ref().map(|image| image.width()), Some(600));
+		assert!(session.image_cache_high_water_bytes() >= 4096 * 2048 * 4);
+	}
+
+	#[test]
+	fn headless_last_region_is_reported_and_captured_again_without_overlay_windows() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-repeat-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			done_actions: vec![DoneAction::Save],
+			..headless_config()
+		};
+		let mut overlay =
+			HeadlessOverlay::new(config.clone(), headless_monitor(), ScriptedCaptureBackend::new());
+
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Done);
+		let _ = overlay.pump_until(|_| false);
+		let region = overlay.session.last_capture_region().expect("last region");
+
+		assert_eq!(region.monitor_id, headless_monitor().id);
+
+		let mut repeat =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
+
+		repeat.session.begin_region_capture(&[headless_monitor()], region).expect("region capture");
+
+		let control = repeat.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
+
+		assert!(repeat.session.windows.is_empty());
+		assert_eq!(saved.dimensions(), (region.rect.width, region.rect.height));
+		assert_eq!(repeat.session.last_capture_region(), Some(region));
+
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
+
+	#[test]
+	fn last_region_toolbar_button_reselects_the_previous_capture_on_this_display() {
+		let earlier = MonitorRectPoints {
+			monitor_id: headless_monitor().id,
+			rect: RectPoints::new(150, 50, 100, 80),
+		};
+		let config = OverlayConfig { recent_capture_regions: vec![earlier], ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
+
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.toolbar_action(FrozenToolbarTool::LastRegion);
+
+		// The earlier region is trimmed to the 200x100 display.
 		assert_eq!(
-			session.scroll_capture.overlay_mouse_passthrough_until,
-			Some(now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE)
+			overlay.session.state.frozen_capture_rect,
+			Some(RectPoints::new(150, 50, 50, 50))
 		);
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::DragRegion);
+	}
 
-		session.sync_scroll_overlay_mouse_passthrough_window(
-			now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE / 2,
+	#[test]
+	fn headless_interval_capture_picker_hands_back_the_selection_on_done() {
+		let mut overlay = HeadlessOverlay::new(
+			headless_config(),
+			headless_monitor(),
+			ScriptedCaptureBackend::new(),
 		);
 
-		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
+		overlay.session.interval_capture_picker = true;
 
-		session.sync_scroll_overlay_mouse_passthrough_window(
-			now + SCROLL_CAPTURE_MOUSE_PASSTHROUGH_IDLE_GRACE + Duration::from_millis(1),
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let control = overlay.toolbar_action(FrozenToolbarTool::Done);
+
+		assert!(matches!(
+			control,
+			OverlayControl::Exit(OverlayExit::IntervalCapture { monitor, rect_px })
+				if monitor == headless_monitor() && rect_px == RectPoints::new(10, 20, 60, 40)
+		));
+		assert!(!overlay.session.interval_capture_picker);
+	}
+
+	#[test]
+	fn headless_theme_pair_hands_back_the_frozen_selection() {
+		let mut overlay = HeadlessOverlay::new(
+			headless_config(),
+			headless_monitor(),
+			ScriptedCaptureBackend::new(),
 		);
 
-		assert!(!session.scroll_capture.overlay_mouse_passthrough_active);
-		assert!(session.scroll_capture.overlay_mouse_passthrough_until.is_none());
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let control = overlay.session.capture_frozen_region_in_both_themes();
+
+		assert!(matches!(
+			control,
+			OverlayControl::Exit(OverlayExit::ThemePair { monitor, rect_px })
+				if monitor == headless_monitor() && rect_px == RectPoints::new(10, 20, 60, 40)
+		));
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn external_scroll_input_extends_passthrough_window_inside_capture_rect() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+	fn headless_shift_drags_add_regions_that_save_as_separate_files() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-regions-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			multi_region_collage: true,
+			..headless_config()
 		};
-		let earlier = Instant::now() - Duration::from_millis(20);
-		let mut session = OverlaySession::new();
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		session.arm_scroll_overlay_mouse_passthrough_window(earlier, "test");
+		// A plain drag in frozen mode does not add a region.
+		let _ = overlay.drag(GlobalPoint::new(100, 10), GlobalPoint::new(140, 40));
 
-		let first_deadline = session.scroll_capture.overlay_mouse_passthrough_until;
+		assert!(overlay.session.state.extra_capture_rects.is_empty());
 
-		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, true, false);
+		overlay.session.keyboard_modifiers = ModifiersState::SHIFT;
 
-		assert!(session.scroll_capture.overlay_mouse_passthrough_active);
-		assert!(session.scroll_capture.overlay_mouse_passthrough_until > first_deadline);
-	}
+		let _ = overlay.drag(GlobalPoint::new(100, 10), GlobalPoint::new(140, 40));
+		let _ = overlay.drag(GlobalPoint::new(150, 50), GlobalPoint::new(190, 90));
 
-	#[test]
-	fn terminal_downward_scroll_event_sets_direction_before_finishing() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+		// A Shift-click inside a region removes it again.
+		let _ = overlay.drag(GlobalPoint::new(160, 60), GlobalPoint::new(160, 60));
+
+		assert_eq!(overlay.session.state.extra_capture_rects.len(), 1);
+
+		let _ = overlay.drag(GlobalPoint::new(150, 50), GlobalPoint::new(190, 90));
+		let regions = overlay.session.state.extra_capture_rects.clone();
+
+		assert_eq!(regions, [RectPoints::new(100, 10, 40, 30), RectPoints::new(150, 50, 40, 40)]);
+
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
 		};
-		let mut session = OverlaySession::new();
+		let collage = image::open(&path).expect("collage PNG").to_rgba8();
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		// Two regions per row: 60 + gap + 40 wide over the 40 and 40 point tall rows.
+		assert_eq!(collage.dimensions(), (60 + 16 + 40, 40 + 16 + 40));
+		assert_eq!(collage.get_pixel(76, 0).0, [100, 10, 100 ^ 10, 255]);
 
-		session.handle_external_scroll_input_delta_y(150.0, 160.0, -4.0, false, true);
+		let saved = std::fs::read_dir(&output_dir).expect("output dir").count();
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert!(session.scroll_capture.input_direction_at.is_some());
-		assert!(!session.scroll_capture.input_gesture_active);
-		assert!(session.scroll_capture_input_allows_growth());
+		// The primary selection, both regions, and the collage.
+		assert_eq!(saved, 4);
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
+	#[cfg(not(target_os = "windows"))]
 	#[test]
-	fn terminal_upward_scroll_event_does_not_allow_growth() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+	fn headless_accepted_redaction_is_mosaicked_in_the_export() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-redact-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			redact_ocr_command: Some(String::from(
+				"printf '5\\t1\\t1\\t1\\t1\\t1\\t4\\t6\\t30\\t10\\t91\\tops@example.com\\n'",
+			)),
+			..headless_config()
 		};
-		let mut session = OverlaySession::new();
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.session.scan_for_redactions();
+		let _ = overlay.pump_until(|session| !session.state.redactions.is_empty());
 
-		session.handle_external_scroll_input_delta_y(150.0, 160.0, 4.0, false, true);
+		assert_eq!(overlay.session.state.redactions[0].rect_px, RectPoints::new(2, 4, 34, 14));
+		assert!(!overlay.session.state.redactions[0].accepted);
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Up));
-		assert!(session.scroll_capture.input_direction_at.is_some());
-		assert!(!session.scroll_capture.input_gesture_active);
-		assert!(!session.scroll_capture_input_allows_growth());
-	}
+		// Clicking the proposal accepts it.
+		let _ = overlay.move_cursor(GlobalPoint::new(20, 30));
+		let _ = overlay.press();
+		let _ = overlay.release();
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn overlay_wheel_fallback_records_direction_with_drain_reader_present() {
-		let observed_at = Instant::now();
-		let mut session = OverlaySession::new();
+		assert!(overlay.session.state.redactions[0].accepted);
 
-		session.scroll_capture.active = true;
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
 
-		session.set_external_scroll_input_drain_reader(Arc::new(|_, _| Vec::new()));
-		session.record_scroll_capture_input_direction_from_overlay_wheel_at(
-			&MouseScrollDelta::LineDelta(0.0, -1.0),
-			observed_at,
-		);
+		// One mosaic block covers (2, 4)..(12, 14); pixels outside the proposal are untouched.
+		assert_eq!(saved.get_pixel(2, 4), saved.get_pixel(11, 13));
+		assert_ne!(saved.get_pixel(2, 4).0, [12, 24, 12 ^ 24, 255]);
+		assert_eq!(saved.get_pixel(50, 30).0, [60, 50, 60 ^ 50, 255]);
 
-		assert_eq!(session.scroll_capture.input_direction, Some(ScrollDirection::Down));
-		assert_eq!(session.scroll_capture.input_direction_at, Some(observed_at));
-		assert!(!session.scroll_capture.input_gesture_active);
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
 	#[test]
-	fn missing_scroll_direction_does_not_allow_growth() {
-		let mut session = OverlaySession::new();
+	fn headless_stamps_undo_redo_and_land_in_the_export() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-stamp-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(150, 90));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		assert!(!session.scroll_capture_input_allows_growth());
-	}
+		overlay.session.toolbar_state.selected_tool = FrozenToolbarTool::Stamp;
 
-	#[test]
-	fn fresh_upward_direction_still_allows_observation() {
-		let mut session = OverlaySession::new();
+		// Outside the selection nothing is placed.
+		let _ = overlay.move_cursor(GlobalPoint::new(5, 5));
+		let _ = overlay.press();
+		let _ = overlay.release();
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		assert!(overlay.session.state.annotations.stamps.is_empty());
 
-		assert!(session.scroll_capture_input_allows_observation());
-		assert!(!session.scroll_capture_input_allows_growth());
-	}
+		let _ = overlay.move_cursor(GlobalPoint::new(60, 50));
+		let _ = overlay.press();
+		let _ = overlay.release();
 
-	#[test]
-	fn fresh_downward_direction_allows_growth_without_active_gesture() {
-		let mut session = OverlaySession::new();
+		assert_eq!(overlay.session.state.annotations.stamps.len(), 1);
+		assert_eq!(overlay.session.state.annotations.stamps[0].center_px, (50, 30));
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = false;
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Undo);
 
-		assert!(session.scroll_capture_input_allows_growth());
+		assert!(overlay.session.state.annotations.stamps.is_empty());
+
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Redo);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
+
+		assert_ne!(saved.get_pixel(50, 30).0, [60, 50, 60 ^ 50, 255]);
+		assert_eq!(saved.get_pixel(130, 60).0, [140, 80, 140 ^ 80, 255]);
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
 	#[test]
-	fn upward_direction_never_allows_growth() {
-		let mut session = OverlaySession::new();
+	fn headless_zoomed_frozen_view_places_stamps_on_native_pixels() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-zoom-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(150, 90));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		assert!(!session.scroll_capture_input_allows_growth());
+		overlay.session.toolbar_state.selected_tool = FrozenToolbarTool::Stamp;
+
+		let _ = overlay.move_cursor(GlobalPoint::new(100, 50));
+
+		for _ in 0..2 {
+			let _ =
+				overlay.session.handle_frozen_view_wheel(&MouseScrollDelta::LineDelta(0.0, 1.0));
+		}
+
+		// Zooming around the pointer keeps the point under it in place.
+		assert_eq!(overlay.session.state.frozen_view.zoom, 1.5625);
+		assert_eq!(overlay.session.state.frozen_view.origin, (36.0, 18.0));
+
+		let _ = overlay.move_cursor(GlobalPoint::new(60, 50));
+		let _ = overlay.press();
+		let _ = overlay.release();
+
+		// Window point (60, 50) shows monitor point (74.4, 50), which is selection pixel (64, 30).
+		assert_eq!(overlay.session.state.annotations.stamps[0].center_px, (64, 30));
+
+		let _ = overlay.session.handle_frozen_view_pan_button(ElementState::Pressed);
+		let _ = overlay.move_cursor(GlobalPoint::new(85, 50));
+		let _ = overlay.session.handle_frozen_view_pan_button(ElementState::Released);
+
+		assert_eq!(overlay.session.state.frozen_view.origin, (20.0, 18.0));
+
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
+
+		// The export stays at native resolution with the stamp where it was placed.
+		assert_eq!((saved.width(), saved.height()), (140, 70));
+		assert_ne!(saved.get_pixel(64, 30).0, [74, 50, 74 ^ 50, 255]);
+		assert_eq!(saved.get_pixel(130, 60).0, [140, 80, 140 ^ 80, 255]);
+
+		let _ = std::fs::remove_dir_all(output_dir);
 	}
 
 	#[test]
-	fn upward_rewind_frame_is_observed_before_resume_frontier_growth() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-			[80, 0, 0, 255],
-		];
-		let mut session = OverlaySession::new();
+	fn headless_highlight_strokes_multiply_into_the_export() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-highlight-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
+		let _ = overlay.drag(GlobalPoint::new(5, 5), GlobalPoint::new(195, 95));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		set_scroll_capture_input(&mut session, ScrollDirection::Down);
+		overlay.session.toolbar_state.selected_tool = FrozenToolbarTool::Highlighter;
 
+		let _ = overlay.drag(GlobalPoint::new(20, 50), GlobalPoint::new(100, 50));
+
+		assert_eq!(overlay.session.state.annotations.highlights.len(), 1);
 		assert_eq!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 1, 5),
-			),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
-		assert_eq!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 2, 5),
-			),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
+			overlay.session.state.annotations.highlights[0].points,
+			vec![(15, 45), (95, 45)]
 		);
 
-		let height_after_second_append = scroll_capture_export_height(&session);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Undo);
 
-		set_scroll_capture_input(&mut session, ScrollDirection::Up);
+		assert!(overlay.session.state.annotations.highlights.is_empty());
 
-		assert!(matches!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 0, 5),
-			),
-			Some(
-				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
-					| ScrollObserveOutcome::PreviewUpdated
-			)
-		));
-		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Redo);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
 
-		set_scroll_capture_input(&mut session, ScrollDirection::Down);
+		// The default yellow ink keeps red, scales green, and drops blue.
+		assert_eq!(saved.get_pixel(50, 45).0, [55, 45, 0, 255]);
+		assert_eq!(saved.get_pixel(50, 80).0, [55, 85, 55 ^ 85, 255]);
 
-		assert!(matches!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 2, 5),
-			),
-			Some(ScrollObserveOutcome::NoChange) | Some(ScrollObserveOutcome::PreviewUpdated)
-		));
-		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
 
-		set_scroll_capture_input(&mut session, ScrollDirection::Up);
+	#[test]
+	fn headless_callout_tail_drags_undo_and_the_callout_lands_in_the_export() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-callout-{}", std::process::id()));
+		let config = OverlayConfig { output_dir: output_dir.clone(), ..headless_config() };
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		assert!(matches!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 1, 5),
-			),
-			Some(
-				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
-					| ScrollObserveOutcome::PreviewUpdated
-					| ScrollObserveOutcome::NoChange
-			)
-		));
-		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
+		let _ = overlay.drag(GlobalPoint::new(5, 5), GlobalPoint::new(195, 95));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
 
-		set_scroll_capture_input(&mut session, ScrollDirection::Down);
+		overlay.session.toolbar_state.selected_tool = FrozenToolbarTool::Callout;
 
-		assert!(matches!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 2, 5),
-			),
-			Some(ScrollObserveOutcome::NoChange) | Some(ScrollObserveOutcome::PreviewUpdated)
-		));
-		assert_eq!(scroll_capture_export_height(&session), height_after_second_append);
-		assert_eq!(
-			observe_scroll_capture_frame(
-				&mut session,
-				make_scroll_capture_window(&document, 3, 3, 5),
-			),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
+		let _ = overlay.move_cursor(GlobalPoint::new(20, 85));
+		let _ = overlay.press();
+		let _ = overlay.release();
+
+		assert_eq!(overlay.session.state.annotations.editing, Some(0));
+		assert!(overlay.session.state.annotations.type_text("Bug"));
+
+		let callout = &overlay.session.state.annotations.callouts[0];
+
+		assert_eq!((callout.tail_px, callout.origin_px), ((15, 80), (47, 0)));
+
+		// Pressing on the tail tip drags the tail while the body stays put.
+		let _ = overlay.drag(GlobalPoint::new(20, 85), GlobalPoint::new(30, 90));
+		let callout = &overlay.session.state.annotations.callouts[0];
+
+		assert_eq!((callout.tail_px, callout.origin_px), ((25, 85), (47, 0)));
+
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Undo);
+
+		assert_eq!(overlay.session.state.annotations.editing, None);
+		assert_eq!(overlay.session.state.annotations.callouts[0].tail_px, (15, 80));
+
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Redo);
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let saved = image::open(&path).expect("saved PNG").to_rgba8();
+
+		assert_eq!(saved.get_pixel(50, 20).0, [255, 255, 255, 255]);
+		assert_eq!(saved.get_pixel(170, 70).0, [175, 75, 175 ^ 75, 255]);
+
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
+
+	#[test]
+	fn headless_qr_scan_finds_codes_live_and_in_the_frozen_selection() {
+		let code = qrcode::QrCode::new("https://example.com/qr").expect("QR code");
+		let width = code.width() as u32;
+		let mut frame = RgbaImage::from_pixel(200, 100, Rgba([255, 255, 255, 255]));
+
+		for (index, color) in code.to_colors().into_iter().enumerate() {
+			if color == qrcode::Color::Dark {
+				let (x, y) = (120 + index as u32 % width * 3, 10 + index as u32 / width * 3);
+
+				image::imageops::replace(
+					&mut frame,
+					&RgbaImage::from_pixel(3, 3, Rgba([0, 0, 0, 255])),
+					i64::from(x),
+					i64::from(y),
+				);
+			}
+		}
+
+		let backend = ScriptedCaptureBackend::new().with_frame(headless_monitor().id, frame);
+		let mut overlay = HeadlessOverlay::new(headless_config(), headless_monitor(), backend);
+		let expected = RectPoints::new(120, 10, width * 3, width * 3);
+
+		let _ = overlay.move_cursor(GlobalPoint::new(20, 20));
+		let _ = overlay.session.scan_for_codes();
+		let _ = overlay.pump_until(|session| !session.state.decoded_codes.is_empty());
+
+		assert_eq!(overlay.session.state.decoded_codes[0].payload, "https://example.com/qr");
+		assert_eq!(overlay.session.state.decoded_codes[0].bounds_px, expected);
+		assert!(overlay.session.state.error_message.is_none());
+		assert!(
+			overlay
+				.session
+				.state
+				.status_message
+				.as_deref()
+				.is_some_and(|status| status.starts_with("QR link: https://example.com/qr"))
 		);
+
+		// In the frozen selection the bounds still come back in monitor pixels.
+		let _ = overlay.drag(GlobalPoint::new(100, 2), GlobalPoint::new(195, 95));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		assert!(overlay.session.state.decoded_codes.is_empty());
+
+		let _ = overlay.session.scan_for_codes();
+		let _ = overlay.pump_until(|session| !session.state.decoded_codes.is_empty());
+
+		assert_eq!(overlay.session.state.decoded_codes[0].bounds_px, expected);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn stale_latched_worker_input_rewinds_without_ax_position() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-			[80, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
+	fn headless_save_downscales_to_the_longest_side_cap() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-scaling-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			export_scaling: ExportScaling { mode: ExportScaleMode::MaxSide, max_side_px: 30 },
+			..headless_config()
 		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let mut session = OverlaySession::new();
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(70, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
 
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
 
-		let height_after_second_append =
-			session.scroll_capture.session.as_ref().unwrap().export_image().height();
+		assert_eq!(image::open(&path).expect("saved PNG").to_rgba8().dimensions(), (30, 20));
 
-		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
-		session.scroll_capture.input_direction_at =
-			Some(Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50));
-		session.scroll_capture.input_gesture_active = false;
-		session.scroll_capture.last_external_scroll_input_seq = 7;
-		session.scroll_capture.inflight_request_id = Some(41);
-		session.scroll_capture.inflight_request_observation =
-			Some(InflightScrollCaptureObservation {
-				input_direction: Some(ScrollDirection::Up),
-				was_observable: true,
-				external_input_seq: 7,
-			});
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
 
-		session.handle_captured_scroll_region(
-			monitor,
-			capture_rect,
-			41,
-			make_scroll_capture_window(&document, 3, 1, 5),
+	#[test]
+	fn display_picker_numbers_displays_left_to_right() {
+		let monitor = |id, x, y| MonitorRect {
+			id,
+			origin: GlobalPoint::new(x, y),
+			width: 1_920,
+			height: 1_080,
+			scale_factor_x1000: 1_000,
+		};
+		let ordered = display_picker_runtime::display_picker_order(
+			[monitor(7, 1_920, 0), monitor(3, -1_920, 200), monitor(5, 0, 0)].into_iter(),
 		);
 
-		assert_eq!(session.scroll_capture.inflight_request_id, None);
-		assert_eq!(session.scroll_capture.inflight_request_observation, None);
+		assert_eq!(ordered.iter().map(|monitor| monitor.id).collect::<Vec<_>>(), vec![3, 5, 7]);
+	}
 
-		let scroll_session_debug =
-			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+	#[test]
+	fn headless_display_picker_freezes_the_picked_display() {
+		let monitor = headless_monitor();
+		let left = MonitorRect { id: 9, origin: GlobalPoint::new(-200, 0), ..monitor };
+		let mut overlay =
+			HeadlessOverlay::new(headless_config(), monitor, ScriptedCaptureBackend::new());
 
-		assert!(
-			scroll_session_debug.contains("resume_frontier_top_y: Some(2)"),
-			"{scroll_session_debug}"
-		);
-		assert!(
-			scroll_session_debug.contains("observed_viewport_top_y: 1"),
-			"{scroll_session_debug}"
-		);
+		let _ = overlay.move_cursor(GlobalPoint::new(50, 50));
+
+		overlay.session.state.display_picker =
+			display_picker_runtime::display_picker_order([monitor, left].into_iter());
+
+		let _ = overlay.session.pick_display(1);
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+
+		assert!(overlay.session.state.display_picker.is_empty());
+		assert_eq!(overlay.session.state.monitor, Some(monitor));
+		assert_eq!(overlay.session.frozen_capture_source, FrozenCaptureSource::FullscreenFallback);
 		assert_eq!(
-			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
-			height_after_second_append
+			overlay.session.state.frozen_capture_rect,
+			Some(RectPoints::new(0, 0, monitor.width, monitor.height))
 		);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn newer_input_supersedes_latched_worker_observation_context() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-			[80, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let mut session = OverlaySession::new();
-
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+	fn headless_display_identify_badges_every_display_then_closes() {
+		let monitor = headless_monitor();
+		let left = MonitorRect { id: 9, origin: GlobalPoint::new(-200, 0), ..monitor };
+		let mut overlay =
+			HeadlessOverlay::new(headless_config(), monitor, ScriptedCaptureBackend::new());
+		let now = Instant::now();
 
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
+		overlay.session.open_display_identify(
+			vec![(monitor, Some(String::from("Built-in"))), (left, None)],
+			now,
 		);
+
+		assert!(overlay.session.state.identify_displays);
+		assert_eq!(overlay.session.state.display_picker, vec![left, monitor]);
 		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
+			overlay.session.state.display_names.get(&monitor.id).map(String::as_str),
+			Some("Built-in")
 		);
+		assert!(overlay.session.maybe_finish_display_identify(now).is_none());
+		assert!(matches!(
+			overlay.session.maybe_finish_display_identify(now + Duration::from_secs(3)),
+			Some(OverlayControl::Exit(OverlayExit::Cancelled))
+		));
+	}
 
-		let height_after_second_append =
-			session.scroll_capture.session.as_ref().unwrap().export_image().height();
+	#[test]
+	fn headless_save_writes_a_2x_and_1x_asset_pair_side_by_side() {
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-headless-asset-pair-{}", std::process::id()));
+		let config = OverlayConfig {
+			output_dir: output_dir.clone(),
+			retina_pair: true,
+			..headless_config()
+		};
+		let mut overlay =
+			HeadlessOverlay::new(config, headless_monitor(), ScriptedCaptureBackend::new());
 
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at =
-			Some(Instant::now() - SCROLL_CAPTURE_INPUT_FRESHNESS - Duration::from_millis(50));
-		session.scroll_capture.input_gesture_active = false;
-		session.scroll_capture.last_external_scroll_input_seq = 8;
-		session.scroll_capture.inflight_request_id = Some(41);
-		session.scroll_capture.inflight_request_observation =
-			Some(InflightScrollCaptureObservation {
-				input_direction: Some(ScrollDirection::Up),
-				was_observable: true,
-				external_input_seq: 7,
-			});
+		let _ = overlay.drag(GlobalPoint::new(10, 20), GlobalPoint::new(71, 60));
+		let _ = overlay.pump_until(|session| session.state.frozen_image.is_some());
+		let _ = overlay.toolbar_action(FrozenToolbarTool::Save);
 
-		session.handle_captured_scroll_region(
-			monitor,
-			capture_rect,
-			41,
-			make_scroll_capture_window(&document, 3, 1, 5),
-		);
+		let control = overlay.pump_until(|_| false);
+		let OverlayControl::Exit(OverlayExit::Saved(path)) = control else {
+			panic!("expected a saved export, got {control:?}");
+		};
+		let file_name = path.file_name().and_then(|name| name.to_str()).expect("file name");
+		let one_x_path = path.with_file_name(file_name.replace("@2x.png", ".png"));
 
-		assert_eq!(session.scroll_capture.inflight_request_id, None);
-		assert_eq!(session.scroll_capture.inflight_request_observation, None);
+		assert!(file_name.ends_with("@2x.png"), "unexpected pair name {file_name}");
+		assert_eq!(image::open(&path).expect("@2x PNG").to_rgba8().dimensions(), (61, 40));
+		assert_eq!(image::open(&one_x_path).expect("@1x PNG").to_rgba8().dimensions(), (31, 20));
 
-		let scroll_session_debug =
-			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+		let progress = overlay.session.take_progress();
 
-		assert!(scroll_session_debug.contains("resume_frontier_top_y: None"));
-		assert!(scroll_session_debug.contains("current_viewport_top_y: 2"));
 		assert_eq!(
-			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
-			height_after_second_append
+			progress.iter().map(|progress| (progress.stage, progress.pct)).collect::<Vec<_>>(),
+			vec![
+				(ProgressStage::Composing, 0),
+				(ProgressStage::Encoding, 33),
+				(ProgressStage::Encoding, 66),
+				(ProgressStage::Encoding, 100),
+			]
 		);
+		assert!(overlay.session.take_progress().is_empty());
 
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		let _ = std::fs::remove_dir_all(output_dir);
+	}
 
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 3, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
+	/// Per-channel difference a golden pixel may show before it counts as changed.
+	const GOLDEN_CHANNEL_TOLERANCE: u8 = 3;
+	/// Share of pixels, in thousandths, that may change before a golden comparison fails.
+	const GOLDEN_CHANGED_PIXELS_PER_MILLE: usize = 2;
+
+	/// Golden tests are `#[ignore]`d so machines without a GPU adapter report them as skipped;
+	/// run them with `cargo test -p rsnap-overlay golden -- --ignored`.
+	fn golden_gpu() -> GpuContext {
+		GpuContext::new(GpuPowerPreference::default())
+			.expect("golden tests need a GPU adapter; run them without `--ignored` to skip them")
 	}
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn missing_worker_scroll_frame_clears_inflight_without_mutating_session() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-			[80, 0, 0, 255],
-		];
-		let monitor = MonitorRect {
-			id: 1,
+	fn golden_monitor() -> MonitorRect {
+		MonitorRect {
+			id: 4,
 			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
+			width: 320,
+			height: 200,
 			scale_factor_x1000: 1_000,
-		};
-		let capture_rect = RectPoints::new(100, 120, 200, 240);
-		let mut session = OverlaySession::new();
+		}
+	}
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(capture_rect);
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
-		session.scroll_capture.last_external_scroll_input_seq = 11;
-		session.scroll_capture.inflight_request_id = Some(41);
-		session.scroll_capture.inflight_request_observation =
-			Some(InflightScrollCaptureObservation {
-				input_direction: Some(ScrollDirection::Down),
-				was_observable: true,
-				external_input_seq: 11,
-			});
+	fn golden_frozen_state(monitor: MonitorRect) -> crate::state::OverlayState {
+		let mut state = crate::state::OverlayState::new();
+
+		state.begin_freeze(monitor);
+		state.finish_freeze(monitor, ScriptedCaptureBackend::synthetic_frame(320, 200));
+
+		state.cursor = Some(GlobalPoint::new(120, 80));
+		state.rgb = Some(Rgb::new(120, 80, 120 ^ 80));
+		state.frozen_capture_rect = Some(RectPoints::new(60, 40, 180, 110));
+
+		state
+	}
+
+	/// Overlay window whose draw call a golden frame reproduces.
+	enum GoldenWindow<'a> {
+		Hud { show_hud_blur: bool },
+		Toolbar(&'a mut FrozenToolbarState),
+		Overlay,
+	}
+
+	/// Draws `window` the way the overlay does, with the default appearance settings.
+	///
+	/// egui lays out new areas invisibly on their first frame, so the frame is drawn twice.
+	fn draw_golden_frame(
+		renderer: &mut WindowRenderer,
+		gpu: &GpuContext,
+		state: &crate::state::OverlayState,
+		mut window: GoldenWindow,
+	) -> RgbaImage {
+		let config = OverlayConfig::default();
+
+		for _ in 0..2 {
+			let (draw_hud, hud_local_cursor_override, show_hud_blur, allow_frozen_surface_bg) =
+				match &window {
+					GoldenWindow::Hud { show_hud_blur } => {
+						(true, Some(Pos2::new(-14.0, -14.0)), *show_hud_blur, true)
+					},
+					GoldenWindow::Toolbar(_) => (false, Some(Pos2::ZERO), false, false),
+					GoldenWindow::Overlay => (false, None, false, true),
+				};
+			let toolbar_state = match &mut window {
+				GoldenWindow::Toolbar(toolbar_state) => Some(&mut **toolbar_state),
+				_ => None,
+			};
+
+			renderer
+				.draw(
+					gpu,
+					state,
+					golden_monitor(),
+					draw_hud,
+					hud_local_cursor_override,
+					false,
+					HudAnchor::Cursor,
+					config.toolbar_placement,
+					false,
+					show_hud_blur,
+					false,
+					config.hud_opacity,
+					config.hud_fog_amount,
+					config.hud_milk_amount,
+					config.hud_tint_hue,
+					ThemeMode::Dark,
+					false,
+					config.selection_flow_stroke(),
+					allow_frozen_surface_bg,
+					false,
+					false,
+					toolbar_state,
+					None,
+				)
+				.expect("offscreen draw");
+		}
+
+		renderer.read_offscreen_image(gpu).expect("offscreen readback")
+	}
+
+	/// Compares `image` against `tests/golden/<name>-<os>.png`.
+	///
+	/// With `RSNAP_UPDATE_GOLDENS` set, every golden is written instead, so recording a new one or
+	/// accepting an intended visual change is a rerun followed by reviewing the PNG diff. Without
+	/// it, a missing golden fails the test.
+	fn assert_matches_golden(name: &str, image: &RgbaImage) {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/golden")
+			.join(format!("{name}-{}.png", std::env::consts::OS));
+
+		assert!(
+			path.exists() || std::env::var_os("RSNAP_UPDATE_GOLDENS").is_some(),
+			"{name}: no golden at {}; rerun with RSNAP_UPDATE_GOLDENS=1 to record it",
+			path.display()
+		);
+
+		if std::env::var_os("RSNAP_UPDATE_GOLDENS").is_some() {
+			std::fs::create_dir_all(path.parent().expect("golden directory"))
+				.expect("create golden directory");
+			image.save(&path).expect("write golden");
+
+			return;
+		}
 
-		let scroll_session_before =
-			format!("{:?}", session.scroll_capture.session.as_ref().unwrap());
+		let golden = image::open(&path).expect("read golden").to_rgba8();
 
-		session.handle_missing_scroll_region(monitor, capture_rect, 41);
+		assert_eq!(golden.dimensions(), image.dimensions(), "{name}: golden size changed");
 
-		assert_eq!(session.scroll_capture.inflight_request_id, None);
-		assert_eq!(session.scroll_capture.inflight_request_observation, None);
-		assert_eq!(
-			format!("{:?}", session.scroll_capture.session.as_ref().unwrap()),
-			scroll_session_before
+		let changed =
+			golden
+				.pixels()
+				.zip(image.pixels())
+				.filter(|(expected, actual)| {
+					expected.0.iter().zip(actual.0).any(|(expected, actual)| {
+						expected.abs_diff(actual) > GOLDEN_CHANNEL_TOLERANCE
+					})
+				})
+				.count();
+		let allowed = golden.pixels().len() * GOLDEN_CHANGED_PIXELS_PER_MILLE / 1_000;
+
+		assert!(
+			changed <= allowed,
+			"{name}: {changed} pixels differ from {} (allowed {allowed}); rerun with \
+			 RSNAP_UPDATE_GOLDENS=1 if the change is intended",
+			path.display()
 		);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn maybe_tick_scroll_capture_stays_on_stream_path_without_worker_fallback() {
-		let monitor = MonitorRect {
-			id: 1,
-			origin: GlobalPoint::new(0, 0),
-			width: 1_000,
-			height: 800,
-			scale_factor_x1000: 1_000,
-		};
-		let mut session = OverlaySession::new();
+	#[ignore = "renders on the GPU"]
+	fn golden_live_hud_pill() {
+		let gpu = golden_gpu();
+		let monitor = golden_monitor();
+		let mut state = crate::state::OverlayState::new();
 
-		session.scroll_capture.active = true;
-		session.scroll_capture.monitor = Some(monitor);
-		session.scroll_capture.capture_rect_pixels = Some(RectPoints::new(100, 120, 200, 240));
-		session.scroll_capture.live_stream = Some(MacLiveFrameStream::new());
+		state.monitor = Some(monitor);
+		state.cursor = Some(GlobalPoint::new(40, 30));
+		state.rgb = Some(Rgb::new(200, 120, 40));
 
-		session.maybe_tick_scroll_capture();
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(360, 120), 1.0);
+		let image = draw_golden_frame(
+			&mut renderer,
+			&gpu,
+			&state,
+			GoldenWindow::Hud { show_hud_blur: false },
+		);
 
-		assert!(!session.scroll_capture.paused);
-		assert!(session.state.error_message.is_none());
-		assert_eq!(session.scroll_capture.inflight_request_id, None);
+		assert_matches_golden("live_hud_pill", &image);
 	}
 
 	#[test]
-	fn upward_input_with_lower_frame_never_appends_growth() {
-		let document = [
-			[10, 0, 0, 255],
-			[20, 0, 0, 255],
-			[30, 0, 0, 255],
-			[40, 0, 0, 255],
-			[50, 0, 0, 255],
-			[60, 0, 0, 255],
-			[70, 0, 0, 255],
-		];
-		let mut session = OverlaySession::new();
-
-		session.scroll_capture.active = true;
-		session.scroll_capture.session =
-			Some(ScrollSession::new(make_scroll_capture_window(&document, 3, 0, 5), 320).unwrap());
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+	#[ignore = "renders on the GPU"]
+	fn golden_live_hud_color_compare() {
+		let gpu = golden_gpu();
+		let monitor = golden_monitor();
+		let mut state = crate::state::OverlayState::new();
 
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 1, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
+		state.monitor = Some(monitor);
+		state.cursor = Some(GlobalPoint::new(40, 30));
+		state.rgb = Some(Rgb::new(200, 120, 40));
+		state.color_picker = true;
+		state.reference_sample =
+			Some(ReferenceSample { point: GlobalPoint::new(10, 10), rgb: Rgb::new(190, 128, 52) });
+
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(420, 120), 1.0);
+		let image = draw_golden_frame(
+			&mut renderer,
+			&gpu,
+			&state,
+			GoldenWindow::Hud { show_hud_blur: false },
 		);
 
-		let height_after_first_append =
-			session.scroll_capture.session.as_ref().unwrap().export_image().height();
+		assert_matches_golden("live_hud_color_compare", &image);
+	}
 
-		session.scroll_capture.input_direction = Some(ScrollDirection::Up);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+	#[test]
+	#[ignore = "renders on the GPU"]
+	fn golden_live_loupe_tile() {
+		let gpu = golden_gpu();
+		let monitor = golden_monitor();
+		let config = OverlayConfig::default();
+		let mut state = crate::state::OverlayState::new();
 
-		assert!(matches!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
-				.transpose()
-				.unwrap(),
-			Some(
-				ScrollObserveOutcome::UnsupportedDirection { direction: ScrollDirection::Up }
-					| ScrollObserveOutcome::PreviewUpdated
-					| ScrollObserveOutcome::NoChange
-			)
-		));
-		assert_eq!(
-			session.scroll_capture.session.as_ref().unwrap().export_image().height(),
-			height_after_first_append
-		);
-		assert!(matches!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::PreviewUpdated | ScrollObserveOutcome::NoChange)
-		));
+		state.monitor = Some(monitor);
+		state.cursor = Some(GlobalPoint::new(40, 30));
+		state.rgb = Some(Rgb::new(40, 30, 40 ^ 30));
+		state.alt_held = true;
+		state.loupe_patch_side_px = 21;
+		state.loupe = Some(LoupeSample {
+			center: GlobalPoint::new(40, 30),
+			patch: ScriptedCaptureBackend::synthetic_frame(21, 21),
+		});
 
-		session.scroll_capture.input_direction = Some(ScrollDirection::Down);
-		session.scroll_capture.input_direction_at = Some(Instant::now());
-		session.scroll_capture.input_gesture_active = true;
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(200, 200), 1.0);
 
-		assert_eq!(
-			session
-				.observe_scroll_capture_frame(make_scroll_capture_window(&document, 3, 2, 5))
-				.transpose()
-				.unwrap(),
-			Some(ScrollObserveOutcome::Committed {
-				direction: ScrollDirection::Down,
-				growth_rows: 1,
-			})
-		);
-	}
+		for _ in 0..2 {
+			renderer
+				.draw_loupe_tile_window(
+					&gpu,
+					&state,
+					monitor,
+					false,
+					false,
+					config.hud_opacity,
+					config.hud_fog_amount,
+					config.hud_milk_amount,
+					config.hud_tint_hue,
+					ThemeMode::Dark,
+				)
+				.expect("offscreen loupe draw");
+		}
 
-	#[cfg(target_os = "macos")]
-	#[test]
-	fn positive_pixel_delta_maps_to_upward_scroll_capture() {
-		assert_eq!(
-			OverlaySession::scroll_capture_direction_from_wheel_delta(
-				&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 2.0))
-			),
-			Some(ScrollDirection::Up)
-		);
+		let image = renderer.read_offscreen_image(&gpu).expect("offscreen readback");
+
+		assert_matches_golden("live_loupe_tile", &image);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn macos_scroll_wheel_events_use_hid_system_source_state() {
-		assert_eq!(
-			super::macos_hid_event_source_state_id(),
-			super::KCG_EVENT_SOURCE_STATE_HID_SYSTEM_STATE
-		);
+	#[ignore = "renders on the GPU"]
+	fn golden_live_loupe_pixel_grid() {
+		let gpu = golden_gpu();
+		let monitor = golden_monitor();
+		let config = OverlayConfig::default();
+		let mut state = crate::state::OverlayState::new();
+
+		state.monitor = Some(monitor);
+		state.cursor = Some(GlobalPoint::new(40, 30));
+		state.alt_held = true;
+		state.loupe_patch_side_px = 11;
+		state.loupe_cell_points = 20.0;
+		state.loupe_lock = Some((2, -1));
+		state.loupe_row_column_highlight = true;
+		state.loupe = Some(LoupeSample {
+			center: GlobalPoint::new(40, 30),
+			patch: ScriptedCaptureBackend::synthetic_frame(11, 11),
+		});
+
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(260, 260), 1.0);
+
+		for _ in 0..2 {
+			renderer
+				.draw_loupe_tile_window(
+					&gpu,
+					&state,
+					monitor,
+					false,
+					false,
+					config.hud_opacity,
+					config.hud_fog_amount,
+					config.hud_milk_amount,
+					config.hud_tint_hue,
+					ThemeMode::Dark,
+				)
+				.expect("offscreen loupe draw");
+		}
+
+		let image = renderer.read_offscreen_image(&gpu).expect("offscreen readback");
+
+		assert_matches_golden("live_loupe_pixel_grid", &image);
 	}
 
-	#[cfg(target_os = "macos")]
 	#[test]
-	fn pixel_delta_residuals_accumulate_until_whole_pixels_emit() {
-		let mut residual = MacOSScrollPixelResidual::default();
-		let first = OverlaySession::normalize_macos_scroll_wheel_delta(
-			&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.4, -0.4)),
-			&mut residual,
+	#[ignore = "renders on the GPU"]
+	fn golden_frozen_toolbar() {
+		let gpu = golden_gpu();
+		let state = golden_frozen_state(golden_monitor());
+		let mut toolbar_state = FrozenToolbarState {
+			floating_position: Some(Pos2::ZERO),
+			..FrozenToolbarState::default()
+		};
+		let size = WindowRenderer::frozen_toolbar_size(&toolbar_state);
+		let mut renderer = WindowRenderer::new_offscreen(
+			&gpu,
+			PhysicalSize::new(size.x.ceil() as u32, size.y.ceil() as u32),
+			1.0,
 		);
-		let second = OverlaySession::normalize_macos_scroll_wheel_delta(
-			&MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.7, -0.8)),
-			&mut residual,
+		let image = draw_golden_frame(
+			&mut renderer,
+			&gpu,
+			&state,
+			GoldenWindow::Toolbar(&mut toolbar_state),
 		);
 
-		assert_eq!(first.units, KCG_SCROLL_EVENT_UNIT_PIXEL);
-		assert_eq!(first.posted_x, 0);
-		assert_eq!(first.posted_y, 0);
-		assert!((first.residual.x - 0.4).abs() < f64::EPSILON);
-		assert!((first.residual.y + 0.4).abs() < f64::EPSILON);
-		assert_eq!(second.posted_x, 1);
-		assert_eq!(second.posted_y, -1);
-		assert!((second.residual.x - 0.1).abs() < 1e-9);
-		assert!((second.residual.y + 0.2).abs() < 1e-9);
+		assert_matches_golden("frozen_toolbar", &image);
 	}
 
 	#[test]
-	fn frozen_toolbar_mode_tools_are_identifiable() {
-		assert!(FrozenToolbarTool::Pointer.is_mode_tool());
-		assert!(FrozenToolbarTool::Pen.is_mode_tool());
-		assert!(FrozenToolbarTool::Text.is_mode_tool());
-		assert!(FrozenToolbarTool::Mosaic.is_mode_tool());
-	}
+	#[ignore = "renders on the GPU"]
+	fn golden_frozen_overlay() {
+		let gpu = golden_gpu();
+		let state = golden_frozen_state(golden_monitor());
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(320, 200), 1.0);
+		let image = draw_golden_frame(&mut renderer, &gpu, &state, GoldenWindow::Overlay);
 
-	#[test]
-	fn frozen_toolbar_action_tools_are_not_mode_tools() {
-		assert!(!FrozenToolbarTool::Undo.is_mode_tool());
-		assert!(!FrozenToolbarTool::Redo.is_mode_tool());
-		assert!(!FrozenToolbarTool::Scroll.is_mode_tool());
-		assert!(!FrozenToolbarTool::Copy.is_mode_tool());
-		assert!(!FrozenToolbarTool::Save.is_mode_tool());
+		assert_matches_golden("frozen_overlay", &image);
 	}
 
 	#[test]
-	fn tinted_hud_body_fill_amount_zero_keeps_base_fill() {
-		for theme in [HudTheme::Dark, HudTheme::Light] {
-			let base_fill = hud_helpers::hud_body_fill_srgba8(theme, false);
-			let no_tint =
-				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 0.0, 0.585);
+	#[ignore = "renders on the GPU"]
+	fn color_vision_filter_recolors_only_the_frozen_preview() {
+		let gpu = golden_gpu();
+		let mut state = golden_frozen_state(golden_monitor());
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(320, 200), 1.0);
+		let plain = draw_golden_frame(&mut renderer, &gpu, &state, GoldenWindow::Overlay);
 
-			assert_eq!(no_tint.r(), base_fill[0]);
-			assert_eq!(no_tint.g(), base_fill[1]);
-			assert_eq!(no_tint.b(), base_fill[2]);
-			assert_eq!(no_tint.a(), 255);
-		}
+		state.color_vision = ColorVisionFilter::Deuteranopia;
+
+		let simulated = draw_golden_frame(&mut renderer, &gpu, &state, GoldenWindow::Overlay);
+
+		assert!(renderer.shader_pipelines.color_vision_valid);
+		assert!(renderer.color_vision_active);
+		assert_ne!(plain.get_pixel(200, 120), simulated.get_pixel(200, 120));
 	}
 
 	#[test]
-	fn tinted_hud_body_fill_100pct_tint_is_visibly_blue() {
-		let dark_min_delta: u16 = 57;
-		let light_min_delta: u16 = 24;
-		let sky_tint = 0.585;
+	#[ignore = "renders on the GPU"]
+	fn zoomed_frozen_view_magnifies_the_frozen_preview() {
+		let gpu = golden_gpu();
+		let mut state = golden_frozen_state(golden_monitor());
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(320, 200), 1.0);
 
-		for theme in [HudTheme::Dark, HudTheme::Light] {
-			let base_fill =
-				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 0.0, sky_tint);
-			let tinted_fill =
-				WindowRenderer::tinted_hud_body_fill(theme, false, false, 1.0, 1.0, sky_tint);
-			let rgb_delta = u16::from(base_fill.r()).abs_diff(u16::from(tinted_fill.r()))
-				+ u16::from(base_fill.g()).abs_diff(u16::from(tinted_fill.g()))
-				+ u16::from(base_fill.b()).abs_diff(u16::from(tinted_fill.b()));
-			let min_delta =
-				if matches!(theme, HudTheme::Dark) { dark_min_delta } else { light_min_delta };
+		state.frozen_view = FrozenView { zoom: 4.0, origin: (100.0, 60.0) };
 
-			assert!(
-				rgb_delta >= min_delta,
-				"expected minimum tint delta >= {min_delta}, got {rgb_delta}"
-			);
-		}
-	}
+		let zoomed = draw_golden_frame(&mut renderer, &gpu, &state, GoldenWindow::Overlay);
 
-	#[test]
-	fn tinted_hud_body_fill_preserves_alpha() {
-		for theme in [HudTheme::Dark, HudTheme::Light] {
-			let tint_hue = 0.585;
-			let opaque =
-				WindowRenderer::tinted_hud_body_fill(theme, false, true, 0.25, 1.0, tint_hue);
-			let translucent =
-				WindowRenderer::tinted_hud_body_fill(theme, false, false, 0.33, 1.0, tint_hue);
+		assert!(renderer.frozen_view_texture.is_some());
+		// Each frozen pixel covers a 4×4 block starting at the view origin.
+		assert_eq!(zoomed.get_pixel(10, 10).0, [102, 62, 102 ^ 62, 255]);
+		assert_eq!(zoomed.get_pixel(13, 10).0, [103, 62, 103 ^ 62, 255]);
 
-			assert_eq!(opaque.a(), 255);
-			assert_eq!(translucent.a(), (0.33_f32 * 255.0).round().clamp(0.0, 255.0) as u8);
-		}
-	}
+		state.frozen_view = FrozenView::default();
 
-	#[test]
-	fn tinted_hud_body_fill_blur_active_enforces_min_opacity() {
-		for theme in [HudTheme::Dark, HudTheme::Light] {
-			let tint_hue = 0.585;
-			let fill = WindowRenderer::tinted_hud_body_fill(theme, true, false, 0.0, 0.0, tint_hue);
-			let expected =
-				(hud_helpers::hud_blur_tint_alpha(theme) * 255.0).round().clamp(0.0, 255.0) as u8;
+		let _ = draw_golden_frame(&mut renderer, &gpu, &state, GoldenWindow::Overlay);
 
-			assert_eq!(fill.a(), expected);
-		}
+		assert!(renderer.frozen_view_texture.is_none());
 	}
 
 	#[test]
-	fn frozen_toolbar_clamps_floating_position() {
-		let monitor = Rect::from_min_size(Pos2::new(-200.0, -100.0), Vec2::new(500.0, 400.0));
-		let toolbar_size = Vec2::new(220.0, 42.0);
-		let clamped = WindowRenderer::clamp_toolbar_position(
-			monitor,
-			toolbar_size,
-			Pos2::new(-400.0, -240.0),
-			TOOLBAR_SCREEN_MARGIN_PX,
-			TOOLBAR_SCREEN_MARGIN_PX,
+	#[ignore = "renders on the GPU"]
+	fn golden_frozen_hud_blur() {
+		let gpu = golden_gpu();
+		let state = golden_frozen_state(golden_monitor());
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(360, 120), 1.0);
+		let image = draw_golden_frame(
+			&mut renderer,
+			&gpu,
+			&state,
+			GoldenWindow::Hud { show_hud_blur: true },
 		);
 
-		assert_eq!(clamped.x, monitor.min.x + TOOLBAR_SCREEN_MARGIN_PX);
-		assert_eq!(clamped.y, monitor.min.y + TOOLBAR_SCREEN_MARGIN_PX);
+		assert_matches_golden("frozen_hud_blur", &image);
 	}
 
 	#[test]
-	fn interactive_repaint_fps_uses_known_lower_monitor_refresh() {
-		assert_eq!(OverlaySession::interactive_repaint_fps(Some(60.0), Some(144.0)), 60.0);
-		assert_eq!(OverlaySession::interactive_repaint_fps(Some(75.0), Some(120.0)), 75.0);
-	}
+	#[ignore = "renders on the GPU"]
+	fn shader_that_fails_validation_disables_only_its_effect() {
+		let gpu = golden_gpu();
+		let state = golden_frozen_state(golden_monitor());
+		let mut renderer = WindowRenderer::new_offscreen(&gpu, PhysicalSize::new(360, 120), 1.0);
+		let broken = ShaderSources {
+			hud_blur: "fn fs_main( {".into(),
+			generation: 1,
+			..ShaderSources::default()
+		};
 
-	#[test]
-	fn interactive_repaint_fps_caps_known_higher_refresh_to_contract_limit() {
-		assert_eq!(OverlaySession::interactive_repaint_fps(Some(144.0), Some(60.0)), 120.0);
-		assert_eq!(OverlaySession::interactive_repaint_fps(Some(240.0), None), 120.0);
+		// A reload adopts the shaders that validate and keeps the last good blur pipeline.
+		assert!(renderer.sync_shaders(&gpu, &broken));
+		assert!(renderer.shader_pipelines.hud_blur_valid);
+
+		renderer.shader_pipelines = WindowRenderer::create_shader_pipelines(
+			&gpu,
+			renderer.surface_config.format,
+			&renderer.mipgen_bind_group_layout,
+			&renderer.hud_blur_bind_group_layout,
+			&renderer.frozen_dim_bind_group_layout,
+			&renderer.color_vision_bind_group_layout,
+			&broken,
+		);
+
+		assert_eq!(renderer.shader_pipelines.failed_shaders(), ["hud_blur.wgsl"]);
+
+		// Built without a valid blur pipeline, the HUD still draws; only the blur pass is skipped.
+		let image = draw_golden_frame(
+			&mut renderer,
+			&gpu,
+			&state,
+			GoldenWindow::Hud { show_hud_blur: true },
+		);
+
+		assert!(image.pixels().any(|pixel| pixel.0[3] > 0));
 	}
 
+	#[cfg(not(target_os = "macos"))]
 	#[test]
-	fn interactive_repaint_fps_falls_back_to_known_or_default_cap() {
-		assert_eq!(OverlaySession::interactive_repaint_fps(None, Some(90.0)), 90.0);
-		assert_eq!(OverlaySession::interactive_repaint_fps(None, Some(144.0)), 120.0);
-		assert_eq!(OverlaySession::interactive_repaint_fps(None, None), 120.0);
+	fn headless_live_cursor_samples_the_scripted_frame() {
+		let mut overlay = HeadlessOverlay::new(
+			headless_config(),
+			headless_monitor(),
+			ScriptedCaptureBackend::new(),
+		);
+
+		let _ = overlay.move_cursor(GlobalPoint::new(30, 20));
+		let _ = overlay.pump_until(|session| session.state.rgb.is_some());
+
+		assert!(matches!(overlay.session.state.mode, OverlayMode::Live));
+		assert_eq!(overlay.session.state.rgb, Some(Rgb::new(30, 20, 30 ^ 20)));
 	}
 }
@@ -0,0 +1,111 @@
+use std::{fs, path::PathBuf, thread, time::Duration};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+
+use crate::external_command;
+#[cfg(target_os = "macos")]
+use crate::overlay::output;
+
+const ALT_TEXT_TIMEOUT: Duration = Duration::from_secs(30);
+const ALT_TEXT_MAX_CHARS: usize = 1_000;
+
+/// Where a suggested caption is delivered once the hook command returns.
+#[derive(Debug)]
+pub(super) enum AltTextDestination {
+	/// Added as plain text to the pasteboard item holding the copied image, unless the user has
+	/// copied something else in the meantime.
+	#[cfg(target_os = "macos")]
+	Pasteboard { change_count: isize },
+	/// Written next to the saved image as `<name>.alt.txt`.
+	Sidecar(PathBuf),
+}
+
+/// Runs the configured captioning command on a background thread so the session can exit
+/// immediately. The command gets the PNG on stdin (and `RSNAP_IMAGE_PATH` for saved files) and
+/// prints the suggested alt text to stdout.
+pub(super) fn spawn_alt_text_hook(
+	command: String,
+	png_bytes: Vec<u8>,
+	destination: AltTextDestination,
+) {
+	let spawned = thread::Builder::new().name(String::from("rsnap-alt-text")).spawn(move || {
+		let result = run_alt_text_command(&command, png_bytes, &destination)
+			.and_then(|alt_text| deliver_alt_text(&alt_text, &destination));
+
+		if let Err(err) = result {
+			tracing::warn!(
+				op = "overlay.alt_text",
+				error = %format!("{err:#}"),
+				"Alt text hook failed."
+			);
+		}
+	});
+
+	if let Err(err) = spawned {
+		tracing::warn!(op = "overlay.alt_text", error = %err, "Alt text thread spawn failed.");
+	}
+}
+
+/// Collapses whitespace and bounds the length of captioning output; `None` when it is empty.
+pub(super) fn sanitize_alt_text(raw: &str) -> Option<String> {
+	let collapsed = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+
+	if collapsed.is_empty() {
+		return None;
+	}
+
+	Some(collapsed.chars().take(ALT_TEXT_MAX_CHARS).collect())
+}
+
+fn run_alt_text_command(
+	command: &str,
+	png_bytes: Vec<u8>,
+	destination: &AltTextDestination,
+) -> Result<String> {
+	let mut shell = external_command::shell_command(command);
+
+	match destination {
+		#[cfg(target_os = "macos")]
+		AltTextDestination::Pasteboard { .. } => {},
+		AltTextDestination::Sidecar(path) => {
+			shell.env("RSNAP_IMAGE_PATH", path);
+		},
+	}
+
+	let output = external_command::run_with_timeout(shell, Some(png_bytes), ALT_TEXT_TIMEOUT)
+		.wrap_err("Alt text command failed")?;
+	let output = String::from_utf8(output).wrap_err("Alt text command printed invalid UTF-8")?;
+
+	sanitize_alt_text(&output).ok_or_else(|| eyre::eyre!("Alt text command printed nothing"))
+}
+
+fn deliver_alt_text(alt_text: &str, destination: &AltTextDestination) -> Result<()> {
+	match destination {
+		#[cfg(target_os = "macos")]
+		AltTextDestination::Pasteboard { change_count } => {
+			if output::add_text_to_pasteboard_if_unchanged(alt_text, *change_count)? {
+				tracing::info!(op = "overlay.alt_text", "Alt text added to the copied capture.");
+			} else {
+				tracing::info!(
+					op = "overlay.alt_text",
+					"Clipboard changed before alt text was ready; dropped it."
+				);
+			}
+		},
+		AltTextDestination::Sidecar(path) => {
+			let sidecar = path.with_extension("alt.txt");
+
+			fs::write(&sidecar, format!("{alt_text}\n")).wrap_err_with(|| {
+				format!("Failed to write alt text sidecar: {}", sidecar.display())
+			})?;
+
+			tracing::info!(
+				op = "overlay.alt_text",
+				path = %sidecar.display(),
+				"Alt text written next to the capture."
+			);
+		},
+	}
+
+	Ok(())
+}
@@ -0,0 +1,216 @@
+use egui::{Color32, ColorImage, Id, LayerId, Order, Pos2, Rect, TextureHandle, TextureOptions};
+
+use crate::callout;
+use crate::highlighter;
+use crate::overlay::{
+	MonitorRect, OverlayControl, OverlaySession, OverlayState, RectPoints, WindowRenderer,
+};
+use crate::stamp;
+
+impl OverlaySession {
+	pub(super) fn undo_annotation(&mut self) -> OverlayControl {
+		if self.state.annotations.undo() {
+			self.annotations_changed();
+		}
+
+		OverlayControl::Continue
+	}
+
+	pub(super) fn redo_annotation(&mut self) -> OverlayControl {
+		if self.state.annotations.redo() {
+			self.annotations_changed();
+		}
+
+		OverlayControl::Continue
+	}
+
+	/// Refreshes the export preview and the frozen monitor after stamps or callouts change.
+	pub(super) fn annotations_changed(&mut self) {
+		self.request_export_preview();
+
+		if let Some(monitor) = self.state.monitor {
+			self.request_redraw_for_monitor(monitor);
+		}
+	}
+}
+
+impl WindowRenderer {
+	/// Draws the highlights, stamps, and callouts over the frozen selection, in export order.
+	pub(super) fn render_annotations(
+		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
+	) {
+		Self::render_highlights(ctx, state, monitor);
+		Self::render_stamps(ctx, state, monitor);
+		Self::render_callouts(ctx, state, monitor);
+	}
+
+	/// Draws the placed stamps over the frozen selection from the same raster the export uses, so
+	/// what is on screen is what lands in the file.
+	fn render_stamps(ctx: &egui::Context, state: &OverlayState, monitor: MonitorRect) {
+		if state.annotations.stamps.is_empty() {
+			return;
+		}
+
+		let capture_rect = state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let scale = monitor.scale_factor();
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-stamps-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+
+		for placed in &state.annotations.stamps {
+			let texture_id = Id::new(("frozen-stamp", placed.kind, placed.size_px));
+			let texture =
+				ctx.data(|data| data.get_temp::<TextureHandle>(texture_id)).or_else(|| {
+					let image = stamp::render_stamp(placed.kind, placed.size_px)?;
+					let texture = ctx.load_texture(
+						format!("frozen-stamp-{:?}-{}", placed.kind, placed.size_px),
+						ColorImage::from_rgba_unmultiplied(
+							[image.width() as usize, image.height() as usize],
+							image.as_raw(),
+						),
+						TextureOptions::LINEAR,
+					);
+
+					ctx.data_mut(|data| data.insert_temp(texture_id, texture.clone()));
+
+					Some(texture)
+				});
+			let Some(texture) = texture else {
+				continue;
+			};
+			let size = texture.size_vec2() / scale;
+			let center = Pos2::new(
+				capture_rect.x as f32 + placed.center_px.0 as f32 / scale,
+				capture_rect.y as f32 + placed.center_px.1 as f32 / scale,
+			);
+
+			painter.image(
+				texture.id(),
+				Rect::from_center_size(center, size),
+				Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+				Color32::WHITE,
+			);
+		}
+	}
+
+	/// Draws the highlight strokes over the frozen selection. egui cannot multiply-blend, so each
+	/// stroke's texture already holds the multiplied pixels and only its coverage is alpha-blended.
+	fn render_highlights(ctx: &egui::Context, state: &OverlayState, monitor: MonitorRect) {
+		let strokes = &state.annotations.highlights;
+		let Some(frozen) = state.frozen_image.as_ref().filter(|_| !strokes.is_empty()) else {
+			return;
+		};
+		let capture_rect = state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let selection_px = monitor.local_rect_to_pixels(capture_rect);
+		let scale = monitor.scale_factor();
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-highlights-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+
+		for index in 0..strokes.len() {
+			let slot_id = Id::new(("frozen-highlight", index));
+			// Earlier strokes show through, so they are part of this texture's content.
+			let content_id = Id::new((
+				(selection_px.x, selection_px.y, selection_px.width, selection_px.height),
+				&strokes[..=index],
+			));
+			let cached = ctx
+				.data(|data| data.get_temp::<(Id, TextureHandle, (i64, i64))>(slot_id))
+				.filter(|(cached_id, _, _)| *cached_id == content_id);
+			let texture = cached.map(|(_, texture, origin)| (texture, origin)).or_else(|| {
+				let (image, origin) =
+					highlighter::render_highlight(frozen, selection_px, strokes, index)?;
+				let texture = ctx.load_texture(
+					format!("frozen-highlight-{index}"),
+					ColorImage::from_rgba_unmultiplied(
+						[image.width() as usize, image.height() as usize],
+						image.as_raw(),
+					),
+					TextureOptions::NEAREST,
+				);
+
+				ctx.data_mut(|data| {
+					data.insert_temp(slot_id, (content_id, texture.clone(), origin));
+				});
+
+				Some((texture, origin))
+			});
+			let Some((texture, (left, top))) = texture else {
+				continue;
+			};
+			let min = Pos2::new(
+				capture_rect.x as f32 + left as f32 / scale,
+				capture_rect.y as f32 + top as f32 / scale,
+			);
+
+			painter.image(
+				texture.id(),
+				Rect::from_min_size(min, texture.size_vec2() / scale),
+				Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+				Color32::WHITE,
+			);
+		}
+	}
+
+	/// Draws the callouts over the frozen selection from the export raster, with a caret in the
+	/// one being edited. Each callout keeps one texture, replaced whenever its content changes.
+	fn render_callouts(ctx: &egui::Context, state: &OverlayState, monitor: MonitorRect) {
+		if state.annotations.callouts.is_empty() {
+			return;
+		}
+
+		let capture_rect = state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let scale = monitor.scale_factor();
+		let layer =
+			LayerId::new(Order::Foreground, Id::new(format!("frozen-callouts-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+
+		for (index, placed) in state.annotations.callouts.iter().enumerate() {
+			let caret = state.annotations.editing == Some(index);
+			let slot_id = Id::new(("frozen-callout", index));
+			let content_id = Id::new((placed, caret));
+			let cached = ctx
+				.data(|data| data.get_temp::<(Id, TextureHandle, (i64, i64))>(slot_id))
+				.filter(|(cached_id, _, _)| *cached_id == content_id);
+			let texture = cached.map(|(_, texture, origin)| (texture, origin)).or_else(|| {
+				let (image, origin) = callout::render_callout(placed, caret)?;
+				let texture = ctx.load_texture(
+					format!("frozen-callout-{index}"),
+					ColorImage::from_rgba_unmultiplied(
+						[image.width() as usize, image.height() as usize],
+						image.as_raw(),
+					),
+					TextureOptions::LINEAR,
+				);
+
+				ctx.data_mut(|data| {
+					data.insert_temp(slot_id, (content_id, texture.clone(), origin));
+				});
+
+				Some((texture, origin))
+			});
+			let Some((texture, (left, top))) = texture else {
+				continue;
+			};
+			let min = Pos2::new(
+				capture_rect.x as f32 + left as f32 / scale,
+				capture_rect.y as f32 + top as f32 / scale,
+			);
+
+			painter.image(
+				texture.id(),
+				Rect::from_min_size(min, texture.size_vec2() / scale),
+				Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+				Color32::WHITE,
+			);
+		}
+	}
+}
@@ -0,0 +1,70 @@
+use image::{RgbaImage, imageops};
+
+use crate::backdrop::BackdropJob;
+use crate::overlay::{BackdropFill, OverlayMode, OverlaySession};
+use crate::state::RectPoints;
+
+impl OverlaySession {
+	/// Turns the share-ready backdrop on or off for the current frozen capture's exports.
+	pub(super) fn toggle_export_backdrop(&mut self) {
+		if !matches!(self.state.mode, OverlayMode::Frozen) {
+			return;
+		}
+
+		self.toolbar_state.backdrop_active = !self.toolbar_state.backdrop_active;
+		self.toolbar_state.needs_redraw = true;
+
+		self.request_export_preview();
+		self.request_redraw_all();
+	}
+
+	/// Describes the backdrop the worker should compose before encoding, if it is turned on.
+	pub(super) fn export_backdrop_job(&self) -> Option<BackdropJob> {
+		if !self.toolbar_state.backdrop_active {
+			return None;
+		}
+
+		let backdrop = self.config.export_backdrop;
+		let blur_source = match backdrop.fill {
+			BackdropFill::Blur => self.backdrop_blur_source(),
+			BackdropFill::Solid | BackdropFill::Gradient => None,
+		};
+
+		Some(BackdropJob { backdrop, blur_source })
+	}
+
+	/// Crops the frozen screen around the selection, padded by the backdrop margin, so the blur
+	/// shows what was actually behind the capture. Scroll captures have no single backdrop area.
+	fn backdrop_blur_source(&self) -> Option<RgbaImage> {
+		if self.scroll_capture.active {
+			return None;
+		}
+
+		let frozen_image = self.state.frozen_image.as_ref()?;
+		let monitor = self.state.monitor?;
+		let capture_rect = self
+			.state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let capture_rect = monitor.local_rect_to_pixels(capture_rect);
+		let padding = self.config.export_backdrop.padding_px;
+		let x = capture_rect.x.saturating_sub(padding).min(frozen_image.width());
+		let y = capture_rect.y.saturating_sub(padding).min(frozen_image.height());
+		let right = capture_rect
+			.x
+			.saturating_add(capture_rect.width)
+			.saturating_add(padding)
+			.min(frozen_image.width());
+		let bottom = capture_rect
+			.y
+			.saturating_add(capture_rect.height)
+			.saturating_add(padding)
+			.min(frozen_image.height());
+
+		if right <= x || bottom <= y {
+			return None;
+		}
+
+		Some(imageops::crop_imm(frozen_image, x, y, right - x, bottom - y).to_image())
+	}
+}
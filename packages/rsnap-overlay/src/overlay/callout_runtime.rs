@@ -0,0 +1,111 @@
+use winit::event::KeyEvent;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::callout::Callout;
+use crate::overlay::{FrozenToolbarTool, OverlayControl, OverlayMode, OverlaySession};
+use crate::state::GlobalPoint;
+
+/// Callout text size in points; scaled by the frozen monitor's scale factor.
+const CALLOUT_TEXT_SIZE_PT: f32 = 16.0;
+/// A new callout's body starts this far above and to the right of the point its tail marks.
+const CALLOUT_BODY_OFFSET_PT: f32 = 32.0;
+
+impl OverlaySession {
+	pub(super) fn callout_tool_active(&self) -> bool {
+		matches!(self.state.mode, OverlayMode::Frozen)
+			&& !self.scroll_capture.active
+			&& self.toolbar_state.selected_tool == FrozenToolbarTool::Callout
+	}
+
+	/// Starts dragging the callout body or tail under `global`, or adds a callout whose tail
+	/// points there; `false` outside the frozen selection.
+	pub(super) fn press_callout_at(&mut self, global: GlobalPoint) -> bool {
+		let Some((point, _)) = self.selection_pixel_unclipped_at(global) else {
+			return false;
+		};
+
+		if self.state.annotations.begin_callout_drag(point) {
+			self.annotations_changed();
+
+			return true;
+		}
+		if self.selection_pixel_at(global).is_none() {
+			if self.state.annotations.finish_edit() {
+				self.annotations_changed();
+			}
+
+			return false;
+		}
+
+		let scale = self.state.monitor.map_or(1.0, |monitor| monitor.scale_factor());
+		let text_size_px = (CALLOUT_TEXT_SIZE_PT * scale).round().max(1.0) as u32;
+		let offset = (CALLOUT_BODY_OFFSET_PT * scale).round() as i64;
+		let origin_px = (point.0 + offset, (point.1 - offset - 3 * i64::from(text_size_px)).max(0));
+
+		self.state.annotations.add_callout(Callout {
+			origin_px,
+			tail_px: point,
+			text: String::new(),
+			text_size_px,
+		});
+
+		tracing::debug!(op = "overlay.callout_added", point = ?point);
+
+		self.annotations_changed();
+
+		true
+	}
+
+	/// Moves the dragged callout body or tail with the pointer.
+	pub(super) fn drag_callout_to(&mut self, global: GlobalPoint) {
+		if !self.state.annotations.dragging_callout() {
+			return;
+		}
+
+		if let Some((point, _)) = self.selection_pixel_unclipped_at(global)
+			&& self.state.annotations.drag_callout_to(point)
+			&& let Some(monitor) = self.state.monitor
+		{
+			self.request_redraw_for_monitor(monitor);
+		}
+	}
+
+	/// Ends a callout drag; `false` when none was in progress.
+	pub(super) fn release_callout(&mut self) -> bool {
+		if !self.state.annotations.dragging_callout() {
+			return false;
+		}
+
+		self.state.annotations.end_callout_drag();
+		self.annotations_changed();
+
+		true
+	}
+
+	/// Types into the callout being edited. Enter and Escape finish it, Shift+Enter breaks the
+	/// line, and Cmd/Ctrl shortcuts fall through so undo still works mid-edit. `None` when no
+	/// callout is being edited.
+	pub(super) fn handle_callout_key_event(&mut self, event: &KeyEvent) -> Option<OverlayControl> {
+		self.state.annotations.editing?;
+
+		let changed = match &event.logical_key {
+			Key::Named(NamedKey::Escape) => self.state.annotations.finish_edit(),
+			Key::Named(NamedKey::Enter) if self.keyboard_modifiers.shift_key() => {
+				self.state.annotations.type_text("\n")
+			},
+			Key::Named(NamedKey::Enter) => self.state.annotations.finish_edit(),
+			Key::Named(NamedKey::Backspace) => self.state.annotations.backspace(),
+			_ if self.is_save_shortcut_pressed() => return None,
+			_ => match event.text.as_ref() {
+				Some(text) => self.state.annotations.type_text(text),
+				None => false,
+			},
+		};
+
+		if changed {
+			self.annotations_changed();
+		}
+
+		Some(OverlayControl::Continue)
+	}
+}
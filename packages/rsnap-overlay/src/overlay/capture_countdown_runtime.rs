@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+use crate::overlay::{
+	ActiveEventLoop, CaptureCountdown, CaptureMode, GlobalPoint, Key, KeyEvent, MonitorRect,
+	MonitorRectPoints, NamedKey, OverlayControl, OverlaySession, RectPoints,
+	WindowFreezeCaptureTarget,
+};
+
+impl OverlaySession {
+	/// Starts the session the configured [`CaptureMode`] asks for.
+	pub fn start_capture(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+		match self.config.capture_mode {
+			CaptureMode::ColorPick => self.start_color_picker(event_loop),
+			CaptureMode::Region | CaptureMode::Window | CaptureMode::Display => {
+				self.start(event_loop)
+			},
+		}
+	}
+
+	/// Freezes a selection made in live mode, first counting down the capture delay when one is
+	/// configured.
+	pub(super) fn begin_selected_capture(
+		&mut self,
+		monitor: MonitorRect,
+		rect: Option<RectPoints>,
+		window_target: Option<WindowFreezeCaptureTarget>,
+		cursor: Option<GlobalPoint>,
+	) {
+		if self.config.capture_delay_secs == 0 {
+			self.begin_frozen_capture_with_rect(monitor, rect, window_target, cursor);
+
+			return;
+		}
+
+		let delay = Duration::from_secs(u64::from(self.config.capture_delay_secs));
+
+		tracing::info!(
+			op = "overlay.capture_countdown",
+			monitor_id = monitor.id,
+			delay_secs = self.config.capture_delay_secs,
+			"Capture countdown started."
+		);
+
+		self.capture_countdown = Some(CaptureCountdown {
+			fires_at: Instant::now() + delay,
+			monitor,
+			rect,
+			window_target,
+			cursor,
+		});
+		self.state.drag_rect = None;
+		self.state.hovered_window_rect = None;
+
+		// Let clicks reach the apps underneath, so menus and hover states can be set up.
+		self.set_capture_windows_hittest(false);
+		self.maybe_tick_capture_countdown(Instant::now());
+	}
+
+	/// Updates the seconds shown and freezes the selection once the delay has run out.
+	pub(super) fn maybe_tick_capture_countdown(&mut self, now: Instant) {
+		let Some(countdown) = self.capture_countdown else {
+			return;
+		};
+
+		if now >= countdown.fires_at {
+			self.capture_countdown = None;
+			self.state.capture_countdown = None;
+
+			self.set_capture_windows_hittest(true);
+			self.begin_frozen_capture_with_rect(
+				countdown.monitor,
+				countdown.rect,
+				countdown.window_target,
+				countdown.cursor,
+			);
+
+			return;
+		}
+
+		let remaining = countdown.fires_at.duration_since(now);
+		let remaining_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+		let region = MonitorRectPoints {
+			monitor_id: countdown.monitor.id,
+			rect: countdown.rect.unwrap_or(RectPoints::new(
+				0,
+				0,
+				countdown.monitor.width,
+				countdown.monitor.height,
+			)),
+		};
+		let shown = Some((region, u32::try_from(remaining_secs).unwrap_or(u32::MAX)));
+
+		if self.state.capture_countdown != shown {
+			self.state.capture_countdown = shown;
+
+			self.request_redraw_for_monitor(countdown.monitor);
+		}
+	}
+
+	/// Esc drops the pending selection and returns to live selection; other keys wait.
+	pub(super) fn handle_capture_countdown_key_event(
+		&mut self,
+		event: &KeyEvent,
+	) -> OverlayControl {
+		if event.logical_key == Key::Named(NamedKey::Escape) {
+			self.cancel_capture_countdown();
+		}
+
+		OverlayControl::Continue
+	}
+
+	fn cancel_capture_countdown(&mut self) {
+		let Some(countdown) = self.capture_countdown.take() else {
+			return;
+		};
+
+		self.state.capture_countdown = None;
+
+		self.set_capture_windows_hittest(true);
+		self.request_redraw_for_monitor(countdown.monitor);
+
+		tracing::info!(op = "overlay.capture_countdown", "Capture countdown cancelled.");
+	}
+
+	fn set_capture_windows_hittest(&self, hittest: bool) {
+		for overlay_window in self.windows.values() {
+			let _ = overlay_window.window.set_cursor_hittest(hittest);
+		}
+	}
+}
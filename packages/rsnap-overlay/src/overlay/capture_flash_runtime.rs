@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use crate::overlay::{
+	CaptureFlash, MonitorRectPoints, OverlayControl, OverlayExit, OverlayMode, OverlaySession,
+	RectPoints,
+};
+
+/// How long the captured region stays washed white before the overlay closes.
+const CAPTURE_FLASH_DURATION: Duration = Duration::from_millis(180);
+/// Opacity of the wash when it starts; it fades to nothing over [`CAPTURE_FLASH_DURATION`].
+const CAPTURE_FLASH_PEAK_ALPHA: u8 = 200;
+
+impl OverlaySession {
+	/// Ends a finished export, first flashing the captured region when that is configured.
+	///
+	/// Direct captures never showed a selection, so they exit right away.
+	pub(super) fn finish_export(&mut self, exit: OverlayExit) -> OverlayControl {
+		let region = self.state.monitor.map(|monitor| MonitorRectPoints {
+			monitor_id: monitor.id,
+			rect: self.state.frozen_capture_rect.unwrap_or(RectPoints::new(
+				0,
+				0,
+				monitor.width,
+				monitor.height,
+			)),
+		});
+		let Some(region) = region.filter(|_| {
+			self.config.capture_flash
+				&& !self.direct_capture
+				&& matches!(self.state.mode, OverlayMode::Frozen)
+		}) else {
+			return self.exit(exit);
+		};
+
+		self.capture_flash = Some(CaptureFlash { started_at: Instant::now(), region, exit });
+		self.state.capture_flash = Some((region, CAPTURE_FLASH_PEAK_ALPHA));
+
+		self.set_toolbar_window_hidden();
+		self.hide_loupe_window();
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	/// Fades the flash and reports the held export once it has run its course.
+	pub(super) fn maybe_tick_capture_flash(&mut self, now: Instant) -> Option<OverlayControl> {
+		let flash = self.capture_flash.as_ref()?;
+		let elapsed = now.saturating_duration_since(flash.started_at);
+
+		if elapsed >= CAPTURE_FLASH_DURATION {
+			let flash = self.capture_flash.take()?;
+
+			return Some(self.exit(flash.exit));
+		}
+
+		let remaining = 1.0 - elapsed.as_secs_f32() / CAPTURE_FLASH_DURATION.as_secs_f32();
+		let alpha = (f32::from(CAPTURE_FLASH_PEAK_ALPHA) * remaining).round() as u8;
+
+		self.state.capture_flash = Some((flash.region, alpha));
+
+		self.request_redraw_all();
+
+		None
+	}
+}
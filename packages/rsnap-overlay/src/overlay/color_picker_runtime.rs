@@ -0,0 +1,179 @@
+#[cfg(not(target_os = "macos"))]
+use device_query::{DeviceQuery, Keycode};
+#[cfg(target_os = "macos")]
+use objc2_core_graphics::{CGEventSource, CGEventSourceStateID};
+
+use crate::overlay::{
+	ActiveEventLoop, Key, KeyEvent, NamedKey, OverlayControl, OverlayExit, OverlaySession,
+	nonactivating_window, output,
+};
+
+#[cfg(target_os = "macos")]
+const MACOS_KEY_CODE_C: u16 = 8;
+#[cfg(target_os = "macos")]
+const MACOS_KEY_CODE_RETURN: u16 = 36;
+#[cfg(target_os = "macos")]
+const MACOS_KEY_CODE_SPACE: u16 = 49;
+#[cfg(target_os = "macos")]
+const MACOS_KEY_CODE_ESCAPE: u16 = 53;
+#[cfg(target_os = "macos")]
+const MACOS_KEY_CODE_KEYPAD_ENTER: u16 = 76;
+
+/// Keys the picker still answers while its windows never hold keyboard focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ColorPickerKey {
+	Cancel,
+	Pick,
+	Compare,
+}
+
+impl OverlaySession {
+	/// Starts the session as an eyedropper instead of a capture overlay.
+	///
+	/// The picker shows only the HUD and loupe; a click (or Enter/Space) copies the hovered color
+	/// to the clipboard and ends the session without ever freezing or exporting a capture. `C`
+	/// holds the hovered color as color A, and the HUD then compares it with the color under the
+	/// cursor as a CIEDE2000 ΔE. Its windows float as non-activating panels, so a full-screen app
+	/// on another Space stays frontmost while it is sampled.
+	pub fn start_color_picker(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		self.color_picker_active = true;
+
+		if let Err(err) = self.start(event_loop) {
+			self.color_picker_active = false;
+
+			return Err(err);
+		}
+
+		self.state.color_picker = true;
+		// A key already down when the picker opens must be released before it counts.
+		self.color_picker_held_key = self.held_color_picker_key();
+
+		self.set_alt_held(true);
+
+		tracing::info!(op = "overlay.color_picker_start", "Entered color picker mode.");
+
+		Ok(())
+	}
+
+	/// Returns whether the running session is a color picker rather than a capture overlay.
+	#[must_use]
+	pub fn is_color_picker(&self) -> bool {
+		self.color_picker_active
+	}
+
+	pub(super) fn handle_color_picker_key_event(&mut self, event: &KeyEvent) -> OverlayControl {
+		match &event.logical_key {
+			Key::Named(NamedKey::Escape) => self.exit(OverlayExit::Cancelled),
+			Key::Named(NamedKey::Enter | NamedKey::Space | NamedKey::Tab) => self.pick_color(),
+			Key::Character(key_text) if matches!(key_text.as_str(), "+" | "=") => {
+				self.step_loupe_zoom(1)
+			},
+			Key::Character(key_text) if matches!(key_text.as_str(), "-" | "_") => {
+				self.step_loupe_zoom(-1)
+			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("u") => {
+				self.state.hud_unit = self.state.hud_unit.next();
+
+				self.request_redraw_all();
+
+				OverlayControl::Continue
+			},
+			_ => OverlayControl::Continue,
+		}
+	}
+
+	/// Floats every picker window above full-screen apps without activating rsnap.
+	pub(super) fn float_color_picker_windows(&self) {
+		let windows = self
+			.windows
+			.values()
+			.map(|overlay_window| overlay_window.window.as_ref())
+			.chain(self.hud_window.iter().map(|hud_window| hud_window.window.as_ref()))
+			.chain(self.loupe_window.iter().map(|loupe_window| loupe_window.window.as_ref()));
+
+		for window in windows {
+			nonactivating_window::float_without_activation(window);
+		}
+	}
+
+	/// Reads Escape/Enter/Space/C from the global key state; the picker's windows never take
+	/// focus, so these keys would otherwise keep going to the app underneath.
+	pub(super) fn poll_color_picker_keys(&mut self) -> Option<OverlayControl> {
+		if !self.color_picker_active || !self.is_active() {
+			return None;
+		}
+
+		let held = self.held_color_picker_key();
+		let pressed = held.filter(|key| self.color_picker_held_key != Some(*key));
+
+		self.color_picker_held_key = held;
+
+		match pressed? {
+			ColorPickerKey::Cancel => Some(self.exit(OverlayExit::Cancelled)),
+			ColorPickerKey::Pick => match self.pick_color() {
+				OverlayControl::Continue => None,
+				control => Some(control),
+			},
+			ColorPickerKey::Compare => {
+				self.toggle_reference_sample();
+
+				None
+			},
+		}
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	fn held_color_picker_key(&self) -> Option<ColorPickerKey> {
+		let keys = self.cursor_device.as_ref()?.get_keys();
+
+		if keys.contains(&Keycode::Escape) {
+			Some(ColorPickerKey::Cancel)
+		} else if keys
+			.iter()
+			.any(|key| matches!(key, Keycode::Enter | Keycode::NumpadEnter | Keycode::Space))
+		{
+			Some(ColorPickerKey::Pick)
+		} else if keys.contains(&Keycode::C) {
+			Some(ColorPickerKey::Compare)
+		} else {
+			None
+		}
+	}
+
+	#[cfg(target_os = "macos")]
+	fn held_color_picker_key(&self) -> Option<ColorPickerKey> {
+		let down = |key_code| {
+			CGEventSource::key_state(CGEventSourceStateID::CombinedSessionState, key_code)
+		};
+
+		if down(MACOS_KEY_CODE_ESCAPE) {
+			Some(ColorPickerKey::Cancel)
+		} else if [MACOS_KEY_CODE_RETURN, MACOS_KEY_CODE_KEYPAD_ENTER, MACOS_KEY_CODE_SPACE]
+			.into_iter()
+			.any(down)
+		{
+			Some(ColorPickerKey::Pick)
+		} else if down(MACOS_KEY_CODE_C) {
+			Some(ColorPickerKey::Compare)
+		} else {
+			None
+		}
+	}
+
+	/// Copies the sampled color under the cursor and ends the session.
+	pub(super) fn pick_color(&mut self) -> OverlayControl {
+		let Some(rgb) = self.state.rgb else {
+			return OverlayControl::Continue;
+		};
+		let text = self.state.color_format.format(rgb);
+
+		match output::write_text_to_clipboard(&text) {
+			Ok(()) => self.exit(OverlayExit::ColorCopied(text)),
+			Err(err) => self.exit(OverlayExit::Error(format!("{err:#}"))),
+		}
+	}
+}
@@ -0,0 +1,18 @@
+use crate::overlay::{OverlayControl, OverlaySession};
+
+impl OverlaySession {
+	/// Steps the frozen preview to the next color vision simulation. Exports are unaffected.
+	pub(super) fn cycle_color_vision_filter(&mut self) -> OverlayControl {
+		self.state.color_vision = self.state.color_vision.next();
+
+		tracing::debug!(
+			op = "overlay.color_vision_filter",
+			filter = self.state.color_vision.label(),
+			"Color vision simulation changed."
+		);
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+}
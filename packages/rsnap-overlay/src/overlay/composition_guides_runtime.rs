@@ -0,0 +1,82 @@
+use egui::{Color32, Painter, Pos2, Rect, Stroke};
+
+use crate::overlay::{CompositionGuides, OverlayControl, OverlayMode, OverlaySession};
+
+/// Action-safe and title-safe insets, as fractions of the selection size.
+const SAFE_MARGIN_INSETS: [f32; 2] = [0.05, 0.1];
+/// Selections smaller than this in either direction draw no guides.
+const COMPOSITION_GUIDES_MIN_SIDE_PX: f32 = 24.0;
+
+impl OverlaySession {
+	/// Shows or hides the composition guides inside a live drag selection.
+	pub(super) fn toggle_composition_guides(&mut self) -> OverlayControl {
+		if !matches!(self.state.mode, OverlayMode::Live) {
+			return OverlayControl::Continue;
+		}
+
+		self.state.composition_guides.visible = !self.state.composition_guides.visible;
+
+		tracing::debug!(
+			op = "overlay.composition_guides",
+			visible = self.state.composition_guides.visible,
+			"Composition guides toggled."
+		);
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+}
+
+/// Line segments for the enabled guides inside `rect`.
+pub(super) fn composition_guide_segments(guides: CompositionGuides, rect: Rect) -> Vec<[Pos2; 2]> {
+	let mut segments = Vec::new();
+
+	if !guides.visible
+		|| rect.width() < COMPOSITION_GUIDES_MIN_SIDE_PX
+		|| rect.height() < COMPOSITION_GUIDES_MIN_SIDE_PX
+	{
+		return segments;
+	}
+
+	if guides.thirds {
+		for fraction in [1.0 / 3.0, 2.0 / 3.0] {
+			let x = rect.left() + rect.width() * fraction;
+			let y = rect.top() + rect.height() * fraction;
+
+			segments.push([Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())]);
+			segments.push([Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)]);
+		}
+	}
+	if guides.center {
+		let center = rect.center();
+		let arm = rect.width().min(rect.height()) * 0.06;
+
+		segments.push([Pos2::new(center.x - arm, center.y), Pos2::new(center.x + arm, center.y)]);
+		segments.push([Pos2::new(center.x, center.y - arm), Pos2::new(center.x, center.y + arm)]);
+	}
+	if guides.safe_margins {
+		for inset in SAFE_MARGIN_INSETS {
+			let inner = rect.shrink2(rect.size() * inset);
+
+			segments.push([inner.left_top(), inner.right_top()]);
+			segments.push([inner.right_top(), inner.right_bottom()]);
+			segments.push([inner.right_bottom(), inner.left_bottom()]);
+			segments.push([inner.left_bottom(), inner.left_top()]);
+		}
+	}
+
+	segments
+}
+
+/// Draws the guides as thin light lines over a dark halo, so they read on any content.
+pub(super) fn paint_composition_guides(painter: &Painter, guides: CompositionGuides, rect: Rect) {
+	let segments = composition_guide_segments(guides, rect);
+
+	for segment in &segments {
+		painter.line_segment(*segment, Stroke::new(3.0, Color32::from_black_alpha(70)));
+	}
+	for segment in &segments {
+		painter.line_segment(*segment, Stroke::new(1.0, Color32::from_white_alpha(170)));
+	}
+}
@@ -0,0 +1,305 @@
+use std::path::PathBuf;
+
+use crate::app_windows::AppWindows;
+use crate::backend;
+use crate::focused_window;
+use crate::overlay::{
+	FreezeCaptureTarget, FrozenCaptureSource, MonitorRect, MonitorRectPoints, OverlayConfig,
+	OverlayControl, OverlayExit, OverlayMode, OverlaySession, PngAction,
+	WINDOW_CAPTURE_MATTE_DARK_RGBA, WINDOW_CAPTURE_MATTE_LIGHT_RGBA, WindowCaptureAlphaMode,
+	WindowFreezeCaptureTarget, WindowShadowMode, image_helpers, output,
+};
+use crate::png;
+use crate::state::{RectPoints, WindowRect};
+
+impl OverlaySession {
+	/// Captures the focused window and runs the Done pipeline on it without creating any overlay
+	/// window. The session exits through [`Self::about_to_wait`] once the export finishes or fails.
+	pub fn start_focused_window_capture(&mut self) -> Result<(), String> {
+		if self.is_active() || self.direct_capture {
+			return Ok(());
+		}
+
+		let window = focused_window::focused_window()
+			.map_err(|err| format!("Failed to look up the focused window: {err:#}"))?
+			.ok_or_else(|| String::from("No focused window to capture"))?;
+		let monitors = Self::available_overlay_monitors()?;
+
+		self.reset_for_start();
+		self.ensure_capture_pipeline();
+
+		self.begin_focused_window_capture(&monitors, window)
+	}
+
+	/// Freezes `window` on the display showing most of it and sends the window capture straight
+	/// to the worker; with no overlay on screen there is nothing to hide first.
+	pub(super) fn begin_focused_window_capture(
+		&mut self,
+		monitors: &[MonitorRect],
+		window: WindowRect,
+	) -> Result<(), String> {
+		let window_id =
+			window.window_id.ok_or_else(|| String::from("The focused window has no id"))?;
+		let (monitor, rect) = monitors
+			.iter()
+			.filter_map(|monitor| {
+				let rect = monitor.clip_global_rect_i64(
+					window.x,
+					window.y,
+					window.x.saturating_add(window.width),
+					window.y.saturating_add(window.height),
+				)?;
+
+				Some((*monitor, rect))
+			})
+			.max_by_key(|(_, rect)| u64::from(rect.width) * u64::from(rect.height))
+			.ok_or_else(|| String::from("The focused window is not on any display"))?;
+		let target = WindowFreezeCaptureTarget { monitor, window_id, rect };
+
+		tracing::info!(
+			op = "overlay.focused_window_capture",
+			window_id,
+			monitor_id = monitor.id,
+			rect = ?rect,
+			"Capturing the focused window."
+		);
+
+		self.direct_capture = true;
+
+		self.begin_frozen_capture_with_rect(monitor, Some(rect), Some(target), None);
+
+		let freeze_target = FreezeCaptureTarget::Window {
+			window_id,
+			keep_shadow: self.config.window_shadow_mode == WindowShadowMode::Keep,
+		};
+		let sent = self
+			.worker
+			.as_ref()
+			.is_some_and(|worker| worker.request_freeze_capture(monitor, freeze_target));
+
+		if !sent {
+			self.direct_capture = false;
+
+			return Err(String::from("Capture worker unavailable"));
+		}
+
+		self.pending_freeze_capture = None;
+		self.pending_freeze_capture_armed = false;
+		self.pending_window_freeze_capture = None;
+		self.inflight_window_freeze_capture = Some(target);
+
+		Ok(())
+	}
+
+	/// Captures `region` and runs the Done pipeline on it without creating any overlay window,
+	/// like [`Self::start_focused_window_capture`].
+	pub fn start_region_capture(&mut self, region: MonitorRectPoints) -> Result<(), String> {
+		if self.is_active() || self.direct_capture {
+			return Ok(());
+		}
+
+		let monitors = Self::available_overlay_monitors()?;
+
+		self.reset_for_start();
+		self.ensure_capture_pipeline();
+
+		self.begin_region_capture(&monitors, region)
+	}
+
+	/// Captures `region` with the configured backend and saves it to the configured output
+	/// directory, without a session or any window. Backs `rsnap capture --preset`.
+	pub fn capture_region_to_output_dir(
+		config: &OverlayConfig,
+		region: MonitorRectPoints,
+	) -> Result<PathBuf, String> {
+		let (monitor, rect) = locate_region(&Self::available_overlay_monitors()?, region)?;
+		let image = backend::capture_backend(config.capture_backend)
+			.capture_monitor_region(monitor, monitor.local_rect_to_pixels(rect))
+			.map_err(|err| format!("Failed to capture the region: {err:#}"))?;
+		let png_bytes = png::rgba_image_to_png_bytes(&image)
+			.map_err(|err| format!("Failed to encode the capture: {err:#}"))?;
+
+		output::save_png_bytes_to_configured_dir(&png_bytes, config)
+			.map_err(|err| format!("Failed to save the capture: {err:#}"))
+	}
+
+	/// Captures each window of `app` separately with the configured backend and saves them side by
+	/// side in the output directory, front to back, trimmed and matted like single-window
+	/// exports. Windows that fail to capture are skipped. Backs `rsnap capture --app` and the
+	/// tray's Capture App Windows menu.
+	pub fn capture_app_windows_to_output_dir(
+		config: &OverlayConfig,
+		app: &AppWindows,
+	) -> Result<Vec<PathBuf>, String> {
+		let mut backend = backend::capture_backend(config.capture_backend);
+		let keep_shadow = config.window_shadow_mode == WindowShadowMode::Keep;
+		let mut png_set = Vec::with_capacity(app.windows.len());
+
+		for window_id in app.windows.iter().filter_map(|window| window.window_id) {
+			let image = if keep_shadow {
+				backend.capture_window_with_shadow(window_id)
+			} else {
+				backend.capture_window(window_id)
+			};
+			let image = match image {
+				Ok(image) if keep_shadow => image,
+				Ok(image) => image_helpers::trim_window_image(&image),
+				Err(err) => {
+					tracing::warn!(
+						window_id,
+						error = %format!("{err:#}"),
+						"Skipped an app window that failed to capture."
+					);
+
+					continue;
+				},
+			};
+			let image = match config.window_capture_alpha_mode {
+				WindowCaptureAlphaMode::Background => image,
+				WindowCaptureAlphaMode::MatteLight => {
+					Self::flatten_window_image_with_matte(&image, WINDOW_CAPTURE_MATTE_LIGHT_RGBA)
+				},
+				WindowCaptureAlphaMode::MatteDark => {
+					Self::flatten_window_image_with_matte(&image, WINDOW_CAPTURE_MATTE_DARK_RGBA)
+				},
+			};
+
+			png_set.push(
+				png::rgba_image_to_png_bytes(&image)
+					.map_err(|err| format!("Failed to encode the capture: {err:#}"))?,
+			);
+		}
+
+		if png_set.is_empty() {
+			return Err(format!("No window of {} could be captured", app.name));
+		}
+
+		output::save_png_set_to_configured_dir(&png_set, &app.name, config)
+			.map_err(|err| format!("Failed to save the captures: {err:#}"))
+	}
+
+	/// Freezes the monitor holding `region` and crops the capture to it.
+	pub(super) fn begin_region_capture(
+		&mut self,
+		monitors: &[MonitorRect],
+		region: MonitorRectPoints,
+	) -> Result<(), String> {
+		let (monitor, rect) = locate_region(monitors, region)?;
+
+		tracing::info!(
+			op = "overlay.region_capture",
+			monitor_id = monitor.id,
+			rect = ?rect,
+			"Capturing a fixed region."
+		);
+
+		self.direct_capture = true;
+
+		self.begin_frozen_capture_with_rect(monitor, Some(rect), None, None);
+
+		let sent = self.worker.as_ref().is_some_and(|worker| {
+			worker.request_freeze_capture(monitor, FreezeCaptureTarget::Monitor)
+		});
+
+		if !sent {
+			self.direct_capture = false;
+
+			return Err(String::from("Capture worker unavailable"));
+		}
+
+		self.pending_freeze_capture = None;
+		self.pending_freeze_capture_armed = false;
+		self.pending_window_freeze_capture = None;
+
+		Ok(())
+	}
+
+	/// Replaces the frozen selection with the most recent earlier capture on the same display.
+	pub(super) fn reselect_last_capture_region(&mut self) {
+		if !matches!(self.state.mode, OverlayMode::Frozen) || self.scroll_capture.active {
+			return;
+		}
+
+		let Some(monitor) = self.state.monitor else {
+			return;
+		};
+		let Some(rect) = self
+			.config
+			.recent_capture_regions
+			.iter()
+			.find(|region| region.monitor_id == monitor.id)
+			.and_then(|region| clip_region_to_monitor(region.rect, monitor))
+		else {
+			self.state.set_error("No earlier capture on this display.");
+			self.request_redraw_for_monitor(monitor);
+
+			return;
+		};
+
+		self.state.clear_error();
+
+		self.frozen_capture_source = FrozenCaptureSource::DragRegion;
+		self.frozen_window_image = None;
+		self.state.frozen_capture_rect = Some(rect);
+		self.toolbar_state.floating_position = None;
+		self.toolbar_state.needs_redraw = true;
+		self.toolbar_state.layout_last_screen_size_points = None;
+		self.toolbar_state.layout_stable_frames = 0;
+
+		self.request_selection_histogram();
+		self.request_export_preview();
+		self.request_redraw_all();
+	}
+
+	/// Exports the capture as soon as it arrives.
+	pub(super) fn maybe_finish_direct_capture_freeze(&mut self) {
+		if !self.direct_capture {
+			return;
+		}
+
+		self.begin_png_action(PngAction::Done);
+
+		if self.pending_png_action.is_none() {
+			self.state.set_error("The capture came back empty.");
+		}
+	}
+
+	/// Ends a direct capture whose freeze or export failed, since there is no overlay to show the
+	/// error on.
+	pub(super) fn direct_capture_failure(&mut self) -> Option<OverlayControl> {
+		if !self.direct_capture || self.pending_png_action.is_some() {
+			return None;
+		}
+
+		let message = self.state.error_message.clone()?;
+
+		tracing::warn!(op = "overlay.direct_capture", error = %message, "Capture failed.");
+
+		Some(self.exit(OverlayExit::Error(message)))
+	}
+}
+
+/// Finds the monitor holding `region` and clips the region to it.
+fn locate_region(
+	monitors: &[MonitorRect],
+	region: MonitorRectPoints,
+) -> Result<(MonitorRect, RectPoints), String> {
+	let monitor = monitors
+		.iter()
+		.copied()
+		.find(|monitor| monitor.id == region.monitor_id)
+		.ok_or_else(|| format!("Display {} is not connected", region.monitor_id))?;
+	let rect = clip_region_to_monitor(region.rect, monitor)
+		.ok_or_else(|| String::from("The region is off the display"))?;
+
+	Ok((monitor, rect))
+}
+
+/// Trims `rect` (monitor-local points) to `monitor`, e.g. after the display's resolution changed.
+fn clip_region_to_monitor(rect: RectPoints, monitor: MonitorRect) -> Option<RectPoints> {
+	let right = rect.x.saturating_add(rect.width).min(monitor.width);
+	let bottom = rect.y.saturating_add(rect.height).min(monitor.height);
+
+	(rect.x < right && rect.y < bottom)
+		.then(|| RectPoints::new(rect.x, rect.y, right - rect.x, bottom - rect.y))
+}
@@ -0,0 +1,187 @@
+use std::time::{Duration, Instant};
+
+use crate::overlay::{
+	ActiveEventLoop, Key, KeyCode, KeyEvent, MonitorRect, NamedKey, OverlayControl, OverlayExit,
+	OverlayMode, OverlaySession, PhysicalKey,
+};
+
+/// Digit keys address at most this many displays.
+const DISPLAY_PICKER_MAX_DISPLAYS: usize = 9;
+/// How long the identify-displays badges stay up before the overlay closes itself.
+const DISPLAY_IDENTIFY_DURATION: Duration = Duration::from_millis(2_500);
+
+impl OverlaySession {
+	/// Starts the capture overlay with the display picker open, so a digit key freezes a whole
+	/// display without first moving the pointer onto it.
+	pub fn start_display_picker(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		self.start(event_loop)?;
+		self.open_display_picker();
+
+		Ok(())
+	}
+
+	/// Briefly badges every display with its number, name, resolution and scale factor, then
+	/// closes; any key or click closes it early.
+	pub fn start_display_identify(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		self.start(event_loop)?;
+
+		let displays = self
+			.windows
+			.values()
+			.map(|overlay_window| {
+				let _ = overlay_window.window.set_cursor_hittest(false);
+
+				(
+					overlay_window.monitor,
+					overlay_window.window.current_monitor().and_then(|handle| handle.name()),
+				)
+			})
+			.collect();
+
+		self.open_display_identify(displays, Instant::now());
+
+		Ok(())
+	}
+
+	pub(super) fn open_display_identify(
+		&mut self,
+		displays: Vec<(MonitorRect, Option<String>)>,
+		now: Instant,
+	) {
+		self.state.display_picker =
+			display_picker_order(displays.iter().map(|(monitor, _)| *monitor));
+		self.state.display_names =
+			displays.into_iter().filter_map(|(monitor, name)| Some((monitor.id, name?))).collect();
+		self.state.identify_displays = true;
+		self.display_identify_until = Some(now + DISPLAY_IDENTIFY_DURATION);
+
+		tracing::info!(
+			op = "overlay.display_identify",
+			displays = self.state.display_picker.len(),
+			"Identifying displays."
+		);
+
+		self.request_redraw_all();
+	}
+
+	/// Ends the identify-displays session once its badges have been up long enough.
+	pub(super) fn maybe_finish_display_identify(&mut self, now: Instant) -> Option<OverlayControl> {
+		let until = self.display_identify_until?;
+
+		(now >= until).then(|| self.exit(OverlayExit::Cancelled))
+	}
+
+	/// Shows or hides the numbered display badges; only live mode has a picker.
+	pub(super) fn toggle_display_picker(&mut self) -> OverlayControl {
+		if self.state.display_picker.is_empty() {
+			self.open_display_picker();
+		} else {
+			self.close_display_picker();
+		}
+
+		OverlayControl::Continue
+	}
+
+	fn open_display_picker(&mut self) {
+		if !matches!(self.state.mode, OverlayMode::Live) {
+			return;
+		}
+
+		let monitors = display_picker_order(self.windows.values().map(|window| window.monitor));
+
+		tracing::info!(
+			op = "overlay.display_picker",
+			displays = monitors.len(),
+			"Display picker opened."
+		);
+
+		self.state.display_picker = monitors;
+
+		self.request_redraw_all();
+	}
+
+	fn close_display_picker(&mut self) {
+		self.state.display_picker.clear();
+
+		self.request_redraw_all();
+	}
+
+	pub(super) fn handle_display_picker_key_event(&mut self, event: &KeyEvent) -> OverlayControl {
+		if self.state.identify_displays {
+			return self.exit(OverlayExit::Cancelled);
+		}
+		if let Some(index) = display_picker_index(event.physical_key) {
+			return self.pick_display(index);
+		}
+
+		match &event.logical_key {
+			Key::Named(NamedKey::Escape) => {
+				self.close_display_picker();
+
+				OverlayControl::Continue
+			},
+			Key::Character(key_text) if key_text.as_str().eq_ignore_ascii_case("d") => {
+				self.toggle_display_picker()
+			},
+			_ => OverlayControl::Continue,
+		}
+	}
+
+	/// Freezes the whole display behind badge `index + 1`, wherever the pointer is.
+	pub(super) fn pick_display(&mut self, index: usize) -> OverlayControl {
+		let Some(monitor) = self.state.display_picker.get(index).copied() else {
+			return OverlayControl::Continue;
+		};
+
+		tracing::info!(
+			op = "overlay.display_picker",
+			badge = index + 1,
+			monitor_id = monitor.id,
+			"Display picked."
+		);
+
+		self.close_display_picker();
+		self.begin_selected_capture(monitor, None, None, None);
+
+		OverlayControl::Continue
+	}
+}
+
+/// Numbers displays left to right, then top to bottom, keeping the first nine.
+pub(super) fn display_picker_order(
+	monitors: impl Iterator<Item = MonitorRect>,
+) -> Vec<MonitorRect> {
+	let mut monitors = monitors.collect::<Vec<_>>();
+
+	monitors.sort_by_key(|monitor| (monitor.origin.x, monitor.origin.y, monitor.id));
+	monitors.truncate(DISPLAY_PICKER_MAX_DISPLAYS);
+
+	monitors
+}
+
+fn display_picker_index(physical_key: PhysicalKey) -> Option<usize> {
+	let PhysicalKey::Code(code) = physical_key else {
+		return None;
+	};
+
+	match code {
+		KeyCode::Digit1 | KeyCode::Numpad1 => Some(0),
+		KeyCode::Digit2 | KeyCode::Numpad2 => Some(1),
+		KeyCode::Digit3 | KeyCode::Numpad3 => Some(2),
+		KeyCode::Digit4 | KeyCode::Numpad4 => Some(3),
+		KeyCode::Digit5 | KeyCode::Numpad5 => Some(4),
+		KeyCode::Digit6 | KeyCode::Numpad6 => Some(5),
+		KeyCode::Digit7 | KeyCode::Numpad7 => Some(6),
+		KeyCode::Digit8 | KeyCode::Numpad8 => Some(7),
+		KeyCode::Digit9 | KeyCode::Numpad9 => Some(8),
+		_ => None,
+	}
+}
@@ -0,0 +1,40 @@
+use crate::overlay::{OverlayMode, OverlaySession};
+use crate::state::{GlobalPoint, MonitorRect};
+
+impl OverlaySession {
+	/// Lets clicks through the overlay while the cursor rests in a configured edge dead zone.
+	pub(super) fn sync_edge_dead_zone_passthrough(
+		&mut self,
+		monitor: MonitorRect,
+		global: GlobalPoint,
+	) {
+		let zones = self.config.edge_dead_zones;
+		let eligible = zones.is_enabled()
+			&& matches!(self.state.mode, OverlayMode::Live)
+			&& !self.left_mouse_button_down
+			&& !self.magnifier_active
+			&& !self.color_picker_active
+			&& !self.scroll_capture.active;
+
+		self.set_edge_dead_zone_passthrough(eligible && zones.contains(monitor, global));
+	}
+
+	/// Restores normal hit-testing; called whenever the overlay leaves the live hover state.
+	pub(super) fn clear_edge_dead_zone_passthrough(&mut self) {
+		self.set_edge_dead_zone_passthrough(false);
+	}
+
+	fn set_edge_dead_zone_passthrough(&mut self, passthrough: bool) {
+		if self.edge_dead_zone_passthrough == passthrough {
+			return;
+		}
+
+		self.edge_dead_zone_passthrough = passthrough;
+
+		for overlay_window in self.windows.values() {
+			let _ = overlay_window.window.set_cursor_hittest(!passthrough);
+		}
+
+		tracing::debug!(passthrough, "Edge dead zone passthrough changed.");
+	}
+}
@@ -0,0 +1,65 @@
+use image::RgbaImage;
+
+use crate::overlay::{EXPORT_PREVIEW_MAX_SIZE_PX, ExportPreview, OverlayMode, OverlaySession};
+use crate::worker::PngExport;
+
+impl OverlaySession {
+	/// Asks the worker to re-render the toolbar's export preview from the current crop and
+	/// backdrop. Scroll captures keep their own side preview instead.
+	pub(super) fn request_export_preview(&mut self) {
+		if !self.toolbar_state.export_preview_enabled
+			|| !matches!(self.state.mode, OverlayMode::Frozen)
+			|| self.scroll_capture.active
+		{
+			return;
+		}
+
+		let Some(image) = self.current_export_image() else {
+			return;
+		};
+		// The preview leaves out the watermark, which only ever appears in the exported file.
+		let export = PngExport {
+			image,
+			backdrop: self.export_backdrop_job(),
+			resize: self.export_resize(),
+			watermark: None,
+			retina_pair: false,
+		};
+
+		self.frozen_request_ids.export_preview =
+			self.frozen_request_ids.export_preview.wrapping_add(1);
+
+		let sent = self.worker.as_ref().is_some_and(|worker| {
+			worker.request_export_preview(
+				export,
+				EXPORT_PREVIEW_MAX_SIZE_PX,
+				self.frozen_request_ids.export_preview,
+			)
+		});
+
+		if !sent {
+			tracing::debug!(
+				op = "overlay.export_preview_request",
+				request_id = self.frozen_request_ids.export_preview,
+				"Export preview request dropped: worker queue unavailable."
+			);
+		}
+	}
+
+	pub(super) fn handle_rendered_export_preview_response(
+		&mut self,
+		request_id: u64,
+		image: RgbaImage,
+	) {
+		if request_id != self.frozen_request_ids.export_preview
+			|| !matches!(self.state.mode, OverlayMode::Frozen)
+		{
+			return;
+		}
+
+		self.toolbar_state.export_preview = Some(ExportPreview { request_id, image });
+		self.toolbar_state.needs_redraw = true;
+
+		self.request_redraw_all();
+	}
+}
@@ -0,0 +1,264 @@
+use egui::emath::TSTransform;
+use egui::{
+	Color32, ColorImage, Id, LayerId, Order, Painter, Pos2, Rect, TextureId, TextureOptions, Vec2,
+};
+
+use crate::overlay::{
+	ElementState, FROZEN_VIEW_PAN_KEY_POINTS, FROZEN_VIEW_ZOOM_STEP, Key, KeyEvent,
+	LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP, MouseScrollDelta, NamedKey, OverlayControl, OverlayMode,
+	OverlaySession, OverlayState, RectPoints, WindowRenderer, image_helpers,
+};
+use crate::state::{FrozenView, GlobalPoint, MonitorRect};
+
+impl OverlaySession {
+	/// Handles the frozen view keys: `+`/`-` zoom around the pointer, `0` resets, and the arrows
+	/// pan while zoomed.
+	pub(super) fn handle_frozen_view_key(&mut self, event: &KeyEvent) -> Option<OverlayControl> {
+		match &event.logical_key {
+			Key::Character(key_text) if matches!(key_text.as_str(), "+" | "=") => {
+				Some(self.step_frozen_view_zoom(1))
+			},
+			Key::Character(key_text) if matches!(key_text.as_str(), "-" | "_") => {
+				Some(self.step_frozen_view_zoom(-1))
+			},
+			Key::Character(key_text) if key_text == "0" => Some(self.reset_frozen_view()),
+			Key::Named(key) if self.state.frozen_view.is_zoomed() => {
+				let (dx, dy) = match key {
+					NamedKey::ArrowLeft => (FROZEN_VIEW_PAN_KEY_POINTS, 0.0),
+					NamedKey::ArrowRight => (-FROZEN_VIEW_PAN_KEY_POINTS, 0.0),
+					NamedKey::ArrowUp => (0.0, FROZEN_VIEW_PAN_KEY_POINTS),
+					NamedKey::ArrowDown => (0.0, -FROZEN_VIEW_PAN_KEY_POINTS),
+					_ => return None,
+				};
+
+				Some(self.pan_frozen_view(dx, dy))
+			},
+			_ => None,
+		}
+	}
+
+	/// Converts wheel input over the frozen monitor into zoom steps around the pointer; trackpad
+	/// pixel deltas are accumulated like the loupe zoom.
+	pub(super) fn handle_frozen_view_wheel(&mut self, delta: &MouseScrollDelta) -> OverlayControl {
+		let steps = match delta {
+			MouseScrollDelta::LineDelta(_, y) => {
+				self.frozen_view_wheel_accum_px = 0.0;
+
+				y.signum() as i32
+			},
+			MouseScrollDelta::PixelDelta(position) => {
+				self.frozen_view_wheel_accum_px += position.y as f32;
+
+				let steps =
+					(self.frozen_view_wheel_accum_px / LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP).trunc();
+
+				self.frozen_view_wheel_accum_px -= steps * LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP;
+
+				steps as i32
+			},
+		};
+
+		if steps == 0 {
+			return OverlayControl::Continue;
+		}
+
+		self.step_frozen_view_zoom(steps)
+	}
+
+	/// Starts or ends a middle-button pan of the zoomed frozen view.
+	pub(super) fn handle_frozen_view_pan_button(&mut self, state: ElementState) -> OverlayControl {
+		self.frozen_view_drag = match state {
+			ElementState::Pressed if self.frozen_view_monitor().is_some() => self.state.cursor,
+			_ => None,
+		};
+
+		OverlayControl::Continue
+	}
+
+	/// Pans the zoomed frozen view along with a middle-button drag.
+	pub(super) fn drag_frozen_view_to(&mut self, global: GlobalPoint) {
+		let Some(last) = self.frozen_view_drag else {
+			return;
+		};
+
+		self.frozen_view_drag = Some(global);
+
+		self.pan_frozen_view((global.x - last.x) as f32, (global.y - last.y) as f32);
+	}
+
+	fn step_frozen_view_zoom(&mut self, steps: i32) -> OverlayControl {
+		let Some(monitor) = self.frozen_view_monitor() else {
+			return OverlayControl::Continue;
+		};
+		let anchor = self
+			.state
+			.cursor
+			.filter(|cursor| monitor.contains(*cursor))
+			.map_or((monitor.width as f32 / 2.0, monitor.height as f32 / 2.0), |cursor| {
+				((cursor.x - monitor.origin.x) as f32, (cursor.y - monitor.origin.y) as f32)
+			});
+
+		self.state.frozen_view.zoom_around(
+			FROZEN_VIEW_ZOOM_STEP.powi(steps),
+			anchor,
+			(monitor.width as f32, monitor.height as f32),
+		);
+
+		tracing::debug!(
+			op = "overlay.frozen_view_zoom",
+			zoom = self.state.frozen_view.zoom,
+			origin = ?self.state.frozen_view.origin,
+			"Frozen view zoom changed."
+		);
+
+		self.request_redraw_for_monitor(monitor);
+
+		OverlayControl::Continue
+	}
+
+	fn pan_frozen_view(&mut self, dx: f32, dy: f32) -> OverlayControl {
+		let Some(monitor) = self.frozen_view_monitor() else {
+			return OverlayControl::Continue;
+		};
+
+		self.state.frozen_view.pan_by(dx, dy, (monitor.width as f32, monitor.height as f32));
+
+		self.request_redraw_for_monitor(monitor);
+
+		OverlayControl::Continue
+	}
+
+	fn reset_frozen_view(&mut self) -> OverlayControl {
+		self.state.frozen_view = FrozenView::default();
+		self.frozen_view_drag = None;
+
+		if let Some(monitor) = self.state.monitor {
+			self.request_redraw_for_monitor(monitor);
+		}
+
+		OverlayControl::Continue
+	}
+
+	/// The frozen monitor, while its image is ready to be zoomed.
+	fn frozen_view_monitor(&self) -> Option<MonitorRect> {
+		if !matches!(self.state.mode, OverlayMode::Frozen) || self.state.frozen_image.is_none() {
+			return None;
+		}
+
+		self.state.monitor
+	}
+}
+
+impl WindowRenderer {
+	/// Uploads the frozen image for [`Self::render_frozen_view`] while this window draws the
+	/// frozen surface and the frozen view is zoomed; otherwise the background shader draws it and
+	/// the upload is dropped.
+	pub(super) fn sync_frozen_view_texture(
+		&mut self,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		needs_frozen_surface_bg: bool,
+	) -> Option<TextureId> {
+		let image = state.frozen_image.as_ref().filter(|_| {
+			needs_frozen_surface_bg
+				&& matches!(state.mode, OverlayMode::Frozen)
+				&& state.monitor == Some(monitor)
+				&& state.frozen_view.is_zoomed()
+		});
+		let Some(image) = image else {
+			self.frozen_view_texture = None;
+
+			return None;
+		};
+
+		if self
+			.frozen_view_texture
+			.as_ref()
+			.is_none_or(|(generation, _)| *generation != state.frozen_generation)
+		{
+			let max_side = self.egui_ctx.input(|i| i.max_texture_side) as u32;
+			let upload_image = image_helpers::downscale_for_gpu_upload(image, max_side);
+			let size = [upload_image.width() as usize, upload_image.height() as usize];
+			let color_image = ColorImage::from_rgba_unmultiplied(size, upload_image.as_raw());
+			// Nearest filtering keeps magnified pixels square for placing annotations.
+			let texture = self.egui_ctx.load_texture(
+				String::from("frozen-view"),
+				color_image,
+				TextureOptions::NEAREST,
+			);
+
+			self.frozen_view_texture = Some((state.frozen_generation, texture));
+		}
+
+		self.frozen_view_texture.as_ref().map(|(_, texture)| texture.id())
+	}
+
+	/// Draws the magnified frozen image behind everything else, dimming the area outside the
+	/// selection like the background shader does.
+	pub(super) fn render_frozen_view(
+		ctx: &egui::Context,
+		state: &OverlayState,
+		monitor: MonitorRect,
+		texture_id: TextureId,
+	) {
+		let layer = LayerId::new(Order::Background, Id::new(format!("frozen-view-{}", monitor.id)));
+		let painter = Self::frozen_view_painter(ctx, state, layer);
+		let monitor_rect =
+			Rect::from_min_size(Pos2::ZERO, Vec2::new(monitor.width as f32, monitor.height as f32));
+
+		painter.image(
+			texture_id,
+			monitor_rect,
+			Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+			Color32::WHITE,
+		);
+
+		let Some(selection) = state.frozen_capture_rect.filter(|_| state.frozen_dim_opacity > 0.0)
+		else {
+			return;
+		};
+		let selection = Self::rect_points_to_rect(selection);
+		let dim = Color32::from_black_alpha((state.frozen_dim_opacity * 255.0).round() as u8);
+
+		for band in [
+			Rect::from_min_max(monitor_rect.min, Pos2::new(monitor_rect.max.x, selection.min.y)),
+			Rect::from_min_max(Pos2::new(monitor_rect.min.x, selection.max.y), monitor_rect.max),
+			Rect::from_min_max(
+				Pos2::new(monitor_rect.min.x, selection.min.y),
+				Pos2::new(selection.min.x, selection.max.y),
+			),
+			Rect::from_min_max(
+				Pos2::new(selection.max.x, selection.min.y),
+				Pos2::new(monitor_rect.max.x, selection.max.y),
+			),
+		] {
+			painter.rect_filled(band, 0.0, dim);
+		}
+	}
+
+	/// Returns a painter for a layer drawn over the frozen image in monitor-local points; the
+	/// frozen view maps it onto the zoomed preview.
+	pub(super) fn frozen_view_painter(
+		ctx: &egui::Context,
+		state: &OverlayState,
+		layer: LayerId,
+	) -> Painter {
+		let view = state.frozen_view;
+		let transform = TSTransform::new(
+			Vec2::new(-view.origin.0 * view.zoom, -view.origin.1 * view.zoom),
+			view.zoom,
+		);
+
+		// The transform is sticky, so unzoomed frames set the identity to clear it.
+		ctx.set_transform_layer(layer, transform);
+
+		ctx.layer_painter(layer)
+	}
+
+	fn rect_points_to_rect(rect: RectPoints) -> Rect {
+		Rect::from_min_size(
+			Pos2::new(rect.x as f32, rect.y as f32),
+			Vec2::new(rect.width as f32, rect.height as f32),
+		)
+	}
+}
@@ -0,0 +1,94 @@
+//! Headless overlay driver for end-to-end tests.
+//!
+//! The driver runs a real [`OverlaySession`] without windows, a GPU, or an event loop. Its worker
+//! is backed by a [`ScriptedCaptureBackend`], and pointer input is fed the way the event loop
+//! would deliver it once a `CursorMoved` event has been mapped to a monitor.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use winit::event::ElementState;
+use winit::window::WindowId;
+
+use crate::backend::ScriptedCaptureBackend;
+use crate::overlay::{FrozenToolbarTool, OverlayConfig, OverlayControl, OverlaySession};
+use crate::state::{GlobalPoint, MonitorRect};
+use crate::worker::OverlayWorker;
+
+const HEADLESS_PUMP_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(super) struct HeadlessOverlay {
+	pub(super) session: OverlaySession,
+	monitor: MonitorRect,
+}
+impl HeadlessOverlay {
+	/// Starts a live session on `monitor` whose captures come from `backend`.
+	pub(super) fn new(
+		config: OverlayConfig,
+		monitor: MonitorRect,
+		backend: ScriptedCaptureBackend,
+	) -> Self {
+		let mut session = OverlaySession::with_config(config);
+
+		session.reset_for_start();
+
+		session.worker = Some(OverlayWorker::new(Box::new(backend), None));
+
+		Self { session, monitor }
+	}
+
+	/// Moves the pointer to `global`, as a mapped `CursorMoved` event would.
+	pub(super) fn move_cursor(&mut self, global: GlobalPoint) -> OverlayControl {
+		let old_monitor = self.session.active_cursor_monitor();
+
+		self.session.last_event_cursor = Some((self.monitor, global));
+		self.session.last_event_cursor_at = Some(Instant::now());
+
+		self.session.apply_live_cursor_move(old_monitor, self.monitor, global)
+	}
+
+	pub(super) fn press(&mut self) -> OverlayControl {
+		self.session.handle_left_mouse_input(WindowId::dummy(), ElementState::Pressed)
+	}
+
+	pub(super) fn release(&mut self) -> OverlayControl {
+		self.session.handle_left_mouse_input(WindowId::dummy(), ElementState::Released)
+	}
+
+	/// Presses at `from`, drags to `to`, and releases there.
+	pub(super) fn drag(&mut self, from: GlobalPoint, to: GlobalPoint) -> OverlayControl {
+		let _ = self.move_cursor(from);
+		let _ = self.press();
+		let _ = self.move_cursor(to);
+
+		self.release()
+	}
+
+	pub(super) fn toolbar_action(&mut self, action: FrozenToolbarTool) -> OverlayControl {
+		self.session.handle_toolbar_action(action)
+	}
+
+	/// Redraws and drains worker responses until `done` holds or the session exits.
+	///
+	/// Panics when neither happens within [`HEADLESS_PUMP_TIMEOUT`].
+	pub(super) fn pump_until(
+		&mut self,
+		mut done: impl FnMut(&OverlaySession) -> bool,
+	) -> OverlayControl {
+		let deadline = Instant::now() + HEADLESS_PUMP_TIMEOUT;
+
+		loop {
+			let control = self.session.drain_worker_responses();
+
+			if matches!(control, OverlayControl::Exit(_)) || done(&self.session) {
+				return control;
+			}
+
+			assert!(Instant::now() < deadline, "headless overlay did not settle");
+
+			self.session.handle_capture_redraw_post(self.monitor);
+
+			thread::sleep(Duration::from_millis(1));
+		}
+	}
+}
@@ -0,0 +1,55 @@
+use crate::overlay::{FrozenToolbarTool, OverlayMode, OverlaySession};
+use crate::state::GlobalPoint;
+
+impl OverlaySession {
+	pub(super) fn highlighter_tool_active(&self) -> bool {
+		matches!(self.state.mode, OverlayMode::Frozen)
+			&& !self.scroll_capture.active
+			&& self.toolbar_state.selected_tool == FrozenToolbarTool::Highlighter
+	}
+
+	/// Starts a highlight stroke at `global`; `false` outside the frozen selection.
+	pub(super) fn begin_highlight_at(&mut self, global: GlobalPoint) -> bool {
+		let Some((x, y)) = self.selection_pixel_at(global) else {
+			return false;
+		};
+		let scale = self.state.monitor.map_or(1.0, |monitor| monitor.scale_factor());
+		let width_px = (self.config.highlighter_width_px * scale).round().max(1.0) as u32;
+
+		self.state.annotations.begin_highlight(
+			(i64::from(x), i64::from(y)),
+			width_px,
+			self.config.highlighter_rgb,
+		);
+		self.annotations_changed();
+
+		true
+	}
+
+	/// Extends the stroke in progress to `global`; strokes may run past the selection edge.
+	pub(super) fn extend_highlight_to(&mut self, global: GlobalPoint) {
+		if let Some((point, _)) = self.selection_pixel_unclipped_at(global)
+			&& self.state.annotations.extend_highlight(point)
+			&& let Some(monitor) = self.state.monitor
+		{
+			self.request_redraw_for_monitor(monitor);
+		}
+	}
+
+	/// Ends the stroke in progress; `false` when none was being drawn.
+	pub(super) fn finish_highlight(&mut self) -> bool {
+		if !self.state.annotations.end_highlight() {
+			return false;
+		}
+
+		tracing::debug!(
+			op = "overlay.highlight_drawn",
+			points =
+				self.state.annotations.highlights.last().map_or(0, |stroke| stroke.points.len()),
+		);
+
+		self.annotations_changed();
+
+		true
+	}
+}
@@ -0,0 +1,67 @@
+use crate::overlay::{OverlayControl, OverlayMode, OverlaySession};
+use crate::state::Histogram;
+
+impl OverlaySession {
+	/// Collapses the frozen-mode histogram panel to its header, or expands it again.
+	pub(super) fn toggle_histogram_panel(&mut self) -> OverlayControl {
+		if !self.state.histogram_panel {
+			return OverlayControl::Continue;
+		}
+
+		self.state.histogram_expanded = !self.state.histogram_expanded;
+
+		if self.state.histogram.is_none() {
+			self.request_selection_histogram();
+		}
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	/// Asks the worker for a histogram of the exported selection while the panel is expanded.
+	pub(super) fn request_selection_histogram(&mut self) {
+		if !self.state.histogram_panel
+			|| !self.state.histogram_expanded
+			|| !matches!(self.state.mode, OverlayMode::Frozen)
+		{
+			return;
+		}
+
+		let Some(image) = self.cropped_frozen_capture_image() else {
+			return;
+		};
+
+		self.frozen_request_ids.histogram = self.frozen_request_ids.histogram.wrapping_add(1);
+
+		let sent = self.worker.as_ref().is_some_and(|worker| {
+			worker.request_histogram(image, self.frozen_request_ids.histogram)
+		});
+
+		if !sent {
+			tracing::debug!(
+				op = "overlay.histogram_request",
+				request_id = self.frozen_request_ids.histogram,
+				"Histogram request dropped: worker queue unavailable."
+			);
+		}
+	}
+
+	pub(super) fn handle_computed_histogram_response(
+		&mut self,
+		request_id: u64,
+		histogram: Histogram,
+	) {
+		if request_id != self.frozen_request_ids.histogram
+			|| !matches!(self.state.mode, OverlayMode::Frozen)
+		{
+			return;
+		}
+
+		self.state.histogram = Some(histogram);
+
+		if let Some(monitor) = self.state.monitor {
+			self.request_redraw_for_monitor(monitor);
+		}
+	}
+}
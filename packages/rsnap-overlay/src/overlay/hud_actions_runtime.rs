@@ -0,0 +1,163 @@
+use crate::overlay::{
+	ElementState, HudPointerState, HudQuickAction, MouseButton, OverlayControl, OverlayMode,
+	OverlaySession, Pos2, WindowEvent, WindowId, output,
+};
+
+impl OverlaySession {
+	/// Pins the live HUD at the current sample point, or releases it.
+	///
+	/// The HUD normally trails the cursor and ignores the mouse, so it can never be clicked. While
+	/// pinned it stops following, keeps showing the pinned point, and its window accepts clicks on
+	/// the quick-action row.
+	pub(super) fn toggle_hud_pin(&mut self) -> OverlayControl {
+		if !matches!(self.state.mode, OverlayMode::Live) {
+			return OverlayControl::Continue;
+		}
+
+		self.set_hud_pinned(!self.state.hud_pinned);
+
+		OverlayControl::Continue
+	}
+
+	pub(super) fn set_hud_pinned(&mut self, pinned: bool) {
+		if self.state.hud_pinned == pinned {
+			return;
+		}
+
+		self.state.hud_pinned = pinned;
+		self.hud_pointer = HudPointerState::default();
+
+		self.set_loupe_lock(None);
+
+		if let Some(hud_window) = self.hud_window.as_ref() {
+			let _ = hud_window.window.set_cursor_hittest(pinned);
+		}
+
+		tracing::info!(op = "overlay.hud_pin", pinned, "HUD pin changed.");
+
+		self.request_redraw_hud_window();
+	}
+
+	/// Routes pointer input over the pinned HUD window. Returns `None` for events the HUD does
+	/// not own so they fall through to the regular overlay handling.
+	pub(super) fn handle_hud_window_event(
+		&mut self,
+		window_id: WindowId,
+		event: &WindowEvent,
+	) -> Option<OverlayControl> {
+		let hud_window = self.hud_window.as_ref()?;
+
+		if hud_window.window.id() != window_id || !self.state.hud_pinned {
+			return None;
+		}
+
+		match event {
+			WindowEvent::CursorMoved { position, .. } => {
+				let scale = hud_window.window.scale_factor().max(1.0);
+
+				self.hud_pointer.cursor_local =
+					Some(Pos2::new((position.x / scale) as f32, (position.y / scale) as f32));
+			},
+			WindowEvent::CursorLeft { .. } => {
+				self.hud_pointer = HudPointerState::default();
+			},
+			WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+				let down = matches!(state, ElementState::Pressed);
+
+				if down == self.hud_pointer.left_button_down {
+					return Some(OverlayControl::Continue);
+				}
+				if down {
+					self.hud_pointer.left_button_went_down = true;
+				} else {
+					self.hud_pointer.left_button_went_up = true;
+				}
+
+				self.hud_pointer.left_button_down = down;
+			},
+			_ => return None,
+		}
+
+		self.request_redraw_hud_window();
+
+		Some(OverlayControl::Continue)
+	}
+
+	pub(super) fn handle_hud_quick_action(&mut self, action: HudQuickAction) -> OverlayControl {
+		tracing::debug!(op = "overlay.hud_quick_action", ?action, "HUD quick action clicked.");
+
+		match action {
+			HudQuickAction::CopyColor => self.copy_sampled_color(),
+			HudQuickAction::CycleColorFormat => {
+				self.state.color_format = self.state.color_format.next();
+
+				self.request_redraw_hud_window();
+
+				OverlayControl::Continue
+			},
+			HudQuickAction::ToggleLoupe => {
+				if self.state.alt_held {
+					self.set_loupe_lock(None);
+				}
+
+				self.set_alt_held(!self.state.alt_held);
+				self.request_redraw_all();
+
+				OverlayControl::Continue
+			},
+			HudQuickAction::Freeze => {
+				let (Some(monitor), Some(cursor)) =
+					(self.active_cursor_monitor(), self.state.cursor)
+				else {
+					return OverlayControl::Continue;
+				};
+
+				self.set_hud_pinned(false);
+				self.request_click_capture_hit_test(monitor, cursor);
+
+				OverlayControl::Continue
+			},
+		}
+	}
+
+	/// Locks the color readout to the loupe cell `offset` pixels from the pinned point, or
+	/// unlocks it when that cell is already locked or is the pinned point itself.
+	pub(super) fn toggle_loupe_lock(&mut self, offset: (i32, i32)) {
+		let lock = (offset != (0, 0) && self.state.loupe_lock != Some(offset)).then_some(offset);
+
+		self.set_loupe_lock(lock);
+
+		tracing::debug!(op = "overlay.loupe_lock", ?lock, "Loupe lock changed.");
+	}
+
+	/// Points the color readout at the locked loupe cell, or back at the loupe center.
+	pub(super) fn set_loupe_lock(&mut self, lock: Option<(i32, i32)>) {
+		if self.state.loupe_lock == lock {
+			return;
+		}
+
+		self.state.loupe_lock = lock;
+
+		if let Some(rgb) =
+			self.state.loupe.as_ref().and_then(|loupe| loupe.rgb_at(lock.unwrap_or((0, 0))))
+		{
+			self.state.rgb = Some(rgb);
+		}
+
+		self.request_redraw_hud_window();
+	}
+
+	/// Copies the sampled color in the selected format without ending the session.
+	pub(super) fn copy_sampled_color(&mut self) -> OverlayControl {
+		let Some(rgb) = self.state.rgb else {
+			return OverlayControl::Continue;
+		};
+
+		if let Err(err) = output::write_text_to_clipboard(&self.state.color_format.format(rgb)) {
+			self.state.set_error(format!("{err:#}"));
+			self.request_redraw_all();
+		}
+
+		OverlayControl::Continue
+	}
+}
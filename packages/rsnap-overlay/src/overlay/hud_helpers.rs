@@ -1,10 +1,15 @@
+#[cfg(not(target_os = "macos"))]
+use std::collections::HashMap;
+#[cfg(not(target_os = "macos"))]
+use std::sync::{Mutex, OnceLock, PoisonError};
+
 use winit::window::Theme;
 
 use crate::overlay::{
 	HUD_PILL_BLUR_TINT_ALPHA_DARK, HUD_PILL_BLUR_TINT_ALPHA_LIGHT, HUD_PILL_BODY_FILL_DARK_SRGBA8,
-	HUD_PILL_BODY_FILL_LIGHT_SRGBA8, HudTheme, ThemeMode,
+	HUD_PILL_BODY_FILL_LIGHT_SRGBA8, HudTheme, HudUnit, ThemeMode,
 };
-use crate::state::{GlobalPoint, MonitorRect, OverlayState, Rgb};
+use crate::state::{GlobalPoint, MonitorRect, OverlayState, RectPoints, Rgb};
 
 pub(super) fn srgb8_to_linear_f32(x: u8) -> f32 {
 	let c = (x as f32) / 255.0;
@@ -102,13 +107,165 @@ pub(super) fn live_hud_coordinate_text_width(min_value: i32, max_value: i32) ->
 	min_value.to_string().len().max(max_value.to_string().len()).max(1)
 }
 
-pub(super) fn format_live_hud_position_text(monitor: MonitorRect, cursor: GlobalPoint) -> String {
-	let max_x = monitor.origin.x.saturating_add_unsigned(monitor.width.saturating_sub(1));
-	let max_y = monitor.origin.y.saturating_add_unsigned(monitor.height.saturating_sub(1));
-	let x_width = live_hud_coordinate_text_width(monitor.origin.x, max_x);
-	let y_width = live_hud_coordinate_text_width(monitor.origin.y, max_y);
+pub(super) fn format_live_hud_position_text(
+	monitor: MonitorRect,
+	cursor: GlobalPoint,
+	unit: HudUnit,
+	millimeters_per_point: f32,
+) -> String {
+	match unit {
+		HudUnit::Points => {
+			let max_x = monitor.origin.x.saturating_add_unsigned(monitor.width.saturating_sub(1));
+			let max_y = monitor.origin.y.saturating_add_unsigned(monitor.height.saturating_sub(1));
+			let x_width = live_hud_coordinate_text_width(monitor.origin.x, max_x);
+			let y_width = live_hud_coordinate_text_width(monitor.origin.y, max_y);
+
+			format!("x={:>x_width$}, y={:>y_width$}", cursor.x, cursor.y)
+		},
+		HudUnit::Pixels => {
+			let sf = monitor.scale_factor();
+			let max_x = ((monitor.width.saturating_sub(1) as f32) * sf).round() as i32;
+			let max_y = ((monitor.height.saturating_sub(1) as f32) * sf).round() as i32;
+			let x_width = live_hud_coordinate_text_width(0, max_x);
+			let y_width = live_hud_coordinate_text_width(0, max_y);
+			let (x, y) = monitor.local_u32_pixels(cursor).unwrap_or((0, 0));
+
+			format!("x={x:>x_width$}, y={y:>y_width$}")
+		},
+		HudUnit::Millimeters => {
+			let max_x = (monitor.width.saturating_sub(1) as f32) * millimeters_per_point;
+			let max_y = (monitor.height.saturating_sub(1) as f32) * millimeters_per_point;
+			let x_width = format!("{max_x:.1}").len();
+			let y_width = format!("{max_y:.1}").len();
+			let (x, y) = monitor.local_u32(cursor).unwrap_or((0, 0));
+			let x = (x as f32) * millimeters_per_point;
+			let y = (y as f32) * millimeters_per_point;
+
+			format!("x={x:>x_width$.1}, y={y:>y_width$.1}")
+		},
+	}
+}
+
+/// Formats the size of the monitor-local `rect` in `unit`, e.g. `640×480 px`.
+pub(super) fn format_hud_size_text(
+	monitor: MonitorRect,
+	rect: RectPoints,
+	unit: HudUnit,
+	millimeters_per_point: f32,
+) -> String {
+	match unit {
+		HudUnit::Points => format!("{}×{} pt", rect.width, rect.height),
+		HudUnit::Pixels => {
+			let rect_px = monitor.local_rect_to_pixels(rect);
+
+			format!("{}×{} px", rect_px.width, rect_px.height)
+		},
+		HudUnit::Millimeters => {
+			let width = (rect.width as f32) * millimeters_per_point;
+			let height = (rect.height as f32) * millimeters_per_point;
+
+			format!("{width:.1}×{height:.1} mm")
+		},
+	}
+}
+
+/// Returns the unit the HUD shows on `monitor` together with its millimeters per point.
+///
+/// Millimeters fall back to points when the platform does not report the panel size, so the HUD
+/// never shows a length it would have to guess.
+pub(super) fn monitor_hud_unit(unit: HudUnit, monitor: MonitorRect) -> (HudUnit, f32) {
+	if unit != HudUnit::Millimeters {
+		return (unit, 0.0);
+	}
+
+	displayable_hud_unit(unit, monitor_millimeters_per_point(monitor))
+}
+
+pub(super) fn displayable_hud_unit(
+	unit: HudUnit,
+	millimeters_per_point: Option<f32>,
+) -> (HudUnit, f32) {
+	match (unit, millimeters_per_point) {
+		(HudUnit::Millimeters, None) => (HudUnit::Points, 0.0),
+		(unit, millimeters_per_point) => (unit, millimeters_per_point.unwrap_or(0.0)),
+	}
+}
+
+/// Returns the physical size of one logical point on `monitor`, in millimeters, or `None` when
+/// the display does not report its panel size (virtual displays, projectors, Wayland).
+pub(super) fn monitor_millimeters_per_point(monitor: MonitorRect) -> Option<f32> {
+	let width_mm = monitor_physical_width_millimeters(monitor.id)?;
+
+	(width_mm > 0.0 && monitor.width > 0).then(|| (width_mm / f64::from(monitor.width)) as f32)
+}
+
+#[cfg(target_os = "macos")]
+fn monitor_physical_width_millimeters(monitor_id: u32) -> Option<f64> {
+	Some(objc2_core_graphics::CGDisplayScreenSize(monitor_id).width)
+}
+
+/// Looks the panel width up once per monitor; the query opens a display connection on X11 and a
+/// device context on Windows, which is too slow to repeat every frame.
+#[cfg(not(target_os = "macos"))]
+fn monitor_physical_width_millimeters(monitor_id: u32) -> Option<f64> {
+	static WIDTHS_MM: OnceLock<Mutex<HashMap<u32, Option<u32>>>> = OnceLock::new();
 
-	format!("x={:>x_width$}, y={:>y_width$}", cursor.x, cursor.y)
+	let mut widths_mm =
+		WIDTHS_MM.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner);
+
+	widths_mm
+		.entry(monitor_id)
+		.or_insert_with(|| query_physical_width_millimeters(monitor_id))
+		.map(f64::from)
+}
+
+#[cfg(target_os = "linux")]
+fn query_physical_width_millimeters(monitor_id: u32) -> Option<u32> {
+	crate::x11_capture_linux::monitor_width_millimeters(monitor_id)
+}
+
+/// Reads `HORZSIZE` from a device context on the monitor, which Windows fills in from the EDID.
+#[cfg(target_os = "windows")]
+fn query_physical_width_millimeters(monitor_id: u32) -> Option<u32> {
+	use std::{mem, ptr};
+
+	use windows_sys::Win32::Graphics::Gdi::{
+		CreateDCW, DeleteDC, GetDeviceCaps, GetMonitorInfoW, HMONITOR, HORZSIZE, MONITORINFO,
+		MONITORINFOEXW,
+	};
+
+	// Monitor ids are the `HMONITOR` handle the capture stack enumerated.
+	let monitor = monitor_id as usize as HMONITOR;
+	// SAFETY: zeroed is a valid `MONITORINFOEXW`; its size field is set before the call.
+	let mut info = unsafe { mem::zeroed::<MONITORINFOEXW>() };
+
+	info.monitorInfo.cbSize = mem::size_of::<MONITORINFOEXW>() as u32;
+
+	// SAFETY: `info` is a writable `MONITORINFOEXW` whose `cbSize` tells the call so; a stale
+	// handle only makes the call fail. The device context is released before returning.
+	unsafe {
+		if GetMonitorInfoW(monitor, ptr::from_mut(&mut info).cast::<MONITORINFO>()) == 0 {
+			return None;
+		}
+
+		let dc =
+			CreateDCW(info.szDevice.as_ptr(), info.szDevice.as_ptr(), ptr::null(), ptr::null());
+
+		if dc.is_null() {
+			return None;
+		}
+
+		let width_mm = GetDeviceCaps(dc, HORZSIZE as i32);
+
+		DeleteDC(dc);
+
+		u32::try_from(width_mm).ok().filter(|width_mm| *width_mm > 0)
+	}
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn query_physical_width_millimeters(_monitor_id: u32) -> Option<u32> {
+	None
 }
 
 pub(super) fn format_live_hud_rgb_text(rgb: Option<Rgb>) -> (String, String) {
@@ -118,6 +275,26 @@ pub(super) fn format_live_hud_rgb_text(rgb: Option<Rgb>) -> (String, String) {
 	}
 }
 
+/// Formats the loupe magnification as physical pixels drawn per source pixel, e.g. `2×`.
+pub(super) fn format_loupe_zoom_label(cell_points: f32, pixels_per_point: f32) -> String {
+	let magnification = cell_points * pixels_per_point;
+
+	if (magnification - magnification.round()).abs() < 0.05 {
+		format!("{magnification:.0}×")
+	} else {
+		format!("{magnification:.1}×")
+	}
+}
+
+/// Labels every `step`-th pixel coordinate on the loupe grid, using the smallest step whose
+/// cells leave room for a label `label_width` points wide.
+pub(super) fn loupe_coordinate_label_step(cell: f32, label_width: f32) -> u32 {
+	[1, 2, 5, 10, 20, 50]
+		.into_iter()
+		.find(|step| (*step as f32) * cell >= label_width + 2.0)
+		.unwrap_or(100)
+}
+
 pub(super) fn stable_live_loupe_side_px(state: &OverlayState) -> u32 {
 	state.loupe_patch_side_px.max(1)
 }
@@ -6,7 +6,12 @@ use image::{
 };
 
 use crate::overlay::SCROLL_CAPTURE_PREVIEW_WIDTH_PX;
-use crate::state::{GlobalPoint, MonitorRect, Rgb};
+use crate::state::{CursorImage, GlobalPoint, MonitorRect, Rgb};
+
+/// Outline of the standard arrow pointer in points, with the hotspot at the origin.
+const FALLBACK_ARROW_POINTS: [(f32, f32); 7] =
+	[(0.0, 0.0), (0.0, 17.0), (4.0, 13.0), (7.0, 19.0), (9.5, 18.0), (6.5, 12.0), (12.0, 12.0)];
+const FALLBACK_ARROW_SCALE: f32 = 2.0;
 
 pub(super) fn resize_scroll_preview_segment(segment: &RgbaImage) -> RgbaImage {
 	if segment.width() <= SCROLL_CAPTURE_PREVIEW_WIDTH_PX {
@@ -21,6 +26,115 @@ pub(super) fn resize_scroll_preview_segment(segment: &RgbaImage) -> RgbaImage {
 	imageops::resize(segment, SCROLL_CAPTURE_PREVIEW_WIDTH_PX, preview_height, FilterType::Triangle)
 }
 
+/// Alpha-blends `cursor_image` into a monitor capture with its hotspot at `cursor`, resampling
+/// the sprite to the monitor's pixel density.
+pub(super) fn composite_cursor(
+	image: &mut RgbaImage,
+	monitor: MonitorRect,
+	cursor: GlobalPoint,
+	cursor_image: &CursorImage,
+) {
+	let scale = monitor.scale_factor();
+	let ratio = scale / cursor_image.scale_factor.max(f32::EPSILON);
+	let width = (cursor_image.image.width() as f32 * ratio).round().max(1.0) as u32;
+	let height = (cursor_image.image.height() as f32 * ratio).round().max(1.0) as u32;
+	let sprite = if width == cursor_image.image.width() && height == cursor_image.image.height() {
+		Cow::Borrowed(&cursor_image.image)
+	} else {
+		Cow::Owned(imageops::resize(&cursor_image.image, width, height, FilterType::Triangle))
+	};
+	let x = ((cursor.x - monitor.origin.x) as f32 - cursor_image.hotspot_x) * scale;
+	let y = ((cursor.y - monitor.origin.y) as f32 - cursor_image.hotspot_y) * scale;
+
+	imageops::overlay(image, sprite.as_ref(), x.round() as i64, y.round() as i64);
+}
+
+/// Draws a black-outlined white arrow for platforms where the system cursor image is unavailable.
+pub(super) fn fallback_arrow_cursor() -> CursorImage {
+	let width = (13.0 * FALLBACK_ARROW_SCALE) as u32;
+	let height = (20.0 * FALLBACK_ARROW_SCALE) as u32;
+	let outline = 1.25 * FALLBACK_ARROW_SCALE;
+	let inside = |x: f32, y: f32| {
+		let points = FALLBACK_ARROW_POINTS;
+		let mut inside = false;
+
+		for (index, &(x1, y1)) in points.iter().enumerate() {
+			let (x2, y2) = points[(index + 1) % points.len()];
+			let (x1, y1, x2, y2) = (
+				x1 * FALLBACK_ARROW_SCALE,
+				y1 * FALLBACK_ARROW_SCALE,
+				x2 * FALLBACK_ARROW_SCALE,
+				y2 * FALLBACK_ARROW_SCALE,
+			);
+
+			if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+				inside = !inside;
+			}
+		}
+
+		inside
+	};
+	let image = RgbaImage::from_fn(width, height, |x, y| {
+		let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+
+		if !inside(cx, cy) {
+			return image::Rgba([0, 0, 0, 0]);
+		}
+
+		let interior = [(-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)]
+			.into_iter()
+			.all(|(dx, dy)| inside(cx + dx * outline, cy + dy * outline));
+
+		if interior { image::Rgba([255, 255, 255, 255]) } else { image::Rgba([0, 0, 0, 255]) }
+	});
+
+	CursorImage { image, hotspot_x: 0.0, hotspot_y: 0.0, scale_factor: FALLBACK_ARROW_SCALE }
+}
+
+/// Crops a window capture to its non-transparent bounds and squares off rounded corners.
+///
+/// Each row's transparent ends are filled by extending its outermost mostly-opaque pixel, so the
+/// result is a tight rectangle without losing window content.
+pub(super) fn trim_window_image(image: &RgbaImage) -> RgbaImage {
+	const OPAQUE_ALPHA: u8 = 128;
+
+	let visible_rows = (0..image.height())
+		.filter(|&y| (0..image.width()).any(|x| image.get_pixel(x, y)[3] > 0))
+		.collect::<Vec<_>>();
+	let visible_columns = (0..image.width())
+		.filter(|&x| (0..image.height()).any(|y| image.get_pixel(x, y)[3] > 0))
+		.collect::<Vec<_>>();
+	let (Some(&top), Some(&bottom), Some(&left), Some(&right)) = (
+		visible_rows.first(),
+		visible_rows.last(),
+		visible_columns.first(),
+		visible_columns.last(),
+	) else {
+		return image.clone();
+	};
+	let mut trimmed =
+		imageops::crop_imm(image, left, top, right - left + 1, bottom - top + 1).to_image();
+
+	for y in 0..trimmed.height() {
+		let opaque = |x: &u32| trimmed.get_pixel(*x, y)[3] >= OPAQUE_ALPHA;
+		let (Some(first), Some(last)) =
+			((0..trimmed.width()).find(opaque), (0..trimmed.width()).rev().find(opaque))
+		else {
+			continue;
+		};
+		let (first_pixel, last_pixel) = (*trimmed.get_pixel(first, y), *trimmed.get_pixel(last, y));
+
+		for x in 0..first {
+			trimmed.put_pixel(x, y, first_pixel);
+		}
+		for x in last + 1..trimmed.width() {
+			trimmed.put_pixel(x, y, last_pixel);
+		}
+	}
+
+	trimmed
+}
+
 pub(super) fn frozen_rgb(
 	image: &Option<RgbaImage>,
 	monitor: Option<MonitorRect>,
@@ -99,8 +213,14 @@ pub(super) fn pad_rows(
 	out
 }
 
+/// The image is larger than the GPU's max texture side, so its on-screen preview is downscaled.
+/// Sampling and export keep reading the full-resolution image.
+pub(super) fn exceeds_gpu_texture_side(image: &RgbaImage, max_side: u32) -> bool {
+	image.width() > max_side || image.height() > max_side
+}
+
 pub(super) fn downscale_for_gpu_upload(image: &RgbaImage, max_side: u32) -> Cow<'_, RgbaImage> {
-	if image.width() <= max_side && image.height() <= max_side {
+	if !exceeds_gpu_texture_side(image, max_side) {
 		return Cow::Borrowed(image);
 	}
 
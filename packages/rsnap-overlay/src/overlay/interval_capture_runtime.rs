@@ -0,0 +1,42 @@
+use crate::overlay::{ActiveEventLoop, OverlayControl, OverlayExit, OverlaySession, PngAction};
+
+impl OverlaySession {
+	/// Starts the capture overlay to pick the region an interval capture should record; Done
+	/// hands the frozen selection back instead of exporting it.
+	pub fn start_interval_capture_picker(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+	) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		self.start(event_loop)?;
+
+		self.interval_capture_picker = true;
+
+		self.state.set_error("Interval capture: select a region, then press Space.");
+
+		Ok(())
+	}
+
+	/// Ends the session and hands the frozen selection to the caller to capture on a schedule.
+	pub(super) fn capture_frozen_region_on_interval(&mut self) -> OverlayControl {
+		let Some((monitor, rect_px)) = self.frozen_selection_px() else {
+			return OverlayControl::Continue;
+		};
+
+		self.exit(OverlayExit::IntervalCapture { monitor, rect_px })
+	}
+
+	/// Runs the Done pipeline, or finishes the interval capture picker with the selection.
+	pub(super) fn finish_frozen_done(&mut self) -> OverlayControl {
+		if self.interval_capture_picker {
+			return self.capture_frozen_region_on_interval();
+		}
+
+		self.begin_png_action(PngAction::Done);
+
+		OverlayControl::Continue
+	}
+}
@@ -0,0 +1,204 @@
+use crate::overlay::{
+	GlobalPoint, Instant, KEYBOARD_NAV_FAST_STEP_POINTS, KEYBOARD_NAV_STEP_POINTS, Key, KeyEvent,
+	LogicalPosition, MonitorRect, NamedKey, OverlayControl, OverlaySession,
+};
+use crate::state::WindowRect;
+
+impl OverlaySession {
+	/// Handles the live-mode keyboard selection keys, returning `None` for keys it does not own.
+	///
+	/// Arrows move the crosshair (Shift for larger steps) by warping the pointer, so hover,
+	/// sampling, and drag tracking behave exactly as with a mouse. Enter starts a selection at the
+	/// crosshair and Enter again finishes it; Alt + arrows move the selection's anchor corner
+	/// instead, which resizes it from the opposite edge. Once keyboard navigation is in use, Tab
+	/// cycles the crosshair through the windows on the current display.
+	pub(super) fn handle_keyboard_nav_key_event(
+		&mut self,
+		event: &KeyEvent,
+	) -> Option<OverlayControl> {
+		if self.state.hud_pinned {
+			return None;
+		}
+
+		match &event.logical_key {
+			Key::Named(
+				key @ (NamedKey::ArrowLeft
+				| NamedKey::ArrowRight
+				| NamedKey::ArrowUp
+				| NamedKey::ArrowDown),
+			) => {
+				let step = if self.keyboard_modifiers.shift_key() {
+					KEYBOARD_NAV_FAST_STEP_POINTS
+				} else {
+					KEYBOARD_NAV_STEP_POINTS
+				};
+				let (dx, dy) = Self::keyboard_nav_delta(*key, step);
+
+				self.keyboard_nav_active = true;
+
+				if self.keyboard_modifiers.alt_key() && self.left_mouse_button_down {
+					self.move_keyboard_selection_anchor(dx, dy);
+				} else {
+					self.move_keyboard_crosshair(dx, dy);
+				}
+
+				Some(OverlayControl::Continue)
+			},
+			Key::Named(NamedKey::Enter) => Some(self.toggle_keyboard_selection()),
+			Key::Named(NamedKey::Tab) if self.keyboard_nav_active => {
+				self.cycle_keyboard_hovered_window();
+
+				Some(OverlayControl::Continue)
+			},
+			_ => None,
+		}
+	}
+
+	fn keyboard_nav_delta(key: NamedKey, step: i32) -> (i32, i32) {
+		match key {
+			NamedKey::ArrowLeft => (-step, 0),
+			NamedKey::ArrowRight => (step, 0),
+			NamedKey::ArrowUp => (0, -step),
+			NamedKey::ArrowDown => (0, step),
+			_ => (0, 0),
+		}
+	}
+
+	fn move_keyboard_crosshair(&mut self, dx: i32, dy: i32) {
+		let Some(cursor) = self.state.cursor else {
+			return;
+		};
+		let target = GlobalPoint::new(cursor.x.saturating_add(dx), cursor.y.saturating_add(dy));
+
+		self.warp_keyboard_crosshair(target);
+	}
+
+	/// Moves the crosshair to `target` and feeds it through the regular live cursor path.
+	fn warp_keyboard_crosshair(&mut self, target: GlobalPoint) {
+		let Some(monitor) = self.monitor_at(target) else {
+			return;
+		};
+		let old_monitor = self.active_cursor_monitor();
+
+		if let Some(window) = self.windows.values().find(|window| window.monitor == monitor) {
+			let _ = window.window.set_cursor_position(LogicalPosition::new(
+				f64::from(target.x - monitor.origin.x),
+				f64::from(target.y - monitor.origin.y),
+			));
+		}
+
+		// The OS cursor catches up asynchronously; treat the target as a fresh event position so
+		// cursor polling does not snap back to the pre-warp location in the meantime.
+		self.last_event_cursor = Some((monitor, target));
+		self.last_event_cursor_at = Some(Instant::now());
+
+		self.update_cursor_for_live_move(monitor, target);
+		self.update_live_drag_rect(monitor, target);
+		self.request_cursor_move_samples(monitor, target);
+
+		if let Some(old_monitor) = old_monitor
+			&& old_monitor != monitor
+		{
+			self.request_redraw_for_monitor(old_monitor);
+		}
+
+		self.request_redraw_for_monitor(monitor);
+	}
+
+	fn move_keyboard_selection_anchor(&mut self, dx: i32, dy: i32) {
+		let (Some(monitor), Some(anchor), Some(cursor)) = (
+			self.left_mouse_button_down_monitor,
+			self.left_mouse_button_down_global,
+			self.state.cursor,
+		) else {
+			return;
+		};
+		let anchor = GlobalPoint::new(anchor.x.saturating_add(dx), anchor.y.saturating_add(dy));
+
+		if !monitor.contains(anchor) {
+			return;
+		}
+
+		self.left_mouse_button_down_global = Some(anchor);
+
+		self.update_live_drag_rect(monitor, cursor);
+		self.request_redraw_for_monitor(monitor);
+	}
+
+	fn toggle_keyboard_selection(&mut self) -> OverlayControl {
+		let (Some(monitor), Some(cursor)) = (self.active_cursor_monitor(), self.state.cursor)
+		else {
+			return OverlayControl::Continue;
+		};
+
+		self.keyboard_nav_active = true;
+
+		if self.left_mouse_button_down {
+			let start_monitor = self.left_mouse_button_down_monitor.unwrap_or(monitor);
+
+			tracing::debug!(op = "overlay.keyboard_selection_end", monitor_id = monitor.id);
+
+			return self.finish_live_press(start_monitor, monitor, cursor);
+		}
+
+		tracing::debug!(op = "overlay.keyboard_selection_start", monitor_id = monitor.id);
+
+		self.left_mouse_button_down = true;
+		self.left_mouse_button_down_monitor = Some(monitor);
+		self.left_mouse_button_down_global = Some(cursor);
+		self.state.drag_rect = None;
+		self.state.hovered_window_rect = None;
+
+		self.request_redraw_for_monitor(monitor);
+
+		OverlayControl::Continue
+	}
+
+	fn cycle_keyboard_hovered_window(&mut self) {
+		let Some(monitor) = self.active_cursor_monitor() else {
+			return;
+		};
+		let Some(snapshot) = self.window_list_snapshot.as_ref() else {
+			let _ = self.request_live_window_list_refresh_if_needed();
+
+			return;
+		};
+		let Some((index, target)) = Self::next_keyboard_window_target(
+			&snapshot.windows,
+			monitor,
+			self.keyboard_window_cycle_index,
+		) else {
+			return;
+		};
+
+		self.keyboard_window_cycle_index = Some(index);
+
+		self.warp_keyboard_crosshair(target);
+	}
+
+	/// Picks the window after `previous` (in hit-test order) that is visible on `monitor` and
+	/// returns its index with the center of its on-monitor area.
+	pub(super) fn next_keyboard_window_target(
+		windows: &[WindowRect],
+		monitor: MonitorRect,
+		previous: Option<usize>,
+	) -> Option<(usize, GlobalPoint)> {
+		let start = previous.map_or(0, |index| index + 1);
+
+		(0..windows.len()).map(|offset| (start + offset) % windows.len()).find_map(|index| {
+			let window = windows[index];
+			let rect = monitor.clip_global_rect_i64(
+				window.x,
+				window.y,
+				window.x.saturating_add(window.width),
+				window.y.saturating_add(window.height),
+			)?;
+			let center = GlobalPoint::new(
+				monitor.origin.x.saturating_add_unsigned(rect.x + rect.width / 2),
+				monitor.origin.y.saturating_add_unsigned(rect.y + rect.height / 2),
+			);
+
+			Some((index, center))
+		})
+	}
+}
@@ -0,0 +1,100 @@
+use crate::overlay::{
+	LOUPE_CELL_POINTS, LOUPE_ZOOM_CELL_POINTS, LOUPE_ZOOM_DEFAULT_INDEX,
+	LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP, MouseScrollDelta, OverlayControl, OverlayMode,
+	OverlaySession,
+};
+
+impl OverlaySession {
+	/// Steps the live loupe zoom by `delta` levels, keeping the on-screen tile size stable.
+	pub(super) fn step_loupe_zoom(&mut self, delta: isize) -> OverlayControl {
+		if !matches!(self.state.mode, OverlayMode::Live) || self.magnifier_active {
+			return OverlayControl::Continue;
+		}
+
+		let index = self
+			.loupe_zoom_index
+			.saturating_add_signed(delta)
+			.min(LOUPE_ZOOM_CELL_POINTS.len() - 1);
+
+		if index == self.loupe_zoom_index {
+			return OverlayControl::Continue;
+		}
+
+		self.loupe_zoom_index = index;
+
+		self.apply_loupe_zoom();
+
+		tracing::debug!(
+			op = "overlay.loupe_zoom",
+			index,
+			cell_points = self.state.loupe_cell_points,
+			patch_side_px = self.loupe_patch_width_px,
+			"Loupe zoom changed."
+		);
+
+		OverlayControl::Continue
+	}
+
+	/// Converts Alt + wheel input into loupe zoom steps; trackpad pixel deltas are accumulated so
+	/// one flick does not jump across every level.
+	pub(super) fn handle_loupe_zoom_wheel(&mut self, delta: &MouseScrollDelta) -> OverlayControl {
+		let steps = match delta {
+			MouseScrollDelta::LineDelta(_, y) => {
+				self.loupe_zoom_wheel_accum_px = 0.0;
+
+				y.signum() as isize
+			},
+			MouseScrollDelta::PixelDelta(position) => {
+				self.loupe_zoom_wheel_accum_px += position.y as f32;
+
+				let steps =
+					(self.loupe_zoom_wheel_accum_px / LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP).trunc();
+
+				self.loupe_zoom_wheel_accum_px -= steps * LOUPE_ZOOM_WHEEL_PIXELS_PER_STEP;
+
+				steps as isize
+			},
+		};
+
+		if steps == 0 {
+			return OverlayControl::Continue;
+		}
+
+		self.step_loupe_zoom(steps)
+	}
+
+	pub(super) fn reset_loupe_zoom(&mut self) {
+		let (patch_side_px, _) =
+			Self::loupe_zoom_geometry(self.config.loupe_sample_side_px, LOUPE_ZOOM_DEFAULT_INDEX);
+
+		self.loupe_zoom_index = LOUPE_ZOOM_DEFAULT_INDEX;
+		self.loupe_zoom_wheel_accum_px = 0.0;
+		self.loupe_patch_width_px = patch_side_px;
+		self.loupe_patch_height_px = patch_side_px;
+	}
+
+	fn apply_loupe_zoom(&mut self) {
+		let (patch_side_px, cell_points) =
+			Self::loupe_zoom_geometry(self.config.loupe_sample_side_px, self.loupe_zoom_index);
+
+		self.loupe_patch_width_px = patch_side_px;
+		self.loupe_patch_height_px = patch_side_px;
+		self.state.loupe_patch_side_px = patch_side_px;
+		self.state.loupe_cell_points = cell_points;
+		self.state.loupe = None;
+
+		self.request_loupe_sample_for_patch_change();
+		self.request_redraw_all();
+	}
+
+	/// Returns the sampled patch side in source pixels and the cell size in points for a zoom
+	/// level, scaled so the tile covers about the same area as the configured sample size.
+	pub(super) fn loupe_zoom_geometry(base_side_px: u32, index: usize) -> (u32, f32) {
+		let cell_points = LOUPE_ZOOM_CELL_POINTS[index.min(LOUPE_ZOOM_CELL_POINTS.len() - 1)];
+		let tile_points =
+			Self::normalized_loupe_sample_side_px(base_side_px) as f32 * LOUPE_CELL_POINTS;
+		let patch_side_px = (tile_points / cell_points).round() as u32;
+
+		(Self::normalized_loupe_sample_side_px(patch_side_px), cell_points)
+	}
+}
@@ -0,0 +1,116 @@
+use crate::overlay::{
+	ActiveEventLoop, Key, KeyEvent, MAGNIFIER_LENS_SIDE_POINTS, MAGNIFIER_ZOOM_MAX,
+	MAGNIFIER_ZOOM_MIN, MonitorRect, NamedKey, OverlayControl, OverlayExit, OverlaySession,
+};
+
+impl OverlaySession {
+	/// Starts the session as a screen magnifier instead of a capture overlay.
+	///
+	/// The magnifier keeps the loupe pinned open at `OverlayConfig::magnifier_zoom`, lets mouse
+	/// input pass through to the applications underneath, and never freezes or exports a capture.
+	pub fn start_magnifier(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		self.magnifier_active = true;
+		self.magnifier_zoom = Self::clamp_magnifier_zoom(self.config.magnifier_zoom);
+
+		if let Err(err) = self.start(event_loop) {
+			self.magnifier_active = false;
+
+			return Err(err);
+		}
+
+		self.state.magnifier = true;
+
+		for overlay_window in self.windows.values() {
+			let _ = overlay_window.window.set_cursor_hittest(false);
+		}
+
+		self.apply_magnifier_geometry();
+		self.set_alt_held(true);
+
+		tracing::info!(
+			op = "overlay.magnifier_start",
+			zoom = self.magnifier_zoom,
+			"Entered magnifier mode."
+		);
+
+		Ok(())
+	}
+
+	/// Returns whether the running session is a magnifier rather than a capture overlay.
+	#[must_use]
+	pub fn is_magnifier(&self) -> bool {
+		self.magnifier_active
+	}
+
+	/// Returns whether the session only shows the loupe (magnifier or color picker), so live
+	/// selection affordances and Alt tracking stay disabled.
+	pub(super) fn is_loupe_only_session(&self) -> bool {
+		self.magnifier_active || self.color_picker_active
+	}
+
+	pub(super) fn handle_magnifier_key_event(&mut self, event: &KeyEvent) -> OverlayControl {
+		match &event.logical_key {
+			Key::Named(NamedKey::Escape) => self.exit(OverlayExit::Cancelled),
+			Key::Character(key_text) if matches!(key_text.as_str(), "+" | "=") => {
+				self.step_magnifier_zoom(1);
+
+				OverlayControl::Continue
+			},
+			Key::Character(key_text) if matches!(key_text.as_str(), "-" | "_") => {
+				self.step_magnifier_zoom(-1);
+
+				OverlayControl::Continue
+			},
+			_ => OverlayControl::Continue,
+		}
+	}
+
+	fn step_magnifier_zoom(&mut self, delta: i32) {
+		let zoom = Self::clamp_magnifier_zoom(self.magnifier_zoom.saturating_add_signed(delta));
+
+		if zoom == self.magnifier_zoom {
+			return;
+		}
+
+		self.magnifier_zoom = zoom;
+
+		self.apply_magnifier_geometry();
+	}
+
+	fn apply_magnifier_geometry(&mut self) {
+		let Some(monitor) = self
+			.active_cursor_monitor()
+			.or_else(|| self.windows.values().next().map(|window| window.monitor))
+		else {
+			return;
+		};
+		let (patch_side_px, cell_points) = Self::magnifier_geometry(monitor, self.magnifier_zoom);
+
+		self.loupe_patch_width_px = patch_side_px;
+		self.loupe_patch_height_px = patch_side_px;
+		self.state.loupe_patch_side_px = patch_side_px;
+		self.state.loupe_cell_points = cell_points;
+		self.state.loupe = None;
+
+		self.request_loupe_sample_for_patch_change();
+		self.request_redraw_all();
+	}
+
+	/// Returns the sampled patch side in source pixels and the on-screen size of one source
+	/// pixel in points, so the lens shows `zoom` times the physical screen content.
+	pub(super) fn magnifier_geometry(monitor: MonitorRect, zoom: u32) -> (u32, f32) {
+		let scale_factor = monitor.scale_factor().max(0.5);
+		let cell_points = (Self::clamp_magnifier_zoom(zoom) as f32) / scale_factor;
+		let patch_side_px = (MAGNIFIER_LENS_SIDE_POINTS / cell_points).round() as u32;
+
+		(Self::normalized_loupe_sample_side_px(patch_side_px), cell_points)
+	}
+
+	pub(super) fn clamp_magnifier_zoom(zoom: u32) -> u32 {
+		zoom.clamp(MAGNIFIER_ZOOM_MIN, MAGNIFIER_ZOOM_MAX)
+	}
+}
@@ -0,0 +1,69 @@
+use crate::memory_budget::{self, ImageCache};
+use crate::overlay::{OverlayMode, OverlaySession, image_helpers};
+
+/// The live background is never shrunk below this longest side; it only feeds the HUD blur.
+const LIVE_BG_MIN_SIDE_PX: u32 = 512;
+
+impl OverlaySession {
+	/// Records the session's image caches with the memory accountant and, over budget, halves the
+	/// live background until it fits. The HUD blur texture is rebuilt from the smaller image.
+	/// Frozen images are what the user captured and are never shrunk.
+	pub(super) fn account_image_caches(&mut self) {
+		self.memory.set_budget_mb(self.config.image_cache_budget_mb);
+		self.record_image_caches();
+
+		while self.memory.excess_bytes() > 0 {
+			let Some(image) = self.state.live_bg_image.as_ref() else {
+				break;
+			};
+			let longest_side = image.width().max(image.height());
+
+			if longest_side / 2 < LIVE_BG_MIN_SIDE_PX {
+				break;
+			}
+
+			let downscaled =
+				image_helpers::downscale_for_gpu_upload(image, longest_side / 2).into_owned();
+
+			tracing::debug!(
+				op = "overlay.live_bg_downsampled",
+				width = downscaled.width(),
+				height = downscaled.height(),
+				excess_bytes = self.memory.excess_bytes(),
+				"Image caches exceed the memory budget; the live background is downsampled."
+			);
+
+			self.state.live_bg_image = Some(downscaled);
+			self.state.live_bg_generation = self.state.live_bg_generation.wrapping_add(1);
+			self.live_bg_downsampled = true;
+
+			self.record_image_caches();
+		}
+	}
+
+	/// Peak bytes held by the image caches across this session's lifetime.
+	pub fn image_cache_high_water_bytes(&self) -> u64 {
+		self.memory.high_water_bytes()
+	}
+
+	fn record_image_caches(&mut self) {
+		let bytes_of =
+			|image: Option<&image::RgbaImage>| image.map_or(0, memory_budget::rgba_bytes);
+		let live_bg = bytes_of(self.state.live_bg_image.as_ref());
+		let frozen = bytes_of(self.state.frozen_image.as_ref());
+		let hud_bg_source = match self.state.mode {
+			_ if !self.use_fake_hud_blur() => 0,
+			OverlayMode::Live => live_bg,
+			OverlayMode::Frozen => frozen,
+		};
+
+		self.memory.record(ImageCache::LiveBg, live_bg);
+		self.memory.record(ImageCache::Frozen, frozen);
+		self.memory.record(ImageCache::FrozenWindow, bytes_of(self.frozen_window_image.as_ref()));
+		self.memory.record(
+			ImageCache::Loupe,
+			bytes_of(self.state.loupe.as_ref().map(|sample| &sample.patch)),
+		);
+		self.memory.record(ImageCache::HudBg, memory_budget::mip_chain_bytes(hud_bg_source));
+	}
+}
@@ -0,0 +1,110 @@
+//! Entry animations and spring smoothing for the floating HUD and toolbar windows.
+
+use std::time::{Duration, Instant};
+
+use egui::emath::easing;
+use egui::epaint::Primitive;
+use egui::{ClippedPrimitive, Pos2, Vec2};
+
+/// Angular frequency of the toolbar drag spring; higher follows the pointer more tightly.
+const DRAG_SPRING_OMEGA: f32 = 30.0;
+/// Distance and speed below which the drag spring snaps to its target.
+const DRAG_SPRING_REST_DISTANCE_POINTS: f32 = 0.5;
+const DRAG_SPRING_REST_SPEED_POINTS: f32 = 4.0;
+/// Frames longer than this (a stalled event loop) are not integrated in one step.
+const DRAG_SPRING_MAX_STEP: Duration = Duration::from_millis(50);
+
+/// A one-shot ease-out animation from 0 to 1, started when a window first shows.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct EntryAnimation {
+	started_at: Option<Instant>,
+	duration: Duration,
+}
+impl EntryAnimation {
+	pub(super) fn start(&mut self, now: Instant, duration: Duration) {
+		*self = Self { started_at: Some(now), duration };
+	}
+
+	/// Eased progress; 1 once finished or when the animation never started.
+	pub(super) fn progress(&self, now: Instant) -> f32 {
+		let Some(started_at) = self.started_at else {
+			return 1.0;
+		};
+
+		if self.duration.is_zero() {
+			return 1.0;
+		}
+
+		let linear =
+			now.saturating_duration_since(started_at).as_secs_f32() / self.duration.as_secs_f32();
+
+		easing::cubic_out(linear.clamp(0.0, 1.0))
+	}
+
+	pub(super) fn is_running(&self, now: Instant) -> bool {
+		self.progress(now) < 1.0
+	}
+}
+
+/// Critically damped spring in global points: it reaches the target as fast as possible
+/// without overshooting.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct DragSpring {
+	position: Pos2,
+	velocity: Vec2,
+	target: Pos2,
+	last_step_at: Instant,
+}
+impl DragSpring {
+	pub(super) fn new(position: Pos2, now: Instant) -> Self {
+		Self { position, velocity: Vec2::ZERO, target: position, last_step_at: now }
+	}
+
+	pub(super) fn set_target(&mut self, target: Pos2) {
+		self.target = target;
+	}
+
+	pub(super) fn is_settled(&self) -> bool {
+		self.position == self.target
+	}
+
+	/// Advances the spring to `now` and returns the new position.
+	pub(super) fn step(&mut self, now: Instant) -> Pos2 {
+		let dt = now.saturating_duration_since(self.last_step_at).min(DRAG_SPRING_MAX_STEP);
+
+		self.last_step_at = now;
+
+		let dt = dt.as_secs_f32();
+		let offset = self.position - self.target;
+		let decay = (-DRAG_SPRING_OMEGA * dt).exp();
+		let carry = self.velocity + offset * DRAG_SPRING_OMEGA;
+
+		self.position = self.target + (offset + carry * dt) * decay;
+		self.velocity = (self.velocity - carry * (DRAG_SPRING_OMEGA * dt)) * decay;
+
+		if (self.position - self.target).length() < DRAG_SPRING_REST_DISTANCE_POINTS
+			&& self.velocity.length() < DRAG_SPRING_REST_SPEED_POINTS
+		{
+			self.position = self.target;
+			self.velocity = Vec2::ZERO;
+		}
+
+		self.position
+	}
+}
+
+/// Scales every vertex color, fading a whole egui frame. Colors are premultiplied, so all four
+/// channels scale together.
+pub(super) fn fade_paint_jobs(paint_jobs: &mut [ClippedPrimitive], opacity: f32) {
+	if opacity >= 1.0 {
+		return;
+	}
+
+	for job in paint_jobs {
+		if let Primitive::Mesh(mesh) = &mut job.primitive {
+			for vertex in &mut mesh.vertices {
+				vertex.color = vertex.color.gamma_multiply(opacity.max(0.0));
+			}
+		}
+	}
+}
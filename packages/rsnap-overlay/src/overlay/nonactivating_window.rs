@@ -0,0 +1,102 @@
+//! Floating windows that stay on top without taking focus from the frontmost app.
+
+use winit::window::Window;
+
+#[cfg(target_os = "macos")]
+macro_rules! sel {
+	($($tt:tt)*) => {
+		objc::sel!($($tt)*)
+	};
+}
+
+#[cfg(target_os = "macos")]
+macro_rules! sel_impl {
+	($($tt:tt)*) => {
+		objc::sel_impl!($($tt)*)
+	};
+}
+
+/// Makes `window` behave like a non-activating panel: it joins every Space, floats over
+/// full-screen apps and orders front without making rsnap the active app.
+///
+/// winit windows are not `NSPanel`s, so the panel style bit is paired with the collection
+/// behavior, and a visible window is brought up with `orderFrontRegardless` rather than
+/// `makeKeyAndOrderFront:`.
+#[cfg(target_os = "macos")]
+pub(super) fn float_without_activation(window: &Window) {
+	use objc::runtime::{BOOL, NO, Object, YES};
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+	const NS_WINDOW_STYLE_MASK_NONACTIVATING_PANEL: u64 = 1 << 7;
+	const NS_WINDOW_COLLECTION_BEHAVIOR_CAN_JOIN_ALL_SPACES: u64 = 1 << 0;
+	const NS_WINDOW_COLLECTION_BEHAVIOR_STATIONARY: u64 = 1 << 4;
+	const NS_WINDOW_COLLECTION_BEHAVIOR_FULL_SCREEN_AUXILIARY: u64 = 1 << 8;
+
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+		return;
+	};
+	let ns_view = appkit.ns_view.as_ptr().cast::<Object>();
+
+	// SAFETY: `ns_view` comes from a live winit window and is only messaged on the main thread.
+	unsafe {
+		let ns_window: *mut Object = objc::msg_send![ns_view, window];
+
+		if ns_window.is_null() {
+			return;
+		}
+
+		let style_mask: u64 = objc::msg_send![ns_window, styleMask];
+		let _: () = objc::msg_send![
+			ns_window,
+			setStyleMask: style_mask | NS_WINDOW_STYLE_MASK_NONACTIVATING_PANEL
+		];
+		let behavior: u64 = objc::msg_send![ns_window, collectionBehavior];
+		let _: () = objc::msg_send![
+			ns_window,
+			setCollectionBehavior: behavior
+				| NS_WINDOW_COLLECTION_BEHAVIOR_CAN_JOIN_ALL_SPACES
+				| NS_WINDOW_COLLECTION_BEHAVIOR_STATIONARY
+				| NS_WINDOW_COLLECTION_BEHAVIOR_FULL_SCREEN_AUXILIARY
+		];
+		let _: () = objc::msg_send![ns_window, setHidesOnDeactivate: NO];
+		let visible: BOOL = objc::msg_send![ns_window, isVisible];
+
+		if visible == YES {
+			let _: () = objc::msg_send![ns_window, orderFrontRegardless];
+		}
+	}
+}
+
+/// Adds `WS_EX_NOACTIVATE`, so neither showing nor clicking the window activates it.
+#[cfg(target_os = "windows")]
+pub(super) fn float_without_activation(window: &Window) {
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use windows_sys::Win32::Foundation::HWND;
+	use windows_sys::Win32::UI::WindowsAndMessaging::{
+		GWL_EXSTYLE, GetWindowLongPtrW, SetWindowLongPtrW, WS_EX_NOACTIVATE,
+	};
+
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+		return;
+	};
+	let hwnd = handle.hwnd.get() as HWND;
+
+	// SAFETY: `hwnd` comes from a live winit window.
+	unsafe {
+		let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+
+		SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_NOACTIVATE as isize);
+	}
+}
+
+/// X11 window managers decide focus themselves, so the window is left as created.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(super) fn float_without_activation(window: &Window) {
+	let _ = window;
+}
@@ -0,0 +1,117 @@
+use std::{
+	path::{Path, PathBuf},
+	process::{Child, Command, Stdio},
+	thread,
+};
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::external_command;
+use crate::overlay::output;
+
+/// Replaced by the quoted image path in the editor command.
+const OPEN_WITH_PATH_PLACEHOLDER: &str = "{path}";
+/// Hands a URL to the default browser.
+#[cfg(target_os = "macos")]
+const URL_OPENER: &str = "open";
+#[cfg(target_os = "windows")]
+const URL_OPENER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const URL_OPENER: &str = "xdg-open";
+
+/// Hands the export to the configured editor. An export that was already saved opens in place;
+/// otherwise it is written to a temp file first. Returns the opened file.
+pub(super) fn open_png_with(
+	command: &str,
+	png_bytes: &[u8],
+	saved_path: Option<&Path>,
+) -> Result<PathBuf> {
+	let path = match saved_path {
+		Some(path) => path.to_path_buf(),
+		None => output::save_png_bytes_to_temp_dir(png_bytes)?,
+	};
+	let command_line = editor_command_line(command, &path);
+	let child = external_command::shell_command(&command_line)
+		.env("RSNAP_IMAGE_PATH", &path)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.wrap_err_with(|| format!("Failed to start external editor: {command}"))?;
+
+	tracing::info!(op = "overlay.open_with", path = %path.display(), "Opened capture in editor.");
+
+	reap_in_background(child);
+
+	Ok(path)
+}
+
+/// Opens `url` in the default browser through the platform opener, without a shell.
+pub(super) fn open_url(url: &str) -> Result<()> {
+	let child = Command::new(URL_OPENER)
+		.arg(url)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.wrap_err("Failed to open the link in the browser")?;
+
+	tracing::info!(op = "overlay.open_url", "Opened a decoded link.");
+
+	reap_in_background(child);
+
+	Ok(())
+}
+
+// Editors and browsers outlive the session; reap them off the event loop.
+fn reap_in_background(mut child: Child) {
+	let spawned = thread::Builder::new().name(String::from("rsnap-open-with")).spawn(move || {
+		match child.wait() {
+			Ok(status) if !status.success() => {
+				tracing::warn!(
+					op = "overlay.open_with",
+					%status,
+					"External program exited with an error."
+				);
+			},
+			Ok(_) => {},
+			Err(err) => {
+				tracing::warn!(
+					op = "overlay.open_with",
+					error = %err,
+					"External program wait failed."
+				);
+			},
+		}
+	});
+
+	if let Err(err) = spawned {
+		tracing::warn!(
+			op = "overlay.open_with",
+			error = %err,
+			"External program thread spawn failed."
+		);
+	}
+}
+
+/// Builds the shell command line: `{path}` is replaced by the quoted path, or the path is
+/// appended when the command has no placeholder.
+pub(super) fn editor_command_line(command: &str, path: &Path) -> String {
+	let quoted = quote_path(path);
+
+	if command.contains(OPEN_WITH_PATH_PLACEHOLDER) {
+		command.replace(OPEN_WITH_PATH_PLACEHOLDER, &quoted)
+	} else {
+		format!("{} {quoted}", command.trim_end())
+	}
+}
+
+#[cfg(target_os = "windows")]
+fn quote_path(path: &Path) -> String {
+	format!("\"{}\"", path.display())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn quote_path(path: &Path) -> String {
+	format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
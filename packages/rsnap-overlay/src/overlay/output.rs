@@ -1,9 +1,8 @@
-#[cfg(not(target_os = "macos"))]
-use std::borrow::Cow;
 #[cfg(target_os = "macos")]
 use std::ffi::CString;
 use std::{
-	fs,
+	borrow::Cow,
+	env, fs,
 	path::{Path, PathBuf},
 	time::{SystemTime, UNIX_EPOCH},
 };
@@ -14,10 +13,16 @@ use arboard::ImageData;
 #[cfg(target_os = "macos")]
 use color_eyre::eyre;
 use color_eyre::eyre::{Result, WrapErr};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{ExtendedColorType, ImageEncoder, Rgb, RgbImage, RgbaImage};
 #[cfg(target_os = "macos")]
 use objc::runtime::{BOOL, Object, YES};
 
-use crate::overlay::{OutputNaming, OverlayConfig};
+use crate::interval_capture;
+use crate::overlay::{ExportFormat, OutputNaming, OverlayConfig};
+use crate::png;
+use crate::project::PROJECT_EXTENSION;
 
 #[cfg(target_os = "macos")]
 macro_rules! sel {
@@ -33,10 +38,107 @@ macro_rules! sel_impl {
 	};
 }
 
+/// An `@2x` export and its half-size `@1x` copy, saved side by side.
+#[derive(Debug)]
+pub(super) struct AssetPairPaths {
+	pub(super) one_x: PathBuf,
+	pub(super) two_x: PathBuf,
+}
+
+/// Saves the export to the output directory, re-encoded in the configured export format.
 pub(super) fn save_png_bytes_to_configured_dir(
 	png_bytes: &[u8],
 	config: &OverlayConfig,
 ) -> Result<PathBuf> {
+	let target_path = next_configured_output_path(config, config.export_format.extension())?;
+
+	write_png_bytes_atomic(&target_path, &encode_export(png_bytes, config)?)?;
+
+	Ok(target_path)
+}
+
+/// Saves the export in the configured format under the system temp directory, for copying it
+/// as a file.
+pub(super) fn save_export_to_temp_dir(png_bytes: &[u8], config: &OverlayConfig) -> Result<PathBuf> {
+	let target_path = next_temp_path(config.export_format.extension())?;
+
+	write_png_bytes_atomic(&target_path, &encode_export(png_bytes, config)?)?;
+
+	Ok(target_path)
+}
+
+/// Saves a project as `<name>.rsnap` in the output directory, numbered like the PNG exports.
+pub(super) fn save_project_bytes_to_configured_dir(
+	project_bytes: &[u8],
+	config: &OverlayConfig,
+) -> Result<PathBuf> {
+	let target_path = next_configured_output_path(config, PROJECT_EXTENSION)?;
+
+	write_png_bytes_atomic(&target_path, project_bytes)?;
+
+	Ok(target_path)
+}
+
+/// Saves the export under the system temp directory, for handing it to another application.
+pub(super) fn save_png_bytes_to_temp_dir(png_bytes: &[u8]) -> Result<PathBuf> {
+	let target_path = next_temp_path("png")?;
+
+	write_png_bytes_atomic(&target_path, png_bytes)?;
+
+	Ok(target_path)
+}
+
+/// Saves an asset pair as `<name>.png` and `<name>@2x.png` in the output directory.
+pub(super) fn save_asset_pair_to_configured_dir(
+	png_2x: &[u8],
+	png_1x: &[u8],
+	config: &OverlayConfig,
+) -> Result<AssetPairPaths> {
+	write_asset_pair(next_configured_output_path(config, "png")?, png_2x, png_1x)
+}
+
+/// Saves an asset pair under the system temp directory, so both files can be copied.
+pub(super) fn save_asset_pair_to_temp_dir(png_2x: &[u8], png_1x: &[u8]) -> Result<AssetPairPaths> {
+	write_asset_pair(next_temp_path("png")?, png_2x, png_1x)
+}
+
+/// Saves related exports side by side as `<name>-<label>-<n>.<ext>` in the output directory, in
+/// the configured export format and numbered from 1 in the given order.
+pub(super) fn save_png_set_to_configured_dir(
+	png_set: &[Vec<u8>],
+	label: &str,
+	config: &OverlayConfig,
+) -> Result<Vec<PathBuf>> {
+	let extension = config.export_format.extension();
+	let base = next_configured_output_path(config, extension)?;
+	let stem = base.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+	let label = sanitize_output_filename_prefix(label);
+
+	png_set
+		.iter()
+		.enumerate()
+		.map(|(index, png_bytes)| {
+			let path = base.with_file_name(format!("{stem}-{label}-{}.{extension}", index + 1));
+
+			write_png_bytes_atomic(&path, &encode_export(png_bytes, config)?)?;
+
+			Ok(path)
+		})
+		.collect()
+}
+
+fn write_asset_pair(one_x: PathBuf, png_2x: &[u8], png_1x: &[u8]) -> Result<AssetPairPaths> {
+	let stem =
+		one_x.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+	let two_x = one_x.with_file_name(format!("{stem}@2x.png"));
+
+	write_png_bytes_atomic(&one_x, png_1x)?;
+	write_png_bytes_atomic(&two_x, png_2x)?;
+
+	Ok(AssetPairPaths { one_x, two_x })
+}
+
+fn next_configured_output_path(config: &OverlayConfig, extension: &str) -> Result<PathBuf> {
 	let output_dir = if config.output_dir.as_os_str().is_empty() {
 		PathBuf::from(".")
 	} else {
@@ -47,16 +149,168 @@ pub(super) fn save_png_bytes_to_configured_dir(
 		.wrap_err_with(|| format!("Failed to create output directory: {}", output_dir.display()))?;
 
 	let prefix = sanitize_output_filename_prefix(&config.output_filename_prefix);
-	let target_path = next_output_png_path(&output_dir, &prefix, config.output_naming);
 
-	write_png_bytes_atomic(&target_path, png_bytes)?;
+	if config.output_filename_template.trim().is_empty() {
+		return Ok(next_output_path(&output_dir, &prefix, config.output_naming, extension));
+	}
 
-	Ok(target_path)
+	let base = render_filename_template(
+		&config.output_filename_template,
+		&prefix,
+		current_unix_millis(),
+		|head, tail| next_sequence_index(&output_dir, head, tail),
+	);
+
+	Ok(unique_output_path(&output_dir, &base, extension))
+}
+
+fn next_temp_path(extension: &str) -> Result<PathBuf> {
+	let temp_dir = env::temp_dir().join("rsnap");
+
+	fs::create_dir_all(&temp_dir)
+		.wrap_err_with(|| format!("Failed to create temp directory: {}", temp_dir.display()))?;
+
+	Ok(next_output_path(&temp_dir, "rsnap", OutputNaming::Timestamp, extension))
+}
+
+/// Example file name for the given output naming settings, as the next save would name it in an
+/// empty directory. Shown as a live preview next to the filename template editor.
+pub fn output_filename_preview(
+	template: &str,
+	prefix: &str,
+	naming: OutputNaming,
+	format: ExportFormat,
+) -> String {
+	let prefix = sanitize_output_filename_prefix(prefix);
+	let base = if template.trim().is_empty() {
+		match naming {
+			OutputNaming::Timestamp => format!("{prefix}-{}", current_unix_millis()),
+			OutputNaming::Sequence => format!("{prefix}-0001"),
+		}
+	} else {
+		render_filename_template(template, &prefix, current_unix_millis(), |_, _| 1)
+	};
+
+	format!("{base}.{}", format.extension())
+}
+
+/// Expands `{prefix}`, `{date}`, `{time}` (UTC), `{ts}` (Unix milliseconds) and `{seq}` in a
+/// filename template. `next_seq` receives the expanded text around the first `{seq}` and returns
+/// the number to put there.
+fn render_filename_template(
+	template: &str,
+	prefix: &str,
+	unix_millis: u128,
+	next_seq: impl FnOnce(&str, &str) -> u32,
+) -> String {
+	let (date, time) = interval_capture::utc_date_and_time((unix_millis / 1_000) as u64);
+	let expand = |text: &str| {
+		sanitize_filename_part(
+			&text
+				.replace("{prefix}", prefix)
+				.replace("{date}", &date)
+				.replace("{time}", &time)
+				.replace("{ts}", &unix_millis.to_string()),
+		)
+	};
+	let base = match template.trim().split_once("{seq}") {
+		Some((head, tail)) => {
+			let head = expand(head);
+			let tail = expand(&tail.replace("{seq}", ""));
+
+			format!("{head}{:04}{tail}", next_seq(&head, &tail))
+		},
+		None => expand(template.trim()),
+	};
+	let base = base.trim_start_matches('.');
+
+	if base.is_empty() { String::from("rsnap") } else { base.to_owned() }
+}
+
+/// Re-encodes a PNG export in the configured format; PNG exports pass through untouched.
+fn encode_export<'a>(png_bytes: &'a [u8], config: &OverlayConfig) -> Result<Cow<'a, [u8]>> {
+	let decode = || -> Result<RgbaImage> {
+		Ok(image::load_from_memory(png_bytes).wrap_err("Failed to decode PNG bytes")?.to_rgba8())
+	};
+	let mut bytes = Vec::new();
+
+	match config.export_format {
+		ExportFormat::Png => return Ok(Cow::Borrowed(png_bytes)),
+		ExportFormat::Jpeg => {
+			let flattened = flatten_onto_white(&decode()?);
+
+			JpegEncoder::new_with_quality(&mut bytes, config.export_quality.clamp(1, 100))
+				.encode_image(&flattened)
+				.wrap_err("Failed to encode JPEG")?;
+		},
+		ExportFormat::Webp => {
+			let image = decode()?;
+
+			WebPEncoder::new_lossless(&mut bytes)
+				.write_image(
+					image.as_raw(),
+					image.width(),
+					image.height(),
+					ExtendedColorType::Rgba8,
+				)
+				.wrap_err("Failed to encode WebP")?;
+		},
+	}
+
+	Ok(Cow::Owned(bytes))
+}
+
+/// JPEG has no alpha channel, so transparent pixels are blended onto white.
+fn flatten_onto_white(image: &RgbaImage) -> RgbImage {
+	RgbImage::from_fn(image.width(), image.height(), |x, y| {
+		let [r, g, b, a] = image.get_pixel(x, y).0;
+		let blend = |channel: u8| {
+			((u16::from(channel) * u16::from(a) + 255 * (255 - u16::from(a)) + 127) / 255) as u8
+		};
+
+		Rgb([blend(r), blend(g), blend(b)])
+	})
 }
 
+/// Summarizes an exported PNG for alt-text and size notes, e.g. `1920×1080 PNG, 412 KB`.
+pub(super) fn export_summary(png_bytes: &[u8]) -> Option<String> {
+	let (width, height) = png::png_dimensions(png_bytes)?;
+
+	Some(format!("{width}×{height} PNG, {}", format_byte_size(png_bytes.len())))
+}
+
+fn format_byte_size(bytes: usize) -> String {
+	const KIB: usize = 1_024;
+	const MIB: usize = KIB * KIB;
+
+	if bytes < KIB {
+		format!("{bytes} B")
+	} else if bytes < MIB {
+		format!("{} KB", (bytes + KIB / 2) / KIB)
+	} else {
+		format!("{:.1} MB", bytes as f64 / MIB as f64)
+	}
+}
+
+/// Copies an encoded PNG to the clipboard, as a Copy step would, for callers outside a session.
+pub fn copy_png_to_clipboard(png_bytes: &[u8]) -> Result<()> {
+	write_png_bytes_to_clipboard(png_bytes, None)
+}
+
+/// Saves an encoded PNG to the configured output directory, as a Save step would, for callers
+/// outside a session.
+pub fn save_png_to_output_dir(png_bytes: &[u8], config: &OverlayConfig) -> Result<PathBuf> {
+	save_png_bytes_to_configured_dir(png_bytes, config)
+}
+
+/// Copies the PNG to the general pasteboard. When `summary` is set it is stored on the same
+/// pasteboard item as plain text, so pasting into a text field yields the summary instead.
 #[cfg(target_os = "macos")]
-pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8]) -> Result<()> {
+pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8], summary: Option<&str>) -> Result<()> {
 	let pasteboard_type = CString::new("public.png").wrap_err("Invalid NSPasteboard type")?;
+	let text_type =
+		CString::new("public.utf8-plain-text").wrap_err("Invalid NSPasteboard text type")?;
+	let summary = summary.map(CString::new).transpose().wrap_err("Invalid export summary")?;
 
 	unsafe {
 		let data: *mut Object = objc::msg_send![objc::class!(NSData), dataWithBytes: png_bytes.as_ptr() length: png_bytes.len()];
@@ -70,13 +324,67 @@ pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8]) -> Result<()> {
 		if ok != YES {
 			return Err(eyre::eyre!("NSPasteboard setData:forType failed"));
 		}
+
+		if let Some(summary) = summary {
+			let text: *mut Object =
+				objc::msg_send![objc::class!(NSString), stringWithUTF8String: summary.as_ptr()];
+			let ty: *mut Object =
+				objc::msg_send![objc::class!(NSString), stringWithUTF8String: text_type.as_ptr()];
+			let ok: BOOL = objc::msg_send![pasteboard, setString: text forType: ty];
+
+			if ok != YES {
+				return Err(eyre::eyre!("NSPasteboard setString:forType failed"));
+			}
+		}
 	}
 
 	Ok(())
 }
 
+/// Returns the general pasteboard's change counter, which advances whenever another owner writes.
+#[cfg(target_os = "macos")]
+pub(crate) fn pasteboard_change_count() -> isize {
+	unsafe {
+		let pasteboard: *mut Object =
+			objc::msg_send![objc::class!(NSPasteboard), generalPasteboard];
+
+		objc::msg_send![pasteboard, changeCount]
+	}
+}
+
+/// Adds plain text to the current pasteboard item unless something else has been copied since
+/// `change_count` was read. Returns whether the text was added.
+#[cfg(target_os = "macos")]
+pub(super) fn add_text_to_pasteboard_if_unchanged(text: &str, change_count: isize) -> Result<bool> {
+	let text = CString::new(text).wrap_err("Invalid pasteboard text")?;
+	let text_type =
+		CString::new("public.utf8-plain-text").wrap_err("Invalid NSPasteboard text type")?;
+
+	if pasteboard_change_count() != change_count {
+		return Ok(false);
+	}
+
+	unsafe {
+		let pasteboard: *mut Object =
+			objc::msg_send![objc::class!(NSPasteboard), generalPasteboard];
+		let text: *mut Object =
+			objc::msg_send![objc::class!(NSString), stringWithUTF8String: text.as_ptr()];
+		let ty: *mut Object =
+			objc::msg_send![objc::class!(NSString), stringWithUTF8String: text_type.as_ptr()];
+		let ok: BOOL = objc::msg_send![pasteboard, setString: text forType: ty];
+
+		if ok != YES {
+			return Err(eyre::eyre!("NSPasteboard setString:forType failed"));
+		}
+	}
+
+	Ok(true)
+}
+
+/// Copies the PNG to the clipboard. arboard replaces the whole clipboard per write, so the
+/// export summary cannot ride along with the image here.
 #[cfg(not(target_os = "macos"))]
-pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8]) -> Result<()> {
+pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8], _summary: Option<&str>) -> Result<()> {
 	let image = image::load_from_memory(png_bytes).wrap_err("Failed to decode PNG bytes")?;
 	let rgba = image.to_rgba8();
 	let (width, height) = rgba.dimensions();
@@ -93,6 +401,15 @@ pub(super) fn write_png_bytes_to_clipboard(png_bytes: &[u8]) -> Result<()> {
 	Ok(())
 }
 
+/// Places `paths` on the clipboard as files, for pasting into a file manager or design tool.
+pub(super) fn write_files_to_clipboard(paths: &[&Path]) -> Result<()> {
+	let mut clipboard = Clipboard::new().wrap_err("Failed to initialize clipboard")?;
+
+	clipboard.set().file_list(paths).wrap_err("Failed to write files to clipboard")?;
+
+	Ok(())
+}
+
 pub(super) fn write_text_to_clipboard(text: &str) -> Result<()> {
 	let mut clipboard = Clipboard::new().wrap_err("Failed to initialize clipboard")?;
 
@@ -118,36 +435,52 @@ fn sanitize_output_filename_prefix(raw: &str) -> String {
 	if sanitized.is_empty() { String::from("rsnap") } else { sanitized.to_owned() }
 }
 
-fn next_output_png_path(output_dir: &Path, prefix: &str, naming: OutputNaming) -> PathBuf {
+/// Replaces characters that are unsafe in file names, keeping separators like `_` at the ends so
+/// template text can sit flush against `{seq}`.
+fn sanitize_filename_part(raw: &str) -> String {
+	raw.chars()
+		.map(
+			|ch| {
+				if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') { ch } else { '_' }
+			},
+		)
+		.collect()
+}
+
+fn next_output_path(
+	output_dir: &Path,
+	prefix: &str,
+	naming: OutputNaming,
+	extension: &str,
+) -> PathBuf {
 	let base = match naming {
 		OutputNaming::Timestamp => format!("{prefix}-{}", current_unix_millis()),
 		OutputNaming::Sequence => {
-			format!("{prefix}-{:04}", next_sequence_index(output_dir, prefix))
+			format!("{prefix}-{:04}", next_sequence_index(output_dir, &format!("{prefix}-"), ""))
 		},
 	};
 
-	unique_png_path(output_dir, &base)
+	unique_output_path(output_dir, &base, extension)
 }
 
 fn current_unix_millis() -> u128 {
 	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis())
 }
 
-fn next_sequence_index(output_dir: &Path, prefix: &str) -> u32 {
+/// One past the highest number `n` among files named `<head><n><tail>.<any extension>`, so
+/// exports in different formats share one sequence.
+fn next_sequence_index(output_dir: &Path, head: &str, tail: &str) -> u32 {
 	let Ok(entries) = fs::read_dir(output_dir) else {
 		return 1;
 	};
 	let mut max_seen = 0_u32;
 
 	for entry in entries.flatten() {
-		let file_name = entry.file_name();
-		let Some(file_name) = file_name.to_str() else {
-			continue;
-		};
-		let Some(stem) = file_name.strip_suffix(".png") else {
+		let path = entry.path();
+		let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
 			continue;
 		};
-		let Some(number_text) = stem.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('-'))
+		let Some(number_text) = stem.strip_prefix(head).and_then(|rest| rest.strip_suffix(tail))
 		else {
 			continue;
 		};
@@ -164,8 +497,8 @@ fn next_sequence_index(output_dir: &Path, prefix: &str) -> u32 {
 	max_seen.saturating_add(1).max(1)
 }
 
-fn unique_png_path(output_dir: &Path, base: &str) -> PathBuf {
-	let direct_path = output_dir.join(format!("{base}.png"));
+fn unique_output_path(output_dir: &Path, base: &str, extension: &str) -> PathBuf {
+	let direct_path = output_dir.join(format!("{base}.{extension}"));
 
 	if !direct_path.exists() {
 		return direct_path;
@@ -174,7 +507,7 @@ fn unique_png_path(output_dir: &Path, base: &str) -> PathBuf {
 	let mut suffix = 2_u32;
 
 	loop {
-		let candidate = output_dir.join(format!("{base}-{suffix}.png"));
+		let candidate = output_dir.join(format!("{base}-{suffix}.{extension}"));
 
 		if !candidate.exists() {
 			return candidate;
@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use crate::overlay::{OverlayControl, OverlayMode, OverlaySession};
+use crate::state::{MonitorRect, PerfHudMetrics};
+use crate::worker::WORKER_QUEUE_CAPACITY;
+
+/// How often the performance HUD readings refresh; also bounds the redraws it causes.
+const PERF_HUD_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+/// Weight of the newest frame/sample in the smoothed readings.
+const PERF_HUD_SMOOTHING: f32 = 0.1;
+
+/// Running counters behind the performance HUD, kept across overlay sessions while it is on.
+#[derive(Debug, Default)]
+pub(super) struct PerfHudCounters {
+	frame_time_ms: Option<f32>,
+	frame_time_max_ms: f32,
+	frames_since_refresh: u32,
+	sample_latency_ms: Option<f32>,
+	last_refresh_at: Option<Instant>,
+}
+impl PerfHudCounters {
+	pub(super) fn record_frame(&mut self, elapsed: Duration) {
+		let elapsed_ms = elapsed.as_secs_f32() * 1_000.0;
+
+		self.frame_time_ms = Some(smooth(self.frame_time_ms, elapsed_ms));
+		self.frame_time_max_ms = self.frame_time_max_ms.max(elapsed_ms);
+		self.frames_since_refresh = self.frames_since_refresh.saturating_add(1);
+	}
+
+	pub(super) fn record_sample_latency(&mut self, latency: Duration) {
+		self.sample_latency_ms =
+			Some(smooth(self.sample_latency_ms, latency.as_secs_f32() * 1_000.0));
+	}
+
+	/// Returns fresh readings once per refresh interval and starts the next window.
+	pub(super) fn refresh(
+		&mut self,
+		now: Instant,
+		queue_depth: usize,
+		dropped_requests: u64,
+	) -> Option<PerfHudMetrics> {
+		let since_refresh = self.last_refresh_at.map(|at| now.saturating_duration_since(at));
+
+		if since_refresh.is_some_and(|elapsed| elapsed < PERF_HUD_REFRESH_INTERVAL) {
+			return None;
+		}
+
+		let redraws_per_second = since_refresh
+			.filter(|elapsed| !elapsed.is_zero())
+			.map_or(0.0, |elapsed| self.frames_since_refresh as f32 / elapsed.as_secs_f32());
+		let metrics = PerfHudMetrics {
+			frame_time_ms: self.frame_time_ms.unwrap_or_default(),
+			frame_time_max_ms: self.frame_time_max_ms,
+			redraws_per_second,
+			sample_latency_ms: self.sample_latency_ms,
+			queue_depth,
+			queue_capacity: WORKER_QUEUE_CAPACITY,
+			dropped_requests,
+		};
+
+		self.frame_time_max_ms = 0.0;
+		self.frames_since_refresh = 0;
+		self.last_refresh_at = Some(now);
+
+		Some(metrics)
+	}
+}
+
+fn smooth(previous: Option<f32>, sample: f32) -> f32 {
+	previous.map_or(sample, |previous| previous + (sample - previous) * PERF_HUD_SMOOTHING)
+}
+
+impl OverlaySession {
+	/// Shows or hides the performance HUD (F12).
+	pub(super) fn toggle_perf_hud(&mut self) -> OverlayControl {
+		if self.perf_hud.take().is_none() {
+			self.perf_hud = Some(PerfHudCounters::default());
+		}
+
+		self.state.perf_hud = self.perf_hud.as_ref().map(|_| PerfHudMetrics::default());
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	pub(super) fn record_perf_hud_frame(&mut self, elapsed: Duration) {
+		let Some(counters) = self.perf_hud.as_mut() else {
+			return;
+		};
+
+		counters.record_frame(elapsed);
+
+		let (queue_depth, dropped_requests) = self
+			.worker
+			.as_ref()
+			.map_or((0, 0), |worker| (worker.queue_depth(), worker.dropped_requests()));
+		let Some(metrics) = counters.refresh(Instant::now(), queue_depth, dropped_requests) else {
+			return;
+		};
+
+		self.state.perf_hud = Some(metrics);
+
+		if let Some(monitor) = self.perf_hud_monitor() {
+			self.request_redraw_for_monitor(monitor);
+		}
+	}
+
+	pub(super) fn record_perf_hud_sample_latency(&mut self, latency: Duration) {
+		if let Some(counters) = self.perf_hud.as_mut() {
+			counters.record_sample_latency(latency);
+		}
+	}
+
+	fn perf_hud_monitor(&self) -> Option<MonitorRect> {
+		match self.state.mode {
+			OverlayMode::Frozen => self.state.monitor,
+			OverlayMode::Live => self.state.cursor.and_then(|cursor| self.monitor_at(cursor)),
+		}
+	}
+}
@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::Path;
+
+use winit::event_loop::ActiveEventLoop;
+
+use crate::annotation::AnnotationLayer;
+use crate::overlay::{
+	MonitorRect, OverlayControl, OverlayExit, OverlayMode, OverlaySession, RectPoints, output,
+};
+use crate::project::{self, Project};
+
+impl OverlaySession {
+	/// Opens a saved `.rsnap` project in the frozen editor, with its selection, annotations and
+	/// undo history as they were saved.
+	pub fn start_project(
+		&mut self,
+		event_loop: &ActiveEventLoop,
+		path: &Path,
+	) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		let bytes =
+			fs::read(path).map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+		let project = project::decode_project(&bytes)
+			.map_err(|err| format!("Failed to open {}: {err:#}", path.display()))?;
+		let monitor = project.monitor(&Self::available_overlay_monitors()?).ok_or_else(|| {
+			let (width, height) = project.image.dimensions();
+
+			format!("No connected display matches the project's {width}x{height} capture")
+		})?;
+
+		self.start(event_loop)?;
+
+		tracing::info!(
+			op = "overlay.open_project",
+			path = %path.display(),
+			monitor_id = monitor.id,
+			crop = ?project.crop,
+			"Opening a saved project."
+		);
+
+		self.restore_project(monitor, project)
+	}
+
+	/// Opens a PNG, such as an earlier capture, in the frozen editor so it can be annotated and
+	/// exported again. The image sits centred on the first display large enough to hold it.
+	pub fn start_image(&mut self, event_loop: &ActiveEventLoop, path: &Path) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		let image = image::open(path)
+			.map_err(|err| format!("Failed to open {}: {err}", path.display()))?
+			.to_rgba8();
+		let (monitor, project) = Project::from_image(&image, &Self::available_overlay_monitors()?)
+			.ok_or_else(|| {
+				let (width, height) = image.dimensions();
+
+				format!("No connected display is large enough for the {width}x{height} image")
+			})?;
+
+		self.start(event_loop)?;
+
+		tracing::info!(
+			op = "overlay.open_image",
+			path = %path.display(),
+			monitor_id = monitor.id,
+			"Opening an image in the editor."
+		);
+
+		self.restore_project(monitor, project)
+	}
+
+	/// Freezes `monitor` on the project's image instead of a fresh capture.
+	pub(super) fn restore_project(
+		&mut self,
+		monitor: MonitorRect,
+		project: Project,
+	) -> Result<(), String> {
+		// A live frame already on hand would otherwise be adopted as the freeze.
+		self.state.live_bg_monitor = None;
+		self.state.live_bg_image = None;
+
+		self.begin_frozen_capture_with_rect(monitor, Some(project.crop), None, None);
+
+		if !matches!(self.state.mode, OverlayMode::Frozen) {
+			return Err(self
+				.state
+				.error_message
+				.clone()
+				.unwrap_or_else(|| String::from("Failed to freeze the project's display")));
+		}
+
+		self.pending_freeze_capture = None;
+		self.pending_freeze_capture_armed = false;
+		self.pending_freeze_cursor = None;
+
+		self.handle_captured_freeze_response(monitor, project.image, None, None);
+
+		self.state.annotations = AnnotationLayer::from_saved(project.annotations);
+
+		self.annotations_changed();
+
+		Ok(())
+	}
+
+	/// Saves the frozen capture as a `.rsnap` project in the output directory and ends the
+	/// session, like Save does for the PNG.
+	pub(super) fn save_frozen_project(&mut self) -> OverlayControl {
+		if !matches!(self.state.mode, OverlayMode::Frozen) || self.scroll_capture.active {
+			return OverlayControl::Continue;
+		}
+
+		let (Some(monitor), Some(image)) = (self.state.monitor, self.state.frozen_image.clone())
+		else {
+			return OverlayControl::Continue;
+		};
+		let crop = self
+			.state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let project = Project::new(image, monitor, crop, &self.state.annotations);
+		let saved = project::encode_project(&project)
+			.and_then(|bytes| output::save_project_bytes_to_configured_dir(&bytes, &self.config));
+
+		match saved {
+			Ok(path) => {
+				tracing::info!(op = "overlay.save_project", path = %path.display(), "Saved a project.");
+
+				self.exit(OverlayExit::Saved(path))
+			},
+			Err(err) => {
+				self.state.set_error(format!("Failed to save the project: {err:#}"));
+				self.request_redraw_for_monitor(monitor);
+
+				OverlayControl::Continue
+			},
+		}
+	}
+}
@@ -0,0 +1,140 @@
+use crate::overlay::{OverlayControl, OverlayMode, OverlaySession, open_with, output};
+use crate::recognition::DecodedCode;
+use crate::state::{GlobalPoint, MonitorRect, RectPoints};
+use crate::worker::CodeScanSource;
+
+impl OverlaySession {
+	/// Looks for QR codes in the frozen selection, or in live mode in the hovered window (the
+	/// whole display under the cursor when no window is hovered).
+	pub(super) fn scan_for_codes(&mut self) -> OverlayControl {
+		if self.scroll_capture.active {
+			return OverlayControl::Continue;
+		}
+
+		let scan = match self.state.mode {
+			OverlayMode::Frozen => self.state.monitor.and_then(|monitor| {
+				let image = self.cropped_frozen_capture_image()?;
+				let capture_rect = self
+					.state
+					.frozen_capture_rect
+					.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+				let capture_px = monitor.local_rect_to_pixels(capture_rect);
+
+				Some((
+					monitor,
+					CodeScanSource::Image { image, origin_px: (capture_px.x, capture_px.y) },
+				))
+			}),
+			OverlayMode::Live => self.active_cursor_monitor().map(|monitor| {
+				let rect = self
+					.state
+					.hovered_window_rect
+					.filter(|hovered| hovered.monitor_id == monitor.id)
+					.map_or_else(
+						|| RectPoints::new(0, 0, monitor.width, monitor.height),
+						|hovered| hovered.rect,
+					);
+
+				(
+					monitor,
+					CodeScanSource::MonitorRegion {
+						monitor,
+						rect_px: monitor.local_rect_to_pixels(rect),
+					},
+				)
+			}),
+		};
+		let Some((monitor, source)) = scan else {
+			return OverlayControl::Continue;
+		};
+
+		self.frozen_request_ids.code_scan = self.frozen_request_ids.code_scan.wrapping_add(1);
+		self.state.decoded_codes.clear();
+		self.state.decoded_codes_monitor = Some(monitor);
+
+		let sent = self.worker.as_ref().is_some_and(|worker| {
+			worker.request_code_scan(source, self.frozen_request_ids.code_scan)
+		});
+
+		if sent {
+			self.state.set_status("Scanning for QR codes...");
+		} else {
+			tracing::debug!(
+				op = "overlay.code_scan",
+				request_id = self.frozen_request_ids.code_scan,
+				"QR scan request dropped: worker queue unavailable."
+			);
+		}
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	pub(super) fn handle_scanned_codes_response(
+		&mut self,
+		request_id: u64,
+		codes: Vec<DecodedCode>,
+	) {
+		if request_id != self.frozen_request_ids.code_scan {
+			return;
+		}
+
+		tracing::info!(op = "overlay.code_scan", codes = codes.len(), "QR scan finished.");
+
+		match codes.as_slice() {
+			[] => self.state.set_status("No QR code found."),
+			[code] if code.is_url() => self.state.set_status(format!(
+				"QR link: {}. Click to copy, {}-click to open.",
+				code.payload.trim(),
+				save_modifier_label()
+			)),
+			[code] => self.state.set_status(format!("QR code: {}. Click to copy.", code.payload)),
+			_ => self
+				.state
+				.set_status(format!("Found {} QR codes. Click one to copy it.", codes.len())),
+		}
+
+		self.state.decoded_codes = codes;
+
+		self.request_redraw_all();
+	}
+
+	/// Copies the payload of the code under `global`, or opens it when it is a link and the save
+	/// shortcut modifier is held. Returns `false` when there is no code there.
+	pub(super) fn activate_code_at(&mut self, monitor: MonitorRect, global: GlobalPoint) -> bool {
+		if self.state.decoded_codes_monitor != Some(monitor) {
+			return false;
+		}
+
+		let Some(local) = monitor.local_u32(global) else {
+			return false;
+		};
+		let point = self.state.frozen_view.pixel_at(local, monitor.scale_factor());
+		let Some(code) =
+			self.state.decoded_codes.iter().rev().find(|code| code.bounds_px.contains(point))
+		else {
+			return false;
+		};
+		let result = if code.is_url() && self.is_save_shortcut_pressed() {
+			open_with::open_url(code.payload.trim())
+				.map(|()| format!("Opened {}", code.payload.trim()))
+		} else {
+			output::write_text_to_clipboard(&code.payload)
+				.map(|()| String::from("Copied the QR code payload."))
+		};
+
+		match result {
+			Ok(status) => self.state.set_status(status),
+			Err(err) => self.state.set_error(format!("{err:#}")),
+		}
+
+		self.request_redraw_all();
+
+		true
+	}
+}
+
+fn save_modifier_label() -> &'static str {
+	if cfg!(target_os = "macos") { "⌘" } else { "Ctrl" }
+}
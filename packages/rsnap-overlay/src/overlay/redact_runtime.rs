@@ -0,0 +1,127 @@
+use crate::overlay::{OverlayControl, OverlayMode, OverlaySession};
+use crate::redact::RedactionProposal;
+use crate::state::{GlobalPoint, RectPoints};
+
+impl OverlaySession {
+	/// Sends the frozen selection to the configured OCR command to look for text worth redacting.
+	pub(super) fn scan_for_redactions(&mut self) -> OverlayControl {
+		if !matches!(self.state.mode, OverlayMode::Frozen) || self.scroll_capture.active {
+			return OverlayControl::Continue;
+		}
+
+		let Some(command) = self.config.redact_ocr_command.clone() else {
+			self.state.set_error("No OCR command is configured. Set one in Settings → Output.");
+			self.request_redraw_all();
+
+			return OverlayControl::Continue;
+		};
+		let Some(image) = self.cropped_frozen_capture_image() else {
+			return OverlayControl::Continue;
+		};
+
+		self.frozen_request_ids.redaction_scan =
+			self.frozen_request_ids.redaction_scan.wrapping_add(1);
+
+		let sent = self.worker.as_ref().is_some_and(|worker| {
+			worker.request_redaction_scan(image, command, self.frozen_request_ids.redaction_scan)
+		});
+
+		if sent {
+			self.state.set_status("Scanning for sensitive text...");
+		} else {
+			tracing::debug!(
+				op = "overlay.redaction_scan",
+				request_id = self.frozen_request_ids.redaction_scan,
+				"Redaction scan request dropped: worker unavailable."
+			);
+		}
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	pub(super) fn handle_scanned_redactions_response(
+		&mut self,
+		request_id: u64,
+		proposals: Vec<RedactionProposal>,
+	) {
+		if request_id != self.frozen_request_ids.redaction_scan
+			|| !matches!(self.state.mode, OverlayMode::Frozen)
+		{
+			return;
+		}
+
+		tracing::info!(
+			op = "overlay.redaction_scan",
+			proposals = proposals.len(),
+			"Redaction scan finished."
+		);
+
+		if proposals.is_empty() {
+			self.state.set_status("No sensitive text found.");
+		} else {
+			self.state.set_status(format!(
+				"Found {} possible secret(s). Click each one to redact it.",
+				proposals.len()
+			));
+		}
+
+		self.state.redactions = proposals;
+
+		self.request_redraw_all();
+	}
+
+	/// Accepts or skips the redaction proposal under `global`; `false` when there is none.
+	pub(super) fn toggle_redaction_at(&mut self, global: GlobalPoint) -> bool {
+		let Some(point) = self.selection_pixel_at(global) else {
+			return false;
+		};
+		let Some(proposal) = self
+			.state
+			.redactions
+			.iter_mut()
+			.rev()
+			.find(|proposal| proposal.rect_px.contains(point))
+		else {
+			return false;
+		};
+
+		proposal.accepted = !proposal.accepted;
+
+		self.request_export_preview();
+
+		true
+	}
+
+	/// Maps `global` into pixels of the frozen selection image that redactions and stamps are
+	/// measured in; `None` outside the selection.
+	pub(super) fn selection_pixel_at(&self, global: GlobalPoint) -> Option<(u32, u32)> {
+		let ((x, y), (width, height)) = self.selection_pixel_unclipped_at(global)?;
+
+		((0..i64::from(width)).contains(&x) && (0..i64::from(height)).contains(&y))
+			.then_some((x as u32, y as u32))
+	}
+
+	/// Like [`Self::selection_pixel_at`], but also maps points on the frozen monitor outside the
+	/// selection, which callout drags can reach. Returns the selection size alongside. Points go
+	/// through the frozen view, so a zoomed preview maps back to native pixels.
+	pub(super) fn selection_pixel_unclipped_at(
+		&self,
+		global: GlobalPoint,
+	) -> Option<((i64, i64), (u32, u32))> {
+		let monitor = self.state.monitor?;
+		let capture_rect = self
+			.state
+			.frozen_capture_rect
+			.unwrap_or_else(|| RectPoints::new(0, 0, monitor.width, monitor.height));
+		let capture_px = monitor.local_rect_to_pixels(capture_rect);
+		let (x, y) =
+			self.state.frozen_view.pixel_at(monitor.local_u32(global)?, monitor.scale_factor());
+
+		Some((
+			(i64::from(x) - i64::from(capture_px.x), i64::from(y) - i64::from(capture_px.y)),
+			(capture_px.width, capture_px.height),
+		))
+	}
+}
@@ -0,0 +1,38 @@
+use crate::overlay::{OverlayControl, OverlayMode, OverlaySession};
+use crate::state::ReferenceSample;
+
+impl OverlaySession {
+	/// Locks the color readout and loupe at the cursor as a reference, or releases it. The live
+	/// readout keeps following the cursor, and the HUD shows both colors.
+	pub(super) fn toggle_reference_sample(&mut self) -> OverlayControl {
+		if self.state.reference_sample.take().is_some() {
+			self.refresh_loupe_after_reference();
+		} else if let (Some(point), Some(rgb)) = (self.state.cursor, self.state.rgb) {
+			self.state.reference_sample = Some(ReferenceSample { point, rgb });
+		} else {
+			return OverlayControl::Continue;
+		}
+
+		tracing::debug!(
+			op = "overlay.reference_sample",
+			reference = ?self.state.reference_sample,
+			"Reference sample changed."
+		);
+
+		self.request_redraw_all();
+
+		OverlayControl::Continue
+	}
+
+	/// Points the loupe held by the released reference back at the cursor.
+	fn refresh_loupe_after_reference(&mut self) {
+		match self.state.mode {
+			OverlayMode::Live => self.request_loupe_sample_for_patch_change(),
+			OverlayMode::Frozen => {
+				if let (Some(monitor), Some(cursor)) = (self.state.monitor, self.state.cursor) {
+					self.update_cursor_state(monitor, cursor);
+				}
+			},
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use crate::overlay::{MonitorRect, OverlayControl, OverlayExit, OverlayMode, OverlaySession};
+use crate::state::RectPoints;
+
+impl OverlaySession {
+	/// Ends the session and hands the frozen selection to the caller to watch for changes.
+	pub(super) fn watch_frozen_region(&mut self) -> OverlayControl {
+		let Some((monitor, rect_px)) = self.frozen_selection_px() else {
+			return OverlayControl::Continue;
+		};
+
+		self.exit(OverlayExit::WatchRegion { monitor, rect_px })
+	}
+
+	/// Ends the session and hands the frozen selection to the caller to capture once in each
+	/// appearance.
+	pub(super) fn capture_frozen_region_in_both_themes(&mut self) -> OverlayControl {
+		let Some((monitor, rect_px)) = self.frozen_selection_px() else {
+			return OverlayControl::Continue;
+		};
+
+		self.exit(OverlayExit::ThemePair { monitor, rect_px })
+	}
+
+	/// The frozen selection in monitor-local pixels, for handing to a background sampler.
+	pub(super) fn frozen_selection_px(&self) -> Option<(MonitorRect, RectPoints)> {
+		if !matches!(self.state.mode, OverlayMode::Frozen) || self.scroll_capture.active {
+			return None;
+		}
+
+		let (Some(monitor), Some(capture_rect)) =
+			(self.state.monitor, self.state.frozen_capture_rect)
+		else {
+			return None;
+		};
+		let rect_px = monitor.local_rect_to_pixels(capture_rect);
+
+		if rect_px.width == 0 || rect_px.height == 0 {
+			return None;
+		}
+
+		Some((monitor, rect_px))
+	}
+}
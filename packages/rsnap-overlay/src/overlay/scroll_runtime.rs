@@ -12,6 +12,7 @@ use crate::overlay::{MonitorRect, RectPoints};
 use crate::overlay::{
 	OverlayControl, OverlaySession, ScrollCaptureFrameSource, ScrollObserveOutcome, ScrollSession,
 };
+use crate::progress::OverlayProgress;
 #[cfg(target_os = "macos")]
 use crate::scroll_capture::ScrollDirection;
 #[cfg(not(target_os = "macos"))]
@@ -482,6 +483,10 @@ impl OverlaySession {
 					"Scroll sample committed stitched growth."
 				);
 
+				if let Some(session) = self.scroll_capture.session.as_ref() {
+					self.pending_progress.push(OverlayProgress::stitched(session.merged_frames()));
+				}
+
 				self.sync_scroll_preview_segments();
 				self.request_redraw_scroll_preview_window();
 			},
@@ -0,0 +1,90 @@
+use crate::overlay::{MonitorRect, MonitorRectPoints, OverlaySession};
+use crate::state::{RectPoints, WindowRect};
+
+impl OverlaySession {
+	/// Pulls the edges of a drag selection onto nearby window edges from the cached window list.
+	/// Holding Cmd (macOS) / Ctrl drags freely.
+	pub(super) fn snap_drag_rect(
+		&self,
+		monitor: MonitorRect,
+		drag_rect: MonitorRectPoints,
+	) -> MonitorRectPoints {
+		let threshold = self.config.selection_snap_threshold_px;
+
+		if threshold == 0 || self.is_snap_bypass_pressed() {
+			return drag_rect;
+		}
+
+		let Some(snapshot) = self.window_list_snapshot.as_ref() else {
+			return drag_rect;
+		};
+		let rect = snap_rect_to_windows(drag_rect.rect, monitor, &snapshot.windows, threshold);
+
+		MonitorRectPoints { rect, ..drag_rect }
+	}
+
+	fn is_snap_bypass_pressed(&self) -> bool {
+		#[cfg(target_os = "macos")]
+		{
+			self.keyboard_modifiers.super_key()
+		}
+		#[cfg(not(target_os = "macos"))]
+		{
+			self.keyboard_modifiers.control_key()
+		}
+	}
+}
+
+/// Moves each edge of `rect` (monitor-local points) to the closest window edge within
+/// `threshold` points. Only window edges that run alongside the selection count, so a window far
+/// above the selection does not pull its sides.
+pub(super) fn snap_rect_to_windows(
+	rect: RectPoints,
+	monitor: MonitorRect,
+	windows: &[WindowRect],
+	threshold: u32,
+) -> RectPoints {
+	let threshold = i64::from(threshold);
+	let origin_x = i64::from(monitor.origin.x);
+	let origin_y = i64::from(monitor.origin.y);
+	let left = i64::from(rect.x);
+	let top = i64::from(rect.y);
+	let right = left + i64::from(rect.width);
+	let bottom = top + i64::from(rect.height);
+	let mut vertical = Vec::new();
+	let mut horizontal = Vec::new();
+
+	for window in windows.iter().filter(|window| window.width > 0 && window.height > 0) {
+		let window_left = window.x - origin_x;
+		let window_top = window.y - origin_y;
+		let window_right = window_left + window.width;
+		let window_bottom = window_top + window.height;
+
+		if window_top <= bottom + threshold && window_bottom >= top - threshold {
+			vertical.extend([window_left, window_right]);
+		}
+		if window_left <= right + threshold && window_right >= left - threshold {
+			horizontal.extend([window_top, window_bottom]);
+		}
+	}
+
+	let snap = |edge: i64, candidates: &[i64]| {
+		candidates
+			.iter()
+			.copied()
+			.filter(|candidate| (candidate - edge).abs() <= threshold)
+			.min_by_key(|candidate| (candidate - edge).abs())
+			.unwrap_or(edge)
+	};
+	let left = snap(left, &vertical).clamp(0, i64::from(monitor.width));
+	let right = snap(right, &vertical).clamp(0, i64::from(monitor.width));
+	let top = snap(top, &horizontal).clamp(0, i64::from(monitor.height));
+	let bottom = snap(bottom, &horizontal).clamp(0, i64::from(monitor.height));
+
+	// Two edges snapping onto the same line would collapse the selection; keep the drag instead.
+	if right <= left || bottom <= top {
+		return rect;
+	}
+
+	RectPoints::new(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32)
+}
@@ -3,13 +3,18 @@ use std::{
 	time::{Duration, Instant},
 };
 
+use image::RgbaImage;
+
 use crate::overlay::{
-	DeviceCursorPointSource, FrozenToolbarTool, GlobalPoint, LIVE_PRESENT_INTERVAL_MIN,
-	MonitorRect, PhysicalPosition, Pos2, REDRAW_SUBSTEP_CONTRIBUTION_FLOOR, RectPoints,
-	SLOW_OP_WARN_INTERVAL, ScrollDirection, ScrollSession, Vec2, WindowId,
+	DEFAULT_KEYPAD_QUICK_ACTIONS, DeviceCursorPointSource, FrozenToolbarTool, GlobalPoint,
+	KEYPAD_QUICK_ACTION_SLOTS, KeypadQuickAction, LIVE_PRESENT_INTERVAL_MIN, MonitorRect,
+	MonitorRectPoints, OverlayConfig, OverlayEventLoopPhase, OverlayExit, PhysicalPosition, Pos2,
+	REDRAW_SUBSTEP_CONTRIBUTION_FLOOR, RectPoints, SLOW_OP_WARN_INTERVAL, ScrollDirection,
+	ScrollSession, Vec2, WindowId,
 };
 #[cfg(target_os = "macos")]
 use crate::overlay::{ExternalScrollInputDrainReader, MacLiveFrameStream};
+use crate::stamp::StampKind;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(super) struct WindowFreezeCaptureTarget {
@@ -18,6 +23,72 @@ pub(super) struct WindowFreezeCaptureTarget {
 	pub(super) rect: RectPoints,
 }
 
+/// A finished live selection held back until the capture delay runs out.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct CaptureCountdown {
+	pub(super) fires_at: Instant,
+	pub(super) monitor: MonitorRect,
+	pub(super) rect: Option<RectPoints>,
+	pub(super) window_target: Option<WindowFreezeCaptureTarget>,
+	pub(super) cursor: Option<GlobalPoint>,
+}
+
+/// A finished export held open while the captured region flashes, then reported as `exit`.
+#[derive(Debug)]
+pub(super) struct CaptureFlash {
+	pub(super) started_at: Instant,
+	pub(super) region: MonitorRectPoints,
+	pub(super) exit: OverlayExit,
+}
+
+/// Bookkeeping for live cursor samples requested from the capture worker.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct LiveSampleRequests {
+	pub(super) request_id: u64,
+	pub(super) latest_request_id: Option<u64>,
+	pub(super) applied_request_id: Option<u64>,
+	pub(super) latest_requested_at: Option<Instant>,
+	pub(super) last_idle_request_at: Option<Instant>,
+	pub(super) last_cursor: Option<GlobalPoint>,
+	pub(super) stall_started_at: Option<Instant>,
+	pub(super) last_stall_log_at: Option<Instant>,
+}
+
+/// Ids of the newest worker request per frozen-image analysis. A result whose id is older is
+/// dropped.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct FrozenRequestIds {
+	pub(super) histogram: u64,
+	pub(super) redaction_scan: u64,
+	pub(super) code_scan: u64,
+	pub(super) export_preview: u64,
+}
+
+/// What the overlay event loop last did, reported when it stalls.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct EventLoopProgress {
+	pub(super) phase: OverlayEventLoopPhase,
+	pub(super) progress_seq: u64,
+	pub(super) last_progress_at: Instant,
+	pub(super) last_progress_window_id: Option<WindowId>,
+	pub(super) last_progress_monitor_id: Option<u32>,
+	pub(super) last_progress_detail: Option<&'static str>,
+	pub(super) last_stall_warn_at: Option<Instant>,
+}
+impl EventLoopProgress {
+	pub(super) const fn new(now: Instant) -> Self {
+		Self {
+			phase: OverlayEventLoopPhase::Idle,
+			progress_seq: 0,
+			last_progress_at: now,
+			last_progress_window_id: None,
+			last_progress_monitor_id: None,
+			last_progress_detail: None,
+			last_stall_warn_at: None,
+		}
+	}
+}
+
 #[derive(Default)]
 pub(super) struct SlowOperationLogger {
 	last_warn_at: HashMap<&'static str, Instant>,
@@ -121,13 +192,26 @@ pub(super) struct HudDrawConfig {
 	pub(super) hud_glass_active: bool,
 }
 
+/// Downscaled render of the pending export, shown at the start of the frozen toolbar.
+#[derive(Debug)]
+pub(super) struct ExportPreview {
+	/// Worker request that produced the image; renderers re-upload their texture when it changes.
+	pub(super) request_id: u64,
+	pub(super) image: RgbaImage,
+}
+
 #[derive(Debug)]
 pub(super) struct FrozenToolbarState {
 	pub(super) visible: bool,
 	pub(super) dragging: bool,
 	pub(super) selected_tool: FrozenToolbarTool,
+	/// The stamp the Stamp tool places; clicking the selected Stamp button picks the next one.
+	pub(super) stamp_kind: StampKind,
 	pub(super) scroll_capture_active: bool,
 	pub(super) scroll_capture_available: bool,
+	pub(super) backdrop_active: bool,
+	pub(super) export_preview_enabled: bool,
+	pub(super) export_preview: Option<ExportPreview>,
 	pub(super) pending_action: Option<FrozenToolbarTool>,
 	pub(super) needs_redraw: bool,
 	pub(super) pill_height_points: Option<f32>,
@@ -136,6 +220,17 @@ pub(super) struct FrozenToolbarState {
 	pub(super) layout_stable_frames: u8,
 	pub(super) drag_offset: Vec2,
 	pub(super) drag_anchor: Option<Pos2>,
+	pub(super) keypad_quick_actions: [KeypadQuickAction; KEYPAD_QUICK_ACTION_SLOTS],
+}
+impl FrozenToolbarState {
+	pub(super) fn with_config(config: &OverlayConfig) -> Self {
+		Self {
+			keypad_quick_actions: config.keypad_quick_actions,
+			backdrop_active: config.export_backdrop.enabled_by_default,
+			export_preview_enabled: config.export_preview,
+			..Self::default()
+		}
+	}
 }
 impl Default for FrozenToolbarState {
 	fn default() -> Self {
@@ -143,8 +238,12 @@ impl Default for FrozenToolbarState {
 			visible: true,
 			dragging: false,
 			selected_tool: FrozenToolbarTool::Pointer,
+			stamp_kind: StampKind::default(),
 			scroll_capture_active: false,
 			scroll_capture_available: false,
+			backdrop_active: false,
+			export_preview_enabled: false,
+			export_preview: None,
 			pending_action: None,
 			needs_redraw: false,
 			pill_height_points: None,
@@ -153,6 +252,7 @@ impl Default for FrozenToolbarState {
 			layout_stable_frames: 0,
 			drag_offset: Vec2::ZERO,
 			drag_anchor: None,
+			keypad_quick_actions: DEFAULT_KEYPAD_QUICK_ACTIONS,
 		}
 	}
 }
@@ -234,6 +334,32 @@ pub(super) struct FrozenToolbarPointerState {
 	pub(super) left_button_went_up: bool,
 }
 
+/// Pointer input received by the pinned live HUD window, consumed once per HUD redraw.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct HudPointerState {
+	pub(super) cursor_local: Option<Pos2>,
+	pub(super) left_button_down: bool,
+	pub(super) left_button_went_down: bool,
+	pub(super) left_button_went_up: bool,
+}
+impl HudPointerState {
+	/// Returns the pointer snapshot for this frame and clears the one-shot button edges.
+	pub(super) fn take_frame(&mut self) -> Option<FrozenToolbarPointerState> {
+		let cursor_local = self.cursor_local?;
+		let state = FrozenToolbarPointerState {
+			cursor_local,
+			left_button_down: self.left_button_down,
+			left_button_went_down: self.left_button_went_down,
+			left_button_went_up: self.left_button_went_up,
+		};
+
+		self.left_button_went_down = false;
+		self.left_button_went_up = false;
+
+		Some(state)
+	}
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub(super) struct LiveSampleApplyResult {
 	pub(super) overlay_changed: bool,
@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::overlay::OverlaySession;
+
+/// Directory watched for `*.wgsl` edits in debug builds.
+const SHADER_DIR_ENV: &str = "RSNAP_SHADER_DIR";
+const SHADER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MIPGEN_WGSL: &str = include_str!("../mipgen.wgsl");
+const HUD_BLUR_WGSL: &str = include_str!("../hud_blur.wgsl");
+const FROZEN_DIM_WGSL: &str = include_str!("../frozen_dim.wgsl");
+const COLOR_VISION_WGSL: &str = include_str!("../color_vision.wgsl");
+const SHADER_FILE_NAMES: [&str; 4] =
+	["mipgen.wgsl", "hud_blur.wgsl", "frozen_dim.wgsl", "color_vision.wgsl"];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// WGSL sources for the overlay pipelines. `generation` changes whenever any source does.
+pub(super) struct ShaderSources {
+	pub(super) mipgen: Cow<'static, str>,
+	pub(super) hud_blur: Cow<'static, str>,
+	pub(super) frozen_dim: Cow<'static, str>,
+	pub(super) color_vision: Cow<'static, str>,
+	pub(super) generation: u64,
+}
+impl ShaderSources {
+	/// Reads each shader from `dir`, keeping the built-in source for files that are missing or
+	/// unreadable.
+	pub(super) fn from_dir(dir: &Path, generation: u64) -> Self {
+		let read = |name: &str, embedded: &'static str| match fs::read_to_string(dir.join(name)) {
+			Ok(source) => Cow::Owned(source),
+			Err(err) => {
+				tracing::debug!(file = name, error = %err, "Using the built-in shader source.");
+
+				Cow::Borrowed(embedded)
+			},
+		};
+
+		Self {
+			mipgen: read(SHADER_FILE_NAMES[0], MIPGEN_WGSL),
+			hud_blur: read(SHADER_FILE_NAMES[1], HUD_BLUR_WGSL),
+			frozen_dim: read(SHADER_FILE_NAMES[2], FROZEN_DIM_WGSL),
+			color_vision: read(SHADER_FILE_NAMES[3], COLOR_VISION_WGSL),
+			generation,
+		}
+	}
+}
+impl Default for ShaderSources {
+	fn default() -> Self {
+		Self {
+			mipgen: Cow::Borrowed(MIPGEN_WGSL),
+			hud_blur: Cow::Borrowed(HUD_BLUR_WGSL),
+			frozen_dim: Cow::Borrowed(FROZEN_DIM_WGSL),
+			color_vision: Cow::Borrowed(COLOR_VISION_WGSL),
+			generation: 0,
+		}
+	}
+}
+
+/// Polls the shader directory named by `RSNAP_SHADER_DIR` and reloads the sources when a file's
+/// modification time changes. Only debug builds honor the variable.
+pub(super) struct ShaderHotReload {
+	dir: PathBuf,
+	modified: [Option<SystemTime>; 4],
+	last_poll_at: Instant,
+	sources: ShaderSources,
+}
+impl ShaderHotReload {
+	pub(super) fn from_env() -> Option<Self> {
+		if !cfg!(debug_assertions) {
+			return None;
+		}
+
+		let dir = PathBuf::from(env::var_os(SHADER_DIR_ENV)?);
+
+		tracing::info!(
+			op = "overlay.shader_hot_reload",
+			dir = %dir.display(),
+			"Watching overlay shaders for changes."
+		);
+
+		Some(Self {
+			modified: Self::modified_times(&dir),
+			sources: ShaderSources::from_dir(&dir, 1),
+			last_poll_at: Instant::now(),
+			dir,
+		})
+	}
+
+	pub(super) fn sources(&self) -> &ShaderSources {
+		&self.sources
+	}
+
+	/// Returns `true` when the sources were reloaded since the previous poll.
+	pub(super) fn poll(&mut self, now: Instant) -> bool {
+		if now.duration_since(self.last_poll_at) < SHADER_POLL_INTERVAL {
+			return false;
+		}
+
+		self.last_poll_at = now;
+
+		let modified = Self::modified_times(&self.dir);
+
+		if modified == self.modified {
+			return false;
+		}
+
+		self.modified = modified;
+		self.sources = ShaderSources::from_dir(&self.dir, self.sources.generation + 1);
+
+		tracing::info!(
+			op = "overlay.shader_hot_reload",
+			generation = self.sources.generation,
+			"Overlay shader sources changed."
+		);
+
+		true
+	}
+
+	fn modified_times(dir: &Path) -> [Option<SystemTime>; 4] {
+		SHADER_FILE_NAMES
+			.map(|name| fs::metadata(dir.join(name)).and_then(|meta| meta.modified()).ok())
+	}
+}
+
+impl OverlaySession {
+	/// Rebuilds every window's pipelines after a shader edit. Renderers created later pick up the
+	/// current sources on their next tick.
+	pub(super) fn maybe_tick_shader_hot_reload(&mut self) {
+		let Some(reload) = self.shader_hot_reload.as_mut() else {
+			return;
+		};
+
+		reload.poll(Instant::now());
+
+		let Some(gpu) = self.gpu.as_ref() else {
+			return;
+		};
+		let sources = reload.sources();
+		let mut reloaded = false;
+		let renderers = self
+			.windows
+			.values_mut()
+			.map(|window| &mut window.renderer)
+			.chain(self.hud_window.as_mut().map(|window| &mut window.renderer))
+			.chain(self.loupe_window.as_mut().map(|window| &mut window.renderer))
+			.chain(self.toolbar_window.as_mut().map(|window| &mut window.renderer));
+
+		for renderer in renderers {
+			reloaded |= renderer.sync_shaders(gpu, sources);
+		}
+
+		if reloaded {
+			self.request_redraw_all();
+		}
+	}
+}
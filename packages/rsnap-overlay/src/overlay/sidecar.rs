@@ -0,0 +1,211 @@
+use std::{fs, path::Path};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::Serialize;
+
+use crate::annotation::AnnotationLayer;
+use crate::state::{MonitorRect, RectPoints};
+
+const GEOMETRY_SIDECAR_VERSION: u32 = 1;
+const ANNOTATION_SIDECAR_VERSION: u32 = 1;
+
+/// Machine-readable layout of a saved export, written next to the PNG as `<name>.json`.
+#[derive(Debug, Serialize)]
+pub(super) struct ExportGeometry {
+	version: u32,
+	image: ImageSize,
+	monitor_id: Option<u32>,
+	/// Physical pixels per point on the captured monitor.
+	scale_factor: Option<f32>,
+	regions: Vec<ExportRegion>,
+}
+
+#[derive(Debug, Serialize)]
+struct ImageSize {
+	width: u32,
+	height: u32,
+}
+
+/// One rectangle in the exported image, with its on-screen position when it has one.
+#[derive(Debug, Serialize)]
+struct ExportRegion {
+	kind: &'static str,
+	/// Pixels, relative to the exported image's top-left corner.
+	image_rect: GeometryRect,
+	/// Global points, as used by window-system input APIs.
+	screen_rect_points: Option<GeometryRect>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeometryRect {
+	x: i64,
+	y: i64,
+	width: u32,
+	height: u32,
+}
+
+/// The frozen-toolbar annotations of a saved export, written next to the PNG as
+/// `<name>.annotations.json` so a later tool can re-edit them over the unannotated capture.
+#[derive(Debug, Serialize)]
+pub(super) struct ExportAnnotations {
+	version: u32,
+	image: ImageSize,
+	capture: AnnotationCanvas,
+	/// Drawn in this order, below the stamps, which are below the callouts.
+	highlights: Vec<HighlightRecord>,
+	stamps: Vec<StampRecord>,
+	callouts: Vec<CalloutRecord>,
+}
+
+/// The pixel space annotation coordinates are in: the capture before any backdrop or export
+/// scaling, `width`×`height`, which lands on `image_rect` in the exported image.
+#[derive(Debug, Serialize)]
+struct AnnotationCanvas {
+	width: u32,
+	height: u32,
+	image_rect: GeometryRect,
+}
+
+/// A multiply-blended marker stroke through `points`.
+#[derive(Debug, Serialize)]
+struct HighlightRecord {
+	points: Vec<[i64; 2]>,
+	width_px: u32,
+	/// `#RRGGBB`.
+	color: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StampRecord {
+	kind: &'static str,
+	center: [u32; 2],
+	/// Side of the square the glyph is fitted into.
+	size_px: u32,
+}
+
+/// A speech bubble with its body's top-left corner at `origin` and its tail tip at `tail`.
+#[derive(Debug, Serialize)]
+struct CalloutRecord {
+	origin: [i64; 2],
+	tail: [i64; 2],
+	text: String,
+	text_size_px: u32,
+}
+
+/// Describes where the captured content sits in an export of `image_size`, inset by any
+/// backdrop `padding_px`. `capture_rect` is monitor-local and omitted for stitched scroll
+/// captures, which have no single on-screen rect.
+pub(super) fn export_geometry(
+	image_size: (u32, u32),
+	padding_px: u32,
+	monitor: Option<MonitorRect>,
+	capture_rect: Option<RectPoints>,
+) -> ExportGeometry {
+	let (width, height) = image_size;
+	let image_rect = inset_image_rect(image_size, padding_px);
+	let screen_rect_points = monitor.zip(capture_rect).map(|(monitor, rect)| GeometryRect {
+		x: i64::from(monitor.origin.x) + i64::from(rect.x),
+		y: i64::from(monitor.origin.y) + i64::from(rect.y),
+		width: rect.width,
+		height: rect.height,
+	});
+
+	ExportGeometry {
+		version: GEOMETRY_SIDECAR_VERSION,
+		image: ImageSize { width, height },
+		monitor_id: monitor.map(|monitor| monitor.id),
+		scale_factor: monitor.map(|monitor| monitor.scale_factor()),
+		regions: vec![ExportRegion { kind: "capture", image_rect, screen_rect_points }],
+	}
+}
+
+/// Describes `annotations`, drawn on a capture of `capture_size` pixels, for an export of
+/// `image_size` inset by any backdrop `padding_px`.
+pub(super) fn export_annotations(
+	image_size: (u32, u32),
+	padding_px: u32,
+	capture_size: (u32, u32),
+	annotations: &AnnotationLayer,
+) -> ExportAnnotations {
+	ExportAnnotations {
+		version: ANNOTATION_SIDECAR_VERSION,
+		image: ImageSize { width: image_size.0, height: image_size.1 },
+		capture: AnnotationCanvas {
+			width: capture_size.0,
+			height: capture_size.1,
+			image_rect: inset_image_rect(image_size, padding_px),
+		},
+		highlights: annotations
+			.highlights
+			.iter()
+			.map(|stroke| {
+				let [r, g, b] = stroke.rgb;
+
+				HighlightRecord {
+					points: stroke.points.iter().map(|&(x, y)| [x, y]).collect(),
+					width_px: stroke.width_px,
+					color: format!("#{r:02X}{g:02X}{b:02X}"),
+				}
+			})
+			.collect(),
+		stamps: annotations
+			.stamps
+			.iter()
+			.map(|stamp| StampRecord {
+				kind: stamp.kind.id(),
+				center: [stamp.center_px.0, stamp.center_px.1],
+				size_px: stamp.size_px,
+			})
+			.collect(),
+		callouts: annotations
+			.callouts
+			.iter()
+			.map(|callout| CalloutRecord {
+				origin: [callout.origin_px.0, callout.origin_px.1],
+				tail: [callout.tail_px.0, callout.tail_px.1],
+				text: callout.text.clone(),
+				text_size_px: callout.text_size_px,
+			})
+			.collect(),
+	}
+}
+
+/// Writes `geometry` next to the saved image at `image_path`.
+pub(super) fn write_geometry_sidecar(image_path: &Path, geometry: &ExportGeometry) -> Result<()> {
+	let sidecar = image_path.with_extension("json");
+	let mut json =
+		serde_json::to_string_pretty(geometry).wrap_err("Failed to serialize export geometry")?;
+
+	json.push('\n');
+
+	fs::write(&sidecar, json)
+		.wrap_err_with(|| format!("Failed to write geometry sidecar: {}", sidecar.display()))
+}
+
+/// Writes `annotations` next to the saved image at `image_path`.
+pub(super) fn write_annotation_sidecar(
+	image_path: &Path,
+	annotations: &ExportAnnotations,
+) -> Result<()> {
+	let sidecar = image_path.with_extension("annotations.json");
+	let mut json =
+		serde_json::to_string_pretty(annotations).wrap_err("Failed to serialize annotations")?;
+
+	json.push('\n');
+
+	fs::write(&sidecar, json)
+		.wrap_err_with(|| format!("Failed to write annotation sidecar: {}", sidecar.display()))
+}
+
+/// The rect the capture covers in an export of `image_size` inset by `padding_px`.
+fn inset_image_rect((width, height): (u32, u32), padding_px: u32) -> GeometryRect {
+	let inset_x = padding_px.min(width / 2);
+	let inset_y = padding_px.min(height / 2);
+
+	GeometryRect {
+		x: i64::from(inset_x),
+		y: i64::from(inset_y),
+		width: width - inset_x * 2,
+		height: height - inset_y * 2,
+	}
+}
@@ -0,0 +1,58 @@
+use winit::event::MouseScrollDelta;
+
+use crate::overlay::{FrozenToolbarTool, OverlayControl, OverlayMode, OverlaySession};
+use crate::state::GlobalPoint;
+
+/// Pixel-delta wheels (trackpads) report about this many pixels per notch.
+const STAMP_WHEEL_PIXELS_PER_NOTCH: f64 = 40.0;
+
+impl OverlaySession {
+	pub(super) fn stamp_tool_active(&self) -> bool {
+		matches!(self.state.mode, OverlayMode::Frozen)
+			&& !self.scroll_capture.active
+			&& self.toolbar_state.selected_tool == FrozenToolbarTool::Stamp
+	}
+
+	/// Places the picked stamp at `global`; `false` outside the frozen selection.
+	pub(super) fn place_stamp_at(&mut self, global: GlobalPoint) -> bool {
+		let Some(point) = self.selection_pixel_at(global) else {
+			return false;
+		};
+
+		self.state.annotations.place_stamp(self.toolbar_state.stamp_kind, point);
+
+		tracing::debug!(
+			op = "overlay.stamp_placed",
+			kind = self.toolbar_state.stamp_kind.id(),
+			point = ?point,
+		);
+
+		self.annotations_changed();
+
+		true
+	}
+
+	/// Scales the stamp under the pointer with the wheel while the Stamp tool is active.
+	pub(super) fn handle_stamp_wheel(&mut self, delta: &MouseScrollDelta) -> OverlayControl {
+		let notches = match delta {
+			MouseScrollDelta::LineDelta(_, y) => *y,
+			MouseScrollDelta::PixelDelta(position) => {
+				(position.y / STAMP_WHEEL_PIXELS_PER_NOTCH) as f32
+			},
+		};
+
+		if notches == 0.0 {
+			return OverlayControl::Continue;
+		}
+
+		let cursor = self.current_device_cursor();
+
+		if let Some(point) = self.selection_pixel_at(cursor)
+			&& self.state.annotations.scale_stamp_at(point, notches)
+		{
+			self.annotations_changed();
+		}
+
+		OverlayControl::Continue
+	}
+}
@@ -1,17 +1,28 @@
-use std::{sync::Arc, time::Instant};
+use std::{
+	sync::{Arc, mpsc::TryRecvError},
+	time::Instant,
+};
 
+use color_eyre::eyre;
+#[cfg(target_os = "linux")]
+use winit::platform::x11::{WindowAttributesExtX11, WindowType};
 use winit::window::Window;
 
 use crate::backend;
+use crate::capture_exclusion;
+use crate::latency::CaptureLatency;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use crate::overlay::GlobalPoint;
 #[cfg(target_os = "macos")]
 use crate::overlay::{self, MacLiveFrameStream, MainThreadMarker, NSScreen};
 use crate::overlay::{
-	ActiveEventLoop, FrozenCaptureSource, FrozenToolbarState, GlobalPoint, GpuContext,
-	HUD_PILL_CORNER_RADIUS_POINTS, HudOverlayWindow, LOUPE_TILE_CORNER_RADIUS_POINTS,
-	LiveSampleApplyResult, LogicalPosition, LogicalSize, MonitorRect, OverlayEventLoopPhase,
-	OverlayMode, OverlaySession, OverlayWindow, OverlayWorker, Result, ScrollCaptureState,
+	ActiveEventLoop, EntryAnimation, EventLoopProgress, FrozenCaptureSource, FrozenToolbarState,
+	GPU_INIT_TIMEOUT, HUD_ENTRY_FADE_DURATION, HUD_PILL_CORNER_RADIUS_POINTS, HudOverlayWindow,
+	LOUPE_TILE_CORNER_RADIUS_POINTS, LiveSampleApplyResult, LiveSampleRequests, LogicalPosition,
+	LogicalSize, MonitorRect, OverlayMode, OverlaySession, OverlayWindow, OverlayWorker,
+	PendingGpuContext, PerfHudCounters, PerfHudMetrics, Result, ScrollCaptureState,
 	ScrollPreviewWindow, SlowOperationLogger, TOOLBAR_EXPANDED_HEIGHT_PX,
-	TOOLBAR_EXPANDED_WIDTH_PX, WindowLevel, WindowRenderer, hud_helpers,
+	TOOLBAR_EXPANDED_WIDTH_PX, WindowLevel, WindowRenderer, hud_helpers, window_shape,
 };
 
 impl OverlaySession {
@@ -21,44 +32,157 @@ impl OverlaySession {
 			return Ok(());
 		}
 
+		#[cfg(target_os = "macos")]
+		let prewarmed_stream = self.live_sample_stream.take();
+
 		self.reset_for_start();
 
-		self.worker = Some(OverlayWorker::new(
-			backend::default_capture_backend(),
-			self.response_waker.clone(),
-		));
 		#[cfg(target_os = "macos")]
 		{
-			self.live_sample_stream = Some(MacLiveFrameStream::new());
+			self.live_sample_stream = prewarmed_stream;
 		}
 
-		let monitors = self.available_overlay_monitors()?;
+		self.ensure_capture_pipeline();
+
+		let monitors = match self.prewarmed_monitors.take() {
+			Some(monitors) => monitors,
+			None => Self::available_overlay_monitors()?,
+		};
 
 		if monitors.is_empty() {
 			return Err(String::from("No monitors detected"));
 		}
 
-		self.gpu = Some(GpuContext::new().map_err(|err| format!("{err:#}"))?);
+		self.ensure_gpu()?;
 
 		self.create_overlay_windows(event_loop, &monitors)?;
+		self.request_monitor_capture_probes(&monitors);
+		self.load_display_transfers(&monitors);
 		self.create_hud_window(event_loop)?;
 		self.create_loupe_window(event_loop)?;
 		self.create_toolbar_window(event_loop)?;
 		self.create_scroll_preview_window(event_loop)?;
 		self.initialize_cursor_state();
-		#[cfg(target_os = "macos")]
-		self.focus_live_capture_window();
+
+		if self.color_picker_active {
+			self.float_color_picker_windows();
+		} else {
+			#[cfg(target_os = "macos")]
+			self.focus_live_capture_window();
+		}
+
 		self.request_redraw_all();
 
 		Ok(())
 	}
 
+	/// Does the slow, window-independent part of [`Self::start`] ahead of time: the capture
+	/// worker, the macOS live stream, the monitor list and the GPU device. The next `start` on this
+	/// session reuses them; dropping the session instead cancels the prewarm.
+	pub fn prewarm(&mut self) -> Result<(), String> {
+		if self.is_active() {
+			return Ok(());
+		}
+
+		let started_at = Instant::now();
+
+		self.ensure_capture_pipeline();
+
+		let monitors = Self::available_overlay_monitors()?;
+
+		#[cfg(target_os = "macos")]
+		if let Some(stream) = self.live_sample_stream.as_ref() {
+			for monitor in &monitors {
+				stream.ensure_monitor_nonblocking(*monitor);
+			}
+		}
+
+		self.prewarmed_monitors = Some(monitors);
+
+		if self.gpu.is_none() && self.pending_gpu.is_none() {
+			self.pending_gpu = Some(PendingGpuContext::spawn(self.config.gpu_power_preference));
+		}
+
+		tracing::debug!(
+			op = "overlay.prewarm",
+			elapsed_ms = started_at.elapsed().as_millis(),
+			"Overlay prewarmed."
+		);
+
+		Ok(())
+	}
+
+	pub(super) fn ensure_capture_pipeline(&mut self) {
+		if self.worker.is_none() {
+			self.worker = Some(OverlayWorker::new(
+				backend::capture_backend(self.config.capture_backend),
+				self.response_waker.clone(),
+			));
+		}
+
+		#[cfg(target_os = "macos")]
+		if self.live_sample_stream.is_none() {
+			self.live_sample_stream = (self.config.capture_backend.resolve()
+				== backend::CaptureBackendKind::Sck)
+				.then(MacLiveFrameStream::new);
+		}
+	}
+
+	/// Checks on the GPU context without waiting, starting its initialization when none runs.
+	///
+	/// `Ok(false)` means initialization is still running; callers poll again later, e.g. from the
+	/// event loop's `about_to_wait`, and start the overlay once this returns `Ok(true)`. It fails
+	/// [`GPU_INIT_TIMEOUT`] after initialization started, but a timed-out initialization keeps
+	/// running, so a later start can still pick it up.
+	pub fn poll_gpu(&mut self) -> Result<bool, String> {
+		if self.gpu.is_some() {
+			return Ok(true);
+		}
+
+		let power_preference = self.config.gpu_power_preference;
+		let pending =
+			self.pending_gpu.get_or_insert_with(|| PendingGpuContext::spawn(power_preference));
+		let gpu = match pending.receiver.try_recv() {
+			Ok(gpu) => gpu,
+			Err(TryRecvError::Empty) if pending.started_at.elapsed() < GPU_INIT_TIMEOUT => {
+				return Ok(false);
+			},
+			Err(TryRecvError::Empty) => {
+				return Err(format!(
+					"GPU initialization did not finish within {} s",
+					GPU_INIT_TIMEOUT.as_secs()
+				));
+			},
+			Err(TryRecvError::Disconnected) => {
+				Err(eyre::eyre!("GPU initialization stopped without a result"))
+			},
+		};
+
+		self.pending_gpu = None;
+		self.gpu = Some(gpu.map_err(|err| format!("{err:#}"))?);
+
+		Ok(true)
+	}
+
+	/// Requires a ready GPU context; a start made before [`Self::poll_gpu`] reported one fails
+	/// instead of blocking the event loop.
+	pub(super) fn ensure_gpu(&mut self) -> Result<(), String> {
+		if self.poll_gpu()? {
+			Ok(())
+		} else {
+			Err(String::from("GPU initialization is still running"))
+		}
+	}
+
 	pub(super) fn reset_for_start(&mut self) {
 		let now = Instant::now();
 
 		#[cfg(target_os = "macos")]
 		self.set_scroll_overlay_mouse_passthrough(false);
 
+		self.capture_latency = CaptureLatency::new(now);
+		self.last_export_region = None;
+		self.edge_dead_zone_passthrough = false;
 		self.hud_inner_size_points = None;
 		self.hud_outer_pos = None;
 		self.pending_hud_outer_pos = None;
@@ -67,6 +191,14 @@ impl OverlaySession {
 		self.pending_loupe_outer_pos = None;
 		self.toolbar_inner_size_points = None;
 		self.toolbar_outer_pos = None;
+		self.toolbar_entry = EntryAnimation::default();
+		self.toolbar_drag_spring = None;
+		self.hud_entry = EntryAnimation::default();
+
+		if !self.config.reduce_motion {
+			self.hud_entry.start(now, HUD_ENTRY_FADE_DURATION);
+		}
+
 		self.scroll_preview_window = None;
 		self.cursor_monitor = None;
 		#[cfg(target_os = "macos")]
@@ -75,7 +207,17 @@ impl OverlaySession {
 			self.live_sample_stream = None;
 		}
 
-		self.state.reset_for_start(self.loupe_patch_width_px);
+		self.reset_loupe_zoom();
+		self.state.reset_for_start(self.loupe_patch_width_px, self.config.hud_unit);
+
+		self.state.composition_guides = self.config.composition_guides;
+		self.state.histogram_panel = self.config.histogram_panel;
+		self.state.frozen_dim_opacity = self.config.frozen_dim_opacity;
+		self.state.frozen_dim_vignette = self.config.frozen_dim_vignette;
+		self.state.frozen_dim_noise = self.config.frozen_dim_noise;
+		self.state.hud_blur_dither = self.config.hud_blur_dither;
+		self.perf_hud = self.perf_hud.take().map(|_| PerfHudCounters::default());
+		self.state.perf_hud = self.perf_hud.as_ref().map(|_| PerfHudMetrics::default());
 
 		self.pending_freeze_capture = None;
 		self.pending_freeze_capture_armed = false;
@@ -85,34 +227,26 @@ impl OverlaySession {
 		self.frozen_capture_source = FrozenCaptureSource::None;
 		self.hit_test_send_full_count = 0;
 		self.hit_test_send_disconnected_count = 0;
-		self.live_cursor_sample_request_id = 0;
-		self.latest_live_cursor_sample_request_id = None;
-		self.applied_live_cursor_sample_request_id = None;
-		self.latest_live_cursor_sample_requested_at = None;
-		self.last_idle_live_sample_request_at = None;
+		self.live_sample = LiveSampleRequests::default();
 		self.pending_click_hit_test_request_id = None;
 		self.last_event_cursor = None;
 		self.last_event_cursor_at = None;
-		self.last_live_sample_cursor = None;
-		self.live_sample_stall_started_at = None;
-		self.last_live_sample_stall_log_at = None;
 		self.slow_op_logger = SlowOperationLogger::default();
 		self.last_hud_window_move_at = now;
 		self.last_loupe_window_move_at = now;
-		self.event_loop_phase = OverlayEventLoopPhase::Idle;
-		self.event_loop_progress_seq = 0;
-		self.event_loop_last_progress_at = now;
-		self.event_loop_last_progress_window_id = None;
-		self.event_loop_last_progress_monitor_id = None;
-		self.event_loop_last_progress_detail = None;
-		self.event_loop_last_stall_warn_at = None;
+		self.event_loop = EventLoopProgress::new(now);
 
 		#[cfg(target_os = "macos")]
 		self.clear_macos_hud_window_config_cache();
 
 		self.window_list_snapshot = None;
 		self.last_window_list_refresh_request_at = now - self.window_list_refresh_interval;
-		self.toolbar_state = FrozenToolbarState::default();
+		self.toolbar_state = FrozenToolbarState {
+			keypad_quick_actions: self.config.keypad_quick_actions,
+			backdrop_active: self.config.export_backdrop.enabled_by_default,
+			export_preview_enabled: self.config.export_preview,
+			..FrozenToolbarState::default()
+		};
 		self.toolbar_left_button_down = false;
 		self.toolbar_left_button_went_down = false;
 		self.toolbar_left_button_went_up = false;
@@ -132,18 +266,88 @@ impl OverlaySession {
 		}
 	}
 
+	/// Records displays whose transfer table is tinted so the HUD can show the on-screen color.
+	fn load_display_transfers(&mut self, monitors: &[MonitorRect]) {
+		if !self.config.show_display_adjusted_color {
+			return;
+		}
+
+		for monitor in monitors {
+			let Some(transfer) = backend::display_transfer(monitor.id) else {
+				continue;
+			};
+
+			if transfer.is_identity() {
+				continue;
+			}
+
+			tracing::info!(
+				op = "overlay.display_transfer",
+				monitor_id = monitor.id,
+				samples = transfer.red.len(),
+				"Display color adjustment detected."
+			);
+
+			let _ = self.state.display_transfer.insert(monitor.id, transfer);
+		}
+	}
+
+	fn request_monitor_capture_probes(&self, monitors: &[MonitorRect]) {
+		let Some(worker) = self.worker.as_ref() else {
+			return;
+		};
+
+		if !worker.request_probe_monitors(monitors.to_vec()) {
+			tracing::debug!(
+				op = "overlay.monitor_probe",
+				"Worker queue full; skipping monitor capture probes."
+			);
+		}
+	}
+
+	pub(super) fn handle_probed_monitor_response(
+		&mut self,
+		monitor: MonitorRect,
+		error: Option<String>,
+	) {
+		let Some(reason) = error else {
+			if self.state.capture_unavailable.remove(&monitor.id).is_some() {
+				self.request_redraw_for_monitor(monitor);
+			}
+
+			return;
+		};
+
+		tracing::warn!(
+			op = "overlay.monitor_probe",
+			monitor_id = monitor.id,
+			reason = %reason,
+			"Capture is unavailable on this monitor."
+		);
+
+		self.state.capture_unavailable.insert(monitor.id, reason);
+		self.request_redraw_for_monitor(monitor);
+	}
+
 	#[cfg(target_os = "macos")]
 	fn clear_macos_hud_window_config_cache(&mut self) {
 		self.macos_hud_window_config_cache.clear();
 	}
 
-	fn available_overlay_monitors(&self) -> Result<Vec<MonitorRect>, String> {
+	/// Lists the monitors overlays cover, in window-system order (the main display first).
+	pub(crate) fn available_overlay_monitors() -> Result<Vec<MonitorRect>, String> {
 		#[cfg(target_os = "macos")]
 		{
 			Self::macos_monitor_rects()
 		}
 
-		#[cfg(not(target_os = "macos"))]
+		#[cfg(target_os = "linux")]
+		{
+			crate::x11_capture_linux::monitor_rects()
+				.map_err(|err| format!("X11 monitor enumeration failed: {err:#}"))
+		}
+
+		#[cfg(target_os = "windows")]
 		{
 			let monitors =
 				xcap::Monitor::all().map_err(|err| format!("xcap Monitor::all failed: {err:?}"))?;
@@ -196,7 +400,7 @@ impl OverlaySession {
 		Ok(monitor_rects)
 	}
 
-	#[cfg(not(target_os = "macos"))]
+	#[cfg(target_os = "windows")]
 	fn monitor_rect_from_xcap_monitor(monitor: &xcap::Monitor) -> Result<MonitorRect, String> {
 		Ok(MonitorRect {
 			id: monitor.id().map_err(|err| {
@@ -250,6 +454,7 @@ impl OverlaySession {
 				.with_decorations(false)
 				.with_resizable(false)
 				.with_transparent(true)
+				.with_active(!self.color_picker_active)
 				.with_window_level(WindowLevel::AlwaysOnTop)
 				.with_inner_size(LogicalSize::new(
 					monitor_rect.width as f64,
@@ -263,6 +468,9 @@ impl OverlaySession {
 				.create_window(attrs)
 				.map_err(|err| format!("Unable to create overlay window: {err}"))?;
 			let window = Arc::new(window);
+
+			capture_exclusion::exclude_window_from_capture(window.as_ref());
+
 			let scale_factor = monitor_rect.scale_factor();
 			let inner_size = window.inner_size();
 
@@ -286,7 +494,10 @@ impl OverlaySession {
 				window.current_monitor().and_then(|monitor| monitor.refresh_rate_millihertz());
 
 			window.request_redraw();
-			window.focus_window();
+
+			if !self.color_picker_active {
+				window.focus_window();
+			}
 
 			let gpu = self.gpu.as_ref().ok_or_else(|| String::from("Missing GPU context"))?;
 			let renderer = WindowRenderer::new(
@@ -311,12 +522,16 @@ impl OverlaySession {
 			.with_decorations(false)
 			.with_resizable(false)
 			.with_transparent(true)
+			.with_active(!self.color_picker_active)
 			.with_window_level(WindowLevel::AlwaysOnTop)
 			.with_inner_size(LogicalSize::new(460.0, 52.0));
 		let window = event_loop
 			.create_window(attrs)
 			.map_err(|err| format!("Unable to create HUD window: {err}"))?;
 		let window = Arc::new(window);
+
+		capture_exclusion::exclude_window_from_capture(window.as_ref());
+
 		#[cfg(target_os = "macos")]
 		let _ = window.set_cursor_hittest(false);
 		#[cfg(not(target_os = "macos"))]
@@ -343,6 +558,7 @@ impl OverlaySession {
 			.with_decorations(false)
 			.with_resizable(false)
 			.with_transparent(true)
+			.with_active(!self.color_picker_active)
 			.with_visible(false)
 			.with_window_level(WindowLevel::AlwaysOnTop)
 			.with_inner_size(LogicalSize::new(
@@ -353,6 +569,9 @@ impl OverlaySession {
 			.create_window(attrs)
 			.map_err(|err| format!("Unable to create loupe window: {err}"))?;
 		let window = Arc::new(window);
+
+		capture_exclusion::exclude_window_from_capture(window.as_ref());
+
 		#[cfg(target_os = "macos")]
 		let _ = window.set_cursor_hittest(false);
 		#[cfg(not(target_os = "macos"))]
@@ -384,20 +603,27 @@ impl OverlaySession {
 			.with_transparent(true)
 			.with_visible(false)
 			.with_window_level(WindowLevel::AlwaysOnTop);
+		// X11 compositors key their shadow and corner rules off the window type.
+		#[cfg(target_os = "linux")]
+		let attrs = attrs.with_x11_window_type(vec![WindowType::PopupMenu]);
 		let window = event_loop
 			.create_window(attrs)
 			.map_err(|err| format!("Unable to create toolbar window: {err}"))?;
 		let window = Arc::new(window);
-		#[cfg(target_os = "macos")]
+
+		capture_exclusion::exclude_window_from_capture(window.as_ref());
+
 		let _ = window.set_cursor_hittest(true);
-		#[cfg(not(target_os = "macos"))]
-		let _ = window.set_cursor_hittest(false);
 
 		window.set_transparent(true);
 		self.configure_hud_window_common(
 			window.as_ref(),
 			Some(f64::from(HUD_PILL_CORNER_RADIUS_POINTS)),
 		);
+		window_shape::apply_rounded_window_region(
+			window.as_ref(),
+			f64::from(HUD_PILL_CORNER_RADIUS_POINTS),
+		);
 		window.request_redraw();
 
 		let gpu = self.gpu.as_ref().ok_or_else(|| String::from("Missing GPU context"))?;
@@ -452,12 +678,11 @@ impl OverlaySession {
 			loupe.window.request_redraw();
 		}
 
-		// macOS uses a native toolbar popup window with compositor blur; keep shader-viewport
-		// toolbar redraw on the fullscreen overlay path disabled for this platform.
-		// Future direction: if toolbar styling moves off native blur, add a dedicated capture
+		// The toolbar lives in its own popup window on every platform, so the fullscreen overlay
+		// never redraws toolbar UI.
+		// Future direction: if toolbar styling moves off compositor blur, add a dedicated capture
 		// pass feeding a toolbar-local shader-blur texture.
-		if cfg!(target_os = "macos")
-			&& matches!(self.state.mode, OverlayMode::Frozen)
+		if matches!(self.state.mode, OverlayMode::Frozen)
 			&& self.toolbar_state.visible
 			&& self.state.monitor == Some(monitor)
 			&& self.state.frozen_image.is_some()
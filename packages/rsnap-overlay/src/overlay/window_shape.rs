@@ -0,0 +1,51 @@
+//! Native shaping for floating HUD windows on platforms without macOS panel styling.
+
+use winit::window::Window;
+
+/// Clips a window to a rounded rectangle so its transparent corners neither show nor take
+/// clicks. Call it again whenever the window is resized.
+#[cfg(target_os = "windows")]
+pub(super) fn apply_rounded_window_region(window: &Window, corner_radius_points: f64) {
+	use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+	use windows_sys::Win32::Foundation::HWND;
+	use windows_sys::Win32::Graphics::Gdi::{CreateRoundRectRgn, DeleteObject, SetWindowRgn};
+
+	let Ok(handle) = window.window_handle() else {
+		return;
+	};
+	let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+		return;
+	};
+	let size = window.inner_size();
+	let diameter_px = (corner_radius_points * 2.0 * window.scale_factor()).round() as i32;
+	let hwnd = handle.hwnd.get() as HWND;
+
+	// SAFETY: `hwnd` comes from a live winit window. On success the system owns the region;
+	// otherwise it is still ours to free.
+	unsafe {
+		// Region coordinates are exclusive on the right/bottom edge.
+		let region = CreateRoundRectRgn(
+			0,
+			0,
+			size.width as i32 + 1,
+			size.height as i32 + 1,
+			diameter_px,
+			diameter_px,
+		);
+
+		if region.is_null() {
+			return;
+		}
+		if SetWindowRgn(hwnd, region, 1) == 0 {
+			DeleteObject(region);
+
+			tracing::debug!(op = "overlay.window_shape", "SetWindowRgn failed.");
+		}
+	}
+}
+
+/// Compositors round transparent windows through the surface alpha, so no native region is set.
+#[cfg(not(target_os = "windows"))]
+pub(super) fn apply_rounded_window_region(window: &Window, corner_radius_points: f64) {
+	let _ = (window, corner_radius_points);
+}
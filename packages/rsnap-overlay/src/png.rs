@@ -1,4 +1,6 @@
-use color_eyre::eyre::{Result, WrapErr};
+use std::time::Duration;
+
+use color_eyre::eyre::{self, Result, WrapErr};
 use image::RgbaImage;
 use image::codecs::png::{CompressionType, FilterType, PngEncoder};
 use image::{ExtendedColorType, ImageEncoder};
@@ -27,6 +29,49 @@ pub(crate) fn rgba_image_to_png_bytes(image: &RgbaImage) -> Result<Vec<u8>> {
 	Ok(bytes)
 }
 
+/// Encodes equally sized frames as a looping animated PNG, each shown for its paired delay.
+pub(crate) fn rgba_frames_to_apng_bytes(frames: &[(&RgbaImage, Duration)]) -> Result<Vec<u8>> {
+	let Some((first, _)) = frames.first() else {
+		return Err(eyre::eyre!("no frames to encode"));
+	};
+	let mut bytes = Vec::new();
+	let mut encoder = ::png::Encoder::new(&mut bytes, first.width(), first.height());
+
+	encoder.set_color(::png::ColorType::Rgba);
+	encoder.set_depth(::png::BitDepth::Eight);
+	encoder.set_compression(::png::Compression::Fast);
+	encoder.set_animated(frames.len() as u32, 0).wrap_err("failed to configure APNG")?;
+
+	let mut writer = encoder.write_header().wrap_err("failed to write APNG header")?;
+
+	for (image, delay) in frames {
+		if image.dimensions() != first.dimensions() {
+			return Err(eyre::eyre!("APNG frames must share one size"));
+		}
+
+		let delay_ms = delay.as_millis().clamp(1, u128::from(u16::MAX)) as u16;
+
+		writer.set_frame_delay(delay_ms, 1_000).wrap_err("failed to set APNG frame delay")?;
+		writer.write_image_data(image.as_raw()).wrap_err("failed to encode APNG frame")?;
+	}
+
+	writer.finish().wrap_err("failed to finish APNG")?;
+
+	Ok(bytes)
+}
+
+/// Reads the pixel dimensions from a PNG's `IHDR` chunk without decoding the image.
+pub(crate) fn png_dimensions(png_bytes: &[u8]) -> Option<(u32, u32)> {
+	if !png_bytes.starts_with(b"\x89PNG\r\n\x1a\n") || png_bytes.get(12..16)? != b"IHDR" {
+		return None;
+	}
+
+	let width = u32::from_be_bytes(png_bytes.get(16..20)?.try_into().ok()?);
+	let height = u32::from_be_bytes(png_bytes.get(20..24)?.try_into().ok()?);
+
+	Some((width, height))
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::png::{self, RgbaImage};
@@ -38,4 +83,26 @@ mod tests {
 
 		assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
 	}
+
+	#[test]
+	fn png_dimensions_reads_ihdr() {
+		let image = RgbaImage::from_pixel(7, 3, image::Rgba([1, 2, 3, 255]));
+		let png = png::rgba_image_to_png_bytes(&image).unwrap();
+
+		assert_eq!(png::png_dimensions(&png), Some((7, 3)));
+		assert_eq!(png::png_dimensions(b"not a png"), None);
+	}
+
+	#[test]
+	fn apng_declares_every_frame() {
+		let red = RgbaImage::from_pixel(3, 2, image::Rgba([255, 0, 0, 255]));
+		let blue = RgbaImage::from_pixel(3, 2, image::Rgba([0, 0, 255, 255]));
+		let delay = std::time::Duration::from_millis(200);
+		let apng = png::rgba_frames_to_apng_bytes(&[(&red, delay), (&blue, delay)]).unwrap();
+		let actl = apng.windows(4).position(|chunk| chunk == b"acTL").expect("acTL chunk");
+
+		assert_eq!(png::png_dimensions(&apng), Some((3, 2)));
+		assert_eq!(&apng[actl + 4..actl + 8], &2_u32.to_be_bytes());
+		assert!(png::rgba_frames_to_apng_bytes(&[]).is_err());
+	}
 }
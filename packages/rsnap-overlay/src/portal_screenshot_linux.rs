@@ -0,0 +1,305 @@
+//! Last-resort freeze capture through the xdg-desktop-portal Screenshot interface.
+//!
+//! Locked-down Wayland compositors may refuse both direct capture and PipeWire streams while still
+//! serving `org.freedesktop.portal.Screenshot`, which asks the user to confirm each request.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::RgbaImage;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+use crate::backend::{self, CaptureBackend, CaptureBackendError};
+use crate::state::{
+	GlobalPoint, MonitorImageSnapshot, MonitorRect, RectPoints, Rgb, WindowListSnapshot,
+};
+use crate::x11_capture_linux;
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const PORTAL_SCREENSHOT_INTERFACE: &str = "org.freedesktop.portal.Screenshot";
+const PORTAL_REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+/// The portal shows a confirmation dialog, so allow the user time to answer it.
+const PORTAL_RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+/// One confirmed screenshot covers every monitor frozen by the same capture.
+const DESKTOP_IMAGE_REUSE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Capture backend that freezes monitors from a portal screenshot of the whole desktop.
+///
+/// Every request may prompt the user, so only freeze and export captures are served; live
+/// sampling, window captures, and caches report unsupported.
+pub(crate) struct PortalScreenshotCaptureBackend {
+	request_seq: u64,
+	desktop: Option<(Instant, Arc<RgbaImage>)>,
+}
+impl PortalScreenshotCaptureBackend {
+	pub(crate) fn new() -> Self {
+		Self { request_seq: 0, desktop: None }
+	}
+
+	fn desktop_image(&mut self) -> Result<Arc<RgbaImage>> {
+		if let Some((captured_at, image)) = &self.desktop
+			&& captured_at.elapsed() <= DESKTOP_IMAGE_REUSE_WINDOW
+		{
+			return Ok(image.clone());
+		}
+
+		self.request_seq += 1;
+
+		let token = format!("rsnap_{}_{}", process::id(), self.request_seq);
+		let image = Arc::new(request_desktop_screenshot(token)?);
+
+		self.desktop = Some((Instant::now(), image.clone()));
+
+		Ok(image)
+	}
+}
+
+impl Default for PortalScreenshotCaptureBackend {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl CaptureBackend for PortalScreenshotCaptureBackend {
+	fn capture_monitor(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
+		let desktop = self.desktop_image()?;
+
+		crop_desktop_to_monitor(&desktop, monitor, desktop_origin_px())
+	}
+
+	fn capture_monitor_region(
+		&mut self,
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+	) -> Result<RgbaImage> {
+		let image = self.capture_monitor(monitor)?;
+
+		backend::crop_monitor_image_region(&image, rect_px)
+	}
+
+	fn pixel_rgb_in_monitor(
+		&mut self,
+		_monitor: MonitorRect,
+		_point: GlobalPoint,
+	) -> Result<Option<Rgb>> {
+		Ok(None)
+	}
+
+	fn rgba_patch_in_monitor(
+		&mut self,
+		_monitor: MonitorRect,
+		_point: GlobalPoint,
+		_width_px: u32,
+		_height_px: u32,
+	) -> Result<Option<RgbaImage>> {
+		Ok(None)
+	}
+
+	fn refresh_monitor_cache(
+		&mut self,
+		_monitor: MonitorRect,
+	) -> Result<Arc<MonitorImageSnapshot>> {
+		Err(CaptureBackendError::NotSupported { backend: "portal" }.into())
+	}
+
+	fn refresh_window_cache(&mut self) -> Result<Arc<WindowListSnapshot>> {
+		Err(CaptureBackendError::NotSupported { backend: "portal" }.into())
+	}
+
+	fn capture_window(&mut self, _window_id: u32) -> Result<RgbaImage> {
+		Err(CaptureBackendError::NotSupported { backend: "portal" }.into())
+	}
+}
+
+/// Runs the portal request on a helper thread so an unanswered dialog cannot stall the worker.
+fn request_desktop_screenshot(token: String) -> Result<RgbaImage> {
+	let (tx, rx) = mpsc::channel();
+
+	thread::Builder::new()
+		.name("rsnap-portal-screenshot".to_owned())
+		.spawn(move || {
+			let _ = tx.send(request_desktop_screenshot_blocking(&token));
+		})
+		.wrap_err("Failed to spawn the portal screenshot thread")?;
+
+	let path = rx
+		.recv_timeout(PORTAL_RESPONSE_TIMEOUT)
+		.map_err(|_| eyre::eyre!("portal screenshot request timed out"))??;
+	let image = image::open(&path)
+		.wrap_err_with(|| format!("failed to decode portal screenshot: {}", path.display()))?
+		.to_rgba8();
+
+	tracing::info!(
+		op = "capture_backend.portal_screenshot",
+		path = %path.display(),
+		image_px = ?image.dimensions(),
+		"Captured the desktop through the screenshot portal."
+	);
+
+	Ok(image)
+}
+
+fn request_desktop_screenshot_blocking(token: &str) -> Result<PathBuf> {
+	let connection = Connection::session().wrap_err("Failed to connect to the session bus")?;
+	let sender = connection
+		.unique_name()
+		.ok_or_else(|| eyre::eyre!("session bus connection has no unique name"))?
+		.trim_start_matches(':')
+		.replace('.', "_");
+	let request_path = format!("{PORTAL_OBJECT_PATH}/request/{sender}/{token}");
+	// Subscribe before calling so a fast response cannot slip past.
+	let request =
+		Proxy::new(&connection, PORTAL_BUS_NAME, request_path.as_str(), PORTAL_REQUEST_INTERFACE)
+			.wrap_err("Failed to create the portal request proxy")?;
+	let mut responses =
+		request.receive_signal("Response").wrap_err("Failed to watch the portal request")?;
+	let screenshot =
+		Proxy::new(&connection, PORTAL_BUS_NAME, PORTAL_OBJECT_PATH, PORTAL_SCREENSHOT_INTERFACE)
+			.wrap_err("Failed to create the screenshot portal proxy")?;
+	let options = HashMap::from([
+		("handle_token", Value::from(token)),
+		("interactive", Value::from(false)),
+		("modal", Value::from(true)),
+	]);
+	let _handle: OwnedObjectPath =
+		screenshot.call("Screenshot", &("", options)).wrap_err("Screenshot portal call failed")?;
+	let message =
+		responses.next().ok_or_else(|| eyre::eyre!("portal request closed without a response"))?;
+	let (response, results): (u32, HashMap<String, OwnedValue>) =
+		message.body().deserialize().wrap_err("Failed to decode the portal response")?;
+
+	match response {
+		0 => {},
+		1 => return Err(eyre::eyre!("screenshot portal request was cancelled")),
+		code => return Err(eyre::eyre!("screenshot portal request failed (response {code})")),
+	}
+
+	let uri = results
+		.get("uri")
+		.and_then(|value| <&str>::try_from(value).ok())
+		.ok_or_else(|| eyre::eyre!("screenshot portal response has no uri"))?;
+
+	file_path_from_uri(uri).ok_or_else(|| eyre::eyre!("unsupported portal screenshot uri: {uri}"))
+}
+
+/// Best-effort pixel origin of the virtual desktop the portal image covers, from each monitor's
+/// scaled origin; exact only when all monitors share a scale factor.
+fn desktop_origin_px() -> (i64, i64) {
+	let Ok(monitors) = x11_capture_linux::monitor_rects() else {
+		return (0, 0);
+	};
+
+	monitors
+		.iter()
+		.map(MonitorRect::scaled_origin_px)
+		.reduce(|a, b| (a.0.min(b.0), a.1.min(b.1)))
+		.unwrap_or((0, 0))
+}
+
+/// Cuts one monitor out of a whole-desktop screenshot whose top-left is `desktop_origin_px`.
+fn crop_desktop_to_monitor(
+	desktop: &RgbaImage,
+	monitor: MonitorRect,
+	desktop_origin_px: (i64, i64),
+) -> Result<RgbaImage> {
+	let (width, height) = monitor.size_px();
+
+	if desktop.dimensions() == (width, height) {
+		return Ok(desktop.clone());
+	}
+
+	let (origin_x, origin_y) = monitor.scaled_origin_px();
+	let x = (origin_x - desktop_origin_px.0).clamp(0, i64::from(u32::MAX)) as u32;
+	let y = (origin_y - desktop_origin_px.1).clamp(0, i64::from(u32::MAX)) as u32;
+
+	backend::crop_monitor_image_region(desktop, RectPoints::new(x, y, width, height))
+}
+
+/// Decodes a `file://` URI into a local path, undoing percent escapes.
+fn file_path_from_uri(uri: &str) -> Option<PathBuf> {
+	let path = uri.strip_prefix("file://")?;
+	let path = path.strip_prefix("localhost").unwrap_or(path);
+
+	if !path.starts_with('/') {
+		return None;
+	}
+
+	let bytes = path.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+
+	while i < bytes.len() {
+		if bytes[i] == b'%' {
+			let hex = bytes.get(i + 1..i + 3)?;
+			let value = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+
+			decoded.push(value);
+
+			i += 3;
+		} else {
+			decoded.push(bytes[i]);
+
+			i += 1;
+		}
+	}
+
+	Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use image::{Rgba, RgbaImage};
+
+	use crate::portal_screenshot_linux::{crop_desktop_to_monitor, file_path_from_uri};
+	use crate::state::{GlobalPoint, MonitorRect};
+
+	#[test]
+	fn file_path_from_uri_decodes_percent_escapes() {
+		assert_eq!(
+			file_path_from_uri("file:///home/a%20b/Screenshot%E2%80%931.png"),
+			Some(PathBuf::from("/home/a b/Screenshot\u{2013}1.png"))
+		);
+		assert_eq!(
+			file_path_from_uri("file://localhost/tmp/shot.png"),
+			Some(PathBuf::from("/tmp/shot.png"))
+		);
+		assert_eq!(file_path_from_uri("https://example.com/shot.png"), None);
+		assert_eq!(file_path_from_uri("file:///tmp/bad%2"), None);
+	}
+
+	#[test]
+	fn crop_desktop_to_monitor_offsets_by_desktop_origin() {
+		let mut desktop = RgbaImage::new(300, 100);
+
+		desktop.put_pixel(100, 0, Rgba([255, 0, 0, 255]));
+
+		let right = MonitorRect {
+			id: 2,
+			origin: GlobalPoint::new(0, 0),
+			width: 200,
+			height: 100,
+			scale_factor_x1000: 1_000,
+		};
+		let cropped = crop_desktop_to_monitor(&desktop, right, (-100, 0)).unwrap();
+
+		assert_eq!(cropped.dimensions(), (200, 100));
+		assert_eq!(*cropped.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+
+		let whole = RgbaImage::new(200, 100);
+
+		assert_eq!(
+			crop_desktop_to_monitor(&whole, right, (-100, 0)).unwrap().dimensions(),
+			(200, 100)
+		);
+	}
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// A step of a long export job that reports progress before the session exits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStage {
+	/// Merging scroll-capture frames into the stitched image, reported once per merged frame.
+	Stitching,
+	/// Composing the backdrop, resize, and watermark of a regular export.
+	Composing,
+	/// Encoding the finished images to PNG.
+	Encoding,
+}
+
+/// An intermediate progress event of an export, sent through the worker channel ahead of the
+/// session's [`crate::OverlayExit`].
+///
+/// Serializes as one JSON line such as `{"type":"progress","stage":"stitching","pct":40}`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename = "progress")]
+pub struct OverlayProgress {
+	/// The step the job is in.
+	pub stage: ProgressStage,
+	/// Share of the whole job done so far, from 0 to 100. A scroll capture has no known end while
+	/// it stitches, so stitching events stay at 0 and count [`Self::frames`] instead.
+	pub pct: u8,
+	/// Scroll-capture frames merged into the stitched image so far; only set while stitching.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub frames: Option<u32>,
+}
+impl OverlayProgress {
+	/// Progress of a job that finished `done` of its `total` steps, now in `stage`.
+	pub(crate) fn of_steps(stage: ProgressStage, done: usize, total: usize) -> Self {
+		let pct = (done.min(total) * 100).checked_div(total).unwrap_or(100);
+
+		Self { stage, pct: pct as u8, frames: None }
+	}
+
+	/// Progress of a scroll capture that merged `frames` frames into its stitched image.
+	pub(crate) fn stitched(frames: usize) -> Self {
+		Self {
+			stage: ProgressStage::Stitching,
+			pct: 0,
+			frames: Some(u32::try_from(frames).unwrap_or(u32::MAX)),
+		}
+	}
+
+	/// Formats the event as a single JSON line for consumers that stream it.
+	#[must_use]
+	pub fn to_json_line(&self) -> String {
+		serde_json::to_string(self).unwrap_or_default()
+	}
+
+	/// Parses a line written by [`Self::to_json_line`]; other messages and malformed lines give
+	/// `None`.
+	#[must_use]
+	pub fn parse(line: &str) -> Option<Self> {
+		serde_json::from_str(line.trim()).ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::progress::{OverlayProgress, ProgressStage};
+
+	#[test]
+	fn progress_round_trips_through_a_json_line() {
+		let progress = OverlayProgress::of_steps(ProgressStage::Encoding, 2, 5);
+		let line = progress.to_json_line();
+
+		assert_eq!(line, r#"{"type":"progress","stage":"encoding","pct":40}"#);
+		assert_eq!(OverlayProgress::parse(&line), Some(progress));
+
+		let stitched = OverlayProgress::stitched(12);
+		let line = stitched.to_json_line();
+
+		assert_eq!(line, r#"{"type":"progress","stage":"stitching","pct":0,"frames":12}"#);
+		assert_eq!(OverlayProgress::parse(&line), Some(stitched));
+		assert_eq!(OverlayProgress::parse(r#"{"type":"result","path":"a.png"}"#), None);
+		assert_eq!(OverlayProgress::parse("not json"), None);
+	}
+}
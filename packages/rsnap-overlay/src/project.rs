@@ -0,0 +1,292 @@
+//! The `.rsnap` project format: a PNG of the whole frozen monitor whose `iTXt` chunk holds the
+//! selection, the annotations with their undo history, and where the capture came from. Any image
+//! viewer still opens the file as the unannotated screen.
+
+use std::io::Cursor;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::imageops;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::annotation::{AnnotationLayer, SavedAnnotations};
+use crate::state::{MonitorRect, RectPoints};
+
+/// File extension of saved projects.
+pub const PROJECT_EXTENSION: &str = "rsnap";
+/// Keyword of the `iTXt` chunk holding the manifest.
+const PROJECT_KEYWORD: &str = "rsnap:project";
+const PROJECT_VERSION: u32 = 1;
+/// Backdrop around an image opened with [`Project::from_image`].
+const IMAGE_CANVAS_RGBA: [u8; 4] = [38, 38, 42, 255];
+
+/// A frozen capture that can be reopened in the editor.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Project {
+	/// The whole frozen monitor, uncropped and without annotations.
+	pub(crate) image: RgbaImage,
+	pub(crate) monitor_id: u32,
+	pub(crate) scale_factor_x1000: u32,
+	/// The selection, in monitor-local points.
+	pub(crate) crop: RectPoints,
+	pub(crate) annotations: SavedAnnotations,
+	pub(crate) saved_at_unix_ms: u64,
+}
+impl Project {
+	pub(crate) fn new(
+		image: RgbaImage,
+		monitor: MonitorRect,
+		crop: RectPoints,
+		annotations: &AnnotationLayer,
+	) -> Self {
+		let saved_at_unix_ms = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_millis() as u64);
+
+		Self {
+			image,
+			monitor_id: monitor.id,
+			scale_factor_x1000: monitor.scale_factor_x1000,
+			crop,
+			annotations: annotations.to_saved(),
+			saved_at_unix_ms,
+		}
+	}
+
+	/// The connected display the project reopens on: the one it was captured on when it still has
+	/// the same size and scale, otherwise the first display that does.
+	pub(crate) fn monitor(&self, monitors: &[MonitorRect]) -> Option<MonitorRect> {
+		let fits = |monitor: &&MonitorRect| {
+			monitor.scale_factor_x1000 == self.scale_factor_x1000
+				&& monitor.size_px() == self.image.dimensions()
+				&& self.crop.x.saturating_add(self.crop.width) <= monitor.width
+				&& self.crop.y.saturating_add(self.crop.height) <= monitor.height
+		};
+
+		monitors
+			.iter()
+			.filter(fits)
+			.find(|monitor| monitor.id == self.monitor_id)
+			.or_else(|| monitors.iter().find(fits))
+			.copied()
+	}
+
+	/// A project that opens a plain image in the editor: the image centred on a neutral canvas the
+	/// size of the first display that holds it, selected, and without annotations.
+	pub(crate) fn from_image(
+		image: &RgbaImage,
+		monitors: &[MonitorRect],
+	) -> Option<(MonitorRect, Self)> {
+		let (width_px, height_px) = image.dimensions();
+
+		monitors.iter().find_map(|monitor| {
+			let (canvas_width_px, canvas_height_px) = monitor.size_px();
+
+			if width_px > canvas_width_px || height_px > canvas_height_px {
+				return None;
+			}
+
+			let to_points = |pixels: u32, limit: u32| {
+				((f64::from(pixels) / f64::from(monitor.scale_factor())).round() as u32)
+					.clamp(1, limit)
+			};
+			let width = to_points(width_px, monitor.width);
+			let height = to_points(height_px, monitor.height);
+			let crop = RectPoints::new(
+				(monitor.width - width) / 2,
+				(monitor.height - height) / 2,
+				width,
+				height,
+			);
+			let mut canvas =
+				RgbaImage::from_pixel(canvas_width_px, canvas_height_px, Rgba(IMAGE_CANVAS_RGBA));
+
+			imageops::replace(
+				&mut canvas,
+				image,
+				i64::from(monitor.points_to_pixels(crop.x)),
+				i64::from(monitor.points_to_pixels(crop.y)),
+			);
+
+			Some((*monitor, Self::new(canvas, *monitor, crop, &AnnotationLayer::default())))
+		})
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ProjectManifest {
+	version: u32,
+	/// The `rsnap-overlay` version that wrote the file.
+	writer: String,
+	saved_at_unix_ms: u64,
+	monitor_id: u32,
+	scale_factor_x1000: u32,
+	crop: CropRect,
+	annotations: SavedAnnotations,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CropRect {
+	x: u32,
+	y: u32,
+	width: u32,
+	height: u32,
+}
+
+pub(crate) fn encode_project(project: &Project) -> Result<Vec<u8>> {
+	let crop = project.crop;
+	let manifest = ProjectManifest {
+		version: PROJECT_VERSION,
+		writer: String::from(env!("CARGO_PKG_VERSION")),
+		saved_at_unix_ms: project.saved_at_unix_ms,
+		monitor_id: project.monitor_id,
+		scale_factor_x1000: project.scale_factor_x1000,
+		crop: CropRect { x: crop.x, y: crop.y, width: crop.width, height: crop.height },
+		annotations: project.annotations.clone(),
+	};
+	let manifest =
+		serde_json::to_string(&manifest).wrap_err("failed to serialize the project manifest")?;
+	let mut bytes = Vec::new();
+	let mut encoder =
+		::png::Encoder::new(&mut bytes, project.image.width(), project.image.height());
+
+	encoder.set_color(::png::ColorType::Rgba);
+	encoder.set_depth(::png::BitDepth::Eight);
+	encoder.set_compression(::png::Compression::Fast);
+	encoder
+		.add_itxt_chunk(String::from(PROJECT_KEYWORD), manifest)
+		.wrap_err("failed to add the project manifest")?;
+
+	let mut writer = encoder.write_header().wrap_err("failed to write the project header")?;
+
+	writer.write_image_data(project.image.as_raw()).wrap_err("failed to encode the project")?;
+	writer.finish().wrap_err("failed to finish the project")?;
+
+	Ok(bytes)
+}
+
+pub(crate) fn decode_project(bytes: &[u8]) -> Result<Project> {
+	let reader = ::png::Decoder::new(Cursor::new(bytes))
+		.read_info()
+		.wrap_err("not a PNG-based rsnap project")?;
+	let manifest = reader
+		.info()
+		.utf8_text
+		.iter()
+		.find(|chunk| chunk.keyword == PROJECT_KEYWORD)
+		.ok_or_else(|| eyre::eyre!("the image has no rsnap project data"))?
+		.get_text()
+		.map_err(|err| eyre::eyre!("failed to read the project manifest: {err}"))?;
+	let manifest = serde_json::from_str::<ProjectManifest>(&manifest)
+		.wrap_err("failed to parse the project manifest")?;
+
+	if manifest.version > PROJECT_VERSION {
+		return Err(eyre::eyre!(
+			"project version {} was written by a newer rsnap ({})",
+			manifest.version,
+			manifest.writer
+		));
+	}
+
+	let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+		.wrap_err("failed to decode the project image")?
+		.to_rgba8();
+	let CropRect { x, y, width, height } = manifest.crop;
+
+	if width == 0 || height == 0 {
+		return Err(eyre::eyre!("the project selection is empty"));
+	}
+
+	Ok(Project {
+		image,
+		monitor_id: manifest.monitor_id,
+		scale_factor_x1000: manifest.scale_factor_x1000,
+		crop: RectPoints::new(x, y, width, height),
+		annotations: manifest.annotations,
+		saved_at_unix_ms: manifest.saved_at_unix_ms,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::annotation::AnnotationLayer;
+	use crate::png;
+	use crate::project::{self, Project};
+	use crate::stamp::StampKind;
+	use crate::state::{GlobalPoint, MonitorRect, RectPoints};
+
+	fn monitor(id: u32, width: u32, scale_factor_x1000: u32) -> MonitorRect {
+		MonitorRect { id, origin: GlobalPoint::new(0, 0), width, height: 50, scale_factor_x1000 }
+	}
+
+	fn project() -> Project {
+		let image = RgbaImage::from_fn(160, 100, |x, y| Rgba([x as u8, y as u8, 7, 255]));
+		let mut annotations = AnnotationLayer::default();
+
+		annotations.place_stamp(StampKind::Heart, (20, 20));
+		annotations.place_stamp(StampKind::Star, (40, 30));
+		assert!(annotations.undo());
+
+		Project::new(image, monitor(3, 80, 2_000), RectPoints::new(10, 5, 40, 30), &annotations)
+	}
+
+	#[test]
+	fn projects_round_trip_with_their_undo_history() {
+		let saved = project();
+		let bytes = project::encode_project(&saved).expect("encode");
+
+		assert_eq!(png::png_dimensions(&bytes), Some((160, 100)));
+
+		let reopened = project::decode_project(&bytes).expect("decode");
+
+		assert_eq!(reopened, saved);
+
+		let mut layer = AnnotationLayer::from_saved(reopened.annotations);
+
+		assert_eq!(layer.stamps.len(), 1);
+		assert!(layer.redo());
+		assert_eq!(layer.stamps[1].kind, StampKind::Star);
+		assert!(layer.undo());
+		assert!(layer.undo());
+		assert!(layer.stamps.is_empty());
+	}
+
+	#[test]
+	fn plain_pngs_are_not_projects() {
+		let bytes = png::rgba_image_to_png_bytes(&RgbaImage::new(4, 4)).expect("png");
+
+		assert!(project::decode_project(&bytes).is_err());
+		assert!(project::decode_project(b"not an image").is_err());
+	}
+
+	#[test]
+	fn projects_reopen_on_a_display_with_the_same_pixels() {
+		let saved = project();
+		let elsewhere = monitor(9, 80, 2_000);
+
+		assert_eq!(saved.monitor(&[monitor(9, 160, 1_000), elsewhere]), Some(elsewhere));
+		assert_eq!(
+			saved.monitor(&[elsewhere, monitor(3, 80, 2_000)]).map(|monitor| monitor.id),
+			Some(3)
+		);
+		assert_eq!(saved.monitor(&[monitor(3, 160, 1_000)]), None);
+	}
+
+	#[test]
+	fn images_open_centred_on_the_first_display_that_holds_them() {
+		let image = RgbaImage::from_pixel(60, 40, Rgba([200, 10, 10, 255]));
+		let (opened_on, project) =
+			Project::from_image(&image, &[monitor(1, 20, 1_000), monitor(2, 80, 2_000)])
+				.expect("a display that fits");
+
+		assert_eq!(opened_on.id, 2);
+		assert_eq!(project.image.dimensions(), (160, 100));
+		assert_eq!(project.crop, RectPoints::new(25, 15, 30, 20));
+		assert_eq!(project.image.get_pixel(50, 30).0, [200, 10, 10, 255]);
+		assert_eq!(project.image.get_pixel(49, 30).0, [38, 38, 42, 255]);
+		assert!(Project::from_image(&image, &[monitor(1, 20, 1_000)]).is_none());
+	}
+}
@@ -0,0 +1,479 @@
+//! Pure-Rust QR code recognition in captured images.
+//!
+//! The image is binarized with an Otsu threshold, finder patterns are located by their 1:1:3:1:1
+//! run ratio in both directions, and every plausible triple of finders is sampled into a module
+//! grid with the affine map they span. Screen captures are not perspective-distorted, so that map
+//! is accurate without alignment-pattern refinement. Both polarities are tried so light-on-dark
+//! codes are found too.
+
+mod qr;
+
+use std::cmp::Reverse;
+
+use image::RgbaImage;
+
+use crate::recognition::qr::ModuleGrid;
+use crate::state::RectPoints;
+
+/// More finder clusters than this are treated as noise; triples grow cubically.
+const MAX_FINDER_CANDIDATES: usize = 24;
+
+/// A code found in an image, in that image's pixel coordinates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DecodedCode {
+	pub(crate) payload: String,
+	pub(crate) bounds_px: RectPoints,
+}
+impl DecodedCode {
+	/// Whether the payload is a web link that can be handed to the browser.
+	pub(crate) fn is_url(&self) -> bool {
+		let lower = self.payload.trim().to_ascii_lowercase();
+
+		lower.starts_with("https://") || lower.starts_with("http://")
+	}
+}
+
+/// Finds and decodes every QR code in `image`.
+pub(crate) fn scan_codes(image: &RgbaImage) -> Vec<DecodedCode> {
+	let luma = image
+		.pixels()
+		.map(|pixel| {
+			let [r, g, b, a] = pixel.0;
+			let luma = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1_000;
+
+			// Transparent pixels read as the light background a code sits on.
+			if a == 0 { 255 } else { luma as u8 }
+		})
+		.collect::<Vec<_>>();
+	let threshold = otsu_threshold(&luma);
+	let (width, height) = (image.width() as usize, image.height() as usize);
+
+	for inverted in [false, true] {
+		let dark = luma.iter().map(|&value| (value <= threshold) != inverted).collect();
+		let codes = Binary { width, height, dark }.decode_all();
+
+		if !codes.is_empty() {
+			return codes;
+		}
+	}
+
+	Vec::new()
+}
+
+fn otsu_threshold(luma: &[u8]) -> u8 {
+	let mut histogram = [0_u64; 256];
+
+	for &value in luma {
+		histogram[usize::from(value)] += 1;
+	}
+
+	let total = luma.len() as f64;
+	let weighted_total =
+		histogram.iter().enumerate().map(|(i, &n)| i as f64 * n as f64).sum::<f64>();
+	let mut background = 0.0;
+	let mut weighted_background = 0.0;
+	let mut best = (0.0, 127);
+
+	for (value, &count) in histogram.iter().enumerate() {
+		background += count as f64;
+
+		if background == 0.0 || background == total {
+			continue;
+		}
+
+		weighted_background += value as f64 * count as f64;
+
+		let foreground = total - background;
+		let mean_background = weighted_background / background;
+		let mean_foreground = (weighted_total - weighted_background) / foreground;
+		let variance = background * foreground * (mean_background - mean_foreground).powi(2);
+
+		if variance > best.0 {
+			best = (variance, value as u8);
+		}
+	}
+
+	best.1
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Finder {
+	x: f32,
+	y: f32,
+	module: f32,
+	hits: u32,
+}
+
+struct Binary {
+	width: usize,
+	height: usize,
+	dark: Vec<bool>,
+}
+impl Binary {
+	fn is_dark(&self, x: usize, y: usize) -> bool {
+		self.dark[y * self.width + x]
+	}
+
+	fn decode_all(&self) -> Vec<DecodedCode> {
+		let mut finders = self.find_finders();
+
+		finders.sort_by_key(|finder| Reverse(finder.hits));
+		finders.truncate(MAX_FINDER_CANDIDATES);
+
+		let mut used = vec![false; finders.len()];
+		let mut codes = Vec::new();
+
+		for i in 0..finders.len() {
+			for j in i + 1..finders.len() {
+				for k in j + 1..finders.len() {
+					if used[i] || used[j] || used[k] {
+						continue;
+					}
+					if let Some(code) = self.decode_triple([finders[i], finders[j], finders[k]]) {
+						used[i] = true;
+						used[j] = true;
+						used[k] = true;
+
+						codes.push(code);
+					}
+				}
+			}
+		}
+
+		codes
+	}
+
+	/// Scans every row for finder ratios, confirms them across both axes and merges the hits.
+	fn find_finders(&self) -> Vec<Finder> {
+		let mut finders = Vec::<Finder>::new();
+
+		for y in 0..self.height {
+			let runs = self.row_runs(y);
+
+			for window in runs.windows(5) {
+				if !window[0].2 {
+					continue;
+				}
+
+				let counts = [0, 1, 2, 3, 4].map(|i| window[i].1);
+
+				if finder_module(counts).is_none() {
+					continue;
+				}
+
+				let total = counts.iter().sum::<usize>();
+				let x = window[2].0 + window[2].1 / 2;
+				let Some((center_y, vertical_total)) = self.cross_check(x, y, total, true) else {
+					continue;
+				};
+				let Some((center_x, horizontal_total)) =
+					self.cross_check(center_y as usize, x, total, false)
+				else {
+					continue;
+				};
+
+				if (vertical_total as f32 - horizontal_total as f32).abs() * 5.0
+					> horizontal_total as f32 * 2.0
+				{
+					continue;
+				}
+
+				let module = (vertical_total + horizontal_total) as f32 / 14.0;
+				let candidate = Finder { x: center_x, y: center_y, module, hits: 1 };
+
+				if let Some(existing) = finders.iter_mut().find(|finder| {
+					(finder.x - candidate.x).abs() <= finder.module * 2.0
+						&& (finder.y - candidate.y).abs() <= finder.module * 2.0
+				}) {
+					let hits = existing.hits as f32;
+
+					existing.x = (existing.x * hits + candidate.x) / (hits + 1.0);
+					existing.y = (existing.y * hits + candidate.y) / (hits + 1.0);
+					existing.module = (existing.module * hits + candidate.module) / (hits + 1.0);
+					existing.hits += 1;
+				} else {
+					finders.push(candidate);
+				}
+			}
+		}
+
+		finders.retain(|finder| finder.hits >= 2);
+
+		finders
+	}
+
+	/// `(start, length, dark)` runs of row `y`.
+	fn row_runs(&self, y: usize) -> Vec<(usize, usize, bool)> {
+		let mut runs = Vec::new();
+		let mut start = 0;
+
+		for x in 1..=self.width {
+			if x == self.width || self.is_dark(x, y) != self.is_dark(start, y) {
+				runs.push((start, x - start, self.is_dark(start, y)));
+
+				start = x;
+			}
+		}
+
+		runs
+	}
+
+	/// Re-measures the finder ratio from `start` along column `fixed` when `vertical`, else along
+	/// row `fixed`. Returns the center of the middle run and the total length.
+	fn cross_check(
+		&self,
+		fixed: usize,
+		start: usize,
+		expected_total: usize,
+		vertical: bool,
+	) -> Option<(f32, usize)> {
+		let limit = if vertical { self.height } else { self.width };
+		let dark = |i: usize| {
+			if vertical { self.is_dark(fixed, i) } else { self.is_dark(i, fixed) }
+		};
+
+		if !dark(start) {
+			return None;
+		}
+
+		let max_run = expected_total;
+		let mut counts = [0_usize; 5];
+		// Walk backwards through the center, the light ring and the outer dark ring.
+		let mut i = start as isize;
+
+		for (slot, want_dark) in [(2, true), (1, false), (0, true)] {
+			while i >= 0 && dark(i as usize) == want_dark && counts[slot] <= max_run {
+				counts[slot] += 1;
+				i -= 1;
+			}
+		}
+
+		let center_start = start + 1 - counts[2];
+		let mut i = start + 1;
+
+		for (slot, want_dark) in [(2, true), (3, false), (4, true)] {
+			while i < limit && dark(i) == want_dark && counts[slot] <= max_run {
+				counts[slot] += 1;
+				i += 1;
+			}
+		}
+
+		finder_module(counts)?;
+
+		let total = counts.iter().sum::<usize>();
+
+		if total.abs_diff(expected_total) * 5 >= expected_total * 2 {
+			return None;
+		}
+
+		Some((center_start as f32 + counts[2] as f32 / 2.0, total))
+	}
+
+	/// Samples the grid spanned by three finders and decodes it, trying nearby versions.
+	fn decode_triple(&self, finders: [Finder; 3]) -> Option<DecodedCode> {
+		let modules = finders.map(|finder| finder.module);
+		let (min_module, max_module) = modules
+			.iter()
+			.fold((f32::MAX, 0.0_f32), |(min, max), &module| (min.min(module), max.max(module)));
+
+		if max_module > min_module * 1.5 {
+			return None;
+		}
+
+		let distance = |a: Finder, b: Finder| ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt();
+		let [p, q, r] = finders;
+		// The corner finder sits opposite the longest side.
+		let (corner, mut b, mut c) = {
+			let (pq, pr, qr) = (distance(p, q), distance(p, r), distance(q, r));
+
+			if qr >= pq && qr >= pr {
+				(p, q, r)
+			} else if pr >= pq {
+				(q, p, r)
+			} else {
+				(r, p, q)
+			}
+		};
+		let (ab, ac, bc) = (distance(corner, b), distance(corner, c), distance(b, c));
+
+		if (ab - ac).abs() > ab.max(ac) * 0.2
+			|| (bc.powi(2) - (ab.powi(2) + ac.powi(2))).abs() > (ab.powi(2) + ac.powi(2)) * 0.2
+		{
+			return None;
+		}
+
+		// With y pointing down, the top-right finder is clockwise from the bottom-left one.
+		if (b.x - corner.x) * (c.y - corner.y) - (b.y - corner.y) * (c.x - corner.x) < 0.0 {
+			std::mem::swap(&mut b, &mut c);
+		}
+
+		let module = modules.iter().sum::<f32>() / 3.0;
+		let estimated = (((ab + ac) / 2.0 / module + 7.0 - 17.0) / 4.0).round() as isize;
+
+		for offset in [0, 1, -1, 2, -2] {
+			let version = estimated + offset;
+
+			if !(1..=40).contains(&version) {
+				continue;
+			}
+
+			let size = qr::version_size(version as usize);
+			let span = (size - 7) as f32;
+			let map = |mx: f32, my: f32| {
+				let (u, v) = ((mx - 3.5) / span, (my - 3.5) / span);
+
+				(
+					corner.x + u * (b.x - corner.x) + v * (c.x - corner.x),
+					corner.y + u * (b.y - corner.y) + v * (c.y - corner.y),
+				)
+			};
+			let mut grid = ModuleGrid::new(size);
+
+			for my in 0..size {
+				for mx in 0..size {
+					let (x, y) = map(mx as f32 + 0.5, my as f32 + 0.5);
+
+					if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+						continue;
+					}
+
+					grid.set(mx, my, self.is_dark(x as usize, y as usize));
+				}
+			}
+
+			let Some(payload) = qr::decode(&grid) else {
+				continue;
+			};
+			let corners =
+				[(0.0, 0.0), (size as f32, 0.0), (0.0, size as f32), (size as f32, size as f32)]
+					.map(|(mx, my)| map(mx, my));
+			let clamp_x = |x: f32| x.clamp(0.0, self.width as f32) as u32;
+			let clamp_y = |y: f32| y.clamp(0.0, self.height as f32) as u32;
+			let left = clamp_x(corners.iter().map(|point| point.0).fold(f32::MAX, f32::min));
+			let right =
+				clamp_x(corners.iter().map(|point| point.0).fold(f32::MIN, f32::max).ceil());
+			let top = clamp_y(corners.iter().map(|point| point.1).fold(f32::MAX, f32::min));
+			let bottom =
+				clamp_y(corners.iter().map(|point| point.1).fold(f32::MIN, f32::max).ceil());
+
+			return Some(DecodedCode {
+				payload,
+				bounds_px: RectPoints::new(left, top, right - left, bottom - top),
+			});
+		}
+
+		None
+	}
+}
+
+/// Module size when five run lengths match the finder's 1:1:3:1:1 ratio within half a module.
+fn finder_module(counts: [usize; 5]) -> Option<f32> {
+	if counts.contains(&0) {
+		return None;
+	}
+
+	let total = counts.iter().sum::<usize>();
+
+	if total < 7 {
+		return None;
+	}
+
+	let module = total as f32 / 7.0;
+	let tolerance = module / 2.0;
+	let fits = counts
+		.iter()
+		.zip([1.0, 1.0, 3.0, 1.0, 1.0])
+		.all(|(&count, weight)| (count as f32 - module * weight).abs() < tolerance * weight);
+
+	fits.then_some(module)
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+	use qrcode::{EcLevel, QrCode, Version};
+
+	use crate::recognition;
+	use crate::state::RectPoints;
+
+	fn render(code: &QrCode, module_px: u32, image: &mut RgbaImage, x: u32, y: u32) {
+		let width = code.width() as u32;
+
+		for (index, color) in code.to_colors().into_iter().enumerate() {
+			let (mx, my) = (index as u32 % width, index as u32 / width);
+			let value = if color == qrcode::Color::Dark { 0 } else { 255 };
+
+			for dy in 0..module_px {
+				for dx in 0..module_px {
+					image.put_pixel(
+						x + mx * module_px + dx,
+						y + my * module_px + dy,
+						Rgba([value, value, value, 255]),
+					);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn scan_decodes_codes_across_versions_levels_and_modes() {
+		let cases = [
+			("https://example.com/rsnap", 2, EcLevel::L, 4),
+			("rsnap", 1, EcLevel::H, 3),
+			("0123456789012345", 2, EcLevel::M, 3),
+			("HELLO RSNAP $%*+-./:", 5, EcLevel::Q, 3),
+			("Grüße aus dem Screenshot-Werkzeug, diesmal etwas länger.", 8, EcLevel::H, 2),
+			(&"rsnap ".repeat(40), 15, EcLevel::M, 2),
+		];
+
+		for (payload, version, level, module_px) in cases {
+			let code = QrCode::with_version(payload, Version::Normal(version), level).unwrap();
+			let side = code.width() as u32 * module_px;
+			let mut image = RgbaImage::from_pixel(side + 60, side + 40, Rgba([240, 240, 240, 255]));
+
+			render(&code, module_px, &mut image, 30, 20);
+
+			let codes = recognition::scan_codes(&image);
+
+			assert_eq!(codes.len(), 1, "version {version}");
+			assert_eq!(codes[0].payload, payload);
+			assert_eq!(codes[0].bounds_px, RectPoints::new(30, 20, side, side));
+		}
+	}
+
+	#[test]
+	fn scan_corrects_damaged_modules_and_inverted_codes() {
+		let code =
+			QrCode::with_version("https://example.com/a", Version::Normal(5), EcLevel::H).unwrap();
+		let mut image = RgbaImage::from_pixel(200, 200, Rgba([255, 255, 255, 255]));
+
+		render(&code, 4, &mut image, 10, 10);
+
+		// Scribble over a few data modules in the middle of the symbol.
+		for x in 10 + 18 * 4..10 + 22 * 4 {
+			for y in 10 + 18 * 4..10 + 19 * 4 {
+				let pixel = image.get_pixel_mut(x, y);
+
+				pixel.0[..3].iter_mut().for_each(|channel| *channel = 255 - *channel);
+			}
+		}
+
+		for pixel in image.pixels_mut() {
+			pixel.0[..3].iter_mut().for_each(|channel| *channel = 255 - *channel);
+		}
+
+		let codes = recognition::scan_codes(&image);
+
+		assert_eq!(codes.len(), 1);
+		assert_eq!(codes[0].payload, "https://example.com/a");
+		assert!(codes[0].is_url());
+	}
+
+	#[test]
+	fn scan_finds_nothing_in_a_plain_gradient() {
+		let image =
+			RgbaImage::from_fn(120, 80, |x, y| Rgba([(x * 2) as u8, (y * 3) as u8, 90, 255]));
+
+		assert!(recognition::scan_codes(&image).is_empty());
+	}
+}
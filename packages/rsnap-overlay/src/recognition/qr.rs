@@ -0,0 +1,569 @@
+//! QR symbol decoding from a sampled module grid: format info, codeword extraction, Reed-Solomon
+//! correction and segment parsing.
+
+/// Error correction codewords per block, indexed by `[level][version]` with levels in L, M, Q, H
+/// order.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+	[
+		0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+		30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+	],
+	[
+		0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+		28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+	],
+	[
+		0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+		30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+	],
+	[
+		0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+		30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+	],
+];
+/// Error correction blocks, indexed like [`ECC_CODEWORDS_PER_BLOCK`].
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+	[
+		0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+		14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+	],
+	[
+		0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+		23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+	],
+	[
+		0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+		34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+	],
+	[
+		0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+		35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+	],
+];
+const ALPHANUMERIC_CHARSET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+const GF: GaloisField = GaloisField::new();
+
+/// A square grid of sampled modules, `true` for dark.
+pub(super) struct ModuleGrid {
+	size: usize,
+	modules: Vec<bool>,
+}
+impl ModuleGrid {
+	pub(super) fn new(size: usize) -> Self {
+		Self { size, modules: vec![false; size * size] }
+	}
+
+	pub(super) fn set(&mut self, x: usize, y: usize, dark: bool) {
+		self.modules[y * self.size + x] = dark;
+	}
+
+	fn get(&self, x: usize, y: usize) -> bool {
+		self.modules[y * self.size + x]
+	}
+}
+
+/// Side length in modules of a symbol of `version`.
+pub(super) const fn version_size(version: usize) -> usize {
+	version * 4 + 17
+}
+
+/// Decodes the symbol in `grid` to its text payload; `None` when any stage fails.
+pub(super) fn decode(grid: &ModuleGrid) -> Option<String> {
+	let version = grid.size.checked_sub(17)? / 4;
+
+	if !(1..=40).contains(&version) || version_size(version) != grid.size {
+		return None;
+	}
+
+	let (level, mask) = read_format(grid)?;
+	let function = function_modules(version);
+	let codewords = read_codewords(grid, &function, mask, raw_data_modules(version) / 8);
+	let data = correct_blocks(&codewords, version, level)?;
+
+	parse_segments(&data, version)
+}
+
+/// Reads both format info copies and returns `(level index, mask)` of the nearest valid pattern.
+fn read_format(grid: &ModuleGrid) -> Option<(usize, usize)> {
+	let size = grid.size;
+	let mut first = 0_u32;
+	let mut second = 0_u32;
+
+	for i in 0..15 {
+		let (x, y) = match i {
+			0..=5 => (8, i),
+			6 => (8, 7),
+			7 => (8, 8),
+			8 => (7, 8),
+			_ => (14 - i, 8),
+		};
+
+		first |= u32::from(grid.get(x, y)) << i;
+
+		let (x, y) = if i < 8 { (size - 1 - i, 8) } else { (8, size - 15 + i) };
+
+		second |= u32::from(grid.get(x, y)) << i;
+	}
+
+	let (distance, data) = (0..32_u32)
+		.map(|data| {
+			let encoded = encode_format(data);
+
+			((encoded ^ first).count_ones().min((encoded ^ second).count_ones()), data)
+		})
+		.min()?;
+
+	if distance > 3 {
+		return None;
+	}
+
+	// Format bits store L, M, Q, H as 1, 0, 3, 2.
+	let level = [1, 0, 3, 2][(data >> 3) as usize];
+
+	Some((level, (data & 7) as usize))
+}
+
+fn encode_format(data: u32) -> u32 {
+	let mut remainder = data;
+
+	for _ in 0..10 {
+		remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+	}
+
+	((data << 10) | remainder) ^ 0x5412
+}
+
+/// Marks finder, timing, alignment, format and version modules, which never carry data.
+fn function_modules(version: usize) -> Vec<bool> {
+	let size = version_size(version);
+	let mut function = vec![false; size * size];
+	let mut mark = |x: usize, y: usize| function[y * size + x] = true;
+
+	for i in 0..size {
+		mark(6, i);
+		mark(i, 6);
+	}
+
+	for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+		for y in cy.saturating_sub(4)..(cy + 5).min(size) {
+			for x in cx.saturating_sub(4)..(cx + 5).min(size) {
+				mark(x, y);
+			}
+		}
+	}
+
+	let positions = alignment_positions(version);
+	let last = positions.len().saturating_sub(1);
+
+	for (i, &cy) in positions.iter().enumerate() {
+		for (j, &cx) in positions.iter().enumerate() {
+			if (i == 0 && j == 0) || (i == 0 && j == last) || (i == last && j == 0) {
+				continue;
+			}
+
+			for y in cy - 2..=cy + 2 {
+				for x in cx - 2..=cx + 2 {
+					mark(x, y);
+				}
+			}
+		}
+	}
+
+	for i in 0..9 {
+		mark(8, i);
+		mark(i, 8);
+	}
+
+	for i in size - 8..size {
+		mark(i, 8);
+		mark(8, i);
+	}
+
+	if version >= 7 {
+		for i in 0..18 {
+			let (a, b) = (size - 11 + i % 3, i / 3);
+
+			mark(a, b);
+			mark(b, a);
+		}
+	}
+
+	function
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+	if version == 1 {
+		return Vec::new();
+	}
+
+	let count = version / 7 + 2;
+	let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+	let mut positions =
+		(0..count - 1).map(|i| version_size(version) - 7 - i * step).collect::<Vec<_>>();
+
+	positions.push(6);
+	positions.reverse();
+
+	positions
+}
+
+fn raw_data_modules(version: usize) -> usize {
+	let mut modules = (16 * version + 128) * version + 64;
+
+	if version >= 2 {
+		let count = version / 7 + 2;
+
+		modules -= (25 * count - 10) * count - 55;
+
+		if version >= 7 {
+			modules -= 36;
+		}
+	}
+
+	modules
+}
+
+fn mask_bit(mask: usize, x: usize, y: usize) -> bool {
+	match mask {
+		0 => (x + y).is_multiple_of(2),
+		1 => y.is_multiple_of(2),
+		2 => x.is_multiple_of(3),
+		3 => (x + y).is_multiple_of(3),
+		4 => (x / 3 + y / 2).is_multiple_of(2),
+		5 => x * y % 2 + x * y % 3 == 0,
+		6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+		_ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+	}
+}
+
+/// Reads the unmasked data modules in the standard two-column zigzag order.
+fn read_codewords(grid: &ModuleGrid, function: &[bool], mask: usize, count: usize) -> Vec<u8> {
+	let size = grid.size;
+	let mut codewords = vec![0_u8; count];
+	let mut bit = 0;
+	let mut right = size - 1;
+
+	while right >= 1 {
+		if right == 6 {
+			right = 5;
+		}
+
+		let upward = (right + 1) & 2 == 0;
+
+		for vert in 0..size {
+			let y = if upward { size - 1 - vert } else { vert };
+
+			for x in [right, right - 1] {
+				if function[y * size + x] || bit >= count * 8 {
+					continue;
+				}
+				if grid.get(x, y) ^ mask_bit(mask, x, y) {
+					codewords[bit / 8] |= 0x80 >> (bit % 8);
+				}
+
+				bit += 1;
+			}
+		}
+
+		if right < 2 {
+			break;
+		}
+
+		right -= 2;
+	}
+
+	codewords
+}
+
+/// De-interleaves the error correction blocks, corrects each one and joins their data codewords.
+fn correct_blocks(codewords: &[u8], version: usize, level: usize) -> Option<Vec<u8>> {
+	let block_count = usize::from(NUM_ERROR_CORRECTION_BLOCKS[level][version]);
+	let ecc_len = usize::from(ECC_CODEWORDS_PER_BLOCK[level][version]);
+	let short_blocks = block_count - codewords.len() % block_count;
+	let short_len = codewords.len() / block_count;
+	let short_data_len = short_len.checked_sub(ecc_len)?;
+	let mut blocks = vec![Vec::with_capacity(short_len + 1); block_count];
+	let mut next = codewords.iter().copied();
+
+	// Short blocks are one data codeword shorter; that slot is skipped while interleaving.
+	for i in 0..=short_len {
+		for (j, block) in blocks.iter_mut().enumerate() {
+			if i != short_data_len || j >= short_blocks {
+				block.push(next.next()?);
+			}
+		}
+	}
+
+	let mut data = Vec::new();
+
+	for mut block in blocks {
+		correct_errors(&mut block, ecc_len)?;
+
+		data.extend_from_slice(&block[..block.len() - ecc_len]);
+	}
+
+	Some(data)
+}
+
+/// Corrects up to `ecc_len / 2` byte errors in place; `None` when the block is unrecoverable.
+fn correct_errors(block: &mut [u8], ecc_len: usize) -> Option<()> {
+	let syndromes = (0..ecc_len).map(|j| evaluate(block, GF.exp[j])).collect::<Vec<_>>();
+
+	if syndromes.iter().all(|&syndrome| syndrome == 0) {
+		return Some(());
+	}
+
+	// Berlekamp-Massey for the error locator, lowest degree first.
+	let mut locator = vec![1_u8];
+	let mut previous = vec![1_u8];
+	let mut errors = 0;
+	let mut shift = 1;
+	let mut last_discrepancy = 1_u8;
+
+	for n in 0..ecc_len {
+		let mut discrepancy = syndromes[n];
+
+		for i in 1..=errors.min(locator.len() - 1) {
+			discrepancy ^= GF.mul(locator[i], syndromes[n - i]);
+		}
+
+		if discrepancy == 0 {
+			shift += 1;
+
+			continue;
+		}
+
+		let scale = GF.div(discrepancy, last_discrepancy);
+		let snapshot = locator.clone();
+
+		locator.resize(locator.len().max(previous.len() + shift), 0);
+
+		for (i, &coefficient) in previous.iter().enumerate() {
+			locator[i + shift] ^= GF.mul(scale, coefficient);
+		}
+
+		if 2 * errors <= n {
+			errors = n + 1 - errors;
+			previous = snapshot;
+			last_discrepancy = discrepancy;
+			shift = 1;
+		} else {
+			shift += 1;
+		}
+	}
+
+	if errors * 2 > ecc_len {
+		return None;
+	}
+
+	// Chien search: the byte at index `k` holds the coefficient of x^(len - 1 - k).
+	let len = block.len();
+	let positions = (0..len)
+		.filter(|&power| {
+			let inverse = GF.exp[(255 - power % 255) % 255];
+
+			evaluate_low_first(&locator, inverse) == 0
+		})
+		.collect::<Vec<_>>();
+
+	if positions.len() != errors {
+		return None;
+	}
+
+	// Forney with the first consecutive root at alpha^0.
+	let mut evaluator = vec![0_u8; ecc_len];
+
+	for (i, &syndrome) in syndromes.iter().enumerate() {
+		for (j, &coefficient) in locator.iter().enumerate() {
+			if i + j < ecc_len {
+				evaluator[i + j] ^= GF.mul(syndrome, coefficient);
+			}
+		}
+	}
+
+	let derivative = locator
+		.iter()
+		.enumerate()
+		.skip(1)
+		.map(|(i, &coefficient)| if i % 2 == 1 { coefficient } else { 0 })
+		.collect::<Vec<_>>();
+
+	for power in positions {
+		let location = GF.exp[power % 255];
+		let inverse = GF.exp[(255 - power % 255) % 255];
+		let denominator = evaluate_low_first(&derivative, inverse);
+
+		if denominator == 0 {
+			return None;
+		}
+
+		let magnitude =
+			GF.mul(location, GF.div(evaluate_low_first(&evaluator, inverse), denominator));
+
+		block[len - 1 - power] ^= magnitude;
+	}
+
+	(0..ecc_len).all(|j| evaluate(block, GF.exp[j]) == 0).then_some(())
+}
+
+/// Evaluates a polynomial stored highest degree first.
+fn evaluate(polynomial: &[u8], x: u8) -> u8 {
+	polynomial.iter().fold(0, |acc, &coefficient| GF.mul(acc, x) ^ coefficient)
+}
+
+/// Evaluates a polynomial stored lowest degree first.
+fn evaluate_low_first(polynomial: &[u8], x: u8) -> u8 {
+	polynomial.iter().rev().fold(0, |acc, &coefficient| GF.mul(acc, x) ^ coefficient)
+}
+
+/// Parses numeric, alphanumeric, byte and kanji segments; ECI designators are skipped.
+fn parse_segments(data: &[u8], version: usize) -> Option<String> {
+	let mut reader = BitReader { data, position: 0 };
+	let mut bytes = Vec::new();
+	let size_class = match version {
+		1..=9 => 0,
+		10..=26 => 1,
+		_ => 2,
+	};
+
+	while reader.remaining() >= 4 {
+		match reader.read(4)? {
+			0 => break,
+			1 => {
+				let mut count = reader.read([10, 12, 14][size_class])?;
+
+				while count >= 3 {
+					bytes.extend(format!("{:03}", reader.read(10)?).bytes());
+
+					count -= 3;
+				}
+
+				match count {
+					2 => bytes.extend(format!("{:02}", reader.read(7)?).bytes()),
+					1 => bytes.extend(format!("{}", reader.read(4)?).bytes()),
+					_ => {},
+				}
+			},
+			2 => {
+				let mut count = reader.read([9, 11, 13][size_class])?;
+
+				while count >= 2 {
+					let pair = reader.read(11)?;
+
+					bytes.push(*ALPHANUMERIC_CHARSET.get(pair / 45)?);
+					bytes.push(*ALPHANUMERIC_CHARSET.get(pair % 45)?);
+
+					count -= 2;
+				}
+
+				if count == 1 {
+					bytes.push(*ALPHANUMERIC_CHARSET.get(reader.read(6)?)?);
+				}
+			},
+			4 => {
+				let count = reader.read([8, 16, 16][size_class])?;
+
+				for _ in 0..count {
+					bytes.push(reader.read(8)? as u8);
+				}
+			},
+			7 => {
+				let first = reader.read(8)?;
+
+				if first & 0x80 != 0 {
+					reader.read(if first & 0x40 == 0 { 8 } else { 16 })?;
+				}
+			},
+			8 => {
+				// Shift JIS needs a mapping table; each kanji becomes a replacement character.
+				let count = reader.read([8, 10, 12][size_class])?;
+
+				for _ in 0..count {
+					reader.read(13)?;
+					bytes.extend("\u{FFFD}".bytes());
+				}
+			},
+			_ => return None,
+		}
+	}
+
+	// Byte mode defaults to ISO-8859-1, but almost every encoder writes UTF-8 in practice.
+	Some(match String::from_utf8(bytes) {
+		Ok(text) => text,
+		Err(err) => err.into_bytes().into_iter().map(char::from).collect(),
+	})
+}
+
+struct BitReader<'a> {
+	data: &'a [u8],
+	position: usize,
+}
+impl BitReader<'_> {
+	fn remaining(&self) -> usize {
+		self.data.len() * 8 - self.position
+	}
+
+	fn read(&mut self, bits: usize) -> Option<usize> {
+		if bits > self.remaining() {
+			return None;
+		}
+
+		let mut value = 0;
+
+		for _ in 0..bits {
+			let byte = self.data[self.position / 8];
+
+			value = (value << 1) | usize::from((byte >> (7 - self.position % 8)) & 1);
+
+			self.position += 1;
+		}
+
+		Some(value)
+	}
+}
+
+/// GF(256) with the QR reducing polynomial `x^8 + x^4 + x^3 + x^2 + 1`.
+struct GaloisField {
+	exp: [u8; 512],
+	log: [u8; 256],
+}
+impl GaloisField {
+	const fn new() -> Self {
+		let mut exp = [0_u8; 512];
+		let mut log = [0_u8; 256];
+		let mut value = 1_u16;
+		let mut i = 0;
+
+		while i < 255 {
+			exp[i] = value as u8;
+			exp[i + 255] = value as u8;
+			log[value as usize] = i as u8;
+			value <<= 1;
+
+			if value & 0x100 != 0 {
+				value ^= 0x11D;
+			}
+
+			i += 1;
+		}
+
+		Self { exp, log }
+	}
+
+	fn mul(&self, a: u8, b: u8) -> u8 {
+		if a == 0 || b == 0 {
+			return 0;
+		}
+
+		self.exp[usize::from(self.log[usize::from(a)]) + usize::from(self.log[usize::from(b)])]
+	}
+
+	fn div(&self, a: u8, b: u8) -> u8 {
+		if a == 0 {
+			return 0;
+		}
+
+		self.exp
+			[usize::from(self.log[usize::from(a)]) + 255 - usize::from(self.log[usize::from(b)])]
+	}
+}
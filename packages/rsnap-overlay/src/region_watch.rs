@@ -0,0 +1,240 @@
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Result, WrapErr};
+use image::RgbaImage;
+
+use crate::backend::{self, CaptureBackendKind};
+use crate::diff::{self, DiffOptions};
+use crate::external_command;
+use crate::png;
+use crate::state::{MonitorRect, RectPoints};
+
+const REGION_WATCH_MIN_INTERVAL_SECS: u32 = 1;
+const REGION_WATCH_ALERT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq)]
+/// Tunables for a [`RegionWatch`].
+pub struct RegionWatchConfig {
+	/// Seconds between samples of the watched region.
+	pub interval_secs: u32,
+	/// Per-pixel perceptual sensitivity passed to [`crate::diff_images`].
+	pub threshold: f32,
+	/// Share of changed pixels, from 0.0 to 1.0, that must be exceeded before an alert fires.
+	pub min_changed_ratio: f64,
+	/// Shell command run on each alert with the changed region as PNG on stdin.
+	pub alert_command: Option<String>,
+	/// Backend that samples the watched region.
+	pub capture_backend: CaptureBackendKind,
+}
+impl RegionWatchConfig {
+	fn interval(&self) -> Duration {
+		Duration::from_secs(u64::from(self.interval_secs.max(REGION_WATCH_MIN_INTERVAL_SECS)))
+	}
+}
+impl Default for RegionWatchConfig {
+	fn default() -> Self {
+		Self {
+			interval_secs: 5,
+			threshold: 0.1,
+			min_changed_ratio: 0.001,
+			alert_command: None,
+			capture_backend: CaptureBackendKind::Auto,
+		}
+	}
+}
+
+/// Samples a screen region on a background thread and alerts whenever its content drifts from
+/// the last alerted state by more than the configured ratio.
+pub struct RegionWatch {
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl RegionWatch {
+	/// Starts watching `rect_px`, given in monitor-local pixels. The first sample becomes the
+	/// baseline; each alert resets it, so a steady new value alerts only once.
+	pub fn start(
+		monitor: MonitorRect,
+		rect_px: RectPoints,
+		config: RegionWatchConfig,
+	) -> Result<Self> {
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let worker = thread::Builder::new()
+			.name(String::from("rsnap-region-watch"))
+			.spawn(move || region_watch_loop(monitor, rect_px, &config, &stop_rx))
+			.wrap_err("Failed to start the region watch thread")?;
+
+		tracing::info!(
+			op = "region_watch.start",
+			monitor_id = monitor.id,
+			x = rect_px.x,
+			y = rect_px.y,
+			width = rect_px.width,
+			height = rect_px.height,
+			"Region watch started."
+		);
+
+		Ok(Self { monitor, rect_px, stop_tx, worker: Some(worker) })
+	}
+
+	#[must_use]
+	/// Returns the watched monitor.
+	pub fn monitor(&self) -> MonitorRect {
+		self.monitor
+	}
+
+	#[must_use]
+	/// Returns the watched region in monitor-local pixels.
+	pub fn rect_px(&self) -> RectPoints {
+		self.rect_px
+	}
+}
+impl Drop for RegionWatch {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+
+		tracing::info!(op = "region_watch.stop", "Region watch stopped.");
+	}
+}
+
+fn region_watch_loop(
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	config: &RegionWatchConfig,
+	stop_rx: &Receiver<()>,
+) {
+	let interval = config.interval();
+	let mut backend = backend::capture_backend(config.capture_backend);
+	let mut baseline: Option<RgbaImage> = None;
+	let mut next_at = Instant::now();
+
+	loop {
+		match stop_rx.recv_timeout(next_at.saturating_duration_since(Instant::now())) {
+			Err(RecvTimeoutError::Timeout) => {},
+			Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+		}
+
+		next_at = (next_at + interval).max(Instant::now());
+
+		let sample = match backend.capture_monitor_region(monitor, rect_px) {
+			Ok(sample) => sample,
+			Err(err) => {
+				tracing::debug!(
+					op = "region_watch.capture",
+					error = %format!("{err:#}"),
+					"Region watch sample failed."
+				);
+
+				continue;
+			},
+		};
+		let Some(previous) = baseline.as_ref() else {
+			baseline = Some(sample);
+
+			continue;
+		};
+		let Some(changed_ratio) = region_change_ratio(previous, &sample, config) else {
+			continue;
+		};
+
+		tracing::info!(op = "region_watch.changed", changed_ratio, "Watched region changed.");
+
+		if let Some(command) = config.alert_command.as_deref() {
+			spawn_alert_command(command, &sample, changed_ratio, rect_px);
+		}
+
+		baseline = Some(sample);
+	}
+}
+
+/// Returns the changed-pixel ratio when `current` differs enough from `baseline` to alert.
+fn region_change_ratio(
+	baseline: &RgbaImage,
+	current: &RgbaImage,
+	config: &RegionWatchConfig,
+) -> Option<f64> {
+	let report = diff::diff_images(baseline, current, DiffOptions { threshold: config.threshold });
+	let ratio = report.differing_ratio();
+
+	(report.size_mismatch || ratio > config.min_changed_ratio).then_some(ratio)
+}
+
+/// Runs the alert hook on its own thread so a slow webhook never delays the next sample.
+fn spawn_alert_command(command: &str, sample: &RgbaImage, changed_ratio: f64, rect_px: RectPoints) {
+	let png_bytes = match png::rgba_image_to_png_bytes(sample) {
+		Ok(png_bytes) => png_bytes,
+		Err(err) => {
+			tracing::warn!(error = %format!("{err:#}"), "Failed to encode region watch alert.");
+
+			return;
+		},
+	};
+	let mut shell = external_command::shell_command(command);
+
+	shell.env("RSNAP_CHANGED_RATIO", format!("{changed_ratio:.6}")).env(
+		"RSNAP_WATCH_RECT",
+		format!("{},{},{},{}", rect_px.x, rect_px.y, rect_px.width, rect_px.height),
+	);
+
+	let spawned =
+		thread::Builder::new().name(String::from("rsnap-region-alert")).spawn(move || {
+			if let Err(err) = run_alert_command(shell, png_bytes) {
+				tracing::warn!(error = %format!("{err:#}"), "Region watch alert command failed.");
+			}
+		});
+
+	if let Err(err) = spawned {
+		tracing::warn!(error = %err, "Failed to start region watch alert thread.");
+	}
+}
+
+fn run_alert_command(shell: Command, png_bytes: Vec<u8>) -> Result<()> {
+	external_command::run_with_timeout(shell, Some(png_bytes), REGION_WATCH_ALERT_TIMEOUT)
+		.wrap_err("Region watch alert command failed")?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::region_watch::{self, RegionWatchConfig};
+
+	#[test]
+	fn region_change_ratio_ignores_noise_and_flags_real_changes() {
+		let config = RegionWatchConfig { min_changed_ratio: 0.05, ..RegionWatchConfig::default() };
+		let baseline = RgbaImage::from_pixel(10, 10, Rgba([240, 240, 240, 255]));
+		let mut noisy = baseline.clone();
+
+		noisy.put_pixel(0, 0, Rgba([241, 240, 240, 255]));
+
+		assert_eq!(region_watch::region_change_ratio(&baseline, &noisy, &config), None);
+
+		let mut one_digit = baseline.clone();
+
+		for x in 0..4 {
+			one_digit.put_pixel(x, 5, Rgba([0, 0, 0, 255]));
+		}
+
+		assert_eq!(region_watch::region_change_ratio(&baseline, &one_digit, &config), None);
+
+		for x in 0..4 {
+			one_digit.put_pixel(x, 6, Rgba([0, 0, 0, 255]));
+		}
+
+		assert_eq!(region_watch::region_change_ratio(&baseline, &one_digit, &config), Some(0.08));
+
+		let resized = RgbaImage::from_pixel(10, 11, Rgba([240, 240, 240, 255]));
+
+		assert!(region_watch::region_change_ratio(&baseline, &resized, &config).is_some());
+	}
+}
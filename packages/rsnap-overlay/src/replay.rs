@@ -0,0 +1,301 @@
+use std::collections::VecDeque;
+use std::sync::{
+	Arc, Mutex,
+	mpsc::{self, Receiver, RecvTimeoutError, Sender},
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+
+#[cfg(not(target_os = "macos"))]
+use crate::backend;
+use crate::backend::CaptureBackendKind;
+#[cfg(target_os = "macos")]
+use crate::live_frame_stream_macos::MacLiveFrameStream;
+use crate::overlay::OverlaySession;
+use crate::png;
+use crate::state::MonitorRect;
+
+const REPLAY_MAX_FPS: u32 = 30;
+const REPLAY_MIN_WIDTH_PX: u32 = 160;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Tunables for the instant replay ring buffer.
+pub struct ReplayConfig {
+	/// Frames captured per second.
+	pub fps: u32,
+	/// Frames wider than this are downscaled before they are buffered.
+	pub max_width_px: u32,
+	/// Seconds of history kept in memory.
+	pub history_secs: u32,
+	/// Upper bound for buffered pixel data; the oldest frames are dropped first.
+	pub memory_budget_mb: u32,
+	/// Backend that captures the buffered frames.
+	pub capture_backend: CaptureBackendKind,
+}
+impl ReplayConfig {
+	fn frame_interval(self) -> Duration {
+		Duration::from_secs(1) / self.fps.clamp(1, REPLAY_MAX_FPS)
+	}
+}
+impl Default for ReplayConfig {
+	fn default() -> Self {
+		Self {
+			fps: 5,
+			max_width_px: 960,
+			history_secs: 15,
+			memory_budget_mb: 256,
+			capture_backend: CaptureBackendKind::Auto,
+		}
+	}
+}
+
+/// Keeps the last few seconds of the main display in memory so they can be exported as an
+/// animated PNG after the fact.
+pub struct ReplayBuffer {
+	frames: Arc<Mutex<ReplayFrames>>,
+	frame_interval: Duration,
+	stop_tx: Sender<()>,
+	worker: Option<JoinHandle<()>>,
+}
+impl ReplayBuffer {
+	/// Starts buffering the main display on a background thread. Call this from the main thread:
+	/// macOS only enumerates displays there.
+	pub fn start(config: ReplayConfig) -> Result<Self> {
+		let monitor = OverlaySession::available_overlay_monitors()
+			.map_err(|err| eyre::eyre!(err))?
+			.into_iter()
+			.next()
+			.ok_or_else(|| eyre::eyre!("No display available for the replay buffer"))?;
+		let frames = Arc::new(Mutex::new(ReplayFrames::new(config)));
+		let (stop_tx, stop_rx) = mpsc::channel();
+		let worker_frames = Arc::clone(&frames);
+		let worker = thread::Builder::new()
+			.name(String::from("rsnap-replay"))
+			.spawn(move || replay_capture_loop(monitor, config, &worker_frames, &stop_rx))
+			.wrap_err("Failed to start the replay buffer thread")?;
+
+		tracing::info!(
+			op = "replay.start",
+			monitor_id = monitor.id,
+			fps = config.fps,
+			history_secs = config.history_secs,
+			memory_budget_mb = config.memory_budget_mb,
+			"Replay buffer started."
+		);
+
+		Ok(Self { frames, frame_interval: config.frame_interval(), stop_tx, worker: Some(worker) })
+	}
+
+	/// Takes the buffered frames from the last `seconds` without copying pixels, so encoding can
+	/// happen off the caller's thread while the buffer keeps recording.
+	pub fn clip(&self, seconds: u32) -> Result<ReplayClip> {
+		let frames = self
+			.frames
+			.lock()
+			.map_err(|_| eyre::eyre!("Replay buffer lock poisoned"))?
+			.recent(Duration::from_secs(u64::from(seconds)), Instant::now());
+
+		if frames.is_empty() {
+			return Err(eyre::eyre!("Replay buffer has no frames yet"));
+		}
+
+		Ok(ReplayClip { frames, frame_interval: self.frame_interval })
+	}
+}
+impl Drop for ReplayBuffer {
+	fn drop(&mut self) {
+		let _ = self.stop_tx.send(());
+
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+	}
+}
+
+/// A fixed run of replay frames taken from a [`ReplayBuffer`].
+pub struct ReplayClip {
+	frames: Vec<ReplayFrame>,
+	frame_interval: Duration,
+}
+impl ReplayClip {
+	/// Encodes the clip as a looping animated PNG that keeps the original capture timing.
+	pub fn encode_apng(&self) -> Result<Vec<u8>> {
+		let timed = self
+			.frames
+			.iter()
+			.enumerate()
+			.map(|(index, frame)| {
+				let delay = self
+					.frames
+					.get(index + 1)
+					.map(|next| next.captured_at.duration_since(frame.captured_at))
+					.unwrap_or(self.frame_interval);
+
+				(frame.image.as_ref(), delay)
+			})
+			.collect::<Vec<_>>();
+
+		png::rgba_frames_to_apng_bytes(&timed)
+	}
+}
+#[derive(Clone, Debug)]
+struct ReplayFrame {
+	captured_at: Instant,
+	image: Arc<RgbaImage>,
+}
+
+/// Time- and memory-bounded frame history, oldest first.
+#[derive(Debug)]
+struct ReplayFrames {
+	frames: VecDeque<ReplayFrame>,
+	bytes: usize,
+	history: Duration,
+	budget_bytes: usize,
+}
+impl ReplayFrames {
+	fn new(config: ReplayConfig) -> Self {
+		Self {
+			frames: VecDeque::new(),
+			bytes: 0,
+			history: Duration::from_secs(u64::from(config.history_secs)),
+			budget_bytes: (config.memory_budget_mb as usize).saturating_mul(1_024 * 1_024),
+		}
+	}
+
+	fn push(&mut self, captured_at: Instant, image: RgbaImage) {
+		self.bytes = self.bytes.saturating_add(image.as_raw().len());
+
+		self.frames.push_back(ReplayFrame { captured_at, image: Arc::new(image) });
+
+		// The newest frame always stays, even when it alone exceeds the budget.
+		while self.frames.len() > 1
+			&& self.frames.front().is_some_and(|oldest| {
+				captured_at.duration_since(oldest.captured_at) > self.history
+					|| self.bytes > self.budget_bytes
+			}) {
+			if let Some(oldest) = self.frames.pop_front() {
+				self.bytes = self.bytes.saturating_sub(oldest.image.as_raw().len());
+			}
+		}
+	}
+
+	/// Frames captured within `window` of `now` that match the newest frame's size, since a
+	/// resolution change mid-buffer cannot share one animation canvas.
+	fn recent(&self, window: Duration, now: Instant) -> Vec<ReplayFrame> {
+		let Some(newest) = self.frames.back() else {
+			return Vec::new();
+		};
+		let dimensions = newest.image.dimensions();
+
+		self.frames
+			.iter()
+			.filter(|frame| now.saturating_duration_since(frame.captured_at) <= window)
+			.filter(|frame| frame.image.dimensions() == dimensions)
+			.cloned()
+			.collect()
+	}
+}
+
+fn replay_capture_loop(
+	monitor: MonitorRect,
+	config: ReplayConfig,
+	frames: &Mutex<ReplayFrames>,
+	stop_rx: &Receiver<()>,
+) {
+	let interval = config.frame_interval();
+	let max_width_px = config.max_width_px.max(REPLAY_MIN_WIDTH_PX);
+	#[cfg(target_os = "macos")]
+	let mut stream =
+		(config.capture_backend.resolve() == CaptureBackendKind::Sck).then(MacLiveFrameStream::new);
+	#[cfg(not(target_os = "macos"))]
+	let mut backend = backend::capture_backend(config.capture_backend);
+	let mut next_at = Instant::now();
+
+	loop {
+		match stop_rx.recv_timeout(next_at.saturating_duration_since(Instant::now())) {
+			Err(RecvTimeoutError::Timeout) => {},
+			Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+		}
+
+		let now = Instant::now();
+
+		next_at = (next_at + interval).max(now);
+
+		#[cfg(target_os = "macos")]
+		let frame = stream
+			.as_mut()
+			.and_then(|stream| stream.latest_rgba_snapshot(monitor))
+			.map(|snapshot| downscale_replay_frame(&snapshot.image, max_width_px));
+		#[cfg(not(target_os = "macos"))]
+		let frame = match backend.capture_monitor(monitor) {
+			Ok(image) => Some(downscale_replay_frame(&image, max_width_px)),
+			Err(err) => {
+				tracing::debug!(
+					op = "replay.capture",
+					error = %format!("{err:#}"),
+					"Replay frame capture failed."
+				);
+
+				None
+			},
+		};
+
+		if let Some(frame) = frame
+			&& let Ok(mut frames) = frames.lock()
+		{
+			frames.push(now, frame);
+		}
+	}
+}
+
+fn downscale_replay_frame(image: &RgbaImage, max_width_px: u32) -> RgbaImage {
+	if image.width() <= max_width_px {
+		return image.clone();
+	}
+
+	let height = (u64::from(image.height()) * u64::from(max_width_px) / u64::from(image.width()))
+		.max(1) as u32;
+
+	imageops::resize(image, max_width_px, height, FilterType::Triangle)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use image::{Rgba, RgbaImage};
+
+	use crate::replay::{ReplayConfig, ReplayFrames};
+
+	#[test]
+	fn replay_frames_evict_by_age_and_memory_budget() {
+		let config =
+			ReplayConfig { history_secs: 2, memory_budget_mb: 1, ..ReplayConfig::default() };
+		let mut frames = ReplayFrames::new(config);
+		let start = Instant::now();
+		// 256×256 RGBA is a quarter of the 1 MiB budget.
+		let frame = RgbaImage::from_pixel(256, 256, Rgba([0, 0, 0, 255]));
+
+		for second in 0..4 {
+			frames.push(start + Duration::from_secs(second), frame.clone());
+		}
+
+		assert_eq!(frames.frames.len(), 3);
+
+		for tick in 0..6 {
+			frames.push(start + Duration::from_millis(4_000 + tick * 100), frame.clone());
+		}
+
+		assert_eq!(frames.frames.len(), 4);
+		assert_eq!(frames.bytes, 4 * 256 * 256 * 4);
+
+		let recent =
+			frames.recent(Duration::from_millis(200), start + Duration::from_millis(4_500));
+
+		assert_eq!(recent.len(), 3);
+	}
+}
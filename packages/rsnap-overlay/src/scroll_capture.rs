@@ -1625,6 +1625,11 @@ impl ScrollSession {
 		self.export_image.dimensions()
 	}
 
+	/// Frames merged into the stitched image after the base frame, net of undone ones.
+	pub(crate) fn merged_frames(&self) -> usize {
+		self.growth_history.len()
+	}
+
 	pub(crate) fn undo_last_append(&mut self) -> bool {
 		let Some(_commit) = self.growth_history.pop() else {
 			return false;
@@ -0,0 +1,56 @@
+//! The native macOS share menu (Messages, AirDrop, Mail, …) for a finished capture.
+
+use std::path::Path;
+
+use color_eyre::eyre::{self, Result};
+use objc2::AnyThread;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::{NSSharingServicePicker, NSView};
+use objc2_foundation::{NSArray, NSPoint, NSRect, NSRectEdge, NSSize, NSString, NSURL};
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+/// Where the share menu points at inside the anchoring window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ShareAnchor {
+	/// The whole window, for small windows such as the frozen toolbar.
+	Window,
+	/// The middle of the window, for full-screen overlay windows.
+	Center,
+}
+
+/// Shows `NSSharingServicePicker` for the file at `path`, anchored in `window`.
+///
+/// The picker's menu tracks modally, so by the time this returns the user has either picked a
+/// service, which keeps its own reference to the file and carries on after the overlay closes,
+/// or dismissed the menu. Must run on the main thread.
+pub(crate) fn show_share_picker(window: &Window, anchor: ShareAnchor, path: &Path) -> Result<()> {
+	let handle = window
+		.window_handle()
+		.map_err(|err| eyre::eyre!("No window to anchor the share menu to: {err}"))?;
+	let RawWindowHandle::AppKit(appkit) = handle.as_raw() else {
+		return Err(eyre::eyre!("The share menu needs an AppKit window"));
+	};
+	// SAFETY: `ns_view` comes from a live winit window, which outlives this call, and the view
+	// is only messaged on the main thread.
+	let view = unsafe { appkit.ns_view.cast::<NSView>().as_ref() };
+	let bounds = view.bounds();
+	let rect = match anchor {
+		ShareAnchor::Window => bounds,
+		ShareAnchor::Center => NSRect::new(
+			NSPoint::new(bounds.mid().x - 1.0, bounds.mid().y - 1.0),
+			NSSize::new(2.0, 2.0),
+		),
+	};
+	let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+	let items = NSArray::<AnyObject>::from_slice(&[AsRef::<AnyObject>::as_ref(&*url)]);
+	// SAFETY: the items are file URLs, which conform to `NSPasteboardWriting`.
+	let picker =
+		unsafe { NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &items) };
+
+	picker.showRelativeToRect_ofView_preferredEdge(rect, view, NSRectEdge::MinY);
+
+	tracing::info!(op = "overlay.share", path = %path.display(), "Showed the share menu.");
+
+	Ok(())
+}
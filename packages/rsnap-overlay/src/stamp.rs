@@ -0,0 +1,200 @@
+use ab_glyph::{Font, FontRef, PxScale, point};
+use egui_phosphor::{Variant, fill};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::watermark;
+
+/// Side of a newly placed stamp, in pixels of the selection image.
+pub(crate) const STAMP_DEFAULT_SIZE_PX: u32 = 64;
+
+/// A stamp from the frozen toolbar's picker: a monochrome emoji or a filled sticker icon.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StampKind {
+	#[default]
+	Arrow,
+	Check,
+	Cross,
+	ThumbsUp,
+	Heart,
+	Star,
+	Fire,
+}
+impl StampKind {
+	pub(crate) const ALL: [Self; 7] = [
+		Self::Arrow,
+		Self::Check,
+		Self::Cross,
+		Self::ThumbsUp,
+		Self::Heart,
+		Self::Star,
+		Self::Fire,
+	];
+
+	pub(crate) fn label(self) -> &'static str {
+		crate::i18n::tr(match self {
+			Self::Arrow => "stamp.arrow",
+			Self::Check => "stamp.check",
+			Self::Cross => "stamp.cross",
+			Self::ThumbsUp => "stamp.thumbs_up",
+			Self::Heart => "stamp.heart",
+			Self::Star => "stamp.star",
+			Self::Fire => "stamp.fire",
+		})
+	}
+
+	/// Stable name used in the annotation sidecar.
+	pub(crate) const fn id(self) -> &'static str {
+		match self {
+			Self::Arrow => "arrow",
+			Self::Check => "check",
+			Self::Cross => "cross",
+			Self::ThumbsUp => "thumbs_up",
+			Self::Heart => "heart",
+			Self::Star => "star",
+			Self::Fire => "fire",
+		}
+	}
+
+	/// The picker cycles through [`Self::ALL`].
+	pub(crate) fn next(self) -> Self {
+		let index = Self::ALL.iter().position(|kind| *kind == self).unwrap_or(0);
+
+		Self::ALL[(index + 1) % Self::ALL.len()]
+	}
+
+	pub(crate) const fn glyph(self) -> &'static str {
+		match self {
+			Self::Arrow => fill::ARROW_FAT_RIGHT,
+			Self::Check => fill::CHECK_CIRCLE,
+			Self::Cross => fill::X_CIRCLE,
+			Self::ThumbsUp => "\u{1F44D}",
+			Self::Heart => "\u{2764}",
+			Self::Star => "\u{2B50}",
+			Self::Fire => "\u{1F525}",
+		}
+	}
+
+	const fn rgb(self) -> [u8; 3] {
+		match self {
+			Self::Arrow | Self::Cross => [255, 59, 48],
+			Self::Check => [52, 199, 89],
+			Self::ThumbsUp | Self::Star => [255, 204, 0],
+			Self::Heart => [255, 45, 85],
+			Self::Fire => [255, 149, 0],
+		}
+	}
+
+	fn font_bytes(self) -> &'static [u8] {
+		match self {
+			Self::Arrow | Self::Check | Self::Cross => Variant::Fill.font_bytes(),
+			_ => epaint_default_fonts::NOTO_EMOJI_REGULAR,
+		}
+	}
+}
+
+/// A stamp on the frozen selection, positioned in pixels of the selection image like the
+/// auto-redaction proposals.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct PlacedStamp {
+	pub(crate) kind: StampKind,
+	pub(crate) center_px: (u32, u32),
+	pub(crate) size_px: u32,
+}
+impl PlacedStamp {
+	pub(crate) fn contains(&self, (x, y): (u32, u32)) -> bool {
+		let half = i64::from(self.size_px / 2);
+		let (center_x, center_y) = (i64::from(self.center_px.0), i64::from(self.center_px.1));
+
+		(i64::from(x) - center_x).abs() <= half && (i64::from(y) - center_y).abs() <= half
+	}
+}
+
+/// Rasterizes `kind` so its glyph fits a `size_px` square, with a soft shadow for contrast.
+pub(crate) fn render_stamp(kind: StampKind, size_px: u32) -> Option<RgbaImage> {
+	let font = FontRef::try_from_slice(kind.font_bytes()).ok()?;
+	let id = font.glyph_id(kind.glyph().chars().next()?);
+	let reference = font.outline_glyph(id.with_scale(PxScale::from(size_px as f32)))?;
+	let bounds = reference.px_bounds();
+	let fit = size_px as f32 / bounds.width().max(bounds.height()).max(1.0);
+	let outlined =
+		font.outline_glyph(id.with_scale_and_position(size_px as f32 * fit, point(0.0, 0.0)))?;
+	let bounds = outlined.px_bounds();
+	let shadow_offset = (size_px / 24).max(1);
+	let width = bounds.width().ceil() as u32 + shadow_offset;
+	let height = bounds.height().ceil() as u32 + shadow_offset;
+	let mut coverage = vec![0.0_f32; (width * height) as usize];
+
+	outlined.draw(|x, y, value| {
+		if x < width && y < height {
+			coverage[(y * width + x) as usize] = value.min(1.0);
+		}
+	});
+
+	let rgb = kind.rgb();
+	let mut stamp = RgbaImage::new(width, height);
+
+	for y in 0..height {
+		for x in 0..width {
+			let glyph_alpha = coverage[(y * width + x) as usize];
+			let shadow_alpha = match (x.checked_sub(shadow_offset), y.checked_sub(shadow_offset)) {
+				(Some(sx), Some(sy)) => coverage[(sy * width + sx) as usize] * 0.45,
+				_ => 0.0,
+			};
+			let mut pixel = Rgba([0, 0, 0, (shadow_alpha * 255.0).round() as u8]);
+
+			watermark::blend_pixel(&mut pixel, Rgba([rgb[0], rgb[1], rgb[2], 255]), glyph_alpha);
+
+			stamp.put_pixel(x, y, pixel);
+		}
+	}
+
+	Some(stamp)
+}
+
+/// Composites `stamps` onto the cropped export in placement order.
+pub(crate) fn apply_stamps(image: &mut RgbaImage, stamps: &[PlacedStamp]) {
+	for stamp in stamps {
+		let Some(rendered) = render_stamp(stamp.kind, stamp.size_px) else {
+			continue;
+		};
+		let x = i64::from(stamp.center_px.0) - i64::from(rendered.width() / 2);
+		let y = i64::from(stamp.center_px.1) - i64::from(rendered.height() / 2);
+
+		watermark::blend_onto(image, &rendered, x, y, 1.0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::annotation::AnnotationLayer;
+	use crate::stamp::{self, StampKind};
+
+	#[test]
+	fn every_stamp_has_a_glyph_in_its_font() {
+		for kind in StampKind::ALL {
+			let rendered = stamp::render_stamp(kind, 48).expect("stamp glyph");
+
+			let longest_side = rendered.width().max(rendered.height());
+
+			assert!((44..=52).contains(&longest_side), "{kind:?}: {longest_side}");
+		}
+	}
+
+	#[test]
+	fn stamps_are_composited_around_their_center() {
+		let background = Rgba([10, 20, 30, 255]);
+		let mut image = RgbaImage::from_pixel(200, 200, background);
+		let mut layer = AnnotationLayer::default();
+
+		layer.place_stamp(StampKind::Check, (50, 60));
+		stamp::apply_stamps(&mut image, &layer.stamps);
+
+		assert_ne!(*image.get_pixel(50, 60), background);
+		assert_eq!(*image.get_pixel(150, 150), background);
+		assert_eq!(*image.get_pixel(10, 60), background);
+	}
+}
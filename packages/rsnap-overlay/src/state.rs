@@ -1,13 +1,30 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
 use image::RgbaImage;
 
+use crate::annotation::AnnotationLayer;
+use crate::color_vision::ColorVisionFilter;
+use crate::overlay::{CompositionGuides, HudUnit, LOUPE_CELL_POINTS};
+use crate::recognition::DecodedCode;
+use crate::redact::RedactionProposal;
+
 #[derive(Debug)]
 pub(crate) struct LoupeSample {
 	pub center: GlobalPoint,
 	pub patch: RgbaImage,
 }
+impl LoupeSample {
+	/// Returns the color `offset` pixels from the patch's center pixel.
+	pub(crate) fn rgb_at(&self, offset: (i32, i32)) -> Option<Rgb> {
+		let x = (self.patch.width() / 2).checked_add_signed(offset.0)?;
+		let y = (self.patch.height() / 2).checked_add_signed(offset.1)?;
+		let pixel = self.patch.get_pixel_checked(x, y)?;
+
+		Some(Rgb::new(pixel.0[0], pixel.0[1], pixel.0[2]))
+	}
+}
 
 #[derive(Debug)]
 /// Cached full-monitor frame used for RGB and loupe sampling.
@@ -44,6 +61,15 @@ pub struct WindowRect {
 	pub height: i64,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// Who owns a window and what it is called, shown while hovering it.
+pub struct WindowLabel {
+	/// Display name of the owning application; empty when unknown.
+	pub app_name: String,
+	/// Window title; empty when the window has none or the platform withholds it.
+	pub title: String,
+}
+
 #[derive(Debug)]
 /// Cached window-list snapshot used for live hit testing.
 pub struct WindowListSnapshot {
@@ -51,6 +77,8 @@ pub struct WindowListSnapshot {
 	pub captured_at: Instant,
 	/// Windows ordered for hit testing.
 	pub windows: Arc<Vec<WindowRect>>,
+	/// Owner and title by window identifier, where the platform reports them.
+	pub labels: Arc<HashMap<u32, WindowLabel>>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -124,6 +152,14 @@ impl GlobalPoint {
 	}
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// A color locked with `L` for comparison against the live readout.
+pub struct ReferenceSample {
+	/// Where the color was sampled, in global points.
+	pub point: GlobalPoint,
+	pub rgb: Rgb,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 /// RGB color sample without alpha.
 pub struct Rgb {
@@ -148,6 +184,152 @@ impl Rgb {
 	}
 }
 
+/// Per-channel display transfer (gamma) table reported by the window system.
+///
+/// Screen capture reads pixels before this table is applied, so a sample is the value apps drew
+/// (the "true" design color), while mapping it through the table gives what the panel emits
+/// under adjustments such as f.lux.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DisplayTransfer {
+	pub red: Vec<f32>,
+	pub green: Vec<f32>,
+	pub blue: Vec<f32>,
+}
+impl DisplayTransfer {
+	/// Tolerance of half an 8-bit step, so rounding noise in stock tables is not an adjustment.
+	const IDENTITY_TOLERANCE: f32 = 0.5 / 255.0;
+
+	pub(crate) fn is_identity(&self) -> bool {
+		[&self.red, &self.green, &self.blue].into_iter().all(|table| {
+			let last = table.len().saturating_sub(1).max(1) as f32;
+
+			table.iter().enumerate().all(|(index, value)| {
+				(value - index as f32 / last).abs() <= Self::IDENTITY_TOLERANCE
+			})
+		})
+	}
+
+	/// Maps a sampled color to the value shown on screen under this table.
+	pub(crate) fn apply(&self, rgb: Rgb) -> Rgb {
+		Rgb::new(
+			Self::map_channel(&self.red, rgb.r),
+			Self::map_channel(&self.green, rgb.g),
+			Self::map_channel(&self.blue, rgb.b),
+		)
+	}
+
+	fn map_channel(table: &[f32], value: u8) -> u8 {
+		let Some(&last_value) = table.last() else {
+			return value;
+		};
+		let position = f32::from(value) / 255.0 * (table.len() - 1) as f32;
+		let index = position.floor() as usize;
+		let output = match table.get(index + 1) {
+			Some(&next) => {
+				let fraction = position - index as f32;
+
+				table[index] + (next - table[index]) * fraction
+			},
+			None => last_value,
+		};
+
+		(output.clamp(0.0, 1.0) * 255.0).round() as u8
+	}
+}
+
+/// RGB and luminance histogram of a frozen selection, computed on the overlay worker.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Histogram {
+	pub red: [u32; 256],
+	pub green: [u32; 256],
+	pub blue: [u32; 256],
+	/// Rec. 709 luma of each pixel.
+	pub luma: [u32; 256],
+	pub pixel_count: u32,
+}
+impl Histogram {
+	pub(crate) fn from_image(image: &RgbaImage) -> Self {
+		let mut histogram =
+			Self { red: [0; 256], green: [0; 256], blue: [0; 256], luma: [0; 256], pixel_count: 0 };
+
+		for pixel in image.pixels() {
+			let [r, g, b, _] = pixel.0;
+			let luma =
+				(2_126 * u32::from(r) + 7_152 * u32::from(g) + 722 * u32::from(b) + 5_000) / 10_000;
+
+			histogram.red[usize::from(r)] += 1;
+			histogram.green[usize::from(g)] += 1;
+			histogram.blue[usize::from(b)] += 1;
+			histogram.luma[luma as usize] += 1;
+			histogram.pixel_count += 1;
+		}
+
+		histogram
+	}
+
+	/// Tallest bin across all channels, used to scale the plot.
+	pub(crate) fn peak(&self) -> u32 {
+		[&self.red, &self.green, &self.blue, &self.luma]
+			.into_iter()
+			.flat_map(|bins| bins.iter().copied())
+			.max()
+			.unwrap_or(0)
+	}
+
+	/// Percent of pixels whose luma is pure black and pure white, respectively.
+	pub(crate) fn clipped_percent(&self) -> (f32, f32) {
+		if self.pixel_count == 0 {
+			return (0.0, 0.0);
+		}
+
+		let total = self.pixel_count as f32;
+
+		(self.luma[0] as f32 * 100.0 / total, self.luma[255] as f32 * 100.0 / total)
+	}
+}
+
+/// Pointer sprite composited into frozen captures when cursor inclusion is enabled.
+#[derive(Clone, Debug)]
+pub(crate) struct CursorImage {
+	/// Straight-alpha sprite pixels.
+	pub image: RgbaImage,
+	/// Hotspot offset from the sprite's top-left corner, in points.
+	pub hotspot_x: f32,
+	pub hotspot_y: f32,
+	/// Sprite pixels per point.
+	pub scale_factor: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+/// Text format used when a sampled color is copied.
+pub(crate) enum ColorFormat {
+	#[default]
+	Hex,
+	Rgb,
+}
+impl ColorFormat {
+	pub(crate) const fn next(self) -> Self {
+		match self {
+			Self::Hex => Self::Rgb,
+			Self::Rgb => Self::Hex,
+		}
+	}
+
+	pub(crate) const fn label(self) -> &'static str {
+		match self {
+			Self::Hex => "HEX",
+			Self::Rgb => "RGB",
+		}
+	}
+
+	pub(crate) fn format(self, rgb: Rgb) -> String {
+		match self {
+			Self::Hex => rgb.hex_upper(),
+			Self::Rgb => format!("rgb({}, {}, {})", rgb.r, rgb.g, rgb.b),
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 /// Monitor bounds and scale factor in global desktop space.
 pub struct MonitorRect {
@@ -169,6 +351,80 @@ impl MonitorRect {
 		(self.scale_factor_x1000 as f32) / 1_000.0
 	}
 
+	#[must_use]
+	/// Returns the monitor origin scaled by this monitor's own scale factor.
+	///
+	/// This is not a shared pixel frame: on mixed-DPI desktops each monitor scales its origin by a
+	/// different factor, so neighbouring monitors may overlap or leave gaps in this space.
+	pub fn scaled_origin_px(&self) -> (i64, i64) {
+		let sf = f64::from(self.scale_factor());
+
+		(
+			(f64::from(self.origin.x) * sf).round() as i64,
+			(f64::from(self.origin.y) * sf).round() as i64,
+		)
+	}
+
+	#[must_use]
+	/// Returns the monitor size in physical pixels.
+	pub fn size_px(&self) -> (u32, u32) {
+		(self.points_to_pixels(self.width), self.points_to_pixels(self.height))
+	}
+
+	#[must_use]
+	/// Converts a monitor-local point offset into physical pixels.
+	pub fn points_to_pixels(&self, points: u32) -> u32 {
+		(f64::from(points) * f64::from(self.scale_factor())).round() as u32
+	}
+
+	#[must_use]
+	/// Converts a monitor-local physical position, such as a window event position, into a
+	/// global point clamped to the monitor bounds.
+	pub fn global_from_local_pixels(&self, x_px: f64, y_px: f64) -> GlobalPoint {
+		let sf = f64::from(self.scale_factor());
+		let max_x = i64::from(self.width.saturating_sub(1));
+		let max_y = i64::from(self.height.saturating_sub(1));
+		let local_x = ((x_px / sf).round() as i64).clamp(0, max_x);
+		let local_y = ((y_px / sf).round() as i64).clamp(0, max_y);
+
+		GlobalPoint::new(
+			self.origin.x.saturating_add(local_x as i32),
+			self.origin.y.saturating_add(local_y as i32),
+		)
+	}
+
+	#[must_use]
+	/// Converts a point in this monitor's scaled space (see [`Self::scaled_origin_px`]) into a
+	/// global point when it lies on this monitor.
+	pub fn global_from_scaled_pixels(&self, x_px: i64, y_px: i64) -> Option<GlobalPoint> {
+		let (origin_x, origin_y) = self.scaled_origin_px();
+		let (width_px, height_px) = self.size_px();
+		let local_x = x_px.checked_sub(origin_x)?;
+		let local_y = y_px.checked_sub(origin_y)?;
+
+		if local_x < 0
+			|| local_y < 0
+			|| local_x >= i64::from(width_px)
+			|| local_y >= i64::from(height_px)
+		{
+			return None;
+		}
+
+		Some(self.global_from_local_pixels(local_x as f64, local_y as f64))
+	}
+
+	#[must_use]
+	/// Clamps a global point onto the nearest point inside the monitor bounds.
+	pub fn clamp_global(&self, point: GlobalPoint) -> GlobalPoint {
+		let right = self.origin.x.saturating_add_unsigned(self.width.saturating_sub(1));
+		let bottom = self.origin.y.saturating_add_unsigned(self.height.saturating_sub(1));
+
+		GlobalPoint::new(
+			point.x.clamp(self.origin.x, right.max(self.origin.x)),
+			point.y.clamp(self.origin.y, bottom.max(self.origin.y)),
+		)
+	}
+
 	#[must_use]
 	/// Returns `true` when the global point lies inside the monitor bounds.
 	pub fn contains(&self, point: GlobalPoint) -> bool {
@@ -274,8 +530,17 @@ impl MonitorRect {
 
 	#[must_use]
 	/// Converts a monitor-local point rectangle into pixel coordinates.
+	///
+	/// Edges are rounded rather than the size, so adjacent rects stay adjacent at fractional
+	/// scale factors and the result never extends past the monitor's pixel bounds.
 	pub fn local_rect_to_pixels(&self, rect: RectPoints) -> RectPoints {
-		rect.scaled(self.scale_factor())
+		let (width_px, height_px) = self.size_px();
+		let left = self.points_to_pixels(rect.x).min(width_px);
+		let top = self.points_to_pixels(rect.y).min(height_px);
+		let right = self.points_to_pixels(rect.x.saturating_add(rect.width)).min(width_px);
+		let bottom = self.points_to_pixels(rect.y.saturating_add(rect.height)).min(height_px);
+
+		RectPoints::new(left, top, right - left, bottom - top)
 	}
 }
 
@@ -288,6 +553,76 @@ pub struct MonitorRectPoints {
 	pub rect: RectPoints,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Zoom and pan of the frozen preview. Annotation input and drawing go through it; the export
+/// stays at native resolution.
+pub struct FrozenView {
+	/// Magnification, from 1 (unzoomed) to [`Self::MAX_ZOOM`].
+	pub zoom: f32,
+	/// Monitor-local point shown at the window's top-left corner.
+	pub origin: (f32, f32),
+}
+impl FrozenView {
+	pub const MAX_ZOOM: f32 = 16.0;
+
+	#[must_use]
+	pub fn is_zoomed(&self) -> bool {
+		self.zoom > 1.0
+	}
+
+	#[must_use]
+	/// Maps a window-local point to the monitor-local point drawn under it.
+	pub fn image_point(&self, x: f32, y: f32) -> (f32, f32) {
+		(self.origin.0 + x / self.zoom, self.origin.1 + y / self.zoom)
+	}
+
+	#[must_use]
+	/// Maps a window-local point to the frozen image pixel drawn under it. Unzoomed this matches
+	/// [`MonitorRect::local_u32_pixels`]; zoomed it picks the magnified pixel the point lies in.
+	pub fn pixel_at(&self, local: (u32, u32), scale_factor: f32) -> (u32, u32) {
+		if !self.is_zoomed() {
+			return (
+				((local.0 as f32) * scale_factor).round() as u32,
+				((local.1 as f32) * scale_factor).round() as u32,
+			);
+		}
+
+		let (x, y) = self.image_point(local.0 as f32, local.1 as f32);
+
+		((x * scale_factor).floor() as u32, (y * scale_factor).floor() as u32)
+	}
+
+	/// Multiplies the zoom by `factor`, keeping the point under window-local `anchor` in place.
+	pub fn zoom_around(&mut self, factor: f32, anchor: (f32, f32), size: (f32, f32)) {
+		let (x, y) = self.image_point(anchor.0, anchor.1);
+
+		self.zoom = (self.zoom * factor).clamp(1.0, Self::MAX_ZOOM);
+		self.origin = (x - anchor.0 / self.zoom, y - anchor.1 / self.zoom);
+
+		self.clamp_to(size);
+	}
+
+	/// Moves the view by a window-local distance, so dragging right reveals the left side.
+	pub fn pan_by(&mut self, dx: f32, dy: f32, size: (f32, f32)) {
+		self.origin = (self.origin.0 - dx / self.zoom, self.origin.1 - dy / self.zoom);
+
+		self.clamp_to(size);
+	}
+
+	/// Keeps the visible area inside a monitor of `size` points.
+	fn clamp_to(&mut self, size: (f32, f32)) {
+		let max_x = (size.0 - size.0 / self.zoom).max(0.0);
+		let max_y = (size.1 - size.1 / self.zoom).max(0.0);
+
+		self.origin = (self.origin.0.clamp(0.0, max_x), self.origin.1.clamp(0.0, max_y));
+	}
+}
+impl Default for FrozenView {
+	fn default() -> Self {
+		Self { zoom: 1.0, origin: (0.0, 0.0) }
+	}
+}
+
 #[derive(Clone, Copy, Debug)]
 /// Internal overlay runtime mode.
 pub enum OverlayMode {
@@ -295,6 +630,24 @@ pub enum OverlayMode {
 	Frozen,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Readings shown by the performance HUD (F12), refreshed a few times per second.
+pub struct PerfHudMetrics {
+	/// Smoothed redraw handling time.
+	pub frame_time_ms: f32,
+	/// Slowest redraw since the previous refresh.
+	pub frame_time_max_ms: f32,
+	/// Redraws per second since the previous refresh.
+	pub redraws_per_second: f32,
+	/// Smoothed RGB/loupe sample round trip, once a sample has arrived.
+	pub sample_latency_ms: Option<f32>,
+	/// Requests waiting for the capture worker.
+	pub queue_depth: usize,
+	pub queue_capacity: usize,
+	/// Requests dropped because the worker queue was full or gone.
+	pub dropped_requests: u64,
+}
+
 #[derive(Debug)]
 /// Internal mutable state owned by a running overlay session.
 pub struct OverlayState {
@@ -303,17 +656,84 @@ pub struct OverlayState {
 	pub rgb: Option<Rgb>,
 	pub monitor: Option<MonitorRect>,
 	pub hovered_window_rect: Option<MonitorRectPoints>,
+	/// Owner and title of the window behind [`Self::hovered_window_rect`], when known.
+	pub hovered_window_label: Option<WindowLabel>,
 	pub drag_rect: Option<MonitorRectPoints>,
 	pub frozen_capture_rect: Option<RectPoints>,
+	/// Zoom and pan of the frozen preview; reset on every freeze.
+	pub frozen_view: FrozenView,
+	/// Extra Shift-drag selections on the frozen monitor, in monitor-local points and in the order
+	/// they were added; each is exported as its own file alongside the primary selection.
+	pub extra_capture_rects: Vec<RectPoints>,
 	pub live_bg_monitor: Option<MonitorRect>,
 	pub live_bg_image: Option<RgbaImage>,
 	pub live_bg_generation: u64,
 	pub frozen_image: Option<RgbaImage>,
 	pub frozen_generation: u64,
 	pub error_message: Option<String>,
+	/// Progress or outcome note shown in the HUD while there is no error; unlike an error it
+	/// never fails a direct capture.
+	pub status_message: Option<String>,
 	pub alt_held: bool,
 	pub loupe: Option<LoupeSample>,
 	pub loupe_patch_side_px: u32,
+	pub hud_unit: HudUnit,
+	/// Guides drawn inside the live drag selection; `visible` follows the `G` toggle.
+	pub composition_guides: CompositionGuides,
+	pub loupe_cell_points: f32,
+	pub magnifier: bool,
+	pub color_picker: bool,
+	/// The live HUD is pinned in place and accepts clicks on its quick actions.
+	pub hud_pinned: bool,
+	/// The loupe cell clicked on the pinned HUD, in pixels from the patch center; the color
+	/// readout follows it instead of the pinned point.
+	pub loupe_lock: Option<(i32, i32)>,
+	/// Mirrors `OverlayConfig::loupe_row_column_highlight`.
+	pub loupe_row_column_highlight: bool,
+	/// The color locked with `L`; while set, the loupe keeps the patch it showed then.
+	pub reference_sample: Option<ReferenceSample>,
+	pub color_format: ColorFormat,
+	/// Non-identity display transfer tables keyed by monitor id, loaded when the adjusted color
+	/// readout is enabled.
+	pub display_transfer: HashMap<u32, DisplayTransfer>,
+	/// Monitors that refused the capture probe, keyed by monitor id, with the backend reason.
+	pub capture_unavailable: HashMap<u32, String>,
+	/// Frozen mode shows the selection histogram panel.
+	pub histogram_panel: bool,
+	/// The histogram panel shows the plot rather than only its header.
+	pub histogram_expanded: bool,
+	pub histogram: Option<Histogram>,
+	/// Frozen dim layer flat opacity, 0..=1.
+	pub frozen_dim_opacity: f32,
+	/// Frozen dim layer vignette strength, 0..=1.
+	pub frozen_dim_vignette: f32,
+	/// Frozen dim layer grain strength, 0..=1.
+	pub frozen_dim_noise: f32,
+	/// The HUD blur pass dithers its output.
+	pub hud_blur_dither: bool,
+	/// Latest performance HUD readings; `None` while the HUD is hidden.
+	pub perf_hud: Option<PerfHudMetrics>,
+	/// Auto-redaction matches in the frozen selection; accepted ones are mosaicked on export.
+	pub redactions: Vec<RedactionProposal>,
+	/// Stamps and callouts on the frozen selection; composited on export.
+	pub annotations: AnnotationLayer,
+	/// Color vision simulation applied to the frozen preview only; kept across freezes.
+	pub color_vision: ColorVisionFilter,
+	/// QR codes from the last scan, with bounds in local pixels of `decoded_codes_monitor`.
+	pub decoded_codes: Vec<DecodedCode>,
+	/// The display the last QR scan looked at.
+	pub decoded_codes_monitor: Option<MonitorRect>,
+	/// Displays badged by the open display picker, in badge order; empty while it is closed.
+	pub display_picker: Vec<MonitorRect>,
+	/// The display picker badges only identify displays (name, resolution, scale factor) and do
+	/// not offer a capture.
+	pub identify_displays: bool,
+	/// Display names reported by the window system, keyed by monitor id.
+	pub display_names: HashMap<u32, String>,
+	/// Selection waiting out the capture delay, with the whole seconds left.
+	pub capture_countdown: Option<(MonitorRectPoints, u32)>,
+	/// Region washed white to confirm an export, with the wash's current opacity.
+	pub capture_flash: Option<(MonitorRectPoints, u8)>,
 }
 impl OverlayState {
 	pub fn new() -> Self {
@@ -323,37 +743,90 @@ impl OverlayState {
 			rgb: None,
 			monitor: None,
 			hovered_window_rect: None,
+			hovered_window_label: None,
 			drag_rect: None,
 			frozen_capture_rect: None,
+			frozen_view: FrozenView::default(),
+			extra_capture_rects: Vec::new(),
 			live_bg_monitor: None,
 			live_bg_image: None,
 			live_bg_generation: 0,
 			frozen_image: None,
 			frozen_generation: 0,
 			error_message: None,
+			status_message: None,
 			alt_held: false,
 			loupe: None,
 			loupe_patch_side_px: 21,
+			hud_unit: HudUnit::Points,
+			composition_guides: CompositionGuides::default(),
+			loupe_cell_points: LOUPE_CELL_POINTS,
+			magnifier: false,
+			color_picker: false,
+			hud_pinned: false,
+			loupe_lock: None,
+			loupe_row_column_highlight: false,
+			reference_sample: None,
+			color_format: ColorFormat::Hex,
+			display_transfer: HashMap::new(),
+			capture_unavailable: HashMap::new(),
+			histogram_panel: false,
+			histogram_expanded: true,
+			histogram: None,
+			frozen_dim_opacity: 0.0,
+			frozen_dim_vignette: 0.0,
+			frozen_dim_noise: 0.0,
+			hud_blur_dither: true,
+			perf_hud: None,
+			redactions: Vec::new(),
+			annotations: AnnotationLayer::default(),
+			color_vision: ColorVisionFilter::Off,
+			decoded_codes: Vec::new(),
+			decoded_codes_monitor: None,
+			display_picker: Vec::new(),
+			identify_displays: false,
+			display_names: HashMap::new(),
+			capture_countdown: None,
+			capture_flash: None,
 		}
 	}
 
 	pub fn set_error(&mut self, message: impl Into<String>) {
 		self.error_message = Some(message.into());
+		self.status_message = None;
+	}
+
+	pub fn set_status(&mut self, message: impl Into<String>) {
+		self.status_message = Some(message.into());
+	}
+
+	/// Returns why capture is unavailable on `monitor_id`, if its probe failed.
+	pub fn capture_unavailable_reason(&self, monitor_id: u32) -> Option<&str> {
+		self.capture_unavailable.get(&monitor_id).map(String::as_str)
 	}
 
 	pub fn clear_error(&mut self) {
 		self.error_message = None;
 	}
 
-	pub fn reset_for_start(&mut self, loupe_patch_side_px: u32) {
+	pub fn reset_for_start(&mut self, loupe_patch_side_px: u32, hud_unit: HudUnit) {
 		*self = Self::new();
 		self.loupe_patch_side_px = loupe_patch_side_px;
+		self.hud_unit = hud_unit;
 	}
 
 	pub fn begin_freeze(&mut self, monitor: MonitorRect) {
 		self.monitor = Some(monitor);
 		self.frozen_image = None;
+		self.frozen_view = FrozenView::default();
 		self.loupe = None;
+		self.histogram = None;
+		self.status_message = None;
+		self.redactions.clear();
+		self.annotations.clear();
+		self.decoded_codes.clear();
+		self.decoded_codes_monitor = None;
+		self.display_picker.clear();
 		self.mode = OverlayMode::Frozen;
 		self.frozen_generation = self.frozen_generation.wrapping_add(1);
 	}
@@ -369,7 +842,12 @@ impl OverlayState {
 
 #[cfg(test)]
 mod tests {
-	use crate::state::{GlobalPoint, MonitorRect, RectPoints};
+	use image::{Rgba, RgbaImage};
+
+	use crate::state::{
+		ColorFormat, DisplayTransfer, FrozenView, GlobalPoint, Histogram, MonitorRect, RectPoints,
+		Rgb,
+	};
 
 	#[test]
 	fn monitor_contains_and_local_coords() {
@@ -390,6 +868,28 @@ mod tests {
 		assert_eq!(monitor.local_u32(GlobalPoint::new(100, 50)), None);
 	}
 
+	#[test]
+	fn frozen_view_zooms_around_the_anchor_and_stays_on_the_monitor() {
+		let mut view = FrozenView::default();
+
+		assert_eq!(view.pixel_at((33, 7), 1.5), (50, 11));
+
+		view.zoom_around(4.0, (100.0, 40.0), (200.0, 100.0));
+
+		assert_eq!(view.origin, (75.0, 30.0));
+		assert_eq!(view.image_point(100.0, 40.0), (100.0, 40.0));
+		// Zoomed, a point picks the magnified pixel it lies in.
+		assert_eq!(view.pixel_at((3, 3), 2.0), (151, 61));
+
+		view.pan_by(-1_000.0, 1_000.0, (200.0, 100.0));
+
+		assert_eq!(view.origin, (150.0, 0.0));
+
+		view.zoom_around(0.01, (0.0, 0.0), (200.0, 100.0));
+
+		assert_eq!(view, FrozenView::default());
+	}
+
 	#[test]
 	fn local_rect_and_pixels() {
 		let monitor = MonitorRect {
@@ -408,4 +908,126 @@ mod tests {
 
 		assert_eq!(pixel_rect, RectPoints::new(20, 40, 260, 260));
 	}
+
+	/// 1.0, 1.25, and 2.0 displays side by side, each 1920×1080-ish in pixels.
+	fn mixed_dpi_monitors() -> [MonitorRect; 3] {
+		[
+			MonitorRect {
+				id: 1,
+				origin: GlobalPoint::new(0, 0),
+				width: 1_920,
+				height: 1_080,
+				scale_factor_x1000: 1_000,
+			},
+			MonitorRect {
+				id: 2,
+				origin: GlobalPoint::new(1_920, 0),
+				width: 1_536,
+				height: 864,
+				scale_factor_x1000: 1_250,
+			},
+			MonitorRect {
+				id: 3,
+				origin: GlobalPoint::new(3_456, 0),
+				width: 1_512,
+				height: 982,
+				scale_factor_x1000: 2_000,
+			},
+		]
+	}
+
+	#[test]
+	fn mixed_dpi_pixel_point_conversions_round_trip() {
+		let [standard, fractional, retina] = mixed_dpi_monitors();
+
+		assert_eq!(standard.size_px(), (1_920, 1_080));
+		assert_eq!(fractional.size_px(), (1_920, 1_080));
+		assert_eq!(retina.size_px(), (3_024, 1_964));
+
+		for monitor in mixed_dpi_monitors() {
+			let (width_px, height_px) = monitor.size_px();
+
+			for (x_px, y_px) in
+				[(0, 0), (1, 1), (width_px / 2, height_px / 3), (width_px - 1, height_px - 1)]
+			{
+				let global = monitor.global_from_local_pixels(f64::from(x_px), f64::from(y_px));
+				let (back_x, back_y) =
+					monitor.local_u32_pixels(global).expect("converted point stays on monitor");
+
+				assert!(back_x.abs_diff(x_px) <= 1, "{monitor:?} x {x_px} -> {back_x}");
+				assert!(back_y.abs_diff(y_px) <= 1, "{monitor:?} y {y_px} -> {back_y}");
+			}
+		}
+
+		assert_eq!(
+			fractional.global_from_local_pixels(-40.0, 5_000.0),
+			GlobalPoint::new(1_920, 863)
+		);
+	}
+
+	#[test]
+	fn mixed_dpi_pixel_rects_tile_and_stay_in_bounds() {
+		let [_, fractional, retina] = mixed_dpi_monitors();
+		let left = fractional.local_rect_to_pixels(RectPoints::new(0, 0, 3, 3));
+		let right = fractional.local_rect_to_pixels(RectPoints::new(3, 0, 4, 3));
+
+		assert_eq!(left, RectPoints::new(0, 0, 4, 4));
+		assert_eq!(right, RectPoints::new(4, 0, 5, 4));
+		assert_eq!(
+			fractional.local_rect_to_pixels(RectPoints::new(0, 0, 1_536, 864)),
+			RectPoints::new(0, 0, 1_920, 1_080)
+		);
+		assert_eq!(
+			fractional.local_rect_to_pixels(RectPoints::new(1_530, 0, 10, 10)),
+			RectPoints::new(1_913, 0, 7, 13)
+		);
+		assert_eq!(
+			retina.local_rect_to_pixels(RectPoints::new(0, 0, 1_512, 982)),
+			RectPoints::new(0, 0, 3_024, 1_964)
+		);
+		assert_eq!(
+			fractional.clamp_global(GlobalPoint::new(5_000, -3)),
+			GlobalPoint::new(3_455, 0)
+		);
+	}
+
+	#[test]
+	fn color_format_cycles_and_formats() {
+		let rgb = Rgb::new(18, 52, 255);
+
+		assert_eq!(ColorFormat::Hex.format(rgb), "#1234FF");
+		assert_eq!(ColorFormat::Rgb.format(rgb), "rgb(18, 52, 255)");
+		assert_eq!(ColorFormat::Hex.next().next(), ColorFormat::Hex);
+	}
+
+	#[test]
+	fn histogram_counts_channels_luma_and_clipping() {
+		let mut image = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]));
+
+		image.put_pixel(1, 0, Rgba([255, 255, 255, 255]));
+		image.put_pixel(0, 1, Rgba([255, 0, 0, 255]));
+		image.put_pixel(1, 1, Rgba([0, 0, 255, 255]));
+
+		let histogram = Histogram::from_image(&image);
+
+		assert_eq!(histogram.pixel_count, 4);
+		assert_eq!((histogram.red[0], histogram.red[255]), (2, 2));
+		assert_eq!((histogram.green[0], histogram.green[255]), (3, 1));
+		assert_eq!((histogram.blue[0], histogram.blue[255]), (2, 2));
+		assert_eq!((histogram.luma[0], histogram.luma[54], histogram.luma[18]), (1, 1, 1));
+		assert_eq!(histogram.peak(), 3);
+		assert_eq!(histogram.clipped_percent(), (25.0, 25.0));
+	}
+
+	#[test]
+	fn display_transfer_maps_samples_through_adjusted_tables() {
+		let ramp = |scale: f32| (0..=255).map(|step| step as f32 / 255.0 * scale).collect();
+		let identity = DisplayTransfer { red: ramp(1.0), green: ramp(1.0), blue: ramp(1.0) };
+		let warm = DisplayTransfer { red: ramp(1.0), green: ramp(0.9), blue: ramp(0.5) };
+
+		assert!(identity.is_identity());
+		assert_eq!(identity.apply(Rgb::new(10, 128, 255)), Rgb::new(10, 128, 255));
+		assert!(!warm.is_identity());
+		assert_eq!(warm.apply(Rgb::new(255, 255, 255)), Rgb::new(255, 230, 128));
+	}
 }
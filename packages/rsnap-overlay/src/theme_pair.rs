@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{Result, WrapErr};
+use image::RgbaImage;
+
+use crate::backend::{self, CaptureBackendKind};
+use crate::external_command;
+use crate::interval_capture;
+use crate::png;
+use crate::state::{MonitorRect, RectPoints};
+
+/// Gives the overlay windows time to leave the screen before the first capture.
+const THEME_PAIR_OVERLAY_CLOSE_DELAY: Duration = Duration::from_millis(300);
+const THEME_PAIR_TOGGLE_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[cfg(target_os = "macos")]
+const DEFAULT_TOGGLE_COMMAND: &str = "osascript -e 'tell application \"System Events\" to tell \
+	appearance preferences to set dark mode to not dark mode'";
+#[cfg(target_os = "windows")]
+const DEFAULT_TOGGLE_COMMAND: &str = "powershell -NoProfile -Command \"$k = \
+	'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize'; $v = 1 - \
+	(Get-ItemPropertyValue $k AppsUseLightTheme); Set-ItemProperty $k AppsUseLightTheme $v; \
+	Set-ItemProperty $k SystemUsesLightTheme $v\"";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_TOGGLE_COMMAND: &str = "if [ \"$(gsettings get org.gnome.desktop.interface \
+	color-scheme)\" = \"'prefer-dark'\" ]; then gsettings set org.gnome.desktop.interface \
+	color-scheme default; else gsettings set org.gnome.desktop.interface color-scheme \
+	prefer-dark; fi";
+
+#[derive(Clone, Debug, PartialEq)]
+/// Tunables for [`capture_theme_pair`].
+pub struct ThemePairConfig {
+	/// Shell command that flips between the light and dark appearance; `None` uses the
+	/// platform's system-wide toggle.
+	pub toggle_command: Option<String>,
+	/// Milliseconds to wait after toggling before the second capture, so apps can repaint.
+	pub settle_ms: u32,
+	/// Directory that receives the pair.
+	pub output_dir: PathBuf,
+	/// Backend that captures both shots.
+	pub capture_backend: CaptureBackendKind,
+}
+impl ThemePairConfig {
+	fn toggle_command(&self) -> &str {
+		self.toggle_command.as_deref().unwrap_or(DEFAULT_TOGGLE_COMMAND)
+	}
+
+	fn output_dir(&self) -> PathBuf {
+		if self.output_dir.as_os_str().is_empty() {
+			PathBuf::from(".")
+		} else {
+			self.output_dir.clone()
+		}
+	}
+}
+impl Default for ThemePairConfig {
+	fn default() -> Self {
+		Self {
+			toggle_command: None,
+			settle_ms: 1_500,
+			output_dir: PathBuf::new(),
+			capture_backend: CaptureBackendKind::Auto,
+		}
+	}
+}
+
+/// Captures `rect_px`, given in monitor-local pixels, once in each appearance on a background
+/// thread: it captures, runs the toggle command, waits, captures again and toggles back. The shots
+/// are saved together as `theme-<YYYY-MM-DD>-<HH-MM-SS>-light.png` and `…-dark.png`.
+pub fn capture_theme_pair(
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	config: ThemePairConfig,
+) -> Result<()> {
+	thread::Builder::new()
+		.name(String::from("rsnap-theme-pair"))
+		.spawn(move || match run_theme_pair(monitor, rect_px, &config) {
+			Ok((light, dark)) => {
+				tracing::info!(
+					op = "theme_pair.saved",
+					light = %light.display(),
+					dark = %dark.display(),
+					"Theme pair saved."
+				);
+			},
+			Err(err) => tracing::warn!(error = %format!("{err:#}"), "Theme pair capture failed."),
+		})
+		.wrap_err("Failed to start the theme pair thread")?;
+
+	tracing::info!(
+		op = "theme_pair.start",
+		monitor_id = monitor.id,
+		x = rect_px.x,
+		y = rect_px.y,
+		width = rect_px.width,
+		height = rect_px.height,
+		"Theme pair capture started."
+	);
+
+	Ok(())
+}
+
+fn run_theme_pair(
+	monitor: MonitorRect,
+	rect_px: RectPoints,
+	config: &ThemePairConfig,
+) -> Result<(PathBuf, PathBuf)> {
+	let command = config.toggle_command();
+	let mut backend = backend::capture_backend(config.capture_backend);
+
+	thread::sleep(THEME_PAIR_OVERLAY_CLOSE_DELAY);
+
+	let first = backend
+		.capture_monitor_region(monitor, rect_px)
+		.wrap_err("Failed to capture the current appearance")?;
+
+	run_toggle_command(command)?;
+	thread::sleep(Duration::from_millis(u64::from(config.settle_ms)));
+
+	let second = backend.capture_monitor_region(monitor, rect_px);
+
+	// Restore the original appearance even when the second capture failed.
+	if let Err(err) = run_toggle_command(command) {
+		tracing::warn!(error = %format!("{err:#}"), "Failed to restore the appearance.");
+	}
+
+	let second = second.wrap_err("Failed to capture the toggled appearance")?;
+	let (light, dark) = order_by_lightness(first, second);
+
+	save_pair(&config.output_dir(), &light, &dark, SystemTime::now())
+}
+
+fn run_toggle_command(command: &str) -> Result<()> {
+	external_command::run_with_timeout(
+		external_command::shell_command(command),
+		None,
+		THEME_PAIR_TOGGLE_TIMEOUT,
+	)
+	.wrap_err("Appearance toggle command failed")?;
+
+	Ok(())
+}
+
+/// Returns the shots as `(light, dark)`; the toggle does not say which way it went, so the
+/// brighter capture is taken as the light one.
+fn order_by_lightness(first: RgbaImage, second: RgbaImage) -> (RgbaImage, RgbaImage) {
+	if mean_luma(&first) >= mean_luma(&second) { (first, second) } else { (second, first) }
+}
+
+/// Mean Rec. 709 luma of `image`, from 0.0 to 255.0.
+fn mean_luma(image: &RgbaImage) -> f64 {
+	let pixels = image.pixels().len().max(1);
+	let total = image
+		.pixels()
+		.map(|pixel| {
+			let [r, g, b, _] = pixel.0.map(f64::from);
+
+			0.2126 * r + 0.7152 * g + 0.0722 * b
+		})
+		.sum::<f64>();
+
+	total / pixels as f64
+}
+
+fn save_pair(
+	output_dir: &Path,
+	light: &RgbaImage,
+	dark: &RgbaImage,
+	now: SystemTime,
+) -> Result<(PathBuf, PathBuf)> {
+	let unix_secs = now.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+	let (date, time) = interval_capture::utc_date_and_time(unix_secs);
+
+	fs::create_dir_all(output_dir)
+		.wrap_err_with(|| format!("Failed to create output directory: {}", output_dir.display()))?;
+
+	let light_path = output_dir.join(format!("theme-{date}-{time}-light.png"));
+	let dark_path = output_dir.join(format!("theme-{date}-{time}-dark.png"));
+
+	for (path, image) in [(&light_path, light), (&dark_path, dark)] {
+		fs::write(path, png::rgba_image_to_png_bytes(image)?)
+			.wrap_err_with(|| format!("Failed to write theme capture: {}", path.display()))?;
+	}
+
+	Ok((light_path, dark_path))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::time::{Duration, UNIX_EPOCH};
+
+	use image::{Rgba, RgbaImage};
+
+	use crate::theme_pair;
+
+	#[test]
+	fn the_brighter_shot_is_saved_as_light_whichever_came_first() {
+		let light = RgbaImage::from_pixel(4, 3, Rgba([236, 236, 236, 255]));
+		let dark = RgbaImage::from_pixel(4, 3, Rgba([30, 30, 30, 255]));
+
+		assert_eq!(
+			theme_pair::order_by_lightness(dark.clone(), light.clone()),
+			(light.clone(), dark.clone())
+		);
+		assert_eq!(theme_pair::order_by_lightness(light.clone(), dark.clone()), (light, dark));
+
+		let output_dir =
+			std::env::temp_dir().join(format!("rsnap-theme-pair-{}", std::process::id()));
+		let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+		let (light_path, dark_path) =
+			theme_pair::save_pair(&output_dir, &RgbaImage::new(4, 3), &RgbaImage::new(4, 3), at)
+				.expect("pair");
+
+		assert_eq!(light_path, output_dir.join("theme-2023-11-14-22-13-20-light.png"));
+		assert_eq!(dark_path, output_dir.join("theme-2023-11-14-22-13-20-dark.png"));
+		assert!(fs::metadata(&dark_path).is_ok());
+
+		let _ = fs::remove_dir_all(output_dir);
+	}
+}
@@ -0,0 +1,100 @@
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+
+use crate::backdrop::{self, BackdropJob};
+use crate::worker::PngExport;
+
+/// Renders `export` as it would be encoded, shrunk to fit within `max_size` pixels.
+///
+/// The capture is downscaled before any backdrop is composed, with the backdrop's padding and
+/// radius scaled to match, so a preview never pays for a full-size blur.
+pub(crate) fn render_export_thumbnail(export: &PngExport, max_size: (u32, u32)) -> RgbaImage {
+	let padding = export.backdrop.as_ref().map_or(0, |job| job.backdrop.padding_px);
+	let full_width = export.image.width().saturating_add(padding.saturating_mul(2)).max(1);
+	let full_height = export.image.height().saturating_add(padding.saturating_mul(2)).max(1);
+	let scale = (max_size.0 as f32 / full_width as f32)
+		.min(max_size.1 as f32 / full_height as f32)
+		.min(1.0);
+	let scaled = |value: u32| (value as f32 * scale).round() as u32;
+	let image = imageops::resize(
+		&export.image,
+		scaled(export.image.width()).max(1),
+		scaled(export.image.height()).max(1),
+		FilterType::Triangle,
+	);
+	let Some(job) = export.backdrop.as_ref() else {
+		return image;
+	};
+	let mut backdrop = job.backdrop;
+
+	backdrop.padding_px = scaled(backdrop.padding_px);
+	backdrop.corner_radius_px = scaled(backdrop.corner_radius_px);
+
+	let blur_source = job.blur_source.as_ref().map(|source| {
+		imageops::resize(
+			source,
+			scaled(source.width()).max(1),
+			scaled(source.height()).max(1),
+			FilterType::Triangle,
+		)
+	});
+
+	backdrop::compose_backdrop(&image, &BackdropJob { backdrop, blur_source })
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::backdrop::BackdropJob;
+	use crate::overlay::{BackdropFill, ExportBackdrop, ExportScaling};
+	use crate::thumbnail;
+	use crate::worker::{ExportResize, PngExport};
+
+	#[test]
+	fn export_thumbnail_fits_the_box_and_keeps_backdrop_proportions() {
+		let resize = ExportResize { scaling: ExportScaling::default(), scale_factor: 1.0 };
+		let plain = PngExport {
+			image: RgbaImage::from_pixel(400, 100, Rgba([255, 0, 0, 255])),
+			backdrop: None,
+			resize,
+			watermark: None,
+			retina_pair: false,
+		};
+
+		assert_eq!(thumbnail::render_export_thumbnail(&plain, (80, 48)).dimensions(), (80, 20));
+
+		let small = PngExport {
+			image: RgbaImage::from_pixel(10, 6, Rgba([0, 0, 0, 255])),
+			backdrop: None,
+			resize,
+			watermark: None,
+			retina_pair: false,
+		};
+
+		assert_eq!(thumbnail::render_export_thumbnail(&small, (80, 48)).dimensions(), (10, 6));
+
+		let padded = PngExport {
+			image: RgbaImage::from_pixel(300, 100, Rgba([255, 0, 0, 255])),
+			backdrop: Some(BackdropJob {
+				backdrop: ExportBackdrop {
+					fill: BackdropFill::Solid,
+					primary_rgb: [0, 0, 255],
+					padding_px: 50,
+					shadow: false,
+					..ExportBackdrop::default()
+				},
+				blur_source: None,
+			}),
+			resize,
+			watermark: None,
+			retina_pair: false,
+		};
+		let preview = thumbnail::render_export_thumbnail(&padded, (80, 48));
+
+		// 400×200 scaled by 0.2: a 60×20 capture on 10 px of padding.
+		assert_eq!(preview.dimensions(), (80, 40));
+		assert_eq!(*preview.get_pixel(2, 2), Rgba([0, 0, 255, 255]));
+		assert_eq!(*preview.get_pixel(40, 20), Rgba([255, 0, 0, 255]));
+	}
+}
@@ -0,0 +1,237 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+use crate::overlay::{Watermark, WatermarkCorner};
+
+/// A logo never covers more than this fraction of the export's width or height.
+const WATERMARK_LOGO_MAX_FRACTION: f32 = 0.25;
+
+/// Stamps `watermark` onto a finished export. A logo that cannot be loaded is skipped with a
+/// warning so the export itself still succeeds.
+pub(crate) fn apply_watermark(image: &mut RgbaImage, watermark: &Watermark) {
+	let stamp = if watermark.logo_path.trim().is_empty() {
+		render_text(&watermark.text, watermark.text_size_px, watermark.text_rgb)
+	} else {
+		match load_logo(watermark.logo_path.trim(), image.dimensions()) {
+			Ok(logo) => Some(logo),
+			Err(err) => {
+				tracing::warn!(
+					op = "export.watermark",
+					error = %format!("{err:#}"),
+					"Watermark logo skipped."
+				);
+
+				None
+			},
+		}
+	};
+	let Some(stamp) = stamp else {
+		return;
+	};
+	let margin = i64::from(watermark.margin_px);
+	let (left, top) = (margin, margin);
+	let right = i64::from(image.width()) - i64::from(stamp.width()) - margin;
+	let bottom = i64::from(image.height()) - i64::from(stamp.height()) - margin;
+	let (x, y) = match watermark.corner {
+		WatermarkCorner::TopLeft => (left, top),
+		WatermarkCorner::TopRight => (right, top),
+		WatermarkCorner::BottomLeft => (left, bottom),
+		WatermarkCorner::BottomRight => (right, bottom),
+	};
+
+	blend_onto(image, &stamp, x, y, f32::from(watermark.opacity_percent.min(100)) / 100.0);
+}
+
+/// Rasterizes `text` in the bundled UI font with a soft dark shadow for contrast.
+fn render_text(text: &str, size_px: u32, rgb: [u8; 3]) -> Option<RgbaImage> {
+	let text = text.trim();
+
+	if text.is_empty() || size_px == 0 {
+		return None;
+	}
+
+	let font = FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT).ok()?;
+	let scale = PxScale::from(size_px as f32);
+	let scaled = font.as_scaled(scale);
+	let shadow_offset = (size_px / 16).max(1);
+	let mut caret = 0.0;
+	let mut previous = None;
+	let mut glyphs = Vec::new();
+
+	for ch in text.chars() {
+		let id = scaled.glyph_id(ch);
+
+		if let Some(previous) = previous {
+			caret += scaled.kern(previous, id);
+		}
+
+		glyphs.push(id.with_scale_and_position(scale, point(caret, scaled.ascent())));
+
+		caret += scaled.h_advance(id);
+		previous = Some(id);
+	}
+
+	let width = caret.ceil() as u32 + shadow_offset;
+	let height = (scaled.ascent() - scaled.descent()).ceil() as u32 + shadow_offset;
+	let mut coverage = vec![0.0_f32; (width * height) as usize];
+
+	for glyph in glyphs {
+		let Some(outlined) = font.outline_glyph(glyph) else {
+			continue;
+		};
+		let bounds = outlined.px_bounds();
+
+		outlined.draw(|gx, gy, value| {
+			let x = bounds.min.x as i64 + i64::from(gx);
+			let y = bounds.min.y as i64 + i64::from(gy);
+
+			if (0..i64::from(width)).contains(&x) && (0..i64::from(height)).contains(&y) {
+				let cell = &mut coverage[(y as u32 * width + x as u32) as usize];
+
+				*cell = cell.max(value.min(1.0));
+			}
+		});
+	}
+
+	let mut stamp = RgbaImage::new(width, height);
+
+	for y in 0..height {
+		for x in 0..width {
+			let text_alpha = coverage[(y * width + x) as usize];
+			let shadow_alpha = match (x.checked_sub(shadow_offset), y.checked_sub(shadow_offset)) {
+				(Some(sx), Some(sy)) => coverage[(sy * width + sx) as usize] * 0.5,
+				_ => 0.0,
+			};
+			let shadow = Rgba([0, 0, 0, (shadow_alpha * 255.0).round() as u8]);
+			let mut pixel = shadow;
+
+			blend_pixel(&mut pixel, Rgba([rgb[0], rgb[1], rgb[2], 255]), text_alpha);
+
+			stamp.put_pixel(x, y, pixel);
+		}
+	}
+
+	Some(stamp)
+}
+
+/// Loads the logo PNG, shrinking it to a quarter of the export when it is larger.
+fn load_logo(path: &str, export_size: (u32, u32)) -> Result<RgbaImage> {
+	let logo = image::open(path)
+		.wrap_err_with(|| format!("Failed to load watermark logo {path}"))?
+		.to_rgba8();
+
+	if logo.width() == 0 || logo.height() == 0 {
+		return Err(eyre!("Watermark logo {path} is empty"));
+	}
+
+	let max_width = export_size.0 as f32 * WATERMARK_LOGO_MAX_FRACTION;
+	let max_height = export_size.1 as f32 * WATERMARK_LOGO_MAX_FRACTION;
+	let ratio = (max_width / logo.width() as f32).min(max_height / logo.height() as f32);
+
+	if ratio >= 1.0 {
+		return Ok(logo);
+	}
+
+	let width = ((logo.width() as f32 * ratio).round() as u32).max(1);
+	let height = ((logo.height() as f32 * ratio).round() as u32).max(1);
+
+	Ok(imageops::resize(&logo, width, height, FilterType::Lanczos3))
+}
+
+/// Composites `stamp` over `image` at (`x`, `y`), clipping whatever falls outside.
+pub(crate) fn blend_onto(image: &mut RgbaImage, stamp: &RgbaImage, x: i64, y: i64, opacity: f32) {
+	for (sx, sy, source) in stamp.enumerate_pixels() {
+		let (tx, ty) = (x + i64::from(sx), y + i64::from(sy));
+
+		if tx < 0 || ty < 0 || tx >= i64::from(image.width()) || ty >= i64::from(image.height()) {
+			continue;
+		}
+
+		let alpha = f32::from(source[3]) / 255.0 * opacity;
+
+		blend_pixel(image.get_pixel_mut(tx as u32, ty as u32), *source, alpha);
+	}
+}
+
+/// Source-over blend of `source`'s color at `alpha` onto `target`.
+pub(crate) fn blend_pixel(target: &mut Rgba<u8>, source: Rgba<u8>, alpha: f32) {
+	if alpha <= 0.0 {
+		return;
+	}
+
+	let target_alpha = f32::from(target[3]) / 255.0;
+	let out_alpha = alpha + target_alpha * (1.0 - alpha);
+
+	for channel in 0..3 {
+		let blended = (f32::from(source[channel]) * alpha
+			+ f32::from(target[channel]) * target_alpha * (1.0 - alpha))
+			/ out_alpha;
+
+		target[channel] = blended.round().clamp(0.0, 255.0) as u8;
+	}
+
+	target[3] = (out_alpha * 255.0).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgba, RgbaImage};
+
+	use crate::overlay::{Watermark, WatermarkCorner};
+	use crate::watermark;
+
+	#[test]
+	fn text_watermark_lands_in_the_chosen_corner() {
+		let background = Rgba([20, 40, 60, 255]);
+		let mut image = RgbaImage::from_pixel(400, 200, background);
+		let watermark = Watermark {
+			enabled: true,
+			text: String::from("rsnap"),
+			corner: WatermarkCorner::BottomRight,
+			margin_px: 10,
+			text_size_px: 32,
+			opacity_percent: 100,
+			..Watermark::default()
+		};
+
+		watermark::apply_watermark(&mut image, &watermark);
+
+		let changed = |x0: u32, y0: u32, x1: u32, y1: u32| {
+			(y0..y1).any(|y| (x0..x1).any(|x| *image.get_pixel(x, y) != background))
+		};
+
+		assert!(changed(300, 150, 390, 190));
+		assert!(!changed(0, 0, 200, 100));
+		assert!(!changed(391, 0, 400, 200));
+		assert!(!changed(0, 191, 400, 200));
+	}
+
+	#[test]
+	fn logo_watermark_blends_at_the_configured_opacity() {
+		let path =
+			std::env::temp_dir().join(format!("rsnap-watermark-logo-{}.png", std::process::id()));
+
+		RgbaImage::from_pixel(4, 4, Rgba([255, 255, 255, 255])).save(&path).expect("logo");
+
+		let mut image = RgbaImage::from_pixel(100, 100, Rgba([0, 0, 0, 255]));
+		let watermark = Watermark {
+			enabled: true,
+			logo_path: path.display().to_string(),
+			corner: WatermarkCorner::TopLeft,
+			margin_px: 5,
+			opacity_percent: 50,
+			..Watermark::default()
+		};
+
+		watermark::apply_watermark(&mut image, &watermark);
+
+		assert_eq!(*image.get_pixel(5, 5), Rgba([128, 128, 128, 255]));
+		assert_eq!(*image.get_pixel(8, 8), Rgba([128, 128, 128, 255]));
+		assert_eq!(*image.get_pixel(9, 9), Rgba([0, 0, 0, 255]));
+		assert_eq!(*image.get_pixel(4, 5), Rgba([0, 0, 0, 255]));
+
+		let _ = std::fs::remove_file(path);
+	}
+}
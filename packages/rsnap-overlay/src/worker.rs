@@ -1,5 +1,6 @@
 use std::sync::{
 	Arc,
+	atomic::{AtomicIsize, AtomicU64, Ordering},
 	mpsc::{self, Receiver, Sender, SyncSender, TryRecvError, TrySendError},
 };
 use std::thread;
@@ -7,19 +8,90 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use image::RgbaImage;
+use image::imageops::{self, FilterType};
 
-use crate::backend::CaptureBackend;
+use crate::backdrop::{self, BackdropJob};
+use crate::backend::{self, CaptureBackend};
+use crate::collage;
+use crate::overlay::{ExportScaling, Watermark};
 use crate::png;
+use crate::progress::{OverlayProgress, ProgressStage};
+use crate::recognition::{self, DecodedCode};
+use crate::redact::{self, RedactionProposal};
 #[cfg(not(target_os = "macos"))]
 use crate::state::LiveCursorSample;
-#[cfg(any(not(target_os = "macos"), test))]
-use crate::state::RectPoints;
-use crate::state::{GlobalPoint, MonitorRect, WindowHit, WindowListSnapshot};
+use crate::state::{
+	GlobalPoint, Histogram, MonitorRect, RectPoints, WindowHit, WindowListSnapshot,
+};
+use crate::thumbnail;
+use crate::watermark;
+
+/// Requests the worker queue holds before new ones are dropped.
+pub(crate) const WORKER_QUEUE_CAPACITY: usize = 64;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum FreezeCaptureTarget {
 	Monitor,
-	Window { window_id: u32 },
+	Window { window_id: u32, keep_shadow: bool },
+}
+
+/// An export waiting to be encoded, optionally placed on a backdrop first.
+#[derive(Debug)]
+pub(crate) struct PngExport {
+	pub(crate) image: RgbaImage,
+	pub(crate) backdrop: Option<BackdropJob>,
+	pub(crate) resize: ExportResize,
+	pub(crate) watermark: Option<Watermark>,
+	/// Also encodes a half-size copy, so the export ships as an `@2x` + `@1x` asset pair.
+	pub(crate) retina_pair: bool,
+}
+
+/// Export resampling settings together with the scale factor of the captured display.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ExportResize {
+	pub(crate) scaling: ExportScaling,
+	pub(crate) scale_factor: f32,
+}
+impl ExportResize {
+	/// Shrinks `image` with Lanczos resampling; images already small enough pass through.
+	pub(crate) fn apply(self, image: RgbaImage) -> RgbaImage {
+		match self.scaling.target_size(image.dimensions(), self.scale_factor) {
+			Some((width, height)) => imageops::resize(&image, width, height, FilterType::Lanczos3),
+			None => image,
+		}
+	}
+}
+
+/// Resamples a composed export and stamps its watermark, the last steps before encoding.
+fn finish_export(
+	image: RgbaImage,
+	resize: ExportResize,
+	watermark: Option<&Watermark>,
+) -> RgbaImage {
+	let mut image = resize.apply(image);
+
+	if let Some(watermark) = watermark {
+		watermark::apply_watermark(&mut image, watermark);
+	}
+
+	image
+}
+
+/// The `@1x` half of an asset pair: `image` at half its size, rounded up.
+fn one_x_copy(image: &RgbaImage) -> RgbaImage {
+	let width = image.width().div_ceil(2).max(1);
+	let height = image.height().div_ceil(2).max(1);
+
+	imageops::resize(image, width, height, FilterType::Lanczos3)
+}
+
+/// A multi-region export: the primary selection plus the extra regions, each encoded on its own.
+#[derive(Debug)]
+pub(crate) struct PngBatchExport {
+	pub(crate) primary: PngExport,
+	pub(crate) regions: Vec<RgbaImage>,
+	/// Also encodes one collage of the primary (without its backdrop) and every region.
+	pub(crate) collage: bool,
 }
 
 #[derive(Debug)]
@@ -39,6 +111,9 @@ pub(crate) enum WorkerRequest {
 		patch_height_px: u32,
 	},
 	RefreshWindowList,
+	ProbeMonitors {
+		monitors: Vec<MonitorRect>,
+	},
 	FreezeCapture {
 		monitor: MonitorRect,
 		target: FreezeCaptureTarget,
@@ -50,10 +125,35 @@ pub(crate) enum WorkerRequest {
 		request_id: u64,
 	},
 	EncodePng {
+		export: Box<PngExport>,
+	},
+	EncodePngBatch {
+		batch: Box<PngBatchExport>,
+	},
+	ComputeHistogram {
 		image: RgbaImage,
+		request_id: u64,
+	},
+	RenderExportPreview {
+		export: PngExport,
+		max_size: (u32, u32),
+		request_id: u64,
+	},
+	ScanCodes {
+		source: CodeScanSource,
+		request_id: u64,
 	},
 }
 
+/// What a QR scan looks at.
+#[derive(Debug)]
+pub(crate) enum CodeScanSource {
+	/// An image the session already holds, whose top-left is `origin_px` in monitor pixels.
+	Image { image: RgbaImage, origin_px: (u32, u32) },
+	/// A fresh capture of `rect_px` on `monitor`, for live mode.
+	MonitorRegion { monitor: MonitorRect, rect_px: RectPoints },
+}
+
 #[derive(Debug)]
 pub(crate) enum WorkerResponse {
 	#[cfg(not(target_os = "macos"))]
@@ -72,14 +172,44 @@ pub(crate) enum WorkerResponse {
 	RefreshedWindowList {
 		snapshot: Arc<WindowListSnapshot>,
 	},
+	ProbedMonitor {
+		monitor: MonitorRect,
+		error: Option<String>,
+	},
 	CapturedFreeze {
 		monitor: MonitorRect,
 		image: RgbaImage,
 		window_image: Option<RgbaImage>,
 		captured_window_id: Option<u32>,
 	},
+	/// `one_x_png` is the half-size copy when the export asked for an asset pair.
 	EncodedPng {
 		png_bytes: Vec<u8>,
+		one_x_png: Option<Vec<u8>>,
+	},
+	/// The primary export first, then each extra region in selection order.
+	EncodedPngBatch {
+		region_pngs: Vec<Vec<u8>>,
+		collage_png: Option<Vec<u8>>,
+	},
+	/// Sent while an encode job runs, ahead of its encoded response.
+	Progress(OverlayProgress),
+	ComputedHistogram {
+		request_id: u64,
+		histogram: Box<Histogram>,
+	},
+	RenderedExportPreview {
+		request_id: u64,
+		image: RgbaImage,
+	},
+	ScannedRedactions {
+		request_id: u64,
+		proposals: Vec<RedactionProposal>,
+	},
+	/// Decoded codes with bounds in monitor-local pixels.
+	ScannedCodes {
+		request_id: u64,
+		codes: Vec<DecodedCode>,
 	},
 	Error(String),
 }
@@ -106,44 +236,65 @@ pub(crate) struct CapturedMonitorRegionResponse {
 	pub(crate) result: CapturedMonitorRegionResult,
 }
 
+/// Queue occupancy and send failures, read by the performance HUD.
+#[derive(Debug, Default)]
+struct WorkerQueueStats {
+	/// Signed because the worker can dequeue a request before its send is counted.
+	queued: AtomicIsize,
+	dropped: AtomicU64,
+}
+
 pub(crate) struct OverlayWorker {
 	req_tx: SyncSender<WorkerRequest>,
+	/// Lets jobs that run on their own thread answer through the worker's response channel.
+	resp_tx: Sender<WorkerResponse>,
 	resp_rx: Receiver<WorkerResponse>,
+	response_waker: Option<Arc<dyn Fn() + Send + Sync>>,
 	#[cfg(any(not(target_os = "macos"), test))]
 	region_capture_resp_rx: Receiver<CapturedMonitorRegionResponse>,
+	queue_stats: Arc<WorkerQueueStats>,
 }
 impl OverlayWorker {
 	pub(crate) fn new(
 		backend: Box<dyn CaptureBackend>,
 		response_waker: Option<Arc<dyn Fn() + Send + Sync>>,
 	) -> Self {
-		let (req_tx, req_rx) = mpsc::sync_channel(64);
+		let (req_tx, req_rx) = mpsc::sync_channel(WORKER_QUEUE_CAPACITY);
+		let queue_stats = Arc::new(WorkerQueueStats::default());
+		let worker_queue_stats = Arc::clone(&queue_stats);
 		let (resp_tx, resp_rx) = mpsc::channel();
 		#[cfg(any(not(target_os = "macos"), test))]
 		let (region_capture_resp_tx, region_capture_resp_rx) = mpsc::channel();
+		let worker_resp_tx = resp_tx.clone();
+		let worker_response_waker = response_waker.clone();
 
 		thread::spawn(move || {
 			Self::run_worker_loop(
 				backend,
 				req_rx,
-				resp_tx,
+				&worker_queue_stats,
+				worker_resp_tx,
 				#[cfg(any(not(target_os = "macos"), test))]
 				region_capture_resp_tx,
-				response_waker,
+				worker_response_waker,
 			)
 		});
 
 		Self {
 			req_tx,
+			resp_tx,
 			resp_rx,
+			response_waker,
 			#[cfg(any(not(target_os = "macos"), test))]
 			region_capture_resp_rx,
+			queue_stats,
 		}
 	}
 
 	fn run_worker_loop(
 		mut backend: Box<dyn CaptureBackend>,
 		req_rx: Receiver<WorkerRequest>,
+		queue_stats: &WorkerQueueStats,
 		resp_tx: Sender<WorkerResponse>,
 		#[cfg(any(not(target_os = "macos"), test))] region_capture_resp_tx: Sender<
 			CapturedMonitorRegionResponse,
@@ -155,10 +306,16 @@ impl OverlayWorker {
 
 			pending.record(first);
 
+			let mut received = 1_isize;
+
 			while let Ok(next) = req_rx.try_recv() {
 				pending.record(next);
+
+				received += 1;
 			}
 
+			queue_stats.queued.fetch_sub(received, Ordering::Relaxed);
+
 			pending.dispatch(
 				&mut *backend,
 				&resp_tx,
@@ -172,14 +329,187 @@ impl OverlayWorker {
 	fn handle_encode_request(
 		resp_tx: &Sender<WorkerResponse>,
 		response_waker: Option<&(dyn Fn() + Send + Sync)>,
-		image: RgbaImage,
+		export: PngExport,
+	) {
+		let _span = tracing::info_span!(
+			"encode",
+			width = export.image.width(),
+			height = export.image.height()
+		)
+		.entered();
+		// Composing, then the PNG and its optional `@1x` copy.
+		let steps = 2 + usize::from(export.retina_pair);
+		let progress = |stage, done| {
+			Self::send_response(
+				resp_tx,
+				response_waker,
+				WorkerResponse::Progress(OverlayProgress::of_steps(stage, done, steps)),
+			);
+		};
+
+		progress(ProgressStage::Composing, 0);
+
+		let image = match export.backdrop {
+			Some(job) => backdrop::compose_backdrop(&export.image, &job),
+			None => export.image,
+		};
+		let image = finish_export(image, export.resize, export.watermark.as_ref());
+
+		progress(ProgressStage::Encoding, 1);
+
+		let encoded = png::rgba_image_to_png_bytes(&image).and_then(|png_bytes| {
+			let one_x_png = export
+				.retina_pair
+				.then(|| {
+					progress(ProgressStage::Encoding, 2);
+
+					png::rgba_image_to_png_bytes(&one_x_copy(&image))
+				})
+				.transpose()?;
+
+			Ok((png_bytes, one_x_png))
+		});
+
+		match encoded {
+			Ok((png_bytes, one_x_png)) => {
+				progress(ProgressStage::Encoding, steps);
+
+				Self::send_response(
+					resp_tx,
+					response_waker,
+					WorkerResponse::EncodedPng { png_bytes, one_x_png },
+				);
+			},
+			Err(err) => {
+				Self::send_response(
+					resp_tx,
+					response_waker,
+					WorkerResponse::Error(format!("{err:#}")),
+				);
+			},
+		}
+	}
+
+	fn handle_encode_batch_request(
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		batch: PngBatchExport,
+	) {
+		let _span =
+			tracing::info_span!("encode_batch", regions = batch.regions.len() + 1).entered();
+		// Composing, then one PNG per region and the optional collage.
+		let steps = 2 + batch.regions.len() + usize::from(batch.collage);
+		let progress = |stage, done| {
+			Self::send_response(
+				resp_tx,
+				response_waker,
+				WorkerResponse::Progress(OverlayProgress::of_steps(stage, done, steps)),
+			);
+		};
+
+		progress(ProgressStage::Composing, 0);
+
+		let collage = batch.collage.then(|| {
+			let mut images = Vec::with_capacity(batch.regions.len() + 1);
+
+			images.push(batch.primary.image.clone());
+			images.extend(batch.regions.iter().cloned());
+
+			collage::compose_collage(&images, collage::COLLAGE_GAP_PX)
+		});
+		let (resize, watermark) = (batch.primary.resize, batch.primary.watermark.as_ref());
+		let collage = collage.map(|collage| finish_export(collage, resize, watermark));
+		let primary = match batch.primary.backdrop {
+			Some(job) => backdrop::compose_backdrop(&batch.primary.image, &job),
+			None => batch.primary.image,
+		};
+		let encoded = std::iter::once(primary)
+			.chain(batch.regions)
+			.enumerate()
+			.map(|(index, image)| {
+				progress(ProgressStage::Encoding, 1 + index);
+
+				png::rgba_image_to_png_bytes(&finish_export(image, resize, watermark))
+			})
+			.collect::<color_eyre::eyre::Result<Vec<_>>>()
+			.and_then(|region_pngs| {
+				let collage_png = collage
+					.as_ref()
+					.map(|collage| {
+						progress(ProgressStage::Encoding, 1 + region_pngs.len());
+
+						png::rgba_image_to_png_bytes(collage)
+					})
+					.transpose()?;
+
+				Ok(WorkerResponse::EncodedPngBatch { region_pngs, collage_png })
+			});
+
+		match encoded {
+			Ok(response) => {
+				progress(ProgressStage::Encoding, steps);
+
+				Self::send_response(resp_tx, response_waker, response);
+			},
+			Err(err) => {
+				Self::send_response(
+					resp_tx,
+					response_waker,
+					WorkerResponse::Error(format!("{err:#}")),
+				);
+			},
+		}
+	}
+
+	fn handle_histogram_request(
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		image: &RgbaImage,
+		request_id: u64,
+	) {
+		let histogram = Box::new(Histogram::from_image(image));
+
+		Self::send_response(
+			resp_tx,
+			response_waker,
+			WorkerResponse::ComputedHistogram { request_id, histogram },
+		);
+	}
+
+	fn handle_export_preview_request(
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		export: &PngExport,
+		max_size: (u32, u32),
+		request_id: u64,
+	) {
+		let image = thumbnail::render_export_thumbnail(export, max_size);
+
+		Self::send_response(
+			resp_tx,
+			response_waker,
+			WorkerResponse::RenderedExportPreview { request_id, image },
+		);
+	}
+
+	fn handle_scan_redactions_request(
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		image: &RgbaImage,
+		command: &str,
+		request_id: u64,
 	) {
-		match png::rgba_image_to_png_bytes(&image) {
-			Ok(png_bytes) => {
+		let _span = tracing::info_span!("scan_redactions", request_id).entered();
+		let scanned = png::rgba_image_to_png_bytes(image).and_then(|png_bytes| {
+			redact::scan_for_redactions(command, png_bytes, image.dimensions())
+		});
+
+		match scanned {
+			Ok(proposals) => {
 				Self::send_response(
 					resp_tx,
 					response_waker,
-					WorkerResponse::EncodedPng { png_bytes },
+					WorkerResponse::ScannedRedactions { request_id, proposals },
 				);
 			},
 			Err(err) => {
@@ -192,6 +522,52 @@ impl OverlayWorker {
 		}
 	}
 
+	fn handle_scan_codes_request(
+		backend: &mut dyn CaptureBackend,
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		source: CodeScanSource,
+		request_id: u64,
+	) {
+		let _span = tracing::info_span!("scan_codes", request_id).entered();
+		let (image, origin_px) = match source {
+			CodeScanSource::Image { image, origin_px } => (image, origin_px),
+			CodeScanSource::MonitorRegion { monitor, rect_px } => {
+				let captured = backend
+					.capture_monitor(monitor)
+					.and_then(|image| backend::crop_monitor_image_region(&image, rect_px));
+
+				match captured {
+					Ok(image) => (image, (rect_px.x, rect_px.y)),
+					Err(err) => {
+						Self::send_response(
+							resp_tx,
+							response_waker,
+							WorkerResponse::Error(format!("{err:#}")),
+						);
+
+						return;
+					},
+				}
+			},
+		};
+		let codes = recognition::scan_codes(&image)
+			.into_iter()
+			.map(|mut code| {
+				code.bounds_px.x += origin_px.0;
+				code.bounds_px.y += origin_px.1;
+
+				code
+			})
+			.collect();
+
+		Self::send_response(
+			resp_tx,
+			response_waker,
+			WorkerResponse::ScannedCodes { request_id, codes },
+		);
+	}
+
 	fn handle_freeze_request(
 		backend: &mut dyn CaptureBackend,
 		resp_tx: &Sender<WorkerResponse>,
@@ -199,12 +575,17 @@ impl OverlayWorker {
 		monitor: MonitorRect,
 		target: FreezeCaptureTarget,
 	) {
+		let _span =
+			tracing::info_span!("capture", monitor_id = monitor.id, target = ?target).entered();
 		let mut captured_window_id = None;
 		let mut window_image = None;
 
-		if let FreezeCaptureTarget::Window { window_id } = target
-			&& let Ok(image) = backend.capture_window(window_id)
-		{
+		if let FreezeCaptureTarget::Window { window_id, keep_shadow } = target
+			&& let Ok(image) = if keep_shadow {
+				backend.capture_window_with_shadow(window_id)
+			} else {
+				backend.capture_window(window_id)
+			} {
 			captured_window_id = Some(window_id);
 			window_image = Some(image);
 		}
@@ -232,6 +613,23 @@ impl OverlayWorker {
 		}
 	}
 
+	fn handle_probe_monitors_request(
+		backend: &mut dyn CaptureBackend,
+		resp_tx: &Sender<WorkerResponse>,
+		response_waker: Option<&(dyn Fn() + Send + Sync)>,
+		monitors: Vec<MonitorRect>,
+	) {
+		for monitor in monitors {
+			let error = backend.probe_monitor_capture(monitor).err().map(|err| format!("{err:#}"));
+
+			Self::send_response(
+				resp_tx,
+				response_waker,
+				WorkerResponse::ProbedMonitor { monitor, error },
+			);
+		}
+	}
+
 	fn handle_refresh_window_list_request(
 		backend: &mut dyn CaptureBackend,
 		resp_tx: &Sender<WorkerResponse>,
@@ -308,6 +706,8 @@ impl OverlayWorker {
 		sample_req: (MonitorRect, GlobalPoint, u64, bool, u32, u32),
 	) {
 		let (monitor, point, request_id, want_patch, patch_width_px, patch_height_px) = sample_req;
+		let _span = tracing::debug_span!("sample", monitor_id = monitor.id, request_id, want_patch)
+			.entered();
 		let started_at = Instant::now();
 		let sample = backend
 			.live_sample_cursor(monitor, point, want_patch, patch_width_px, patch_height_px)
@@ -382,8 +782,33 @@ impl OverlayWorker {
 		}
 	}
 
+	/// Counts a send attempt as queued until the worker picks it up, or as dropped.
+	fn track_send<E>(&self, result: Result<(), E>) -> Result<(), E> {
+		if result.is_ok() {
+			self.queue_stats.queued.fetch_add(1, Ordering::Relaxed);
+		} else {
+			self.queue_stats.dropped.fetch_add(1, Ordering::Relaxed);
+		}
+
+		result
+	}
+
+	/// Requests sent but not yet picked up by the worker thread.
+	pub(crate) fn queue_depth(&self) -> usize {
+		self.queue_stats.queued.load(Ordering::Relaxed).max(0).cast_unsigned()
+	}
+
+	/// Requests rejected because the queue was full or the worker had exited.
+	pub(crate) fn dropped_requests(&self) -> u64 {
+		self.queue_stats.dropped.load(Ordering::Relaxed)
+	}
+
 	pub(crate) fn request_refresh_window_list(&self) -> bool {
-		self.req_tx.try_send(WorkerRequest::RefreshWindowList).is_ok()
+		self.track_send(self.req_tx.try_send(WorkerRequest::RefreshWindowList)).is_ok()
+	}
+
+	pub(crate) fn request_probe_monitors(&self, monitors: Vec<MonitorRect>) -> bool {
+		self.track_send(self.req_tx.try_send(WorkerRequest::ProbeMonitors { monitors })).is_ok()
 	}
 
 	pub(crate) fn request_freeze_capture(
@@ -391,7 +816,8 @@ impl OverlayWorker {
 		monitor: MonitorRect,
 		target: FreezeCaptureTarget,
 	) -> bool {
-		self.req_tx.try_send(WorkerRequest::FreezeCapture { monitor, target }).is_ok()
+		self.track_send(self.req_tx.try_send(WorkerRequest::FreezeCapture { monitor, target }))
+			.is_ok()
 	}
 
 	pub(crate) fn request_hit_test_window(
@@ -402,7 +828,7 @@ impl OverlayWorker {
 	) -> Result<(), WorkerRequestSendError> {
 		let request = WorkerRequest::HitTestWindow { monitor, point, request_id };
 
-		self.req_tx.try_send(request).map_err(Self::map_try_send_error)
+		self.track_send(self.req_tx.try_send(request)).map_err(Self::map_try_send_error)
 	}
 
 	#[cfg(not(target_os = "macos"))]
@@ -424,20 +850,86 @@ impl OverlayWorker {
 			patch_height_px,
 		};
 
-		self.req_tx.try_send(request).map_err(Self::map_try_send_error)
+		self.track_send(self.req_tx.try_send(request)).map_err(Self::map_try_send_error)
+	}
+
+	pub(crate) fn request_histogram(&self, image: RgbaImage, request_id: u64) -> bool {
+		self.track_send(self.req_tx.try_send(WorkerRequest::ComputeHistogram { image, request_id }))
+			.is_ok()
+	}
+
+	pub(crate) fn request_export_preview(
+		&self,
+		export: PngExport,
+		max_size: (u32, u32),
+		request_id: u64,
+	) -> bool {
+		self.track_send(self.req_tx.try_send(WorkerRequest::RenderExportPreview {
+			export,
+			max_size,
+			request_id,
+		}))
+		.is_ok()
+	}
+
+	/// Runs the redaction scan on its own thread: the OCR command can take many seconds, which
+	/// would otherwise hold up captures, exports, and previews queued behind it.
+	pub(crate) fn request_redaction_scan(
+		&self,
+		image: RgbaImage,
+		command: String,
+		request_id: u64,
+	) -> bool {
+		let resp_tx = self.resp_tx.clone();
+		let response_waker = self.response_waker.clone();
+		let spawned =
+			thread::Builder::new().name(String::from("rsnap-redaction-scan")).spawn(move || {
+				Self::handle_scan_redactions_request(
+					&resp_tx,
+					response_waker.as_deref(),
+					&image,
+					&command,
+					request_id,
+				);
+			});
+
+		if let Err(err) = &spawned {
+			tracing::warn!(error = %err, "Failed to start the redaction scan thread.");
+		}
+
+		spawned.is_ok()
+	}
+
+	pub(crate) fn request_code_scan(&self, source: CodeScanSource, request_id: u64) -> bool {
+		self.track_send(self.req_tx.try_send(WorkerRequest::ScanCodes { source, request_id }))
+			.is_ok()
 	}
 
-	pub(crate) fn request_encode_png(&self, image: RgbaImage) -> Result<(), RgbaImage> {
-		match self.req_tx.try_send(WorkerRequest::EncodePng { image }) {
+	pub(crate) fn request_encode_png(&self, export: Box<PngExport>) -> Result<(), Box<PngExport>> {
+		match self.track_send(self.req_tx.try_send(WorkerRequest::EncodePng { export })) {
 			Ok(()) => Ok(()),
-			Err(TrySendError::Full(WorkerRequest::EncodePng { image })) => Err(image),
-			Err(TrySendError::Disconnected(WorkerRequest::EncodePng { image })) => Err(image),
+			Err(TrySendError::Full(WorkerRequest::EncodePng { export })) => Err(export),
+			Err(TrySendError::Disconnected(WorkerRequest::EncodePng { export })) => Err(export),
 			Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
 				unreachable!("request_encode_png only sends WorkerRequest::EncodePng")
 			},
 		}
 	}
 
+	pub(crate) fn request_encode_png_batch(
+		&self,
+		batch: Box<PngBatchExport>,
+	) -> Result<(), Box<PngBatchExport>> {
+		match self.track_send(self.req_tx.try_send(WorkerRequest::EncodePngBatch { batch })) {
+			Ok(()) => Ok(()),
+			Err(TrySendError::Full(WorkerRequest::EncodePngBatch { batch })) => Err(batch),
+			Err(TrySendError::Disconnected(WorkerRequest::EncodePngBatch { batch })) => Err(batch),
+			Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+				unreachable!("request_encode_png_batch only sends WorkerRequest::EncodePngBatch")
+			},
+		}
+	}
+
 	#[cfg(not(target_os = "macos"))]
 	pub(crate) fn request_capture_monitor_region(
 		&self,
@@ -447,7 +939,7 @@ impl OverlayWorker {
 	) -> Result<(), WorkerRequestSendError> {
 		let request = WorkerRequest::CaptureMonitorRegion { monitor, rect_px, request_id };
 
-		self.req_tx.try_send(request).map_err(Self::map_try_send_error)
+		self.track_send(self.req_tx.try_send(request)).map_err(Self::map_try_send_error)
 	}
 
 	pub(crate) fn try_recv(&self) -> Option<WorkerResponse> {
@@ -472,10 +964,15 @@ struct PendingWorkerRequests {
 	#[cfg(not(target_os = "macos"))]
 	last_sample_cursor: Option<(MonitorRect, GlobalPoint, u64, bool, u32, u32)>,
 	last_refresh_window_list: bool,
+	probe_monitors: Vec<MonitorRect>,
 	last_freeze: Option<(MonitorRect, FreezeCaptureTarget)>,
 	#[cfg(not(target_os = "macos"))]
 	last_capture_region: Option<(MonitorRect, RectPoints, u64)>,
-	last_encode: Option<RgbaImage>,
+	last_encode: Option<Box<PngExport>>,
+	last_encode_batch: Option<Box<PngBatchExport>>,
+	last_histogram: Option<(RgbaImage, u64)>,
+	last_export_preview: Option<(PngExport, (u32, u32), u64)>,
+	last_code_scan: Option<(CodeScanSource, u64)>,
 }
 impl PendingWorkerRequests {
 	fn record(&mut self, request: WorkerRequest) {
@@ -498,6 +995,9 @@ impl PendingWorkerRequests {
 			WorkerRequest::RefreshWindowList => {
 				self.last_refresh_window_list = true;
 			},
+			WorkerRequest::ProbeMonitors { monitors } => {
+				self.probe_monitors.extend(monitors);
+			},
 			WorkerRequest::FreezeCapture { monitor, target } => {
 				self.last_freeze = Some((monitor, target));
 			},
@@ -505,8 +1005,20 @@ impl PendingWorkerRequests {
 			WorkerRequest::CaptureMonitorRegion { monitor, rect_px, request_id } => {
 				self.last_capture_region = Some((monitor, rect_px, request_id));
 			},
-			WorkerRequest::EncodePng { image } => {
-				self.last_encode = Some(image);
+			WorkerRequest::EncodePng { export } => {
+				self.last_encode = Some(export);
+			},
+			WorkerRequest::EncodePngBatch { batch } => {
+				self.last_encode_batch = Some(batch);
+			},
+			WorkerRequest::ComputeHistogram { image, request_id } => {
+				self.last_histogram = Some((image, request_id));
+			},
+			WorkerRequest::RenderExportPreview { export, max_size, request_id } => {
+				self.last_export_preview = Some((export, max_size, request_id));
+			},
+			WorkerRequest::ScanCodes { source, request_id } => {
+				self.last_code_scan = Some((source, request_id));
 			},
 		}
 	}
@@ -520,8 +1032,45 @@ impl PendingWorkerRequests {
 		>,
 		response_waker: Option<&(dyn Fn() + Send + Sync)>,
 	) {
-		if let Some(image) = self.last_encode {
-			OverlayWorker::handle_encode_request(resp_tx, response_waker, image);
+		// Probes are one-shot per session, so they run before any coalesced early return.
+		if !self.probe_monitors.is_empty() {
+			OverlayWorker::handle_probe_monitors_request(
+				backend,
+				resp_tx,
+				response_waker,
+				self.probe_monitors,
+			);
+		}
+		// Histograms are cheap and independent of the backend, so they never defer other work.
+		if let Some((image, request_id)) = self.last_histogram {
+			OverlayWorker::handle_histogram_request(resp_tx, response_waker, &image, request_id);
+		}
+		if let Some((export, max_size, request_id)) = self.last_export_preview {
+			OverlayWorker::handle_export_preview_request(
+				resp_tx,
+				response_waker,
+				&export,
+				max_size,
+				request_id,
+			);
+		}
+		// OCR scans do not touch the backend either; they run before the coalesced captures.
+		if let Some((source, request_id)) = self.last_code_scan {
+			OverlayWorker::handle_scan_codes_request(
+				backend,
+				resp_tx,
+				response_waker,
+				source,
+				request_id,
+			);
+		}
+		if let Some(export) = self.last_encode {
+			OverlayWorker::handle_encode_request(resp_tx, response_waker, *export);
+
+			return;
+		}
+		if let Some(batch) = self.last_encode_batch {
+			OverlayWorker::handle_encode_batch_request(resp_tx, response_waker, *batch);
 
 			return;
 		}
@@ -577,6 +1126,8 @@ mod tests {
 		Arc,
 		atomic::{AtomicUsize, Ordering},
 	};
+	use std::thread;
+	use std::time::{Duration, Instant};
 
 	use color_eyre::eyre::{self, Result};
 	use image::{Rgba, RgbaImage};
@@ -666,6 +1217,38 @@ mod tests {
 		}
 	}
 
+	struct MockProbeBackend {
+		blocked_monitor_id: u32,
+	}
+
+	impl CaptureBackend for MockProbeBackend {
+		fn capture_monitor(&mut self, _monitor: MonitorRect) -> Result<RgbaImage> {
+			Err(eyre::eyre!("unused in this test"))
+		}
+
+		fn pixel_rgb_in_monitor(
+			&mut self,
+			monitor: MonitorRect,
+			_point: GlobalPoint,
+		) -> Result<Option<Rgb>> {
+			if monitor.id == self.blocked_monitor_id {
+				return Err(eyre::eyre!("display is protected"));
+			}
+
+			Ok(Some(Rgb::new(1, 2, 3)))
+		}
+
+		fn rgba_patch_in_monitor(
+			&mut self,
+			_monitor: MonitorRect,
+			_point: GlobalPoint,
+			_width_px: u32,
+			_height_px: u32,
+		) -> Result<Option<RgbaImage>> {
+			Ok(None)
+		}
+	}
+
 	fn sample_monitor() -> MonitorRect {
 		MonitorRect {
 			id: 7,
@@ -708,6 +1291,24 @@ mod tests {
 		assert_eq!(wake_count.load(Ordering::Acquire), 1);
 	}
 
+	#[test]
+	fn histogram_request_responds_with_matching_request_id() {
+		let (resp_tx, resp_rx) = mpsc::channel::<WorkerResponse>();
+		let image = RgbaImage::from_pixel(3, 2, Rgba([10, 20, 30, 255]));
+
+		OverlayWorker::handle_histogram_request(&resp_tx, None, &image, 7);
+
+		let response = resp_rx.try_recv().expect("histogram response");
+		let WorkerResponse::ComputedHistogram { request_id, histogram } = response else {
+			panic!("expected a histogram response");
+		};
+
+		assert_eq!(request_id, 7);
+		assert_eq!(histogram.pixel_count, 6);
+		assert_eq!(histogram.red[10], 6);
+		assert_eq!(histogram.blue[30], 6);
+	}
+
 	#[test]
 	fn send_region_capture_response_wakes_after_scroll_region_result() {
 		let (region_tx, region_rx) = mpsc::channel::<CapturedMonitorRegionResponse>();
@@ -833,4 +1434,61 @@ mod tests {
 			other => panic!("expected worker error, got {other:?}"),
 		}
 	}
+
+	#[test]
+	fn probe_monitors_request_reports_each_monitor() {
+		let (resp_tx, resp_rx) = mpsc::channel::<WorkerResponse>();
+		let blocked = sample_monitor();
+		let usable = MonitorRect { id: 8, origin: GlobalPoint::new(640, 0), ..blocked };
+		let mut backend = MockProbeBackend { blocked_monitor_id: blocked.id };
+
+		OverlayWorker::handle_probe_monitors_request(
+			&mut backend,
+			&resp_tx,
+			None,
+			vec![blocked, usable],
+		);
+
+		match resp_rx.try_recv().expect("blocked probe") {
+			WorkerResponse::ProbedMonitor { monitor, error } => {
+				assert_eq!(monitor, blocked);
+				assert!(error.is_some_and(|message| message.contains("protected")));
+			},
+			other => panic!("expected probe result, got {other:?}"),
+		}
+		match resp_rx.try_recv().expect("usable probe") {
+			WorkerResponse::ProbedMonitor { monitor, error } => {
+				assert_eq!(monitor, usable);
+				assert!(error.is_none());
+			},
+			other => panic!("expected probe result, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn queue_stats_track_requests_until_the_worker_picks_them_up() {
+		let worker = OverlayWorker::new(
+			Box::new(MockScrollCaptureBackend {
+				scroll_capture_result: MockScrollCaptureResult::NoNewFrame,
+			}),
+			None,
+		);
+
+		assert!(worker.request_refresh_window_list());
+
+		let deadline = Instant::now() + Duration::from_secs(5);
+		let response = loop {
+			if let Some(response) = worker.try_recv() {
+				break response;
+			}
+
+			assert!(Instant::now() < deadline, "worker did not answer");
+
+			thread::sleep(Duration::from_millis(1));
+		};
+
+		assert!(matches!(response, WorkerResponse::Error(_)));
+		assert_eq!(worker.queue_depth(), 0);
+		assert_eq!(worker.dropped_requests(), 0);
+	}
 }
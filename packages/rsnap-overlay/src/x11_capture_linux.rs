@@ -0,0 +1,759 @@
+//! Direct X11 capture for Linux: monitors through RandR, pixels through MIT-SHM (with a plain
+//! `GetImage` fallback), windows through the EWMH client list and their contents through
+//! Composite, and the cursor through XFixes.
+//!
+//! X11 reports everything in root-window pixels; `Xft.dpi` supplies the single scale factor that
+//! converts them to the global points the overlay works in.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::os::fd::AsRawFd;
+use std::process;
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::thread;
+use std::time::Duration;
+
+use color_eyre::eyre::{self, Result, WrapErr};
+use image::RgbaImage;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::composite::{self, ConnectionExt as _, Redirect};
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::shm::{self, ConnectionExt as _};
+use x11rb::protocol::xfixes::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{self, AtomEnum, ConnectionExt as _, ImageFormat, ImageOrder};
+use x11rb::rust_connection::RustConnection;
+
+use crate::state::{CursorImage, GlobalPoint, MonitorRect, WindowLabel, WindowRect};
+
+/// Base DPI that `Xft.dpi` is measured against.
+const XFT_BASE_DPI: f32 = 96.0;
+/// Segments grow to the largest capture seen, rounded up so small size changes reuse them.
+const SHM_SEGMENT_ALIGN_BYTES: usize = 1 << 20;
+/// How long a window redirected just for one capture gets to repaint its covered parts.
+const COMPOSITE_REPAINT_WAIT: Duration = Duration::from_millis(80);
+
+/// One X server connection plus the shared-memory segment used for fast captures.
+pub(crate) struct X11Capture {
+	conn: RustConnection,
+	root: xproto::Window,
+	scale_factor: f32,
+	pixel_layout: PixelLayout,
+	shm_available: bool,
+	shm: Option<ShmSegment>,
+	composite_available: bool,
+	net_client_list_stacking: xproto::Atom,
+	net_active_window: xproto::Atom,
+	net_wm_pid: xproto::Atom,
+	net_wm_name: xproto::Atom,
+	net_wm_state: xproto::Atom,
+	net_wm_state_hidden: xproto::Atom,
+	utf8_string: xproto::Atom,
+}
+impl X11Capture {
+	/// Connects to `$DISPLAY`. Wayland sessions are refused because XWayland's root window does
+	/// not show native Wayland clients; those sessions rely on the screenshot portal instead.
+	pub(crate) fn connect() -> Result<Self> {
+		if env::var_os("WAYLAND_DISPLAY").is_some() {
+			return Err(eyre::eyre!("X11 capture is unavailable in a Wayland session"));
+		}
+
+		Self::open()
+	}
+
+	fn open() -> Result<Self> {
+		let (conn, screen_num) = x11rb::connect(None).wrap_err("Failed to connect to X11")?;
+		let screen = &conn.setup().roots[screen_num];
+		let root = screen.root;
+		let pixel_layout = PixelLayout::from_setup(conn.setup(), screen.root_depth)?;
+		let shm_available =
+			conn.extension_information(shm::X11_EXTENSION_NAME).ok().flatten().is_some()
+				&& conn.shm_query_version().ok().and_then(|cookie| cookie.reply().ok()).is_some();
+		let composite_available = conn
+			.extension_information(composite::X11_EXTENSION_NAME)
+			.ok()
+			.flatten()
+			.is_some()
+			&& conn
+				.composite_query_version(composite::X11_XML_VERSION.0, composite::X11_XML_VERSION.1)
+				.ok()
+				.and_then(|cookie| cookie.reply().ok())
+				.is_some_and(|reply| (reply.major_version, reply.minor_version) >= (0, 2));
+		let intern = |name: &[u8]| -> Result<xproto::Atom> {
+			Ok(conn.intern_atom(false, name)?.reply()?.atom)
+		};
+		let net_client_list_stacking = intern(b"_NET_CLIENT_LIST_STACKING")?;
+		let net_active_window = intern(b"_NET_ACTIVE_WINDOW")?;
+		let net_wm_pid = intern(b"_NET_WM_PID")?;
+		let net_wm_name = intern(b"_NET_WM_NAME")?;
+		let net_wm_state = intern(b"_NET_WM_STATE")?;
+		let net_wm_state_hidden = intern(b"_NET_WM_STATE_HIDDEN")?;
+		let utf8_string = intern(b"UTF8_STRING")?;
+		let scale_factor = xft_scale_factor(&conn, root);
+
+		Ok(Self {
+			conn,
+			root,
+			scale_factor,
+			pixel_layout,
+			shm_available,
+			shm: None,
+			composite_available,
+			net_client_list_stacking,
+			net_active_window,
+			net_wm_pid,
+			net_wm_name,
+			net_wm_state,
+			net_wm_state_hidden,
+			utf8_string,
+		})
+	}
+
+	/// Lists the active RandR monitors in points, keyed by their first output id.
+	pub(crate) fn monitor_rects(&self) -> Result<Vec<MonitorRect>> {
+		let reply = self
+			.conn
+			.randr_get_monitors(self.root, true)?
+			.reply()
+			.wrap_err("RandR GetMonitors failed")?;
+		let scale_factor_x1000 = (self.scale_factor * 1_000.0).round().max(1.0) as u32;
+		let mut monitors = reply
+			.monitors
+			.iter()
+			.filter(|info| info.width > 0 && info.height > 0)
+			.map(|info| {
+				(
+					info.primary,
+					MonitorRect {
+						id: info.outputs.first().copied().unwrap_or(info.name),
+						origin: GlobalPoint::new(
+							self.px_to_points(i32::from(info.x)),
+							self.px_to_points(i32::from(info.y)),
+						),
+						width: self.px_to_points(i32::from(info.width)) as u32,
+						height: self.px_to_points(i32::from(info.height)) as u32,
+						scale_factor_x1000,
+					},
+				)
+			})
+			.collect::<Vec<_>>();
+
+		// Primary first, matching the main-display-first order used on macOS.
+		monitors.sort_by_key(|(primary, _)| !primary);
+
+		Ok(monitors.into_iter().map(|(_, monitor)| monitor).collect())
+	}
+
+	/// Reads the physical width RandR reports for the monitor `monitor_rects` listed as
+	/// `monitor_id`, or `None` when the output does not report one.
+	fn monitor_width_millimeters(&self, monitor_id: u32) -> Result<Option<u32>> {
+		let reply = self
+			.conn
+			.randr_get_monitors(self.root, true)?
+			.reply()
+			.wrap_err("RandR GetMonitors failed")?;
+
+		Ok(reply
+			.monitors
+			.iter()
+			.find(|info| info.outputs.first().copied().unwrap_or(info.name) == monitor_id)
+			.map(|info| info.width_in_millimeters)
+			.filter(|width_mm| *width_mm > 0))
+	}
+
+	/// Captures a rectangle of the root window given in global pixels.
+	pub(crate) fn capture_root_region(
+		&mut self,
+		x: i64,
+		y: i64,
+		width: u32,
+		height: u32,
+	) -> Result<RgbaImage> {
+		let x = i16::try_from(x).wrap_err("capture x is outside the X11 coordinate range")?;
+		let y = i16::try_from(y).wrap_err("capture y is outside the X11 coordinate range")?;
+		let width = u16::try_from(width.max(1)).wrap_err("capture width is too large for X11")?;
+		let height =
+			u16::try_from(height.max(1)).wrap_err("capture height is too large for X11")?;
+
+		if self.shm_available {
+			match self.capture_root_region_shm(x, y, width, height) {
+				Ok(image) => return Ok(image),
+				Err(err) => {
+					tracing::debug!(
+						op = "capture_backend.x11_shm",
+						error = %format!("{err:#}"),
+						"MIT-SHM capture failed; falling back to GetImage."
+					);
+
+					self.shm_available = false;
+
+					self.release_shm();
+				},
+			}
+		}
+
+		let reply = self
+			.conn
+			.get_image(ImageFormat::Z_PIXMAP, self.root, x, y, width, height, !0)?
+			.reply()
+			.wrap_err("X11 GetImage failed")?;
+
+		self.pixel_layout.to_rgba(&reply.data, u32::from(width), u32::from(height))
+	}
+
+	fn capture_root_region_shm(
+		&mut self,
+		x: i16,
+		y: i16,
+		width: u16,
+		height: u16,
+	) -> Result<RgbaImage> {
+		let len = usize::from(width) * usize::from(height) * 4;
+
+		if self.shm.as_ref().is_none_or(|segment| segment.len < len) {
+			self.release_shm();
+			self.shm = Some(ShmSegment::create(&self.conn, len)?);
+		}
+
+		let Some(segment) = self.shm.as_ref() else {
+			return Err(eyre::eyre!("missing shared memory segment"));
+		};
+
+		self.conn
+			.shm_get_image(
+				self.root,
+				x,
+				y,
+				width,
+				height,
+				!0,
+				ImageFormat::Z_PIXMAP.into(),
+				segment.seg,
+				0,
+			)?
+			.reply()
+			.wrap_err("MIT-SHM GetImage failed")?;
+
+		self.pixel_layout.to_rgba(&segment.bytes()[..len], u32::from(width), u32::from(height))
+	}
+
+	/// Captures a monitor through the root window.
+	pub(crate) fn capture_monitor(&mut self, monitor: MonitorRect) -> Result<RgbaImage> {
+		let (x, y) = monitor.scaled_origin_px();
+		let (width, height) = monitor.size_px();
+
+		self.capture_root_region(x, y, width, height)
+	}
+
+	/// Captures a client window from its own contents through Composite, so parts other windows
+	/// cover still show. Without Composite the window is read from the screen as it appears.
+	pub(crate) fn capture_window(&mut self, window_id: u32) -> Result<RgbaImage> {
+		if self.composite_available {
+			match self.capture_window_contents(window_id) {
+				Ok(image) => return Ok(image),
+				Err(err) => {
+					tracing::debug!(
+						op = "capture_backend.x11_composite",
+						window_id,
+						error = %format!("{err:#}"),
+						"Composite window capture failed; reading the window from the screen."
+					);
+				},
+			}
+		}
+
+		let (x, y, width, height) = self.window_root_geometry_px(window_id)?;
+
+		self.capture_root_region(x, y, width, height)
+	}
+
+	/// Reads the client out of its top-level frame's off-screen pixmap. A compositing manager
+	/// already keeps frames redirected; otherwise the frame is redirected for this one capture
+	/// and given a moment to repaint what was covered.
+	fn capture_window_contents(&self, window_id: u32) -> Result<RgbaImage> {
+		let frame = self.top_level_frame(window_id)?;
+		let frame_geometry = self.conn.get_geometry(frame)?.reply()?;
+		let client_geometry = self.conn.get_geometry(window_id)?.reply()?;
+		let offset = self.conn.translate_coordinates(window_id, frame, 0, 0)?.reply()?;
+		let pixel_layout = PixelLayout::from_setup(self.conn.setup(), frame_geometry.depth)?;
+		let pixmap = self.conn.generate_id()?;
+		let redirected_here =
+			self.conn.composite_name_window_pixmap(frame, pixmap)?.check().is_err();
+
+		if redirected_here {
+			self.conn.composite_redirect_window(frame, Redirect::AUTOMATIC)?.check()?;
+
+			thread::sleep(COMPOSITE_REPAINT_WAIT);
+		}
+
+		let named = !redirected_here
+			|| self.conn.composite_name_window_pixmap(frame, pixmap)?.check().is_ok();
+		let image = if named {
+			self.read_pixmap(pixmap, (offset.dst_x, offset.dst_y), &client_geometry, pixel_layout)
+		} else {
+			Err(eyre::eyre!("window {window_id} has no Composite pixmap"))
+		};
+
+		if named {
+			let _ = self.conn.free_pixmap(pixmap);
+		}
+		if redirected_here {
+			let _ = self.conn.composite_unredirect_window(frame, Redirect::AUTOMATIC);
+		}
+
+		let _ = self.conn.flush();
+
+		image
+	}
+
+	fn read_pixmap(
+		&self,
+		pixmap: xproto::Pixmap,
+		(x, y): (i16, i16),
+		geometry: &xproto::GetGeometryReply,
+		pixel_layout: PixelLayout,
+	) -> Result<RgbaImage> {
+		let reply = self
+			.conn
+			.get_image(ImageFormat::Z_PIXMAP, pixmap, x, y, geometry.width, geometry.height, !0)?
+			.reply()
+			.wrap_err("X11 GetImage on the window pixmap failed")?;
+
+		pixel_layout.to_rgba(&reply.data, u32::from(geometry.width), u32::from(geometry.height))
+	}
+
+	/// The root window's child that holds `window_id`, i.e. the window manager's frame around it.
+	fn top_level_frame(&self, window_id: u32) -> Result<xproto::Window> {
+		let mut window = window_id;
+
+		loop {
+			let tree = self.conn.query_tree(window)?.reply()?;
+
+			if tree.parent == self.root || tree.parent == x11rb::NONE {
+				return Ok(window);
+			}
+
+			window = tree.parent;
+		}
+	}
+
+	/// Lists visible client windows front to back in points, skipping this process's windows,
+	/// together with each window's process name and title keyed by window id.
+	pub(crate) fn window_geometries_with_labels(
+		&self,
+	) -> Result<(Vec<WindowRect>, HashMap<u32, WindowLabel>)> {
+		let mut labels = HashMap::new();
+		let windows = self
+			.windows_with_pids()?
+			.into_iter()
+			.map(|(pid, window)| {
+				if let Some(window_id) = window.window_id {
+					labels.insert(
+						window_id,
+						WindowLabel {
+							app_name: process_name(pid),
+							title: self.window_title(window_id),
+						},
+					);
+				}
+
+				window
+			})
+			.collect();
+
+		Ok((windows, labels))
+	}
+
+	/// Lists visible client windows front to back in points with each window's `_NET_WM_PID`;
+	/// windows that do not set it report pid 0.
+	pub(crate) fn windows_with_pids(&self) -> Result<Vec<(u32, WindowRect)>> {
+		let stacking = self
+			.conn
+			.get_property(
+				false,
+				self.root,
+				self.net_client_list_stacking,
+				AtomEnum::WINDOW,
+				0,
+				u32::MAX / 4,
+			)?
+			.reply()
+			.wrap_err("Failed to read _NET_CLIENT_LIST_STACKING")?;
+		let Some(windows) = stacking.value32() else {
+			return Ok(Vec::new());
+		};
+		let self_pid = process::id();
+		let mut geometries = Vec::new();
+
+		// EWMH lists bottom to top; hit testing wants the topmost window first.
+		for window_id in windows.collect::<Vec<_>>().into_iter().rev() {
+			let pid = self.window_pid(window_id);
+
+			if pid == Some(self_pid) || self.window_is_hidden(window_id) {
+				continue;
+			}
+
+			let Ok((x, y, width, height)) = self.window_root_geometry_px(window_id) else {
+				continue;
+			};
+
+			geometries.push((
+				pid.unwrap_or(0),
+				WindowRect {
+					window_id: Some(window_id),
+					x: i64::from(self.px_to_points(x as i32)),
+					y: i64::from(self.px_to_points(y as i32)),
+					width: i64::from(self.px_to_points(width as i32)),
+					height: i64::from(self.px_to_points(height as i32)),
+				},
+			));
+		}
+
+		Ok(geometries)
+	}
+
+	/// Returns the window the window manager reports as focused, in points, or `None` when nothing
+	/// or one of this process's windows has focus.
+	pub(crate) fn active_window(&self) -> Result<Option<WindowRect>> {
+		let active = self
+			.conn
+			.get_property(false, self.root, self.net_active_window, AtomEnum::WINDOW, 0, 1)?
+			.reply()
+			.wrap_err("Failed to read _NET_ACTIVE_WINDOW")?;
+		let Some(window_id) = active.value32().and_then(|mut ids| ids.next()) else {
+			return Ok(None);
+		};
+
+		if window_id == x11rb::NONE || self.window_pid(window_id) == Some(process::id()) {
+			return Ok(None);
+		}
+
+		let (x, y, width, height) = self.window_root_geometry_px(window_id)?;
+
+		Ok(Some(WindowRect {
+			window_id: Some(window_id),
+			x: i64::from(self.px_to_points(x as i32)),
+			y: i64::from(self.px_to_points(y as i32)),
+			width: i64::from(self.px_to_points(width as i32)),
+			height: i64::from(self.px_to_points(height as i32)),
+		}))
+	}
+
+	/// Returns the pointer position in global points.
+	pub(crate) fn cursor_position(&self) -> Result<GlobalPoint> {
+		let reply = self.conn.query_pointer(self.root)?.reply()?;
+
+		Ok(GlobalPoint::new(
+			self.px_to_points(i32::from(reply.root_x)),
+			self.px_to_points(i32::from(reply.root_y)),
+		))
+	}
+
+	/// Reads the current cursor sprite through XFixes.
+	pub(crate) fn cursor_image(&self) -> Result<CursorImage> {
+		self.conn
+			.xfixes_query_version(xfixes::X11_XML_VERSION.0, xfixes::X11_XML_VERSION.1)?
+			.reply()
+			.wrap_err("XFixes is unavailable")?;
+
+		let reply = self.conn.xfixes_get_cursor_image()?.reply()?;
+		let image = argb_cursor_to_rgba(
+			&reply.cursor_image,
+			u32::from(reply.width),
+			u32::from(reply.height),
+		)?;
+
+		Ok(CursorImage {
+			image,
+			hotspot_x: f32::from(reply.xhot) / self.scale_factor,
+			hotspot_y: f32::from(reply.yhot) / self.scale_factor,
+			scale_factor: self.scale_factor,
+		})
+	}
+
+	fn window_root_geometry_px(&self, window_id: u32) -> Result<(i64, i64, u32, u32)> {
+		let geometry = self.conn.get_geometry(window_id)?.reply()?;
+		let origin = self.conn.translate_coordinates(window_id, self.root, 0, 0)?.reply()?;
+
+		if geometry.width == 0 || geometry.height == 0 {
+			return Err(eyre::eyre!("window {window_id} has no area"));
+		}
+
+		Ok((
+			i64::from(origin.dst_x),
+			i64::from(origin.dst_y),
+			u32::from(geometry.width),
+			u32::from(geometry.height),
+		))
+	}
+
+	fn window_pid(&self, window_id: u32) -> Option<u32> {
+		let reply = self
+			.conn
+			.get_property(false, window_id, self.net_wm_pid, AtomEnum::CARDINAL, 0, 1)
+			.ok()?
+			.reply()
+			.ok()?;
+
+		reply.value32()?.next()
+	}
+
+	/// Reads `_NET_WM_NAME`, falling back to the legacy `WM_NAME`.
+	fn window_title(&self, window_id: u32) -> String {
+		[(self.net_wm_name, self.utf8_string), (AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())]
+			.into_iter()
+			.find_map(|(property, kind)| {
+				let reply = self
+					.conn
+					.get_property(false, window_id, property, kind, 0, 1024)
+					.ok()?
+					.reply()
+					.ok()?;
+
+				(!reply.value.is_empty())
+					.then(|| String::from_utf8_lossy(&reply.value).into_owned())
+			})
+			.unwrap_or_default()
+	}
+
+	fn window_is_hidden(&self, window_id: u32) -> bool {
+		let Some(reply) = self
+			.conn
+			.get_property(false, window_id, self.net_wm_state, AtomEnum::ATOM, 0, 64)
+			.ok()
+			.and_then(|cookie| cookie.reply().ok())
+		else {
+			return false;
+		};
+
+		reply.value32().is_some_and(|mut atoms| atoms.any(|atom| atom == self.net_wm_state_hidden))
+	}
+
+	fn release_shm(&mut self) {
+		if let Some(segment) = self.shm.take() {
+			let _ = self.conn.shm_detach(segment.seg);
+		}
+	}
+
+	fn px_to_points(&self, px: i32) -> i32 {
+		((px as f32) / self.scale_factor).round() as i32
+	}
+}
+
+/// Lists monitors through RandR. Unlike capture, enumeration also works through XWayland.
+pub(crate) fn monitor_rects() -> Result<Vec<MonitorRect>> {
+	X11Capture::open()?.monitor_rects()
+}
+
+/// Reads a monitor's physical width in millimeters, or `None` when X11 cannot report it.
+pub(crate) fn monitor_width_millimeters(monitor_id: u32) -> Option<u32> {
+	X11Capture::open().ok()?.monitor_width_millimeters(monitor_id).ok().flatten()
+}
+
+/// Lists visible client windows with their owner pids through EWMH.
+pub(crate) fn windows_with_pids() -> Result<Vec<(u32, WindowRect)>> {
+	X11Capture::connect()?.windows_with_pids()
+}
+
+/// Reads a process's short name from `/proc`, or an empty string when it is gone.
+pub(crate) fn process_name(pid: u32) -> String {
+	fs::read_to_string(format!("/proc/{pid}/comm"))
+		.map(|name| name.trim().to_owned())
+		.unwrap_or_default()
+}
+
+/// Looks up the focused client window through EWMH.
+pub(crate) fn active_window() -> Result<Option<WindowRect>> {
+	X11Capture::connect()?.active_window()
+}
+
+/// Reads the current cursor sprite, or `None` when XFixes cannot provide it.
+pub(crate) fn current_cursor_image() -> Option<CursorImage> {
+	X11Capture::connect().ok()?.cursor_image().ok()
+}
+
+/// A server-allocated MIT-SHM segment mapped into this process.
+struct ShmSegment {
+	seg: shm::Seg,
+	ptr: NonNull<u8>,
+	len: usize,
+}
+impl ShmSegment {
+	fn create(conn: &RustConnection, min_len: usize) -> Result<Self> {
+		let len = min_len.div_ceil(SHM_SEGMENT_ALIGN_BYTES) * SHM_SEGMENT_ALIGN_BYTES;
+		let seg = conn.generate_id()?;
+		let reply = conn
+			.shm_create_segment(seg, u32::try_from(len)?, false)?
+			.reply()
+			.wrap_err("MIT-SHM CreateSegment failed")?;
+		// SAFETY: the server handed us a shared memory fd of at least `len` bytes; the mapping
+		// stays valid after the fd closes and is unmapped in `Drop`.
+		let ptr = unsafe {
+			libc::mmap(
+				ptr::null_mut(),
+				len,
+				libc::PROT_READ,
+				libc::MAP_SHARED,
+				reply.shm_fd.as_raw_fd(),
+				0,
+			)
+		};
+
+		if ptr == libc::MAP_FAILED {
+			let _ = conn.shm_detach(seg);
+
+			return Err(eyre::eyre!("mmap of the MIT-SHM segment failed"));
+		}
+
+		Ok(Self {
+			seg,
+			ptr: NonNull::new(ptr.cast()).ok_or_else(|| eyre::eyre!("mmap returned null"))?,
+			len,
+		})
+	}
+
+	fn bytes(&self) -> &[u8] {
+		// SAFETY: `ptr` maps `len` readable bytes for the lifetime of `self`.
+		unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+	}
+}
+// SAFETY: the mapping is owned by the segment and only read through `&self`.
+unsafe impl Send for ShmSegment {}
+
+impl Drop for ShmSegment {
+	fn drop(&mut self) {
+		// SAFETY: `ptr`/`len` describe the mapping created in `create`.
+		unsafe {
+			libc::munmap(self.ptr.as_ptr().cast(), self.len);
+		}
+	}
+}
+
+/// How the server packs 24/32-bit ZPixmap pixels.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PixelLayout {
+	/// 32 bits per pixel, least significant byte first: B, G, R, X in memory.
+	Bgrx,
+	/// 32 bits per pixel, most significant byte first: X, R, G, B in memory.
+	Xrgb,
+}
+impl PixelLayout {
+	fn from_setup(setup: &xproto::Setup, depth: u8) -> Result<Self> {
+		let bits_per_pixel = setup
+			.pixmap_formats
+			.iter()
+			.find(|format| format.depth == depth)
+			.map(|format| format.bits_per_pixel);
+
+		if bits_per_pixel != Some(32) {
+			return Err(eyre::eyre!(
+				"unsupported X11 pixel format: depth {depth}, {bits_per_pixel:?} bits per pixel"
+			));
+		}
+
+		Ok(if setup.image_byte_order == ImageOrder::LSB_FIRST { Self::Bgrx } else { Self::Xrgb })
+	}
+
+	fn to_rgba(self, data: &[u8], width: u32, height: u32) -> Result<RgbaImage> {
+		let len = (width as usize) * (height as usize) * 4;
+		let Some(data) = data.get(..len) else {
+			return Err(eyre::eyre!("X11 image is shorter than {width}x{height}"));
+		};
+		let mut rgba = Vec::with_capacity(len);
+
+		for &[b0, b1, b2, b3] in data.as_chunks::<4>().0 {
+			match self {
+				Self::Bgrx => rgba.extend_from_slice(&[b2, b1, b0, 255]),
+				Self::Xrgb => rgba.extend_from_slice(&[b1, b2, b3, 255]),
+			}
+		}
+
+		RgbaImage::from_raw(width, height, rgba)
+			.ok_or_else(|| eyre::eyre!("X11 image buffer size mismatch"))
+	}
+}
+
+/// Converts XFixes' premultiplied ARGB cursor words into straight RGBA.
+fn argb_cursor_to_rgba(pixels: &[u32], width: u32, height: u32) -> Result<RgbaImage> {
+	let count = (width as usize) * (height as usize);
+	let Some(pixels) = pixels.get(..count) else {
+		return Err(eyre::eyre!("cursor image is shorter than {width}x{height}"));
+	};
+	let mut rgba = Vec::with_capacity(count * 4);
+
+	for &argb in pixels {
+		let [a, r, g, b] = argb.to_be_bytes();
+		let unpremultiply = |c: u8| {
+			if a == 0 {
+				0
+			} else {
+				((u32::from(c) * 255 + u32::from(a) / 2) / u32::from(a)).min(255) as u8
+			}
+		};
+
+		rgba.extend_from_slice(&[unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+	}
+
+	RgbaImage::from_raw(width, height, rgba)
+		.ok_or_else(|| eyre::eyre!("cursor image buffer size mismatch"))
+}
+
+/// Reads `Xft.dpi` from the root window's resource database; 1.0 when unset.
+fn xft_scale_factor(conn: &RustConnection, root: xproto::Window) -> f32 {
+	let Some(reply) = conn
+		.get_property(false, root, AtomEnum::RESOURCE_MANAGER, AtomEnum::STRING, 0, u32::MAX / 4)
+		.ok()
+		.and_then(|cookie| cookie.reply().ok())
+	else {
+		return 1.0;
+	};
+
+	xft_scale_factor_from_resources(&String::from_utf8_lossy(&reply.value))
+}
+
+fn xft_scale_factor_from_resources(resources: &str) -> f32 {
+	resources
+		.lines()
+		.find_map(|line| line.strip_prefix("Xft.dpi:"))
+		.and_then(|value| value.trim().parse::<f32>().ok())
+		.filter(|dpi| *dpi > 0.0)
+		.map_or(1.0, |dpi| dpi / XFT_BASE_DPI)
+}
+
+#[cfg(test)]
+mod tests {
+	use image::Rgba;
+
+	use crate::x11_capture_linux::{
+		PixelLayout, argb_cursor_to_rgba, xft_scale_factor_from_resources,
+	};
+
+	#[test]
+	fn pixel_layout_swizzles_to_opaque_rgba() {
+		let data = [10, 20, 30, 0, 40, 50, 60, 0];
+		let bgrx = PixelLayout::Bgrx.to_rgba(&data, 2, 1).unwrap();
+		let xrgb = PixelLayout::Xrgb.to_rgba(&data, 2, 1).unwrap();
+
+		assert_eq!(*bgrx.get_pixel(0, 0), Rgba([30, 20, 10, 255]));
+		assert_eq!(*bgrx.get_pixel(1, 0), Rgba([60, 50, 40, 255]));
+		assert_eq!(*xrgb.get_pixel(0, 0), Rgba([20, 30, 0, 255]));
+		assert!(PixelLayout::Bgrx.to_rgba(&data, 3, 1).is_err());
+	}
+
+	#[test]
+	fn argb_cursor_is_unpremultiplied() {
+		let image = argb_cursor_to_rgba(&[0x8040_2000, 0x0000_0000, 0xFFFF_FFFF], 3, 1).unwrap();
+
+		assert_eq!(*image.get_pixel(0, 0), Rgba([128, 64, 0, 128]));
+		assert_eq!(*image.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+		assert_eq!(*image.get_pixel(2, 0), Rgba([255, 255, 255, 255]));
+	}
+
+	#[test]
+	fn xft_dpi_sets_the_scale_factor() {
+		assert_eq!(xft_scale_factor_from_resources("Xft.antialias:\t1\nXft.dpi:\t192\n"), 2.0);
+		assert_eq!(xft_scale_factor_from_resources("Xft.hinting:\t1\n"), 1.0);
+		assert_eq!(xft_scale_factor_from_resources("Xft.dpi:\tnope\n"), 1.0);
+	}
+}